@@ -308,6 +308,7 @@ mod tests {
                 ..Default::default()
             }),
             distinct: false,
+            order_by_fields: vec![],
         };
 
         let agg_prost = HashAggNode {
@@ -368,6 +369,7 @@ mod tests {
                 ..Default::default()
             }),
             distinct: false,
+            order_by_fields: vec![],
         };
 
         let agg_prost = HashAggNode {
@@ -0,0 +1,199 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::Result;
+use risingwave_pb::plan::plan_node::NodeBody;
+use risingwave_pb::plan::SetOpType;
+
+use super::{BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder};
+
+/// Hash set operation executor, for `INTERSECT` and `EXCEPT` with set semantics.
+///
+/// All the rows of the right child are collected into a hash set in `open`, and the rows of the
+/// left child are then probed against it in `next`. NULLs are considered equal, and each distinct
+/// row is emitted at most once.
+pub(super) struct HashSetOpExecutor {
+    left: BoxedExecutor,
+    right: BoxedExecutor,
+    set_op: SetOpType,
+    /// For `INTERSECT`, the right rows which have not been emitted yet. For `EXCEPT`, the right
+    /// rows and the emitted rows, i.e. the rows which must not be emitted anymore.
+    rows: HashSet<Row>,
+    /// Identity string of the executor
+    identity: String,
+}
+
+impl BoxedExecutorBuilder for HashSetOpExecutor {
+    fn new_boxed_executor(source: &ExecutorBuilder) -> Result<BoxedExecutor> {
+        ensure!(source.plan_node().get_children().len() == 2);
+
+        let set_op_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::HashSetOp
+        )?;
+
+        let left = source
+            .clone_for_plan(&source.plan_node().get_children()[0])
+            .build()?;
+        let right = source
+            .clone_for_plan(&source.plan_node().get_children()[1])
+            .build()?;
+        Ok(Box::new(
+            Self::new(
+                left,
+                right,
+                set_op_node.get_set_op_type()?,
+                source.plan_node().get_identity().clone(),
+            )
+            .fuse(),
+        ))
+    }
+}
+
+impl HashSetOpExecutor {
+    fn new(left: BoxedExecutor, right: BoxedExecutor, set_op: SetOpType, identity: String) -> Self {
+        Self {
+            left,
+            right,
+            set_op,
+            rows: HashSet::new(),
+            identity,
+        }
+    }
+
+    /// Returns whether `row` from the left child should be emitted, updating the hash set.
+    fn probe(&mut self, row: Row) -> bool {
+        match self.set_op {
+            SetOpType::Intersect => self.rows.remove(&row),
+            SetOpType::Except => self.rows.insert(row),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Executor for HashSetOpExecutor {
+    async fn open(&mut self) -> Result<()> {
+        self.right.open().await?;
+        while let Some(chunk) = self.right.next().await? {
+            self.rows.extend(chunk.rows().map(Row::from));
+        }
+        self.right.close().await?;
+
+        self.left.open().await
+    }
+
+    async fn next(&mut self) -> Result<Option<DataChunk>> {
+        while let Some(chunk) = self.left.next().await? {
+            let mut rows = vec![];
+            for row in chunk.rows() {
+                let row: Row = row.into();
+                if self.probe(row.clone()) {
+                    rows.push(row);
+                }
+            }
+            if !rows.is_empty() {
+                return Ok(Some(DataChunk::from_rows(
+                    &rows,
+                    &self.schema().data_types(),
+                )?));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.rows.clear();
+        self.left.close().await
+    }
+
+    fn schema(&self) -> &Schema {
+        self.left.schema()
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use risingwave_common::array::column::Column;
+    use risingwave_common::array::{Array, I32Array};
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::executor::test_utils::MockExecutor;
+
+    fn mock_executor(chunks: Vec<Vec<Option<i32>>>) -> BoxedExecutor {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int32)],
+        };
+        let mut executor = MockExecutor::new(schema);
+        for values in chunks {
+            let array = I32Array::from_slice(&values).unwrap();
+            let column = Column::new(Arc::new(array.into()));
+            executor.add(DataChunk::builder().columns(vec![column]).build());
+        }
+        Box::new(executor)
+    }
+
+    async fn set_op(
+        set_op: SetOpType,
+        left: Vec<Vec<Option<i32>>>,
+        right: Vec<Vec<Option<i32>>>,
+    ) -> Vec<Option<i32>> {
+        let mut executor = HashSetOpExecutor::new(
+            mock_executor(left),
+            mock_executor(right),
+            set_op,
+            "HashSetOpExecutor".to_string(),
+        );
+        executor.open().await.unwrap();
+        let mut result = vec![];
+        while let Some(chunk) = executor.next().await.unwrap() {
+            result.extend(chunk.column_at(0).array().as_int32().iter());
+        }
+        executor.close().await.unwrap();
+        result
+    }
+
+    #[tokio::test]
+    async fn test_hash_set_op_executor() {
+        let left = vec![
+            vec![Some(1), Some(2), Some(2), None],
+            vec![Some(3), None, Some(4), Some(1)],
+        ];
+        let right = vec![vec![Some(2), None, Some(5)], vec![Some(4), Some(4)]];
+
+        assert_eq!(
+            set_op(SetOpType::Intersect, left.clone(), right.clone()).await,
+            vec![Some(2), None, Some(4)]
+        );
+        assert_eq!(
+            set_op(SetOpType::Except, left, right).await,
+            vec![Some(1), Some(3)]
+        );
+        assert_eq!(
+            set_op(SetOpType::Except, vec![vec![Some(1), Some(1)]], vec![]).await,
+            vec![Some(1)]
+        );
+    }
+}
@@ -12,18 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::iter::once;
 use std::sync::Arc;
 
 use futures::future::try_join_all;
+use itertools::Itertools;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{
-    ArrayBuilder, ArrayImpl, DataChunk, I64ArrayBuilder, Op, PrimitiveArrayBuilder, StreamChunk,
+    ArrayBuilder, ArrayImpl, DataChunk, I64ArrayBuilder, Op, PrimitiveArrayBuilder, Row,
+    StreamChunk,
 };
 use risingwave_common::catalog::{Field, Schema, TableId};
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::DataType;
 use risingwave_pb::plan::plan_node::NodeBody;
+use risingwave_pb::plan::InsertOnConflict;
 use risingwave_source::SourceManagerRef;
 
 use super::BoxedExecutor;
@@ -44,6 +48,9 @@ pub struct InsertExecutor {
     // If this is set, we put the row id at the first column
     // TODO: remove this
     frontend_v2: bool,
+
+    /// Set for `INSERT ... ON CONFLICT`.
+    on_conflict: Option<OnConflict>,
 }
 
 impl InsertExecutor {
@@ -65,8 +72,54 @@ impl InsertExecutor {
             },
             identity: "InsertExecutor".to_string(),
             frontend_v2,
+            on_conflict: None,
+        }
+    }
+
+    /// Inserts the rows with `on_conflict`, which resolves the rows conflicting with the existing
+    /// ones on the primary key.
+    fn with_on_conflict(mut self, on_conflict: InsertOnConflict) -> Self {
+        self.on_conflict = Some(OnConflict::new(on_conflict));
+        self
+    }
+}
+
+/// `INSERT ... ON CONFLICT` on a table created with `on_conflict = 'overwrite'`. The inserted rows
+/// are registered with it in the table source by their row ids, and the materialize executor of the
+/// table resolves the conflicting ones against its latest rows, including those not committed yet.
+/// Only the rows of the statement conflicting with each other are checked here.
+struct OnConflict {
+    desc: Arc<InsertOnConflict>,
+    /// Indices of the primary key columns in the inserted rows.
+    pk_indices: Vec<usize>,
+    /// The primary keys already proposed for insertion by the statement.
+    proposed_pks: HashSet<Row>,
+}
+
+impl OnConflict {
+    fn new(desc: InsertOnConflict) -> Self {
+        Self {
+            pk_indices: desc.pk_indices.iter().map(|idx| *idx as usize).collect(),
+            desc: Arc::new(desc),
+            proposed_pks: HashSet::new(),
         }
     }
+
+    /// Checks that `DO UPDATE` affects no row twice with the rows of `chunk`.
+    fn check(&mut self, chunk: &DataChunk) -> Result<()> {
+        if self.desc.update_exprs.is_empty() {
+            return Ok(());
+        }
+        for row in chunk.rows() {
+            if !self.proposed_pks.insert(row.row_by_slice(&self.pk_indices)) {
+                return Err(ErrorCode::InvalidInputSyntax(
+                    "ON CONFLICT DO UPDATE command cannot affect row a second time".to_string(),
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -88,15 +141,20 @@ impl Executor for InsertExecutor {
         let mut notifiers = Vec::new();
 
         while let Some(child_chunk) = self.child.next().await? {
+            if let Some(on_conflict) = &mut self.on_conflict {
+                on_conflict.check(&child_chunk)?;
+            }
+
             let len = child_chunk.cardinality();
             assert!(child_chunk.visibility().is_none());
 
             // add row-id column as first column
+            let row_ids = (0..len)
+                .map(|_| source.next_row_id(self.worker_id))
+                .collect_vec();
             let mut builder = I64ArrayBuilder::new(len).unwrap();
-            for _ in 0..len {
-                builder
-                    .append(Some(source.next_row_id(self.worker_id)))
-                    .unwrap();
+            for row_id in &row_ids {
+                builder.append(Some(*row_id)).unwrap();
             }
 
             let rowid_column = once(Column::new(Arc::new(ArrayImpl::from(
@@ -115,11 +173,17 @@ impl Executor for InsertExecutor {
             };
             let chunk = StreamChunk::new(vec![Op::Insert; len], columns, None);
 
-            let notifier = source.write_chunk(chunk)?;
+            let notifier = match &self.on_conflict {
+                Some(on_conflict) => {
+                    source.write_chunk_on_conflict(chunk, &row_ids, on_conflict.desc.clone())?
+                }
+                None => source.write_chunk(chunk)?,
+            };
             notifiers.push(notifier);
         }
 
-        // Wait for all chunks to be taken / written.
+        // Wait for all chunks to be taken / written. The rows of `ON CONFLICT` are counted once
+        // resolved, except those skipped.
         let rows_inserted = try_join_all(notifiers)
             .await
             .map_err(|_| {
@@ -176,16 +240,18 @@ impl BoxedExecutorBuilder for InsertExecutor {
         })?;
         let child = source.clone_for_plan(proto_child).build()?;
 
-        Ok(Box::new(
-            Self::new(
-                table_id,
-                source.global_batch_env().source_manager_ref(),
-                child,
-                source.global_batch_env().worker_id(),
-                insert_node.frontend_v2,
-            )
-            .fuse(),
-        ))
+        let mut executor = Self::new(
+            table_id,
+            source.global_batch_env().source_manager_ref(),
+            child,
+            source.global_batch_env().worker_id(),
+            insert_node.frontend_v2,
+        );
+        if let Some(on_conflict) = &insert_node.on_conflict {
+            executor = executor.with_on_conflict(on_conflict.clone());
+        }
+
+        Ok(Box::new(executor.fuse()))
     }
 }
 
@@ -194,10 +260,15 @@ mod tests {
     use std::ops::Bound;
     use std::sync::Arc;
 
+    use itertools::Itertools;
     use risingwave_common::array::{Array, I64Array};
     use risingwave_common::catalog::{schema_test_utils, ColumnDesc, ColumnId};
     use risingwave_common::column_nonnull;
     use risingwave_common::types::DataType;
+    use risingwave_pb::data::data_type::TypeName;
+    use risingwave_pb::data::DataType as ProstDataType;
+    use risingwave_pb::expr::expr_node::{RexNode, Type};
+    use risingwave_pb::expr::{ExprNode, InputRefExpr};
     use risingwave_source::{
         MemSourceManager, Source, SourceManager, StreamSourceReader, TableV2ReaderContext,
     };
@@ -316,4 +387,102 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_insert_on_conflict() -> Result<()> {
+        let source_manager = Arc::new(MemSourceManager::new());
+        let schema = schema_test_utils::ii();
+        let mut mock_executor = MockExecutor::new(schema.clone());
+
+        // The table has the columns (row id, pk, v).
+        let table_columns = (0..3)
+            .map(|i| ColumnDesc::unnamed(ColumnId::from(i), DataType::Int64))
+            .collect_vec();
+        let table_id = TableId::new(0);
+        source_manager.create_table_source_v2(&table_id, table_columns)?;
+        let source_desc = source_manager.get_source(&table_id)?;
+        let source = source_desc.source.as_table_v2().unwrap();
+        let mut reader =
+            source.stream_reader(TableV2ReaderContext, vec![0.into(), 1.into(), 2.into()])?;
+
+        let col1 = column_nonnull! { I64Array, [1, 2] };
+        let col2 = column_nonnull! { I64Array, [5, 20] };
+        mock_executor.add(DataChunk::builder().columns(vec![col1, col2]).build());
+
+        // `ON CONFLICT (pk) DO NOTHING`.
+        let on_conflict = InsertOnConflict {
+            pk_indices: vec![0],
+            update_exprs: vec![],
+        };
+        let mut insert_executor = InsertExecutor::new(
+            table_id,
+            source_manager.clone(),
+            Box::new(mock_executor),
+            0,
+            true,
+        )
+        .with_on_conflict(on_conflict.clone());
+        let handle = tokio::spawn(async move {
+            insert_executor.open().await.unwrap();
+            let result = insert_executor.next().await.unwrap().unwrap();
+            assert_eq!(
+                result
+                    .column_at(0)
+                    .array()
+                    .as_int64()
+                    .iter()
+                    .collect::<Vec<_>>(),
+                vec![Some(1)] // the rows not skipped
+            );
+        });
+
+        // The rows are resolved by the materialize executor of the table, which skips the second
+        // one here.
+        reader.open().await?;
+        let chunk = reader.next().await?;
+        let row_ids = chunk.columns()[0]
+            .array()
+            .as_int64()
+            .iter()
+            .collect::<Vec<_>>();
+        for (row_id, affected) in row_ids.into_iter().zip_eq([true, false]) {
+            let write = source.take_on_conflict_row(row_id.unwrap()).unwrap();
+            assert_eq!(write.on_conflict().as_ref(), &on_conflict);
+            write.resolve_row(affected);
+        }
+        handle.await.unwrap();
+
+        // `ON CONFLICT (pk) DO UPDATE SET v = excluded.v`, over the existing row followed by the
+        // proposed one.
+        let input_ref = |column_idx| ExprNode {
+            expr_type: Type::InputRef as i32,
+            return_type: Some(ProstDataType {
+                type_name: TypeName::Int64 as i32,
+                ..Default::default()
+            }),
+            rex_node: Some(RexNode::InputRef(InputRefExpr { column_idx })),
+        };
+        let on_conflict = InsertOnConflict {
+            pk_indices: vec![0],
+            update_exprs: vec![input_ref(3), input_ref(4)],
+        };
+        let mut mock_executor = MockExecutor::new(schema);
+        let col1 = column_nonnull! { I64Array, [1, 1] };
+        let col2 = column_nonnull! { I64Array, [5, 7] };
+        mock_executor.add(DataChunk::builder().columns(vec![col1, col2]).build());
+        let mut insert_executor = InsertExecutor::new(
+            table_id,
+            source_manager.clone(),
+            Box::new(mock_executor),
+            0,
+            true,
+        )
+        .with_on_conflict(on_conflict);
+
+        // A row can't be updated twice by a statement.
+        insert_executor.open().await?;
+        assert!(insert_executor.next().await.is_err());
+
+        Ok(())
+    }
 }
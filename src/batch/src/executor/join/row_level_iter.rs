@@ -150,3 +150,64 @@ impl RowLevelIter {
         self.cur_row_matched
     }
 }
+
+/// Unlike [`RowLevelIter`], `StreamingRowIter` only keeps the current chunk of the data source in
+/// memory and pulls the next one when it is exhausted. It can only move forward, which is enough
+/// for joins whose inputs are already sorted on the join key.
+pub(crate) struct StreamingRowIter {
+    data_source: BoxedExecutor,
+    /// The chunk being iterated. None if the data source is exhausted.
+    cur_chunk: Option<DataChunk>,
+    /// Pos of row in current chunk.
+    row_idx: usize,
+}
+
+impl StreamingRowIter {
+    pub fn new(data_source: BoxedExecutor) -> Self {
+        Self {
+            data_source,
+            cur_chunk: None,
+            row_idx: 0,
+        }
+    }
+
+    /// Opens the data source and fetches its first chunk.
+    pub async fn open(&mut self) -> Result<()> {
+        self.data_source.open().await?;
+        self.next_chunk().await
+    }
+
+    pub async fn close(&mut self) -> Result<()> {
+        self.cur_chunk = None;
+        self.data_source.close().await
+    }
+
+    /// Fetches the next non-empty chunk from the data source.
+    async fn next_chunk(&mut self) -> Result<()> {
+        self.row_idx = 0;
+        self.cur_chunk = None;
+        while let Some(chunk) = self.data_source.next().await? {
+            if chunk.cardinality() > 0 {
+                self.cur_chunk = Some(chunk.compact()?);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_current_row_ref(&self) -> Option<RowRef<'_>> {
+        self.cur_chunk
+            .as_ref()
+            .map(|chunk| chunk.row_at_unchecked_vis(self.row_idx))
+    }
+
+    pub async fn advance_row(&mut self) -> Result<()> {
+        if let Some(chunk) = &self.cur_chunk {
+            self.row_idx += 1;
+            if self.row_idx >= chunk.capacity() {
+                self.next_chunk().await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -23,21 +23,24 @@ use risingwave_common::util::sort_util::OrderType;
 use risingwave_pb::plan::plan_node::NodeBody;
 use risingwave_pb::plan::OrderType as OrderTypeProst;
 
-use crate::executor::join::row_level_iter::RowLevelIter;
+use crate::executor::join::row_level_iter::StreamingRowIter;
 use crate::executor::join::JoinType;
 use crate::executor::{BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder};
 
 /// [`SortMergeJoinExecutor`] will not sort the data. If the join key is not sorted, optimizer
 /// should insert a sort executor above the data source.
+///
+/// Both sides are pulled chunk by chunk, so besides the current chunk of each side, only the build
+/// rows matching the current probe key are kept in memory.
 pub struct SortMergeJoinExecutor {
     /// Ascending or descending. Note that currently the sort order of probe side and build side
     /// should be the same.
     sort_order: OrderType,
     join_type: JoinType,
     /// Row-level iteration of probe side.
-    probe_side_source: RowLevelIter,
+    probe_side_source: StreamingRowIter,
     /// Row-level iteration of build side.
-    build_side_source: RowLevelIter,
+    build_side_source: StreamingRowIter,
     /// Return data chunk in batch.
     chunk_builder: DataChunkBuilder,
     /// Join result of last row. It only contains the build side. Should concatenate with probe row
@@ -60,8 +63,8 @@ pub struct SortMergeJoinExecutor {
 impl Executor for SortMergeJoinExecutor {
     async fn open(&mut self) -> risingwave_common::error::Result<()> {
         // Init data source.
-        self.probe_side_source.load_data().await?;
-        self.build_side_source.load_data().await
+        self.probe_side_source.open().await?;
+        self.build_side_source.open().await
     }
 
     /// The code logic:
@@ -89,7 +92,7 @@ impl Executor for SortMergeJoinExecutor {
                     }
                 }
                 self.last_join_results_write_idx = 0;
-                self.probe_side_source.advance_row();
+                self.probe_side_source.advance_row().await?;
                 if self.compare_with_last_row(self.probe_side_source.get_current_row_ref()) {
                     continue;
                 } else {
@@ -120,44 +123,35 @@ impl Executor for SortMergeJoinExecutor {
                     match probe_key.cmp(&build_key) {
                         Ordering::Greater => {
                             if self.sort_order == OrderType::Descending {
-                                // Before advance to next row, record last probe key.
-                                self.last_probe_key = Some(probe_key);
-                                self.probe_side_source.advance_row();
-                                if !self.compare_with_last_row(
-                                    self.probe_side_source.get_current_row_ref(),
-                                ) {
-                                    self.last_join_results.clear();
-                                }
+                                self.advance_probe_row(probe_key).await?;
                             } else {
-                                self.build_side_source.advance_row();
+                                self.build_side_source.advance_row().await?;
                             }
                         }
 
                         Ordering::Less => {
                             if self.sort_order == OrderType::Descending {
-                                self.build_side_source.advance_row();
+                                self.build_side_source.advance_row().await?;
                             } else {
-                                self.last_probe_key = Some(probe_key);
-                                self.probe_side_source.advance_row();
-                                if !self.compare_with_last_row(
-                                    self.probe_side_source.get_current_row_ref(),
-                                ) {
-                                    self.last_join_results.clear();
-                                }
+                                self.advance_probe_row(probe_key).await?;
                             }
                         }
 
+                        // Null never equals to null, so a probe row with null keys matches
+                        // nothing.
+                        Ordering::Equal if probe_key.0.iter().any(Option::is_none) => {
+                            self.advance_probe_row(probe_key).await?;
+                        }
+
                         Ordering::Equal => {
                             // Matched rows. Write into chunk builder and maintain last join
                             // results.
                             self.last_join_results
                                 .push(cur_build_row_ref.clone().into());
-                            let join_row = Self::combine_two_row_ref(
-                                cur_probe_row_ref.clone(),
-                                cur_build_row_ref.clone(),
-                            );
-                            let ret = self.chunk_builder.append_one_row_ref(join_row.clone())?;
-                            self.build_side_source.advance_row();
+                            let join_row =
+                                Self::combine_two_row_ref(cur_probe_row_ref, cur_build_row_ref);
+                            let ret = self.chunk_builder.append_one_row_ref(join_row)?;
+                            self.build_side_source.advance_row().await?;
                             if let Some(ret_chunk) = ret {
                                 return Ok(Some(ret_chunk));
                             }
@@ -166,12 +160,10 @@ impl Executor for SortMergeJoinExecutor {
                 }
 
                 (Some(cur_probe_row_ref), None) => {
-                    self.last_probe_key = Some(
-                        cur_probe_row_ref
-                            .value_by_slice(&self.probe_key_idxs)
-                            .into(),
-                    );
-                    self.probe_side_source.advance_row();
+                    let probe_key = cur_probe_row_ref
+                        .value_by_slice(&self.probe_key_idxs)
+                        .into();
+                    self.advance_probe_row(probe_key).await?;
                 }
                 // Once probe row is None, consume all results or terminate.
                 (_, _) => {
@@ -186,7 +178,8 @@ impl Executor for SortMergeJoinExecutor {
     }
 
     async fn close(&mut self) -> risingwave_common::error::Result<()> {
-        Ok(())
+        self.probe_side_source.close().await?;
+        self.build_side_source.close().await
     }
 
     fn schema(&self) -> &Schema {
@@ -202,8 +195,8 @@ impl SortMergeJoinExecutor {
     pub(super) fn new(
         join_type: JoinType,
         schema: Schema,
-        probe_side_source: RowLevelIter,
-        build_side_source: RowLevelIter,
+        probe_side_source: StreamingRowIter,
+        build_side_source: StreamingRowIter,
         probe_key_idxs: Vec<usize>,
         build_key_idxs: Vec<usize>,
         identity: String,
@@ -233,6 +226,17 @@ impl SortMergeJoinExecutor {
             })
     }
 
+    /// Moves the probe side past a row with `probe_key`. The cached join results are dropped once
+    /// the probe key changes.
+    async fn advance_probe_row(&mut self, probe_key: Row) -> risingwave_common::error::Result<()> {
+        self.last_probe_key = Some(probe_key);
+        self.probe_side_source.advance_row().await?;
+        if !self.compare_with_last_row(self.probe_side_source.get_current_row_ref()) {
+            self.last_join_results.clear();
+        }
+        Ok(())
+    }
+
     fn combine_two_row_ref<'a>(left_row: RowRef<'a>, right_row: RowRef<'a>) -> RowRef<'a> {
         let row_vec = left_row
             .0
@@ -290,8 +294,8 @@ impl BoxedExecutorBuilder for SortMergeJoinExecutor {
                 match join_type {
                     JoinType::Inner => {
                         // TODO: Support more join type.
-                        let probe_table_source = RowLevelIter::new(left_child);
-                        let build_table_source = RowLevelIter::new(right_child);
+                        let probe_table_source = StreamingRowIter::new(left_child);
+                        let build_table_source = StreamingRowIter::new(right_child);
                         Ok(Box::new(
                             Self::new(
                                 join_type,
@@ -327,7 +331,7 @@ mod tests {
     use risingwave_common::catalog::{Field, Schema};
     use risingwave_common::types::DataType;
 
-    use crate::executor::join::sort_merge_join::{RowLevelIter, SortMergeJoinExecutor};
+    use crate::executor::join::sort_merge_join::{SortMergeJoinExecutor, StreamingRowIter};
     use crate::executor::join::JoinType;
     use crate::executor::test_utils::{diff_executor_output, MockExecutor};
     use crate::executor::BoxedExecutor;
@@ -476,8 +480,8 @@ mod tests {
             Box::new(SortMergeJoinExecutor::new(
                 join_type,
                 schema,
-                RowLevelIter::new(left_child),
-                RowLevelIter::new(right_child),
+                StreamingRowIter::new(left_child),
+                StreamingRowIter::new(right_child),
                 vec![0],
                 vec![0],
                 "SortMergeJoinExecutor".to_string(),
@@ -17,9 +17,12 @@ use drop_table::*;
 use filter::*;
 use generic_exchange::*;
 use hash_agg::*;
+use hash_set_op::*;
 use limit::*;
 use merge_sort_exchange::*;
 use order_by::*;
+use peek_source::*;
+use project_set::*;
 use projection::*;
 use risingwave_common::array::DataChunk;
 use risingwave_common::catalog::Schema;
@@ -28,6 +31,7 @@ use risingwave_common::error::Result;
 use risingwave_pb::plan::plan_node::NodeBody;
 use risingwave_pb::plan::PlanNode;
 pub use row_seq_scan::*;
+use sample::*;
 use sort_agg::*;
 use top_n::*;
 
@@ -55,14 +59,18 @@ mod fuse;
 mod generate_series;
 mod generic_exchange;
 mod hash_agg;
+mod hash_set_op;
 mod insert;
 mod join;
 mod limit;
 mod merge_sort_exchange;
 pub mod monitor;
 mod order_by;
+mod peek_source;
+mod project_set;
 mod projection;
 mod row_seq_scan;
+mod sample;
 mod sort_agg;
 mod stream_scan;
 #[cfg(test)]
@@ -168,18 +176,22 @@ impl<'a> ExecutorBuilder<'a> {
             NodeBody::Exchange => ExchangeExecutor,
             NodeBody::Filter => FilterExecutor,
             NodeBody::Project => ProjectionExecutor,
+            NodeBody::ProjectSet => ProjectSetExecutor,
             NodeBody::SortAgg => SortAggExecutor,
             NodeBody::OrderBy => OrderByExecutor,
             NodeBody::CreateSource => CreateSourceExecutor,
             NodeBody::SourceScan => StreamScanExecutor,
             NodeBody::TopN => TopNExecutor,
             NodeBody::Limit => LimitExecutor,
+            NodeBody::Sample => SampleExecutor,
+            NodeBody::PeekSource => PeekSourceExecutor,
             NodeBody::Values => ValuesExecutor,
             NodeBody::NestedLoopJoin => NestedLoopJoinExecutor,
             NodeBody::HashJoin => HashJoinExecutorBuilder,
             NodeBody::SortMergeJoin => SortMergeJoinExecutor,
             NodeBody::DropSource => DropStreamExecutor,
             NodeBody::HashAgg => HashAggExecutorBuilder,
+            NodeBody::HashSetOp => HashSetOpExecutor,
             NodeBody::MergeSortExchange => MergeSortExchangeExecutor,
             NodeBody::GenerateInt32Series => GenerateSeriesI32Executor
         }?;
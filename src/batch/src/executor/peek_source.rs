@@ -0,0 +1,193 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::{Field, Schema, TableId};
+use risingwave_common::error::ErrorCode::NotImplemented;
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, NaiveDateTimeWrapper, ScalarImpl};
+use risingwave_pb::plan::plan_node::NodeBody;
+use risingwave_source::{RawSourceMessage, SourceImpl, SourceRef};
+
+use super::{BoxedExecutor, BoxedExecutorBuilder};
+use crate::executor::{Executor, ExecutorBuilder};
+
+/// `PeekSourceExecutor` reads raw messages of a source without parsing them, so that users can
+/// debug parser or schema mismatches. It doesn't affect the offsets consumed by streaming jobs.
+pub struct PeekSourceExecutor {
+    source: SourceRef,
+    limit: usize,
+    done: bool,
+    schema: Schema,
+    identity: String,
+}
+
+impl BoxedExecutorBuilder for PeekSourceExecutor {
+    fn new_boxed_executor(source: &ExecutorBuilder) -> Result<BoxedExecutor> {
+        let peek_source_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::PeekSource
+        )?;
+
+        let table_id = TableId::from(&peek_source_node.table_ref_id);
+        let source_desc = source
+            .global_batch_env()
+            .source_manager()
+            .get_source(&table_id)?;
+
+        Ok(Box::new(
+            Self {
+                source: source_desc.source,
+                limit: peek_source_node.limit as usize,
+                done: false,
+                schema: Self::peek_schema(),
+                identity: source.plan_node().get_identity().clone(),
+            }
+            .fuse(),
+        ))
+    }
+}
+
+impl PeekSourceExecutor {
+    /// The schema of raw messages, which must be kept in sync with the binder of `peek_source`.
+    fn peek_schema() -> Schema {
+        Schema::new(vec![
+            Field::with_name(DataType::Varchar, "split_id"),
+            Field::with_name(DataType::Varchar, "offset"),
+            Field::with_name(DataType::Bytea, "key"),
+            Field::with_name(DataType::Bytea, "payload"),
+            Field::with_name(DataType::Timestamp, "timestamp"),
+        ])
+    }
+
+    fn build_chunk(messages: Vec<RawSourceMessage>, schema: &Schema) -> Result<DataChunk> {
+        let rows = messages
+            .into_iter()
+            .map(|msg| {
+                let timestamp = msg.timestamp_ms.and_then(|ms| {
+                    NaiveDateTimeWrapper::new_with_secs_nsecs(
+                        ms.div_euclid(1000),
+                        (ms.rem_euclid(1000) * 1_000_000) as u32,
+                    )
+                    .ok()
+                });
+                Row(vec![
+                    Some(ScalarImpl::Utf8(msg.split_id)),
+                    Some(ScalarImpl::Utf8(msg.offset)),
+                    msg.key.map(|key| ScalarImpl::Bytea(key.into_boxed_slice())),
+                    msg.payload
+                        .map(|payload| ScalarImpl::Bytea(payload.into_boxed_slice())),
+                    timestamp.map(ScalarImpl::NaiveDateTime),
+                ])
+            })
+            .collect::<Vec<_>>();
+        DataChunk::from_rows(&rows, &schema.data_types())
+    }
+}
+
+#[async_trait::async_trait]
+impl Executor for PeekSourceExecutor {
+    async fn open(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn next(&mut self) -> Result<Option<DataChunk>> {
+        if self.done {
+            return Ok(None);
+        }
+        self.done = true;
+
+        let messages = match self.source.as_ref() {
+            SourceImpl::HighLevelKafka(k) => k.peek(self.limit).await?,
+            _ => {
+                return Err(NotImplemented(
+                    "peek_source only supports kafka sources".to_string(),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+        if messages.is_empty() {
+            return Ok(None);
+        }
+        Self::build_chunk(messages, &self.schema).map(Some)
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use risingwave_common::array::Array;
+
+    use super::*;
+
+    #[test]
+    fn test_build_chunk() {
+        let messages = vec![
+            RawSourceMessage {
+                split_id: "0".to_string(),
+                offset: "42".to_string(),
+                key: None,
+                payload: Some(b"{\"v1\": 1}".to_vec()),
+                timestamp_ms: Some(1_500),
+            },
+            RawSourceMessage {
+                split_id: "1".to_string(),
+                offset: "7".to_string(),
+                key: Some(b"k".to_vec()),
+                payload: None,
+                timestamp_ms: None,
+            },
+        ];
+        let schema = PeekSourceExecutor::peek_schema();
+        let chunk = PeekSourceExecutor::build_chunk(messages, &schema).unwrap();
+        assert_eq!(chunk.cardinality(), 2);
+        assert_eq!(
+            chunk.column_at(1).array().as_utf8().iter().collect_vec(),
+            vec![Some("42"), Some("7")]
+        );
+        assert_eq!(
+            chunk.column_at(2).array().as_bytea().iter().collect_vec(),
+            vec![None, Some(&b"k"[..])]
+        );
+        assert_eq!(
+            chunk.column_at(3).array().as_bytea().iter().collect_vec(),
+            vec![Some(&b"{\"v1\": 1}"[..]), None]
+        );
+        assert_eq!(
+            chunk
+                .column_at(4)
+                .array()
+                .as_naivedatetime()
+                .iter()
+                .collect_vec(),
+            vec![
+                Some(NaiveDateTimeWrapper::new_with_secs_nsecs(1, 500_000_000).unwrap()),
+                None
+            ]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::DataType;
+use risingwave_expr::table_function::{eval_project_set, ProjectSetSelectItem};
+use risingwave_pb::plan::plan_node::NodeBody;
+
+use super::{BoxedExecutor, BoxedExecutorBuilder};
+use crate::executor::{Executor, ExecutorBuilder};
+
+/// `ProjectSetExecutor` evaluates a select list containing table functions like `unnest`, and
+/// outputs `projected_row_id` followed by the items of the select list.
+pub(super) struct ProjectSetExecutor {
+    select_list: Vec<ProjectSetSelectItem>,
+    child: BoxedExecutor,
+    schema: Schema,
+    identity: String,
+}
+
+#[async_trait::async_trait]
+impl Executor for ProjectSetExecutor {
+    async fn open(&mut self) -> Result<()> {
+        self.child.open().await?;
+        Ok(())
+    }
+
+    async fn next(&mut self) -> Result<Option<DataChunk>> {
+        loop {
+            let child_chunk = match self.child.next().await? {
+                Some(chunk) => chunk.compact()?,
+                None => return Ok(None),
+            };
+            let (columns, _) = eval_project_set(&self.select_list, &child_chunk)?;
+            let chunk = DataChunk::builder().columns(columns).build();
+            // Skip the chunks in which all rows are expanded to empty sets.
+            if chunk.cardinality() > 0 {
+                return Ok(Some(chunk));
+            }
+        }
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.child.close().await?;
+        Ok(())
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+}
+
+impl BoxedExecutorBuilder for ProjectSetExecutor {
+    fn new_boxed_executor(source: &ExecutorBuilder) -> Result<BoxedExecutor> {
+        ensure!(source.plan_node().get_children().len() == 1);
+
+        let project_set_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::ProjectSet
+        )?;
+
+        let proto_child = source.plan_node.get_children().get(0).ok_or_else(|| {
+            RwError::from(ErrorCode::InternalError(String::from(
+                "Child interpreting error",
+            )))
+        })?;
+        let child_node = source.clone_for_plan(proto_child).build()?;
+
+        let select_list = project_set_node
+            .get_select_list()
+            .iter()
+            .map(ProjectSetSelectItem::from_prost)
+            .collect::<Result<Vec<_>>>()?;
+
+        let fields = std::iter::once(Field::unnamed(DataType::Int64))
+            .chain(
+                select_list
+                    .iter()
+                    .map(|item| Field::unnamed(item.return_type())),
+            )
+            .collect::<Vec<Field>>();
+
+        Ok(Box::new(
+            Self {
+                select_list,
+                child: child_node,
+                schema: Schema { fields },
+                identity: source.plan_node().get_identity().clone(),
+            }
+            .fuse(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::column::Column;
+    use risingwave_common::array::{Array, I32Array, ListValue};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::ScalarImpl;
+    use risingwave_expr::expr::{Expression, InputRefExpression};
+
+    use super::*;
+    use crate::executor::test_utils::MockExecutor;
+    use crate::*;
+
+    #[tokio::test]
+    async fn test_project_set_executor() -> Result<()> {
+        let list_type = DataType::List {
+            datatype: Box::new(DataType::Int32),
+        };
+        let mut list_builder = list_type.create_array_builder(2)?;
+        for values in [vec![1, 2, 3], vec![]] {
+            list_builder.append_datum(&Some(ScalarImpl::List(ListValue::new(
+                values.into_iter().map(|v| Some(v.into())).collect(),
+            ))))?;
+        }
+        let chunk = DataChunk::builder()
+            .columns(vec![
+                column_nonnull! { I32Array, [7, 8] },
+                Column::new(std::sync::Arc::new(list_builder.finish()?)),
+            ])
+            .build();
+
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(list_type.clone()),
+        ]);
+        let mut mock_executor = MockExecutor::new(schema);
+        mock_executor.add(chunk);
+
+        let mut executor = ProjectSetExecutor {
+            select_list: vec![
+                ProjectSetSelectItem::Expr(InputRefExpression::new(DataType::Int32, 0).boxed()),
+                ProjectSetSelectItem::Unnest(InputRefExpression::new(list_type, 1).boxed()),
+            ],
+            child: Box::new(mock_executor),
+            schema: schema_unnamed! { DataType::Int64, DataType::Int32, DataType::Int32 },
+            identity: "ProjectSetExecutor".to_string(),
+        };
+        executor.open().await?;
+
+        let chunk = executor.next().await?.unwrap();
+        assert_eq!(chunk.cardinality(), 3);
+        assert_eq!(
+            chunk
+                .column_at(2)
+                .array()
+                .as_int32()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(
+            chunk
+                .column_at(1)
+                .array()
+                .as_int32()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![Some(7), Some(7), Some(7)]
+        );
+        assert!(executor.next().await?.is_none());
+
+        executor.close().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,325 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::mem;
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::Result;
+use risingwave_pb::plan::plan_node::NodeBody;
+
+use super::{BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder};
+
+/// Sample executor, for `ORDER BY random() [LIMIT limit] [OFFSET offset]`.
+///
+/// With a limit, rows are picked uniformly at random from the input with reservoir sampling, so
+/// only `limit + offset` rows are kept in memory instead of sorting the whole input.
+///
+/// Without a limit, every row is returned, so rows are streamed through a shuffle buffer of
+/// `chunk_size` rows: once the buffer is full, each input row replaces a random row of the buffer,
+/// which is emitted. Memory is bounded, at the cost of rows not moving further than the buffer.
+pub(super) struct SampleExecutor {
+    child: BoxedExecutor,
+    /// limit parameter, or `None` if there's no `LIMIT`
+    limit: Option<usize>,
+    /// offset parameter
+    offset: usize,
+    chunk_size: usize,
+    rng: StdRng,
+    /// The shuffle buffer, used only without a limit.
+    buffer: Vec<Row>,
+    /// Rows ready to be emitted.
+    output: VecDeque<Row>,
+    /// Number of rows skipped for the offset so far.
+    skipped: usize,
+    /// Whether the child is exhausted.
+    child_done: bool,
+    /// Identity string of the executor
+    identity: String,
+}
+
+impl BoxedExecutorBuilder for SampleExecutor {
+    fn new_boxed_executor(source: &ExecutorBuilder) -> Result<BoxedExecutor> {
+        ensure!(source.plan_node().get_children().len() == 1);
+
+        let sample_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::Sample
+        )?;
+
+        if let Some(child_plan) = source.plan_node.get_children().get(0) {
+            let child = source.clone_for_plan(child_plan).build()?;
+            let limit = if sample_node.get_unlimited() {
+                None
+            } else {
+                Some(sample_node.get_limit() as usize)
+            };
+            return Ok(Box::new(
+                Self::new(
+                    child,
+                    limit,
+                    sample_node.get_offset() as usize,
+                    Self::DEFAULT_CHUNK_SIZE,
+                    StdRng::from_entropy(),
+                    source.plan_node().get_identity().clone(),
+                )
+                .fuse(),
+            ));
+        }
+        Err(InternalError("Sample must have one child".to_string()).into())
+    }
+}
+
+impl SampleExecutor {
+    const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+    fn new(
+        child: BoxedExecutor,
+        limit: Option<usize>,
+        offset: usize,
+        chunk_size: usize,
+        rng: StdRng,
+        identity: String,
+    ) -> Self {
+        Self {
+            child,
+            limit,
+            offset,
+            chunk_size,
+            rng,
+            buffer: Vec::new(),
+            output: VecDeque::new(),
+            skipped: 0,
+            child_done: false,
+            identity,
+        }
+    }
+
+    /// Reads the whole input into a reservoir of `limit + offset` rows.
+    async fn fill_reservoir(&mut self, limit: usize) -> Result<()> {
+        let capacity = limit.saturating_add(self.offset);
+        let mut reservoir = Vec::new();
+        let mut seen = 0usize;
+        while let Some(chunk) = self.child.next().await? {
+            for row in chunk.rows() {
+                if reservoir.len() < capacity {
+                    reservoir.push(row.into());
+                } else {
+                    let idx = self.rng.gen_range(0..=seen);
+                    if idx < capacity {
+                        reservoir[idx] = row.into();
+                    }
+                }
+                seen += 1;
+            }
+        }
+        self.child.close().await?;
+        self.child_done = true;
+
+        // The reservoir keeps the input order until it's full, so shuffle it.
+        reservoir.shuffle(&mut self.rng);
+        reservoir.into_iter().for_each(|row| self.emit(row));
+        Ok(())
+    }
+
+    /// Puts the row into the shuffle buffer, emitting a random buffered row if it's full.
+    fn push_shuffled(&mut self, row: Row) {
+        if self.buffer.len() < self.chunk_size {
+            self.buffer.push(row);
+        } else {
+            let idx = self.rng.gen_range(0..self.buffer.len());
+            let row = mem::replace(&mut self.buffer[idx], row);
+            self.emit(row);
+        }
+    }
+
+    fn emit(&mut self, row: Row) {
+        if self.skipped < self.offset {
+            self.skipped += 1;
+        } else {
+            self.output.push_back(row);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Executor for SampleExecutor {
+    async fn open(&mut self) -> Result<()> {
+        self.child.open().await?;
+        if let Some(limit) = self.limit {
+            self.fill_reservoir(limit).await?;
+        }
+        Ok(())
+    }
+
+    async fn next(&mut self) -> Result<Option<DataChunk>> {
+        while self.output.len() < self.chunk_size && !self.child_done {
+            match self.child.next().await? {
+                Some(chunk) => chunk.rows().for_each(|row| self.push_shuffled(row.into())),
+                None => {
+                    self.child.close().await?;
+                    self.child_done = true;
+                    let mut buffer = mem::take(&mut self.buffer);
+                    buffer.shuffle(&mut self.rng);
+                    buffer.into_iter().for_each(|row| self.emit(row));
+                }
+            }
+        }
+
+        if self.output.is_empty() {
+            return Ok(None);
+        }
+        let len = self.output.len().min(self.chunk_size);
+        let rows = self.output.drain(..len).collect_vec();
+        Ok(Some(DataChunk::from_rows(
+            &rows,
+            &self.schema().data_types(),
+        )?))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn schema(&self) -> &Schema {
+        self.child.schema()
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use risingwave_common::array::column::Column;
+    use risingwave_common::array::{Array, PrimitiveArray};
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::executor::test_utils::MockExecutor;
+
+    /// Samples `0..rows` and returns the values of each output chunk.
+    async fn sample_chunks(
+        rows: i32,
+        limit: Option<usize>,
+        offset: usize,
+        chunk_size: usize,
+        seed: u64,
+    ) -> Vec<Vec<i32>> {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int32)],
+        };
+        let mut mock_executor = MockExecutor::new(schema);
+        // split the input into chunks of 4 rows
+        for start in (0..rows).step_by(4) {
+            let values = (start..(start + 4).min(rows)).map(Some).collect::<Vec<_>>();
+            let array = PrimitiveArray::from_slice(&values).unwrap();
+            let column = Column::new(Arc::new(array.into()));
+            mock_executor.add(DataChunk::builder().columns(vec![column]).build());
+        }
+
+        let mut executor = SampleExecutor::new(
+            Box::new(mock_executor),
+            limit,
+            offset,
+            chunk_size,
+            StdRng::seed_from_u64(seed),
+            "SampleExecutor".to_string(),
+        );
+        executor.open().await.unwrap();
+        let mut result = vec![];
+        while let Some(chunk) = executor.next().await.unwrap() {
+            result.push(
+                chunk
+                    .column_at(0)
+                    .array()
+                    .as_int32()
+                    .iter()
+                    .flatten()
+                    .collect(),
+            );
+        }
+        executor.close().await.unwrap();
+        result
+    }
+
+    async fn sample(rows: i32, limit: usize, offset: usize, seed: u64) -> Vec<i32> {
+        sample_chunks(rows, Some(limit), offset, 1024, seed)
+            .await
+            .concat()
+    }
+
+    #[tokio::test]
+    async fn test_sample_executor() {
+        for seed in 0..8 {
+            let result = sample(100, 10, 0, seed).await;
+            assert_eq!(result.len(), 10);
+            assert_eq!(result.iter().collect::<HashSet<_>>().len(), 10);
+            assert!(result.iter().all(|v| (0..100).contains(v)));
+        }
+
+        // different seeds should not always pick the same rows
+        let mut picked = HashSet::new();
+        for seed in 0..8 {
+            picked.extend(sample(100, 10, 0, seed).await);
+        }
+        assert!(picked.len() > 10);
+
+        let mut result = sample(5, 10, 0, 0).await;
+        result.sort_unstable();
+        assert_eq!(result, vec![0, 1, 2, 3, 4]);
+
+        assert_eq!(sample(5, 10, 3, 0).await.len(), 2);
+        assert_eq!(sample(5, 10, 5, 0).await.len(), 0);
+        assert_eq!(sample(0, 10, 0, 0).await.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sample_executor_chunks() {
+        // Output is split into chunks of at most `chunk_size` rows.
+        let chunks = sample_chunks(100, Some(50), 0, 16, 0).await;
+        assert_eq!(
+            chunks.iter().map(|c| c.len()).collect::<Vec<_>>(),
+            vec![16, 16, 16, 2]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sample_executor_unlimited() {
+        // Without a limit, every row is returned, after skipping `offset`.
+        let chunks = sample_chunks(100, None, 0, 8, 0).await;
+        assert!(chunks.iter().all(|c| c.len() <= 8));
+        let mut result = chunks.concat();
+        assert_ne!(result, (0..100).collect::<Vec<_>>());
+        result.sort_unstable();
+        assert_eq!(result, (0..100).collect::<Vec<_>>());
+
+        let result = sample_chunks(100, None, 30, 8, 0).await.concat();
+        assert_eq!(result.len(), 70);
+        assert_eq!(result.iter().collect::<HashSet<_>>().len(), 70);
+
+        assert!(sample_chunks(0, None, 0, 8, 0).await.is_empty());
+    }
+}
@@ -232,6 +232,7 @@ mod tests {
                 ..Default::default()
             }),
             distinct: false,
+            order_by_fields: vec![],
         };
 
         let s = AggStateFactory::new(&prost)?.create_agg_state()?;
@@ -313,6 +314,7 @@ mod tests {
                 ..Default::default()
             }),
             distinct: false,
+            order_by_fields: vec![],
         };
 
         let s = AggStateFactory::new(&prost)?.create_agg_state()?;
@@ -31,11 +31,12 @@ struct TopNHeap {
     order_pairs: Arc<Vec<OrderPair>>,
     min_heap: BinaryHeap<Reverse<HeapElem>>,
     limit: usize,
+    offset: usize,
 }
 
 impl TopNHeap {
     fn insert(&mut self, elem: HeapElem) {
-        if self.min_heap.len() < self.limit {
+        if self.min_heap.len() < self.limit + self.offset {
             self.min_heap.push(Reverse(elem));
         } else if elem > self.min_heap.peek().unwrap().0 {
             self.min_heap.push(Reverse(elem));
@@ -69,6 +70,11 @@ impl TopNHeap {
             .map(|e| e.0.chunk)
             .collect::<Vec<_>>();
         chunks.reverse();
+        // The first `offset` rows are skipped, and each chunk holds exactly one row.
+        chunks.drain(..self.offset.min(chunks.len()));
+        if chunks.is_empty() {
+            return None;
+        }
         if let Ok(mut res) = DataChunk::rechunk(&chunks, self.limit) {
             assert_eq!(res.len(), 1);
             Some(res.remove(0))
@@ -103,6 +109,7 @@ impl BoxedExecutorBuilder for TopNExecutor {
                     child,
                     order_pairs,
                     top_n_node.get_limit() as usize,
+                    top_n_node.get_offset() as usize,
                     source.plan_node().get_identity().clone(),
                 )
                 .fuse(),
@@ -117,12 +124,14 @@ impl TopNExecutor {
         child: BoxedExecutor,
         order_pairs: Vec<OrderPair>,
         limit: usize,
+        offset: usize,
         identity: String,
     ) -> Self {
         Self {
             top_n_heap: TopNHeap {
                 min_heap: BinaryHeap::new(),
                 limit,
+                offset,
                 order_pairs: Arc::new(order_pairs),
             },
             child,
@@ -210,6 +219,7 @@ mod tests {
             Box::new(mock_executor),
             order_pairs,
             2usize,
+            0usize,
             "TopNExecutor".to_string(),
         );
         let fields = &top_n_executor.schema().fields;
@@ -228,4 +238,35 @@ mod tests {
         assert!(matches!(res, None));
         top_n_executor.close().await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_top_n_executor_with_offset() {
+        let col0 = create_column(&[Some(1), Some(2), Some(3), Some(4)]).unwrap();
+        let data_chunk = DataChunk::builder().columns(vec![col0]).build();
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int32)],
+        };
+        let mut mock_executor = MockExecutor::new(schema);
+        mock_executor.add(data_chunk);
+        let order_pairs = vec![OrderPair {
+            column_idx: 0,
+            order_type: OrderType::Descending,
+        }];
+        let mut top_n_executor = TopNExecutor::new(
+            Box::new(mock_executor),
+            order_pairs,
+            2usize,
+            1usize,
+            "TopNExecutor".to_string(),
+        );
+        top_n_executor.open().await.unwrap();
+        let res = top_n_executor.next().await.unwrap().unwrap();
+        assert_eq!(res.cardinality(), 2);
+        let col0 = res.column_at(0);
+        assert_eq!(col0.array().as_int32().value_at(0), Some(3));
+        assert_eq!(col0.array().as_int32().value_at(1), Some(2));
+        let res = top_n_executor.next().await.unwrap();
+        assert!(matches!(res, None));
+        top_n_executor.close().await.unwrap();
+    }
 }
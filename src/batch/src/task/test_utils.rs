@@ -237,6 +237,7 @@ impl<'a> TableBuilder<'a> {
             table_source_ref_id: None,
             column_ids: vec![0; self.col_types.len()],
             frontend_v2: false,
+            on_conflict: None,
         };
 
         let tuples = self
@@ -135,6 +135,12 @@ async fn main() {
         write_conflict_detection_enabled: false,
         block_cache_capacity: 256 << 20,
         meta_cache_capacity: 64 << 20,
+        block_prefetch_depth: 2,
+        block_cache_warmup_path: "".to_string(),
+        block_cache_warmup_blocks: 0,
+        sst_upload_concurrency: 4,
+        sst_upload_bandwidth: 0,
+        multipart_upload_part_size: 16 << 20,
     });
 
     let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
@@ -160,6 +166,7 @@ async fn main() {
                 sstable_store: hummock.inner().sstable_store(),
                 stats: state_store_stats.clone(),
                 is_share_buffer_compact: false,
+                sst_upload_limiter: None,
             }),
             hummock.inner().local_version_manager().clone(),
         ));
@@ -0,0 +1,137 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::sync::Arc;
+
+use clap::StructOpt;
+use pgwire::pg_response::PgResponse;
+use pgwire::pg_server::{Session, SessionManager};
+use risingwave_compute::server::compute_node_serve;
+use risingwave_compute::ComputeNodeOpts;
+use risingwave_frontend::session::SessionManagerImpl;
+use risingwave_frontend::FrontendOpts;
+use risingwave_meta::test_utils::LocalMeta;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+
+pub type BoxedError = Box<dyn Error + Send + Sync>;
+
+/// The ports the nodes of an [`EmbeddedCluster`] listen at. Clusters running at the same time,
+/// e.g. in parallel tests, should use different ports.
+#[derive(Clone, Debug)]
+pub struct EmbeddedClusterOpts {
+    pub meta_port: u16,
+    pub compute_port: u16,
+    pub frontend_port: u16,
+}
+
+impl Default for EmbeddedClusterOpts {
+    fn default() -> Self {
+        Self {
+            meta_port: 15690,
+            compute_port: 15688,
+            frontend_port: 14566,
+        }
+    }
+}
+
+/// `EmbeddedCluster` runs a meta node, a compute node with in-memory Hummock and a frontend in
+/// the current process, so that end-to-end flows like creating a source, a materialized view on it
+/// and querying the view can be tested without risedev. SQL is run through [`Self::run_sql`]
+/// instead of pgwire.
+///
+/// ```ignore
+/// let cluster = EmbeddedCluster::start(EmbeddedClusterOpts::default()).await?;
+/// cluster.run_sql("create table t (v int)").await?;
+/// cluster.run_sql("create materialized view mv as select sum(v) from t").await?;
+/// cluster.run_sql("insert into t values (1), (2)").await?;
+/// cluster.run_sql("flush").await?;
+/// assert_eq!(cluster.query("select * from mv").await?, vec![vec![Some("3".to_string())]]);
+/// cluster.stop().await;
+/// ```
+pub struct EmbeddedCluster {
+    meta: LocalMeta,
+    compute_join_handle: JoinHandle<()>,
+    compute_shutdown_sender: UnboundedSender<()>,
+    session_mgr: SessionManagerImpl,
+}
+
+impl EmbeddedCluster {
+    /// Starts the nodes one by one, and returns after all of them are ready to serve.
+    pub async fn start(opts: EmbeddedClusterOpts) -> Result<Self, BoxedError> {
+        let meta = LocalMeta::start(opts.meta_port).await;
+        let meta_addr = format!("http://{}", meta.meta_addr());
+
+        let compute_addr = format!("127.0.0.1:{}", opts.compute_port);
+        let compute_opts = ComputeNodeOpts::parse_from([
+            "compute-node",
+            "--host",
+            &compute_addr,
+            "--state-store",
+            "hummock+memory",
+            "--meta-address",
+            &meta_addr,
+        ]);
+        // The compute node is activated before `compute_node_serve` returns.
+        let (compute_join_handle, compute_shutdown_sender) =
+            compute_node_serve(compute_addr.parse()?, compute_addr.parse()?, compute_opts).await;
+
+        let frontend_opts = FrontendOpts::parse_from([
+            "frontend-node",
+            "--host",
+            &format!("127.0.0.1:{}", opts.frontend_port),
+            "--meta-addr",
+            &meta_addr,
+        ]);
+        let session_mgr = SessionManagerImpl::new(&frontend_opts).await?;
+
+        Ok(Self {
+            meta,
+            compute_join_handle,
+            compute_shutdown_sender,
+            session_mgr,
+        })
+    }
+
+    /// Opens a session on the default database.
+    pub fn session(&self) -> Result<Arc<dyn Session>, BoxedError> {
+        self.session_mgr
+            .connect(risingwave_common::catalog::DEFAULT_DATABASE_NAME)
+    }
+
+    /// Runs one SQL statement in a new session.
+    pub async fn run_sql(&self, sql: impl AsRef<str>) -> Result<PgResponse, BoxedError> {
+        self.session()?.run_statement(sql.as_ref()).await
+    }
+
+    /// Runs a query and returns the rows, where each value is in the text format of pgwire.
+    pub async fn query(
+        &self,
+        sql: impl AsRef<str>,
+    ) -> Result<Vec<Vec<Option<String>>>, BoxedError> {
+        let rsp = self.run_sql(sql).await?;
+        Ok(rsp.iter().map(|row| row.values().to_vec()).collect())
+    }
+
+    /// Stops all the nodes. Tasks spawned by the nodes that can't be shut down gracefully are
+    /// aborted.
+    pub async fn stop(self) {
+        self.session_mgr.terminate();
+        if self.compute_shutdown_sender.send(()).is_ok() {
+            let _ = self.compute_join_handle.await;
+        }
+        self.meta.stop().await;
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Besides the binaries, this crate exposes [`embedded::EmbeddedCluster`], which runs a whole
+//! cluster in the current process for end-to-end tests.
+
+#![warn(clippy::dbg_macro)]
+#![warn(clippy::doc_markdown)]
+
+pub mod embedded;
@@ -0,0 +1,48 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_cmd::embedded::{EmbeddedCluster, EmbeddedClusterOpts};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_embedded_cluster() {
+    let cluster = EmbeddedCluster::start(EmbeddedClusterOpts::default())
+        .await
+        .unwrap();
+
+    cluster
+        .run_sql("create table t (v1 int, v2 int)")
+        .await
+        .unwrap();
+    cluster
+        .run_sql("create materialized view mv as select v1, sum(v2) as s from t group by v1")
+        .await
+        .unwrap();
+    cluster
+        .run_sql("insert into t values (1, 10), (2, 20), (1, 30)")
+        .await
+        .unwrap();
+    cluster.run_sql("flush").await.unwrap();
+
+    let mut rows = cluster.query("select v1, s from mv").await.unwrap();
+    rows.sort();
+    assert_eq!(
+        rows,
+        vec![
+            vec![Some("1".to_string()), Some("40".to_string())],
+            vec![Some("2".to_string()), Some("20".to_string())],
+        ]
+    );
+
+    cluster.stop().await;
+}
@@ -0,0 +1,49 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use risingwave_common::array::{ArrayBuilder, Utf8ArrayBuilder};
+
+const CHUNK_SIZE: usize = 1024;
+
+fn build_array(values: &[String]) {
+    let mut builder = Utf8ArrayBuilder::new(values.len()).unwrap();
+    for value in values {
+        builder.append(Some(value.as_str())).unwrap();
+    }
+    builder.finish().unwrap();
+}
+
+fn bench_utf8_array_finish(c: &mut Criterion) {
+    // Low cardinality arrays are dictionary encoded, while the others are rejected either by the
+    // sample or by the full scan.
+    for distinct in [4, 64, CHUNK_SIZE / 8, CHUNK_SIZE] {
+        let values = (0..CHUNK_SIZE)
+            .map(|i| format!("value-{:08}", i % distinct))
+            .collect::<Vec<_>>();
+        c.bench_with_input(
+            BenchmarkId::new(
+                format!("utf8 array - build - {} rows", CHUNK_SIZE),
+                format!("{} distinct", distinct),
+            ),
+            &values,
+            |b, values| {
+                b.iter(|| build_array(values));
+            },
+        );
+    }
+}
+
+criterion_group!(benches, bench_utf8_array_finish);
+criterion_main!(benches);
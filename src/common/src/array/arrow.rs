@@ -0,0 +1,648 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between RisingWave arrays and [Apache Arrow](https://arrow.apache.org) arrays, so
+//! that chunks can be exchanged with Arrow-based systems, e.g. through Arrow IPC.
+//!
+//! | RisingWave              | Arrow                                         |
+//! |-------------------------|-----------------------------------------------|
+//! | `boolean`               | `Boolean`                                     |
+//! | `smallint/int/bigint`   | `Int16/Int32/Int64`                           |
+//! | `real/double`           | `Float32/Float64`                             |
+//! | `decimal`               | `Decimal(38, s)`, `s` is the largest scale    |
+//! | `date`                  | `Date32`                                      |
+//! | `time`                  | `Time64(Microsecond)`                         |
+//! | `timestamp`             | `Timestamp(Microsecond, None)`                |
+//! | `interval`              | `Interval(MonthDayNano)`                      |
+//! | `varchar`               | `Utf8`                                        |
+//! | `bytea`                 | `Binary`                                      |
+//! | `struct`                | `Struct`                                      |
+//! | `list`                  | `List`                                        |
+//!
+//! Arrays do not keep their logical type, so `timestamp with time zone` is converted as `bigint`.
+//! Conversely, `Timestamp` with a time zone is read as `timestamp with time zone`. `bit varying`
+//! has no Arrow counterpart that reads back as itself, so it is not supported.
+
+use std::sync::Arc;
+
+use arrow::array::{self as arrow_array, Array as ArrowArray, ArrayRef as ArrowArrayRef};
+use arrow::buffer::Buffer;
+use arrow::datatypes::{
+    DataType as ArrowDataType, Field, IntervalUnit as ArrowIntervalUnit, Schema, TimeUnit,
+};
+use arrow::record_batch::RecordBatch;
+use chrono::{Datelike, Timelike};
+use itertools::Itertools;
+
+use crate::array::column::Column;
+use crate::array::{
+    Array, ArrayImpl, BoolArray, BytesArray, DataChunk, DecimalArray, F32Array, F64Array, I16Array,
+    I32Array, I64Array, IntervalArray, ListArray, NaiveDateArray, NaiveDateTimeArray,
+    NaiveTimeArray, Op, StreamChunk, StructArray, Utf8Array,
+};
+use crate::buffer::Bitmap;
+use crate::error::ErrorCode::{InternalError, NotImplemented};
+use crate::error::Result;
+use crate::types::{
+    DataType, Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper,
+    UNIX_EPOCH_DAYS,
+};
+
+/// Precision of the Arrow decimals converted from RisingWave decimals, which is the largest one
+/// supported by Arrow.
+const ARROW_DECIMAL_PRECISION: usize = 38;
+
+/// Name of the column holding the [`Op`]s, when a [`StreamChunk`] is converted to a record batch.
+pub const ARROW_OP_COLUMN_NAME: &str = "__op";
+
+/// Downcasts an Arrow array to its concrete type.
+fn downcast<T: 'static>(array: &dyn ArrowArray) -> Result<&T> {
+    array.as_any().downcast_ref::<T>().ok_or_else(|| {
+        InternalError(format!(
+            "failed to downcast arrow array of type {:?}",
+            array.data_type()
+        ))
+        .into()
+    })
+}
+
+/// Converts the null bitmap of a RisingWave array to the validity buffer of an Arrow array.
+pub(super) fn to_arrow_null_buffer(bitmap: &Bitmap) -> Buffer {
+    let mut builder = arrow_array::BooleanBufferBuilder::new(bitmap.len());
+    for valid in bitmap.iter() {
+        builder.append(valid);
+    }
+    builder.finish()
+}
+
+/// Converts the validity of an Arrow array to the null bitmap of a RisingWave array.
+pub(super) fn from_arrow_null_bitmap(array: &dyn ArrowArray) -> Result<Bitmap> {
+    Bitmap::try_from(
+        (0..array.len())
+            .map(|idx| array.is_valid(idx))
+            .collect_vec(),
+    )
+}
+
+/// Packs an interval as the `i128` of an Arrow `MonthDayNano` interval, i.e. months, days and
+/// nanoseconds from the most significant bits.
+fn to_arrow_interval(interval: IntervalUnit) -> i128 {
+    let months = interval.get_months() as u32 as u128;
+    let days = interval.get_days() as u32 as u128;
+    let nanos = (interval.get_ms() * 1_000_000) as u64 as u128;
+    ((months << 96) | (days << 64) | nanos) as i128
+}
+
+fn from_arrow_interval(value: i128) -> IntervalUnit {
+    let value = value as u128;
+    let months = (value >> 96) as u32 as i32;
+    let days = (value >> 64) as u32 as i32;
+    let nanos = value as u64 as i64;
+    IntervalUnit::new(months, days, nanos / 1_000_000)
+}
+
+fn to_arrow_decimal_array(array: &DecimalArray) -> Result<ArrowArrayRef> {
+    let scale = array.iter().flatten().map(|v| v.scale()).max().unwrap_or(0) as usize;
+    let mut builder = arrow_array::DecimalBuilder::new(array.len(), ARROW_DECIMAL_PRECISION, scale);
+    for value in array.iter() {
+        match value {
+            Some(value @ Decimal::Normalized(_)) => {
+                let mantissa = 10i128
+                    .checked_pow((scale as i32 - value.scale()) as u32)
+                    .and_then(|factor| value.mantissa().checked_mul(factor))
+                    .ok_or_else(|| {
+                        InternalError(format!(
+                            "decimal {} is out of range of arrow decimal with scale {}",
+                            value, scale
+                        ))
+                    })?;
+                builder.append_value(mantissa)?;
+            }
+            Some(value) => {
+                return Err(
+                    InternalError(format!("decimal {} is not supported by arrow", value)).into(),
+                )
+            }
+            None => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+impl ArrayImpl {
+    /// Converts the array to an Arrow array, keeping the nulls.
+    pub fn to_arrow(&self) -> Result<ArrowArrayRef> {
+        let array: ArrowArrayRef = match self {
+            ArrayImpl::Bool(array) => Arc::new(arrow_array::BooleanArray::from_iter(array.iter())),
+            ArrayImpl::Int16(array) => Arc::new(arrow_array::Int16Array::from_iter(array.iter())),
+            ArrayImpl::Int32(array) => Arc::new(arrow_array::Int32Array::from_iter(array.iter())),
+            ArrayImpl::Int64(array) => Arc::new(arrow_array::Int64Array::from_iter(array.iter())),
+            ArrayImpl::Float32(array) => Arc::new(arrow_array::Float32Array::from_iter(
+                array.iter().map(|v| v.map(|v| v.0)),
+            )),
+            ArrayImpl::Float64(array) => Arc::new(arrow_array::Float64Array::from_iter(
+                array.iter().map(|v| v.map(|v| v.0)),
+            )),
+            ArrayImpl::Decimal(array) => to_arrow_decimal_array(array)?,
+            ArrayImpl::NaiveDate(array) => Arc::new(arrow_array::Date32Array::from_iter(
+                array
+                    .iter()
+                    .map(|v| v.map(|v| v.0.num_days_from_ce() - UNIX_EPOCH_DAYS)),
+            )),
+            ArrayImpl::NaiveTime(array) => Arc::new(
+                arrow_array::Time64MicrosecondArray::from_iter(array.iter().map(|v| {
+                    v.map(|v| {
+                        v.0.num_seconds_from_midnight() as i64 * 1_000_000
+                            + v.0.nanosecond() as i64 / 1000
+                    })
+                })),
+            ),
+            ArrayImpl::NaiveDateTime(array) => {
+                Arc::new(arrow_array::TimestampMicrosecondArray::from_iter(
+                    array
+                        .iter()
+                        .map(|v| v.map(|v| v.0.timestamp_nanos() / 1000)),
+                ))
+            }
+            ArrayImpl::Interval(array) => {
+                Arc::new(arrow_array::IntervalMonthDayNanoArray::from_iter(
+                    array.iter().map(|v| v.map(to_arrow_interval)),
+                ))
+            }
+            ArrayImpl::Utf8(array) => Arc::new(arrow_array::StringArray::from_iter(array.iter())),
+            ArrayImpl::Bytea(array) => Arc::new(arrow_array::BinaryArray::from_iter(array.iter())),
+            ArrayImpl::BitString(_) => {
+                return Err(NotImplemented(
+                    "converting bit varying array to arrow".to_string(),
+                    None.into(),
+                )
+                .into())
+            }
+            ArrayImpl::Struct(array) => array.to_arrow()?,
+            ArrayImpl::List(array) => array.to_arrow()?,
+        };
+        Ok(array)
+    }
+
+    /// Converts an Arrow array to a RisingWave array, keeping the nulls.
+    pub fn from_arrow(array: &dyn ArrowArray) -> Result<Self> {
+        let array = match array.data_type() {
+            ArrowDataType::Boolean => {
+                let array = downcast::<arrow_array::BooleanArray>(array)?;
+                BoolArray::from_slice(&array.iter().collect_vec())?.into()
+            }
+            ArrowDataType::Int16 => {
+                let array = downcast::<arrow_array::Int16Array>(array)?;
+                I16Array::from_slice(&array.iter().collect_vec())?.into()
+            }
+            ArrowDataType::Int32 => {
+                let array = downcast::<arrow_array::Int32Array>(array)?;
+                I32Array::from_slice(&array.iter().collect_vec())?.into()
+            }
+            ArrowDataType::Int64 => {
+                let array = downcast::<arrow_array::Int64Array>(array)?;
+                I64Array::from_slice(&array.iter().collect_vec())?.into()
+            }
+            ArrowDataType::Float32 => {
+                let array = downcast::<arrow_array::Float32Array>(array)?;
+                F32Array::from_slice(&array.iter().map(|v| v.map(Into::into)).collect_vec())?.into()
+            }
+            ArrowDataType::Float64 => {
+                let array = downcast::<arrow_array::Float64Array>(array)?;
+                F64Array::from_slice(&array.iter().map(|v| v.map(Into::into)).collect_vec())?.into()
+            }
+            ArrowDataType::Decimal(_, scale) => {
+                let array = downcast::<arrow_array::DecimalArray>(array)?;
+                let values = (0..array.len())
+                    .map(|idx| {
+                        (!array.is_null(idx))
+                            .then(|| Decimal::from_i128_with_scale(array.value(idx), *scale as u32))
+                    })
+                    .collect_vec();
+                DecimalArray::from_slice(&values)?.into()
+            }
+            ArrowDataType::Date32 => {
+                let array = downcast::<arrow_array::Date32Array>(array)?;
+                let values: Vec<_> = array
+                    .iter()
+                    .map(|v| v.map(NaiveDateWrapper::from_protobuf).transpose())
+                    .try_collect()?;
+                NaiveDateArray::from_slice(&values)?.into()
+            }
+            ArrowDataType::Time64(TimeUnit::Microsecond) => {
+                let array = downcast::<arrow_array::Time64MicrosecondArray>(array)?;
+                let values: Vec<_> = array
+                    .iter()
+                    .map(|v| v.map(NaiveTimeWrapper::from_protobuf).transpose())
+                    .try_collect()?;
+                NaiveTimeArray::from_slice(&values)?.into()
+            }
+            ArrowDataType::Timestamp(unit, tz) => {
+                let micros = match unit {
+                    TimeUnit::Second => {
+                        let array = downcast::<arrow_array::TimestampSecondArray>(array)?;
+                        array.iter().map(|v| v.map(|v| v * 1_000_000)).collect_vec()
+                    }
+                    TimeUnit::Millisecond => {
+                        let array = downcast::<arrow_array::TimestampMillisecondArray>(array)?;
+                        array.iter().map(|v| v.map(|v| v * 1000)).collect_vec()
+                    }
+                    TimeUnit::Microsecond => {
+                        let array = downcast::<arrow_array::TimestampMicrosecondArray>(array)?;
+                        array.iter().collect_vec()
+                    }
+                    TimeUnit::Nanosecond => {
+                        let array = downcast::<arrow_array::TimestampNanosecondArray>(array)?;
+                        array.iter().map(|v| v.map(|v| v / 1000)).collect_vec()
+                    }
+                };
+                if tz.is_some() {
+                    I64Array::from_slice(&micros)?.into()
+                } else {
+                    let values: Vec<_> = micros
+                        .into_iter()
+                        .map(|v| v.map(NaiveDateTimeWrapper::from_protobuf).transpose())
+                        .try_collect()?;
+                    NaiveDateTimeArray::from_slice(&values)?.into()
+                }
+            }
+            ArrowDataType::Interval(ArrowIntervalUnit::MonthDayNano) => {
+                let array = downcast::<arrow_array::IntervalMonthDayNanoArray>(array)?;
+                IntervalArray::from_slice(
+                    &array
+                        .iter()
+                        .map(|v| v.map(from_arrow_interval))
+                        .collect_vec(),
+                )?
+                .into()
+            }
+            ArrowDataType::Utf8 => {
+                let array = downcast::<arrow_array::StringArray>(array)?;
+                Utf8Array::from_slice(&array.iter().collect_vec())?.into()
+            }
+            ArrowDataType::Binary => {
+                let array = downcast::<arrow_array::BinaryArray>(array)?;
+                BytesArray::from_slice(&array.iter().collect_vec())?.into()
+            }
+            ArrowDataType::Struct(_) => {
+                StructArray::from_arrow(downcast::<arrow_array::StructArray>(array)?)?
+            }
+            ArrowDataType::List(_) => {
+                ListArray::from_arrow(downcast::<arrow_array::ListArray>(array)?)?
+            }
+            other => {
+                return Err(NotImplemented(
+                    format!("converting arrow array of type {:?}", other),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+        Ok(array)
+    }
+}
+
+/// Returns the RisingWave type of the arrays converted from Arrow arrays of `data_type`.
+pub fn from_arrow_data_type(data_type: &ArrowDataType) -> Result<DataType> {
+    let data_type = match data_type {
+        ArrowDataType::Boolean => DataType::Boolean,
+        ArrowDataType::Int16 => DataType::Int16,
+        ArrowDataType::Int32 => DataType::Int32,
+        ArrowDataType::Int64 => DataType::Int64,
+        ArrowDataType::Float32 => DataType::Float32,
+        ArrowDataType::Float64 => DataType::Float64,
+        ArrowDataType::Decimal(_, _) => DataType::Decimal,
+        ArrowDataType::Date32 => DataType::Date,
+        ArrowDataType::Time64(TimeUnit::Microsecond) => DataType::Time,
+        ArrowDataType::Timestamp(_, None) => DataType::Timestamp,
+        ArrowDataType::Timestamp(_, Some(_)) => DataType::Timestampz,
+        ArrowDataType::Interval(ArrowIntervalUnit::MonthDayNano) => DataType::Interval,
+        ArrowDataType::Utf8 => DataType::Varchar,
+        ArrowDataType::Binary => DataType::Bytea,
+        ArrowDataType::Struct(fields) => DataType::Struct {
+            fields: fields
+                .iter()
+                .map(|field| from_arrow_data_type(field.data_type()))
+                .try_collect::<_, Vec<_>, _>()?
+                .into(),
+        },
+        ArrowDataType::List(field) => DataType::List {
+            datatype: Box::new(from_arrow_data_type(field.data_type())?),
+        },
+        other => {
+            return Err(NotImplemented(
+                format!("converting arrow array of type {:?}", other),
+                None.into(),
+            )
+            .into())
+        }
+    };
+    Ok(data_type)
+}
+
+impl Column {
+    pub fn to_arrow(&self) -> Result<ArrowArrayRef> {
+        self.array_ref().to_arrow()
+    }
+
+    pub fn from_arrow(array: &dyn ArrowArray) -> Result<Self> {
+        Ok(Column::new(Arc::new(ArrayImpl::from_arrow(array)?)))
+    }
+}
+
+/// Builds a record batch from `columns`, which are named by their indexes.
+fn columns_to_record_batch(columns: Vec<ArrowArrayRef>) -> Result<RecordBatch> {
+    let fields = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, array)| Field::new(&idx.to_string(), array.data_type().clone(), true))
+        .collect();
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+impl DataChunk {
+    /// Converts the visible rows of the chunk to an Arrow record batch. The columns are named by
+    /// their indexes.
+    pub fn to_arrow(&self) -> Result<RecordBatch> {
+        let columns = self
+            .clone()
+            .compact()?
+            .columns()
+            .iter()
+            .map(|column| column.to_arrow())
+            .try_collect()?;
+        columns_to_record_batch(columns)
+    }
+
+    pub fn from_arrow(batch: &RecordBatch) -> Result<Self> {
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|array| Column::from_arrow(&**array))
+            .try_collect()?;
+        Ok(DataChunk::new(columns, None))
+    }
+}
+
+impl StreamChunk {
+    /// Converts the visible rows of the chunk to an Arrow record batch. The ops are converted to
+    /// their protobuf values in the first column, named [`ARROW_OP_COLUMN_NAME`], followed by the
+    /// data columns.
+    pub fn to_arrow(&self) -> Result<RecordBatch> {
+        let chunk = self.clone().compact()?;
+        let ops: ArrowArrayRef = Arc::new(arrow_array::Int32Array::from_iter_values(
+            chunk.ops().iter().map(|op| op.to_protobuf() as i32),
+        ));
+        let columns = chunk
+            .columns()
+            .iter()
+            .map(|column| column.to_arrow())
+            .try_collect::<_, Vec<_>, _>()?;
+        let mut fields = vec![Field::new(
+            ARROW_OP_COLUMN_NAME,
+            ArrowDataType::Int32,
+            false,
+        )];
+        fields.extend(
+            columns
+                .iter()
+                .enumerate()
+                .map(|(idx, array)| Field::new(&idx.to_string(), array.data_type().clone(), true)),
+        );
+        let columns = std::iter::once(ops).chain(columns).collect();
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    pub fn from_arrow(batch: &RecordBatch) -> Result<Self> {
+        let (ops, columns) = match batch.columns().split_first() {
+            Some((ops, columns)) if batch.schema().field(0).name() == ARROW_OP_COLUMN_NAME => {
+                (ops, columns)
+            }
+            _ => {
+                return Err(InternalError(format!(
+                    "the first column of a stream chunk must be {}",
+                    ARROW_OP_COLUMN_NAME
+                ))
+                .into())
+            }
+        };
+        let ops = downcast::<arrow_array::Int32Array>(&**ops)?
+            .values()
+            .iter()
+            .map(Op::from_protobuf)
+            .try_collect()?;
+        let columns = columns
+            .iter()
+            .map(|array| Column::from_arrow(&**array))
+            .try_collect()?;
+        Ok(StreamChunk::new(ops, columns, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::BitStringArray;
+    use crate::types::{BitString, OrderedF64, Scalar, ScalarImpl};
+
+    fn assert_round_trip(array: ArrayImpl) {
+        let arrow_array = array.to_arrow().unwrap();
+        assert_eq!(arrow_array.len(), array.len());
+        assert_eq!(
+            arrow_array.null_count(),
+            array.len() - array.iter().flatten().count()
+        );
+        let converted = ArrayImpl::from_arrow(&*arrow_array).unwrap();
+        assert_eq!(
+            array
+                .iter()
+                .map(|v| v.map(|v| v.into_scalar_impl()))
+                .collect_vec(),
+            converted
+                .iter()
+                .map(|v| v.map(|v| v.into_scalar_impl()))
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn test_arrow_primitive_round_trip() {
+        assert_round_trip(
+            BoolArray::from_slice(&[Some(true), None, Some(false)])
+                .unwrap()
+                .into(),
+        );
+        assert_round_trip(
+            I16Array::from_slice(&[Some(1), None, Some(-3)])
+                .unwrap()
+                .into(),
+        );
+        assert_round_trip(
+            I64Array::from_slice(&[None, Some(i64::MAX)])
+                .unwrap()
+                .into(),
+        );
+        assert_round_trip(
+            F64Array::from_slice(&[Some(OrderedF64::from(1.5)), None])
+                .unwrap()
+                .into(),
+        );
+        assert_round_trip(
+            DecimalArray::from_slice(&[
+                Some(Decimal::new(12345, 2)),
+                None,
+                Some(Decimal::new(-1, 4)),
+            ])
+            .unwrap()
+            .into(),
+        );
+        assert_round_trip(
+            NaiveDateArray::from_slice(&[
+                Some(NaiveDateWrapper::from_protobuf(19000).unwrap()),
+                None,
+            ])
+            .unwrap()
+            .into(),
+        );
+        assert_round_trip(
+            NaiveTimeArray::from_slice(&[Some(
+                NaiveTimeWrapper::from_protobuf(3_600_000_001).unwrap(),
+            )])
+            .unwrap()
+            .into(),
+        );
+        assert_round_trip(
+            NaiveDateTimeArray::from_slice(&[
+                None,
+                Some(NaiveDateTimeWrapper::from_protobuf(1_600_000_000_000_123).unwrap()),
+            ])
+            .unwrap()
+            .into(),
+        );
+        assert_round_trip(
+            IntervalArray::from_slice(&[Some(IntervalUnit::new(-14, 3, 86_400_001)), None])
+                .unwrap()
+                .into(),
+        );
+        assert_round_trip(
+            Utf8Array::from_slice(&[Some("a"), None, Some("")])
+                .unwrap()
+                .into(),
+        );
+        assert_round_trip(
+            BytesArray::from_slice(&[Some(&b"\x00\xff"[..]), None])
+                .unwrap()
+                .into(),
+        );
+    }
+
+    #[test]
+    fn test_arrow_nested_round_trip() {
+        let struct_array = StructArray::from_slices(
+            &[true, false, true],
+            vec![
+                I32Array::from_slice(&[Some(1), None, Some(3)])
+                    .unwrap()
+                    .into(),
+                Utf8Array::from_slice(&[Some("a"), None, None])
+                    .unwrap()
+                    .into(),
+            ],
+            vec![DataType::Int32, DataType::Varchar],
+        )
+        .unwrap();
+        assert_round_trip(struct_array.into());
+
+        let list_array = ListArray::from_slices(
+            &[true, false, true],
+            vec![
+                Some(I64Array::from_slice(&[Some(1), None]).unwrap().into()),
+                None,
+                Some(I64Array::from_slice(&[Some(3)]).unwrap().into()),
+            ],
+            DataType::Int64,
+        )
+        .unwrap();
+        assert_round_trip(list_array.into());
+    }
+
+    #[test]
+    fn test_arrow_unsupported() {
+        let array: ArrayImpl = DecimalArray::from_slice(&[Some(Decimal::NaN)])
+            .unwrap()
+            .into();
+        assert!(array.to_arrow().is_err());
+
+        let bits: BitString = "101".parse().unwrap();
+        let array: ArrayImpl = BitStringArray::from_slice(&[Some(bits.as_scalar_ref())])
+            .unwrap()
+            .into();
+        assert!(array.to_arrow().is_err());
+    }
+
+    #[test]
+    fn test_data_chunk_arrow() {
+        let chunk = DataChunk::new(
+            vec![
+                I32Array::from_slice(&[Some(1), Some(2), None])
+                    .unwrap()
+                    .into(),
+                Utf8Array::from_slice(&[Some("a"), None, Some("c")])
+                    .unwrap()
+                    .into(),
+            ],
+            Some(Bitmap::try_from(vec![true, false, true]).unwrap()),
+        );
+        let batch = chunk.to_arrow().unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 2);
+
+        let converted = DataChunk::from_arrow(&batch).unwrap();
+        assert_eq!(converted.cardinality(), 2);
+        assert_eq!(converted.column_at(0).array_ref().datum_at(1), None);
+        assert_eq!(
+            converted.column_at(1).array_ref().datum_at(1),
+            Some(ScalarImpl::Utf8("c".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_stream_chunk_arrow() {
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::UpdateDelete, Op::UpdateInsert],
+            vec![I64Array::from_slice(&[Some(1), Some(2), Some(3)])
+                .unwrap()
+                .into()],
+            None,
+        );
+        let batch = chunk.to_arrow().unwrap();
+        assert_eq!(batch.schema().field(0).name(), ARROW_OP_COLUMN_NAME);
+
+        let converted = StreamChunk::from_arrow(&batch).unwrap();
+        assert_eq!(converted.ops(), chunk.ops());
+        assert_eq!(
+            converted.column_at(0).array_ref().datum_at(2),
+            Some(ScalarImpl::Int64(3))
+        );
+
+        let data_batch = chunk.clone().into_parts().0.to_arrow().unwrap();
+        assert!(StreamChunk::from_arrow(&data_batch).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::hash::{Hash, Hasher};
+use std::io::{Cursor, Read};
+
+use byteorder::{BigEndian, ReadBytesExt};
+use risingwave_pb::data::buffer::CompressionType;
+use risingwave_pb::data::{Array as ProstArray, ArrayType, Buffer};
+
+use super::{Array, ArrayBuilder, ArrayImpl, ArrayIterator, ArrayMeta, NULL_VAL_FOR_HASH};
+use crate::array::ArrayBuilderImpl;
+use crate::buffer::{Bitmap, BitmapBuilder};
+use crate::error::ErrorCode::InternalError;
+use crate::error::Result;
+use crate::types::{BitString, BitStringRef, Scalar};
+
+/// `BitStringArray` is a collection of [`BitString`]s. The bits of each element are packed into
+/// `data` starting at a byte boundary, with `offset` pointing to the bytes and `len` keeping the
+/// number of bits.
+#[derive(Debug)]
+pub struct BitStringArray {
+    offset: Vec<usize>,
+    len: Vec<usize>,
+    bitmap: Bitmap,
+    data: Vec<u8>,
+}
+
+impl Array for BitStringArray {
+    type Builder = BitStringArrayBuilder;
+    type Iter<'a> = ArrayIterator<'a, Self>;
+    type OwnedItem = BitString;
+    type RefItem<'a> = BitStringRef<'a>;
+
+    fn value_at(&self, idx: usize) -> Option<BitStringRef<'_>> {
+        if !self.is_null(idx) {
+            Some(BitStringRef::new(
+                &self.data[self.offset[idx]..self.offset[idx + 1]],
+                self.len[idx],
+            ))
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len.len()
+    }
+
+    fn iter(&self) -> ArrayIterator<'_, Self> {
+        ArrayIterator::new(self)
+    }
+
+    /// The only buffer holds every non-null element as its number of bits in `u32`, followed by
+    /// its packed bytes.
+    fn to_protobuf(&self) -> ProstArray {
+        let mut buffer = Vec::with_capacity(self.data.len() + self.len() * 4);
+        for bits in self.iter().flatten() {
+            buffer.extend_from_slice(&(bits.len() as u32).to_be_bytes());
+            buffer.extend_from_slice(bits.bits());
+        }
+        let values = vec![Buffer {
+            compression: CompressionType::None as i32,
+            body: buffer,
+        }];
+        let null_bitmap = self.null_bitmap().to_protobuf();
+        ProstArray {
+            null_bitmap: Some(null_bitmap),
+            values,
+            array_type: ArrayType::BitString as i32,
+            struct_array_data: None,
+            list_array_data: None,
+            dictionary_data: None,
+            suppressed_null_bitmap: None,
+        }
+    }
+
+    fn null_bitmap(&self) -> &Bitmap {
+        &self.bitmap
+    }
+
+    fn set_bitmap(&mut self, bitmap: Bitmap) {
+        self.bitmap = bitmap;
+    }
+
+    #[inline(always)]
+    fn hash_at<H: Hasher>(&self, idx: usize, state: &mut H) {
+        if let Some(bits) = self.value_at(idx) {
+            bits.hash(state);
+        } else {
+            NULL_VAL_FOR_HASH.hash(state);
+        }
+    }
+
+    fn create_builder(&self, capacity: usize) -> Result<ArrayBuilderImpl> {
+        let array_builder = BitStringArrayBuilder::new(capacity)?;
+        Ok(ArrayBuilderImpl::BitString(array_builder))
+    }
+}
+
+impl BitStringArray {
+    pub fn from_protobuf(array: &ProstArray, cardinality: usize) -> Result<ArrayImpl> {
+        ensure!(
+            array.get_values().len() == 1,
+            "Must have only 1 buffer in a bit string array"
+        );
+        let buf = array.get_values()[0].get_body().as_slice();
+        let bitmap: Bitmap = array.get_null_bitmap()?.try_into()?;
+        let mut builder = BitStringArrayBuilder::new(cardinality)?;
+        let mut cursor = Cursor::new(buf);
+        let mut bytes = vec![];
+        for not_null in bitmap.iter() {
+            if not_null {
+                let len = cursor.read_u32::<BigEndian>().map_err(|e| {
+                    InternalError(format!("failed to read bit string length: {}", e))
+                })? as usize;
+                bytes.resize((len + 7) / 8, 0);
+                cursor.read_exact(&mut bytes).map_err(|e| {
+                    InternalError(format!(
+                        "failed to read bit string from data buffer: {} [length={}]",
+                        e, len
+                    ))
+                })?;
+                builder.append(Some(BitStringRef::new(&bytes, len)))?;
+            } else {
+                builder.append(None)?;
+            }
+        }
+        Ok(builder.finish()?.into())
+    }
+}
+
+impl BitStringArray {
+    pub fn from_slice(data: &[Option<BitStringRef<'_>>]) -> Result<Self> {
+        let mut builder = <Self as Array>::Builder::new(data.len())?;
+        for i in data {
+            builder.append(*i)?;
+        }
+        builder.finish()
+    }
+}
+
+/// `BitStringArrayBuilder` use `BitStringRef` to build an `BitStringArray`.
+#[derive(Debug)]
+pub struct BitStringArrayBuilder {
+    offset: Vec<usize>,
+    len: Vec<usize>,
+    bitmap: BitmapBuilder,
+    data: Vec<u8>,
+}
+
+impl ArrayBuilder for BitStringArrayBuilder {
+    type ArrayType = BitStringArray;
+
+    fn new_with_meta(capacity: usize, _meta: ArrayMeta) -> Result<Self> {
+        let mut offset = Vec::with_capacity(capacity + 1);
+        offset.push(0);
+        Ok(Self {
+            offset,
+            len: Vec::with_capacity(capacity),
+            data: Vec::with_capacity(capacity),
+            bitmap: BitmapBuilder::with_capacity(capacity),
+        })
+    }
+
+    fn append<'a>(&'a mut self, value: Option<BitStringRef<'a>>) -> Result<()> {
+        match value {
+            Some(x) => {
+                self.bitmap.append(true);
+                self.data.extend_from_slice(x.bits());
+                self.len.push(x.len());
+            }
+            None => {
+                self.bitmap.append(false);
+                self.len.push(0);
+            }
+        }
+        self.offset.push(self.data.len());
+        Ok(())
+    }
+
+    fn append_array(&mut self, other: &BitStringArray) -> Result<()> {
+        for bit in other.bitmap.iter() {
+            self.bitmap.append(bit);
+        }
+        self.data.extend_from_slice(&other.data);
+        self.len.extend_from_slice(&other.len);
+        let start = *self.offset.last().unwrap();
+        for other_offset in &other.offset[1..] {
+            self.offset.push(*other_offset + start);
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<BitStringArray> {
+        Ok(BitStringArray {
+            bitmap: (self.bitmap).finish(),
+            data: self.data,
+            offset: self.offset,
+            len: self.len,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_bit_string_array() {
+        let values = ["1", "", "0000000011", "10110"]
+            .map(|s| s.parse::<BitString>().unwrap())
+            .to_vec();
+        let input = vec![
+            Some(values[0].as_scalar_ref()),
+            None,
+            Some(values[1].as_scalar_ref()),
+            Some(values[2].as_scalar_ref()),
+            None,
+            Some(values[3].as_scalar_ref()),
+        ];
+
+        let array = BitStringArray::from_slice(&input).unwrap();
+        assert_eq!(array.len(), input.len());
+        assert_eq!(array.data.len(), 1 + 2 + 1);
+        assert_eq!(input, array.iter().collect_vec());
+
+        let mut builder = BitStringArrayBuilder::new(0).unwrap();
+        builder.append_array(&array).unwrap();
+        builder.append_array(&array).unwrap();
+        let array = builder.finish().unwrap();
+        assert_eq!(array.len(), input.len() * 2);
+        assert_eq!(array.value_at(9).unwrap().to_string(), "0000000011");
+    }
+
+    #[test]
+    fn test_bit_string_array_protobuf_conversion() {
+        let values = ["1", "0000000011", "", "111000111000111"]
+            .map(|s| s.parse::<BitString>().unwrap())
+            .to_vec();
+        let input = vec![
+            None,
+            Some(values[0].as_scalar_ref()),
+            Some(values[1].as_scalar_ref()),
+            None,
+            Some(values[2].as_scalar_ref()),
+            Some(values[3].as_scalar_ref()),
+        ];
+        let array = BitStringArray::from_slice(&input).unwrap();
+        let prost_array = array.to_protobuf();
+        assert_eq!(prost_array.array_type, ArrayType::BitString as i32);
+
+        let decoded = BitStringArray::from_protobuf(&prost_array, input.len()).unwrap();
+        assert_eq!(input, decoded.as_bit_string().iter().collect_vec());
+    }
+}
@@ -37,6 +37,11 @@ impl BoolArray {
         }
         builder.finish()
     }
+
+    /// Returns a bitmap of the true values, where nulls are taken as false.
+    pub fn to_bitmap(&self) -> Result<Bitmap> {
+        &self.data & &self.bitmap
+    }
 }
 
 impl Array for BoolArray {
@@ -80,6 +85,8 @@ impl Array for BoolArray {
             array_type: ArrayType::Bool as i32,
             struct_array_data: None,
             list_array_data: None,
+            dictionary_data: None,
+            suppressed_null_bitmap: None,
         }
     }
 
@@ -171,6 +178,14 @@ mod tests {
         builder.finish().unwrap()
     }
 
+    #[test]
+    fn test_bool_to_bitmap() {
+        let array = BoolArray::from_slice(&[Some(true), None, Some(false), Some(true)]).unwrap();
+        let bitmap = array.to_bitmap().unwrap();
+        assert_eq!(bitmap.iter().collect_vec(), vec![true, false, false, true]);
+        assert_eq!(bitmap.num_high_bits(), 2);
+    }
+
     #[test]
     fn test_bool_builder() {
         let v = (0..1000)
@@ -89,6 +89,8 @@ macro_rules! get_chrono_array {
                         array_type: Self::get_array_type() as i32,
                         struct_array_data: None,
                         list_array_data: None,
+                        dictionary_data: None,
+                        suppressed_null_bitmap: None,
                     }
                 }
 
@@ -37,6 +37,21 @@ impl Column {
         ProstColumn { array: Some(array) }
     }
 
+    /// Like [`Column::to_protobuf`], but uses the encodings a stream exchange receiver speaking
+    /// `protocol_version` understands, see
+    /// [`crate::array::stream_chunk::STREAM_EXCHANGE_PROTOCOL_VERSION`]. Dictionary encoded arrays
+    /// are kept as they are, which suppresses the null rows as well.
+    pub fn to_protobuf_with_version(&self, protocol_version: u32) -> Result<ProstColumn> {
+        let array = match &*self.array {
+            ArrayImpl::Utf8(array) if protocol_version >= 1 && array.is_dictionary() => {
+                array.to_protobuf_with_dictionary()
+            }
+            array if protocol_version >= 2 => array.to_protobuf_null_suppressed()?,
+            array => array.to_protobuf(),
+        };
+        Ok(ProstColumn { array: Some(array) })
+    }
+
     pub fn from_protobuf(col: &ProstColumn, cardinality: usize) -> Result<Self> {
         Ok(Column {
             array: Arc::new(ArrayImpl::from_protobuf(col.get_array()?, cardinality)?),
@@ -71,12 +86,15 @@ mod tests {
     use super::*;
     use crate::array::{
         Array, ArrayBuilder, BoolArray, BoolArrayBuilder, DecimalArray, DecimalArrayBuilder,
-        I32Array, I32ArrayBuilder, NaiveDateArray, NaiveDateArrayBuilder, NaiveDateTimeArray,
-        NaiveDateTimeArrayBuilder, NaiveTimeArray, NaiveTimeArrayBuilder, Utf8Array,
-        Utf8ArrayBuilder,
+        I32Array, I32ArrayBuilder, IntervalArray, IntervalArrayBuilder, ListArray, NaiveDateArray,
+        NaiveDateArrayBuilder, NaiveDateTimeArray, NaiveDateTimeArrayBuilder, NaiveTimeArray,
+        NaiveTimeArrayBuilder, StructArray, Utf8Array, Utf8ArrayBuilder,
     };
     use crate::error::Result;
-    use crate::types::{Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper};
+    use crate::types::{
+        DataType, Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper,
+    };
+    use crate::{array, empty_array};
 
     // Convert a column to protobuf, then convert it back to column, and ensures the two are
     // identical.
@@ -272,4 +290,112 @@ mod tests {
         });
         Ok(())
     }
+
+    #[test]
+    fn test_interval_protobuf_conversion() -> Result<()> {
+        let cardinality = 2048;
+        let mut builder = IntervalArrayBuilder::new(cardinality).unwrap();
+        for i in 0..cardinality {
+            if i % 2 == 0 {
+                builder
+                    .append(Some(IntervalUnit::new(
+                        i as i32,
+                        -(i as i32),
+                        i as i64 * 1000,
+                    )))
+                    .unwrap();
+            } else {
+                builder.append(None).unwrap();
+            }
+        }
+        let col = Column::new(Arc::new(ArrayImpl::from(builder.finish().unwrap())));
+        let new_col = Column::from_protobuf(&col.to_protobuf(), cardinality).unwrap();
+        assert_eq!(new_col.array.len(), cardinality);
+        let arr: &IntervalArray = new_col.array_ref().as_interval();
+        arr.iter().enumerate().for_each(|(i, x)| {
+            if i % 2 == 0 {
+                assert_eq!(
+                    IntervalUnit::new(i as i32, -(i as i32), i as i64 * 1000),
+                    x.unwrap()
+                );
+            } else {
+                assert!(x.is_none());
+            }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_protobuf_conversion() -> Result<()> {
+        // The lists have different lengths, so that there are more values than rows.
+        let inner = ListArray::from_slices(
+            &[true, true, false, true],
+            vec![
+                Some(array! { I32Array, [Some(1), None, Some(3)] }.into()),
+                Some(empty_array! { I32Array }.into()),
+                None,
+                Some(array! { I32Array, [Some(4), Some(5), Some(6), Some(7)] }.into()),
+            ],
+            DataType::Int32,
+        )?;
+        let list_type = DataType::List {
+            datatype: Box::new(DataType::Int32),
+        };
+        let col: Column =
+            ListArray::from_slices(&[true, false], vec![Some(inner.into()), None], list_type)?
+                .into();
+        let cardinality = col.array.len();
+        let new_col = Column::from_protobuf(&col.to_protobuf(), cardinality)?;
+        assert_eq!(new_col.array.len(), cardinality);
+        assert_eq!(new_col.array_ref(), col.array_ref());
+        assert_eq!(
+            new_col.array_ref().as_list().array_meta(),
+            col.array_ref().as_list().array_meta()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_protobuf_conversion() -> Result<()> {
+        let list_type = DataType::List {
+            datatype: Box::new(DataType::Int32),
+        };
+        let nested_type = DataType::Struct {
+            fields: Arc::new([DataType::Int32, list_type.clone()]),
+        };
+        let nested = StructArray::from_slices(
+            &[true, true, false],
+            vec![
+                array! { I32Array, [Some(1), None, None] }.into(),
+                ListArray::from_slices(
+                    &[true, false, false],
+                    vec![
+                        Some(array! { I32Array, [Some(2), Some(3)] }.into()),
+                        None,
+                        None,
+                    ],
+                    DataType::Int32,
+                )?
+                .into(),
+            ],
+            vec![DataType::Int32, list_type],
+        )?;
+        let col: Column = StructArray::from_slices(
+            &[true, false, true],
+            vec![
+                array! { Utf8Array, [Some("a"), None, Some("c")] }.into(),
+                nested.into(),
+            ],
+            vec![DataType::Varchar, nested_type],
+        )?
+        .into();
+        let new_col = Column::from_protobuf(&col.to_protobuf(), 3)?;
+        assert_eq!(new_col.array.len(), 3);
+        assert_eq!(new_col.array_ref(), col.array_ref());
+        assert_eq!(
+            new_col.array_ref().as_struct().array_meta(),
+            col.array_ref().as_struct().array_meta()
+        );
+        Ok(())
+    }
 }
@@ -453,7 +453,8 @@ pub type DataChunkRef = Arc<DataChunk>;
 mod tests {
     use crate::array::column::Column;
     use crate::array::*;
-    use crate::{column, column_nonnull};
+    use crate::types::{DataType, IntervalUnit};
+    use crate::{array, column, column_nonnull};
 
     #[test]
     fn test_rechunk() {
@@ -569,4 +570,39 @@ mod tests {
         assert_eq!(chunk_after_serde.rows().count(), 10);
         assert_eq!(chunk_after_serde.cardinality(), 10);
     }
+
+    #[test]
+    fn test_nested_chunk_protobuf_conversion() {
+        let list: ArrayImpl = ListArray::from_slices(
+            &[true, false, true],
+            vec![
+                Some(array! { I64Array, [Some(1), Some(2)] }.into()),
+                None,
+                Some(array! { I64Array, [Some(3), None, Some(4)] }.into()),
+            ],
+            DataType::Int64,
+        )
+        .unwrap()
+        .into();
+        let row: ArrayImpl = StructArray::from_slices(
+            &[true, true, false],
+            vec![
+                array! { I64Array, [Some(5), None, None] }.into(),
+                array! { IntervalArray, [Some(IntervalUnit::from_month(1)), None, None] }.into(),
+            ],
+            vec![DataType::Int64, DataType::Interval],
+        )
+        .unwrap()
+        .into();
+        let chunk = DataChunk::new(
+            vec![Column::new(list.into()), Column::new(row.into())],
+            None,
+        );
+
+        let chunk_after_serde = DataChunk::from_protobuf(&chunk.to_protobuf()).unwrap();
+        assert_eq!(chunk_after_serde.cardinality(), 3);
+        for (col, col_after_serde) in chunk.columns().iter().zip(chunk_after_serde.columns()) {
+            assert_eq!(col.array_ref(), col_after_serde.array_ref());
+        }
+    }
 }
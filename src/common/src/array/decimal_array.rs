@@ -95,6 +95,8 @@ impl Array for DecimalArray {
             array_type: ArrayType::Decimal as i32,
             struct_array_data: None,
             list_array_data: None,
+            dictionary_data: None,
+            suppressed_null_bitmap: None,
         }
     }
 
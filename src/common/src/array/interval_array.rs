@@ -84,6 +84,8 @@ impl Array for IntervalArray {
             array_type: ArrayType::Interval as i32,
             struct_array_data: None,
             list_array_data: None,
+            dictionary_data: None,
+            suppressed_null_bitmap: None,
         }
     }
 
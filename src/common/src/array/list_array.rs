@@ -16,11 +16,18 @@ use core::fmt;
 use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
+use arrow::array::{
+    Array as ArrowArray, ArrayData, ArrayRef as ArrowArrayRef, ListArray as ArrowListArray,
+};
+use arrow::buffer::Buffer;
+use arrow::datatypes::{DataType as ArrowDataType, Field};
 use itertools::EitherOrBoth::{Both, Left, Right};
 use itertools::Itertools;
 use risingwave_pb::data::{Array as ProstArray, ArrayType as ProstArrayType, ListArrayData};
 
+use super::arrow::{from_arrow_data_type, from_arrow_null_bitmap, to_arrow_null_buffer};
 use super::{
     Array, ArrayBuilder, ArrayBuilderImpl, ArrayImpl, ArrayIterator, ArrayMeta, NULL_VAL_FOR_HASH,
 };
@@ -159,6 +166,8 @@ impl Array for ListArray {
             })),
             null_bitmap: Some(self.bitmap.to_protobuf()),
             values: vec![],
+            dictionary_data: None,
+            suppressed_null_bitmap: None,
         }
     }
 
@@ -198,10 +207,18 @@ impl ListArray {
         let bitmap: Bitmap = array.get_null_bitmap()?.try_into()?;
         let cardinality = bitmap.len();
         let array_data = array.get_list_array_data()?.to_owned();
-        let value = ArrayImpl::from_protobuf(array_data.value.as_ref().unwrap(), cardinality)?;
+        let offsets: Vec<usize> = array_data.offsets.iter().map(|u| *u as usize).collect();
+        ensure!(
+            offsets.len() == cardinality + 1,
+            "Must have cardinality + 1 offsets in a list array"
+        );
+        // The value array holds the elements of all the lists, rather than one item per row.
+        let value_cardinality = *offsets.last().unwrap();
+        let value =
+            ArrayImpl::from_protobuf(array_data.value.as_ref().unwrap(), value_cardinality)?;
         let arr = ListArray {
             bitmap,
-            offsets: array_data.offsets.iter().map(|u| *u as usize).collect(),
+            offsets,
             value: Box::new(value),
             value_type: DataType::from(&array_data.value_type.unwrap()),
             len: cardinality,
@@ -209,6 +226,45 @@ impl ListArray {
         Ok(arr.into())
     }
 
+    /// Converts the list array to an Arrow list array, whose child array holds the elements of all
+    /// the lists.
+    pub fn to_arrow(&self) -> Result<ArrowArrayRef> {
+        let value = self.value.to_arrow()?;
+        let offsets = self.offsets.iter().map(|o| *o as i32).collect_vec();
+        let data = ArrayData::builder(ArrowDataType::List(Box::new(Field::new(
+            "item",
+            value.data_type().clone(),
+            true,
+        ))))
+        .len(self.len)
+        .add_buffer(Buffer::from_slice_ref(&offsets))
+        .add_child_data(value.data().clone())
+        .null_bit_buffer(Some(to_arrow_null_buffer(&self.bitmap)))
+        .build()?;
+        Ok(Arc::new(ArrowListArray::from(data)))
+    }
+
+    pub fn from_arrow(array: &ArrowListArray) -> Result<ArrayImpl> {
+        // The array may be a slice of a larger one, so only the referenced elements are kept.
+        let arrow_offsets = array.value_offsets();
+        let start = arrow_offsets[0];
+        let end = arrow_offsets[array.len()];
+        let offsets = arrow_offsets
+            .iter()
+            .map(|o| (*o - start) as usize)
+            .collect_vec();
+        let value =
+            ArrayImpl::from_arrow(&*array.values().slice(start as usize, (end - start) as usize))?;
+        let arr = ListArray {
+            bitmap: from_arrow_null_bitmap(array)?,
+            offsets,
+            value: Box::new(value),
+            value_type: from_arrow_data_type(&array.value_type())?,
+            len: array.len(),
+        };
+        Ok(arr.into())
+    }
+
     #[cfg(test)]
     pub fn from_slices(
         null_bitmap: &[bool],
@@ -14,7 +14,10 @@
 
 //! `Array` defines all in-memory representations of vectorized execution framework.
 
+pub mod arrow;
+mod bit_string_array;
 mod bool_array;
+mod bytes_array;
 mod chrono_array;
 pub mod column;
 mod column_proto_readers;
@@ -36,7 +39,9 @@ use std::convert::From;
 use std::hash::Hasher;
 use std::sync::Arc;
 
+pub use bit_string_array::{BitStringArray, BitStringArrayBuilder};
 pub use bool_array::{BoolArray, BoolArrayBuilder};
+pub use bytes_array::*;
 pub use chrono_array::{
     NaiveDateArray, NaiveDateArrayBuilder, NaiveDateTimeArray, NaiveDateTimeArrayBuilder,
     NaiveTimeArray, NaiveTimeArrayBuilder,
@@ -248,7 +253,9 @@ macro_rules! for_all_variants {
             { NaiveDateTime, naivedatetime, NaiveDateTimeArray, NaiveDateTimeArrayBuilder },
             { NaiveTime, naivetime, NaiveTimeArray, NaiveTimeArrayBuilder },
             { Struct, struct, StructArray, StructArrayBuilder },
-            { List, list, ListArray, ListArrayBuilder }
+            { List, list, ListArray, ListArrayBuilder },
+            { Bytea, bytea, BytesArray, BytesArrayBuilder },
+            { BitString, bit_string, BitStringArray, BitStringArrayBuilder }
         }
     };
 }
@@ -324,6 +331,18 @@ impl From<ListArray> for ArrayImpl {
     }
 }
 
+impl From<BytesArray> for ArrayImpl {
+    fn from(arr: BytesArray) -> Self {
+        Self::Bytea(arr)
+    }
+}
+
+impl From<BitStringArray> for ArrayImpl {
+    fn from(arr: BitStringArray) -> Self {
+        Self::BitString(arr)
+    }
+}
+
 for_all_variants! { array_impl_enum }
 
 /// `impl_convert` implements several conversions for `Array` and `ArrayBuilder`.
@@ -548,7 +567,49 @@ impl ArrayImpl {
         ArrayImplIterator::new(self)
     }
 
+    /// Like [`ArrayImpl::to_protobuf`], but only the non-null rows are encoded, with the null
+    /// bitmap of all rows kept in `suppressed_null_bitmap`. Arrays without nulls are encoded as
+    /// usual. Only the receivers that support it can decode the result, see
+    /// [`crate::array::stream_chunk::STREAM_EXCHANGE_PROTOCOL_VERSION`].
+    pub fn to_protobuf_null_suppressed(&self) -> Result<ProstArray> {
+        let bitmap = self.null_bitmap();
+        let non_null_count = bitmap.num_high_bits();
+        if non_null_count == self.len() {
+            return Ok(self.to_protobuf());
+        }
+        let mut array = self.compact(bitmap, non_null_count)?.to_protobuf();
+        array.suppressed_null_bitmap = Some(bitmap.to_protobuf());
+        Ok(array)
+    }
+
     pub fn from_protobuf(array: &ProstArray, cardinality: usize) -> Result<Self> {
+        match &array.suppressed_null_bitmap {
+            Some(bitmap) => Self::from_protobuf_null_suppressed(array, bitmap.try_into()?),
+            None => Self::from_protobuf_plain(array, cardinality),
+        }
+    }
+
+    /// Decodes an array encoded by [`ArrayImpl::to_protobuf_null_suppressed`], by filling the
+    /// null rows of `bitmap` back.
+    fn from_protobuf_null_suppressed(array: &ProstArray, bitmap: Bitmap) -> Result<Self> {
+        let non_null = Self::from_protobuf_plain(array, bitmap.num_high_bits())?;
+        let mut builder = non_null.create_builder(bitmap.len())?;
+        let mut values = non_null.iter();
+        for not_null in bitmap.iter() {
+            if not_null {
+                let value = values.next().ok_or_else(|| {
+                    InternalError("fewer values than the non-null rows".to_string())
+                })?;
+                builder.append_datum_ref(value)?;
+            } else {
+                builder.append_null()?;
+            }
+        }
+        ensure!(values.next().is_none());
+        builder.finish()
+    }
+
+    fn from_protobuf_plain(array: &ProstArray, cardinality: usize) -> Result<Self> {
         use self::column_proto_readers::*;
         use crate::array::value_reader::*;
         let array = match array.array_type() {
@@ -562,6 +623,9 @@ impl ArrayImpl {
                 read_numeric_array::<OrderedF64, F64ValueReader>(array, cardinality)?
             }
             ProstArrayType::Bool => read_bool_array(array, cardinality)?,
+            ProstArrayType::Utf8 if array.dictionary_data.is_some() => {
+                Utf8Array::from_protobuf_with_dictionary(array, cardinality)?.into()
+            }
             ProstArrayType::Utf8 => {
                 read_string_array::<Utf8ArrayBuilder, Utf8ValueReader>(array, cardinality)?
             }
@@ -574,6 +638,10 @@ impl ArrayImpl {
             ProstArrayType::Interval => read_interval_unit_array(array, cardinality)?,
             ProstArrayType::Struct => StructArray::from_protobuf(array)?,
             ProstArrayType::List => ListArray::from_protobuf(array)?,
+            ProstArrayType::Bytea => {
+                read_string_array::<BytesArrayBuilder, BytesValueReader>(array, cardinality)?
+            }
+            ProstArrayType::BitString => BitStringArray::from_protobuf(array, cardinality)?,
         };
         Ok(array)
     }
@@ -661,6 +729,29 @@ mod tests {
             assert_eq!(data, Some(idx as i64 * 2));
         }
     }
+    #[test]
+    fn test_null_suppressed_protobuf() -> Result<()> {
+        let input = (0..10)
+            .map(|i| (i % 3 == 0).then(|| i as i64))
+            .collect_vec();
+        let array: ArrayImpl = I64Array::from_slice(&input)?.into();
+
+        // Only the non-null rows are encoded.
+        let prost = array.to_protobuf_null_suppressed()?;
+        assert!(prost.suppressed_null_bitmap.is_some());
+        assert_eq!(
+            prost.get_values()[0].get_body().len(),
+            4 * std::mem::size_of::<i64>()
+        );
+        let decoded = ArrayImpl::from_protobuf(&prost, input.len())?;
+        assert_eq!(decoded.as_int64().iter().collect_vec(), input);
+
+        // Arrays without nulls are encoded as usual.
+        let array: ArrayImpl = I64Array::from_slice(&[Some(1), Some(2)])?.into();
+        assert_eq!(array.to_protobuf_null_suppressed()?, array.to_protobuf());
+
+        Ok(())
+    }
 }
 #[cfg(test)]
 mod test_util {
@@ -142,6 +142,8 @@ impl<T: PrimitiveArrayItemType> Array for PrimitiveArray<T> {
             array_type: T::array_type() as i32,
             struct_array_data: None,
             list_array_data: None,
+            dictionary_data: None,
+            suppressed_null_bitmap: None,
         }
     }
 
@@ -28,6 +28,13 @@ use crate::error::{ErrorCode, Result, RwError};
 use crate::types::DataType;
 use crate::util::hash_util::finalize_hashers;
 
+/// The version of the stream exchange protocol, sent by the receiver of a stream exchange so that
+/// the sender only uses the encodings the receiver understands.
+/// * 0: plain encoding.
+/// * 1: dictionary encoded string columns are kept as they are.
+/// * 2: only the non-null rows of the other columns are encoded.
+pub const STREAM_EXCHANGE_PROTOCOL_VERSION: u32 = 2;
+
 /// `Op` represents three operations in `StreamChunk`.
 ///
 /// `UpdateDelete` and `UpdateInsert` are semantically equivalent to `Delete` and `Insert`
@@ -173,6 +180,35 @@ impl StreamChunk {
         }
     }
 
+    /// Concatenate the given chunks into a single compacted chunk. All chunks must have the same
+    /// schema, and there must be at least one chunk.
+    pub fn concat(chunks: Vec<StreamChunk>) -> Result<Self> {
+        assert!(!chunks.is_empty());
+        let chunks: Vec<_> = chunks.into_iter().map(|c| c.compact()).try_collect()?;
+        if chunks.len() == 1 {
+            return Ok(chunks.into_iter().next().unwrap());
+        }
+
+        let cardinality = chunks.iter().map(|c| c.cardinality()).sum();
+        let mut builders: Vec<_> = chunks[0]
+            .columns
+            .iter()
+            .map(|col| col.array_ref().create_builder(cardinality))
+            .try_collect()?;
+        let mut ops = Vec::with_capacity(cardinality);
+        for chunk in chunks {
+            for (builder, column) in builders.iter_mut().zip_eq(chunk.columns.iter()) {
+                builder.append_array(column.array_ref())?;
+            }
+            ops.extend(chunk.ops);
+        }
+        let columns = builders
+            .into_iter()
+            .map(|builder| Ok::<_, RwError>(Column::new(Arc::new(builder.finish()?))))
+            .try_collect()?;
+        Ok(StreamChunk::new(ops, columns, None))
+    }
+
     pub fn into_parts(self) -> (DataChunk, Vec<Op>) {
         let (ops, columns, visibility) = self.into_inner();
 
@@ -209,6 +245,22 @@ impl StreamChunk {
         }
     }
 
+    /// Serializes the chunk for a stream exchange whose receiver speaks `protocol_version`.
+    pub fn to_protobuf_with_version(&self, protocol_version: u32) -> Result<ProstStreamChunk> {
+        if protocol_version < 1 {
+            return Ok(self.to_protobuf());
+        }
+        Ok(ProstStreamChunk {
+            cardinality: self.cardinality() as u32,
+            ops: self.ops.iter().map(|op| op.to_protobuf() as i32).collect(),
+            columns: self
+                .columns
+                .iter()
+                .map(|col| col.to_protobuf_with_version(protocol_version))
+                .collect::<Result<_>>()?,
+        })
+    }
+
     pub fn from_protobuf(prost: &ProstStreamChunk) -> Result<Self> {
         let cardinality = prost.get_cardinality() as usize;
         let mut ops = Vec::with_capacity(cardinality);
@@ -340,4 +392,28 @@ mod tests {
 +----+---+---+"
         );
     }
+
+    #[test]
+    fn test_concat() {
+        let chunk1 = StreamChunk::new(
+            vec![Op::Insert, Op::Delete],
+            vec![column_nonnull!(I64Array, [1, 2])],
+            Some((vec![false, true]).try_into().unwrap()),
+        );
+        let chunk2 = StreamChunk::new(
+            vec![Op::UpdateDelete, Op::UpdateInsert],
+            vec![column!(I64Array, [Some(3), None])],
+            None,
+        );
+        let chunk = StreamChunk::concat(vec![chunk1, chunk2]).unwrap();
+        assert_eq!(
+            chunk.to_pretty_string(),
+            "\
++----+---+
+|  - | 2 |
+| U- | 3 |
+| U+ |   |
++----+---+"
+        );
+    }
 }
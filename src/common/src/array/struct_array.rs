@@ -18,9 +18,14 @@ use std::fmt::{Debug, Display};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
+use arrow::array::{
+    Array as ArrowArray, ArrayRef as ArrowArrayRef, StructArray as ArrowStructArray,
+};
+use arrow::datatypes::Field;
 use itertools::Itertools;
 use risingwave_pb::data::{Array as ProstArray, ArrayType as ProstArrayType, StructArrayData};
 
+use super::arrow::{from_arrow_data_type, from_arrow_null_bitmap, to_arrow_null_buffer};
 use super::{
     Array, ArrayBuilder, ArrayBuilderImpl, ArrayImpl, ArrayIterator, ArrayMeta, NULL_VAL_FOR_HASH,
 };
@@ -159,6 +164,8 @@ impl Array for StructArray {
                 children_type,
             }),
             list_array_data: None,
+            dictionary_data: None,
+            suppressed_null_bitmap: None,
             null_bitmap: Some(self.bitmap.to_protobuf()),
             values: vec![],
         }
@@ -230,6 +237,45 @@ impl StructArray {
         &self.children_type
     }
 
+    /// Converts the struct array to an Arrow struct array. The fields are named by their indexes.
+    pub fn to_arrow(&self) -> Result<ArrowArrayRef> {
+        let fields = self
+            .children
+            .iter()
+            .enumerate()
+            .map(|(idx, child)| {
+                let array = child.to_arrow()?;
+                Ok((
+                    Field::new(&idx.to_string(), array.data_type().clone(), true),
+                    array,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(ArrowStructArray::from((
+            fields,
+            to_arrow_null_buffer(&self.bitmap),
+        ))))
+    }
+
+    pub fn from_arrow(array: &ArrowStructArray) -> Result<ArrayImpl> {
+        let children = array
+            .columns()
+            .into_iter()
+            .map(|child| ArrayImpl::from_arrow(&**child))
+            .collect::<Result<Vec<_>>>()?;
+        let children_type = match from_arrow_data_type(array.data_type())? {
+            DataType::Struct { fields } => fields,
+            _ => unreachable!(),
+        };
+        let arr = StructArray {
+            bitmap: from_arrow_null_bitmap(array)?,
+            children,
+            children_type,
+            len: array.len(),
+        };
+        Ok(arr.into())
+    }
+
     #[cfg(test)]
     pub fn from_slices(
         null_bitmap: &[bool],
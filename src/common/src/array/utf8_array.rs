@@ -12,25 +12,46 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::iter;
 use std::mem::size_of;
 
 use itertools::Itertools;
 use risingwave_pb::data::buffer::CompressionType;
-use risingwave_pb::data::{Array as ProstArray, ArrayType, Buffer};
+use risingwave_pb::data::{Array as ProstArray, ArrayType, Buffer, DictionaryData};
 
 use super::{Array, ArrayBuilder, ArrayIterator, ArrayMeta, NULL_VAL_FOR_HASH};
 use crate::array::ArrayBuilderImpl;
 use crate::buffer::{Bitmap, BitmapBuilder};
-use crate::error::Result;
+use crate::error::ErrorCode::InternalError;
+use crate::error::{Result, RwError};
+
+/// Arrays shorter than this are never dictionary encoded.
+const DICTIONARY_MIN_LEN: usize = 64;
+
+/// An array is dictionary encoded only if it has at most `len / DICTIONARY_DISTINCT_RATIO`
+/// distinct values.
+const DICTIONARY_DISTINCT_RATIO: usize = 4;
+
+/// Number of rows sampled to estimate the cardinality before building the dictionary. The array
+/// is kept plain without a full scan if more than half of the sampled values are distinct.
+const DICTIONARY_SAMPLE_LEN: usize = 64;
 
 /// `Utf8Array` is a collection of Rust Utf8 `String`s.
+///
+/// When the builder finds only a few distinct values, the array is dictionary encoded: `offset`
+/// and `data` store every distinct value once in sorted order, and `keys` maps each row to its
+/// value. The encoding is transparent to users of [`Array`].
+///
+/// Keys are only comparable within one array, so they are used by sorting but not by hashing:
+/// group-by hash keys are still built from the values.
 #[derive(Debug)]
 pub struct Utf8Array {
     offset: Vec<usize>,
     bitmap: Bitmap,
     data: Vec<u8>,
+    keys: Option<Vec<u32>>,
 }
 
 impl Array for Utf8Array {
@@ -41,7 +62,7 @@ impl Array for Utf8Array {
 
     fn value_at(&self, idx: usize) -> Option<&str> {
         if !self.is_null(idx) {
-            let data_slice = &self.data[self.offset[idx]..self.offset[idx + 1]];
+            let data_slice = self.value_slice(idx);
             Some(unsafe { std::str::from_utf8_unchecked(data_slice) })
         } else {
             None
@@ -49,7 +70,10 @@ impl Array for Utf8Array {
     }
 
     fn len(&self) -> usize {
-        self.offset.len() - 1
+        match &self.keys {
+            Some(keys) => keys.len(),
+            None => self.offset.len() - 1,
+        }
     }
 
     fn iter(&self) -> ArrayIterator<'_, Self> {
@@ -57,6 +81,11 @@ impl Array for Utf8Array {
     }
 
     fn to_protobuf(&self) -> ProstArray {
+        if self.is_dictionary() {
+            // The protobuf format is always plain.
+            return self.decode_dictionary().to_protobuf();
+        }
+
         let offset_buffer = self
             .offset
             .iter()
@@ -97,6 +126,8 @@ impl Array for Utf8Array {
             array_type: ArrayType::Utf8 as i32,
             struct_array_data: None,
             list_array_data: None,
+            dictionary_data: None,
+            suppressed_null_bitmap: None,
         }
     }
 
@@ -111,7 +142,7 @@ impl Array for Utf8Array {
     #[inline(always)]
     fn hash_at<H: Hasher>(&self, idx: usize, state: &mut H) {
         if !self.is_null(idx) {
-            let data_slice = &self.data[self.offset[idx]..self.offset[idx + 1]];
+            let data_slice = self.value_slice(idx);
             state.write(data_slice);
         } else {
             NULL_VAL_FOR_HASH.hash(state);
@@ -119,7 +150,10 @@ impl Array for Utf8Array {
     }
 
     fn create_builder(&self, capacity: usize) -> Result<ArrayBuilderImpl> {
-        let array_builder = Utf8ArrayBuilder::new(capacity)?;
+        let mut array_builder = Utf8ArrayBuilder::new(capacity)?;
+        // A plain array long enough to be dictionary encoded has too many distinct values, so
+        // the arrays built after it for the same column skip the detection.
+        array_builder.detect_dictionary = self.is_dictionary() || self.len() < DICTIONARY_MIN_LEN;
         Ok(ArrayBuilderImpl::Utf8(array_builder))
     }
 }
@@ -132,6 +166,208 @@ impl Utf8Array {
         }
         builder.finish()
     }
+
+    /// Whether the array is dictionary encoded.
+    pub fn is_dictionary(&self) -> bool {
+        self.keys.is_some()
+    }
+
+    /// Returns the key of each row if the array is dictionary encoded. Keys are ordered the same
+    /// way as the values they refer to, so comparing keys of the same array is equivalent to
+    /// comparing values. The key of a null row is unspecified.
+    pub fn dictionary_keys(&self) -> Option<&[u32]> {
+        self.keys.as_deref()
+    }
+
+    /// Like [`Array::to_protobuf`], but keeps the dictionary encoding, in which every distinct
+    /// value is sent only once. Only the receivers that support it can decode the result, see
+    /// [`crate::array::stream_chunk::STREAM_EXCHANGE_PROTOCOL_VERSION`].
+    pub fn to_protobuf_with_dictionary(&self) -> ProstArray {
+        let keys = match &self.keys {
+            Some(keys) => keys,
+            None => return self.to_protobuf(),
+        };
+
+        let offset_buffer = self
+            .offset
+            .iter()
+            .flat_map(|offset| (*offset as u64).to_be_bytes())
+            .collect();
+        // Keys of null rows are suppressed.
+        let keys = keys
+            .iter()
+            .zip_eq(self.bitmap.iter())
+            .filter_map(|(key, not_null)| not_null.then(|| *key))
+            .collect();
+
+        ProstArray {
+            null_bitmap: Some(self.bitmap.to_protobuf()),
+            values: vec![
+                Buffer {
+                    compression: CompressionType::None as i32,
+                    body: offset_buffer,
+                },
+                Buffer {
+                    compression: CompressionType::None as i32,
+                    body: self.data.clone(),
+                },
+            ],
+            array_type: ArrayType::Utf8 as i32,
+            struct_array_data: None,
+            list_array_data: None,
+            dictionary_data: Some(DictionaryData { keys }),
+            suppressed_null_bitmap: None,
+        }
+    }
+
+    /// Decodes an array encoded by [`Utf8Array::to_protobuf_with_dictionary`] with
+    /// `dictionary_data` set.
+    pub fn from_protobuf_with_dictionary(array: &ProstArray, cardinality: usize) -> Result<Self> {
+        ensure!(
+            array.get_values().len() == 2,
+            "Must have exactly 2 buffers in a string array"
+        );
+        let bitmap: Bitmap = array.get_null_bitmap()?.try_into()?;
+        ensure!(bitmap.len() == cardinality);
+
+        let offset = array.get_values()[0]
+            .get_body()
+            .chunks(size_of::<u64>())
+            .map(|bytes| {
+                let bytes = bytes.try_into().map_err(|_| {
+                    InternalError("failed to read u64 from offset buffer".to_string())
+                })?;
+                Ok(u64::from_be_bytes(bytes) as usize)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let data = array.get_values()[1].get_body().clone();
+        ensure!(!offset.is_empty() && *offset.last().unwrap() == data.len());
+
+        let mut non_null_keys = array.get_dictionary_data()?.get_keys().iter();
+        let keys = bitmap
+            .iter()
+            .map(|not_null| match not_null {
+                true => non_null_keys
+                    .next()
+                    .copied()
+                    .filter(|key| (*key as usize) < offset.len() - 1)
+                    .ok_or_else(|| {
+                        RwError::from(InternalError("invalid dictionary key".to_string()))
+                    }),
+                false => Ok(0),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(non_null_keys.next().is_none());
+
+        Ok(Self {
+            offset,
+            bitmap,
+            data,
+            keys: Some(keys),
+        })
+    }
+
+    fn value_slice(&self, idx: usize) -> &[u8] {
+        let value_idx = match &self.keys {
+            Some(keys) => keys[idx] as usize,
+            None => idx,
+        };
+        &self.data[self.offset[value_idx]..self.offset[value_idx + 1]]
+    }
+
+    /// Converts a plain array into a dictionary encoded one if it holds few distinct values.
+    fn encode_dictionary(self) -> Self {
+        match self.build_dictionary() {
+            Some((offset, data, keys)) => Self {
+                offset,
+                bitmap: self.bitmap,
+                data,
+                keys: Some(keys),
+            },
+            None => self,
+        }
+    }
+
+    /// Returns the `offset`, `data` and `keys` of the dictionary encoding, or `None` if the array
+    /// is too short or has too many distinct values.
+    fn build_dictionary(&self) -> Option<(Vec<usize>, Vec<u8>, Vec<u32>)> {
+        let len = self.len();
+        if self.is_dictionary() || len < DICTIONARY_MIN_LEN {
+            return None;
+        }
+
+        if self.sample_distinct() > DICTIONARY_SAMPLE_LEN / 2 {
+            return None;
+        }
+
+        let max_distinct = len / DICTIONARY_DISTINCT_RATIO;
+        let mut dict: HashMap<&[u8], u32> = HashMap::new();
+        for idx in 0..len {
+            if !self.is_null(idx) {
+                dict.entry(self.value_slice(idx)).or_default();
+                if dict.len() > max_distinct {
+                    return None;
+                }
+            }
+        }
+
+        let mut values = dict.keys().copied().collect_vec();
+        values.sort_unstable();
+        let mut offset = Vec::with_capacity(values.len() + 1);
+        let mut data = Vec::with_capacity(values.iter().map(|v| v.len()).sum());
+        offset.push(0);
+        for (key, value) in values.into_iter().enumerate() {
+            data.extend_from_slice(value);
+            offset.push(data.len());
+            *dict.get_mut(value).unwrap() = key as u32;
+        }
+
+        let keys = (0..len)
+            .map(|idx| {
+                if self.is_null(idx) {
+                    0
+                } else {
+                    dict[self.value_slice(idx)]
+                }
+            })
+            .collect();
+        Some((offset, data, keys))
+    }
+
+    /// Returns the number of distinct values among [`DICTIONARY_SAMPLE_LEN`] rows evenly spread
+    /// over the array, which is cheap compared to hashing every row.
+    fn sample_distinct(&self) -> usize {
+        let len = self.len();
+        let step = (len / DICTIONARY_SAMPLE_LEN).max(1);
+        let mut sample = (0..len)
+            .step_by(step)
+            .take(DICTIONARY_SAMPLE_LEN)
+            .filter(|idx| !self.is_null(*idx))
+            .map(|idx| self.value_slice(idx))
+            .collect_vec();
+        sample.sort_unstable();
+        sample.dedup();
+        sample.len()
+    }
+
+    /// Converts a dictionary encoded array back into the plain encoding.
+    fn decode_dictionary(&self) -> Self {
+        let mut offset = Vec::with_capacity(self.len() + 1);
+        let mut data = Vec::new();
+        offset.push(0);
+        for idx in 0..self.len() {
+            if !self.is_null(idx) {
+                data.extend_from_slice(self.value_slice(idx));
+            }
+            offset.push(data.len());
+        }
+        Self {
+            offset,
+            bitmap: self.bitmap.clone(),
+            data,
+            keys: None,
+        }
+    }
 }
 
 /// `Utf8ArrayBuilder` use `&str` to build an `Utf8Array`.
@@ -140,6 +376,8 @@ pub struct Utf8ArrayBuilder {
     offset: Vec<usize>,
     bitmap: BitmapBuilder,
     data: Vec<u8>,
+    /// Whether `finish` tries the dictionary encoding.
+    detect_dictionary: bool,
 }
 
 impl ArrayBuilder for Utf8ArrayBuilder {
@@ -152,6 +390,7 @@ impl ArrayBuilder for Utf8ArrayBuilder {
             offset,
             data: Vec::with_capacity(capacity),
             bitmap: BitmapBuilder::with_capacity(capacity),
+            detect_dictionary: true,
         })
     }
 
@@ -171,6 +410,12 @@ impl ArrayBuilder for Utf8ArrayBuilder {
     }
 
     fn append_array(&mut self, other: &Utf8Array) -> Result<()> {
+        if other.is_dictionary() {
+            for value in other.iter() {
+                self.append(value)?;
+            }
+            return Ok(());
+        }
         for bit in other.bitmap.iter() {
             self.bitmap.append(bit);
         }
@@ -183,11 +428,17 @@ impl ArrayBuilder for Utf8ArrayBuilder {
     }
 
     fn finish(mut self) -> Result<Utf8Array> {
-        Ok(Utf8Array {
+        let array = Utf8Array {
             bitmap: (self.bitmap).finish(),
             data: self.data,
             offset: self.offset,
-        })
+            keys: None,
+        };
+        if self.detect_dictionary {
+            Ok(array.encode_dictionary())
+        } else {
+            Ok(array)
+        }
     }
 }
 
@@ -290,6 +541,7 @@ mod tests {
 
     use super::*;
     use crate::error::Result;
+    use crate::types::ScalarRefImpl;
 
     #[test]
     fn test_utf8_builder() {
@@ -347,6 +599,88 @@ mod tests {
         assert_eq!(input, array.iter().collect_vec());
     }
 
+    #[test]
+    fn test_utf8_array_dictionary() -> Result<()> {
+        let input = (0..200)
+            .map(|i| match i % 5 {
+                0 => None,
+                1 => Some("banana"),
+                2 => Some("apple"),
+                3 => Some("cherry"),
+                _ => Some("apple"),
+            })
+            .collect_vec();
+
+        let array = Utf8Array::from_slice(&input)?;
+        assert!(array.is_dictionary());
+        assert_eq!(array.len(), input.len());
+        assert_eq!(array.data.len(), "applebananacherry".len());
+        assert_eq!(input, array.iter().collect_vec());
+
+        // Keys follow the order of values.
+        let keys = array.dictionary_keys().unwrap();
+        assert!(keys[2] < keys[1] && keys[1] < keys[3]);
+        assert_eq!(keys[2], keys[4]);
+
+        // Protobuf uses the plain encoding.
+        let plain = array.decode_dictionary();
+        assert!(!plain.is_dictionary());
+        assert_eq!(array.to_protobuf(), plain.to_protobuf());
+        assert_eq!(input, plain.iter().collect_vec());
+
+        let mut builder = Utf8ArrayBuilder::new(0)?;
+        builder.append(Some("durian"))?;
+        builder.append_array(&array)?;
+        let appended = builder.finish()?;
+        assert_eq!(appended.value_at(0), Some("durian"));
+        assert_eq!(input, appended.iter().skip(1).collect_vec());
+
+        // High cardinality arrays stay plain.
+        let input = (0..200).map(|i| i.to_string()).collect_vec();
+        let input = input.iter().map(|s| Some(s.as_str())).collect_vec();
+        let array = Utf8Array::from_slice(&input)?;
+        assert!(!array.is_dictionary());
+        assert_eq!(array.sample_distinct(), DICTIONARY_SAMPLE_LEN);
+        assert_eq!(input, array.iter().collect_vec());
+
+        // Builders created from a high cardinality array skip the detection.
+        let mut builder = array.create_builder(200)?;
+        for _ in 0..200 {
+            builder.append_datum_ref(Some(ScalarRefImpl::Utf8("apple")))?;
+        }
+        assert!(!builder.finish()?.as_utf8().is_dictionary());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_utf8_array_dictionary_protobuf() -> Result<()> {
+        let input = (0..200)
+            .map(|i| match i % 4 {
+                0 => None,
+                1 => Some("banana"),
+                _ => Some("apple"),
+            })
+            .collect_vec();
+        let array = Utf8Array::from_slice(&input)?;
+        assert!(array.is_dictionary());
+
+        // Every distinct value is sent once, and keys of null rows are suppressed.
+        let prost = array.to_protobuf_with_dictionary();
+        assert_eq!(prost.get_values()[1].get_body(), b"applebanana");
+        assert_eq!(prost.get_dictionary_data()?.get_keys().len(), 150);
+
+        let decoded = crate::array::ArrayImpl::from_protobuf(&prost, input.len())?;
+        assert!(decoded.as_utf8().is_dictionary());
+        assert_eq!(input, decoded.as_utf8().iter().collect_vec());
+
+        // Plain arrays are sent in the plain encoding.
+        let plain = array.decode_dictionary();
+        assert_eq!(plain.to_protobuf_with_dictionary(), plain.to_protobuf());
+
+        Ok(())
+    }
+
     #[test]
     fn test_utf8_array_to_protobuf() {
         let input = vec![
@@ -246,11 +246,56 @@ impl Bitmap {
     }
 }
 
+impl Bitmap {
+    /// Applies `op` on the bits of `self` and `rhs`, which must have the same length. The bits are
+    /// processed 64 at a time, so that the loop can be auto-vectorized.
+    fn bin_op<F>(&self, rhs: &Bitmap, op: F) -> Result<Bitmap>
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        ensure!(self.num_bits == rhs.num_bits);
+        let num_bytes = (self.num_bits + 7) / 8;
+        let lhs = &self.bits.as_slice()[..num_bytes];
+        let rhs = &rhs.bits.as_slice()[..num_bytes];
+        let mut bytes = vec![0u8; num_bytes];
+
+        let mut lhs_words = lhs.chunks_exact(8);
+        let mut rhs_words = rhs.chunks_exact(8);
+        let mut out_words = bytes.chunks_exact_mut(8);
+        for ((l, r), out) in (&mut lhs_words).zip(&mut rhs_words).zip(&mut out_words) {
+            let word = op(
+                u64::from_le_bytes(l.try_into().unwrap()),
+                u64::from_le_bytes(r.try_into().unwrap()),
+            );
+            out.copy_from_slice(&word.to_le_bytes());
+        }
+        for ((l, r), out) in lhs_words
+            .remainder()
+            .iter()
+            .zip(rhs_words.remainder())
+            .zip(out_words.into_remainder())
+        {
+            *out = op(*l as u64, *r as u64) as u8;
+        }
+        // Clear the padding bits, so that they're not counted as high bits.
+        if self.num_bits % 8 != 0 {
+            *bytes.last_mut().unwrap() &= (1u8 << (self.num_bits % 8)) - 1;
+        }
+
+        let num_high_bits = bytes.iter().map(|b| b.count_ones() as usize).sum();
+        Ok(Bitmap {
+            bits: Buffer::from_slice(&bytes)?,
+            num_bits: self.num_bits,
+            num_high_bits,
+        })
+    }
+}
+
 impl<'a, 'b> BitAnd<&'b Bitmap> for &'a Bitmap {
     type Output = Result<Bitmap>;
 
     fn bitand(self, rhs: &'b Bitmap) -> Result<Bitmap> {
-        Ok(Bitmap::from((&self.bits & &rhs.bits)?))
+        self.bin_op(rhs, |a, b| a & b)
     }
 }
 
@@ -258,7 +303,7 @@ impl<'a, 'b> BitOr<&'b Bitmap> for &'a Bitmap {
     type Output = Result<Bitmap>;
 
     fn bitor(self, rhs: &'b Bitmap) -> Result<Bitmap> {
-        Ok(Bitmap::from((&self.bits | &rhs.bits)?))
+        self.bin_op(rhs, |a, b| a | b)
     }
 }
 
@@ -277,11 +322,7 @@ impl TryFrom<&BoolArray> for Bitmap {
     type Error = RwError;
 
     fn try_from(bools: &BoolArray) -> Result<Bitmap> {
-        let mut builder = BitmapBuilder::default();
-        bools.iter().for_each(|e| {
-            builder.append(e.unwrap_or(false));
-        });
-        Ok(builder.finish())
+        bools.to_bitmap()
     }
 }
 
@@ -432,6 +473,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bitwise_and_unaligned() {
+        let bits1 = (0..100).map(|i| i % 3 == 0).collect_vec();
+        let bits2 = (0..100).map(|i| i % 2 == 0).collect_vec();
+        let bitmap1 = Bitmap::try_from(bits1.clone()).unwrap();
+        let bitmap2 = Bitmap::try_from(bits2.clone()).unwrap();
+        let expected = bits1
+            .iter()
+            .zip_eq(bits2.iter())
+            .map(|(a, b)| *a && *b)
+            .collect_vec();
+
+        let bitmap = (&bitmap1 & &bitmap2).unwrap();
+        assert_eq!(bitmap, Bitmap::try_from(expected.clone()).unwrap());
+        assert_eq!(bitmap.len(), 100);
+        assert_eq!(
+            bitmap.num_high_bits(),
+            expected.iter().filter(|b| **b).count()
+        );
+
+        let shorter = Bitmap::try_from(vec![true; 99]).unwrap();
+        assert!((&bitmap1 & &shorter).is_err());
+    }
+
     #[test]
     fn test_bitmap_is_set() {
         let bitmap = Bitmap::from(Buffer::try_from([0b01001010]).unwrap());
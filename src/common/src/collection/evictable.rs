@@ -39,6 +39,11 @@ impl<K: Hash + Eq, V> EvictableHashMap<K, V> {
         self.target_cap
     }
 
+    /// Update the target capacity, which takes effect on the next `evict_to_target_cap`.
+    pub fn set_target_cap(&mut self, target_cap: usize) {
+        self.target_cap = target_cap;
+    }
+
     /// Returns a mutable reference to the value of the key, or put with `construct` if it is not
     /// present.
     pub fn get_or_put<'a, I>(&'a mut self, key: &K, construct: I) -> &'a mut V
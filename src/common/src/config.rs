@@ -39,6 +39,10 @@ pub struct ComputeNodeConfig {
     // Below for Hummock.
     #[serde(default)]
     pub storage: StorageConfig,
+
+    // Below for the memory governor.
+    #[serde(default)]
+    pub memory: MemoryConfig,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
@@ -46,6 +50,14 @@ pub struct FrontendConfig {
     // For connection
     #[serde(default)]
     pub server: ServerConfig,
+
+    // For slow query logging.
+    #[serde(default)]
+    pub slow_log: SlowLogConfig,
+
+    // For caching the plans of batch queries.
+    #[serde(default)]
+    pub plan_cache: PlanCacheConfig,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -76,6 +88,38 @@ impl Default for BatchConfig {
 pub struct StreamingConfig {
     #[serde(default = "default::chunk_size")]
     pub chunk_size: u32,
+
+    /// Emit the changes of aggregations in the order of group keys, so that the output is
+    /// deterministic across runs. Mainly used for diff-based testing.
+    #[serde(default)]
+    pub sorted_agg_output: bool,
+
+    /// Number of join keys cached by each lookup executor, including keys absent in the
+    /// arrangement. 0 disables the cache.
+    #[serde(default = "default::lookup_cache_capacity")]
+    pub lookup_cache_capacity: usize,
+
+    /// Number of entries cached by each MIN/MAX aggregation state. The other entries stay in the
+    /// state store and are read back when the cache is drained by retractions.
+    #[serde(default = "default::extreme_cache_capacity")]
+    pub extreme_cache_capacity: usize,
+
+    /// Number of rows each hash dispatcher buffers for a downstream actor before sending them as a
+    /// chunk. The buffered rows are also sent on barriers. 0 disables the buffering.
+    #[serde(default)]
+    pub dispatch_chunk_size: usize,
+
+    /// Fuse adjacent projects and filters in an actor into one executor, which evaluates their
+    /// expressions back-to-back on each chunk.
+    #[serde(default = "default::fuse_stateless_operators")]
+    pub fuse_stateless_operators: bool,
+
+    /// Validate the invariants of the chunks between executors in debug builds, e.g. that every
+    /// `UpdateDelete` is followed by an `UpdateInsert` and no key is inserted twice, and panic
+    /// with the identity of the offending executor. All keys of each executor are kept in memory,
+    /// so this is ignored in release builds.
+    #[serde(default)]
+    pub consistency_check: bool,
 }
 
 impl Default for StreamingConfig {
@@ -84,6 +128,36 @@ impl Default for StreamingConfig {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SlowLogConfig {
+    /// Batch queries taking longer than this (ms) are logged as slow queries. 0 disables the log.
+    #[serde(default = "default::slow_query_threshold_ms")]
+    pub slow_query_threshold_ms: u64,
+
+    /// Number of the latest slow queries kept in memory.
+    #[serde(default = "default::slow_log_capacity")]
+    pub capacity: usize,
+}
+
+impl Default for SlowLogConfig {
+    fn default() -> Self {
+        toml::from_str("").unwrap()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlanCacheConfig {
+    /// Number of batch query plans cached by the frontend. 0 disables the cache.
+    #[serde(default = "default::plan_cache_capacity")]
+    pub capacity: usize,
+}
+
+impl Default for PlanCacheConfig {
+    fn default() -> Self {
+        toml::from_str("").unwrap()
+    }
+}
+
 /// Currently all configurations are server before they can be specified with DDL syntaxes.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StorageConfig {
@@ -126,6 +200,33 @@ pub struct StorageConfig {
     /// Capacity of sstable meta cache.
     #[serde(default = "default::meta_cache_capacity")]
     pub meta_cache_capacity: usize,
+
+    /// Number of blocks read ahead when iterating sstables sequentially. 0 disables prefetching.
+    #[serde(default = "default::block_prefetch_depth")]
+    pub block_prefetch_depth: usize,
+
+    /// Local file to persist the access frequency of blocks to, which is used to warm up the block
+    /// cache on restart. Empty disables warm-up.
+    #[serde(default)]
+    pub block_cache_warmup_path: String,
+
+    /// Number of the hottest blocks prefetched into the block cache on restart.
+    #[serde(default = "default::block_cache_warmup_blocks")]
+    pub block_cache_warmup_blocks: usize,
+
+    /// Max number of SSTs uploaded concurrently by each split of a compaction. Should NOT be 0.
+    #[serde(default = "default::sst_upload_concurrency")]
+    pub sst_upload_concurrency: u32,
+
+    /// Bandwidth cap of uploading the SSTs synced from shared buffers (bytes per second). 0
+    /// means unlimited.
+    #[serde(default)]
+    pub sst_upload_bandwidth: u64,
+
+    /// Objects larger than it are uploaded to S3 in parts of this size concurrently. 0 disables
+    /// multipart upload.
+    #[serde(default = "default::multipart_upload_part_size")]
+    pub multipart_upload_part_size: usize,
 }
 
 impl Default for StorageConfig {
@@ -134,6 +235,26 @@ impl Default for StorageConfig {
     }
 }
 
+/// Configurations of the memory governor, which divides a total budget between the block cache,
+/// the shared buffer and the streaming executor caches of the compute node.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MemoryConfig {
+    /// Total memory budget of the caches and buffers (bytes). 0 disables the governor, and each
+    /// of them is limited by its own capacity in other sections.
+    #[serde(default)]
+    pub total_budget: usize,
+
+    /// Interval of rebalancing the budget between the consumers (ms).
+    #[serde(default = "default::memory_rebalance_interval_ms")]
+    pub rebalance_interval_ms: u64,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        toml::from_str("").unwrap()
+    }
+}
+
 impl ComputeNodeConfig {
     pub fn init(path: PathBuf) -> Result<ComputeNodeConfig> {
         let config_str = fs::read_to_string(path.clone()).map_err(|e| {
@@ -178,6 +299,30 @@ mod default {
         1024
     }
 
+    pub fn lookup_cache_capacity() -> usize {
+        65536
+    }
+
+    pub fn fuse_stateless_operators() -> bool {
+        true
+    }
+
+    pub fn extreme_cache_capacity() -> usize {
+        1024
+    }
+
+    pub fn slow_query_threshold_ms() -> u64 {
+        5000
+    }
+
+    pub fn slow_log_capacity() -> usize {
+        128
+    }
+
+    pub fn plan_cache_capacity() -> usize {
+        1024
+    }
+
     pub fn sst_size() -> u32 {
         // 256MB
         268435456
@@ -216,6 +361,27 @@ mod default {
         // 64 MB
         67108864
     }
+
+    pub fn block_prefetch_depth() -> usize {
+        2
+    }
+
+    pub fn block_cache_warmup_blocks() -> usize {
+        4096
+    }
+
+    pub fn sst_upload_concurrency() -> u32 {
+        4
+    }
+
+    pub fn multipart_upload_part_size() -> usize {
+        // 16 MB
+        16777216
+    }
+
+    pub fn memory_rebalance_interval_ms() -> u64 {
+        10000
+    }
 }
 
 #[cfg(test)]
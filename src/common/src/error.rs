@@ -20,9 +20,10 @@ use std::fmt::{Debug, Display, Formatter};
 use std::io::Error as IoError;
 use std::sync::Arc;
 
+use arrow::error::ArrowError;
 use memcomparable::Error as MemComparableError;
 use prost::Message;
-use risingwave_pb::common::Status;
+use risingwave_pb::common::{ErrorComponent, Status};
 use risingwave_pb::ProstFieldNotFound;
 use thiserror::Error;
 use tokio::task::JoinError;
@@ -127,6 +128,11 @@ pub enum ErrorCode {
 
     #[error("Unknown error: {0}")]
     UnknownError(String),
+
+    /// An error returned by another node, decoded from the [`RW_ERROR_GRPC_HEADER`] of the grpc
+    /// status, so that its SQLSTATE and retryability are kept.
+    #[error("{}", .0.message)]
+    RemoteError(Status),
 }
 
 #[derive(Clone)]
@@ -163,6 +169,39 @@ impl RwError {
         Status {
             code: self.inner.get_code() as i32,
             message: self.to_string(),
+            sqlstate: self.inner.sqlstate().to_string(),
+            retryable: self.inner.is_retryable(),
+            component: self.inner.component() as i32,
+        }
+    }
+
+    /// Converts a grpc error returned by another node. The error is restored from the
+    /// [`RW_ERROR_GRPC_HEADER`] if the node sent a [`RwError`], otherwise it's an RPC failure.
+    fn from_grpc_status(status: tonic::Status, context: Option<String>) -> Self {
+        let with_context = |message: &str| match &context {
+            Some(context) => format!("{}: {}", context, message),
+            None => message.to_string(),
+        };
+        if let Some(mut remote) = status
+            .metadata()
+            .get_bin(RW_ERROR_GRPC_HEADER)
+            .and_then(|header| header.to_bytes().ok())
+            .and_then(|bytes| Status::decode(bytes).ok())
+        {
+            remote.message = with_context(&remote.message);
+            return ErrorCode::RemoteError(remote).into();
+        }
+        match status.code() {
+            // The node is down or overloaded, which may recover soon.
+            Code::Unavailable | Code::DeadlineExceeded => ErrorCode::RemoteError(Status {
+                code: ErrorCode::InternalError(String::new()).get_code() as i32,
+                message: with_context(&format!("rpc error: {}", status.message())),
+                sqlstate: "08006".to_string(),
+                retryable: true,
+                component: ErrorComponent::Rpc as i32,
+            })
+            .into(),
+            _ => ErrorCode::InternalError(with_context(status.message())).into(),
         }
     }
 
@@ -213,6 +252,12 @@ impl From<std::net::AddrParseError> for RwError {
     }
 }
 
+impl From<ArrowError> for RwError {
+    fn from(arrow_error: ArrowError) -> Self {
+        ErrorCode::InternalError(format!("arrow error: {}", arrow_error)).into()
+    }
+}
+
 impl From<Infallible> for RwError {
     fn from(x: Infallible) -> Self {
         match x {}
@@ -272,6 +317,61 @@ impl ErrorCode {
             ErrorCode::Eof => 22,
             ErrorCode::BindError(_) => 23,
             ErrorCode::UnknownError(_) => 101,
+            ErrorCode::RemoteError(status) => status.code as u32,
+        }
+    }
+
+    /// The SQLSTATE reported to the client. See
+    /// <https://www.postgresql.org/docs/current/errcodes-appendix.html>.
+    pub fn sqlstate(&self) -> &str {
+        match self {
+            ErrorCode::OK => "00000",
+            ErrorCode::MemoryError { .. } => "53200",
+            ErrorCode::NotImplemented(..) => "0A000",
+            ErrorCode::IoError(_) => "58030",
+            ErrorCode::StorageError(_) => "58000",
+            ErrorCode::ParseError(_) | ErrorCode::InvalidInputSyntax(_) => "42601",
+            ErrorCode::BindError(_) | ErrorCode::CatalogError(_) => "42000",
+            ErrorCode::ItemNotFound(_) => "42704",
+            ErrorCode::NumericValueOutOfRange => "22003",
+            ErrorCode::ProtocolError(_) => "08P01",
+            ErrorCode::MetaError(_) => "08006",
+            ErrorCode::RemoteError(status) if !status.sqlstate.is_empty() => &status.sqlstate,
+            _ => "XX000",
+        }
+    }
+
+    /// Whether the failed request may succeed if retried without any change, e.g. when the meta
+    /// service or the storage is temporarily unreachable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ErrorCode::IoError(_)
+            | ErrorCode::StorageError(_)
+            | ErrorCode::MetaError(_)
+            | ErrorCode::TaskNotFound => true,
+            ErrorCode::RemoteError(status) => status.retryable,
+            _ => false,
+        }
+    }
+
+    /// The part of the system where the error comes from.
+    pub fn component(&self) -> ErrorComponent {
+        match self {
+            ErrorCode::ParseError(_)
+            | ErrorCode::BindError(_)
+            | ErrorCode::CatalogError(_)
+            | ErrorCode::InvalidInputSyntax(_) => ErrorComponent::Frontend,
+            ErrorCode::MetaError(_) => ErrorComponent::Meta,
+            ErrorCode::StorageError(_) => ErrorComponent::Storage,
+            ErrorCode::StreamError(_)
+            | ErrorCode::NumericValueOutOfRange
+            | ErrorCode::TaskNotFound
+            | ErrorCode::MemoryError { .. } => ErrorComponent::Compute,
+            ErrorCode::ProtocolError(_) | ErrorCode::ProstError(_) => ErrorComponent::Rpc,
+            ErrorCode::RemoteError(status) => {
+                ErrorComponent::from_i32(status.component).unwrap_or(ErrorComponent::Unspecified)
+            }
+            _ => ErrorComponent::Unspecified,
         }
     }
 }
@@ -326,6 +426,12 @@ pub trait ToRwResult<T, E> {
     fn to_rw_result_with(self, func: impl FnOnce() -> String) -> Result<T>;
 }
 
+impl From<tonic::Status> for RwError {
+    fn from(status: tonic::Status) -> Self {
+        RwError::from_grpc_status(status, None)
+    }
+}
+
 impl<T, E: ToErrorStr> ToRwResult<T, E> for std::result::Result<T, E> {
     fn to_rw_result(self) -> Result<T> {
         self.map_err(|e| ErrorCode::InternalError(e.to_error_str()).into())
@@ -338,11 +444,15 @@ impl<T, E: ToErrorStr> ToRwResult<T, E> for std::result::Result<T, E> {
     }
 }
 
-impl ToErrorStr for tonic::Status {
-    /// [`tonic::Status`] means no transportation error but only application-level failure.
-    /// In this case we focus on the message rather than other fields.
-    fn to_error_str(self) -> String {
-        self.message().to_string()
+/// [`tonic::Status`] means no transportation error but only application-level failure. The
+/// [`RwError`] sent by the remote node is restored, rather than being flattened into a string.
+impl<T> ToRwResult<T, tonic::Status> for std::result::Result<T, tonic::Status> {
+    fn to_rw_result(self) -> Result<T> {
+        self.map_err(RwError::from)
+    }
+
+    fn to_rw_result_with(self, func: impl FnOnce() -> String) -> Result<T> {
+        self.map_err(|status| RwError::from_grpc_status(status, Some(func())))
     }
 }
 
@@ -560,6 +670,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_grpc_status_round_trip() {
+        let err = RwError::from(ErrorCode::BindError("column not found".to_string()));
+        let remote = Err::<(), _>(err.to_grpc_status())
+            .to_rw_result_with(|| "failed to create task".to_string())
+            .unwrap_err();
+        assert!(matches!(remote.inner(), ErrorCode::RemoteError(_)));
+        assert_eq!(
+            remote.to_string(),
+            "failed to create task: Bind error: column not found"
+        );
+        assert_eq!(remote.inner().sqlstate(), "42000");
+        assert_eq!(remote.inner().component(), ErrorComponent::Frontend);
+        assert!(!remote.inner().is_retryable());
+
+        let err = RwError::from(ErrorCode::MetaError("connection refused".to_string()));
+        let remote = RwError::from(err.to_grpc_status());
+        assert_eq!(remote.inner().sqlstate(), "08006");
+        assert_eq!(remote.inner().component(), ErrorComponent::Meta);
+        assert!(remote.inner().is_retryable());
+
+        // Errors not sent by a node, e.g. when the node is down.
+        let unavailable = RwError::from(tonic::Status::unavailable("connection reset"));
+        assert_eq!(unavailable.to_string(), "rpc error: connection reset");
+        assert_eq!(unavailable.inner().component(), ErrorComponent::Rpc);
+        assert!(unavailable.inner().is_retryable());
+        let unknown = RwError::from(tonic::Status::unknown("oops"));
+        assert_eq!(unknown.to_string(), "internal error: oops");
+        assert_eq!(unknown.inner().sqlstate(), "XX000");
+        assert!(!unknown.inner().is_retryable());
+    }
+
     #[test]
     fn test_to_rw_result() {
         let res: core::result::Result<(), anyhow::Error> = Err(anyhow::Error::new(
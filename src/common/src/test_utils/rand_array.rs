@@ -27,8 +27,8 @@ use rand::{Rng, SeedableRng};
 
 use crate::array::{Array, ArrayBuilder, ArrayRef, ListValue, StructValue};
 use crate::types::{
-    Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, NativeType,
-    Scalar,
+    BitString, Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper,
+    NativeType, Scalar,
 };
 
 pub trait RandValue {
@@ -53,6 +53,20 @@ impl RandValue for String {
     }
 }
 
+impl RandValue for Box<[u8]> {
+    fn rand_value<R: Rng>(rand: &mut R) -> Self {
+        let len = rand.gen_range(1..=10);
+        (0..len).map(|_| rand.gen::<u8>()).collect()
+    }
+}
+
+impl RandValue for BitString {
+    fn rand_value<R: Rng>(rand: &mut R) -> Self {
+        let len = rand.gen_range(1..=20);
+        (0..len).map(|_| rand.gen::<bool>()).collect()
+    }
+}
+
 impl RandValue for Decimal {
     fn rand_value<R: Rng>(rand: &mut R) -> Self {
         Decimal::from_f64((rand.gen::<u32>() as f64) + 0.1f64).unwrap()
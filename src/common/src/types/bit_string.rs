@@ -0,0 +1,325 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::ErrorCode::InvalidInputSyntax;
+use crate::error::{Result, RwError};
+use crate::types::{Scalar, ScalarImpl, ScalarRef};
+
+/// Number of bytes needed to hold `len` bits.
+#[inline]
+fn byte_len(len: usize) -> usize {
+    (len + 7) / 8
+}
+
+/// Clears the padding bits after the last `len` bits, so that equal bit strings always have equal
+/// bytes.
+fn clear_padding(bits: &mut [u8], len: usize) {
+    if len % 8 != 0 {
+        if let Some(last) = bits.last_mut() {
+            *last &= 0xffu8 << (8 - len % 8);
+        }
+    }
+}
+
+/// `BitString` is the value of a `BIT` or `BIT VARYING` column, e.g. `B'10110'`.
+///
+/// Bits are packed starting from the most significant bit of the first byte, and the padding bits
+/// of the last byte are always zero. With this layout, comparing the bytes and then the length
+/// gives the same order as PostgreSQL, where a bit string is smaller than any longer string it is a
+/// prefix of.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitString {
+    bits: Box<[u8]>,
+    len: usize,
+}
+
+/// Reference of [`BitString`], which points to the packed bits inside an array or a scalar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitStringRef<'a> {
+    bits: &'a [u8],
+    len: usize,
+}
+
+impl BitString {
+    /// Creates a bit string of `len` bits from packed `bits`. Padding bits are cleared.
+    pub fn new(bits: impl Into<Box<[u8]>>, len: usize) -> Self {
+        let mut bits = bits.into();
+        assert_eq!(bits.len(), byte_len(len), "bytes mismatch with bit length");
+        clear_padding(&mut bits, len);
+        Self { bits, len }
+    }
+
+    /// Creates a bit string of `len` zero bits.
+    pub fn zeros(len: usize) -> Self {
+        Self {
+            bits: vec![0; byte_len(len)].into_boxed_slice(),
+            len,
+        }
+    }
+}
+
+/// Implement `Scalar` for `BitString`.
+/// `BitString` could be converted to `BitStringRef`.
+impl Scalar for BitString {
+    type ScalarRefType<'a> = BitStringRef<'a>;
+
+    fn as_scalar_ref(&self) -> BitStringRef<'_> {
+        BitStringRef {
+            bits: &self.bits,
+            len: self.len,
+        }
+    }
+
+    fn to_scalar_value(self) -> ScalarImpl {
+        ScalarImpl::BitString(self)
+    }
+}
+
+/// Implement `ScalarRef` for `BitStringRef`.
+impl<'a> ScalarRef<'a> for BitStringRef<'a> {
+    type ScalarType = BitString;
+
+    fn to_owned_scalar(&self) -> BitString {
+        BitString {
+            bits: self.bits.into(),
+            len: self.len,
+        }
+    }
+}
+
+impl FromIterator<bool> for BitString {
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let mut bits = vec![];
+        let mut len = 0;
+        for bit in iter {
+            if len % 8 == 0 {
+                bits.push(0);
+            }
+            if bit {
+                *bits.last_mut().unwrap() |= 0x80 >> (len % 8);
+            }
+            len += 1;
+        }
+        Self {
+            bits: bits.into_boxed_slice(),
+            len,
+        }
+    }
+}
+
+impl FromStr for BitString {
+    type Err = RwError;
+
+    /// Parses a string of `0`s and `1`s, e.g. `10110`.
+    fn from_str(s: &str) -> Result<Self> {
+        s.chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => {
+                    Err(InvalidInputSyntax(format!("\"{}\" is not a valid binary digit", c)).into())
+                }
+            })
+            .collect()
+    }
+}
+
+impl Display for BitString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.as_scalar_ref().fmt(f)
+    }
+}
+
+impl<'a> BitStringRef<'a> {
+    pub fn new(bits: &'a [u8], len: usize) -> Self {
+        debug_assert_eq!(bits.len(), byte_len(len));
+        Self { bits, len }
+    }
+
+    /// Number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The packed bytes, including the zero padding bits of the last byte.
+    pub fn bits(&self) -> &'a [u8] {
+        self.bits
+    }
+
+    /// Returns the `idx`-th bit, counting from the leftmost one.
+    pub fn get(&self, idx: usize) -> bool {
+        assert!(idx < self.len);
+        self.bits[idx / 8] & (0x80 >> (idx % 8)) != 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + 'a {
+        let this = *self;
+        (0..self.len).map(move |idx| this.get(idx))
+    }
+
+    fn zip_with(
+        &self,
+        other: BitStringRef<'_>,
+        op_name: &str,
+        f: impl Fn(u8, u8) -> u8,
+    ) -> Result<BitString> {
+        if self.len != other.len {
+            return Err(InvalidInputSyntax(format!(
+                "cannot {} bit strings of different sizes",
+                op_name
+            ))
+            .into());
+        }
+        let bits = self
+            .bits
+            .iter()
+            .zip(other.bits.iter())
+            .map(|(l, r)| f(*l, *r))
+            .collect::<Vec<_>>();
+        Ok(BitString::new(bits, self.len))
+    }
+
+    pub fn bitand(&self, other: BitStringRef<'_>) -> Result<BitString> {
+        self.zip_with(other, "AND", |l, r| l & r)
+    }
+
+    pub fn bitor(&self, other: BitStringRef<'_>) -> Result<BitString> {
+        self.zip_with(other, "OR", |l, r| l | r)
+    }
+
+    pub fn bitxor(&self, other: BitStringRef<'_>) -> Result<BitString> {
+        self.zip_with(other, "XOR", |l, r| l ^ r)
+    }
+
+    pub fn bitnot(&self) -> BitString {
+        let bits = self.bits.iter().map(|b| !b).collect::<Vec<_>>();
+        BitString::new(bits, self.len)
+    }
+
+    /// Shifts the bits to the left, keeping the length and filling zeros at the right. A negative
+    /// `n` shifts to the right.
+    pub fn shl(&self, n: i32) -> BitString {
+        if n < 0 {
+            return self.shr(n.saturating_neg());
+        }
+        let n = n as usize;
+        (0..self.len)
+            .map(|idx| idx + n < self.len && self.get(idx + n))
+            .collect()
+    }
+
+    /// Shifts the bits to the right, keeping the length and filling zeros at the left. A negative
+    /// `n` shifts to the left.
+    pub fn shr(&self, n: i32) -> BitString {
+        if n < 0 {
+            return self.shl(n.saturating_neg());
+        }
+        let n = n as usize;
+        (0..self.len)
+            .map(|idx| idx >= n && self.get(idx - n))
+            .collect()
+    }
+}
+
+impl Display for BitStringRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for bit in self.iter() {
+            write!(f, "{}", if bit { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bs(s: &str) -> BitString {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in ["", "0", "1", "10110", "0000000011", "111111111"] {
+            assert_eq!(bs(s).to_string(), s);
+            assert_eq!(bs(s).as_scalar_ref().len(), s.len());
+        }
+        assert_eq!(bs("101").as_scalar_ref().bits(), &[0b1010_0000]);
+        assert!("102".parse::<BitString>().is_err());
+        assert_eq!(BitString::new(vec![0xff], 3), bs("111"));
+        assert_eq!(BitString::zeros(9), bs("000000000"));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut values = [
+            "1",
+            "01",
+            "10",
+            "",
+            "0",
+            "00000000",
+            "000000000",
+            "0000000010",
+        ]
+        .map(bs)
+        .to_vec();
+        values.sort();
+        assert_eq!(
+            values.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+            [
+                "",
+                "0",
+                "00000000",
+                "000000000",
+                "0000000010",
+                "01",
+                "1",
+                "10"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bitwise_ops() {
+        let l = bs("1100110011");
+        let r = bs("1010101010");
+        assert_eq!(
+            l.as_scalar_ref().bitand(r.as_scalar_ref()).unwrap(),
+            bs("1000100010")
+        );
+        assert_eq!(
+            l.as_scalar_ref().bitor(r.as_scalar_ref()).unwrap(),
+            bs("1110111011")
+        );
+        assert_eq!(
+            l.as_scalar_ref().bitxor(r.as_scalar_ref()).unwrap(),
+            bs("0110011001")
+        );
+        assert_eq!(l.as_scalar_ref().bitnot(), bs("0011001100"));
+        assert!(l.as_scalar_ref().bitand(bs("1").as_scalar_ref()).is_err());
+
+        assert_eq!(l.as_scalar_ref().shl(3), bs("0110011000"));
+        assert_eq!(l.as_scalar_ref().shr(3), bs("0001100110"));
+        assert_eq!(l.as_scalar_ref().shl(-3), l.as_scalar_ref().shr(3));
+        assert_eq!(l.as_scalar_ref().shl(20), bs("0000000000"));
+    }
+}
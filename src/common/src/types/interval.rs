@@ -19,6 +19,7 @@ use std::ops::{Add, Sub};
 use byteorder::{BigEndian, WriteBytesExt};
 use bytes::BytesMut;
 use num_traits::{CheckedAdd, CheckedSub};
+use risingwave_pb::data::IntervalUnit as ProstIntervalUnit;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
@@ -131,6 +132,14 @@ impl IntervalUnit {
         .map_err(|e| RwError::from(IoError(e)))
     }
 
+    pub fn to_protobuf_prost(&self) -> ProstIntervalUnit {
+        ProstIntervalUnit {
+            months: self.months,
+            days: self.days,
+            ms: self.ms,
+        }
+    }
+
     /// Multiple [`IntervalUnit`] by an integer with overflow check.
     pub fn checked_mul_int<I>(&self, rhs: I) -> Option<Self>
     where
@@ -177,6 +186,16 @@ impl IntervalUnit {
     }
 }
 
+impl From<&ProstIntervalUnit> for IntervalUnit {
+    fn from(p: &ProstIntervalUnit) -> Self {
+        Self {
+            months: p.months,
+            days: p.days,
+            ms: p.ms,
+        }
+    }
+}
+
 impl Add for IntervalUnit {
     type Output = Self;
 
@@ -29,13 +29,17 @@ pub use native_type::*;
 use risingwave_pb::data::data_type::TypeName;
 pub use scalar_impl::*;
 
+mod bit_string;
 mod chrono_wrapper;
 mod decimal;
 pub mod interval;
 
 mod ordered_float;
+pub use bit_string::{BitString, BitStringRef};
 use chrono::{Datelike, Timelike};
-pub use chrono_wrapper::{NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper};
+pub use chrono_wrapper::{
+    NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, UNIX_EPOCH_DAYS,
+};
 pub use decimal::Decimal;
 pub use interval::*;
 pub use ordered_float::IntoOrdered;
@@ -63,6 +67,8 @@ pub enum DataType {
     Timestamp,
     Timestampz,
     Interval,
+    Bytea,
+    Varbit,
     Struct { fields: Arc<[DataType]> },
     List { datatype: Box<DataType> },
 }
@@ -97,12 +103,19 @@ impl From<&ProstDataType> for DataType {
             TypeName::Timestampz => DataType::Timestampz,
             TypeName::Decimal => DataType::Decimal,
             TypeName::Interval => DataType::Interval,
+            TypeName::Bytea => DataType::Bytea,
+            TypeName::Varbit => DataType::Varbit,
             TypeName::Symbol => DataType::Varchar,
             TypeName::Struct => DataType::Struct {
-                fields: Arc::new([]),
+                fields: proto.field_type.iter().map(DataType::from).collect(),
             },
             TypeName::List => DataType::List {
-                datatype: Box::new(DataType::Int32),
+                datatype: Box::new(DataType::from(
+                    proto
+                        .field_type
+                        .first()
+                        .expect("missing element type of list"),
+                )),
             },
         }
     }
@@ -125,6 +138,8 @@ impl DataType {
             DataType::Timestamp => NaiveDateTimeArrayBuilder::new(capacity)?.into(),
             DataType::Timestampz => PrimitiveArrayBuilder::<i64>::new(capacity)?.into(),
             DataType::Interval => IntervalArrayBuilder::new(capacity)?.into(),
+            DataType::Bytea => BytesArrayBuilder::new(capacity)?.into(),
+            DataType::Varbit => BitStringArrayBuilder::new(capacity)?.into(),
             DataType::Struct { .. } => {
                 todo!()
             }
@@ -153,15 +168,23 @@ impl DataType {
             DataType::Timestampz => TypeName::Timestampz,
             DataType::Decimal => TypeName::Decimal,
             DataType::Interval => TypeName::Interval,
+            DataType::Bytea => TypeName::Bytea,
+            DataType::Varbit => TypeName::Varbit,
             DataType::Struct { .. } => TypeName::Struct,
             DataType::List { .. } => TypeName::List,
         }
     }
 
     pub fn to_protobuf(&self) -> ProstDataType {
+        let field_type = match self {
+            DataType::Struct { fields } => fields.iter().map(|f| f.to_protobuf()).collect(),
+            DataType::List { datatype } => vec![datatype.to_protobuf()],
+            _ => vec![],
+        };
         ProstDataType {
             type_name: self.prost_type_name() as i32,
             is_nullable: true,
+            field_type,
             ..Default::default()
         }
     }
@@ -182,6 +205,8 @@ impl DataType {
             DataType::Timestamp => DataSize::Fixed(size_of::<i64>()),
             DataType::Timestampz => DataSize::Fixed(size_of::<i64>()),
             DataType::Interval => DataSize::Variable,
+            DataType::Bytea => DataSize::Variable,
+            DataType::Varbit => DataSize::Variable,
             DataType::Struct { .. } => DataSize::Variable,
             DataType::List { .. } => DataSize::Variable,
         }
@@ -274,7 +299,9 @@ macro_rules! for_all_scalar_variants {
             { NaiveDateTime, naivedatetime, NaiveDateTimeWrapper, NaiveDateTimeWrapper },
             { NaiveTime, naivetime, NaiveTimeWrapper, NaiveTimeWrapper },
             { Struct, struct, StructValue, StructRef<'scalar> },
-            { List, list, ListValue, ListRef<'scalar> }
+            { List, list, ListValue, ListRef<'scalar> },
+            { Bytea, bytea, Box<[u8]>, &'scalar [u8] },
+            { BitString, bit_string, BitString, BitStringRef<'scalar> }
         }
     };
 }
@@ -539,6 +566,8 @@ impl std::hash::Hash for ScalarImpl {
                     Self::NaiveTime(naivetime) => naivetime.hash(state),
                     Self::Struct(v) => v.hash(state),
                     Self::List(v) => v.hash(state),
+                    Self::Bytea(v) => v.hash(state),
+                    Self::BitString(v) => v.hash(state),
                 }
             };
         }
@@ -546,13 +575,28 @@ impl std::hash::Hash for ScalarImpl {
     }
 }
 
+/// Formats the inner value of a scalar. `bytea` does not implement `Display`, so it is formatted
+/// in the hex format of PostgreSQL, e.g. `\xdeadbeef`.
+macro_rules! display_scalar_inner {
+    (Bytea, $inner:expr, $f:ident) => {{
+        write!($f, "\\x")?;
+        for byte in $inner.iter() {
+            write!($f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }};
+    ($variant_name:ident, $inner:expr, $f:ident) => {
+        Display::fmt($inner, $f)
+    };
+}
+
 impl Display for ScalarImpl {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         macro_rules! impl_display_fmt {
             ([], $( { $variant_name:ident, $suffix_name:ident, $scalar:ty, $scalar_ref:ty } ),*) => {
                 match self {
                     $( Self::$variant_name(ref inner) => {
-                        Display::fmt(inner, f)
+                        display_scalar_inner!($variant_name, inner, f)
                     }, )*
                 }
             }
@@ -568,7 +612,7 @@ impl Display for ScalarRefImpl<'_> {
             ([], $( { $variant_name:ident, $suffix_name:ident, $scalar:ty, $scalar_ref:ty } ),*) => {
                 match self {
                     $( Self::$variant_name(inner) => {
-                        Display::fmt(inner, f)
+                        display_scalar_inner!($variant_name, inner, f)
                     }, )*
                 }
             }
@@ -603,6 +647,11 @@ impl ScalarRefImpl<'_> {
             &Self::NaiveTime(v) => {
                 ser.serialize_naivetime(v.0.num_seconds_from_midnight(), v.0.nanosecond())?
             }
+            &Self::Bytea(v) => ser.serialize_byte_array(v)?,
+            &Self::BitString(v) => {
+                ser.serialize_byte_array(v.bits())?;
+                (v.len() as u32).serialize(ser)?;
+            }
             _ => {
                 panic!("Type is unable to be serialized.")
             }
@@ -657,6 +706,12 @@ impl ScalarImpl {
                 let days = de.deserialize_naivedate()?;
                 NaiveDateWrapper::new_with_days(days)?
             }),
+            Ty::Bytea => Self::Bytea(de.deserialize_byte_array()?.into_boxed_slice()),
+            Ty::Varbit => Self::BitString({
+                let bits = de.deserialize_byte_array()?;
+                let len = u32::deserialize(de)?;
+                BitString::new(bits, len as usize)
+            }),
             _ => {
                 panic!("Type is unable to be deserialized.")
             }
@@ -744,4 +799,36 @@ mod tests {
         assert!(decoded_floats.is_sorted());
         assert_eq!(floats, decoded_floats);
     }
+
+    #[test]
+    fn test_bit_string_memcomparable() {
+        let bit_strings = [
+            "",
+            "0",
+            "00000000",
+            "000000000",
+            "0000000010",
+            "01",
+            "1",
+            "10",
+        ]
+        .map(|s| s.parse::<BitString>().unwrap());
+
+        let memcomparables = bit_strings
+            .iter()
+            .map(|v| {
+                let mut serializer = memcomparable::Serializer::new(vec![]);
+                serialize_datum_not_null_into(&Some(v.clone().into()), &mut serializer).unwrap();
+                serializer.into_inner()
+            })
+            .collect_vec();
+        assert!(memcomparables.is_sorted());
+
+        for (v, data) in bit_strings.iter().zip_eq(memcomparables) {
+            let mut deserializer = memcomparable::Deserializer::new(data.as_slice());
+            let datum =
+                deserialize_datum_not_null_from(DataType::Varbit, &mut deserializer).unwrap();
+            assert_eq!(datum, Some(ScalarImpl::BitString(v.clone())));
+        }
+    }
 }
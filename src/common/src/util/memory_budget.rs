@@ -0,0 +1,143 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Memory budgets of node-level memory consumers, e.g. caches and buffers. A budget is owned by
+//! its consumer, and may be resized at any time by the memory governor of the node, which decides
+//! from the hit and miss counts recorded by the consumer.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The memory budget of a consumer, in bytes.
+#[derive(Debug)]
+pub struct MemoryBudget {
+    /// Name of the consumer, used in logs.
+    name: &'static str,
+    bytes: AtomicUsize,
+
+    /// Number of [`MemoryShare`]s the budget is evenly divided into.
+    shares: AtomicUsize,
+
+    /// Accesses served by the consumer, since the last [`MemoryBudget::take_stats`].
+    hits: AtomicU64,
+    /// Accesses the consumer failed to serve due to lack of memory, e.g. cache misses or writes
+    /// stalled by a full buffer, since the last [`MemoryBudget::take_stats`].
+    misses: AtomicU64,
+}
+
+pub type MemoryBudgetRef = Arc<MemoryBudget>;
+
+impl MemoryBudget {
+    pub fn new(name: &'static str, bytes: usize) -> Self {
+        Self {
+            name,
+            bytes: AtomicUsize::new(bytes),
+            shares: AtomicUsize::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn set_bytes(&self, bytes: usize) {
+        self.bytes.store(bytes, Ordering::Relaxed);
+    }
+
+    pub fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the hit and miss counts since the last call, and resets them.
+    pub fn take_stats(&self) -> (u64, u64) {
+        (
+            self.hits.swap(0, Ordering::Relaxed),
+            self.misses.swap(0, Ordering::Relaxed),
+        )
+    }
+
+    /// Registers a consumer sharing this budget with others, e.g. one of the per-executor caches.
+    /// The budget is evenly divided between all live shares.
+    pub fn share(self: &Arc<Self>) -> MemoryShare {
+        self.shares.fetch_add(1, Ordering::Relaxed);
+        MemoryShare {
+            budget: self.clone(),
+        }
+    }
+}
+
+/// A part of a [`MemoryBudget`], released when dropped.
+#[derive(Debug)]
+pub struct MemoryShare {
+    budget: MemoryBudgetRef,
+}
+
+impl MemoryShare {
+    /// Returns the bytes of this share, which changes as the budget is resized or other shares
+    /// come and go.
+    pub fn bytes(&self) -> usize {
+        self.budget.bytes() / self.budget.shares.load(Ordering::Relaxed).max(1)
+    }
+
+    pub fn record_hit(&self) {
+        self.budget.record_hit();
+    }
+
+    pub fn record_miss(&self) {
+        self.budget.record_miss();
+    }
+}
+
+impl Drop for MemoryShare {
+    fn drop(&mut self) {
+        self.budget.shares.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_budget() {
+        let budget = Arc::new(MemoryBudget::new("test", 1000));
+        budget.record_hit();
+        budget.record_hit();
+        budget.record_miss();
+        assert_eq!(budget.take_stats(), (2, 1));
+        assert_eq!(budget.take_stats(), (0, 0));
+
+        let share1 = budget.share();
+        assert_eq!(share1.bytes(), 1000);
+        let share2 = budget.share();
+        assert_eq!(share1.bytes(), 500);
+        budget.set_bytes(2000);
+        assert_eq!(share2.bytes(), 1000);
+        drop(share2);
+        assert_eq!(share1.bytes(), 2000);
+
+        share1.record_miss();
+        assert_eq!(budget.take_stats(), (0, 1));
+    }
+}
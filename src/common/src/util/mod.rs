@@ -25,8 +25,10 @@ pub mod chunk_coalesce;
 pub mod encoding_for_comparison;
 pub mod env_var;
 pub mod hash_util;
+pub mod memory_budget;
 pub mod ordered;
 pub mod prost;
+pub mod slow_log;
 pub mod sort_util;
 #[macro_use]
 pub mod try_match;
@@ -74,6 +74,13 @@ impl OrderedRowSerializer {
         Self { order_types }
     }
 
+    /// Returns the serializer of the first `len` columns, e.g. for a prefix of the key.
+    pub fn prefix(&self, len: usize) -> Self {
+        Self {
+            order_types: self.order_types[..len].to_vec(),
+        }
+    }
+
     pub fn serialize(&self, row: &Row, append_to: &mut Vec<u8>) {
         for (datum, order_type) in row.0.iter().zip_eq(self.order_types.iter()) {
             let mut serializer = memcomparable::Serializer::new(vec![]);
@@ -0,0 +1,77 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Slow logs of batch queries and barriers. Records exceeding a threshold are emitted to the
+//! [`SLOW_LOG_TARGET`] log target, and kept in a [`SlowLogBuffer`] exposed as
+//! `rw_catalog.rw_slow_queries` and `rw_catalog.rw_slow_barriers` respectively.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// The dedicated log target of slow logs.
+pub const SLOW_LOG_TARGET: &str = "risingwave_slow_log";
+
+/// A bounded ring buffer keeping the latest slow log records. The oldest record is evicted once
+/// the buffer is full.
+#[derive(Debug)]
+pub struct SlowLogBuffer<T> {
+    capacity: usize,
+    records: Mutex<VecDeque<T>>,
+}
+
+pub type SlowLogBufferRef<T> = Arc<SlowLogBuffer<T>>;
+
+impl<T: Clone> SlowLogBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn push(&self, record: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Returns all records in the buffer, from the oldest to the latest.
+    pub fn records(&self) -> Vec<T> {
+        self.records.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slow_log_buffer() {
+        let buffer = SlowLogBuffer::new(3);
+        assert!(buffer.records().is_empty());
+        for i in 0..5 {
+            buffer.push(i);
+        }
+        assert_eq!(buffer.records(), vec![2, 3, 4]);
+
+        let buffer = SlowLogBuffer::new(0);
+        buffer.push(1);
+        assert!(buffer.records().is_empty());
+    }
+}
@@ -18,7 +18,7 @@ use std::sync::Arc;
 use risingwave_pb::expr::InputRefExpr;
 use risingwave_pb::plan::{ColumnOrder, OrderType as ProstOrderType};
 
-use crate::array::{Array, ArrayImpl, DataChunk, DataChunkRef};
+use crate::array::{Array, ArrayImpl, DataChunk, DataChunkRef, Utf8Array};
 use crate::error::ErrorCode::InternalError;
 use crate::error::Result;
 use crate::types::{ScalarPartialOrd, ScalarRef};
@@ -136,7 +136,32 @@ where
         lhs_array.value_at(lhs_idx).unwrap(),
         rhs_array.value_at(rhs_idx).unwrap(),
     );
-    match lhs_val.to_owned_scalar().scalar_cmp(rhs_val).unwrap() {
+    apply_order_type(
+        lhs_val.to_owned_scalar().scalar_cmp(rhs_val).unwrap(),
+        order_type,
+    )
+}
+
+/// Rows from the same dictionary encoded `Utf8Array` are compared by their keys, which avoids
+/// touching the strings.
+fn compare_utf8_in_array(
+    lhs_array: &Utf8Array,
+    lhs_idx: usize,
+    rhs_array: &Utf8Array,
+    rhs_idx: usize,
+    order_type: &OrderType,
+) -> Ordering {
+    if std::ptr::eq(lhs_array, rhs_array) {
+        if let Some(keys) = lhs_array.dictionary_keys() {
+            debug_assert!(!lhs_array.is_null(lhs_idx) && !rhs_array.is_null(rhs_idx));
+            return apply_order_type(keys[lhs_idx].cmp(&keys[rhs_idx]), order_type);
+        }
+    }
+    compare_value_in_array(lhs_array, lhs_idx, rhs_array, rhs_idx, order_type)
+}
+
+fn apply_order_type(ordering: Ordering, order_type: &OrderType) -> Ordering {
+    match ordering {
         Ordering::Equal => Ordering::Equal,
         Ordering::Less => {
             if *order_type == OrderType::Ascending {
@@ -174,24 +199,37 @@ pub fn compare_two_row(
         }
     }
         let (lhs_array, rhs_array) = (lhs_array.as_ref(), rhs_array.as_ref());
-        let res = gen_match!(
-            lhs_array,
-            rhs_array,
-            [
-                Int16,
-                Int32,
-                Int64,
-                Float32,
-                Float64,
-                Utf8,
-                Bool,
-                Decimal,
-                Interval,
-                NaiveDate,
-                NaiveDateTime,
-                NaiveTime
-            ]
-        );
+        let res = if let (ArrayImpl::Utf8(lhs_inner), ArrayImpl::Utf8(rhs_inner)) =
+            (lhs_array, rhs_array)
+        {
+            compare_utf8_in_array(
+                lhs_inner,
+                lhs_idx,
+                rhs_inner,
+                rhs_idx,
+                &order_pair.order_type,
+            )
+        } else {
+            gen_match!(
+                lhs_array,
+                rhs_array,
+                [
+                    Int16,
+                    Int32,
+                    Int64,
+                    Float32,
+                    Float64,
+                    Bytea,
+                    BitString,
+                    Bool,
+                    Decimal,
+                    Interval,
+                    NaiveDate,
+                    NaiveDateTime,
+                    NaiveTime
+                ]
+            )
+        };
         if res != Ordering::Equal {
             return Ok(res);
         }
@@ -31,6 +31,7 @@
 #[macro_use]
 extern crate log;
 
+pub mod memory_governor;
 pub mod rpc;
 pub mod server;
 
@@ -70,6 +71,11 @@ pub struct ComputeNodeOpts {
     /// Enable reporting tracing information to jaeger
     #[clap(long)]
     pub enable_jaeger_tracing: bool,
+
+    /// Don't deploy a hummock compactor along with the compute node, e.g. to benchmark without
+    /// compaction.
+    #[clap(long)]
+    pub disable_compactor: bool,
 }
 
 use crate::server::compute_node_serve;
@@ -0,0 +1,207 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use risingwave_common::util::memory_budget::MemoryBudgetRef;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+
+/// Every consumer keeps at least this percentage of the total budget.
+const MIN_SHARE_PERCENT: usize = 10;
+
+/// Percentage of the total budget moved between two consumers in one rebalance.
+const STEP_PERCENT: usize = 5;
+
+/// The budget is only moved if the miss rates of the two consumers differ by more than this, so
+/// that the budgets don't flap between consumers with similar pressure.
+const MIN_MISS_RATE_DIFF: f64 = 0.05;
+
+/// Divides a total memory budget between the memory consumers of the compute node, i.e. the block
+/// cache, the shared buffer and the streaming executor caches, instead of limiting each of them
+/// independently.
+///
+/// The budgets start proportional to the capacities configured for each consumer. On every
+/// rebalance, a step of the budget is moved from the consumer with the lowest miss rate to the one
+/// with the highest. For the shared buffer, writes stalled by flushes count as misses, so that the
+/// backpressure of the uploader draws memory from the caches.
+pub struct MemoryGovernor {
+    total: usize,
+    budgets: Vec<MemoryBudgetRef>,
+}
+
+impl MemoryGovernor {
+    pub fn new(total: usize, budgets: Vec<MemoryBudgetRef>) -> Self {
+        let configured = budgets
+            .iter()
+            .map(|budget| budget.bytes())
+            .sum::<usize>()
+            .max(1);
+        let min = total / 100 * MIN_SHARE_PERCENT;
+        let mut assigned = 0;
+        for budget in &budgets {
+            let bytes =
+                ((total as u128 * budget.bytes() as u128 / configured as u128) as usize).max(min);
+            budget.set_bytes(bytes);
+            assigned += bytes;
+        }
+        // Give the rounding error or the excess of minimums to the largest budget.
+        if let Some(largest) = budgets.iter().max_by_key(|budget| budget.bytes()) {
+            largest.set_bytes((largest.bytes() + total).saturating_sub(assigned));
+        }
+        for budget in &budgets {
+            tracing::info!(
+                "Memory budget of {}: {} bytes",
+                budget.name(),
+                budget.bytes()
+            );
+        }
+
+        Self { total, budgets }
+    }
+
+    /// Moves a step of the budget from the least pressured consumer to the most pressured one,
+    /// based on the miss rates since the last rebalance. Consumers without any access are treated
+    /// as not pressured.
+    fn rebalance(&self) {
+        let miss_rates = self
+            .budgets
+            .iter()
+            .map(|budget| {
+                let (hits, misses) = budget.take_stats();
+                if hits + misses == 0 {
+                    0.0
+                } else {
+                    misses as f64 / (hits + misses) as f64
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let min = self.total / 100 * MIN_SHARE_PERCENT;
+        let step = self.total / 100 * STEP_PERCENT;
+        let by_rate = |a: &(usize, &f64), b: &(usize, &f64)| a.1.partial_cmp(b.1).unwrap();
+        let receiver = miss_rates.iter().enumerate().max_by(by_rate);
+        let donor = miss_rates
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| self.budgets[*idx].bytes() > min)
+            .min_by(by_rate);
+        let (receiver, donor) = match (receiver, donor) {
+            (Some((receiver, receiver_rate)), Some((donor, donor_rate)))
+                if receiver != donor && receiver_rate - donor_rate > MIN_MISS_RATE_DIFF =>
+            {
+                (&self.budgets[receiver], &self.budgets[donor])
+            }
+            _ => return,
+        };
+
+        let moved = step.min(donor.bytes() - min);
+        donor.set_bytes(donor.bytes() - moved);
+        receiver.set_bytes(receiver.bytes() + moved);
+        tracing::debug!(
+            "Moved {} bytes of memory budget from {} to {}",
+            moved,
+            donor.name(),
+            receiver.name()
+        );
+    }
+
+    /// Starts rebalancing the budgets every `interval`.
+    pub fn start(self, interval: Duration) -> (JoinHandle<()>, UnboundedSender<()>) {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::unbounded_channel();
+        let join_handle = tokio::spawn(async move {
+            let mut min_interval = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    // Wait for interval.
+                    _ = min_interval.tick() => {},
+                    // Shutdown memory governor.
+                    _ = shutdown_rx.recv() => {
+                        tracing::info!("Memory governor is shutting down");
+                        return;
+                    }
+                }
+                self.rebalance();
+            }
+        });
+
+        (join_handle, shutdown_tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use risingwave_common::util::memory_budget::MemoryBudget;
+
+    use super::*;
+
+    fn access(budget: &MemoryBudgetRef, hits: usize, misses: usize) {
+        (0..hits).for_each(|_| budget.record_hit());
+        (0..misses).for_each(|_| budget.record_miss());
+    }
+
+    #[test]
+    fn test_memory_governor() {
+        let block_cache = Arc::new(MemoryBudget::new("block_cache", 200));
+        let shared_buffer = Arc::new(MemoryBudget::new("shared_buffer", 200));
+        let stream_cache = Arc::new(MemoryBudget::new("stream_cache", 100));
+        let governor = MemoryGovernor::new(
+            1000,
+            vec![
+                block_cache.clone(),
+                shared_buffer.clone(),
+                stream_cache.clone(),
+            ],
+        );
+        assert_eq!(block_cache.bytes(), 400);
+        assert_eq!(shared_buffer.bytes(), 400);
+        assert_eq!(stream_cache.bytes(), 200);
+
+        // Similar miss rates, nothing moves.
+        access(&block_cache, 90, 10);
+        access(&shared_buffer, 88, 12);
+        access(&stream_cache, 89, 11);
+        governor.rebalance();
+        assert_eq!(block_cache.bytes(), 400);
+        assert_eq!(shared_buffer.bytes(), 400);
+        assert_eq!(stream_cache.bytes(), 200);
+
+        // The stalled shared buffer takes from the idle stream cache, which keeps the minimum.
+        for _ in 0..2 {
+            access(&block_cache, 90, 10);
+            access(&shared_buffer, 50, 50);
+            governor.rebalance();
+        }
+        assert_eq!(block_cache.bytes(), 400);
+        assert_eq!(shared_buffer.bytes(), 500);
+        assert_eq!(stream_cache.bytes(), 100);
+
+        // Then from the block cache, the least pressured consumer above the minimum.
+        access(&block_cache, 90, 10);
+        access(&shared_buffer, 50, 50);
+        governor.rebalance();
+        assert_eq!(block_cache.bytes(), 350);
+        assert_eq!(shared_buffer.bytes(), 550);
+        assert_eq!(stream_cache.bytes(), 100);
+
+        // Idle consumers are treated as not pressured.
+        access(&stream_cache, 0, 10);
+        governor.rebalance();
+        assert_eq!(block_cache.bytes(), 300);
+        assert_eq!(shared_buffer.bytes(), 550);
+        assert_eq!(stream_cache.bytes(), 150);
+    }
+}
@@ -85,7 +85,10 @@ impl ExchangeService for ExchangeServiceImpl {
             .stream_mgr
             .take_receiver(up_down_ids)
             .map_err(|e| e.to_grpc_status())?;
-        match self.get_stream_impl(peer_addr, receiver).await {
+        match self
+            .get_stream_impl(peer_addr, receiver, req.protocol_version)
+            .await
+        {
             Ok(resp) => Ok(resp),
             Err(e) => {
                 error!(
@@ -139,6 +142,7 @@ impl ExchangeServiceImpl {
         &self,
         peer_addr: SocketAddr,
         mut receiver: Receiver<Message>,
+        protocol_version: u32,
     ) -> Result<Response<<Self as ExchangeService>::GetStreamStream>> {
         let (tx, rx) = tokio::sync::mpsc::channel(EXCHANGE_BUFFER_SIZE);
         tracing::trace!(target: "events::compute::exchange", peer_addr = %peer_addr, "serve stream exchange RPC");
@@ -149,7 +153,7 @@ impl ExchangeServiceImpl {
                     // the sender is closed, we close the receiver and stop forwarding message
                     None => break,
                     Some(msg) => {
-                        let res = match msg.to_protobuf() {
+                        let res = match msg.to_protobuf_with_version(protocol_version) {
                             Ok(stream_msg) => Ok(GetStreamResponse {
                                 message: Some(stream_msg),
                             }),
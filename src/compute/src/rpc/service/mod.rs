@@ -13,4 +13,5 @@
 // limitations under the License.
 
 pub mod exchange_service;
+pub mod serving_service;
 pub mod stream_service;
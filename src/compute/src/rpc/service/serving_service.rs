@@ -0,0 +1,189 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::TableId;
+use risingwave_common::error::{tonic_err, ErrorCode, Result as RwResult};
+use risingwave_common::util::ordered::OrderedRowSerializer;
+use risingwave_pb::data::DataChunk as ProstDataChunk;
+use risingwave_pb::serving_service::serving_service_server::ServingService;
+use risingwave_pb::serving_service::*;
+use risingwave_storage::monitor::StateStoreMetrics;
+use risingwave_storage::table::cell_based_table::CellBasedTable;
+use risingwave_storage::{dispatch_state_store, Keyspace, StateStore, StateStoreImpl};
+use risingwave_stream::task::{ArrangementDesc, ArrangementRegistryRef};
+use tonic::{Request, Response, Status};
+
+/// `ServingServiceImpl` serves point and range lookups on the materialized views owned by the
+/// local actors, reading from the state store at the latest committed epoch flushed by the actors.
+///
+/// The rows of a materialized view may be distributed to the actors on different compute nodes,
+/// so a point lookup should be sent to the node owning the key, and a range lookup only returns
+/// the rows owned by this node.
+#[derive(Clone)]
+pub struct ServingServiceImpl {
+    registry: ArrangementRegistryRef,
+    state_store: StateStoreImpl,
+}
+
+impl ServingServiceImpl {
+    pub fn new(registry: ArrangementRegistryRef, state_store: StateStoreImpl) -> Self {
+        Self {
+            registry,
+            state_store,
+        }
+    }
+
+    async fn lookup_inner(&self, req: LookupRequest) -> RwResult<LookupResponse> {
+        let (desc, epoch) = self.get_arrangement(req.table_id)?;
+        let keys = decode_rows(req.keys.as_ref(), desc.order_types.len(), "keys")?;
+
+        let rows = dispatch_state_store!(self.state_store.clone(), store, {
+            let stats = store.stats();
+            let table = build_table(store, req.table_id, &desc, stats);
+            let mut rows = Vec::with_capacity(keys.len());
+            for key in &keys {
+                if let Some(row) = table.get_row(key, epoch).await? {
+                    rows.push(row);
+                }
+            }
+            rows
+        });
+
+        Ok(LookupResponse {
+            rows: Some(encode_rows(&rows, &desc)?),
+            epoch,
+        })
+    }
+
+    async fn range_lookup_inner(&self, req: RangeLookupRequest) -> RwResult<RangeLookupResponse> {
+        let (desc, epoch) = self.get_arrangement(req.table_id)?;
+        let start = decode_bound(req.start.as_ref(), desc.order_types.len(), "start")?;
+        let end = decode_bound(req.end.as_ref(), desc.order_types.len(), "end")?;
+        let limit = (req.limit > 0).then(|| req.limit as usize);
+
+        let rows = dispatch_state_store!(self.state_store.clone(), store, {
+            let stats = store.stats();
+            let table = build_table(store, req.table_id, &desc, stats);
+            table
+                .scan_range(start.as_ref(), end.as_ref(), limit, epoch)
+                .await?
+        });
+
+        Ok(RangeLookupResponse {
+            rows: Some(encode_rows(&rows, &desc)?),
+            epoch,
+        })
+    }
+
+    fn get_arrangement(&self, table_id: u32) -> RwResult<(Arc<ArrangementDesc>, u64)> {
+        self.registry.get(&TableId::new(table_id)).ok_or_else(|| {
+            ErrorCode::ItemNotFound(format!(
+                "materialized view {} is not served by this node",
+                table_id
+            ))
+            .into()
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ServingService for ServingServiceImpl {
+    #[cfg_attr(coverage, no_coverage)]
+    async fn lookup(
+        &self,
+        request: Request<LookupRequest>,
+    ) -> Result<Response<LookupResponse>, Status> {
+        self.lookup_inner(request.into_inner())
+            .await
+            .map(Response::new)
+            .map_err(tonic_err)
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn range_lookup(
+        &self,
+        request: Request<RangeLookupRequest>,
+    ) -> Result<Response<RangeLookupResponse>, Status> {
+        self.range_lookup_inner(request.into_inner())
+            .await
+            .map(Response::new)
+            .map_err(tonic_err)
+    }
+}
+
+fn build_table<S: StateStore>(
+    store: S,
+    table_id: u32,
+    desc: &ArrangementDesc,
+    stats: Arc<StateStoreMetrics>,
+) -> CellBasedTable<S> {
+    let keyspace = Keyspace::table_root(store, &TableId::new(table_id));
+    CellBasedTable::new(
+        keyspace,
+        desc.column_descs.clone(),
+        Some(OrderedRowSerializer::new(desc.order_types.clone())),
+        stats,
+    )
+}
+
+/// Decodes the rows of `chunk`, each of which should be a full key with `key_len` columns.
+fn decode_rows(chunk: Option<&ProstDataChunk>, key_len: usize, name: &str) -> RwResult<Vec<Row>> {
+    let chunk = match chunk {
+        Some(chunk) => DataChunk::from_protobuf(chunk)?,
+        None => return Ok(vec![]),
+    };
+    if chunk.cardinality() > 0 && chunk.dimension() != key_len {
+        return Err(ErrorCode::InvalidInputSyntax(format!(
+            "{} should have {} columns, but got {}",
+            name,
+            key_len,
+            chunk.dimension()
+        ))
+        .into());
+    }
+    Ok(chunk.rows().map(Row::from).collect())
+}
+
+/// Decodes a range bound, which is a chunk of at most one row with a prefix of the key.
+fn decode_bound(
+    chunk: Option<&ProstDataChunk>,
+    key_len: usize,
+    name: &str,
+) -> RwResult<Option<Row>> {
+    let chunk = match chunk {
+        Some(chunk) => DataChunk::from_protobuf(chunk)?,
+        None => return Ok(None),
+    };
+    if chunk.cardinality() > 1 || chunk.dimension() > key_len {
+        return Err(ErrorCode::InvalidInputSyntax(format!(
+            "{} should be a single row with at most {} columns",
+            name, key_len
+        ))
+        .into());
+    }
+    Ok(chunk.rows().next().map(Row::from))
+}
+
+fn encode_rows(rows: &[Row], desc: &ArrangementDesc) -> RwResult<ProstDataChunk> {
+    let data_types = desc
+        .column_descs
+        .iter()
+        .map(|c| c.data_type.clone())
+        .collect_vec();
+    Ok(DataChunk::from_rows(rows, &data_types)?.to_protobuf())
+}
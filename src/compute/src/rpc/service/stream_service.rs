@@ -12,14 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::{tonic_err, Result as RwResult};
 use risingwave_pb::catalog::Source;
+use risingwave_pb::stream_service::inject_barrier_response::ActorCollectTime;
 use risingwave_pb::stream_service::stream_service_server::StreamService;
 use risingwave_pb::stream_service::*;
+use risingwave_storage::KeyspaceOwner;
 use risingwave_stream::executor::{Barrier, Epoch};
 use risingwave_stream::task::{LocalStreamManager, StreamEnvironment};
 use tonic::{Request, Response, Status};
@@ -151,9 +154,39 @@ impl StreamService for StreamServiceImpl {
             .map(Into::into)
             .collect();
 
+        let slowest_actors = collect_result
+            .slowest_actors
+            .into_iter()
+            .map(|(actor_id, duration)| ActorCollectTime {
+                actor_id,
+                duration_us: duration.as_micros() as u64,
+            })
+            .collect();
+
+        let mut table_write_bytes = HashMap::new();
+        let mut actor_write_bytes = HashMap::new();
+        for (owner, bytes) in collect_result.write_bytes {
+            match owner {
+                KeyspaceOwner::Table(table_id) => {
+                    *table_write_bytes.entry(table_id).or_default() += bytes
+                }
+                KeyspaceOwner::Executor(_) => {
+                    *actor_write_bytes
+                        .entry(owner.actor_id().unwrap())
+                        .or_default() += bytes
+                }
+                // The states of shared executors can't be attributed to actors, so they're only
+                // exposed in the metrics.
+                KeyspaceOwner::Operator(_) => {}
+            }
+        }
+
         Ok(Response::new(InjectBarrierResponse {
             request_id: req.request_id,
             finished_create_mviews,
+            slowest_actors,
+            table_write_bytes,
+            actor_write_bytes,
             status: None,
         }))
     }
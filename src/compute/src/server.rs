@@ -24,28 +24,37 @@ use risingwave_batch::rpc::service::task_service::BatchServiceImpl;
 use risingwave_batch::task::{BatchEnvironment, BatchManager};
 use risingwave_common::config::ComputeNodeConfig;
 use risingwave_common::util::addr::HostAddr;
+use risingwave_common::util::memory_budget::MemoryBudget;
+use risingwave_connector::monitor::ConnectorMetrics;
 use risingwave_pb::common::WorkerType;
+use risingwave_pb::serving_service::serving_service_server::ServingServiceServer;
 use risingwave_pb::stream_service::stream_service_server::StreamServiceServer;
 use risingwave_pb::task_service::exchange_service_server::ExchangeServiceServer;
 use risingwave_pb::task_service::task_service_server::TaskServiceServer;
-use risingwave_rpc_client::MetaClient;
+use risingwave_rpc_client::{MetaClient, MetaClientMetrics};
 use risingwave_source::MemSourceManager;
 use risingwave_storage::hummock::compactor::Compactor;
 use risingwave_storage::hummock::hummock_meta_client::MonitoredHummockMetaClient;
 use risingwave_storage::monitor::{HummockMetrics, StateStoreMetrics};
 use risingwave_storage::StateStoreImpl;
 use risingwave_stream::executor::monitor::StreamingMetrics;
-use risingwave_stream::task::{LocalStreamManager, StreamEnvironment};
+use risingwave_stream::executor_v2::LOOKUP_CACHE_ENTRY_SIZE;
+use risingwave_stream::task::{EventReporter, LocalStreamManager, StreamEnvironment};
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::JoinHandle;
 use tower::make::Shared;
 use tower::ServiceBuilder;
 use tower_http::add_extension::AddExtensionLayer;
 
+use crate::memory_governor::MemoryGovernor;
 use crate::rpc::service::exchange_service::ExchangeServiceImpl;
+use crate::rpc::service::serving_service::ServingServiceImpl;
 use crate::rpc::service::stream_service::StreamServiceImpl;
 use crate::ComputeNodeOpts;
 
+/// Interval of persisting the block access sketch for warming up the block cache on restart.
+const BLOCK_ACCESS_SKETCH_PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
 fn load_config(opts: &ComputeNodeOpts) -> ComputeNodeConfig {
     if opts.config_path.is_empty() {
         return ComputeNodeConfig::default();
@@ -77,7 +86,11 @@ pub async fn compute_node_serve(
         get_compile_mode()
     );
 
+    // Initialize the metrics subsystem.
+    let registry = prometheus::Registry::new();
+
     let mut meta_client = MetaClient::new(&opts.meta_address).await.unwrap();
+    meta_client.set_metrics(Arc::new(MetaClientMetrics::new(registry.clone())));
 
     // Register to the cluster. We're not ready to serve until activate is called.
     let worker_id = meta_client
@@ -92,8 +105,6 @@ pub async fn compute_node_serve(
             Duration::from_millis(config.server.heartbeat_interval as u64),
         )];
 
-    // Initialize the metrics subsystem.
-    let registry = prometheus::Registry::new();
     let hummock_metrics = Arc::new(HummockMetrics::new(registry.clone()));
     let streaming_metrics = Arc::new(StreamingMetrics::new(registry.clone()));
     let batch_metrics = Arc::new(BatchMetrics::new(registry.clone()));
@@ -115,7 +126,10 @@ pub async fn compute_node_serve(
     .unwrap();
 
     // A hummock compactor is deployed along with compute node for now.
-    if let Some(hummock) = state_store.as_hummock_state_store() {
+    if let Some(hummock) = state_store
+        .as_hummock_state_store()
+        .filter(|_| !opts.disable_compactor)
+    {
         sub_tasks.push(Compactor::start_compactor(
             hummock.inner().options().clone(),
             hummock.inner().hummock_meta_client().clone(),
@@ -124,6 +138,51 @@ pub async fn compute_node_serve(
         ));
     }
 
+    // Warm up the block cache before serving, i.e. before any actor is built on this node.
+    if let Some(hummock) = state_store.as_hummock_state_store() {
+        let sstable_store = hummock.inner().sstable_store();
+        if let Some(access_sketch) = sstable_store.access_sketch() {
+            match hummock.inner().warm_up_block_cache().await {
+                Ok(blocks) => tracing::info!("Warmed up the block cache with {} blocks", blocks),
+                Err(e) => tracing::warn!("Failed to warm up the block cache: {}", e),
+            }
+            sub_tasks.push(access_sketch.clone().start_persister(
+                PathBuf::from(&hummock.inner().options().block_cache_warmup_path),
+                BLOCK_ACCESS_SKETCH_PERSIST_INTERVAL,
+            ));
+        }
+    }
+
+    // Let the memory governor divide the total budget between the block cache, the shared buffer
+    // and the streaming executor caches.
+    let mut stream_cache_budget = None;
+    if config.memory.total_budget > 0 {
+        if let Some(hummock) = state_store.as_hummock_state_store() {
+            let cache_budget = Arc::new(MemoryBudget::new(
+                "stream_cache",
+                config.streaming.lookup_cache_capacity * LOOKUP_CACHE_ENTRY_SIZE,
+            ));
+            let governor = MemoryGovernor::new(
+                config.memory.total_budget,
+                vec![
+                    hummock.inner().sstable_store().block_cache_budget(),
+                    hummock
+                        .inner()
+                        .shared_buffer_manager()
+                        .stats()
+                        .shared_buffer_threshold
+                        .clone(),
+                    cache_budget.clone(),
+                ],
+            );
+            sub_tasks
+                .push(governor.start(Duration::from_millis(config.memory.rebalance_interval_ms)));
+            stream_cache_budget = Some(cache_budget);
+        } else {
+            tracing::warn!("Memory governor is only supported with hummock state store");
+        }
+    }
+
     // Initialize the managers.
     let batch_mgr = Arc::new(BatchManager::new());
     let stream_mgr = Arc::new(LocalStreamManager::new(
@@ -131,7 +190,9 @@ pub async fn compute_node_serve(
         state_store.clone(),
         streaming_metrics.clone(),
     ));
-    let source_mgr = Arc::new(MemSourceManager::new());
+    let source_mgr = Arc::new(MemSourceManager::with_connector_metrics(Arc::new(
+        ConnectorMetrics::new(registry.clone()),
+    )));
 
     // Initialize batch environment.
     let batch_config = Arc::new(config.batch.clone());
@@ -153,11 +214,15 @@ pub async fn compute_node_serve(
         stream_config,
         worker_id,
         state_store,
+        stream_cache_budget,
+        EventReporter::new(Some(meta_client.clone()), client_addr.clone()),
     );
 
     // Boot the runtime gRPC services.
     let batch_srv = BatchServiceImpl::new(batch_mgr.clone(), batch_env);
     let exchange_srv = ExchangeServiceImpl::new(batch_mgr, stream_mgr.clone());
+    let serving_srv =
+        ServingServiceImpl::new(stream_env.arrangement_registry(), stream_env.state_store());
     let stream_srv = StreamServiceImpl::new(stream_mgr, stream_env.clone());
 
     let (shutdown_send, mut shutdown_recv) = tokio::sync::mpsc::unbounded_channel();
@@ -166,6 +231,7 @@ pub async fn compute_node_serve(
             .add_service(TaskServiceServer::new(batch_srv))
             .add_service(ExchangeServiceServer::new(exchange_srv))
             .add_service(StreamServiceServer::new(stream_srv))
+            .add_service(ServingServiceServer::new(serving_srv))
             .serve_with_shutdown(listen_addr, async move {
                 tokio::select! {
                     _ = tokio::signal::ctrl_c() => {},
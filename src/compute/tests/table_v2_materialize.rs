@@ -42,6 +42,7 @@ use risingwave_stream::executor::{
 use risingwave_stream::executor_v2::{
     Executor as ExecutorV2, MaterializeExecutor as MaterializeExecutorV2,
 };
+use risingwave_stream::task::EventReporter;
 use tokio::sync::mpsc::unbounded_channel;
 
 struct SingleChunkExecutor {
@@ -159,9 +160,11 @@ async fn test_table_v2_materialize() -> Result<()> {
         barrier_rx,
         1,
         1,
+        1,
         "SourceExecutor".to_string(),
         Arc::new(StreamingMetrics::unused()),
         vec![],
+        EventReporter::disabled("127.0.0.1:5688".parse().unwrap()),
     )?;
 
     // Create a `Materialize` to write the changes to storage
@@ -242,7 +245,7 @@ async fn test_table_v2_materialize() -> Result<()> {
             assert_eq!(col_row_id.value_at(0).unwrap(), 0);
             assert_eq!(col_row_id.value_at(1).unwrap(), 1);
         }
-        Message::Barrier(_) => panic!(),
+        Message::Barrier(_) | Message::Watermark(_) => panic!(),
     }
 
     // Send a barrier and poll again, should write changes to storage
@@ -310,7 +313,7 @@ async fn test_table_v2_materialize() -> Result<()> {
             let col_row_id = c.columns()[1].array_ref().as_int64();
             assert_eq!(col_row_id.value_at(0).unwrap(), 0);
         }
-        Message::Barrier(_) => panic!(),
+        Message::Barrier(_) | Message::Watermark(_) => panic!(),
     }
 
     // Send a barrier and poll again, should write changes to storage
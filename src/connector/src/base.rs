@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -19,24 +21,31 @@ use itertools::Itertools;
 use kafka::enumerator::KafkaSplitEnumerator;
 use serde::{Deserialize, Serialize};
 
+use crate::filesystem::enumerator::FsSplitEnumerator;
+use crate::filesystem::reader::FsSplitReader;
 use crate::kafka::source::KafkaSplitReader;
+use crate::kinesis::enumerator::client::KinesisSplitEnumerator;
 use crate::kinesis::source::reader::KinesisSplitReader;
+use crate::monitor::{ConnectorMetrics, ConnectorMetricsReporter, MonitoredSourceReader};
+use crate::pulsar::source::reader::PulsarSplitReader;
 
 pub enum SourceOffset {
     Number(i64),
     String(String),
 }
 
+use crate::filesystem::split::FsSplit;
 use crate::kafka::KafkaSplit;
 use crate::kinesis::split::KinesisSplit;
 use crate::pulsar::{PulsarSplit, PulsarSplitEnumerator};
 use crate::utils::AnyhowProperties;
-use crate::{kafka, kinesis, pulsar, Properties};
+use crate::{filesystem, kafka, kinesis, pulsar, Properties};
 
 const UPSTREAM_SOURCE_KEY: &str = "connector";
 const KAFKA_SOURCE: &str = "kafka";
 const KINESIS_SOURCE: &str = "kinesis";
 const PULSAR_SOURCE: &str = "pulsar";
+const FS_SOURCE: &str = "filesystem";
 
 pub trait SourceMessage {
     fn payload(&self) -> Result<Option<&[u8]>>;
@@ -71,6 +80,17 @@ pub trait SourceReader {
     async fn new(properties: Properties, state: Option<ConnectorState>) -> Result<Self>
     where
         Self: Sized;
+
+    /// Sets the reporter of the events specific to the connector, like rebalances and
+    /// reconnections.
+    fn set_metrics_reporter(&mut self, _reporter: ConnectorMetricsReporter) {}
+
+    /// Starts reading the splits newly assigned to the reader, e.g. the partitions added to a
+    /// Kafka topic. The splits already being read continue from where they are. Readers that
+    /// discover their splits by themselves ignore the assignment.
+    async fn assign_splits(&mut self, _splits: Vec<SplitImpl>) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -83,6 +103,7 @@ pub enum SplitEnumeratorImpl {
     Kafka(kafka::enumerator::KafkaSplitEnumerator),
     Pulsar(pulsar::enumerator::PulsarSplitEnumerator),
     Kinesis(kinesis::enumerator::client::KinesisSplitEnumerator),
+    Fs(filesystem::enumerator::FsSplitEnumerator),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +111,7 @@ pub enum SplitImpl {
     Kafka(kafka::KafkaSplit),
     Pulsar(pulsar::PulsarSplit),
     Kinesis(kinesis::split::KinesisSplit),
+    Fs(filesystem::split::FsSplit),
 }
 
 impl SplitImpl {
@@ -98,6 +120,7 @@ impl SplitImpl {
             SplitImpl::Kafka(k) => k.id(),
             SplitImpl::Pulsar(p) => p.id(),
             SplitImpl::Kinesis(k) => k.id(),
+            SplitImpl::Fs(f) => f.id(),
         }
     }
 
@@ -106,6 +129,7 @@ impl SplitImpl {
             SplitImpl::Kafka(k) => k.to_string(),
             SplitImpl::Pulsar(p) => p.to_string(),
             SplitImpl::Kinesis(k) => k.to_string(),
+            SplitImpl::Fs(f) => f.to_string(),
         }
     }
 
@@ -114,6 +138,7 @@ impl SplitImpl {
             SplitImpl::Kafka(k) => k.get_type(),
             SplitImpl::Pulsar(p) => p.get_type(),
             SplitImpl::Kinesis(k) => k.get_type(),
+            SplitImpl::Fs(f) => f.get_type(),
         }
     }
 
@@ -126,6 +151,9 @@ impl SplitImpl {
             kinesis::split::KINESIS_SPLIT_TYPE => {
                 KinesisSplit::restore_from_bytes(bytes).map(SplitImpl::Kinesis)
             }
+            filesystem::split::FS_SPLIT_TYPE => {
+                FsSplit::restore_from_bytes(bytes).map(SplitImpl::Fs)
+            }
             other => Err(anyhow!("split type {} not supported", other)),
         }
     }
@@ -146,17 +174,26 @@ impl SplitEnumeratorImpl {
                 .list_splits()
                 .await
                 .map(|ss| ss.into_iter().map(SplitImpl::Kinesis).collect_vec()),
+            SplitEnumeratorImpl::Fs(f) => f
+                .list_splits()
+                .await
+                .map(|ss| ss.into_iter().map(SplitImpl::Fs).collect_vec()),
         }
     }
 
-    pub fn create(properties: &AnyhowProperties) -> Result<SplitEnumeratorImpl> {
+    pub async fn create(properties: &AnyhowProperties) -> Result<SplitEnumeratorImpl> {
         let source_type = properties.get(UPSTREAM_SOURCE_KEY)?;
         match source_type.as_str() {
             KAFKA_SOURCE => KafkaSplitEnumerator::new(properties).map(SplitEnumeratorImpl::Kafka),
             PULSAR_SOURCE => {
                 PulsarSplitEnumerator::new(properties).map(SplitEnumeratorImpl::Pulsar)
             }
-            KINESIS_SOURCE => todo!(),
+            KINESIS_SOURCE => KinesisSplitEnumerator::new(properties)
+                .await
+                .map(SplitEnumeratorImpl::Kinesis),
+            FS_SOURCE => FsSplitEnumerator::new(properties)
+                .await
+                .map(SplitEnumeratorImpl::Fs),
             _ => Err(anyhow!("unsupported source type: {}", source_type)),
         }
     }
@@ -170,9 +207,25 @@ pub async fn new_connector(
     let connector: Box<dyn SourceReader + Send + Sync> = match upstream_type.as_str() {
         KAFKA_SOURCE => Box::new(KafkaSplitReader::new(config, state).await?),
         KINESIS_SOURCE => Box::new(KinesisSplitReader::new(config, state).await?),
+        PULSAR_SOURCE => Box::new(PulsarSplitReader::new(config, state).await?),
+        FS_SOURCE => Box::new(FsSplitReader::new(config, state).await?),
         _other => {
             todo!()
         }
     };
     Ok(connector)
 }
+
+/// Creates a connector like [`new_connector`], which reports its metrics to `metrics` with the
+/// labels of `source_id` and the connector type.
+pub async fn new_monitored_connector(
+    config: Properties,
+    state: Option<ConnectorState>,
+    source_id: u32,
+    metrics: Arc<ConnectorMetrics>,
+) -> Result<Box<dyn SourceReader + Send + Sync>> {
+    let upstream_type = config.get(UPSTREAM_SOURCE_KEY)?;
+    let reporter = ConnectorMetricsReporter::new(metrics, source_id, &upstream_type);
+    let connector = new_connector(config, state).await?;
+    Ok(Box::new(MonitoredSourceReader::new(connector, reporter)))
+}
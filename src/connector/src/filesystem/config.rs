@@ -0,0 +1,227 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use globset::{Glob, GlobMatcher};
+
+use crate::filesystem::s3::s3_dir::{
+    new_s3_client, new_share_config, AwsCredential, AwsCustomConfig, S3SourceBasicConfig,
+    S3SourceConfig, SqsReceiveMsgConfig,
+};
+
+/// The files to read, e.g. `file:///data/orders/*.csv` or `s3://bucket/orders/2022-*.json`.
+pub const FS_PATH: &str = "fs.path";
+/// The max bytes of a split. Files larger than it are read by several splits in parallel.
+pub const FS_SPLIT_SIZE: &str = "fs.split.size";
+/// Whether the first line of each file is a header to be skipped, e.g. the column names of a CSV.
+pub const FS_SKIP_HEADER: &str = "fs.skip_header";
+
+const LOCAL_SCHEME: &str = "file://";
+const S3_SCHEME: &str = "s3://";
+
+/// A file or a glob of files, in the local filesystem or in S3.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FsLocation {
+    /// An absolute local path.
+    Local(String),
+    S3 {
+        bucket: String,
+        key: String,
+    },
+}
+
+impl FsLocation {
+    pub fn parse(path: &str) -> Result<Self> {
+        if let Some(path) = path.strip_prefix(LOCAL_SCHEME) {
+            Ok(FsLocation::Local(path.to_string()))
+        } else if let Some(path) = path.strip_prefix(S3_SCHEME) {
+            match path.split_once('/') {
+                Some((bucket, key)) if !bucket.is_empty() => Ok(FsLocation::S3 {
+                    bucket: bucket.to_string(),
+                    key: key.to_string(),
+                }),
+                _ => Err(anyhow!(
+                    "illegal path {}, S3 path format is s3://bucket_name/object_key",
+                    path
+                )),
+            }
+        } else {
+            Err(anyhow!(
+                "unsupported path {}, expect a path starting with {} or {}",
+                path,
+                LOCAL_SCHEME,
+                S3_SCHEME
+            ))
+        }
+    }
+
+    /// The path or the key without the scheme and the bucket.
+    pub fn path(&self) -> &str {
+        match self {
+            FsLocation::Local(path) => path,
+            FsLocation::S3 { key, .. } => key,
+        }
+    }
+
+    /// Returns the location of a file in the same place as `self`.
+    pub fn with_path(&self, path: String) -> Self {
+        match self {
+            FsLocation::Local(_) => FsLocation::Local(path),
+            FsLocation::S3 { bucket, .. } => FsLocation::S3 {
+                bucket: bucket.clone(),
+                key: path,
+            },
+        }
+    }
+
+    /// Splits the path into the directory to list and the glob that the listed files must match.
+    /// The glob is `None` if the path is a plain file or directory.
+    pub fn split_glob(&self) -> Result<(&str, Option<GlobMatcher>)> {
+        let path = self.path();
+        match path.find(|c| matches!(c, '*' | '?' | '[' | '{')) {
+            Some(idx) => {
+                let base = &path[..path[..idx].rfind('/').map_or(0, |slash| slash + 1)];
+                let matcher = Glob::new(path)?.compile_matcher();
+                Ok((base, Some(matcher)))
+            }
+            None => Ok((path, None)),
+        }
+    }
+}
+
+impl std::fmt::Display for FsLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FsLocation::Local(path) => write!(f, "{}{}", LOCAL_SCHEME, path),
+            FsLocation::S3 { bucket, key } => write!(f, "{}{}/{}", S3_SCHEME, bucket, key),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FsConfig {
+    pub location: FsLocation,
+    pub split_size: Option<u64>,
+    pub skip_header: bool,
+    /// The region and the credentials of S3, read from the `s3.*` properties.
+    pub s3: S3SourceBasicConfig,
+}
+
+impl FsConfig {
+    pub fn build(properties: &HashMap<String, String>) -> Result<Self> {
+        let path = properties
+            .get(FS_PATH)
+            .ok_or_else(|| anyhow!("{} should be provided", FS_PATH))?;
+        let split_size = properties
+            .get(FS_SPLIT_SIZE)
+            .map(|size| match size.parse::<u64>() {
+                Ok(size) if size > 0 => Ok(size),
+                _ => Err(anyhow!(
+                    "{} should be a positive integer, got {}",
+                    FS_SPLIT_SIZE,
+                    size
+                )),
+            })
+            .transpose()?;
+        let skip_header = properties
+            .get(FS_SKIP_HEADER)
+            .map(|skip| skip.parse::<bool>())
+            .transpose()
+            .map_err(|e| anyhow!("{} should be true or false: {}", FS_SKIP_HEADER, e))?
+            .unwrap_or(false);
+
+        Ok(Self {
+            location: FsLocation::parse(path)?,
+            split_size,
+            skip_header,
+            s3: S3SourceBasicConfig::from(properties.clone()),
+        })
+    }
+
+    /// Builds an S3 client if the files are in S3.
+    pub async fn build_s3_client(&self) -> Result<Option<aws_sdk_s3::Client>> {
+        if !matches!(self.location, FsLocation::S3 { .. }) {
+            return Ok(None);
+        }
+        let credential = if self.s3.access.is_empty() || self.s3.secret.is_empty() {
+            AwsCredential::Default
+        } else {
+            AwsCredential::Static {
+                access_key: self.s3.access.clone(),
+                secret_access: self.s3.secret.clone(),
+                session_token: None,
+            }
+        };
+        let shared_config = new_share_config(self.s3.region.clone(), credential).await?;
+        Ok(Some(new_s3_client(S3SourceConfig {
+            basic_config: self.s3.clone(),
+            shared_config,
+            custom_config: Some(AwsCustomConfig::default()),
+            sqs_config: SqsReceiveMsgConfig::default(),
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::hashmap;
+
+    use super::*;
+
+    #[test]
+    fn test_fs_location() {
+        let location = FsLocation::parse("s3://bucket/orders/2022-*.json").unwrap();
+        assert_eq!(
+            location,
+            FsLocation::S3 {
+                bucket: "bucket".to_string(),
+                key: "orders/2022-*.json".to_string()
+            }
+        );
+        assert_eq!(location.to_string(), "s3://bucket/orders/2022-*.json");
+        let (base, matcher) = location.split_glob().unwrap();
+        assert_eq!(base, "orders/");
+        assert!(matcher.unwrap().is_match("orders/2022-01.json"));
+
+        let location = FsLocation::parse("file:///data/orders.csv").unwrap();
+        assert_eq!(location, FsLocation::Local("/data/orders.csv".to_string()));
+        let (base, matcher) = location.split_glob().unwrap();
+        assert_eq!(base, "/data/orders.csv");
+        assert!(matcher.is_none());
+
+        assert!(FsLocation::parse("s3://bucket").is_err());
+        assert!(FsLocation::parse("hdfs:///data").is_err());
+    }
+
+    #[test]
+    fn test_fs_config() {
+        let config = FsConfig::build(&hashmap! {
+            FS_PATH.to_string() => "file:///data/*.csv".to_string(),
+            FS_SPLIT_SIZE.to_string() => "1024".to_string(),
+            FS_SKIP_HEADER.to_string() => "true".to_string(),
+        })
+        .unwrap();
+        assert_eq!(config.split_size, Some(1024));
+        assert!(config.skip_header);
+
+        assert!(FsConfig::build(&hashmap! {
+            FS_PATH.to_string() => "file:///data/*.csv".to_string(),
+            FS_SPLIT_SIZE.to_string() => "0".to_string(),
+        })
+        .is_err());
+        assert!(FsConfig::build(&HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use crate::base::SplitEnumerator;
+use crate::filesystem::config::{FsConfig, FsLocation};
+use crate::filesystem::split::FsSplit;
+use crate::AnyhowProperties;
+
+/// `FsSplitEnumerator` lists the files matching `fs.path`, and cuts every non-empty file into
+/// splits of `fs.split.size` bytes, or a single split if the size is not given.
+pub struct FsSplitEnumerator {
+    config: FsConfig,
+    s3_client: Option<aws_sdk_s3::Client>,
+}
+
+impl FsSplitEnumerator {
+    pub async fn new(properties: &AnyhowProperties) -> Result<FsSplitEnumerator> {
+        let config = FsConfig::build(&properties.0)?;
+        Self::with_config(config).await
+    }
+
+    pub async fn with_config(config: FsConfig) -> Result<FsSplitEnumerator> {
+        let s3_client = config.build_s3_client().await?;
+        Ok(Self { config, s3_client })
+    }
+
+    /// Returns the files and their sizes, ordered by path.
+    async fn list_files(&self) -> Result<Vec<(FsLocation, u64)>> {
+        let location = &self.config.location;
+        let (base, matcher) = location.split_glob()?;
+        let mut files = match location {
+            FsLocation::Local(_) => list_local_files(base, matcher.is_some()).await?,
+            FsLocation::S3 { bucket, .. } => {
+                list_s3_files(self.s3_client.as_ref().unwrap(), bucket, base).await?
+            }
+        };
+        if let Some(matcher) = matcher {
+            files.retain(|(path, _)| matcher.is_match(path));
+        }
+        files.sort();
+        Ok(files
+            .into_iter()
+            .map(|(path, size)| (location.with_path(path), size))
+            .collect())
+    }
+}
+
+/// Lists the file at `path`, or the files in the directory at `path`. The subdirectories are
+/// listed as well if `recursive`.
+async fn list_local_files(path: &str, recursive: bool) -> Result<Vec<(String, u64)>> {
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| anyhow!("failed to list {}: {}", path, e))?;
+    if metadata.is_file() {
+        return Ok(vec![(path.to_string(), metadata.len())]);
+    }
+
+    let mut files = vec![];
+    let mut dirs = vec![std::path::PathBuf::from(path)];
+    while let Some(dir) = dirs.pop() {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() {
+                let path = entry
+                    .path()
+                    .into_os_string()
+                    .into_string()
+                    .map_err(|path| anyhow!("non-UTF-8 path {:?}", path))?;
+                files.push((path, metadata.len()));
+            } else if metadata.is_dir() && recursive {
+                dirs.push(entry.path());
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Lists the objects in `bucket` whose keys start with `prefix`.
+async fn list_s3_files(
+    client: &aws_sdk_s3::Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<Vec<(String, u64)>> {
+    let mut files = vec![];
+    let mut continuation_token = None;
+    loop {
+        let output = client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(prefix)
+            .set_continuation_token(continuation_token)
+            .send()
+            .await?;
+        for object in output.contents.unwrap_or_default() {
+            match object.key {
+                Some(key) if !key.ends_with('/') => files.push((key, object.size.max(0) as u64)),
+                _ => {}
+            }
+        }
+        continuation_token = output.next_continuation_token;
+        if !output.is_truncated || continuation_token.is_none() {
+            break;
+        }
+    }
+    Ok(files)
+}
+
+/// Cuts a file of `size` bytes into splits of `split_size` bytes.
+pub(crate) fn split_file(path: String, size: u64, split_size: Option<u64>) -> Vec<FsSplit> {
+    let split_size = split_size.unwrap_or(size).max(1);
+    (0..size)
+        .step_by(split_size as usize)
+        .map(|start| FsSplit::new(path.clone(), start, (start + split_size).min(size)))
+        .collect()
+}
+
+#[async_trait]
+impl SplitEnumerator for FsSplitEnumerator {
+    type Split = FsSplit;
+
+    async fn list_splits(&mut self) -> Result<Vec<FsSplit>> {
+        let files = self.list_files().await?;
+        if files.is_empty() {
+            return Err(anyhow!("no files match {}", self.config.location));
+        }
+        Ok(files
+            .into_iter()
+            .flat_map(|(location, size)| {
+                split_file(location.to_string(), size, self.config.split_size)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::base::SourceSplit;
+    use crate::filesystem::config::{FS_PATH, FS_SPLIT_SIZE};
+
+    #[test]
+    fn test_split_file() {
+        let ranges = |splits: Vec<FsSplit>| {
+            splits
+                .iter()
+                .map(|split| (split.start, split.end))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ranges(split_file("a".to_string(), 10, None)), vec![(0, 10)]);
+        assert_eq!(
+            ranges(split_file("a".to_string(), 10, Some(4))),
+            vec![(0, 4), (4, 8), (8, 10)]
+        );
+        assert!(split_file("a".to_string(), 0, Some(4)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fs_split_enumerator() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("a.csv"), "1,2\n3,4\n")?;
+        std::fs::write(dir.path().join("b.csv"), "5,6\n")?;
+        std::fs::write(dir.path().join("c.json"), "{}\n")?;
+        std::fs::write(dir.path().join("empty.csv"), "")?;
+
+        let dir_path = dir.path().to_str().unwrap();
+        let properties = AnyhowProperties::new(HashMap::from([
+            (FS_PATH.to_string(), format!("file://{}/*.csv", dir_path)),
+            (FS_SPLIT_SIZE.to_string(), "5".to_string()),
+        ]));
+        let mut enumerator = FsSplitEnumerator::new(&properties).await?;
+        let splits = enumerator.list_splits().await?;
+        let ids = splits.iter().map(|split| split.id()).collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec![
+                format!("file://{}/a.csv#0", dir_path),
+                format!("file://{}/a.csv#5", dir_path),
+                format!("file://{}/b.csv#0", dir_path),
+            ]
+        );
+        assert_eq!(splits[1].end, 8);
+
+        let properties = AnyhowProperties::new(HashMap::from([(
+            FS_PATH.to_string(),
+            format!("file://{}/*.txt", dir_path),
+        )]));
+        let mut enumerator = FsSplitEnumerator::new(&properties).await?;
+        assert!(enumerator.list_splits().await.is_err());
+        Ok(())
+    }
+}
@@ -11,5 +11,9 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+pub mod config;
+pub mod enumerator;
 mod file_common;
+pub mod reader;
 mod s3;
+pub mod split;
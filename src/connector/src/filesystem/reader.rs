@@ -0,0 +1,330 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::io::SeekFrom;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::base::{InnerMessage, SourceReader, SourceSplit};
+use crate::filesystem::config::{FsConfig, FsLocation};
+use crate::filesystem::enumerator::FsSplitEnumerator;
+use crate::filesystem::split::FsSplit;
+use crate::{ConnectorState, SplitEnumerator};
+
+/// Bytes read from a file at a time.
+const READ_CHUNK_SIZE: u64 = 1024 * 1024;
+/// Max lines returned by one call of `next`.
+const MAX_BATCH_LINES: usize = 1024;
+/// How long `next` waits before returning nothing once all the splits are read, so that the
+/// caller doesn't spin.
+const EXHAUSTED_WAIT: Duration = Duration::from_millis(500);
+
+/// `FsSplitReader` reads the splits of a filesystem source line by line. Each line is a message,
+/// whose offset is the byte offset in the file right after the line, i.e. where to resume.
+pub struct FsSplitReader {
+    s3_client: Option<aws_sdk_s3::Client>,
+    skip_header: bool,
+    /// The splits to read after the current one, with the offsets to start from.
+    splits: VecDeque<(FsSplit, u64)>,
+    cursor: Option<FsSplitCursor>,
+}
+
+/// The progress of reading a split.
+struct FsSplitCursor {
+    split_id: String,
+    location: FsLocation,
+    size: u64,
+    /// The offset of the next line to read.
+    offset: u64,
+    end: u64,
+    /// The bytes after `offset` already read from the file, which don't hold a complete line yet.
+    buf: Vec<u8>,
+    /// Whether the next line is skipped, as it's the header of the file, or it's the end of the
+    /// line before the split, which belongs to the previous split.
+    skip_line: bool,
+}
+
+impl FsSplitCursor {
+    /// Reads the next lines, and returns whether the split is exhausted.
+    async fn read_lines(
+        &mut self,
+        s3_client: Option<&aws_sdk_s3::Client>,
+    ) -> Result<(Vec<InnerMessage>, bool)> {
+        let mut messages = vec![];
+        while messages.len() < MAX_BATCH_LINES {
+            // A split reads the lines starting before its end.
+            if !self.skip_line && self.offset >= self.end {
+                return Ok((messages, true));
+            }
+
+            let mut line = match self.buf.iter().position(|b| *b == b'\n') {
+                Some(pos) => self.buf.drain(..=pos).collect::<Vec<_>>(),
+                None => {
+                    let read_offset = self.offset + self.buf.len() as u64;
+                    if read_offset < self.size {
+                        let len = READ_CHUNK_SIZE.min(self.size - read_offset);
+                        let bytes = read_file(s3_client, &self.location, read_offset, len).await?;
+                        if bytes.is_empty() {
+                            return Err(anyhow!("{} is truncated while reading", self.location));
+                        }
+                        self.buf.extend_from_slice(&bytes);
+                        continue;
+                    } else if self.buf.is_empty() {
+                        return Ok((messages, true));
+                    } else {
+                        // The last line of the file doesn't end with a newline.
+                        std::mem::take(&mut self.buf)
+                    }
+                }
+            };
+            self.offset += line.len() as u64;
+
+            if self.skip_line {
+                self.skip_line = false;
+                continue;
+            }
+            while matches!(line.last(), Some(b'\n' | b'\r')) {
+                line.pop();
+            }
+            if line.is_empty() {
+                continue;
+            }
+            messages.push(InnerMessage {
+                payload: Some(Bytes::from(line)),
+                offset: self.offset.to_string(),
+                split_id: self.split_id.clone(),
+            });
+        }
+        Ok((messages, false))
+    }
+}
+
+/// Returns the size of the file at `location`.
+async fn file_size(s3_client: Option<&aws_sdk_s3::Client>, location: &FsLocation) -> Result<u64> {
+    match location {
+        FsLocation::Local(path) => Ok(tokio::fs::metadata(path).await?.len()),
+        FsLocation::S3 { bucket, key } => {
+            let output = s3_client
+                .ok_or_else(|| anyhow!("no S3 client to read {}", location))?
+                .head_object()
+                .bucket(bucket)
+                .key(key)
+                .send()
+                .await?;
+            Ok(output.content_length.max(0) as u64)
+        }
+    }
+}
+
+/// Reads at most `len` bytes from `offset` of the file at `location`.
+async fn read_file(
+    s3_client: Option<&aws_sdk_s3::Client>,
+    location: &FsLocation,
+    offset: u64,
+    len: u64,
+) -> Result<Bytes> {
+    match location {
+        FsLocation::Local(path) => {
+            let mut file = tokio::fs::File::open(path).await?;
+            file.seek(SeekFrom::Start(offset)).await?;
+            let mut buf = Vec::with_capacity(len as usize);
+            file.take(len).read_to_end(&mut buf).await?;
+            Ok(Bytes::from(buf))
+        }
+        FsLocation::S3 { bucket, key } => {
+            let output = s3_client
+                .ok_or_else(|| anyhow!("no S3 client to read {}", location))?
+                .get_object()
+                .bucket(bucket)
+                .key(key)
+                .range(format!("bytes={}-{}", offset, offset + len - 1))
+                .send()
+                .await?;
+            Ok(output.body.collect().await?.into_bytes())
+        }
+    }
+}
+
+impl FsSplitReader {
+    async fn open(&self, split: FsSplit, offset: u64) -> Result<FsSplitCursor> {
+        let location = FsLocation::parse(&split.path)?;
+        let size = file_size(self.s3_client.as_ref(), &location).await?;
+        // Reading from the byte before the offset, the line ending there is skipped. So the split
+        // starts at a line boundary, no matter where the offset is.
+        let (offset, skip_line) = if offset > 0 {
+            (offset - 1, true)
+        } else {
+            (0, self.skip_header)
+        };
+        Ok(FsSplitCursor {
+            split_id: split.id(),
+            location,
+            size,
+            offset,
+            end: split.end,
+            buf: vec![],
+            skip_line,
+        })
+    }
+}
+
+#[async_trait]
+impl SourceReader for FsSplitReader {
+    async fn next(&mut self) -> Result<Option<Vec<InnerMessage>>> {
+        loop {
+            if self.cursor.is_none() {
+                match self.splits.pop_front() {
+                    Some((split, offset)) => self.cursor = Some(self.open(split, offset).await?),
+                    None => {
+                        tokio::time::sleep(EXHAUSTED_WAIT).await;
+                        return Ok(None);
+                    }
+                }
+            }
+
+            let cursor = self.cursor.as_mut().unwrap();
+            let (messages, exhausted) = cursor.read_lines(self.s3_client.as_ref()).await?;
+            if exhausted {
+                self.cursor = None;
+            }
+            if !messages.is_empty() {
+                return Ok(Some(messages));
+            }
+        }
+    }
+
+    /// The identifier of the state is the split id, i.e. `path#start`. The start offset is where
+    /// to resume, and the end offset is the end of the split, or the end of the file if empty.
+    /// Without a state, all the files are read one by one.
+    async fn new(properties: crate::Properties, state: Option<ConnectorState>) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let config = FsConfig::build(&properties.0)?;
+        let s3_client = config.build_s3_client().await?;
+        let skip_header = config.skip_header;
+
+        let splits = match state {
+            Some(state) => {
+                let split_id = String::from_utf8(state.identifier.to_vec())?;
+                let (path, start) = FsSplit::parse_id(&split_id)?;
+                let offset = if state.start_offset.is_empty() {
+                    start
+                } else {
+                    state.start_offset.parse()?
+                };
+                let end = if state.end_offset.is_empty() {
+                    u64::MAX
+                } else {
+                    state.end_offset.parse()?
+                };
+                VecDeque::from([(FsSplit::new(path, start, end), offset)])
+            }
+            None => FsSplitEnumerator::with_config(config)
+                .await?
+                .list_splits()
+                .await?
+                .into_iter()
+                .map(|split| {
+                    let start = split.start;
+                    (split, start)
+                })
+                .collect(),
+        };
+
+        Ok(Self {
+            s3_client,
+            skip_header,
+            splits,
+            cursor: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::filesystem::config::{FS_PATH, FS_SKIP_HEADER, FS_SPLIT_SIZE};
+    use crate::Properties;
+
+    /// Reads all the messages of the reader, returning their payloads and offsets.
+    async fn read_all(reader: &mut FsSplitReader) -> Result<(Vec<String>, Vec<String>)> {
+        let mut payloads = vec![];
+        let mut offsets = vec![];
+        while let Some(messages) = reader.next().await? {
+            for message in messages {
+                payloads.push(String::from_utf8(message.payload.unwrap().to_vec())?);
+                offsets.push(message.offset);
+            }
+        }
+        Ok((payloads, offsets))
+    }
+
+    fn state(split_id: String, start_offset: &str, end_offset: &str) -> ConnectorState {
+        ConnectorState {
+            identifier: Bytes::from(split_id),
+            start_offset: start_offset.to_string(),
+            end_offset: end_offset.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fs_split_reader() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("a.csv"), "id,v\n1,a\n22,bb\r\n\n333,ccc")?;
+        std::fs::write(dir.path().join("b.csv"), "id,v\n4,d\n")?;
+        let path = format!("file://{}/a.csv", dir.path().to_str().unwrap());
+        let properties = Properties::new(HashMap::from([
+            (
+                FS_PATH.to_string(),
+                format!("file://{}/*.csv", dir.path().to_str().unwrap()),
+            ),
+            (FS_SKIP_HEADER.to_string(), "true".to_string()),
+            (FS_SPLIT_SIZE.to_string(), "7".to_string()),
+        ]));
+
+        // Without a state, all the splits of all the files are read.
+        let mut reader = FsSplitReader::new(properties.clone(), None).await?;
+        let (payloads, offsets) = read_all(&mut reader).await?;
+        assert_eq!(payloads, vec!["1,a", "22,bb", "333,ccc", "4,d"]);
+        assert_eq!(offsets, vec!["9", "16", "24", "9"]);
+
+        // Each line is read by the split it starts in.
+        let mut lines = vec![];
+        for (start, end) in [(0, 7), (7, 14), (14, 21), (21, 24)] {
+            let state = state(format!("{}#{}", path, start), "", &end.to_string());
+            let mut reader = FsSplitReader::new(properties.clone(), Some(state)).await?;
+            lines.push(read_all(&mut reader).await?.0);
+        }
+        assert_eq!(
+            lines,
+            vec![vec!["1,a"], vec!["22,bb"], vec!["333,ccc"], vec![]]
+        );
+
+        // Resume from the offset of a message.
+        let state = state(format!("{}#0", path), "9", "");
+        let mut reader = FsSplitReader::new(properties, Some(state)).await?;
+        let (payloads, offsets) = read_all(&mut reader).await?;
+        assert_eq!(payloads, vec!["22,bb", "333,ccc"]);
+        assert_eq!(offsets, vec!["16", "24"]);
+        Ok(())
+    }
+}
@@ -11,6 +11,6 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-mod s3_dir;
+pub(crate) mod s3_dir;
 mod s3_notification_event;
 mod source;
@@ -0,0 +1,64 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::base::SourceSplit;
+
+pub const FS_SPLIT_TYPE: &str = "filesystem";
+
+/// `FsSplit` is the byte range `[start, end)` of a file. A split reads the lines starting in its
+/// range, so a line crossing `end` is read by this split to its end and skipped by the next one.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FsSplit {
+    /// The path of the file with its scheme, e.g. `s3://bucket/orders.csv`.
+    pub(crate) path: String,
+    pub(crate) start: u64,
+    pub(crate) end: u64,
+}
+
+impl FsSplit {
+    pub fn new(path: String, start: u64, end: u64) -> Self {
+        Self { path, start, end }
+    }
+
+    /// Parses the path and the start offset out of a split id.
+    pub fn parse_id(id: &str) -> Result<(String, u64)> {
+        let (path, start) = id
+            .rsplit_once('#')
+            .ok_or_else(|| anyhow!("illegal filesystem split id {}", id))?;
+        Ok((path.to_string(), start.parse()?))
+    }
+}
+
+impl SourceSplit for FsSplit {
+    /// The id is `path#start`, where `start` is the offset the split begins with, so that it
+    /// doesn't change when the split is restored from a later offset.
+    fn id(&self) -> String {
+        format!("{}#{}", self.path, self.start)
+    }
+
+    fn to_string(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| anyhow!(e))
+    }
+
+    fn restore_from_bytes(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes).map_err(|e| anyhow!(e))
+    }
+
+    fn get_type(&self) -> String {
+        FS_SPLIT_TYPE.to_string()
+    }
+}
@@ -13,18 +13,21 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::anyhow;
 use async_trait::async_trait;
 use rdkafka::consumer::{BaseConsumer, Consumer, DefaultConsumerContext};
 use rdkafka::error::KafkaResult;
 use rdkafka::{Offset, TopicPartitionList};
+use regex::Regex;
 
 use crate::base::SplitEnumerator;
 use crate::kafka::split::KafkaSplit;
 use crate::kafka::{
-    KAFKA_CONFIG_BROKERS_KEY, KAFKA_CONFIG_SCAN_STARTUP_MODE, KAFKA_CONFIG_TIME_OFFSET,
-    KAFKA_CONFIG_TOPIC_KEY, KAFKA_SYNC_CALL_TIMEOUT,
+    KAFKA_CONFIG_BROKERS_KEY, KAFKA_CONFIG_CONSUME_GROUP, KAFKA_CONFIG_SCAN_STARTUP_MODE,
+    KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS, KAFKA_CONFIG_TIME_OFFSET, KAFKA_CONFIG_TOPIC_KEY,
+    KAFKA_CONFIG_TOPIC_PATTERN_KEY, KAFKA_SYNC_CALL_TIMEOUT,
 };
 use crate::utils::AnyhowProperties;
 
@@ -34,12 +37,118 @@ pub enum KafkaEnumeratorOffset {
     Latest,
     Offset(i64),
     Timestamp(i64),
+    /// The offsets committed by the consumer group given by `kafka.consumer.group`.
+    GroupOffsets,
     None,
 }
 
+impl KafkaEnumeratorOffset {
+    /// Parses the startup mode given by `kafka.scan.startup.mode`, which is one of `earliest`
+    /// (default), `latest`, `timestamp` and `group-offsets`.
+    fn from_properties(properties: &AnyhowProperties) -> anyhow::Result<Self> {
+        let timestamp = properties
+            .0
+            .get(KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS)
+            .or_else(|| properties.0.get(KAFKA_CONFIG_TIME_OFFSET));
+        let mode = properties
+            .0
+            .get(KAFKA_CONFIG_SCAN_STARTUP_MODE)
+            .map(String::as_str);
+
+        match (mode, timestamp) {
+            (Some("earliest"), _) => Ok(Self::Earliest),
+            (Some("latest"), _) => Ok(Self::Latest),
+            (Some("timestamp"), None) => Err(anyhow!(
+                "property {} must be specified in the timestamp startup mode",
+                KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS
+            )),
+            (Some("timestamp") | None, Some(timestamp)) => {
+                let timestamp = timestamp.parse::<i64>().map_err(|e| {
+                    anyhow!(
+                        "invalid {} {:?}: {}",
+                        KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS,
+                        timestamp,
+                        e
+                    )
+                })?;
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as i64;
+                if !(0..=now).contains(&timestamp) {
+                    return Err(anyhow!(
+                        "{} {} must be in the past",
+                        KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS,
+                        timestamp
+                    ));
+                }
+                Ok(Self::Timestamp(timestamp))
+            }
+            (Some("group-offsets"), _) => {
+                if !properties.0.contains_key(KAFKA_CONFIG_CONSUME_GROUP) {
+                    return Err(anyhow!(
+                        "property {} must be specified in the group-offsets startup mode",
+                        KAFKA_CONFIG_CONSUME_GROUP
+                    ));
+                }
+                Ok(Self::GroupOffsets)
+            }
+            (None, None) => Ok(Self::Earliest),
+            (Some(_), _) => Err(anyhow!(
+                "property {} only supports earliest, latest, timestamp and group-offsets or leave \
+                 it empty",
+                KAFKA_CONFIG_SCAN_STARTUP_MODE
+            )),
+        }
+    }
+}
+
+/// The topics a Kafka source subscribes to.
+#[derive(Debug, Clone)]
+pub enum KafkaTopicSubscription {
+    /// A single, fixed topic given by `kafka.topic`.
+    Topic(String),
+    /// All topics whose names fully match the regex given by `kafka.topic.pattern`. Topics are
+    /// re-listed on every call to `list_splits`, so newly created topics are picked up.
+    Pattern(Regex),
+}
+
+impl KafkaTopicSubscription {
+    fn from_properties(properties: &AnyhowProperties) -> anyhow::Result<Self> {
+        match (
+            properties.0.get(KAFKA_CONFIG_TOPIC_KEY),
+            properties.0.get(KAFKA_CONFIG_TOPIC_PATTERN_KEY),
+        ) {
+            (Some(topic), None) => Ok(Self::Topic(topic.clone())),
+            (None, Some(pattern)) => {
+                // anchor the pattern so that it must match the whole topic name
+                let regex = Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| {
+                    anyhow!(
+                        "invalid {} {:?}: {}",
+                        KAFKA_CONFIG_TOPIC_PATTERN_KEY,
+                        pattern,
+                        e
+                    )
+                })?;
+                Ok(Self::Pattern(regex))
+            }
+            (Some(_), Some(_)) => Err(anyhow!(
+                "properties {} and {} can not be specified at the same time",
+                KAFKA_CONFIG_TOPIC_KEY,
+                KAFKA_CONFIG_TOPIC_PATTERN_KEY
+            )),
+            (None, None) => Err(anyhow!(
+                "either {} or {} must be specified",
+                KAFKA_CONFIG_TOPIC_KEY,
+                KAFKA_CONFIG_TOPIC_PATTERN_KEY
+            )),
+        }
+    }
+}
+
 pub struct KafkaSplitEnumerator {
     broker_address: String,
-    topic: String,
+    subscription: KafkaTopicSubscription,
     admin_client: BaseConsumer,
     start_offset: KafkaEnumeratorOffset,
 
@@ -50,37 +159,23 @@ pub struct KafkaSplitEnumerator {
 impl KafkaSplitEnumerator {
     pub fn new(properties: &AnyhowProperties) -> anyhow::Result<KafkaSplitEnumerator> {
         let broker_address = properties.get_kafka(KAFKA_CONFIG_BROKERS_KEY)?;
-        let topic = properties.get_kafka(KAFKA_CONFIG_TOPIC_KEY)?;
+        let subscription = KafkaTopicSubscription::from_properties(properties)?;
 
-        let mut scan_start_offset = match properties
-            .0
-            .get(KAFKA_CONFIG_SCAN_STARTUP_MODE)
-            .map(String::as_str)
-        {
-            Some("earliest") => KafkaEnumeratorOffset::Earliest,
-            Some("latest") => KafkaEnumeratorOffset::Latest,
-            None => KafkaEnumeratorOffset::Earliest,
-            _ => {
-                return Err(anyhow!(
-                    "properties {} only support earliest and latest or leave it empty",
-                    KAFKA_CONFIG_SCAN_STARTUP_MODE
-                ));
-            }
-        };
+        let scan_start_offset = KafkaEnumeratorOffset::from_properties(properties)?;
 
-        if let Some(s) = properties.0.get(KAFKA_CONFIG_TIME_OFFSET) {
-            let time_offset = s.parse::<i64>().map_err(|e| anyhow!(e))?;
-            scan_start_offset = KafkaEnumeratorOffset::Timestamp(time_offset)
+        let mut config = rdkafka::ClientConfig::new();
+        config.set("bootstrap.servers", &broker_address);
+        // The committed offsets are fetched on behalf of the group.
+        if let Some(group) = properties.0.get(KAFKA_CONFIG_CONSUME_GROUP) {
+            config.set("group.id", group);
         }
-
-        let client: BaseConsumer = rdkafka::ClientConfig::new()
-            .set("bootstrap.servers", &broker_address)
+        let client: BaseConsumer = config
             .create_with_context(DefaultConsumerContext)
             .map_err(|e| anyhow!(e))?;
 
         Ok(Self {
             broker_address,
-            topic,
+            subscription,
             admin_client: client,
             start_offset: scan_start_offset,
             stop_offset: KafkaEnumeratorOffset::None,
@@ -93,39 +188,48 @@ impl SplitEnumerator for KafkaSplitEnumerator {
     type Split = KafkaSplit;
 
     async fn list_splits(&mut self) -> anyhow::Result<Vec<KafkaSplit>> {
-        let topic_partitions = self.fetch_topic_partition()?;
+        let mut ret = vec![];
 
-        let mut start_offsets = self
-            .fetch_start_offset(topic_partitions.as_ref())
-            .map_err(|e| anyhow!("{}", e))?;
+        for (topic, partitions) in self.fetch_topic_partitions()? {
+            let mut start_offsets = self
+                .fetch_start_offset(&topic, partitions.as_ref())
+                .map_err(|e| anyhow!("{}", e))?;
+            if matches!(
+                self.start_offset,
+                KafkaEnumeratorOffset::Timestamp(_) | KafkaEnumeratorOffset::GroupOffsets
+            ) {
+                self.validate_start_offsets(&topic, &start_offsets)?;
+            }
 
-        let mut stop_offsets = self
-            .fetch_stop_offset(topic_partitions.as_ref())
-            .map_err(|e| anyhow!("{}", e))?;
+            let mut stop_offsets = self
+                .fetch_stop_offset(&topic, partitions.as_ref())
+                .map_err(|e| anyhow!("{}", e))?;
 
-        let ret = topic_partitions
-            .into_iter()
-            .map(|partition| KafkaSplit {
-                topic: self.topic.clone(),
+            ret.extend(partitions.into_iter().map(|partition| KafkaSplit {
+                topic: topic.clone(),
                 partition,
                 start_offset: start_offsets.remove(&partition).unwrap(),
                 stop_offset: stop_offsets.remove(&partition).unwrap(),
-            })
-            .collect();
+            }));
+        }
 
         Ok(ret)
     }
 }
 
 impl KafkaSplitEnumerator {
-    fn fetch_stop_offset(&self, partitions: &[i32]) -> KafkaResult<HashMap<i32, Option<i64>>> {
+    fn fetch_stop_offset(
+        &self,
+        topic: &str,
+        partitions: &[i32],
+    ) -> KafkaResult<HashMap<i32, Option<i64>>> {
         match self.stop_offset {
-            KafkaEnumeratorOffset::Earliest => unreachable!(),
+            KafkaEnumeratorOffset::Earliest | KafkaEnumeratorOffset::GroupOffsets => unreachable!(),
             KafkaEnumeratorOffset::Latest => partitions
                 .iter()
                 .map(|partition| {
                     self.admin_client
-                        .fetch_watermarks(self.topic.as_str(), *partition, KAFKA_SYNC_CALL_TIMEOUT)
+                        .fetch_watermarks(topic, *partition, KAFKA_SYNC_CALL_TIMEOUT)
                         .map(|watermark| (*partition, Some(watermark.1)))
                 })
                 .collect(),
@@ -133,7 +237,9 @@ impl KafkaSplitEnumerator {
                 .iter()
                 .map(|partition| Ok((*partition, Some(offset))))
                 .collect(),
-            KafkaEnumeratorOffset::Timestamp(time) => self.fetch_offset_for_time(partitions, time),
+            KafkaEnumeratorOffset::Timestamp(time) => {
+                self.fetch_offset_for_time(topic, partitions, time)
+            }
             KafkaEnumeratorOffset::None => partitions
                 .iter()
                 .map(|partition| Ok((*partition, None)))
@@ -141,13 +247,17 @@ impl KafkaSplitEnumerator {
         }
     }
 
-    fn fetch_start_offset(&self, partitions: &[i32]) -> KafkaResult<HashMap<i32, Option<i64>>> {
+    fn fetch_start_offset(
+        &self,
+        topic: &str,
+        partitions: &[i32],
+    ) -> KafkaResult<HashMap<i32, Option<i64>>> {
         match self.start_offset {
             KafkaEnumeratorOffset::Earliest | KafkaEnumeratorOffset::Latest => partitions
                 .iter()
                 .map(|partition| {
                     self.admin_client
-                        .fetch_watermarks(self.topic.as_str(), *partition, KAFKA_SYNC_CALL_TIMEOUT)
+                        .fetch_watermarks(topic, *partition, KAFKA_SYNC_CALL_TIMEOUT)
                         .map(|watermark| match self.start_offset {
                             KafkaEnumeratorOffset::Earliest => Some(watermark.0),
                             KafkaEnumeratorOffset::Latest => Some(watermark.1),
@@ -162,7 +272,11 @@ impl KafkaSplitEnumerator {
                 .map(|partition| Ok((*partition, Some(offset))))
                 .collect(),
 
-            KafkaEnumeratorOffset::Timestamp(time) => self.fetch_offset_for_time(partitions, time),
+            KafkaEnumeratorOffset::Timestamp(time) => {
+                self.fetch_offset_for_time(topic, partitions, time)
+            }
+
+            KafkaEnumeratorOffset::GroupOffsets => self.fetch_committed_offsets(topic, partitions),
 
             KafkaEnumeratorOffset::None => partitions
                 .iter()
@@ -173,13 +287,14 @@ impl KafkaSplitEnumerator {
 
     fn fetch_offset_for_time(
         &self,
+        topic: &str,
         partitions: &[i32],
         time: i64,
     ) -> KafkaResult<HashMap<i32, Option<i64>>> {
         let mut tpl = TopicPartitionList::new();
 
         for partition in partitions {
-            tpl.add_partition_offset(self.topic.as_str(), *partition, Offset::Offset(time))?;
+            tpl.add_partition_offset(topic, *partition, Offset::Offset(time))?;
         }
 
         let offsets = self
@@ -188,14 +303,14 @@ impl KafkaSplitEnumerator {
 
         let mut result = HashMap::with_capacity(partitions.len());
 
-        for elem in offsets.elements_for_topic(self.topic.as_str()) {
+        for elem in offsets.elements_for_topic(topic) {
             match elem.offset() {
                 Offset::Offset(offset) => {
                     result.insert(elem.partition(), Some(offset));
                 }
                 _ => {
                     let (_, high_watermark) = self.admin_client.fetch_watermarks(
-                        self.topic.as_str(),
+                        topic,
                         elem.partition(),
                         KAFKA_SYNC_CALL_TIMEOUT,
                     )?;
@@ -207,21 +322,205 @@ impl KafkaSplitEnumerator {
         Ok(result)
     }
 
-    fn fetch_topic_partition(&mut self) -> anyhow::Result<Vec<i32>> {
-        // for now, we only support one topic
-        let metadata = self
+    /// Fetches the offsets committed by the consumer group. A partition without a committed
+    /// offset, e.g. one added after the group stopped consuming, starts from the earliest offset.
+    fn fetch_committed_offsets(
+        &self,
+        topic: &str,
+        partitions: &[i32],
+    ) -> KafkaResult<HashMap<i32, Option<i64>>> {
+        let mut tpl = TopicPartitionList::new();
+        for partition in partitions {
+            tpl.add_partition(topic, *partition);
+        }
+
+        let committed = self
             .admin_client
-            .fetch_metadata(Some(self.topic.as_str()), KAFKA_SYNC_CALL_TIMEOUT)?;
+            .committed_offsets(tpl, KAFKA_SYNC_CALL_TIMEOUT)?;
+
+        let mut result = HashMap::with_capacity(partitions.len());
+        for elem in committed.elements_for_topic(topic) {
+            let offset = match elem.offset() {
+                Offset::Offset(offset) => offset,
+                _ => {
+                    let (low_watermark, _) = self.admin_client.fetch_watermarks(
+                        topic,
+                        elem.partition(),
+                        KAFKA_SYNC_CALL_TIMEOUT,
+                    )?;
+                    low_watermark
+                }
+            };
+            result.insert(elem.partition(), Some(offset));
+        }
+        Ok(result)
+    }
 
-        let topic_meta = match metadata.topics() {
-            [meta] => meta,
-            _ => return Err(anyhow!("topic {} not found", self.topic)),
+    /// Checks that the start offsets resolved from a timestamp or a consumer group are still
+    /// readable, i.e. they are not truncated by the retention of the topic.
+    fn validate_start_offsets(
+        &self,
+        topic: &str,
+        offsets: &HashMap<i32, Option<i64>>,
+    ) -> anyhow::Result<()> {
+        for (partition, offset) in offsets {
+            let offset = match offset {
+                Some(offset) => *offset,
+                None => continue,
+            };
+            let (low_watermark, high_watermark) =
+                self.admin_client
+                    .fetch_watermarks(topic, *partition, KAFKA_SYNC_CALL_TIMEOUT)?;
+            if !(low_watermark..=high_watermark).contains(&offset) {
+                return Err(anyhow!(
+                    "start offset {} of partition {} of topic {} is out of range [{}, {}]",
+                    offset,
+                    partition,
+                    topic,
+                    low_watermark,
+                    high_watermark
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves the subscribed topics and their partitions. With a topic pattern, the full topic
+    /// list is fetched from the brokers each time, so topics created after the source will show up
+    /// as new splits.
+    fn fetch_topic_partitions(&self) -> anyhow::Result<Vec<(String, Vec<i32>)>> {
+        let metadata = match &self.subscription {
+            KafkaTopicSubscription::Topic(topic) => {
+                let metadata = self
+                    .admin_client
+                    .fetch_metadata(Some(topic.as_str()), KAFKA_SYNC_CALL_TIMEOUT)?;
+                if metadata.topics().len() != 1 {
+                    return Err(anyhow!("topic {} not found", topic));
+                }
+                metadata
+            }
+            KafkaTopicSubscription::Pattern(_) => self
+                .admin_client
+                .fetch_metadata(None, KAFKA_SYNC_CALL_TIMEOUT)?,
         };
 
-        Ok(topic_meta
-            .partitions()
+        let mut ret = metadata
+            .topics()
             .iter()
-            .map(|partition| partition.id())
-            .collect())
+            .filter(|topic_meta| match &self.subscription {
+                KafkaTopicSubscription::Topic(_) => true,
+                KafkaTopicSubscription::Pattern(regex) => regex.is_match(topic_meta.name()),
+            })
+            .map(|topic_meta| {
+                (
+                    topic_meta.name().to_string(),
+                    topic_meta
+                        .partitions()
+                        .iter()
+                        .map(|partition| partition.id())
+                        .collect(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // keep the split order stable across listings
+        ret.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties(kvs: &[(&str, &str)]) -> AnyhowProperties {
+        AnyhowProperties::new(
+            kvs.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    fn subscription(kvs: &[(&str, &str)]) -> anyhow::Result<KafkaTopicSubscription> {
+        KafkaTopicSubscription::from_properties(&properties(kvs))
+    }
+
+    fn startup_offset(kvs: &[(&str, &str)]) -> anyhow::Result<KafkaEnumeratorOffset> {
+        KafkaEnumeratorOffset::from_properties(&properties(kvs))
+    }
+
+    #[test]
+    fn test_topic_subscription() {
+        assert!(matches!(
+            subscription(&[(KAFKA_CONFIG_TOPIC_KEY, "t")]).unwrap(),
+            KafkaTopicSubscription::Topic(t) if t == "t"
+        ));
+
+        let pattern = match subscription(&[(KAFKA_CONFIG_TOPIC_PATTERN_KEY, "logs\\..*")]).unwrap()
+        {
+            KafkaTopicSubscription::Pattern(regex) => regex,
+            _ => unreachable!(),
+        };
+        assert!(pattern.is_match("logs.order"));
+        assert!(!pattern.is_match("app.logs.order"));
+        assert!(!pattern.is_match("logs"));
+
+        assert!(subscription(&[]).is_err());
+        assert!(subscription(&[(KAFKA_CONFIG_TOPIC_PATTERN_KEY, "(")]).is_err());
+        assert!(subscription(&[
+            (KAFKA_CONFIG_TOPIC_KEY, "t"),
+            (KAFKA_CONFIG_TOPIC_PATTERN_KEY, "t.*")
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_startup_offset() {
+        assert_eq!(
+            startup_offset(&[]).unwrap(),
+            KafkaEnumeratorOffset::Earliest
+        );
+        assert_eq!(
+            startup_offset(&[(KAFKA_CONFIG_SCAN_STARTUP_MODE, "latest")]).unwrap(),
+            KafkaEnumeratorOffset::Latest
+        );
+
+        assert_eq!(
+            startup_offset(&[
+                (KAFKA_CONFIG_SCAN_STARTUP_MODE, "timestamp"),
+                (KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS, "1650000000000")
+            ])
+            .unwrap(),
+            KafkaEnumeratorOffset::Timestamp(1650000000000)
+        );
+        assert_eq!(
+            startup_offset(&[(KAFKA_CONFIG_TIME_OFFSET, "1650000000000")]).unwrap(),
+            KafkaEnumeratorOffset::Timestamp(1650000000000)
+        );
+        assert!(startup_offset(&[(KAFKA_CONFIG_SCAN_STARTUP_MODE, "timestamp")]).is_err());
+        assert!(startup_offset(&[
+            (KAFKA_CONFIG_SCAN_STARTUP_MODE, "timestamp"),
+            (KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS, "-1")
+        ])
+        .is_err());
+        assert!(startup_offset(&[
+            (KAFKA_CONFIG_SCAN_STARTUP_MODE, "timestamp"),
+            (
+                KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS,
+                &i64::MAX.to_string()
+            )
+        ])
+        .is_err());
+
+        assert_eq!(
+            startup_offset(&[
+                (KAFKA_CONFIG_SCAN_STARTUP_MODE, "group-offsets"),
+                (KAFKA_CONFIG_CONSUME_GROUP, "g")
+            ])
+            .unwrap(),
+            KafkaEnumeratorOffset::GroupOffsets
+        );
+        assert!(startup_offset(&[(KAFKA_CONFIG_SCAN_STARTUP_MODE, "group-offsets")]).is_err());
+        assert!(startup_offset(&[(KAFKA_CONFIG_SCAN_STARTUP_MODE, "specific")]).is_err());
     }
 }
@@ -25,6 +25,10 @@ const KAFKA_SYNC_CALL_TIMEOUT: Duration = Duration::from_secs(1);
 
 const KAFKA_CONFIG_BROKERS_KEY: &str = "kafka.brokers";
 const KAFKA_CONFIG_TOPIC_KEY: &str = "kafka.topic";
+const KAFKA_CONFIG_TOPIC_PATTERN_KEY: &str = "kafka.topic.pattern";
 const KAFKA_CONFIG_SCAN_STARTUP_MODE: &str = "kafka.scan.startup.mode";
+const KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS: &str = "kafka.scan.startup.timestamp.millis";
+/// The legacy key of [`KAFKA_CONFIG_SCAN_STARTUP_TIMESTAMP_MILLIS`], which implies the timestamp
+/// startup mode.
 const KAFKA_CONFIG_TIME_OFFSET: &str = "kafka.time.offset";
 const KAFKA_CONFIG_CONSUME_GROUP: &str = "kafka.consumer.group";
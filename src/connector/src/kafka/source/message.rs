@@ -19,6 +19,7 @@ use rdkafka::Message;
 use serde::{Deserialize, Serialize};
 
 use crate::base::{InnerMessage, SourceMessage, SourceOffset};
+use crate::kafka::split::split_id;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct KafkaMessage {
@@ -47,7 +48,7 @@ impl<'a> From<BorrowedMessage<'a>> for InnerMessage {
         InnerMessage {
             payload: message.payload().map(Bytes::copy_from_slice),
             offset: message.offset().to_string(),
-            split_id: message.partition().to_string(),
+            split_id: split_id(message.topic(), message.partition()),
         }
     }
 }
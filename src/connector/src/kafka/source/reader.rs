@@ -13,27 +13,45 @@
 // limitations under the License.
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use futures::StreamExt;
 use rdkafka::config::RDKafkaLogLevel;
-use rdkafka::consumer::{DefaultConsumerContext, StreamConsumer};
-use rdkafka::ClientConfig;
+use rdkafka::consumer::{Consumer, ConsumerContext, Rebalance, StreamConsumer};
+use rdkafka::{ClientConfig, ClientContext, Offset, TopicPartitionList};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::RwError;
 
-use crate::base::{InnerMessage, SourceReader};
+use crate::base::{InnerMessage, SourceReader, SplitImpl};
 use crate::kafka::split::KafkaSplit;
 use crate::kafka::KAFKA_CONFIG_BROKERS_KEY;
+use crate::monitor::{ConnectorEvent, ConnectorMetricsReporter};
 use crate::Properties;
 
 const KAFKA_MAX_FETCH_MESSAGES: usize = 1024;
 
+/// Reports the rebalances of the consumer to the metrics of the source.
+#[derive(Default)]
+struct KafkaConsumerContext {
+    reporter: Mutex<Option<ConnectorMetricsReporter>>,
+}
+
+impl ClientContext for KafkaConsumerContext {}
+
+impl ConsumerContext for KafkaConsumerContext {
+    fn post_rebalance<'a>(&self, _rebalance: &Rebalance<'a>) {
+        if let Some(reporter) = self.reporter.lock().unwrap().as_ref() {
+            reporter.report_event(ConnectorEvent::Rebalance);
+        }
+    }
+}
+
 pub struct KafkaSplitReader {
-    consumer: Arc<StreamConsumer<DefaultConsumerContext>>,
+    consumer: Arc<StreamConsumer<KafkaConsumerContext>>,
+    /// The splits assigned to the consumer, keyed by topic.
     assigned_splits: HashMap<String, Vec<KafkaSplit>>,
 }
 
@@ -86,7 +104,7 @@ impl SourceReader for KafkaSplitReader {
 
         let consumer = config
             .set_log_level(RDKafkaLogLevel::Info)
-            .create_with_context(DefaultConsumerContext)
+            .create_with_context(KafkaConsumerContext::default())
             .map_err(|e| RwError::from(InternalError(format!("consumer creation failed {}", e))))?;
 
         Ok(Self {
@@ -94,4 +112,47 @@ impl SourceReader for KafkaSplitReader {
             assigned_splits: HashMap::new(),
         })
     }
+
+    fn set_metrics_reporter(&mut self, reporter: ConnectorMetricsReporter) {
+        *self.consumer.context().reporter.lock().unwrap() = Some(reporter);
+    }
+
+    /// Assigns the partitions of the new splits to the consumer from their start offsets. As the
+    /// whole assignment is replaced, the partitions already assigned are reassigned at their
+    /// current positions.
+    async fn assign_splits(&mut self, splits: Vec<SplitImpl>) -> Result<()> {
+        let mut changed = false;
+        for split in splits {
+            let split = match split {
+                SplitImpl::Kafka(split) => split,
+                other => return Err(anyhow!("unexpected split {} for kafka", other.id())),
+            };
+            let topic_splits = self.assigned_splits.entry(split.topic.clone()).or_default();
+            if !topic_splits.iter().any(|s| s.partition == split.partition) {
+                topic_splits.push(split);
+                changed = true;
+            }
+        }
+        if !changed {
+            return Ok(());
+        }
+
+        let positions = self.consumer.position()?;
+        let mut assignment = TopicPartitionList::new();
+        for (topic, splits) in &self.assigned_splits {
+            for split in splits {
+                let position = positions
+                    .find_partition(topic, split.partition)
+                    .map(|elem| elem.offset());
+                let offset = match (position, split.start_offset) {
+                    (Some(offset @ Offset::Offset(_)), _) => offset,
+                    (_, Some(start_offset)) => Offset::Offset(start_offset),
+                    (_, None) => Offset::Beginning,
+                };
+                assignment.add_partition_offset(topic, split.partition, offset)?;
+            }
+        }
+        self.consumer.assign(&assignment)?;
+        Ok(())
+    }
 }
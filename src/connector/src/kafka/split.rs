@@ -29,7 +29,7 @@ pub const KAFKA_SPLIT_TYPE: &str = "kafka";
 
 impl SourceSplit for KafkaSplit {
     fn id(&self) -> String {
-        format!("{}", self.partition)
+        split_id(&self.topic, self.partition)
     }
 
     fn to_string(&self) -> anyhow::Result<String> {
@@ -45,6 +45,11 @@ impl SourceSplit for KafkaSplit {
     }
 }
 
+/// The id of the split of `partition` in `topic`, unique across topics.
+pub(crate) fn split_id(topic: &str, partition: i32) -> String {
+    format!("{}-{}", topic, partition)
+}
+
 impl KafkaSplit {
     pub fn new(
         partition: i32,
@@ -19,6 +19,7 @@ use aws_config::default_provider::credentials::DefaultCredentialsChain;
 use aws_config::sts::AssumeRoleProvider;
 use aws_types::credentials::SharedCredentialsProvider;
 use aws_types::region::Region;
+use http::Uri;
 use maplit::hashmap;
 use risingwave_common::error::ErrorCode::ProtocolError;
 use risingwave_common::error::RwError;
@@ -100,6 +101,18 @@ impl AwsConfigInfo {
         Ok(config_loader.load().await)
     }
 
+    /// Builds a Kinesis client, which talks to `endpoint` instead of the default one if given.
+    pub async fn build_client(&self) -> Result<aws_sdk_kinesis::Client> {
+        let aws_config = self.load().await?;
+        let mut builder = aws_sdk_kinesis::config::Builder::from(&aws_config);
+        if let Some(endpoint) = &self.endpoint {
+            let uri = endpoint.parse::<Uri>()?;
+            builder =
+                builder.endpoint_resolver(aws_smithy_http::endpoint::Endpoint::immutable(uri));
+        }
+        Ok(aws_sdk_kinesis::Client::from_conf(builder.build()))
+    }
+
     pub fn build(properties: &Properties) -> risingwave_common::error::Result<Self> {
         let stream_name = properties.get_kinesis(KINESIS_STREAM_NAME)?;
         let region = properties.get_kinesis(KINESIS_STREAM_REGION)?;
@@ -12,17 +12,79 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use aws_sdk_kinesis::model::Shard;
 use aws_sdk_kinesis::Client as kinesis_client;
 
 use crate::base::SplitEnumerator;
+use crate::kinesis::config::AwsConfigInfo;
 use crate::kinesis::split::{KinesisOffset, KinesisSplit};
+use crate::{AnyhowProperties, Properties};
+
+/// `KinesisShardLister` lists the shards of a Kinesis stream page by page. It's implemented by the
+/// Kinesis client, and by mocks in tests.
+#[async_trait]
+pub trait KinesisShardLister: Send + Sync {
+    /// Returns one page of shards, and the token of the next page if there is one.
+    async fn list_shards(
+        &self,
+        stream_name: &str,
+        next_token: Option<String>,
+    ) -> Result<(Vec<Shard>, Option<String>)>;
+}
+
+#[async_trait]
+impl KinesisShardLister for kinesis_client {
+    async fn list_shards(
+        &self,
+        stream_name: &str,
+        next_token: Option<String>,
+    ) -> Result<(Vec<Shard>, Option<String>)> {
+        // The stream name must not be given along with a token of the next page.
+        let request = match next_token {
+            Some(token) => self.list_shards().next_token(token),
+            None => self.list_shards().stream_name(stream_name),
+        };
+        let output = request.send().await?;
+        Ok((output.shards.unwrap_or_default(), output.next_token))
+    }
+}
 
 pub struct KinesisSplitEnumerator {
     stream_name: String,
-    client: kinesis_client,
+    client: Box<dyn KinesisShardLister>,
+
+    /// Ids of the shards seen by the last listing, `None` before the first listing.
+    shard_ids: Option<HashSet<String>>,
+
+    /// Whether the shards changed in the last listing, i.e. shards have been split or merged.
+    resharded: bool,
+}
+
+impl KinesisSplitEnumerator {
+    pub async fn new(properties: &AnyhowProperties) -> Result<KinesisSplitEnumerator> {
+        let config = AwsConfigInfo::build(&Properties::new(properties.0.clone()))?;
+        let client = config.build_client().await?;
+        Ok(Self::with_client(config.stream_name, Box::new(client)))
+    }
+
+    pub fn with_client(stream_name: String, client: Box<dyn KinesisShardLister>) -> Self {
+        Self {
+            stream_name,
+            client,
+            shard_ids: None,
+            resharded: false,
+        }
+    }
+
+    /// Whether the shards listed by the last [`SplitEnumerator::list_splits`] differ from the ones
+    /// listed by the one before, so that the splits should be scheduled again.
+    pub fn resharded(&self) -> bool {
+        self.resharded
+    }
 }
 
 #[async_trait]
@@ -34,28 +96,39 @@ impl SplitEnumerator for KinesisSplitEnumerator {
         let mut shard_collect: Vec<Shard> = Vec::new();
 
         loop {
-            let list_shard_output = self
+            let (shards, token) = self
                 .client
-                .list_shards()
-                .set_next_token(next_token)
-                .stream_name(&self.stream_name)
-                .send()
+                .list_shards(&self.stream_name, next_token)
                 .await?;
-            match list_shard_output.shards {
-                Some(shard) => shard_collect.extend(shard),
-                None => {
-                    return Err(anyhow::Error::msg(format!(
-                        "no shards in stream {}",
-                        &self.stream_name
-                    )));
-                }
-            }
-
-            match list_shard_output.next_token {
+            shard_collect.extend(shards);
+            match token {
                 Some(token) => next_token = Some(token),
                 None => break,
             }
         }
+        if shard_collect.is_empty() {
+            return Err(anyhow::Error::msg(format!(
+                "no shards in stream {}",
+                &self.stream_name
+            )));
+        }
+
+        // Closed shards are still listed after resharding until they expire, as their records
+        // can be read. So a changed set of shard ids means that shards were split or merged.
+        let shard_ids: HashSet<String> = shard_collect
+            .iter()
+            .map(|x| x.shard_id().unwrap_or_default().to_string())
+            .collect();
+        self.resharded = matches!(&self.shard_ids, Some(prev) if *prev != shard_ids);
+        if self.resharded {
+            log::info!(
+                "kinesis stream {} is resharded, {} shards now",
+                self.stream_name,
+                shard_ids.len()
+            );
+        }
+        self.shard_ids = Some(shard_ids);
+
         Ok(shard_collect
             .into_iter()
             .map(|x| KinesisSplit {
@@ -69,9 +142,85 @@ impl SplitEnumerator for KinesisSplitEnumerator {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Mutex;
+
     use aws_sdk_kinesis::Region;
 
     use super::*;
+    use crate::base::SourceSplit;
+
+    /// Returns the shards of the current version page by page, where each page holds two shards.
+    struct MockShardLister {
+        versions: Mutex<Vec<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl KinesisShardLister for MockShardLister {
+        async fn list_shards(
+            &self,
+            stream_name: &str,
+            next_token: Option<String>,
+        ) -> Result<(Vec<Shard>, Option<String>)> {
+            assert_eq!(stream_name, "kinesis_test_stream");
+            let mut versions = self.versions.lock().unwrap();
+            let page = next_token.map_or(0, |token| token.parse().unwrap());
+            let shard_ids = &versions[0];
+            let shards = shard_ids
+                .iter()
+                .skip(page * 2)
+                .take(2)
+                .map(|id| Shard::builder().shard_id(*id).build())
+                .collect();
+            let next_token = if (page + 1) * 2 < shard_ids.len() {
+                Some((page + 1).to_string())
+            } else {
+                // The next listing sees the next version.
+                if versions.len() > 1 {
+                    versions.remove(0);
+                }
+                None
+            };
+            Ok((shards, next_token))
+        }
+    }
+
+    fn split_ids(splits: &[KinesisSplit]) -> Vec<String> {
+        splits.iter().map(|split| split.id()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_kinesis_split_enumerator_with_mock_client() -> Result<()> {
+        let client = MockShardLister {
+            versions: Mutex::new(vec![
+                vec!["shard-0", "shard-1", "shard-2"],
+                vec!["shard-0", "shard-1", "shard-2"],
+                // shard-2 is split into shard-3 and shard-4.
+                vec!["shard-0", "shard-1", "shard-2", "shard-3", "shard-4"],
+            ]),
+        };
+        let mut enumerator = KinesisSplitEnumerator::with_client(
+            "kinesis_test_stream".to_string(),
+            Box::new(client),
+        );
+
+        let splits = enumerator.list_splits().await?;
+        assert_eq!(split_ids(&splits), vec!["shard-0", "shard-1", "shard-2"]);
+        assert!(!enumerator.resharded());
+
+        enumerator.list_splits().await?;
+        assert!(!enumerator.resharded());
+
+        let splits = enumerator.list_splits().await?;
+        assert_eq!(
+            split_ids(&splits),
+            vec!["shard-0", "shard-1", "shard-2", "shard-3", "shard-4"]
+        );
+        assert!(enumerator.resharded());
+
+        enumerator.list_splits().await?;
+        assert!(!enumerator.resharded());
+        Ok(())
+    }
 
     #[tokio::test]
     #[ignore]
@@ -82,10 +231,7 @@ mod tests {
             .load()
             .await;
         let client = aws_sdk_kinesis::Client::new(&config);
-        let mut enumerator = KinesisSplitEnumerator {
-            stream_name,
-            client,
-        };
+        let mut enumerator = KinesisSplitEnumerator::with_client(stream_name, Box::new(client));
         let list_splits_resp = enumerator.list_splits().await?;
         // println!("{:#?}", list_splits_resp);
         assert_eq!(list_splits_resp.len(), 4);
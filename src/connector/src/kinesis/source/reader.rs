@@ -22,13 +22,13 @@ use aws_sdk_kinesis::output::GetRecordsOutput;
 use aws_sdk_kinesis::types::SdkError;
 use aws_sdk_kinesis::Client as kinesis_client;
 use aws_smithy_types::DateTime;
-use http::Uri;
 
 use crate::base::{InnerMessage, SourceReader};
 use crate::kinesis::config::AwsConfigInfo;
 use crate::kinesis::source::message::KinesisMessage;
 use crate::kinesis::source::state::KinesisSplitReaderState;
 use crate::kinesis::split::{KinesisOffset, KinesisSplit};
+use crate::monitor::{ConnectorEvent, ConnectorMetricsReporter};
 use crate::Properties;
 
 pub struct KinesisSplitReader {
@@ -38,6 +38,7 @@ pub struct KinesisSplitReader {
     latest_sequence_num: String,
     shard_iter: Option<String>,
     assigned_split: Option<KinesisSplit>,
+    metrics_reporter: Option<ConnectorMetricsReporter>,
 }
 
 #[async_trait]
@@ -70,6 +71,9 @@ impl SourceReader for KinesisSplitReader {
                             return Err(e);
                         }
                     }
+                    if let Some(reporter) = &self.metrics_reporter {
+                        reporter.report_event(ConnectorEvent::Reconnect);
+                    }
                     return self.next().await;
                 }
                 Err(SdkError::ServiceError { err, .. })
@@ -116,14 +120,7 @@ impl SourceReader for KinesisSplitReader {
         Self: Sized,
     {
         let config = AwsConfigInfo::build(&config)?;
-        let aws_config = config.load().await?;
-        let mut builder = aws_sdk_kinesis::config::Builder::from(&aws_config);
-        if let Some(endpoint) = &config.endpoint {
-            let uri = endpoint.clone().parse::<Uri>().unwrap();
-            builder =
-                builder.endpoint_resolver(aws_smithy_http::endpoint::Endpoint::immutable(uri));
-        }
-        let client = kinesis_client::from_conf(builder.build());
+        let client = config.build_client().await?;
 
         let mut split_reader = KinesisSplitReader {
             client,
@@ -132,6 +129,7 @@ impl SourceReader for KinesisSplitReader {
             latest_sequence_num: "".to_string(),
             shard_iter: None,
             assigned_split: None,
+            metrics_reporter: None,
         };
 
         if let Some(state) = state {
@@ -185,6 +183,10 @@ impl SourceReader for KinesisSplitReader {
 
         Ok(split_reader)
     }
+
+    fn set_metrics_reporter(&mut self, reporter: ConnectorMetricsReporter) {
+        self.metrics_reporter = Some(reporter);
+    }
 }
 
 impl KinesisSplitReader {
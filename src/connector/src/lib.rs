@@ -29,10 +29,13 @@
 #![feature(binary_heap_drain_sorted)]
 
 pub mod base;
-mod filesystem;
+pub mod filesystem;
 mod kafka;
 pub mod kinesis;
+pub mod monitor;
 mod pulsar;
+pub mod schema_registry;
+pub mod sink;
 mod utils;
 pub use base::*;
 pub use utils::{AnyhowProperties, Properties};
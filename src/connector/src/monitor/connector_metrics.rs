@@ -0,0 +1,192 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use prometheus::{
+    exponential_buckets, histogram_opts, register_histogram_vec_with_registry,
+    register_int_counter_vec_with_registry, HistogramVec, IntCounterVec, Registry,
+};
+
+/// Metrics of the connectors, shared by all the sources of a node. The metrics are labeled with
+/// the source id, the split id and the connector type, so that all the connectors report in the
+/// same way.
+pub struct ConnectorMetrics {
+    pub registry: Registry,
+
+    /// Latency of each fetch from the upstream, labeled by the splits the fetched messages are
+    /// from
+    pub fetch_latency: HistogramVec,
+    /// Number of messages from each split in a fetch
+    pub fetch_batch_size: HistogramVec,
+    pub fetch_message_count: IntCounterVec,
+
+    /// Events of the connectors like rebalances and reconnections
+    pub event_count: IntCounterVec,
+    pub error_count: IntCounterVec,
+    /// Messages that can't be parsed, which are skipped instead of failing the source
+    pub dead_letter_count: IntCounterVec,
+}
+
+impl ConnectorMetrics {
+    pub fn new(registry: Registry) -> Self {
+        let opts = histogram_opts!(
+            "connector_fetch_latency",
+            "Time spent in each fetch from the upstream (s)",
+            exponential_buckets(0.0001, 2.0, 20).unwrap() // max 52s
+        );
+        let fetch_latency = register_histogram_vec_with_registry!(
+            opts,
+            &["source_id", "split_id", "connector"],
+            registry
+        )
+        .unwrap();
+
+        let opts = histogram_opts!(
+            "connector_fetch_batch_size",
+            "Number of messages from each split in a fetch",
+            exponential_buckets(1.0, 2.0, 16).unwrap() // max 32768
+        );
+        let fetch_batch_size = register_histogram_vec_with_registry!(
+            opts,
+            &["source_id", "split_id", "connector"],
+            registry
+        )
+        .unwrap();
+
+        let fetch_message_count = register_int_counter_vec_with_registry!(
+            "connector_fetch_message_count",
+            "Total number of messages fetched from each split",
+            &["source_id", "split_id", "connector"],
+            registry
+        )
+        .unwrap();
+
+        let event_count = register_int_counter_vec_with_registry!(
+            "connector_event_count",
+            "Total number of events like rebalances and reconnections of the connectors",
+            &["source_id", "connector", "event"],
+            registry
+        )
+        .unwrap();
+
+        let error_count = register_int_counter_vec_with_registry!(
+            "connector_error_count",
+            "Total number of errors returned by the connectors",
+            &["source_id", "connector"],
+            registry
+        )
+        .unwrap();
+
+        let dead_letter_count = register_int_counter_vec_with_registry!(
+            "connector_dead_letter_count",
+            "Total number of messages from each split that failed to be parsed and are skipped",
+            &["source_id", "split_id", "connector"],
+            registry
+        )
+        .unwrap();
+
+        Self {
+            registry,
+            fetch_latency,
+            fetch_batch_size,
+            fetch_message_count,
+            event_count,
+            error_count,
+            dead_letter_count,
+        }
+    }
+
+    /// Create a new `ConnectorMetrics` instance used in tests or other places.
+    pub fn unused() -> Self {
+        Self::new(Registry::new())
+    }
+}
+
+/// An event of a connector, other than fetching messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectorEvent {
+    /// The splits assigned to the reader are changed by the upstream, e.g. a Kafka consumer
+    /// group rebalance.
+    Rebalance,
+    /// The reader re-establishes its position or connection in the upstream.
+    Reconnect,
+}
+
+impl ConnectorEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectorEvent::Rebalance => "rebalance",
+            ConnectorEvent::Reconnect => "reconnect",
+        }
+    }
+}
+
+/// Reports the metrics of a source reader to [`ConnectorMetrics`] with its labels.
+#[derive(Clone)]
+pub struct ConnectorMetricsReporter {
+    metrics: Arc<ConnectorMetrics>,
+    source_id: String,
+    connector: String,
+}
+
+impl ConnectorMetricsReporter {
+    pub fn new(metrics: Arc<ConnectorMetrics>, source_id: u32, connector: &str) -> Self {
+        Self {
+            metrics,
+            source_id: source_id.to_string(),
+            connector: connector.to_string(),
+        }
+    }
+
+    /// Reports a fetch taking `latency`, which reads `batch_size` messages from `split_id`.
+    pub fn report_fetch(&self, split_id: &str, latency: Duration, batch_size: usize) {
+        let labels = [self.source_id.as_str(), split_id, self.connector.as_str()];
+        self.metrics
+            .fetch_latency
+            .with_label_values(&labels)
+            .observe(latency.as_secs_f64());
+        self.metrics
+            .fetch_batch_size
+            .with_label_values(&labels)
+            .observe(batch_size as f64);
+        self.metrics
+            .fetch_message_count
+            .with_label_values(&labels)
+            .inc_by(batch_size as u64);
+    }
+
+    pub fn report_event(&self, event: ConnectorEvent) {
+        self.metrics
+            .event_count
+            .with_label_values(&[&self.source_id, &self.connector, event.as_str()])
+            .inc();
+    }
+
+    pub fn report_error(&self) {
+        self.metrics
+            .error_count
+            .with_label_values(&[&self.source_id, &self.connector])
+            .inc();
+    }
+
+    /// Reports a message from `split_id` that fails to be parsed.
+    pub fn report_dead_letter(&self, split_id: &str) {
+        self.metrics
+            .dead_letter_count
+            .with_label_values(&[&self.source_id, split_id, &self.connector])
+            .inc();
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use super::ConnectorMetricsReporter;
+use crate::base::{InnerMessage, SourceReader, SplitImpl};
+use crate::{ConnectorState, Properties};
+
+/// A [`SourceReader`] that wraps another one, and reports the fetches and errors of it to
+/// [`super::ConnectorMetrics`].
+pub struct MonitoredSourceReader {
+    inner: Box<dyn SourceReader + Send + Sync>,
+    reporter: ConnectorMetricsReporter,
+}
+
+impl MonitoredSourceReader {
+    pub fn new(
+        mut inner: Box<dyn SourceReader + Send + Sync>,
+        reporter: ConnectorMetricsReporter,
+    ) -> Self {
+        inner.set_metrics_reporter(reporter.clone());
+        Self { inner, reporter }
+    }
+}
+
+#[async_trait]
+impl SourceReader for MonitoredSourceReader {
+    async fn next(&mut self) -> Result<Option<Vec<InnerMessage>>> {
+        let start = Instant::now();
+        let result = self.inner.next().await;
+        let latency = start.elapsed();
+        match &result {
+            Ok(Some(messages)) => {
+                let mut batch_sizes: HashMap<&str, usize> = HashMap::new();
+                for message in messages {
+                    *batch_sizes.entry(message.split_id.as_str()).or_default() += 1;
+                }
+                for (split_id, batch_size) in batch_sizes {
+                    self.reporter.report_fetch(split_id, latency, batch_size);
+                }
+            }
+            Ok(None) => {}
+            Err(_) => self.reporter.report_error(),
+        }
+        result
+    }
+
+    async fn new(_properties: Properties, _state: Option<ConnectorState>) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(anyhow!(
+            "MonitoredSourceReader should be created by wrapping another reader"
+        ))
+    }
+
+    fn set_metrics_reporter(&mut self, reporter: ConnectorMetricsReporter) {
+        self.inner.set_metrics_reporter(reporter.clone());
+        self.reporter = reporter;
+    }
+
+    async fn assign_splits(&mut self, splits: Vec<SplitImpl>) -> Result<()> {
+        self.inner.assign_splits(splits).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::monitor::ConnectorMetrics;
+
+    struct MockReader {
+        batches: Vec<Result<Option<Vec<InnerMessage>>>>,
+    }
+
+    #[async_trait]
+    impl SourceReader for MockReader {
+        async fn next(&mut self) -> Result<Option<Vec<InnerMessage>>> {
+            self.batches.remove(0)
+        }
+
+        async fn new(_properties: Properties, _state: Option<ConnectorState>) -> Result<Self> {
+            unreachable!()
+        }
+    }
+
+    fn message(split_id: &str) -> InnerMessage {
+        InnerMessage {
+            payload: None,
+            offset: "0".to_string(),
+            split_id: split_id.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_monitored_source_reader() {
+        let metrics = Arc::new(ConnectorMetrics::unused());
+        let inner = MockReader {
+            batches: vec![
+                Ok(Some(vec![message("0"), message("1"), message("0")])),
+                Err(anyhow!("broken")),
+            ],
+        };
+        let mut reader = MonitoredSourceReader::new(
+            Box::new(inner),
+            ConnectorMetricsReporter::new(metrics.clone(), 1, "kafka"),
+        );
+
+        assert_eq!(reader.next().await.unwrap().unwrap().len(), 3);
+        assert!(reader.next().await.is_err());
+
+        let message_count = |split_id: &str| {
+            metrics
+                .fetch_message_count
+                .with_label_values(&["1", split_id, "kafka"])
+                .get()
+        };
+        assert_eq!(message_count("0"), 2);
+        assert_eq!(message_count("1"), 1);
+        assert_eq!(
+            metrics.error_count.with_label_values(&["1", "kafka"]).get(),
+            1
+        );
+    }
+}
@@ -60,8 +60,8 @@ impl SplitEnumerator for PulsarSplitEnumerator {
                 let sub_topic = self.topic.sub_topic(p as i32);
                 PulsarSplit {
                     sub_topic: sub_topic.to_string(),
-                    start_offset: self.start_offset,
-                    stop_offset: self.stop_offset,
+                    start_offset: self.start_offset.clone(),
+                    stop_offset: self.stop_offset.clone(),
                 }
             })
             .collect();
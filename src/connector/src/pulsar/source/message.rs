@@ -14,6 +14,7 @@
 
 use anyhow::anyhow;
 use pulsar::consumer::Message;
+use pulsar::message::proto::MessageIdData;
 use serde::{Deserialize, Serialize};
 
 use crate::base::{InnerMessage, SourceMessage, SourceOffset};
@@ -21,7 +22,7 @@ use crate::base::{InnerMessage, SourceMessage, SourceOffset};
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PulsarMessage {
     pub payload: Option<Vec<u8>>,
-    pub offset: i64,
+    pub offset: String,
     pub split_id: String,
 }
 
@@ -31,7 +32,7 @@ impl SourceMessage for PulsarMessage {
     }
 
     fn offset(&self) -> anyhow::Result<Option<SourceOffset>> {
-        Ok(Some(SourceOffset::Number(self.offset)))
+        Ok(Some(SourceOffset::String(self.offset.clone())))
     }
 
     fn serialize(&self) -> anyhow::Result<String> {
@@ -43,8 +44,63 @@ impl From<Message<Vec<u8>>> for InnerMessage {
     fn from(msg: Message<Vec<u8>>) -> Self {
         InnerMessage {
             payload: Some(bytes::Bytes::from(msg.payload.data)),
-            offset: msg.message_id.id.entry_id.to_string(),
+            offset: message_id_to_string(&msg.message_id.id),
             split_id: msg.topic,
         }
     }
 }
+
+/// Formats a message id as `ledger_id:entry_id:partition:batch_index` to be kept as the offset of
+/// a split, where a missing partition or batch index is `-1`.
+pub(crate) fn message_id_to_string(id: &MessageIdData) -> String {
+    format!(
+        "{}:{}:{}:{}",
+        id.ledger_id,
+        id.entry_id,
+        id.partition.unwrap_or(-1),
+        id.batch_index.unwrap_or(-1)
+    )
+}
+
+/// Parses a message id formatted by [`message_id_to_string`].
+pub(crate) fn parse_message_id(id: &str) -> anyhow::Result<MessageIdData> {
+    let parts = id.split(':').collect::<Vec<_>>();
+    if parts.len() != 4 {
+        return Err(anyhow!("invalid pulsar message id {}", id));
+    }
+    let optional = |part: &str| -> anyhow::Result<Option<i32>> {
+        let value = part.parse::<i32>()?;
+        Ok((value >= 0).then(|| value))
+    };
+    Ok(MessageIdData {
+        ledger_id: parts[0].parse()?,
+        entry_id: parts[1].parse()?,
+        partition: optional(parts[2])?,
+        batch_index: optional(parts[3])?,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_id_string() {
+        let id = MessageIdData {
+            ledger_id: 12,
+            entry_id: 34,
+            partition: Some(1),
+            ..Default::default()
+        };
+        let s = message_id_to_string(&id);
+        assert_eq!(s, "12:34:1:-1");
+        let parsed = parse_message_id(&s).unwrap();
+        assert_eq!(parsed.ledger_id, 12);
+        assert_eq!(parsed.entry_id, 34);
+        assert_eq!(parsed.partition, Some(1));
+        assert_eq!(parsed.batch_index, None);
+
+        assert!(parse_message_id("12:34").is_err());
+    }
+}
@@ -17,16 +17,52 @@ use std::borrow::BorrowMut;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use futures::StreamExt;
-use pulsar::{Consumer, Pulsar, TokioExecutor};
+use pulsar::consumer::{ConsumerOptions, InitialPosition};
+use pulsar::message::proto::MessageIdData;
+use pulsar::{Consumer, Pulsar, SubType, TokioExecutor};
 
+use super::message::parse_message_id;
 use crate::base::{InnerMessage, SourceReader};
 use crate::pulsar::split::{PulsarOffset, PulsarSplit};
-use crate::Properties;
+use crate::pulsar::{
+    PULSAR_CONFIG_SERVICE_URL_KEY, PULSAR_CONFIG_SUBSCRIPTION_KEY, PULSAR_CONFIG_TOPIC_KEY,
+};
+use crate::{ConnectorState, Properties};
 
 pub struct PulsarSplitReader {
+    /// The client of the consumer, kept alive along with it.
+    #[allow(dead_code)]
     pulsar: Pulsar<TokioExecutor>,
     consumer: Consumer<Vec<u8>, TokioExecutor>,
     split: PulsarSplit,
+
+    /// The id of the last message read before recovery, parsed from the start offset of the
+    /// split. The messages up to it are skipped, as seeking to it may deliver it again.
+    last_message_id: Option<MessageIdData>,
+
+    /// The id parsed from the stop offset of the split, at which the reader stops.
+    stop_message_id: Option<MessageIdData>,
+}
+
+/// The default subscription of the readers. A subscription belongs to a single sub topic, so the
+/// readers of different splits never share it.
+const PULSAR_DEFAULT_SUBSCRIPTION: &str = "risingwave";
+
+/// Whether the message `id` is not after `other`.
+fn is_not_after(id: &MessageIdData, other: &MessageIdData) -> bool {
+    (id.ledger_id, id.entry_id, id.batch_index.unwrap_or(-1))
+        <= (
+            other.ledger_id,
+            other.entry_id,
+            other.batch_index.unwrap_or(-1),
+        )
+}
+
+fn parse_offset(offset: &PulsarOffset) -> Result<Option<MessageIdData>> {
+    match offset {
+        PulsarOffset::MessageID(id) => Ok(Some(parse_message_id(id)?)),
+        _ => Ok(None),
+    }
 }
 
 const PULSAR_MAX_FETCH_MESSAGES: u32 = 1024;
@@ -49,10 +85,15 @@ impl SourceReader for PulsarSplitReader {
         for msg in chunk {
             let msg = msg.map_err(|e| anyhow!(e))?;
 
-            let entry_id = msg.message_id.id.entry_id;
+            let id = &msg.message_id.id;
+            if matches!(&self.last_message_id, Some(last) if is_not_after(id, last)) {
+                continue;
+            }
 
             let should_stop = match self.split.stop_offset {
-                PulsarOffset::MessageID(id) => entry_id >= id,
+                PulsarOffset::MessageID(_) => {
+                    matches!(&self.stop_message_id, Some(stop) if is_not_after(stop, id))
+                }
                 PulsarOffset::Timestamp(timestamp) => {
                     msg.payload.metadata.event_time() >= timestamp
                 }
@@ -74,10 +115,74 @@ impl SourceReader for PulsarSplitReader {
         Ok(Some(ret))
     }
 
-    async fn new(_props: Properties, _state: Option<crate::ConnectorState>) -> Result<Self>
+    async fn new(props: Properties, state: Option<ConnectorState>) -> Result<Self>
     where
         Self: Sized,
     {
-        todo!()
+        let service_url = props.get(PULSAR_CONFIG_SERVICE_URL_KEY)?;
+        // Sources reading the same topic must set different subscriptions, as each one is
+        // exclusive.
+        let subscription = props
+            .0
+            .get(PULSAR_CONFIG_SUBSCRIPTION_KEY)
+            .cloned()
+            .unwrap_or_else(|| PULSAR_DEFAULT_SUBSCRIPTION.to_string());
+
+        // The state carries the sub topic of the split and the message ids to read from and to.
+        let split = match state {
+            Some(state) => {
+                let offset = |id: String| {
+                    if id.is_empty() {
+                        PulsarOffset::None
+                    } else {
+                        PulsarOffset::MessageID(id)
+                    }
+                };
+                PulsarSplit::new(
+                    String::from_utf8(state.identifier.to_vec())?,
+                    offset(state.start_offset),
+                    offset(state.end_offset),
+                )
+            }
+            None => PulsarSplit::new(
+                props.get(PULSAR_CONFIG_TOPIC_KEY)?,
+                PulsarOffset::None,
+                PulsarOffset::None,
+            ),
+        };
+        let last_message_id = parse_offset(&split.start_offset)?;
+        let stop_message_id = parse_offset(&split.stop_offset)?;
+
+        let pulsar: Pulsar<TokioExecutor> = Pulsar::builder(service_url, TokioExecutor)
+            .build()
+            .await
+            .map_err(|e| anyhow!(e))?;
+
+        let mut consumer: Consumer<Vec<u8>, TokioExecutor> = pulsar
+            .consumer()
+            .with_topic(&split.sub_topic)
+            .with_subscription_type(SubType::Exclusive)
+            .with_subscription(subscription)
+            .with_options(
+                ConsumerOptions::default().with_initial_position(InitialPosition::Earliest),
+            )
+            .build()
+            .await
+            .map_err(|e| anyhow!(e))?;
+
+        if let Some(message_id) = &last_message_id {
+            consumer
+                .seek(None, Some(message_id.clone()), None, pulsar.clone())
+                .await
+                .map_err(|e| anyhow!(e))?;
+        }
+
+        Ok(Self {
+            pulsar,
+            consumer,
+            split,
+            last_message_id,
+            stop_message_id,
+        })
     }
 }
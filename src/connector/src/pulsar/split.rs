@@ -17,9 +17,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::base::SourceSplit;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum PulsarOffset {
-    MessageID(u64),
+    /// A message id formatted as `ledger_id:entry_id:partition:batch_index`, the same format as
+    /// the offsets of the messages read.
+    MessageID(String),
     Timestamp(u64),
     None,
 }
@@ -0,0 +1,167 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, Result};
+use hyper::body::Buf;
+use hyper::{Client, StatusCode, Uri};
+use serde_derive::{Deserialize, Serialize};
+
+/// The schema type of the Confluent schema registry when `schemaType` is absent.
+const DEFAULT_SCHEMA_TYPE: &str = "AVRO";
+
+/// Returns the subject of the value schema of `topic`, following the default `TopicNameStrategy`
+/// of the Confluent schema registry.
+pub fn topic_value_subject(topic: &str) -> String {
+    format!("{}-value", topic)
+}
+
+/// A client of the REST API of the Confluent schema registry.
+#[derive(Debug, Default)]
+pub struct SchemaRegistryClient {
+    pub(crate) base_path: String,
+}
+
+impl SchemaRegistryClient {
+    pub fn new(base_path: String) -> Self {
+        Self {
+            base_path: base_path.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl SchemaRegistryClient {
+    /// Gets the latest version of the schema registered under `subject`.
+    pub async fn get_latest_schema(&self, subject: &str) -> Result<SubjectSchema> {
+        self.get(&format!("subjects/{}/versions/latest", subject))
+            .await
+    }
+
+    /// Gets the given version of the schema registered under `subject`.
+    pub async fn get_schema_by_version(
+        &self,
+        subject: &str,
+        version: i32,
+    ) -> Result<SubjectSchema> {
+        self.get(&format!("subjects/{}/versions/{}", subject, version))
+            .await
+    }
+
+    async fn get<T>(&self, api: &str) -> Result<T>
+    where
+        T: for<'a> serde::Deserialize<'a>,
+    {
+        let client = Client::new();
+
+        let url: Uri = format!("{}/{}", self.base_path, api).parse()?;
+        let res = client.get(url.clone()).await?;
+        let status = res.status();
+        let body = hyper::body::aggregate(res).await?;
+        if status != StatusCode::OK {
+            let error: ErrorMessage = serde_json::from_reader(body.reader())?;
+            return Err(anyhow!(
+                "failed to request schema registry ({}): {}",
+                url,
+                error.message
+            ));
+        }
+        let result: T = serde_json::from_reader(body.reader())?;
+        Ok(result)
+    }
+}
+
+/// A schema registered under a subject.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectSchema {
+    pub subject: String,
+    pub version: i32,
+    /// The globally unique id of the schema.
+    pub id: i32,
+    pub schema_type: Option<String>,
+    /// The schema definition, e.g. the content of the `.proto` file for `PROTOBUF` schemas.
+    pub schema: String,
+}
+
+impl SubjectSchema {
+    pub fn schema_type(&self) -> &str {
+        self.schema_type.as_deref().unwrap_or(DEFAULT_SCHEMA_TYPE)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+struct ErrorMessage {
+    error_code: i32,
+    message: String,
+}
+
+#[cfg(test)]
+mod test {
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    async fn mock_server(web_path: &str, status: u16, body: &str) -> MockServer {
+        let mock_server = MockServer::start().await;
+        use wiremock::matchers::{method, path};
+
+        let response = ResponseTemplate::new(status)
+            .set_body_string(body)
+            .append_header("content-type", "application/vnd.schemaregistry.v1+json");
+
+        Mock::given(method("GET"))
+            .and(path(web_path))
+            .respond_with(response)
+            .mount(&mock_server)
+            .await;
+
+        mock_server
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_schema() {
+        let server = mock_server(
+            "/subjects/t-value/versions/latest",
+            200,
+            r#"{"subject":"t-value","version":2,"id":5,"schemaType":"PROTOBUF","schema":"syntax = \"proto3\";"}"#,
+        )
+        .await;
+
+        let client = SchemaRegistryClient::new(format!("{}/", server.uri()));
+        let schema = client
+            .get_latest_schema(&topic_value_subject("t"))
+            .await
+            .unwrap();
+
+        assert_eq!(schema.version, 2);
+        assert_eq!(schema.id, 5);
+        assert_eq!(schema.schema_type(), "PROTOBUF");
+        assert_eq!(schema.schema, "syntax = \"proto3\";");
+    }
+
+    #[tokio::test]
+    async fn test_subject_not_found() {
+        let server = mock_server(
+            "/subjects/t-value/versions/latest",
+            404,
+            r#"{"error_code":40401,"message":"Subject 't-value' not found."}"#,
+        )
+        .await;
+
+        let client = SchemaRegistryClient::new(server.uri());
+        let err = client.get_latest_schema("t-value").await.unwrap_err();
+
+        assert!(err.to_string().contains("Subject 't-value' not found."));
+    }
+}
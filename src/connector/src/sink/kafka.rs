@@ -0,0 +1,320 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::OwnedHeaders;
+use rdkafka::producer::{DeliveryFuture, FutureProducer, FutureRecord, Producer};
+use rdkafka::{ClientConfig, Message, Offset, TopicPartitionList};
+use risingwave_common::array::StreamChunk;
+use risingwave_common::catalog::Schema;
+use serde_json::{Map, Value};
+
+use crate::sink::{changelog_records, DeadLetter, SinkWriter};
+use crate::Properties;
+
+const KAFKA_SINK_BROKERS_KEY: &str = "kafka.brokers";
+const KAFKA_SINK_TOPIC_KEY: &str = "kafka.topic";
+const KAFKA_SINK_TRANSACTIONAL_ID_KEY: &str = "kafka.transactional.id";
+const KAFKA_SINK_MAX_MESSAGE_BYTES_KEY: &str = "kafka.max.message.bytes";
+const KAFKA_SINK_EPOCH_TOPIC_KEY: &str = "kafka.epoch.topic";
+
+/// The topic recording the last committed epoch of each writer, keyed by the transactional id. It
+/// should be a compacted topic, since only the latest record of each key is read.
+const KAFKA_DEFAULT_EPOCH_TOPIC: &str = "__risingwave_sink_epochs";
+
+/// Same as the default `message.max.bytes` of the brokers.
+const KAFKA_DEFAULT_MAX_MESSAGE_BYTES: &str = "1000000";
+
+/// Timeout of the blocking transaction calls.
+const KAFKA_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Name of the header carrying the epoch of a record.
+const KAFKA_EPOCH_HEADER: &str = "risingwave.epoch";
+
+/// Writes changelog records as JSON to a Kafka topic. The records of each epoch are written in a
+/// Kafka transaction, which is committed after the epoch is committed in the state store, so
+/// consumers with `read_committed` isolation see either all or none of an epoch, and never an epoch
+/// rolled back by recovery. The primary key of a row is used as the record
+/// key, so the changes of a row go to the same partition in order.
+///
+/// The epoch is also written to the first partition of `kafka.epoch.topic` in its transaction, so
+/// the last committed epoch is known after recovery, and the epochs up to it aren't written again.
+///
+/// Records larger than `kafka.max.message.bytes` are rejected by the producer, and become dead
+/// letters.
+pub struct KafkaSinkWriter {
+    producer: FutureProducer,
+    topic: String,
+    epoch_topic: String,
+    transactional_id: String,
+    schema: Schema,
+    pk_indices: Vec<usize>,
+
+    /// The epoch being written, `None` if no transaction is open.
+    epoch: Option<u64>,
+
+    /// Deliveries of the open transaction not yet acknowledged by the brokers.
+    pending: Vec<DeliveryFuture>,
+
+    /// The last epoch committed by the previous producers with the same transactional id.
+    committed_epoch: Option<u64>,
+}
+
+impl KafkaSinkWriter {
+    pub async fn new(
+        properties: Properties,
+        schema: Schema,
+        pk_indices: Vec<usize>,
+        identifier: String,
+    ) -> Result<Self> {
+        let brokers = properties.get_kafka(KAFKA_SINK_BROKERS_KEY)?;
+        let topic = properties.get_kafka(KAFKA_SINK_TOPIC_KEY)?;
+        // Each writer needs its own transactional id, otherwise writers fence each other.
+        let transactional_id = properties
+            .get(KAFKA_SINK_TRANSACTIONAL_ID_KEY)
+            .map(|prefix| format!("{}-{}", prefix, identifier))
+            .unwrap_or_else(|_| format!("risingwave-sink-{}-{}", topic, identifier));
+        let max_message_bytes = properties
+            .get(KAFKA_SINK_MAX_MESSAGE_BYTES_KEY)
+            .unwrap_or_else(|_| KAFKA_DEFAULT_MAX_MESSAGE_BYTES.to_string());
+        let epoch_topic = properties
+            .get(KAFKA_SINK_EPOCH_TOPIC_KEY)
+            .unwrap_or_else(|_| KAFKA_DEFAULT_EPOCH_TOPIC.to_string());
+
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", &brokers)
+            .set("transactional.id", &transactional_id)
+            .set("enable.idempotence", "true")
+            .set("message.max.bytes", max_message_bytes)
+            .create()
+            .map_err(|e| anyhow!("kafka producer creation failed: {}", e))?;
+
+        // Fences previous producers with the same transactional id, and aborts their ongoing
+        // transactions, e.g. the one of an epoch not committed before recovery.
+        let init_producer = producer.clone();
+        tokio::task::spawn_blocking(move || {
+            init_producer.init_transactions(KAFKA_TRANSACTION_TIMEOUT)
+        })
+        .await??;
+
+        // Read after the transactions are aborted, so only the committed epochs are seen.
+        let committed_epoch = {
+            let epoch_topic = epoch_topic.clone();
+            let transactional_id = transactional_id.clone();
+            tokio::task::spawn_blocking(move || {
+                read_committed_epoch(&brokers, &epoch_topic, &transactional_id)
+            })
+            .await??
+        };
+
+        Ok(Self {
+            producer,
+            topic,
+            epoch_topic,
+            transactional_id,
+            schema,
+            pk_indices,
+            epoch: None,
+            pending: vec![],
+            committed_epoch,
+        })
+    }
+
+    fn key(&self, row: &Map<String, Value>) -> Result<Option<String>> {
+        if self.pk_indices.is_empty() {
+            return Ok(None);
+        }
+        let key = self
+            .pk_indices
+            .iter()
+            .map(|idx| {
+                let name = &self.schema.fields()[*idx].name;
+                (name.clone(), row.get(name).cloned().unwrap_or(Value::Null))
+            })
+            .collect::<Map<_, _>>();
+        Ok(Some(serde_json::to_string(&key)?))
+    }
+
+    fn check_epoch(&self, epoch: u64) -> Result<()> {
+        match self.epoch {
+            Some(current) if current == epoch => Ok(()),
+            current => Err(anyhow!(
+                "epoch {} is not the open epoch {:?}",
+                epoch,
+                current
+            )),
+        }
+    }
+
+    async fn wait_pending(&mut self) -> Result<()> {
+        let results = try_join_all(std::mem::take(&mut self.pending))
+            .await
+            .map_err(|_| anyhow!("kafka delivery cancelled"))?;
+        for result in results {
+            result.map_err(|(e, _)| anyhow!("kafka delivery failed: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SinkWriter for KafkaSinkWriter {
+    async fn begin_epoch(&mut self, epoch: u64) -> Result<()> {
+        if let Some(current) = self.epoch {
+            return Err(anyhow!(
+                "epoch {} begins before epoch {} is committed",
+                epoch,
+                current
+            ));
+        }
+        self.producer.begin_transaction()?;
+        self.epoch = Some(epoch);
+        Ok(())
+    }
+
+    async fn write_chunk(&mut self, chunk: StreamChunk) -> Result<Vec<DeadLetter>> {
+        let epoch = self
+            .epoch
+            .ok_or_else(|| anyhow!("chunk written outside of an epoch"))?
+            .to_string();
+        let mut dead_letters = vec![];
+        for record in changelog_records(&chunk, &self.schema)? {
+            let row = record.after.as_ref().or(record.before.as_ref()).unwrap();
+            let key = self.key(row)?;
+            let payload = match serde_json::to_string(&record) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    dead_letters.push(DeadLetter {
+                        record: format!("{:?}", record),
+                        reason: format!("serialization failed: {}", e),
+                    });
+                    continue;
+                }
+            };
+            loop {
+                let mut kafka_record = FutureRecord::to(&self.topic)
+                    .payload(&payload)
+                    .headers(OwnedHeaders::new().add(KAFKA_EPOCH_HEADER, &epoch));
+                if let Some(key) = &key {
+                    kafka_record = kafka_record.key(key);
+                }
+                match self.producer.send_result(kafka_record) {
+                    Ok(delivery) => {
+                        self.pending.push(delivery);
+                        break;
+                    }
+                    // Wait for the queued records to be delivered, then retry.
+                    Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), _)) => {
+                        self.wait_pending().await?
+                    }
+                    Err((
+                        KafkaError::MessageProduction(RDKafkaErrorCode::MessageSizeTooLarge),
+                        _,
+                    )) => {
+                        dead_letters.push(DeadLetter {
+                            record: payload.clone(),
+                            reason: format!(
+                                "record of {} bytes exceeds {}",
+                                payload.len(),
+                                KAFKA_SINK_MAX_MESSAGE_BYTES_KEY
+                            ),
+                        });
+                        break;
+                    }
+                    Err((e, _)) => return Err(anyhow!("kafka send failed: {}", e)),
+                }
+            }
+        }
+        Ok(dead_letters)
+    }
+
+    async fn flush(&mut self, epoch: u64) -> Result<()> {
+        self.check_epoch(epoch)?;
+        // The transaction is left open on failure. It's aborted when the writer of the recovered
+        // actor fences this one.
+        self.wait_pending().await
+    }
+
+    async fn commit(&mut self, epoch: u64) -> Result<()> {
+        self.check_epoch(epoch)?;
+        // Recorded in the same transaction, so the epoch is committed iff its records are.
+        let payload = epoch.to_string();
+        let delivery = self
+            .producer
+            .send_result(
+                FutureRecord::to(&self.epoch_topic)
+                    .partition(0)
+                    .key(&self.transactional_id)
+                    .payload(&payload),
+            )
+            .map_err(|(e, _)| anyhow!("kafka send failed: {}", e))?;
+        self.pending.push(delivery);
+        self.wait_pending().await?;
+        self.epoch = None;
+        let producer = self.producer.clone();
+        tokio::task::spawn_blocking(move || producer.commit_transaction(KAFKA_TRANSACTION_TIMEOUT))
+            .await??;
+        self.committed_epoch = Some(epoch);
+        Ok(())
+    }
+
+    async fn last_committed_epoch(&mut self) -> Result<Option<u64>> {
+        Ok(self.committed_epoch)
+    }
+}
+
+/// Reads the last epoch recorded for `transactional_id` in the first partition of `epoch_topic`,
+/// skipping the aborted transactions.
+fn read_committed_epoch(
+    brokers: &str,
+    epoch_topic: &str,
+    transactional_id: &str,
+) -> Result<Option<u64>> {
+    let consumer: BaseConsumer = ClientConfig::new()
+        .set("bootstrap.servers", brokers)
+        .set("group.id", transactional_id)
+        .set("enable.auto.commit", "false")
+        .set("enable.partition.eof", "true")
+        .set("isolation.level", "read_committed")
+        .create()
+        .map_err(|e| anyhow!("kafka consumer creation failed: {}", e))?;
+    let mut partitions = TopicPartitionList::new();
+    partitions.add_partition_offset(epoch_topic, 0, Offset::Beginning)?;
+    consumer.assign(&partitions)?;
+
+    let mut committed_epoch = None;
+    loop {
+        match consumer.poll(KAFKA_TRANSACTION_TIMEOUT) {
+            Some(Ok(message)) => {
+                if message.key() != Some(transactional_id.as_bytes()) {
+                    continue;
+                }
+                let epoch = message
+                    .payload()
+                    .and_then(|payload| std::str::from_utf8(payload).ok())
+                    .and_then(|payload| payload.parse::<u64>().ok())
+                    .ok_or_else(|| anyhow!("invalid epoch record in {}", epoch_topic))?;
+                committed_epoch = Some(epoch);
+            }
+            Some(Err(KafkaError::PartitionEOF(_))) => return Ok(committed_epoch),
+            Some(Err(e)) => return Err(anyhow!("kafka read of {} failed: {}", epoch_topic, e)),
+            None => return Err(anyhow!("kafka read of {} timed out", epoch_topic)),
+        }
+    }
+}
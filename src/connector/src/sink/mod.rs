@@ -0,0 +1,281 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Sinks deliver the changes of a stream, e.g. of a materialized view, to external systems.
+//!
+//! A [`SinkWriter`] receives [`StreamChunk`]s between two barriers and makes them visible to
+//! consumers atomically on [`SinkWriter::commit`], which is called only after the epoch is
+//! committed in the state store. So an epoch failed before its commit is never visible, and is
+//! written again after recovery. An epoch committed to the external system but not yet known to be
+//! by the sink is skipped on recovery, according to [`SinkWriter::last_committed_epoch`].
+//!
+//! Rows that can never be delivered, e.g. those too large for the external system, are returned
+//! from [`SinkWriter::write_chunk`] as [`DeadLetter`]s. The sink executor keeps them in the
+//! dead-letter table of the sink instead of failing.
+
+pub mod kafka;
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use risingwave_common::array::{Op, StreamChunk};
+use risingwave_common::catalog::Schema;
+use risingwave_common::types::{Datum, ScalarImpl};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::sink::kafka::KafkaSinkWriter;
+use crate::Properties;
+
+const SINK_CONNECTOR_KEY: &str = "connector";
+const KAFKA_SINK: &str = "kafka";
+
+/// How long the rows in the dead-letter table of a sink are kept. They're kept forever if not set.
+const SINK_DEAD_LETTER_RETENTION_SECONDS_KEY: &str = "dead_letter.retention.seconds";
+
+#[async_trait]
+pub trait SinkWriter: Send {
+    /// Starts writing the changes of `epoch`.
+    async fn begin_epoch(&mut self, epoch: u64) -> Result<()>;
+
+    /// Writes the changes in `chunk`. They must not be visible to consumers before the epoch is
+    /// committed. The rows that can never be delivered are returned rather than failing the chunk.
+    async fn write_chunk(&mut self, chunk: StreamChunk) -> Result<Vec<DeadLetter>>;
+
+    /// Waits until all changes of `epoch` are written to the external system, without making them
+    /// visible. Called on the barrier ending the epoch, before the epoch is committed in the state
+    /// store, so that a failed write fails the epoch.
+    async fn flush(&mut self, epoch: u64) -> Result<()>;
+
+    /// Makes all changes of `epoch` visible to consumers atomically. Called after the epoch is
+    /// committed in the state store.
+    async fn commit(&mut self, epoch: u64) -> Result<()>;
+
+    /// Returns the last epoch committed to the external system, including those committed by the
+    /// previous writers with the same identifier, `None` if there's none.
+    async fn last_committed_epoch(&mut self) -> Result<Option<u64>>;
+}
+
+/// Creates the writer of the sink specified by the `connector` property. `identifier` must be
+/// unique among all writers of the sink, e.g. the id of the sink actor.
+pub async fn build_sink_writer(
+    properties: Properties,
+    schema: Schema,
+    pk_indices: Vec<usize>,
+    identifier: String,
+) -> Result<Box<dyn SinkWriter>> {
+    let connector = properties.get(SINK_CONNECTOR_KEY)?;
+    match connector.as_str() {
+        KAFKA_SINK => Ok(Box::new(
+            KafkaSinkWriter::new(properties, schema, pk_indices, identifier).await?,
+        )),
+        other => Err(anyhow!("sink connector {} is not supported", other)),
+    }
+}
+
+/// Returns the retention of the dead-letter table of the sink, `None` if the rows are kept forever.
+pub fn dead_letter_retention(properties: &Properties) -> Result<Option<Duration>> {
+    match properties.0.get(SINK_DEAD_LETTER_RETENTION_SECONDS_KEY) {
+        Some(seconds) => {
+            let seconds = seconds.parse::<u64>().map_err(|e| {
+                anyhow!("invalid {}: {}", SINK_DEAD_LETTER_RETENTION_SECONDS_KEY, e)
+            })?;
+            Ok(Some(Duration::from_secs(seconds)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// A row the sink permanently fails to deliver, e.g. it can't be serialized or is too large for
+/// the external system, so retrying won't help.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadLetter {
+    /// The change of the row, formatted as a [`ChangelogRecord`] in JSON if possible.
+    pub record: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangelogOp {
+    Insert,
+    Delete,
+    Update,
+}
+
+/// A change of a row, serialized as `{"op": .., "before": {..}, "after": {..}}`. `before` is absent
+/// for insertions, and `after` is absent for deletions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangelogRecord {
+    pub op: ChangelogOp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Map<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Map<String, Value>>,
+}
+
+/// Converts the visible rows of `chunk` to changelog records. An `UpdateDelete` immediately
+/// followed by an `UpdateInsert` becomes a single update.
+pub fn changelog_records(chunk: &StreamChunk, schema: &Schema) -> Result<Vec<ChangelogRecord>> {
+    let row_json = |idx: usize| -> Map<String, Value> {
+        schema
+            .fields()
+            .iter()
+            .zip(chunk.columns())
+            .map(|(field, column)| {
+                (
+                    field.name.clone(),
+                    datum_to_json(column.array_ref().datum_at(idx)),
+                )
+            })
+            .collect()
+    };
+    let visible = |idx: usize| {
+        chunk
+            .visibility()
+            .as_ref()
+            .map_or(true, |v| v.is_set(idx).unwrap())
+    };
+
+    let mut records = Vec::with_capacity(chunk.cardinality());
+    let mut update_before = None;
+    for (idx, op) in chunk.ops().iter().enumerate() {
+        if !visible(idx) {
+            continue;
+        }
+        match op {
+            Op::Insert => records.push(ChangelogRecord {
+                op: ChangelogOp::Insert,
+                before: None,
+                after: Some(row_json(idx)),
+            }),
+            Op::Delete => records.push(ChangelogRecord {
+                op: ChangelogOp::Delete,
+                before: Some(row_json(idx)),
+                after: None,
+            }),
+            Op::UpdateDelete => {
+                if update_before.replace(row_json(idx)).is_some() {
+                    return Err(anyhow!("UpdateDelete is not followed by UpdateInsert"));
+                }
+            }
+            Op::UpdateInsert => {
+                let before = update_before
+                    .take()
+                    .ok_or_else(|| anyhow!("UpdateInsert is not preceded by UpdateDelete"))?;
+                records.push(ChangelogRecord {
+                    op: ChangelogOp::Update,
+                    before: Some(before),
+                    after: Some(row_json(idx)),
+                });
+            }
+        }
+    }
+    if update_before.is_some() {
+        return Err(anyhow!("UpdateDelete is not followed by UpdateInsert"));
+    }
+    Ok(records)
+}
+
+/// Numbers and booleans are kept as JSON numbers and booleans, and other types are formatted as
+/// strings.
+fn datum_to_json(datum: Datum) -> Value {
+    match datum {
+        None => Value::Null,
+        Some(ScalarImpl::Int16(v)) => Value::from(v),
+        Some(ScalarImpl::Int32(v)) => Value::from(v),
+        Some(ScalarImpl::Int64(v)) => Value::from(v),
+        Some(ScalarImpl::Float32(v)) => Value::from(v.0),
+        Some(ScalarImpl::Float64(v)) => Value::from(v.0),
+        Some(ScalarImpl::Bool(v)) => Value::from(v),
+        Some(v) => Value::from(v.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{I32Array, Utf8Array};
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+    use risingwave_common::{column, column_nonnull};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_changelog_records() {
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int32, "id"),
+            Field::with_name(DataType::Varchar, "name"),
+        ]);
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::UpdateDelete, Op::UpdateInsert, Op::Delete],
+            vec![
+                column_nonnull! { I32Array, [1, 2, 2, 3] },
+                column! { Utf8Array, [Some("a"), Some("b"), None, Some("c")] },
+            ],
+            None,
+        );
+        let records = changelog_records(&chunk, &schema)
+            .unwrap()
+            .into_iter()
+            .map(|r| serde_json::to_value(r).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            vec![
+                json!({"op": "insert", "after": {"id": 1, "name": "a"}}),
+                json!({
+                    "op": "update",
+                    "before": {"id": 2, "name": "b"},
+                    "after": {"id": 2, "name": null}
+                }),
+                json!({"op": "delete", "before": {"id": 3, "name": "c"}}),
+            ]
+        );
+
+        let chunk = StreamChunk::new(
+            vec![Op::UpdateDelete],
+            vec![
+                column_nonnull! { I32Array, [1] },
+                column_nonnull! { Utf8Array, ["a"] },
+            ],
+            None,
+        );
+        assert!(changelog_records(&chunk, &schema).is_err());
+    }
+
+    #[test]
+    fn test_dead_letter_retention() {
+        let properties = |retention: Option<&str>| {
+            Properties::new(
+                retention
+                    .map(|r| {
+                        (
+                            SINK_DEAD_LETTER_RETENTION_SECONDS_KEY.to_string(),
+                            r.to_string(),
+                        )
+                    })
+                    .into_iter()
+                    .collect(),
+            )
+        };
+        assert_eq!(dead_letter_retention(&properties(None)).unwrap(), None);
+        assert_eq!(
+            dead_letter_retention(&properties(Some("3600"))).unwrap(),
+            Some(Duration::from_secs(3600))
+        );
+        assert!(dead_letter_retention(&properties(Some("1h"))).is_err());
+    }
+}
@@ -13,3 +13,5 @@
 // limitations under the License.
 
 pub mod hummock;
+pub mod meta;
+pub mod source;
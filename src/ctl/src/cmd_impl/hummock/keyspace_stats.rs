@@ -0,0 +1,34 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::MetaServiceOpts;
+
+pub async fn keyspace_stats() -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let keyspace_stats = meta_client.get_keyspace_stats().await?;
+    println!(
+        "{:<24} {:>12} {:>16}",
+        "keyspace", "key count", "total size"
+    );
+    for stats in keyspace_stats {
+        println!(
+            "{:<24} {:>12} {:>16}",
+            format!("{:?}", bytes::Bytes::from(stats.prefix)),
+            stats.key_count,
+            stats.total_size
+        );
+    }
+    Ok(())
+}
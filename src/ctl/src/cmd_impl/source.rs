@@ -0,0 +1,38 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::data::SourceThrottle;
+
+use crate::common::MetaServiceOpts;
+
+/// Limit the rate of a source. Zero means unlimited.
+pub async fn throttle(
+    source_id: u32,
+    rows_per_second: u64,
+    bytes_per_second: u64,
+) -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    meta_client
+        .throttle_source(
+            source_id,
+            SourceThrottle {
+                rows_per_second,
+                bytes_per_second,
+            },
+        )
+        .await?;
+    println!("source {} throttled", source_id);
+    Ok(())
+}
@@ -31,6 +31,12 @@ enum Commands {
     /// Commands for Hummock
     #[clap(subcommand)]
     Hummock(HummockCommands),
+    /// Commands for Sources
+    #[clap(subcommand)]
+    Source(SourceCommands),
+    /// Commands for Meta
+    #[clap(subcommand)]
+    Meta(MetaCommands),
 }
 
 #[derive(Subcommand)]
@@ -39,6 +45,26 @@ enum HummockCommands {
     ListVersion,
     /// list all Hummock key-value pairs
     ListKv,
+    /// print the key count and size of each keyspace
+    KeyspaceStats,
+}
+
+#[derive(Subcommand)]
+enum SourceCommands {
+    /// limit the rate of a source without restarting it, 0 for unlimited
+    Throttle {
+        source_id: u32,
+        #[clap(long, default_value = "0")]
+        rows_per_second: u64,
+        #[clap(long, default_value = "0")]
+        bytes_per_second: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetaCommands {
+    /// inject a checkpoint barrier immediately, and wait for it to be collected
+    TriggerCheckpoint,
 }
 
 pub async fn start(opts: CliOpts) {
@@ -47,5 +73,18 @@ pub async fn start(opts: CliOpts) {
             cmd_impl::hummock::list_version().await.unwrap()
         }
         Commands::Hummock(HummockCommands::ListKv) => cmd_impl::hummock::list_kv().await.unwrap(),
+        Commands::Hummock(HummockCommands::KeyspaceStats) => {
+            cmd_impl::hummock::keyspace_stats().await.unwrap()
+        }
+        Commands::Source(SourceCommands::Throttle {
+            source_id,
+            rows_per_second,
+            bytes_per_second,
+        }) => cmd_impl::source::throttle(*source_id, *rows_per_second, *bytes_per_second)
+            .await
+            .unwrap(),
+        Commands::Meta(MetaCommands::TriggerCheckpoint) => {
+            cmd_impl::meta::trigger_checkpoint().await.unwrap()
+        }
     }
 }
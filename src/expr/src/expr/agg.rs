@@ -28,6 +28,11 @@ pub enum AggKind {
     Avg,
     StringAgg,
     SingleValue,
+    First,
+    Last,
+    TimeWeightedAvg,
+    BitAnd,
+    BitOr,
 }
 
 impl std::fmt::Display for AggKind {
@@ -41,6 +46,11 @@ impl std::fmt::Display for AggKind {
             AggKind::Avg => write!(f, "avg"),
             AggKind::StringAgg => write!(f, "string_agg"),
             AggKind::SingleValue => write!(f, "single_value"),
+            AggKind::First => write!(f, "first"),
+            AggKind::Last => write!(f, "last"),
+            AggKind::TimeWeightedAvg => write!(f, "time_weighted_avg"),
+            AggKind::BitAnd => write!(f, "bit_and"),
+            AggKind::BitOr => write!(f, "bit_or"),
         }
     }
 }
@@ -57,6 +67,11 @@ impl TryFrom<Type> for AggKind {
             Type::Count => Ok(AggKind::Count),
             Type::StringAgg => Ok(AggKind::StringAgg),
             Type::SingleValue => Ok(AggKind::SingleValue),
+            Type::First => Ok(AggKind::First),
+            Type::Last => Ok(AggKind::Last),
+            Type::TimeWeightedAvg => Ok(AggKind::TimeWeightedAvg),
+            Type::BitAnd => Ok(AggKind::BitAnd),
+            Type::BitOr => Ok(AggKind::BitOr),
             _ => Err(ErrorCode::InternalError("Unrecognized agg.".into()).into()),
         }
     }
@@ -72,6 +87,11 @@ impl AggKind {
             Self::Count => Type::Count,
             Self::StringAgg => Type::StringAgg,
             Self::SingleValue => Type::SingleValue,
+            Self::First => Type::First,
+            Self::Last => Type::Last,
+            Self::TimeWeightedAvg => Type::TimeWeightedAvg,
+            Self::BitAnd => Type::BitAnd,
+            Self::BitOr => Type::BitOr,
             Self::RowCount => {
                 panic!("cannot convert RowCount to prost, TODO: remove RowCount from AggKind")
             }
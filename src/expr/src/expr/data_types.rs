@@ -143,6 +143,30 @@ macro_rules! varchar {
 
 pub(crate) use varchar;
 
+#[macro_export]
+macro_rules! bytea {
+    ($macro:ident) => {
+        $macro! {
+            risingwave_common::types::DataType::Bytea,
+            risingwave_common::array::BytesArray
+        }
+    };
+}
+
+pub(crate) use bytea;
+
+#[macro_export]
+macro_rules! varbit {
+    ($macro:ident) => {
+        $macro! {
+            risingwave_common::types::DataType::Varbit,
+            risingwave_common::array::BitStringArray
+        }
+    };
+}
+
+pub(crate) use varbit;
+
 #[macro_export]
 macro_rules! time {
     ($macro:ident) => {
@@ -13,8 +13,8 @@
 // limitations under the License.
 
 use risingwave_common::array::{
-    Array, BoolArray, DecimalArray, I32Array, IntervalArray, NaiveDateArray, NaiveDateTimeArray,
-    Utf8Array,
+    Array, BitStringArray, BoolArray, BytesArray, DecimalArray, I32Array, IntervalArray,
+    NaiveDateArray, NaiveDateTimeArray, Utf8Array,
 };
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
@@ -24,7 +24,9 @@ use risingwave_pb::expr::expr_node::Type;
 use crate::expr::template::BinaryExpression;
 use crate::expr::BoxedExpression;
 use crate::vector_op::arithmetic_op::*;
+use crate::vector_op::bitwise_op::*;
 use crate::vector_op::cmp::*;
+use crate::vector_op::encode::{decode, encode};
 use crate::vector_op::extract::{extract_from_date, extract_from_timestamp};
 use crate::vector_op::like::like_default;
 use crate::vector_op::position::position;
@@ -254,6 +256,62 @@ macro_rules! gen_binary_expr_atm {
     };
 }
 
+/// Based on the data type of `$l` and `$r`, return the bitwise expression on integers with
+/// `$general_f`, or on bit strings with `$bit_string_f`.
+macro_rules! gen_binary_expr_bitwise {
+    ($general_f:ident, $bit_string_f:ident, $l:expr, $r:expr, $ret:expr) => {
+        match ($l.return_type(), $r.return_type()) {
+            (DataType::Varbit, DataType::Varbit) => {
+                Box::new(BinaryExpression::<
+                    BitStringArray,
+                    BitStringArray,
+                    BitStringArray,
+                    _,
+                >::new($l, $r, $ret, $bit_string_f))
+            }
+            _ => {
+                gen_atm_impl! {
+                    [$l, $r, $ret],
+                    { int16, int16, int16, $general_f },
+                    { int16, int32, int32, $general_f },
+                    { int16, int64, int64, $general_f },
+                    { int32, int16, int32, $general_f },
+                    { int32, int32, int32, $general_f },
+                    { int32, int64, int64, $general_f },
+                    { int64, int16, int64, $general_f },
+                    { int64, int32, int64, $general_f },
+                    { int64, int64, int64, $general_f },
+                }
+            }
+        }
+    };
+}
+
+/// Similar to `gen_binary_expr_bitwise`, but the shift amount `$r` is always `int4`, and the result
+/// has the type of `$l`.
+macro_rules! gen_binary_expr_shift {
+    ($general_f:ident, $bit_string_f:ident, $l:expr, $r:expr, $ret:expr) => {
+        match ($l.return_type(), $r.return_type()) {
+            (DataType::Varbit, DataType::Int32) => {
+                Box::new(BinaryExpression::<
+                    BitStringArray,
+                    I32Array,
+                    BitStringArray,
+                    _,
+                >::new($l, $r, $ret, $bit_string_f))
+            }
+            _ => {
+                gen_atm_impl! {
+                    [$l, $r, $ret],
+                    { int16, int32, int16, $general_f },
+                    { int32, int32, int32, $general_f },
+                    { int64, int32, int64, $general_f },
+                }
+            }
+        }
+    };
+}
+
 fn build_extract_expr(ret: DataType, l: BoxedExpression, r: BoxedExpression) -> BoxedExpression {
     match r.return_type() {
         DataType::Date => Box::new(
@@ -360,6 +418,21 @@ pub fn new_binary_expr(
                 },
             }
         }
+        Type::BitwiseAnd => {
+            gen_binary_expr_bitwise! {general_bitand, bit_string_bitand, l, r, ret}
+        }
+        Type::BitwiseOr => {
+            gen_binary_expr_bitwise! {general_bitor, bit_string_bitor, l, r, ret}
+        }
+        Type::BitwiseXor => {
+            gen_binary_expr_bitwise! {general_bitxor, bit_string_bitxor, l, r, ret}
+        }
+        Type::BitwiseShiftLeft => {
+            gen_binary_expr_shift! {general_shl, bit_string_shl, l, r, ret}
+        }
+        Type::BitwiseShiftRight => {
+            gen_binary_expr_shift! {general_shr, bit_string_shr, l, r, ret}
+        }
         Type::Extract => build_extract_expr(ret, l, r),
         Type::RoundDigit => Box::new(
             BinaryExpression::<DecimalArray, I32Array, DecimalArray, _>::new(
@@ -372,6 +445,12 @@ pub fn new_binary_expr(
         Type::Position => Box::new(BinaryExpression::<Utf8Array, Utf8Array, I32Array, _>::new(
             l, r, ret, position,
         )),
+        Type::Encode => Box::new(
+            BinaryExpression::<BytesArray, Utf8Array, Utf8Array, _>::new(l, r, ret, encode),
+        ),
+        Type::Decode => Box::new(
+            BinaryExpression::<Utf8Array, Utf8Array, BytesArray, _>::new(l, r, ret, decode),
+        ),
         Type::TumbleStart => new_tumble_start(l, r, ret),
         tp => {
             unimplemented!(
@@ -53,6 +53,10 @@ impl Expression for InputRefExpression {
             None => Ok(array),
         }
     }
+
+    fn input_ref_index(&self) -> Option<usize> {
+        Some(self.idx)
+    }
 }
 
 impl InputRefExpression {
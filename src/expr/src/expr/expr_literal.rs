@@ -23,7 +23,9 @@ use risingwave_common::array::{
 };
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{ErrorCode, Result, RwError};
-use risingwave_common::types::{DataType, Datum, Decimal, IntervalUnit, Scalar, ScalarImpl};
+use risingwave_common::types::{
+    DataType, Datum, Decimal, IntervalUnit, NaiveDateTimeWrapper, Scalar, ScalarImpl,
+};
 use risingwave_common::{ensure, for_all_variants};
 use risingwave_pb::data::data_type::IntervalType::*;
 use risingwave_pb::data::data_type::{IntervalType, TypeName};
@@ -98,6 +100,8 @@ fn literal_type_match(return_type: &DataType, literal: Option<&ScalarImpl>) -> b
                     | (DataType::Float64, ScalarImpl::Float64(_))
                     | (DataType::Date, ScalarImpl::Int32(_))
                     | (DataType::Varchar, ScalarImpl::Utf8(_))
+                    | (DataType::Bytea, ScalarImpl::Bytea(_))
+                    | (DataType::Varbit, ScalarImpl::BitString(_))
                     | (DataType::Date, ScalarImpl::NaiveDate(_))
                     | (DataType::Time, ScalarImpl::NaiveTime(_))
                     | (DataType::Timestamp, ScalarImpl::NaiveDateTime(_))
@@ -214,12 +218,33 @@ impl<'a> TryFrom<&'a ExprNode> for LiteralExpression {
                         })?
                         .to_string(),
                 ),
+                TypeName::Bytea => {
+                    ScalarImpl::Bytea(prost_value.get_body().clone().into_boxed_slice())
+                }
+                // Bit strings are serialized as their text representation, e.g. `10110`.
+                TypeName::Varbit => ScalarImpl::BitString(
+                    std::str::from_utf8(prost_value.get_body())
+                        .map_err(|e| {
+                            InternalError(format!("Failed to deserialize varbit, reason: {:?}", e))
+                        })?
+                        .parse()?,
+                ),
                 TypeName::Decimal => ScalarImpl::Decimal(
                     Decimal::from_str(std::str::from_utf8(prost_value.get_body()).unwrap())
                         .map_err(|e| {
                             InternalError(format!("Failed to deserialize decimal, reason: {:?}", e))
                         })?,
                 ),
+                TypeName::Timestamp => ScalarImpl::NaiveDateTime(
+                    NaiveDateTimeWrapper::from_protobuf(i64::from_be_bytes(
+                        prost_value.get_body().as_slice().try_into().map_err(|e| {
+                            InternalError(format!(
+                                "Failed to deserialize timestamp, reason: {:?}",
+                                e
+                            ))
+                        })?,
+                    ))?,
+                ),
                 TypeName::Interval => {
                     let bytes = prost_value.get_body();
                     ScalarImpl::Interval(make_interval(
@@ -26,6 +26,7 @@ use crate::expr::template::UnaryNullableExpression;
 use crate::expr::BoxedExpression;
 use crate::vector_op::arithmetic_op::general_neg;
 use crate::vector_op::ascii::ascii;
+use crate::vector_op::bitwise_op::{bit_string_bitnot, general_bitnot};
 use crate::vector_op::cast::*;
 use crate::vector_op::cmp::{is_false, is_not_false, is_not_true, is_true};
 use crate::vector_op::conjunction;
@@ -100,8 +101,13 @@ macro_rules! gen_cast {
             { varchar, float64, str_parse },
             { varchar, decimal, str_parse },
             { varchar, boolean, str_to_bool },
+            { varchar, bytea, str_to_bytea },
+            { varchar, varbit, str_to_bit_string },
+            { varbit, varbit, |x| Ok(x.to_owned_scalar()) },
 
             { boolean, varchar, bool_to_str },
+            { bytea, varchar, bytea_to_str },
+            { varbit, varchar, bit_string_to_str },
 
             { int16, int32, general_cast },
             { int16, int64, general_cast },
@@ -184,6 +190,38 @@ macro_rules! gen_neg {
     };
 }
 
+/// This macro helps to create bitwise not expression on integers. Bit strings are handled
+/// separately, as their owned type is different from the reference one.
+macro_rules! gen_bitnot_impl {
+    ($child:expr, $ret:expr, $($input:ident),*) => {
+        match $child.return_type() {
+            $(
+                $input! {type_match_pattern} => Box::new(
+                    UnaryExpression::<$input! {type_array}, $input! {type_array}, _>::new(
+                        $child,
+                        $ret.clone(),
+                        general_bitnot,
+                    )
+                ),
+            )*
+            DataType::Varbit => Box::new(
+                UnaryExpression::<BitStringArray, BitStringArray, _>::new(
+                    $child,
+                    $ret.clone(),
+                    bit_string_bitnot,
+                )
+            ),
+            _ => {
+                return Err(ErrorCode::NotImplemented(format!(
+                    "BitwiseNot is not supported on {:?}",
+                    $child.return_type()
+                ), 112.into())
+                .into());
+            }
+        }
+    };
+}
+
 pub fn new_unary_expr(
     expr_type: ProstType,
     return_type: DataType,
@@ -248,6 +286,9 @@ pub fn new_unary_expr(
         (ProstType::Neg, _, _) => {
             gen_neg! { child_expr, return_type }
         }
+        (ProstType::BitwiseNot, _, _) => {
+            gen_bitnot_impl! { child_expr, return_type, int16, int32, int64 }
+        }
         (ProstType::PgSleep, _, DataType::Decimal) => Box::new(PgSleepExpression::new(child_expr)),
 
         (expr, ret, child) => {
@@ -0,0 +1,131 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+use risingwave_common::array::{
+    ArrayBuilder, ArrayImpl, ArrayRef, DataChunk, I64ArrayBuilder, NaiveDateTimeArrayBuilder,
+};
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, NaiveDateTimeWrapper};
+
+use crate::expr::Expression;
+
+/// `MONOTONIC_ID()` generates an increasing id for each visible row, starting from 0. The ids are
+/// only increasing within the expression instance, i.e. each parallel actor generates its own ids.
+///
+/// The next id is the state of the expression. It's checkpointed by the streaming `Project`, so
+/// that the ids keep increasing after recovery.
+#[derive(Debug, Default)]
+pub struct MonotonicIdExpression {
+    next_id: AtomicI64,
+}
+
+impl MonotonicIdExpression {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Expression for MonotonicIdExpression {
+    fn return_type(&self) -> DataType {
+        DataType::Int64
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let mut builder = I64ArrayBuilder::new(input.capacity())?;
+        let mut next_id = self.next_id.load(Ordering::Relaxed);
+        for i in 0..input.capacity() {
+            let vis = match input.visibility() {
+                Some(bitmap) => bitmap.is_set(i)?,
+                None => true,
+            };
+            if vis {
+                builder.append(Some(next_id))?;
+                next_id += 1;
+            } else {
+                builder.append(None)?;
+            }
+        }
+        self.next_id.store(next_id, Ordering::Relaxed);
+        Ok(Arc::new(ArrayImpl::from(builder.finish()?)))
+    }
+
+    fn state(&self) -> Option<Vec<u8>> {
+        Some(self.next_id.load(Ordering::Relaxed).to_be_bytes().to_vec())
+    }
+
+    fn restore_state(&self, state: &[u8]) -> Result<()> {
+        let next_id = state
+            .try_into()
+            .map_err(|_| InternalError(format!("invalid state of monotonic_id: {:?}", state)))?;
+        self.next_id
+            .store(i64::from_be_bytes(next_id), Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// `PROCTIME()` returns the wall-clock time when the chunk is evaluated. Unlike `NOW()`, it differs
+/// between the chunks of a statement.
+#[derive(Debug, Default)]
+pub struct ProctimeExpression;
+
+impl Expression for ProctimeExpression {
+    fn return_type(&self) -> DataType {
+        DataType::Timestamp
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let now = NaiveDateTimeWrapper::new(chrono::Utc::now().naive_utc());
+        let mut builder = NaiveDateTimeArrayBuilder::new(input.capacity())?;
+        for _ in 0..input.capacity() {
+            builder.append(Some(now))?;
+        }
+        Ok(Arc::new(ArrayImpl::from(builder.finish()?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{Array, I32Array};
+    use risingwave_common::column_nonnull;
+
+    use super::*;
+
+    #[test]
+    fn test_monotonic_id() {
+        let chunk = DataChunk::builder()
+            .columns(vec![column_nonnull! { I32Array, [1, 2, 3] }])
+            .visibility(vec![true, false, true].try_into().unwrap())
+            .build();
+        let expr = MonotonicIdExpression::new();
+        let ids = expr.eval(&chunk).unwrap();
+        assert_eq!(
+            ids.as_int64().iter().collect::<Vec<_>>(),
+            vec![Some(0), None, Some(1)]
+        );
+
+        // A restored expression continues from the checkpointed id.
+        let restored = MonotonicIdExpression::new();
+        restored.restore_state(&expr.state().unwrap()).unwrap();
+        let ids = restored.eval(&chunk).unwrap();
+        assert_eq!(
+            ids.as_int64().iter().collect::<Vec<_>>(),
+            vec![Some(2), None, Some(3)]
+        );
+        assert!(restored.restore_state(b"bad").is_err());
+    }
+}
@@ -25,6 +25,7 @@ mod expr_is_null;
 mod expr_literal;
 mod expr_ternary_bytes;
 pub mod expr_unary;
+mod expr_volatile;
 mod pg_sleep;
 mod template;
 
@@ -35,6 +36,7 @@ use std::sync::Arc;
 pub use agg::AggKind;
 pub use expr_input_ref::InputRefExpression;
 pub use expr_literal::*;
+pub use expr_volatile::{MonotonicIdExpression, ProctimeExpression};
 use risingwave_common::array::{ArrayRef, DataChunk, Row};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
@@ -56,6 +58,22 @@ pub trait Expression: std::fmt::Debug + Sync + Send {
     /// * `input` - input data of the Project Executor
     fn eval(&self, input: &DataChunk) -> Result<ArrayRef>;
 
+    /// The index of the input column if the expression is a plain input reference.
+    fn input_ref_index(&self) -> Option<usize> {
+        None
+    }
+
+    /// The state of a stateful expression, e.g. the next id of `monotonic_id()`, which is
+    /// checkpointed by the executor. Returns `None` if the expression is stateless.
+    fn state(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Restores the state returned by [`Expression::state`].
+    fn restore_state(&self, _state: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
     fn boxed(self) -> BoxedExpression
     where
         Self: Sized + Send + 'static,
@@ -71,12 +89,17 @@ pub fn build_from_prost(prost: &ExprNode) -> Result<BoxedExpression> {
 
     match prost.get_expr_type()? {
         Cast | Upper | Lower | Not | PgSleep | IsTrue | IsNotTrue | IsFalse | IsNotFalse
-        | IsNull | IsNotNull | Neg | Ascii => build_unary_expr_prost(prost),
+        | IsNull | IsNotNull | Neg | Ascii | BitwiseNot => build_unary_expr_prost(prost),
         Equal | NotEqual | LessThan | LessThanOrEqual | GreaterThan | GreaterThanOrEqual => {
             build_binary_expr_prost(prost)
         }
         Add | Subtract | Multiply | Divide | Modulus => build_binary_expr_prost(prost),
-        Extract | RoundDigit | TumbleStart | Position => build_binary_expr_prost(prost),
+        BitwiseAnd | BitwiseOr | BitwiseXor | BitwiseShiftLeft | BitwiseShiftRight => {
+            build_binary_expr_prost(prost)
+        }
+        Extract | RoundDigit | TumbleStart | Position | Encode | Decode => {
+            build_binary_expr_prost(prost)
+        }
         StreamNullByRowCount | And | Or => build_nullable_binary_expr_prost(prost),
         Substr => build_substr_expr(prost),
         Length => build_length_expr(prost),
@@ -90,6 +113,8 @@ pub fn build_from_prost(prost: &ExprNode) -> Result<BoxedExpression> {
         Case => build_case_expr(prost),
         Translate => build_translate_expr(prost),
         In => build_in_expr(prost),
+        MonotonicId => Ok(MonotonicIdExpression::new().boxed()),
+        Proctime => Ok(ProctimeExpression.boxed()),
         _ => Err(InternalError(format!(
             "Unsupported expression type: {:?}",
             prost.get_expr_type()
@@ -0,0 +1,197 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Table functions, which return a set of rows for each input row, e.g. `unnest`. They are only
+//! evaluated in the select list of `ProjectSet`.
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{ArrayBuilder, ArrayImpl, ArrayRef, DataChunk, I64ArrayBuilder};
+use risingwave_common::ensure;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, ScalarRefImpl};
+use risingwave_pb::expr::expr_node::{RexNode, Type};
+use risingwave_pb::expr::ExprNode;
+
+use crate::expr::{build_from_prost, BoxedExpression};
+
+/// An item in the select list of `ProjectSet`.
+#[derive(Debug)]
+pub enum ProjectSetSelectItem {
+    /// A scalar expression, whose value is repeated in all the rows expanded from an input row.
+    Expr(BoxedExpression),
+    /// `unnest(list)`, which expands a list into rows of its elements.
+    Unnest(BoxedExpression),
+}
+
+impl ProjectSetSelectItem {
+    pub fn from_prost(prost: &ExprNode) -> Result<Self> {
+        match prost.get_expr_type()? {
+            Type::Unnest => {
+                let children = match prost.get_rex_node()? {
+                    RexNode::FuncCall(func_call) => func_call.get_children(),
+                    _ => return Err(InternalError("expects a function call".into()).into()),
+                };
+                ensure!(children.len() == 1);
+                let list = build_from_prost(&children[0])?;
+                ensure!(matches!(list.return_type(), DataType::List { .. }));
+                Ok(Self::Unnest(list))
+            }
+            _ => Ok(Self::Expr(build_from_prost(prost)?)),
+        }
+    }
+
+    pub fn return_type(&self) -> DataType {
+        match self {
+            Self::Expr(expr) => expr.return_type(),
+            Self::Unnest(list) => match list.return_type() {
+                DataType::List { datatype } => *datatype,
+                _ => unreachable!(),
+            },
+        }
+    }
+}
+
+/// Evaluates the select list of `ProjectSet` on the compacted `chunk`.
+///
+/// Each input row is expanded to as many rows as the longest result of the table functions on it,
+/// and the shorter results are padded with nulls, just like PostgreSQL. Returns the output columns
+/// prepended with `projected_row_id`, which is the index of each output row among the rows expanded
+/// from the same input row, together with the index of the input row of each output row.
+pub fn eval_project_set(
+    select_list: &[ProjectSetSelectItem],
+    chunk: &DataChunk,
+) -> Result<(Vec<Column>, Vec<usize>)> {
+    ensure!(chunk.visibility().is_none());
+    let arrays = select_list
+        .iter()
+        .map(|item| match item {
+            ProjectSetSelectItem::Expr(expr) | ProjectSetSelectItem::Unnest(expr) => {
+                expr.eval(chunk)
+            }
+        })
+        .collect::<Result<Vec<ArrayRef>>>()?;
+
+    let mut input_row_indices = vec![];
+    let mut projected_row_id_builder = I64ArrayBuilder::new(chunk.cardinality())?;
+    let mut builders = select_list
+        .iter()
+        .map(|item| item.return_type().create_array_builder(chunk.cardinality()))
+        .collect::<Result<Vec<_>>>()?;
+
+    for row_idx in 0..chunk.cardinality() {
+        let values = select_list
+            .iter()
+            .zip_eq(&arrays)
+            .map(|(item, array)| match item {
+                ProjectSetSelectItem::Expr(_) => vec![array.value_at(row_idx)],
+                ProjectSetSelectItem::Unnest(_) => match array.value_at(row_idx) {
+                    Some(ScalarRefImpl::List(list)) => list.values_ref(),
+                    _ => vec![],
+                },
+            })
+            .collect::<Vec<_>>();
+        let row_count = select_list
+            .iter()
+            .zip_eq(&values)
+            .filter(|(item, _)| matches!(item, ProjectSetSelectItem::Unnest(_)))
+            .map(|(_, values)| values.len())
+            .max()
+            .unwrap_or(1);
+
+        for projected_row_id in 0..row_count {
+            input_row_indices.push(row_idx);
+            projected_row_id_builder.append(Some(projected_row_id as i64))?;
+            for ((item, values), builder) in
+                select_list.iter().zip_eq(&values).zip_eq(&mut builders)
+            {
+                let datum = match item {
+                    ProjectSetSelectItem::Expr(_) => values[0],
+                    ProjectSetSelectItem::Unnest(_) => {
+                        values.get(projected_row_id).copied().flatten()
+                    }
+                };
+                builder.append_datum_ref(datum)?;
+            }
+        }
+    }
+
+    let mut columns = vec![Column::new(Arc::new(ArrayImpl::from(
+        projected_row_id_builder.finish()?,
+    )))];
+    for builder in builders {
+        columns.push(Column::new(Arc::new(builder.finish()?)));
+    }
+    Ok((columns, input_row_indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{I32Array, ListValue};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::ScalarImpl;
+
+    use super::*;
+    use crate::expr::{Expression, InputRefExpression};
+
+    #[test]
+    fn test_eval_project_set() {
+        let list_type = DataType::List {
+            datatype: Box::new(DataType::Int32),
+        };
+        let mut list_builder = list_type.create_array_builder(3).unwrap();
+        for list in [Some(vec![1, 2]), None, Some(vec![3])] {
+            let list = list.map(|values| {
+                ScalarImpl::List(ListValue::new(
+                    values.into_iter().map(|v| Some(v.into())).collect(),
+                ))
+            });
+            list_builder.append_datum(&list).unwrap();
+        }
+        let chunk = DataChunk::builder()
+            .columns(vec![
+                column_nonnull! { I32Array, [10, 20, 30] },
+                Column::new(Arc::new(list_builder.finish().unwrap())),
+            ])
+            .build();
+
+        let select_list = vec![
+            ProjectSetSelectItem::Expr(InputRefExpression::new(DataType::Int32, 0).boxed()),
+            ProjectSetSelectItem::Unnest(InputRefExpression::new(list_type, 1).boxed()),
+        ];
+        let (columns, input_row_indices) = eval_project_set(&select_list, &chunk).unwrap();
+
+        assert_eq!(input_row_indices, vec![0, 0, 2]);
+        let values = |column: &Column| {
+            (0..column.array_ref().len())
+                .map(|i| column.array_ref().datum_at(i))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            values(&columns[0]),
+            vec![Some(0i64.into()), Some(1i64.into()), Some(0i64.into())]
+        );
+        assert_eq!(
+            values(&columns[1]),
+            vec![Some(10.into()), Some(10.into()), Some(30.into())]
+        );
+        assert_eq!(
+            values(&columns[2]),
+            vec![Some(1.into()), Some(2.into()), Some(3.into())]
+        );
+    }
+}
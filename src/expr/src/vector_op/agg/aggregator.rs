@@ -18,11 +18,13 @@ use risingwave_common::types::*;
 use risingwave_pb::expr::AggCall;
 
 use crate::expr::AggKind;
+use crate::vector_op::agg::bitwise::{is_bitwise_agg, BitwiseAgg};
 use crate::vector_op::agg::count_star::CountStar;
 use crate::vector_op::agg::functions::*;
 use crate::vector_op::agg::general_agg::*;
 use crate::vector_op::agg::general_distinct_agg::*;
 use crate::vector_op::agg::general_sorted_grouper::EqGroups;
+use crate::vector_op::agg::time_series::{is_time_series_agg, TimeSeriesAgg};
 
 /// An `Aggregator` supports `update` data and `output` result.
 pub trait Aggregator: Send + 'static {
@@ -60,6 +62,9 @@ pub struct AggStateFactory {
     // When agg func is count(*), the args is empty and input type is None.
     input_type: Option<DataType>,
     input_col_idx: usize,
+    // The column of the time argument, only for time-series aggregations such as `first(value,
+    // time)`.
+    time_col_idx: Option<usize>,
     agg_kind: AggKind,
     return_type: DataType,
     distinct: bool,
@@ -77,6 +82,7 @@ impl AggStateFactory {
                 Ok(Self {
                     input_type: Some(input_type),
                     input_col_idx,
+                    time_col_idx: None,
                     agg_kind,
                     return_type,
                     distinct,
@@ -86,6 +92,7 @@ impl AggStateFactory {
                 (AggKind::Count, DataType::Int64) => Ok(Self {
                     input_type: None,
                     input_col_idx: 0,
+                    time_col_idx: None,
                     agg_kind,
                     return_type,
                     distinct,
@@ -96,6 +103,14 @@ impl AggStateFactory {
                 ))
                 .into()),
             },
+            [ref value, ref time] if is_time_series_agg(&agg_kind) => Ok(Self {
+                input_type: Some(DataType::from(value.get_type()?)),
+                input_col_idx: value.get_input()?.get_column_idx() as usize,
+                time_col_idx: Some(time.get_input()?.get_column_idx() as usize),
+                agg_kind,
+                return_type,
+                distinct,
+            }),
             _ => Err(
                 ErrorCode::InternalError("Agg with more than 1 input not supported.".into()).into(),
             ),
@@ -103,7 +118,14 @@ impl AggStateFactory {
     }
 
     pub fn create_agg_state(&self) -> Result<Box<dyn Aggregator>> {
-        if let Some(input_type) = self.input_type.clone() {
+        if let Some(time_col_idx) = self.time_col_idx {
+            Ok(Box::new(TimeSeriesAgg::new(
+                self.agg_kind.clone(),
+                self.return_type.clone(),
+                self.input_col_idx,
+                time_col_idx,
+            )))
+        } else if let Some(input_type) = self.input_type.clone() {
             create_agg_state_unary(
                 input_type,
                 self.input_col_idx,
@@ -130,6 +152,20 @@ pub fn create_agg_state_unary(
 ) -> Result<Box<dyn Aggregator>> {
     use crate::expr::data_types::*;
 
+    // `DISTINCT` makes no difference to bitwise aggregations.
+    if is_bitwise_agg(agg_type) {
+        return match input_type {
+            DataType::Int16 | DataType::Int32 | DataType::Int64 | DataType::Varbit => Ok(Box::new(
+                BitwiseAgg::new(agg_type.clone(), return_type, input_col_idx),
+            )),
+            _ => Err(ErrorCode::InternalError(format!(
+                "unsupported aggregator: type={:?} input={:?}",
+                agg_type, input_type
+            ))
+            .into()),
+        };
+    }
+
     macro_rules! gen_arms {
         [$(($agg:ident, $fn:expr, $in:tt, $ret:tt)),* $(,)?] => {
             match (
@@ -0,0 +1,229 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bitwise aggregations `bit_and(value)` and `bit_or(value)` over integers and bit strings.
+
+use risingwave_common::array::*;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::*;
+
+use crate::expr::AggKind;
+use crate::vector_op::agg::aggregator::Aggregator;
+use crate::vector_op::agg::general_sorted_grouper::EqGroups;
+
+/// Whether `agg_kind` is a bitwise aggregation.
+pub fn is_bitwise_agg(agg_kind: &AggKind) -> bool {
+    matches!(agg_kind, AggKind::BitAnd | AggKind::BitOr)
+}
+
+/// Folds a non-null `value` into the current `result` of a bitwise aggregation. Bit strings of
+/// different lengths cannot be combined, like the binary operators.
+pub fn eval_bitwise_agg(
+    agg_kind: &AggKind,
+    result: Datum,
+    value: ScalarRefImpl<'_>,
+) -> Result<Datum> {
+    let result = match result {
+        Some(result) => result,
+        None => return Ok(Some(value.into_scalar_impl())),
+    };
+    let is_and = match agg_kind {
+        AggKind::BitAnd => true,
+        AggKind::BitOr => false,
+        _ => {
+            return Err(ErrorCode::InternalError(format!(
+                "{} is not a bitwise aggregation",
+                agg_kind
+            ))
+            .into())
+        }
+    };
+    let result = match (result, value) {
+        (ScalarImpl::Int16(r), ScalarRefImpl::Int16(v)) => {
+            ScalarImpl::Int16(if is_and { r & v } else { r | v })
+        }
+        (ScalarImpl::Int32(r), ScalarRefImpl::Int32(v)) => {
+            ScalarImpl::Int32(if is_and { r & v } else { r | v })
+        }
+        (ScalarImpl::Int64(r), ScalarRefImpl::Int64(v)) => {
+            ScalarImpl::Int64(if is_and { r & v } else { r | v })
+        }
+        (ScalarImpl::BitString(r), ScalarRefImpl::BitString(v)) => {
+            ScalarImpl::BitString(if is_and {
+                r.as_scalar_ref().bitand(v)?
+            } else {
+                r.as_scalar_ref().bitor(v)?
+            })
+        }
+        (r, v) => {
+            return Err(ErrorCode::InternalError(format!(
+                "unsupported input of {}: ({:?}, {:?})",
+                agg_kind, r, v
+            ))
+            .into())
+        }
+    };
+    Ok(Some(result))
+}
+
+/// [`BitwiseAgg`] folds the non-null values of the current group with `&` or `|`.
+pub struct BitwiseAgg {
+    agg_kind: AggKind,
+    return_type: DataType,
+    input_col_idx: usize,
+    result: Datum,
+}
+
+impl BitwiseAgg {
+    pub fn new(agg_kind: AggKind, return_type: DataType, input_col_idx: usize) -> Self {
+        Self {
+            agg_kind,
+            return_type,
+            input_col_idx,
+            result: None,
+        }
+    }
+}
+
+impl Aggregator for BitwiseAgg {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn update_with_row(&mut self, input: &DataChunk, row_id: usize) -> Result<()> {
+        if let Some(visibility) = input.visibility() {
+            if !visibility.is_set(row_id)? {
+                return Ok(());
+            }
+        }
+        if let Some(value) = input
+            .column_at(self.input_col_idx)
+            .array_ref()
+            .value_at(row_id)
+        {
+            self.result = eval_bitwise_agg(&self.agg_kind, self.result.take(), value)?;
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, input: &DataChunk) -> Result<()> {
+        for row_id in 0..input.capacity() {
+            self.update_with_row(input, row_id)?;
+        }
+        Ok(())
+    }
+
+    fn output(&self, builder: &mut ArrayBuilderImpl) -> Result<()> {
+        builder.append_datum(&self.result)
+    }
+
+    fn update_and_output_with_sorted_groups(
+        &mut self,
+        input: &DataChunk,
+        builder: &mut ArrayBuilderImpl,
+        groups: &EqGroups,
+    ) -> Result<()> {
+        let mut group_starts = groups.get_starting_indices().iter().peekable();
+        for row_id in 0..input.capacity() {
+            if group_starts.next_if_eq(&&row_id).is_some() {
+                self.output(builder)?;
+                self.result = None;
+            }
+            self.update_with_row(input, row_id)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use risingwave_common::array::column::Column;
+
+    use super::*;
+
+    fn eval_agg(agg_kind: AggKind, return_type: DataType, array: ArrayImpl) -> Datum {
+        let chunk = DataChunk::builder()
+            .columns(vec![Column::new(Arc::new(array))])
+            .build();
+        let mut agg = BitwiseAgg::new(agg_kind, return_type.clone(), 0);
+        agg.update(&chunk).unwrap();
+        let mut builder = return_type.create_array_builder(1).unwrap();
+        agg.output(&mut builder).unwrap();
+        builder.finish().unwrap().datum_at(0)
+    }
+
+    #[test]
+    fn test_bitwise_agg_int() {
+        let values = [Some(0b1101), None, Some(0b0111)];
+        assert_eq!(
+            eval_agg(
+                AggKind::BitAnd,
+                DataType::Int32,
+                I32Array::from_slice(&values).unwrap().into()
+            ),
+            Some(ScalarImpl::Int32(0b0101))
+        );
+        assert_eq!(
+            eval_agg(
+                AggKind::BitOr,
+                DataType::Int32,
+                I32Array::from_slice(&values).unwrap().into()
+            ),
+            Some(ScalarImpl::Int32(0b1111))
+        );
+        assert_eq!(
+            eval_agg(
+                AggKind::BitOr,
+                DataType::Int64,
+                I64Array::from_slice(&[None]).unwrap().into()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bitwise_agg_bit_string() {
+        let values = ["1100", "1010"]
+            .map(|s| s.parse::<BitString>().unwrap())
+            .to_vec();
+        let array = || {
+            BitStringArray::from_slice(&[
+                Some(values[0].as_scalar_ref()),
+                None,
+                Some(values[1].as_scalar_ref()),
+            ])
+            .unwrap()
+            .into()
+        };
+        assert_eq!(
+            eval_agg(AggKind::BitAnd, DataType::Varbit, array()),
+            Some(ScalarImpl::BitString("1000".parse().unwrap()))
+        );
+        assert_eq!(
+            eval_agg(AggKind::BitOr, DataType::Varbit, array()),
+            Some(ScalarImpl::BitString("1110".parse().unwrap()))
+        );
+
+        // Bit strings of different lengths cannot be combined.
+        let short: BitString = "1".parse().unwrap();
+        assert!(eval_bitwise_agg(
+            &AggKind::BitAnd,
+            Some(ScalarImpl::BitString(values[0].clone())),
+            ScalarRefImpl::BitString(short.as_scalar_ref())
+        )
+        .is_err());
+    }
+}
@@ -337,6 +337,7 @@ mod tests {
                 ..Default::default()
             }),
             distinct: false,
+            order_by_fields: vec![],
         };
         let mut a = AggStateFactory::new(&prost)
             .unwrap()
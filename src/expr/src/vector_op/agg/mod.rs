@@ -13,11 +13,15 @@
 // limitations under the License.
 
 mod aggregator;
+mod bitwise;
 mod count_star;
 mod functions;
 mod general_agg;
 mod general_distinct_agg;
 mod general_sorted_grouper;
+mod time_series;
 
 pub use aggregator::{AggStateFactory, BoxedAggState};
+pub use bitwise::{eval_bitwise_agg, is_bitwise_agg};
 pub use general_sorted_grouper::{create_sorted_grouper, BoxedSortedGrouper, EqGroups};
+pub use time_series::{eval_time_series_agg, is_time_series_agg};
@@ -0,0 +1,288 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Time-series aggregations whose result depends on the order of the input by time, i.e.
+//! `first(value, time)`, `last(value, time)` and `time_weighted_avg(value, time)`.
+//!
+//! An OHLC candle is expressed as `first(price, time), max(price), min(price), last(price,
+//! time)` over the same group.
+
+use itertools::Itertools;
+use num_traits::ToPrimitive;
+use risingwave_common::array::*;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::*;
+
+use crate::expr::AggKind;
+use crate::vector_op::agg::aggregator::Aggregator;
+use crate::vector_op::agg::general_sorted_grouper::EqGroups;
+
+/// Whether `agg_kind` is a time-series aggregation taking `(value, time)` as arguments.
+pub fn is_time_series_agg(agg_kind: &AggKind) -> bool {
+    matches!(
+        agg_kind,
+        AggKind::First | AggKind::Last | AggKind::TimeWeightedAvg
+    )
+}
+
+/// Converts a time into a number. Only the differences between times are used as weights, so the
+/// unit does not matter as long as it is the same for all times.
+fn time_to_f64(time: ScalarRefImpl<'_>) -> Option<f64> {
+    match time {
+        ScalarRefImpl::Int16(v) => Some(v as f64),
+        ScalarRefImpl::Int32(v) => Some(v as f64),
+        ScalarRefImpl::Int64(v) => Some(v as f64),
+        ScalarRefImpl::NaiveDate(v) => Some(v.0.and_hms(0, 0, 0).timestamp() as f64),
+        ScalarRefImpl::NaiveDateTime(v) => {
+            Some(v.0.timestamp() as f64 + v.0.timestamp_subsec_nanos() as f64 / 1e9)
+        }
+        _ => None,
+    }
+}
+
+fn value_to_f64(value: ScalarRefImpl<'_>) -> Option<f64> {
+    match value {
+        ScalarRefImpl::Int16(v) => Some(v as f64),
+        ScalarRefImpl::Int32(v) => Some(v as f64),
+        ScalarRefImpl::Int64(v) => Some(v as f64),
+        ScalarRefImpl::Float32(v) => Some(v.0 as f64),
+        ScalarRefImpl::Float64(v) => Some(v.0),
+        ScalarRefImpl::Decimal(v) => v.to_f64(),
+        _ => None,
+    }
+}
+
+/// Evaluates a time-series aggregation over `points` of `(time, value)`, which must be sorted by
+/// time in ascending order.
+///
+/// `time_weighted_avg` interpolates linearly between adjacent points and ignores null values. If
+/// all the values are at the same time, it falls back to their plain average.
+pub fn eval_time_series_agg<'a>(
+    agg_kind: &AggKind,
+    points: impl IntoIterator<Item = (ScalarRefImpl<'a>, DatumRef<'a>)>,
+) -> Result<Datum> {
+    let mut points = points.into_iter();
+    match agg_kind {
+        AggKind::First => Ok(points.next().and_then(|(_, v)| v.to_owned_datum())),
+        AggKind::Last => Ok(points.last().and_then(|(_, v)| v.to_owned_datum())),
+        AggKind::TimeWeightedAvg => {
+            let mut prev: Option<(f64, f64)> = None;
+            let mut weighted_sum = 0.0;
+            let mut duration = 0.0;
+            let mut sum = 0.0;
+            let mut count = 0;
+            for (time, value) in points {
+                let value = match value {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let (time, value) =
+                    time_to_f64(time).zip(value_to_f64(value)).ok_or_else(|| {
+                        ErrorCode::InternalError(format!(
+                            "unsupported input of time_weighted_avg: ({:?}, {:?})",
+                            value, time
+                        ))
+                    })?;
+                if let Some((prev_time, prev_value)) = prev {
+                    weighted_sum += (prev_value + value) / 2.0 * (time - prev_time);
+                    duration += time - prev_time;
+                }
+                prev = Some((time, value));
+                sum += value;
+                count += 1;
+            }
+            let avg = if count == 0 {
+                return Ok(None);
+            } else if duration > 0.0 {
+                weighted_sum / duration
+            } else {
+                sum / count as f64
+            };
+            Ok(Some(ScalarImpl::Float64(avg.into())))
+        }
+        _ => Err(ErrorCode::InternalError(format!(
+            "{} is not a time-series aggregation",
+            agg_kind
+        ))
+        .into()),
+    }
+}
+
+/// [`TimeSeriesAgg`] collects the `(time, value)` points of the current group, and sorts them by
+/// time when the result is outputted.
+pub struct TimeSeriesAgg {
+    agg_kind: AggKind,
+    return_type: DataType,
+    value_col_idx: usize,
+    time_col_idx: usize,
+    points: Vec<(ScalarImpl, Datum)>,
+}
+
+impl TimeSeriesAgg {
+    pub fn new(
+        agg_kind: AggKind,
+        return_type: DataType,
+        value_col_idx: usize,
+        time_col_idx: usize,
+    ) -> Self {
+        Self {
+            agg_kind,
+            return_type,
+            value_col_idx,
+            time_col_idx,
+            points: vec![],
+        }
+    }
+}
+
+impl Aggregator for TimeSeriesAgg {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn update_with_row(&mut self, input: &DataChunk, row_id: usize) -> Result<()> {
+        if let Some(visibility) = input.visibility() {
+            if !visibility.is_set(row_id)? {
+                return Ok(());
+            }
+        }
+        // Rows without a time cannot be placed in the series.
+        if let Some(time) = input
+            .column_at(self.time_col_idx)
+            .array_ref()
+            .datum_at(row_id)
+        {
+            let value = input
+                .column_at(self.value_col_idx)
+                .array_ref()
+                .datum_at(row_id);
+            self.points.push((time, value));
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, input: &DataChunk) -> Result<()> {
+        for row_id in 0..input.capacity() {
+            self.update_with_row(input, row_id)?;
+        }
+        Ok(())
+    }
+
+    fn output(&self, builder: &mut ArrayBuilderImpl) -> Result<()> {
+        // The sort is stable, so points at the same time keep their input order.
+        let points = self
+            .points
+            .iter()
+            .sorted_by(|(t1, _), (t2, _)| t1.cmp(t2))
+            .map(|(t, v)| {
+                (
+                    t.as_scalar_ref_impl(),
+                    v.as_ref().map(|v| v.as_scalar_ref_impl()),
+                )
+            });
+        builder.append_datum(&eval_time_series_agg(&self.agg_kind, points)?)
+    }
+
+    fn update_and_output_with_sorted_groups(
+        &mut self,
+        input: &DataChunk,
+        builder: &mut ArrayBuilderImpl,
+        groups: &EqGroups,
+    ) -> Result<()> {
+        let mut group_starts = groups.get_starting_indices().iter().peekable();
+        for row_id in 0..input.capacity() {
+            if group_starts.next_if_eq(&&row_id).is_some() {
+                self.output(builder)?;
+                self.points.clear();
+            }
+            self.update_with_row(input, row_id)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use risingwave_common::array::column::Column;
+
+    use super::*;
+
+    fn eval_agg(agg_kind: AggKind, values: &[Option<i32>], times: &[Option<i64>]) -> Datum {
+        let chunk = DataChunk::builder()
+            .columns(vec![
+                Column::new(Arc::new(I32Array::from_slice(values).unwrap().into())),
+                Column::new(Arc::new(I64Array::from_slice(times).unwrap().into())),
+            ])
+            .build();
+        let return_type = match agg_kind {
+            AggKind::TimeWeightedAvg => DataType::Float64,
+            _ => DataType::Int32,
+        };
+        let mut agg = TimeSeriesAgg::new(agg_kind, return_type.clone(), 0, 1);
+        agg.update(&chunk).unwrap();
+        let mut builder = return_type.create_array_builder(1).unwrap();
+        agg.output(&mut builder).unwrap();
+        builder.finish().unwrap().datum_at(0)
+    }
+
+    #[test]
+    fn test_first_last() {
+        let values = [Some(3), Some(1), None, Some(4)];
+        let times = [Some(20), Some(10), Some(40), Some(30)];
+        assert_eq!(
+            eval_agg(AggKind::First, &values, &times),
+            Some(ScalarImpl::Int32(1))
+        );
+        // The value at the latest time is null.
+        assert_eq!(eval_agg(AggKind::Last, &values, &times), None);
+        // Rows without a time are ignored.
+        assert_eq!(
+            eval_agg(
+                AggKind::Last,
+                &values,
+                &[Some(20), Some(10), None, Some(30)]
+            ),
+            Some(ScalarImpl::Int32(4))
+        );
+        assert_eq!(eval_agg(AggKind::First, &[], &[]), None);
+    }
+
+    #[test]
+    fn test_time_weighted_avg() {
+        // (0, 0) -> (10, 10) -> (30, 10): (50 + 200) / 30
+        assert_eq!(
+            eval_agg(
+                AggKind::TimeWeightedAvg,
+                &[Some(10), Some(0), None, Some(10)],
+                &[Some(30), Some(0), Some(20), Some(10)],
+            ),
+            Some(ScalarImpl::Float64((250.0 / 30.0).into()))
+        );
+        // All values at the same time.
+        assert_eq!(
+            eval_agg(
+                AggKind::TimeWeightedAvg,
+                &[Some(1), Some(2)],
+                &[Some(5), Some(5)]
+            ),
+            Some(ScalarImpl::Float64(1.5.into()))
+        );
+        assert_eq!(
+            eval_agg(AggKind::TimeWeightedAvg, &[None], &[Some(5)]),
+            None
+        );
+    }
+}
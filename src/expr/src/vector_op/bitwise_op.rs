@@ -0,0 +1,156 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryInto;
+use std::fmt::Debug;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+use num_traits::{WrappingShl, WrappingShr};
+use risingwave_common::error::Result;
+use risingwave_common::types::{BitString, BitStringRef};
+
+use super::arithmetic_op::general_atm;
+
+#[inline(always)]
+pub fn general_bitand<T1, T2, T3>(l: T1, r: T2) -> Result<T3>
+where
+    T1: TryInto<T3> + Debug,
+    T2: TryInto<T3> + Debug,
+    T3: BitAnd<Output = T3>,
+{
+    general_atm(l, r, |a, b| Ok(a & b))
+}
+
+#[inline(always)]
+pub fn general_bitor<T1, T2, T3>(l: T1, r: T2) -> Result<T3>
+where
+    T1: TryInto<T3> + Debug,
+    T2: TryInto<T3> + Debug,
+    T3: BitOr<Output = T3>,
+{
+    general_atm(l, r, |a, b| Ok(a | b))
+}
+
+#[inline(always)]
+pub fn general_bitxor<T1, T2, T3>(l: T1, r: T2) -> Result<T3>
+where
+    T1: TryInto<T3> + Debug,
+    T2: TryInto<T3> + Debug,
+    T3: BitXor<Output = T3>,
+{
+    general_atm(l, r, |a, b| Ok(a ^ b))
+}
+
+#[inline(always)]
+pub fn general_bitnot<T1: Not<Output = T1>>(expr: T1) -> Result<T1> {
+    Ok(!expr)
+}
+
+/// Shifts an integer to the left. Like PostgreSQL, the shift amount is masked by the width of the
+/// integer, e.g. `1 << 33` is `2` for `int4`.
+#[inline(always)]
+pub fn general_shl<T1, T2, T3>(l: T1, r: T2) -> Result<T3>
+where
+    T1: Into<T3>,
+    T2: Into<i32>,
+    T3: WrappingShl,
+{
+    Ok(l.into().wrapping_shl(r.into() as u32))
+}
+
+/// Shifts an integer to the right arithmetically, with the shift amount masked as
+/// [`general_shl`].
+#[inline(always)]
+pub fn general_shr<T1, T2, T3>(l: T1, r: T2) -> Result<T3>
+where
+    T1: Into<T3>,
+    T2: Into<i32>,
+    T3: WrappingShr,
+{
+    Ok(l.into().wrapping_shr(r.into() as u32))
+}
+
+#[inline(always)]
+pub fn bit_string_bitand(l: BitStringRef<'_>, r: BitStringRef<'_>) -> Result<BitString> {
+    l.bitand(r)
+}
+
+#[inline(always)]
+pub fn bit_string_bitor(l: BitStringRef<'_>, r: BitStringRef<'_>) -> Result<BitString> {
+    l.bitor(r)
+}
+
+#[inline(always)]
+pub fn bit_string_bitxor(l: BitStringRef<'_>, r: BitStringRef<'_>) -> Result<BitString> {
+    l.bitxor(r)
+}
+
+#[inline(always)]
+pub fn bit_string_bitnot(expr: BitStringRef<'_>) -> Result<BitString> {
+    Ok(expr.bitnot())
+}
+
+#[inline(always)]
+pub fn bit_string_shl(l: BitStringRef<'_>, r: i32) -> Result<BitString> {
+    Ok(l.shl(r))
+}
+
+#[inline(always)]
+pub fn bit_string_shr(l: BitStringRef<'_>, r: i32) -> Result<BitString> {
+    Ok(l.shr(r))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::types::Scalar;
+
+    use super::*;
+
+    #[test]
+    fn test_int_bitwise() {
+        assert_eq!(
+            general_bitand::<i32, i32, i32>(0b1100, 0b1010).unwrap(),
+            0b1000
+        );
+        assert_eq!(
+            general_bitor::<i16, i32, i32>(0b1100, 0b1010).unwrap(),
+            0b1110
+        );
+        assert_eq!(
+            general_bitxor::<i64, i64, i64>(0b1100, 0b1010).unwrap(),
+            0b0110
+        );
+        assert_eq!(general_bitnot(0i16).unwrap(), -1);
+        assert_eq!(general_shl::<i32, i32, i32>(1, 4).unwrap(), 16);
+        assert_eq!(general_shl::<i32, i32, i32>(1, 33).unwrap(), 2);
+        assert_eq!(general_shr::<i64, i32, i64>(-16, 2).unwrap(), -4);
+        assert_eq!(general_shr::<i16, i32, i16>(256, 4).unwrap(), 16);
+    }
+
+    #[test]
+    fn test_bit_string_bitwise() {
+        let l: BitString = "10101".parse().unwrap();
+        let r: BitString = "11000".parse().unwrap();
+        let (l, r) = (l.as_scalar_ref(), r.as_scalar_ref());
+        assert_eq!(bit_string_bitand(l, r).unwrap().to_string(), "10000");
+        assert_eq!(bit_string_bitor(l, r).unwrap().to_string(), "11101");
+        assert_eq!(bit_string_bitxor(l, r).unwrap().to_string(), "01101");
+        assert_eq!(bit_string_bitnot(l).unwrap().to_string(), "01010");
+        assert_eq!(bit_string_shl(l, 2).unwrap().to_string(), "10100");
+        assert_eq!(bit_string_shr(l, 2).unwrap().to_string(), "00101");
+
+        let short: BitString = "101".parse().unwrap();
+        assert!(bit_string_bitand(l, short.as_scalar_ref()).is_err());
+    }
+}
@@ -21,9 +21,12 @@ use num_traits::ToPrimitive;
 use risingwave_common::error::ErrorCode::{InternalError, InvalidInputSyntax, ParseError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::{
-    Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, OrderedF32, OrderedF64,
+    BitString, BitStringRef, Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper,
+    OrderedF32, OrderedF64,
 };
 
+use crate::vector_op::encode::{escape_decode, hex_decode};
+
 /// String literals for bool type.
 ///
 /// See [`https://www.postgresql.org/docs/9.5/datatype-boolean.html`]
@@ -196,6 +199,36 @@ pub fn str_to_bool(input: &str) -> Result<bool> {
     }
 }
 
+/// Parses a bytea literal in either the hex format (`\x` followed by hex digits) or the escape
+/// format.
+///
+/// See [`https://www.postgresql.org/docs/current/datatype-binary.html`]
+#[inline(always)]
+pub fn str_to_bytea(elem: &str) -> Result<Box<[u8]>> {
+    let bytes = match elem.strip_prefix("\\x") {
+        Some(hex) => hex_decode(hex)?,
+        None => escape_decode(elem)?,
+    };
+    Ok(bytes.into_boxed_slice())
+}
+
+/// Formats a bytea value in the hex format, which is the output format of PG.
+#[inline(always)]
+pub fn bytea_to_str(elem: &[u8]) -> Result<String> {
+    Ok(format!("\\x{}", hex::encode(elem)))
+}
+
+/// Parses a bit string of `0`s and `1`s, e.g. `10110`.
+#[inline(always)]
+pub fn str_to_bit_string(elem: &str) -> Result<BitString> {
+    elem.parse()
+}
+
+#[inline(always)]
+pub fn bit_string_to_str(elem: BitStringRef<'_>) -> Result<String> {
+    Ok(elem.to_string())
+}
+
 #[inline(always)]
 pub fn bool_to_str(input: bool) -> Result<String> {
     match input {
@@ -0,0 +1,121 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::ErrorCode::InvalidInputSyntax;
+use risingwave_common::error::Result;
+
+/// Encodes binary data into a textual representation.
+///
+/// Supported formats are `hex`, `base64` and `escape`, see
+/// [`https://www.postgresql.org/docs/current/functions-binarystring.html`]
+#[inline(always)]
+pub fn encode(data: &[u8], format: &str) -> Result<String> {
+    match format.to_ascii_lowercase().as_str() {
+        "hex" => Ok(hex::encode(data)),
+        "base64" => Ok(base64::encode(data)),
+        "escape" => Ok(escape_encode(data)),
+        _ => Err(InvalidInputSyntax(format!("unrecognized encoding: \"{}\"", format)).into()),
+    }
+}
+
+/// Decodes binary data from the textual representation produced by [`encode`].
+#[inline(always)]
+pub fn decode(text: &str, format: &str) -> Result<Box<[u8]>> {
+    let bytes = match format.to_ascii_lowercase().as_str() {
+        "hex" => hex_decode(text)?,
+        "base64" => base64::decode(text.split_ascii_whitespace().collect::<String>())
+            .map_err(|e| InvalidInputSyntax(format!("invalid base64 input: {}", e)))?,
+        "escape" => escape_decode(text)?,
+        _ => {
+            return Err(InvalidInputSyntax(format!("unrecognized encoding: \"{}\"", format)).into())
+        }
+    };
+    Ok(bytes.into_boxed_slice())
+}
+
+/// Decodes hex digits, ignoring whitespace between them as PG does.
+pub fn hex_decode(text: &str) -> Result<Vec<u8>> {
+    let digits: String = text.split_ascii_whitespace().collect();
+    hex::decode(digits)
+        .map_err(|e| InvalidInputSyntax(format!("invalid hexadecimal data: {}", e)).into())
+}
+
+/// Encodes bytes in the `escape` format. Backslashes are doubled and non-printable bytes are
+/// written as `\nnn` octal sequences.
+pub fn escape_encode(data: &[u8]) -> String {
+    let mut s = String::with_capacity(data.len());
+    for &b in data {
+        match b {
+            b'\\' => s.push_str("\\\\"),
+            0x20..=0x7e => s.push(b as char),
+            _ => s.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    s
+}
+
+/// Decodes bytes in the `escape` format, the inverse of [`escape_encode`].
+pub fn escape_decode(text: &str) -> Result<Vec<u8>> {
+    let input = text.as_bytes();
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] != b'\\' {
+            bytes.push(input[i]);
+            i += 1;
+        } else if input.get(i + 1) == Some(&b'\\') {
+            bytes.push(b'\\');
+            i += 2;
+        } else {
+            let octal = input
+                .get(i + 1..i + 4)
+                .filter(|d| d[0] <= b'3' && d.iter().all(|c| (b'0'..=b'7').contains(c)))
+                .ok_or_else(|| InvalidInputSyntax("invalid input syntax for type bytea".into()))?;
+            bytes.push(octal.iter().fold(0u8, |acc, d| (acc << 3) | (d - b'0')));
+            i += 4;
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let cases: [(&[u8], &str, &str); 6] = [
+            (b"\x12\x34\x56", "hex", "123456"),
+            (b"\xde\xad\xbe\xef", "HEX", "deadbeef"),
+            (b"hello", "base64", "aGVsbG8="),
+            (b"\x00\x01", "base64", "AAE="),
+            (b"a\\b", "escape", "a\\\\b"),
+            (b"\x00\xffz", "escape", "\\000\\377z"),
+        ];
+
+        for (data, format, text) in cases {
+            assert_eq!(encode(data, format).unwrap(), text);
+            assert_eq!(&*decode(text, format).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decode_error() {
+        assert!(decode("abc", "hex").is_err());
+        assert!(decode("zz", "hex").is_err());
+        assert!(decode("\\9", "escape").is_err());
+        assert!(decode("abc", "rot13").is_err());
+        assert!(encode(b"abc", "rot13").is_err());
+    }
+}
@@ -15,9 +15,11 @@
 pub mod agg;
 pub mod arithmetic_op;
 pub mod ascii;
+pub mod bitwise_op;
 pub mod cast;
 pub mod cmp;
 pub mod conjunction;
+pub mod encode;
 pub mod extract;
 pub mod length;
 pub mod like;
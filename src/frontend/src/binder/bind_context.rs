@@ -17,6 +17,7 @@ use std::fmt::Display;
 
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::Ident;
 
 #[derive(Debug)]
 pub struct ColumnBinding {
@@ -85,10 +86,12 @@ impl BindContext {
     }
 
     fn get_index(&self, column_name: &String) -> Result<usize> {
-        let columns = self
-            .indexs_of
-            .get(column_name)
-            .ok_or_else(|| ErrorCode::ItemNotFound(format!("Invalid column: {}", column_name)))?;
+        let columns = self.indexs_of.get(column_name).ok_or_else(|| {
+            ErrorCode::ItemNotFound(format!(
+                "Invalid column: {}",
+                Ident::from_real_value(column_name)
+            ))
+        })?;
         if columns.len() > 1 {
             Err(ErrorCode::InternalError("Ambiguous column name".into()).into())
         } else {
@@ -101,10 +104,12 @@ impl BindContext {
         column_name: &String,
         table_name: &String,
     ) -> Result<usize> {
-        let column_indexes = self
-            .indexs_of
-            .get(column_name)
-            .ok_or_else(|| ErrorCode::ItemNotFound(format!("Invalid column: {}", column_name)))?;
+        let column_indexes = self.indexs_of.get(column_name).ok_or_else(|| {
+            ErrorCode::ItemNotFound(format!(
+                "Invalid column: {}",
+                Ident::from_real_value(column_name)
+            ))
+        })?;
         match column_indexes
             .iter()
             .find(|column_index| self.columns[**column_index].table_name == *table_name)
@@ -43,6 +43,11 @@ impl Binder {
             BinaryOperator::Or => ExprType::Or,
             BinaryOperator::Like => ExprType::Like,
             BinaryOperator::NotLike => return self.bind_not_like(bound_left, bound_right),
+            BinaryOperator::BitwiseAnd => ExprType::BitwiseAnd,
+            BinaryOperator::BitwiseOr => ExprType::BitwiseOr,
+            BinaryOperator::PGBitwiseXor => ExprType::BitwiseXor,
+            BinaryOperator::PGBitwiseShiftLeft => ExprType::BitwiseShiftLeft,
+            BinaryOperator::PGBitwiseShiftRight => ExprType::BitwiseShiftRight,
             _ => return Err(ErrorCode::NotImplemented(format!("{:?}", op), 112.into()).into()),
         };
         FunctionCall::new_or_else(func_type, vec![bound_left, bound_right], |inputs| {
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_sqlparser::ast::Ident;
 
@@ -20,11 +21,11 @@ use crate::expr::{CorrelatedInputRef, ExprImpl, InputRef};
 
 impl Binder {
     pub fn bind_column(&mut self, idents: &[Ident]) -> Result<ExprImpl> {
-        // TODO: check quote style of `ident`.
-        let (_schema_name, table_name, column_name) = match idents {
-            [column] => (None, None, &column.value),
-            [table, column] => (None, Some(&table.value), &column.value),
-            [schema, table, column] => (Some(&schema.value), Some(&table.value), &column.value),
+        let idents = idents.iter().map(Ident::real_value).collect_vec();
+        let (_schema_name, table_name, column_name) = match idents.as_slice() {
+            [column] => (None, None, column),
+            [table, column] => (None, Some(table), column),
+            [schema, table, column] => (Some(schema), Some(table), column),
             _ => {
                 return Err(
                     ErrorCode::InternalError(format!("Too many idents: {:?}", idents)).into(),
@@ -38,7 +39,11 @@ impl Binder {
         }
 
         // Try to find a correlated column in `upper_contexts`, starting from the innermost context.
-        let mut err = ErrorCode::ItemNotFound(format!("Invalid column: {}", column_name)).into();
+        let mut err = ErrorCode::ItemNotFound(format!(
+            "Invalid column: {}",
+            Ident::from_real_value(column_name)
+        ))
+        .into();
         for (i, context) in self.upper_contexts.iter().rev().enumerate() {
             // `depth` starts from 1.
             let depth = i + 1;
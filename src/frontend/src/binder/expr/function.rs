@@ -16,11 +16,12 @@ use itertools::Itertools;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::DataType;
 use risingwave_expr::expr::AggKind;
-use risingwave_sqlparser::ast::{Function, FunctionArg, FunctionArgExpr};
+use risingwave_sqlparser::ast::{Function, FunctionArg, FunctionArgExpr, OrderByExpr};
 
 use crate::binder::bind_context::Clause;
 use crate::binder::Binder;
-use crate::expr::{AggCall, Expr, ExprImpl, ExprType, FunctionCall, Literal};
+use crate::expr::{AggCall, AggOrderByExpr, Expr, ExprImpl, ExprType, FunctionCall, Literal};
+use crate::optimizer::property::Direction;
 
 impl Binder {
     pub(super) fn bind_function(&mut self, f: Function) -> Result<ExprImpl> {
@@ -32,19 +33,68 @@ impl Binder {
             .try_collect()?;
 
         if f.name.0.len() == 1 {
-            let function_name = f.name.0.get(0).unwrap().value.as_str();
-            let function_name = function_name.to_lowercase();
+            let function_name = f.name.0.get(0).unwrap().real_value();
             let agg_kind = match function_name.as_str() {
                 "count" => Some(AggKind::Count),
                 "sum" => Some(AggKind::Sum),
                 "min" => Some(AggKind::Min),
                 "max" => Some(AggKind::Max),
                 "avg" => Some(AggKind::Avg),
+                "string_agg" => Some(AggKind::StringAgg),
+                "first" => Some(AggKind::First),
+                "last" => Some(AggKind::Last),
+                "time_weighted_avg" => Some(AggKind::TimeWeightedAvg),
+                "bit_and" => Some(AggKind::BitAnd),
+                "bit_or" => Some(AggKind::BitOr),
                 _ => None,
             };
             if let Some(kind) = agg_kind {
                 self.ensure_aggregate_allowed()?;
-                return Ok(ExprImpl::AggCall(Box::new(AggCall::new(kind, inputs)?)));
+                let distinct = match kind {
+                    // `DISTINCT` makes no difference to the result of min, max and the bitwise
+                    // aggregations.
+                    AggKind::Min | AggKind::Max | AggKind::BitAnd | AggKind::BitOr => false,
+                    AggKind::StringAgg if f.distinct => {
+                        return Err(ErrorCode::NotImplemented(
+                            "string_agg with DISTINCT".to_string(),
+                            None.into(),
+                        )
+                        .into())
+                    }
+                    // These are ordered by their time argument.
+                    AggKind::First | AggKind::Last | AggKind::TimeWeightedAvg
+                        if f.distinct || !f.order_by.is_empty() =>
+                    {
+                        return Err(ErrorCode::NotImplemented(
+                            format!("{} with DISTINCT or ORDER BY", kind),
+                            None.into(),
+                        )
+                        .into())
+                    }
+                    _ => f.distinct,
+                };
+                let order_by = f
+                    .order_by
+                    .into_iter()
+                    .map(|e| self.bind_agg_order_by_expr(e))
+                    .try_collect()?;
+                return Ok(ExprImpl::AggCall(Box::new(AggCall::new_with_clauses(
+                    kind, inputs, distinct, order_by,
+                )?)));
+            }
+            if f.distinct {
+                return Err(ErrorCode::InvalidInputSyntax(format!(
+                    "DISTINCT specified, but {} is not an aggregate function",
+                    function_name
+                ))
+                .into());
+            }
+            if !f.order_by.is_empty() {
+                return Err(ErrorCode::InvalidInputSyntax(format!(
+                    "ORDER BY specified, but {} is not an aggregate function",
+                    function_name
+                ))
+                .into());
             }
             let function_type = match function_name.as_str() {
                 "substr" => ExprType::Substr,
@@ -57,6 +107,8 @@ impl Binder {
                 "position" => ExprType::Position,
                 "ltrim" => ExprType::Ltrim,
                 "rtrim" => ExprType::Rtrim,
+                "encode" => ExprType::Encode,
+                "decode" => ExprType::Decode,
                 "case" => ExprType::Case,
                 "is true" => ExprType::IsTrue,
                 "is not true" => ExprType::IsNotTrue,
@@ -64,6 +116,8 @@ impl Binder {
                 "is not false" => ExprType::IsNotFalse,
                 "is null" => ExprType::IsNull,
                 "is not null" => ExprType::IsNotNull,
+                "monotonic_id" => ExprType::MonotonicId,
+                "proctime" => ExprType::Proctime,
                 "round" => {
                     inputs = Self::rewrite_round_args(inputs);
                     ExprType::RoundDigit
@@ -89,6 +143,17 @@ impl Binder {
         }
     }
 
+    fn bind_agg_order_by_expr(&mut self, order_by_expr: OrderByExpr) -> Result<AggOrderByExpr> {
+        let direction = match order_by_expr.asc {
+            None | Some(true) => Direction::Asc,
+            Some(false) => Direction::Desc,
+        };
+        Ok(AggOrderByExpr {
+            expr: self.bind_expr(order_by_expr.expr)?,
+            direction,
+        })
+    }
+
     fn err_unsupported_func(function_name: &str, inputs: &[ExprImpl]) -> RwError {
         let args = inputs
             .iter()
@@ -12,11 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use risingwave_common::catalog::ColumnDesc;
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_sqlparser::ast::{Ident, ObjectName, Query, SetExpr};
+use risingwave_sqlparser::ast::{
+    Assignment, Ident, ObjectName, OnConflict, OnConflictAction, Query, SetExpr,
+};
 
 use super::{BoundQuery, BoundSetExpr};
 use crate::binder::{Binder, BoundTableSource};
+use crate::expr::{ExprImpl, InputRef};
+
+/// The name of the row proposed for insertion in `ON CONFLICT DO UPDATE`.
+const EXCLUDED: &str = "excluded";
 
 #[derive(Debug)]
 pub struct BoundInsert {
@@ -24,6 +34,27 @@ pub struct BoundInsert {
     pub table_source: BoundTableSource,
 
     pub source: BoundQuery,
+
+    pub on_conflict: Option<BoundOnConflict>,
+}
+
+/// `ON CONFLICT` of an INSERT. The conflicts are detected on the primary key of a table created
+/// with `on_conflict = 'overwrite'`, which is the only kind of table keyed by its primary key.
+#[derive(Debug)]
+pub struct BoundOnConflict {
+    /// Indices of the primary key columns in the inserted rows, in the order of the table's
+    /// primary key.
+    pub pk_indices: Vec<usize>,
+
+    pub action: BoundOnConflictAction,
+}
+
+#[derive(Debug)]
+pub enum BoundOnConflictAction {
+    DoNothing,
+    /// The new values of the inserted columns of a conflicting row, over the columns of the
+    /// existing row followed by those of the row proposed for insertion.
+    DoUpdate(Vec<ExprImpl>),
 }
 
 impl Binder {
@@ -32,7 +63,9 @@ impl Binder {
         source_name: ObjectName,
         _columns: Vec<Ident>,
         source: Query,
+        on_conflict: Option<OnConflict>,
     ) -> Result<BoundInsert> {
+        let (schema_name, table_name) = Self::resolve_table_name(source_name.clone())?;
         let table_source = self.bind_table_source(source_name)?;
 
         let limit = source.get_limit_value();
@@ -50,6 +83,7 @@ impl Binder {
                 BoundQuery {
                     body,
                     order: vec![],
+                    order_by_random: false,
                     limit,
                     offset,
                 }
@@ -65,11 +99,149 @@ impl Binder {
             }
         };
 
+        let on_conflict = on_conflict
+            .map(|on_conflict| {
+                self.bind_on_conflict(
+                    &schema_name,
+                    &table_name,
+                    &table_source.columns,
+                    on_conflict,
+                )
+            })
+            .transpose()?;
+
         let insert = BoundInsert {
             table_source,
             source,
+            on_conflict,
         };
 
         Ok(insert)
     }
+
+    /// Binds `ON CONFLICT` against the table and the inserted `columns` of its source.
+    fn bind_on_conflict(
+        &mut self,
+        schema_name: &str,
+        table_name: &str,
+        columns: &[ColumnDesc],
+        on_conflict: OnConflict,
+    ) -> Result<BoundOnConflict> {
+        let OnConflict {
+            conflict_target,
+            action,
+        } = on_conflict;
+
+        // The existing row comes first in the context, followed by the `excluded` one.
+        let table = self.bind_table(schema_name, table_name, None)?;
+        let table_columns = table.table_catalog.columns();
+        let pk_desc = table.table_catalog.pk_desc();
+
+        // The other tables are keyed by the hidden row id, which is never inserted.
+        let pk_indices = pk_desc
+            .iter()
+            .map(|pk| {
+                columns
+                    .iter()
+                    .position(|c| c.column_id == pk.column_desc.column_id)
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                ErrorCode::BindError(format!(
+                    "ON CONFLICT requires table \"{}\" to have a PRIMARY KEY and on_conflict = 'overwrite'",
+                    table_name
+                ))
+            })?;
+
+        if !conflict_target.is_empty() {
+            let target: HashSet<_> = conflict_target.iter().map(|c| c.real_value()).collect();
+            let pk_names: HashSet<_> = pk_desc.iter().map(|c| c.column_desc.name.clone()).collect();
+            if target != pk_names {
+                return Err(ErrorCode::BindError(
+                    "there is no unique or exclusion constraint matching the ON CONFLICT specification"
+                        .to_string(),
+                )
+                .into());
+            }
+        }
+
+        let action = match action {
+            OnConflictAction::DoNothing => BoundOnConflictAction::DoNothing,
+            OnConflictAction::DoUpdate(_) if conflict_target.is_empty() => {
+                return Err(ErrorCode::BindError(
+                    "ON CONFLICT DO UPDATE requires inference specification or constraint name"
+                        .to_string(),
+                )
+                .into())
+            }
+            OnConflictAction::DoUpdate(assignments) => {
+                // A column not assigned keeps its existing value.
+                let mut exprs = columns
+                    .iter()
+                    .map(|c| {
+                        let idx = table_columns
+                            .iter()
+                            .position(|tc| tc.column_id() == c.column_id)
+                            .expect("inserted column not found in table");
+                        InputRef::new(idx, c.data_type.clone()).into()
+                    })
+                    .collect_vec();
+
+                self.bind_context(
+                    columns
+                        .iter()
+                        .map(|c| (c.name.clone(), c.data_type.clone(), false)),
+                    EXCLUDED.to_string(),
+                    None,
+                )?;
+
+                let mut assigned = HashSet::new();
+                for Assignment { id, value } in assignments {
+                    let name = match &id[..] {
+                        [name] => name.real_value(),
+                        _ => {
+                            return Err(ErrorCode::NotImplemented(
+                                format!("assignment to {}", id.iter().join(".")),
+                                None.into(),
+                            )
+                            .into())
+                        }
+                    };
+                    let idx = columns.iter().position(|c| c.name == name).ok_or_else(|| {
+                        ErrorCode::BindError(format!(
+                            "column \"{}\" of relation \"{}\" does not exist",
+                            name, table_name
+                        ))
+                    })?;
+                    if pk_indices.contains(&idx) {
+                        return Err(ErrorCode::BindError(format!(
+                            "ON CONFLICT DO UPDATE cannot update primary key column \"{}\"",
+                            name
+                        ))
+                        .into());
+                    }
+                    if !assigned.insert(idx) {
+                        return Err(ErrorCode::BindError(format!(
+                            "multiple assignments to same column \"{}\"",
+                            name
+                        ))
+                        .into());
+                    }
+
+                    let expr = self.bind_expr(value)?;
+                    if expr.has_agg_call() || expr.has_subquery() {
+                        return Err(ErrorCode::BindError(
+                            "aggregations and subqueries are not allowed in ON CONFLICT DO UPDATE"
+                                .to_string(),
+                        )
+                        .into());
+                    }
+                    exprs[idx] = expr.cast_assign(columns[idx].data_type.clone())?;
+                }
+                BoundOnConflictAction::DoUpdate(exprs)
+            }
+        };
+
+        Ok(BoundOnConflict { pk_indices, action })
+    }
 }
@@ -19,26 +19,38 @@ mod bind_context;
 mod delete;
 pub(crate) mod expr;
 mod insert;
+mod peek_source;
 mod query;
 mod relation;
 mod select;
 mod set_expr;
 mod statement;
+mod system_table;
+mod table_function;
 mod values;
+mod watermark;
 mod window_table_function;
 
 pub use bind_context::BindContext;
 pub use delete::BoundDelete;
-pub use insert::BoundInsert;
+pub use insert::{BoundInsert, BoundOnConflict, BoundOnConflictAction};
+pub use peek_source::BoundPeekSource;
 pub use query::BoundQuery;
 pub use relation::{BoundBaseTable, BoundJoin, BoundSource, BoundTableSource, Relation};
 pub use select::BoundSelect;
-pub use set_expr::BoundSetExpr;
+pub use set_expr::{BoundSetExpr, BoundSetOperation};
 pub use statement::BoundStatement;
+pub use system_table::{
+    may_refer_to_runtime_table, may_refer_to_system_table, BoundSystemTable, StreamingRuntimeInfo,
+    RW_ACTORS_TABLE_NAME, RW_CATALOG_SCHEMA_NAME, RW_EVENTS_TABLE_NAME, RW_FRAGMENTS_TABLE_NAME,
+    RW_SLOW_BARRIERS_TABLE_NAME, RW_SLOW_QUERIES_TABLE_NAME, RW_TABLE_WRITE_STATS_TABLE_NAME,
+};
+pub use table_function::BoundTableFunction;
 pub use values::BoundValues;
 pub use window_table_function::{BoundWindowTableFunction, WindowTableFunctionKind};
 
 use crate::catalog::catalog_service::CatalogReadGuard;
+use crate::scheduler::slow_query_log::SlowQueryRecord;
 
 /// `Binder` binds the identifiers in AST to columns in relations
 pub struct Binder {
@@ -52,6 +64,13 @@ pub struct Binder {
     upper_contexts: Vec<BindContext>,
 
     next_subquery_id: usize,
+
+    /// The runtime graph of streaming jobs, which is only fetched when the statement refers to
+    /// `rw_fragments` or `rw_actors`.
+    runtime_info: Option<StreamingRuntimeInfo>,
+
+    /// The latest slow queries of this frontend, for `rw_slow_queries`.
+    slow_queries: Vec<SlowQueryRecord>,
 }
 
 impl Binder {
@@ -62,9 +81,23 @@ impl Binder {
             context: BindContext::new(),
             upper_contexts: vec![],
             next_subquery_id: 0,
+            runtime_info: None,
+            slow_queries: vec![],
         }
     }
 
+    #[must_use]
+    pub fn with_runtime_info(mut self, runtime_info: StreamingRuntimeInfo) -> Self {
+        self.runtime_info = Some(runtime_info);
+        self
+    }
+
+    #[must_use]
+    pub fn with_slow_queries(mut self, slow_queries: Vec<SlowQueryRecord>) -> Self {
+        self.slow_queries = slow_queries;
+        self
+    }
+
     /// Bind a [`Statement`].
     pub fn bind(&mut self, stmt: Statement) -> Result<BoundStatement> {
         self.bind_statement(stmt)
@@ -0,0 +1,118 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::Field;
+use risingwave_common::error::{ErrorCode, RwError};
+use risingwave_common::types::DataType;
+use risingwave_pb::stream_plan::source_node::SourceType;
+use risingwave_sqlparser::ast::{
+    Expr, FunctionArg, FunctionArgExpr, ObjectName, TableAlias, Value,
+};
+
+use super::{Binder, Result};
+use crate::catalog::source_catalog::SourceCatalog;
+
+/// Number of messages `peek_source` reads if not specified.
+const DEFAULT_PEEK_LIMIT: usize = 10;
+
+/// `peek_source(source [, limit])` reads raw messages of a source, for debugging parser or schema
+/// mismatches of the source.
+#[derive(Debug)]
+pub struct BoundPeekSource {
+    pub(crate) catalog: SourceCatalog,
+    pub(crate) limit: usize,
+}
+
+impl BoundPeekSource {
+    /// The columns of raw messages. Must be kept in sync with the `PeekSourceExecutor` of batch.
+    pub fn fields() -> Vec<Field> {
+        vec![
+            Field::with_name(DataType::Varchar, "split_id"),
+            Field::with_name(DataType::Varchar, "offset"),
+            Field::with_name(DataType::Bytea, "key"),
+            Field::with_name(DataType::Bytea, "payload"),
+            Field::with_name(DataType::Timestamp, "timestamp"),
+        ]
+    }
+}
+
+impl Binder {
+    pub(super) fn bind_peek_source(
+        &mut self,
+        args: Vec<FunctionArg>,
+        alias: Option<TableAlias>,
+    ) -> Result<BoundPeekSource> {
+        let mut args = args.into_iter();
+
+        let source_name = match args.next() {
+            Some(FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Identifier(ident)))) => {
+                ObjectName(vec![ident])
+            }
+            Some(FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::CompoundIdentifier(idents)))) => {
+                ObjectName(idents)
+            }
+            _ => {
+                return Err(ErrorCode::BindError(
+                    "the 1st arg of peek_source should be a source".to_string(),
+                )
+                .into())
+            }
+        };
+        let (schema_name, source_name) = Self::resolve_table_name(source_name)?;
+        let catalog = self
+            .catalog
+            .get_source_by_name(&self.db_name, &schema_name, &source_name)?
+            .clone();
+        if catalog.source_type != SourceType::Source {
+            return Err(ErrorCode::BindError(format!(
+                "peek_source only supports sources, but {} is a table",
+                source_name
+            ))
+            .into());
+        }
+
+        let limit = match args.next() {
+            None => DEFAULT_PEEK_LIMIT,
+            Some(FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Value(Value::Number(n, _))))) => {
+                n.parse().map_err(|_| {
+                    RwError::from(ErrorCode::BindError(format!(
+                        "the 2nd arg of peek_source should be a non-negative integer, got {}",
+                        n
+                    )))
+                })?
+            }
+            Some(_) => {
+                return Err(ErrorCode::BindError(
+                    "the 2nd arg of peek_source should be a non-negative integer".to_string(),
+                )
+                .into())
+            }
+        };
+        if args.next().is_some() {
+            return Err(
+                ErrorCode::BindError("peek_source takes at most 2 arguments".to_string()).into(),
+            );
+        }
+
+        self.bind_context(
+            BoundPeekSource::fields()
+                .into_iter()
+                .map(|f| (f.name, f.data_type, false)),
+            "peek_source".to_string(),
+            alias,
+        )?;
+
+        Ok(BoundPeekSource { catalog, limit })
+    }
+}
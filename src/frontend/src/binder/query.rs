@@ -16,7 +16,7 @@ use std::collections::HashMap;
 
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
-use risingwave_sqlparser::ast::{Expr, OrderByExpr, Query};
+use risingwave_sqlparser::ast::{Expr, Function, OrderByExpr, Query};
 
 use crate::binder::{Binder, BoundSetExpr};
 use crate::optimizer::property::{Direction, FieldOrder};
@@ -27,6 +27,8 @@ use crate::optimizer::property::{Direction, FieldOrder};
 pub struct BoundQuery {
     pub body: BoundSetExpr,
     pub order: Vec<FieldOrder>,
+    /// Whether the query is `ORDER BY random()`, in which case `order` is empty.
+    pub order_by_random: bool,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
 }
@@ -74,15 +76,33 @@ impl Binder {
                 }
             }),
             BoundSetExpr::Values(_) => {}
+            BoundSetExpr::SetOperation { .. } => {
+                body.names()
+                    .into_iter()
+                    .enumerate()
+                    .for_each(|(index, name)| {
+                        name_to_index.insert(name, index);
+                    })
+            }
+        };
+        // `ORDER BY random()` is planned as sampling rather than sorting on a random key.
+        let order_by_random = matches!(
+            query.order_by.as_slice(),
+            [OrderByExpr { expr: Expr::Function(func), .. }] if is_random_function(func)
+        );
+        let order = if order_by_random {
+            vec![]
+        } else {
+            query
+                .order_by
+                .into_iter()
+                .map(|order_by_expr| self.bind_order_by_expr(order_by_expr, &name_to_index))
+                .collect::<Result<_>>()?
         };
-        let order = query
-            .order_by
-            .into_iter()
-            .map(|order_by_expr| self.bind_order_by_expr(order_by_expr, &name_to_index))
-            .collect::<Result<_>>()?;
         Ok(BoundQuery {
             body,
             order,
+            order_by_random,
             limit,
             offset,
         })
@@ -98,7 +118,7 @@ impl Binder {
             Some(false) => Direction::Desc,
         };
         let name = match order_by_expr.expr {
-            Expr::Identifier(name) => name.value,
+            Expr::Identifier(name) => name.real_value(),
             expr => {
                 return Err(
                     ErrorCode::NotImplemented(format!("ORDER BY {:?}", expr), 1635.into()).into(),
@@ -111,3 +131,10 @@ impl Binder {
         Ok(FieldOrder { index, direct })
     }
 }
+
+fn is_random_function(func: &Function) -> bool {
+    func.args.is_empty()
+        && func.over.is_none()
+        && !func.distinct
+        && func.name.to_string().eq_ignore_ascii_case("random")
+}
@@ -25,7 +25,10 @@ use risingwave_sqlparser::ast::{
 };
 
 use super::bind_context::ColumnBinding;
-use super::{BoundQuery, BoundWindowTableFunction, WindowTableFunctionKind, UNNAMED_SUBQUERY};
+use super::{
+    BoundPeekSource, BoundQuery, BoundSystemTable, BoundTableFunction, BoundWindowTableFunction,
+    WindowTableFunctionKind, RW_CATALOG_SCHEMA_NAME, UNNAMED_SUBQUERY,
+};
 use crate::binder::Binder;
 use crate::catalog::source_catalog::SourceCatalog;
 use crate::catalog::table_catalog::TableCatalog;
@@ -41,6 +44,9 @@ pub enum Relation {
     Subquery(Box<BoundSubquery>),
     Join(Box<BoundJoin>),
     WindowTableFunction(Box<BoundWindowTableFunction>),
+    TableFunction(Box<BoundTableFunction>),
+    PeekSource(Box<BoundPeekSource>),
+    SystemTable(Box<BoundSystemTable>),
 }
 
 #[derive(Debug)]
@@ -71,6 +77,9 @@ impl From<&TableCatalog> for BoundBaseTable {
 #[derive(Debug)]
 pub struct BoundSubquery {
     pub query: BoundQuery,
+    /// Whether the subquery is `LATERAL`, which can refer to the relations on its left in the same
+    /// `FROM` clause.
+    pub lateral: bool,
 }
 
 /// `BoundTableSource` is used by DML statement on table source like insert, updata
@@ -169,14 +178,22 @@ impl Binder {
                 if args.is_empty() {
                     let (schema_name, table_name) = Self::resolve_table_name(name)?;
                     self.bind_table_or_source(&schema_name, &table_name, alias)
+                } else if name.0.len() == 1 && name.0[0].real_value() == "peek_source" {
+                    Ok(Relation::PeekSource(Box::new(
+                        self.bind_peek_source(args, alias)?,
+                    )))
+                } else if name.0.len() == 1 && name.0[0].real_value() == "unnest" {
+                    Ok(Relation::TableFunction(Box::new(
+                        self.bind_table_function(args, alias)?,
+                    )))
                 } else {
-                    let kind =
-                        WindowTableFunctionKind::from_str(&name.0[0].value).map_err(|_| {
-                            ErrorCode::NotImplemented(
-                                format!("unknown window function kind: {}", name.0[0].value),
-                                1191.into(),
-                            )
-                        })?;
+                    let func_name = name.0[0].real_value();
+                    let kind = WindowTableFunctionKind::from_str(&func_name).map_err(|_| {
+                        ErrorCode::NotImplemented(
+                            format!("unknown window function kind: {}", func_name),
+                            1191.into(),
+                        )
+                    })?;
                     Ok(Relation::WindowTableFunction(Box::new(
                         self.bind_window_table_function(kind, args)?,
                     )))
@@ -186,15 +203,9 @@ impl Binder {
                 lateral,
                 subquery,
                 alias,
-            } => {
-                if lateral {
-                    Err(ErrorCode::NotImplemented("unsupported lateral".into(), None.into()).into())
-                } else {
-                    Ok(Relation::Subquery(Box::new(
-                        self.bind_subquery_relation(*subquery, alias)?,
-                    )))
-                }
-            }
+            } => Ok(Relation::Subquery(Box::new(
+                self.bind_subquery_relation(*subquery, alias, lateral)?,
+            ))),
             _ => Err(ErrorCode::NotImplemented(
                 format!("unsupported table factor {:?}", table_factor),
                 None.into(),
@@ -224,6 +235,11 @@ impl Binder {
             )
             .into());
         }
+        if schema_name == RW_CATALOG_SCHEMA_NAME {
+            return Ok(Relation::SystemTable(Box::new(
+                self.bind_system_table(table_name, alias)?,
+            )));
+        }
 
         let (ret, columns) = {
             let catalog = &self.catalog;
@@ -287,17 +303,15 @@ impl Binder {
         })
     }
 
-    /// return the (`schema_name`, `table_name`)
+    /// return the (`schema_name`, `table_name`), folded to lower case unless quoted
     pub fn resolve_table_name(name: ObjectName) -> Result<(String, String)> {
-        let mut identifiers = name.0;
+        let mut identifiers = name.real_values();
         let table_name = identifiers
             .pop()
-            .ok_or_else(|| ErrorCode::InternalError("empty table name".into()))?
-            .value;
+            .ok_or_else(|| ErrorCode::InternalError("empty table name".into()))?;
 
         let schema_name = identifiers
             .pop()
-            .map(|ident| ident.value)
             .unwrap_or_else(|| DEFAULT_SCHEMA_NAME.into());
 
         Ok((schema_name, table_name))
@@ -336,7 +350,7 @@ impl Binder {
     ) -> Result<()> {
         let (table_name, column_aliases) = match alias {
             None => (table_name, vec![]),
-            Some(TableAlias { name, columns }) => (name.value, columns),
+            Some(TableAlias { name, columns }) => (name.real_value(), columns),
         };
 
         let begin = self.context.columns.len();
@@ -349,7 +363,7 @@ impl Binder {
             .for_each(|(index, (name, data_type, is_hidden))| {
                 let name = match is_hidden {
                     true => name,
-                    false => alias_iter.next().map(|t| t.value).unwrap_or(name),
+                    false => alias_iter.next().map(|t| t.real_value()).unwrap_or(name),
                 };
                 self.context.columns.push(ColumnBinding::new(
                     table_name.clone(),
@@ -387,13 +401,25 @@ impl Binder {
     /// Binds a subquery using [`bind_query`](Self::bind_query), which will use a new empty
     /// [`BindContext`](super::BindContext) for it.
     ///
+    /// A `LATERAL` subquery sees the relations bound so far in the current context as its outer
+    /// query, so that the columns on its left are bound as correlated columns of depth 1. Otherwise
+    /// the current context is hidden from the subquery.
+    ///
     /// After finishing binding, we update the current context with the output of the subquery.
     pub(super) fn bind_subquery_relation(
         &mut self,
         query: Query,
         alias: Option<TableAlias>,
+        lateral: bool,
     ) -> Result<BoundSubquery> {
-        let query = self.bind_query(query)?;
+        let query = if lateral {
+            self.bind_query(query)?
+        } else {
+            let context = std::mem::take(&mut self.context);
+            let result = self.bind_query(query);
+            self.context = context;
+            result?
+        };
         let sub_query_id = self.next_subquery_id();
         self.bind_context(
             query
@@ -404,6 +430,6 @@ impl Binder {
             format!("{}_{}", UNNAMED_SUBQUERY, sub_query_id),
             alias,
         )?;
-        Ok(BoundSubquery { query })
+        Ok(BoundSubquery { query, lateral })
     }
 }
@@ -17,7 +17,7 @@ use std::fmt::Debug;
 use itertools::Itertools;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
-use risingwave_sqlparser::ast::{Expr, Select, SelectItem};
+use risingwave_sqlparser::ast::{Expr, Ident, Select, SelectItem};
 
 use super::bind_context::{Clause, ColumnBinding};
 use super::UNNAMED_COLUMN;
@@ -116,10 +116,8 @@ impl Binder {
             match item {
                 SelectItem::UnnamedExpr(expr) => {
                     let alias = match &expr {
-                        Expr::Identifier(ident) => Some(ident.value.clone()),
-                        Expr::CompoundIdentifier(idents) => {
-                            idents.last().map(|ident| ident.value.clone())
-                        }
+                        Expr::Identifier(ident) => Some(ident.real_value()),
+                        Expr::CompoundIdentifier(idents) => idents.last().map(Ident::real_value),
                         _ => None,
                     };
                     let expr = self.bind_expr(expr)?;
@@ -127,14 +125,15 @@ impl Binder {
                     aliases.push(alias);
                 }
                 SelectItem::ExprWithAlias { expr, alias } => {
-                    check_valid_column_name(&alias.value)?;
+                    let alias = alias.real_value();
+                    check_valid_column_name(&alias)?;
 
                     let expr = self.bind_expr(expr)?;
                     select_list.push(expr);
-                    aliases.push(Some(alias.value));
+                    aliases.push(Some(alias));
                 }
                 SelectItem::QualifiedWildcard(obj_name) => {
-                    let table_name = &obj_name.0.last().unwrap().value;
+                    let table_name = &obj_name.0.last().unwrap().real_value();
                     let (begin, end) = self.context.range_of.get(table_name).ok_or_else(|| {
                         ErrorCode::ItemNotFound(format!("relation \"{}\"", table_name))
                     })?;
@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
-use risingwave_sqlparser::ast::SetExpr;
+use risingwave_sqlparser::ast::{SetExpr, SetOperator};
 
 use crate::binder::{Binder, BoundSelect, BoundValues};
 
@@ -24,6 +25,31 @@ use crate::binder::{Binder, BoundSelect, BoundValues};
 pub enum BoundSetExpr {
     Select(Box<BoundSelect>),
     Values(Box<BoundValues>),
+    /// `INTERSECT` or `EXCEPT` with set semantics, or `UNION ALL`. The output names and types
+    /// follow `left`.
+    SetOperation {
+        op: BoundSetOperation,
+        left: Box<BoundSetExpr>,
+        right: Box<BoundSetExpr>,
+    },
+}
+
+/// The set operators supported by [`BoundSetExpr::SetOperation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundSetOperation {
+    Intersect,
+    Except,
+    UnionAll,
+}
+
+impl std::fmt::Display for BoundSetOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoundSetOperation::Intersect => write!(f, "INTERSECT"),
+            BoundSetOperation::Except => write!(f, "EXCEPT"),
+            BoundSetOperation::UnionAll => write!(f, "UNION"),
+        }
+    }
 }
 
 impl BoundSetExpr {
@@ -32,6 +58,7 @@ impl BoundSetExpr {
         match self {
             BoundSetExpr::Select(s) => s.names(),
             BoundSetExpr::Values(v) => v.schema.fields().iter().map(|f| f.name.clone()).collect(),
+            BoundSetExpr::SetOperation { left, .. } => left.names(),
         }
     }
 
@@ -45,6 +72,7 @@ impl BoundSetExpr {
                 .iter()
                 .map(|f| f.data_type.clone())
                 .collect(),
+            BoundSetExpr::SetOperation { left, .. } => left.data_types(),
         }
     }
 
@@ -52,6 +80,9 @@ impl BoundSetExpr {
         match self {
             BoundSetExpr::Select(s) => s.is_correlated(),
             BoundSetExpr::Values(_) => false,
+            BoundSetExpr::SetOperation { left, right, .. } => {
+                left.is_correlated() || right.is_correlated()
+            }
         }
     }
 }
@@ -61,7 +92,73 @@ impl Binder {
         match set_expr {
             SetExpr::Select(s) => Ok(BoundSetExpr::Select(Box::new(self.bind_select(*s)?))),
             SetExpr::Values(v) => Ok(BoundSetExpr::Values(Box::new(self.bind_values(v, None)?))),
+            SetExpr::SetOperation {
+                op: op @ (SetOperator::Intersect | SetOperator::Except),
+                all: false,
+                left,
+                right,
+            } => {
+                let op = match op {
+                    SetOperator::Intersect => BoundSetOperation::Intersect,
+                    _ => BoundSetOperation::Except,
+                };
+                self.bind_set_operation(op, *left, *right)
+            }
+            SetExpr::SetOperation {
+                op: SetOperator::Union,
+                all: true,
+                left,
+                right,
+            } => self.bind_set_operation(BoundSetOperation::UnionAll, *left, *right),
             _ => Err(ErrorCode::NotImplemented(format!("{:?}", set_expr), None.into()).into()),
         }
     }
+
+    /// Bind both sides of a set operation. Each side has its own
+    /// [`BindContext`](super::BindContext) so that the tables of one side are invisible to the
+    /// other, while the upper contexts are shared so that both sides may still be correlated
+    /// with an outer query.
+    fn bind_set_operation(
+        &mut self,
+        op: BoundSetOperation,
+        left: SetExpr,
+        right: SetExpr,
+    ) -> Result<BoundSetExpr> {
+        let left = self.bind_set_expr_in_new_context(left)?;
+        let right = self.bind_set_expr_in_new_context(right)?;
+
+        let left_types = left.data_types();
+        let right_types = right.data_types();
+        if left_types.len() != right_types.len() {
+            return Err(ErrorCode::BindError(format!(
+                "each {} query must have the same number of columns",
+                op
+            ))
+            .into());
+        }
+        if let Some((l, r)) = left_types
+            .iter()
+            .zip_eq(right_types.iter())
+            .find(|(l, r)| l != r)
+        {
+            return Err(ErrorCode::BindError(format!(
+                "{} types {:?} and {:?} cannot be matched",
+                op, l, r
+            ))
+            .into());
+        }
+
+        Ok(BoundSetExpr::SetOperation {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        })
+    }
+
+    fn bind_set_expr_in_new_context(&mut self, set_expr: SetExpr) -> Result<BoundSetExpr> {
+        let old_context = std::mem::take(&mut self.context);
+        let result = self.bind_set_expr(set_expr);
+        self.context = old_context;
+        result
+    }
 }
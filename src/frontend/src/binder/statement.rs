@@ -32,8 +32,10 @@ impl Binder {
                 table_name,
                 columns,
                 source,
+                on_conflict,
             } => Ok(BoundStatement::Insert(
-                self.bind_insert(table_name, columns, *source)?.into(),
+                self.bind_insert(table_name, columns, *source, on_conflict)?
+                    .into(),
             )),
 
             Statement::Delete {
@@ -0,0 +1,400 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use itertools::Itertools;
+use risingwave_common::catalog::Field;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{DataType, NaiveDateTimeWrapper, ScalarImpl};
+use risingwave_pb::common::WorkerNode;
+use risingwave_pb::meta::event::EventType;
+use risingwave_pb::meta::slow_barrier::CollectTime;
+use risingwave_pb::meta::table_fragments::fragment::{FragmentDistributionType, FragmentType};
+use risingwave_pb::meta::table_fragments::{ActorState, Fragment as ProstFragment};
+use risingwave_pb::meta::{
+    Event as ProstEvent, SlowBarrier, TableFragments as ProstTableFragments, TableWriteStats,
+};
+use risingwave_pb::stream_plan::DispatcherType;
+use risingwave_sqlparser::ast::TableAlias;
+
+use super::Binder;
+use crate::catalog::CatalogError;
+use crate::expr::{ExprImpl, Literal};
+use crate::scheduler::slow_query_log::SlowQueryRecord;
+
+/// The schema of system tables maintained by RisingWave.
+pub const RW_CATALOG_SCHEMA_NAME: &str = "rw_catalog";
+
+/// The table of recent cluster events, e.g. actor failures, source parse failures and recoveries.
+pub const RW_EVENTS_TABLE_NAME: &str = "rw_events";
+
+/// The table of fragments of all streaming jobs.
+pub const RW_FRAGMENTS_TABLE_NAME: &str = "rw_fragments";
+
+/// The table of actors of all streaming jobs, with the worker nodes they are scheduled on.
+pub const RW_ACTORS_TABLE_NAME: &str = "rw_actors";
+
+/// The table of bytes written to the state store by each materialized view.
+pub const RW_TABLE_WRITE_STATS_TABLE_NAME: &str = "rw_table_write_stats";
+
+/// The table of the latest slow batch queries served by this frontend, including failed ones.
+pub const RW_SLOW_QUERIES_TABLE_NAME: &str = "rw_slow_queries";
+
+/// The table of the latest barriers which took longer than the slow barrier threshold.
+pub const RW_SLOW_BARRIERS_TABLE_NAME: &str = "rw_slow_barriers";
+
+/// Whether `sql` may refer to a system table whose rows are fetched from meta, so that the
+/// [`StreamingRuntimeInfo`] has to be fetched before binding. It may return false positives.
+pub fn may_refer_to_runtime_table(sql: &str) -> bool {
+    let sql = sql.to_lowercase();
+    sql.contains(RW_FRAGMENTS_TABLE_NAME)
+        || sql.contains(RW_ACTORS_TABLE_NAME)
+        || sql.contains(RW_TABLE_WRITE_STATS_TABLE_NAME)
+        || sql.contains(RW_SLOW_BARRIERS_TABLE_NAME)
+}
+
+/// Whether `sql` may refer to a system table, whose rows are bound into the plan. It may return
+/// false positives.
+pub fn may_refer_to_system_table(sql: &str) -> bool {
+    sql.to_lowercase().contains(RW_CATALOG_SCHEMA_NAME)
+}
+
+/// The runtime state of streaming jobs, fetched from meta for `rw_fragments`, `rw_actors`,
+/// `rw_table_write_stats` and `rw_slow_barriers`.
+#[derive(Debug, Default)]
+pub struct StreamingRuntimeInfo {
+    pub table_fragments: Vec<ProstTableFragments>,
+    pub workers: Vec<WorkerNode>,
+    pub table_write_stats: Vec<TableWriteStats>,
+    pub slow_barriers: Vec<SlowBarrier>,
+}
+
+/// A system table whose rows are materialized from the frontend's in-memory state at bind time.
+#[derive(Debug)]
+pub struct BoundSystemTable {
+    pub name: String, // explain-only
+    pub fields: Vec<Field>,
+    pub rows: Vec<Vec<ExprImpl>>,
+}
+
+impl BoundSystemTable {
+    /// The columns of `rw_catalog.rw_events`.
+    pub fn rw_events_fields() -> Vec<Field> {
+        vec![
+            Field::with_name(DataType::Timestamp, "timestamp"),
+            Field::with_name(DataType::Varchar, "event_type"),
+            Field::with_name(DataType::Int32, "object_id"),
+            Field::with_name(DataType::Varchar, "node"),
+            Field::with_name(DataType::Varchar, "info"),
+        ]
+    }
+
+    fn rw_events_row(event: &ProstEvent) -> Result<Vec<ExprImpl>> {
+        let literal =
+            |v: ScalarImpl, ty: DataType| -> ExprImpl { Literal::new(Some(v), ty).into() };
+        let timestamp = NaiveDateTimeWrapper::from_protobuf((event.timestamp_ms * 1000) as i64)?;
+        let event_type = match event.event_type() {
+            EventType::Unspecified => "UNSPECIFIED",
+            EventType::ActorFailure => "ACTOR_FAILURE",
+            EventType::SourceParseFailure => "SOURCE_PARSE_FAILURE",
+            EventType::SinkDeliveryFailure => "SINK_DELIVERY_FAILURE",
+            EventType::Recovery => "RECOVERY",
+            EventType::SourcePollFailure => "SOURCE_POLL_FAILURE",
+        };
+        Ok(vec![
+            literal(ScalarImpl::NaiveDateTime(timestamp), DataType::Timestamp),
+            literal(ScalarImpl::Utf8(event_type.to_string()), DataType::Varchar),
+            literal(ScalarImpl::Int32(event.object_id as i32), DataType::Int32),
+            literal(ScalarImpl::Utf8(event.node.clone()), DataType::Varchar),
+            literal(ScalarImpl::Utf8(event.info.clone()), DataType::Varchar),
+        ])
+    }
+
+    /// The columns of `rw_catalog.rw_fragments`.
+    pub fn rw_fragments_fields() -> Vec<Field> {
+        vec![
+            Field::with_name(DataType::Int32, "fragment_id"),
+            Field::with_name(DataType::Int32, "table_id"),
+            Field::with_name(DataType::Varchar, "fragment_type"),
+            Field::with_name(DataType::Varchar, "distribution_type"),
+            Field::with_name(DataType::Int32, "parallelism"),
+        ]
+    }
+
+    fn rw_fragments_rows(info: &StreamingRuntimeInfo) -> Vec<Vec<ExprImpl>> {
+        info.table_fragments
+            .iter()
+            .flat_map(|tf| {
+                tf.fragments
+                    .values()
+                    .map(|fragment| Self::rw_fragments_row(tf.table_id, fragment))
+            })
+            .collect()
+    }
+
+    fn rw_fragments_row(table_id: u32, fragment: &ProstFragment) -> Vec<ExprImpl> {
+        let fragment_type = match fragment.fragment_type() {
+            FragmentType::Source => "SOURCE",
+            FragmentType::Sink => "SINK",
+            FragmentType::Others => "OTHERS",
+        };
+        let distribution_type = match fragment.distribution_type() {
+            FragmentDistributionType::Single => "SINGLE",
+            FragmentDistributionType::Hash => "HASH",
+        };
+        vec![
+            int32_literal(fragment.fragment_id),
+            int32_literal(table_id),
+            varchar_literal(fragment_type),
+            varchar_literal(distribution_type),
+            int32_literal(fragment.actors.len() as u32),
+        ]
+    }
+
+    /// The columns of `rw_catalog.rw_actors`.
+    pub fn rw_actors_fields() -> Vec<Field> {
+        vec![
+            Field::with_name(DataType::Int32, "actor_id"),
+            Field::with_name(DataType::Int32, "fragment_id"),
+            Field::with_name(DataType::Int32, "table_id"),
+            Field::with_name(DataType::Int32, "worker_id"),
+            Field::with_name(DataType::Varchar, "worker_host"),
+            Field::with_name(DataType::Varchar, "dispatcher_type"),
+            Field::with_name(DataType::Varchar, "state"),
+        ]
+    }
+
+    fn rw_actors_rows(info: &StreamingRuntimeInfo) -> Vec<Vec<ExprImpl>> {
+        let mut rows = vec![];
+        for tf in &info.table_fragments {
+            for fragment in tf.fragments.values() {
+                for actor in &fragment.actors {
+                    let status = tf.actor_status.get(&actor.actor_id);
+                    let worker_id = status.map(|s| s.node_id);
+                    let worker_host = worker_id
+                        .and_then(|id| info.workers.iter().find(|w| w.id == id))
+                        .and_then(|w| w.host.as_ref())
+                        .map(|h| format!("{}:{}", h.host, h.port));
+                    // An actor without downstream, e.g. the one of a materialize fragment, has no
+                    // dispatcher.
+                    let dispatcher_type = actor
+                        .dispatcher
+                        .iter()
+                        .map(|d| match d.get_type() {
+                            Ok(DispatcherType::Hash) => "HASH",
+                            Ok(DispatcherType::Broadcast) => "BROADCAST",
+                            Ok(DispatcherType::Simple) => "SIMPLE",
+                            Ok(DispatcherType::NoShuffle) => "NO_SHUFFLE",
+                            Ok(DispatcherType::Invalid) | Err(_) => "INVALID",
+                        })
+                        .join(",");
+                    let state = status.map(|s| match s.state() {
+                        ActorState::Inactive => "INACTIVE",
+                        ActorState::Running => "RUNNING",
+                    });
+                    rows.push(vec![
+                        int32_literal(actor.actor_id),
+                        int32_literal(fragment.fragment_id),
+                        int32_literal(tf.table_id),
+                        worker_id.map_or_else(|| null_literal(DataType::Int32), int32_literal),
+                        worker_host.map_or_else(
+                            || null_literal(DataType::Varchar),
+                            |h| varchar_literal(&h),
+                        ),
+                        varchar_literal(&dispatcher_type),
+                        state.map_or_else(|| null_literal(DataType::Varchar), varchar_literal),
+                    ]);
+                }
+            }
+        }
+        rows
+    }
+
+    /// The columns of `rw_catalog.rw_table_write_stats`.
+    pub fn rw_table_write_stats_fields() -> Vec<Field> {
+        vec![
+            Field::with_name(DataType::Int32, "table_id"),
+            Field::with_name(DataType::Int64, "total_bytes"),
+            Field::with_name(DataType::Int64, "last_epoch"),
+            Field::with_name(DataType::Int64, "last_epoch_bytes"),
+        ]
+    }
+
+    fn rw_table_write_stats_rows(info: &StreamingRuntimeInfo) -> Vec<Vec<ExprImpl>> {
+        info.table_write_stats
+            .iter()
+            .map(|stats| {
+                vec![
+                    int32_literal(stats.table_id),
+                    int64_literal(stats.total_bytes),
+                    int64_literal(stats.last_epoch),
+                    int64_literal(stats.last_epoch_bytes),
+                ]
+            })
+            .collect()
+    }
+
+    /// The columns of `rw_catalog.rw_slow_barriers`.
+    pub fn rw_slow_barriers_fields() -> Vec<Field> {
+        vec![
+            Field::with_name(DataType::Int64, "prev_epoch"),
+            Field::with_name(DataType::Int64, "curr_epoch"),
+            Field::with_name(DataType::Varchar, "command"),
+            Field::with_name(DataType::Int64, "duration_ms"),
+            Field::with_name(DataType::Varchar, "slowest_nodes"),
+            Field::with_name(DataType::Varchar, "slowest_actors"),
+        ]
+    }
+
+    fn rw_slow_barriers_rows(info: &StreamingRuntimeInfo) -> Vec<Vec<ExprImpl>> {
+        // Formatted as `id: duration`, slowest first.
+        let collect_times = |times: &[CollectTime]| {
+            times
+                .iter()
+                .map(|t| format!("{}: {}ms", t.id, t.duration_us / 1000))
+                .join(", ")
+        };
+        info.slow_barriers
+            .iter()
+            .map(|barrier| {
+                vec![
+                    int64_literal(barrier.prev_epoch),
+                    int64_literal(barrier.curr_epoch),
+                    varchar_literal(&barrier.command),
+                    int64_literal(barrier.duration_us / 1000),
+                    varchar_literal(&collect_times(&barrier.slowest_nodes)),
+                    varchar_literal(&collect_times(&barrier.slowest_actors)),
+                ]
+            })
+            .collect()
+    }
+
+    /// The columns of `rw_catalog.rw_slow_queries`.
+    pub fn rw_slow_queries_fields() -> Vec<Field> {
+        vec![
+            Field::with_name(DataType::Varchar, "sql"),
+            Field::with_name(DataType::Varchar, "plan_fingerprint"),
+            Field::with_name(DataType::Int64, "row_count"),
+            Field::with_name(DataType::Int64, "duration_ms"),
+            Field::with_name(DataType::Varchar, "stage_timings"),
+            Field::with_name(DataType::Varchar, "error"),
+        ]
+    }
+
+    fn rw_slow_queries_row(record: &SlowQueryRecord) -> Vec<ExprImpl> {
+        let stage_timings = record
+            .stage_timings
+            .iter()
+            .map(|(stage, duration)| format!("{}: {}ms", stage, duration.as_millis()))
+            .join(", ");
+        vec![
+            varchar_literal(&record.sql),
+            varchar_literal(&format!("{:016x}", record.plan_fingerprint)),
+            int64_literal(record.row_count as u64),
+            int64_literal(record.duration.as_millis() as u64),
+            varchar_literal(&stage_timings),
+            record
+                .error
+                .as_deref()
+                .map_or_else(|| null_literal(DataType::Varchar), varchar_literal),
+        ]
+    }
+}
+
+fn int32_literal(v: u32) -> ExprImpl {
+    Literal::new(Some(ScalarImpl::Int32(v as i32)), DataType::Int32).into()
+}
+
+fn int64_literal(v: u64) -> ExprImpl {
+    Literal::new(Some(ScalarImpl::Int64(v as i64)), DataType::Int64).into()
+}
+
+fn varchar_literal(v: &str) -> ExprImpl {
+    Literal::new(Some(ScalarImpl::Utf8(v.to_string())), DataType::Varchar).into()
+}
+
+fn null_literal(ty: DataType) -> ExprImpl {
+    Literal::new(None, ty).into()
+}
+
+impl Binder {
+    pub(super) fn bind_system_table(
+        &mut self,
+        table_name: &str,
+        alias: Option<TableAlias>,
+    ) -> Result<BoundSystemTable> {
+        let (fields, rows) = match table_name {
+            RW_EVENTS_TABLE_NAME => (
+                BoundSystemTable::rw_events_fields(),
+                self.catalog
+                    .events()
+                    .map(BoundSystemTable::rw_events_row)
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            RW_FRAGMENTS_TABLE_NAME => (
+                BoundSystemTable::rw_fragments_fields(),
+                self.runtime_info
+                    .as_ref()
+                    .map(BoundSystemTable::rw_fragments_rows)
+                    .unwrap_or_default(),
+            ),
+            RW_ACTORS_TABLE_NAME => (
+                BoundSystemTable::rw_actors_fields(),
+                self.runtime_info
+                    .as_ref()
+                    .map(BoundSystemTable::rw_actors_rows)
+                    .unwrap_or_default(),
+            ),
+            RW_TABLE_WRITE_STATS_TABLE_NAME => (
+                BoundSystemTable::rw_table_write_stats_fields(),
+                self.runtime_info
+                    .as_ref()
+                    .map(BoundSystemTable::rw_table_write_stats_rows)
+                    .unwrap_or_default(),
+            ),
+            RW_SLOW_BARRIERS_TABLE_NAME => (
+                BoundSystemTable::rw_slow_barriers_fields(),
+                self.runtime_info
+                    .as_ref()
+                    .map(BoundSystemTable::rw_slow_barriers_rows)
+                    .unwrap_or_default(),
+            ),
+            RW_SLOW_QUERIES_TABLE_NAME => (
+                BoundSystemTable::rw_slow_queries_fields(),
+                self.slow_queries
+                    .iter()
+                    .map(BoundSystemTable::rw_slow_queries_row)
+                    .collect(),
+            ),
+            _ => {
+                return Err(RwError::from(CatalogError::NotFound(
+                    "system table",
+                    table_name.to_string(),
+                )))
+            }
+        };
+
+        self.bind_context(
+            fields
+                .iter()
+                .map(|f| (f.name.clone(), f.data_type.clone(), false)),
+            table_name.to_string(),
+            alias,
+        )?;
+
+        Ok(BoundSystemTable {
+            name: table_name.to_string(),
+            fields,
+            rows,
+        })
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use risingwave_common::error::ErrorCode;
+use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::{FunctionArg, TableAlias};
+
+use super::{Binder, Result};
+use crate::expr::{Expr, ExprImpl, ExprType, FunctionCall};
+
+/// A table function in the `FROM` clause, e.g. `unnest(t.arr)`.
+///
+/// Its arguments may refer to the columns of the relations on its left in the same `FROM` clause,
+/// which are bound as `InputRef`s to the left side of the join.
+#[derive(Debug)]
+pub struct BoundTableFunction {
+    pub(crate) expr: ExprImpl,
+}
+
+impl BoundTableFunction {
+    /// Whether the arguments refer to the relations on the left.
+    pub fn is_lateral(&self) -> bool {
+        self.expr.has_input_ref()
+    }
+}
+
+impl Binder {
+    pub(super) fn bind_table_function(
+        &mut self,
+        args: Vec<FunctionArg>,
+        alias: Option<TableAlias>,
+    ) -> Result<BoundTableFunction> {
+        let args: Vec<ExprImpl> = args
+            .into_iter()
+            .map(|arg| self.bind_function_arg(arg))
+            .flatten_ok()
+            .try_collect()?;
+        let [arg]: [ExprImpl; 1] = args
+            .try_into()
+            .map_err(|_| ErrorCode::BindError("unnest expects exactly 1 argument".to_string()))?;
+        if arg.has_subquery() || arg.has_correlated_input_ref() {
+            return Err(ErrorCode::NotImplemented(
+                "subqueries or outer columns in the argument of unnest".to_string(),
+                None.into(),
+            )
+            .into());
+        }
+        let element_type = match arg.return_type() {
+            DataType::List { datatype } => *datatype,
+            t => {
+                return Err(ErrorCode::BindError(format!(
+                    "the argument of unnest should be an array, not type {:?}",
+                    t
+                ))
+                .into())
+            }
+        };
+
+        self.bind_context(
+            [("unnest".to_string(), element_type.clone(), false)],
+            "unnest".to_string(),
+            alias,
+        )?;
+
+        let expr =
+            FunctionCall::new_with_return_type(ExprType::Unnest, vec![arg], element_type).into();
+        Ok(BoundTableFunction { expr })
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::ErrorCode;
+use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::{Expr, Ident};
+
+use super::{Binder, Result};
+use crate::catalog::column_catalog::ColumnCatalog;
+use crate::catalog::EVENT_TIME_COLUMN_NAME;
+use crate::expr::{Expr as _, ExprImpl};
+
+impl Binder {
+    /// Binds `WATERMARK FOR <column> AS <expr>` against the columns of a source being created.
+    /// Returns the index of the event time column and the watermark expression.
+    pub fn bind_watermark(
+        &mut self,
+        source_name: String,
+        columns: &[ColumnCatalog],
+        column: Ident,
+        expr: Expr,
+    ) -> Result<(usize, ExprImpl)> {
+        self.bind_context(
+            columns
+                .iter()
+                .map(|c| (c.name().to_string(), c.data_type().clone(), c.is_hidden)),
+            source_name,
+            None,
+        )?;
+
+        let column = column.real_value();
+        let watermark_idx = columns
+            .iter()
+            .position(|c| {
+                (!c.is_hidden || c.name() == EVENT_TIME_COLUMN_NAME) && c.name() == column
+            })
+            .ok_or_else(|| {
+                ErrorCode::BindError(format!(
+                    "column \"{}\" of WATERMARK FOR does not exist",
+                    column
+                ))
+            })?;
+        let event_time_type = columns[watermark_idx].data_type();
+
+        let expr = self.bind_expr(expr)?;
+        if expr.has_agg_call() || expr.has_subquery() {
+            return Err(ErrorCode::BindError(
+                "aggregations and subqueries are not allowed in WATERMARK FOR".to_string(),
+            )
+            .into());
+        }
+        if expr.return_type() != *event_time_type {
+            return Err(ErrorCode::BindError(format!(
+                "the watermark of column \"{}\" should be {:?}, but got {:?}",
+                column,
+                event_time_type,
+                expr.return_type()
+            ))
+            .into());
+        }
+
+        Ok((watermark_idx, expr))
+    }
+
+    /// Binds `EVENT TIME AS <expr>` against the columns of a source being created. The expression
+    /// should return a timestamp.
+    pub fn bind_event_time(
+        &mut self,
+        source_name: String,
+        columns: &[ColumnCatalog],
+        expr: Expr,
+    ) -> Result<ExprImpl> {
+        self.bind_context(
+            columns
+                .iter()
+                .map(|c| (c.name().to_string(), c.data_type().clone(), c.is_hidden)),
+            source_name,
+            None,
+        )?;
+
+        let expr = self.bind_expr(expr)?;
+        if expr.has_agg_call() || expr.has_subquery() {
+            return Err(ErrorCode::BindError(
+                "aggregations and subqueries are not allowed in EVENT TIME".to_string(),
+            )
+            .into());
+        }
+        if expr.return_type() != DataType::Timestamp {
+            return Err(ErrorCode::BindError(format!(
+                "the event time should be {:?}, but got {:?}",
+                DataType::Timestamp,
+                expr.return_type()
+            ))
+            .into());
+        }
+
+        Ok(expr)
+    }
+}
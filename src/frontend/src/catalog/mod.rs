@@ -15,6 +15,7 @@
 use risingwave_common::catalog::ColumnDesc;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::Ident;
 use thiserror::Error;
 pub(crate) mod catalog_service;
 
@@ -58,6 +59,9 @@ pub fn is_row_id_column_name(name: &str) -> bool {
 
 pub const TABLE_SOURCE_PK_COLID: ColumnId = ColumnId::new(0);
 
+/// The name of the hidden column filled by the `EVENT TIME AS` clause of a source.
+pub const EVENT_TIME_COLUMN_NAME: &str = "_event_time";
+
 /// Creates a row ID column (for implicit primary key).
 pub fn row_id_column_desc() -> ColumnDesc {
     ColumnDesc {
@@ -69,11 +73,13 @@ pub fn row_id_column_desc() -> ColumnDesc {
     }
 }
 
+/// The names in the messages are quoted if necessary, so that they can be referred to in SQL as
+/// they are.
 #[derive(Error, Debug)]
 pub enum CatalogError {
-    #[error("{0} not found: {1}")]
+    #[error("{0} not found: {}", Ident::from_real_value(.1))]
     NotFound(&'static str, String),
-    #[error("{0} with name {1} exists")]
+    #[error("{0} with name {} exists", Ident::from_real_value(.1))]
     Duplicated(&'static str, String),
 }
 
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use itertools::Itertools;
 use risingwave_common::catalog::{CatalogVersion, TableId};
@@ -20,6 +20,7 @@ use risingwave_common::error::Result;
 use risingwave_pb::catalog::{
     Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
 };
+use risingwave_pb::meta::Event as ProstEvent;
 
 use super::source_catalog::SourceCatalog;
 use super::{CatalogError, SourceId};
@@ -42,6 +43,9 @@ pub struct Catalog {
     version: CatalogVersion,
     database_by_name: HashMap<String, DatabaseCatalog>,
     db_name_by_id: HashMap<DatabaseId, String>,
+    /// Recent cluster events pushed by meta, exposed as `rw_catalog.rw_events`.
+    events: VecDeque<ProstEvent>,
+    event_log_capacity: usize,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -51,6 +55,8 @@ impl Default for Catalog {
             version: 0,
             database_by_name: HashMap::new(),
             db_name_by_id: HashMap::new(),
+            events: VecDeque::new(),
+            event_log_capacity: 0,
         }
     }
 }
@@ -64,6 +70,32 @@ impl Catalog {
     pub fn clear(&mut self) {
         self.database_by_name.clear();
         self.db_name_by_id.clear();
+        self.events.clear();
+    }
+
+    /// Replace the event log with the snapshot from meta.
+    pub fn reset_events(&mut self, capacity: usize, events: Vec<ProstEvent>) {
+        self.event_log_capacity = capacity;
+        self.events = events.into();
+        while self.events.len() > capacity {
+            self.events.pop_front();
+        }
+    }
+
+    /// Append an event, evicting the oldest ones like meta does.
+    pub fn add_event(&mut self, event: ProstEvent) {
+        if self.event_log_capacity == 0 {
+            return;
+        }
+        while self.events.len() >= self.event_log_capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Recent cluster events, from the oldest to the latest.
+    pub fn events(&self) -> impl Iterator<Item = &ProstEvent> {
+        self.events.iter()
     }
 
     pub fn create_database(&mut self, db: ProstDatabase) {
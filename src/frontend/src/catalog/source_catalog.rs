@@ -13,7 +13,7 @@
 // limitations under the License.
 use itertools::Itertools;
 use risingwave_pb::catalog::source::Info;
-use risingwave_pb::catalog::Source as ProstSource;
+use risingwave_pb::catalog::{EventTimeDesc, Source as ProstSource, WatermarkDesc};
 use risingwave_pb::stream_plan::source_node::SourceType;
 
 use super::column_catalog::ColumnCatalog;
@@ -27,6 +27,10 @@ pub struct SourceCatalog {
     pub columns: Vec<ColumnCatalog>,
     pub pk_col_ids: Vec<ColumnId>,
     pub source_type: SourceType,
+    /// The `WATERMARK FOR` clause of the source, whose column index refers to `columns`.
+    pub watermark_desc: Option<WatermarkDesc>,
+    /// The `EVENT TIME AS` clause of the source, whose column index refers to `columns`.
+    pub event_time_desc: Option<EventTimeDesc>,
 }
 
 impl SourceCatalog {
@@ -56,23 +60,28 @@ impl From<&ProstSource> for SourceCatalog {
     fn from(prost: &ProstSource) -> Self {
         let id = prost.id;
         let name = prost.name.clone();
-        let (source_type, prost_columns, pk_col_ids) = match &prost.info {
-            Some(Info::StreamSource(source)) => (
-                SourceType::Source,
-                source.columns.clone(),
-                source
-                    .pk_column_ids
-                    .iter()
-                    .map(|id| ColumnId::new(*id))
-                    .collect(),
-            ),
-            Some(Info::TableSource(source)) => (
-                SourceType::Table,
-                source.columns.clone(),
-                vec![TABLE_SOURCE_PK_COLID],
-            ),
-            None => unreachable!(),
-        };
+        let (source_type, prost_columns, pk_col_ids, watermark_desc, event_time_desc) =
+            match &prost.info {
+                Some(Info::StreamSource(source)) => (
+                    SourceType::Source,
+                    source.columns.clone(),
+                    source
+                        .pk_column_ids
+                        .iter()
+                        .map(|id| ColumnId::new(*id))
+                        .collect(),
+                    source.watermark_desc.clone(),
+                    source.event_time_desc.clone(),
+                ),
+                Some(Info::TableSource(source)) => (
+                    SourceType::Table,
+                    source.columns.clone(),
+                    vec![TABLE_SOURCE_PK_COLID],
+                    source.watermark_desc.clone(),
+                    None,
+                ),
+                None => unreachable!(),
+            };
         let columns = prost_columns.into_iter().map(ColumnCatalog::from).collect();
         Self {
             id,
@@ -80,6 +89,8 @@ impl From<&ProstSource> for SourceCatalog {
             columns,
             pk_col_ids,
             source_type,
+            watermark_desc,
+            event_time_desc,
         }
     }
 }
@@ -18,12 +18,23 @@ use risingwave_common::types::DataType;
 use risingwave_expr::expr::AggKind;
 
 use super::{Expr, ExprImpl};
+use crate::optimizer::property::Direction;
+
+/// An `ORDER BY` item inside an aggregate call, e.g. `b DESC` in `string_agg(a, ',' ORDER BY b
+/// DESC)`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AggOrderByExpr {
+    pub expr: ExprImpl,
+    pub direction: Direction,
+}
 
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct AggCall {
     agg_kind: AggKind,
     return_type: DataType,
     inputs: Vec<ExprImpl>,
+    distinct: bool,
+    order_by: Vec<AggOrderByExpr>,
 }
 
 impl std::fmt::Debug for AggCall {
@@ -33,12 +44,27 @@ impl std::fmt::Debug for AggCall {
                 .field("agg_kind", &self.agg_kind)
                 .field("return_type", &self.return_type)
                 .field("inputs", &self.inputs)
+                .field("distinct", &self.distinct)
+                .field("order_by", &self.order_by)
                 .finish()
         } else {
             let mut builder = f.debug_tuple(&format!("{}", self.agg_kind));
-            self.inputs.iter().for_each(|child| {
-                builder.field(child);
-            });
+            if self.distinct {
+                builder.field(&format_args!(
+                    "distinct {}",
+                    self.inputs
+                        .iter()
+                        .map(|child| format!("{:?}", child))
+                        .join(", ")
+                ));
+            } else {
+                self.inputs.iter().for_each(|child| {
+                    builder.field(child);
+                });
+            }
+            if !self.order_by.is_empty() {
+                builder.field(&self.order_by);
+            }
             builder.finish()
         }
     }
@@ -70,6 +96,24 @@ impl AggCall {
                 _ => return None,
             },
             (AggKind::Count, _) => DataType::Int64,
+            (AggKind::StringAgg, [DataType::Varchar, DataType::Varchar]) => DataType::Varchar,
+            (AggKind::StringAgg, _) => return None,
+            (
+                AggKind::BitAnd | AggKind::BitOr,
+                [input @ (DataType::Int16 | DataType::Int32 | DataType::Int64 | DataType::Varbit)],
+            ) => input.clone(),
+            (AggKind::BitAnd | AggKind::BitOr, _) => return None,
+            // The time-series aggregations are aligned with timescaledb, see
+            // https://docs.timescale.com/api/latest/hyperfunctions/.
+            (AggKind::First | AggKind::Last, [value, time]) if Self::is_time_type(time) => {
+                value.clone()
+            }
+            (AggKind::TimeWeightedAvg, [value, time])
+                if value.is_numeric() && Self::is_time_type(time) =>
+            {
+                DataType::Float64
+            }
+            (AggKind::First | AggKind::Last | AggKind::TimeWeightedAvg, _) => return None,
             (other_kind, other_inputs) => {
                 todo!(
                     "Unsupported aggregate function: {:?} with {} inputs",
@@ -81,9 +125,34 @@ impl AggCall {
         Some(return_type)
     }
 
+    /// Whether `data_type` can be the time argument of time-series aggregations like
+    /// `first(value, time)`.
+    fn is_time_type(data_type: &DataType) -> bool {
+        matches!(
+            data_type,
+            DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::Date
+                | DataType::Timestamp
+                | DataType::Timestampz
+        )
+    }
+
     /// Returns error if the function name matches with an existing function
     /// but with illegal arguments.
     pub fn new(agg_kind: AggKind, inputs: Vec<ExprImpl>) -> Result<Self> {
+        Self::new_with_clauses(agg_kind, inputs, false, vec![])
+    }
+
+    /// Same as [`AggCall::new`], with `DISTINCT` and `ORDER BY` clauses inside the aggregate
+    /// call.
+    pub fn new_with_clauses(
+        agg_kind: AggKind,
+        inputs: Vec<ExprImpl>,
+        distinct: bool,
+        order_by: Vec<AggOrderByExpr>,
+    ) -> Result<Self> {
         // TODO(TaoWu): Add arguments validator.
         let data_types = inputs.iter().map(ExprImpl::return_type).collect_vec();
         let return_type = Self::infer_return_type(&agg_kind, &data_types).ok_or_else(|| {
@@ -97,11 +166,13 @@ impl AggCall {
             agg_kind,
             return_type,
             inputs,
+            distinct,
+            order_by,
         })
     }
 
-    pub fn decompose(self) -> (AggKind, Vec<ExprImpl>) {
-        (self.agg_kind, self.inputs)
+    pub fn decompose(self) -> (AggKind, Vec<ExprImpl>, bool, Vec<AggOrderByExpr>) {
+        (self.agg_kind, self.inputs, self.distinct, self.order_by)
     }
 
     pub fn agg_kind(&self) -> AggKind {
@@ -112,6 +183,16 @@ impl AggCall {
     pub fn inputs(&self) -> &[ExprImpl] {
         self.inputs.as_ref()
     }
+
+    /// Whether the agg call only takes distinct values of its inputs.
+    pub fn distinct(&self) -> bool {
+        self.distinct
+    }
+
+    /// Get a reference to the agg call's order by items.
+    pub fn order_by(&self) -> &[AggOrderByExpr] {
+        self.order_by.as_ref()
+    }
 }
 impl Expr for AggCall {
     fn return_type(&self) -> DataType {
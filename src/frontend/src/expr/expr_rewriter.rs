@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{AggCall, CorrelatedInputRef, ExprImpl, FunctionCall, InputRef, Literal, Subquery};
+use super::{
+    AggCall, AggOrderByExpr, CorrelatedInputRef, ExprImpl, FunctionCall, InputRef, Literal,
+    Subquery,
+};
 
 /// By default, `ExprRewriter` simply traverses the expression tree and leaves nodes unchanged.
 /// Implementations can override a subset of methods and perform transformation on some particular
@@ -37,12 +40,21 @@ pub trait ExprRewriter {
         FunctionCall::new_with_return_type(func_type, inputs, ret).into()
     }
     fn rewrite_agg_call(&mut self, agg_call: AggCall) -> ExprImpl {
-        let (func_type, inputs) = agg_call.decompose();
+        let (func_type, inputs, distinct, order_by) = agg_call.decompose();
         let inputs = inputs
             .into_iter()
             .map(|expr| self.rewrite_expr(expr))
             .collect();
-        AggCall::new(func_type, inputs).unwrap().into()
+        let order_by = order_by
+            .into_iter()
+            .map(|AggOrderByExpr { expr, direction }| AggOrderByExpr {
+                expr: self.rewrite_expr(expr),
+                direction,
+            })
+            .collect();
+        AggCall::new_with_clauses(func_type, inputs, distinct, order_by)
+            .unwrap()
+            .into()
     }
     fn rewrite_literal(&mut self, literal: Literal) -> ExprImpl {
         literal.into()
@@ -43,7 +43,11 @@ pub trait ExprVisitor {
         agg_call
             .inputs()
             .iter()
-            .for_each(|expr| self.visit_expr(expr))
+            .for_each(|expr| self.visit_expr(expr));
+        agg_call
+            .order_by()
+            .iter()
+            .for_each(|o| self.visit_expr(&o.expr))
     }
     fn visit_literal(&mut self, _: &Literal) {}
     fn visit_input_ref(&mut self, _: &InputRef) {}
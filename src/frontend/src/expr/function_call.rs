@@ -71,6 +71,11 @@ impl std::fmt::Debug for FunctionCall {
                 ExprType::GreaterThanOrEqual => debug_binary_op(f, ">=", &self.inputs),
                 ExprType::And => debug_binary_op(f, "AND", &self.inputs),
                 ExprType::Or => debug_binary_op(f, "OR", &self.inputs),
+                ExprType::BitwiseAnd => debug_binary_op(f, "&", &self.inputs),
+                ExprType::BitwiseOr => debug_binary_op(f, "|", &self.inputs),
+                ExprType::BitwiseXor => debug_binary_op(f, "#", &self.inputs),
+                ExprType::BitwiseShiftLeft => debug_binary_op(f, "<<", &self.inputs),
+                ExprType::BitwiseShiftRight => debug_binary_op(f, ">>", &self.inputs),
                 _ => {
                     let func_name = format!("{:?}", self.func_type);
                     let mut builder = f.debug_tuple(&func_name);
@@ -169,6 +174,18 @@ impl FunctionCall {
     pub fn inputs(&self) -> &[ExprImpl] {
         self.inputs.as_ref()
     }
+
+    /// Whether the function is volatile, i.e. it may return different results for the same
+    /// inputs. See [`ExprImpl::is_volatile`].
+    pub fn is_volatile(&self) -> bool {
+        matches!(self.func_type, ExprType::MonotonicId | ExprType::Proctime)
+    }
+
+    /// Whether the function has a state that must be checkpointed by the streaming executor
+    /// evaluating it.
+    pub fn is_stateful(&self) -> bool {
+        self.func_type == ExprType::MonotonicId
+    }
 }
 impl Expr for FunctionCall {
     fn return_type(&self) -> DataType {
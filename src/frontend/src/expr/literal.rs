@@ -33,9 +33,11 @@ impl std::fmt::Debug for Literal {
         } else {
             match &self.data {
                 None => write!(f, "null"),
-                // Add single quotation marks for string and interval literals
+                // Add single quotation marks for string, bytea, bit string and interval literals
                 Some(ScalarImpl::Utf8(v)) => write!(f, "'{}'", v),
                 Some(ScalarImpl::Interval(v)) => write!(f, "'{}'", v),
+                Some(v @ ScalarImpl::Bytea(_)) => write!(f, "'{}'", v),
+                Some(ScalarImpl::BitString(v)) => write!(f, "B'{}'", v),
                 Some(v) => write!(f, "{}", v),
             }?;
             write!(f, ":{:?}", self.data_type)
@@ -86,11 +88,14 @@ fn literal_to_protobuf(d: &Datum) -> Option<RexNode> {
         ScalarImpl::Float32(v) => v.to_be_bytes().to_vec(),
         ScalarImpl::Float64(v) => v.to_be_bytes().to_vec(),
         ScalarImpl::Utf8(s) => s.as_bytes().to_vec(),
+        ScalarImpl::Bytea(v) => v.to_vec(),
+        ScalarImpl::BitString(v) => v.to_string().into_bytes(),
         ScalarImpl::Bool(v) => (*v as i8).to_be_bytes().to_vec(),
         ScalarImpl::Decimal(v) => v.to_string().as_bytes().to_vec(),
         ScalarImpl::Interval(v) => v.to_protobuf_owned(),
         ScalarImpl::NaiveDate(_) => todo!(),
-        ScalarImpl::NaiveDateTime(_) => todo!(),
+        // Microseconds since epoch, the same as `NaiveDateTimeWrapper::to_protobuf`.
+        ScalarImpl::NaiveDateTime(v) => (v.0.timestamp_nanos() / 1000).to_be_bytes().to_vec(),
         ScalarImpl::NaiveTime(_) => todo!(),
         ScalarImpl::Struct(_) => todo!(),
         ScalarImpl::List(_) => todo!(),
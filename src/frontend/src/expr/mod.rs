@@ -32,7 +32,7 @@ mod expr_visitor;
 mod type_inference;
 mod utils;
 
-pub use agg_call::AggCall;
+pub use agg_call::{AggCall, AggOrderByExpr};
 pub use correlated_input_ref::CorrelatedInputRef;
 pub use function_call::FunctionCall;
 pub use input_ref::{as_alias_display, input_ref_to_column_indices, InputRef, InputRefDisplay};
@@ -163,9 +163,15 @@ impl ExprImpl {
             }
 
             fn visit_subquery(&mut self, subquery: &Subquery) {
+                self.visit_set_expr(&subquery.query.body);
+            }
+        }
+
+        impl Has {
+            fn visit_set_expr(&mut self, set_expr: &crate::binder::BoundSetExpr) {
                 use crate::binder::BoundSetExpr;
 
-                match &subquery.query.body {
+                match set_expr {
                     BoundSetExpr::Select(select) => select
                         .select_items
                         .iter()
@@ -173,7 +179,36 @@ impl ExprImpl {
                         .chain(select.where_clause.iter())
                         .for_each(|expr| self.visit_expr(expr)),
                     BoundSetExpr::Values(_) => {}
+                    BoundSetExpr::SetOperation { left, right, .. } => {
+                        self.visit_set_expr(left);
+                        self.visit_set_expr(right);
+                    }
+                }
+            }
+        }
+
+        let mut visitor = Has { has: false };
+        visitor.visit_expr(self);
+        visitor.has
+    }
+
+    /// Whether the expression calls a volatile function, e.g. `proctime()` or `monotonic_id()`.
+    /// Their results depend on when and how many times they are evaluated, so such an expression
+    /// must not be duplicated, or evaluated before a filter which is written above it.
+    pub fn is_volatile(&self) -> bool {
+        struct Has {
+            has: bool,
+        }
+
+        impl ExprVisitor for Has {
+            fn visit_function_call(&mut self, func_call: &FunctionCall) {
+                if func_call.is_volatile() {
+                    self.has = true;
                 }
+                func_call
+                    .inputs()
+                    .iter()
+                    .for_each(|expr| self.visit_expr(expr));
             }
         }
 
@@ -43,6 +43,8 @@ enum DataTypeName {
     Interval,
     Struct,
     List,
+    Bytea,
+    Varbit,
 }
 
 fn name_of(ty: &DataType) -> DataTypeName {
@@ -62,6 +64,8 @@ fn name_of(ty: &DataType) -> DataTypeName {
         DataType::Interval => DataTypeName::Interval,
         DataType::Struct { .. } => DataTypeName::Struct,
         DataType::List { .. } => DataTypeName::List,
+        DataType::Bytea => DataTypeName::Bytea,
+        DataType::Varbit => DataTypeName::Varbit,
     }
 }
 
@@ -91,6 +95,8 @@ pub fn infer_type(func_type: ExprType, inputs_type: Vec<DataType>) -> Option<Dat
         DataTypeName::List => DataType::List {
             datatype: Box::new(DataType::Int32),
         },
+        DataTypeName::Bytea => DataType::Bytea,
+        DataTypeName::Varbit => DataType::Varbit,
     })
 }
 
@@ -232,7 +238,10 @@ fn build_type_derive_map() -> HashMap<FuncSign, DataTypeName> {
         T::Interval,
         T::Date,
         T::Timestampz,
+        T::Bytea,
+        T::Varbit,
     ];
+    let int_types = vec![T::Int16, T::Int32, T::Int64];
     let str_types = vec![T::Varchar];
     let atm_exprs = vec![E::Add, E::Subtract, E::Multiply, E::Divide, E::Modulus];
     let cmp_exprs = vec![
@@ -293,6 +302,32 @@ fn build_type_derive_map() -> HashMap<FuncSign, DataTypeName> {
         T::Interval,
     );
 
+    // Bitwise operators
+    let bitwise_exprs = vec![E::BitwiseAnd, E::BitwiseOr, E::BitwiseXor];
+    for (expr, t1, t2) in iproduct!(bitwise_exprs.clone(), int_types.clone(), int_types.clone()) {
+        map.insert(
+            FuncSign::new_binary(expr, t1, t2),
+            arithmetic_type_derive(t1, t2),
+        );
+    }
+    build_binary_funcs(
+        &mut map,
+        &bitwise_exprs,
+        &[T::Varbit],
+        &[T::Varbit],
+        T::Varbit,
+    );
+    for t in int_types.iter().copied().chain([T::Varbit]) {
+        map.insert(FuncSign::new_unary(E::BitwiseNot, t), t);
+        build_binary_funcs(
+            &mut map,
+            &[E::BitwiseShiftLeft, E::BitwiseShiftRight],
+            &[t],
+            &[T::Int32],
+            t,
+        );
+    }
+
     build_binary_funcs(
         &mut map,
         &[E::And, E::Or],
@@ -336,6 +371,8 @@ fn build_type_derive_map() -> HashMap<FuncSign, DataTypeName> {
         T::Varchar,
     );
     build_binary_funcs(&mut map, &[E::Like], &str_types, &str_types, T::Boolean);
+    build_binary_funcs(&mut map, &[E::Encode], &[T::Bytea], &str_types, T::Varchar);
+    build_binary_funcs(&mut map, &[E::Decode], &str_types, &str_types, T::Bytea);
     build_ternary_funcs(
         &mut map,
         &[E::Replace],
@@ -365,6 +402,8 @@ fn build_type_derive_map() -> HashMap<FuncSign, DataTypeName> {
         &[T::Interval],
         T::Timestamp,
     );
+    map.insert(FuncSign::new_no_input(E::MonotonicId), T::Int64);
+    map.insert(FuncSign::new_no_input(E::Proctime), T::Timestamp);
     map
 }
 
@@ -437,6 +476,7 @@ fn build_cast_map() -> HashMap<(DataTypeName, DataTypeName), CastContext> {
     // As we do not support size parameters in types, there are no sizing casts.
     m.insert((T::Boolean, T::Boolean), CastContext::Explicit);
     m.insert((T::Varchar, T::Varchar), CastContext::Explicit);
+    m.insert((T::Varbit, T::Varbit), CastContext::Explicit);
 
     // Casting to and from string type.
     for t in [
@@ -452,6 +492,8 @@ fn build_cast_map() -> HashMap<(DataTypeName, DataTypeName), CastContext> {
         T::Timestampz,
         T::Time,
         T::Interval,
+        T::Bytea,
+        T::Varbit,
     ] {
         m.insert((t, T::Varchar), CastContext::Assign);
         // Casting from string is explicit-only in PG.
@@ -565,6 +607,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_bitwise() {
+        use DataType::*;
+        let bitwise_exprs = vec![
+            ExprType::BitwiseAnd,
+            ExprType::BitwiseOr,
+            ExprType::BitwiseXor,
+        ];
+        for expr in bitwise_exprs {
+            test_simple_infer_type(expr, vec![Int16, Int16], Int16);
+            test_simple_infer_type(expr, vec![Int16, Int64], Int64);
+            test_simple_infer_type(expr, vec![Int32, Int16], Int32);
+            test_simple_infer_type(expr, vec![Varbit, Varbit], Varbit);
+            test_infer_type_not_exist(expr, vec![Float64, Int32]);
+            test_infer_type_not_exist(expr, vec![Varbit, Int32]);
+        }
+        for t in [Int16, Int32, Int64, Varbit] {
+            test_simple_infer_type(ExprType::BitwiseNot, vec![t.clone()], t.clone());
+            test_simple_infer_type(
+                ExprType::BitwiseShiftLeft,
+                vec![t.clone(), Int32],
+                t.clone(),
+            );
+            test_simple_infer_type(ExprType::BitwiseShiftRight, vec![t.clone(), Int32], t);
+        }
+        test_infer_type_not_exist(ExprType::BitwiseNot, vec![Decimal]);
+    }
+
     #[test]
     fn test_bool_num_not_exist() {
         let exprs = vec![
@@ -0,0 +1,30 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result};
+
+use crate::session::OptimizerContext;
+
+pub(super) async fn handle_cancel_job(
+    context: OptimizerContext,
+    job_id: u64,
+) -> Result<PgResponse> {
+    let table_id = u32::try_from(job_id)
+        .map_err(|_| ErrorCode::InvalidInputSyntax(format!("invalid job id {}", job_id)))?;
+    let client = context.session_ctx.env().meta_client();
+    client.cancel_job(table_id).await?;
+
+    Ok(PgResponse::empty_result(StatementType::CANCEL_JOB))
+}
@@ -12,12 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::ErrorCode::InvalidInputSyntax;
 use risingwave_common::error::Result;
 use risingwave_pb::catalog::Table as ProstTable;
-use risingwave_sqlparser::ast::{ObjectName, Query};
+use risingwave_sqlparser::ast::{ObjectName, Query, SqlOption};
 
+use super::create_source::handle_with_properties;
 use crate::binder::Binder;
+use crate::optimizer::plan_node::{
+    BROADCAST_JOIN_MAX_ROWS, DELTA_JOIN, MINI_BATCH_INTERVAL_MS, MINI_BATCH_MAX_ROWS,
+    SNAPSHOT_ONLY, TWO_PHASE_AGG,
+};
 use crate::optimizer::property::Distribution;
 use crate::optimizer::PlanRef;
 use crate::planner::Planner;
@@ -54,12 +62,42 @@ pub fn gen_create_mv_plan(
     Ok((plan, table))
 }
 
+/// Check the options in the `WITH` clause of `CREATE MATERIALIZED VIEW`.
+fn check_with_options(with_options: &HashMap<String, String>) -> Result<()> {
+    for key in [
+        MINI_BATCH_MAX_ROWS,
+        MINI_BATCH_INTERVAL_MS,
+        BROADCAST_JOIN_MAX_ROWS,
+    ] {
+        if let Some(value) = with_options.get(key) && value.parse::<u32>().is_err() {
+            return Err(InvalidInputSyntax(format!(
+                "invalid value for option \"{}\": {}",
+                key, value
+            ))
+            .into());
+        }
+    }
+    for key in [SNAPSHOT_ONLY, TWO_PHASE_AGG, DELTA_JOIN] {
+        if let Some(value) = with_options.get(key) && value.parse::<bool>().is_err() {
+            return Err(InvalidInputSyntax(format!(
+                "invalid value for option \"{}\": {}",
+                key, value
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
 pub async fn handle_create_mv(
-    context: OptimizerContext,
+    mut context: OptimizerContext,
     name: ObjectName,
     query: Box<Query>,
+    with_options: Vec<SqlOption>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
+    context.with_options = handle_with_properties(with_options)?;
+    check_with_options(&context.with_options)?;
 
     let (table, stream_plan) = {
         let (plan, table) = gen_create_mv_plan(&session, context.into(), query, name)?;
@@ -149,4 +187,86 @@ pub mod tests {
         };
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_create_mv_with_mini_batch() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int)")
+            .await
+            .unwrap();
+
+        let sql = "create materialized view mv1 with (mini_batch_max_rows = '1024') \
+                   as select v1, count(*) from t group by v1";
+        frontend.run_sql(sql).await.unwrap();
+
+        let sql = "create materialized view mv2 with (mini_batch_max_rows = 'abc') \
+                   as select v1, count(*) from t group by v1";
+        assert!(frontend.run_sql(sql).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_mv_with_two_phase_agg() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int)")
+            .await
+            .unwrap();
+
+        let rows = frontend
+            .query_formatted_result(
+                "explain create materialized view mv1 with (two_phase_agg = 'true') \
+                 as select v1, count(*), sum(v2) from t group by v1",
+            )
+            .await;
+        let output = rows.join("\n");
+        assert!(output.contains("StreamLocalHashAgg"), "{}", output);
+
+        let rows = frontend
+            .query_formatted_result(
+                "explain create materialized view mv1 as \
+                 select v1, count(*), sum(v2) from t group by v1",
+            )
+            .await;
+        let output = rows.join("\n");
+        assert!(!output.contains("StreamLocalHashAgg"), "{}", output);
+
+        let sql = "create materialized view mv2 with (two_phase_agg = 'abc') \
+                   as select v1, count(*) from t group by v1";
+        assert!(frontend.run_sql(sql).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_mv_with_delta_join() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        for table in ["a", "b", "c"] {
+            frontend
+                .run_sql(format!("create table {} (k int, v int)", table))
+                .await
+                .unwrap();
+        }
+
+        let rows = frontend
+            .query_formatted_result(
+                "explain create materialized view mv1 with (delta_join = 'true') as \
+                 select a.v, b.v, c.v from a join b on a.k = b.k join c on b.v = c.k",
+            )
+            .await;
+        let output = rows.join("\n");
+        assert_eq!(output.matches("StreamDeltaJoin").count(), 2, "{}", output);
+
+        // Outer joins are kept as hash joins.
+        let rows = frontend
+            .query_formatted_result(
+                "explain create materialized view mv1 with (delta_join = 'true') as \
+                 select a.v, b.v from a left join b on a.k = b.k",
+            )
+            .await;
+        let output = rows.join("\n");
+        assert!(!output.contains("StreamDeltaJoin"), "{}", output);
+
+        let sql = "create materialized view mv2 with (delta_join = 'abc') \
+                   as select a.v from a join b on a.k = b.k";
+        assert!(frontend.run_sql(sql).await.is_err());
+    }
 }
@@ -16,21 +16,30 @@ use std::collections::HashMap;
 
 use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::ErrorCode::ProtocolError;
+use risingwave_common::catalog::{ColumnDesc, ColumnId};
+use risingwave_common::error::ErrorCode::{self, ProtocolError};
 use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::DataType;
 use risingwave_pb::catalog::source::Info;
-use risingwave_pb::catalog::{Source as ProstSource, StreamSourceInfo};
+use risingwave_pb::catalog::{
+    EventTimeDesc, SchemaRegistryRef, Source as ProstSource, StreamSourceInfo,
+};
 use risingwave_pb::plan::{ColumnCatalog as ProstColumnCatalog, RowFormatType};
-use risingwave_source::ProtobufParser;
+use risingwave_source::{AvroParser, ProtobufParser};
 use risingwave_sqlparser::ast::{
-    CreateSourceStatement, ObjectName, ProtobufSchema, SourceSchema, SqlOption, Value,
+    AvroSchema, CreateSourceStatement, ObjectName, ProtobufSchema, SourceSchema, SqlOption,
+    TableConstraint, Value,
 };
 
-use super::create_table::{bind_sql_columns, gen_materialized_source_plan};
+use super::create_table::{bind_sql_columns, bind_sql_watermark, gen_materialized_source_plan};
 use crate::binder::Binder;
 use crate::catalog::column_catalog::ColumnCatalog;
+use crate::catalog::EVENT_TIME_COLUMN_NAME;
+use crate::expr::Expr as _;
 use crate::session::{OptimizerContext, SessionImpl};
 
+const KAFKA_TOPIC_KEY: &str = "kafka.topic";
+
 pub(crate) fn make_prost_source(
     session: &SessionImpl,
     name: ObjectName,
@@ -67,7 +76,71 @@ fn extract_protobuf_table_schema(schema: &ProtobufSchema) -> Result<Vec<ProstCol
         .collect_vec())
 }
 
-fn handle_source_with_properties(options: Vec<SqlOption>) -> Result<HashMap<String, String>> {
+/// Map an avro schema to a relational schema.
+fn extract_avro_table_schema(schema: &AvroSchema) -> Result<Vec<ProstColumnCatalog>> {
+    let parser = AvroParser::new(&schema.row_schema_location.0)?;
+    let column_descs = parser.map_to_columns()?;
+
+    Ok(column_descs
+        .into_iter()
+        .map(|col| ProstColumnCatalog {
+            column_desc: Some(col),
+            is_hidden: false,
+        })
+        .collect_vec())
+}
+
+/// Map the latest protobuf or avro schema of the source's topic in a Confluent schema registry to
+/// a relational schema. The value schema of the topic is looked up under the subject
+/// `<topic>-value`, i.e. the default `TopicNameStrategy`.
+async fn extract_schema_registry_table_schema(
+    schema: &SourceSchema,
+    properties: &HashMap<String, String>,
+) -> Result<(Vec<ProstColumnCatalog>, SchemaRegistryRef)> {
+    let topic = properties.get(KAFKA_TOPIC_KEY).ok_or_else(|| {
+        RwError::from(ProtocolError(format!(
+            "{} must be specified to use a schema registry",
+            KAFKA_TOPIC_KEY
+        )))
+    })?;
+    let subject = format!("{}-value", topic);
+    let (url, column_descs, subject_schema) = match schema {
+        SourceSchema::Protobuf(schema) => {
+            let url = &schema.row_schema_location.0;
+            let (parser, subject_schema) =
+                ProtobufParser::new_from_schema_registry(url, &subject, &schema.message_name.0)
+                    .await?;
+            (url, parser.map_to_columns()?, subject_schema)
+        }
+        SourceSchema::Avro(schema) => {
+            let url = &schema.row_schema_location.0;
+            let (parser, subject_schema) =
+                AvroParser::new_from_schema_registry(url, &subject).await?;
+            (url, parser.map_to_columns()?, subject_schema)
+        }
+        SourceSchema::Json | SourceSchema::Csv => {
+            unreachable!("schema registry is not used by json or csv sources")
+        }
+    };
+
+    let columns = column_descs
+        .into_iter()
+        .map(|col| ProstColumnCatalog {
+            column_desc: Some(col),
+            is_hidden: false,
+        })
+        .collect_vec();
+    let registry = SchemaRegistryRef {
+        url: url.clone(),
+        subject,
+        schema_id: subject_schema.id,
+        version: subject_schema.version,
+    };
+    Ok((columns, registry))
+}
+
+/// Convert the options in a `WITH` clause to a map. Only single-quoted string values are allowed.
+pub(crate) fn handle_with_properties(options: Vec<SqlOption>) -> Result<HashMap<String, String>> {
     options
         .into_iter()
         .map(|x| match x.value {
@@ -79,40 +152,179 @@ fn handle_source_with_properties(options: Vec<SqlOption>) -> Result<HashMap<Stri
         .collect()
 }
 
+/// Binds the `EVENT TIME AS` clause declared in CREATE SOURCE, if any, and appends the hidden
+/// column filled by it to `columns`.
+fn bind_sql_event_time(
+    session: &SessionImpl,
+    source_name: &ObjectName,
+    columns: &mut Vec<ProstColumnCatalog>,
+    constraints: &[TableConstraint],
+) -> Result<Option<EventTimeDesc>> {
+    let mut exprs = constraints.iter().filter_map(|c| match c {
+        TableConstraint::EventTime { expr } => Some(expr),
+        _ => None,
+    });
+    let expr = match exprs.next() {
+        Some(expr) => expr.as_ref().clone(),
+        None => return Ok(None),
+    };
+    if exprs.next().is_some() {
+        return Err(
+            ErrorCode::BindError("only one EVENT TIME is allowed in a source".to_string()).into(),
+        );
+    }
+
+    let catalogs = columns
+        .iter()
+        .cloned()
+        .map(ColumnCatalog::from)
+        .collect_vec();
+    if catalogs.iter().any(|c| c.name() == EVENT_TIME_COLUMN_NAME) {
+        return Err(ErrorCode::BindError(format!(
+            "column name \"{}\" is reserved for EVENT TIME",
+            EVENT_TIME_COLUMN_NAME
+        ))
+        .into());
+    }
+    let mut binder = Binder::new(
+        session.env().catalog_reader().read_guard(),
+        session.database().to_string(),
+    );
+    let (_, source_name) = Binder::resolve_table_name(source_name.clone())?;
+    let expr = binder.bind_event_time(source_name, &catalogs, expr)?;
+
+    let column_id = catalogs
+        .iter()
+        .map(|c| c.column_id().get_id())
+        .max()
+        .unwrap_or(-1)
+        + 1;
+    let event_time_column = ColumnCatalog {
+        column_desc: ColumnDesc {
+            data_type: DataType::Timestamp,
+            column_id: ColumnId::new(column_id),
+            name: EVENT_TIME_COLUMN_NAME.to_string(),
+            field_descs: vec![],
+            type_name: "".to_string(),
+        },
+        is_hidden: true,
+    };
+    columns.push(event_time_column.to_protobuf());
+    Ok(Some(EventTimeDesc {
+        event_time_idx: (columns.len() - 1) as u32,
+        expr: Some(expr.to_protobuf()),
+    }))
+}
+
+/// The columns of protobuf and avro sources are flattened when queried, which would shift the
+/// column indices in the watermark and event time expressions, so they're rejected for now.
+fn check_no_time_constraints(constraints: &[TableConstraint], row_format: &str) -> Result<()> {
+    if let Some(clause) = constraints.iter().find_map(|c| match c {
+        TableConstraint::Watermark { .. } => Some("WATERMARK FOR"),
+        TableConstraint::EventTime { .. } => Some("EVENT TIME"),
+        _ => None,
+    }) {
+        return Err(ErrorCode::NotImplemented(
+            format!("{} on sources with {} row format", clause, row_format),
+            None.into(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
 pub async fn handle_create_source(
     context: OptimizerContext,
     is_materialized: bool,
     stmt: CreateSourceStatement,
 ) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
     let source = match &stmt.source_schema {
         SourceSchema::Protobuf(protobuf_schema) => {
+            check_no_time_constraints(&stmt.constraints, "protobuf")?;
+            let properties = handle_with_properties(stmt.with_properties.0)?;
             let mut columns = vec![ColumnCatalog::row_id_column().to_protobuf()];
-            columns.extend(extract_protobuf_table_schema(protobuf_schema)?.into_iter());
+            let (row_schema_location, schema_registry) = if protobuf_schema.use_schema_registry {
+                let (table_schema, registry) =
+                    extract_schema_registry_table_schema(&stmt.source_schema, &properties).await?;
+                columns.extend(table_schema.into_iter());
+                ("".to_string(), Some(registry))
+            } else {
+                columns.extend(extract_protobuf_table_schema(protobuf_schema)?.into_iter());
+                (protobuf_schema.row_schema_location.0.clone(), None)
+            };
             StreamSourceInfo {
-                properties: handle_source_with_properties(stmt.with_properties.0)?,
+                properties,
                 row_format: RowFormatType::Protobuf as i32,
-                row_schema_location: protobuf_schema.row_schema_location.0.clone(),
+                row_schema_location,
                 row_id_index: 0,
                 columns,
                 pk_column_ids: vec![0],
+                watermark_desc: None,
+                schema_registry,
+                event_time_desc: None,
+            }
+        }
+        SourceSchema::Avro(avro_schema) => {
+            check_no_time_constraints(&stmt.constraints, "avro")?;
+            let properties = handle_with_properties(stmt.with_properties.0)?;
+            let mut columns = vec![ColumnCatalog::row_id_column().to_protobuf()];
+            let (row_schema_location, schema_registry) = if avro_schema.use_schema_registry {
+                let (table_schema, registry) =
+                    extract_schema_registry_table_schema(&stmt.source_schema, &properties).await?;
+                columns.extend(table_schema.into_iter());
+                ("".to_string(), Some(registry))
+            } else {
+                columns.extend(extract_avro_table_schema(avro_schema)?.into_iter());
+                (avro_schema.row_schema_location.0.clone(), None)
+            };
+            StreamSourceInfo {
+                properties,
+                row_format: RowFormatType::Avro as i32,
+                row_schema_location,
+                row_id_index: 0,
+                columns,
+                pk_column_ids: vec![0],
+                watermark_desc: None,
+                schema_registry,
+                event_time_desc: None,
+            }
+        }
+        SourceSchema::Json | SourceSchema::Csv => {
+            let mut columns = bind_sql_columns(stmt.columns)?;
+            // The event time column may be used in `WATERMARK FOR`, so it's bound first.
+            let event_time_desc =
+                bind_sql_event_time(&session, &stmt.source_name, &mut columns, &stmt.constraints)?;
+            let watermark_desc = bind_sql_watermark(
+                &session,
+                &stmt.source_name,
+                &columns,
+                stmt.constraints,
+                &stmt.with_properties.0,
+            )?;
+            StreamSourceInfo {
+                properties: handle_with_properties(stmt.with_properties.0)?,
+                row_format: if matches!(stmt.source_schema, SourceSchema::Csv) {
+                    RowFormatType::Csv as i32
+                } else {
+                    RowFormatType::Json as i32
+                },
+                row_schema_location: "".to_string(),
+                row_id_index: 0,
+                columns,
+                pk_column_ids: vec![0],
+                watermark_desc,
+                schema_registry: None,
+                event_time_desc,
             }
         }
-        SourceSchema::Json => StreamSourceInfo {
-            properties: handle_source_with_properties(stmt.with_properties.0)?,
-            row_format: RowFormatType::Json as i32,
-            row_schema_location: "".to_string(),
-            row_id_index: 0,
-            columns: bind_sql_columns(stmt.columns)?,
-            pk_column_ids: vec![0],
-        },
     };
 
-    let session = context.session_ctx.clone();
     let source = make_prost_source(&session, stmt.source_name, Info::StreamSource(source))?;
     let catalog_writer = session.env().catalog_writer();
     if is_materialized {
         let (plan, table) = {
-            let (plan, table) = gen_materialized_source_plan(context.into(), source.clone())?;
+            let (plan, table) = gen_materialized_source_plan(context.into(), source.clone(), None)?;
             let plan = plan.to_stream_prost();
             (plan, table)
         };
@@ -189,4 +401,31 @@ pub mod tests {
         };
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_create_source_with_event_time() {
+        let sql = "create source s (v1 int, ts timestamp, \
+            event time as ts + interval '1' hour, \
+            watermark for _event_time as _event_time - interval '5' second) row format json;";
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "s")
+            .unwrap()
+            .clone();
+        // The hidden event time column comes after the hidden row id column and user columns.
+        let event_time_desc = source.event_time_desc.unwrap();
+        assert_eq!(event_time_desc.event_time_idx, 3);
+        assert!(event_time_desc.expr.is_some());
+        assert!(source.columns[3].is_hidden);
+        assert_eq!(source.columns[3].data_type(), &DataType::Timestamp);
+        assert_eq!(source.watermark_desc.unwrap().watermark_idx, 3);
+
+        let sql = "create source s2 (v1 int, event time as v1) row format json;";
+        assert!(frontend.run_sql(sql).await.is_err());
+    }
 }
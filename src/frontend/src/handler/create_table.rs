@@ -18,21 +18,39 @@ use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::catalog::{ColumnDesc, ColumnId};
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::DataType;
 use risingwave_pb::catalog::source::Info;
-use risingwave_pb::catalog::{Source as ProstSource, Table as ProstTable, TableSourceInfo};
+use risingwave_pb::catalog::{
+    Source as ProstSource, Table as ProstTable, TableSourceInfo, WatermarkDesc,
+};
 use risingwave_pb::plan::ColumnCatalog;
-use risingwave_sqlparser::ast::{ColumnDef, ObjectName};
+use risingwave_sqlparser::ast::{
+    ColumnDef, ColumnOption, ObjectName, SqlOption, TableConstraint, Value,
+};
 
 use super::create_source::make_prost_source;
 use crate::binder::expr::bind_data_type;
+use crate::binder::Binder;
 use crate::catalog::{check_valid_column_name, row_id_column_desc};
-use crate::optimizer::plan_node::{LogicalSource, StreamSource};
+use crate::expr::Expr as _;
+use crate::optimizer::plan_node::{ConflictOverwrite, LogicalSource, ToStream};
 use crate::optimizer::property::{Distribution, Order};
 use crate::optimizer::{PlanRef, PlanRoot};
 use crate::session::{OptimizerContext, OptimizerContextRef, SessionImpl};
 // FIXME: store PK columns in ProstTableSourceInfo as Catalog information, and then remove this
 
+/// The option of CREATE TABLE on what to do with a row whose primary key already exists. Only
+/// `overwrite` is supported, which replaces the existing row.
+pub const ON_CONFLICT: &str = "on_conflict";
+/// The option of CREATE TABLE naming the column by which the overwriting row is chosen, see
+/// [`ConflictOverwrite`].
+pub const VERSION_COLUMN: &str = "version_column";
+const ON_CONFLICT_OVERWRITE: &str = "overwrite";
+/// The option of CREATE TABLE and CREATE SOURCE on how long in milliseconds the source can be idle
+/// before its watermark advances with the wall clock. See `WatermarkDesc::idle_timeout_ms`.
+pub const WATERMARK_IDLE_TIMEOUT_MS: &str = "watermark_idle_timeout_ms";
+
 /// Binds the column schemas declared in CREATE statement into `ColumnCatalog`.
 pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
     let column_descs = {
@@ -41,11 +59,12 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
         column_descs.push(row_id_column_desc());
         // Then user columns.
         for (i, column) in columns.into_iter().enumerate() {
-            check_valid_column_name(&column.name.value)?;
+            let name = column.name.real_value();
+            check_valid_column_name(&name)?;
             column_descs.push(ColumnDesc {
                 data_type: bind_data_type(&column.data_type)?,
                 column_id: ColumnId::new((i + 1) as i32),
-                name: column.name.value,
+                name,
                 field_descs: vec![],
                 type_name: "".to_string(),
             });
@@ -64,44 +83,244 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
     Ok(columns_catalog)
 }
 
+/// Binds the `WATERMARK FOR` clause declared in CREATE statement into `WatermarkDesc`, if any.
+pub(crate) fn bind_sql_watermark(
+    session: &SessionImpl,
+    source_name: &ObjectName,
+    columns: &[ColumnCatalog],
+    constraints: Vec<TableConstraint>,
+    with_options: &[SqlOption],
+) -> Result<Option<WatermarkDesc>> {
+    let idle_timeout_ms = match with_options
+        .iter()
+        .find(|o| o.name.real_value() == WATERMARK_IDLE_TIMEOUT_MS)
+    {
+        Some(SqlOption {
+            value: Value::SingleQuotedString(value),
+            ..
+        }) => value.parse::<u64>().map_err(|_| {
+            ErrorCode::InvalidInputSyntax(format!(
+                "{} should be a number of milliseconds, but got '{}'",
+                WATERMARK_IDLE_TIMEOUT_MS, value
+            ))
+        })?,
+        Some(_) => {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "{} only supports single quoted string value",
+                WATERMARK_IDLE_TIMEOUT_MS
+            ))
+            .into())
+        }
+        None => 0,
+    };
+
+    let mut watermarks = constraints.into_iter().filter_map(|c| match c {
+        TableConstraint::Watermark { column, expr } => Some((column, expr)),
+        _ => None,
+    });
+    let (column, expr) = match watermarks.next() {
+        Some(watermark) => watermark,
+        None if idle_timeout_ms > 0 => {
+            return Err(ErrorCode::BindError(format!(
+                "{} requires WATERMARK FOR",
+                WATERMARK_IDLE_TIMEOUT_MS
+            ))
+            .into())
+        }
+        None => return Ok(None),
+    };
+    if watermarks.next().is_some() {
+        return Err(ErrorCode::BindError(
+            "only one WATERMARK FOR is allowed in a source".to_string(),
+        )
+        .into());
+    }
+
+    let columns = columns.iter().cloned().map(Into::into).collect_vec();
+    let mut binder = Binder::new(
+        session.env().catalog_reader().read_guard(),
+        session.database().to_string(),
+    );
+    let (_, source_name) = Binder::resolve_table_name(source_name.clone())?;
+    let (watermark_idx, expr) = binder.bind_watermark(source_name, &columns, column, *expr)?;
+    // The watermark of an idle source advances by the elapsed wall-clock time.
+    if idle_timeout_ms > 0
+        && !matches!(
+            expr.return_type(),
+            DataType::Timestamp | DataType::Timestampz
+        )
+    {
+        return Err(ErrorCode::BindError(format!(
+            "{} requires the watermark to be a timestamp, but got {:?}",
+            WATERMARK_IDLE_TIMEOUT_MS,
+            expr.return_type()
+        ))
+        .into());
+    }
+    Ok(Some(WatermarkDesc {
+        watermark_idx: watermark_idx as u32,
+        expr: Some(expr.to_protobuf()),
+        idle_timeout_ms,
+    }))
+}
+
+/// Binds the `on_conflict` and `version_column` options of CREATE TABLE into how the rows are
+/// deduplicated by the `PRIMARY KEY`, if they are given. The other options are ignored.
+fn bind_sql_on_conflict(
+    columns: &[ColumnDef],
+    constraints: &[TableConstraint],
+    with_options: &[SqlOption],
+) -> Result<Option<ConflictOverwrite>> {
+    let option = |name: &str| -> Result<Option<String>> {
+        match with_options.iter().find(|o| o.name.real_value() == name) {
+            Some(SqlOption {
+                value: Value::SingleQuotedString(value),
+                ..
+            }) => Ok(Some(value.clone())),
+            Some(_) => Err(ErrorCode::InvalidInputSyntax(format!(
+                "{} only supports single quoted string value",
+                name
+            ))
+            .into()),
+            None => Ok(None),
+        }
+    };
+    let version_column = option(VERSION_COLUMN)?;
+    match option(ON_CONFLICT)? {
+        Some(on_conflict) if on_conflict.eq_ignore_ascii_case(ON_CONFLICT_OVERWRITE) => {}
+        Some(on_conflict) => {
+            return Err(ErrorCode::NotImplemented(
+                format!("{} = '{}'", ON_CONFLICT, on_conflict),
+                None.into(),
+            )
+            .into())
+        }
+        None if version_column.is_some() => {
+            return Err(ErrorCode::BindError(format!(
+                "{} requires {} = '{}'",
+                VERSION_COLUMN, ON_CONFLICT, ON_CONFLICT_OVERWRITE
+            ))
+            .into())
+        }
+        None => return Ok(None),
+    }
+
+    let mut primary_keys = constraints
+        .iter()
+        .filter_map(|c| match c {
+            TableConstraint::Unique {
+                columns,
+                is_primary: true,
+                ..
+            } => Some(columns.iter().map(|c| c.real_value()).collect_vec()),
+            _ => None,
+        })
+        .chain(columns.iter().filter_map(|c| {
+            c.options
+                .iter()
+                .any(|o| matches!(o.option, ColumnOption::Unique { is_primary: true }))
+                .then(|| vec![c.name.real_value()])
+        }));
+    let pk_names = match (primary_keys.next(), primary_keys.next()) {
+        (Some(pk_names), None) => pk_names,
+        (Some(_), Some(_)) => {
+            return Err(ErrorCode::BindError(
+                "multiple primary keys for a table are not allowed".to_string(),
+            )
+            .into())
+        }
+        (None, _) => {
+            return Err(ErrorCode::BindError(format!(
+                "{} = '{}' requires a PRIMARY KEY",
+                ON_CONFLICT, ON_CONFLICT_OVERWRITE
+            ))
+            .into())
+        }
+    };
+
+    // The hidden row id column comes first, see `bind_sql_columns`.
+    let column_index = |name: &str| -> Result<usize> {
+        columns
+            .iter()
+            .position(|c| c.name.real_value() == name)
+            .map(|idx| idx + 1)
+            .ok_or_else(|| ErrorCode::BindError(format!("column {} not found", name)).into())
+    };
+    Ok(Some(ConflictOverwrite {
+        pk_indices: pk_names
+            .iter()
+            .map(|name| column_index(name))
+            .collect::<Result<_>>()?,
+        version_column_index: version_column.map(|name| column_index(&name)).transpose()?,
+        row_id_index: 0,
+    }))
+}
+
 pub(crate) fn gen_create_table_plan(
     session: &SessionImpl,
     context: OptimizerContextRef,
     table_name: ObjectName,
     columns: Vec<ColumnDef>,
+    constraints: Vec<TableConstraint>,
+    with_options: Vec<SqlOption>,
 ) -> Result<(PlanRef, ProstSource, ProstTable)> {
+    if constraints
+        .iter()
+        .any(|c| matches!(c, TableConstraint::EventTime { .. }))
+    {
+        return Err(
+            ErrorCode::NotImplemented("EVENT TIME on tables".to_string(), None.into()).into(),
+        );
+    }
+    let overwrite = bind_sql_on_conflict(&columns, &constraints, &with_options)?;
+    let columns = bind_sql_columns(columns)?;
+    let watermark_desc =
+        bind_sql_watermark(session, &table_name, &columns, constraints, &with_options)?;
     let source = make_prost_source(
         session,
         table_name,
         Info::TableSource(TableSourceInfo {
-            columns: bind_sql_columns(columns)?,
+            columns,
+            watermark_desc,
         }),
     )?;
-    let (plan, table) = gen_materialized_source_plan(context, source.clone())?;
+    let (plan, table) = gen_materialized_source_plan(context, source.clone(), overwrite)?;
     Ok((plan, source, table))
 }
 
 /// Generate a stream plan with `StreamSource` + `StreamMaterialize`, it ressembles a
-/// `CREATE MATERIALIZED VIEW AS SELECT * FROM <source>`.
+/// `CREATE MATERIALIZED VIEW AS SELECT * FROM <source>`. The rows are deduplicated by the primary
+/// key of `overwrite` if given.
 pub(crate) fn gen_materialized_source_plan(
     context: OptimizerContextRef,
     source: ProstSource,
+    overwrite: Option<ConflictOverwrite>,
 ) -> Result<(PlanRef, ProstTable)> {
     let materialize = {
         // Manually assemble the materialization plan for the table.
         let source_node: PlanRef =
-            StreamSource::new(LogicalSource::new(Rc::new((&source).into()), context)).into();
+            LogicalSource::new(Rc::new((&source).into()), context).to_stream();
         let mut required_cols = FixedBitSet::with_capacity(source_node.schema().len());
         required_cols.toggle_range(..);
         required_cols.toggle(0);
 
-        PlanRoot::new(
+        // The rows of the same key must be materialized by the same actor to overwrite each
+        // other.
+        let dist_key = match &overwrite {
+            Some(overwrite) => overwrite.pk_indices.clone(),
+            None => vec![0],
+        };
+        let materialize = PlanRoot::new(
             source_node,
-            Distribution::HashShard(vec![0]),
+            Distribution::HashShard(dist_key),
             Order::any().clone(),
             required_cols,
         )
-        .gen_create_mv_plan(source.name.clone())?
+        .gen_create_mv_plan(source.name.clone())?;
+        match overwrite {
+            Some(overwrite) => materialize.with_conflict_overwrite(overwrite),
+            None => materialize,
+        }
     };
     let table = materialize
         .table()
@@ -114,12 +333,20 @@ pub async fn handle_create_table(
     context: OptimizerContext,
     table_name: ObjectName,
     columns: Vec<ColumnDef>,
+    constraints: Vec<TableConstraint>,
+    with_options: Vec<SqlOption>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
 
     let (plan, source, table) = {
-        let (plan, source, table) =
-            gen_create_table_plan(&session, context.into(), table_name.clone(), columns)?;
+        let (plan, source, table) = gen_create_table_plan(
+            &session,
+            context.into(),
+            table_name.clone(),
+            columns,
+            constraints,
+            with_options,
+        )?;
         let plan = plan.to_stream_prost();
 
         (plan, source, table)
@@ -192,4 +419,88 @@ mod tests {
 
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_create_table_with_watermark() {
+        let sql =
+            "create table t (v1 int, ts timestamp, watermark for ts as ts - interval '5' second);";
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap()
+            .clone();
+        let watermark_desc = source.watermark_desc.unwrap();
+        // The hidden row id column comes first.
+        assert_eq!(watermark_desc.watermark_idx, 2);
+        assert!(watermark_desc.expr.is_some());
+
+        assert_eq!(watermark_desc.idle_timeout_ms, 0);
+
+        let sql = "create table t2 (v1 int, ts timestamp, watermark for ts as v1);";
+        assert!(frontend.run_sql(sql).await.is_err());
+        let sql = "create table t3 (v1 int, watermark for ts as ts);";
+        assert!(frontend.run_sql(sql).await.is_err());
+
+        let sql = "create table t4 (v1 int, ts timestamp, watermark for ts as ts) \
+                   with (watermark_idle_timeout_ms = '5000');";
+        frontend.run_sql(sql).await.unwrap();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t4")
+            .unwrap()
+            .clone();
+        assert_eq!(source.watermark_desc.unwrap().idle_timeout_ms, 5000);
+
+        // The watermark can only advance with the wall clock if it's a timestamp.
+        let sql = "create table t5 (v1 int, watermark for v1 as v1) \
+                   with (watermark_idle_timeout_ms = '5000');";
+        assert!(frontend.run_sql(sql).await.is_err());
+        let sql = "create table t6 (v1 int) with (watermark_idle_timeout_ms = '5000');";
+        assert!(frontend.run_sql(sql).await.is_err());
+        let sql = "create table t7 (v1 int, ts timestamp, watermark for ts as ts) \
+                   with (watermark_idle_timeout_ms = '5s');";
+        assert!(frontend.run_sql(sql).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_table_on_conflict_overwrite() {
+        let sql = "create table t (id int primary key, v int, ts bigint) \
+                   with (on_conflict = 'overwrite', version_column = 'ts');";
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let table = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap()
+            .clone();
+        // The rows are keyed by the primary key instead of the hidden row id.
+        let pk_names = table
+            .pk_desc()
+            .iter()
+            .map(|c| c.column_desc.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(pk_names, vec!["id"]);
+
+        let sql = "create table t2 (id int, v int, primary key (id, v)) \
+                   with (on_conflict = 'overwrite');";
+        frontend.run_sql(sql).await.unwrap();
+
+        for sql in [
+            "create table t3 (id int, v int) with (on_conflict = 'overwrite');",
+            "create table t3 (id int primary key, v int) with (on_conflict = 'ignore');",
+            "create table t3 (id int primary key, v int) with (version_column = 'v');",
+            "create table t3 (id int primary key, v int) \
+             with (on_conflict = 'overwrite', version_column = 'ts');",
+        ] {
+            assert!(frontend.run_sql(sql).await.is_err());
+        }
+    }
 }
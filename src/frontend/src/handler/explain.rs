@@ -15,21 +15,28 @@
 use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use pgwire::pg_response::{PgResponse, StatementType};
 use pgwire::types::Row;
+use risingwave_common::error::ErrorCode::InvalidInputSyntax;
 use risingwave_common::error::Result;
 use risingwave_sqlparser::ast::Statement;
 
 use super::create_mv::gen_create_mv_plan;
+use super::create_source::handle_with_properties;
 use super::create_table::gen_create_table_plan;
 use crate::binder::Binder;
 use crate::planner::Planner;
 use crate::session::OptimizerContext;
 
 pub(super) fn handle_explain(
-    context: OptimizerContext,
+    mut context: OptimizerContext,
     stmt: Statement,
     _verbose: bool,
+    state: bool,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
+    // The options of `CREATE MATERIALIZED VIEW` may change the plan.
+    if let Statement::CreateView { with_options, .. } = &stmt {
+        context.with_options = handle_with_properties(with_options.clone())?;
+    }
     // bind, plan, optimize, and serialize here
     let mut planner = Planner::new(context.into());
 
@@ -42,8 +49,30 @@ pub(super) fn handle_explain(
             ..
         } => gen_create_mv_plan(&*session, planner.ctx(), query, name)?.0,
 
-        Statement::CreateTable { name, columns, .. } => {
-            gen_create_table_plan(&*session, planner.ctx(), name, columns)?.0
+        Statement::CreateTable {
+            name,
+            columns,
+            constraints,
+            with_options,
+            ..
+        } => {
+            gen_create_table_plan(
+                &*session,
+                planner.ctx(),
+                name,
+                columns,
+                constraints,
+                with_options,
+            )?
+            .0
+        }
+
+        stmt if state => {
+            return Err(InvalidInputSyntax(format!(
+                "EXPLAIN (STATE) only supports streaming statements, got: {}",
+                stmt
+            ))
+            .into());
         }
 
         stmt => {
@@ -59,7 +88,11 @@ pub(super) fn handle_explain(
         }
     };
 
-    let output = plan.explain_to_string()?;
+    let output = if state {
+        plan.explain_state_to_string()?
+    } else {
+        plan.explain_to_string()?
+    };
 
     let rows = output
         .lines()
@@ -76,3 +109,43 @@ pub(super) fn handle_explain(
         )],
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_explain_state() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int)")
+            .await
+            .unwrap();
+
+        let rows = frontend
+            .query_formatted_result(
+                "explain (state) create materialized view mv1 as \
+                 select v1, max(v2) from t group by v1",
+            )
+            .await;
+        let output = rows.join("\n");
+        assert!(output.contains("state: materialize"), "{}", output);
+        assert!(output.contains("state: hash_agg"), "{}", output);
+        assert!(output.contains("_max { key"), "{}", output);
+
+        let rows = frontend
+            .query_formatted_result(
+                "explain (state) create materialized view mv2 as \
+                 select v1, count(distinct v2) from t group by v1 order by v1 limit 3",
+            )
+            .await;
+        let output = rows.join("\n");
+        assert!(output.contains("_distinct { key"), "{}", output);
+        assert!(output.contains("state: top_n_higher"), "{}", output);
+
+        assert!(frontend
+            .run_sql("explain (state) select v1 from t")
+            .await
+            .is_err());
+    }
+}
@@ -20,6 +20,7 @@ use risingwave_sqlparser::ast::{DropStatement, ObjectName, ObjectType, Statement
 
 use crate::session::{OptimizerContext, SessionImpl};
 
+mod cancel_job;
 pub mod create_mv;
 pub mod create_source;
 pub mod create_table;
@@ -39,14 +40,24 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
     let context = OptimizerContext::new(session.clone());
     match stmt {
         Statement::Explain {
-            statement, verbose, ..
-        } => explain::handle_explain(context, *statement, verbose),
+            statement,
+            verbose,
+            state,
+            ..
+        } => explain::handle_explain(context, *statement, verbose, state),
         Statement::CreateSource {
             is_materialized,
             stmt,
         } => create_source::handle_create_source(context, is_materialized, stmt).await,
-        Statement::CreateTable { name, columns, .. } => {
-            create_table::handle_create_table(context, name, columns).await
+        Statement::CreateTable {
+            name,
+            columns,
+            constraints,
+            with_options,
+            ..
+        } => {
+            create_table::handle_create_table(context, name, columns, constraints, with_options)
+                .await
         }
         Statement::Describe { name } => describe::handle_describe(context, name).await,
         // TODO: support complex sql for `show columns from <table>`
@@ -79,9 +90,11 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             or_replace: false,
             name,
             query,
+            with_options,
             ..
-        } => create_mv::handle_create_mv(context, name, query).await,
+        } => create_mv::handle_create_mv(context, name, query, with_options).await,
         Statement::Flush => flush::handle_flush(context).await,
+        Statement::CancelJob { job_id } => cancel_job::handle_cancel_job(context, job_id).await,
         Statement::SetVariable {
             local: _,
             variable,
@@ -12,18 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use futures_async_stream::for_await;
 use pgwire::pg_field_descriptor::PgFieldDescriptor;
 use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::types::Row;
 use risingwave_common::error::Result;
 use risingwave_sqlparser::ast::Statement;
 use tracing::info;
 
-use crate::binder::{Binder, BoundStatement};
+use crate::binder::{
+    may_refer_to_runtime_table, may_refer_to_system_table, Binder, BoundStatement,
+    StreamingRuntimeInfo,
+};
 use crate::handler::util::{to_pg_field, to_pg_rows};
 use crate::planner::Planner;
-use crate::scheduler::plan_fragmenter::BatchPlanFragmenter;
-use crate::scheduler::{DataChunkStream, ExecutionContext, ExecutionContextRef};
+use crate::scheduler::plan_cache::{CachedPlan, PlanCacheKey};
+use crate::scheduler::plan_fragmenter::{BatchPlanFragmenter, Query};
+use crate::scheduler::slow_query_log::{plan_fingerprint, SlowQueryRecord};
+use crate::scheduler::{ExecutionContext, ExecutionContextRef};
 use crate::session::{OptimizerContext, SessionImpl};
 
 /// If `RW_IMPLICIT_FLUSH` is on, then every INSERT/UPDATE/DELETE statement will block
@@ -32,27 +41,123 @@ use crate::session::{OptimizerContext, SessionImpl};
 /// TODO: Use session config to set this.
 pub static IMPLICIT_FLUSH: &str = "RW_IMPLICIT_FLUSH";
 
+/// If `RW_ENABLE_PLAN_CACHE` is on, the plans of SELECT queries are cached and reused by the same
+/// queries until the catalog changes.
+pub static ENABLE_PLAN_CACHE: &str = "RW_ENABLE_PLAN_CACHE";
+
 pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<PgResponse> {
     let stmt_type = to_statement_type(&stmt);
     let session = context.session_ctx.clone();
-
-    let bound = {
-        let mut binder = Binder::new(
-            session.env().catalog_reader().read_guard(),
-            session.database().to_string(),
-        );
-        binder.bind(stmt)?
+    let sql = stmt.to_string();
+    let start = Instant::now();
+
+    let cache_key = plan_cache_key(&session, stmt_type, &sql);
+    let cached_plan = cache_key
+        .as_ref()
+        .and_then(|key| session.env().plan_cache().get(key));
+
+    let (query, pg_descs, fingerprint, mut stage_timings) = match cached_plan {
+        Some(plan) => (
+            plan.query.clone_with_new_id(),
+            plan.pg_descs.clone(),
+            plan.plan_fingerprint,
+            vec![("plan_cache", start.elapsed())],
+        ),
+        None => {
+            let runtime_info = if may_refer_to_runtime_table(&sql) {
+                Some(StreamingRuntimeInfo {
+                    table_fragments: session.env().meta_client().list_table_fragments().await?,
+                    workers: session.env().worker_node_manager().list_worker_nodes(),
+                    table_write_stats: session.env().meta_client().list_table_write_stats().await?,
+                    slow_barriers: session.env().meta_client().list_slow_barriers().await?,
+                })
+            } else {
+                None
+            };
+
+            let bound = {
+                let mut binder = Binder::new(
+                    session.env().catalog_reader().read_guard(),
+                    session.database().to_string(),
+                );
+                if let Some(runtime_info) = runtime_info {
+                    binder = binder.with_runtime_info(runtime_info);
+                }
+                if may_refer_to_system_table(&sql) {
+                    binder = binder.with_slow_queries(session.env().slow_query_log().records());
+                }
+                binder.bind(stmt)?
+            };
+
+            let bind_done = Instant::now();
+            let plan = gen_batch_query(context, bound)?;
+            let stage_timings = vec![("bind", bind_done - start), ("plan", bind_done.elapsed())];
+
+            let pg_descs = plan.pg_descs.clone();
+            let fingerprint = plan.plan_fingerprint;
+            let query = match cache_key {
+                Some(key) => {
+                    let query = plan.query.clone_with_new_id();
+                    session.env().plan_cache().insert(key, plan);
+                    query
+                }
+                None => plan.query,
+            };
+            (query, pg_descs, fingerprint, stage_timings)
+        }
     };
 
-    let (data_stream, pg_descs) = distribute_execute(context, bound).await?;
+    let result = execute_query(&session, query, &mut stage_timings)
+        .await
+        .map(|rows| (affected_rows(stmt_type, &rows), rows));
+
+    session.env().slow_query_log().observe(SlowQueryRecord {
+        sql,
+        plan_fingerprint: fingerprint,
+        row_count: result
+            .as_ref()
+            .map_or(0, |(rows_count, _)| *rows_count as usize),
+        duration: start.elapsed(),
+        stage_timings,
+        error: result.as_ref().err().map(|e| e.to_string()),
+    });
+    let (rows_count, rows) = result?;
 
+    if let Some(flag) = session.get_config(IMPLICIT_FLUSH) {
+        if flag.is_set(false) {
+            flush_for_write(&session, stmt_type).await?;
+        }
+    }
+
+    Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs))
+}
+
+/// Schedules the query and fetches all its rows, appending the time spent in each stage to
+/// `stage_timings`.
+async fn execute_query(
+    session: &Arc<SessionImpl>,
+    query: Query,
+    stage_timings: &mut Vec<(&'static str, Duration)>,
+) -> Result<Vec<Row>> {
+    let schedule_start = Instant::now();
+    let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
+    let query_manager = execution_context.session().env().query_manager().clone();
+    let data_stream = query_manager.schedule(execution_context, query).await?;
+    stage_timings.push(("schedule", schedule_start.elapsed()));
+
+    let fetch_start = Instant::now();
     let mut rows = vec![];
     #[for_await]
     for chunk in data_stream {
         rows.extend(to_pg_rows(chunk?));
     }
+    stage_timings.push(("fetch", fetch_start.elapsed()));
+    Ok(rows)
+}
 
-    let rows_count = match stmt_type {
+/// Returns the number of rows returned by a SELECT query, or affected by a DML statement.
+fn affected_rows(stmt_type: StatementType, rows: &[Row]) -> i32 {
+    match stmt_type {
         StatementType::SELECT => rows.len() as i32,
 
         // TODO(renjie): We need a better solution for this.
@@ -65,15 +170,7 @@ pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<
         }
 
         _ => unreachable!(),
-    };
-
-    if let Some(flag) = session.get_config(IMPLICIT_FLUSH) {
-        if flag.is_set(false) {
-            flush_for_write(&session, stmt_type).await?;
-        }
     }
-
-    Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs))
 }
 
 async fn flush_for_write(session: &SessionImpl, stmt_type: StatementType) -> Result<()> {
@@ -98,39 +195,55 @@ fn to_statement_type(stmt: &Statement) -> StatementType {
     }
 }
 
-async fn distribute_execute(
-    context: OptimizerContext,
-    stmt: BoundStatement,
-) -> Result<(impl DataChunkStream, Vec<PgFieldDescriptor>)> {
-    let session = context.session_ctx.clone();
-    // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
-    let (query, pg_descs) = {
-        let plan = Planner::new(context.into())
-            .plan(stmt)?
-            .gen_dist_batch_query_plan();
-
-        info!(
-            "Generated distributed plan: {:?}",
-            plan.explain_to_string()?
-        );
-
-        let pg_descs = plan
-            .schema()
-            .fields()
-            .iter()
-            .map(to_pg_field)
-            .collect::<Vec<PgFieldDescriptor>>();
-
-        let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
-        let query = plan_fragmenter.split(plan)?;
-        info!("Generated query after plan fragmenter: {:?}", &query);
-        (query, pg_descs)
-    };
+/// Returns the key to cache the plan of the statement, or `None` if it's not to be cached. Only
+/// SELECT queries are cached, except those on system tables whose rows are bound into the plan.
+fn plan_cache_key(
+    session: &SessionImpl,
+    stmt_type: StatementType,
+    sql: &str,
+) -> Option<PlanCacheKey> {
+    let enabled = session
+        .get_config(ENABLE_PLAN_CACHE)
+        .map_or(true, |flag| flag.is_set(true));
+    if !enabled
+        || !session.env().plan_cache().is_enabled()
+        || stmt_type != StatementType::SELECT
+        || may_refer_to_system_table(sql)
+    {
+        return None;
+    }
+    Some(PlanCacheKey {
+        database: session.database().to_string(),
+        sql: sql.to_string(),
+        catalog_version: session.env().catalog_reader().read_guard().version(),
+        worker_count: session.env().worker_node_manager().worker_node_count(),
+    })
+}
 
-    let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
-    let query_manager = execution_context.session().env().query_manager().clone();
-    Ok((
-        query_manager.schedule(execution_context, query).await?,
+/// Optimizes and fragments the bound statement into a distributed query.
+fn gen_batch_query(context: OptimizerContext, stmt: BoundStatement) -> Result<CachedPlan> {
+    let session = context.session_ctx.clone();
+    let plan = Planner::new(context.into())
+        .plan(stmt)?
+        .gen_dist_batch_query_plan();
+
+    let explain = plan.explain_to_string()?;
+    info!("Generated distributed plan: {:?}", explain);
+    let fingerprint = plan_fingerprint(&explain);
+
+    let pg_descs = plan
+        .schema()
+        .fields()
+        .iter()
+        .map(to_pg_field)
+        .collect::<Vec<PgFieldDescriptor>>();
+
+    let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
+    let query = plan_fragmenter.split(plan)?;
+    info!("Generated query after plan fragmenter: {:?}", &query);
+    Ok(CachedPlan {
+        query,
         pg_descs,
-    ))
+        plan_fingerprint: fingerprint,
+    })
 }
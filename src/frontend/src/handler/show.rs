@@ -22,23 +22,83 @@ use risingwave_sqlparser::ast::{Ident, ShowObject};
 
 use crate::session::OptimizerContext;
 
-fn schema_or_default(schema: &Option<Ident>) -> &str {
+fn schema_or_default(schema: &Option<Ident>) -> String {
     schema
         .as_ref()
-        .map_or_else(|| DEFAULT_SCHEMA_NAME, |s| &s.value)
+        .map_or_else(|| DEFAULT_SCHEMA_NAME.to_string(), Ident::real_value)
+}
+
+/// Shows the materialized views being created, with the ratio of their chain actors that have
+/// finished ingesting the snapshot.
+async fn handle_show_jobs(context: OptimizerContext) -> Result<PgResponse> {
+    let jobs = context.session_ctx.env().meta_client().list_jobs().await?;
+    let rows = jobs
+        .into_iter()
+        .sorted_by_key(|job| job.table_id)
+        .map(|job| {
+            let progress = if job.total_actors == 0 {
+                100.0
+            } else {
+                job.finished_actors as f64 * 100.0 / job.total_actors as f64
+            };
+            Row::new(vec![
+                Some(job.table_id.to_string()),
+                Some(format!("{:.2}%", progress)),
+            ])
+        })
+        .collect_vec();
+
+    Ok(PgResponse::new(
+        StatementType::SHOW_COMMAND,
+        rows.len() as i32,
+        rows,
+        vec![
+            PgFieldDescriptor::new("Id".to_owned(), TypeOid::Int),
+            PgFieldDescriptor::new("Progress".to_owned(), TypeOid::Varchar),
+        ],
+    ))
+}
+
+/// Shows the hits and misses of the plan cache shared by the sessions.
+fn handle_show_plan_cache(context: OptimizerContext) -> Result<PgResponse> {
+    let stats = context.session_ctx.env().plan_cache().stats();
+    let rows = vec![Row::new(vec![
+        Some(stats.hits.to_string()),
+        Some(stats.misses.to_string()),
+        Some(format!("{:.2}%", stats.hit_rate() * 100.0)),
+        Some(stats.entries.to_string()),
+    ])];
+
+    Ok(PgResponse::new(
+        StatementType::SHOW_COMMAND,
+        rows.len() as i32,
+        rows,
+        vec![
+            PgFieldDescriptor::new("Hits".to_owned(), TypeOid::BigInt),
+            PgFieldDescriptor::new("Misses".to_owned(), TypeOid::BigInt),
+            PgFieldDescriptor::new("Hit Rate".to_owned(), TypeOid::Varchar),
+            PgFieldDescriptor::new("Entries".to_owned(), TypeOid::BigInt),
+        ],
+    ))
 }
 
 pub async fn handle_show_object(
     context: OptimizerContext,
     command: ShowObject,
 ) -> Result<PgResponse> {
+    match command {
+        ShowObject::Jobs => return handle_show_jobs(context).await,
+        ShowObject::PlanCache => return handle_show_plan_cache(context),
+        _ => {}
+    }
+
     let session = context.session_ctx;
     let catalog_reader = session.env().catalog_reader().read_guard();
 
     let names = match command {
         // If not include schema name, use default schema name
         ShowObject::Table { schema } => catalog_reader
-            .get_schema_by_name(session.database(), schema_or_default(&schema))?
+            .get_schema_by_name(session.database(), &schema_or_default(&schema))?
             .iter_table()
             .map(|t| t.name.clone())
             .collect(),
@@ -46,20 +106,21 @@ pub async fn handle_show_object(
         ShowObject::Schema => catalog_reader.get_all_schema_names(session.database())?,
         // If not include schema name, use default schema name
         ShowObject::MaterializedView { schema } => catalog_reader
-            .get_schema_by_name(session.database(), schema_or_default(&schema))?
+            .get_schema_by_name(session.database(), &schema_or_default(&schema))?
             .iter_mv()
             .map(|t| t.name.clone())
             .collect(),
         ShowObject::Source { schema } => catalog_reader
-            .get_schema_by_name(session.database(), schema_or_default(&schema))?
+            .get_schema_by_name(session.database(), &schema_or_default(&schema))?
             .iter_source()
             .map(|t| t.name.clone())
             .collect(),
         ShowObject::MaterializedSource { schema } => catalog_reader
-            .get_schema_by_name(session.database(), schema_or_default(&schema))?
+            .get_schema_by_name(session.database(), &schema_or_default(&schema))?
             .iter_materialized_source()
             .map(|t| t.name.clone())
             .collect(),
+        ShowObject::Jobs | ShowObject::PlanCache => unreachable!(),
     };
 
     let rows = names
@@ -108,4 +169,23 @@ mod tests {
             .await;
         assert_eq!(rows, vec!["Row([Some(\"t2\")])".to_string()]);
     }
+
+    #[tokio::test]
+    async fn test_show_jobs() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+
+        let rows = frontend.query_formatted_result("SHOW JOBS").await;
+        assert!(rows.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_show_plan_cache() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+
+        let rows = frontend.query_formatted_result("SHOW PLAN CACHE").await;
+        assert_eq!(
+            rows,
+            vec!["Row([Some(\"0\"), Some(\"0\"), Some(\"0.00%\"), Some(\"0\")])".to_string()]
+        );
+    }
 }
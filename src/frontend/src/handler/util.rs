@@ -78,6 +78,8 @@ pub fn data_type_to_type_oid(data_type: DataType) -> TypeOid {
         DataType::Timestampz => TypeOid::Timestampz,
         DataType::Decimal => TypeOid::Decimal,
         DataType::Interval => TypeOid::Varchar,
+        DataType::Bytea => TypeOid::Bytea,
+        DataType::Varbit => TypeOid::Varbit,
         DataType::Struct { .. } => TypeOid::Varchar,
         DataType::List { .. } => TypeOid::Varchar,
     }
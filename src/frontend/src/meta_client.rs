@@ -13,6 +13,9 @@
 // limitations under the License.
 
 use risingwave_common::error::Result;
+use risingwave_pb::meta::{
+    Job, SlowBarrier, TableFragments as ProstTableFragments, TableWriteStats,
+};
 use risingwave_rpc_client::{HummockMetaClient, MetaClient};
 
 /// A wrapper around the `MetaClient` that only provides a minor set of meta rpc.
@@ -27,6 +30,16 @@ pub trait FrontendMetaClient: Send + Sync {
     async fn flush(&self) -> Result<()>;
 
     async fn unpin_snapshot(&self, epoch: u64) -> Result<()>;
+
+    async fn list_table_fragments(&self) -> Result<Vec<ProstTableFragments>>;
+
+    async fn list_jobs(&self) -> Result<Vec<Job>>;
+
+    async fn cancel_job(&self, table_id: u32) -> Result<()>;
+
+    async fn list_table_write_stats(&self) -> Result<Vec<TableWriteStats>>;
+
+    async fn list_slow_barriers(&self) -> Result<Vec<SlowBarrier>>;
 }
 
 pub struct FrontendMetaClientImpl(pub MetaClient);
@@ -44,4 +57,24 @@ impl FrontendMetaClient for FrontendMetaClientImpl {
     async fn unpin_snapshot(&self, epoch: u64) -> Result<()> {
         self.0.unpin_snapshot(&[epoch]).await
     }
+
+    async fn list_table_fragments(&self) -> Result<Vec<ProstTableFragments>> {
+        self.0.list_table_fragments().await
+    }
+
+    async fn list_jobs(&self) -> Result<Vec<Job>> {
+        self.0.list_jobs().await
+    }
+
+    async fn cancel_job(&self, table_id: u32) -> Result<()> {
+        self.0.cancel_job(table_id).await
+    }
+
+    async fn list_table_write_stats(&self) -> Result<Vec<TableWriteStats>> {
+        self.0.list_table_write_stats().await
+    }
+
+    async fn list_slow_barriers(&self) -> Result<Vec<SlowBarrier>> {
+        self.0.list_slow_barriers().await
+    }
 }
@@ -27,6 +27,7 @@ use tokio::sync::watch::Sender;
 use tokio::task::JoinHandle;
 
 use crate::catalog::root_catalog::Catalog;
+use crate::scheduler::plan_cache::PlanCacheRef;
 use crate::scheduler::worker_node_manager::WorkerNodeManagerRef;
 
 /// `ObserverManager` is used to update data based on notification from meta.
@@ -39,6 +40,7 @@ pub(crate) struct ObserverManager {
     worker_node_manager: WorkerNodeManagerRef,
     catalog: Arc<RwLock<Catalog>>,
     catalog_updated_tx: Sender<CatalogVersion>,
+    plan_cache: PlanCacheRef,
 }
 
 const RE_SUBSCRIBE_RETRY_INTERVAL: Duration = Duration::from_millis(100);
@@ -50,6 +52,7 @@ impl ObserverManager {
         worker_node_manager: WorkerNodeManagerRef,
         catalog: Arc<RwLock<Catalog>>,
         catalog_updated_tx: Sender<CatalogVersion>,
+        plan_cache: PlanCacheRef,
     ) -> Self {
         let rx = meta_client
             .subscribe(&addr, WorkerType::Frontend)
@@ -62,6 +65,7 @@ impl ObserverManager {
             worker_node_manager,
             catalog,
             catalog_updated_tx,
+            plan_cache,
         }
     }
 
@@ -82,6 +86,7 @@ impl ObserverManager {
                 for source in snapshot.source {
                     catalog_guard.create_source(source)
                 }
+                catalog_guard.reset_events(snapshot.event_log_capacity as usize, snapshot.events);
                 self.worker_node_manager.refresh_worker_node(snapshot.nodes);
             }
             _ => {
@@ -93,6 +98,7 @@ impl ObserverManager {
             }
         }
         catalog_guard.set_version(resp.version);
+        self.plan_cache.invalidate();
         self.catalog_updated_tx.send(resp.version).unwrap();
         Ok(())
     }
@@ -145,6 +151,10 @@ impl ObserverManager {
                 }
                 _ => panic!("receive an unsupported notify {:?}", resp),
             },
+            Some(Info::Event(event)) => match resp.operation() {
+                Operation::Add => catalog_guard.add_event(event.clone()),
+                _ => panic!("receive an unsupported notify {:?}", resp),
+            },
             Some(Info::FeSnapshot(_)) => {
                 panic!(
                     "receiving an FeSnapshot in the middle is unsupported now {:?}",
@@ -160,6 +170,7 @@ impl ObserverManager {
             catalog_guard.version()
         );
         catalog_guard.set_version(resp.version);
+        self.plan_cache.invalidate();
         self.catalog_updated_tx.send(resp.version).unwrap();
     }
 
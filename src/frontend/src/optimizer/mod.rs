@@ -20,6 +20,7 @@ mod heuristic;
 mod plan_rewriter;
 mod plan_visitor;
 mod rule;
+mod stream_plan_checker;
 
 use fixedbitset::FixedBitSet;
 use itertools::Itertools as _;
@@ -30,7 +31,16 @@ use risingwave_common::error::Result;
 use self::heuristic::{ApplyOrder, HeuristicOptimizer};
 use self::plan_node::{Convention, LogicalProject, StreamMaterialize};
 use self::rule::*;
+use self::stream_plan_checker::StreamPlanChecker;
 use crate::expr::InputRef;
+use crate::utils::Condition;
+
+/// Unnests the [`LogicalApply`](plan_node::LogicalApply)s in `plan` into joins where possible.
+pub fn unnest_applies(plan: PlanRef) -> PlanRef {
+    let heuristic_optimizer =
+        HeuristicOptimizer::new(ApplyOrder::BottomUp, vec![ApplyToJoinRule::create()]);
+    heuristic_optimizer.optimize(plan)
+}
 
 /// `PlanRoot` is used to describe a plan. planner will construct a `PlanRoot` with `LogicalNode`.
 /// and required distribution and order. And `PlanRoot` can generate corresponding streaming or
@@ -115,16 +125,11 @@ impl PlanRoot {
     pub fn gen_optimized_logical_plan(&self) -> PlanRef {
         let mut plan = self.plan.clone();
 
+        // Unnest the correlated subqueries.
+        plan = unnest_applies(plan);
+
         // Predicate Push-down
-        plan = {
-            let rules = vec![
-                FilterJoinRule::create(),
-                FilterProjectRule::create(),
-                FilterAggRule::create(),
-            ];
-            let heuristic_optimizer = HeuristicOptimizer::new(ApplyOrder::TopDown, rules);
-            heuristic_optimizer.optimize(plan)
-        };
+        plan = plan.predicate_pushdown(Condition::true_cond());
 
         // Prune Columns
         plan = plan.prune_col(&self.out_fields);
@@ -174,6 +179,17 @@ impl PlanRoot {
         let stream_plan = match self.plan.convention() {
             Convention::Logical => {
                 let plan = self.gen_optimized_logical_plan();
+                // The dynamic filter has no batch counterpart, and the eager aggregation only pays
+                // off for the join state of streaming, so they're only introduced here.
+                let plan = HeuristicOptimizer::new(
+                    ApplyOrder::TopDown,
+                    vec![
+                        JoinToDynamicFilterRule::create(),
+                        AggJoinTransposeRule::create(),
+                    ],
+                )
+                .optimize(plan);
+                StreamPlanChecker::check(plan.clone())?;
                 let (plan, out_col_change) = plan.logical_rewrite_for_stream();
                 self.required_dist = out_col_change
                     .rewrite_required_distribution(&self.required_dist)
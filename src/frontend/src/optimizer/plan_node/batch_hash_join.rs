@@ -55,7 +55,7 @@ impl BatchHashJoin {
         }
     }
 
-    fn derive_dist(
+    pub(super) fn derive_dist(
         left: &Distribution,
         right: &Distribution,
         predicate: &EqJoinPredicate,
@@ -0,0 +1,102 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_pb::plan::plan_node::NodeBody;
+use risingwave_pb::plan::{HashSetOpNode, SetOpType};
+
+use super::{PlanBase, PlanRef, PlanTreeNodeBinary, ToBatchProst, ToDistributedBatch};
+use crate::optimizer::property::{Distribution, Order};
+
+/// `BatchHashSetOp` implements [`super::LogicalIntersect`] and [`super::LogicalExcept`] with a
+/// hash set. It builds a hash set from the right-side relation and then probes it with the rows
+/// from the left-side relation.
+#[derive(Debug, Clone)]
+pub struct BatchHashSetOp {
+    pub base: PlanBase,
+    left: PlanRef,
+    right: PlanRef,
+    set_op: SetOpType,
+}
+
+impl BatchHashSetOp {
+    pub fn new(left: PlanRef, right: PlanRef, set_op: SetOpType) -> Self {
+        let ctx = left.ctx();
+        let dist = Self::derive_dist(left.distribution(), right.distribution());
+        let base = PlanBase::new_batch(ctx, left.schema().clone(), dist, Order::any().clone());
+        BatchHashSetOp {
+            base,
+            left,
+            right,
+            set_op,
+        }
+    }
+
+    fn derive_dist(left: &Distribution, right: &Distribution) -> Distribution {
+        match (left, right) {
+            (Distribution::Any, Distribution::Any) => Distribution::Any,
+            (Distribution::Single, Distribution::Single) => Distribution::Single,
+            (Distribution::HashShard(_), Distribution::HashShard(_)) => left.clone(),
+            (_, _) => panic!(),
+        }
+    }
+
+    pub fn set_op(&self) -> SetOpType {
+        self.set_op
+    }
+}
+
+impl fmt::Display for BatchHashSetOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BatchHashSetOp {{ type: {:?} }}", self.set_op)
+    }
+}
+
+impl PlanTreeNodeBinary for BatchHashSetOp {
+    fn left(&self) -> PlanRef {
+        self.left.clone()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.right.clone()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(left, right, self.set_op)
+    }
+}
+
+impl_plan_tree_node_for_binary! { BatchHashSetOp }
+
+impl ToDistributedBatch for BatchHashSetOp {
+    fn to_distributed(&self) -> PlanRef {
+        let dist = Distribution::HashShard((0..self.schema().len()).collect());
+        let left = self
+            .left()
+            .to_distributed_with_required(Order::any(), &dist);
+        let right = self
+            .right()
+            .to_distributed_with_required(Order::any(), &dist);
+        self.clone_with_left_right(left, right).into()
+    }
+}
+
+impl ToBatchProst for BatchHashSetOp {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::HashSetOp(HashSetOpNode {
+            set_op_type: self.set_op as i32,
+        })
+    }
+}
@@ -15,9 +15,10 @@
 use std::fmt;
 
 use risingwave_pb::plan::plan_node::NodeBody;
-use risingwave_pb::plan::{InsertNode, TableRefId};
+use risingwave_pb::plan::{InsertNode, InsertOnConflict as ProstInsertOnConflict, TableRefId};
 
 use super::{LogicalInsert, PlanRef, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch};
+use crate::expr::Expr;
 use crate::optimizer::plan_node::PlanBase;
 use crate::optimizer::property::{Distribution, Order};
 
@@ -77,6 +78,22 @@ impl ToBatchProst for BatchInsert {
             .into(),
             column_ids: vec![], // unused
             frontend_v2: true,
+            on_conflict: self
+                .logical
+                .on_conflict()
+                .map(|on_conflict| ProstInsertOnConflict {
+                    pk_indices: on_conflict
+                        .pk_indices
+                        .iter()
+                        .map(|idx| *idx as u32)
+                        .collect(),
+                    update_exprs: on_conflict
+                        .update_exprs
+                        .iter()
+                        .flatten()
+                        .map(|expr| expr.to_protobuf())
+                        .collect(),
+                }),
         })
     }
 }
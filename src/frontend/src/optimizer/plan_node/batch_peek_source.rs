@@ -0,0 +1,77 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_pb::plan::plan_node::NodeBody;
+use risingwave_pb::plan::{PeekSourceNode, TableRefId};
+
+use super::{
+    LogicalPeekSource, PlanBase, PlanRef, PlanTreeNodeLeaf, ToBatchProst, ToDistributedBatch,
+};
+use crate::optimizer::property::{Distribution, Order};
+
+/// `BatchPeekSource` implements [`super::LogicalPeekSource`] on a single compute node.
+#[derive(Debug, Clone)]
+pub struct BatchPeekSource {
+    pub base: PlanBase,
+    logical: LogicalPeekSource,
+}
+
+impl PlanTreeNodeLeaf for BatchPeekSource {}
+impl_plan_tree_node_for_leaf!(BatchPeekSource);
+
+impl BatchPeekSource {
+    pub fn new(logical: LogicalPeekSource) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let base = PlanBase::new_batch(
+            ctx,
+            logical.schema().clone(),
+            Distribution::Single,
+            Order::any().clone(),
+        );
+        BatchPeekSource { base, logical }
+    }
+}
+
+impl fmt::Display for BatchPeekSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "BatchPeekSource {{ source: {}, limit: {} }}",
+            self.logical.source_catalog.name,
+            self.logical.limit()
+        )
+    }
+}
+
+impl ToDistributedBatch for BatchPeekSource {
+    fn to_distributed(&self) -> PlanRef {
+        self.clone().into()
+    }
+}
+
+impl ToBatchProst for BatchPeekSource {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::PeekSource(PeekSourceNode {
+            // TODO: Refactor this id
+            table_ref_id: TableRefId {
+                table_id: self.logical.source_catalog.id as i32,
+                ..Default::default()
+            }
+            .into(),
+            limit: self.logical.limit() as u32,
+        })
+    }
+}
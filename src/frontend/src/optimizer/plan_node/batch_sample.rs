@@ -0,0 +1,91 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_pb::plan::plan_node::NodeBody;
+use risingwave_pb::plan::SampleNode;
+
+use super::{
+    LogicalSample, PlanBase, PlanRef, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch,
+};
+use crate::optimizer::property::{Distribution, Order};
+
+/// `BatchSample` implements [`super::LogicalSample`] on a single node, with reservoir sampling if
+/// there's a limit, or by streaming the rows through a bounded shuffle buffer otherwise.
+#[derive(Debug, Clone)]
+pub struct BatchSample {
+    pub base: PlanBase,
+    logical: LogicalSample,
+}
+
+impl BatchSample {
+    pub fn new(logical: LogicalSample) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let base = PlanBase::new_batch(
+            ctx,
+            logical.schema().clone(),
+            logical.input().distribution().clone(),
+            Order::any().clone(),
+        );
+        BatchSample { base, logical }
+    }
+}
+
+impl fmt::Display for BatchSample {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.logical.limit {
+            Some(limit) => write!(
+                f,
+                "BatchSample {{ limit: {limit}, offset: {offset} }}",
+                limit = limit,
+                offset = self.logical.offset
+            ),
+            None => write!(
+                f,
+                "BatchSample {{ offset: {offset} }}",
+                offset = self.logical.offset
+            ),
+        }
+    }
+}
+
+impl PlanTreeNodeUnary for BatchSample {
+    fn input(&self) -> PlanRef {
+        self.logical.input()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_input(input))
+    }
+}
+impl_plan_tree_node_for_unary! {BatchSample}
+impl ToDistributedBatch for BatchSample {
+    fn to_distributed(&self) -> PlanRef {
+        let new_input = self
+            .input()
+            .to_distributed_with_required(Order::any(), &Distribution::Single);
+        self.clone_with_input(new_input).into()
+    }
+}
+
+impl ToBatchProst for BatchSample {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::Sample(SampleNode {
+            limit: self.logical.limit().unwrap_or_default() as u32,
+            offset: self.logical.offset() as u32,
+            unlimited: self.logical.limit().is_none(),
+        })
+    }
+}
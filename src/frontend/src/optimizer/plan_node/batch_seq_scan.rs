@@ -12,14 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt;
 
+use risingwave_common::util::sort_util::OrderType;
 use risingwave_pb::plan::plan_node::NodeBody;
 use risingwave_pb::plan::{CellBasedTableDesc, ColumnDesc as ProstColumnDesc, RowSeqScanNode};
 
 use super::{PlanBase, PlanRef, ToBatchProst, ToDistributedBatch};
 use crate::optimizer::plan_node::LogicalScan;
-use crate::optimizer::property::{Distribution, Order};
+use crate::optimizer::property::{Direction, Distribution, FieldOrder, Order};
 
 /// `BatchSeqScan` implements [`super::LogicalScan`] to scan from a row-oriented table
 #[derive(Debug, Clone)]
@@ -31,8 +33,8 @@ pub struct BatchSeqScan {
 impl BatchSeqScan {
     pub fn new_inner(logical: LogicalScan, dist: Distribution) -> Self {
         let ctx = logical.base.ctx.clone();
-        // TODO: derive from input
-        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any().clone());
+        let order = Self::derive_order(&logical);
+        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, order);
 
         Self { base, logical }
     }
@@ -45,6 +47,31 @@ impl BatchSeqScan {
         Self::new_inner(logical, Distribution::AnyShard)
     }
 
+    /// Rows are scanned in the order of the table's primary key, so the output is sorted on the
+    /// longest prefix of the primary key whose columns are all scanned.
+    fn derive_order(logical: &LogicalScan) -> Order {
+        let id_to_op_idx: HashMap<_, _> = logical
+            .column_descs()
+            .iter()
+            .enumerate()
+            .map(|(op_idx, col)| (col.column_id, op_idx))
+            .collect();
+        let field_order = logical
+            .table_desc()
+            .pk
+            .iter()
+            .map_while(|col| {
+                let index = *id_to_op_idx.get(&col.column_desc.column_id)?;
+                let direct = match col.order {
+                    OrderType::Ascending => Direction::Asc,
+                    OrderType::Descending => Direction::Desc,
+                };
+                Some(FieldOrder { index, direct })
+            })
+            .collect();
+        Order::new(field_order)
+    }
+
     /// Get a reference to the batch seq scan's logical.
     #[must_use]
     pub fn logical(&self) -> &LogicalScan {
@@ -0,0 +1,179 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_pb::plan::plan_node::NodeBody;
+use risingwave_pb::plan::{OrderType as ProstOrderType, SortMergeJoinNode};
+
+use super::{
+    BatchHashJoin, EqJoinPredicate, LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary,
+    ToBatchProst, ToDistributedBatch,
+};
+use crate::optimizer::property::{Direction, Distribution, FieldOrder, Order};
+
+/// Hash join is still preferred if the right side is known to have at most this many rows, since
+/// its hash table is small anyway.
+const HASH_JOIN_MAX_BUILD_ROWS: usize = 1024;
+
+/// `BatchSortMergeJoin` implements [`super::LogicalJoin`] by merging both inputs, which are already
+/// sorted on the join keys, e.g. scans of materialized views arranged on the join keys. Unlike
+/// [`BatchHashJoin`], it doesn't build a hash table, so the memory usage stays flat no matter how
+/// large the inputs are.
+#[derive(Debug, Clone)]
+pub struct BatchSortMergeJoin {
+    pub base: PlanBase,
+    logical: LogicalJoin,
+
+    /// The join condition must be equivalent to `logical.on`. Only equal conditions are allowed.
+    eq_join_predicate: EqJoinPredicate,
+
+    /// Pairs of left and right key column indices, in the order that both inputs are sorted on.
+    merge_keys: Vec<(usize, usize)>,
+}
+
+impl BatchSortMergeJoin {
+    pub fn new(
+        logical: LogicalJoin,
+        eq_join_predicate: EqJoinPredicate,
+        merge_keys: Vec<(usize, usize)>,
+    ) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let l2o = logical.l2o_col_mapping();
+        let dist = BatchHashJoin::derive_dist(
+            logical.left().distribution(),
+            logical.right().distribution(),
+            &eq_join_predicate,
+            &l2o,
+        );
+        // The output is sorted on the join keys as the left side is.
+        let order =
+            l2o.rewrite_provided_order(&Self::key_order(merge_keys.iter().map(|(left, _)| *left)));
+        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, order);
+
+        Self {
+            base,
+            logical,
+            eq_join_predicate,
+            merge_keys,
+        }
+    }
+
+    /// Returns the merge keys if an inner join with `eq_join_predicate` can be done by merging
+    /// `left` and `right`, i.e. both inputs are sorted ascending on the join keys in the same
+    /// order, and the right side may be too large for a hash table. `right_rows` is the estimated
+    /// row count of the right side.
+    pub fn try_merge_keys(
+        left: &PlanRef,
+        right: &PlanRef,
+        eq_join_predicate: &EqJoinPredicate,
+        right_rows: Option<usize>,
+    ) -> Option<Vec<(usize, usize)>> {
+        if eq_join_predicate.has_non_eq()
+            || right_rows.map_or(false, |rows| rows <= HASH_JOIN_MAX_BUILD_ROWS)
+        {
+            return None;
+        }
+        let eq_indexes = eq_join_predicate.eq_indexes();
+        let mut merge_keys = Vec::with_capacity(eq_indexes.len());
+        for i in 0..eq_indexes.len() {
+            let left_field = left.order().field_order.get(i)?;
+            let right_field = right.order().field_order.get(i)?;
+            let key = (left_field.index, right_field.index);
+            if left_field.direct != Direction::Asc
+                || right_field.direct != Direction::Asc
+                || !eq_indexes.contains(&key)
+            {
+                return None;
+            }
+            merge_keys.push(key);
+        }
+        Some(merge_keys)
+    }
+
+    fn key_order(keys: impl Iterator<Item = usize>) -> Order {
+        Order::new(keys.map(FieldOrder::ascending).collect())
+    }
+
+    /// Get a reference to the batch sort merge join's eq join predicate.
+    pub fn eq_join_predicate(&self) -> &EqJoinPredicate {
+        &self.eq_join_predicate
+    }
+}
+
+impl fmt::Display for BatchSortMergeJoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "BatchSortMergeJoin {{ type: {:?}, predicate: {} }}",
+            self.logical.join_type(),
+            self.eq_join_predicate()
+        )
+    }
+}
+
+impl PlanTreeNodeBinary for BatchSortMergeJoin {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(
+            self.logical.clone_with_left_right(left, right),
+            self.eq_join_predicate.clone(),
+            self.merge_keys.clone(),
+        )
+    }
+}
+
+impl_plan_tree_node_for_binary! { BatchSortMergeJoin }
+
+impl ToDistributedBatch for BatchSortMergeJoin {
+    fn to_distributed(&self) -> PlanRef {
+        // Each partition of the inputs is merged from sorted upstreams, so it's still sorted.
+        let left = self.left().to_distributed_with_required(
+            &Self::key_order(self.merge_keys.iter().map(|(left, _)| *left)),
+            &Distribution::HashShard(self.eq_join_predicate().left_eq_indexes()),
+        );
+        let right = self.right().to_distributed_with_required(
+            &Self::key_order(self.merge_keys.iter().map(|(_, right)| *right)),
+            &Distribution::HashShard(self.eq_join_predicate().right_eq_indexes()),
+        );
+
+        self.clone_with_left_right(left, right).into()
+    }
+}
+
+impl ToBatchProst for BatchSortMergeJoin {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::SortMergeJoin(SortMergeJoinNode {
+            join_type: self.logical.join_type() as i32,
+            left_keys: self
+                .merge_keys
+                .iter()
+                .map(|(left, _)| *left as i32)
+                .collect(),
+            right_keys: self
+                .merge_keys
+                .iter()
+                .map(|(_, right)| *right as i32)
+                .collect(),
+            direction: ProstOrderType::Ascending as i32,
+        })
+    }
+}
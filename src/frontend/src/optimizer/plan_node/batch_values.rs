@@ -83,7 +83,7 @@ impl ToBatchProst for BatchValues {
     }
 }
 
-fn row_to_protobuf(row: &[ExprImpl]) -> ExprTuple {
+pub(super) fn row_to_protobuf(row: &[ExprImpl]) -> ExprTuple {
     let cells = row.iter().map(Expr::to_protobuf).collect();
     ExprTuple { cells }
 }
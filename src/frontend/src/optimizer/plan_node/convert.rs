@@ -39,6 +39,22 @@ pub trait ToStream {
     /// 2. add `row_count`() in every Agg
     fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping);
 
+    /// Same as `logical_rewrite_for_stream`, but the extra columns added during the rewriting
+    /// (e.g. the hidden pk columns) are projected out, so the output columns stay unchanged. It is
+    /// used by nodes comparing whole rows of their inputs, like [`LogicalIntersect`].
+    fn logical_rewrite_for_stream_without_hidden_columns(&self) -> PlanRef {
+        let (plan, col_change) = self.logical_rewrite_for_stream();
+        let origin_len = col_change.source_size();
+        if plan.schema().len() == origin_len && (0..origin_len).all(|i| col_change.map(i) == i) {
+            return plan;
+        }
+        let mut map = vec![None; plan.schema().len()];
+        for i in 0..origin_len {
+            map[col_change.map(i)] = Some(i);
+        }
+        LogicalProject::with_mapping(plan, ColIndexMapping::with_target_size(map, origin_len))
+    }
+
     /// `to_stream` is equivalent to `to_stream_with_dist_required(Distribution::any())`
     fn to_stream(&self) -> PlanRef;
 
@@ -0,0 +1,270 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Describes the internal state a stream plan node will keep, for `EXPLAIN (STATE)`.
+
+use std::fmt;
+
+use itertools::Itertools;
+use paste::paste;
+use risingwave_common::catalog::Field;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::{DataSize, DataType};
+use risingwave_expr::expr::AggKind;
+use risingwave_expr::vector_op::agg::is_time_series_agg;
+
+use super::*;
+use crate::{for_batch_plan_nodes, for_logical_plan_nodes};
+
+/// How a state table grows as the input keeps coming.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateGrowth {
+    /// The number of rows has an upper bound, described by the string.
+    Bounded(&'static str),
+    /// The number of rows grows with the input, described by the string.
+    Unbounded(&'static str),
+}
+
+impl fmt::Display for StateGrowth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StateGrowth::Bounded(desc) => write!(f, "bounded ({})", desc),
+            StateGrowth::Unbounded(desc) => write!(f, "unbounded ({})", desc),
+        }
+    }
+}
+
+/// An internal state table created by a stream plan node.
+#[derive(Debug, Clone)]
+pub struct StateTableDesc {
+    pub name: String,
+    pub key: Vec<Field>,
+    pub value: Vec<Field>,
+    pub growth: StateGrowth,
+}
+
+impl StateTableDesc {
+    /// Estimated size of one row in bytes, and whether the row contains variable-sized columns,
+    /// in which case the size is a lower bound.
+    pub fn row_size(&self) -> (usize, bool) {
+        self.key
+            .iter()
+            .chain(self.value.iter())
+            .fold((0, false), |(size, variable), field| {
+                match field.data_type.data_size() {
+                    DataSize::Fixed(s) => (size + s, variable),
+                    DataSize::Variable => (size, true),
+                }
+            })
+    }
+}
+
+impl fmt::Display for StateTableDesc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (size, variable) = self.row_size();
+        write!(
+            f,
+            "{} {{ key: {:?}, value: {:?}, growth: {}, row_size: {}{} bytes }}",
+            self.name,
+            self.key,
+            self.value,
+            self.growth,
+            if variable { ">= " } else { "" },
+            size
+        )
+    }
+}
+
+/// Describes the internal state tables the executor of a plan node will create. Every stream node
+/// implements it in its own module, so that a new stateful node can't be missed by
+/// `EXPLAIN (STATE)`. Stateless nodes and nodes of other conventions keep the empty default.
+pub trait StateTables {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        vec![]
+    }
+}
+
+/// impl the empty `StateTables` for logical and batch nodes.
+macro_rules! impl_empty_state_tables {
+    ([], $( { $convention:ident, $name:ident }),*) => {
+        paste! {
+            $(impl StateTables for [<$convention $name>] { })*
+        }
+    }
+}
+for_logical_plan_nodes! { impl_empty_state_tables }
+for_batch_plan_nodes! { impl_empty_state_tables }
+
+impl dyn PlanNode {
+    /// Write explain the whole plan tree, with the state tables of each node listed under it.
+    pub fn explain_state(&self, level: usize, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        writeln!(f, "{}{}", " ".repeat(level * 2), self)?;
+        for state_table in self.state_tables() {
+            writeln!(f, "{}  state: {}", " ".repeat(level * 2), state_table)?;
+        }
+        for input in self.inputs() {
+            input.explain_state(level + 1, f)?;
+        }
+        Ok(())
+    }
+
+    /// Explain the plan node with its state tables and return a string.
+    pub fn explain_state_to_string(&self) -> Result<String> {
+        let mut output = String::new();
+        self.explain_state(0, &mut output)
+            .map_err(|e| ErrorCode::InternalError(format!("failed to explain: {}", e)))?;
+        Ok(output)
+    }
+}
+
+/// The state tables of the aggregation calls besides the result table, keyed by the group key
+/// `group_key` followed by the columns of each call.
+pub(super) fn agg_call_state_tables(
+    agg_calls: &[PlanAggCall],
+    group_key: Vec<Field>,
+    append_only: bool,
+    input_fields: &[Field],
+) -> Vec<StateTableDesc> {
+    let mut tables = extreme_state_tables(agg_calls, group_key.clone(), append_only, input_fields);
+    tables.extend(string_agg_state_tables(
+        agg_calls,
+        group_key.clone(),
+        input_fields,
+    ));
+    tables.extend(time_series_state_tables(
+        agg_calls,
+        group_key.clone(),
+        input_fields,
+    ));
+    tables.extend(distinct_state_tables(agg_calls, group_key, input_fields));
+    tables
+}
+
+/// Min and max on a non-append-only input keep all input values of each group, so that the
+/// result can be recovered after the current extreme value is retracted.
+fn extreme_state_tables(
+    agg_calls: &[PlanAggCall],
+    group_key: Vec<Field>,
+    append_only: bool,
+    input_fields: &[Field],
+) -> Vec<StateTableDesc> {
+    if append_only {
+        return vec![];
+    }
+    agg_calls
+        .iter()
+        .enumerate()
+        .filter(|(_, call)| matches!(call.agg_kind, AggKind::Min | AggKind::Max))
+        .map(|(idx, call)| StateTableDesc {
+            name: format!("agg_call_{}_{}", idx, call.agg_kind),
+            key: group_key
+                .iter()
+                .cloned()
+                .chain(
+                    call.inputs
+                        .iter()
+                        .map(|input| input_fields[input.index()].clone()),
+                )
+                .collect_vec(),
+            value: vec![],
+            growth: StateGrowth::Unbounded("one row per distinct input value of each group"),
+        })
+        .collect()
+}
+
+/// String agg keeps all input values of each group, ordered by the `ORDER BY` columns, so that the
+/// concatenated result can be rebuilt after any value is retracted.
+fn string_agg_state_tables(
+    agg_calls: &[PlanAggCall],
+    group_key: Vec<Field>,
+    input_fields: &[Field],
+) -> Vec<StateTableDesc> {
+    agg_calls
+        .iter()
+        .enumerate()
+        .filter(|(_, call)| call.agg_kind == AggKind::StringAgg)
+        .map(|(idx, call)| StateTableDesc {
+            name: format!("agg_call_{}_{}", idx, call.agg_kind),
+            key: group_key
+                .iter()
+                .cloned()
+                .chain(
+                    call.order_by_fields
+                        .iter()
+                        .map(|o| input_fields[o.input.index()].clone()),
+                )
+                .collect_vec(),
+            value: call
+                .inputs
+                .iter()
+                .map(|input| input_fields[input.index()].clone())
+                .collect_vec(),
+            growth: StateGrowth::Unbounded("one row per input row of each group"),
+        })
+        .collect()
+}
+
+/// Time-series aggregations like `first(value, time)` keep all input points of each group, ordered
+/// by time, so that the result can be re-evaluated after any point is retracted.
+fn time_series_state_tables(
+    agg_calls: &[PlanAggCall],
+    group_key: Vec<Field>,
+    input_fields: &[Field],
+) -> Vec<StateTableDesc> {
+    agg_calls
+        .iter()
+        .enumerate()
+        .filter(|(_, call)| is_time_series_agg(&call.agg_kind))
+        .map(|(idx, call)| StateTableDesc {
+            name: format!("agg_call_{}_{}", idx, call.agg_kind),
+            key: group_key
+                .iter()
+                .cloned()
+                .chain(std::iter::once(
+                    input_fields[call.inputs[1].index()].clone(),
+                ))
+                .collect_vec(),
+            value: vec![input_fields[call.inputs[0].index()].clone()],
+            growth: StateGrowth::Unbounded("one row per input row of each group"),
+        })
+        .collect()
+}
+
+/// `DISTINCT` aggregations keep the number of occurrences of each argument value of each group, so
+/// that only the first insertion and the last deletion of a value reach the aggregation.
+fn distinct_state_tables(
+    agg_calls: &[PlanAggCall],
+    group_key: Vec<Field>,
+    input_fields: &[Field],
+) -> Vec<StateTableDesc> {
+    agg_calls
+        .iter()
+        .enumerate()
+        .filter(|(_, call)| call.distinct)
+        .map(|(idx, call)| StateTableDesc {
+            name: format!("agg_call_{}_{}_distinct", idx, call.agg_kind),
+            key: group_key
+                .iter()
+                .cloned()
+                .chain(
+                    call.inputs
+                        .iter()
+                        .map(|input| input_fields[input.index()].clone()),
+                )
+                .collect_vec(),
+            value: vec![Field::with_name(DataType::Int64, "count")],
+            growth: StateGrowth::Unbounded("one row per distinct input value of each group"),
+        })
+        .collect()
+}
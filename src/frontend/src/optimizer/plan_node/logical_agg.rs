@@ -21,16 +21,49 @@ use risingwave_common::catalog::{Field, Schema};
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
 use risingwave_expr::expr::AggKind;
+use risingwave_pb::expr::agg_call::OrderByField as ProstAggOrderByField;
 use risingwave_pb::expr::AggCall as ProstAggCall;
 
 use super::{
-    BatchHashAgg, BatchSimpleAgg, ColPrunable, PlanBase, PlanNode, PlanRef, PlanTreeNodeUnary,
-    StreamHashAgg, StreamSimpleAgg, ToBatch, ToStream,
+    gen_filter_and_pushdown, BatchHashAgg, BatchSimpleAgg, ColPrunable, PlanBase, PlanNode,
+    PlanRef, PlanTreeNodeUnary, PredicatePushdown, StreamHashAgg, StreamLocalHashAgg,
+    StreamSimpleAgg, ToBatch, ToStream, TWO_PHASE_AGG,
+};
+use crate::expr::{
+    AggCall, AggOrderByExpr, Expr, ExprImpl, ExprRewriter, ExprType, FunctionCall, InputRef,
 };
-use crate::expr::{AggCall, Expr, ExprImpl, ExprRewriter, ExprType, FunctionCall, InputRef};
 use crate::optimizer::plan_node::LogicalProject;
-use crate::optimizer::property::Distribution;
-use crate::utils::ColIndexMapping;
+use crate::optimizer::property::{Direction, Distribution, Order};
+use crate::utils::{ColIndexMapping, Condition, Substitute};
+
+/// Rewritten version of [`AggOrderByExpr`] which uses `InputRef` instead of `ExprImpl`.
+#[derive(Clone)]
+pub struct PlanAggOrderByField {
+    pub input: InputRef,
+    pub direction: Direction,
+}
+
+impl fmt::Debug for PlanAggOrderByField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.input)?;
+        match self.direction {
+            Direction::Asc => write!(f, " ASC"),
+            Direction::Desc => write!(f, " DESC"),
+            Direction::Any => Ok(()),
+        }
+    }
+}
+
+impl PlanAggOrderByField {
+    fn to_protobuf(&self) -> ProstAggOrderByField {
+        let arg = self.input.to_agg_arg_protobuf();
+        ProstAggOrderByField {
+            input: arg.input,
+            r#type: arg.r#type,
+            descending: self.direction == Direction::Desc,
+        }
+    }
+}
 
 /// Aggregation Call
 #[derive(Clone)]
@@ -43,14 +76,33 @@ pub struct PlanAggCall {
 
     /// Column indexes of input columns
     pub inputs: Vec<InputRef>,
+
+    /// Whether only distinct values of the inputs are aggregated, e.g. `count(DISTINCT a)`
+    pub distinct: bool,
+
+    /// `ORDER BY` columns inside the aggregation, e.g. for `string_agg`
+    pub order_by_fields: Vec<PlanAggOrderByField>,
 }
 
 impl fmt::Debug for PlanAggCall {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut builder = f.debug_tuple(&format!("{}", self.agg_kind));
-        self.inputs.iter().for_each(|child| {
-            builder.field(child);
-        });
+        if self.distinct {
+            builder.field(&format_args!(
+                "distinct {}",
+                self.inputs
+                    .iter()
+                    .map(|child| format!("{:?}", child))
+                    .join(", ")
+            ));
+        } else {
+            self.inputs.iter().for_each(|child| {
+                builder.field(child);
+            });
+        }
+        if !self.order_by_fields.is_empty() {
+            builder.field(&self.order_by_fields);
+        }
         builder.finish()
     }
 }
@@ -65,8 +117,12 @@ impl PlanAggCall {
                 .iter()
                 .map(InputRef::to_agg_arg_protobuf)
                 .collect(),
-            // TODO: support distinct
-            distinct: false,
+            distinct: self.distinct,
+            order_by_fields: self
+                .order_by_fields
+                .iter()
+                .map(PlanAggOrderByField::to_protobuf)
+                .collect(),
         }
     }
 
@@ -75,8 +131,27 @@ impl PlanAggCall {
             agg_kind: AggKind::Count,
             return_type: DataType::Int64,
             inputs: vec![],
+            distinct: false,
+            order_by_fields: vec![],
         }
     }
+
+    /// Get the input column indices of the agg call, including its `ORDER BY` columns.
+    pub fn input_indices(&self) -> Vec<usize> {
+        self.inputs
+            .iter()
+            .chain(self.order_by_fields.iter().map(|o| &o.input))
+            .map(|x| x.index())
+            .collect()
+    }
+
+    /// Remap the input column indices of the agg call, including its `ORDER BY` columns.
+    pub fn rewrite_input_index(&mut self, mapping: &ColIndexMapping) {
+        self.inputs
+            .iter_mut()
+            .chain(self.order_by_fields.iter_mut().map(|o| &mut o.input))
+            .for_each(|i| *i = InputRef::new(mapping.map(i.index()), i.return_type()));
+    }
 }
 
 /// `LogicalAgg` groups input data by their group keys and computes aggregation functions.
@@ -142,6 +217,22 @@ impl ExprHandler {
             error: None,
         })
     }
+
+    /// Add an input of agg call to `project` if it is not there yet, and return the `InputRef` to
+    /// it.
+    fn project_agg_input(&mut self, expr: ExprImpl) -> InputRef {
+        let return_type = expr.return_type();
+        let index = match self.expr_index.get(&expr) {
+            Some(idx) => *idx,
+            None => {
+                let idx = self.project.len();
+                self.expr_index.insert(expr.clone(), idx);
+                self.project.push(expr);
+                idx
+            }
+        };
+        InputRef::new(index, return_type)
+    }
 }
 
 impl ExprRewriter for ExprHandler {
@@ -153,21 +244,19 @@ impl ExprRewriter for ExprHandler {
     // Note that the rewriter does not traverse into inputs of agg calls.
     fn rewrite_agg_call(&mut self, agg_call: AggCall) -> ExprImpl {
         let return_type = agg_call.return_type();
-        let (agg_kind, inputs) = agg_call.decompose();
-
-        let mut index = self.project.len();
-        let mut input_refs = vec![];
-        self.project.extend(inputs.into_iter().filter(|expr| {
-            if let Some(idx) = self.expr_index.get(expr) {
-                input_refs.push(InputRef::new(*idx, expr.return_type()));
-                false
-            } else {
-                self.expr_index.insert(expr.clone(), index);
-                input_refs.push(InputRef::new(index, expr.return_type()));
-                index += 1;
-                true
-            }
-        }));
+        let (agg_kind, inputs, distinct, order_by) = agg_call.decompose();
+
+        let input_refs = inputs
+            .into_iter()
+            .map(|expr| self.project_agg_input(expr))
+            .collect_vec();
+        let order_by_fields = order_by
+            .into_iter()
+            .map(|AggOrderByExpr { expr, direction }| PlanAggOrderByField {
+                input: self.project_agg_input(expr),
+                direction,
+            })
+            .collect_vec();
 
         if agg_kind == AggKind::Avg {
             assert_eq!(input_refs.len(), 1);
@@ -180,6 +269,8 @@ impl ExprRewriter for ExprHandler {
                 agg_kind: AggKind::Sum,
                 return_type: left_return_type.clone(),
                 inputs: input_refs.clone(),
+                distinct,
+                order_by_fields: vec![],
             });
             let left = ExprImpl::from(InputRef::new(
                 self.group_key_len + self.agg_calls.len() - 1,
@@ -196,6 +287,8 @@ impl ExprRewriter for ExprHandler {
                 agg_kind: AggKind::Count,
                 return_type: right_return_type.clone(),
                 inputs: input_refs,
+                distinct,
+                order_by_fields: vec![],
             });
 
             let right = InputRef::new(
@@ -209,6 +302,8 @@ impl ExprRewriter for ExprHandler {
                 agg_kind,
                 return_type: return_type.clone(),
                 inputs: input_refs,
+                distinct,
+                order_by_fields,
             });
             ExprImpl::from(InputRef::new(
                 self.group_key_len + self.agg_calls.len() - 1,
@@ -369,6 +464,67 @@ impl LogicalAgg {
         self.group_keys.as_ref()
     }
 
+    /// Whether the two-phase agg is enabled by the [`TWO_PHASE_AGG`] option.
+    fn two_phase_agg_enabled(&self) -> bool {
+        self.base
+            .ctx
+            .inner()
+            .with_options
+            .get(TWO_PHASE_AGG)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(false)
+    }
+
+    /// Whether the partial results of all the agg calls can be merged by another agg, i.e. they
+    /// are counts and sums, or min and max over an append-only input.
+    fn can_two_phase(&self, input_append_only: bool) -> bool {
+        self.agg_calls().iter().all(|agg_call| {
+            !agg_call.distinct
+                && agg_call.order_by_fields.is_empty()
+                && match agg_call.agg_kind {
+                    AggKind::Count | AggKind::Sum => true,
+                    AggKind::Min | AggKind::Max => input_append_only,
+                    _ => false,
+                }
+        })
+    }
+
+    /// Splits the agg into a [`StreamLocalHashAgg`] pre-aggregating the input on each shard, and a
+    /// [`StreamHashAgg`] merging the partial results shuffled by the group keys. The counts are
+    /// merged by sum.
+    fn gen_two_phase_stream_agg(&self, input: PlanRef) -> PlanRef {
+        let num_group_keys = self.group_keys().len();
+        let local_agg = StreamLocalHashAgg::new(self.clone_with_input(input));
+        let exchange = Distribution::HashShard((0..num_group_keys).collect())
+            .enforce_if_not_satisfies(local_agg.into(), Order::any());
+
+        let global_agg_calls = self
+            .agg_calls()
+            .iter()
+            .enumerate()
+            .map(|(i, agg_call)| PlanAggCall {
+                agg_kind: match agg_call.agg_kind {
+                    AggKind::Min | AggKind::Max => agg_call.agg_kind.clone(),
+                    _ => AggKind::Sum,
+                },
+                return_type: agg_call.return_type.clone(),
+                inputs: vec![InputRef::new(
+                    num_group_keys + i,
+                    agg_call.return_type.clone(),
+                )],
+                distinct: false,
+                order_by_fields: vec![],
+            })
+            .collect();
+        StreamHashAgg::new(LogicalAgg::new(
+            global_agg_calls,
+            self.agg_call_alias().to_vec(),
+            (0..num_group_keys).collect(),
+            exchange,
+        ))
+        .into()
+    }
+
     pub fn decompose(self) -> (Vec<PlanAggCall>, Vec<Option<String>>, Vec<usize>, PlanRef) {
         (
             self.agg_calls,
@@ -404,9 +560,7 @@ impl PlanTreeNodeUnary for LogicalAgg {
             .iter()
             .cloned()
             .map(|mut agg_call| {
-                agg_call.inputs.iter_mut().for_each(|i| {
-                    *i = InputRef::new(input_col_change.map(i.index()), i.return_type())
-                });
+                agg_call.rewrite_input_index(&input_col_change);
                 agg_call
             })
             .collect();
@@ -449,18 +603,15 @@ impl ColPrunable for LogicalAgg {
             .map(|index| {
                 let index = index - self.group_keys.len();
                 let agg_call = self.agg_calls[index].clone();
-                child_required_cols.extend(agg_call.inputs.iter().map(|x| x.index()));
+                child_required_cols.extend(agg_call.input_indices());
                 (agg_call, self.agg_call_alias[index].clone())
             })
             .multiunzip();
 
         let mapping = ColIndexMapping::with_remaining_columns(&child_required_cols);
-        agg_calls.iter_mut().for_each(|agg_call| {
-            agg_call
-                .inputs
-                .iter_mut()
-                .for_each(|i| *i = InputRef::new(mapping.map(i.index()), i.return_type()));
-        });
+        agg_calls
+            .iter_mut()
+            .for_each(|agg_call| agg_call.rewrite_input_index(&mapping));
         group_keys.iter_mut().for_each(|i| *i = mapping.map(*i));
 
         let agg = LogicalAgg::new(
@@ -488,6 +639,34 @@ impl ColPrunable for LogicalAgg {
     }
 }
 
+impl PredicatePushdown for LogicalAgg {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        let num_group_keys = self.group_keys().len();
+        let num_agg_calls = self.agg_calls().len();
+        assert!(num_group_keys + num_agg_calls == self.schema().len());
+
+        // If the filter references agg_calls, we can not push it.
+        let mut agg_call_columns = FixedBitSet::with_capacity(num_group_keys + num_agg_calls);
+        agg_call_columns.insert_range(num_group_keys..num_group_keys + num_agg_calls);
+        let (agg_call_pred, pushed_predicate) = predicate.split_disjoint(&agg_call_columns);
+
+        // convert the predicate to one that references the child of the agg
+        let mut subst = Substitute {
+            mapping: self
+                .group_keys()
+                .iter()
+                .enumerate()
+                .map(|(i, group_key)| {
+                    InputRef::new(*group_key, self.schema().fields()[i].data_type()).into()
+                })
+                .collect(),
+        };
+        let pushed_predicate = pushed_predicate.rewrite_expr(&mut subst);
+
+        gen_filter_and_pushdown(self, agg_call_pred, pushed_predicate)
+    }
+}
+
 impl ToBatch for LogicalAgg {
     fn to_batch(&self) -> PlanRef {
         let new_input = self.input().to_batch();
@@ -510,6 +689,23 @@ impl ToStream for LogicalAgg {
                 ),
             )
             .into()
+        } else if self.two_phase_agg_enabled() {
+            let required_dist = Distribution::HashShard(self.group_keys().to_vec());
+            let input = self.input().to_stream();
+            let input_dist = input.distribution();
+            if input_dist.satisfies(&Distribution::AnyShard)
+                && !input_dist.satisfies(&required_dist)
+                && self.can_two_phase(input.append_only())
+            {
+                self.gen_two_phase_stream_agg(input)
+            } else {
+                StreamHashAgg::new(
+                    self.clone_with_input(
+                        required_dist.enforce_if_not_satisfies(input, Order::any()),
+                    ),
+                )
+                .into()
+            }
         } else {
             StreamHashAgg::new(
                 self.clone_with_input(self.input().to_stream_with_dist_required(
@@ -538,6 +734,8 @@ impl ToStream for LogicalAgg {
                 agg_kind: AggKind::Count,
                 return_type: DataType::Int64,
                 inputs: vec![],
+                distinct: false,
+                order_by_fields: vec![],
             },
         );
         agg_call_alias.insert(0, None);
@@ -716,6 +914,8 @@ mod tests {
             agg_kind: AggKind::Min,
             return_type: ty.clone(),
             inputs: vec![InputRef::new(2, ty.clone())],
+            distinct: false,
+            order_by_fields: vec![],
         };
         let agg = LogicalAgg::new(
             vec![agg_call],
@@ -775,6 +975,8 @@ mod tests {
             agg_kind: AggKind::Min,
             return_type: ty.clone(),
             inputs: vec![InputRef::new(2, ty.clone())],
+            distinct: false,
+            order_by_fields: vec![],
         };
         let agg = LogicalAgg::new(
             vec![agg_call],
@@ -843,11 +1045,15 @@ mod tests {
                 agg_kind: AggKind::Min,
                 return_type: ty.clone(),
                 inputs: vec![InputRef::new(2, ty.clone())],
+                distinct: false,
+                order_by_fields: vec![],
             },
             PlanAggCall {
                 agg_kind: AggKind::Max,
                 return_type: ty.clone(),
                 inputs: vec![InputRef::new(1, ty.clone())],
+                distinct: false,
+                order_by_fields: vec![],
             },
         ];
         let agg = LogicalAgg::new(
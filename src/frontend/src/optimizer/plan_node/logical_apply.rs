@@ -17,31 +17,46 @@ use std::fmt;
 use fixedbitset::FixedBitSet;
 use risingwave_pb::plan::JoinType;
 
-use super::{ColPrunable, LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary, ToBatch, ToStream};
-use crate::utils::ColIndexMapping;
+use super::{
+    ColPrunable, LogicalFilter, LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary,
+    PredicatePushdown, ToBatch, ToStream,
+};
+use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalApply` represents a correlated join, where the right side may refer to columns from the
 /// left side.
+///
+/// `on` refers to the columns of both sides like [`LogicalJoin`], and is only non-trivial for the
+/// `LATERAL` subqueries.
 #[derive(Debug, Clone)]
 pub struct LogicalApply {
     pub base: PlanBase,
     left: PlanRef,
     right: PlanRef,
     join_type: JoinType,
+    on: Condition,
 }
 
 impl fmt::Display for LogicalApply {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "LogicalApply {{ type: {:?} }}", &self.join_type)
+        if self.on.always_true() {
+            write!(f, "LogicalApply {{ type: {:?} }}", &self.join_type)
+        } else {
+            write!(
+                f,
+                "LogicalApply {{ type: {:?}, on: {} }}",
+                &self.join_type, &self.on
+            )
+        }
     }
 }
 
 impl LogicalApply {
-    pub(crate) fn new(left: PlanRef, right: PlanRef, join_type: JoinType) -> Self {
+    pub(crate) fn new(left: PlanRef, right: PlanRef, join_type: JoinType, on: Condition) -> Self {
         assert!(
             matches!(
                 join_type,
-                JoinType::LeftOuter | JoinType::LeftSemi | JoinType::LeftAnti
+                JoinType::Inner | JoinType::LeftOuter | JoinType::LeftSemi | JoinType::LeftAnti
             ),
             "Invalid join type {:?} for LogicalApply",
             join_type
@@ -61,17 +76,23 @@ impl LogicalApply {
             left,
             right,
             join_type,
+            on,
         }
     }
 
-    pub fn create(left: PlanRef, right: PlanRef, join_type: JoinType) -> PlanRef {
-        Self::new(left, right, join_type).into()
+    pub fn create(left: PlanRef, right: PlanRef, join_type: JoinType, on: Condition) -> PlanRef {
+        Self::new(left, right, join_type, on).into()
     }
 
     /// Get the join type of the logical apply.
     pub fn join_type(&self) -> JoinType {
         self.join_type
     }
+
+    /// Get a reference to the logical apply's on condition.
+    pub fn on(&self) -> &Condition {
+        &self.on
+    }
 }
 
 impl PlanTreeNodeBinary for LogicalApply {
@@ -84,7 +105,7 @@ impl PlanTreeNodeBinary for LogicalApply {
     }
 
     fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
-        Self::new(left, right, self.join_type)
+        Self::new(left, right, self.join_type, self.on.clone())
     }
 }
 
@@ -96,6 +117,15 @@ impl ColPrunable for LogicalApply {
     }
 }
 
+impl PredicatePushdown for LogicalApply {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        let new_left = self.left().predicate_pushdown(Condition::true_cond());
+        let new_right = self.right().predicate_pushdown(Condition::true_cond());
+        let new_apply = self.clone_with_left_right(new_left, new_right);
+        LogicalFilter::create(new_apply.into(), predicate)
+    }
+}
+
 impl ToBatch for LogicalApply {
     fn to_batch(&self) -> PlanRef {
         panic!("LogicalApply should be unnested")
@@ -19,8 +19,12 @@ use risingwave_common::catalog::{Field, Schema};
 use risingwave_common::error::Result;
 use risingwave_common::types::DataType;
 
-use super::{BatchDelete, ColPrunable, PlanBase, PlanRef, PlanTreeNodeUnary, ToBatch, ToStream};
+use super::{
+    gen_filter_and_pushdown, BatchDelete, ColPrunable, PlanBase, PlanRef, PlanTreeNodeUnary,
+    PredicatePushdown, ToBatch, ToStream,
+};
 use crate::catalog::TableId;
+use crate::utils::Condition;
 
 /// [`LogicalDelete`] iterates on input relation and delete the data from specified table.
 ///
@@ -91,6 +95,12 @@ impl ColPrunable for LogicalDelete {
     }
 }
 
+impl PredicatePushdown for LogicalDelete {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        gen_filter_and_pushdown(self, predicate, Condition::true_cond())
+    }
+}
+
 impl ToBatch for LogicalDelete {
     fn to_batch(&self) -> PlanRef {
         let new_input = self.input().to_batch();
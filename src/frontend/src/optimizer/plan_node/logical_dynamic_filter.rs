@@ -0,0 +1,179 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+
+use super::{
+    ColPrunable, LogicalProject, PlanBase, PlanRef, PlanTreeNodeBinary, PredicatePushdown,
+    StreamDynamicFilter, ToBatch, ToStream,
+};
+use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef};
+use crate::optimizer::property::Distribution;
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalDynamicFilter` returns the rows of `left` whose `left_index`-th column compares with
+/// the single value of `right` by `comparator`, e.g. `WHERE v > (SELECT max(x) FROM s)`.
+///
+/// Unlike a join, the value of `right` is not in the output, so the output schema is the same as
+/// `left`. It's only generated for streaming, where the value of `right` may change over time.
+#[derive(Debug, Clone)]
+pub struct LogicalDynamicFilter {
+    pub base: PlanBase,
+    left_index: usize,
+    comparator: ExprType,
+    left: PlanRef,
+    right: PlanRef,
+}
+
+impl LogicalDynamicFilter {
+    pub fn new(left: PlanRef, left_index: usize, comparator: ExprType, right: PlanRef) -> Self {
+        assert!(matches!(
+            comparator,
+            ExprType::LessThan
+                | ExprType::LessThanOrEqual
+                | ExprType::GreaterThan
+                | ExprType::GreaterThanOrEqual
+        ));
+        assert_eq!(right.schema().len(), 1);
+        let ctx = left.ctx();
+        let schema = left.schema().clone();
+        let pk_indices = left.pk_indices().to_vec();
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        LogicalDynamicFilter {
+            base,
+            left_index,
+            comparator,
+            left,
+            right,
+        }
+    }
+
+    pub fn left_index(&self) -> usize {
+        self.left_index
+    }
+
+    pub fn comparator(&self) -> ExprType {
+        self.comparator
+    }
+
+    /// The condition as an expression on the concatenation of `left` and `right`.
+    pub fn predicate(&self) -> ExprImpl {
+        let left_field = &self.left.schema().fields()[self.left_index];
+        let right_field = &self.right.schema().fields()[0];
+        FunctionCall::new(
+            self.comparator,
+            vec![
+                InputRef::new(self.left_index, left_field.data_type()).into(),
+                InputRef::new(self.left.schema().len(), right_field.data_type()).into(),
+            ],
+        )
+        .unwrap()
+        .into()
+    }
+}
+
+impl fmt::Display for LogicalDynamicFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LogicalDynamicFilter {{ predicate: {:?} }}",
+            self.predicate()
+        )
+    }
+}
+
+impl PlanTreeNodeBinary for LogicalDynamicFilter {
+    fn left(&self) -> PlanRef {
+        self.left.clone()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.right.clone()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(left, self.left_index, self.comparator, right)
+    }
+}
+
+impl_plan_tree_node_for_binary! { LogicalDynamicFilter }
+
+impl ColPrunable for LogicalDynamicFilter {
+    fn prune_col(&self, required_cols: &FixedBitSet) -> PlanRef {
+        self.must_contain_columns(required_cols);
+
+        let mut left_required_cols = required_cols.clone();
+        left_required_cols.insert(self.left_index);
+        let mapping = ColIndexMapping::with_remaining_columns(&left_required_cols);
+        let filter = Self::new(
+            self.left.prune_col(&left_required_cols),
+            mapping.map(self.left_index),
+            self.comparator,
+            self.right.clone(),
+        );
+
+        if required_cols == &left_required_cols {
+            filter.into()
+        } else {
+            let mut remaining_columns = FixedBitSet::with_capacity(filter.schema().len());
+            remaining_columns.extend(required_cols.ones().map(|i| mapping.map(i)));
+            LogicalProject::with_mapping(
+                filter.into(),
+                ColIndexMapping::with_remaining_columns(&remaining_columns),
+            )
+        }
+    }
+}
+
+impl PredicatePushdown for LogicalDynamicFilter {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        // The output columns are all from `left`.
+        let left = self.left.predicate_pushdown(predicate);
+        let right = self.right.predicate_pushdown(Condition::true_cond());
+        self.clone_with_left_right(left, right).into()
+    }
+}
+
+impl ToBatch for LogicalDynamicFilter {
+    fn to_batch(&self) -> PlanRef {
+        panic!("there is no dynamic filter batch operator");
+    }
+}
+
+impl ToStream for LogicalDynamicFilter {
+    fn to_stream(&self) -> PlanRef {
+        // The value of `right` is needed by every parallel unit of `left`.
+        let left = self.left.to_stream();
+        let right = self
+            .right
+            .to_stream_with_dist_required(&Distribution::Broadcast);
+        StreamDynamicFilter::new(self.clone_with_left_right(left, right)).into()
+    }
+
+    fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
+        let (left, left_col_change) = self.left.logical_rewrite_for_stream();
+        let right = self
+            .right
+            .logical_rewrite_for_stream_without_hidden_columns();
+        let filter = Self::new(
+            left,
+            left_col_change.map(self.left_index),
+            self.comparator,
+            right,
+        );
+        (filter.into(), left_col_change)
+    }
+}
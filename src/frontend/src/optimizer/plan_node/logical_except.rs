@@ -0,0 +1,135 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+use risingwave_pb::plan::SetOpType;
+
+use super::{
+    BatchHashSetOp, ColPrunable, LogicalProject, PlanBase, PlanRef, PlanTreeNodeBinary,
+    PredicatePushdown, StreamHashSetOp, ToBatch, ToStream,
+};
+use crate::optimizer::property::Distribution;
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalExcept` returns the distinct rows of `left` which don't appear in `right`.
+///
+/// Both inputs must have the same number of columns with the same types, and the output schema
+/// follows `left`. As with `GROUP BY`, NULLs are considered equal when comparing rows.
+#[derive(Debug, Clone)]
+pub struct LogicalExcept {
+    pub base: PlanBase,
+    left: PlanRef,
+    right: PlanRef,
+}
+
+impl LogicalExcept {
+    pub fn new(left: PlanRef, right: PlanRef) -> Self {
+        let ctx = left.ctx();
+        let schema = left.schema().clone();
+        // The output rows are distinct, so all the columns form the primary key.
+        let pk_indices = (0..schema.len()).collect();
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        LogicalExcept { base, left, right }
+    }
+
+    pub fn create(left: PlanRef, right: PlanRef) -> PlanRef {
+        Self::new(left, right).into()
+    }
+}
+
+impl fmt::Display for LogicalExcept {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LogicalExcept")
+    }
+}
+
+impl PlanTreeNodeBinary for LogicalExcept {
+    fn left(&self) -> PlanRef {
+        self.left.clone()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.right.clone()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(left, right)
+    }
+}
+
+impl_plan_tree_node_for_binary! { LogicalExcept }
+
+impl ColPrunable for LogicalExcept {
+    fn prune_col(&self, required_cols: &FixedBitSet) -> PlanRef {
+        self.must_contain_columns(required_cols);
+
+        // Every column takes part in the comparison of rows, so none of the input columns can be
+        // pruned. Only a projection is put on the top if needed.
+        let mut all_cols = FixedBitSet::with_capacity(self.schema().len());
+        all_cols.insert_range(..);
+        let except = Self::new(
+            self.left.prune_col(&all_cols),
+            self.right.prune_col(&all_cols),
+        );
+        if required_cols == &all_cols {
+            except.into()
+        } else {
+            LogicalProject::with_mapping(
+                except.into(),
+                ColIndexMapping::with_remaining_columns(required_cols),
+            )
+        }
+    }
+}
+
+impl PredicatePushdown for LogicalExcept {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        // Whether a row satisfying the predicate appears in `right` doesn't change after filtering
+        // `right` with the same predicate, so the predicate can be applied on both.
+        let left = self.left.predicate_pushdown(predicate.clone());
+        let right = self.right.predicate_pushdown(predicate);
+        Self::create(left, right)
+    }
+}
+
+impl ToBatch for LogicalExcept {
+    fn to_batch(&self) -> PlanRef {
+        let left = self.left().to_batch();
+        let right = self.right().to_batch();
+        BatchHashSetOp::new(left, right, SetOpType::Except).into()
+    }
+}
+
+impl ToStream for LogicalExcept {
+    fn to_stream(&self) -> PlanRef {
+        let dist = Distribution::HashShard((0..self.schema().len()).collect());
+        let left = self.left().to_stream_with_dist_required(&dist);
+        let right = self.right().to_stream_with_dist_required(&dist);
+        StreamHashSetOp::new(left, right, SetOpType::Except).into()
+    }
+
+    fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
+        let left = self
+            .left
+            .logical_rewrite_for_stream_without_hidden_columns();
+        let right = self
+            .right
+            .logical_rewrite_for_stream_without_hidden_columns();
+        let except = Self::new(left, right);
+        let out_col_change = ColIndexMapping::identity(except.schema().len());
+        (except.into(), out_col_change)
+    }
+}
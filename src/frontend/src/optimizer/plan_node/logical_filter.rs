@@ -18,7 +18,7 @@ use fixedbitset::FixedBitSet;
 
 use super::{
     ColPrunable, CollectInputRef, LogicalProject, PlanBase, PlanNode, PlanRef, PlanTreeNodeUnary,
-    ToBatch, ToStream,
+    PredicatePushdown, ToBatch, ToStream,
 };
 use crate::expr::{assert_input_ref, ExprImpl};
 use crate::optimizer::plan_node::{BatchFilter, StreamFilter};
@@ -125,6 +125,13 @@ impl ColPrunable for LogicalFilter {
     }
 }
 
+impl PredicatePushdown for LogicalFilter {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        let predicate = predicate.and(self.predicate.clone());
+        self.input.predicate_pushdown(predicate)
+    }
+}
+
 impl ToBatch for LogicalFilter {
     fn to_batch(&self) -> PlanRef {
         let new_input = self.input().to_batch();
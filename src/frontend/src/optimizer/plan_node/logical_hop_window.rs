@@ -0,0 +1,199 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::{DataType, IntervalUnit};
+
+use super::{
+    gen_filter_and_pushdown, ColPrunable, LogicalProject, PlanBase, PlanNode, PlanRef,
+    PlanTreeNodeUnary, PredicatePushdown, StreamHopWindow, ToBatch, ToStream,
+};
+use crate::expr::{Expr, InputRef};
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalHopWindow` implements the `HOP` window table function. Each input row is expanded into
+/// every hopping window its time column falls into, with `window_start` and `window_end` appended
+/// as the last two columns.
+#[derive(Debug, Clone)]
+pub struct LogicalHopWindow {
+    pub base: PlanBase,
+    input: PlanRef,
+    pub(super) time_col: InputRef,
+    pub(super) window_slide: IntervalUnit,
+    pub(super) window_size: IntervalUnit,
+}
+
+impl LogicalHopWindow {
+    fn new(
+        input: PlanRef,
+        time_col: InputRef,
+        window_slide: IntervalUnit,
+        window_size: IntervalUnit,
+    ) -> Self {
+        let ctx = input.ctx();
+        let schema = Schema::new(
+            input
+                .schema()
+                .fields()
+                .iter()
+                .cloned()
+                .chain([
+                    Field::with_name(DataType::Timestamp, "window_start"),
+                    Field::with_name(DataType::Timestamp, "window_end"),
+                ])
+                .collect(),
+        );
+        // A row may appear in several windows, so `window_start` is needed to identify it.
+        let mut pk_indices = input.pk_indices().to_vec();
+        pk_indices.push(input.schema().len());
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        LogicalHopWindow {
+            base,
+            input,
+            time_col,
+            window_slide,
+            window_size,
+        }
+    }
+
+    pub fn create(
+        input: PlanRef,
+        time_col: InputRef,
+        window_slide: IntervalUnit,
+        window_size: IntervalUnit,
+    ) -> PlanRef {
+        Self::new(input, time_col, window_slide, window_size).into()
+    }
+
+    pub fn time_col(&self) -> &InputRef {
+        &self.time_col
+    }
+
+    pub fn window_slide(&self) -> IntervalUnit {
+        self.window_slide
+    }
+
+    pub fn window_size(&self) -> IntervalUnit {
+        self.window_size
+    }
+
+    /// Extends the column mapping of the input with the two window columns.
+    fn output_col_change(&self, input_col_change: ColIndexMapping) -> ColIndexMapping {
+        let (mut map, target_size) = input_col_change.into_parts();
+        map.push(Some(target_size));
+        map.push(Some(target_size + 1));
+        ColIndexMapping::with_target_size(map, target_size + 2)
+    }
+}
+
+impl PlanTreeNodeUnary for LogicalHopWindow {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(
+            input,
+            self.time_col.clone(),
+            self.window_slide,
+            self.window_size,
+        )
+    }
+
+    #[must_use]
+    fn rewrite_with_input(
+        &self,
+        input: PlanRef,
+        input_col_change: ColIndexMapping,
+    ) -> (Self, ColIndexMapping) {
+        let time_col = InputRef::new(
+            input_col_change.map(self.time_col.index()),
+            self.time_col.return_type(),
+        );
+        let hop = Self::new(input, time_col, self.window_slide, self.window_size);
+        (hop, self.output_col_change(input_col_change))
+    }
+}
+impl_plan_tree_node_for_unary! {LogicalHopWindow}
+
+impl fmt::Display for LogicalHopWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LogicalHopWindow {{ time_col: {}, slide: {}, size: {} }}",
+            self.input.schema().fields()[self.time_col.index()].name,
+            self.window_slide,
+            self.window_size
+        )
+    }
+}
+
+impl ColPrunable for LogicalHopWindow {
+    fn prune_col(&self, required_cols: &FixedBitSet) -> PlanRef {
+        self.must_contain_columns(required_cols);
+
+        let input_len = self.input.schema().len();
+        let mut input_required_cols = FixedBitSet::with_capacity(input_len);
+        input_required_cols.extend(required_cols.ones().filter(|&i| i < input_len));
+        input_required_cols.insert(self.time_col.index());
+
+        let mapping = ColIndexMapping::with_remaining_columns(&input_required_cols);
+        let (hop, output_col_change) =
+            self.rewrite_with_input(self.input.prune_col(&input_required_cols), mapping);
+
+        let output_required_cols = required_cols
+            .ones()
+            .map(|i| output_col_change.map(i))
+            .collect_vec();
+        if output_required_cols.len() == hop.schema().len() {
+            hop.into()
+        } else {
+            let mut remaining_columns = FixedBitSet::with_capacity(hop.schema().len());
+            remaining_columns.extend(output_required_cols);
+            LogicalProject::with_mapping(
+                hop.into(),
+                ColIndexMapping::with_remaining_columns(&remaining_columns),
+            )
+        }
+    }
+}
+
+impl PredicatePushdown for LogicalHopWindow {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        gen_filter_and_pushdown(self, predicate, Condition::true_cond())
+    }
+}
+
+impl ToBatch for LogicalHopWindow {
+    fn to_batch(&self) -> PlanRef {
+        panic!("there is no hop window batch operator");
+    }
+}
+
+impl ToStream for LogicalHopWindow {
+    fn to_stream(&self) -> PlanRef {
+        let new_input = self.input().to_stream();
+        let new_logical = self.clone_with_input(new_input);
+        StreamHopWindow::new(new_logical).into()
+    }
+
+    fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
+        let (input, input_col_change) = self.input.logical_rewrite_for_stream();
+        let (hop, out_col_change) = self.rewrite_with_input(input, input_col_change);
+        (hop.into(), out_col_change)
+    }
+}
@@ -19,8 +19,13 @@ use risingwave_common::catalog::{Field, Schema};
 use risingwave_common::error::Result;
 use risingwave_common::types::DataType;
 
-use super::{BatchInsert, ColPrunable, PlanBase, PlanRef, PlanTreeNodeUnary, ToBatch, ToStream};
+use super::{
+    gen_filter_and_pushdown, BatchInsert, ColPrunable, PlanBase, PlanRef, PlanTreeNodeUnary,
+    PredicatePushdown, ToBatch, ToStream,
+};
 use crate::catalog::TableId;
+use crate::expr::ExprImpl;
+use crate::utils::Condition;
 
 /// `LogicalInsert` iterates on input relation and insert the data into specified table.
 ///
@@ -31,12 +36,29 @@ pub struct LogicalInsert {
     pub base: PlanBase,
     table_source_name: String, // explain-only
     source_id: TableId,        // TODO: use SourceId
+    on_conflict: Option<InsertOnConflict>,
     input: PlanRef,
 }
 
+/// How a [`LogicalInsert`] resolves the rows conflicting with the existing ones on the primary
+/// key of the table, see [`BoundOnConflict`](crate::binder::BoundOnConflict).
+#[derive(Debug, Clone)]
+pub struct InsertOnConflict {
+    /// Indices of the primary key columns in the inserted rows.
+    pub pk_indices: Vec<usize>,
+    /// The new values of the inserted columns of a conflicting row, over the columns of the
+    /// existing row followed by those of the row proposed for insertion. `None` for `DO NOTHING`.
+    pub update_exprs: Option<Vec<ExprImpl>>,
+}
+
 impl LogicalInsert {
     /// Create a [`LogicalInsert`] node. Used internally by optimizer.
-    pub fn new(input: PlanRef, table_source_name: String, source_id: TableId) -> Self {
+    pub fn new(
+        input: PlanRef,
+        table_source_name: String,
+        source_id: TableId,
+        on_conflict: Option<InsertOnConflict>,
+    ) -> Self {
         let ctx = input.ctx();
         let schema = Schema::new(vec![Field::unnamed(DataType::Int64)]);
         let base = PlanBase::new_logical(ctx, schema, vec![]);
@@ -44,17 +66,29 @@ impl LogicalInsert {
             base,
             table_source_name,
             source_id,
+            on_conflict,
             input,
         }
     }
 
     /// Create a [`LogicalInsert`] node. Used by planner.
-    pub fn create(input: PlanRef, table_source_name: String, source_id: TableId) -> Result<Self> {
-        Ok(Self::new(input, table_source_name, source_id))
+    pub fn create(
+        input: PlanRef,
+        table_source_name: String,
+        source_id: TableId,
+        on_conflict: Option<InsertOnConflict>,
+    ) -> Result<Self> {
+        Ok(Self::new(input, table_source_name, source_id, on_conflict))
     }
 
     pub(super) fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
-        write!(f, "{} {{ table: {} }}", name, self.table_source_name)
+        write!(f, "{} {{ table: {}", name, self.table_source_name)?;
+        match self.on_conflict.as_ref().map(|c| &c.update_exprs) {
+            None => {}
+            Some(None) => write!(f, ", on_conflict: DoNothing")?,
+            Some(Some(exprs)) => write!(f, ", on_conflict: DoUpdate({:?})", exprs)?,
+        }
+        write!(f, " }}")
     }
 
     /// Get the logical insert's source id.
@@ -62,6 +96,11 @@ impl LogicalInsert {
     pub fn source_id(&self) -> TableId {
         self.source_id
     }
+
+    /// Get the logical insert's `ON CONFLICT`.
+    pub fn on_conflict(&self) -> Option<&InsertOnConflict> {
+        self.on_conflict.as_ref()
+    }
 }
 
 impl PlanTreeNodeUnary for LogicalInsert {
@@ -70,7 +109,12 @@ impl PlanTreeNodeUnary for LogicalInsert {
     }
 
     fn clone_with_input(&self, input: PlanRef) -> Self {
-        Self::new(input, self.table_source_name.clone(), self.source_id)
+        Self::new(
+            input,
+            self.table_source_name.clone(),
+            self.source_id,
+            self.on_conflict.clone(),
+        )
     }
 }
 
@@ -91,6 +135,12 @@ impl ColPrunable for LogicalInsert {
     }
 }
 
+impl PredicatePushdown for LogicalInsert {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        gen_filter_and_pushdown(self, predicate, Condition::true_cond())
+    }
+}
+
 impl ToBatch for LogicalInsert {
     fn to_batch(&self) -> PlanRef {
         let new_input = self.input().to_batch();
@@ -16,17 +16,19 @@ use std::fmt;
 
 use fixedbitset::FixedBitSet;
 use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::plan::JoinType;
 
 use super::{
-    ColPrunable, LogicalProject, PlanBase, PlanNode, PlanRef, PlanTreeNodeBinary, StreamHashJoin,
-    ToBatch, ToStream,
+    ColPrunable, LogicalProject, PlanBase, PlanNode, PlanRef, PlanTreeNodeBinary,
+    PlanTreeNodeUnary, PredicatePushdown, StreamHashJoin, ToBatch, ToStream,
 };
 use crate::expr::ExprImpl;
 use crate::optimizer::plan_node::{
-    BatchFilter, BatchHashJoin, CollectInputRef, EqJoinPredicate, LogicalFilter, StreamFilter,
+    BatchFilter, BatchHashJoin, BatchSortMergeJoin, CollectInputRef, EqJoinPredicate,
+    LogicalFilter, StreamFilter, StreamNestedLoopJoin, BROADCAST_JOIN_MAX_ROWS,
 };
-use crate::optimizer::property::Distribution;
+use crate::optimizer::property::{Distribution, Order};
 use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalJoin` combines two relations according to some condition.
@@ -225,6 +227,92 @@ impl LogicalJoin {
     pub fn clone_with_cond(&self, cond: Condition) -> Self {
         Self::new(self.left.clone(), self.right.clone(), self.join_type, cond)
     }
+
+    /// Try to split and pushdown `predicate` into a join's left/right child or the on clause.
+    /// Returns the pushed predicates. The pushed part will be removed from the original predicate.
+    ///
+    /// `InputRef`s in the right `Condition` are shifted by `-left_col_num`.
+    fn push_down(
+        predicate: &mut Condition,
+        left_col_num: usize,
+        right_col_num: usize,
+        push_left: bool,
+        push_right: bool,
+        push_on: bool,
+    ) -> (Option<Condition>, Option<Condition>, Option<Condition>) {
+        let conjunctions = std::mem::take(&mut predicate.conjunctions);
+        let (left, right, mut others) =
+            Condition { conjunctions }.split(left_col_num, right_col_num);
+
+        let mut cannot_pushed = vec![];
+
+        let left = if push_left {
+            Some(left)
+        } else {
+            cannot_pushed.extend(left);
+            None
+        };
+
+        let right = if push_right {
+            let mut mapping = ColIndexMapping::with_shift_offset(
+                left_col_num + right_col_num,
+                -(left_col_num as isize),
+            );
+            Some(right.rewrite_expr(&mut mapping))
+        } else {
+            cannot_pushed.extend(right);
+            None
+        };
+
+        let on = if push_on {
+            others
+                .conjunctions
+                .extend(std::mem::take(&mut cannot_pushed));
+            Some(others)
+        } else {
+            cannot_pushed.extend(others);
+            None
+        };
+
+        predicate.conjunctions = cannot_pushed;
+
+        (left, right, on)
+    }
+
+    fn can_push_left_from_filter(ty: JoinType) -> bool {
+        matches!(
+            ty,
+            JoinType::Inner | JoinType::LeftOuter | JoinType::LeftSemi | JoinType::LeftAnti
+        )
+    }
+
+    fn can_push_right_from_filter(ty: JoinType) -> bool {
+        matches!(
+            ty,
+            JoinType::Inner | JoinType::RightOuter | JoinType::RightSemi | JoinType::RightAnti
+        )
+    }
+
+    fn can_push_on_from_filter(ty: JoinType) -> bool {
+        matches!(
+            ty,
+            JoinType::Inner | JoinType::LeftSemi | JoinType::RightSemi
+        )
+    }
+
+    fn can_push_left_from_on(ty: JoinType) -> bool {
+        matches!(
+            ty,
+            JoinType::Inner | JoinType::RightOuter | JoinType::LeftSemi
+        )
+    }
+
+    fn can_push_right_from_on(ty: JoinType) -> bool {
+        matches!(
+            ty,
+            JoinType::Inner | JoinType::LeftOuter | JoinType::RightSemi
+        )
+    }
 }
 
 impl PlanTreeNodeBinary for LogicalJoin {
@@ -320,6 +408,74 @@ impl ColPrunable for LogicalJoin {
     }
 }
 
+/// Pushes predicates above and within a join node into the join node and/or its children nodes.
+///
+/// # Which predicates can be pushed
+///
+/// For inner join, we can do all kinds of pushdown.
+///
+/// For left/right semi join, we can push filter to left/right and on-clause,
+/// and push on-clause to left/right.
+///
+/// For left/right anti join, we can push filter to left/right, but on-clause can not be pushed
+///
+/// ## Outer Join
+///
+/// Preserved Row table
+/// : The table in an Outer Join that must return all rows.
+///
+/// Null Supplying table
+/// : This is the table that has nulls filled in for its columns in unmatched rows.
+///
+/// |                          | Preserved Row table | Null Supplying table |
+/// |--------------------------|---------------------|----------------------|
+/// | Join predicate (on)      | Not Pushed          | Pushed               |
+/// | Where predicate (filter) | Pushed              | Not Pushed           |
+impl PredicatePushdown for LogicalJoin {
+    fn predicate_pushdown(&self, mut predicate: Condition) -> PlanRef {
+        let join_type = self.join_type();
+        let left_col_num = self.left().schema().len();
+        let right_col_num = self.right().schema().len();
+
+        let (left_from_filter, right_from_filter, on) = LogicalJoin::push_down(
+            &mut predicate,
+            left_col_num,
+            right_col_num,
+            LogicalJoin::can_push_left_from_filter(join_type),
+            LogicalJoin::can_push_right_from_filter(join_type),
+            LogicalJoin::can_push_on_from_filter(join_type),
+        );
+
+        let mut new_on = self.on().clone();
+        if let Some(on) = on {
+            new_on = new_on.and(on);
+        }
+
+        let (left_from_on, right_from_on, on) = LogicalJoin::push_down(
+            &mut new_on,
+            left_col_num,
+            right_col_num,
+            LogicalJoin::can_push_left_from_on(join_type),
+            LogicalJoin::can_push_right_from_on(join_type),
+            false,
+        );
+        assert!(on.is_none(), "On-clause should not be pushed to on-clause.");
+
+        let left_predicate = left_from_filter
+            .unwrap_or_else(Condition::true_cond)
+            .and(left_from_on.unwrap_or_else(Condition::true_cond));
+        let right_predicate = right_from_filter
+            .unwrap_or_else(Condition::true_cond)
+            .and(right_from_on.unwrap_or_else(Condition::true_cond));
+
+        let new_left = self.left().predicate_pushdown(left_predicate);
+        let new_right = self.right().predicate_pushdown(right_predicate);
+        let new_join = LogicalJoin::new(new_left, new_right, join_type, new_on);
+
+        LogicalFilter::create(new_join.into(), predicate)
+    }
+}
+
 impl ToBatch for LogicalJoin {
     fn to_batch(&self) -> PlanRef {
         let predicate = EqJoinPredicate::create(
@@ -343,11 +499,11 @@ impl ToBatch for LogicalJoin {
                     self.left.schema().len(),
                 );
                 let logical_join = logical_join.clone_with_cond(eq_cond.eq_cond());
-                let hash_join = BatchHashJoin::new(logical_join, eq_cond).into();
-                let logical_filter = LogicalFilter::new(hash_join, predicate.non_eq_cond());
+                let eq_join = self.to_batch_eq_join(logical_join, eq_cond);
+                let logical_filter = LogicalFilter::new(eq_join, predicate.non_eq_cond());
                 BatchFilter::new(logical_filter).into()
             } else {
-                BatchHashJoin::new(logical_join, predicate).into()
+                self.to_batch_eq_join(logical_join, predicate)
             }
         } else {
             // Convert to Nested-loop Join for non-equal joins
@@ -356,6 +512,106 @@ impl ToBatch for LogicalJoin {
     }
 }
 
+impl LogicalJoin {
+    /// Converts an equi-join to a [`BatchSortMergeJoin`] if both inputs are already sorted on the
+    /// join keys, e.g. scans of materialized views arranged on them. Otherwise, converts it to a
+    /// [`BatchHashJoin`].
+    fn to_batch_eq_join(&self, logical_join: LogicalJoin, predicate: EqJoinPredicate) -> PlanRef {
+        if self.join_type == JoinType::Inner {
+            if let Some(merge_keys) = BatchSortMergeJoin::try_merge_keys(
+                &logical_join.left(),
+                &logical_join.right(),
+                &predicate,
+                Self::estimate_row_count(&self.right),
+            ) {
+                return BatchSortMergeJoin::new(logical_join, predicate, merge_keys).into();
+            }
+        }
+        BatchHashJoin::new(logical_join, predicate).into()
+    }
+
+    /// Checks whether the join can be converted to a stream plan. A join without equi-conditions
+    /// becomes a [`StreamNestedLoopJoin`], which broadcasts the right side, so the right side must
+    /// be small enough.
+    pub fn check_to_stream(&self) -> Result<()> {
+        let predicate = EqJoinPredicate::create(
+            self.left.schema().len(),
+            self.right.schema().len(),
+            self.on.clone(),
+        );
+        if predicate.has_eq() {
+            return Ok(());
+        }
+        if !matches!(self.join_type, JoinType::Inner | JoinType::LeftOuter) {
+            return Err(ErrorCode::NotImplemented(
+                format!(
+                    "streaming {:?} join without equi-conditions",
+                    self.join_type
+                ),
+                None.into(),
+            )
+            .into());
+        }
+        let estimated_rows = Self::estimate_row_count(&self.right);
+        if !StreamNestedLoopJoin::can_broadcast(&self.ctx(), estimated_rows) {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "the right side of a streaming join without equi-conditions is too large to be \
+                 broadcast, estimated rows: {}, consider adding an equi-condition or setting `{}`",
+                estimated_rows.map_or_else(|| "unbounded".to_string(), |rows| rows.to_string()),
+                BROADCAST_JOIN_MAX_ROWS
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Converts a join without equi-conditions to a [`StreamNestedLoopJoin`]. The join must have
+    /// passed [`LogicalJoin::check_to_stream`].
+    fn to_stream_nested_loop_join(&self) -> PlanRef {
+        debug_assert!(self.check_to_stream().is_ok());
+
+        let left = self.left().to_stream();
+        // The left side stays where it is, unless it's not partitioned in a determined way.
+        let left = if matches!(
+            left.distribution(),
+            Distribution::Single | Distribution::HashShard(_)
+        ) {
+            left
+        } else {
+            Distribution::HashShard(self.left.pk_indices().to_vec())
+                .enforce_if_not_satisfies(left, Order::any())
+        };
+        let right = self
+            .right()
+            .to_stream_with_dist_required(&Distribution::Broadcast);
+        StreamNestedLoopJoin::new(self.clone_with_left_right(left, right)).into()
+    }
+
+    /// Estimates the number of rows of a logical plan from its structure. Returns `None` if the
+    /// number is unbounded or unknown, e.g. for a table scan.
+    pub fn estimate_row_count(plan: &PlanRef) -> Option<usize> {
+        if let Some(values) = plan.as_logical_values() {
+            Some(values.rows().len())
+        } else if let Some(agg) = plan.as_logical_agg() {
+            if agg.group_keys().is_empty() {
+                Some(1)
+            } else {
+                Self::estimate_row_count(&agg.input())
+            }
+        } else if let Some(limit) = plan.as_logical_limit() {
+            Some(limit.limit())
+        } else if let Some(top_n) = plan.as_logical_top_n() {
+            Some(top_n.limit())
+        } else if let Some(project) = plan.as_logical_project() {
+            Self::estimate_row_count(&project.input())
+        } else if let Some(filter) = plan.as_logical_filter() {
+            Self::estimate_row_count(&filter.input())
+        } else {
+            None
+        }
+    }
+}
+
 impl ToStream for LogicalJoin {
     fn to_stream(&self) -> PlanRef {
         let predicate = EqJoinPredicate::create(
@@ -363,6 +619,10 @@ impl ToStream for LogicalJoin {
             self.right.schema().len(),
             self.on.clone(),
         );
+        if !predicate.has_eq() {
+            // Convert to Nested-loop Join for non-equal joins
+            return self.to_stream_nested_loop_join();
+        }
         let left = self
             .left()
             .to_stream_with_dist_required(&Distribution::HashShard(predicate.left_eq_indexes()));
@@ -371,26 +631,21 @@ impl ToStream for LogicalJoin {
             .to_stream_with_dist_required(&Distribution::HashShard(predicate.right_eq_indexes()));
         let logical_join = self.clone_with_left_right(left, right);
 
-        if predicate.has_eq() {
-            // Convert to Hash Join for equal joins
-            // For inner joins, pull non-equal conditions to a filter operator on top of it
-            let pull_filter = self.join_type == JoinType::Inner && predicate.has_non_eq();
-            if pull_filter {
-                let eq_cond = EqJoinPredicate::new(
-                    Condition::true_cond(),
-                    predicate.eq_keys().to_vec(),
-                    self.left.schema().len(),
-                );
-                let logical_join = logical_join.clone_with_cond(eq_cond.eq_cond());
-                let hash_join = StreamHashJoin::new(logical_join, eq_cond).into();
-                let logical_filter = LogicalFilter::new(hash_join, predicate.non_eq_cond());
-                StreamFilter::new(logical_filter).into()
-            } else {
-                StreamHashJoin::new(logical_join, predicate).into()
-            }
+        // Convert to Hash Join for equal joins
+        // For inner joins, pull non-equal conditions to a filter operator on top of it
+        let pull_filter = self.join_type == JoinType::Inner && predicate.has_non_eq();
+        if pull_filter {
+            let eq_cond = EqJoinPredicate::new(
+                Condition::true_cond(),
+                predicate.eq_keys().to_vec(),
+                self.left.schema().len(),
+            );
+            let logical_join = logical_join.clone_with_cond(eq_cond.eq_cond());
+            let hash_join = StreamHashJoin::new(logical_join, eq_cond).into();
+            let logical_filter = LogicalFilter::new(hash_join, predicate.non_eq_cond());
+            StreamFilter::new(logical_filter).into()
         } else {
-            // Convert to Nested-loop Join for non-equal joins
-            todo!("nested loop join")
+            StreamHashJoin::new(logical_join, predicate).into()
         }
     }
 
@@ -406,12 +661,15 @@ impl ToStream for LogicalJoin {
 #[cfg(test)]
 mod tests {
 
+    use rand::Rng;
     use risingwave_common::catalog::Field;
     use risingwave_common::types::{DataType, Datum};
     use risingwave_pb::expr::expr_node::Type;
 
     use super::*;
-    use crate::expr::{assert_eq_input_ref, FunctionCall, InputRef, Literal};
+    use crate::expr::{
+        assert_eq_input_ref, Expr, ExprImpl, ExprType, FunctionCall, InputRef, Literal,
+    };
     use crate::optimizer::plan_node::{LogicalValues, PlanTreeNodeUnary};
     use crate::session::OptimizerContext;
 
@@ -743,4 +1001,233 @@ mod tests {
         // let hash_join = result.as_stream_hash_join().unwrap();
         // assert_eq!(hash_join.eq_join_predicate().all_cond().as_expr(), on_cond);
     }
+
+    /// Convert
+    /// ```text
+    /// Join(join_type: inner, on: $1 < $2)
+    ///   Values(v1, v2)
+    ///   Values(v3) with 2 rows
+    /// ```
+    /// to
+    /// ```text
+    /// NestedLoopJoin(join_type: inner, on: $1 < $2)
+    ///   Values(v1, v2)
+    ///   Exchange(dist: broadcast)
+    ///     Values(v3)
+    /// ```
+    #[tokio::test]
+    async fn test_join_to_stream_nested_loop() {
+        let ctx = OptimizerContext::mock().await;
+        let fields: Vec<Field> = (1..4)
+            .map(|i| Field::with_name(DataType::Int32, format!("v{}", i)))
+            .collect();
+        let left = LogicalValues::new(
+            vec![],
+            Schema {
+                fields: fields[0..2].to_vec(),
+            },
+            ctx.clone(),
+        );
+        let rows = (0..2_i32)
+            .map(|i| {
+                vec![ExprImpl::Literal(Box::new(Literal::new(
+                    Datum::Some(i.into()),
+                    DataType::Int32,
+                )))]
+            })
+            .collect();
+        let right = LogicalValues::new(
+            rows,
+            Schema {
+                fields: fields[2..3].to_vec(),
+            },
+            ctx,
+        );
+        let on_cond = ExprImpl::FunctionCall(Box::new(
+            FunctionCall::new(
+                Type::LessThan,
+                vec![
+                    ExprImpl::InputRef(Box::new(InputRef::new(1, DataType::Int32))),
+                    ExprImpl::InputRef(Box::new(InputRef::new(2, DataType::Int32))),
+                ],
+            )
+            .unwrap(),
+        ));
+
+        let right: PlanRef = right.into();
+        assert_eq!(LogicalJoin::estimate_row_count(&right), Some(2));
+        let logical_join = LogicalJoin::new(
+            left.into(),
+            right,
+            JoinType::Inner,
+            Condition::with_expr(on_cond.clone()),
+        );
+
+        // Perform `to_stream`
+        let result = logical_join.to_stream();
+
+        // Expected plan: NestedLoopJoin($1 < $2) with the right side broadcast
+        let nested_loop_join = result.as_stream_nested_loop_join().unwrap();
+        assert_eq!(
+            nested_loop_join.right().distribution(),
+            &Distribution::Broadcast
+        );
+        assert_eq!(nested_loop_join.schema().fields(), &fields[..]);
+        assert_eq!(ExprImpl::from(logical_join.on().clone()), on_cond);
+    }
+
+    #[test]
+    fn test_push_down() {
+        let left_col_num = 3;
+        let right_col_num = 2;
+
+        let ty = DataType::Int32;
+
+        let mut rng = rand::thread_rng();
+        let left: ExprImpl = FunctionCall::new(
+            ExprType::Equal,
+            vec![
+                InputRef::new(rng.gen_range(0..left_col_num), ty.clone()).into(),
+                InputRef::new(rng.gen_range(0..left_col_num), ty.clone()).into(),
+            ],
+        )
+        .unwrap()
+        .into();
+        let right_inputs = vec![
+            InputRef::new(
+                rng.gen_range(left_col_num..left_col_num + right_col_num),
+                ty.clone(),
+            )
+            .into(),
+            InputRef::new(
+                rng.gen_range(left_col_num..left_col_num + right_col_num),
+                ty.clone(),
+            )
+            .into(),
+        ];
+        let right: ExprImpl = FunctionCall::new(ExprType::LessThan, right_inputs.clone())
+            .unwrap()
+            .into();
+        let right_inputs_shifted = right_inputs
+            .iter()
+            .map(|input| match input {
+                ExprImpl::InputRef(i) => {
+                    InputRef::new(i.index() - left_col_num, i.return_type()).into()
+                }
+                _ => panic!("Expect InputRef, got {:?}", input),
+            })
+            .collect();
+        let right_shifted: ExprImpl = FunctionCall::new(ExprType::LessThan, right_inputs_shifted)
+            .unwrap()
+            .into();
+        let other: ExprImpl = FunctionCall::new(
+            ExprType::GreaterThan,
+            vec![
+                InputRef::new(rng.gen_range(0..left_col_num), ty.clone()).into(),
+                InputRef::new(
+                    rng.gen_range(left_col_num..left_col_num + right_col_num),
+                    ty,
+                )
+                .into(),
+            ],
+        )
+        .unwrap()
+        .into();
+
+        let predicate = Condition::with_expr(other.clone())
+            .and(Condition::with_expr(right.clone()))
+            .and(Condition::with_expr(left.clone()));
+
+        // Only push to left
+        let mut predicate_push_left = predicate.clone();
+        let (left_pushed, right_pushed, on_pushed) = LogicalJoin::push_down(
+            &mut predicate_push_left,
+            left_col_num,
+            right_col_num,
+            true,
+            false,
+            false,
+        );
+
+        assert_eq!(left_pushed.unwrap().conjunctions, vec![left.clone()]);
+        assert!(right_pushed.is_none());
+        assert!(on_pushed.is_none());
+        if predicate_push_left.conjunctions[0] != other {
+            assert_eq!(
+                predicate_push_left.conjunctions,
+                vec![right.clone(), other.clone()]
+            );
+        } else {
+            assert_eq!(
+                predicate_push_left.conjunctions,
+                vec![other.clone(), right.clone()]
+            );
+        }
+
+        // Only push to right
+        let mut predicate_push_right = predicate.clone();
+        let (left_pushed, right_pushed, on_pushed) = LogicalJoin::push_down(
+            &mut predicate_push_right,
+            left_col_num,
+            right_col_num,
+            false,
+            true,
+            false,
+        );
+
+        assert!(left_pushed.is_none());
+        assert_eq!(
+            right_pushed.unwrap().conjunctions,
+            vec![right_shifted.clone()]
+        );
+        assert!(on_pushed.is_none());
+        if predicate_push_right.conjunctions[0] != other {
+            assert_eq!(
+                predicate_push_right.conjunctions,
+                vec![left.clone(), other.clone()]
+            );
+        } else {
+            assert_eq!(
+                predicate_push_right.conjunctions,
+                vec![other.clone(), left.clone()]
+            );
+        }
+
+        // Push to left, and on
+        let mut predicate_push_left_on = predicate.clone();
+        let (left_pushed, right_pushed, on_pushed) = LogicalJoin::push_down(
+            &mut predicate_push_left_on,
+            left_col_num,
+            right_col_num,
+            true,
+            false,
+            true,
+        );
+
+        assert_eq!(left_pushed.unwrap().conjunctions, vec![left.clone()]);
+        assert!(right_pushed.is_none());
+        let on_pushed = on_pushed.unwrap();
+        if on_pushed.conjunctions[0] != other {
+            assert_eq!(on_pushed.conjunctions, vec![right, other.clone()]);
+        } else {
+            assert_eq!(on_pushed.conjunctions, vec![other.clone(), right]);
+        }
+        assert_eq!(predicate_push_left_on.conjunctions, vec![]);
+
+        // Push to left, right and on
+        let mut predicate_push_all = predicate;
+        let (left_pushed, right_pushed, on_pushed) = LogicalJoin::push_down(
+            &mut predicate_push_all,
+            left_col_num,
+            right_col_num,
+            true,
+            true,
+            true,
+        );
+
+        assert_eq!(left_pushed.unwrap().conjunctions, vec![left]);
+        assert_eq!(right_pushed.unwrap().conjunctions, vec![right_shifted]);
+        assert_eq!(on_pushed.unwrap().conjunctions, vec![other]);
+        assert_eq!(predicate_push_all.conjunctions, vec![]);
+    }
 }
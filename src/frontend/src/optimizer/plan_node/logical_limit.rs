@@ -17,9 +17,10 @@ use std::fmt;
 use fixedbitset::FixedBitSet;
 
 use super::{
-    BatchLimit, ColPrunable, PlanBase, PlanNode, PlanRef, PlanTreeNodeUnary, ToBatch, ToStream,
+    gen_filter_and_pushdown, BatchLimit, ColPrunable, PlanBase, PlanNode, PlanRef,
+    PlanTreeNodeUnary, PredicatePushdown, ToBatch, ToStream,
 };
-use crate::utils::ColIndexMapping;
+use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalLimit` fetches up to `limit` rows from `offset`
 #[derive(Debug, Clone)]
@@ -96,6 +97,12 @@ impl ColPrunable for LogicalLimit {
     }
 }
 
+impl PredicatePushdown for LogicalLimit {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        gen_filter_and_pushdown(self, predicate, Condition::true_cond())
+    }
+}
+
 impl ToBatch for LogicalLimit {
     fn to_batch(&self) -> PlanRef {
         let new_input = self.input().to_batch();
@@ -0,0 +1,103 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::rc::Rc;
+
+use fixedbitset::FixedBitSet;
+use risingwave_common::catalog::Schema;
+
+use super::{
+    BatchPeekSource, ColPrunable, LogicalFilter, LogicalProject, PlanBase, PlanNode, PlanRef,
+    PredicatePushdown, ToBatch, ToStream,
+};
+use crate::binder::BoundPeekSource;
+use crate::catalog::source_catalog::SourceCatalog;
+use crate::session::OptimizerContextRef;
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalPeekSource` reads up to `limit` raw messages of a source, for `peek_source`.
+#[derive(Debug, Clone)]
+pub struct LogicalPeekSource {
+    pub base: PlanBase,
+    pub source_catalog: Rc<SourceCatalog>,
+    limit: usize,
+}
+
+impl LogicalPeekSource {
+    pub fn new(source_catalog: Rc<SourceCatalog>, limit: usize, ctx: OptimizerContextRef) -> Self {
+        let schema = Schema::new(BoundPeekSource::fields());
+        let base = PlanBase::new_logical(ctx, schema, vec![]);
+        LogicalPeekSource {
+            base,
+            source_catalog,
+            limit,
+        }
+    }
+
+    pub fn create(
+        source_catalog: Rc<SourceCatalog>,
+        limit: usize,
+        ctx: OptimizerContextRef,
+    ) -> PlanRef {
+        Self::new(source_catalog, limit, ctx).into()
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl_plan_tree_node_for_leaf! {LogicalPeekSource}
+
+impl fmt::Display for LogicalPeekSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LogicalPeekSource {{ source: {}, limit: {} }}",
+            self.source_catalog.name, self.limit
+        )
+    }
+}
+
+impl ColPrunable for LogicalPeekSource {
+    fn prune_col(&self, required_cols: &FixedBitSet) -> PlanRef {
+        self.must_contain_columns(required_cols);
+        let mapping = ColIndexMapping::with_remaining_columns(required_cols);
+        LogicalProject::with_mapping(self.clone().into(), mapping)
+    }
+}
+
+impl PredicatePushdown for LogicalPeekSource {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        LogicalFilter::create(self.clone().into(), predicate)
+    }
+}
+
+impl ToBatch for LogicalPeekSource {
+    fn to_batch(&self) -> PlanRef {
+        BatchPeekSource::new(self.clone()).into()
+    }
+}
+
+/// `peek_source` is rejected in streaming queries by the `StreamPlanChecker`.
+impl ToStream for LogicalPeekSource {
+    fn to_stream(&self) -> PlanRef {
+        unreachable!("peek_source is only supported in batch queries")
+    }
+
+    fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
+        unreachable!("peek_source is only supported in batch queries")
+    }
+}
@@ -20,15 +20,16 @@ use itertools::Itertools;
 use risingwave_common::catalog::{Field, Schema};
 
 use super::{
-    BatchProject, ColPrunable, PlanBase, PlanNode, PlanRef, PlanTreeNodeUnary, StreamProject,
-    ToBatch, ToStream,
+    gen_filter_and_pushdown, BatchProject, ColPrunable, PlanBase, PlanNode, PlanRef,
+    PlanTreeNodeUnary, PredicatePushdown, StreamProject, ToBatch, ToStream,
 };
 use crate::expr::{
-    as_alias_display, assert_input_ref, Expr, ExprImpl, ExprRewriter, ExprVisitor, InputRef,
+    as_alias_display, assert_input_ref, Expr, ExprImpl, ExprRewriter, ExprVisitor, FunctionCall,
+    InputRef,
 };
 use crate::optimizer::plan_node::CollectInputRef;
 use crate::optimizer::property::{Distribution, Order};
-use crate::utils::ColIndexMapping;
+use crate::utils::{ColIndexMapping, Condition, Substitute};
 
 /// `LogicalProject` computes a set of expressions from its input relation.
 #[derive(Debug, Clone)]
@@ -185,6 +186,63 @@ impl LogicalProject {
             .finish()
     }
 
+    /// Pulls the stateful calls nested in the expressions, e.g. `monotonic_id() + 1`, out into a
+    /// new project below, as the streaming executor only checkpoints the states of top-level
+    /// expressions.
+    fn split_nested_stateful_calls(self) -> PlanRef {
+        struct Extract {
+            input_len: usize,
+            calls: Vec<ExprImpl>,
+        }
+
+        impl ExprRewriter for Extract {
+            fn rewrite_function_call(&mut self, func_call: FunctionCall) -> ExprImpl {
+                if func_call.is_stateful() {
+                    let index = self.input_len + self.calls.len();
+                    let input_ref = InputRef::new(index, func_call.return_type());
+                    self.calls.push(func_call.into());
+                    return input_ref.into();
+                }
+                let (func_type, inputs, ret) = func_call.decompose();
+                let inputs = inputs
+                    .into_iter()
+                    .map(|expr| self.rewrite_expr(expr))
+                    .collect();
+                FunctionCall::new_with_return_type(func_type, inputs, ret).into()
+            }
+        }
+
+        let mut extract = Extract {
+            input_len: self.input.schema().len(),
+            calls: vec![],
+        };
+        let exprs = self
+            .exprs
+            .iter()
+            .cloned()
+            .map(|expr| match expr {
+                ExprImpl::FunctionCall(call) if call.is_stateful() => ExprImpl::FunctionCall(call),
+                expr => extract.rewrite_expr(expr),
+            })
+            .collect_vec();
+        if extract.calls.is_empty() {
+            return self.into();
+        }
+
+        let inner_exprs = self
+            .input
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| InputRef::new(i, field.data_type()).into())
+            .chain(extract.calls)
+            .collect_vec();
+        let inner_alias = vec![None; inner_exprs.len()];
+        let inner = Self::new(self.input.clone(), inner_exprs, inner_alias);
+        Self::new(inner.into(), exprs, self.expr_alias).into()
+    }
+
     pub fn is_identity(&self) -> bool {
         self.schema().len() == self.input.schema().len()
             && self
@@ -270,6 +328,24 @@ impl ColPrunable for LogicalProject {
     }
 }
 
+impl PredicatePushdown for LogicalProject {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        // Predicates on volatile columns stay above the project, otherwise they would be evaluated
+        // on different results of the volatile functions.
+        let mut volatile_cols = FixedBitSet::with_capacity(self.exprs().len());
+        volatile_cols.extend(self.exprs().iter().positions(|expr| expr.is_volatile()));
+        let (kept_predicate, predicate) = predicate.split_disjoint(&volatile_cols);
+
+        // convert the predicate to one that references the child of the project
+        let mut subst = Substitute {
+            mapping: self.exprs().clone(),
+        };
+        let predicate = predicate.rewrite_expr(&mut subst);
+
+        gen_filter_and_pushdown(self, kept_predicate, predicate)
+    }
+}
+
 impl ToBatch for LogicalProject {
     fn to_batch(&self) -> PlanRef {
         let new_input = self.input().to_batch();
@@ -320,7 +396,7 @@ impl ToStream for LogicalProject {
             .unzip();
         let proj = Self::new(input, exprs, expr_alias);
         // the added columns is at the end, so it will not change the exists column index
-        (proj.into(), out_col_change)
+        (proj.split_nested_stateful_calls(), out_col_change)
     }
 }
 #[cfg(test)]
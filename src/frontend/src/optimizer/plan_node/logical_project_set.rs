@@ -0,0 +1,334 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::DataType;
+
+use super::{
+    gen_filter_and_pushdown, BatchProjectSet, ColPrunable, LogicalProject, PlanBase, PlanNode,
+    PlanRef, PlanTreeNodeUnary, PredicatePushdown, StreamProjectSet, ToBatch, ToStream,
+};
+use crate::expr::{assert_input_ref, Expr, ExprImpl, ExprType, ExprVisitor, InputRef};
+use crate::optimizer::plan_node::CollectInputRef;
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalProjectSet` evaluates a select list containing table functions like `unnest` on its
+/// input. Each input row is expanded into as many rows as the longest result of the table
+/// functions, and `projected_row_id`, the index of each row among the expanded ones, is prepended
+/// to the select list in the output.
+#[derive(Debug, Clone)]
+pub struct LogicalProjectSet {
+    pub base: PlanBase,
+    select_list: Vec<ExprImpl>,
+    input: PlanRef,
+}
+
+impl LogicalProjectSet {
+    pub fn new(input: PlanRef, select_list: Vec<ExprImpl>) -> Self {
+        let ctx = input.ctx();
+        for expr in &select_list {
+            assert_input_ref!(expr, input.schema().fields().len());
+            assert!(!expr.has_subquery());
+            assert!(!expr.has_agg_call());
+        }
+        let schema = Self::derive_schema(&select_list, input.schema());
+        let pk_indices = Self::derive_pk(input.pk_indices(), &select_list);
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        LogicalProjectSet {
+            base,
+            select_list,
+            input,
+        }
+    }
+
+    pub fn create(input: PlanRef, select_list: Vec<ExprImpl>) -> PlanRef {
+        Self::new(input, select_list).into()
+    }
+
+    /// Whether `expr` is a table function call, which changes the number of rows.
+    pub fn is_table_function(expr: &ExprImpl) -> bool {
+        match expr {
+            ExprImpl::FunctionCall(func_call) => func_call.get_expr_type() == ExprType::Unnest,
+            _ => false,
+        }
+    }
+
+    /// Gets the mapping from the output columns to the input columns, where `projected_row_id`
+    /// and the items other than input refs map to nothing.
+    pub fn o2i_col_mapping(&self) -> ColIndexMapping {
+        let map = std::iter::once(None)
+            .chain(self.select_list.iter().map(|expr| match expr {
+                ExprImpl::InputRef(input_ref) => Some(input_ref.index()),
+                _ => None,
+            }))
+            .collect();
+        ColIndexMapping::with_target_size(map, self.input.schema().len())
+    }
+
+    pub fn i2o_col_mapping(&self) -> ColIndexMapping {
+        self.o2i_col_mapping().inverse()
+    }
+
+    fn derive_schema(select_list: &[ExprImpl], input_schema: &Schema) -> Schema {
+        let fields = std::iter::once(Field::with_name(DataType::Int64, "projected_row_id"))
+            .chain(select_list.iter().enumerate().map(|(id, expr)| {
+                let name = match expr {
+                    ExprImpl::InputRef(input_ref) => {
+                        input_schema.fields()[input_ref.index()].name.clone()
+                    }
+                    _ if Self::is_table_function(expr) => "unnest".to_string(),
+                    _ => format!("expr#{}", id),
+                };
+                Field::with_name(expr.return_type(), name)
+            }))
+            .collect();
+        Schema { fields }
+    }
+
+    /// The rows expanded from the same input row are identified by the input pk, and they are
+    /// distinguished by `projected_row_id`.
+    fn derive_pk(input_pk: &[usize], select_list: &[ExprImpl]) -> Vec<usize> {
+        let output_of = |input_idx: usize| {
+            select_list.iter().position(|expr| match expr {
+                ExprImpl::InputRef(input_ref) => input_ref.index() == input_idx,
+                _ => false,
+            })
+        };
+        input_pk
+            .iter()
+            .map(|pk_col| output_of(*pk_col).map(|idx| idx + 1))
+            .collect::<Option<Vec<_>>>()
+            .map(|pk| std::iter::once(0).chain(pk).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn select_list(&self) -> &Vec<ExprImpl> {
+        &self.select_list
+    }
+
+    pub(super) fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+        f.debug_struct(name)
+            .field("select_list", self.select_list())
+            .finish()
+    }
+}
+
+impl PlanTreeNodeUnary for LogicalProjectSet {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.select_list.clone())
+    }
+
+    #[must_use]
+    fn rewrite_with_input(
+        &self,
+        input: PlanRef,
+        mut input_col_change: ColIndexMapping,
+    ) -> (Self, ColIndexMapping) {
+        let select_list = self
+            .select_list
+            .clone()
+            .into_iter()
+            .map(|expr| input_col_change.rewrite_expr(expr))
+            .collect();
+        let project_set = Self::new(input, select_list);
+        // change the input columns index will not change the output column index
+        let out_col_change = ColIndexMapping::identity(self.schema().len());
+        (project_set, out_col_change)
+    }
+}
+impl_plan_tree_node_for_unary! {LogicalProjectSet}
+
+impl fmt::Display for LogicalProjectSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_name(f, "LogicalProjectSet")
+    }
+}
+
+impl ColPrunable for LogicalProjectSet {
+    fn prune_col(&self, required_cols: &FixedBitSet) -> PlanRef {
+        self.must_contain_columns(required_cols);
+
+        // The table functions decide the number of output rows, so they are always kept.
+        let kept_items = (0..self.select_list.len())
+            .filter(|&i| {
+                required_cols.contains(i + 1) || Self::is_table_function(&self.select_list[i])
+            })
+            .collect_vec();
+
+        let mut visitor = CollectInputRef::with_capacity(self.input.schema().len());
+        kept_items
+            .iter()
+            .for_each(|&i| visitor.visit_expr(&self.select_list[i]));
+        let input_required_cols = visitor.collect();
+        let mut mapping = ColIndexMapping::with_remaining_columns(&input_required_cols);
+        let select_list = kept_items
+            .iter()
+            .map(|&i| mapping.rewrite_expr(self.select_list[i].clone()))
+            .collect();
+        let project_set = Self::new(self.input.prune_col(&input_required_cols), select_list);
+
+        // Map the output columns of the original node to the pruned one.
+        let mut output_map = vec![None; self.schema().len()];
+        output_map[0] = Some(0);
+        for (new_idx, &old_idx) in kept_items.iter().enumerate() {
+            output_map[old_idx + 1] = Some(new_idx + 1);
+        }
+        let output_col_change =
+            ColIndexMapping::with_target_size(output_map, project_set.schema().len());
+        let output_required_cols = required_cols
+            .ones()
+            .map(|i| output_col_change.map(i))
+            .collect_vec();
+        if output_required_cols.len() == project_set.schema().len() {
+            project_set.into()
+        } else {
+            let mut remaining_columns = FixedBitSet::with_capacity(project_set.schema().len());
+            remaining_columns.extend(output_required_cols);
+            LogicalProject::with_mapping(
+                project_set.into(),
+                ColIndexMapping::with_remaining_columns(&remaining_columns),
+            )
+        }
+    }
+}
+
+impl PredicatePushdown for LogicalProjectSet {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        // TODO: push down the predicates which only refer to the input refs in the select list.
+        gen_filter_and_pushdown(self, predicate, Condition::true_cond())
+    }
+}
+
+impl ToBatch for LogicalProjectSet {
+    fn to_batch(&self) -> PlanRef {
+        let new_input = self.input().to_batch();
+        let new_logical = self.clone_with_input(new_input);
+        BatchProjectSet::new(new_logical).into()
+    }
+}
+
+impl ToStream for LogicalProjectSet {
+    fn to_stream(&self) -> PlanRef {
+        let new_input = self.input().to_stream();
+        let new_logical = self.clone_with_input(new_input);
+        StreamProjectSet::new(new_logical).into()
+    }
+
+    fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
+        let (input, input_col_change) = self.input.logical_rewrite_for_stream();
+        let (project_set, out_col_change) =
+            self.rewrite_with_input(input.clone(), input_col_change);
+
+        // Add the input pk columns missing in the select list, so that the output has a pk.
+        let i2o = project_set.i2o_col_mapping();
+        let input_schema = input.schema();
+        let select_list = project_set
+            .select_list()
+            .iter()
+            .cloned()
+            .chain(
+                input
+                    .pk_indices()
+                    .iter()
+                    .filter(|i| i2o.try_map(**i).is_none())
+                    .map(|&idx| InputRef::new(idx, input_schema.fields()[idx].data_type()).into()),
+            )
+            .collect();
+        let project_set = Self::new(input, select_list);
+        // the added columns is at the end, so it will not change the exists column index
+        (project_set.into(), out_col_change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::Field;
+
+    use super::*;
+    use crate::expr::{assert_eq_input_ref, FunctionCall};
+    use crate::optimizer::plan_node::LogicalValues;
+    use crate::session::OptimizerContext;
+
+    #[tokio::test]
+    /// Pruning
+    /// ```text
+    /// ProjectSet(input_ref(0), unnest(input_ref(2)), input_ref(1))
+    ///   Values(v1, v2, v3)
+    /// ```
+    /// with required columns `[0, 3]` will result in
+    /// ```text
+    /// Project(input_ref(0), input_ref(2))
+    ///   ProjectSet(unnest(input_ref(1)), input_ref(0))
+    ///     Values(v2, v3)
+    /// ```
+    async fn test_prune_project_set() {
+        let ctx = OptimizerContext::mock().await;
+        let list_type = DataType::List {
+            datatype: Box::new(DataType::Int32),
+        };
+        let fields: Vec<Field> = vec![
+            Field::with_name(DataType::Int32, "v1"),
+            Field::with_name(DataType::Int32, "v2"),
+            Field::with_name(list_type.clone(), "v3"),
+        ];
+        let values = LogicalValues::new(
+            vec![],
+            Schema {
+                fields: fields.clone(),
+            },
+            ctx,
+        );
+        let unnest = FunctionCall::new_with_return_type(
+            ExprType::Unnest,
+            vec![InputRef::new(2, list_type).into()],
+            DataType::Int32,
+        );
+        let project_set = LogicalProjectSet::new(
+            values.into(),
+            vec![
+                InputRef::new(0, DataType::Int32).into(),
+                unnest.into(),
+                InputRef::new(1, DataType::Int32).into(),
+            ],
+        );
+
+        let mut required_cols = FixedBitSet::with_capacity(4);
+        required_cols.insert(0);
+        required_cols.insert(3);
+        let plan = project_set.prune_col(&required_cols);
+
+        let project = plan.as_logical_project().unwrap();
+        assert_eq!(project.schema().fields().len(), 2);
+        let project_set = project.input();
+        let project_set = project_set.as_logical_project_set().unwrap();
+        assert_eq!(project_set.select_list().len(), 2);
+        assert!(LogicalProjectSet::is_table_function(
+            &project_set.select_list()[0]
+        ));
+        assert_eq_input_ref!(&project_set.select_list()[1], 0);
+        let values = project_set.input();
+        let values = values.as_logical_values().unwrap();
+        assert_eq!(values.schema().fields().len(), 2);
+        assert_eq!(values.schema().fields()[0], fields[1]);
+        assert_eq!(values.schema().fields()[1], fields[2]);
+    }
+}
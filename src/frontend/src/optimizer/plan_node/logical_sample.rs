@@ -0,0 +1,128 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+
+use super::{
+    gen_filter_and_pushdown, BatchSample, ColPrunable, PlanBase, PlanNode, PlanRef,
+    PlanTreeNodeUnary, PredicatePushdown, ToBatch, ToStream,
+};
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalSample` randomly picks up to `limit` rows from `offset`, in random order. It's planned
+/// for `ORDER BY random()`. Without a limit, every row after `offset` is returned in random
+/// order.
+#[derive(Debug, Clone)]
+pub struct LogicalSample {
+    pub base: PlanBase,
+    input: PlanRef,
+    pub(super) limit: Option<usize>,
+    pub(super) offset: usize,
+}
+
+impl LogicalSample {
+    fn new(input: PlanRef, limit: Option<usize>, offset: usize) -> Self {
+        let ctx = input.ctx();
+        let schema = input.schema().clone();
+        let pk_indices = input.pk_indices().to_vec();
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        LogicalSample {
+            base,
+            input,
+            limit,
+            offset,
+        }
+    }
+
+    pub fn create(input: PlanRef, limit: Option<usize>, offset: usize) -> PlanRef {
+        Self::new(input, limit, offset).into()
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl PlanTreeNodeUnary for LogicalSample {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.limit, self.offset)
+    }
+
+    #[must_use]
+    fn rewrite_with_input(
+        &self,
+        input: PlanRef,
+        input_col_change: ColIndexMapping,
+    ) -> (Self, ColIndexMapping) {
+        (Self::new(input, self.limit, self.offset), input_col_change)
+    }
+}
+impl_plan_tree_node_for_unary! {LogicalSample}
+impl fmt::Display for LogicalSample {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.limit {
+            Some(limit) => write!(
+                f,
+                "LogicalSample {{ limit: {}, offset: {} }}",
+                limit, self.offset
+            ),
+            None => write!(f, "LogicalSample {{ offset: {} }}", self.offset),
+        }
+    }
+}
+
+impl ColPrunable for LogicalSample {
+    fn prune_col(&self, required_cols: &FixedBitSet) -> PlanRef {
+        self.must_contain_columns(required_cols);
+
+        let new_input = self.input.prune_col(required_cols);
+        self.clone_with_input(new_input).into()
+    }
+}
+
+impl PredicatePushdown for LogicalSample {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        gen_filter_and_pushdown(self, predicate, Condition::true_cond())
+    }
+}
+
+impl ToBatch for LogicalSample {
+    fn to_batch(&self) -> PlanRef {
+        let new_input = self.input().to_batch();
+        let new_logical = self.clone_with_input(new_input);
+        BatchSample::new(new_logical).into()
+    }
+}
+
+impl ToStream for LogicalSample {
+    fn to_stream(&self) -> PlanRef {
+        panic!("there is no sample stream operator");
+    }
+
+    fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
+        let (input, input_col_change) = self.input.logical_rewrite_for_stream();
+        let (sample, out_col_change) = self.rewrite_with_input(input, input_col_change);
+        (sample.into(), out_col_change)
+    }
+}
@@ -21,10 +21,13 @@ use itertools::Itertools;
 use risingwave_common::catalog::{ColumnDesc, Schema, TableDesc};
 use risingwave_common::error::Result;
 
-use super::{ColPrunable, PlanBase, PlanNode, PlanRef, StreamTableScan, ToBatch, ToStream};
+use super::{
+    ColPrunable, LogicalFilter, PlanBase, PlanNode, PlanRef, PredicatePushdown, StreamTableScan,
+    ToBatch, ToStream,
+};
 use crate::optimizer::plan_node::BatchSeqScan;
 use crate::session::OptimizerContextRef;
-use crate::utils::ColIndexMapping;
+use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalScan` returns contents of a table or other equivalent object
 #[derive(Debug, Clone)]
@@ -151,6 +154,12 @@ impl ColPrunable for LogicalScan {
     }
 }
 
+impl PredicatePushdown for LogicalScan {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        LogicalFilter::create(self.clone().into(), predicate)
+    }
+}
+
 impl ToBatch for LogicalScan {
     fn to_batch(&self) -> PlanRef {
         BatchSeqScan::new(self.clone()).into()
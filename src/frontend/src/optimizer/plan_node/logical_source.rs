@@ -20,11 +20,12 @@ use fixedbitset::FixedBitSet;
 use risingwave_common::catalog::Schema;
 
 use super::{
-    ColPrunable, LogicalProject, PlanBase, PlanNode, PlanRef, StreamSource, ToBatch, ToStream,
+    ColPrunable, LogicalFilter, LogicalProject, PlanBase, PlanNode, PlanRef, PredicatePushdown,
+    StreamSource, StreamWatermarkFilter, ToBatch, ToStream,
 };
 use crate::catalog::source_catalog::SourceCatalog;
 use crate::session::OptimizerContextRef;
-use crate::utils::ColIndexMapping;
+use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalSource` returns contents of a table or other equivalent object
 #[derive(Debug, Clone)]
@@ -89,6 +90,12 @@ impl ColPrunable for LogicalSource {
     }
 }
 
+impl PredicatePushdown for LogicalSource {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        LogicalFilter::create(self.clone().into(), predicate)
+    }
+}
+
 impl ToBatch for LogicalSource {
     fn to_batch(&self) -> PlanRef {
         panic!("there is no batch source operator");
@@ -97,7 +104,13 @@ impl ToBatch for LogicalSource {
 
 impl ToStream for LogicalSource {
     fn to_stream(&self) -> PlanRef {
-        StreamSource::new(self.clone()).into()
+        let source = StreamSource::new(self.clone()).into();
+        match &self.source_catalog.watermark_desc {
+            Some(watermark_desc) => {
+                StreamWatermarkFilter::new(source, watermark_desc.clone()).into()
+            }
+            None => source,
+        }
     }
 
     fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
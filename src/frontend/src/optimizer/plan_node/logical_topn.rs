@@ -16,10 +16,13 @@ use std::fmt;
 
 use fixedbitset::FixedBitSet;
 
-use super::{ColPrunable, PlanBase, PlanNode, PlanRef, PlanTreeNodeUnary, ToBatch, ToStream};
+use super::{
+    gen_filter_and_pushdown, BatchTopN, ColPrunable, PlanBase, PlanNode, PlanRef,
+    PlanTreeNodeUnary, PredicatePushdown, StreamTopN, ToBatch, ToStream,
+};
 use crate::optimizer::plan_node::LogicalProject;
-use crate::optimizer::property::{FieldOrder, Order};
-use crate::utils::ColIndexMapping;
+use crate::optimizer::property::{Distribution, FieldOrder, Order};
+use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalTopN` sorts the input data and fetches up to `limit` rows from `offset`
 #[derive(Debug, Clone)]
@@ -50,6 +53,26 @@ impl LogicalTopN {
     pub fn create(input: PlanRef, limit: usize, offset: usize, order: Order) -> PlanRef {
         Self::new(input, limit, offset, order).into()
     }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn topn_order(&self) -> &Order {
+        &self.order
+    }
+
+    pub(super) fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {{ order: {}, limit: {}, offset: {} }}",
+            name, self.order, self.limit, self.offset
+        )
+    }
 }
 
 impl PlanTreeNodeUnary for LogicalTopN {
@@ -82,8 +105,8 @@ impl PlanTreeNodeUnary for LogicalTopN {
 }
 impl_plan_tree_node_for_unary! {LogicalTopN}
 impl fmt::Display for LogicalTopN {
-    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_name(f, "LogicalTopN")
     }
 }
 
@@ -109,7 +132,7 @@ impl ColPrunable for LogicalTopN {
                 })
                 .collect(),
         };
-        let new_input = self.input.prune_col(required_cols);
+        let new_input = self.input.prune_col(&input_required_cols);
         let top_n = Self::new(new_input, self.limit, self.offset, new_order).into();
 
         if *required_cols == input_required_cols {
@@ -125,15 +148,28 @@ impl ColPrunable for LogicalTopN {
     }
 }
 
+impl PredicatePushdown for LogicalTopN {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        gen_filter_and_pushdown(self, predicate, Condition::true_cond())
+    }
+}
+
 impl ToBatch for LogicalTopN {
     fn to_batch(&self) -> PlanRef {
-        todo!()
+        let new_input = self.input().to_batch();
+        let new_logical = self.clone_with_input(new_input);
+        BatchTopN::new(new_logical).into()
     }
 }
 
 impl ToStream for LogicalTopN {
     fn to_stream(&self) -> PlanRef {
-        todo!()
+        // The top n rows are picked globally, so all the input goes to one actor.
+        let new_input = self
+            .input()
+            .to_stream_with_dist_required(&Distribution::Single);
+        let new_logical = self.clone_with_input(new_input);
+        StreamTopN::new(new_logical).into()
     }
 
     fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
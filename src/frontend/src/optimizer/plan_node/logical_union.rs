@@ -0,0 +1,198 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use risingwave_common::types::{DataType, ScalarImpl};
+use smallvec::SmallVec;
+
+use super::{
+    ColPrunable, LogicalProject, PlanBase, PlanRef, PlanTreeNode, PredicatePushdown, StreamUnion,
+    ToBatch, ToStream,
+};
+use crate::expr::{ExprImpl, InputRef, Literal};
+use crate::optimizer::property::{Distribution, Order};
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalUnion` returns all the rows of its inputs, i.e. `UNION ALL`.
+///
+/// All inputs must have the same number of columns with the same types, and the output schema
+/// follows the first input. Nested `UNION ALL`s are flattened into one node.
+#[derive(Debug, Clone)]
+pub struct LogicalUnion {
+    pub base: PlanBase,
+    inputs: Vec<PlanRef>,
+}
+
+impl LogicalUnion {
+    /// Creates a `LogicalUnion` without primary key. The rows of different inputs are told apart
+    /// only after [`ToStream::logical_rewrite_for_stream`].
+    pub fn new(inputs: Vec<PlanRef>) -> Self {
+        Self::new_with_pk(inputs, vec![])
+    }
+
+    pub fn new_with_pk(inputs: Vec<PlanRef>, pk_indices: Vec<usize>) -> Self {
+        assert!(!inputs.is_empty());
+        let ctx = inputs[0].ctx();
+        let schema = inputs[0].schema().clone();
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        LogicalUnion { base, inputs }
+    }
+
+    pub fn create(inputs: Vec<PlanRef>) -> PlanRef {
+        let inputs = inputs
+            .into_iter()
+            .flat_map(|input| match input.as_logical_union() {
+                Some(union) => union.inputs.clone(),
+                None => vec![input],
+            })
+            .collect();
+        Self::new(inputs).into()
+    }
+
+    pub fn inputs(&self) -> &[PlanRef] {
+        &self.inputs
+    }
+}
+
+impl fmt::Display for LogicalUnion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LogicalUnion {{ all: true }}")
+    }
+}
+
+impl PlanTreeNode for LogicalUnion {
+    fn inputs(&self) -> SmallVec<[PlanRef; 2]> {
+        self.inputs.iter().cloned().collect()
+    }
+
+    fn clone_with_inputs(&self, inputs: &[PlanRef]) -> PlanRef {
+        assert_eq!(inputs.len(), self.inputs.len());
+        Self::new_with_pk(inputs.to_vec(), self.base.pk_indices.clone()).into()
+    }
+}
+
+impl ColPrunable for LogicalUnion {
+    fn prune_col(&self, required_cols: &FixedBitSet) -> PlanRef {
+        self.must_contain_columns(required_cols);
+
+        // The inputs are pruned in the same way, so that their columns still line up.
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| input.prune_col(required_cols))
+            .collect();
+        Self::new(inputs).into()
+    }
+}
+
+impl PredicatePushdown for LogicalUnion {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| input.predicate_pushdown(predicate.clone()))
+            .collect();
+        Self::new(inputs).into()
+    }
+}
+
+impl ToBatch for LogicalUnion {
+    fn to_batch(&self) -> PlanRef {
+        panic!("there is no union batch operator");
+    }
+}
+
+impl ToStream for LogicalUnion {
+    fn to_stream(&self) -> PlanRef {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| input.to_stream())
+            .collect_vec();
+        let inputs = if inputs
+            .iter()
+            .all(|input| *input.distribution() == Distribution::Single)
+        {
+            inputs
+        } else {
+            // The inputs are merged in the same actors, so they must be partitioned in the same
+            // way.
+            let dist = Distribution::HashShard(self.pk_indices().to_vec());
+            inputs
+                .into_iter()
+                .map(|input| dist.enforce_if_not_satisfies(input, Order::any()))
+                .collect()
+        };
+        StreamUnion::new(Self::new_with_pk(inputs, self.base.pk_indices.clone())).into()
+    }
+
+    /// Appends the primary key of every input and the index of the input as hidden columns, so
+    /// that the rows of different inputs never share a primary key. The primary key slots of the
+    /// other inputs are filled with NULLs.
+    fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
+        let rewritten = self
+            .inputs
+            .iter()
+            .map(|input| input.logical_rewrite_for_stream())
+            .collect_vec();
+
+        let len = self.schema().len();
+        let pk_slots = rewritten
+            .iter()
+            .map(|(input, _)| {
+                input
+                    .pk_indices()
+                    .iter()
+                    .map(|idx| (*idx, input.schema().fields()[*idx].data_type()))
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let inputs = rewritten
+            .into_iter()
+            .enumerate()
+            .map(|(input_idx, (input, col_change))| {
+                let mut exprs: Vec<ExprImpl> = (0..len)
+                    .map(|i| {
+                        let idx = col_change.map(i);
+                        InputRef::new(idx, input.schema().fields()[idx].data_type()).into()
+                    })
+                    .collect();
+                for (slot_idx, slot) in pk_slots.iter().enumerate() {
+                    for (idx, data_type) in slot {
+                        exprs.push(if slot_idx == input_idx {
+                            InputRef::new(*idx, data_type.clone()).into()
+                        } else {
+                            Literal::new(None, data_type.clone()).into()
+                        });
+                    }
+                }
+                exprs.push(
+                    Literal::new(Some(ScalarImpl::Int32(input_idx as i32)), DataType::Int32).into(),
+                );
+                let alias = vec![None; exprs.len()];
+                LogicalProject::create(input, exprs, alias)
+            })
+            .collect_vec();
+
+        let new_len = inputs[0].schema().len();
+        let union = Self::new_with_pk(inputs, (len..new_len).collect());
+        let out_col_change =
+            ColIndexMapping::with_target_size((0..len).map(Some).collect(), new_len);
+        (union.into(), out_col_change)
+    }
+}
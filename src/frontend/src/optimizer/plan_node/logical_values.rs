@@ -16,11 +16,19 @@ use std::sync::Arc;
 use std::{fmt, vec};
 
 use fixedbitset::FixedBitSet;
-use risingwave_common::catalog::Schema;
-
-use super::{BatchValues, ColPrunable, PlanBase, PlanNode, PlanRef, ToBatch, ToStream};
-use crate::expr::{Expr, ExprImpl};
+use itertools::Itertools;
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::{DataType, ScalarImpl};
+
+use super::{
+    BatchValues, ColPrunable, LogicalFilter, PlanBase, PlanNode, PlanRef, PredicatePushdown,
+    StreamValues, ToBatch, ToStream,
+};
+use crate::catalog::gen_row_id_column_name;
+use crate::expr::{Expr, ExprImpl, Literal};
+use crate::optimizer::property::{Distribution, Order};
 use crate::session::OptimizerContextRef;
+use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalValues` builds rows according to a list of expressions
 #[derive(Debug, Clone)]
@@ -32,12 +40,21 @@ pub struct LogicalValues {
 impl LogicalValues {
     /// Create a [`LogicalValues`] node. Used internally by optimizer.
     pub fn new(rows: Vec<Vec<ExprImpl>>, schema: Schema, ctx: OptimizerContextRef) -> Self {
+        Self::with_pk(rows, schema, vec![], ctx)
+    }
+
+    fn with_pk(
+        rows: Vec<Vec<ExprImpl>>,
+        schema: Schema,
+        pk_indices: Vec<usize>,
+        ctx: OptimizerContextRef,
+    ) -> Self {
         for exprs in &rows {
             for (i, expr) in exprs.iter().enumerate() {
                 assert_eq!(schema.fields()[i].data_type(), expr.return_type())
             }
         }
-        let base = PlanBase::new_logical(ctx, schema, vec![]);
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
         Self {
             rows: rows.into(),
             base,
@@ -84,6 +101,12 @@ impl ColPrunable for LogicalValues {
     }
 }
 
+impl PredicatePushdown for LogicalValues {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        LogicalFilter::create(self.clone().into(), predicate)
+    }
+}
+
 impl ToBatch for LogicalValues {
     fn to_batch(&self) -> PlanRef {
         BatchValues::new(self.clone()).into()
@@ -92,11 +115,54 @@ impl ToBatch for LogicalValues {
 
 impl ToStream for LogicalValues {
     fn to_stream(&self) -> PlanRef {
-        unimplemented!("Stream values executor is unimplemented!")
+        // The rows are emitted by a single actor. Shuffle them by the row id, so that the actor
+        // is a fragment of its own, into which the barriers are injected as into a source.
+        Distribution::HashShard(self.pk_indices().to_vec())
+            .enforce_if_not_satisfies(StreamValues::new(self.clone()).into(), Order::any())
     }
 
-    fn logical_rewrite_for_stream(&self) -> (PlanRef, crate::utils::ColIndexMapping) {
-        unimplemented!("Stream values executor is unimplemented!")
+    fn logical_rewrite_for_stream(&self) -> (PlanRef, ColIndexMapping) {
+        if !self.base.pk_indices.is_empty() {
+            return (
+                self.clone().into(),
+                ColIndexMapping::identity(self.schema().len()),
+            );
+        }
+
+        // Append a hidden row id column holding the row index, so that the rows can be told
+        // apart by the downstream operators even if some of them are identical.
+        let len = self.schema().len();
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(row_id, row)| {
+                let mut row = row.clone();
+                row.push(
+                    Literal::new(Some(ScalarImpl::Int64(row_id as i64)), DataType::Int64).into(),
+                );
+                row
+            })
+            .collect();
+        // Name the columns `column1`, `column2`, ... as PostgreSQL does, so that they don't
+        // conflict with each other in the materialized view.
+        let mut fields = self
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match field.name.is_empty() {
+                true => Field::with_name(field.data_type(), format!("column{}", i + 1)),
+                false => field.clone(),
+            })
+            .collect_vec();
+        fields.push(Field::with_name(DataType::Int64, gen_row_id_column_name(0)));
+        let values = Self::with_pk(rows, Schema { fields }, vec![len], self.base.ctx.clone());
+
+        (
+            values.into(),
+            ColIndexMapping::with_target_size((0..len).map(Some).collect(), len + 1),
+        )
     }
 }
 
@@ -53,10 +53,12 @@ pub trait PlanNode:
     + Display
     + Downcast
     + ColPrunable
+    + PredicatePushdown
     + ToBatch
     + ToStream
     + ToDistributedBatch
     + ToProst
+    + StateTables
 {
     fn node_type(&self) -> PlanNodeType;
     fn plan_base(&self) -> &PlanBase;
@@ -206,6 +208,10 @@ mod convert;
 pub use convert::*;
 mod eq_join_predicate;
 pub use eq_join_predicate::*;
+mod explain_state;
+pub use explain_state::*;
+mod predicate_pushdown;
+pub use predicate_pushdown::*;
 mod to_prost;
 pub use to_prost::*;
 
@@ -214,68 +220,118 @@ mod batch_exchange;
 mod batch_filter;
 mod batch_hash_agg;
 mod batch_hash_join;
+mod batch_hash_set_op;
 mod batch_insert;
 mod batch_limit;
+mod batch_peek_source;
 mod batch_project;
+mod batch_project_set;
+mod batch_sample;
 mod batch_seq_scan;
 mod batch_simple_agg;
 mod batch_sort;
+mod batch_sort_merge_join;
+mod batch_topn;
 mod batch_values;
 mod logical_agg;
 mod logical_apply;
 mod logical_delete;
+mod logical_dynamic_filter;
+mod logical_except;
 mod logical_filter;
+mod logical_hop_window;
 mod logical_insert;
+mod logical_intersect;
 mod logical_join;
 mod logical_limit;
+mod logical_peek_source;
 mod logical_project;
+mod logical_project_set;
+mod logical_sample;
 mod logical_scan;
 mod logical_source;
 mod logical_topn;
+mod logical_union;
 mod logical_values;
+mod stream_dynamic_filter;
 mod stream_exchange;
 mod stream_filter;
 mod stream_hash_agg;
 mod stream_hash_join;
+mod stream_hash_set_op;
+mod stream_hop_window;
+mod stream_local_hash_agg;
 mod stream_materialize;
+mod stream_nested_loop_join;
 mod stream_project;
+mod stream_project_set;
 mod stream_simple_agg;
 mod stream_source;
 mod stream_table_scan;
+mod stream_topn;
+mod stream_union;
+mod stream_values;
+mod stream_watermark_filter;
 
 pub use batch_delete::BatchDelete;
 pub use batch_exchange::BatchExchange;
 pub use batch_filter::BatchFilter;
 pub use batch_hash_agg::BatchHashAgg;
 pub use batch_hash_join::BatchHashJoin;
+pub use batch_hash_set_op::BatchHashSetOp;
 pub use batch_insert::BatchInsert;
 pub use batch_limit::BatchLimit;
+pub use batch_peek_source::BatchPeekSource;
 pub use batch_project::BatchProject;
+pub use batch_project_set::BatchProjectSet;
+pub use batch_sample::BatchSample;
 pub use batch_seq_scan::BatchSeqScan;
 pub use batch_simple_agg::BatchSimpleAgg;
 pub use batch_sort::BatchSort;
+pub use batch_sort_merge_join::BatchSortMergeJoin;
+pub use batch_topn::BatchTopN;
 pub use batch_values::BatchValues;
 pub use logical_agg::{LogicalAgg, PlanAggCall};
 pub use logical_apply::LogicalApply;
 pub use logical_delete::LogicalDelete;
+pub use logical_dynamic_filter::LogicalDynamicFilter;
+pub use logical_except::LogicalExcept;
 pub use logical_filter::LogicalFilter;
-pub use logical_insert::LogicalInsert;
+pub use logical_hop_window::LogicalHopWindow;
+pub use logical_insert::{InsertOnConflict, LogicalInsert};
+pub use logical_intersect::LogicalIntersect;
 pub use logical_join::LogicalJoin;
 pub use logical_limit::LogicalLimit;
+pub use logical_peek_source::LogicalPeekSource;
 pub use logical_project::LogicalProject;
+pub use logical_project_set::LogicalProjectSet;
+pub use logical_sample::LogicalSample;
 pub use logical_scan::LogicalScan;
 pub use logical_source::LogicalSource;
 pub use logical_topn::LogicalTopN;
+pub use logical_union::LogicalUnion;
 pub use logical_values::LogicalValues;
+pub use stream_dynamic_filter::StreamDynamicFilter;
 pub use stream_exchange::StreamExchange;
 pub use stream_filter::StreamFilter;
-pub use stream_hash_agg::StreamHashAgg;
-pub use stream_hash_join::StreamHashJoin;
-pub use stream_materialize::StreamMaterialize;
+pub use stream_hash_agg::{
+    StreamHashAgg, MINI_BATCH_INTERVAL_MS, MINI_BATCH_MAX_ROWS, TWO_PHASE_AGG,
+};
+pub use stream_hash_join::{StreamHashJoin, DELTA_JOIN};
+pub use stream_hash_set_op::StreamHashSetOp;
+pub use stream_hop_window::StreamHopWindow;
+pub use stream_local_hash_agg::StreamLocalHashAgg;
+pub use stream_materialize::{ConflictOverwrite, StreamMaterialize};
+pub use stream_nested_loop_join::{StreamNestedLoopJoin, BROADCAST_JOIN_MAX_ROWS};
 pub use stream_project::StreamProject;
+pub use stream_project_set::StreamProjectSet;
 pub use stream_simple_agg::StreamSimpleAgg;
 pub use stream_source::StreamSource;
-pub use stream_table_scan::StreamTableScan;
+pub use stream_table_scan::{StreamTableScan, SNAPSHOT_ONLY};
+pub use stream_topn::StreamTopN;
+pub use stream_union::StreamUnion;
+pub use stream_values::StreamValues;
+pub use stream_watermark_filter::StreamWatermarkFilter;
 
 use crate::session::OptimizerContextRef;
 
@@ -308,6 +364,14 @@ macro_rules! for_all_plan_nodes {
             ,{ Logical, Values }
             ,{ Logical, Limit }
             ,{ Logical, TopN }
+            ,{ Logical, Sample }
+            ,{ Logical, PeekSource }
+            ,{ Logical, Intersect }
+            ,{ Logical, Except }
+            ,{ Logical, HopWindow }
+            ,{ Logical, ProjectSet }
+            ,{ Logical, Union }
+            ,{ Logical, DynamicFilter }
             // ,{ Logical, Sort } we don't need a LogicalSort, just require the Order
             ,{ Batch, SimpleAgg }
             ,{ Batch, HashAgg }
@@ -317,10 +381,16 @@ macro_rules! for_all_plan_nodes {
             ,{ Batch, Delete }
             ,{ Batch, SeqScan }
             ,{ Batch, HashJoin }
+            ,{ Batch, SortMergeJoin }
             ,{ Batch, Values }
             ,{ Batch, Sort }
             ,{ Batch, Exchange }
             ,{ Batch, Limit }
+            ,{ Batch, Sample }
+            ,{ Batch, PeekSource }
+            ,{ Batch, TopN }
+            ,{ Batch, HashSetOp }
+            ,{ Batch, ProjectSet }
             ,{ Stream, Project }
             ,{ Stream, Filter }
             ,{ Stream, TableScan }
@@ -328,8 +398,18 @@ macro_rules! for_all_plan_nodes {
             ,{ Stream, HashJoin }
             ,{ Stream, Exchange }
             ,{ Stream, HashAgg }
+            ,{ Stream, LocalHashAgg }
             ,{ Stream, SimpleAgg }
             ,{ Stream, Materialize }
+            ,{ Stream, WatermarkFilter }
+            ,{ Stream, Values }
+            ,{ Stream, TopN }
+            ,{ Stream, HashSetOp }
+            ,{ Stream, HopWindow }
+            ,{ Stream, NestedLoopJoin }
+            ,{ Stream, ProjectSet }
+            ,{ Stream, Union }
+            ,{ Stream, DynamicFilter }
         }
     };
 }
@@ -351,6 +431,14 @@ macro_rules! for_logical_plan_nodes {
             ,{ Logical, Values }
             ,{ Logical, Limit }
             ,{ Logical, TopN }
+            ,{ Logical, Sample }
+            ,{ Logical, PeekSource }
+            ,{ Logical, Intersect }
+            ,{ Logical, Except }
+            ,{ Logical, HopWindow }
+            ,{ Logical, ProjectSet }
+            ,{ Logical, Union }
+            ,{ Logical, DynamicFilter }
             // ,{ Logical, Sort} not sure if we will support Order by clause in subquery/view/MV
             // if we dont support thatk, we don't need LogicalSort, just require the Order at the top of query
         }
@@ -369,12 +457,18 @@ macro_rules! for_batch_plan_nodes {
             ,{ Batch, Filter }
             ,{ Batch, SeqScan }
             ,{ Batch, HashJoin }
+            ,{ Batch, SortMergeJoin }
             ,{ Batch, Values }
             ,{ Batch, Limit }
+            ,{ Batch, Sample }
+            ,{ Batch, PeekSource }
             ,{ Batch, Sort }
             ,{ Batch, Exchange }
             ,{ Batch, Insert }
             ,{ Batch, Delete }
+            ,{ Batch, TopN }
+            ,{ Batch, HashSetOp }
+            ,{ Batch, ProjectSet }
         }
     };
 }
@@ -392,8 +486,18 @@ macro_rules! for_stream_plan_nodes {
             ,{ Stream, TableScan }
             ,{ Stream, Source }
             ,{ Stream, HashAgg }
+            ,{ Stream, LocalHashAgg }
             ,{ Stream, SimpleAgg }
             ,{ Stream, Materialize }
+            ,{ Stream, WatermarkFilter }
+            ,{ Stream, Values }
+            ,{ Stream, TopN }
+            ,{ Stream, HashSetOp }
+            ,{ Stream, HopWindow }
+            ,{ Stream, NestedLoopJoin }
+            ,{ Stream, ProjectSet }
+            ,{ Stream, Union }
+            ,{ Stream, DynamicFilter }
         }
     };
 }
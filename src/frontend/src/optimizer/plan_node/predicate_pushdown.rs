@@ -0,0 +1,61 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use paste::paste;
+
+use super::*;
+use crate::utils::Condition;
+use crate::{for_batch_plan_nodes, for_stream_plan_nodes};
+
+/// The trait for predicate pushdown, only logical plan node will use it, though all plan node impl
+/// it.
+pub trait PredicatePushdown {
+    /// Push `predicate` down into the plan node and its inputs, returning the new plan.
+    ///
+    /// Each conjunction of `predicate` is handled in one of three ways:
+    ///
+    /// 1. If it can't be pushed down, a [`LogicalFilter`] is created for it above the node.
+    /// 2. If it can be merged into the node (e.g. the on-clause of a [`LogicalJoin`]), it is merged
+    ///    with the node's [`Condition`].
+    /// 3. Otherwise it is passed down to the node's inputs.
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef;
+}
+
+/// Implements [`PredicatePushdown`] for batch and streaming node.
+macro_rules! impl_predicate_pushdown {
+    ([], $( { $convention:ident, $name:ident }),*) => {
+        paste!{
+            $(impl PredicatePushdown for [<$convention $name>] {
+                fn predicate_pushdown(&self, _predicate: Condition) -> PlanRef {
+                    panic!("predicate pushdown is only allowed on logical plan")
+                }
+            })*
+        }
+    }
+}
+for_batch_plan_nodes! { impl_predicate_pushdown }
+for_stream_plan_nodes! { impl_predicate_pushdown }
+
+/// Creates a [`LogicalFilter`] with `filter_predicate` above `node`, whose input is replaced by
+/// the result of pushing `pushed_predicate` into it.
+#[inline]
+pub fn gen_filter_and_pushdown<T: PlanTreeNodeUnary + Into<PlanRef>>(
+    node: &T,
+    filter_predicate: Condition,
+    pushed_predicate: Condition,
+) -> PlanRef {
+    let new_input = node.input().predicate_pushdown(pushed_predicate);
+    let new_node = node.clone_with_input(new_input);
+    LogicalFilter::create(new_node.into(), filter_predicate)
+}
@@ -0,0 +1,115 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::catalog::Field;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_pb::stream_plan::DynamicFilterNode;
+
+use super::{
+    LogicalDynamicFilter, PlanBase, PlanRef, PlanTreeNodeBinary, StateGrowth, StateTableDesc,
+    StateTables, ToStreamProst,
+};
+use crate::optimizer::property::Distribution;
+
+/// `StreamDynamicFilter` implements [`super::LogicalDynamicFilter`]. The right side is broadcast
+/// to every parallel unit, while the left side stays in its original distribution and is kept in
+/// state ordered by the filter column.
+#[derive(Debug, Clone)]
+pub struct StreamDynamicFilter {
+    pub base: PlanBase,
+    logical: LogicalDynamicFilter,
+}
+
+impl StreamDynamicFilter {
+    pub fn new(logical: LogicalDynamicFilter) -> Self {
+        assert_eq!(logical.right().distribution(), &Distribution::Broadcast);
+        let ctx = logical.base.ctx.clone();
+        let dist = logical.left().distribution().clone();
+        // A row may be retracted once the right value changes, even if both inputs are
+        // append-only.
+        let base = PlanBase::new_stream(
+            ctx,
+            logical.schema().clone(),
+            logical.base.pk_indices.to_vec(),
+            dist,
+            false,
+        );
+
+        Self { base, logical }
+    }
+}
+
+impl fmt::Display for StreamDynamicFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "StreamDynamicFilter {{ predicate: {:?} }}",
+            self.logical.predicate()
+        )
+    }
+}
+
+impl PlanTreeNodeBinary for StreamDynamicFilter {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_left_right(left, right))
+    }
+}
+
+impl_plan_tree_node_for_binary! { StreamDynamicFilter }
+
+impl ToStreamProst for StreamDynamicFilter {
+    fn to_stream_prost_body(&self) -> Node {
+        Node::DynamicFilterNode(DynamicFilterNode {
+            left_key: self.logical.left_index() as u32,
+            comparator: self.logical.comparator() as i32,
+        })
+    }
+}
+
+impl StateTables for StreamDynamicFilter {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        let left = self.left();
+        let left_fields = left.schema().fields();
+        vec![
+            StateTableDesc {
+                name: "dynamic_filter_left".to_string(),
+                key: std::iter::once(self.logical.left_index())
+                    .chain(left.pk_indices().iter().copied())
+                    .map(|idx| left_fields[idx].clone())
+                    .collect(),
+                value: left_fields.to_vec(),
+                growth: StateGrowth::Unbounded("one row per input row of the left side"),
+            },
+            StateTableDesc {
+                name: "dynamic_filter_right".to_string(),
+                key: vec![],
+                value: vec![Field::with_name(
+                    self.right().schema().fields()[0].data_type(),
+                    "value",
+                )],
+                growth: StateGrowth::Bounded("a single row"),
+            },
+        ]
+    }
+}
@@ -17,7 +17,7 @@ use std::fmt;
 use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_pb::stream_plan::{DispatchStrategy, DispatcherType, ExchangeNode};
 
-use super::{PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use super::{PlanBase, PlanRef, PlanTreeNodeUnary, StateTables, ToStreamProst};
 use crate::optimizer::property::Distribution;
 
 /// `StreamExchange` imposes a particular distribution on its input
@@ -66,6 +66,11 @@ impl ToStreamProst for StreamExchange {
         Node::ExchangeNode(ExchangeNode {
             strategy: Some(DispatchStrategy {
                 r#type: match &self.base.dist {
+                    // The input is already partitioned in the same way, so each upstream actor
+                    // can be piped into its paired downstream actor directly.
+                    Distribution::HashShard(_) if self.input.distribution() == &self.base.dist => {
+                        DispatcherType::NoShuffle
+                    }
                     Distribution::HashShard(_) => DispatcherType::Hash,
                     Distribution::Single => DispatcherType::Simple,
                     Distribution::Broadcast => DispatcherType::Broadcast,
@@ -79,3 +84,5 @@ impl ToStreamProst for StreamExchange {
         })
     }
 }
+
+impl StateTables for StreamExchange {}
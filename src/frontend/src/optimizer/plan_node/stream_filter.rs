@@ -17,7 +17,7 @@ use std::fmt;
 use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
 use risingwave_pb::stream_plan::FilterNode;
 
-use super::{LogicalFilter, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use super::{LogicalFilter, PlanRef, PlanTreeNodeUnary, StateTables, ToStreamProst};
 use crate::expr::{Expr, ExprImpl};
 use crate::optimizer::plan_node::PlanBase;
 use crate::utils::Condition;
@@ -76,3 +76,5 @@ impl ToStreamProst for StreamFilter {
         })
     }
 }
+
+impl StateTables for StreamFilter {}
@@ -17,11 +17,27 @@ use std::fmt;
 use itertools::Itertools;
 use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
 
+use super::explain_state::agg_call_state_tables;
 use super::logical_agg::PlanAggCall;
-use super::{LogicalAgg, PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use super::{
+    LogicalAgg, PlanBase, PlanRef, PlanTreeNodeUnary, StateGrowth, StateTableDesc, StateTables,
+    ToStreamProst,
+};
 use crate::expr::InputRefDisplay;
 use crate::optimizer::property::Distribution;
 
+/// Option of `CREATE MATERIALIZED VIEW ... WITH (...)` enabling the mini-batch mode of hash agg:
+/// pre-aggregate at most this many rows before touching the states.
+pub const MINI_BATCH_MAX_ROWS: &str = "mini_batch_max_rows";
+
+/// Option of `CREATE MATERIALIZED VIEW ... WITH (...)` enabling the mini-batch mode of hash agg:
+/// pre-aggregate rows arriving within this interval before touching the states.
+pub const MINI_BATCH_INTERVAL_MS: &str = "mini_batch_interval_ms";
+
+/// Option of `CREATE MATERIALIZED VIEW ... WITH (...)` enabling the two-phase hash agg: the rows
+/// are pre-aggregated by a [`super::StreamLocalHashAgg`] before shuffled by the group keys.
+pub const TWO_PHASE_AGG: &str = "two_phase_agg";
+
 #[derive(Debug, Clone)]
 pub struct StreamHashAgg {
     pub base: PlanBase,
@@ -60,6 +76,17 @@ impl StreamHashAgg {
     pub fn distribution_keys(&self) -> &[usize] {
         self.logical.group_keys()
     }
+
+    /// Get the mini-batch option from the `WITH` clause, 0 if not set.
+    fn mini_batch_option<T: std::str::FromStr + Default>(&self, key: &str) -> T {
+        self.base
+            .ctx
+            .inner()
+            .with_options
+            .get(key)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
 }
 
 impl fmt::Display for StreamHashAgg {
@@ -105,6 +132,28 @@ impl ToStreamProst for StreamHashAgg {
                 .iter()
                 .map(PlanAggCall::to_protobuf)
                 .collect_vec(),
+            mini_batch_max_rows: self.mini_batch_option(MINI_BATCH_MAX_ROWS),
+            mini_batch_interval_ms: self.mini_batch_option(MINI_BATCH_INTERVAL_MS),
         })
     }
 }
+
+impl StateTables for StreamHashAgg {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        let group_key_len = self.distribution_keys().len();
+        let fields = self.base.schema.fields();
+        let mut tables = vec![StateTableDesc {
+            name: "hash_agg".to_string(),
+            key: fields[..group_key_len].to_vec(),
+            value: fields[group_key_len..].to_vec(),
+            growth: StateGrowth::Unbounded("one row per distinct group key"),
+        }];
+        tables.extend(agg_call_state_tables(
+            self.agg_calls(),
+            fields[..group_key_len].to_vec(),
+            self.input().append_only(),
+            self.input().schema().fields(),
+        ));
+        tables
+    }
+}
@@ -19,12 +19,21 @@ use risingwave_pb::plan::JoinType;
 use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_pb::stream_plan::HashJoinNode;
 
-use super::{LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary, ToStreamProst};
+use super::{
+    LogicalJoin, PlanBase, PlanNode, PlanRef, PlanTreeNodeBinary, StateGrowth, StateTableDesc,
+    StateTables, ToStreamProst,
+};
 use crate::expr::Expr;
 use crate::optimizer::plan_node::EqJoinPredicate;
 use crate::optimizer::property::Distribution;
 use crate::utils::ColIndexMapping;
 
+/// Option of `CREATE MATERIALIZED VIEW ... WITH (...)` enabling delta joins: inner equi-joins are
+/// executed by looking up the changes of each input in the shared arrangements of the others,
+/// instead of keeping all the inputs in join states. A tree of such joins becomes one multi-way
+/// delta join.
+pub const DELTA_JOIN: &str = "delta_join";
+
 /// `BatchHashJoin` implements [`super::LogicalJoin`] with hash table. It builds a hash table
 /// from inner (right-side) relation and probes with data from outer (left-side) relation to
 /// get output rows.
@@ -73,6 +82,36 @@ impl StreamHashJoin {
         &self.eq_join_predicate
     }
 
+    /// Whether the join can be executed in append-only mode, i.e. it's an inner join of two
+    /// append-only inputs, and the join key of each side contains the pk of that side. Then a row
+    /// matches at most one row of the other side, and neither of them can be matched again.
+    pub fn is_append_only(&self) -> bool {
+        let key_contains_pk = |input: PlanRef, eq_indexes: Vec<usize>| {
+            input
+                .pk_indices()
+                .iter()
+                .all(|idx| eq_indexes.contains(idx))
+        };
+        self.base.append_only
+            && key_contains_pk(self.left(), self.eq_join_predicate.left_eq_indexes())
+            && key_contains_pk(self.right(), self.eq_join_predicate.right_eq_indexes())
+    }
+
+    /// Whether the join is executed as a delta join under the [`DELTA_JOIN`] option, which is only
+    /// possible for inner joins without non-equi conditions.
+    pub fn is_delta_join(&self) -> bool {
+        self.logical.join_type() == JoinType::Inner
+            && !self.eq_join_predicate.has_non_eq()
+            && self
+                .base
+                .ctx
+                .inner()
+                .with_options
+                .get(DELTA_JOIN)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(false)
+    }
+
     fn derive_dist(
         left: &Distribution,
         right: &Distribution,
@@ -95,7 +134,14 @@ impl fmt::Display for StreamHashJoin {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "StreamHashJoin {{ type: {:?}, predicate: {} }}",
+            "{} {{ type: {:?}, predicate: {} }}",
+            if self.is_delta_join() {
+                "StreamDeltaJoin"
+            } else if self.is_append_only() {
+                "StreamAppendOnlyHashJoin"
+            } else {
+                "StreamHashJoin"
+            },
             self.logical.join_type(),
             self.eq_join_predicate()
         )
@@ -149,6 +195,101 @@ impl ToStreamProst for StreamHashJoin {
                 .iter()
                 .map(|idx| *idx as i32)
                 .collect_vec(),
+            is_append_only: self.is_append_only(),
+            is_delta_join: self.is_delta_join(),
         })
     }
 }
+
+impl StateTables for StreamHashJoin {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        let side = |name: &str, plan: &dyn PlanNode, eq_indexes: Vec<usize>| {
+            let fields = plan.schema().fields();
+            StateTableDesc {
+                name: name.to_string(),
+                key: eq_indexes
+                    .into_iter()
+                    .chain(plan.pk_indices().iter().copied())
+                    .map(|idx| fields[idx].clone())
+                    .collect(),
+                value: fields.to_vec(),
+                growth: if self.is_append_only() {
+                    StateGrowth::Unbounded("one row per unmatched input row of this side")
+                } else {
+                    StateGrowth::Unbounded("one row per input row of this side")
+                },
+            }
+        };
+        vec![
+            side(
+                "hash_join_left",
+                &*self.left(),
+                self.eq_join_predicate.left_eq_indexes(),
+            ),
+            side(
+                "hash_join_right",
+                &*self.right(),
+                self.eq_join_predicate.right_eq_indexes(),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::types::{DataType, Datum};
+    use risingwave_pb::expr::expr_node::Type;
+
+    use super::*;
+    use crate::expr::{ExprImpl, FunctionCall, InputRef, Literal};
+    use crate::optimizer::plan_node::{LogicalValues, ToStream};
+    use crate::session::OptimizerContext;
+    use crate::utils::Condition;
+
+    /// Joins two `VALUES` on `eq_columns`, which are compared with the same column of the other
+    /// side. Each side is rewritten to `[k, _row_id]`, where the hidden row id is its pk.
+    async fn join_values(eq_columns: &[usize]) -> PlanRef {
+        let ctx = OptimizerContext::mock().await;
+        let values = || {
+            let row = vec![Literal::new(Datum::Some(1.into()), DataType::Int32).into()];
+            let schema = Schema::new(vec![Field::with_name(DataType::Int32, "k")]);
+            LogicalValues::new(vec![row], schema, ctx.clone())
+                .logical_rewrite_for_stream()
+                .0
+        };
+        let (left, right) = (values(), values());
+        let conjunctions = eq_columns
+            .iter()
+            .map(|idx| {
+                let ty = left.schema().fields()[*idx].data_type();
+                let eq = FunctionCall::new(
+                    Type::Equal,
+                    vec![
+                        InputRef::new(*idx, ty.clone()).into(),
+                        InputRef::new(idx + 2, ty).into(),
+                    ],
+                )
+                .unwrap();
+                ExprImpl::from(eq)
+            })
+            .collect();
+        LogicalJoin::new(left, right, JoinType::Inner, Condition { conjunctions }).to_stream()
+    }
+
+    #[tokio::test]
+    async fn test_append_only_hash_join() {
+        // The join key contains the pk of both sides.
+        let plan = join_values(&[0, 1]).await;
+        let join = plan.as_stream_hash_join().unwrap();
+        assert!(join.is_append_only());
+        assert!(join.to_string().starts_with("StreamAppendOnlyHashJoin"));
+
+        // A row may match many rows of the other side, so the rows must be kept.
+        let plan = join_values(&[0]).await;
+        let join = plan.as_stream_hash_join().unwrap();
+        assert!(join.append_only());
+        assert!(!join.is_append_only());
+        assert!(join.to_string().starts_with("StreamHashJoin"));
+    }
+}
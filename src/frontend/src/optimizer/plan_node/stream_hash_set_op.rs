@@ -0,0 +1,123 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::catalog::Field;
+use risingwave_common::types::DataType;
+use risingwave_pb::plan::SetOpType;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_pb::stream_plan::HashSetOpNode;
+
+use super::{
+    PlanBase, PlanRef, PlanTreeNodeBinary, StateGrowth, StateTableDesc, StateTables, ToStreamProst,
+};
+use crate::optimizer::property::Distribution;
+
+/// `StreamHashSetOp` implements [`super::LogicalIntersect`] and [`super::LogicalExcept`]. It
+/// maintains the number of occurrences of each row on both sides, and emits a row once it starts
+/// or stops satisfying the set operation, so the retractions of the inputs are also handled.
+#[derive(Debug, Clone)]
+pub struct StreamHashSetOp {
+    pub base: PlanBase,
+    left: PlanRef,
+    right: PlanRef,
+    set_op: SetOpType,
+}
+
+impl StreamHashSetOp {
+    pub fn new(left: PlanRef, right: PlanRef, set_op: SetOpType) -> Self {
+        let ctx = left.ctx();
+        let schema = left.schema().clone();
+        let dist = Self::derive_dist(left.distribution(), right.distribution());
+        // A row may be retracted once the other side changes, even if both inputs are append-only.
+        let base = PlanBase::new_stream(
+            ctx,
+            schema.clone(),
+            (0..schema.len()).collect(),
+            dist,
+            false,
+        );
+        StreamHashSetOp {
+            base,
+            left,
+            right,
+            set_op,
+        }
+    }
+
+    fn derive_dist(left: &Distribution, right: &Distribution) -> Distribution {
+        match (left, right) {
+            (Distribution::Single, Distribution::Single) => Distribution::Single,
+            (Distribution::HashShard(_), Distribution::HashShard(_)) => left.clone(),
+            (_, _) => panic!(),
+        }
+    }
+
+    pub fn set_op(&self) -> SetOpType {
+        self.set_op
+    }
+}
+
+impl fmt::Display for StreamHashSetOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "StreamHashSetOp {{ type: {:?} }}", self.set_op)
+    }
+}
+
+impl PlanTreeNodeBinary for StreamHashSetOp {
+    fn left(&self) -> PlanRef {
+        self.left.clone()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.right.clone()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(left, right, self.set_op)
+    }
+}
+
+impl_plan_tree_node_for_binary! { StreamHashSetOp }
+
+impl ToStreamProst for StreamHashSetOp {
+    fn to_stream_prost_body(&self) -> Node {
+        Node::HashSetOpNode(HashSetOpNode {
+            set_op_type: self.set_op as i32,
+            distribution_keys: self
+                .base
+                .dist
+                .dist_column_indices()
+                .iter()
+                .map(|idx| *idx as i32)
+                .collect_vec(),
+        })
+    }
+}
+
+impl StateTables for StreamHashSetOp {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        vec![StateTableDesc {
+            name: "hash_set_op".to_string(),
+            key: self.left.schema().fields().to_vec(),
+            value: vec![
+                Field::with_name(DataType::Int64, "left_count"),
+                Field::with_name(DataType::Int64, "right_count"),
+            ],
+            growth: StateGrowth::Unbounded("one row per distinct input row of both sides"),
+        }]
+    }
+}
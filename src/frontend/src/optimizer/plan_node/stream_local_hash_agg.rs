@@ -0,0 +1,105 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
+
+use super::logical_agg::PlanAggCall;
+use super::{LogicalAgg, PlanBase, PlanRef, PlanTreeNodeUnary, StateTables, ToStreamProst};
+use crate::expr::InputRefDisplay;
+
+/// The first phase of a two-phase hash agg. It pre-aggregates the rows of each epoch by the group
+/// keys without any state, and emits the partial results as inserts to be shuffled to the
+/// [`super::StreamHashAgg`] merging them.
+#[derive(Debug, Clone)]
+pub struct StreamLocalHashAgg {
+    pub base: PlanBase,
+    logical: LogicalAgg,
+}
+
+impl StreamLocalHashAgg {
+    pub fn new(logical: LogicalAgg) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let pk_indices = logical.base.pk_indices.to_vec();
+        let dist = logical
+            .i2o_col_mapping()
+            .rewrite_provided_distribution(logical.input().distribution());
+        // The partial results are always emitted as inserts.
+        let base = PlanBase::new_stream(ctx, logical.schema().clone(), pk_indices, dist, true);
+        StreamLocalHashAgg { base, logical }
+    }
+
+    pub fn agg_calls(&self) -> &[PlanAggCall] {
+        self.logical.agg_calls()
+    }
+
+    pub fn group_keys(&self) -> &[usize] {
+        self.logical.group_keys()
+    }
+}
+
+impl fmt::Display for StreamLocalHashAgg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamLocalHashAgg")
+            .field(
+                "group_keys",
+                &self
+                    .group_keys()
+                    .iter()
+                    .copied()
+                    .map(InputRefDisplay)
+                    .collect_vec(),
+            )
+            .field("aggs", &self.agg_calls())
+            .finish()
+    }
+}
+
+impl PlanTreeNodeUnary for StreamLocalHashAgg {
+    fn input(&self) -> PlanRef {
+        self.logical.input()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_input(input))
+    }
+}
+impl_plan_tree_node_for_unary! { StreamLocalHashAgg }
+
+impl ToStreamProst for StreamLocalHashAgg {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        use risingwave_pb::stream_plan::*;
+
+        ProstStreamNode::LocalHashAggNode(HashAggNode {
+            distribution_keys: self
+                .group_keys()
+                .iter()
+                .map(|idx| *idx as i32)
+                .collect_vec(),
+            agg_calls: self
+                .agg_calls()
+                .iter()
+                .map(PlanAggCall::to_protobuf)
+                .collect_vec(),
+            mini_batch_max_rows: 0,
+            mini_batch_interval_ms: 0,
+        })
+    }
+}
+
+/// The partial results are emitted and dropped on each barrier, so nothing is kept in the state
+/// store.
+impl StateTables for StreamLocalHashAgg {}
@@ -23,9 +23,11 @@ use risingwave_common::error::Result;
 use risingwave_common::util::sort_util::OrderType;
 use risingwave_pb::expr::InputRefExpr;
 use risingwave_pb::plan::ColumnOrder;
+use risingwave_pb::stream_plan::conflict_overwrite::OptionalVersionColumnIndex;
 use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
+use risingwave_pb::stream_plan::ConflictOverwrite as ProstConflictOverwrite;
 
-use super::{PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use super::{PlanRef, PlanTreeNodeUnary, StateGrowth, StateTableDesc, StateTables, ToStreamProst};
 use crate::catalog::column_catalog::ColumnCatalog;
 use crate::catalog::table_catalog::TableCatalog;
 use crate::catalog::{gen_row_id_column_name, is_row_id_column_name, ColumnId};
@@ -39,6 +41,18 @@ pub struct StreamMaterialize {
     /// Child of Materialize plan
     input: PlanRef,
     table: TableCatalog,
+    overwrite: Option<ConflictOverwrite>,
+}
+
+/// How the rows of a table created with `on_conflict = 'overwrite'` are deduplicated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictOverwrite {
+    /// The primary key of the table, by which a row overwrites the existing one.
+    pub pk_indices: Vec<usize>,
+    /// A row overwrites the existing one only if its version in this column is not lower.
+    pub version_column_index: Option<usize>,
+    /// The hidden row id column, by which the rows of `INSERT ... ON CONFLICT` are resolved.
+    pub row_id_index: usize,
 }
 
 impl StreamMaterialize {
@@ -95,7 +109,12 @@ impl StreamMaterialize {
     #[must_use]
     pub fn new(input: PlanRef, table: TableCatalog) -> Self {
         let base = Self::derive_plan_base(&input).unwrap();
-        Self { base, input, table }
+        Self {
+            base,
+            input,
+            table,
+            overwrite: None,
+        }
     }
 
     /// Create a materialize node.
@@ -153,7 +172,32 @@ impl StreamMaterialize {
             pk_desc,
         };
 
-        Ok(Self { base, input, table })
+        Ok(Self {
+            base,
+            input,
+            table,
+            overwrite: None,
+        })
+    }
+
+    /// Keys the rows by the primary key of `overwrite` instead of the one of the input, so that
+    /// a row overwrites the existing row of the same key. The input must be distributed by the
+    /// key.
+    #[must_use]
+    pub fn with_conflict_overwrite(mut self, overwrite: ConflictOverwrite) -> Self {
+        self.table.pk_desc = overwrite
+            .pk_indices
+            .iter()
+            .map(|idx| OrderedColumnDesc {
+                column_desc: self.table.columns[*idx].column_desc.clone(),
+                order: OrderType::Ascending,
+            })
+            .collect();
+        self.base.pk_indices = overwrite.pk_indices.clone();
+        // The overwritten rows are retracted.
+        self.base.append_only = false;
+        self.overwrite = Some(overwrite);
+        self
     }
 
     /// Get a reference to the stream materialize's table.
@@ -203,7 +247,10 @@ impl PlanTreeNodeUnary for StreamMaterialize {
     }
 
     fn clone_with_input(&self, input: PlanRef) -> Self {
-        let new = Self::new(input, self.table().clone());
+        let mut new = Self::new(input, self.table().clone());
+        if let Some(overwrite) = &self.overwrite {
+            new = new.with_conflict_overwrite(overwrite.clone());
+        }
         assert_eq!(new.plan_base().schema, self.plan_base().schema);
         assert_eq!(new.plan_base().pk_indices, self.plan_base().pk_indices);
         new
@@ -249,6 +296,40 @@ impl ToStreamProst for StreamMaterialize {
                 .iter()
                 .map(|idx| *idx as i32)
                 .collect_vec(),
+            overwrite: self
+                .overwrite
+                .as_ref()
+                .map(|overwrite| ProstConflictOverwrite {
+                    optional_version_column_index: overwrite
+                        .version_column_index
+                        .map(|idx| OptionalVersionColumnIndex::VersionColumnIndex(idx as u32)),
+                    row_id_index: overwrite.row_id_index as u32,
+                    // Filled by the meta service.
+                    table_source_ref_id: None,
+                }),
         })
     }
 }
+
+impl StateTables for StreamMaterialize {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        let key = self
+            .table
+            .pk_desc()
+            .iter()
+            .map(|pk| Field::with_name(pk.column_desc.data_type.clone(), &pk.column_desc.name))
+            .collect();
+        let value = self
+            .table
+            .columns()
+            .iter()
+            .map(|c| Field::with_name(c.data_type().clone(), c.name()))
+            .collect();
+        vec![StateTableDesc {
+            name: "materialize".to_string(),
+            key,
+            value,
+            growth: StateGrowth::Unbounded("one row per distinct primary key"),
+        }]
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::catalog::Field;
+use risingwave_common::types::DataType;
+use risingwave_pb::plan::JoinType;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_pb::stream_plan::NestedLoopJoinNode;
+
+use super::{
+    LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary, StateGrowth, StateTableDesc, StateTables,
+    ToStreamProst,
+};
+use crate::expr::Expr;
+use crate::optimizer::property::Distribution;
+use crate::session::OptimizerContextRef;
+use crate::utils::ColIndexMapping;
+
+/// Option of `CREATE MATERIALIZED VIEW ... WITH (...)`: the maximum number of rows of the right
+/// side of a join without equi-conditions to be broadcast. When set, it also allows broadcasting
+/// relations whose size cannot be estimated, e.g. tables.
+pub const BROADCAST_JOIN_MAX_ROWS: &str = "broadcast_join_max_rows";
+
+/// The default value of [`BROADCAST_JOIN_MAX_ROWS`].
+const DEFAULT_BROADCAST_JOIN_MAX_ROWS: usize = 1024;
+
+/// `StreamNestedLoopJoin` implements [`super::LogicalJoin`] without equi-conditions. The right
+/// side is broadcast to every parallel unit and kept fully in state, so it must be small, while
+/// the left side stays in its original distribution.
+#[derive(Debug, Clone)]
+pub struct StreamNestedLoopJoin {
+    pub base: PlanBase,
+    logical: LogicalJoin,
+}
+
+impl StreamNestedLoopJoin {
+    pub fn new(logical: LogicalJoin) -> Self {
+        assert!(matches!(
+            logical.join_type(),
+            JoinType::Inner | JoinType::LeftOuter
+        ));
+        assert_eq!(logical.right().distribution(), &Distribution::Broadcast);
+        let ctx = logical.base.ctx.clone();
+        let dist = Self::derive_dist(logical.left().distribution(), &logical.l2o_col_mapping());
+        // A row may be retracted once the right side changes, even if both inputs are
+        // append-only.
+        let base = PlanBase::new_stream(
+            ctx,
+            logical.schema().clone(),
+            logical.base.pk_indices.to_vec(),
+            dist,
+            false,
+        );
+
+        Self { base, logical }
+    }
+
+    fn derive_dist(left: &Distribution, l2o_mapping: &ColIndexMapping) -> Distribution {
+        match left {
+            Distribution::Single => Distribution::Single,
+            Distribution::HashShard(_) => l2o_mapping.rewrite_provided_distribution(left),
+            _ => panic!(),
+        }
+    }
+
+    /// Whether a relation with `estimated_rows` rows can be broadcast to a nested loop join, under
+    /// the [`BROADCAST_JOIN_MAX_ROWS`] option of the context.
+    pub fn can_broadcast(ctx: &OptimizerContextRef, estimated_rows: Option<usize>) -> bool {
+        let max_rows = ctx
+            .inner()
+            .with_options
+            .get(BROADCAST_JOIN_MAX_ROWS)
+            .and_then(|value| value.parse::<usize>().ok());
+        match (estimated_rows, max_rows) {
+            (Some(rows), Some(max_rows)) => rows <= max_rows,
+            (Some(rows), None) => rows <= DEFAULT_BROADCAST_JOIN_MAX_ROWS,
+            (None, Some(_)) => true,
+            (None, None) => false,
+        }
+    }
+}
+
+impl fmt::Display for StreamNestedLoopJoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "StreamNestedLoopJoin {{ type: {:?}, predicate: {} }}",
+            self.logical.join_type(),
+            self.logical.on()
+        )
+    }
+}
+
+impl PlanTreeNodeBinary for StreamNestedLoopJoin {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_left_right(left, right))
+    }
+}
+
+impl_plan_tree_node_for_binary! { StreamNestedLoopJoin }
+
+impl ToStreamProst for StreamNestedLoopJoin {
+    fn to_stream_prost_body(&self) -> Node {
+        Node::NestedLoopJoinNode(NestedLoopJoinNode {
+            join_type: self.logical.join_type() as i32,
+            condition: self
+                .logical
+                .on()
+                .as_expr_unless_true()
+                .map(|x| x.to_protobuf()),
+        })
+    }
+}
+
+impl StateTables for StreamNestedLoopJoin {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        let count = Field::with_name(DataType::Int64, "count");
+        // The degree is only kept for the left side of a left outer join.
+        let mut left_value = vec![count.clone()];
+        if self.logical.join_type() == JoinType::LeftOuter {
+            left_value.push(Field::with_name(DataType::Int64, "degree"));
+        }
+        vec![
+            StateTableDesc {
+                name: "nested_loop_join_left".to_string(),
+                key: self.left().schema().fields().to_vec(),
+                value: left_value,
+                growth: StateGrowth::Unbounded("one row per distinct input row of this side"),
+            },
+            StateTableDesc {
+                name: "nested_loop_join_right".to_string(),
+                key: self.right().schema().fields().to_vec(),
+                value: vec![count],
+                growth: StateGrowth::Unbounded("one row per distinct input row of this side"),
+            },
+        ]
+    }
+}
@@ -14,11 +14,16 @@
 
 use std::fmt;
 
+use risingwave_common::catalog::Field;
+use risingwave_common::types::DataType;
 use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
 use risingwave_pb::stream_plan::ProjectNode;
 
-use super::{LogicalProject, PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
-use crate::expr::Expr;
+use super::{
+    LogicalProject, PlanBase, PlanRef, PlanTreeNodeUnary, StateGrowth, StateTableDesc, StateTables,
+    ToStreamProst,
+};
+use crate::expr::{Expr, ExprImpl};
 
 /// `StreamProject` implements [`super::LogicalProject`] to evaluate specified expressions on input
 /// rows.
@@ -53,6 +58,15 @@ impl StreamProject {
         );
         StreamProject { base, logical }
     }
+
+    /// Whether the select list has stateful calls, e.g. `monotonic_id()`. They're always at the
+    /// top level, see `LogicalProject::logical_rewrite_for_stream`.
+    fn is_stateful(&self) -> bool {
+        self.logical
+            .exprs()
+            .iter()
+            .any(|expr| matches!(expr, ExprImpl::FunctionCall(call) if call.is_stateful()))
+    }
 }
 
 impl PlanTreeNodeUnary for StreamProject {
@@ -70,6 +84,21 @@ impl ToStreamProst for StreamProject {
     fn to_stream_prost_body(&self) -> ProstStreamNode {
         ProstStreamNode::ProjectNode(ProjectNode {
             select_list: self.logical.exprs().iter().map(Expr::to_protobuf).collect(),
+            stateful: self.is_stateful(),
         })
     }
 }
+
+impl StateTables for StreamProject {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        if !self.is_stateful() {
+            return vec![];
+        }
+        vec![StateTableDesc {
+            name: "project".to_string(),
+            key: vec![Field::with_name(DataType::Int32, "expr_index")],
+            value: vec![Field::with_name(DataType::Varchar, "state")],
+            growth: StateGrowth::Bounded("one row per stateful expression"),
+        }]
+    }
+}
@@ -17,8 +17,12 @@ use std::fmt;
 use itertools::Itertools;
 use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
 
+use super::explain_state::agg_call_state_tables;
 use super::logical_agg::PlanAggCall;
-use super::{LogicalAgg, PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use super::{
+    LogicalAgg, PlanBase, PlanRef, PlanTreeNodeUnary, StateGrowth, StateTableDesc, StateTables,
+    ToStreamProst,
+};
 use crate::optimizer::property::Distribution;
 
 #[derive(Debug, Clone)]
@@ -89,3 +93,21 @@ impl ToStreamProst for StreamSimpleAgg {
         })
     }
 }
+
+impl StateTables for StreamSimpleAgg {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        let mut tables = vec![StateTableDesc {
+            name: "simple_agg".to_string(),
+            key: vec![],
+            value: self.base.schema.fields().to_vec(),
+            growth: StateGrowth::Bounded("a single row"),
+        }];
+        tables.extend(agg_call_state_tables(
+            self.agg_calls(),
+            vec![],
+            self.input().append_only(),
+            self.input().schema().fields(),
+        ));
+        tables
+    }
+}
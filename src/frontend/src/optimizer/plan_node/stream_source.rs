@@ -14,11 +14,13 @@
 
 use std::fmt;
 
+use risingwave_common::catalog::Field;
+use risingwave_common::types::DataType;
 use risingwave_pb::plan::TableRefId;
 use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
 use risingwave_pb::stream_plan::SourceNode;
 
-use super::{LogicalSource, PlanBase, ToStreamProst};
+use super::{LogicalSource, PlanBase, StateGrowth, StateTableDesc, StateTables, ToStreamProst};
 use crate::optimizer::property::Distribution;
 
 /// [`StreamSource`] represents a table/connector source at the very beginning of the graph.
@@ -64,6 +66,9 @@ impl fmt::Display for StreamSource {
 
 impl ToStreamProst for StreamSource {
     fn to_stream_prost_body(&self) -> ProstStreamNode {
+        // The columns are never pruned, so the indices of the event time column and the inputs of
+        // its expression are the same as in the catalog.
+        let event_time_desc = self.logical.source_catalog.event_time_desc.as_ref();
         ProstStreamNode::SourceNode(SourceNode {
             // TODO: Refactor this id
             table_ref_id: TableRefId {
@@ -80,6 +85,20 @@ impl ToStreamProst for StreamSource {
                 .collect(),
             source_type: self.logical.source_catalog.source_type as i32,
             stream_source_state: None,
+            throttle: None,
+            event_time_expr: event_time_desc.and_then(|desc| desc.expr.clone()),
+            event_time_idx: event_time_desc.map_or(0, |desc| desc.event_time_idx),
         })
     }
 }
+
+impl StateTables for StreamSource {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        vec![StateTableDesc {
+            name: "source".to_string(),
+            key: vec![Field::with_name(DataType::Varchar, "split_id")],
+            value: vec![Field::with_name(DataType::Varchar, "split_state")],
+            growth: StateGrowth::Bounded("one row per source split"),
+        }]
+    }
+}
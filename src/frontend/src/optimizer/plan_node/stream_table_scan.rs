@@ -18,9 +18,14 @@ use itertools::Itertools;
 use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
 use risingwave_pb::stream_plan::StreamNode as ProstStreamPlan;
 
-use super::{LogicalScan, PlanBase, PlanNodeId, ToStreamProst};
+use super::{LogicalScan, PlanBase, PlanNodeId, StateTables, ToStreamProst};
 use crate::optimizer::property::Distribution;
 
+/// Option of `CREATE MATERIALIZED VIEW ... WITH (...)`: if `true`, the upstream mviews are read as
+/// bounded streams, i.e. only their snapshots are consumed, and the changes after the creation are
+/// ignored. It's used by one-shot jobs like `CREATE TABLE AS`.
+pub const SNAPSHOT_ONLY: &str = "snapshot_only";
+
 /// `StreamTableScan` is a virtual plan node to represent a stream table scan. It will be converted
 /// to chain + merge node (for upstream materialize) + batch table scan when converting to `MView`
 /// creation request.
@@ -55,6 +60,18 @@ impl StreamTableScan {
     pub fn table_name(&self) -> &str {
         self.logical.table_name()
     }
+
+    /// Whether only the snapshot of the upstream mview is read, under the [`SNAPSHOT_ONLY`]
+    /// option of the context.
+    pub fn snapshot_only(&self) -> bool {
+        self.base
+            .ctx
+            .inner()
+            .with_options
+            .get(SNAPSHOT_ONLY)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
 }
 impl_plan_tree_node_for_leaf! { StreamTableScan }
 
@@ -62,10 +79,15 @@ impl fmt::Display for StreamTableScan {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "StreamTableScan {{ table: {}, columns: [{}], pk_indices: {:?} }}",
+            "StreamTableScan {{ table: {}, columns: [{}], pk_indices: {:?}{} }}",
             self.logical.table_name(),
             self.logical.column_names().join(", "),
-            self.base.pk_indices
+            self.base.pk_indices,
+            if self.snapshot_only() {
+                ", snapshot_only: true"
+            } else {
+                ""
+            }
         )
     }
 }
@@ -157,6 +179,7 @@ impl StreamTableScan {
                     .iter()
                     .map(|x| x.column_id.get_id())
                     .collect(),
+                snapshot_only: self.snapshot_only(),
             })),
             pk_indices,
             operator_id: if auto_fields {
@@ -172,3 +195,5 @@ impl StreamTableScan {
         }
     }
 }
+
+impl StateTables for StreamTableScan {}
@@ -0,0 +1,146 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_pb::plan::OrderType as ProstOrderType;
+use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
+use risingwave_pb::stream_plan::TopNNode;
+
+use super::{
+    LogicalTopN, PlanBase, PlanRef, PlanTreeNodeUnary, StateGrowth, StateTableDesc, StateTables,
+    ToStreamProst,
+};
+
+/// `StreamTopN` implements [`super::LogicalTopN`] to keep the top N rows of the input stream up to
+/// date. It's lowered to the append-only variant if the input is append-only, which needs no state
+/// for the rows out of the top N.
+#[derive(Debug, Clone)]
+pub struct StreamTopN {
+    pub base: PlanBase,
+    logical: LogicalTopN,
+}
+
+impl StreamTopN {
+    pub fn new(logical: LogicalTopN) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let input = logical.input();
+        // The executor keeps the rows ordered by their pk, so the pk starts with the order key and
+        // is followed by the rest of the input pk.
+        let mut pk_indices = logical
+            .topn_order()
+            .field_order
+            .iter()
+            .map(|fo| fo.index)
+            .collect::<Vec<_>>();
+        for idx in input.pk_indices() {
+            if !pk_indices.contains(idx) {
+                pk_indices.push(*idx);
+            }
+        }
+        // Rows pushed out of the top N are deleted, so the output is never append-only.
+        let base = PlanBase::new_stream(
+            ctx,
+            logical.schema().clone(),
+            pk_indices,
+            input.distribution().clone(),
+            false,
+        );
+        StreamTopN { base, logical }
+    }
+
+    fn is_append_only_input(&self) -> bool {
+        self.input().append_only()
+    }
+}
+
+impl fmt::Display for StreamTopN {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_append_only_input() {
+            self.logical.fmt_with_name(f, "StreamAppendOnlyTopN")
+        } else {
+            self.logical.fmt_with_name(f, "StreamTopN")
+        }
+    }
+}
+
+impl PlanTreeNodeUnary for StreamTopN {
+    fn input(&self) -> PlanRef {
+        self.logical.input()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_input(input))
+    }
+}
+impl_plan_tree_node_for_unary! {StreamTopN}
+
+impl ToStreamProst for StreamTopN {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        let order = &self.logical.topn_order().field_order;
+        // The order types go along with the pk, where the columns after the order key are only
+        // there to make the pk unique.
+        let order_types = order
+            .iter()
+            .map(|fo| fo.direct.to_protobuf())
+            .chain(
+                std::iter::repeat(ProstOrderType::Ascending)
+                    .take(self.pk_indices().len() - order.len()),
+            )
+            .map(|order_type| order_type as i32)
+            .collect();
+        let node = TopNNode {
+            order_types,
+            limit: self.logical.limit() as u64,
+            offset: self.logical.offset() as u64,
+            distribution_keys: vec![],
+        };
+        if self.is_append_only_input() {
+            ProstStreamNode::AppendOnlyTopNNode(node)
+        } else {
+            ProstStreamNode::TopNNode(node)
+        }
+    }
+}
+
+impl StateTables for StreamTopN {
+    /// The rows are split into the ranges before, within, and after `[offset, offset + limit)`,
+    /// each kept in a table keyed by the pk. The append-only variant drops the rows after the
+    /// range.
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        let fields = self.base.schema.fields();
+        let range = |name: &str, growth: StateGrowth| StateTableDesc {
+            name: name.to_string(),
+            key: self
+                .base
+                .pk_indices
+                .iter()
+                .map(|idx| fields[*idx].clone())
+                .collect(),
+            value: fields.to_vec(),
+            growth,
+        };
+        let mut tables = vec![
+            range("top_n_lower", StateGrowth::Bounded("`OFFSET` rows")),
+            range("top_n_middle", StateGrowth::Bounded("`LIMIT` rows")),
+        ];
+        if !self.is_append_only_input() {
+            tables.push(range(
+                "top_n_higher",
+                StateGrowth::Unbounded("one row per input row after the top N"),
+            ));
+        }
+        tables
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::catalog::Field;
+use risingwave_common::types::DataType;
+use risingwave_pb::plan::ValuesNode;
+use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
+
+use super::batch_values::row_to_protobuf;
+use super::{LogicalValues, PlanBase, StateGrowth, StateTableDesc, StateTables, ToStreamProst};
+use crate::optimizer::property::Distribution;
+
+/// [`StreamValues`] emits the rows of `VALUES` once after the first barrier, and is the source of
+/// `CREATE MATERIALIZED VIEW ... AS VALUES`.
+#[derive(Debug, Clone)]
+pub struct StreamValues {
+    pub base: PlanBase,
+    logical: LogicalValues,
+}
+
+impl_plan_tree_node_for_leaf! { StreamValues }
+
+impl StreamValues {
+    pub fn new(logical: LogicalValues) -> Self {
+        let ctx = logical.ctx();
+        // The rows are only emitted by a single actor, and never updated afterwards.
+        let base = PlanBase::new_stream(
+            ctx,
+            logical.schema().clone(),
+            logical.pk_indices().to_vec(),
+            Distribution::Single,
+            true,
+        );
+        Self { base, logical }
+    }
+
+    /// Get a reference to the stream values's logical.
+    #[must_use]
+    pub fn logical(&self) -> &LogicalValues {
+        &self.logical
+    }
+}
+
+impl fmt::Display for StreamValues {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamValues")
+            .field("rows", &self.logical.rows())
+            .finish()
+    }
+}
+
+impl ToStreamProst for StreamValues {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::ValuesNode(ValuesNode {
+            tuples: self
+                .logical
+                .rows()
+                .iter()
+                .map(|row| row_to_protobuf(row))
+                .collect(),
+            fields: self
+                .logical
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.to_prost())
+                .collect(),
+        })
+    }
+}
+
+impl StateTables for StreamValues {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        vec![StateTableDesc {
+            name: "values".to_string(),
+            key: vec![],
+            value: vec![Field::with_name(DataType::Int64, "emitted_epoch")],
+            growth: StateGrowth::Bounded("a single row"),
+        }]
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::catalog::Field;
+use risingwave_pb::catalog::WatermarkDesc;
+use risingwave_pb::stream_plan::stream_node::Node as ProstStreamNode;
+use risingwave_pb::stream_plan::WatermarkFilterNode;
+
+use super::{
+    PlanBase, PlanRef, PlanTreeNodeUnary, StateGrowth, StateTableDesc, StateTables, ToStreamProst,
+};
+
+/// `StreamWatermarkFilter` follows the [`super::StreamSource`] of a source with a `WATERMARK FOR`
+/// clause. It generates the watermark of the event time column and drops the late rows, so that
+/// the downstream operators of the source, e.g. the windowed ones, only see rows within the
+/// watermark.
+#[derive(Debug, Clone)]
+pub struct StreamWatermarkFilter {
+    pub base: PlanBase,
+    input: PlanRef,
+    watermark_desc: WatermarkDesc,
+}
+
+impl StreamWatermarkFilter {
+    pub fn new(input: PlanRef, watermark_desc: WatermarkDesc) -> Self {
+        let ctx = input.ctx();
+        let pk_indices = input.pk_indices().to_vec();
+        // Late rows are dropped, so the output is append-only only if the input is.
+        let base = PlanBase::new_stream(
+            ctx,
+            input.schema().clone(),
+            pk_indices,
+            input.distribution().clone(),
+            input.append_only(),
+        );
+        StreamWatermarkFilter {
+            base,
+            input,
+            watermark_desc,
+        }
+    }
+
+    /// The index of the event time column, whose watermark is generated by this node.
+    pub fn event_time_col_idx(&self) -> usize {
+        self.watermark_desc.watermark_idx as usize
+    }
+}
+
+impl fmt::Display for StreamWatermarkFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "StreamWatermarkFilter {{ event_time: {} }}",
+            self.schema().fields()[self.event_time_col_idx()].name
+        )
+    }
+}
+
+impl PlanTreeNodeUnary for StreamWatermarkFilter {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.watermark_desc.clone())
+    }
+}
+impl_plan_tree_node_for_unary! {StreamWatermarkFilter}
+
+impl ToStreamProst for StreamWatermarkFilter {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::WatermarkFilterNode(WatermarkFilterNode {
+            event_time_col_idx: self.watermark_desc.watermark_idx,
+            watermark_expr: self.watermark_desc.expr.clone(),
+            idle_timeout_ms: self.watermark_desc.idle_timeout_ms,
+        })
+    }
+}
+
+impl StateTables for StreamWatermarkFilter {
+    fn state_tables(&self) -> Vec<StateTableDesc> {
+        let event_time = &self.input.schema().fields()[self.event_time_col_idx()];
+        vec![StateTableDesc {
+            name: "watermark".to_string(),
+            key: vec![],
+            value: vec![Field::with_name(event_time.data_type(), "watermark")],
+            growth: StateGrowth::Bounded("a single row"),
+        }]
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use risingwave_common::types::DataType;
+use risingwave_expr::expr::AggKind;
+use risingwave_pb::plan::JoinType;
+
+use super::super::plan_node::*;
+use super::{BoxedRule, Rule};
+use crate::expr::{ExprImpl, InputRef};
+use crate::utils::ColIndexMapping;
+
+/// Pre-aggregates one side of an inner join by the join keys (eager aggregation), when all the
+/// aggregated columns come from that side and the aggregations can be split into two phases.
+///
+/// ```text
+/// Agg(group_keys, sum(s.v), count(*))
+///   Join(on: o.k = s.k)
+///     o
+///     s
+/// ```
+/// will be rewritten to
+/// ```text
+/// Agg(group_keys, sum(partial_sum), sum(partial_count))
+///   Join(on: o.k = s.k)
+///     o
+///     Agg(group_keys: [s.k], partial_sum: sum(s.v), partial_count: count(*))
+///       s
+/// ```
+/// where the pre-aggregation is also grouped by the columns of `s` in `group_keys`. As a result,
+/// the join keeps one row per key of `s` in its state instead of every row of `s`, and an update
+/// of `s` only churns the join output of its key once.
+///
+/// The rule only applies when it's likely to reduce the rows, i.e. the pre-aggregation does not
+/// group by the whole primary key of `s`, and `s` is not known to be small.
+pub struct AggJoinTransposeRule {}
+impl Rule for AggJoinTransposeRule {
+    fn apply(&self, plan: PlanRef) -> Option<PlanRef> {
+        let agg = plan.as_logical_agg()?;
+        // A simple agg outputs a row even if its input is empty, e.g. `count` outputs 0 rather
+        // than NULL, which can't be derived from the pre-aggregated rows.
+        if agg.group_keys().is_empty() {
+            return None;
+        }
+        if !agg.agg_calls().iter().all(Self::is_decomposable) {
+            return None;
+        }
+
+        // The agg input may be a project of columns from the join.
+        let agg_input = agg.input();
+        let (join_ref, input_to_join) = match agg_input.as_logical_project() {
+            Some(project) => {
+                if !project
+                    .exprs()
+                    .iter()
+                    .all(|expr| matches!(expr, ExprImpl::InputRef(_)))
+                {
+                    return None;
+                }
+                (project.input(), project.o2i_col_mapping())
+            }
+            None => {
+                let input_len = agg_input.schema().len();
+                (agg_input, ColIndexMapping::identity(input_len))
+            }
+        };
+        let join = join_ref.as_logical_join()?;
+        if join.join_type() != JoinType::Inner {
+            return None;
+        }
+
+        let group_keys = agg
+            .group_keys()
+            .iter()
+            .map(|key| input_to_join.map(*key))
+            .collect_vec();
+        let agg_calls = agg
+            .agg_calls()
+            .iter()
+            .cloned()
+            .map(|mut agg_call| {
+                agg_call.rewrite_input_index(&input_to_join);
+                agg_call
+            })
+            .collect_vec();
+
+        let left_len = join.left().schema().len();
+        let agg_inputs = agg_calls
+            .iter()
+            .flat_map(|agg_call| agg_call.input_indices())
+            .collect_vec();
+        let mut result = None;
+        if agg_inputs.iter().all(|i| *i < left_len) {
+            result = Self::push_to_side(join, &group_keys, &agg_calls, true);
+        }
+        if result.is_none() && agg_inputs.iter().all(|i| *i >= left_len) {
+            result = Self::push_to_side(join, &group_keys, &agg_calls, false);
+        }
+        let (join, mapping, partial_start) = result?;
+
+        let group_keys = group_keys.iter().map(|key| mapping.map(*key)).collect_vec();
+        let agg_calls = agg_calls
+            .into_iter()
+            .enumerate()
+            .map(|(i, agg_call)| {
+                let agg_kind = match agg_call.agg_kind {
+                    AggKind::Sum | AggKind::Count => AggKind::Sum,
+                    agg_kind => agg_kind,
+                };
+                PlanAggCall {
+                    agg_kind,
+                    return_type: agg_call.return_type.clone(),
+                    inputs: vec![InputRef::new(partial_start + i, agg_call.return_type)],
+                    distinct: false,
+                    order_by_fields: vec![],
+                }
+            })
+            .collect_vec();
+        Some(
+            LogicalAgg::new(
+                agg_calls,
+                agg.agg_call_alias().to_vec(),
+                group_keys,
+                join.into(),
+            )
+            .into(),
+        )
+    }
+}
+
+impl AggJoinTransposeRule {
+    pub fn create() -> BoxedRule {
+        Box::new(AggJoinTransposeRule {})
+    }
+
+    /// Whether the agg call can be computed by aggregating the partial results of itself.
+    fn is_decomposable(agg_call: &PlanAggCall) -> bool {
+        if agg_call.distinct || !agg_call.order_by_fields.is_empty() {
+            return false;
+        }
+        match agg_call.agg_kind {
+            AggKind::Min | AggKind::Max | AggKind::BitAnd | AggKind::BitOr => true,
+            // The partial results are summed up, so `sum` must be supported on the return type.
+            AggKind::Sum | AggKind::Count => matches!(
+                agg_call.return_type,
+                DataType::Int64 | DataType::Decimal | DataType::Float32 | DataType::Float64
+            ),
+            _ => false,
+        }
+    }
+
+    /// Pre-aggregates the left or right side of `join` by its columns in `group_keys` and the
+    /// join condition. Returns the new join, the mapping from the columns of the original join to
+    /// the new one, and the index of the first partial agg result in the new join.
+    fn push_to_side(
+        join: &LogicalJoin,
+        group_keys: &[usize],
+        agg_calls: &[PlanAggCall],
+        left: bool,
+    ) -> Option<(LogicalJoin, ColIndexMapping, usize)> {
+        let left_len = join.left().schema().len();
+        let join_len = join.schema().len();
+        let (side, offset) = if left {
+            (join.left(), 0)
+        } else {
+            (join.right(), left_len)
+        };
+        let side_len = side.schema().len();
+
+        let mut join_cols = FixedBitSet::with_capacity(join_len);
+        join_cols.extend(group_keys.iter().copied());
+        join.on().conjunctions.iter().for_each(|expr| {
+            join_cols.union_with(&expr.collect_input_refs(join_len));
+        });
+        let pre_agg_keys = join_cols
+            .ones()
+            .filter(|i| (offset..offset + side_len).contains(i))
+            .map(|i| i - offset)
+            .collect_vec();
+
+        // Without keys, the pre-aggregation would output a row even if `side` is empty.
+        if pre_agg_keys.is_empty() {
+            return None;
+        }
+        // Grouping by the primary key can't reduce any row.
+        let side_pk = side.pk_indices();
+        if !side_pk.is_empty() && side_pk.iter().all(|pk| pre_agg_keys.contains(pk)) {
+            return None;
+        }
+        // A small side gains little from the pre-aggregation.
+        if LogicalJoin::estimate_row_count(&side).is_some() {
+            return None;
+        }
+
+        let side_mapping = ColIndexMapping::with_shift_offset(join_len, -(offset as isize));
+        let partial_agg_calls = agg_calls
+            .iter()
+            .cloned()
+            .map(|mut agg_call| {
+                agg_call.rewrite_input_index(&side_mapping);
+                agg_call
+            })
+            .collect_vec();
+        let pre_agg: PlanRef = LogicalAgg::new(
+            partial_agg_calls,
+            vec![None; agg_calls.len()],
+            pre_agg_keys.clone(),
+            side,
+        )
+        .into();
+
+        let (new_left, new_right, new_offset) = if left {
+            (pre_agg, join.right(), 0)
+        } else {
+            (join.left(), pre_agg, left_len)
+        };
+        let new_left_len = new_left.schema().len();
+        let map = (0..join_len)
+            .map(|i| {
+                if (offset..offset + side_len).contains(&i) {
+                    pre_agg_keys
+                        .iter()
+                        .position(|key| *key == i - offset)
+                        .map(|pos| new_offset + pos)
+                } else if i < left_len {
+                    Some(i)
+                } else {
+                    Some(i - left_len + new_left_len)
+                }
+            })
+            .collect_vec();
+        let new_join_len = new_left_len + new_right.schema().len();
+        let mut mapping = ColIndexMapping::with_target_size(map, new_join_len);
+        let on = join.on().clone().rewrite_expr(&mut mapping);
+        let new_join = LogicalJoin::new(new_left, new_right, JoinType::Inner, on);
+
+        let partial_start = new_offset + pre_agg_keys.len();
+        Some((new_join, mapping, partial_start))
+    }
+}
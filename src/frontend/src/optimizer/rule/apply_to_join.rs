@@ -0,0 +1,173 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::plan::JoinType;
+
+use super::super::plan_node::*;
+use super::{BoxedRule, Rule};
+use crate::expr::{CorrelatedInputRef, Expr, ExprImpl, ExprRewriter, InputRef};
+use crate::utils::Condition;
+
+/// Unnest a [`LogicalApply`] into a [`LogicalJoin`], when the correlated columns are only referred
+/// by the [`LogicalProject`]s and [`LogicalFilter`]s on the top of its right side.
+///
+/// ```text
+/// Apply(on: cond)
+///   left
+///   Project(exprs)
+///     Filter(predicate)
+///       right
+/// ```
+/// will be rewritten to
+/// ```text
+/// Project(left.*, exprs)
+///   Join(on: predicate AND cond)
+///     left
+///     right
+/// ```
+/// where the correlated columns in `exprs`, `predicate` and `cond` are replaced by the columns of
+/// `left`.
+pub struct ApplyToJoinRule {}
+impl Rule for ApplyToJoinRule {
+    fn apply(&self, plan: PlanRef) -> Option<PlanRef> {
+        let apply = plan.as_logical_apply()?;
+        let left = apply.left();
+        let left_len = left.schema().len();
+        let (right, exprs, predicate) = Self::pull_up(apply.right(), left_len)?;
+
+        let join_type = apply.join_type();
+        // The expressions would be evaluated on the null-extended rows after the join, where e.g.
+        // a constant would not be null.
+        if join_type == JoinType::LeftOuter
+            && !exprs
+                .iter()
+                .all(|expr| matches!(expr, ExprImpl::InputRef(_)))
+        {
+            return None;
+        }
+
+        // `on` refers to the columns of `left` and the output of the right side.
+        let mut rewriter = Decorrelate {
+            mapping: left
+                .schema()
+                .fields()
+                .iter()
+                .enumerate()
+                .map(|(i, field)| InputRef::new(i, field.data_type()).into())
+                .chain(exprs)
+                .collect(),
+            failed: false,
+        };
+        let on = apply.on().clone().rewrite_expr(&mut rewriter);
+        let join: PlanRef = LogicalJoin::new(left, right, join_type, predicate.and(on)).into();
+        match join_type {
+            JoinType::LeftSemi | JoinType::LeftAnti => Some(join),
+            _ => {
+                let expr_alias = vec![None; rewriter.mapping.len()];
+                Some(LogicalProject::create(join, rewriter.mapping, expr_alias))
+            }
+        }
+    }
+}
+
+impl ApplyToJoinRule {
+    pub fn create() -> BoxedRule {
+        Box::new(ApplyToJoinRule {})
+    }
+
+    /// Pulls up the [`LogicalProject`]s and [`LogicalFilter`]s from the right side `plan` of an
+    /// apply, until an uncorrelated sub-tree is reached.
+    ///
+    /// Returns the uncorrelated sub-tree, the output of `plan` and the pulled up predicates, where
+    /// the expressions refer to the columns of `[left, sub-tree]`.
+    fn pull_up(plan: PlanRef, left_len: usize) -> Option<(PlanRef, Vec<ExprImpl>, Condition)> {
+        if let Some(project) = plan.as_logical_project() {
+            let (input, mapping, predicate) = Self::pull_up(project.input(), left_len)?;
+            let mut rewriter = Decorrelate {
+                mapping,
+                failed: false,
+            };
+            let exprs = project
+                .exprs()
+                .iter()
+                .cloned()
+                .map(|expr| rewriter.rewrite_expr(expr))
+                .collect();
+            (!rewriter.failed).then(|| (input, exprs, predicate))
+        } else if let Some(filter) = plan.as_logical_filter() {
+            let (input, mapping, predicate) = Self::pull_up(filter.input(), left_len)?;
+            let mut rewriter = Decorrelate {
+                mapping,
+                failed: false,
+            };
+            let filter_predicate = filter.predicate().clone().rewrite_expr(&mut rewriter);
+            (!rewriter.failed).then(|| (input, rewriter.mapping, predicate.and(filter_predicate)))
+        } else if Self::has_correlated_input_ref(&plan) {
+            None
+        } else {
+            let exprs = plan
+                .schema()
+                .fields()
+                .iter()
+                .enumerate()
+                .map(|(i, field)| InputRef::new(left_len + i, field.data_type()).into())
+                .collect();
+            Some((plan, exprs, Condition::true_cond()))
+        }
+    }
+
+    /// Whether any node in the tree of `plan` refers to a correlated column.
+    fn has_correlated_input_ref(plan: &PlanRef) -> bool {
+        let exprs = if let Some(project) = plan.as_logical_project() {
+            project.exprs().clone()
+        } else if let Some(filter) = plan.as_logical_filter() {
+            filter.predicate().conjunctions.clone()
+        } else if let Some(join) = plan.as_logical_join() {
+            join.on().conjunctions.clone()
+        } else if let Some(apply) = plan.as_logical_apply() {
+            apply.on().conjunctions.clone()
+        } else if let Some(values) = plan.as_logical_values() {
+            values.rows().concat()
+        } else if let Some(project_set) = plan.as_logical_project_set() {
+            project_set.select_list().clone()
+        } else {
+            vec![]
+        };
+        exprs.iter().any(|expr| expr.has_correlated_input_ref())
+            || plan.inputs().iter().any(Self::has_correlated_input_ref)
+    }
+}
+
+/// Substitutes the `InputRef`s with `mapping`, and replaces the correlated columns referring to the
+/// left side of the apply with `InputRef`s.
+struct Decorrelate {
+    mapping: Vec<ExprImpl>,
+    /// Set if there is a column correlated to an outer query, which can't be unnested.
+    failed: bool,
+}
+
+impl ExprRewriter for Decorrelate {
+    fn rewrite_input_ref(&mut self, input_ref: InputRef) -> ExprImpl {
+        self.mapping[input_ref.index()].clone()
+    }
+
+    fn rewrite_correlated_input_ref(&mut self, input_ref: CorrelatedInputRef) -> ExprImpl {
+        if input_ref.depth() == 1 {
+            InputRef::new(input_ref.index(), input_ref.return_type()).into()
+        } else {
+            self.failed = true;
+            input_ref.into()
+        }
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::plan::JoinType;
+
+use super::super::plan_node::*;
+use super::{BoxedRule, Rule};
+use crate::expr::{ExprImpl, ExprType};
+use crate::utils::Condition;
+
+/// Rewrites a join with a single-row right side into a [`LogicalDynamicFilter`], when the right
+/// side is only used to filter the left side by a comparison, e.g. for
+/// `WHERE v > (SELECT max(x) FROM s)`.
+///
+/// ```text
+/// Project(exprs)
+///   Filter(predicate: left.a > right.b)
+///     Join(on: true)
+///       left
+///       right
+/// ```
+/// will be rewritten to
+/// ```text
+/// Project(exprs)
+///   DynamicFilter(predicate: left.a > right.b)
+///     left
+///     right
+/// ```
+/// where `exprs` only refer to the columns of `left`. For inner joins the comparison can also be
+/// the `on` condition. As a comparison with NULL never holds, a left outer join is handled in the
+/// same way.
+pub struct JoinToDynamicFilterRule {}
+impl Rule for JoinToDynamicFilterRule {
+    fn apply(&self, plan: PlanRef) -> Option<PlanRef> {
+        let project = plan.as_logical_project()?;
+        let input = project.input();
+        let (join_ref, predicate) = match input.as_logical_filter() {
+            Some(filter) => (filter.input(), filter.predicate().clone()),
+            None => (input.clone(), Condition::true_cond()),
+        };
+        let join = join_ref.as_logical_join()?;
+        match join.join_type() {
+            JoinType::Inner => {}
+            JoinType::LeftOuter if join.on().always_true() => {}
+            _ => return None,
+        }
+
+        let left = join.left();
+        let right = join.right();
+        let left_len = left.schema().len();
+        if right.schema().len() != 1 || LogicalJoin::estimate_row_count(&right) != Some(1) {
+            return None;
+        }
+        if project
+            .exprs()
+            .iter()
+            .any(|expr| expr.collect_input_refs(left_len + 1).contains(left_len))
+        {
+            return None;
+        }
+
+        let predicate = predicate.and(join.on().clone());
+        let (left_index, comparator) = match predicate.conjunctions.as_slice() {
+            [cond] => Self::as_comparison(cond, left_len)?,
+            _ => return None,
+        };
+        if left.schema().fields()[left_index].data_type() != right.schema().fields()[0].data_type()
+        {
+            return None;
+        }
+
+        let filter = LogicalDynamicFilter::new(left, left_index, comparator, right);
+        Some(
+            LogicalProject::new(
+                filter.into(),
+                project.exprs().clone(),
+                project.expr_alias().to_vec(),
+            )
+            .into(),
+        )
+    }
+}
+
+impl JoinToDynamicFilterRule {
+    pub fn create() -> BoxedRule {
+        Box::new(JoinToDynamicFilterRule {})
+    }
+
+    /// Matches `left.a <comparator> right.b` or `right.b <comparator> left.a`, and returns the
+    /// index of `left.a` and the comparator with `left.a` on the left-hand side.
+    fn as_comparison(cond: &ExprImpl, left_len: usize) -> Option<(usize, ExprType)> {
+        let call = match cond {
+            ExprImpl::FunctionCall(call) => call,
+            _ => return None,
+        };
+        let (lhs, rhs) = match call.inputs() {
+            [ExprImpl::InputRef(lhs), ExprImpl::InputRef(rhs)] => (lhs.index(), rhs.index()),
+            _ => return None,
+        };
+        let comparator = call.get_expr_type();
+        if !matches!(
+            comparator,
+            ExprType::LessThan
+                | ExprType::LessThanOrEqual
+                | ExprType::GreaterThan
+                | ExprType::GreaterThanOrEqual
+        ) {
+            return None;
+        }
+        if lhs < left_len && rhs == left_len {
+            Some((lhs, comparator))
+        } else if rhs < left_len && lhs == left_len {
+            let comparator = match comparator {
+                ExprType::LessThan => ExprType::GreaterThan,
+                ExprType::LessThanOrEqual => ExprType::GreaterThanOrEqual,
+                ExprType::GreaterThan => ExprType::LessThan,
+                ExprType::GreaterThanOrEqual => ExprType::LessThanOrEqual,
+                _ => unreachable!(),
+            };
+            Some((rhs, comparator))
+        } else {
+            None
+        }
+    }
+}
@@ -27,13 +27,13 @@ pub(super) type BoxedRule = Box<dyn Rule>;
 
 mod project_join;
 pub use project_join::*;
-mod filter_join;
-pub use filter_join::*;
-mod filter_project;
-pub use filter_project::*;
-mod filter_agg;
-pub use filter_agg::*;
 mod project_elim;
 pub use project_elim::*;
 mod project_merge;
 pub use project_merge::*;
+mod apply_to_join;
+pub use apply_to_join::*;
+mod join_to_dynamic_filter;
+pub use join_to_dynamic_filter::*;
+mod agg_join_transpose;
+pub use agg_join_transpose::*;
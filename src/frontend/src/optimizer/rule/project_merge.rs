@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools;
+
 use super::super::plan_node::*;
 use super::{BoxedRule, Rule};
-use crate::expr::ExprRewriter;
+use crate::expr::{ExprRewriter, ExprVisitor, InputRef};
 use crate::utils::Substitute;
 
 /// Merge contiguous [`LogicalProject`] nodes.
@@ -25,6 +27,23 @@ impl Rule for ProjectMergeRule {
         let input = outer_project.input();
         let inner_project = input.as_logical_project()?;
 
+        // A volatile expression can't be duplicated by substituting it into more than one place.
+        let mut counter = CountInputRef {
+            counts: vec![0; inner_project.exprs().len()],
+        };
+        outer_project
+            .exprs()
+            .iter()
+            .for_each(|expr| counter.visit_expr(expr));
+        if inner_project
+            .exprs()
+            .iter()
+            .zip_eq(counter.counts)
+            .any(|(expr, count)| count > 1 && expr.is_volatile())
+        {
+            return None;
+        }
+
         let mut subst = Substitute {
             mapping: inner_project.exprs().clone(),
         };
@@ -50,3 +69,14 @@ impl ProjectMergeRule {
         Box::new(ProjectMergeRule {})
     }
 }
+
+/// Counts the references to each input column.
+struct CountInputRef {
+    counts: Vec<usize>,
+}
+
+impl ExprVisitor for CountInputRef {
+    fn visit_input_ref(&mut self, input_ref: &InputRef) {
+        self.counts[input_ref.index()] += 1;
+    }
+}
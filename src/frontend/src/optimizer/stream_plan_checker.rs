@@ -0,0 +1,71 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::{ErrorCode, Result, RwError};
+
+use super::plan_node::*;
+use super::plan_visitor::PlanVisitor;
+
+/// Checks that a logical plan can be converted to a stream plan, so that the unsupported cases
+/// are reported as errors to the user instead of panicking in `to_stream`.
+#[derive(Default)]
+pub struct StreamPlanChecker {
+    error: Option<RwError>,
+}
+
+impl StreamPlanChecker {
+    pub fn check(plan: PlanRef) -> Result<()> {
+        let mut checker = Self::default();
+        checker.visit(plan);
+        match checker.error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Visits the inputs of `plan`, unless an error has been found.
+    fn visit_inputs(&mut self, plan: &impl PlanTreeNode) {
+        for input in plan.inputs() {
+            if self.error.is_some() {
+                return;
+            }
+            self.visit(input);
+        }
+    }
+}
+
+impl PlanVisitor<()> for StreamPlanChecker {
+    fn visit_logical_join(&mut self, plan: &LogicalJoin) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(err) = plan.check_to_stream() {
+            self.error = Some(err);
+            return;
+        }
+        self.visit_inputs(plan);
+    }
+
+    fn visit_logical_peek_source(&mut self, _plan: &LogicalPeekSource) {
+        if self.error.is_none() {
+            self.error = Some(
+                ErrorCode::NotImplemented(
+                    "peek_source in streaming queries".to_string(),
+                    None.into(),
+                )
+                .into(),
+            );
+        }
+    }
+}
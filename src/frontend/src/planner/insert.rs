@@ -15,8 +15,8 @@
 use fixedbitset::FixedBitSet;
 use risingwave_common::error::Result;
 
-use crate::binder::BoundInsert;
-use crate::optimizer::plan_node::{LogicalInsert, PlanRef};
+use crate::binder::{BoundInsert, BoundOnConflict, BoundOnConflictAction};
+use crate::optimizer::plan_node::{InsertOnConflict, LogicalInsert, PlanRef};
 use crate::optimizer::property::{Distribution, Order};
 use crate::optimizer::PlanRoot;
 use crate::planner::Planner;
@@ -24,11 +24,13 @@ use crate::planner::Planner;
 impl Planner {
     pub(super) fn plan_insert(&mut self, insert: BoundInsert) -> Result<PlanRoot> {
         let input = self.plan_query(insert.source)?.as_subplan();
+        let on_conflict = insert.on_conflict.map(Self::plan_on_conflict);
         // `columns` not used by backend yet.
         let plan: PlanRef = LogicalInsert::create(
             input,
             insert.table_source.name,
             insert.table_source.source_id,
+            on_conflict,
         )?
         .into();
         let order = Order::any().clone();
@@ -39,4 +41,14 @@ impl Planner {
         let root = PlanRoot::new(plan, dist, order, out_fields);
         Ok(root)
     }
+
+    fn plan_on_conflict(on_conflict: BoundOnConflict) -> InsertOnConflict {
+        InsertOnConflict {
+            pk_indices: on_conflict.pk_indices,
+            update_exprs: match on_conflict.action {
+                BoundOnConflictAction::DoNothing => None,
+                BoundOnConflictAction::DoUpdate(exprs) => Some(exprs),
+            },
+        }
+    }
 }
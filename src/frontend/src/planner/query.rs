@@ -16,7 +16,7 @@ use fixedbitset::FixedBitSet;
 use risingwave_common::error::Result;
 
 use crate::binder::BoundQuery;
-use crate::optimizer::plan_node::LogicalLimit;
+use crate::optimizer::plan_node::{LogicalLimit, LogicalSample, LogicalTopN};
 use crate::optimizer::property::{Distribution, Order};
 use crate::optimizer::PlanRoot;
 use crate::planner::Planner;
@@ -27,8 +27,21 @@ impl Planner {
     /// Plan a [`BoundQuery`]. Need to bind before planning.
     pub fn plan_query(&mut self, query: BoundQuery) -> Result<PlanRoot> {
         let mut plan = self.plan_set_expr(query.body)?;
-        // A logical limit is added if limit, offset or both are specified
-        if query.limit.is_some() || query.offset.is_some() {
+        if query.order_by_random {
+            // Randomly pick rows instead of sorting them by random keys.
+            plan = LogicalSample::create(plan, query.limit, query.offset.unwrap_or_default())
+        } else if !query.order.is_empty() && (query.limit.is_some() || query.offset.is_some()) {
+            // Sort and limit at once, as the limit applies to the sorted rows
+            plan = LogicalTopN::create(
+                plan,
+                query.limit.unwrap_or(LIMIT_ALL_COUNT),
+                query.offset.unwrap_or_default(),
+                Order {
+                    field_order: query.order.clone(),
+                },
+            )
+        } else if query.limit.is_some() || query.offset.is_some() {
+            // A logical limit is added if limit, offset or both are specified
             plan = LogicalLimit::create(
                 plan,
                 query.limit.unwrap_or(LIMIT_ALL_COUNT),
@@ -14,18 +14,23 @@
 
 use std::rc::Rc;
 
+use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_common::types::DataType;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_pb::plan::JoinType;
 
 use crate::binder::{
-    BoundBaseTable, BoundJoin, BoundSource, BoundWindowTableFunction, Relation,
-    WindowTableFunctionKind,
+    BoundBaseTable, BoundJoin, BoundPeekSource, BoundSource, BoundSystemTable, BoundTableFunction,
+    BoundWindowTableFunction, Relation, WindowTableFunctionKind,
 };
-use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef};
+use crate::expr::{Expr, ExprImpl, ExprType, FunctionCall, InputRef};
 use crate::optimizer::plan_node::{
-    LogicalJoin, LogicalProject, LogicalScan, LogicalSource, PlanRef,
+    LogicalApply, LogicalFilter, LogicalHopWindow, LogicalJoin, LogicalPeekSource, LogicalProject,
+    LogicalProjectSet, LogicalScan, LogicalSource, LogicalValues, PlanRef,
 };
+use crate::optimizer::unnest_applies;
 use crate::planner::Planner;
+use crate::utils::Condition;
 
 impl Planner {
     pub(super) fn plan_relation(&mut self, relation: Relation) -> Result<PlanRef> {
@@ -35,7 +40,10 @@ impl Planner {
             Relation::Subquery(q) => Ok(self.plan_query(q.query)?.as_subplan()),
             Relation::Join(join) => self.plan_join(*join),
             Relation::WindowTableFunction(tf) => self.plan_window_table_function(*tf),
+            Relation::TableFunction(tf) => self.plan_table_function(*tf),
             Relation::Source(s) => self.plan_source(*s),
+            Relation::PeekSource(p) => self.plan_peek_source(*p),
+            Relation::SystemTable(t) => self.plan_system_table(*t),
         }
     }
 
@@ -51,12 +59,123 @@ impl Planner {
         Ok(LogicalSource::new(Rc::new(source.catalog), self.ctx()).into())
     }
 
+    pub(super) fn plan_peek_source(&mut self, peek_source: BoundPeekSource) -> Result<PlanRef> {
+        Ok(LogicalPeekSource::create(
+            Rc::new(peek_source.catalog),
+            peek_source.limit,
+            self.ctx(),
+        ))
+    }
+
+    pub(super) fn plan_system_table(&mut self, system_table: BoundSystemTable) -> Result<PlanRef> {
+        Ok(LogicalValues::create(
+            system_table.rows,
+            Schema::new(system_table.fields),
+            self.ctx(),
+        ))
+    }
+
     pub(super) fn plan_join(&mut self, join: BoundJoin) -> Result<PlanRef> {
         let left = self.plan_relation(join.left)?;
-        let right = self.plan_relation(join.right)?;
         let join_type = join.join_type;
         let on_clause = join.cond;
-        Ok(LogicalJoin::create(left, right, join_type, on_clause))
+        match join.right {
+            Relation::TableFunction(tf) if tf.is_lateral() => {
+                self.plan_lateral_table_function(left, *tf, join_type, on_clause)
+            }
+            Relation::Subquery(q) if q.lateral && q.query.is_correlated() => {
+                if !matches!(join_type, JoinType::Inner | JoinType::LeftOuter) {
+                    return Err(ErrorCode::NotImplemented(
+                        format!("{:?} join with a correlated LATERAL subquery", join_type),
+                        None.into(),
+                    )
+                    .into());
+                }
+                let right = self.plan_query(q.query)?.as_subplan();
+                let apply =
+                    LogicalApply::create(left, right, join_type, Condition::with_expr(on_clause));
+                // The correlated columns can only be resolved by unnesting the apply.
+                if unnest_applies(apply.clone()).as_logical_apply().is_some() {
+                    return Err(ErrorCode::NotImplemented(
+                        "correlated LATERAL subquery that cannot be unnested".to_string(),
+                        None.into(),
+                    )
+                    .into());
+                }
+                Ok(apply)
+            }
+            right => {
+                let right = self.plan_relation(right)?;
+                Ok(LogicalJoin::create(left, right, join_type, on_clause))
+            }
+        }
+    }
+
+    /// Plans a table function in the `FROM` clause which doesn't refer to other relations, e.g.
+    /// `unnest(array[1, 2])`, as a [`LogicalProjectSet`] on a single empty row.
+    pub(super) fn plan_table_function(
+        &mut self,
+        table_function: BoundTableFunction,
+    ) -> Result<PlanRef> {
+        if table_function.is_lateral() {
+            return Err(ErrorCode::NotImplemented(
+                "table function referring to other relations outside of a join".to_string(),
+                None.into(),
+            )
+            .into());
+        }
+        let return_type = table_function.expr.return_type();
+        let values = LogicalValues::create(vec![vec![]], Schema::default(), self.ctx());
+        let project_set = LogicalProjectSet::create(values, vec![table_function.expr]);
+        // Remove `projected_row_id`.
+        Ok(LogicalProject::create(
+            project_set,
+            vec![InputRef::new(1, return_type).into()],
+            vec![None],
+        ))
+    }
+
+    /// Plans the join of `left` and a table function referring to the columns of `left`, e.g.
+    /// `t, unnest(t.arr)`, as a [`LogicalProjectSet`] on `left` which keeps all the columns of it.
+    fn plan_lateral_table_function(
+        &mut self,
+        left: PlanRef,
+        table_function: BoundTableFunction,
+        join_type: JoinType,
+        on_clause: ExprImpl,
+    ) -> Result<PlanRef> {
+        if join_type != JoinType::Inner {
+            return Err(ErrorCode::NotImplemented(
+                format!("{:?} join with a table function", join_type),
+                None.into(),
+            )
+            .into());
+        }
+        let left_fields = left.schema().fields().to_vec();
+        let return_type = table_function.expr.return_type();
+        let select_list = left_fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| InputRef::new(i, field.data_type()).into())
+            .chain(std::iter::once(table_function.expr))
+            .collect();
+        let project_set = LogicalProjectSet::create(left, select_list);
+        // Remove `projected_row_id`, so that the output is the columns of `left` followed by the
+        // result of the table function.
+        let exprs = left_fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| InputRef::new(i + 1, field.data_type()).into())
+            .chain(std::iter::once(
+                InputRef::new(left_fields.len() + 1, return_type).into(),
+            ))
+            .collect();
+        let expr_alias = vec![None; left_fields.len() + 1];
+        let project = LogicalProject::create(project_set, exprs, expr_alias);
+        Ok(LogicalFilter::create(
+            project,
+            Condition::with_expr(on_clause),
+        ))
     }
 
     pub(super) fn plan_window_table_function(
@@ -70,11 +189,11 @@ impl Planner {
                 table_function.time_col,
                 table_function.args,
             ),
-            Hop => Err(ErrorCode::NotImplemented(
-                "HOP window function is not implemented yet".to_string(),
-                1191.into(),
-            )
-            .into()),
+            Hop => self.plan_hop_window(
+                table_function.input,
+                table_function.time_col,
+                table_function.args,
+            ),
         }
     }
 
@@ -129,4 +248,37 @@ impl Planner {
             .into()),
         }
     }
+
+    fn plan_hop_window(
+        &mut self,
+        input: Relation,
+        time_col: InputRef,
+        args: Vec<ExprImpl>,
+    ) -> Result<PlanRef> {
+        let mut args = args.into_iter();
+
+        let interval = |expr: Option<ExprImpl>| match expr {
+            Some(ExprImpl::Literal(literal)) => match literal.get_data() {
+                Some(ScalarImpl::Interval(interval)) => Some(*interval),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match (interval(args.next()), interval(args.next()), args.next()) {
+            (Some(window_slide), Some(window_size), None) => {
+                let input = self.plan_relation(input)?;
+                Ok(LogicalHopWindow::create(
+                    input,
+                    time_col,
+                    window_slide,
+                    window_size,
+                ))
+            }
+            _ => Err(
+                ErrorCode::BindError("Invalid arguments for HOP window function".to_string())
+                    .into(),
+            ),
+        }
+    }
 }
@@ -233,7 +233,7 @@ impl Planner {
         join_type: JoinType,
     ) -> PlanRef {
         if is_correlated {
-            LogicalApply::create(left, right, join_type)
+            LogicalApply::create(left, right, join_type, Condition::true_cond())
         } else {
             LogicalJoin::create(left, right, join_type, ExprImpl::literal_bool(true))
         }
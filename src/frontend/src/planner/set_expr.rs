@@ -14,8 +14,8 @@
 
 use risingwave_common::error::Result;
 
-use crate::binder::BoundSetExpr;
-use crate::optimizer::plan_node::PlanRef;
+use crate::binder::{BoundSetExpr, BoundSetOperation};
+use crate::optimizer::plan_node::{LogicalExcept, LogicalIntersect, LogicalUnion, PlanRef};
 use crate::planner::Planner;
 
 impl Planner {
@@ -23,6 +23,15 @@ impl Planner {
         match set_expr {
             BoundSetExpr::Select(s) => self.plan_select(*s),
             BoundSetExpr::Values(v) => self.plan_values(*v),
+            BoundSetExpr::SetOperation { op, left, right } => {
+                let left = self.plan_set_expr(*left)?;
+                let right = self.plan_set_expr(*right)?;
+                match op {
+                    BoundSetOperation::Intersect => Ok(LogicalIntersect::create(left, right)),
+                    BoundSetOperation::Except => Ok(LogicalExcept::create(left, right)),
+                    BoundSetOperation::UnionAll => Ok(LogicalUnion::create(vec![left, right])),
+                }
+            }
         }
     }
 }
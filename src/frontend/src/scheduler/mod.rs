@@ -18,10 +18,12 @@ use crate::session::SessionImpl;
 
 #[allow(dead_code)]
 mod execution;
+pub mod plan_cache;
 #[allow(dead_code)]
 pub mod plan_fragmenter;
 mod query_manager;
 pub use query_manager::*;
+pub mod slow_query_log;
 pub mod worker_node_manager;
 
 /// Context for mpp query execution.
@@ -0,0 +1,190 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use pgwire::pg_field_descriptor::PgFieldDescriptor;
+use risingwave_common::catalog::CatalogVersion;
+use risingwave_common::config::PlanCacheConfig;
+
+use crate::scheduler::plan_fragmenter::Query;
+
+/// Identifies a cached plan. Only the simple query protocol is served, so a query has no
+/// parameters and its plan is determined by the SQL text and the catalog it's bound to.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct PlanCacheKey {
+    pub database: String,
+
+    /// The SQL formatted from the parsed statement, so that the whitespaces and the case of the
+    /// keywords don't matter.
+    pub sql: String,
+
+    pub catalog_version: CatalogVersion,
+
+    /// The parallelism of the stages depends on the number of workers.
+    pub worker_count: usize,
+}
+
+/// A batch query bound, optimized and fragmented.
+pub struct CachedPlan {
+    pub query: Query,
+    pub pg_descs: Vec<PgFieldDescriptor>,
+    pub plan_fingerprint: u64,
+}
+
+/// Hits and misses of the plan cache since the frontend started.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlanCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+impl PlanCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// Caches the plans of batch queries shared by all the sessions, so that the same queries, e.g.
+/// of dashboards, are not bound and optimized again. All the plans are dropped once the catalog
+/// changes.
+pub struct PlanCache {
+    /// `None` if the cache is disabled by the config.
+    plans: Option<Mutex<LruCache<PlanCacheKey, Arc<CachedPlan>>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+pub type PlanCacheRef = Arc<PlanCache>;
+
+impl PlanCache {
+    pub fn new(config: &PlanCacheConfig) -> Self {
+        Self {
+            plans: (config.capacity > 0).then(|| Mutex::new(LruCache::new(config.capacity))),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.plans.is_some()
+    }
+
+    pub fn get(&self, key: &PlanCacheKey) -> Option<Arc<CachedPlan>> {
+        let plan = self.plans.as_ref()?.lock().get(key).cloned();
+        match plan {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+        plan
+    }
+
+    pub fn insert(&self, key: PlanCacheKey, plan: CachedPlan) {
+        if let Some(plans) = &self.plans {
+            plans.lock().put(key, Arc::new(plan));
+        }
+    }
+
+    /// Drops all the cached plans. Called when the catalog is updated by meta.
+    pub fn invalidate(&self) {
+        if let Some(plans) = &self.plans {
+            plans.lock().clear();
+        }
+    }
+
+    pub fn stats(&self) -> PlanCacheStats {
+        PlanCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.plans.as_ref().map_or(0, |plans| plans.lock().len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::plan_fragmenter::BatchPlanFragmenter;
+    use crate::scheduler::worker_node_manager::WorkerNodeManager;
+    use crate::test_utils::LocalFrontend;
+
+    fn key(sql: &str, catalog_version: CatalogVersion) -> PlanCacheKey {
+        PlanCacheKey {
+            database: "dev".to_string(),
+            sql: sql.to_string(),
+            catalog_version,
+            worker_count: 0,
+        }
+    }
+
+    async fn plan(frontend: &LocalFrontend, sql: &str) -> CachedPlan {
+        let plan = frontend.to_batch_plan(sql).await.unwrap();
+        let fragmenter = BatchPlanFragmenter::new(Arc::new(WorkerNodeManager::mock(vec![])));
+        CachedPlan {
+            query: fragmenter.split(plan).unwrap(),
+            pg_descs: vec![],
+            plan_fingerprint: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_cache() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let cache = PlanCache::new(&PlanCacheConfig { capacity: 1 });
+
+        assert!(cache.get(&key("SELECT 1", 1)).is_none());
+        cache.insert(key("SELECT 1", 1), plan(&frontend, "select 1").await);
+        let cached = cache.get(&key("SELECT 1", 1)).unwrap();
+        // The plan is scheduled with a new query id every time.
+        assert_ne!(
+            cached.query.clone_with_new_id().query_id,
+            cached.query.query_id
+        );
+        // Plans bound to an outdated catalog are never hit.
+        assert!(cache.get(&key("SELECT 1", 2)).is_none());
+
+        // The least recently used plan is evicted.
+        cache.insert(key("SELECT 2", 1), plan(&frontend, "select 2").await);
+        assert!(cache.get(&key("SELECT 1", 1)).is_none());
+        assert!(cache.get(&key("SELECT 2", 1)).is_some());
+
+        cache.invalidate();
+        assert!(cache.get(&key("SELECT 2", 1)).is_none());
+        let stats = cache.stats();
+        assert_eq!(
+            stats,
+            PlanCacheStats {
+                hits: 2,
+                misses: 4,
+                entries: 0,
+            }
+        );
+        assert!((stats.hit_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+
+        let cache = PlanCache::new(&PlanCacheConfig { capacity: 0 });
+        assert!(!cache.is_enabled());
+        cache.insert(key("SELECT 1", 1), plan(&frontend, "select 1").await);
+        assert!(cache.get(&key("SELECT 1", 1)).is_none());
+        assert_eq!(cache.stats(), PlanCacheStats::default());
+    }
+}
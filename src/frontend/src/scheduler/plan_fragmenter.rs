@@ -126,6 +126,25 @@ impl Query {
     pub fn root_stage_id(&self) -> StageId {
         self.stage_graph.root_stage_id
     }
+
+    /// Copies the query with a new query id, so that a cached query can be scheduled again.
+    pub fn clone_with_new_id(&self) -> Self {
+        let query_id = QueryId::default();
+        let mut stage_graph = self.stage_graph.clone();
+        for stage in stage_graph.stages.values_mut() {
+            *stage = Arc::new(QueryStage {
+                query_id: query_id.clone(),
+                id: stage.id,
+                root: stage.root.clone(),
+                exchange_info: stage.exchange_info.clone(),
+                parallelism: stage.parallelism,
+            });
+        }
+        Self {
+            query_id,
+            stage_graph,
+        }
+    }
 }
 
 /// Fragment part of `Query`.
@@ -200,7 +219,7 @@ impl QueryStageBuilder {
 }
 
 /// Maintains how each stage are connected.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(crate) struct StageGraph {
     pub(crate) root_stage_id: StageId,
     pub stages: HashMap<StageId, QueryStageRef>,
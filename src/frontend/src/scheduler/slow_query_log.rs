@@ -0,0 +1,150 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use risingwave_common::config::SlowLogConfig;
+use risingwave_common::util::slow_log::{SlowLogBuffer, SLOW_LOG_TARGET};
+
+/// A batch query which took longer than the threshold, either succeeded or failed.
+#[derive(Clone, Debug)]
+pub struct SlowQueryRecord {
+    pub sql: String,
+
+    /// Fingerprint of the distributed plan, see [`plan_fingerprint`].
+    pub plan_fingerprint: u64,
+
+    /// Number of rows returned or affected.
+    pub row_count: usize,
+
+    pub duration: Duration,
+
+    /// Time spent in each stage of the query, in execution order.
+    pub stage_timings: Vec<(&'static str, Duration)>,
+
+    /// The error if the query failed during execution.
+    pub error: Option<String>,
+}
+
+/// Logs slow batch queries and keeps the latest ones in memory.
+pub struct SlowQueryLog {
+    threshold: Option<Duration>,
+    buffer: SlowLogBuffer<SlowQueryRecord>,
+}
+
+pub type SlowQueryLogRef = Arc<SlowQueryLog>;
+
+impl SlowQueryLog {
+    pub fn new(config: &SlowLogConfig) -> Self {
+        let threshold = (config.slow_query_threshold_ms > 0)
+            .then(|| Duration::from_millis(config.slow_query_threshold_ms));
+        Self {
+            threshold,
+            buffer: SlowLogBuffer::new(config.capacity),
+        }
+    }
+
+    /// Records the query if it took longer than the threshold.
+    pub fn observe(&self, record: SlowQueryRecord) {
+        match self.threshold {
+            Some(threshold) if record.duration >= threshold => {}
+            _ => return,
+        }
+
+        tracing::warn!(
+            target: SLOW_LOG_TARGET,
+            plan_fingerprint = %format!("{:016x}", record.plan_fingerprint),
+            row_count = record.row_count,
+            duration_ms = record.duration.as_millis() as u64,
+            stage_timings = ?record.stage_timings,
+            error = ?record.error,
+            "slow query: {}",
+            record.sql
+        );
+        self.buffer.push(record);
+    }
+
+    /// Returns the latest slow queries, from the oldest to the latest.
+    pub fn records(&self) -> Vec<SlowQueryRecord> {
+        self.buffer.records()
+    }
+}
+
+/// Computes the fingerprint of a plan from its explain string, so that executions of the same
+/// plan can be grouped together.
+pub fn plan_fingerprint(plan_explain: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    plan_explain.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(duration_ms: u64) -> SlowQueryRecord {
+        SlowQueryRecord {
+            sql: "SELECT 1".to_string(),
+            plan_fingerprint: plan_fingerprint("BatchProject"),
+            row_count: 1,
+            duration: Duration::from_millis(duration_ms),
+            stage_timings: vec![],
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_slow_query_log() {
+        let log = SlowQueryLog::new(&SlowLogConfig {
+            slow_query_threshold_ms: 100,
+            capacity: 2,
+        });
+        log.observe(record(10));
+        log.observe(record(100));
+        log.observe(record(200));
+        log.observe(record(300));
+        let durations = log
+            .records()
+            .iter()
+            .map(|r| r.duration.as_millis())
+            .collect::<Vec<_>>();
+        assert_eq!(durations, vec![200, 300]);
+
+        // Failed queries are recorded as well.
+        log.observe(SlowQueryRecord {
+            error: Some("timeout".to_string()),
+            ..record(400)
+        });
+        assert_eq!(
+            log.records().last().unwrap().error.as_deref(),
+            Some("timeout")
+        );
+
+        let log = SlowQueryLog::new(&SlowLogConfig {
+            slow_query_threshold_ms: 0,
+            capacity: 2,
+        });
+        log.observe(record(300));
+        assert!(log.records().is_empty());
+    }
+
+    #[test]
+    fn test_plan_fingerprint() {
+        assert_eq!(plan_fingerprint("a"), plan_fingerprint("a"));
+        assert_ne!(plan_fingerprint("a"), plan_fingerprint("b"));
+    }
+}
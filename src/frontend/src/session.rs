@@ -22,10 +22,11 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use parking_lot::RwLock;
+use pgwire::error::PgError;
 use pgwire::pg_response::PgResponse;
 use pgwire::pg_server::{Session, SessionManager};
-use risingwave_common::config::FrontendConfig;
-use risingwave_common::error::Result;
+use risingwave_common::config::{FrontendConfig, PlanCacheConfig, SlowLogConfig};
+use risingwave_common::error::{Result, RwError};
 use risingwave_common::util::addr::HostAddr;
 use risingwave_pb::common::WorkerType;
 use risingwave_rpc_client::MetaClient;
@@ -37,10 +38,12 @@ use tokio::task::JoinHandle;
 use crate::catalog::catalog_service::{CatalogReader, CatalogWriter, CatalogWriterImpl};
 use crate::catalog::root_catalog::Catalog;
 use crate::handler::handle;
-use crate::handler::query::IMPLICIT_FLUSH;
+use crate::handler::query::{ENABLE_PLAN_CACHE, IMPLICIT_FLUSH};
 use crate::meta_client::{FrontendMetaClient, FrontendMetaClientImpl};
 use crate::observer::observer_manager::ObserverManager;
 use crate::optimizer::plan_node::PlanNodeId;
+use crate::scheduler::plan_cache::{PlanCache, PlanCacheRef};
+use crate::scheduler::slow_query_log::{SlowQueryLog, SlowQueryLogRef};
 use crate::scheduler::worker_node_manager::{WorkerNodeManager, WorkerNodeManagerRef};
 use crate::scheduler::QueryManager;
 use crate::FrontendOpts;
@@ -49,6 +52,8 @@ pub struct OptimizerContext {
     pub session_ctx: Arc<SessionImpl>,
     // We use `AtomicI32` here because  `Arc<T>` implements `Send` only when `T: Send + Sync`.
     pub next_id: AtomicI32,
+    /// Options in the `WITH` clause of the statement being planned.
+    pub with_options: HashMap<String, String>,
 }
 
 #[derive(Clone, Debug)]
@@ -84,6 +89,7 @@ impl OptimizerContext {
         Self {
             session_ctx,
             next_id: AtomicI32::new(0),
+            with_options: HashMap::new(),
         }
     }
 
@@ -93,6 +99,7 @@ impl OptimizerContext {
         Self {
             session_ctx: Arc::new(SessionImpl::mock()),
             next_id: AtomicI32::new(0),
+            with_options: HashMap::new(),
         }
         .into()
     }
@@ -127,6 +134,8 @@ pub struct FrontendEnv {
     catalog_reader: CatalogReader,
     worker_node_manager: Arc<WorkerNodeManager>,
     query_manager: QueryManager,
+    slow_query_log: SlowQueryLogRef,
+    plan_cache: PlanCacheRef,
 }
 
 impl FrontendEnv {
@@ -151,6 +160,8 @@ impl FrontendEnv {
             worker_node_manager,
             meta_client: Arc::new(MockFrontendMetaClient {}),
             query_manager,
+            slow_query_log: Arc::new(SlowQueryLog::new(&SlowLogConfig::default())),
+            plan_cache: Arc::new(PlanCache::new(&PlanCacheConfig::default())),
         }
     }
 
@@ -187,6 +198,8 @@ impl FrontendEnv {
 
         let worker_node_manager = Arc::new(WorkerNodeManager::new(meta_client.clone()).await?);
         let query_manager = QueryManager::new(worker_node_manager.clone());
+        let slow_query_log = Arc::new(SlowQueryLog::new(&config.slow_log));
+        let plan_cache = Arc::new(PlanCache::new(&config.plan_cache));
 
         let observer_manager = ObserverManager::new(
             meta_client.clone(),
@@ -194,6 +207,7 @@ impl FrontendEnv {
             worker_node_manager.clone(),
             catalog,
             catalog_updated_tx,
+            plan_cache.clone(),
         )
         .await;
         let observer_join_handle = observer_manager.start().await?;
@@ -207,6 +221,8 @@ impl FrontendEnv {
                 worker_node_manager,
                 meta_client: Arc::new(FrontendMetaClientImpl(meta_client)),
                 query_manager,
+                slow_query_log,
+                plan_cache,
             },
             observer_join_handle,
             heartbeat_join_handle,
@@ -243,6 +259,14 @@ impl FrontendEnv {
     pub fn query_manager(&self) -> &QueryManager {
         &self.query_manager
     }
+
+    pub fn slow_query_log(&self) -> &SlowQueryLog {
+        &self.slow_query_log
+    }
+
+    pub fn plan_cache(&self) -> &PlanCache {
+        &self.plan_cache
+    }
 }
 
 pub struct SessionImpl {
@@ -315,6 +339,10 @@ impl SessionImpl {
             IMPLICIT_FLUSH.to_string(),
             ConfigEntry::new("false".to_string()),
         );
+        map.insert(
+            ENABLE_PLAN_CACHE.to_string(),
+            ConfigEntry::new("true".to_string()),
+        );
         RwLock::new(map)
     }
 }
@@ -364,7 +392,8 @@ impl Session for SessionImpl {
         sql: &str,
     ) -> std::result::Result<PgResponse, Box<dyn std::error::Error + Send + Sync>> {
         // Parse sql.
-        let mut stmts = Parser::parse_sql(sql)?;
+        let mut stmts =
+            Parser::parse_sql(sql).map_err(|e| PgError::new(SQLSTATE_SYNTAX_ERROR, Box::new(e)))?;
         // With pgwire, there would be at most 1 statement in the vec.
         assert!(stmts.len() <= 1);
         if stmts.is_empty() {
@@ -376,11 +405,26 @@ impl Session for SessionImpl {
             ));
         }
         let stmt = stmts.swap_remove(0);
-        let rsp = handle(self, stmt).await?;
+        let rsp = handle(self, stmt).await.map_err(to_pg_error)?;
         Ok(rsp)
     }
 }
 
+/// The SQLSTATE of statements failed to parse, i.e. `syntax_error`.
+const SQLSTATE_SYNTAX_ERROR: &str = "42601";
+
+/// Reports the SQLSTATE of the error to the client, along with where it happened and whether it's
+/// worth retrying as the detail.
+fn to_pg_error(error: RwError) -> PgError {
+    let sqlstate = error.inner().sqlstate().to_string();
+    let detail = format!(
+        "component: {:?}, retryable: {}",
+        error.inner().component(),
+        error.inner().is_retryable()
+    );
+    PgError::new(sqlstate, Box::new(error)).with_detail(detail)
+}
+
 // TODO: with a good MockMeta and then we can open the tests.
 // #[cfg(test)]
 // mod tests {
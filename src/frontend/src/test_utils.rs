@@ -27,6 +27,9 @@ use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::{
     Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
 };
+use risingwave_pb::meta::{
+    Job, SlowBarrier, TableFragments as ProstTableFragments, TableWriteStats,
+};
 use risingwave_pb::stream_plan::StreamNode;
 use risingwave_sqlparser::ast::Statement;
 use risingwave_sqlparser::parser::Parser;
@@ -249,6 +252,26 @@ impl FrontendMetaClient for MockFrontendMetaClient {
     async fn unpin_snapshot(&self, _epoch: u64) -> Result<()> {
         Ok(())
     }
+
+    async fn list_table_fragments(&self) -> Result<Vec<ProstTableFragments>> {
+        Ok(vec![])
+    }
+
+    async fn list_jobs(&self) -> Result<Vec<Job>> {
+        Ok(vec![])
+    }
+
+    async fn cancel_job(&self, _table_id: u32) -> Result<()> {
+        Ok(())
+    }
+
+    async fn list_table_write_stats(&self) -> Result<Vec<TableWriteStats>> {
+        Ok(vec![])
+    }
+
+    async fn list_slow_barriers(&self) -> Result<Vec<SlowBarrier>> {
+        Ok(vec![])
+    }
 }
 pub static PROTO_FILE_DATA: &str = r#"
     syntax = "proto3";
@@ -233,8 +233,21 @@ impl TestCase {
                     }
                     result = Some(ret);
                 }
-                Statement::CreateTable { name, columns, .. } => {
-                    create_table::handle_create_table(context, name, columns).await?;
+                Statement::CreateTable {
+                    name,
+                    columns,
+                    constraints,
+                    with_options,
+                    ..
+                } => {
+                    create_table::handle_create_table(
+                        context,
+                        name,
+                        columns,
+                        constraints,
+                        with_options,
+                    )
+                    .await?;
                 }
                 Statement::CreateSource {
                     is_materialized,
@@ -247,9 +260,10 @@ impl TestCase {
                     or_replace: false,
                     name,
                     query,
+                    with_options,
                     ..
                 } => {
-                    create_mv::handle_create_mv(context, name, query).await?;
+                    create_mv::handle_create_mv(context, name, query, with_options).await?;
                 }
                 Statement::Drop(drop_statement) => {
                     let table_object_name = ObjectName(vec![drop_statement.name]);
@@ -320,19 +334,29 @@ impl TestCase {
             }
         }
 
-        if self.stream_plan.is_some() || self.stream_plan_proto.is_some() {
+        // The optimizer errors are only raised when generating stream plans for now.
+        if self.stream_plan.is_some()
+            || self.stream_plan_proto.is_some()
+            || self.optimizer_error.is_some()
+        {
             let q = if let Statement::Query(q) = stmt {
                 q.as_ref().clone()
             } else {
                 return Err(anyhow!("expect a query"));
             };
 
-            let (stream_plan, table) = create_mv::gen_create_mv_plan(
+            let (stream_plan, table) = match create_mv::gen_create_mv_plan(
                 &session,
                 context,
                 Box::new(q),
                 ObjectName(vec!["test".into()]),
-            )?;
+            ) {
+                Ok(plan) => plan,
+                Err(err) => {
+                    ret.optimizer_error = Some(err.to_string());
+                    return Ok(ret);
+                }
+            };
 
             // Only generate stream_plan if it is specified in test case
             if self.stream_plan.is_some() {
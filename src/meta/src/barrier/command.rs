@@ -40,6 +40,14 @@ pub enum Command {
     /// After the barrier is collected, it does nothing.
     Plain(Mutation),
 
+    /// `ForceCheckpoint` command generates a barrier without any mutation, flagged as a checkpoint
+    /// forced by `TriggerCheckpoint`. It's injected as soon as possible instead of waiting for the
+    /// barrier interval.
+    ///
+    /// Barriers from all actors marked as `Created` state will be collected.
+    /// After the barrier is collected, it does nothing.
+    ForceCheckpoint,
+
     /// `DropMaterializedView` command generates a `Stop` barrier by the given [`TableId`]. The
     /// catalog has ensured that this materialized view is safe to be dropped by reference counts
     /// before.
@@ -91,7 +99,7 @@ pub struct CommandContext<'a, S> {
     pub prev_epoch: u64,
     pub curr_epoch: u64,
 
-    command: Command,
+    pub command: Command,
 }
 
 impl<'a, S> CommandContext<'a, S> {
@@ -123,6 +131,8 @@ where
         let mutation = match &self.command {
             Command::Plain(mutation) => mutation.clone(),
 
+            Command::ForceCheckpoint => Mutation::Nothing(NothingMutation {}),
+
             Command::DropMaterializedView(table_id) => {
                 let actors = self.fragment_manager.get_table_actor_ids(table_id).await?;
                 Mutation::Stop(StopMutation { actors })
@@ -163,7 +173,7 @@ where
     /// Do some stuffs after barriers are collected, for the given command.
     pub async fn post_collect(&self) -> Result<()> {
         match &self.command {
-            Command::Plain(_) => {}
+            Command::Plain(_) | Command::ForceCheckpoint => {}
 
             Command::DropMaterializedView(table_id) => {
                 // Tell compute nodes to drop actors.
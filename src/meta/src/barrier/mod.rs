@@ -15,28 +15,34 @@
 use std::collections::VecDeque;
 use std::iter::once;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use futures::future::try_join_all;
 use itertools::Itertools;
+use parking_lot::Mutex;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::{ErrorCode, Result, RwError, ToRwResult};
+use risingwave_common::util::slow_log::{SlowLogBuffer, SlowLogBufferRef};
 use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_pb::common::worker_node::State::Running;
 use risingwave_pb::common::WorkerType;
 use risingwave_pb::data::Barrier;
+use risingwave_pb::meta::{Job, SlowBarrier, TableWriteStats};
 use risingwave_pb::stream_service::{InjectBarrierRequest, InjectBarrierResponse};
 use smallvec::SmallVec;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::sync::{oneshot, watch, RwLock};
 use tokio::task::JoinHandle;
+use tracing::Instrument;
 use uuid::Uuid;
 
 pub use self::command::Command;
 use self::command::CommandContext;
 use self::info::BarrierActorInfo;
 use self::notifier::{Notifier, UnfinishedNotifiers};
-use crate::cluster::{ClusterManagerRef, META_NODE_ID};
+use self::slow_log::{SlowBarrierRecord, SLOWEST_TO_REPORT, SLOW_BARRIER_LOG_CAPACITY};
+use self::write_stats::TableWriteStatsTracker;
+use crate::cluster::{ClusterManagerRef, WorkerId, META_NODE_ID};
 use crate::hummock::HummockManagerRef;
 use crate::manager::{CatalogManagerRef, MetaSrvEnv, INVALID_EPOCH};
 use crate::model::BarrierManagerState;
@@ -48,6 +54,8 @@ mod command;
 mod info;
 mod notifier;
 mod recovery;
+pub mod slow_log;
+mod write_stats;
 
 type Scheduled = (Command, SmallVec<[Notifier; 1]>);
 
@@ -157,6 +165,19 @@ pub struct GlobalBarrierManager<S: MetaStore> {
     metrics: Arc<MetaMetrics>,
 
     env: MetaSrvEnv<S>,
+
+    /// Barriers taking longer than this threshold are written to the slow log.
+    slow_barrier_threshold: Option<Duration>,
+
+    /// The latest slow barriers.
+    slow_barrier_log: SlowLogBufferRef<SlowBarrierRecord>,
+
+    /// The commands waiting for actors to report finishing, i.e. the materialized views being
+    /// created.
+    unfinished: Mutex<UnfinishedNotifiers>,
+
+    /// Bytes written to the state store by each materialized view.
+    table_write_stats: Mutex<TableWriteStatsTracker>,
 }
 
 impl<S> GlobalBarrierManager<S>
@@ -172,10 +193,10 @@ where
         hummock_manager: HummockManagerRef<S>,
         metrics: Arc<MetaMetrics>,
     ) -> Self {
-        // TODO: make interval configurable.
         // TODO: when tracing is on, warn the developer on this short interval.
-        let interval = Duration::from_millis(100);
+        let interval = env.opts.barrier_interval;
         let enable_recovery = env.opts.enable_recovery;
+        let slow_barrier_threshold = env.opts.slow_barrier_threshold;
 
         Self {
             interval,
@@ -187,9 +208,38 @@ where
             hummock_manager,
             metrics,
             env,
+            slow_barrier_threshold,
+            slow_barrier_log: Arc::new(SlowLogBuffer::new(SLOW_BARRIER_LOG_CAPACITY)),
+            unfinished: Mutex::new(UnfinishedNotifiers::default()),
+            table_write_stats: Mutex::new(TableWriteStatsTracker::default()),
         }
     }
 
+    /// Returns the progress of the materialized views being created.
+    pub fn list_jobs(&self) -> Vec<Job> {
+        self.unfinished.lock().jobs()
+    }
+
+    /// Whether the materialized view `table_id` is being created.
+    pub fn is_creating(&self, table_id: TableId) -> bool {
+        self.unfinished.lock().contains_table(table_id)
+    }
+
+    /// Returns the bytes written to the state store by each materialized view.
+    pub fn list_table_write_stats(&self) -> Vec<TableWriteStats> {
+        self.table_write_stats.lock().list()
+    }
+
+    /// Returns the latest barriers which took longer than the slow barrier threshold, from the
+    /// oldest to the latest.
+    pub fn list_slow_barriers(&self) -> Vec<SlowBarrier> {
+        self.slow_barrier_log
+            .records()
+            .iter()
+            .map(SlowBarrierRecord::to_protobuf)
+            .collect()
+    }
+
     pub async fn start(
         barrier_manager: BarrierManagerRef<S>,
     ) -> (JoinHandle<()>, UnboundedSender<()>) {
@@ -205,7 +255,6 @@ where
     async fn run(&self, mut shutdown_rx: UnboundedReceiver<()>) {
         let mut min_interval = tokio::time::interval(self.interval);
         min_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-        let mut unfinished = UnfinishedNotifiers::default();
         let mut state = BarrierManagerState::create(self.env.meta_store()).await;
 
         if self.enable_recovery {
@@ -217,9 +266,12 @@ where
 
             let (new_epoch, actors_to_finish, finished_create_mviews) =
                 self.recovery(state.prev_epoch, None).await;
-            unfinished.add(new_epoch.into_inner(), actors_to_finish, vec![]);
-            for finished in finished_create_mviews {
-                unfinished.finish_actors(finished.epoch, once(finished.actor_id));
+            {
+                let mut unfinished = self.unfinished.lock();
+                unfinished.add(new_epoch.into_inner(), None, actors_to_finish, vec![]);
+                for finished in finished_create_mviews {
+                    unfinished.finish_actors(finished.epoch, once(finished.actor_id));
+                }
             }
             state.prev_epoch = new_epoch.into_inner();
             state.update(self.env.meta_store()).await.unwrap();
@@ -259,12 +311,32 @@ where
                     // Notify about collected first.
                     notifiers.iter_mut().for_each(Notifier::notify_collected);
 
+                    self.record_write_stats(state.prev_epoch, &command, &responses)
+                        .await;
+
                     // Then try to finish the barrier for Create MVs.
                     let actors_to_finish = command_ctx.actors_to_finish();
-                    unfinished.add(new_epoch, actors_to_finish, notifiers);
+                    let mut unfinished = self.unfinished.lock();
+                    unfinished.add(
+                        new_epoch,
+                        command.creating_table_id(),
+                        actors_to_finish,
+                        notifiers,
+                    );
                     for finished in responses.into_iter().flat_map(|r| r.finished_create_mviews) {
                         unfinished.finish_actors(finished.epoch, once(finished.actor_id));
                     }
+                    // Dropping a materialized view being created cancels the creation.
+                    if let Command::DropMaterializedView(table_id) = &command {
+                        unfinished.cancel_table(
+                            *table_id,
+                            ErrorCode::InternalError(format!(
+                                "creation of materialized view {} is canceled",
+                                table_id.table_id
+                            ))
+                            .into(),
+                        );
+                    }
 
                     state.prev_epoch = new_epoch;
                 }
@@ -276,8 +348,9 @@ where
                         // If failed, enter recovery mode.
                         let (new_epoch, actors_to_finish, finished_create_mviews) =
                             self.recovery(state.prev_epoch, Some(command)).await;
-                        unfinished = UnfinishedNotifiers::default();
-                        unfinished.add(new_epoch.into_inner(), actors_to_finish, vec![]);
+                        let mut unfinished = self.unfinished.lock();
+                        *unfinished = UnfinishedNotifiers::default();
+                        unfinished.add(new_epoch.into_inner(), None, actors_to_finish, vec![]);
                         for finished in finished_create_mviews {
                             unfinished.finish_actors(finished.epoch, once(finished.actor_id));
                         }
@@ -293,15 +366,37 @@ where
         }
     }
 
+    /// Records the bytes written in `epoch` by each materialized view, reported by the compute
+    /// nodes on collecting the barrier.
+    async fn record_write_stats(
+        &self,
+        epoch: u64,
+        command: &Command,
+        responses: &[InjectBarrierResponse],
+    ) {
+        // Resolving the actors needs to scan all fragments, so it's skipped if possible.
+        let actor_tables = if responses.iter().any(|r| !r.actor_write_bytes.is_empty()) {
+            self.fragment_manager.actor_table_ids().await
+        } else {
+            Default::default()
+        };
+        let mut table_write_stats = self.table_write_stats.lock();
+        table_write_stats.record(epoch, responses, &actor_tables);
+        if let Command::DropMaterializedView(table_id) = command {
+            table_write_stats.remove(*table_id);
+        }
+    }
+
     /// Running a scheduled command.
     async fn run_inner<'a>(
         &self,
         command_context: &CommandContext<'a, S>,
     ) -> Result<Vec<InjectBarrierResponse>> {
         let timer = self.metrics.barrier_latency.start_timer();
+        let start = Instant::now();
 
         // Wait for all barriers collected
-        let result = self.inject_barrier(command_context).await;
+        let result = self.inject_barrier_with_timing(command_context).await;
         // Commit this epoch to Hummock
         if command_context.prev_epoch != INVALID_EPOCH {
             match result {
@@ -320,12 +415,40 @@ where
                 }
             };
         }
-        let responses = result?;
+        let node_timings = result?;
 
         timer.observe_duration();
+        self.observe_slow_barrier(command_context, start.elapsed(), &node_timings);
         command_context.post_collect().await?; // do some post stuffs
 
-        Ok(responses)
+        Ok(node_timings
+            .into_iter()
+            .map(|(_, _, response)| response)
+            .collect())
+    }
+
+    /// Records the barrier to the slow log if it took longer than the threshold.
+    fn observe_slow_barrier<'a>(
+        &self,
+        command_context: &CommandContext<'a, S>,
+        duration: Duration,
+        node_timings: &[(WorkerId, Duration, InjectBarrierResponse)],
+    ) {
+        match self.slow_barrier_threshold {
+            Some(threshold) if duration >= threshold => {}
+            _ => return,
+        }
+
+        let record = SlowBarrierRecord::new(
+            command_context.prev_epoch,
+            command_context.curr_epoch,
+            format!("{:?}", command_context.command),
+            duration,
+            node_timings,
+            SLOWEST_TO_REPORT,
+        );
+        record.log();
+        self.slow_barrier_log.push(record);
     }
 
     /// Inject barrier to all computer nodes.
@@ -333,8 +456,26 @@ where
         &self,
         command_context: &CommandContext<'a, S>,
     ) -> Result<Vec<InjectBarrierResponse>> {
+        Ok(self
+            .inject_barrier_with_timing(command_context)
+            .await?
+            .into_iter()
+            .map(|(_, _, response)| response)
+            .collect())
+    }
+
+    /// Inject barrier to all computer nodes, returning the time each node took to collect the
+    /// barrier along with its response.
+    async fn inject_barrier_with_timing<'a>(
+        &self,
+        command_context: &CommandContext<'a, S>,
+    ) -> Result<Vec<(WorkerId, Duration, InjectBarrierResponse)>> {
         let mutation = command_context.to_mutation().await?;
         let info = command_context.info;
+        // The root span of the barrier, covering its injection and collection. The barrier spans
+        // of all actors are attached to it with the encoded trace context.
+        let span = tracing::trace_span!("barrier", epoch = command_context.curr_epoch);
+        let trace_context = risingwave_logging::encode_trace_context(&span);
 
         let collect_futures = info.node_map.iter().filter_map(|(node_id, node)| {
             let actor_ids_to_send = info.actor_ids_to_send(node_id).collect_vec();
@@ -353,11 +494,13 @@ where
                         prev: command_context.prev_epoch,
                     }),
                     mutation: Some(mutation),
-                    // TODO(chi): add distributed tracing
-                    span: vec![],
+                    span: trace_context.clone(),
+                    checkpoint: matches!(command_context.command, Command::ForceCheckpoint),
                 };
 
+                let node_id = *node_id;
                 async move {
+                    let start = Instant::now();
                     let mut client = self.env.stream_clients().get(node).await?;
 
                     let request = InjectBarrierRequest {
@@ -375,14 +518,14 @@ where
                     client
                         .inject_barrier(request)
                         .await
-                        .map(tonic::Response::<_>::into_inner)
+                        .map(|resp| (node_id, start.elapsed(), resp.into_inner()))
                         .to_rw_result()
                 }
                 .into()
             }
         });
 
-        try_join_all(collect_futures).await
+        try_join_all(collect_futures).instrument(span).await
     }
 
     /// Resolve actor information from cluster and fragment manager.
@@ -460,15 +603,29 @@ where
                 .hummock_manager
                 .pin_snapshot(META_NODE_ID, HummockEpoch::MAX)
                 .await?;
-            finish_rx.await.unwrap(); // Wait for this command to be finished.
+            let result = finish_rx.await.unwrap(); // Wait for this command to be finished.
             self.hummock_manager
                 .unpin_snapshot(META_NODE_ID, [snapshot])
                 .await?;
+            result
         } else {
-            finish_rx.await.unwrap(); // Wait for this command to be finished.
+            finish_rx.await.unwrap() // Wait for this command to be finished.
         }
+    }
 
-        Ok(())
+    /// Inject a checkpoint barrier as soon as possible, and return when it's collected, i.e. the
+    /// epoch before it is committed.
+    pub async fn trigger_checkpoint(&self) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.do_schedule(
+            Command::ForceCheckpoint,
+            Notifier {
+                collected: Some(tx),
+                ..Default::default()
+            },
+        )
+        .await?;
+        rx.await.unwrap()
     }
 
     /// Wait for the next barrier to collect. Note that the barrier flowing in our stream graph is
@@ -14,7 +14,9 @@
 
 use std::collections::{HashMap, HashSet};
 
+use risingwave_common::catalog::TableId;
 use risingwave_common::error::{Result, RwError};
+use risingwave_pb::meta::Job;
 use tokio::sync::oneshot;
 
 use crate::model::ActorId;
@@ -28,8 +30,8 @@ pub(super) struct Notifier {
     /// Get notified when scheduled barrier is collected or failed.
     pub collected: Option<oneshot::Sender<Result<()>>>,
 
-    /// Get notified when scheduled barrier is finished.
-    pub finished: Option<oneshot::Sender<()>>,
+    /// Get notified when scheduled barrier is finished or canceled.
+    pub finished: Option<oneshot::Sender<Result<()>>>,
 }
 
 impl Notifier {
@@ -61,15 +63,32 @@ impl Notifier {
     /// However for creating MV, this is only called when all `Chain` report it finished.
     pub fn notify_finished(self) {
         if let Some(tx) = self.finished {
-            tx.send(()).ok();
+            tx.send(Ok(())).ok();
+        }
+    }
+
+    /// Notify when the command will never finish, e.g. the MV being created is dropped. This
+    /// function consumes `self`.
+    pub fn notify_finish_failed(self, err: RwError) {
+        if let Some(tx) = self.finished {
+            tx.send(Err(err)).ok();
         }
     }
 }
 
+/// A command waiting for actors to report finishing.
+struct UnfinishedCommand {
+    /// The table being created, or `None` if it's unknown after recovery.
+    table_id: Option<TableId>,
+    total_actors: usize,
+    actors: HashSet<ActorId>,
+    notifiers: Vec<Notifier>,
+}
+
 /// Stores the notifiers for commands that are not finished yet. Essentially for
 /// `CreateMaterializedView`.
 #[derive(Default)]
-pub(super) struct UnfinishedNotifiers(HashMap<u64, (HashSet<ActorId>, Vec<Notifier>)>);
+pub(super) struct UnfinishedNotifiers(HashMap<u64, UnfinishedCommand>);
 
 impl UnfinishedNotifiers {
     /// Add a command with current `epoch` and `notifiers`, that needs to wait for actors with
@@ -78,6 +97,7 @@ impl UnfinishedNotifiers {
     pub fn add(
         &mut self,
         epoch: u64,
+        table_id: Option<TableId>,
         actor_ids: impl IntoIterator<Item = ActorId>,
         notifiers: impl IntoIterator<Item = Notifier>,
     ) {
@@ -93,8 +113,13 @@ impl UnfinishedNotifiers {
                 actor_ids
             );
 
-            let notifiers = notifiers.into_iter().collect();
-            let old = self.0.insert(epoch, (actor_ids, notifiers));
+            let command = UnfinishedCommand {
+                table_id,
+                total_actors: actor_ids.len(),
+                actors: actor_ids,
+                notifiers: notifiers.into_iter().collect(),
+            };
+            let old = self.0.insert(epoch, command);
             assert!(old.is_none());
         }
     }
@@ -108,14 +133,14 @@ impl UnfinishedNotifiers {
             Entry::Occupied(mut o) => {
                 actors.into_iter().for_each(|a| {
                     tracing::debug!("finish actor {} for DDL with epoch {}", a, epoch);
-                    o.get_mut().0.remove(&a);
+                    o.get_mut().actors.remove(&a);
                 });
 
                 // All actors finished.
-                if o.get().0.is_empty() {
+                if o.get().actors.is_empty() {
                     tracing::debug!("finish all actors for DDL with epoch {}!", epoch);
 
-                    let notifiers = o.remove().1;
+                    let notifiers = o.remove().notifiers;
                     notifiers.into_iter().for_each(Notifier::notify_finished);
                 }
             }
@@ -123,4 +148,38 @@ impl UnfinishedNotifiers {
             Entry::Vacant(_) => todo!("handle finish report after meta recovery"),
         }
     }
+
+    /// Whether the creation of `table_id` is waiting for actors to finish.
+    pub fn contains_table(&self, table_id: TableId) -> bool {
+        self.0
+            .values()
+            .any(|command| command.table_id == Some(table_id))
+    }
+
+    /// Stops waiting for the creation of `table_id`, and fails its notifiers with `err`.
+    pub fn cancel_table(&mut self, table_id: TableId, err: RwError) {
+        self.0.retain(|_, command| {
+            if command.table_id != Some(table_id) {
+                return true;
+            }
+            std::mem::take(&mut command.notifiers)
+                .into_iter()
+                .for_each(|notifier| notifier.notify_finish_failed(err.clone()));
+            false
+        });
+    }
+
+    /// The progress of the tables being created.
+    pub fn jobs(&self) -> Vec<Job> {
+        self.0
+            .values()
+            .filter_map(|command| {
+                Some(Job {
+                    table_id: command.table_id?.table_id,
+                    finished_actors: (command.total_actors - command.actors.len()) as u32,
+                    total_actors: command.total_actors as u32,
+                })
+            })
+            .collect()
+    }
 }
@@ -21,6 +21,7 @@ use log::{debug, error};
 use risingwave_common::error::{ErrorCode, Result, RwError, ToRwResult};
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::Epoch as ProstEpoch;
+use risingwave_pb::meta::event::EventType;
 use risingwave_pb::stream_service::inject_barrier_response::FinishedCreateMview;
 use risingwave_pb::stream_service::{
     BroadcastActorInfoTableRequest, BuildActorsRequest, ForceStopActorsRequest, SyncSourcesRequest,
@@ -124,6 +125,18 @@ where
         .await
         .expect("Retry until recovery success.");
         debug!("recovery success");
+        self.env
+            .event_log_manager()
+            .add_local_event(
+                EventType::Recovery,
+                0,
+                format!(
+                    "recovered from epoch {} to epoch {}",
+                    prev_epoch,
+                    new_epoch.into_inner()
+                ),
+            )
+            .await;
 
         return (
             new_epoch,
@@ -0,0 +1,181 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use risingwave_common::util::slow_log::SLOW_LOG_TARGET;
+use risingwave_pb::meta::slow_barrier::CollectTime;
+use risingwave_pb::meta::SlowBarrier;
+use risingwave_pb::stream_service::InjectBarrierResponse;
+
+use crate::cluster::WorkerId;
+use crate::model::ActorId;
+
+/// Number of the slowest compute nodes and actors reported for each slow barrier.
+pub const SLOWEST_TO_REPORT: usize = 3;
+
+/// Number of the latest slow barriers kept in memory, for `rw_catalog.rw_slow_barriers`.
+pub const SLOW_BARRIER_LOG_CAPACITY: usize = 128;
+
+/// A barrier which took longer than the threshold to be collected.
+#[derive(Clone, Debug)]
+pub struct SlowBarrierRecord {
+    pub prev_epoch: u64,
+    pub curr_epoch: u64,
+
+    /// Debug representation of the command carried by the barrier.
+    pub command: String,
+
+    pub duration: Duration,
+
+    /// Compute nodes taking the longest to collect the barrier, slowest first.
+    pub slowest_nodes: Vec<(WorkerId, Duration)>,
+
+    /// Actors taking the longest to collect the barrier across all compute nodes, slowest first.
+    pub slowest_actors: Vec<(ActorId, Duration)>,
+}
+
+impl SlowBarrierRecord {
+    /// Builds the record from the collect time of each compute node and their responses, keeping
+    /// at most `top_n` nodes and actors.
+    pub fn new(
+        prev_epoch: u64,
+        curr_epoch: u64,
+        command: String,
+        duration: Duration,
+        node_timings: &[(WorkerId, Duration, InjectBarrierResponse)],
+        top_n: usize,
+    ) -> Self {
+        let mut slowest_nodes = node_timings
+            .iter()
+            .map(|(node_id, elapsed, _)| (*node_id, *elapsed))
+            .collect::<Vec<_>>();
+        slowest_nodes.sort_by(|a, b| b.1.cmp(&a.1));
+        slowest_nodes.truncate(top_n);
+
+        let mut slowest_actors = node_timings
+            .iter()
+            .flat_map(|(_, _, resp)| resp.slowest_actors.iter())
+            .map(|a| (a.actor_id, Duration::from_micros(a.duration_us)))
+            .collect::<Vec<_>>();
+        slowest_actors.sort_by(|a, b| b.1.cmp(&a.1));
+        slowest_actors.truncate(top_n);
+
+        Self {
+            prev_epoch,
+            curr_epoch,
+            command,
+            duration,
+            slowest_nodes,
+            slowest_actors,
+        }
+    }
+
+    /// Emits the record to the slow log target.
+    pub fn log(&self) {
+        tracing::warn!(
+            target: SLOW_LOG_TARGET,
+            prev_epoch = self.prev_epoch,
+            curr_epoch = self.curr_epoch,
+            duration_ms = self.duration.as_millis() as u64,
+            slowest_nodes = ?self.slowest_nodes,
+            slowest_actors = ?self.slowest_actors,
+            "slow barrier: {}",
+            self.command
+        );
+    }
+
+    pub fn to_protobuf(&self) -> SlowBarrier {
+        let collect_times = |timings: &[(u32, Duration)]| {
+            timings
+                .iter()
+                .map(|(id, duration)| CollectTime {
+                    id: *id,
+                    duration_us: duration.as_micros() as u64,
+                })
+                .collect()
+        };
+        SlowBarrier {
+            prev_epoch: self.prev_epoch,
+            curr_epoch: self.curr_epoch,
+            command: self.command.clone(),
+            duration_us: self.duration.as_micros() as u64,
+            slowest_nodes: collect_times(&self.slowest_nodes),
+            slowest_actors: collect_times(&self.slowest_actors),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::stream_service::inject_barrier_response::ActorCollectTime;
+
+    use super::*;
+
+    #[test]
+    fn test_slow_barrier_record() {
+        let response = |actors: &[(ActorId, u64)]| InjectBarrierResponse {
+            slowest_actors: actors
+                .iter()
+                .map(|&(actor_id, duration_us)| ActorCollectTime {
+                    actor_id,
+                    duration_us,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let node_timings = vec![
+            (
+                1,
+                Duration::from_millis(10),
+                response(&[(1, 9000), (2, 100)]),
+            ),
+            (2, Duration::from_millis(30), response(&[(3, 29000)])),
+            (3, Duration::from_millis(20), response(&[(4, 19000)])),
+        ];
+        let record = SlowBarrierRecord::new(
+            1,
+            2,
+            "Plain(None)".to_string(),
+            Duration::from_millis(30),
+            &node_timings,
+            2,
+        );
+        assert_eq!(
+            record.slowest_nodes,
+            vec![
+                (2, Duration::from_millis(30)),
+                (3, Duration::from_millis(20))
+            ]
+        );
+        assert_eq!(
+            record.slowest_actors,
+            vec![
+                (3, Duration::from_millis(29)),
+                (4, Duration::from_millis(19))
+            ]
+        );
+
+        let prost = record.to_protobuf();
+        assert_eq!(prost.duration_us, 30000);
+        assert_eq!(
+            prost
+                .slowest_actors
+                .iter()
+                .map(|t| (t.id, t.duration_us))
+                .collect::<Vec<_>>(),
+            vec![(3, 29000), (4, 19000)]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+
+use risingwave_common::catalog::TableId;
+use risingwave_pb::meta::TableWriteStats;
+use risingwave_pb::stream_service::InjectBarrierResponse;
+
+use crate::model::ActorId;
+
+/// Bytes written to the state store by each table, aggregated from the responses of the barriers.
+/// The writes of an executor are attributed to the materialized view its actor belongs to.
+#[derive(Debug, Default)]
+pub struct TableWriteStatsTracker {
+    /// Table id => stats, ordered by table id.
+    stats: BTreeMap<u32, TableWriteStats>,
+}
+
+impl TableWriteStatsTracker {
+    /// Records the bytes written in `epoch` reported by `responses`. `actor_tables` maps the
+    /// actors to their materialized views; the writes of unknown actors are ignored.
+    pub fn record(
+        &mut self,
+        epoch: u64,
+        responses: &[InjectBarrierResponse],
+        actor_tables: &HashMap<ActorId, TableId>,
+    ) {
+        let mut epoch_bytes = HashMap::<TableId, u64>::new();
+        for resp in responses {
+            for (table_id, bytes) in &resp.table_write_bytes {
+                *epoch_bytes.entry(TableId::new(*table_id)).or_default() += bytes;
+            }
+            for (actor_id, bytes) in &resp.actor_write_bytes {
+                if let Some(table_id) = actor_tables.get(actor_id) {
+                    *epoch_bytes.entry(*table_id).or_default() += bytes;
+                }
+            }
+        }
+
+        for (table_id, bytes) in epoch_bytes {
+            let stats = self
+                .stats
+                .entry(table_id.table_id)
+                .or_insert_with(|| TableWriteStats {
+                    table_id: table_id.table_id,
+                    ..Default::default()
+                });
+            stats.total_bytes += bytes;
+            stats.last_epoch = epoch;
+            stats.last_epoch_bytes = bytes;
+        }
+    }
+
+    /// Forgets the stats of a dropped table.
+    pub fn remove(&mut self, table_id: TableId) {
+        self.stats.remove(&table_id.table_id);
+    }
+
+    /// Returns the stats of all tables, ordered by table id.
+    pub fn list(&self) -> Vec<TableWriteStats> {
+        self.stats.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_write_stats() {
+        let response = |tables: &[(u32, u64)], actors: &[(ActorId, u64)]| InjectBarrierResponse {
+            table_write_bytes: tables.iter().cloned().collect(),
+            actor_write_bytes: actors.iter().cloned().collect(),
+            ..Default::default()
+        };
+        let actor_tables = HashMap::from([(1, TableId::new(10)), (2, TableId::new(10))]);
+
+        let mut tracker = TableWriteStatsTracker::default();
+        tracker.record(
+            100,
+            &[
+                response(&[(10, 1000)], &[(1, 100)]),
+                response(&[(20, 2000)], &[(2, 10), (3, 1)]),
+            ],
+            &actor_tables,
+        );
+        tracker.record(200, &[response(&[(10, 5)], &[])], &actor_tables);
+
+        let stats = tracker.list();
+        assert_eq!(
+            stats,
+            vec![
+                TableWriteStats {
+                    table_id: 10,
+                    total_bytes: 1115,
+                    last_epoch: 200,
+                    last_epoch_bytes: 5,
+                },
+                TableWriteStats {
+                    table_id: 20,
+                    total_bytes: 2000,
+                    last_epoch: 100,
+                    last_epoch_bytes: 2000,
+                },
+            ]
+        );
+
+        tracker.remove(TableId::new(10));
+        assert_eq!(tracker.list().len(), 1);
+    }
+}
@@ -324,6 +324,7 @@ impl CompactStatus {
                                             id,
                                             // compact node will never use key_range in SstableInfo.
                                             key_range: None,
+                                            keyspace_stats: vec![],
                                         })
                                         .collect_vec(),
                                 })
@@ -335,6 +336,7 @@ impl CompactStatus {
                                         .map(|id| SstableInfo {
                                             id,
                                             key_range: None,
+                                            keyspace_stats: vec![],
                                         })
                                         .collect_vec(),
                                 })
@@ -350,6 +352,7 @@ impl CompactStatus {
                                         .map(|id| SstableInfo {
                                             id,
                                             key_range: None,
+                                            keyspace_stats: vec![],
                                         })
                                         .collect_vec(),
                                 })
@@ -361,6 +364,7 @@ impl CompactStatus {
                                         .map(|id| SstableInfo {
                                             id,
                                             key_range: None,
+                                            keyspace_stats: vec![],
                                         })
                                         .collect_vec(),
                                 })
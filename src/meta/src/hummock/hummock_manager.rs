@@ -21,16 +21,19 @@ use std::time::Duration;
 use itertools::Itertools;
 use prost::Message;
 use risingwave_common::error::{ErrorCode, Result};
+use risingwave_hummock_sdk::version_delta::build_version_delta;
 use risingwave_hummock_sdk::{
     HummockContextId, HummockEpoch, HummockRefCount, HummockSSTableId, HummockVersionId,
-    INVALID_EPOCH,
+    INVALID_EPOCH, INVALID_VERSION_ID,
 };
+use risingwave_pb::hummock::subscribe_version_deltas_response::Payload;
 use risingwave_pb::hummock::{
     CompactTask, CompactTaskAssignment, HummockPinnedSnapshot, HummockPinnedVersion,
-    HummockSnapshot, HummockStaleSstables, HummockVersion, Level, LevelType, SstableIdInfo,
-    SstableInfo, UncommittedEpoch,
+    HummockSnapshot, HummockStaleSstables, HummockVersion, KeyspaceStats, Level, LevelType,
+    SstableIdInfo, SstableInfo, SubscribeVersionDeltasResponse, UncommittedEpoch,
 };
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::mpsc::Receiver;
+use tokio::sync::{watch, Mutex, RwLock};
 
 use crate::cluster::{ClusterManagerRef, META_NODE_ID};
 use crate::hummock::compaction::CompactStatus;
@@ -58,12 +61,18 @@ pub struct HummockManager<S: MetaStore> {
     // be requested before versioning lock.
     compaction: Mutex<Compaction>,
     versioning: RwLock<Versioning>,
+    /// Notifies the subscribers of version deltas of the id of a new current version. A receiver
+    /// is kept so that sending never fails.
+    version_update_tx: watch::Sender<HummockVersionId>,
+    version_update_rx: watch::Receiver<HummockVersionId>,
 
     metrics: Arc<MetaMetrics>,
 }
 
 pub type HummockManagerRef<S> = Arc<HummockManager<S>>;
 
+const VERSION_DELTA_STREAM_BUFFER_SIZE: usize = 4;
+
 struct Compaction {
     compact_status: CompactStatus,
     compact_task_assignment: BTreeMap<u64, CompactTaskAssignment>,
@@ -121,6 +130,7 @@ where
         cluster_manager: ClusterManagerRef<S>,
         metrics: Arc<MetaMetrics>,
     ) -> Result<HummockManager<S>> {
+        let (version_update_tx, version_update_rx) = watch::channel(INVALID_VERSION_ID);
         let instance = HummockManager {
             env,
             versioning: RwLock::new(Versioning {
@@ -135,6 +145,8 @@ where
                 compact_status: CompactStatus::new(),
                 compact_task_assignment: Default::default(),
             }),
+            version_update_tx,
+            version_update_rx,
             metrics,
             cluster_manager,
         };
@@ -330,6 +342,75 @@ where
         Ok(())
     }
 
+    /// Pins a version greater than `last_pinned` like `pin_version`, and returns it as a delta
+    /// from `last_pinned` if `last_pinned` is still kept, or as a full version otherwise.
+    /// Returns `None` if there's no version greater than `last_pinned`.
+    pub async fn pin_version_delta(
+        &self,
+        context_id: HummockContextId,
+        last_pinned: HummockVersionId,
+    ) -> Result<Option<(HummockVersionId, Payload)>> {
+        let version = self.pin_version(context_id, last_pinned).await?;
+        if version.id <= last_pinned {
+            return Ok(None);
+        }
+        let version_id = version.id;
+        let versioning_guard = self.versioning.read().await;
+        let payload = match versioning_guard.hummock_versions.get(&last_pinned) {
+            Some(prev_version) => {
+                Payload::VersionDelta(build_version_delta(prev_version, &version))
+            }
+            None => Payload::Version(version),
+        };
+        Ok(Some((version_id, payload)))
+    }
+
+    /// Subscribes to the versions greater than `last_pinned`. Each version is pinned for
+    /// `context_id` with `pin_version_delta` and pushed as soon as it becomes the current version.
+    pub fn subscribe_version_deltas(
+        self: &Arc<Self>,
+        context_id: HummockContextId,
+        mut last_pinned: HummockVersionId,
+    ) -> Receiver<Result<SubscribeVersionDeltasResponse>> {
+        let (tx, rx) = tokio::sync::mpsc::channel(VERSION_DELTA_STREAM_BUFFER_SIZE);
+        let mut version_update_rx = self.version_update_rx.clone();
+        let hummock_manager = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match hummock_manager
+                    .pin_version_delta(context_id, last_pinned)
+                    .await
+                {
+                    Ok(Some((version_id, payload))) => {
+                        last_pinned = version_id;
+                        let response = SubscribeVersionDeltasResponse {
+                            payload: Some(payload),
+                        };
+                        if tx.send(Ok(response)).await.is_err() {
+                            // The subscriber is gone.
+                            return;
+                        }
+                        // There may be even newer versions.
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        tx.send(Err(e)).await.ok();
+                        return;
+                    }
+                }
+                if version_update_rx.changed().await.is_err() {
+                    return;
+                }
+            }
+        });
+        rx
+    }
+
+    fn notify_version_update(&self, version_id: HummockVersionId) {
+        self.version_update_tx.send(version_id).ok();
+    }
+
     pub async fn add_tables(
         &self,
         context_id: HummockContextId,
@@ -417,6 +498,8 @@ where
             new_hummock_version
         )?;
 
+        self.notify_version_update(ret_hummock_version.id);
+
         // Update metrics
         trigger_commit_stat(&self.metrics, &ret_hummock_version);
 
@@ -661,11 +744,13 @@ where
                                     |SSTableStat {
                                          table_id,
                                          key_range,
+                                         keyspace_stats,
                                          ..
                                      }| {
                                         SstableInfo {
                                             id: *table_id,
                                             key_range: Some(key_range.clone().into()),
+                                            keyspace_stats: keyspace_stats.clone(),
                                         }
                                     },
                                 )
@@ -679,11 +764,13 @@ where
                                     |SSTableStat {
                                          table_id,
                                          key_range,
+                                         keyspace_stats,
                                          ..
                                      }| {
                                         SstableInfo {
                                             id: *table_id,
                                             key_range: Some(key_range.clone().into()),
+                                            keyspace_stats: keyspace_stats.clone(),
                                         }
                                     },
                                 )
@@ -697,7 +784,8 @@ where
                 safe_epoch: max(old_version.safe_epoch, compacted_watermark),
             };
 
-            hummock_versions.insert(current_version_id.id(), new_version);
+            let new_version_id = current_version_id.id();
+            hummock_versions.insert(new_version_id, new_version);
 
             let mut version_stale_sstables = stale_sstables.new_entry_txn_or_default(
                 old_version.id,
@@ -733,6 +821,7 @@ where
                 version_stale_sstables,
                 sstable_id_infos
             )?;
+            self.notify_version_update(new_version_id);
 
             tracing::info!(
                 "Finish hummock compaction task id {}, compact {} SSTs {:?} to {} SSTs {:?}",
@@ -850,6 +939,7 @@ where
             new_hummock_version,
             current_version_id
         )?;
+        self.notify_version_update(new_version_id);
 
         // Update metrics
         trigger_sst_stat(&self.metrics, &compact_status_copy);
@@ -909,6 +999,7 @@ where
                 new_hummock_version,
                 version_stale_sstables
             )?;
+            self.notify_version_update(new_version_id);
         } else {
             abort_multi_var!(current_version_id, new_hummock_version, stale_sstables);
         }
@@ -1247,7 +1338,6 @@ where
         Ok(marked)
     }
 
-    #[cfg(any(test, feature = "test"))]
     pub async fn get_current_version(&self) -> HummockVersion {
         let versioning_guard = self.versioning.read().await;
         versioning_guard
@@ -1256,4 +1346,27 @@ where
             .unwrap()
             .clone()
     }
+
+    /// Aggregates the key count and size of each keyspace over the SSTs of the current version.
+    /// Keys overwritten or deleted but not compacted yet are counted as well.
+    pub async fn get_keyspace_stats(&self) -> Vec<KeyspaceStats> {
+        let version = self.get_current_version().await;
+        let mut stats: BTreeMap<Vec<u8>, KeyspaceStats> = BTreeMap::new();
+        for sst_stats in version
+            .levels
+            .iter()
+            .flat_map(|level| level.table_infos.iter())
+            .flat_map(|sst| sst.keyspace_stats.iter())
+        {
+            let entry = stats
+                .entry(sst_stats.prefix.clone())
+                .or_insert_with(|| KeyspaceStats {
+                    prefix: sst_stats.prefix.clone(),
+                    ..Default::default()
+                });
+            entry.key_count += sst_stats.key_count;
+            entry.total_size += sst_stats.total_size;
+        }
+        stats.into_values().collect()
+    }
 }
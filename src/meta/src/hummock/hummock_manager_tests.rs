@@ -18,13 +18,15 @@ use std::time::Duration;
 use itertools::Itertools;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_hummock_sdk::compact::compact_task_to_string;
+use risingwave_hummock_sdk::version_delta::apply_version_delta;
 use risingwave_hummock_sdk::{
     HummockContextId, HummockSSTableId, FIRST_VERSION_ID, INVALID_EPOCH, INVALID_VERSION_ID,
 };
 use risingwave_pb::common::{HostAddress, WorkerType};
+use risingwave_pb::hummock::subscribe_version_deltas_response::Payload;
 use risingwave_pb::hummock::{
     HummockPinnedSnapshot, HummockPinnedVersion, HummockSnapshot, HummockVersion,
-    HummockVersionRefId,
+    HummockVersionRefId, KeyspaceStats,
 };
 
 use crate::hummock::model::CurrentHummockVersionId;
@@ -970,3 +972,70 @@ async fn test_mark_orphan_ssts() {
         )
     );
 }
+
+#[tokio::test]
+async fn test_get_keyspace_stats() -> Result<()> {
+    let (_env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    let stats = |prefix: &[u8], key_count, total_size| KeyspaceStats {
+        prefix: prefix.to_vec(),
+        key_count,
+        total_size,
+    };
+
+    let epoch = 1;
+    let mut tables = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 2).await);
+    tables[0].keyspace_stats = vec![stats(b"t1", 2, 20), stats(b"t2", 1, 10)];
+    tables[1].keyspace_stats = vec![stats(b"t2", 3, 30)];
+    hummock_manager
+        .add_tables(context_id, tables, epoch)
+        .await?;
+    // Uncommitted SSTs are not counted.
+    assert!(hummock_manager.get_keyspace_stats().await.is_empty());
+
+    hummock_manager.commit_epoch(epoch).await?;
+    assert_eq!(
+        hummock_manager.get_keyspace_stats().await,
+        vec![stats(b"t1", 2, 20), stats(b"t2", 4, 40)]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hummock_subscribe_version_deltas() -> Result<()> {
+    let (env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    let mut rx = hummock_manager.subscribe_version_deltas(context_id, INVALID_VERSION_ID);
+
+    // The first version is pushed in full.
+    let mut version = match rx.recv().await.unwrap()?.payload.unwrap() {
+        Payload::Version(version) => version,
+        Payload::VersionDelta(_) => panic!("expect a full version"),
+    };
+    assert_eq!(version.id, FIRST_VERSION_ID);
+
+    let epoch = 1;
+    let original_tables = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 2).await);
+    hummock_manager
+        .add_tables(context_id, original_tables, epoch)
+        .await?;
+    hummock_manager.commit_epoch(epoch).await?;
+
+    // The following versions are pushed as deltas.
+    let current_version = hummock_manager.get_current_version().await;
+    while version.id < current_version.id {
+        match rx.recv().await.unwrap()?.payload.unwrap() {
+            Payload::Version(_) => panic!("expect a version delta"),
+            Payload::VersionDelta(delta) => apply_version_delta(&mut version, &delta),
+        }
+    }
+    assert_eq!(version, current_version);
+
+    // The pushed versions are pinned.
+    let pinned_versions = HummockPinnedVersion::list(env.meta_store()).await?;
+    assert_eq!(pinned_versions.len(), 1);
+    assert!(pinned_versions[0].version_id.contains(&FIRST_VERSION_ID));
+    assert!(pinned_versions[0].version_id.contains(&current_version.id));
+
+    Ok(())
+}
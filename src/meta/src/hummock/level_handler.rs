@@ -15,13 +15,14 @@
 use itertools::Itertools;
 use risingwave_hummock_sdk::key_range::KeyRange;
 use risingwave_pb::hummock::level_handler::KeyRangeTaskId;
-use risingwave_pb::hummock::SstableInfo;
+use risingwave_pb::hummock::{KeyspaceStats, SstableInfo};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct SSTableStat {
     pub key_range: KeyRange,
     pub table_id: u64,
     pub compact_task: Option<u64>,
+    pub keyspace_stats: Vec<KeyspaceStats>,
 }
 
 impl From<&SstableInfo> for SSTableStat {
@@ -30,6 +31,7 @@ impl From<&SstableInfo> for SSTableStat {
             key_range: info.key_range.as_ref().unwrap().into(),
             table_id: info.id,
             compact_task: None,
+            keyspace_stats: info.keyspace_stats.clone(),
         }
     }
 }
@@ -42,6 +44,7 @@ impl From<&SSTableStat> for risingwave_pb::hummock::SstableStat {
             compact_task: stat
                 .compact_task
                 .map(|it| risingwave_pb::hummock::sstable_stat::CompactTaskId { id: it }),
+            keyspace_stats: stat.keyspace_stats.clone(),
         }
     }
 }
@@ -52,6 +55,7 @@ impl From<&risingwave_pb::hummock::SstableStat> for SSTableStat {
             key_range: stat.key_range.as_ref().unwrap().into(),
             table_id: stat.table_id,
             compact_task: stat.compact_task.as_ref().map(|it| it.id),
+            keyspace_stats: stat.keyspace_stats.clone(),
         }
     }
 }
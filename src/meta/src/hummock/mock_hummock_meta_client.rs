@@ -21,7 +21,7 @@ use risingwave_pb::hummock::{
     CompactTask, HummockSnapshot, HummockVersion, SstableInfo, SubscribeCompactTasksResponse,
     VacuumTask,
 };
-use risingwave_rpc_client::HummockMetaClient;
+use risingwave_rpc_client::{HummockMetaClient, VersionDeltaStream};
 use tonic::Streaming;
 
 use crate::hummock::HummockManager;
@@ -120,6 +120,16 @@ impl HummockMetaClient for MockHummockMetaClient {
     async fn report_vacuum_task(&self, _vacuum_task: VacuumTask) -> Result<()> {
         Ok(())
     }
+
+    async fn subscribe_version_deltas(
+        &self,
+        last_pinned: HummockVersionId,
+    ) -> Result<Box<dyn VersionDeltaStream>> {
+        Ok(Box::new(
+            self.hummock_manager
+                .subscribe_version_deltas(self.context_id, last_pinned),
+        ))
+    }
 }
 
 impl MockHummockMetaClient {
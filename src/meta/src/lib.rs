@@ -86,6 +86,20 @@ pub struct MetaNodeOpts {
     /// e2e tests.
     #[clap(long)]
     disable_recovery: bool,
+
+    /// The maximal interval in ms between two barriers, each of which checkpoints the states of
+    /// all the streaming jobs.
+    #[clap(long, default_value = "100")]
+    barrier_interval_ms: u64,
+
+    /// Barriers taking longer than this threshold in ms are written to the slow log. 0 disables
+    /// the slow barrier log.
+    #[clap(long, default_value = "1000")]
+    slow_barrier_threshold_ms: u64,
+
+    /// Maximum number of cluster events kept in memory and exposed as `rw_catalog.rw_events`.
+    #[clap(long, default_value = "1024")]
+    event_log_capacity: usize,
 }
 
 /// Start meta node
@@ -115,6 +129,10 @@ pub async fn start(opts: MetaNodeOpts) {
         opts.dashboard_ui_path,
         MetaOpts {
             enable_recovery: !opts.disable_recovery,
+            barrier_interval: Duration::from_millis(opts.barrier_interval_ms),
+            slow_barrier_threshold: (opts.slow_barrier_threshold_ms > 0)
+                .then(|| Duration::from_millis(opts.slow_barrier_threshold_ms)),
+            event_log_capacity: opts.event_log_capacity,
         },
     )
     .await
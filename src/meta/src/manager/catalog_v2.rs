@@ -727,6 +727,10 @@ where
         Source::select(self.env.meta_store(), &id).await
     }
 
+    pub async fn get_table(&self, id: TableId) -> Result<Option<Table>> {
+        Table::select(self.env.meta_store(), &id).await
+    }
+
     fn get_ref_count(&self, relation_id: RelationId) -> Option<usize> {
         self.relation_ref_count.get(&relation_id).cloned()
     }
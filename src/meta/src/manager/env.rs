@@ -14,13 +14,14 @@
 
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
 
 use super::{StreamClients, StreamClientsRef};
 #[cfg(any(test, feature = "test"))]
 use crate::manager::MemEpochGenerator;
 use crate::manager::{
-    EpochGenerator, EpochGeneratorRef, IdGeneratorManager, IdGeneratorManagerRef,
-    NotificationManager, NotificationManagerRef,
+    EpochGenerator, EpochGeneratorRef, EventLogManager, EventLogManagerRef, IdGeneratorManager,
+    IdGeneratorManagerRef, NotificationManager, NotificationManagerRef, DEFAULT_EVENT_LOG_CAPACITY,
 };
 #[cfg(any(test, feature = "test"))]
 use crate::storage::MemStore;
@@ -48,14 +49,40 @@ where
     /// stream clients memorization.
     stream_clients: StreamClientsRef,
 
+    /// recent cluster events.
+    event_log_manager: EventLogManagerRef,
+
     /// options read by all services
     pub opts: Arc<MetaOpts>,
 }
 
+/// Barriers are sent at least every 100ms by default.
+const DEFAULT_BARRIER_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Options shared by all meta service instances
-#[derive(Default)]
 pub struct MetaOpts {
     pub enable_recovery: bool,
+
+    /// The maximal interval between two barriers, i.e. the interval of the periodic checkpoints.
+    pub barrier_interval: Duration,
+
+    /// Barriers taking longer than this threshold are written to the slow log. `None` disables
+    /// the slow barrier log.
+    pub slow_barrier_threshold: Option<Duration>,
+
+    /// Maximum number of events kept by the [`EventLogManager`]. Older events are evicted first.
+    pub event_log_capacity: usize,
+}
+
+impl Default for MetaOpts {
+    fn default() -> Self {
+        Self {
+            enable_recovery: false,
+            barrier_interval: DEFAULT_BARRIER_INTERVAL,
+            slow_barrier_threshold: None,
+            event_log_capacity: DEFAULT_EVENT_LOG_CAPACITY,
+        }
+    }
 }
 
 impl<S> MetaSrvEnv<S>
@@ -71,6 +98,10 @@ where
         let id_gen_manager = Arc::new(IdGeneratorManager::new(meta_store.clone()).await);
         let stream_clients = Arc::new(StreamClients::default());
         let notification_manager = Arc::new(NotificationManager::new(epoch_generator.clone()));
+        let event_log_manager = Arc::new(EventLogManager::new(
+            opts.event_log_capacity,
+            notification_manager.clone(),
+        ));
 
         Self {
             id_gen_manager,
@@ -78,6 +109,7 @@ where
             epoch_generator,
             notification_manager,
             stream_clients,
+            event_log_manager,
             opts: opts.into(),
         }
     }
@@ -121,6 +153,14 @@ where
     pub fn stream_clients(&self) -> &StreamClients {
         self.stream_clients.deref()
     }
+
+    pub fn event_log_manager_ref(&self) -> EventLogManagerRef {
+        self.event_log_manager.clone()
+    }
+
+    pub fn event_log_manager(&self) -> &EventLogManager {
+        self.event_log_manager.deref()
+    }
 }
 
 #[cfg(any(test, feature = "test"))]
@@ -133,6 +173,10 @@ impl MetaSrvEnv<MemStore> {
         let epoch_generator = Arc::new(MemEpochGenerator::new());
         let notification_manager = Arc::new(NotificationManager::new(epoch_generator.clone()));
         let stream_clients = Arc::new(StreamClients::default());
+        let event_log_manager = Arc::new(EventLogManager::new(
+            DEFAULT_EVENT_LOG_CAPACITY,
+            notification_manager.clone(),
+        ));
 
         Self {
             id_gen_manager,
@@ -140,6 +184,7 @@ impl MetaSrvEnv<MemStore> {
             epoch_generator,
             notification_manager,
             stream_clients,
+            event_log_manager,
             opts: MetaOpts::default().into(),
         }
     }
@@ -18,8 +18,8 @@ use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use parking_lot::Mutex;
+pub use risingwave_hummock_sdk::EPOCH_PHYSICAL_SHIFT_BITS;
 
-pub const EPOCH_PHYSICAL_SHIFT_BITS: u8 = 16;
 pub const INVALID_EPOCH: u64 = 0;
 
 #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd, Hash, Debug)]
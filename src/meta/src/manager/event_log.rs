@@ -0,0 +1,125 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use risingwave_pb::meta::event::EventType;
+use risingwave_pb::meta::subscribe_response::{Info, Operation};
+use risingwave_pb::meta::Event;
+
+use super::NotificationManagerRef;
+
+/// Number of events kept by default.
+pub const DEFAULT_EVENT_LOG_CAPACITY: usize = 1024;
+
+/// [`EventLogManager`] keeps the most recent cluster events, e.g. actor failures, source parse
+/// failures and recoveries, in a bounded in-memory buffer. Every new event is pushed to frontends,
+/// which expose them as `rw_catalog.rw_events`.
+pub struct EventLogManager {
+    capacity: usize,
+    events: Mutex<VecDeque<Event>>,
+    notification_manager: NotificationManagerRef,
+}
+
+pub type EventLogManagerRef = Arc<EventLogManager>;
+
+impl EventLogManager {
+    pub fn new(capacity: usize, notification_manager: NotificationManagerRef) -> Self {
+        Self {
+            capacity,
+            events: Mutex::new(VecDeque::with_capacity(capacity)),
+            notification_manager,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Records an event reported by meta itself.
+    pub async fn add_local_event(&self, event_type: EventType, object_id: u32, info: String) {
+        self.add_event(Event {
+            timestamp_ms: now_ms(),
+            event_type: event_type as i32,
+            object_id,
+            node: "meta".to_string(),
+            info,
+        })
+        .await
+    }
+
+    /// Records an event, evicting the oldest ones once the capacity is exceeded, and notifies
+    /// frontends.
+    pub async fn add_event(&self, mut event: Event) {
+        if self.capacity == 0 {
+            return;
+        }
+        if event.timestamp_ms == 0 {
+            event.timestamp_ms = now_ms();
+        }
+        {
+            let mut events = self.events.lock();
+            while events.len() >= self.capacity {
+                events.pop_front();
+            }
+            events.push_back(event.clone());
+        }
+        self.notification_manager
+            .notify_frontend(Operation::Add, &Info::Event(event))
+            .await;
+    }
+
+    /// Returns all kept events, oldest first.
+    pub fn list_events(&self) -> Vec<Event> {
+        self.events.lock().iter().cloned().collect()
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Clock may have gone backwards")
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::{MemEpochGenerator, NotificationManager};
+
+    #[tokio::test]
+    async fn test_event_log_eviction() {
+        let notification_manager =
+            Arc::new(NotificationManager::new(Arc::new(MemEpochGenerator::new())));
+        let event_log = EventLogManager::new(2, notification_manager);
+        for object_id in 1..=3 {
+            event_log
+                .add_local_event(
+                    EventType::ActorFailure,
+                    object_id,
+                    "actor failed".to_string(),
+                )
+                .await;
+        }
+        let events = event_log.list_events();
+        assert_eq!(
+            events.iter().map(|e| e.object_id).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert!(events.iter().all(|e| e.timestamp_ms > 0));
+    }
+}
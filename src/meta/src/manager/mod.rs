@@ -16,6 +16,7 @@ mod catalog;
 mod catalog_v2;
 mod env;
 mod epoch;
+mod event_log;
 mod hash_dispatch;
 mod id;
 mod notification;
@@ -25,6 +26,7 @@ pub use catalog::*;
 pub use catalog_v2::*;
 pub use env::*;
 pub use epoch::*;
+pub use event_log::*;
 pub use hash_dispatch::*;
 pub use id::*;
 pub use notification::*;
@@ -22,7 +22,7 @@ use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus, Fragment};
 use risingwave_pb::meta::TableFragments as ProstTableFragments;
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::stream_node::Node;
-use risingwave_pb::stream_plan::{StreamActor, StreamNode};
+use risingwave_pb::stream_plan::{SourceNode, StreamActor, StreamNode};
 
 use super::{ActorId, FragmentId};
 use crate::cluster::WorkerId;
@@ -172,6 +172,67 @@ impl TableFragments {
         None
     }
 
+    fn find_source_node_mut(
+        stream_node: &mut StreamNode,
+        source_id: SourceId,
+    ) -> Option<&mut SourceNode> {
+        if let Some(Node::SourceNode(s)) = stream_node.node.as_mut() {
+            if s.table_ref_id.as_ref().unwrap().table_id as SourceId == source_id {
+                return Some(s);
+            }
+        }
+
+        stream_node
+            .input
+            .iter_mut()
+            .find_map(|child| Self::find_source_node_mut(child, source_id))
+    }
+
+    /// Returns the ids of the stream sources read by the actors.
+    pub fn stream_source_ids(&self) -> HashSet<SourceId> {
+        self.fragments
+            .values()
+            .flat_map(|fragment| fragment.actors.iter())
+            .filter_map(|actor| Self::fetch_stream_source_id(actor.nodes.as_ref().unwrap()))
+            .collect()
+    }
+
+    /// Returns the source nodes reading from `source_id` grouped by fragment, with the ids of
+    /// their actors. The actors of a fragment share the splits of the source.
+    pub fn source_fragment_nodes_mut(
+        &mut self,
+        source_id: SourceId,
+    ) -> Vec<Vec<(ActorId, &mut SourceNode)>> {
+        self.fragments
+            .values_mut()
+            .map(|fragment| {
+                fragment
+                    .actors
+                    .iter_mut()
+                    .filter_map(|actor| {
+                        let actor_id = actor.actor_id;
+                        Self::find_source_node_mut(actor.nodes.as_mut().unwrap(), source_id)
+                            .map(|source_node| (actor_id, source_node))
+                    })
+                    .collect_vec()
+            })
+            .filter(|nodes| !nodes.is_empty())
+            .collect()
+    }
+
+    /// Returns the source nodes reading from `source_id`, with the ids of their actors.
+    pub fn source_nodes_mut(&mut self, source_id: SourceId) -> Vec<(ActorId, &mut SourceNode)> {
+        self.fragments
+            .values_mut()
+            .flat_map(|fragment| fragment.actors.iter_mut())
+            .filter_map(|actor| {
+                let actor_id = actor.actor_id;
+                Self::find_source_node_mut(actor.nodes.as_mut().unwrap(), source_id)
+                    .map(|source_node| (actor_id, source_node))
+            })
+            .collect()
+    }
+
     /// Returns actors that contains Chain node.
     pub fn chain_actor_ids(&self) -> Vec<ActorId> {
         self.fragments
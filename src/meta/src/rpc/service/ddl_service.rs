@@ -16,7 +16,9 @@
 use std::collections::HashSet;
 
 use risingwave_common::catalog::CatalogVersion;
-use risingwave_common::error::{tonic_err, Result as RwResult};
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{tonic_err, Result as RwResult, RwError};
+use risingwave_hummock_sdk::{HummockContextId, HummockVersionId};
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::*;
 use risingwave_pb::ddl_service::ddl_service_server::DdlService;
@@ -27,11 +29,13 @@ use risingwave_pb::stream_plan::StreamNode;
 use tonic::{Request, Response, Status};
 
 use crate::cluster::ClusterManagerRef;
+use crate::hummock::HummockManagerRef;
 use crate::manager::{CatalogManagerRef, IdCategory, MetaSrvEnv, SourceId, TableId};
 use crate::model::TableFragments;
 use crate::storage::MetaStore;
 use crate::stream::{
-    FragmentManagerRef, GlobalStreamManagerRef, SourceManagerRef, StreamFragmenter,
+    export_mview_bundle, FragmentManagerRef, GlobalStreamManagerRef, SourceManagerRef,
+    StreamFragmenter,
 };
 
 #[derive(Clone)]
@@ -43,6 +47,7 @@ pub struct DdlServiceImpl<S: MetaStore> {
     source_manager: SourceManagerRef<S>,
     cluster_manager: ClusterManagerRef<S>,
     fragment_manager: FragmentManagerRef<S>,
+    hummock_manager: HummockManagerRef<S>,
 }
 
 impl<S> DdlServiceImpl<S>
@@ -56,6 +61,7 @@ where
         source_manager: SourceManagerRef<S>,
         cluster_manager: ClusterManagerRef<S>,
         fragment_manager: FragmentManagerRef<S>,
+        hummock_manager: HummockManagerRef<S>,
     ) -> Self {
         Self {
             env,
@@ -64,6 +70,7 @@ where
             source_manager,
             cluster_manager,
             fragment_manager,
+            hummock_manager,
         }
     }
 }
@@ -371,6 +378,23 @@ where
             version,
         }))
     }
+
+    async fn export_materialized_view(
+        &self,
+        request: Request<ExportMaterializedViewRequest>,
+    ) -> Result<Response<ExportMaterializedViewResponse>, Status> {
+        let req = request.into_inner();
+
+        let bundle = self
+            .export_materialized_view_inner(req.table_id, req.context_id)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(ExportMaterializedViewResponse {
+            status: None,
+            bundle: Some(bundle),
+        }))
+    }
 }
 
 impl<S> DdlServiceImpl<S>
@@ -460,10 +484,20 @@ where
         fn fill_source_id(stream_node: &mut StreamNode, source_id: u32) -> usize {
             use risingwave_common::catalog::TableId;
             let mut source_count = 0;
-            if let Node::SourceNode(source_node) = stream_node.node.as_mut().unwrap() {
-                // TODO: refactor using source id.
-                source_node.table_ref_id = TableRefId::from(&TableId::new(source_id)).into();
-                source_count += 1;
+            match stream_node.node.as_mut().unwrap() {
+                Node::SourceNode(source_node) => {
+                    // TODO: refactor using source id.
+                    source_node.table_ref_id = TableRefId::from(&TableId::new(source_id)).into();
+                    source_count += 1;
+                }
+                // The rows of `INSERT ... ON CONFLICT` are registered in the source.
+                Node::MaterializeNode(materialize_node) => {
+                    if let Some(overwrite) = &mut materialize_node.overwrite {
+                        overwrite.table_source_ref_id =
+                            TableRefId::from(&TableId::new(source_id)).into();
+                    }
+                }
+                _ => {}
             }
             for input in &mut stream_node.input {
                 source_count += fill_source_id(input, source_id);
@@ -512,6 +546,38 @@ where
         Ok((source_id, mview_id, version))
     }
 
+    async fn export_materialized_view_inner(
+        &self,
+        table_id: TableId,
+        context_id: HummockContextId,
+    ) -> RwResult<MaterializedViewBundle> {
+        use risingwave_common::catalog::TableId;
+
+        // Hold the catalog lock for the whole export, so that the view cannot be dropped or
+        // replaced between reading its catalog, its fragments and its state.
+        let catalog_guard = self.catalog_manager.get_catalog_core_guard().await;
+        let mview = catalog_guard.get_table(table_id).await?.ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "materialized view not found: id={}",
+                table_id
+            )))
+        })?;
+        let table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(&TableId::new(table_id))
+            .await?;
+
+        // Pin the current version for the caller, so that its SSTs are not vacuumed before they
+        // are copied out. The caller unpins it afterwards, or it is released with the context.
+        let version = self
+            .hummock_manager
+            .pin_version(context_id, HummockVersionId::MAX)
+            .await?;
+        drop(catalog_guard);
+
+        Ok(export_mview_bundle(mview, &table_fragments, &version))
+    }
+
     async fn drop_materialized_source_inner(
         &self,
         source_id: SourceId,
@@ -0,0 +1,45 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use risingwave_pb::meta::event_log_service_server::EventLogService;
+use risingwave_pb::meta::{ReportEventRequest, ReportEventResponse};
+use tonic::{Request, Response, Status};
+
+use crate::manager::EventLogManagerRef;
+
+#[derive(Clone)]
+pub struct EventLogServiceImpl {
+    event_log_manager: EventLogManagerRef,
+}
+
+impl EventLogServiceImpl {
+    pub fn new(event_log_manager: EventLogManagerRef) -> Self {
+        EventLogServiceImpl { event_log_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventLogService for EventLogServiceImpl {
+    #[cfg_attr(coverage, no_coverage)]
+    async fn report_event(
+        &self,
+        request: Request<ReportEventRequest>,
+    ) -> Result<Response<ReportEventResponse>, Status> {
+        let req = request.into_inner();
+        let event = req
+            .event
+            .ok_or_else(|| Status::invalid_argument("event is required"))?;
+        self.event_log_manager.add_event(event).await;
+        Ok(Response::new(ReportEventResponse { status: None }))
+    }
+}
@@ -54,6 +54,7 @@ where
     S: MetaStore,
 {
     type SubscribeCompactTasksStream = RwReceiverStream<SubscribeCompactTasksResponse>;
+    type SubscribeVersionDeltasStream = RwReceiverStream<SubscribeVersionDeltasResponse>;
 
     async fn pin_version(
         &self,
@@ -220,4 +221,26 @@ where
         }
         Ok(Response::new(ReportVacuumTaskResponse { status: None }))
     }
+
+    async fn subscribe_version_deltas(
+        &self,
+        request: Request<SubscribeVersionDeltasRequest>,
+    ) -> Result<Response<Self::SubscribeVersionDeltasStream>, Status> {
+        let req = request.into_inner();
+        let rx = self
+            .hummock_manager
+            .subscribe_version_deltas(req.context_id, req.last_pinned);
+        Ok(Response::new(RwReceiverStream::new(rx)))
+    }
+
+    async fn get_keyspace_stats(
+        &self,
+        _request: Request<GetKeyspaceStatsRequest>,
+    ) -> Result<Response<GetKeyspaceStatsResponse>, Status> {
+        let keyspace_stats = self.hummock_manager.get_keyspace_stats().await;
+        Ok(Response::new(GetKeyspaceStatsResponse {
+            status: None,
+            keyspace_stats,
+        }))
+    }
 }
@@ -16,6 +16,7 @@ pub mod catalog_service;
 pub mod cluster_service;
 pub mod ddl_service;
 pub mod epoch_service;
+pub mod event_log_service;
 pub mod heartbeat_service;
 pub mod hummock_service;
 pub mod notification_service;
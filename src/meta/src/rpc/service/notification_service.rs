@@ -91,6 +91,8 @@ where
                     schema,
                     source,
                     table,
+                    events: self.env.event_log_manager().list_events(),
+                    event_log_capacity: self.env.event_log_manager().capacity() as u32,
                     ..Default::default()
                 };
                 tx.send(Ok(SubscribeResponse {
@@ -20,7 +20,7 @@ use tonic::{Request, Response, Status};
 
 use crate::cluster::ClusterManagerRef;
 use crate::manager::MetaSrvEnv;
-use crate::model::TableFragments;
+use crate::model::{MetadataModel, TableFragments};
 use crate::storage::MetaStore;
 use crate::stream::{FragmentManagerRef, GlobalStreamManagerRef, StreamFragmenter};
 
@@ -134,4 +134,99 @@ where
             .map_err(|e| e.to_grpc_status())?;
         Ok(Response::new(FlushResponse { status: None }))
     }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn trigger_checkpoint(
+        &self,
+        request: Request<TriggerCheckpointRequest>,
+    ) -> TonicResponse<TriggerCheckpointResponse> {
+        let _req = request.into_inner();
+
+        self.global_stream_manager
+            .trigger_checkpoint()
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+        Ok(Response::new(TriggerCheckpointResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn list_table_fragments(
+        &self,
+        request: Request<ListTableFragmentsRequest>,
+    ) -> TonicResponse<ListTableFragmentsResponse> {
+        let _req = request.into_inner();
+
+        let table_fragments = self
+            .fragment_manager
+            .list_table_fragments()
+            .await
+            .map_err(|e| e.to_grpc_status())?
+            .iter()
+            .map(|tf| tf.to_protobuf())
+            .collect();
+        Ok(Response::new(ListTableFragmentsResponse {
+            table_fragments,
+        }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn throttle_source(
+        &self,
+        request: Request<ThrottleSourceRequest>,
+    ) -> TonicResponse<ThrottleSourceResponse> {
+        let req = request.into_inner();
+
+        self.global_stream_manager
+            .throttle_source(req.source_id, req.get_throttle().map_err(tonic_err)?)
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+        Ok(Response::new(ThrottleSourceResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn list_jobs(
+        &self,
+        request: Request<ListJobsRequest>,
+    ) -> TonicResponse<ListJobsResponse> {
+        let _req = request.into_inner();
+
+        let jobs = self.global_stream_manager.list_jobs();
+        Ok(Response::new(ListJobsResponse { jobs }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn list_table_write_stats(
+        &self,
+        request: Request<ListTableWriteStatsRequest>,
+    ) -> TonicResponse<ListTableWriteStatsResponse> {
+        let _req = request.into_inner();
+
+        let stats = self.global_stream_manager.list_table_write_stats();
+        Ok(Response::new(ListTableWriteStatsResponse { stats }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn list_slow_barriers(
+        &self,
+        request: Request<ListSlowBarriersRequest>,
+    ) -> TonicResponse<ListSlowBarriersResponse> {
+        let _req = request.into_inner();
+
+        let barriers = self.global_stream_manager.list_slow_barriers();
+        Ok(Response::new(ListSlowBarriersResponse { barriers }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn cancel_job(
+        &self,
+        request: Request<CancelJobRequest>,
+    ) -> TonicResponse<CancelJobResponse> {
+        let req = request.into_inner();
+
+        self.global_stream_manager
+            .cancel_job(&TableId::new(req.table_id))
+            .await
+            .map_err(|e| e.to_grpc_status())?;
+        Ok(Response::new(CancelJobResponse { status: None }))
+    }
 }
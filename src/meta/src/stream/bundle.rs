@@ -0,0 +1,196 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use risingwave_hummock_sdk::key::{next_key, user_key};
+use risingwave_pb::catalog::Table;
+use risingwave_pb::ddl_service::MaterializedViewBundle;
+use risingwave_pb::hummock::{HummockVersion, KeyRange, SstableInfo};
+use risingwave_pb::stream_plan::StreamNode;
+
+use crate::model::{MetadataModel, TableFragments};
+
+/// Format version of [`MaterializedViewBundle`]. Bump it on any incompatible change of how the
+/// bundle or the state keys it describes are laid out.
+pub const MVIEW_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Builds a bundle of the materialized view `table`, with its state located in the pinned
+/// `version`. There's no import counterpart yet, see [`MaterializedViewBundle`].
+pub fn export_mview_bundle(
+    table: Table,
+    table_fragments: &TableFragments,
+    version: &HummockVersion,
+) -> MaterializedViewBundle {
+    let prefixes = state_key_prefixes(table_fragments);
+    let ssts = ssts_in_prefixes(version, &prefixes);
+    let state_key_ranges = prefixes
+        .into_iter()
+        .map(|prefix| KeyRange {
+            right: next_key(&prefix),
+            left: prefix,
+            inf: false,
+        })
+        .collect();
+
+    MaterializedViewBundle {
+        format_version: MVIEW_BUNDLE_FORMAT_VERSION,
+        materialized_view: Some(table),
+        table_fragments: Some(table_fragments.to_protobuf()),
+        epoch: version.max_committed_epoch,
+        state_key_ranges,
+        ssts,
+        version_id: version.id,
+    }
+}
+
+/// Returns the user key prefixes holding state of a materialized view: the table keyspace, and
+/// the keyspaces of all executors in its actors. Must be kept in sync with how the stream manager
+/// of compute nodes assigns `executor_id` and `operator_id`, and with `Keyspace`.
+pub fn state_key_prefixes(table_fragments: &TableFragments) -> Vec<Vec<u8>> {
+    fn visit(node: &StreamNode, actor_id: u32, fragment_id: u32, prefixes: &mut BTreeSet<Vec<u8>>) {
+        let executor_id = ((actor_id as u64) << 32) + node.operator_id;
+        let operator_id = ((fragment_id as u64) << 32) + node.operator_id;
+        prefixes.insert([&[b'e'][..], &executor_id.to_be_bytes()].concat());
+        prefixes.insert([&[b's'][..], &operator_id.to_be_bytes()].concat());
+        for input in &node.input {
+            visit(input, actor_id, fragment_id, prefixes);
+        }
+    }
+
+    let mut prefixes = BTreeSet::new();
+    prefixes.insert(
+        [
+            &[b't'][..],
+            &table_fragments.table_id().table_id().to_be_bytes(),
+        ]
+        .concat(),
+    );
+    for fragment in table_fragments.fragments() {
+        for actor in &fragment.actors {
+            if let Some(node) = &actor.nodes {
+                visit(node, actor.actor_id, fragment.fragment_id, &mut prefixes);
+            }
+        }
+    }
+
+    prefixes.into_iter().collect()
+}
+
+/// Returns the SSTs in `version` which may contain keys starting with any of `prefixes`.
+fn ssts_in_prefixes(version: &HummockVersion, prefixes: &[Vec<u8>]) -> Vec<SstableInfo> {
+    let overlaps = |sst: &SstableInfo| {
+        let key_range = match &sst.key_range {
+            Some(key_range) if !key_range.inf => key_range,
+            _ => return true,
+        };
+        let (left, right) = (user_key(&key_range.left), user_key(&key_range.right));
+        prefixes.iter().any(|prefix| {
+            let end = next_key(prefix);
+            right >= prefix.as_slice() && (end.is_empty() || left < end.as_slice())
+        })
+    };
+
+    version
+        .levels
+        .iter()
+        .flat_map(|level| level.table_infos.iter())
+        .filter(|sst| overlaps(sst))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use risingwave_common::catalog::TableId;
+    use risingwave_hummock_sdk::key::key_with_epoch;
+    use risingwave_pb::hummock::Level;
+    use risingwave_pb::meta::table_fragments::Fragment;
+    use risingwave_pb::stream_plan::StreamActor;
+
+    use super::*;
+
+    fn sst(id: u64, left: &[u8], right: &[u8]) -> SstableInfo {
+        SstableInfo {
+            id,
+            key_range: Some(KeyRange {
+                left: key_with_epoch(left.to_vec(), 1),
+                right: key_with_epoch(right.to_vec(), 1),
+                inf: false,
+            }),
+            keyspace_stats: vec![],
+        }
+    }
+
+    #[test]
+    fn test_export_mview_bundle() {
+        let actor = StreamActor {
+            actor_id: 2,
+            fragment_id: 1,
+            nodes: Some(StreamNode {
+                operator_id: 4,
+                input: vec![StreamNode {
+                    operator_id: 5,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let fragment = Fragment {
+            fragment_id: 1,
+            actors: vec![actor],
+            ..Default::default()
+        };
+        let table_fragments = TableFragments::new(TableId::new(3), BTreeMap::from([(1, fragment)]));
+
+        let prefixes = state_key_prefixes(&table_fragments);
+        assert_eq!(
+            prefixes,
+            vec![
+                b"e\x00\x00\x00\x02\x00\x00\x00\x04".to_vec(),
+                b"e\x00\x00\x00\x02\x00\x00\x00\x05".to_vec(),
+                b"s\x00\x00\x00\x01\x00\x00\x00\x04".to_vec(),
+                b"s\x00\x00\x00\x01\x00\x00\x00\x05".to_vec(),
+                b"t\x00\x00\x00\x03".to_vec(),
+            ]
+        );
+
+        let version = HummockVersion {
+            levels: vec![Level {
+                table_infos: vec![
+                    sst(1, b"a", b"b"),
+                    sst(2, b"e\x00\x00\x00\x02\x00\x00\x00\x04k", b"f"),
+                    sst(3, b"t\x00\x00\x00\x04", b"u"),
+                    sst(4, b"s", b"t\x00\x00\x00\x03\x00"),
+                ],
+                ..Default::default()
+            }],
+            id: 7,
+            max_committed_epoch: 42,
+            ..Default::default()
+        };
+        let bundle = export_mview_bundle(Table::default(), &table_fragments, &version);
+        assert_eq!(bundle.format_version, MVIEW_BUNDLE_FORMAT_VERSION);
+        assert_eq!(bundle.epoch, 42);
+        assert_eq!(bundle.version_id, 7);
+        assert_eq!(bundle.state_key_ranges.len(), 5);
+        assert_eq!(
+            bundle.ssts.iter().map(|sst| sst.id).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+    }
+}
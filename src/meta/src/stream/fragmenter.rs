@@ -19,13 +19,13 @@ use itertools::Itertools;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_pb::meta::table_fragments::fragment::{FragmentDistributionType, FragmentType};
 use risingwave_pb::meta::table_fragments::Fragment;
-use risingwave_pb::plan::JoinType;
 use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_pb::stream_plan::{
     DispatchStrategy, Dispatcher, DispatcherType, ExchangeNode, StreamNode,
 };
 
 use super::graph::StreamFragmentEdge;
+use super::rewrite::is_delta_join;
 use super::{CreateMaterializedViewContext, FragmentManagerRef};
 use crate::cluster::ParallelUnitId;
 use crate::manager::{IdCategory, IdGeneratorManagerRef};
@@ -173,7 +173,11 @@ where
             let input = match child_node.get_node()? {
                 // For stateful operators, set `exchange_flag = true`. If it's already true, force
                 // add an exchange.
-                Node::HashAggNode(_) | Node::HashJoinNode(_) => {
+                Node::HashAggNode(_)
+                | Node::HashJoinNode(_)
+                | Node::HashSetOpNode(_)
+                | Node::NestedLoopJoinNode(_)
+                | Node::DynamicFilterNode(_) => {
                     // We didn't make `fields` available on Java frontend yet, so we check if schema
                     // is available (by `child_node.fields.is_empty()`) before deciding to do the
                     // rewrite.
@@ -237,14 +241,27 @@ where
         current_fragment: &mut StreamFragment,
         mut stream_node: StreamNode,
     ) -> Result<StreamNode> {
+        // The joins marked by the frontend are rewritten into a delta join, together with the
+        // adjacent ones below.
+        if is_delta_join(&stream_node) {
+            return self.build_delta_join(current_fragment, stream_node);
+        }
+
         // Update current fragment based on the node we're visiting.
         match stream_node.get_node()? {
             Node::SourceNode(_) => current_fragment.fragment_type = FragmentType::Source,
 
+            // The rows of `VALUES` are emitted only once, by a single actor which receives the
+            // barriers like a source.
+            Node::ValuesNode(_) => {
+                current_fragment.fragment_type = FragmentType::Source;
+                current_fragment.is_singleton = true;
+            }
+
             Node::MaterializeNode(_) => current_fragment.fragment_type = FragmentType::Sink,
 
             // TODO: Force singleton for TopN as a workaround. We should implement two phase TopN.
-            Node::TopNNode(_) => current_fragment.is_singleton = true,
+            Node::TopNNode(_) | Node::AppendOnlyTopNNode(_) => current_fragment.is_singleton = true,
 
             // TODO: Remove this when we deprecate Java frontend.
             Node::ChainNode(_) => current_fragment.is_singleton = self.is_legacy_frontend,
@@ -291,20 +308,6 @@ where
                         Ok(child_node)
                     }
 
-                    // For HashJoin nodes, attempting to rewrite to delta joins only on inner join
-                    // with only equal conditions
-                    Node::HashJoinNode(hash_join_node)
-                        if hash_join_node.get_join_type()? == JoinType::Inner
-                            && hash_join_node.condition.is_none() =>
-                    {
-                        const ENABLE_DELTA_JOIN: bool = false;
-                        if ENABLE_DELTA_JOIN {
-                            self.build_delta_join(current_fragment, child_node)
-                        } else {
-                            self.build_fragment(current_fragment, child_node)
-                        }
-                    }
-
                     // For other children, visit recursively.
                     _ => self.build_fragment(current_fragment, child_node),
                 }
@@ -374,7 +377,20 @@ where
                 .expect("downstream fragment not processed yet")
                 .clone();
 
-            match dispatch_edge.dispatch_strategy.get_type()? {
+            let dispatch_type = match dispatch_edge.dispatch_strategy.get_type()? {
+                // A no-shuffle edge pairs upstream and downstream actors one by one. If the two
+                // fragments end up with different parallelism (e.g. one of them is a singleton),
+                // fall back to a hash shuffle on the distribution keys when they are known.
+                DispatcherType::NoShuffle
+                    if downstream_actors.len() != actor_ids.len()
+                        && !dispatch_edge.dispatch_strategy.column_indices.is_empty() =>
+                {
+                    DispatcherType::Hash
+                }
+                ty => ty,
+            };
+
+            match dispatch_type {
                 // Construct a consistent hash mapping of actors based on that of parallel units.
                 // Set the new mapping into hash dispatchers.
                 DispatcherType::Hash => {
@@ -420,9 +436,25 @@ where
                     );
                 }
 
-                ty @ (DispatcherType::Simple
-                | DispatcherType::Broadcast
-                | DispatcherType::NoShuffle) => {
+                // The distribution keys are only kept in the strategy for the fallback above. The
+                // dispatcher itself pipes chunks without looking at them.
+                DispatcherType::NoShuffle => {
+                    self.stream_graph.add_link(
+                        &actor_ids,
+                        &downstream_actors,
+                        dispatch_edge.link_id,
+                        Dispatcher {
+                            r#type: DispatcherType::NoShuffle.into(),
+                            column_indices: vec![],
+                            hash_mapping: None,
+                            downstream_actor_id: vec![],
+                        },
+                        dispatch_edge.same_worker_node,
+                        None,
+                    );
+                }
+
+                ty @ (DispatcherType::Simple | DispatcherType::Broadcast) => {
                     self.stream_graph.add_link(
                         &actor_ids,
                         &downstream_actors,
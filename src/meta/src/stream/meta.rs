@@ -95,6 +95,28 @@ where
         }
     }
 
+    /// Read-modify-writes the table fragments under the write lock, so that concurrent updates are
+    /// not lost. `f` modifies copies of all the table fragments and returns the ids of the changed
+    /// ones, which are then persisted in place of the current ones.
+    pub async fn modify_table_fragments<R>(
+        &self,
+        f: impl FnOnce(&mut [TableFragments]) -> Result<(R, HashSet<TableId>)>,
+    ) -> Result<R> {
+        let map = &mut self.core.write().await.table_fragments;
+
+        let mut all_table_fragments: Vec<_> = map.values().cloned().collect();
+        let (ret, updated_table_ids) = f(&mut all_table_fragments)?;
+        for table_fragments in all_table_fragments {
+            let table_id = table_fragments.table_id();
+            if updated_table_ids.contains(&table_id) {
+                table_fragments.insert(&*self.meta_store).await?;
+                map.insert(table_id, table_fragments);
+            }
+        }
+
+        Ok(ret)
+    }
+
     /// Start create a new `TableFragments` and insert it into meta store, currently the actors'
     /// state is `ActorState::Inactive`.
     pub async fn start_create_table_fragments(&self, table_fragment: TableFragments) -> Result<()> {
@@ -279,6 +301,21 @@ where
         actor_maps
     }
 
+    /// Returns the materialized view each actor belongs to.
+    pub async fn actor_table_ids(&self) -> HashMap<ActorId, TableId> {
+        let map = &self.core.read().await.table_fragments;
+
+        map.values()
+            .flat_map(|table_fragment| {
+                let table_id = table_fragment.table_id();
+                table_fragment
+                    .actor_ids()
+                    .into_iter()
+                    .map(move |actor_id| (actor_id, table_id))
+            })
+            .collect()
+    }
+
     pub async fn all_chain_actor_ids(&self) -> HashSet<ActorId> {
         let map = &self.core.read().await.table_fragments;
 
@@ -312,6 +349,20 @@ where
         }
     }
 
+    pub async fn select_table_fragments_by_table_id(
+        &self,
+        table_id: &TableId,
+    ) -> Result<TableFragments> {
+        let map = &self.core.read().await.table_fragments;
+        match map.get(table_id) {
+            Some(table_fragment) => Ok(table_fragment.clone()),
+            None => Err(RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))),
+        }
+    }
+
     pub async fn get_table_sink_actor_ids(&self, table_id: &TableId) -> Result<Vec<ActorId>> {
         let map = &self.core.read().await.table_fragments;
         match map.get(table_id) {
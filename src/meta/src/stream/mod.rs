@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod bundle;
 mod fragmenter;
 mod graph;
 mod meta;
@@ -23,6 +24,7 @@ mod stream_manager;
 #[cfg(test)]
 mod test_fragmenter;
 
+pub use bundle::*;
 pub use fragmenter::*;
 pub use meta::*;
 pub use scheduler::*;
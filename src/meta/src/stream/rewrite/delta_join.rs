@@ -12,17 +12,121 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risingwave_common::error::Result;
-use risingwave_pb::plan::Field;
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::expr::expr_node::{RexNode, Type};
+use risingwave_pb::expr::{ExprNode, InputRefExpr};
+use risingwave_pb::plan::{Field, JoinType};
 use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_pb::stream_plan::{
-    ArrangeNode, DispatchStrategy, DispatcherType, ExchangeNode, LookupNode, StreamNode, UnionNode,
+    ArrangeNode, DispatchStrategy, DispatcherType, ExchangeNode, LookupNode, ProjectNode,
+    StreamNode, UnionNode,
 };
 
+use crate::model::LocalFragmentId;
 use crate::storage::MetaStore;
 use crate::stream::graph::{StreamFragment, StreamFragmentEdge};
 use crate::stream::StreamFragmenter;
 
+/// A column of an input of a multi-way delta join, as `(input index, column index)`.
+type InputColumn = (usize, usize);
+
+/// A tree of delta joins flattened into a multi-way join.
+#[derive(Default)]
+struct MultiWayJoin {
+    /// The inputs below the joins, without the exchanges on top of them.
+    inputs: Vec<StreamNode>,
+
+    /// The equi-conditions of all the joins.
+    eq_conds: Vec<(InputColumn, InputColumn)>,
+}
+
+impl MultiWayJoin {
+    /// Flattens the delta joins in the tree rooted at `node` into `self`, returning where the
+    /// output columns of `node` come from.
+    fn flatten(&mut self, mut node: StreamNode) -> Vec<InputColumn> {
+        // The exchanges between the joins are for the distribution required by hash joins, which
+        // is rearranged by the lookups.
+        while matches!(node.node, Some(Node::ExchangeNode(_))) && node.input.len() == 1 {
+            node = node.input.remove(0);
+        }
+
+        match &node.node {
+            Some(Node::HashJoinNode(hash_join_node)) if is_delta_join(&node) => {
+                let left_key = hash_join_node.left_key.clone();
+                let right_key = hash_join_node.right_key.clone();
+                assert_eq!(node.input.len(), 2);
+                let right = node.input.pop().unwrap();
+                let left = node.input.pop().unwrap();
+
+                let left_columns = self.flatten(left);
+                let right_columns = self.flatten(right);
+                for (left, right) in left_key.iter().zip_eq(right_key.iter()) {
+                    self.eq_conds
+                        .push((left_columns[*left as usize], right_columns[*right as usize]));
+                }
+                // The output of an inner join is the left columns followed by the right ones.
+                left_columns.into_iter().chain(right_columns).collect()
+            }
+            _ => {
+                let input_idx = self.inputs.len();
+                let columns = (0..node.fields.len())
+                    .map(|column_idx| (input_idx, column_idx))
+                    .collect();
+                self.inputs.push(node);
+                columns
+            }
+        }
+    }
+
+    /// Picks the next input to look up with the changes of the `joined` inputs, whose columns are
+    /// laid out as `columns`. Returns the first input having equi-conditions with the joined
+    /// ones, along with the pairs of the stream key and the arrangement key.
+    fn next_lookup(
+        &self,
+        joined: &[usize],
+        columns: &[InputColumn],
+    ) -> Option<(usize, Vec<(usize, usize)>)> {
+        (0..self.inputs.len())
+            .filter(|input_idx| !joined.contains(input_idx))
+            .find_map(|input_idx| {
+                let keys = self
+                    .eq_conds
+                    .iter()
+                    .filter_map(|(left, right)| {
+                        let (stream_column, arrange_column) = if right.0 == input_idx {
+                            (left, right)
+                        } else if left.0 == input_idx {
+                            (right, left)
+                        } else {
+                            return None;
+                        };
+                        let stream_key = columns.iter().position(|c| c == stream_column)?;
+                        Some((stream_key, arrange_column.1))
+                    })
+                    .unique()
+                    .collect_vec();
+                (!keys.is_empty()).then(|| (input_idx, keys))
+            })
+    }
+}
+
+/// Whether the node is a hash join to be rewritten into a delta join. The fields are required to
+/// build the exchanges in between.
+pub(crate) fn is_delta_join(node: &StreamNode) -> bool {
+    match &node.node {
+        Some(Node::HashJoinNode(hash_join_node)) => {
+            hash_join_node.is_delta_join
+                && hash_join_node.join_type == JoinType::Inner as i32
+                && hash_join_node.condition.is_none()
+                && !node.fields.is_empty()
+        }
+        _ => false,
+    }
+}
+
 impl<S> StreamFragmenter<S>
 where
     S: MetaStore,
@@ -41,61 +145,23 @@ where
         }
     }
 
-    /// The ultimate exchange between delta join fragments and the upstream fragments.
-    ///
-    /// We should shuffle upstream data to make sure it meets the distribution requirement of hash
-    /// join.
-    fn build_input_with_exchange(
+    /// The exchange shuffling the output of `upstream` by `keys`, so that the rows are placed
+    /// together with the rows of the arrangements having the same keys.
+    fn build_hash_exchange_for_delta_join(
         &mut self,
-        mut upstream: StreamNode,
-    ) -> Result<(StreamFragmentEdge, StreamFragment, StreamNode)> {
-        match &upstream.node {
-            // If the upstream contains a exchange, we should follow that distribution.
-            Some(Node::ExchangeNode(exchange_node)) => {
-                let exchange_node = exchange_node.clone();
-                assert_eq!(upstream.input.len(), 1);
-                let child_node = upstream.input.remove(0);
-                let child_fragment = self.build_and_add_fragment(child_node)?;
-                Ok((
-                    StreamFragmentEdge {
-                        dispatch_strategy: exchange_node.get_strategy()?.clone(),
-                        same_worker_node: false,
-                        link_id: upstream.operator_id,
-                    },
-                    child_fragment,
-                    StreamNode {
-                        input: vec![],
-                        ..upstream.clone()
-                    },
-                ))
-            }
-            // Otherwise, use 1-to-1 exchange
-            _ => {
-                let strategy = Self::dispatch_no_shuffle();
-                let operator_id = self.gen_operator_id() as u64;
-                let node = StreamNode {
-                    input: vec![],
-                    identity: "Exchange (Arrange)".into(),
-                    fields: upstream.fields.clone(),
-                    pk_indices: upstream.pk_indices.clone(),
-                    node: Some(Node::ExchangeNode(ExchangeNode {
-                        strategy: Some(strategy.clone()),
-                    })),
-                    operator_id,
-                };
-
-                let child_fragment = self.build_and_add_fragment(upstream)?;
-
-                Ok((
-                    StreamFragmentEdge {
-                        dispatch_strategy: strategy,
-                        same_worker_node: false,
-                        link_id: operator_id,
-                    },
-                    child_fragment,
-                    node,
-                ))
-            }
+        upstream: (&[Field], &[u32]),
+        keys: &[usize],
+        identity: &str,
+    ) -> StreamNode {
+        StreamNode {
+            operator_id: self.gen_operator_id() as u64,
+            identity: identity.into(),
+            fields: upstream.0.to_vec(),
+            pk_indices: upstream.1.to_vec(),
+            node: Some(Node::ExchangeNode(ExchangeNode {
+                strategy: Some(Self::dispatch_hash(keys)),
+            })),
+            input: vec![],
         }
     }
 
@@ -123,6 +189,13 @@ where
         }
     }
 
+    fn dispatch_hash(keys: &[usize]) -> DispatchStrategy {
+        DispatchStrategy {
+            r#type: DispatcherType::Hash.into(),
+            column_indices: keys.iter().map(|key| *key as u32).collect(),
+        }
+    }
+
     fn build_lookup_for_delta_join(
         &mut self,
         (exchange_node_arrangement, exchange_node_stream): (&StreamNode, &StreamNode),
@@ -142,167 +215,268 @@ where
         }
     }
 
+    /// Reorders the output of the last lookup, whose columns are laid out as `columns`, into the
+    /// output of the joins.
+    fn build_project_for_delta_join(
+        &mut self,
+        lookup: StreamNode,
+        columns: &[InputColumn],
+        output_columns: &[InputColumn],
+        (output_fields, output_pk_indices): (Vec<Field>, Vec<u32>),
+    ) -> StreamNode {
+        let select_list = output_columns
+            .iter()
+            .map(|output_column| {
+                let column_idx = columns.iter().position(|c| c == output_column).unwrap();
+                ExprNode {
+                    expr_type: Type::InputRef as i32,
+                    return_type: lookup.fields[column_idx].data_type.clone(),
+                    rex_node: Some(RexNode::InputRef(InputRefExpr {
+                        column_idx: column_idx as i32,
+                    })),
+                }
+            })
+            .collect();
+        StreamNode {
+            operator_id: self.gen_operator_id() as u64,
+            identity: "Project (Delta Join Output)".into(),
+            fields: output_fields,
+            pk_indices: output_pk_indices,
+            node: Some(Node::ProjectNode(ProjectNode {
+                select_list,
+                stateful: false,
+            })),
+            input: vec![lookup],
+        }
+    }
+
+    /// Builds the fragment arranging `input` by `arrange_key`, which is shared by all the lookups
+    /// into it.
+    fn build_arrangement_fragment_for_delta_join(
+        &mut self,
+        input: &StreamNode,
+        input_fragment_id: LocalFragmentId,
+        arrange_key: &[usize],
+    ) -> Result<LocalFragmentId> {
+        let exchange = self.build_hash_exchange_for_delta_join(
+            (&input.fields, &input.pk_indices),
+            arrange_key,
+            "Exchange (Arrange)",
+        );
+        let arrange = self.build_arrange_for_delta_join(
+            &exchange,
+            arrange_key.iter().map(|key| *key as i32).collect(),
+        );
+        let arrange_fragment = self.build_and_add_fragment(arrange)?;
+        self.fragment_graph.add_edge(
+            input_fragment_id,
+            arrange_fragment.fragment_id,
+            StreamFragmentEdge {
+                dispatch_strategy: Self::dispatch_hash(arrange_key),
+                same_worker_node: false,
+                link_id: exchange.operator_id,
+            },
+        );
+        Ok(arrange_fragment.fragment_id)
+    }
+
     pub fn build_delta_join(
         &mut self,
         current_fragment: &mut StreamFragment,
-        mut node: StreamNode,
+        node: StreamNode,
     ) -> Result<StreamNode> {
-        let hash_join_node = match &node.node {
-            Some(Node::HashJoinNode(node)) => node,
+        let distribution_keys = match &node.node {
+            Some(Node::HashJoinNode(node)) => node.distribution_keys.clone(),
             _ => unreachable!(),
         };
+        let output_fields = node.fields.clone();
+        let output_pk_indices = node.pk_indices.clone();
 
-        assert_eq!(node.input.len(), 2);
-
-        // Previous plan:
+        // Previous plan, where the hash joins may be nested in any shape:
         //
         // ```
-        // input_1 --\
-        //            -- HashJoin --- output
-        // input_2 --/
+        // input_0 --\
+        //            -- HashJoin --\
+        // input_1 --/               -- HashJoin --- output
+        // input_2 -----------------/
         // ```
         //
         // Rewritten plan:
         //
         // ```
-        // input_1 --- Arrange --- Lookup ---\
-        //                      X            --- Merge --- output
-        // input_2 --- Arrange --- Lookup ---/
+        // input_0 --- Arrange(k0) --\
+        //         \------------------+-- Lookup(a1) -- Lookup(a2) -- Project --\
+        // input_1 --- Arrange(a1) --/                                          \
+        //         \                                                             -- Union --- output
+        //          --- Lookup(k0) -- Lookup(a2) -- Project ------------------- /
+        // input_2 --- Arrange(a2)                                            /
+        //         \--- Lookup(a1) -- Lookup(k0) -- Project -----------------/
         // ```
         //
-        // Within the whole process, the distribution doesn't get changed, so we always use
-        // NoShuffle as exchange type.
-        //
-        // TODO: support multi-way join.
-
-        let exchange_a0l0 = self.build_exchange_for_delta_join(&node.input[0]);
-        let exchange_a0l1 = self.build_exchange_for_delta_join(&node.input[0]);
-        let exchange_a1l0 = self.build_exchange_for_delta_join(&node.input[1]);
-        let exchange_a1l1 = self.build_exchange_for_delta_join(&node.input[1]);
-
-        let (link_i1a1, input_1_frag, exchange_i1a1) =
-            self.build_input_with_exchange(node.input.remove(1))?;
-
-        let (link_i0a0, input_0_frag, exchange_i0a0) =
-            self.build_input_with_exchange(node.input.remove(0))?;
+        // The changes of each input are looked up in the arrangements of the other inputs one by
+        // one, each of which joins a new input. An input is arranged once by each of the keys it's
+        // looked up by, and the arrangements are shared by the lookups of all the other inputs.
+        // The lookups of an input into the inputs before it read the previous epoch, and the ones
+        // into the inputs after it read the current epoch, so that the changes of the same epoch
+        // are joined exactly once.
+        let mut join = MultiWayJoin::default();
+        let output_columns = join.flatten(node);
 
-        let arrange_0 =
-            self.build_arrange_for_delta_join(&exchange_i0a0, hash_join_node.left_key.clone());
-        let arrange_1 =
-            self.build_arrange_for_delta_join(&exchange_i1a1, hash_join_node.right_key.clone());
+        let input_fragment_ids: Vec<_> = join
+            .inputs
+            .clone()
+            .into_iter()
+            .map(|input| -> Result<_> { Ok(self.build_and_add_fragment(input)?.fragment_id) })
+            .try_collect()?;
+        let mut arrangements = HashMap::new();
 
-        let arrange_0_frag = self.build_and_add_fragment(arrange_0)?;
-        let arrange_1_frag = self.build_and_add_fragment(arrange_1)?;
+        let mut union_inputs = vec![];
+        for (input_idx, input) in join.inputs.iter().enumerate() {
+            let mut joined = vec![input_idx];
+            let mut columns = (0..input.fields.len())
+                .map(|column_idx| (input_idx, column_idx))
+                .collect_vec();
+            let mut stream_fragment_id = input_fragment_ids[input_idx];
+            let mut stream_fields = input.fields.clone();
+            let mut stream_pk_indices = input.pk_indices.clone();
 
-        self.fragment_graph.add_edge(
-            input_0_frag.fragment_id,
-            arrange_0_frag.fragment_id,
-            link_i0a0,
-        );
-
-        self.fragment_graph.add_edge(
-            input_1_frag.fragment_id,
-            arrange_1_frag.fragment_id,
-            link_i1a1,
-        );
-
-        let lookup_0 = self.build_lookup_for_delta_join(
-            (&exchange_a0l0, &exchange_a1l0),
-            (node.fields.clone(), node.pk_indices.clone()),
-            LookupNode {
-                arrange_key: hash_join_node.left_key.clone(),
-                stream_key: hash_join_node.right_key.clone(),
-                use_current_epoch: false,
-                column_mapping: vec![], // TODO: fill column mapping
-            },
-        );
+            while joined.len() < join.inputs.len() {
+                let (arrange_idx, keys) = join.next_lookup(&joined, &columns).ok_or_else(|| {
+                    ErrorCode::InternalError(
+                        "inputs of delta join are not connected by equi-conditions".into(),
+                    )
+                })?;
+                let arrangement = &join.inputs[arrange_idx];
+                let (stream_key, arrange_key): (Vec<_>, Vec<_>) = keys.into_iter().unzip();
 
-        let lookup_1 = self.build_lookup_for_delta_join(
-            (&exchange_a1l1, &exchange_a0l1),
-            (node.fields.clone(), node.pk_indices.clone()),
-            LookupNode {
-                arrange_key: hash_join_node.right_key.clone(),
-                stream_key: hash_join_node.left_key.clone(),
-                use_current_epoch: true,
-                column_mapping: vec![], // TODO: fill column mapping
-            },
-        );
+                let arrange_fragment_id =
+                    match arrangements.get(&(arrange_idx, arrange_key.clone())) {
+                        Some(fragment_id) => *fragment_id,
+                        None => {
+                            let fragment_id = self.build_arrangement_fragment_for_delta_join(
+                                arrangement,
+                                input_fragment_ids[arrange_idx],
+                                &arrange_key,
+                            )?;
+                            arrangements.insert((arrange_idx, arrange_key.clone()), fragment_id);
+                            fragment_id
+                        }
+                    };
 
-        let lookup_0_frag = self.build_and_add_fragment(lookup_0)?;
-        let lookup_1_frag = self.build_and_add_fragment(lookup_1)?;
+                let exchange_arrangement = self.build_exchange_for_delta_join(arrangement);
+                let exchange_stream = self.build_hash_exchange_for_delta_join(
+                    (&stream_fields, &stream_pk_indices),
+                    &stream_key,
+                    "Exchange (Lookup)",
+                );
 
-        self.fragment_graph.add_edge(
-            arrange_0_frag.fragment_id,
-            lookup_0_frag.fragment_id,
-            StreamFragmentEdge {
-                dispatch_strategy: Self::dispatch_no_shuffle(),
-                same_worker_node: false,
-                link_id: exchange_a0l0.operator_id,
-            },
-        );
+                // The output of a lookup is the stream columns followed by the arrangement ones.
+                let lookup_fields = stream_fields
+                    .iter()
+                    .chain(arrangement.fields.iter())
+                    .cloned()
+                    .collect_vec();
+                let lookup_pk_indices = stream_pk_indices
+                    .iter()
+                    .copied()
+                    .chain(
+                        arrangement
+                            .pk_indices
+                            .iter()
+                            .map(|idx| idx + stream_fields.len() as u32),
+                    )
+                    .collect_vec();
+                let lookup = self.build_lookup_for_delta_join(
+                    (&exchange_arrangement, &exchange_stream),
+                    (lookup_fields.clone(), lookup_pk_indices.clone()),
+                    LookupNode {
+                        arrange_key: arrange_key.iter().map(|key| *key as i32).collect(),
+                        stream_key: stream_key.iter().map(|key| *key as i32).collect(),
+                        use_current_epoch: arrange_idx > input_idx,
+                        column_mapping: vec![],
+                    },
+                );
+                joined.push(arrange_idx);
+                columns.extend(
+                    (0..arrangement.fields.len()).map(|column_idx| (arrange_idx, column_idx)),
+                );
 
-        self.fragment_graph.add_edge(
-            arrange_0_frag.fragment_id,
-            lookup_1_frag.fragment_id,
-            StreamFragmentEdge {
-                dispatch_strategy: Self::dispatch_no_shuffle(),
-                same_worker_node: false,
-                link_id: exchange_a0l1.operator_id,
-            },
-        );
+                let lookup = if joined.len() == join.inputs.len() {
+                    self.build_project_for_delta_join(
+                        lookup,
+                        &columns,
+                        &output_columns,
+                        (output_fields.clone(), output_pk_indices.clone()),
+                    )
+                } else {
+                    lookup
+                };
+                let lookup_fragment = self.build_and_add_fragment(lookup)?;
 
-        self.fragment_graph.add_edge(
-            arrange_1_frag.fragment_id,
-            lookup_0_frag.fragment_id,
-            StreamFragmentEdge {
-                dispatch_strategy: Self::dispatch_no_shuffle(),
-                same_worker_node: false,
-                link_id: exchange_a1l0.operator_id,
-            },
-        );
+                self.fragment_graph.add_edge(
+                    arrange_fragment_id,
+                    lookup_fragment.fragment_id,
+                    StreamFragmentEdge {
+                        dispatch_strategy: Self::dispatch_no_shuffle(),
+                        same_worker_node: false,
+                        link_id: exchange_arrangement.operator_id,
+                    },
+                );
+                self.fragment_graph.add_edge(
+                    stream_fragment_id,
+                    lookup_fragment.fragment_id,
+                    StreamFragmentEdge {
+                        dispatch_strategy: Self::dispatch_hash(&stream_key),
+                        same_worker_node: false,
+                        link_id: exchange_stream.operator_id,
+                    },
+                );
 
-        self.fragment_graph.add_edge(
-            arrange_1_frag.fragment_id,
-            lookup_1_frag.fragment_id,
-            StreamFragmentEdge {
-                dispatch_strategy: Self::dispatch_no_shuffle(),
-                same_worker_node: false,
-                link_id: exchange_a1l1.operator_id,
-            },
-        );
+                stream_fragment_id = lookup_fragment.fragment_id;
+                stream_fields = lookup_fields;
+                stream_pk_indices = lookup_pk_indices;
+            }
 
-        let exchange_l0m = self.build_exchange_for_delta_join(&node);
-        let exchange_l1m = self.build_exchange_for_delta_join(&node);
+            // Shuffle the output back to the distribution of the join, if any.
+            let dispatch_strategy = if distribution_keys.is_empty() {
+                Self::dispatch_no_shuffle()
+            } else {
+                Self::dispatch_hash(
+                    &distribution_keys
+                        .iter()
+                        .map(|key| *key as usize)
+                        .collect_vec(),
+                )
+            };
+            let exchange = StreamNode {
+                operator_id: self.gen_operator_id() as u64,
+                identity: "Exchange (Union)".into(),
+                fields: output_fields.clone(),
+                pk_indices: output_pk_indices.clone(),
+                node: Some(Node::ExchangeNode(ExchangeNode {
+                    strategy: Some(dispatch_strategy.clone()),
+                })),
+                input: vec![],
+            };
+            self.fragment_graph.add_edge(
+                stream_fragment_id,
+                current_fragment.fragment_id,
+                StreamFragmentEdge {
+                    dispatch_strategy,
+                    same_worker_node: false,
+                    link_id: exchange.operator_id,
+                },
+            );
+            union_inputs.push(exchange);
+        }
 
-        let union = StreamNode {
+        Ok(StreamNode {
             operator_id: self.gen_operator_id() as u64,
             identity: "Union".into(),
-            fields: node.fields.clone(),
-            pk_indices: node.pk_indices.clone(),
+            fields: output_fields,
+            pk_indices: output_pk_indices,
             node: Some(Node::UnionNode(UnionNode {})),
-            input: vec![exchange_l0m.clone(), exchange_l1m.clone()],
-        };
-
-        self.fragment_graph.add_edge(
-            lookup_0_frag.fragment_id,
-            current_fragment.fragment_id,
-            StreamFragmentEdge {
-                dispatch_strategy: Self::dispatch_no_shuffle(),
-                same_worker_node: false,
-                link_id: exchange_l0m.operator_id,
-            },
-        );
-
-        self.fragment_graph.add_edge(
-            lookup_1_frag.fragment_id,
-            current_fragment.fragment_id,
-            StreamFragmentEdge {
-                dispatch_strategy: Self::dispatch_no_shuffle(),
-                same_worker_node: false,
-                link_id: exchange_l1m.operator_id,
-            },
-        );
-
-        Ok(union)
+            input: union_inputs,
+        })
     }
 }
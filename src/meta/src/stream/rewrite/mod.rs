@@ -13,3 +13,5 @@
 // limitations under the License.
 
 mod delta_join;
+
+pub(super) use delta_join::is_delta_join;
@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::future::try_join_all;
 use itertools::Itertools;
@@ -24,24 +25,33 @@ use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::Source;
 use risingwave_pb::common::worker_node::State::Running;
 use risingwave_pb::common::WorkerType;
+use risingwave_pb::data::barrier::Mutation;
+use risingwave_pb::data::{SourceChangeSplitMutation, SourceSplits};
+use risingwave_pb::stream_plan::StreamSourceState;
 use risingwave_pb::stream_service::{
     CreateSourceRequest as ComputeNodeCreateSourceRequest,
     DropSourceRequest as ComputeNodeDropSourceRequest,
 };
 
-use crate::barrier::BarrierManagerRef;
+use crate::barrier::{BarrierManagerRef, Command};
 use crate::cluster::ClusterManagerRef;
 use crate::manager::{CatalogManagerRef, MetaSrvEnv, SourceId, StreamClient};
 use crate::model::ActorId;
 use crate::storage::MetaStore;
+use crate::stream::FragmentManagerRef;
 
 pub type SourceManagerRef<S> = Arc<SourceManager<S>>;
 
+/// How often the splits of the sources are enumerated again to discover new ones.
+const SPLIT_DISCOVERY_INTERVAL: Duration = Duration::from_secs(30);
+
 #[allow(dead_code)]
 pub struct SourceManager<S: MetaStore> {
     env: MetaSrvEnv<S>,
     cluster_manager: ClusterManagerRef<S>,
+    barrier_manager: BarrierManagerRef<S>,
     catalog_manager: CatalogManagerRef<S>,
+    fragment_manager: FragmentManagerRef<S>,
 }
 
 impl<S> SourceManager<S>
@@ -51,13 +61,16 @@ where
     pub async fn new(
         env: MetaSrvEnv<S>,
         cluster_manager: ClusterManagerRef<S>,
-        _barrier_manager: BarrierManagerRef<S>,
+        barrier_manager: BarrierManagerRef<S>,
         catalog_manager: CatalogManagerRef<S>,
+        fragment_manager: FragmentManagerRef<S>,
     ) -> Result<Self> {
         Ok(Self {
             env,
             cluster_manager,
+            barrier_manager,
             catalog_manager,
+            fragment_manager,
         })
     }
 
@@ -73,6 +86,7 @@ where
 
         let properties = AnyhowProperties::new(info.properties.clone());
         SplitEnumeratorImpl::create(&properties)
+            .await
             .to_rw_result()?
             .list_splits()
             .await
@@ -180,8 +194,195 @@ where
         Ok(())
     }
 
-    pub async fn run(&self) -> Result<()> {
-        // todo: in the future, split change will be pushed as a long running service
+    /// Enumerates the splits of the sources read by streaming actors again, and assigns the new
+    /// ones, e.g. the partitions added to a Kafka topic or the shards of a resharded Kinesis
+    /// stream, to the actors by a `SourceChangeSplit` barrier. The assignment is also kept in the
+    /// source nodes so that it survives recovery.
+    pub async fn discover_splits(&self) -> Result<()> {
+        let source_ids: HashSet<SourceId> = self
+            .fragment_manager
+            .list_table_fragments()
+            .await?
+            .iter()
+            .flat_map(|table_fragments| table_fragments.stream_source_ids())
+            .collect();
+
+        let mut all_splits = HashMap::new();
+        for source_id in source_ids {
+            let source = self
+                .catalog_manager
+                .get_catalog_core_guard()
+                .await
+                .get_source(source_id)
+                .await?;
+            // The source is being dropped.
+            let source = match source {
+                Some(source) => source,
+                None => continue,
+            };
+            all_splits.insert(source_id, self.fetch_splits_for_source(&source).await?);
+        }
+
+        // The assignment is computed and kept in the source nodes under the lock of the fragment
+        // manager, so that concurrent updates of the table fragments are not lost.
+        let actor_splits = self
+            .fragment_manager
+            .modify_table_fragments(|all_table_fragments| {
+                let mut actor_splits = HashMap::new();
+                let mut updated_table_ids = HashSet::new();
+                for (source_id, splits) in &all_splits {
+                    for table_fragments in all_table_fragments.iter_mut() {
+                        let table_id = table_fragments.table_id();
+                        for source_nodes in table_fragments.source_fragment_nodes_mut(*source_id) {
+                            let assignment = source_nodes
+                                .iter()
+                                .map(|(actor_id, source_node)| {
+                                    Ok((
+                                        *actor_id,
+                                        decode_splits(&source_node.stream_source_state)?,
+                                    ))
+                                })
+                                .collect::<Result<HashMap<_, _>>>()?;
+                            let changed = assign_new_splits(splits, assignment);
+                            if changed.is_empty() {
+                                continue;
+                            }
+                            for (actor_id, source_node) in source_nodes {
+                                if let Some(splits) = changed.get(&actor_id) {
+                                    log::info!(
+                                        "assigning splits {:?} to source actor {}",
+                                        splits.iter().map(SplitImpl::id).collect_vec(),
+                                        actor_id
+                                    );
+                                    let state = encode_splits(splits);
+                                    actor_splits.insert(
+                                        actor_id,
+                                        SourceSplits {
+                                            split_type: state.split_type.clone(),
+                                            splits: state.stream_source_splits.clone(),
+                                        },
+                                    );
+                                    source_node.stream_source_state = Some(state);
+                                }
+                            }
+                            updated_table_ids.insert(table_id);
+                        }
+                    }
+                }
+                Ok((actor_splits, updated_table_ids))
+            })
+            .await?;
+        if actor_splits.is_empty() {
+            return Ok(());
+        }
+
+        // If the barrier fails, the actors are rebuilt from the source nodes by recovery, and read
+        // the new splits then.
+        self.barrier_manager
+            .run_command(Command::Plain(Mutation::Splits(
+                SourceChangeSplitMutation { actor_splits },
+            )))
+            .await?;
+
         Ok(())
     }
+
+    /// Discovers the new splits of the sources periodically.
+    pub async fn run(&self) -> Result<()> {
+        let mut ticker = tokio::time::interval(SPLIT_DISCOVERY_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.discover_splits().await {
+                log::warn!("failed to discover the splits of sources: {}", e);
+            }
+        }
+    }
+}
+
+/// Decodes the splits assigned to a source actor.
+fn decode_splits(state: &Option<StreamSourceState>) -> Result<Vec<SplitImpl>> {
+    match state {
+        Some(state) => state
+            .stream_source_splits
+            .iter()
+            .map(|split| SplitImpl::restore_from_bytes(state.split_type.clone(), split))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .to_rw_result(),
+        None => Ok(vec![]),
+    }
+}
+
+/// Encodes the splits assigned to a source actor.
+pub(crate) fn encode_splits(splits: &[SplitImpl]) -> StreamSourceState {
+    StreamSourceState {
+        split_type: splits
+            .first()
+            .map(|split| split.get_type())
+            .unwrap_or_default(),
+        stream_source_splits: splits
+            .iter()
+            .map(|split| split.to_string().unwrap().as_bytes().to_vec())
+            .collect(),
+    }
+}
+
+/// Assigns the splits that none of the actors read yet, each to the actor with the fewest splits,
+/// so that the splits being read stay where they are. Returns the whole assignment of the actors
+/// that get new splits.
+fn assign_new_splits(
+    splits: &[SplitImpl],
+    mut assignment: HashMap<ActorId, Vec<SplitImpl>>,
+) -> HashMap<ActorId, Vec<SplitImpl>> {
+    let assigned: HashSet<String> = assignment.values().flatten().map(SplitImpl::id).collect();
+    let mut changed = HashSet::new();
+    for split in splits {
+        if assigned.contains(&split.id()) {
+            continue;
+        }
+        // Ties are broken by the actor id to be deterministic.
+        let actor_id = match assignment
+            .iter()
+            .min_by_key(|(actor_id, splits)| (splits.len(), **actor_id))
+        {
+            Some((actor_id, _)) => *actor_id,
+            None => break,
+        };
+        assignment.get_mut(&actor_id).unwrap().push(split.clone());
+        changed.insert(actor_id);
+    }
+    assignment.retain(|actor_id, _| changed.contains(actor_id));
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kafka_split(partition: i32) -> SplitImpl {
+        let split = format!(
+            r#"{{"topic":"t","partition":{},"start_offset":null,"stop_offset":null}}"#,
+            partition
+        );
+        SplitImpl::restore_from_bytes("kafka".to_string(), split.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_assign_new_splits() {
+        let ids = |splits: &Vec<SplitImpl>| splits.iter().map(SplitImpl::id).collect_vec();
+        let splits = (0..5).map(kafka_split).collect_vec();
+        let assignment = HashMap::from([
+            (1, vec![splits[0].clone(), splits[2].clone()]),
+            (2, vec![splits[1].clone()]),
+        ]);
+
+        // The new splits go to the actors with the fewest splits.
+        let changed = assign_new_splits(&splits, assignment.clone());
+        assert_eq!(changed.len(), 2);
+        assert_eq!(ids(&changed[&1]), vec!["t-0", "t-2", "t-4"]);
+        assert_eq!(ids(&changed[&2]), vec!["t-1", "t-3"]);
+
+        // Nothing changes without new splits.
+        assert!(assign_new_splits(&splits[..3], assignment).is_empty());
+        assert!(assign_new_splits(&splits, HashMap::new()).is_empty());
+    }
 }
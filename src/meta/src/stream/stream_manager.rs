@@ -12,29 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 
 use itertools::Itertools;
 use log::{debug, info};
 use risingwave_common::catalog::TableId;
-use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::ErrorCode::{InternalError, ItemNotFound};
 use risingwave_common::error::{Result, ToRwResult};
 use risingwave_pb::catalog::Source;
 use risingwave_pb::common::{ActorInfo, WorkerType};
+use risingwave_pb::data::barrier::Mutation;
+use risingwave_pb::data::{SourceThrottle, ThrottleMutation};
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus};
+use risingwave_pb::meta::{Job, SlowBarrier, TableWriteStats};
 use risingwave_pb::stream_plan::stream_node::Node;
-use risingwave_pb::stream_plan::StreamSourceState;
 use risingwave_pb::stream_service::{
     BroadcastActorInfoTableRequest, BuildActorsRequest, HangingChannel, UpdateActorsRequest,
 };
 use uuid::Uuid;
 
+use super::source_manager::encode_splits;
 use super::ScheduledLocations;
 use crate::barrier::{BarrierManagerRef, Command};
 use crate::cluster::{ClusterManagerRef, WorkerId};
-use crate::manager::{MetaSrvEnv, StreamClientsRef};
+use crate::manager::{MetaSrvEnv, SourceId, StreamClientsRef};
 use crate::model::{ActorId, FragmentId, TableFragments};
 use crate::storage::MetaStore;
 use crate::stream::{FragmentManagerRef, Scheduler, SourceManagerRef};
@@ -196,13 +199,7 @@ where
                     );
 
                     if !splits.is_empty() {
-                        s.stream_source_state = Some(StreamSourceState {
-                            split_type: splits.first().unwrap().get_type(),
-                            stream_source_splits: splits
-                                .iter()
-                                .map(|split| split.to_string().unwrap().as_bytes().to_vec())
-                                .collect(),
-                        });
+                        s.stream_source_state = Some(encode_splits(splits));
                     }
                 }
             }
@@ -366,6 +363,87 @@ where
         Ok(())
     }
 
+    /// Returns the progress of the materialized views being created.
+    pub fn list_jobs(&self) -> Vec<Job> {
+        self.barrier_manager.list_jobs()
+    }
+
+    /// Returns the bytes written to the state store by each materialized view.
+    pub fn list_table_write_stats(&self) -> Vec<TableWriteStats> {
+        self.barrier_manager.list_table_write_stats()
+    }
+
+    /// Returns the latest barriers which took longer than the slow barrier threshold.
+    pub fn list_slow_barriers(&self) -> Vec<SlowBarrier> {
+        self.barrier_manager.list_slow_barriers()
+    }
+
+    /// Cancels the creation of the materialized view `table_id` by dropping it. The pending
+    /// creation then fails, which rolls back its catalog.
+    pub async fn cancel_job(&self, table_id: &TableId) -> Result<()> {
+        if !self.barrier_manager.is_creating(*table_id) {
+            return Err(ItemNotFound(format!(
+                "materialized view {} being created",
+                table_id.table_id
+            ))
+            .into());
+        }
+        self.drop_materialized_view(table_id).await
+    }
+
+    /// Limits the rate of the source `source_id` by a `Throttle` barrier. The limit is split evenly
+    /// among the actors reading from the source, and is also kept in their source nodes so that it
+    /// survives recovery.
+    pub async fn throttle_source(
+        &self,
+        source_id: SourceId,
+        throttle: &SourceThrottle,
+    ) -> Result<()> {
+        // The limits are kept in the source nodes under the lock of the fragment manager, so that
+        // concurrent updates of the table fragments are not lost.
+        let actors = self
+            .fragment_manager
+            .modify_table_fragments(|all_table_fragments| {
+                let mut updated_table_ids = HashSet::new();
+                let mut source_nodes = vec![];
+                for table_fragments in all_table_fragments.iter_mut() {
+                    let table_id = table_fragments.table_id();
+                    let nodes = table_fragments.source_nodes_mut(source_id);
+                    if !nodes.is_empty() {
+                        updated_table_ids.insert(table_id);
+                        source_nodes.extend(nodes);
+                    }
+                }
+                if source_nodes.is_empty() {
+                    return Err(ItemNotFound(format!("actors of source {}", source_id)).into());
+                }
+
+                let parallelism = source_nodes.len() as u64;
+                let split = |rate: u64| (rate + parallelism - 1) / parallelism;
+                let actor_throttle = SourceThrottle {
+                    rows_per_second: split(throttle.rows_per_second),
+                    bytes_per_second: split(throttle.bytes_per_second),
+                };
+                let actors = source_nodes
+                    .into_iter()
+                    .map(|(actor_id, source_node)| {
+                        source_node.throttle = Some(actor_throttle.clone());
+                        (actor_id, actor_throttle.clone())
+                    })
+                    .collect::<HashMap<_, _>>();
+                Ok((actors, updated_table_ids))
+            })
+            .await?;
+
+        self.barrier_manager
+            .run_command(Command::Plain(Mutation::Throttle(ThrottleMutation {
+                actors,
+            })))
+            .await?;
+
+        Ok(())
+    }
+
     /// Flush means waiting for the next barrier to collect.
     pub async fn flush(&self) -> Result<()> {
         let start = Instant::now();
@@ -380,6 +458,19 @@ where
 
         Ok(())
     }
+
+    /// Forces a checkpoint barrier immediately and waits for it to be collected.
+    pub async fn trigger_checkpoint(&self) -> Result<()> {
+        let start = Instant::now();
+
+        debug!("start triggering checkpoint");
+        self.barrier_manager.trigger_checkpoint().await?;
+
+        let elapsed = Instant::now().duration_since(start);
+        info!("checkpoint triggered in {:?}", elapsed);
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -587,6 +678,7 @@ mod tests {
                     cluster_manager.clone(),
                     barrier_manager.clone(),
                     catalog_manager.clone(),
+                    fragment_manager.clone(),
                 )
                 .await?,
             );
@@ -15,6 +15,7 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use assert_matches::assert_matches;
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::Result;
@@ -24,12 +25,14 @@ use risingwave_pb::expr::agg_call::{Arg, Type};
 use risingwave_pb::expr::expr_node::RexNode;
 use risingwave_pb::expr::expr_node::Type::{Add, GreaterThan, InputRef};
 use risingwave_pb::expr::{AggCall, ExprNode, FunctionCall, InputRefExpr};
-use risingwave_pb::plan::{ColumnOrder, DatabaseRefId, Field, OrderType, SchemaRefId, TableRefId};
+use risingwave_pb::plan::{
+    ColumnOrder, DatabaseRefId, Field, JoinType, OrderType, SchemaRefId, TableRefId,
+};
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_pb::stream_plan::{
-    DispatchStrategy, DispatcherType, ExchangeNode, FilterNode, MaterializeNode, ProjectNode,
-    SimpleAggNode, SourceNode, StreamNode,
+    DispatchStrategy, DispatcherType, ExchangeNode, FilterNode, HashJoinNode, MaterializeNode,
+    ProjectNode, SimpleAggNode, SourceNode, StreamNode,
 };
 
 use crate::manager::MetaSrvEnv;
@@ -73,6 +76,7 @@ fn make_sum_aggcall(idx: i32) -> AggCall {
             ..Default::default()
         }),
         distinct: false,
+        order_by_fields: vec![],
     }
 }
 
@@ -124,6 +128,9 @@ fn make_stream_node() -> StreamNode {
             column_ids: vec![1, 2, 0],
             source_type: SourceType::Table as i32,
             stream_source_state: None,
+            throttle: None,
+            event_time_expr: None,
+            event_time_idx: 0,
         })),
         pk_indices: vec![2],
         ..Default::default()
@@ -229,6 +236,7 @@ fn make_stream_node() -> StreamNode {
                 make_inputref(0),
                 make_inputref(1),
             ],
+            stateful: false,
         })),
         fields: vec![], // TODO: fill this later
         input: vec![simple_agg_node_1],
@@ -247,6 +255,7 @@ fn make_stream_node() -> StreamNode {
             column_ids: vec![0_i32, 1_i32],
             column_orders: vec![make_column_order(1), make_column_order(2)],
             distribution_keys: Default::default(),
+            overwrite: None,
         })),
         fields: vec![], // TODO: fill this later
         operator_id: 7,
@@ -333,3 +342,158 @@ async fn test_fragmenter() -> Result<()> {
 
     Ok(())
 }
+
+fn make_source_node(table_id: i32) -> StreamNode {
+    StreamNode {
+        node: Some(Node::SourceNode(SourceNode {
+            table_ref_id: Some(make_table_ref_id(table_id)),
+            column_ids: vec![1, 2, 0],
+            source_type: SourceType::Table as i32,
+            stream_source_state: None,
+            throttle: None,
+            event_time_expr: None,
+            event_time_idx: 0,
+        })),
+        fields: vec![
+            make_field(TypeName::Int32),
+            make_field(TypeName::Int32),
+            make_field(TypeName::Int64),
+        ],
+        pk_indices: vec![2],
+        operator_id: table_id as u64,
+        identity: "SourceExecutor".to_string(),
+        ..Default::default()
+    }
+}
+
+fn make_delta_join_node(
+    left: StreamNode,
+    right: StreamNode,
+    (left_key, right_key): (i32, i32),
+    operator_id: u64,
+) -> StreamNode {
+    let fields = left
+        .fields
+        .iter()
+        .chain(right.fields.iter())
+        .cloned()
+        .collect_vec();
+    let pk_indices = left
+        .pk_indices
+        .iter()
+        .copied()
+        .chain(
+            right
+                .pk_indices
+                .iter()
+                .map(|idx| idx + left.fields.len() as u32),
+        )
+        .collect_vec();
+    StreamNode {
+        node: Some(Node::HashJoinNode(HashJoinNode {
+            join_type: JoinType::Inner as i32,
+            left_key: vec![left_key],
+            right_key: vec![right_key],
+            condition: None,
+            distribution_keys: vec![],
+            is_append_only: false,
+            is_delta_join: true,
+        })),
+        fields,
+        input: vec![left, right],
+        pk_indices,
+        operator_id,
+        identity: "HashJoinExecutor".to_string(),
+    }
+}
+
+/// Builds the plan of a 3-way join lowered into a delta join:
+/// ```sql
+/// create materialized view mv with (delta_join = 'true') as
+///     select * from a join b on a.v1 = b.v1 join c on b.v1 = c.v1;
+/// ```
+fn make_delta_join_stream_node() -> StreamNode {
+    let join_ab = make_delta_join_node(make_source_node(1), make_source_node(2), (0, 0), 4);
+    let join_abc = make_delta_join_node(join_ab, make_source_node(3), (3, 0), 5);
+
+    StreamNode {
+        pk_indices: join_abc.pk_indices.clone(),
+        fields: join_abc.fields.clone(),
+        input: vec![join_abc],
+        node: Some(Node::MaterializeNode(MaterializeNode {
+            table_ref_id: Some(make_table_ref_id(4)),
+            associated_table_ref_id: None,
+            column_ids: (0..9).collect(),
+            column_orders: vec![
+                make_column_order(2),
+                make_column_order(5),
+                make_column_order(8),
+            ],
+            distribution_keys: Default::default(),
+            overwrite: None,
+        })),
+        operator_id: 6,
+        identity: "MaterializeExecutor".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_fragmenter_delta_join() -> Result<()> {
+    let env = MetaSrvEnv::for_test().await;
+    let stream_node = make_delta_join_stream_node();
+    let fragment_manager = Arc::new(FragmentManager::new(env.meta_store_ref()).await?);
+    let hash_mapping = (1..5).flat_map(|id| vec![id; 512]).collect_vec();
+    let fragmenter = StreamFragmenter::new(
+        env.id_gen_manager_ref(),
+        fragment_manager,
+        hash_mapping,
+        false,
+    );
+
+    let mut ctx = CreateMaterializedViewContext::default();
+    let graph = fragmenter.generate_graph(&stream_node, &mut ctx).await?;
+    let table_fragments = TableFragments::new(TableId::default(), graph);
+
+    // Each input is arranged once and shared by the lookups of the other two inputs, whose
+    // changes are looked up twice and then projected to the output of the joins.
+    let fragment_roots = table_fragments
+        .fragments()
+        .iter()
+        .map(|fragment| {
+            assert_eq!(fragment.actors.len(), 4);
+            match fragment.actors[0].get_nodes()?.get_node()? {
+                Node::SourceNode(_) => Ok("Source"),
+                Node::ArrangeNode(_) => Ok("Arrange"),
+                Node::LookupNode(_) => Ok("Lookup"),
+                Node::ProjectNode(_) => Ok("Project"),
+                Node::MaterializeNode(_) => Ok("Materialize"),
+                node => panic!("unexpected fragment {:?}", node),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .counts();
+    assert_eq!(
+        fragment_roots,
+        HashMap::from([
+            ("Source", 3),
+            ("Arrange", 3),
+            ("Lookup", 3),
+            ("Project", 3),
+            ("Materialize", 1),
+        ])
+    );
+
+    // The outputs of the three inputs are merged by a union.
+    let sink_actor = table_fragments
+        .actors()
+        .into_iter()
+        .find(|actor| table_fragments.sink_actor_ids()[0] == actor.actor_id)
+        .unwrap();
+    let union = &sink_actor.get_nodes()?.input[0];
+    assert_matches!(union.get_node()?, Node::UnionNode(_));
+    assert_eq!(union.input.len(), 3);
+    assert_eq!(union.fields, stream_node.fields);
+
+    Ok(())
+}
@@ -28,6 +28,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "meta",
         "plan",
         "task_service",
+        "serving_service",
         "stream_plan",
         "stream_service",
         "hummock",
@@ -17,6 +17,8 @@ pub mod plan;
 #[rustfmt::skip]
 pub mod task_service;
 #[rustfmt::skip]
+pub mod serving_service;
+#[rustfmt::skip]
 pub mod stream_plan;
 #[rustfmt::skip]
 pub mod stream_service;
@@ -48,6 +50,9 @@ pub mod plan_serde;
 #[path = "task_service.serde.rs"]
 pub mod task_service_serde;
 #[rustfmt::skip]
+#[path = "serving_service.serde.rs"]
+pub mod serving_service_serde;
+#[rustfmt::skip]
 #[path = "stream_plan.serde.rs"]
 pub mod stream_plan_serde;
 #[rustfmt::skip]
@@ -12,11 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::env;
+
 use anyhow::{anyhow, Result};
 use regex::Regex;
 use yaml_rust::Yaml;
 
-/// Expands `x-${port}` to `x-2333`.
+/// Expands `x-${port}` to `x-2333`, and `${env:AWS_SECRET_ACCESS_KEY}` to the value of the
+/// environment variable, so that secrets are not written in the config.
 pub struct DollarExpander {
     re: Regex,
 }
@@ -47,10 +50,15 @@ impl DollarExpander {
                         for cap in self.re.captures_iter(v) {
                             let cap = cap.get(1).unwrap();
                             let name = cap.as_str();
-                            let value = yaml_to_string(
-                                y.get(&Yaml::String(name.to_string()))
-                                    .ok_or_else(|| anyhow!("{} not found in {:?}", name, y))?,
-                            )?;
+                            let value = match name.strip_prefix("env:") {
+                                Some(var) => env::var(var).map_err(|e| {
+                                    anyhow!("failed to read environment variable {}: {}", var, e)
+                                })?,
+                                None => yaml_to_string(
+                                    y.get(&Yaml::String(name.to_string()))
+                                        .ok_or_else(|| anyhow!("{} not found in {:?}", name, y))?,
+                                )?,
+                            };
                             target += &v[last_location..(cap.start() - 2)]; // ignore `${`
                             target += &value;
                             last_location = cap.end() + 1; // ignore `}`
@@ -111,4 +119,34 @@ a:
 
         assert_eq!(visitor.visit(yaml).unwrap(), yaml_result);
     }
+
+    #[test]
+    fn test_expand_env() {
+        env::set_var("RISEDEV_TEST_SECRET", "hummock");
+        let yaml = YamlLoader::load_from_str(
+            "
+a:
+  b: \"${env:RISEDEV_TEST_SECRET}-${x}\"
+  x: 2333
+    ",
+        )
+        .unwrap()
+        .remove(0);
+        let yaml_result = YamlLoader::load_from_str(
+            "
+a:
+  b: \"hummock-2333\"
+  x: 2333
+    ",
+        )
+        .unwrap()
+        .remove(0);
+        let mut visitor = DollarExpander::new();
+        assert_eq!(visitor.visit(yaml).unwrap(), yaml_result);
+
+        let yaml = YamlLoader::load_from_str("a: \"${env:RISEDEV_TEST_NOT_SET}\"")
+            .unwrap()
+            .remove(0);
+        assert!(visitor.visit(yaml).is_err());
+    }
 }
@@ -32,6 +32,10 @@ pub struct ComputeNodeConfig {
     pub provide_jaeger: Option<Vec<JaegerConfig>>,
     pub user_managed: bool,
     pub enable_in_memory_kv_state_backend: bool,
+    pub enable_compactor: bool,
+    pub config_path: String,
+    pub cpu_list: String,
+    pub memory_limit_bytes: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -51,6 +55,7 @@ pub struct MetaNodeConfig {
     pub provide_etcd_backend: Option<Vec<EtcdConfig>>,
     pub enable_dashboard_v2: bool,
     pub unsafe_disable_recovery: bool,
+    pub barrier_interval_ms: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
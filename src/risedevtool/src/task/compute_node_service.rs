@@ -19,7 +19,7 @@ use std::process::Command;
 use anyhow::{anyhow, Result};
 
 use super::{ExecuteContext, Task};
-use crate::util::{get_program_args, get_program_env_cmd, get_program_name};
+use crate::util::{get_program_args, get_program_env_cmd, get_program_name, limit_resources};
 use crate::ComputeNodeConfig;
 
 pub struct ComputeNodeService {
@@ -54,7 +54,7 @@ impl Task for ComputeNodeService {
         cmd.env("RUST_BACKTRACE", "1");
 
         cmd.arg("--config-path")
-            .arg(Path::new(&prefix_config).join("risingwave.toml"))
+            .arg(Path::new(&prefix_config).join(&self.config.config_path))
             .arg("--host")
             .arg(format!("{}:{}", self.config.address, self.config.port))
             .arg("--prometheus-listener-addr")
@@ -65,6 +65,10 @@ impl Task for ComputeNodeService {
             .arg("--metrics-level")
             .arg("1");
 
+        if !self.config.enable_compactor {
+            cmd.arg("--disable-compactor");
+        }
+
         let provide_jaeger = self.config.provide_jaeger.as_ref().unwrap();
         match provide_jaeger.len() {
             0 => {}
@@ -151,6 +155,8 @@ impl Task for ComputeNodeService {
             }
         };
 
+        let cmd = limit_resources(cmd, &self.config.cpu_list, self.config.memory_limit_bytes);
+
         if !self.config.user_managed {
             ctx.run_command(ctx.tmux_run(cmd)?)?;
             ctx.pb.set_message("started");
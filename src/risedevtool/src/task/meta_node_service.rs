@@ -89,6 +89,9 @@ impl Task for MetaNodeService {
             cmd.arg("--disable-recovery");
         }
 
+        cmd.arg("--barrier-interval-ms")
+            .arg(self.config.barrier_interval_ms.to_string());
+
         if !self.config.user_managed {
             ctx.run_command(ctx.tmux_run(cmd)?)?;
             ctx.pb.set_message("started");
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ffi::OsString;
 use std::process::Command;
 
 use indicatif::{ProgressBar, ProgressStyle};
@@ -41,6 +42,41 @@ pub fn get_program_env_cmd(cmd: &Command) -> String {
         .join("\n")
 }
 
+/// Runs the program of `cmd` pinned to the CPUs in `cpu_list` (e.g. `0-3`) with `taskset`, and
+/// with its data segment, i.e. the heap, limited to `memory_limit_bytes` with `prlimit`. An empty
+/// `cpu_list` or a zero `memory_limit_bytes` means no limit.
+pub fn limit_resources(cmd: Command, cpu_list: &str, memory_limit_bytes: u64) -> Command {
+    let mut wrapper: Vec<OsString> = vec![];
+    if !cpu_list.is_empty() {
+        wrapper.extend(["taskset".into(), "-c".into(), cpu_list.into()]);
+    }
+    if memory_limit_bytes > 0 {
+        wrapper.extend([
+            "prlimit".into(),
+            format!("--data={}", memory_limit_bytes).into(),
+        ]);
+    }
+    if wrapper.is_empty() {
+        return cmd;
+    }
+
+    let mut limited = Command::new(&wrapper[0]);
+    limited
+        .args(&wrapper[1..])
+        .arg(cmd.get_program())
+        .args(cmd.get_args());
+    for (k, v) in cmd.get_envs() {
+        match v {
+            Some(v) => limited.env(k, v),
+            None => limited.env_remove(k),
+        };
+    }
+    if let Some(dir) = cmd.get_current_dir() {
+        limited.current_dir(dir);
+    }
+    limited
+}
+
 pub fn new_spinner() -> ProgressBar {
     let pb = ProgressBar::new(0);
     pb.set_style(ProgressStyle::default_spinner().template("{spinner} {prefix}: {msg}"));
@@ -54,3 +90,38 @@ pub fn complete_spin(pb: &ProgressBar) {
 pub fn fail_spin(pb: &ProgressBar) {
     pb.set_style(ProgressStyle::default_spinner().template("❗ {prefix}: {msg}"));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_resources() {
+        let mut cmd = Command::new("compute-node");
+        cmd.env("RUST_BACKTRACE", "1")
+            .arg("--host")
+            .arg("127.0.0.1:5688");
+
+        let limited = limit_resources(cmd, "0-3", 1 << 30);
+        assert_eq!(get_program_name(&limited), "taskset");
+        assert_eq!(
+            limited
+                .get_args()
+                .map(|arg| arg.to_str().unwrap())
+                .collect_vec(),
+            vec![
+                "-c",
+                "0-3",
+                "prlimit",
+                "--data=1073741824",
+                "compute-node",
+                "--host",
+                "127.0.0.1:5688"
+            ]
+        );
+        assert_eq!(get_program_env_cmd(&limited), "export RUST_BACKTRACE=1");
+
+        let limited = limit_resources(Command::new("compute-node"), "", 0);
+        assert_eq!(get_program_name(&limited), "compute-node");
+    }
+}
@@ -17,6 +17,7 @@ use std::time::Duration;
 
 use futures::StreamExt;
 use log::trace;
+use risingwave_common::array::stream_chunk::STREAM_EXCHANGE_PROTOCOL_VERSION;
 use risingwave_common::array::DataChunk;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, ToRwResult};
@@ -88,6 +89,7 @@ impl ComputeClient {
             .get_stream(GetStreamRequest {
                 up_fragment_id,
                 down_fragment_id,
+                protocol_version: STREAM_EXCHANGE_PROTOCOL_VERSION,
             })
             .await
             .to_rw_result_with(|| {
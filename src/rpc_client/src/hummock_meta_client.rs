@@ -13,11 +13,13 @@
 // limitations under the License.
 
 use async_trait::async_trait;
-use risingwave_common::error::Result;
+use risingwave_common::error::{Result, ToRwResult};
 use risingwave_hummock_sdk::{HummockEpoch, HummockSSTableId, HummockVersionId};
 use risingwave_pb::hummock::{
-    CompactTask, HummockVersion, SstableInfo, SubscribeCompactTasksResponse, VacuumTask,
+    CompactTask, HummockVersion, SstableInfo, SubscribeCompactTasksResponse,
+    SubscribeVersionDeltasResponse, VacuumTask,
 };
+use tokio::sync::mpsc::Receiver;
 use tonic::Streaming;
 
 #[async_trait]
@@ -37,4 +39,32 @@ pub trait HummockMetaClient: Send + Sync + 'static {
     async fn abort_epoch(&self, epoch: HummockEpoch) -> Result<()>;
     async fn subscribe_compact_tasks(&self) -> Result<Streaming<SubscribeCompactTasksResponse>>;
     async fn report_vacuum_task(&self, vacuum_task: VacuumTask) -> Result<()>;
+    /// Subscribes to the versions greater than `last_pinned`, each of which is pinned before
+    /// being pushed.
+    async fn subscribe_version_deltas(
+        &self,
+        last_pinned: HummockVersionId,
+    ) -> Result<Box<dyn VersionDeltaStream>>;
+}
+
+#[async_trait]
+pub trait VersionDeltaStream: Send {
+    /// Ok(Some) => receive a `SubscribeVersionDeltasResponse`.
+    /// Ok(None) => stream terminates.
+    /// Err => error happens.
+    async fn next(&mut self) -> Result<Option<SubscribeVersionDeltasResponse>>;
+}
+
+#[async_trait]
+impl VersionDeltaStream for Streaming<SubscribeVersionDeltasResponse> {
+    async fn next(&mut self) -> Result<Option<SubscribeVersionDeltasResponse>> {
+        self.message().await.to_rw_result()
+    }
+}
+
+#[async_trait]
+impl VersionDeltaStream for Receiver<Result<SubscribeVersionDeltasResponse>> {
+    async fn next(&mut self) -> Result<Option<SubscribeVersionDeltasResponse>> {
+        self.recv().await.transpose()
+    }
 }
@@ -32,4 +32,6 @@ pub use meta_client::{GrpcMetaClient, MetaClient, NotificationStream};
 mod compute_client;
 pub use compute_client::{ComputeClient, ExchangeSource, GrpcExchangeSource};
 mod hummock_meta_client;
-pub use hummock_meta_client::HummockMetaClient;
+pub use hummock_meta_client::{HummockMetaClient, VersionDeltaStream};
+mod metrics;
+pub use metrics::MetaClientMetrics;
@@ -12,12 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use std::fmt::Debug;
-use std::time::Duration;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use paste::paste;
 use risingwave_common::catalog::{CatalogVersion, TableId};
-use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::ErrorCode::{self, InternalError};
 use risingwave_common::error::{Result, ToRwResult};
 use risingwave_common::try_match_expand;
 use risingwave_common::util::addr::HostAddr;
@@ -25,7 +27,8 @@ use risingwave_hummock_sdk::{HummockEpoch, HummockSSTableId, HummockVersionId};
 use risingwave_pb::catalog::{
     Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
 };
-use risingwave_pb::common::{WorkerNode, WorkerType};
+use risingwave_pb::common::{status, WorkerNode, WorkerType};
+use risingwave_pb::data::SourceThrottle;
 use risingwave_pb::ddl_service::ddl_service_client::DdlServiceClient;
 use risingwave_pb::ddl_service::{
     CreateDatabaseRequest, CreateDatabaseResponse, CreateMaterializedSourceRequest,
@@ -33,36 +36,49 @@ use risingwave_pb::ddl_service::{
     CreateMaterializedViewResponse, CreateSchemaRequest, CreateSchemaResponse, CreateSourceRequest,
     CreateSourceResponse, DropMaterializedSourceRequest, DropMaterializedSourceResponse,
     DropMaterializedViewRequest, DropMaterializedViewResponse, DropSourceRequest,
-    DropSourceResponse,
+    DropSourceResponse, ExportMaterializedViewRequest, ExportMaterializedViewResponse,
+    MaterializedViewBundle,
 };
 use risingwave_pb::hummock::hummock_manager_service_client::HummockManagerServiceClient;
 use risingwave_pb::hummock::{
     AbortEpochRequest, AbortEpochResponse, AddTablesRequest, AddTablesResponse, CommitEpochRequest,
-    CommitEpochResponse, CompactTask, GetNewTableIdRequest, GetNewTableIdResponse, HummockSnapshot,
-    HummockVersion, PinSnapshotRequest, PinSnapshotResponse, PinVersionRequest, PinVersionResponse,
+    CommitEpochResponse, CompactTask, GetKeyspaceStatsRequest, GetKeyspaceStatsResponse,
+    GetNewTableIdRequest, GetNewTableIdResponse, HummockSnapshot, HummockVersion, KeyspaceStats,
+    PinSnapshotRequest, PinSnapshotResponse, PinVersionRequest, PinVersionResponse,
     ReportCompactionTasksRequest, ReportCompactionTasksResponse, ReportVacuumTaskRequest,
     ReportVacuumTaskResponse, SstableInfo, SubscribeCompactTasksRequest,
-    SubscribeCompactTasksResponse, UnpinSnapshotRequest, UnpinSnapshotResponse,
-    UnpinVersionRequest, UnpinVersionResponse, VacuumTask,
+    SubscribeCompactTasksResponse, SubscribeVersionDeltasRequest, SubscribeVersionDeltasResponse,
+    UnpinSnapshotRequest, UnpinSnapshotResponse, UnpinVersionRequest, UnpinVersionResponse,
+    VacuumTask,
 };
 use risingwave_pb::meta::catalog_service_client::CatalogServiceClient;
 use risingwave_pb::meta::cluster_service_client::ClusterServiceClient;
+use risingwave_pb::meta::event::EventType;
+use risingwave_pb::meta::event_log_service_client::EventLogServiceClient;
 use risingwave_pb::meta::heartbeat_service_client::HeartbeatServiceClient;
 use risingwave_pb::meta::notification_service_client::NotificationServiceClient;
 use risingwave_pb::meta::stream_manager_service_client::StreamManagerServiceClient;
 use risingwave_pb::meta::{
     ActivateWorkerNodeRequest, ActivateWorkerNodeResponse, AddWorkerNodeRequest,
-    AddWorkerNodeResponse, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, FlushRequest,
-    FlushResponse, HeartbeatRequest, HeartbeatResponse, ListAllNodesRequest, ListAllNodesResponse,
-    SubscribeRequest, SubscribeResponse,
+    AddWorkerNodeResponse, CancelJobRequest, CancelJobResponse, DeleteWorkerNodeRequest,
+    DeleteWorkerNodeResponse, Event, FlushRequest, FlushResponse, HeartbeatRequest,
+    HeartbeatResponse, Job, ListAllNodesRequest, ListAllNodesResponse, ListJobsRequest,
+    ListJobsResponse, ListSlowBarriersRequest, ListSlowBarriersResponse, ListTableFragmentsRequest,
+    ListTableFragmentsResponse, ListTableWriteStatsRequest, ListTableWriteStatsResponse,
+    ReportEventRequest, ReportEventResponse, SlowBarrier, SubscribeRequest, SubscribeResponse,
+    TableFragments as ProstTableFragments, TableWriteStats, ThrottleSourceRequest,
+    ThrottleSourceResponse, TriggerCheckpointRequest, TriggerCheckpointResponse,
 };
 use risingwave_pb::stream_plan::StreamNode;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
 use tokio::task::JoinHandle;
+use tokio_retry::strategy::{jitter, ExponentialBackoff};
+use tokio_retry::RetryIf;
 use tonic::transport::{Channel, Endpoint};
-use tonic::{Status, Streaming};
+use tonic::{Code, Request, Response, Status, Streaming};
 
-use crate::hummock_meta_client::HummockMetaClient;
+use crate::hummock_meta_client::{HummockMetaClient, VersionDeltaStream};
+use crate::MetaClientMetrics;
 
 type DatabaseId = u32;
 type SchemaId = u32;
@@ -87,6 +103,11 @@ impl MetaClient {
         self.worker_id = Some(worker_id);
     }
 
+    /// Report the latency and retries of the RPCs to `metrics`, instead of an unused registry.
+    pub fn set_metrics(&mut self, metrics: Arc<MetaClientMetrics>) {
+        self.inner.metrics = metrics;
+    }
+
     pub fn worker_id(&self) -> u32 {
         self.worker_id.expect("worker node id is not set.")
     }
@@ -220,6 +241,26 @@ impl MetaClient {
         Ok(resp.version)
     }
 
+    /// Exports the materialized view with its state. The hummock version of the bundle is pinned
+    /// for this worker, and must be unpinned with `unpin_version` once its SSTs are copied.
+    pub async fn export_materialized_view(
+        &self,
+        table_id: TableId,
+    ) -> Result<MaterializedViewBundle> {
+        let request = ExportMaterializedViewRequest {
+            table_id: table_id.table_id(),
+            context_id: self.worker_id(),
+        };
+        let resp = self.inner.export_materialized_view(request).await?;
+        if let Some(status) = resp.status {
+            if status.code != status::Code::Ok as i32 {
+                return Err(ErrorCode::RemoteError(status).into());
+            }
+        }
+        resp.bundle
+            .ok_or_else(|| InternalError("no bundle in export response".to_string()).into())
+    }
+
     /// Unregister the current node to the cluster.
     pub async fn unregister(&self, addr: HostAddr) -> Result<()> {
         let request = DeleteWorkerNodeRequest {
@@ -246,6 +287,32 @@ impl MetaClient {
         Ok(resp.nodes)
     }
 
+    /// Report a cluster event observed on node `addr` to meta, which exposes it as
+    /// `rw_catalog.rw_events`.
+    pub async fn report_event(
+        &self,
+        event_type: EventType,
+        object_id: u32,
+        addr: &HostAddr,
+        info: String,
+    ) -> Result<()> {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let request = ReportEventRequest {
+            event: Some(Event {
+                timestamp_ms,
+                event_type: event_type as i32,
+                object_id,
+                node: addr.to_string(),
+                info,
+            }),
+        };
+        self.inner.report_event(request).await?;
+        Ok(())
+    }
+
     pub fn start_heartbeat_loop(
         meta_client: MetaClient,
         min_interval: Duration,
@@ -289,6 +356,57 @@ impl MetaClient {
         self.inner.flush(request).await?;
         Ok(())
     }
+
+    pub async fn trigger_checkpoint(&self) -> Result<()> {
+        let request = TriggerCheckpointRequest::default();
+        self.inner.trigger_checkpoint(request).await?;
+        Ok(())
+    }
+
+    pub async fn list_table_fragments(&self) -> Result<Vec<ProstTableFragments>> {
+        let request = ListTableFragmentsRequest::default();
+        let resp = self.inner.list_table_fragments(request).await?;
+        Ok(resp.table_fragments)
+    }
+
+    pub async fn throttle_source(&self, source_id: u32, throttle: SourceThrottle) -> Result<()> {
+        let request = ThrottleSourceRequest {
+            source_id,
+            throttle: Some(throttle),
+        };
+        self.inner.throttle_source(request).await?;
+        Ok(())
+    }
+
+    pub async fn list_jobs(&self) -> Result<Vec<Job>> {
+        let request = ListJobsRequest::default();
+        let resp = self.inner.list_jobs(request).await?;
+        Ok(resp.jobs)
+    }
+
+    pub async fn cancel_job(&self, table_id: u32) -> Result<()> {
+        let request = CancelJobRequest { table_id };
+        self.inner.cancel_job(request).await?;
+        Ok(())
+    }
+
+    pub async fn list_table_write_stats(&self) -> Result<Vec<TableWriteStats>> {
+        let request = ListTableWriteStatsRequest::default();
+        let resp = self.inner.list_table_write_stats(request).await?;
+        Ok(resp.stats)
+    }
+
+    pub async fn list_slow_barriers(&self) -> Result<Vec<SlowBarrier>> {
+        let request = ListSlowBarriersRequest::default();
+        let resp = self.inner.list_slow_barriers(request).await?;
+        Ok(resp.barriers)
+    }
+
+    pub async fn get_keyspace_stats(&self) -> Result<Vec<KeyspaceStats>> {
+        let request = GetKeyspaceStatsRequest::default();
+        let resp = self.inner.get_keyspace_stats(request).await?;
+        Ok(resp.keyspace_stats)
+    }
 }
 
 #[async_trait]
@@ -387,6 +505,17 @@ impl HummockMetaClient for MetaClient {
         self.inner.report_vacuum_task(req).await?;
         Ok(())
     }
+
+    async fn subscribe_version_deltas(
+        &self,
+        last_pinned: HummockVersionId,
+    ) -> Result<Box<dyn VersionDeltaStream>> {
+        let req = SubscribeVersionDeltasRequest {
+            context_id: self.worker_id(),
+            last_pinned,
+        };
+        Ok(Box::new(self.inner.subscribe_version_deltas(req).await?))
+    }
 }
 
 /// Client to meta server. Cloning the instance is lightweight.
@@ -399,9 +528,17 @@ pub struct GrpcMetaClient {
     pub hummock_client: HummockManagerServiceClient<Channel>,
     pub notification_client: NotificationServiceClient<Channel>,
     pub stream_client: StreamManagerServiceClient<Channel>,
+    pub event_log_client: EventLogServiceClient<Channel>,
+    metrics: Arc<MetaClientMetrics>,
 }
 
 impl GrpcMetaClient {
+    const RPC_MAX_RETRIES: usize = 8;
+    const RPC_RETRY_BASE_INTERVAL_MS: u64 = 20;
+    const RPC_RETRY_MAX_INTERVAL: Duration = Duration::from_secs(2);
+    /// Deadline of each attempt of an idempotent RPC, which is propagated to the meta server.
+    const RPC_TIMEOUT: Duration = Duration::from_secs(10);
+
     /// Connect to the meta server `addr`.
     pub async fn new(addr: &str) -> Result<Self> {
         let channel = Endpoint::from_shared(addr.to_string())
@@ -416,7 +553,8 @@ impl GrpcMetaClient {
         let ddl_client = DdlServiceClient::new(channel.clone());
         let hummock_client = HummockManagerServiceClient::new(channel.clone());
         let notification_client = NotificationServiceClient::new(channel.clone());
-        let stream_client = StreamManagerServiceClient::new(channel);
+        let stream_client = StreamManagerServiceClient::new(channel.clone());
+        let event_log_client = EventLogServiceClient::new(channel);
         Ok(Self {
             cluster_client,
             heartbeat_client,
@@ -425,57 +563,136 @@ impl GrpcMetaClient {
             hummock_client,
             notification_client,
             stream_client,
+            event_log_client,
+            metrics: Arc::new(MetaClientMetrics::unused()),
         })
     }
+
+    /// Send the request with `rpc` and record its latency.
+    ///
+    /// An idempotent RPC is sent with a deadline, and retried with backoff if meta is unavailable
+    /// or the deadline is exceeded, e.g. when meta restarts or fails over. The others are sent
+    /// once without a deadline, as they may not be safe to repeat, and may take long, e.g. to
+    /// create a materialized view.
+    async fn call<Req, Resp, F, Fut>(
+        &self,
+        rpc_name: &'static str,
+        idempotent: bool,
+        request: Req,
+        rpc: F,
+    ) -> Result<Resp>
+    where
+        Req: Clone,
+        F: Fn(Request<Req>) -> Fut,
+        Fut: Future<Output = std::result::Result<Response<Resp>, Status>>,
+    {
+        let _timer = self
+            .metrics
+            .rpc_latency
+            .with_label_values(&[rpc_name])
+            .start_timer();
+        if !idempotent {
+            return rpc(Request::new(request))
+                .await
+                .map(Response::into_inner)
+                .to_rw_result();
+        }
+
+        let retry_strategy = ExponentialBackoff::from_millis(Self::RPC_RETRY_BASE_INTERVAL_MS)
+            .max_delay(Self::RPC_RETRY_MAX_INTERVAL)
+            .map(jitter)
+            .take(Self::RPC_MAX_RETRIES);
+        let mut attempt = 0;
+        RetryIf::spawn(
+            retry_strategy,
+            || {
+                if attempt > 0 {
+                    tracing::warn!("retry meta rpc {}, attempt {}", rpc_name, attempt);
+                    self.metrics
+                        .rpc_retry_counts
+                        .with_label_values(&[rpc_name])
+                        .inc();
+                }
+                attempt += 1;
+
+                let mut request = Request::new(request.clone());
+                request.set_timeout(Self::RPC_TIMEOUT);
+                let response = tokio::time::timeout(Self::RPC_TIMEOUT, rpc(request));
+                async move {
+                    response.await.unwrap_or_else(|_| {
+                        Err(Status::deadline_exceeded(format!(
+                            "meta rpc {} timed out",
+                            rpc_name
+                        )))
+                    })
+                }
+            },
+            |status: &Status| matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded),
+        )
+        .await
+        .map(Response::into_inner)
+        .to_rw_result()
+    }
 }
 
 macro_rules! grpc_meta_client_impl {
-    ([], $( { $client:ident, $fn_name:ident, $req:ty, $resp:ty }),*) => {
+    ([], $( { $client:ident, $fn_name:ident, $req:ty, $resp:ty, $idempotent:literal }),*) => {
         $(paste! {
             impl GrpcMetaClient {
                 pub async fn [<$fn_name>](&self, request: $req) -> Result<$resp> {
-                    Ok(self
-                        .$client
-                        .to_owned()
-                        .$fn_name(request)
-                        .await
-                        .to_rw_result()?
-                        .into_inner())
+                    self.call(stringify!($fn_name), $idempotent, request, |request| {
+                        let mut client = self.$client.to_owned();
+                        async move { client.$fn_name(request).await }
+                    })
+                    .await
                 }
             }
         })*
     }
 }
 
+/// The last field of each RPC tells whether it's idempotent, i.e. safe to retry when its result is
+/// unknown. Streaming RPCs are never retried, as the deadline would apply to the whole stream.
 macro_rules! for_all_meta_rpc {
     ($macro:ident $(, $x:tt)*) => {
         $macro! {
             [$($x),*]
-            ,{ cluster_client, add_worker_node, AddWorkerNodeRequest, AddWorkerNodeResponse }
-            ,{ cluster_client, activate_worker_node, ActivateWorkerNodeRequest, ActivateWorkerNodeResponse }
-            ,{ cluster_client, delete_worker_node, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse }
-            ,{ cluster_client, list_all_nodes, ListAllNodesRequest, ListAllNodesResponse }
-            ,{ heartbeat_client, heartbeat, HeartbeatRequest, HeartbeatResponse }
-            ,{ stream_client, flush, FlushRequest, FlushResponse }
-            ,{ ddl_client, create_materialized_source, CreateMaterializedSourceRequest, CreateMaterializedSourceResponse }
-            ,{ ddl_client, create_materialized_view, CreateMaterializedViewRequest, CreateMaterializedViewResponse }
-            ,{ ddl_client, create_source, CreateSourceRequest, CreateSourceResponse }
-            ,{ ddl_client, create_schema, CreateSchemaRequest, CreateSchemaResponse }
-            ,{ ddl_client, create_database, CreateDatabaseRequest, CreateDatabaseResponse }
-            ,{ ddl_client, drop_materialized_source, DropMaterializedSourceRequest, DropMaterializedSourceResponse }
-            ,{ ddl_client, drop_materialized_view, DropMaterializedViewRequest, DropMaterializedViewResponse }
-            ,{ ddl_client, drop_source, DropSourceRequest, DropSourceResponse }
-            ,{ hummock_client, pin_version, PinVersionRequest, PinVersionResponse }
-            ,{ hummock_client, unpin_version, UnpinVersionRequest, UnpinVersionResponse }
-            ,{ hummock_client, pin_snapshot, PinSnapshotRequest, PinSnapshotResponse }
-            ,{ hummock_client, unpin_snapshot, UnpinSnapshotRequest, UnpinSnapshotResponse }
-            ,{ hummock_client, add_tables, AddTablesRequest, AddTablesResponse }
-            ,{ hummock_client, report_compaction_tasks, ReportCompactionTasksRequest, ReportCompactionTasksResponse }
-            ,{ hummock_client, get_new_table_id, GetNewTableIdRequest, GetNewTableIdResponse }
-            ,{ hummock_client, subscribe_compact_tasks, SubscribeCompactTasksRequest, Streaming<SubscribeCompactTasksResponse> }
-            ,{ hummock_client, report_vacuum_task, ReportVacuumTaskRequest, ReportVacuumTaskResponse }
-            ,{ hummock_client, commit_epoch, CommitEpochRequest, CommitEpochResponse }
-            ,{ hummock_client, abort_epoch, AbortEpochRequest, AbortEpochResponse }
+            ,{ cluster_client, add_worker_node, AddWorkerNodeRequest, AddWorkerNodeResponse, false }
+            ,{ cluster_client, activate_worker_node, ActivateWorkerNodeRequest, ActivateWorkerNodeResponse, true }
+            ,{ cluster_client, delete_worker_node, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, false }
+            ,{ cluster_client, list_all_nodes, ListAllNodesRequest, ListAllNodesResponse, true }
+            ,{ heartbeat_client, heartbeat, HeartbeatRequest, HeartbeatResponse, false }
+            ,{ stream_client, flush, FlushRequest, FlushResponse, true }
+            ,{ stream_client, list_table_fragments, ListTableFragmentsRequest, ListTableFragmentsResponse, true }
+            ,{ stream_client, throttle_source, ThrottleSourceRequest, ThrottleSourceResponse, true }
+            ,{ stream_client, list_jobs, ListJobsRequest, ListJobsResponse, true }
+            ,{ stream_client, cancel_job, CancelJobRequest, CancelJobResponse, false }
+            ,{ stream_client, list_table_write_stats, ListTableWriteStatsRequest, ListTableWriteStatsResponse, true }
+            ,{ stream_client, list_slow_barriers, ListSlowBarriersRequest, ListSlowBarriersResponse, true }
+            ,{ stream_client, trigger_checkpoint, TriggerCheckpointRequest, TriggerCheckpointResponse, false }
+            ,{ ddl_client, create_materialized_source, CreateMaterializedSourceRequest, CreateMaterializedSourceResponse, false }
+            ,{ ddl_client, create_materialized_view, CreateMaterializedViewRequest, CreateMaterializedViewResponse, false }
+            ,{ ddl_client, create_source, CreateSourceRequest, CreateSourceResponse, false }
+            ,{ ddl_client, create_schema, CreateSchemaRequest, CreateSchemaResponse, false }
+            ,{ ddl_client, create_database, CreateDatabaseRequest, CreateDatabaseResponse, false }
+            ,{ ddl_client, drop_materialized_source, DropMaterializedSourceRequest, DropMaterializedSourceResponse, false }
+            ,{ ddl_client, drop_materialized_view, DropMaterializedViewRequest, DropMaterializedViewResponse, false }
+            ,{ ddl_client, drop_source, DropSourceRequest, DropSourceResponse, false }
+            ,{ ddl_client, export_materialized_view, ExportMaterializedViewRequest, ExportMaterializedViewResponse, false }
+            ,{ hummock_client, pin_version, PinVersionRequest, PinVersionResponse, true }
+            ,{ hummock_client, unpin_version, UnpinVersionRequest, UnpinVersionResponse, true }
+            ,{ hummock_client, pin_snapshot, PinSnapshotRequest, PinSnapshotResponse, true }
+            ,{ hummock_client, unpin_snapshot, UnpinSnapshotRequest, UnpinSnapshotResponse, true }
+            ,{ hummock_client, add_tables, AddTablesRequest, AddTablesResponse, false }
+            ,{ hummock_client, report_compaction_tasks, ReportCompactionTasksRequest, ReportCompactionTasksResponse, false }
+            ,{ hummock_client, get_new_table_id, GetNewTableIdRequest, GetNewTableIdResponse, false }
+            ,{ hummock_client, subscribe_compact_tasks, SubscribeCompactTasksRequest, Streaming<SubscribeCompactTasksResponse>, false }
+            ,{ hummock_client, report_vacuum_task, ReportVacuumTaskRequest, ReportVacuumTaskResponse, false }
+            ,{ hummock_client, subscribe_version_deltas, SubscribeVersionDeltasRequest, Streaming<SubscribeVersionDeltasResponse>, false }
+            ,{ hummock_client, commit_epoch, CommitEpochRequest, CommitEpochResponse, false }
+            ,{ hummock_client, abort_epoch, AbortEpochRequest, AbortEpochResponse, false }
+            ,{ hummock_client, get_keyspace_stats, GetKeyspaceStatsRequest, GetKeyspaceStatsResponse, true }
+            ,{ event_log_client, report_event, ReportEventRequest, ReportEventResponse, false }
         }
     };
 }
@@ -0,0 +1,63 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use prometheus::{
+    exponential_buckets, histogram_opts, register_histogram_vec_with_registry,
+    register_int_counter_vec_with_registry, HistogramVec, IntCounterVec, Registry,
+};
+
+/// Metrics of the RPCs sent by [`crate::MetaClient`], labeled by the RPC name.
+pub struct MetaClientMetrics {
+    /// Latency of each meta RPC, including its retries.
+    pub rpc_latency: HistogramVec,
+    /// Number of retries of each idempotent meta RPC.
+    pub rpc_retry_counts: IntCounterVec,
+}
+
+impl MetaClientMetrics {
+    pub fn new(registry: Registry) -> Self {
+        let opts = histogram_opts!(
+            "meta_client_rpc_duration_seconds",
+            "Latency of meta RPCs sent by the client, including retries",
+            exponential_buckets(0.001, 2.0, 16).unwrap()
+        );
+        let rpc_latency = register_histogram_vec_with_registry!(opts, &["rpc"], registry).unwrap();
+
+        let rpc_retry_counts = register_int_counter_vec_with_registry!(
+            "meta_client_rpc_retry_counts",
+            "Number of retries of idempotent meta RPCs sent by the client",
+            &["rpc"],
+            registry
+        )
+        .unwrap();
+
+        Self {
+            rpc_latency,
+            rpc_retry_counts,
+        }
+    }
+
+    /// Creates a new `MetaClientMetrics` instance used in tests or other places.
+    pub fn unused() -> Self {
+        Self::new(Registry::new())
+    }
+}
+
+impl fmt::Debug for MetaClientMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetaClientMetrics").finish_non_exhaustive()
+    }
+}
@@ -21,7 +21,8 @@ use lazy_static::__Deref;
 use risingwave_common::array::StreamChunk;
 use risingwave_common::error::ErrorCode::ProtocolError;
 use risingwave_common::error::{Result, RwError};
-use risingwave_connector::base::SourceReader;
+use risingwave_connector::base::{SourceReader, SplitImpl};
+use risingwave_connector::monitor::ConnectorMetricsReporter;
 use risingwave_connector::state;
 use risingwave_storage::StateStore;
 use tokio::sync::Mutex;
@@ -37,6 +38,10 @@ pub struct ConnectorSource {
     pub parser: Arc<dyn SourceParser + Send + Sync>,
     pub reader: Arc<Mutex<Box<dyn SourceReader + Send + Sync>>>,
     pub column_descs: Vec<SourceColumnDesc>,
+    /// Messages that fail to be parsed are reported as dead letters and skipped.
+    pub metrics_reporter: ConnectorMetricsReporter,
+    /// The failures to parse messages not taken yet, one for each batch with any.
+    parse_failures: Vec<String>,
 }
 
 impl SourceChunkBuilder for ConnectorSource {}
@@ -52,14 +57,22 @@ impl ConnectorSource {
         parser: Arc<dyn SourceParser + Send + Sync>,
         reader: Arc<Mutex<Box<dyn SourceReader + Send + Sync>>>,
         column_descs: Vec<SourceColumnDesc>,
+        metrics_reporter: ConnectorMetricsReporter,
     ) -> Self {
         Self {
             parser,
             reader,
             column_descs,
+            metrics_reporter,
+            parse_failures: vec![],
         }
     }
 
+    /// Takes the failures to parse messages since the last call.
+    pub fn take_parse_failures(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.parse_failures)
+    }
+
     pub async fn next(&mut self) -> Result<StreamChunk> {
         let payload = self
             .reader
@@ -73,11 +86,32 @@ impl ConnectorSource {
             None => Ok(StreamChunk::default()),
             Some(batch) => {
                 let mut events = Vec::with_capacity(batch.len());
+                let mut failures = 0;
+                let mut first_failure = None;
                 for msg in batch {
                     if let Some(content) = msg.payload {
-                        events.push(self.parser.parse(content.deref(), &self.column_descs)?);
+                        match self.parser.parse(content.deref(), &self.column_descs) {
+                            Ok(event) => events.push(event),
+                            // A malformed message shouldn't stop the whole source.
+                            Err(e) => {
+                                let failure = format!(
+                                    "offset {} of split {}: {}",
+                                    msg.offset, msg.split_id, e
+                                );
+                                log::warn!("failed to parse message at {}", failure);
+                                self.metrics_reporter.report_dead_letter(&msg.split_id);
+                                failures += 1;
+                                first_failure.get_or_insert(failure);
+                            }
+                        }
                     }
                 }
+                if let Some(first_failure) = first_failure {
+                    self.parse_failures.push(format!(
+                        "failed to parse {} messages, the first at {}",
+                        failures, first_failure
+                    ));
+                }
 
                 let mut ops = Vec::with_capacity(events.iter().map(|e| e.ops.len()).sum());
                 let mut rows = Vec::with_capacity(events.iter().map(|e| e.rows.len()).sum());
@@ -111,4 +145,18 @@ impl<S: StateStore> StreamSourceReader for ConnectorStreamSource<S> {
     async fn next(&mut self) -> Result<StreamChunk> {
         self.source_reader.next().await
     }
+
+    async fn assign_splits(&mut self, splits: Vec<SplitImpl>) -> Result<()> {
+        self.source_reader
+            .reader
+            .lock()
+            .await
+            .assign_splits(splits)
+            .await
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))
+    }
+
+    fn take_parse_failures(&mut self) -> Vec<String> {
+        self.source_reader.take_parse_failures()
+    }
 }
@@ -29,7 +29,9 @@ use risingwave_common::error::{Result, RwError};
 use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
 
 use crate::common::SourceChunkBuilder;
-use crate::{BatchSourceReader, Source, SourceColumnDesc, SourceParser, StreamSourceReader};
+use crate::{
+    BatchSourceReader, RawSourceMessage, Source, SourceColumnDesc, SourceParser, StreamSourceReader,
+};
 
 /// `KAFKA_SYNC_CALL_TIMEOUT` provides a timeout parameter for `rdkafka` calls, note that currently
 /// we only use `committed_offsets` and `fetch_metadata` for synchronization calls, these two calls
@@ -73,6 +75,8 @@ pub struct HighLevelKafkaSourceStreamReader {
     consumer: StreamConsumer<DefaultConsumerContext>,
     parser: Arc<dyn SourceParser>,
     columns: Arc<Vec<SourceColumnDesc>>,
+    /// The failures to parse messages not taken yet, one for each batch with any.
+    parse_failures: Vec<String>,
 }
 
 /// `HighLevelKafkaSourceStreamReader` is used to generate `DataChunk` messages, when there are
@@ -216,6 +220,63 @@ impl HighLevelKafkaSource {
             .map_err(|e| RwError::from(InternalError(format!("consumer creation failed {}", e))))
     }
 
+    /// Reads up to `limit` raw messages from the earliest offsets of all partitions, which are the
+    /// first messages a newly created stream reader will consume. Only messages existing when
+    /// called are read, and no offset is committed, so other consumers are not affected.
+    pub async fn peek(&self, limit: usize) -> Result<Vec<RawSourceMessage>> {
+        let consumer = self.create_consumer(&HighLevelKafkaSourceReaderContext {
+            query_id: None,
+            bound_timestamp_ms: None,
+        })?;
+
+        let mut bounds = Self::fetch_bounds(
+            &consumer,
+            self.config.topic.as_str(),
+            None,
+            KAFKA_SYNC_CALL_TIMEOUT,
+        )?;
+        bounds.retain(|_, (low, high)| low < high);
+
+        let mut tpl = TopicPartitionList::with_capacity(bounds.len());
+        for (partition, (low, _)) in &bounds {
+            tpl.add_partition_offset(&self.config.topic, *partition, Offset::Offset(*low))
+                .map_err(|e| RwError::from(InternalError(e.to_string())))?;
+        }
+        consumer
+            .assign(&tpl)
+            .map_err(|e| RwError::from(InternalError(e.to_string())))?;
+
+        let mut messages = Vec::with_capacity(limit);
+        while messages.len() < limit && !bounds.is_empty() {
+            let msg = match tokio::time::timeout(KAFKA_SYNC_CALL_TIMEOUT, consumer.recv()).await {
+                Ok(msg) => msg.map_err(|e| RwError::from(InternalError(e.to_string())))?,
+                // The remaining messages are not available now, return what we have.
+                Err(_) => break,
+            };
+
+            let partition = msg.partition();
+            let offset = msg.offset();
+            match bounds.get(&partition) {
+                Some((_, high)) if offset < *high => {
+                    if offset + 1 == *high {
+                        bounds.remove(&partition);
+                    }
+                }
+                _ => continue,
+            }
+
+            messages.push(RawSourceMessage {
+                split_id: partition.to_string(),
+                offset: offset.to_string(),
+                key: msg.key().map(|key| key.to_vec()),
+                payload: msg.payload().map(|payload| payload.to_vec()),
+                timestamp_ms: msg.timestamp().to_millis(),
+            });
+        }
+
+        Ok(messages)
+    }
+
     fn get_target_columns(&self, column_ids: Vec<ColumnId>) -> Result<Vec<SourceColumnDesc>> {
         column_ids
             .iter()
@@ -302,6 +363,7 @@ impl Source for HighLevelKafkaSource {
             consumer,
             parser: self.parser.clone(),
             columns: Arc::new(columns),
+            parse_failures: vec![],
         })
     }
 }
@@ -327,13 +389,35 @@ impl StreamSourceReader for HighLevelKafkaSourceStreamReader {
             None => Ok(StreamChunk::default()),
             Some(batch) => {
                 let mut events = Vec::with_capacity(batch.len());
+                let mut failures = 0;
+                let mut first_failure = None;
 
                 for msg in batch {
                     let msg = msg.map_err(|e| RwError::from(InternalError(e.to_string())))?;
                     if let Some(payload) = msg.payload() {
-                        events.push(self.parser.parse(payload, &self.columns)?);
+                        match self.parser.parse(payload, &self.columns) {
+                            Ok(event) => events.push(event),
+                            // A malformed message shouldn't stop the whole source.
+                            Err(e) => {
+                                let failure = format!(
+                                    "offset {} of partition {}: {}",
+                                    msg.offset(),
+                                    msg.partition(),
+                                    e
+                                );
+                                log::warn!("failed to parse message at {}", failure);
+                                failures += 1;
+                                first_failure.get_or_insert(failure);
+                            }
+                        }
                     }
                 }
+                if let Some(first_failure) = first_failure {
+                    self.parse_failures.push(format!(
+                        "failed to parse {} messages, the first at {}",
+                        failures, first_failure
+                    ));
+                }
 
                 let mut ops = vec![];
                 let mut rows = vec![];
@@ -351,6 +435,10 @@ impl StreamSourceReader for HighLevelKafkaSourceStreamReader {
             }
         }
     }
+
+    fn take_parse_failures(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.parse_failures)
+    }
 }
 
 impl HighLevelKafkaSourceBatchReader {
@@ -41,6 +41,7 @@ pub use parser::*;
 use risingwave_common::array::{DataChunk, StreamChunk};
 use risingwave_common::catalog::ColumnId;
 use risingwave_common::error::Result;
+use risingwave_connector::SplitImpl;
 pub use table_v2::*;
 
 pub mod parser;
@@ -67,6 +68,7 @@ pub enum SourceFormat {
     Protobuf,
     DebeziumJson,
     Avro,
+    Csv,
 }
 
 #[derive(Debug, EnumAsInner)]
@@ -76,6 +78,17 @@ pub enum SourceImpl {
     Connector(ConnectorSource),
 }
 
+/// A raw message read from a source without parsing, for debugging with `peek_source`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawSourceMessage {
+    pub split_id: String,
+    pub offset: String,
+    pub key: Option<Vec<u8>>,
+    pub payload: Option<Vec<u8>>,
+    /// Milliseconds since the unix epoch, if provided by the source.
+    pub timestamp_ms: Option<i64>,
+}
+
 #[async_trait]
 pub trait Source: Send + Sync + 'static {
     type ReaderContext;
@@ -117,4 +130,16 @@ pub trait StreamSourceReader: Send + Sync + 'static {
     /// `next` always returns a StreamChunk. If the queue is empty, it will
     /// block until new data coming
     async fn next(&mut self) -> Result<StreamChunk>;
+
+    /// Starts reading the splits newly assigned to the source actor. Sources without splits
+    /// ignore it.
+    async fn assign_splits(&mut self, _splits: Vec<SplitImpl>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Takes the failures to parse messages since the last call. The messages are skipped rather
+    /// than failing the reader, and the failures are reported by the source executor.
+    fn take_parse_failures(&mut self) -> Vec<String> {
+        vec![]
+    }
 }
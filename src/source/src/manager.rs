@@ -24,14 +24,15 @@ use risingwave_common::error::ErrorCode::{InternalError, ProtocolError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::DataType;
 use risingwave_connector::base::SourceReader;
-use risingwave_connector::{new_connector, Properties};
-use risingwave_pb::catalog::{RowFormatType, StreamSourceInfo};
+use risingwave_connector::monitor::{ConnectorMetrics, ConnectorMetricsReporter};
+use risingwave_connector::{new_monitored_connector, Properties};
+use risingwave_pb::catalog::{RowFormatType, SchemaRegistryRef, StreamSourceInfo};
 
 use crate::connector_source::ConnectorSource;
 use crate::table_v2::TableSourceV2;
 use crate::{
-    DebeziumJsonParser, HighLevelKafkaSource, JSONParser, ProtobufParser, SourceConfig,
-    SourceFormat, SourceImpl, SourceParser,
+    AvroParser, CsvParser, DebeziumJsonParser, HighLevelKafkaSource, JSONParser, ProtobufParser,
+    SourceConfig, SourceFormat, SourceImpl, SourceParser,
 };
 
 pub type SourceRef = Arc<SourceImpl>;
@@ -41,8 +42,10 @@ pub type SourceRef = Arc<SourceImpl>;
 const UPSTREAM_SOURCE_KEY: &str = "connector";
 const KINESIS_SOURCE: &str = "kinesis";
 const KAFKA_SOURCE: &str = "kafka";
+const FS_SOURCE: &str = "filesystem";
 
 const PROTOBUF_MESSAGE_KEY: &str = "proto.message";
+const CSV_DELIMITER_KEY: &str = "csv.delimiter";
 const PROTOBUF_TEMP_LOCAL_FILENAME: &str = "rw.proto";
 const PROTOBUF_FILE_URL_SCHEME: &str = "file";
 
@@ -99,9 +102,18 @@ pub struct SourceDesc {
 
 pub type SourceManagerRef = Arc<dyn SourceManager>;
 
-#[derive(Debug)]
 pub struct MemSourceManager {
     sources: Mutex<HashMap<TableId, SourceDesc>>,
+    /// Metrics of the connectors created by the manager.
+    connector_metrics: Arc<ConnectorMetrics>,
+}
+
+impl Debug for MemSourceManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemSourceManager")
+            .field("sources", &self.sources)
+            .finish()
+    }
 }
 
 #[async_trait]
@@ -124,15 +136,21 @@ impl SourceManager for MemSourceManager {
             SourceConfig::Connector(config) => {
                 let split_reader: Arc<tokio::sync::Mutex<Box<dyn SourceReader + Send + Sync>>> =
                     Arc::new(tokio::sync::Mutex::new(
-                        new_connector(Properties::new(config.clone()), None)
-                            .await
-                            .map_err(|e| RwError::from(InternalError(e.to_string())))?,
+                        new_monitored_connector(
+                            Properties::new(config.clone()),
+                            None,
+                            source_id.table_id(),
+                            self.connector_metrics.clone(),
+                        )
+                        .await
+                        .map_err(|e| RwError::from(InternalError(e.to_string())))?,
                     ));
-                SourceImpl::Connector(ConnectorSource {
-                    parser: parser.clone(),
-                    reader: split_reader,
-                    column_descs: columns.clone(),
-                })
+                SourceImpl::Connector(ConnectorSource::new(
+                    parser.clone(),
+                    split_reader,
+                    columns.clone(),
+                    self.connector_reporter(source_id, config)?,
+                ))
             }
         };
 
@@ -159,17 +177,28 @@ impl SourceManager for MemSourceManager {
             RowFormatType::Protobuf => SourceFormat::Protobuf,
             RowFormatType::DebeziumJson => SourceFormat::DebeziumJson,
             RowFormatType::Avro => SourceFormat::Avro,
+            RowFormatType::Csv => SourceFormat::Csv,
         };
 
-        if format == SourceFormat::Protobuf && info.row_schema_location.is_empty() {
-            return Err(RwError::from(ProtocolError(
-                "protobuf file location not provided".to_string(),
-            )));
-        }
-
         let properties = Properties::new(info.properties.clone());
-        let parser = build_source_parser(&format, &properties, info.row_schema_location.as_str())?;
+        let parser = if let Some(registry) = &info.schema_registry {
+            build_schema_registry_parser(&format, &properties, registry, &info).await?
+        } else {
+            if matches!(format, SourceFormat::Protobuf | SourceFormat::Avro)
+                && info.row_schema_location.is_empty()
+            {
+                return Err(RwError::from(ProtocolError(format!(
+                    "{:?} schema location not provided",
+                    format
+                ))));
+            }
+            build_source_parser(&format, &properties, info.row_schema_location.as_str())?
+        };
 
+        let event_time_idx = info
+            .event_time_desc
+            .as_ref()
+            .map(|desc| desc.event_time_idx as usize);
         let columns = info
             .columns
             .iter()
@@ -180,7 +209,8 @@ impl SourceManager for MemSourceManager {
                     name: c.name.clone(),
                     data_type: DataType::from(&c.column_type.unwrap()),
                     column_id: ColumnId::from(c.column_id),
-                    skip_parse: idx as i32 == info.row_id_index,
+                    // The row id and event time columns are filled by the source executor.
+                    skip_parse: idx as i32 == info.row_id_index || Some(idx) == event_time_idx,
                 }
             })
             .collect::<Vec<SourceColumnDesc>>();
@@ -196,6 +226,7 @@ impl SourceManager for MemSourceManager {
             // TODO support more connector here
             KINESIS_SOURCE => Ok(SourceConfig::Connector(info.properties.clone())),
             KAFKA_SOURCE => Ok(SourceConfig::Connector(info.properties.clone())),
+            FS_SOURCE => Ok(SourceConfig::Connector(info.properties.clone())),
             other => Err(RwError::from(ProtocolError(format!(
                 "source type {} not supported",
                 other
@@ -210,15 +241,21 @@ impl SourceManager for MemSourceManager {
                 SourceConfig::Connector(config) => {
                     let split_reader: Arc<tokio::sync::Mutex<Box<dyn SourceReader + Send + Sync>>> =
                         Arc::new(tokio::sync::Mutex::new(
-                            new_connector(Properties::new(config.clone()), None)
-                                .await
-                                .map_err(|e| RwError::from(InternalError(e.to_string())))?,
+                            new_monitored_connector(
+                                Properties::new(config.clone()),
+                                None,
+                                source_id.table_id(),
+                                self.connector_metrics.clone(),
+                            )
+                            .await
+                            .map_err(|e| RwError::from(InternalError(e.to_string())))?,
                         ));
-                    SourceImpl::Connector(ConnectorSource {
-                        parser: parser.clone(),
-                        reader: split_reader,
-                        column_descs: columns.clone(),
-                    })
+                    SourceImpl::Connector(ConnectorSource::new(
+                        parser.clone(),
+                        split_reader,
+                        columns.clone(),
+                        self.connector_reporter(source_id, &config)?,
+                    ))
                 }
             };
 
@@ -291,11 +328,29 @@ impl SourceManager for MemSourceManager {
 
 impl MemSourceManager {
     pub fn new() -> Self {
+        Self::with_connector_metrics(Arc::new(ConnectorMetrics::unused()))
+    }
+
+    pub fn with_connector_metrics(connector_metrics: Arc<ConnectorMetrics>) -> Self {
         MemSourceManager {
             sources: Mutex::new(HashMap::new()),
+            connector_metrics,
         }
     }
 
+    fn connector_reporter(
+        &self,
+        source_id: &TableId,
+        config: &HashMap<String, String>,
+    ) -> Result<ConnectorMetricsReporter> {
+        let connector = Properties::new(config.clone()).get(UPSTREAM_SOURCE_KEY)?;
+        Ok(ConnectorMetricsReporter::new(
+            self.connector_metrics.clone(),
+            source_id.table_id(),
+            &connector,
+        ))
+    }
+
     fn get_sources(&self) -> Result<MutexGuard<HashMap<TableId, SourceDesc>>> {
         Ok(self.sources.lock())
     }
@@ -324,10 +379,29 @@ fn build_source_parser(
 
             Ok(parser)
         }
+        SourceFormat::Avro => {
+            let parser: Arc<dyn SourceParser + Send + Sync> =
+                Arc::new(AvroParser::new(schema_location)?);
+            Ok(parser)
+        }
         SourceFormat::DebeziumJson => {
             let parser: Arc<dyn SourceParser + Send + Sync> = Arc::new(DebeziumJsonParser {});
             Ok(parser)
         }
+        SourceFormat::Csv => {
+            let delimiter = match properties.0.get(CSV_DELIMITER_KEY).map(|d| d.as_bytes()) {
+                None => b',',
+                Some([delimiter]) => *delimiter,
+                Some(_) => {
+                    return Err(RwError::from(ProtocolError(format!(
+                        "{} should be a single ASCII character",
+                        CSV_DELIMITER_KEY
+                    ))))
+                }
+            };
+            let parser: Arc<dyn SourceParser + Send + Sync> = Arc::new(CsvParser::new(delimiter));
+            Ok(parser)
+        }
         _ => Err(RwError::from(InternalError(
             "format not support".to_string(),
         ))),
@@ -336,6 +410,66 @@ fn build_source_parser(
     Ok(parser)
 }
 
+/// Builds a protobuf or avro parser from the latest schema in the schema registry referenced by
+/// the source. The schema may have evolved since the source was created, so it's only accepted if
+/// every column in the catalog is still derived with the same type.
+async fn build_schema_registry_parser(
+    format: &SourceFormat,
+    properties: &Properties,
+    registry: &SchemaRegistryRef,
+    info: &StreamSourceInfo,
+) -> Result<Arc<dyn SourceParser + Send + Sync>> {
+    let (parser, derived, schema): (Arc<dyn SourceParser + Send + Sync>, _, _) = match format {
+        SourceFormat::Protobuf => {
+            let message_name = properties.get(PROTOBUF_MESSAGE_KEY)?;
+            let (parser, schema) = ProtobufParser::new_from_schema_registry(
+                &registry.url,
+                &registry.subject,
+                &message_name,
+            )
+            .await?;
+            let derived = parser.map_to_columns()?;
+            (Arc::new(parser), derived, schema)
+        }
+        SourceFormat::Avro => {
+            let (parser, schema) =
+                AvroParser::new_from_schema_registry(&registry.url, &registry.subject).await?;
+            let derived = parser.map_to_columns()?;
+            (Arc::new(parser), derived, schema)
+        }
+        _ => {
+            return Err(RwError::from(ProtocolError(format!(
+                "schema registry is not supported for {:?}",
+                format
+            ))))
+        }
+    };
+
+    let derived = derived
+        .into_iter()
+        .map(|c| (c.name.clone(), c))
+        .collect::<HashMap<_, _>>();
+    for (idx, column) in info.columns.iter().enumerate() {
+        if idx as i32 == info.row_id_index {
+            continue;
+        }
+        let column = column.column_desc.as_ref().unwrap();
+        let compatible = derived
+            .get(&column.name)
+            .map(|c| c.column_type == column.column_type)
+            .unwrap_or(false);
+        if !compatible {
+            return Err(RwError::from(ProtocolError(format!(
+                "column {} of the source is missing or has changed its type in version {} of \
+                 subject {}, which is incompatible with version {}",
+                column.name, schema.version, registry.subject, registry.version
+            ))));
+        }
+    }
+
+    Ok(parser)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -424,6 +558,9 @@ mod tests {
             row_id_index: 0,
             pk_column_ids: vec![0],
             columns,
+            watermark_desc: None,
+            schema_registry: None,
+            event_time_desc: None,
         };
         let source_id = TableId::default();
 
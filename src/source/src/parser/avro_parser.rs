@@ -0,0 +1,481 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use apache_avro::schema::{RecordField, UnionSchema};
+use apache_avro::types::Value;
+use apache_avro::{from_avro_datum, Schema};
+use itertools::Itertools;
+use risingwave_common::array::{Op, StructValue};
+use risingwave_common::error::ErrorCode::{self, InternalError, ProtocolError};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{
+    DataType, Datum, Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, ScalarImpl,
+};
+use risingwave_connector::schema_registry::{SchemaRegistryClient, SubjectSchema};
+use risingwave_pb::plan::ColumnDesc;
+use url::Url;
+
+use super::common::split_confluent_header;
+use crate::{Event, SourceColumnDesc, SourceParser};
+
+/// Parser for Avro-encoded bytes. Each message is a single datum of a record schema, whose fields
+/// are mapped to the columns by name.
+#[derive(Debug)]
+pub struct AvroParser {
+    schema: Schema,
+    /// The id of the schema in a Confluent schema registry. If it's set, the messages are in the
+    /// Confluent wire format, and only those written with this schema can be decoded.
+    schema_id: Option<i32>,
+}
+
+impl AvroParser {
+    fn from_schema_str(schema: &str, schema_id: Option<i32>) -> Result<Self> {
+        let schema = Schema::parse_str(schema).map_err(|e| {
+            RwError::from(ProtocolError(format!("failed to parse avro schema: {}", e)))
+        })?;
+        if !matches!(schema, Schema::Record { .. }) {
+            return Err(RwError::from(ProtocolError(
+                "avro schema of a source must be a record".to_string(),
+            )));
+        }
+        Ok(Self { schema, schema_id })
+    }
+
+    /// Create an Avro parser from the URL of a schema file, e.g. `file:///path/to/schema.avsc`.
+    pub fn new(location: &str) -> Result<Self> {
+        let url = Url::parse(location)
+            .map_err(|e| InternalError(format!("failed to parse url ({}): {}", location, e)))?;
+
+        match url.scheme() {
+            "file" => {
+                let path = url.to_file_path().map_err(|_| {
+                    RwError::from(InternalError(format!("illegal path: {}", location)))
+                })?;
+                let schema = std::fs::read_to_string(&path).map_err(|e| {
+                    RwError::from(InternalError(format!(
+                        "failed to read avro schema {}: {}",
+                        location, e
+                    )))
+                })?;
+                Self::from_schema_str(&schema, None)
+            }
+            scheme => Err(RwError::from(ProtocolError(format!(
+                "path scheme {} is not supported",
+                scheme
+            )))),
+        }
+    }
+
+    /// Create an Avro parser from the latest schema of `subject` in a Confluent schema registry.
+    /// The fetched schema is returned as well, so that the caller can record which version the
+    /// parser is built from.
+    pub async fn new_from_schema_registry(
+        url: &str,
+        subject: &str,
+    ) -> Result<(Self, SubjectSchema)> {
+        let client = SchemaRegistryClient::new(url.to_string());
+        let schema = client
+            .get_latest_schema(subject)
+            .await
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
+        if schema.schema_type() != "AVRO" {
+            return Err(RwError::from(ProtocolError(format!(
+                "expected an AVRO schema for subject {}, got {}",
+                subject,
+                schema.schema_type()
+            ))));
+        }
+        let parser = Self::from_schema_str(&schema.schema, Some(schema.id))?;
+
+        Ok((parser, schema))
+    }
+
+    fn fields(&self) -> &[RecordField] {
+        match &self.schema {
+            Schema::Record { fields, .. } => fields,
+            _ => unreachable!("avro schema of a source must be a record"),
+        }
+    }
+
+    /// Maps the Avro schema to relational schema.
+    pub fn map_to_columns(&self) -> Result<Vec<ColumnDesc>> {
+        let mut index = 0;
+        self.fields()
+            .iter()
+            .map(|f| avro_field_to_col_desc(&f.name, &f.schema, "", &mut index))
+            .collect()
+    }
+}
+
+/// Returns `T` if `union` is `[null, T]` or `[T, null]`, i.e. an optional `T`.
+fn optional_variant(union: &UnionSchema) -> Option<&Schema> {
+    match union.variants() {
+        [Schema::Null, schema] | [schema, Schema::Null] => Some(schema),
+        _ => None,
+    }
+}
+
+/// Maps an Avro type to a DB column type. Optional types, i.e. unions with `null`, are mapped to
+/// the type they wrap.
+fn avro_type_mapping(schema: &Schema) -> Result<DataType> {
+    let t = match schema {
+        Schema::Boolean => DataType::Boolean,
+        Schema::Int => DataType::Int32,
+        Schema::Long => DataType::Int64,
+        Schema::Float => DataType::Float32,
+        Schema::Double => DataType::Float64,
+        Schema::Bytes | Schema::Fixed { .. } => DataType::Bytea,
+        Schema::String | Schema::Enum { .. } | Schema::Uuid => DataType::Varchar,
+        Schema::Decimal { .. } => DataType::Decimal,
+        Schema::Date => DataType::Date,
+        Schema::TimeMillis | Schema::TimeMicros => DataType::Time,
+        Schema::TimestampMillis | Schema::TimestampMicros => DataType::Timestamp,
+        Schema::Record { fields, .. } => {
+            let fields = fields
+                .iter()
+                .map(|f| avro_type_mapping(&f.schema))
+                .collect::<Result<Vec<_>>>()?;
+            DataType::Struct {
+                fields: fields.into(),
+            }
+        }
+        Schema::Union(union) if optional_variant(union).is_some() => {
+            avro_type_mapping(optional_variant(union).unwrap())?
+        }
+        actual_type => {
+            return Err(ErrorCode::NotImplemented(
+                format!("unsupported avro type: {:?}", actual_type),
+                None.into(),
+            )
+            .into());
+        }
+    };
+    Ok(t)
+}
+
+/// Creates the column desc of an Avro field, and the column descs of the fields of records
+/// recursively. `index` is used to create increment column ids.
+fn avro_field_to_col_desc(
+    name: &str,
+    schema: &Schema,
+    lastname: &str,
+    index: &mut i32,
+) -> Result<ColumnDesc> {
+    let data_type = avro_type_mapping(schema)?;
+    let schema = match schema {
+        Schema::Union(union) => optional_variant(union).unwrap_or(schema),
+        _ => schema,
+    };
+    match schema {
+        Schema::Record {
+            name: record_name,
+            fields,
+            ..
+        } => {
+            let field_descs = fields
+                .iter()
+                .map(|f| {
+                    avro_field_to_col_desc(
+                        &f.name,
+                        &f.schema,
+                        &format!("{}{}.", lastname, name),
+                        index,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+            *index += 1;
+            Ok(ColumnDesc {
+                column_id: *index,
+                name: format!("{}{}", lastname, name),
+                column_type: Some(data_type.to_protobuf()),
+                field_descs,
+                type_name: record_name.name.clone(),
+            })
+        }
+        _ => {
+            *index += 1;
+            Ok(ColumnDesc {
+                column_id: *index,
+                name: format!("{}{}", lastname, name),
+                column_type: Some(data_type.to_protobuf()),
+                ..Default::default()
+            })
+        }
+    }
+}
+
+/// Converts the unscaled value of an Avro decimal, a big-endian two's-complement integer, to a
+/// decimal with `scale`. Returns None if it's out of the range of `DECIMAL`.
+fn avro_decimal(unscaled: &[u8], scale: usize) -> Option<Decimal> {
+    if unscaled.len() > 16 || scale > 28 {
+        return None;
+    }
+    let sign = match unscaled.first() {
+        Some(byte) if byte & 0x80 != 0 => 0xff,
+        _ => 0,
+    };
+    let mut bytes = [sign; 16];
+    bytes[16 - unscaled.len()..].copy_from_slice(unscaled);
+    let unscaled = i128::from_be_bytes(bytes);
+    // Decimals only hold 96-bit integers.
+    if unscaled.unsigned_abs() >= 1 << 96 {
+        return None;
+    }
+    Some(Decimal::from_i128_with_scale(unscaled, scale as u32))
+}
+
+/// Converts a decoded Avro value of `schema` to a datum of the type mapped by
+/// [`avro_type_mapping`].
+fn avro_value_to_datum(value: Value, schema: &Schema) -> Result<Datum> {
+    let scalar = match (value, schema) {
+        (Value::Null, _) => return Ok(None),
+        (Value::Union(idx, value), Schema::Union(union)) => {
+            let schema = union.variants().get(idx as usize).ok_or_else(|| {
+                RwError::from(ProtocolError(format!("invalid avro union index {}", idx)))
+            })?;
+            return avro_value_to_datum(*value, schema);
+        }
+        (Value::Boolean(v), _) => ScalarImpl::Bool(v),
+        (Value::Int(v), _) => ScalarImpl::Int32(v),
+        (Value::Long(v), _) => ScalarImpl::Int64(v),
+        (Value::Float(v), _) => ScalarImpl::Float32(v.into()),
+        (Value::Double(v), _) => ScalarImpl::Float64(v.into()),
+        (Value::Bytes(v) | Value::Fixed(_, v), _) => ScalarImpl::Bytea(v.into_boxed_slice()),
+        (Value::String(v) | Value::Enum(_, v), _) => ScalarImpl::Utf8(v),
+        (Value::Uuid(v), _) => ScalarImpl::Utf8(v.to_string()),
+        (Value::Decimal(v), Schema::Decimal { scale, .. }) => {
+            let unscaled =
+                Vec::<u8>::try_from(&v).map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
+            let decimal = avro_decimal(&unscaled, *scale).ok_or_else(|| {
+                RwError::from(ProtocolError("avro decimal out of range".to_string()))
+            })?;
+            ScalarImpl::Decimal(decimal)
+        }
+        (Value::Date(days), _) => ScalarImpl::NaiveDate(NaiveDateWrapper::from_protobuf(days)?),
+        (Value::TimeMillis(millis), _) => {
+            ScalarImpl::NaiveTime(NaiveTimeWrapper::from_protobuf(millis as i64 * 1000)?)
+        }
+        (Value::TimeMicros(micros), _) => {
+            ScalarImpl::NaiveTime(NaiveTimeWrapper::from_protobuf(micros)?)
+        }
+        (Value::TimestampMillis(millis), _) => ScalarImpl::NaiveDateTime(
+            NaiveDateTimeWrapper::new_with_secs_nsecs(
+                millis.div_euclid(1000),
+                millis.rem_euclid(1000) as u32 * 1_000_000,
+            )
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))?,
+        ),
+        (Value::TimestampMicros(micros), _) => ScalarImpl::NaiveDateTime(
+            NaiveDateTimeWrapper::new_with_secs_nsecs(
+                micros.div_euclid(1_000_000),
+                micros.rem_euclid(1_000_000) as u32 * 1000,
+            )
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))?,
+        ),
+        (Value::Record(values), Schema::Record { fields, .. }) => {
+            let values = values
+                .into_iter()
+                .zip_eq(fields.iter())
+                .map(|((_, value), field)| avro_value_to_datum(value, &field.schema))
+                .collect::<Result<Vec<_>>>()?;
+            ScalarImpl::Struct(StructValue::new(values))
+        }
+        (value, schema) => {
+            return Err(RwError::from(ProtocolError(format!(
+                "unsupported avro value {:?} of {:?}",
+                value, schema
+            ))));
+        }
+    };
+    Ok(Some(scalar))
+}
+
+impl SourceParser for AvroParser {
+    fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        let mut data = match self.schema_id {
+            Some(schema_id) => {
+                let (id, data) = split_confluent_header(payload)?;
+                if id != schema_id {
+                    return Err(RwError::from(ProtocolError(format!(
+                        "message is written with avro schema {}, but schema {} is expected",
+                        id, schema_id
+                    ))));
+                }
+                data
+            }
+            None => payload,
+        };
+        let values = match from_avro_datum(&self.schema, &mut data, None) {
+            Ok(Value::Record(values)) => values,
+            Ok(_) => unreachable!("avro schema of a source must be a record"),
+            Err(e) => {
+                return Err(RwError::from(ProtocolError(format!(
+                    "failed to decode avro: {}",
+                    e
+                ))))
+            }
+        };
+        let mut values = values
+            .into_iter()
+            .zip_eq(self.fields().iter())
+            .map(|((name, value), field)| (name, (value, &field.schema)))
+            .collect::<HashMap<_, _>>();
+
+        let row = columns
+            .iter()
+            .map(|column| {
+                if column.skip_parse {
+                    return Ok(None);
+                }
+                match values.remove(&column.name) {
+                    Some((value, schema)) => avro_value_to_datum(value, schema),
+                    None => Ok(None),
+                }
+            })
+            .collect::<Result<Vec<Datum>>>()?;
+
+        Ok(Event {
+            ops: vec![Op::Insert],
+            rows: vec![row],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::to_avro_datum;
+    use apache_avro::types::Record;
+    use risingwave_common::catalog::ColumnId;
+
+    use super::*;
+
+    static AVRO_SCHEMA: &str = r#"
+    {
+      "type": "record",
+      "name": "TestRecord",
+      "fields": [
+        { "name": "id", "type": "int" },
+        { "name": "name", "type": ["null", "string"] },
+        { "name": "price", "type": { "type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2 } },
+        { "name": "ts", "type": { "type": "long", "logicalType": "timestamp-millis" } },
+        {
+          "name": "city",
+          "type": {
+            "type": "record",
+            "name": "City",
+            "fields": [{ "name": "zipcode", "type": "long" }]
+          }
+        }
+      ]
+    }"#;
+
+    fn source_columns(parser: &AvroParser) -> Vec<SourceColumnDesc> {
+        parser
+            .map_to_columns()
+            .unwrap()
+            .into_iter()
+            .map(|c| SourceColumnDesc {
+                name: c.name,
+                data_type: DataType::from(c.column_type.as_ref().unwrap()),
+                column_id: ColumnId::from(c.column_id),
+                skip_parse: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_map_to_columns() {
+        let parser = AvroParser::from_schema_str(AVRO_SCHEMA, None).unwrap();
+        let columns = parser.map_to_columns().unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| (c.name.as_str(), c.column_id))
+                .collect_vec(),
+            [("id", 1), ("name", 2), ("price", 3), ("ts", 4), ("city", 6)]
+        );
+        assert_eq!(columns[4].field_descs[0].name, "city.zipcode");
+        assert_eq!(
+            DataType::from(columns[4].column_type.as_ref().unwrap()),
+            DataType::Struct {
+                fields: vec![DataType::Int64].into()
+            }
+        );
+
+        assert!(AvroParser::from_schema_str(r#""string""#, None).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let parser = AvroParser::from_schema_str(AVRO_SCHEMA, None).unwrap();
+        let city_schema = match &parser.fields()[4].schema {
+            s @ Schema::Record { .. } => s.clone(),
+            _ => unreachable!(),
+        };
+        let mut city = Record::new(&city_schema).unwrap();
+        city.put("zipcode", 94105i64);
+        let mut record = Record::new(&parser.schema).unwrap();
+        record.put("id", 1);
+        record.put(
+            "name",
+            Value::Union(1, Box::new(Value::String("foo".to_string()))),
+        );
+        record.put("price", Value::Decimal(vec![0x04, 0xd2].into()));
+        record.put("ts", Value::TimestampMillis(1_000_001));
+        record.put("city", city);
+        let data = to_avro_datum(&parser.schema, record).unwrap();
+
+        let columns = source_columns(&parser);
+        let event = parser.parse(&data, &columns).unwrap();
+        assert_eq!(
+            event.rows[0],
+            vec![
+                Some(ScalarImpl::Int32(1)),
+                Some(ScalarImpl::Utf8("foo".to_string())),
+                Some(ScalarImpl::Decimal(Decimal::from_i128_with_scale(1234, 2))),
+                Some(ScalarImpl::NaiveDateTime(
+                    NaiveDateTimeWrapper::new_with_secs_nsecs(1000, 1_000_000).unwrap()
+                )),
+                Some(ScalarImpl::Struct(StructValue::new(vec![Some(
+                    ScalarImpl::Int64(94105)
+                )]))),
+            ]
+        );
+
+        // Messages from a schema registry are prefixed with the schema id.
+        let parser = AvroParser::from_schema_str(AVRO_SCHEMA, Some(7)).unwrap();
+        let mut payload = vec![0, 0, 0, 0, 7];
+        payload.extend_from_slice(&data);
+        assert_eq!(parser.parse(&payload, &columns).unwrap().rows, event.rows);
+        payload[4] = 8;
+        assert!(parser.parse(&payload, &columns).is_err());
+        assert!(parser.parse(&data, &columns).is_err());
+    }
+
+    #[test]
+    fn test_avro_decimal() {
+        assert_eq!(
+            avro_decimal(&[0xfb, 0x2e], 2),
+            Some(Decimal::from_i128_with_scale(-1234, 2))
+        );
+        assert_eq!(
+            avro_decimal(&[], 0),
+            Some(Decimal::from_i128_with_scale(0, 0))
+        );
+        assert_eq!(avro_decimal(&[0x01; 13], 0), None);
+        assert_eq!(avro_decimal(&[0x01], 29), None);
+    }
+}
@@ -14,7 +14,7 @@
 
 use chrono::NaiveDate;
 use num_traits::FromPrimitive;
-use risingwave_common::error::ErrorCode::{self, InternalError};
+use risingwave_common::error::ErrorCode::{self, InternalError, ProtocolError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::{DataType, Decimal, NaiveDateWrapper, ScalarImpl, ScalarRef};
 use serde_json::Value;
@@ -29,6 +29,23 @@ pub fn str_to_date(elem: &str) -> Result<NaiveDateWrapper> {
 
 use crate::SourceColumnDesc;
 
+/// The magic byte leading the messages in the Confluent wire format.
+const CONFLUENT_MAGIC_BYTE: u8 = 0;
+
+/// Splits a message in the Confluent wire format, which is serialized with a schema from a
+/// Confluent schema registry, into the id of the schema and the serialized data. The data is
+/// prefixed with the magic byte and the schema id as a 4-byte big-endian integer.
+pub(crate) fn split_confluent_header(payload: &[u8]) -> Result<(i32, &[u8])> {
+    match payload {
+        [CONFLUENT_MAGIC_BYTE, a, b, c, d, data @ ..] => {
+            Ok((i32::from_be_bytes([*a, *b, *c, *d]), data))
+        }
+        _ => Err(RwError::from(ProtocolError(
+            "message is not in the Confluent wire format".to_string(),
+        ))),
+    }
+}
+
 macro_rules! make_ScalarImpl {
     ($x:expr, $y:expr) => {
         match $x {
@@ -102,3 +119,51 @@ pub(crate) fn json_parse_value(
         .into()),
     }
 }
+
+/// Parses a non-empty CSV field to a scalar of the column's type.
+pub(crate) fn csv_parse_value(column: &SourceColumnDesc, field: &str) -> Result<ScalarImpl> {
+    let parse_error = |e: &dyn std::fmt::Display| {
+        RwError::from(ProtocolError(format!(
+            "failed to parse {:?} as {:?} for column {}: {}",
+            field, column.data_type, column.name, e
+        )))
+    };
+    match column.data_type {
+        DataType::Boolean => match field.to_lowercase().as_str() {
+            "true" | "t" | "1" => Ok(ScalarImpl::Bool(true)),
+            "false" | "f" | "0" => Ok(ScalarImpl::Bool(false)),
+            _ => Err(parse_error(&"not a boolean")),
+        },
+        DataType::Int16 => field
+            .parse()
+            .map(ScalarImpl::Int16)
+            .map_err(|e| parse_error(&e)),
+        DataType::Int32 => field
+            .parse()
+            .map(ScalarImpl::Int32)
+            .map_err(|e| parse_error(&e)),
+        DataType::Int64 => field
+            .parse()
+            .map(ScalarImpl::Int64)
+            .map_err(|e| parse_error(&e)),
+        DataType::Float32 => field
+            .parse::<f32>()
+            .map(|v| ScalarImpl::Float32(v.into()))
+            .map_err(|e| parse_error(&e)),
+        DataType::Float64 => field
+            .parse::<f64>()
+            .map(|v| ScalarImpl::Float64(v.into()))
+            .map_err(|e| parse_error(&e)),
+        DataType::Decimal => field
+            .parse::<Decimal>()
+            .map(ScalarImpl::Decimal)
+            .map_err(|e| parse_error(&e)),
+        DataType::Varchar => Ok(ScalarImpl::Utf8(field.to_owned_scalar())),
+        DataType::Date => str_to_date(field).map(ScalarImpl::NaiveDate),
+        _ => Err(ErrorCode::NotImplemented(
+            "unsupported type for csv_parse_value".to_string(),
+            None.into(),
+        )
+        .into()),
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::array::Op;
+use risingwave_common::error::ErrorCode::ProtocolError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::Datum;
+
+use crate::parser::common::csv_parse_value;
+use crate::{Event, SourceColumnDesc, SourceParser};
+
+/// Parser for CSV format, where each payload is a line. The fields are mapped to the columns in
+/// order, skipping the columns not parsed from the payload like the row id. An empty field is
+/// null.
+#[derive(Debug)]
+pub struct CsvParser {
+    delimiter: u8,
+}
+
+impl CsvParser {
+    pub fn new(delimiter: u8) -> Self {
+        Self { delimiter }
+    }
+}
+
+impl SourceParser for CsvParser {
+    fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .delimiter(self.delimiter)
+            .from_reader(payload);
+        let record = reader
+            .records()
+            .next()
+            .ok_or_else(|| RwError::from(ProtocolError("empty csv line".to_string())))?
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
+
+        let parsed_columns = columns.iter().filter(|column| !column.skip_parse).count();
+        if record.len() != parsed_columns {
+            return Err(RwError::from(ProtocolError(format!(
+                "expect {} fields in csv line, got {}",
+                parsed_columns,
+                record.len()
+            ))));
+        }
+
+        let mut fields = record.iter();
+        let row = columns
+            .iter()
+            .map(|column| {
+                if column.skip_parse {
+                    return Ok(None);
+                }
+                match fields.next().unwrap() {
+                    "" => Ok(None),
+                    field => csv_parse_value(column, field).map(Some),
+                }
+            })
+            .collect::<Result<Vec<Datum>>>()?;
+
+        Ok(Event {
+            ops: vec![Op::Insert],
+            rows: vec![row],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::ColumnId;
+    use risingwave_common::types::{DataType, ScalarImpl};
+
+    use super::super::common::str_to_date;
+    use super::*;
+
+    fn column(name: &str, data_type: DataType, skip_parse: bool) -> SourceColumnDesc {
+        SourceColumnDesc {
+            name: name.to_string(),
+            data_type,
+            column_id: ColumnId::from(0),
+            skip_parse,
+        }
+    }
+
+    #[test]
+    fn test_csv_parser() {
+        let parser = CsvParser::new(b',');
+        let descs = vec![
+            column("_row_id", DataType::Int64, true),
+            column("id", DataType::Int32, false),
+            column("name", DataType::Varchar, false),
+            column("price", DataType::Float64, false),
+            column("date", DataType::Date, false),
+        ];
+
+        let event = parser
+            .parse(br#"1,"Doe, John",1.5,2021-01-01"#, &descs)
+            .unwrap();
+        assert_eq!(
+            event.rows,
+            vec![vec![
+                None,
+                Some(ScalarImpl::Int32(1)),
+                Some(ScalarImpl::Utf8("Doe, John".to_string())),
+                Some(ScalarImpl::Float64(1.5.into())),
+                Some(ScalarImpl::NaiveDate(str_to_date("2021-01-01").unwrap())),
+            ]]
+        );
+
+        let event = parser.parse(b"2,,,", &descs).unwrap();
+        assert_eq!(
+            event.rows,
+            vec![vec![None, Some(ScalarImpl::Int32(2)), None, None, None]]
+        );
+
+        assert!(parser.parse(b"1,a,1.5", &descs).is_err());
+        assert!(parser.parse(b"x,a,1.5,2021-01-01", &descs).is_err());
+
+        let parser = CsvParser::new(b'|');
+        let event = parser.parse(b"3|a,b|2|2022-02-02", &descs).unwrap();
+        assert_eq!(event.rows[0][2], Some(ScalarImpl::Utf8("a,b".to_string())));
+    }
+}
@@ -14,6 +14,8 @@
 
 use std::fmt::Debug;
 
+pub use avro_parser::*;
+pub use csv_parser::*;
 pub use debezium::*;
 pub use json_parser::*;
 pub use protobuf_parser::*;
@@ -23,7 +25,9 @@ use risingwave_common::types::Datum;
 
 use crate::SourceColumnDesc;
 
+mod avro_parser;
 mod common;
+mod csv_parser;
 mod debezium;
 mod json_parser;
 mod protobuf_parser;
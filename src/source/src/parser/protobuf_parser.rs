@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use protobuf::descriptor::FileDescriptorSet;
@@ -19,7 +20,11 @@ use protobuf::RepeatedField;
 use risingwave_common::array::Op;
 use risingwave_common::error::ErrorCode::{self, InternalError, ItemNotFound, ProtocolError};
 use risingwave_common::error::{Result, RwError};
-use risingwave_common::types::{DataType, Datum, Decimal, OrderedF32, OrderedF64, ScalarImpl};
+use risingwave_common::types::{
+    DataType, Datum, Decimal, IntervalUnit, NaiveDateTimeWrapper, OrderedF32, OrderedF64,
+    ScalarImpl,
+};
+use risingwave_connector::schema_registry::{SchemaRegistryClient, SubjectSchema};
 use risingwave_pb::plan::ColumnDesc;
 use serde::de::Deserialize;
 use serde_protobuf::de::Deserializer;
@@ -27,7 +32,7 @@ use serde_protobuf::descriptor::{Descriptors, FieldDescriptor, FieldType};
 use serde_value::Value;
 use url::Url;
 
-use super::common::str_to_date;
+use super::common::{split_confluent_header, str_to_date};
 use crate::{Event, SourceColumnDesc, SourceParser};
 
 /// Parser for Protobuf-encoded bytes.
@@ -35,6 +40,9 @@ use crate::{Event, SourceColumnDesc, SourceParser};
 pub struct ProtobufParser {
     descriptors: Descriptors,
     message_name: String,
+    /// Whether the messages are in the Confluent wire format, i.e. the schema id and message
+    /// indexes are prepended to the data. It's true if the schema is from a schema registry.
+    confluent_wire_format: bool,
 }
 
 impl ProtobufParser {
@@ -86,6 +94,7 @@ impl ProtobufParser {
         Ok(ProtobufParser {
             descriptors: Descriptors::from_proto(&file_descriptor_set),
             message_name: Self::normalize_message_name(message_name),
+            confluent_wire_format: false,
         })
     }
 
@@ -115,6 +124,37 @@ impl ProtobufParser {
         }
     }
 
+    /// Create a protobuf parser from the latest schema of `subject` in a Confluent schema
+    /// registry. The fetched schema is returned as well, so that the caller can record which
+    /// version the parser is built from.
+    pub async fn new_from_schema_registry(
+        url: &str,
+        subject: &str,
+        message_name: &str,
+    ) -> Result<(Self, SubjectSchema)> {
+        let client = SchemaRegistryClient::new(url.to_string());
+        let schema = client
+            .get_latest_schema(subject)
+            .await
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
+        if schema.schema_type() != "PROTOBUF" {
+            return Err(RwError::from(ProtocolError(format!(
+                "expected a PROTOBUF schema for subject {}, got {}",
+                subject,
+                schema.schema_type()
+            ))));
+        }
+
+        // The parser only accepts files, so the schema is written to a temporary one.
+        let dir = tempfile::tempdir().map_err(|e| InternalError(e.to_string()))?;
+        let path = dir.path().join(format!("{}.proto", schema.id));
+        std::fs::write(&path, &schema.schema).map_err(|e| InternalError(e.to_string()))?;
+        let mut parser = Self::new_from_local(&[dir.path()], &[path.as_path()], message_name)?;
+        parser.confluent_wire_format = true;
+
+        Ok((parser, schema))
+    }
+
     /// Maps the protobuf schema to relational schema.
     pub fn map_to_columns(&self) -> Result<Vec<ColumnDesc>> {
         let msg = match self.descriptors.message_by_name(self.message_name.as_str()) {
@@ -141,35 +181,39 @@ impl ProtobufParser {
     ) -> Result<ColumnDesc> {
         let field_type = field_descriptor.field_type(descriptors);
         let data_type = protobuf_type_mapping(field_descriptor, descriptors)?;
-        if let FieldType::Message(m) = field_type {
-            let column_vec = m
-                .fields()
-                .iter()
-                .map(|f| {
-                    Self::pb_field_to_col_desc(
-                        f,
-                        descriptors,
-                        lastname.clone() + field_descriptor.name() + ".",
-                        index,
-                    )
+        match field_type {
+            // Well-known types are mapped to atomic columns of native types.
+            FieldType::Message(m) if well_known_type_mapping(m.name()).is_none() => {
+                let column_vec = m
+                    .fields()
+                    .iter()
+                    .map(|f| {
+                        Self::pb_field_to_col_desc(
+                            f,
+                            descriptors,
+                            lastname.clone() + field_descriptor.name() + ".",
+                            index,
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                *index += 1;
+                Ok(ColumnDesc {
+                    column_id: *index, // need increment
+                    name: lastname + field_descriptor.name(),
+                    column_type: Some(data_type.to_protobuf()),
+                    field_descs: column_vec,
+                    type_name: m.name().to_string(),
                 })
-                .collect::<Result<Vec<_>>>()?;
-            *index += 1;
-            Ok(ColumnDesc {
-                column_id: *index, // need increment
-                name: lastname + field_descriptor.name(),
-                column_type: Some(data_type.to_protobuf()),
-                field_descs: column_vec,
-                type_name: m.name().to_string(),
-            })
-        } else {
-            *index += 1;
-            Ok(ColumnDesc {
-                column_id: *index, // need increment
-                name: lastname + field_descriptor.name(),
-                column_type: Some(data_type.to_protobuf()),
-                ..Default::default()
-            })
+            }
+            _ => {
+                *index += 1;
+                Ok(ColumnDesc {
+                    column_id: *index, // need increment
+                    name: lastname + field_descriptor.name(),
+                    column_type: Some(data_type.to_protobuf()),
+                    ..Default::default()
+                })
+            }
         }
     }
 }
@@ -205,14 +249,18 @@ fn protobuf_type_mapping(f: &FieldDescriptor, descriptors: &Descriptors) -> Resu
         FieldType::Int32 | FieldType::SFixed32 | FieldType::SInt32 => DataType::Int32,
         FieldType::Bool => DataType::Boolean,
         FieldType::String => DataType::Varchar,
-        FieldType::Message(m) => {
-            let vec = m
-                .fields()
-                .iter()
-                .map(|f| protobuf_type_mapping(f, descriptors))
-                .collect::<Result<Vec<_>>>()?;
-            DataType::Struct { fields: vec.into() }
-        }
+        FieldType::Bytes => DataType::Bytea,
+        FieldType::Message(m) => match well_known_type_mapping(m.name()) {
+            Some(t) => t,
+            None => {
+                let vec = m
+                    .fields()
+                    .iter()
+                    .map(|f| protobuf_type_mapping(f, descriptors))
+                    .collect::<Result<Vec<_>>>()?;
+                DataType::Struct { fields: vec.into() }
+            }
+        },
         actual_type => {
             return Err(ErrorCode::NotImplemented(
                 format!("unsupported field type: {:?}", actual_type),
@@ -224,8 +272,172 @@ fn protobuf_type_mapping(f: &FieldDescriptor, descriptors: &Descriptors) -> Resu
     Ok(t)
 }
 
+/// Maps a well-known type of protobuf to a DB column type, instead of a struct of its fields:
+/// * `Timestamp` is mapped to `TIMESTAMP`, with nanoseconds truncated to microseconds.
+/// * `Duration` is mapped to `INTERVAL`, with nanoseconds truncated to milliseconds.
+/// * `Struct` is mapped to `VARCHAR`, holding the struct as a JSON object.
+/// * Wrappers like `Int32Value` are mapped to the type of the wrapped value, and are null when
+///   absent.
+fn well_known_type_mapping(message_name: &str) -> Option<DataType> {
+    let t = match message_name {
+        ".google.protobuf.Timestamp" => DataType::Timestamp,
+        ".google.protobuf.Duration" => DataType::Interval,
+        ".google.protobuf.Struct" => DataType::Varchar,
+        ".google.protobuf.DoubleValue" => DataType::Float64,
+        ".google.protobuf.FloatValue" => DataType::Float32,
+        ".google.protobuf.Int64Value" => DataType::Int64,
+        ".google.protobuf.UInt64Value" => DataType::Decimal,
+        ".google.protobuf.Int32Value" => DataType::Int32,
+        ".google.protobuf.UInt32Value" => DataType::Int64,
+        ".google.protobuf.BoolValue" => DataType::Boolean,
+        ".google.protobuf.StringValue" => DataType::Varchar,
+        ".google.protobuf.BytesValue" => DataType::Bytea,
+        _ => return None,
+    };
+    Some(t)
+}
+
+/// Strips the `Option`s around a decoded value, and unwraps the message of a wrapper type, which
+/// only has a `value` field.
+fn unwrap_value(value: Option<Value>) -> Option<Value> {
+    match value? {
+        Value::Option(v) => unwrap_value(v.map(|v| *v)),
+        Value::Map(mut m) if m.len() == 1 && m.contains_key(&Value::String("value".into())) => {
+            unwrap_value(m.remove(&Value::String("value".into())))
+        }
+        v => Some(v),
+    }
+}
+
+/// Gets a field of a decoded message. `Option`s around the value are stripped.
+fn message_field(message: &mut BTreeMap<Value, Value>, name: &str) -> Option<Value> {
+    let mut value = message.remove(&Value::String(name.to_string()))?;
+    while let Value::Option(v) = value {
+        value = *v?;
+    }
+    Some(value)
+}
+
+/// Gets the `seconds` and `nanos` fields of a decoded `Timestamp` or `Duration`. Absent fields
+/// have the default value 0.
+fn seconds_and_nanos(value: Value) -> Option<(i64, i32)> {
+    let mut message = match value {
+        Value::Map(m) => m,
+        _ => return None,
+    };
+    let seconds = match message_field(&mut message, "seconds") {
+        Some(Value::I64(v)) => v,
+        None => 0,
+        _ => return None,
+    };
+    let nanos = match message_field(&mut message, "nanos") {
+        Some(Value::I32(v)) => v,
+        None => 0,
+        _ => return None,
+    };
+    Some((seconds, nanos))
+}
+
+/// Converts a decoded `google.protobuf.Struct` to a JSON object.
+fn struct_to_json(value: Value) -> Option<serde_json::Value> {
+    let mut message = match value {
+        Value::Map(m) => m,
+        _ => return None,
+    };
+    // The `map<string, Value>` field is decoded either as a map or as a sequence of entries.
+    let fields = match message_field(&mut message, "fields") {
+        Some(Value::Map(m)) => m.into_iter().collect(),
+        Some(Value::Seq(entries)) => entries
+            .into_iter()
+            .map(|entry| match entry {
+                Value::Map(mut entry) => Some((
+                    message_field(&mut entry, "key")?,
+                    message_field(&mut entry, "value")?,
+                )),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?,
+        None => vec![],
+        _ => return None,
+    };
+    let object = fields
+        .into_iter()
+        .map(|(key, value)| match key {
+            Value::String(key) => Some((key, struct_value_to_json(value)?)),
+            _ => None,
+        })
+        .collect::<Option<serde_json::Map<_, _>>>()?;
+    Some(serde_json::Value::Object(object))
+}
+
+/// Converts a decoded `google.protobuf.Value` to a JSON value, according to the field set in its
+/// `kind`.
+fn struct_value_to_json(value: Value) -> Option<serde_json::Value> {
+    let mut message = match value {
+        Value::Map(m) => m,
+        _ => return None,
+    };
+    if let Some(v) = message_field(&mut message, "struct_value") {
+        return struct_to_json(v);
+    }
+    if let Some(Value::Map(mut list)) = message_field(&mut message, "list_value") {
+        let values = match message_field(&mut list, "values") {
+            Some(Value::Seq(values)) => values,
+            None => vec![],
+            _ => return None,
+        };
+        return values
+            .into_iter()
+            .map(struct_value_to_json)
+            .collect::<Option<Vec<_>>>()
+            .map(serde_json::Value::Array);
+    }
+    let json = match message_field(&mut message, "string_value") {
+        Some(Value::String(v)) => serde_json::Value::String(v),
+        _ => match message_field(&mut message, "number_value") {
+            Some(Value::F64(v)) => serde_json::Number::from_f64(v)?.into(),
+            _ => match message_field(&mut message, "bool_value") {
+                Some(Value::Bool(v)) => serde_json::Value::Bool(v),
+                _ => serde_json::Value::Null,
+            },
+        },
+    };
+    Some(json)
+}
+
+/// Reads a zigzag-encoded varint, and returns it with the remaining data.
+fn read_zigzag_varint(data: &[u8]) -> Result<(i64, &[u8])> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            let value = (value >> 1) as i64 ^ -((value & 1) as i64);
+            return Ok((value, &data[i + 1..]));
+        }
+    }
+    Err(RwError::from(ProtocolError(
+        "invalid varint in protobuf message indexes".to_string(),
+    )))
+}
+
+/// Skips the message indexes following the header of the Confluent wire format, which locate the
+/// message type in the schema. The configured message type is always decoded instead.
+fn skip_message_indexes(data: &[u8]) -> Result<&[u8]> {
+    let (count, mut data) = read_zigzag_varint(data)?;
+    for _ in 0..count {
+        data = read_zigzag_varint(data)?.1;
+    }
+    Ok(data)
+}
+
 impl SourceParser for ProtobufParser {
     fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        let payload = if self.confluent_wire_format {
+            let (_, data) = split_confluent_header(payload)?;
+            skip_message_indexes(data)?
+        } else {
+            payload
+        };
         let mut map = match self.decode(payload)? {
             Value::Map(m) => m,
             _ => return Err(RwError::from(ProtocolError("".to_string()))),
@@ -239,7 +451,7 @@ impl SourceParser for ProtobufParser {
             let key = Value::String(column.name.clone());
 
             // Use `remove` instead of `get` to take the ownership of the value
-            let value = map.remove(&key);
+            let value = unwrap_value(map.remove(&key));
             match column.data_type {
                 DataType::Boolean => {
                     protobuf_match_type!(value, ScalarImpl::Bool, { Bool }, bool)
@@ -263,7 +475,15 @@ impl SourceParser for ProtobufParser {
                     protobuf_match_type!(value, ScalarImpl::Decimal, { I8, I16, I32, I64, U8, U16, U32, U64}, Decimal)
                 }
                 DataType::Varchar => {
-                    protobuf_match_type!(value, ScalarImpl::Utf8, { String }, String)
+                    value.and_then(|v| match v {
+                        Value::String(b) => Some(b),
+                        // A `google.protobuf.Struct`.
+                        v @ Value::Map(_) => struct_to_json(v).map(|json| json.to_string()),
+                        _ => None,
+                    }).map(ScalarImpl::Utf8)
+                }
+                DataType::Bytea => {
+                    protobuf_match_type!(value, ScalarImpl::Bytea, { Bytes }, Box<[u8]>)
                 }
                 DataType::Date => {
                     value.and_then(|v| match v {
@@ -275,6 +495,19 @@ impl SourceParser for ProtobufParser {
                         _ => None,
                     }).map(ScalarImpl::NaiveDate)
                 }
+                DataType::Timestamp => {
+                    value.and_then(seconds_and_nanos).and_then(|(seconds, nanos)| {
+                        // Truncate to microseconds, the precision of `TIMESTAMP`.
+                        let nanos = (nanos - nanos % 1000).try_into().ok()?;
+                        NaiveDateTimeWrapper::new_with_secs_nsecs(seconds, nanos).ok()
+                    }).map(ScalarImpl::NaiveDateTime)
+                }
+                DataType::Interval => {
+                    value.and_then(seconds_and_nanos).map(|(seconds, nanos)| {
+                        // Truncate to milliseconds, the precision of `INTERVAL`.
+                        IntervalUnit::from_millis(seconds * 1000 + (nanos / 1_000_000) as i64)
+                    }).map(ScalarImpl::Interval)
+                }
                 _ => unimplemented!(),
             }
         }).collect::<Vec<Datum>>();
@@ -293,7 +526,7 @@ mod tests {
     use maplit::hashmap;
     use risingwave_common::catalog::ColumnId;
     use risingwave_common::error::Result;
-    use risingwave_common::types::{DataType, ScalarImpl};
+    use risingwave_common::types::{DataType, IntervalUnit, NaiveDateTimeWrapper, ScalarImpl};
     use risingwave_pb::plan::ColumnDesc;
     use serde_value::Value;
     use tempfile::Builder;
@@ -322,6 +555,13 @@ mod tests {
     static PRE_GEN_PROTO_DATA: &[u8] = b"\x08\x7b\x12\x0c\x74\x65\x73\x74\x20\x61\x64\x64\x72\x65\x73\x73\x1a\x09\x74\x65\x73\x74\x20\x63\x69\x74\x79\x20\xc8\x03\x2d\x19\x04\x9e\x3f\x32\x0a\x32\x30\x32\x31\x2d\x30\x31\x2d\x30\x31";
 
     fn create_parser(proto_data: &str) -> Result<ProtobufParser> {
+        create_parser_with_message_name(proto_data, ".test.TestRecord")
+    }
+
+    fn create_parser_with_message_name(
+        proto_data: &str,
+        message_name: &str,
+    ) -> Result<ProtobufParser> {
         let temp_file = Builder::new()
             .prefix("temp")
             .suffix(".proto")
@@ -334,7 +574,7 @@ mod tests {
         file.write_all(proto_data.as_ref())
             .expect("writing binary to test file");
 
-        ProtobufParser::new(format!("file://{}", path).as_str(), ".test.TestRecord")
+        ProtobufParser::new(format!("file://{}", path).as_str(), message_name)
     }
 
     static PROTO_NESTED_FILE_DATA: &str = r#"
@@ -356,6 +596,43 @@ mod tests {
       string zipcode = 2;
     }"#;
 
+    // The well-known types are declared in place, so that no include path is needed.
+    static PROTO_WELL_KNOWN_FILE_DATA: &str = r#"
+    syntax = "proto3";
+    package google.protobuf;
+    message Timestamp {
+      int64 seconds = 1;
+      int32 nanos = 2;
+    }
+    message Duration {
+      int64 seconds = 1;
+      int32 nanos = 2;
+    }
+    message Int32Value {
+      int32 value = 1;
+    }
+    message Value {
+      oneof kind {
+        string string_value = 3;
+      }
+    }
+    message Struct {
+      map<string, Value> fields = 1;
+    }
+    message TestRecord {
+      Timestamp ts = 1;
+      Int32Value id = 2;
+      Duration elapsed = 3;
+      Struct attrs = 4;
+      Int32Value absent = 5;
+    }"#;
+
+    //    Ts:      { Seconds: 1600000000, Nanos: 123456789 },
+    //    Id:      { Value: 456 },
+    //    Elapsed: { Seconds: 90, Nanos: 500000000 },
+    //    Attrs:   { "a": "b" }
+    static PRE_GEN_PROTO_WELL_KNOWN_DATA: &[u8] = b"\x0a\x0b\x08\x80\xa0\xf8\xfa\x05\x10\x95\x9a\xef\x3a\x12\x03\x08\xc8\x03\x1a\x08\x08\x5a\x10\x80\xca\xb5\xee\x01\x22\x0a\x0a\x08\x0a\x01\x61\x12\x03\x1a\x01\x62";
+
     #[test]
     fn test_proto_message_name() {
         assert_eq!(ProtobufParser::normalize_message_name(""), "".to_string());
@@ -476,6 +753,51 @@ mod tests {
         ))))
     }
 
+    #[test]
+    fn test_parser_parse_well_known_types() {
+        let parser = create_parser_with_message_name(
+            PROTO_WELL_KNOWN_FILE_DATA,
+            ".google.protobuf.TestRecord",
+        )
+        .unwrap();
+        let columns = parser.map_to_columns().unwrap();
+        assert_eq!(
+            columns,
+            vec![
+                ColumnDesc::new_atomic(DataType::Timestamp.to_protobuf(), "ts", 1),
+                ColumnDesc::new_atomic(DataType::Int32.to_protobuf(), "id", 2),
+                ColumnDesc::new_atomic(DataType::Interval.to_protobuf(), "elapsed", 3),
+                ColumnDesc::new_atomic(DataType::Varchar.to_protobuf(), "attrs", 4),
+                ColumnDesc::new_atomic(DataType::Int32.to_protobuf(), "absent", 5),
+            ]
+        );
+
+        let descs = columns
+            .iter()
+            .map(|c| SourceColumnDesc {
+                name: c.name.clone(),
+                data_type: DataType::from(c.column_type.as_ref().unwrap()),
+                column_id: ColumnId::from(c.column_id),
+                skip_parse: false,
+            })
+            .collect::<Vec<_>>();
+        let event = parser.parse(PRE_GEN_PROTO_WELL_KNOWN_DATA, &descs).unwrap();
+        let data = event.rows.first().unwrap();
+        assert_eq!(
+            data[0],
+            Some(ScalarImpl::NaiveDateTime(
+                NaiveDateTimeWrapper::new_with_secs_nsecs(1_600_000_000, 123_456_000).unwrap()
+            ))
+        );
+        assert_eq!(data[1], Some(ScalarImpl::Int32(456)));
+        assert_eq!(
+            data[2],
+            Some(ScalarImpl::Interval(IntervalUnit::from_millis(90_500)))
+        );
+        assert_eq!(data[3], Some(ScalarImpl::Utf8(r#"{"a":"b"}"#.to_string())));
+        assert_eq!(data[4], None);
+    }
+
     #[test]
     fn test_map_to_columns() {
         use risingwave_common::types::*;
@@ -12,14 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
+use parking_lot::Mutex;
 use rand::prelude::SliceRandom;
 use risingwave_common::array::StreamChunk;
 use risingwave_common::catalog::{ColumnDesc, ColumnId};
 use risingwave_common::error::Result;
+use risingwave_pb::plan::InsertOnConflict;
 use tokio::sync::{mpsc, oneshot};
 
 use crate::{BatchSourceReader, Source, StreamSourceReader};
@@ -48,6 +51,47 @@ pub struct TableSourceV2 {
 
     /// Current allocated row id.
     next_row_id: AtomicUsize,
+
+    /// The rows inserted by `INSERT ... ON CONFLICT` by their row ids, until resolved by the
+    /// materialize executor of the table.
+    on_conflict_rows: Mutex<HashMap<i64, Arc<OnConflictWrite>>>,
+}
+
+/// A chunk inserted by `INSERT ... ON CONFLICT`. Its rows are resolved against the existing ones
+/// by the materialize executor of the table, which counts the rows actually inserted or updated.
+#[derive(Debug)]
+pub struct OnConflictWrite {
+    on_conflict: Arc<InsertOnConflict>,
+
+    /// The rows not resolved yet.
+    pending_rows: AtomicUsize,
+
+    /// The rows inserted or updated so far.
+    affected_rows: AtomicUsize,
+
+    /// Notified of `affected_rows` once all the rows are resolved.
+    notifier: Mutex<Option<oneshot::Sender<usize>>>,
+}
+
+impl OnConflictWrite {
+    pub fn on_conflict(&self) -> &Arc<InsertOnConflict> {
+        &self.on_conflict
+    }
+
+    /// Marks a row as resolved, where `affected` tells whether it's inserted or updated rather than
+    /// skipped.
+    pub fn resolve_row(&self, affected: bool) {
+        if affected {
+            self.affected_rows.fetch_add(1, Ordering::SeqCst);
+        }
+        if self.pending_rows.fetch_sub(1, Ordering::SeqCst) == 1 {
+            if let Some(notifier) = self.notifier.lock().take() {
+                notifier
+                    .send(self.affected_rows.load(Ordering::SeqCst))
+                    .ok();
+            }
+        }
+    }
 }
 
 impl TableSourceV2 {
@@ -60,6 +104,7 @@ impl TableSourceV2 {
             core: RwLock::new(core),
             column_descs,
             next_row_id: 0.into(),
+            on_conflict_rows: Mutex::new(HashMap::new()),
         }
     }
 
@@ -92,6 +137,45 @@ impl TableSourceV2 {
         Ok(notifier_rx)
     }
 
+    /// Writes `chunk` inserted by `INSERT ... ON CONFLICT`, whose rows have `row_ids`, into table
+    /// like `write_chunk`. The rows are resolved by `on_conflict` in the materialize executor of
+    /// the table, which looks them up with [`TableSourceV2::take_on_conflict_row`].
+    ///
+    /// Returns an oneshot channel which will be notified when all the rows are resolved, and the
+    /// `usize` represents the number of rows actually inserted or updated.
+    pub fn write_chunk_on_conflict(
+        &self,
+        chunk: StreamChunk,
+        row_ids: &[i64],
+        on_conflict: Arc<InsertOnConflict>,
+    ) -> Result<oneshot::Receiver<usize>> {
+        let (notifier_tx, notifier_rx) = oneshot::channel();
+        if row_ids.is_empty() {
+            notifier_tx.send(0).ok();
+            return Ok(notifier_rx);
+        }
+
+        let write = Arc::new(OnConflictWrite {
+            on_conflict,
+            pending_rows: row_ids.len().into(),
+            affected_rows: 0.into(),
+            notifier: Mutex::new(Some(notifier_tx)),
+        });
+        self.on_conflict_rows
+            .lock()
+            .extend(row_ids.iter().map(|row_id| (*row_id, write.clone())));
+
+        // The rows are counted once resolved instead of taken.
+        self.write_chunk(chunk)?;
+        Ok(notifier_rx)
+    }
+
+    /// Takes the `INSERT ... ON CONFLICT` the row of `row_id` is inserted by, if any. The row must
+    /// be resolved with [`OnConflictWrite::resolve_row`] then.
+    pub fn take_on_conflict_row(&self, row_id: i64) -> Option<Arc<OnConflictWrite>> {
+        self.on_conflict_rows.lock().remove(&row_id)
+    }
+
     /// Write stream chunk into table using `write_chunk`, and then block until a reader consumes
     /// the chunk.
     ///
@@ -71,6 +71,10 @@ pub enum DataType {
     String,
     /// Bytea
     Bytea,
+    /// Fixed-length bit string e.g. BIT(8)
+    Bit(Option<u64>),
+    /// Variable-length bit string e.g. BIT VARYING(8) or VARBIT(8)
+    Varbit(Option<u64>),
     /// Custom type such as enums
     Custom(ObjectName),
     /// Arrays
@@ -116,6 +120,8 @@ impl fmt::Display for DataType {
             DataType::Text => write!(f, "TEXT"),
             DataType::String => write!(f, "STRING"),
             DataType::Bytea => write!(f, "BYTEA"),
+            DataType::Bit(size) => format_type_with_optional_length(f, "BIT", size),
+            DataType::Varbit(size) => format_type_with_optional_length(f, "BIT VARYING", size),
             DataType::Array(ty) => write!(f, "{}[]", ty),
             DataType::Custom(ty) => write!(f, "{}", ty),
         }
@@ -189,6 +189,10 @@ pub enum TableConstraint {
         name: Option<Ident>,
         expr: Box<Expr>,
     },
+    /// `WATERMARK FOR <column> AS <expr>`
+    Watermark { column: Ident, expr: Box<Expr> },
+    /// `EVENT TIME AS <expr>`
+    EventTime { expr: Box<Expr> },
 }
 
 impl fmt::Display for TableConstraint {
@@ -232,6 +236,10 @@ impl fmt::Display for TableConstraint {
             TableConstraint::Check { name, expr } => {
                 write!(f, "{}CHECK ({})", display_constraint_name(name), expr)
             }
+            TableConstraint::Watermark { column, expr } => {
+                write!(f, "WATERMARK FOR {} AS {}", column, expr)
+            }
+            TableConstraint::EventTime { expr } => write!(f, "EVENT TIME AS {}", expr),
         }
     }
 }
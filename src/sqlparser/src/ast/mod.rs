@@ -43,7 +43,9 @@ pub use self::query::{
 };
 pub use self::statement::*;
 pub use self::value::{DateTimeField, TrimWhereField, Value};
-use crate::keywords::Keyword;
+use crate::keywords::{
+    Keyword, ALL_KEYWORDS, ALL_KEYWORDS_INDEX, RESERVED_FOR_COLUMN_ALIAS, RESERVED_FOR_TABLE_ALIAS,
+};
 use crate::parser::{Parser, ParserError};
 
 pub struct DisplaySeparated<'a, T>
@@ -118,6 +120,41 @@ impl Ident {
             quote_style: Some(quote),
         }
     }
+
+    /// Create an identifier referring to `value` as it is, i.e. whose [`Ident::real_value`] is
+    /// `value`. It's quoted only if necessary, e.g. `value` has upper case letters or is a
+    /// reserved keyword, as PostgreSQL's `quote_ident` does.
+    pub fn from_real_value(value: &str) -> Self {
+        let is_plain = value
+            .chars()
+            .enumerate()
+            .all(|(i, ch)| ch.is_ascii_lowercase() || ch == '_' || (i > 0 && ch.is_ascii_digit()));
+        if !value.is_empty() && is_plain && !Self::is_reserved_keyword(value) {
+            Self::new(value)
+        } else {
+            Self::with_quote('"', value)
+        }
+    }
+
+    fn is_reserved_keyword(value: &str) -> bool {
+        match ALL_KEYWORDS.binary_search(&value.to_uppercase().as_str()) {
+            Ok(idx) => {
+                let keyword = ALL_KEYWORDS_INDEX[idx];
+                RESERVED_FOR_TABLE_ALIAS.contains(&keyword)
+                    || RESERVED_FOR_COLUMN_ALIAS.contains(&keyword)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// The value the identifier refers to. As in PostgreSQL, an unquoted identifier is folded to
+    /// lower case, while a quoted one is case-sensitive.
+    pub fn real_value(&self) -> String {
+        match self.quote_style {
+            None => self.value.to_ascii_lowercase(),
+            Some(_) => self.value.clone(),
+        }
+    }
 }
 
 impl From<&str> for Ident {
@@ -138,8 +175,12 @@ impl ParseTo for Ident {
 impl fmt::Display for Ident {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.quote_style {
-            Some(q) if q == '"' || q == '\'' || q == '`' => write!(f, "{}{}{}", q, self.value, q),
-            Some(q) if q == '[' => write!(f, "[{}]", self.value),
+            // A quote in the value is escaped by doubling it.
+            Some(q) if q == '"' || q == '\'' || q == '`' => {
+                let value = self.value.replace(q, &q.to_string().repeat(2));
+                write!(f, "{}{}{}", q, value, q)
+            }
+            Some(q) if q == '[' => write!(f, "[{}]", self.value.replace(']', "]]")),
             None => f.write_str(&self.value),
             _ => panic!("unexpected quote style"),
         }
@@ -157,6 +198,13 @@ impl fmt::Display for ObjectName {
     }
 }
 
+impl ObjectName {
+    /// The real values of the parts, see [`Ident::real_value`].
+    pub fn real_values(&self) -> Vec<String> {
+        self.0.iter().map(Ident::real_value).collect()
+    }
+}
+
 impl ParseTo for ObjectName {
     fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
         p.parse_object_name()
@@ -633,6 +681,8 @@ pub enum ShowObject {
     MaterializedView { schema: Option<Ident> },
     Source { schema: Option<Ident> },
     MaterializedSource { schema: Option<Ident> },
+    Jobs,
+    PlanCache,
 }
 
 impl fmt::Display for ShowObject {
@@ -658,6 +708,8 @@ impl fmt::Display for ShowObject {
             ShowObject::MaterializedSource { schema } => {
                 write!(f, "MATERIALIZED SOURCES{}", fmt_schema(schema))
             }
+            ShowObject::Jobs => f.write_str("JOBS"),
+            ShowObject::PlanCache => f.write_str("PLAN CACHE"),
         }
     }
 }
@@ -697,6 +749,8 @@ pub enum Statement {
         columns: Vec<Ident>,
         /// A SQL query that specifies what to insert
         source: Box<Query>,
+        /// `ON CONFLICT ...`, what to do with the rows conflicting with the existing ones
+        on_conflict: Option<OnConflict>,
     },
     Copy {
         /// TABLE
@@ -867,6 +921,8 @@ pub enum Statement {
         analyze: bool,
         // Display additional information regarding the plan.
         verbose: bool,
+        /// Display the internal state tables each stream plan node will create.
+        state: bool,
         /// A SQL query that specifies what to explain
         statement: Box<Statement>,
     },
@@ -874,6 +930,10 @@ pub enum Statement {
     ///
     /// Note: RisingWave specific statement.
     Flush,
+    /// CANCEL JOB the creation of a materialized view, whose id is shown by `SHOW JOBS`.
+    ///
+    /// Note: RisingWave specific statement.
+    CancelJob { job_id: u64 },
 }
 
 impl fmt::Display for Statement {
@@ -886,6 +946,7 @@ impl fmt::Display for Statement {
                 describe_alias,
                 verbose,
                 analyze,
+                state,
                 statement,
             } => {
                 if *describe_alias {
@@ -894,6 +955,10 @@ impl fmt::Display for Statement {
                     write!(f, "EXPLAIN ")?;
                 }
 
+                if *state {
+                    write!(f, "(STATE) ")?;
+                }
+
                 if *analyze {
                     write!(f, "ANALYZE ")?;
                 }
@@ -929,12 +994,17 @@ impl fmt::Display for Statement {
                 table_name,
                 columns,
                 source,
+                on_conflict,
             } => {
                 write!(f, "INSERT INTO {table_name} ", table_name = table_name,)?;
                 if !columns.is_empty() {
                     write!(f, "({}) ", display_comma_separated(columns))?;
                 }
-                write!(f, "{}", source)
+                write!(f, "{}", source)?;
+                if let Some(on_conflict) = on_conflict {
+                    write!(f, " {}", on_conflict)?;
+                }
+                Ok(())
             }
 
             Statement::Copy {
@@ -1244,6 +1314,9 @@ impl fmt::Display for Statement {
             Statement::Flush => {
                 write!(f, "FLUSH")
             }
+            Statement::CancelJob { job_id } => {
+                write!(f, "CANCEL JOB {}", job_id)
+            }
         }
     }
 }
@@ -1268,6 +1341,45 @@ impl fmt::Display for OnInsert {
     }
 }
 
+/// `ON CONFLICT [ ( <columns> ) ] <action>` of INSERT (PostgreSQL)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OnConflict {
+    /// The columns of the unique constraint to detect conflicts on, empty if not specified
+    pub conflict_target: Vec<Ident>,
+    pub action: OnConflictAction,
+}
+
+impl fmt::Display for OnConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ON CONFLICT ")?;
+        if !self.conflict_target.is_empty() {
+            write!(f, "({}) ", display_comma_separated(&self.conflict_target))?;
+        }
+        write!(f, "{}", self.action)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OnConflictAction {
+    /// DO NOTHING
+    DoNothing,
+    /// DO UPDATE SET <assignments>
+    DoUpdate(Vec<Assignment>),
+}
+
+impl fmt::Display for OnConflictAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::DoNothing => write!(f, "DO NOTHING"),
+            Self::DoUpdate(assignments) => {
+                write!(f, "DO UPDATE SET {}", display_comma_separated(assignments))
+            }
+        }
+    }
+}
+
 /// Privileges granted in a GRANT statement or revoked in a REVOKE statement.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -1464,17 +1576,23 @@ pub struct Function {
     pub over: Option<WindowSpec>,
     // aggregate functions may specify eg `COUNT(DISTINCT x)`
     pub distinct: bool,
+    // aggregate functions may specify eg `STRING_AGG(x, ',' ORDER BY y)`
+    pub order_by: Vec<OrderByExpr>,
 }
 
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "{}({}{})",
+            "{}({}{}",
             self.name,
             if self.distinct { "DISTINCT " } else { "" },
             display_comma_separated(&self.args),
         )?;
+        if !self.order_by.is_empty() {
+            write!(f, " ORDER BY {}", display_comma_separated(&self.order_by))?;
+        }
+        write!(f, ")")?;
         if let Some(o) = &self.over {
             write!(f, " OVER ({})", o)?;
         }
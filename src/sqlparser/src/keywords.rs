@@ -96,6 +96,7 @@ define_keywords!(
     BETWEEN,
     BIGINT,
     BINARY,
+    BIT,
     BLOB,
     BOOL,
     BOOLEAN,
@@ -105,6 +106,7 @@ define_keywords!(
     CACHE,
     CALL,
     CALLED,
+    CANCEL,
     CARDINALITY,
     CASCADE,
     CASCADED,
@@ -130,6 +132,8 @@ define_keywords!(
     COMMIT,
     COMMITTED,
     CONDITION,
+    CONFLICT,
+    CONFLUENT,
     CONNECT,
     CONSTRAINT,
     CONTAINS,
@@ -178,6 +182,7 @@ define_keywords!(
     DIRECTORY,
     DISCONNECT,
     DISTINCT,
+    DO,
     DOUBLE,
     DROP,
     DYNAMIC,
@@ -249,6 +254,8 @@ define_keywords!(
     INTO,
     IS,
     ISOLATION,
+    JOB,
+    JOBS,
     JOIN,
     JSON,
     KEY,
@@ -292,6 +299,7 @@ define_keywords!(
     NORMALIZE,
     NOSCAN,
     NOT,
+    NOTHING,
     NTH_VALUE,
     NTILE,
     NULL,
@@ -326,6 +334,7 @@ define_keywords!(
     PERCENTILE_DISC,
     PERCENT_RANK,
     PERIOD,
+    PLAN,
     PORTION,
     POSITION,
     POSITION_REGEX,
@@ -350,6 +359,7 @@ define_keywords!(
     REFERENCES,
     REFERENCING,
     REGCLASS,
+    REGISTRY,
     REGR_AVGX,
     REGR_AVGY,
     REGR_COUNT,
@@ -410,6 +420,7 @@ define_keywords!(
     SQLWARNING,
     SQRT,
     START,
+    STATE,
     STATIC,
     STATISTICS,
     STDDEV_POP,
@@ -474,6 +485,7 @@ define_keywords!(
     VALUES,
     VALUE_OF,
     VARBINARY,
+    VARBIT,
     VARCHAR,
     VARYING,
     VAR_POP,
@@ -483,6 +495,7 @@ define_keywords!(
     VIEW,
     VIEWS,
     VIRTUAL,
+    WATERMARK,
     WHEN,
     WHENEVER,
     WHERE,
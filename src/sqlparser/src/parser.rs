@@ -193,6 +193,7 @@ impl Parser {
                 Keyword::PREPARE => Ok(self.parse_prepare()?),
                 Keyword::COMMENT => Ok(self.parse_comment()?),
                 Keyword::FLUSH => Ok(Statement::Flush),
+                Keyword::CANCEL => Ok(self.parse_cancel_job()?),
                 _ => self.expected("an SQL statement", Token::Word(w)),
             },
             Token::LParen => {
@@ -555,7 +556,7 @@ impl Parser {
     pub fn parse_function(&mut self, name: ObjectName) -> Result<Expr, ParserError> {
         self.expect_token(&Token::LParen)?;
         let distinct = self.parse_all_or_distinct()?;
-        let args = self.parse_optional_args()?;
+        let (args, order_by) = self.parse_optional_args_with_order_by()?;
         let over = if self.parse_keyword(Keyword::OVER) {
             // TBD: support window names (`OVER mywin`) in place of inline specification
             self.expect_token(&Token::LParen)?;
@@ -592,6 +593,7 @@ impl Parser {
             args,
             over,
             distinct,
+            order_by,
         }))
     }
 
@@ -1430,7 +1432,7 @@ impl Parser {
     // [COLUMNS]?
     // [WITH (properties)]?
     // ROW FORMAT <row_format: Ident>
-    // [ROW SCHEMA LOCATION <row_schema_location: String>]?
+    // [ROW SCHEMA LOCATION [CONFLUENT SCHEMA REGISTRY]? <row_schema_location: String>]?
     pub fn parse_create_source(
         &mut self,
         is_materialized: bool,
@@ -1686,6 +1688,34 @@ impl Parser {
                 self.expect_token(&Token::RParen)?;
                 Ok(Some(TableConstraint::Check { name, expr }))
             }
+            // `watermark` is still allowed as a column name.
+            Token::Word(w)
+                if w.keyword == Keyword::WATERMARK
+                    && name.is_none()
+                    && matches!(self.peek_token(), Token::Word(ref w) if w.keyword == Keyword::FOR) =>
+            {
+                self.expect_keyword(Keyword::FOR)?;
+                let column = self.parse_identifier()?;
+                self.expect_keyword(Keyword::AS)?;
+                let expr = Box::new(self.parse_expr()?);
+                Ok(Some(TableConstraint::Watermark { column, expr }))
+            }
+            Token::Word(w)
+                if w.keyword == Keyword::EVENT
+                    && name.is_none()
+                    && matches!(self.peek_token(), Token::Word(ref w) if w.keyword == Keyword::TIME) =>
+            {
+                self.expect_keyword(Keyword::TIME)?;
+                if self.parse_keyword(Keyword::AS) {
+                    let expr = Box::new(self.parse_expr()?);
+                    Ok(Some(TableConstraint::EventTime { expr }))
+                } else {
+                    // A column named `event` of type `TIME`.
+                    self.prev_token();
+                    self.prev_token();
+                    Ok(None)
+                }
+            }
             unexpected => {
                 if name.is_some() {
                     self.expected("PRIMARY, UNIQUE, FOREIGN, or CHECK", unexpected)
@@ -1987,6 +2017,14 @@ impl Parser {
                     }
                 }
                 Keyword::BYTEA => Ok(DataType::Bytea),
+                Keyword::BIT => {
+                    if self.parse_keyword(Keyword::VARYING) {
+                        Ok(DataType::Varbit(self.parse_optional_precision()?))
+                    } else {
+                        Ok(DataType::Bit(self.parse_optional_precision()?))
+                    }
+                }
+                Keyword::VARBIT => Ok(DataType::Varbit(self.parse_optional_precision()?)),
                 Keyword::NUMERIC | Keyword::DECIMAL | Keyword::DEC => {
                     let (precision, scale) = self.parse_optional_precision_scale()?;
                     Ok(DataType::Decimal(precision, scale))
@@ -2195,6 +2233,15 @@ impl Parser {
     }
 
     pub fn parse_explain(&mut self, describe_alias: bool) -> Result<Statement, ParserError> {
+        let state = match (self.peek_token(), self.peek_nth_token(1)) {
+            (Token::LParen, Token::Word(w)) if w.keyword == Keyword::STATE => {
+                self.next_token();
+                self.next_token();
+                self.expect_token(&Token::RParen)?;
+                true
+            }
+            _ => false,
+        };
         let analyze = self.parse_keyword(Keyword::ANALYZE);
         let verbose = self.parse_keyword(Keyword::VERBOSE);
 
@@ -2203,6 +2250,7 @@ impl Parser {
             describe_alias,
             analyze,
             verbose,
+            state,
             statement: Box::new(statement),
         })
     }
@@ -2516,6 +2564,12 @@ impl Parser {
                 Keyword::SCHEMAS => {
                     return Ok(Statement::ShowObjects(ShowObject::Schema));
                 }
+                Keyword::JOBS => {
+                    return Ok(Statement::ShowObjects(ShowObject::Jobs));
+                }
+                Keyword::PLAN if self.parse_keyword(Keyword::CACHE) => {
+                    return Ok(Statement::ShowObjects(ShowObject::PlanCache));
+                }
                 Keyword::MATERIALIZED => {
                     if self.parse_keyword(Keyword::VIEWS) {
                         return Ok(Statement::ShowObjects(ShowObject::MaterializedView {
@@ -2548,6 +2602,14 @@ impl Parser {
         })
     }
 
+    /// Parse `CANCEL JOB <id>`, assuming the `CANCEL` keyword is already consumed.
+    pub fn parse_cancel_job(&mut self) -> Result<Statement, ParserError> {
+        self.expect_keyword(Keyword::JOB)?;
+        Ok(Statement::CancelJob {
+            job_id: self.parse_literal_uint()?,
+        })
+    }
+
     /// Parser `from schema` after `show tables` and `show materialized views`, if not conclude
     /// `from` then use default schema name.
     pub fn parse_from_and_identifier(&mut self) -> Result<Option<Ident>, ParserError> {
@@ -2871,11 +2933,34 @@ impl Parser {
         let columns = self.parse_parenthesized_column_list(Optional)?;
 
         let source = Box::new(self.parse_query()?);
+        let on_conflict = if self.parse_keywords(&[Keyword::ON, Keyword::CONFLICT]) {
+            Some(self.parse_on_conflict()?)
+        } else {
+            None
+        };
 
         Ok(Statement::Insert {
             table_name,
             columns,
             source,
+            on_conflict,
+        })
+    }
+
+    /// Parse the rest of `ON CONFLICT [ ( <columns> ) ] DO NOTHING | DO UPDATE SET <assignments>`
+    pub fn parse_on_conflict(&mut self) -> Result<OnConflict, ParserError> {
+        let conflict_target = self.parse_parenthesized_column_list(Optional)?;
+        self.expect_keyword(Keyword::DO)?;
+        let action = if self.parse_keyword(Keyword::NOTHING) {
+            OnConflictAction::DoNothing
+        } else {
+            self.expect_keywords(&[Keyword::UPDATE, Keyword::SET])?;
+            let assignments = self.parse_comma_separated(Parser::parse_assignment)?;
+            OnConflictAction::DoUpdate(assignments)
+        };
+        Ok(OnConflict {
+            conflict_target,
+            action,
         })
     }
 
@@ -2926,6 +3011,25 @@ impl Parser {
         }
     }
 
+    /// Parse the arguments of an aggregate call, which may end with an `ORDER BY` clause, e.g.
+    /// `string_agg(a, ',' ORDER BY b DESC)`.
+    pub fn parse_optional_args_with_order_by(
+        &mut self,
+    ) -> Result<(Vec<FunctionArg>, Vec<OrderByExpr>), ParserError> {
+        if self.consume_token(&Token::RParen) {
+            Ok((vec![], vec![]))
+        } else {
+            let args = self.parse_comma_separated(Parser::parse_function_args)?;
+            let order_by = if self.parse_keywords(&[Keyword::ORDER, Keyword::BY]) {
+                self.parse_comma_separated(Parser::parse_order_by_expr)?
+            } else {
+                vec![]
+            };
+            self.expect_token(&Token::RParen)?;
+            Ok((args, order_by))
+        }
+    }
+
     /// Parse a comma-delimited list of projections after SELECT
     pub fn parse_select_item(&mut self) -> Result<SelectItem, ParserError> {
         match self.parse_wildcard_expr()? {
@@ -222,7 +222,7 @@ impl Token {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Word {
     /// The value of the token, without the enclosing quotes, and with the
-    /// escape sequences (if any) processed (TODO: escapes are not handled)
+    /// doubled closing quotes (if any) unescaped
     pub value: String,
     /// An identifier can be "quoted" (&lt;delimited identifier> in ANSI parlance).
     /// The standard and most implementations allow using double quotes for this,
@@ -237,7 +237,9 @@ impl fmt::Display for Word {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.quote_style {
             Some(s) if s == '"' || s == '[' || s == '`' => {
-                write!(f, "{}{}{}", s, self.value, Word::matching_end_quote(s))
+                let end = Word::matching_end_quote(s);
+                let value = self.value.replace(end, &end.to_string().repeat(2));
+                write!(f, "{}{}{}", s, value, end)
             }
             None => f.write_str(&self.value),
             _ => panic!("Unexpected quote_style!"),
@@ -246,7 +248,7 @@ impl fmt::Display for Word {
 }
 
 impl Word {
-    fn matching_end_quote(ch: char) -> char {
+    pub(crate) fn matching_end_quote(ch: char) -> char {
         match ch {
             '"' => '"', // ANSI and most dialects
             '[' => ']', // MS SQL
@@ -415,15 +417,24 @@ impl<'a> Tokenizer<'a> {
                 quote_start if is_delimited_identifier_start(quote_start) => {
                     chars.next(); // consume the opening quote
                     let quote_end = Word::matching_end_quote(quote_start);
-                    let s = peeking_take_while(chars, |ch| ch != quote_end);
-                    if chars.next() == Some(quote_end) {
-                        Ok(Some(Token::make_word(&s, Some(quote_start))))
-                    } else {
-                        self.tokenizer_error(format!(
-                            "Expected close delimiter '{}' before EOF.",
-                            quote_end
-                        ))
+                    let mut s = String::new();
+                    loop {
+                        s.push_str(&peeking_take_while(chars, |ch| ch != quote_end));
+                        if chars.next() != Some(quote_end) {
+                            return self.tokenizer_error(format!(
+                                "Expected close delimiter '{}' before EOF.",
+                                quote_end
+                            ));
+                        }
+                        // A doubled closing quote stands for the quote itself.
+                        if chars.peek() == Some(&quote_end) {
+                            chars.next();
+                            s.push(quote_end);
+                        } else {
+                            break;
+                        }
                     }
+                    Ok(Some(Token::make_word(&s, Some(quote_start))))
                 }
                 // numbers and period
                 '0'..='9' | '.' => {
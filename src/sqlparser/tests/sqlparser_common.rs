@@ -377,6 +377,7 @@ fn parse_select_count_wildcard() {
             args: vec![FunctionArg::Unnamed(FunctionArgExpr::Wildcard)],
             over: None,
             distinct: false,
+            order_by: vec![],
         }),
         expr_from_projection(only(&select.projection))
     );
@@ -395,6 +396,7 @@ fn parse_select_count_distinct() {
             }))],
             over: None,
             distinct: true,
+            order_by: vec![],
         }),
         expr_from_projection(only(&select.projection))
     );
@@ -412,6 +414,31 @@ fn parse_select_count_distinct() {
     );
 }
 
+#[test]
+fn parse_select_string_agg_order_by() {
+    let sql = "SELECT STRING_AGG(x, ',' ORDER BY y DESC) FROM customer";
+    let select = verified_only_select(sql);
+    assert_eq!(
+        &Expr::Function(Function {
+            name: ObjectName(vec![Ident::new("STRING_AGG")]),
+            args: vec![
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Identifier(Ident::new("x")))),
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Value(
+                    Value::SingleQuotedString(",".to_string())
+                ))),
+            ],
+            over: None,
+            distinct: false,
+            order_by: vec![OrderByExpr {
+                expr: Expr::Identifier(Ident::new("y")),
+                asc: Some(false),
+                nulls_first: None,
+            }],
+        }),
+        expr_from_projection(only(&select.projection))
+    );
+}
+
 #[test]
 fn parse_not() {
     let sql = "SELECT id FROM customer WHERE NOT salary = ''";
@@ -1093,6 +1120,7 @@ fn parse_select_having() {
                 args: vec![FunctionArg::Unnamed(FunctionArgExpr::Wildcard)],
                 over: None,
                 distinct: false,
+                order_by: vec![],
             })),
             op: BinaryOperator::Gt,
             right: Box::new(Expr::Value(number("1")))
@@ -1739,15 +1767,21 @@ fn parse_bad_constraint() {
 }
 
 fn run_explain_analyze(query: &str, expected_verbose: bool, expected_analyze: bool) {
+    run_explain(query, expected_verbose, expected_analyze, false)
+}
+
+fn run_explain(query: &str, expected_verbose: bool, expected_analyze: bool, expected_state: bool) {
     match verified_stmt(query) {
         Statement::Explain {
             describe_alias: _,
             analyze,
             verbose,
+            state,
             statement,
         } => {
             assert_eq!(verbose, expected_verbose);
             assert_eq!(analyze, expected_analyze);
+            assert_eq!(state, expected_state);
             assert_eq!("SELECT sqrt(id) FROM foo", statement.to_string());
         }
         _ => panic!("Unexpected Statement, must be Explain"),
@@ -1766,6 +1800,27 @@ fn parse_explain_analyze_with_simple_select() {
     );
 }
 
+#[test]
+fn parse_explain_state() {
+    run_explain(
+        "EXPLAIN (STATE) SELECT sqrt(id) FROM foo",
+        false,
+        false,
+        true,
+    );
+    run_explain(
+        "EXPLAIN (STATE) VERBOSE SELECT sqrt(id) FROM foo",
+        true,
+        false,
+        true,
+    );
+    // a parenthesized query is not an option list
+    assert!(matches!(
+        verified_stmt("EXPLAIN (SELECT 1)"),
+        Statement::Explain { state: false, .. }
+    ));
+}
+
 #[test]
 fn parse_named_argument_function() {
     let sql = "SELECT FUN(a => '1', b => '2') FROM foo";
@@ -1790,6 +1845,7 @@ fn parse_named_argument_function() {
             ],
             over: None,
             distinct: false,
+            order_by: vec![],
         }),
         expr_from_projection(only(&select.projection))
     );
@@ -1823,6 +1879,7 @@ fn parse_window_functions() {
                 window_frame: None,
             }),
             distinct: false,
+            order_by: vec![],
         }),
         expr_from_projection(&select.projection[0])
     );
@@ -2067,6 +2124,7 @@ fn parse_delimited_identifiers() {
             args: vec![],
             over: None,
             distinct: false,
+            order_by: vec![],
         }),
         expr_from_projection(&select.projection[1]),
     );
@@ -614,6 +614,57 @@ fn parse_prepare() {
     );
 }
 
+#[test]
+fn parse_insert_on_conflict() {
+    let stmt = verified_stmt("INSERT INTO t (a, b) VALUES (1, 2) ON CONFLICT (a) DO NOTHING");
+    match stmt {
+        Statement::Insert { on_conflict, .. } => assert_eq!(
+            on_conflict,
+            Some(OnConflict {
+                conflict_target: vec!["a".into()],
+                action: OnConflictAction::DoNothing,
+            })
+        ),
+        _ => unreachable!(),
+    }
+
+    let stmt = verified_stmt("INSERT INTO t VALUES (1, 2) ON CONFLICT DO NOTHING");
+    match stmt {
+        Statement::Insert { on_conflict, .. } => assert_eq!(
+            on_conflict,
+            Some(OnConflict {
+                conflict_target: vec![],
+                action: OnConflictAction::DoNothing,
+            })
+        ),
+        _ => unreachable!(),
+    }
+
+    let stmt = verified_stmt(
+        "INSERT INTO t SELECT * FROM s ON CONFLICT (a) DO UPDATE SET b = t.b + excluded.b",
+    );
+    match stmt {
+        Statement::Insert { on_conflict, .. } => assert_eq!(
+            on_conflict,
+            Some(OnConflict {
+                conflict_target: vec!["a".into()],
+                action: OnConflictAction::DoUpdate(vec![Assignment {
+                    id: vec!["b".into()],
+                    value: Expr::BinaryOp {
+                        left: Box::new(Expr::CompoundIdentifier(vec!["t".into(), "b".into()])),
+                        op: BinaryOperator::Plus,
+                        right: Box::new(Expr::CompoundIdentifier(vec![
+                            "excluded".into(),
+                            "b".into()
+                        ])),
+                    },
+                }]),
+            })
+        ),
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_pg_bitwise_binary_ops() {
     let bitwise_ops = &[
@@ -809,3 +860,41 @@ fn parse_comments() {
         _ => unreachable!(),
     }
 }
+
+#[test]
+fn parse_identifier_case_and_quotes() {
+    let select =
+        verified_only_select(r#"SELECT Foo, "Foo", "a ""quoted"" name" FROM "Sch"."T""1""#);
+    let idents = select
+        .projection
+        .iter()
+        .map(|item| match expr_from_projection(item) {
+            Identifier(ident) => ident.clone(),
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(idents[0].real_value(), "foo");
+    assert_eq!(idents[1].real_value(), "Foo");
+    assert_eq!(idents[2].real_value(), r#"a "quoted" name"#);
+    match only(select.from).relation {
+        TableFactor::Table { name, .. } => {
+            assert_eq!(name.real_values(), vec!["Sch", r#"T"1"#]);
+        }
+        _ => unreachable!(),
+    }
+
+    // Only quote the names that would be folded or can't be a plain identifier otherwise.
+    for (value, quoted) in [
+        ("foo_1", "foo_1"),
+        ("Foo", r#""Foo""#),
+        ("1foo", r#""1foo""#),
+        ("a b", r#""a b""#),
+        (r#"a"b"#, r#""a""b""#),
+        ("select", r#""select""#),
+        ("", r#""""#),
+    ] {
+        let ident = Ident::from_real_value(value);
+        assert_eq!(ident.to_string(), quoted);
+        assert_eq!(ident.real_value(), value);
+    }
+}
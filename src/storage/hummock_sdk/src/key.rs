@@ -140,6 +140,31 @@ fn next_key_no_alloc(key: &[u8]) -> Option<(&[u8], u8)> {
 
 // End Copyright 2016 TiKV Project Authors. Licensed under Apache-2.0.
 
+/// Returns the prefix of the root keyspace that `user_key` belongs to, i.e. the type byte followed
+/// by the operator id, executor id or table id. Unknown types are distinguished by the type byte
+/// only.
+///
+/// # Examples
+///
+/// ```rust
+/// use risingwave_hummock_sdk::key::keyspace_prefix;
+/// assert_eq!(
+///     keyspace_prefix(b"e\0\0\0\0\0\0\0\x01key"),
+///     b"e\0\0\0\0\0\0\0\x01"
+/// );
+/// assert_eq!(keyspace_prefix(b"t\0\0\0\x01key"), b"t\0\0\0\x01");
+/// assert_eq!(keyspace_prefix(b"xkey"), b"x");
+/// assert_eq!(keyspace_prefix(b""), b"");
+/// ```
+pub fn keyspace_prefix(user_key: &[u8]) -> &[u8] {
+    let len = match user_key.first() {
+        Some(b's') | Some(b'e') => 9,
+        Some(b't') => 5,
+        _ => 1,
+    };
+    &user_key[..std::cmp::min(len, user_key.len())]
+}
+
 /// [`FullKey`] can be created on either a `Vec<u8>` or a `&[u8]`.
 ///
 /// Its format is (`user_key`, `u64::MAX - epoch`).
@@ -17,6 +17,7 @@ pub use version_cmp::*;
 pub mod compact;
 pub mod key;
 pub mod key_range;
+pub mod version_delta;
 
 pub type HummockSSTableId = u64;
 pub type HummockRefCount = u64;
@@ -24,5 +25,7 @@ pub type HummockVersionId = u64;
 pub type HummockContextId = u32;
 pub type HummockEpoch = u64;
 pub const INVALID_EPOCH: HummockEpoch = 0;
+/// The physical time in milliseconds is kept in the higher bits of an epoch.
+pub const EPOCH_PHYSICAL_SHIFT_BITS: u8 = 16;
 pub const INVALID_VERSION_ID: HummockVersionId = 0;
 pub const FIRST_VERSION_ID: HummockVersionId = 1;
@@ -0,0 +1,206 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use risingwave_pb::hummock::{
+    HummockVersion, HummockVersionDelta, LevelDelta, LevelType, SstableInfo,
+};
+
+use crate::key_range::KeyRange;
+
+/// Builds the delta from `prev` to `next`. Applying it on `prev` with [`apply_version_delta`]
+/// reproduces `next`, except that the tables of an overlapping level may be in another order.
+pub fn build_version_delta(prev: &HummockVersion, next: &HummockVersion) -> HummockVersionDelta {
+    assert_eq!(prev.levels.len(), next.levels.len());
+    let level_deltas = prev
+        .levels
+        .iter()
+        .zip(next.levels.iter())
+        .enumerate()
+        .filter_map(|(level_idx, (prev_level, next_level))| {
+            let prev_ids: HashSet<_> = prev_level.table_infos.iter().map(|t| t.id).collect();
+            let next_ids: HashSet<_> = next_level.table_infos.iter().map(|t| t.id).collect();
+            let removed_table_ids: Vec<_> = prev_ids.difference(&next_ids).copied().collect();
+            let inserted_table_infos: Vec<_> = next_level
+                .table_infos
+                .iter()
+                .filter(|t| !prev_ids.contains(&t.id))
+                .cloned()
+                .collect();
+            if removed_table_ids.is_empty() && inserted_table_infos.is_empty() {
+                return None;
+            }
+            Some(LevelDelta {
+                level_idx: level_idx as u32,
+                removed_table_ids,
+                inserted_table_infos,
+            })
+        })
+        .collect();
+
+    let removed_uncommitted_epochs = prev
+        .uncommitted_epochs
+        .iter()
+        .map(|e| e.epoch)
+        .filter(|epoch| !next.uncommitted_epochs.iter().any(|e| e.epoch == *epoch))
+        .collect();
+    let inserted_uncommitted_epochs = next
+        .uncommitted_epochs
+        .iter()
+        .filter(|e| !prev.uncommitted_epochs.contains(e))
+        .cloned()
+        .collect();
+
+    HummockVersionDelta {
+        id: next.id,
+        prev_id: prev.id,
+        level_deltas,
+        removed_uncommitted_epochs,
+        inserted_uncommitted_epochs,
+        max_committed_epoch: next.max_committed_epoch,
+        safe_epoch: next.safe_epoch,
+    }
+}
+
+/// Applies `delta` on `version`, whose id must be the `prev_id` of `delta`.
+pub fn apply_version_delta(version: &mut HummockVersion, delta: &HummockVersionDelta) {
+    assert_eq!(
+        version.id, delta.prev_id,
+        "version delta {} -> {} can't be applied on version {}",
+        delta.prev_id, delta.id, version.id
+    );
+    for level_delta in &delta.level_deltas {
+        let level = &mut version.levels[level_delta.level_idx as usize];
+        level
+            .table_infos
+            .retain(|t| !level_delta.removed_table_ids.contains(&t.id));
+        level
+            .table_infos
+            .extend(level_delta.inserted_table_infos.iter().cloned());
+        if level.level_type == LevelType::Nonoverlapping as i32 {
+            // Tables of a non-overlapping level are read in the order of their key ranges.
+            level.table_infos.sort_by_cached_key(key_range_of);
+        }
+    }
+
+    version.uncommitted_epochs.retain(|e| {
+        !delta.removed_uncommitted_epochs.contains(&e.epoch)
+            && !delta
+                .inserted_uncommitted_epochs
+                .iter()
+                .any(|inserted| inserted.epoch == e.epoch)
+    });
+    version
+        .uncommitted_epochs
+        .extend(delta.inserted_uncommitted_epochs.iter().cloned());
+
+    version.id = delta.id;
+    version.max_committed_epoch = delta.max_committed_epoch;
+    version.safe_epoch = delta.safe_epoch;
+}
+
+fn key_range_of(table: &SstableInfo) -> KeyRange {
+    KeyRange::from(table.key_range.as_ref().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::hummock::{KeyRange as ProstKeyRange, Level, UncommittedEpoch};
+
+    use super::*;
+    use crate::key::key_with_epoch;
+
+    fn table(id: u64, left: &str, right: &str) -> SstableInfo {
+        SstableInfo {
+            id,
+            key_range: Some(ProstKeyRange {
+                left: key_with_epoch(left.as_bytes().to_vec(), 1),
+                right: key_with_epoch(right.as_bytes().to_vec(), 1),
+                inf: false,
+            }),
+            keyspace_stats: vec![],
+        }
+    }
+
+    fn version(
+        id: u64,
+        l0: Vec<SstableInfo>,
+        l1: Vec<SstableInfo>,
+        uncommitted_epochs: Vec<UncommittedEpoch>,
+        max_committed_epoch: u64,
+    ) -> HummockVersion {
+        HummockVersion {
+            id,
+            levels: vec![
+                Level {
+                    level_type: LevelType::Overlapping as i32,
+                    table_infos: l0,
+                },
+                Level {
+                    level_type: LevelType::Nonoverlapping as i32,
+                    table_infos: l1,
+                },
+            ],
+            uncommitted_epochs,
+            max_committed_epoch,
+            safe_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_version_delta() {
+        let prev = version(
+            1,
+            vec![table(1, "a", "c"), table(2, "b", "d")],
+            vec![table(3, "e", "f")],
+            vec![UncommittedEpoch {
+                epoch: 2,
+                tables: vec![table(4, "a", "b")],
+            }],
+            1,
+        );
+        // Commit epoch 2, and compact table 1 into level 1.
+        let next = version(
+            2,
+            vec![table(2, "b", "d"), table(4, "a", "b")],
+            vec![table(5, "a", "c"), table(3, "e", "f")],
+            vec![UncommittedEpoch {
+                epoch: 3,
+                tables: vec![table(6, "a", "b")],
+            }],
+            2,
+        );
+
+        let delta = build_version_delta(&prev, &next);
+        assert_eq!(delta.level_deltas.len(), 2);
+        assert_eq!(delta.level_deltas[0].removed_table_ids, vec![1]);
+        assert_eq!(delta.level_deltas[1].removed_table_ids, Vec::<u64>::new());
+        assert_eq!(delta.removed_uncommitted_epochs, vec![2]);
+
+        let mut applied = prev.clone();
+        apply_version_delta(&mut applied, &delta);
+        assert_eq!(applied, next);
+
+        // An empty delta only bumps the version.
+        let mut bumped = next.clone();
+        bumped.id = 3;
+        let delta = build_version_delta(&next, &bumped);
+        assert!(delta.level_deltas.is_empty());
+        assert!(delta.inserted_uncommitted_epochs.is_empty());
+        let mut applied = next;
+        apply_version_delta(&mut applied, &delta);
+        assert_eq!(applied, bumped);
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::{Buf, BufMut};
+use itertools::Itertools;
+use parking_lot::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+
+use super::{HummockError, HummockResult};
+
+/// `BlockAccessSketch` approximately tracks how often each block is read, so that the hottest
+/// blocks can be prefetched into the block cache after restart (see
+/// [`SstableStore::warm_up`](super::SstableStore::warm_up)).
+///
+/// At most `capacity` blocks are tracked. Once it's full, all the counts are halved and the blocks
+/// dropping to zero are forgotten, so that blocks no longer read age out.
+pub struct BlockAccessSketch {
+    counts: Mutex<HashMap<(u64, u64), u32>>,
+    capacity: usize,
+}
+
+impl BlockAccessSketch {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            counts: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// Loads the sketch persisted at `path`. Returns an empty sketch if there's no such file.
+    pub fn load(path: &Path, capacity: usize) -> HummockResult<Self> {
+        let sketch = Self::new(capacity);
+        let buf = match std::fs::read(path) {
+            Ok(buf) => buf,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(sketch),
+            Err(e) => return Err(HummockError::decode_error(e)),
+        };
+        sketch.decode(&buf)?;
+        Ok(sketch)
+    }
+
+    /// Persists the sketch to `path`. The file is replaced atomically, so that a crash during
+    /// persisting won't corrupt the previous one.
+    pub fn persist(&self, path: &Path) -> HummockResult<()> {
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, self.encode()).map_err(HummockError::encode_error)?;
+        std::fs::rename(&tmp_path, path).map_err(HummockError::encode_error)
+    }
+
+    pub fn record(&self, sst_id: u64, block_idx: u64) {
+        let mut counts = self.counts.lock();
+        let count = counts.entry((sst_id, block_idx)).or_default();
+        *count = count.saturating_add(1);
+        if counts.len() > self.capacity {
+            counts.retain(|_, count| {
+                *count /= 2;
+                *count > 0
+            });
+        }
+    }
+
+    /// Returns the `(sst_id, block_idx)` of the blocks from the hottest to the coldest.
+    pub fn hottest(&self) -> Vec<(u64, u64)> {
+        self.counts
+            .lock()
+            .iter()
+            .sorted_by(|(block_a, count_a), (block_b, count_b)| {
+                count_b.cmp(count_a).then(block_a.cmp(block_b))
+            })
+            .map(|(block, _)| *block)
+            .collect_vec()
+    }
+
+    /// Format:
+    ///
+    /// ```plain
+    /// | N (4B) | sst id (8B) | block idx (8B) | count (4B) | ... (N entries) |
+    /// ```
+    fn encode(&self) -> Vec<u8> {
+        let counts = self.counts.lock();
+        let mut buf = Vec::with_capacity(4 + counts.len() * 20);
+        buf.put_u32_le(counts.len() as u32);
+        for ((sst_id, block_idx), count) in counts.iter() {
+            buf.put_u64_le(*sst_id);
+            buf.put_u64_le(*block_idx);
+            buf.put_u32_le(*count);
+        }
+        buf
+    }
+
+    fn decode(&self, mut buf: &[u8]) -> HummockResult<()> {
+        if buf.remaining() < 4 {
+            return Err(HummockError::decode_error("block access sketch too short"));
+        }
+        let len = buf.get_u32_le() as usize;
+        if buf.remaining() != len * 20 {
+            return Err(HummockError::decode_error(
+                "block access sketch size mismatch",
+            ));
+        }
+        let mut counts = self.counts.lock();
+        for _ in 0..len {
+            let sst_id = buf.get_u64_le();
+            let block_idx = buf.get_u64_le();
+            counts.insert((sst_id, block_idx), buf.get_u32_le());
+        }
+        Ok(())
+    }
+
+    /// Persists the sketch to `path` every `interval`, and once more on shutdown.
+    pub fn start_persister(
+        self: Arc<Self>,
+        path: PathBuf,
+        interval: Duration,
+    ) -> (JoinHandle<()>, UnboundedSender<()>) {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::unbounded_channel();
+        let join_handle = tokio::spawn(async move {
+            let mut min_interval = tokio::time::interval(interval);
+            // The first tick completes immediately, when there's nothing new to persist.
+            min_interval.tick().await;
+            loop {
+                let shutdown = tokio::select! {
+                    // Wait for interval.
+                    _ = min_interval.tick() => false,
+                    // Shutdown persister.
+                    _ = shutdown_rx.recv() => true,
+                };
+                if let Err(e) = self.persist(&path) {
+                    tracing::warn!("Failed to persist block access sketch: {}", e);
+                }
+                if shutdown {
+                    tracing::info!("Block access sketch persister is shutting down");
+                    return;
+                }
+            }
+        });
+
+        (join_handle, shutdown_tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hottest() {
+        let sketch = BlockAccessSketch::new(4);
+        for (sst_id, block_idx, times) in [(1, 0, 3), (1, 1, 1), (2, 0, 2)] {
+            (0..times).for_each(|_| sketch.record(sst_id, block_idx));
+        }
+        assert_eq!(sketch.hottest(), vec![(1, 0), (2, 0), (1, 1)]);
+
+        // Halve the counts once more than `capacity` blocks are tracked.
+        sketch.record(3, 0);
+        sketch.record(3, 1);
+        assert_eq!(sketch.hottest(), vec![(1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn test_persist_and_load() {
+        let sketch = BlockAccessSketch::new(16);
+        for (sst_id, block_idx, times) in [(1, 0, 3), (1, 1, 1), (2, 0, 2)] {
+            (0..times).for_each(|_| sketch.record(sst_id, block_idx));
+        }
+
+        let path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let loaded = BlockAccessSketch::load(&path, 16).unwrap();
+        assert!(loaded.hottest().is_empty());
+
+        sketch.persist(&path).unwrap();
+        let loaded = BlockAccessSketch::load(&path, 16).unwrap();
+        assert_eq!(loaded.hottest(), sketch.hottest());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -17,32 +17,124 @@ use std::sync::Arc;
 use bytes::{BufMut, Bytes, BytesMut};
 use futures::Future;
 use moka::future::Cache;
+use parking_lot::RwLock;
+use risingwave_common::util::memory_budget::{MemoryBudget, MemoryBudgetRef};
 
 use super::{Block, HummockError, HummockResult, DEFAULT_ENTRY_SIZE};
 
+/// `BlockCache` caches the decoded blocks of sstables, shared by all iterators of the
+/// [`SstableStore`](super::SstableStore). The capacity bounds the total size of the cached blocks,
+/// beyond which the least recently used blocks are evicted (with a frequency-based admission).
 pub struct BlockCache {
-    inner: Cache<Bytes, Arc<Block>>,
+    inner: Arc<RwLock<Caches>>,
+    budget: MemoryBudgetRef,
+}
+
+struct Caches {
+    /// The cache blocks are read from and inserted into.
+    current: Cache<Bytes, Arc<Block>>,
+    /// The capacity `current` is built with.
+    capacity: usize,
+    /// The cache replaced by `current` on the last resize, whose blocks are being moved to
+    /// `current` in the background. Reads fall back to it until then.
+    retired: Option<Cache<Bytes, Arc<Block>>>,
+    /// Number of resizes, so that a finished migration doesn't clear a newer `retired` cache.
+    generation: u64,
 }
 
 impl BlockCache {
     pub fn new(capacity: usize) -> Self {
-        let cache: Cache<Bytes, Arc<Block>> = Cache::builder()
+        Self {
+            inner: Arc::new(RwLock::new(Caches {
+                current: Self::build(capacity),
+                capacity,
+                retired: None,
+                generation: 0,
+            })),
+            budget: Arc::new(MemoryBudget::new("block_cache", capacity)),
+        }
+    }
+
+    fn build(capacity: usize) -> Cache<Bytes, Arc<Block>> {
+        Cache::builder()
             .weigher(|_k, v: &Arc<Block>| v.len() as u32)
             .initial_capacity(capacity / DEFAULT_ENTRY_SIZE)
             .max_capacity(capacity as u64)
-            .build();
-        Self { inner: cache }
+            .build()
+    }
+
+    /// The memory budget of the cache, which may be resized by the memory governor.
+    pub fn budget(&self) -> MemoryBudgetRef {
+        self.budget.clone()
+    }
+
+    /// Returns the current cache and the retired one, if any. The cache can't be resized in place,
+    /// so once the budget is resized, an empty cache of the new capacity replaces the current one,
+    /// and the cached blocks are moved to it by a background task, which evicts the least valuable
+    /// blocks if the budget has shrunk. Blocks not moved yet are lost if the budget is resized
+    /// again before the task finishes.
+    fn caches(&self) -> (Cache<Bytes, Arc<Block>>, Option<Cache<Bytes, Arc<Block>>>) {
+        let capacity = self.budget.bytes();
+        {
+            let inner = self.inner.read();
+            if inner.capacity == capacity {
+                return (inner.current.clone(), inner.retired.clone());
+            }
+        }
+        let mut inner = self.inner.write();
+        if inner.capacity != capacity {
+            let retired = std::mem::replace(&mut inner.current, Self::build(capacity));
+            inner.capacity = capacity;
+            inner.retired = Some(retired.clone());
+            inner.generation += 1;
+            tokio::spawn(Self::migrate(
+                self.inner.clone(),
+                retired,
+                inner.current.clone(),
+                inner.generation,
+            ));
+        }
+        (inner.current.clone(), inner.retired.clone())
+    }
+
+    async fn migrate(
+        inner: Arc<RwLock<Caches>>,
+        from: Cache<Bytes, Arc<Block>>,
+        to: Cache<Bytes, Arc<Block>>,
+        generation: u64,
+    ) {
+        let blocks = from.iter().collect::<Vec<_>>();
+        drop(from);
+        for (key, block) in blocks {
+            to.insert(key.as_ref().clone(), block).await;
+        }
+        let mut inner = inner.write();
+        if inner.generation == generation {
+            inner.retired = None;
+        }
     }
 
     // TODO: Optimize for concurrent get https://github.com/singularity-data/risingwave/pull/627#discussion_r817354730.
     pub fn get(&self, sst_id: u64, block_idx: u64) -> Option<Arc<Block>> {
-        self.inner.get(&Self::key(sst_id, block_idx))
+        let key = Self::key(sst_id, block_idx);
+        let (current, retired) = self.caches();
+        let block = current
+            .get(&key)
+            .or_else(|| retired.and_then(|retired| retired.get(&key)));
+        match block {
+            Some(_) => self.budget.record_hit(),
+            None => self.budget.record_miss(),
+        }
+        block
     }
 
     pub async fn insert(&self, sst_id: u64, block_idx: u64, block: Arc<Block>) {
-        self.inner.insert(Self::key(sst_id, block_idx), block).await
+        let (current, _) = self.caches();
+        current.insert(Self::key(sst_id, block_idx), block).await
     }
 
+    /// Inserts the block fetched by `f` if it's not in the cache. Concurrent calls on the same
+    /// block only fetch it once. Hits and misses should have been recorded by a preceding `get`.
     pub async fn get_or_insert_with<F>(
         &self,
         sst_id: u64,
@@ -52,8 +144,13 @@ impl BlockCache {
     where
         F: Future<Output = HummockResult<Arc<Block>>>,
     {
-        self.inner
-            .try_get_with(Self::key(sst_id, block_idx), f)
+        let key = Self::key(sst_id, block_idx);
+        let (current, retired) = self.caches();
+        if let Some(block) = retired.and_then(|retired| retired.get(&key)) {
+            return Ok(block);
+        }
+        current
+            .try_get_with(key, f)
             .await
             .map_err(HummockError::other)
     }
@@ -65,3 +162,40 @@ impl BlockCache {
         key.freeze()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use risingwave_hummock_sdk::key::key_with_epoch;
+
+    use super::*;
+    use crate::hummock::{BlockBuilder, BlockBuilderOptions};
+
+    fn build_block(user_key: &[u8]) -> Arc<Block> {
+        let mut builder = BlockBuilder::new(BlockBuilderOptions::default());
+        builder.add(&key_with_epoch(user_key.to_vec(), 1), b"v");
+        Arc::new(Block::decode(builder.build()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_resize_keeps_blocks() {
+        let cache = BlockCache::new(1 << 20);
+        cache.insert(1, 0, build_block(b"k1")).await;
+        cache.insert(2, 0, build_block(b"k2")).await;
+
+        // The memory governor grows and then shrinks the budget.
+        cache.budget().set_bytes(2 << 20);
+        assert!(cache.get(1, 0).is_some());
+        assert!(cache.get(2, 0).is_some());
+        cache.budget().set_bytes(1 << 19);
+        assert!(cache.get(1, 0).is_some());
+        assert!(cache.get(2, 0).is_some());
+        assert!(cache.get(3, 0).is_none());
+
+        // The blocks are moved to the resized cache in the background.
+        while cache.inner.read().retired.is_some() {
+            tokio::task::yield_now().await;
+        }
+        assert!(cache.get(1, 0).is_some());
+        assert!(cache.get(2, 0).is_some());
+    }
+}
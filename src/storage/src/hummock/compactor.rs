@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -21,7 +22,7 @@ use futures::Future;
 use itertools::Itertools;
 use risingwave_common::config::StorageConfig;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
-use risingwave_hummock_sdk::key::{get_epoch, Epoch, FullKey};
+use risingwave_hummock_sdk::key::{get_epoch, user_key, Epoch, FullKey};
 use risingwave_hummock_sdk::key_range::KeyRange;
 use risingwave_hummock_sdk::VersionedComparator;
 use risingwave_pb::hummock::{
@@ -34,9 +35,11 @@ use tokio::task::JoinHandle;
 use super::iterator::{BoxedHummockIterator, ConcatIterator, HummockIterator, MergeIterator};
 use super::multi_builder::CapacitySplitTableBuilder;
 use super::shared_buffer::shared_buffer_batch::SharedBufferBatch;
+use super::shared_buffer::upload_limiter::UploadLimiter;
 use super::sstable_store::SstableStoreRef;
 use super::{
-    HummockError, HummockResult, HummockStorage, SSTableBuilder, SSTableIterator, Sstable,
+    HummockError, HummockResult, HummockStorage, KeyspaceTtl, RangeTombstone, SSTableBuilder,
+    SSTableIterator, Sstable, SstableMeta,
 };
 use crate::hummock::vacuum::Vacuum;
 use crate::monitor::StateStoreMetrics;
@@ -58,6 +61,9 @@ pub struct CompactorContext {
 
     /// True if it is a memory compaction (from shared buffer).
     pub is_share_buffer_compact: bool,
+
+    /// Limits the bandwidth of uploading the output SSTs, if any.
+    pub sst_upload_limiter: Option<Arc<UploadLimiter>>,
 }
 
 #[derive(Clone)]
@@ -82,9 +88,15 @@ impl Compactor {
     }
 
     /// For compaction from shared buffer to level 0, this is the only function gets called.
+    ///
+    /// The `range_tombstones` are written to an extra SST without any key, after dropping the
+    /// keys covered by them from the `buffers`. The `keyspace_ttls` are recorded in the SSTs
+    /// overlapping with them, so that they can be respected by the later compactions.
     pub async fn compact_shared_buffer(
         context: Arc<CompactorContext>,
         buffers: Vec<SharedBufferBatch>,
+        range_tombstones: Vec<RangeTombstone>,
+        keyspace_ttls: Vec<KeyspaceTtl>,
         stats: Arc<StateStoreMetrics>,
     ) -> HummockResult<Vec<Sstable>> {
         let mut start_user_keys: Vec<_> = buffers.iter().map(|m| m.start_user_key()).collect();
@@ -129,9 +141,13 @@ impl Compactor {
         let mut output_ssts = Vec::with_capacity(parallelism);
         let mut compaction_futures = vec![];
         let compactor = Compactor::new(context, compact_task.clone());
+        let range_tombstones = Arc::new(range_tombstones);
+        let keyspace_ttls = Arc::new(keyspace_ttls);
 
         for (split_index, _) in compact_task.splits.iter().enumerate() {
             let compactor = compactor.clone();
+            let range_tombstones = range_tombstones.clone();
+            let keyspace_ttls = keyspace_ttls.clone();
             let iter = {
                 let iters = buffers
                     .iter()
@@ -139,7 +155,9 @@ impl Compactor {
                 MergeIterator::new(iters, stats.clone())
             };
             compaction_futures.push(tokio::spawn(async move {
-                compactor.compact_key_range(split_index, iter).await
+                compactor
+                    .compact_key_range(split_index, iter, &range_tombstones, &keyspace_ttls)
+                    .await
             }));
         }
 
@@ -167,6 +185,13 @@ impl Compactor {
             for (_, sst) in output_ssts {
                 level0.extend(sst);
             }
+            if !range_tombstones.is_empty() {
+                level0.push(
+                    compactor
+                        .build_range_tombstone_sst(&range_tombstones)
+                        .await?,
+                );
+            }
 
             Ok(level0)
         } else {
@@ -192,8 +217,11 @@ impl Compactor {
         for (split_index, _) in compact_task.splits.iter().enumerate() {
             let compactor = compactor.clone();
             compaction_futures.push(tokio::spawn(async move {
-                let merge_iter = compactor.build_sst_iter().await?;
-                compactor.compact_key_range(split_index, merge_iter).await
+                let (merge_iter, range_tombstones, keyspace_ttls) =
+                    compactor.build_sst_iter().await?;
+                compactor
+                    .compact_key_range(split_index, merge_iter, &range_tombstones, &keyspace_ttls)
+                    .await
             }));
         }
 
@@ -251,6 +279,7 @@ impl Compactor {
                         right: sst.meta.largest_key.clone(),
                         inf: false,
                     }),
+                    keyspace_stats: sst.meta.keyspace_stats.iter().map(Into::into).collect(),
                 }));
         }
 
@@ -273,6 +302,8 @@ impl Compactor {
         &self,
         split_index: usize,
         iter: MergeIterator<'_>,
+        range_tombstones: &[RangeTombstone],
+        keyspace_ttls: &[KeyspaceTtl],
     ) -> HummockResult<(usize, Vec<Sstable>)> {
         let split = self.compact_task.splits[split_index].clone();
         let kr = KeyRange {
@@ -299,10 +330,22 @@ impl Compactor {
         } else {
             None
         };
+        // The versions expired at `watermark` are expired for any snapshot, so they are dropped
+        // in the same way as the ones deleted by range tombstones. The epoch of the shared buffer
+        // compaction is not a real watermark.
+        let mut dropping_range_tombstones = range_tombstones.to_vec();
+        if !self.context.is_share_buffer_compact {
+            dropping_range_tombstones.extend(
+                keyspace_ttls.iter().filter_map(|keyspace_ttl| {
+                    keyspace_ttl.expired_at(self.compact_task.watermark)
+                }),
+            );
+        }
         Compactor::compact_and_build_sst(
             &mut builder,
             kr,
             iter,
+            &dropping_range_tombstones,
             !self.compact_task.is_target_ultimate_and_leveling,
             self.compact_task.watermark,
         )
@@ -314,35 +357,159 @@ impl Compactor {
         // Seal.
         builder.seal_current();
 
-        let mut ssts: Vec<Sstable> = Vec::new();
-        ssts.reserve(builder.len());
-        // TODO: decide upload concurrency
-        for (table_id, data, meta) in builder.finish() {
-            let sst = Sstable { id: table_id, meta };
-            let len = self
-                .context
-                .sstable_store
-                .put(&sst, data, super::CachePolicy::Fill)
-                .await?;
-
-            if self.context.is_share_buffer_compact {
-                self.context
-                    .stats
-                    .shared_buffer_to_sstable_size
-                    .observe(len as _);
+        let mut sealed_ssts = builder.finish();
+        // Range tombstones from the shared buffer are written to a separate SST.
+        if !self.context.is_share_buffer_compact {
+            let range_tombstones = self.retained_range_tombstones(range_tombstones, &split);
+            if sealed_ssts.is_empty() {
+                // Keep the tombstones even if no key is left in the split, as they may delete
+                // keys in the lower levels.
+                if !range_tombstones.is_empty() {
+                    let table_id = self
+                        .context
+                        .hummock_meta_client
+                        .get_new_table_id()
+                        .await
+                        .map_err(HummockError::meta_error)?;
+                    let mut builder = HummockStorage::get_builder(&self.context.options);
+                    for range_tombstone in range_tombstones {
+                        builder.add_range_tombstone(range_tombstone);
+                    }
+                    let (data, meta) = builder.finish();
+                    sealed_ssts.push((table_id, data, meta));
+                }
             } else {
-                self.context.stats.compaction_upload_sst_counts.inc();
+                let mut metas = sealed_ssts
+                    .iter_mut()
+                    .map(|(_, _, meta)| meta)
+                    .collect_vec();
+                assign_range_tombstones(&mut metas, &range_tombstones);
             }
+        }
 
-            ssts.push(sst);
+        let sealed_ssts = sealed_ssts
+            .into_iter()
+            .map(|(table_id, data, mut meta)| {
+                let smallest_user_key = user_key(&meta.smallest_key);
+                let largest_user_key = user_key(&meta.largest_key);
+                meta.keyspace_ttls = keyspace_ttls
+                    .iter()
+                    .filter(|keyspace_ttl| {
+                        keyspace_ttl.overlaps(smallest_user_key, largest_user_key)
+                    })
+                    .cloned()
+                    .collect_vec();
+                (Sstable { id: table_id, meta }, data)
+            })
+            .collect_vec();
+        if self.context.is_share_buffer_compact {
+            self.context
+                .stats
+                .shared_buffer_upload_queue_depth
+                .add(sealed_ssts.len() as i64);
         }
 
+        // Upload the SSTs concurrently. All the uploads are awaited even if some of them fail, so
+        // that the queue depth is kept accurate.
+        let upload_concurrency = self.context.options.sst_upload_concurrency as usize;
+        let ssts = stream::iter(sealed_ssts)
+            .map(|(sst, data)| self.upload_sst(sst, data))
+            .buffered(upload_concurrency)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<HummockResult<Vec<_>>>()?;
+
         Ok((split_index, ssts))
     }
 
-    /// Build the merge iterator based on the given input ssts.
-    async fn build_sst_iter(&self) -> HummockResult<MergeIterator<'_>> {
+    /// Uploads the `sst` with its `data` to the sstable store, after waiting for the bandwidth.
+    async fn upload_sst(&self, sst: Sstable, data: Bytes) -> HummockResult<Sstable> {
+        if let Some(limiter) = &self.context.sst_upload_limiter {
+            limiter.acquire(data.len()).await;
+        }
+        let result = self
+            .context
+            .sstable_store
+            .put(&sst, data, super::CachePolicy::Fill)
+            .await;
+
+        if self.context.is_share_buffer_compact {
+            self.context.stats.shared_buffer_upload_queue_depth.dec();
+        }
+        let len = result?;
+
+        if self.context.is_share_buffer_compact {
+            self.context
+                .stats
+                .shared_buffer_to_sstable_size
+                .observe(len as _);
+        } else {
+            self.context.stats.compaction_upload_sst_counts.inc();
+        }
+
+        Ok(sst)
+    }
+
+    /// Returns the range tombstones to keep in the output of `split`, clipped to its user key
+    /// range. A range tombstone can be reclaimed once it's compacted into the ultimate level and
+    /// no snapshot is able to read the keys before it.
+    fn retained_range_tombstones(
+        &self,
+        range_tombstones: &[RangeTombstone],
+        split: &risingwave_pb::hummock::KeyRange,
+    ) -> Vec<RangeTombstone> {
+        let start_user_key = (!split.left.is_empty()).then(|| user_key(&split.left));
+        let end_user_key = (!split.right.is_empty()).then(|| user_key(&split.right));
+        range_tombstones
+            .iter()
+            .filter(|range_tombstone| {
+                !self.compact_task.is_target_ultimate_and_leveling
+                    || range_tombstone.epoch >= self.compact_task.watermark
+            })
+            .filter_map(|range_tombstone| range_tombstone.clip(start_user_key, end_user_key))
+            .collect_vec()
+    }
+
+    /// Builds an SST that only contains the `range_tombstones`, whose key range covers all of
+    /// them.
+    async fn build_range_tombstone_sst(
+        &self,
+        range_tombstones: &[RangeTombstone],
+    ) -> HummockResult<Sstable> {
+        let table_id = self
+            .context
+            .hummock_meta_client
+            .get_new_table_id()
+            .await
+            .map_err(HummockError::meta_error)?;
+        let mut builder = HummockStorage::get_builder(&self.context.options);
+        for range_tombstone in range_tombstones {
+            builder.add_range_tombstone(range_tombstone.clone());
+        }
+        let (data, meta) = builder.finish();
+        let sst = Sstable { id: table_id, meta };
+        let len = self
+            .context
+            .sstable_store
+            .put(&sst, data, super::CachePolicy::Fill)
+            .await?;
+        self.context
+            .stats
+            .shared_buffer_to_sstable_size
+            .observe(len as _);
+        Ok(sst)
+    }
+
+    /// Build the merge iterator based on the given input ssts, and collect the range tombstones
+    /// and the keyspace ttls of them. If the ttl of a keyspace differs among the ssts, the longest
+    /// one is taken, so that no version is dropped before it's expired.
+    async fn build_sst_iter(
+        &self,
+    ) -> HummockResult<(MergeIterator<'_>, Vec<RangeTombstone>, Vec<KeyspaceTtl>)> {
         let mut table_iters: Vec<BoxedHummockIterator> = Vec::new();
+        let mut range_tombstones = vec![];
+        let mut keyspace_ttls = BTreeMap::new();
         for LevelEntry {
             level_idx: _,
             level: opt_level,
@@ -353,6 +520,17 @@ impl Compactor {
             // Do not need to filter the table because manager has done it.
             let table_idxs = level.table_infos.iter().map(|sst| sst.id).collect_vec();
             let tables = self.context.sstable_store.sstables(&table_idxs).await?;
+            range_tombstones.extend(
+                tables
+                    .iter()
+                    .flat_map(|table| table.meta.range_tombstones.iter().cloned()),
+            );
+            for keyspace_ttl in tables.iter().flat_map(|table| &table.meta.keyspace_ttls) {
+                let ttl = keyspace_ttls
+                    .entry(keyspace_ttl.prefix.clone())
+                    .or_insert(keyspace_ttl.ttl);
+                *ttl = std::cmp::max(*ttl, keyspace_ttl.ttl);
+            }
 
             // let read_statistics: &mut TableSetStatistics = if *level_idx ==
             // compact_task.target_level {
@@ -383,7 +561,14 @@ impl Compactor {
             }
         }
 
-        Ok(MergeIterator::new(table_iters, self.context.stats.clone()))
+        Ok((
+            MergeIterator::new(table_iters, self.context.stats.clone()),
+            range_tombstones,
+            keyspace_ttls
+                .into_iter()
+                .map(|(prefix, ttl)| KeyspaceTtl::new(prefix, ttl))
+                .collect_vec(),
+        ))
     }
 
     pub async fn try_vacuum(
@@ -424,6 +609,7 @@ impl Compactor {
             sstable_store: sstable_store.clone(),
             stats,
             is_share_buffer_compact: false,
+            sst_upload_limiter: None,
         });
         let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::unbounded_channel();
         let stream_retry_interval = Duration::from_secs(60);
@@ -508,6 +694,7 @@ impl Compactor {
         sst_builder: &mut CapacitySplitTableBuilder<B>,
         kr: KeyRange,
         mut iter: MergeIterator<'_>,
+        range_tombstones: &[RangeTombstone],
         has_user_key_overlap: bool,
         watermark: Epoch,
     ) -> HummockResult<()>
@@ -563,6 +750,15 @@ impl Compactor {
                 }
             }
 
+            // A key covered by a range tombstone below `watermark` is invisible to any snapshot,
+            // so are its older versions.
+            if range_tombstones.iter().any(|range_tombstone| {
+                range_tombstone.epoch < watermark && range_tombstone.covers(iter_key)
+            }) {
+                iter.next().await?;
+                continue;
+            }
+
             // Don't allow two SSTs to share same user key
             sst_builder
                 .add_full_key(FullKey::from_slice(iter_key), iter.value(), is_new_user_key)
@@ -573,3 +769,37 @@ impl Compactor {
         Ok(())
     }
 }
+
+/// Assigns the `range_tombstones` of a split to its output SSTs, whose `metas` are sorted by key.
+/// Each SST takes the parts of the tombstones from its smallest user key up to the smallest user
+/// key of the next SST, and its key range is extended to cover them, so that the tombstones are
+/// kept even where no key is left. The first SST also takes the parts before it.
+fn assign_range_tombstones(metas: &mut [&mut SstableMeta], range_tombstones: &[RangeTombstone]) {
+    let bounds = metas
+        .iter()
+        .map(|meta| user_key(&meta.smallest_key).to_vec())
+        .collect_vec();
+    for (idx, meta) in metas.iter_mut().enumerate() {
+        let start_user_key = (idx > 0).then(|| bounds[idx].as_slice());
+        let end_user_key = bounds.get(idx + 1).map(|bound| bound.as_slice());
+        let clipped = range_tombstones
+            .iter()
+            .filter_map(|range_tombstone| range_tombstone.clip(start_user_key, end_user_key))
+            .collect_vec();
+        for range_tombstone in &clipped {
+            let smallest_key = range_tombstone.smallest_key();
+            if VersionedComparator::compare_key(&smallest_key, &meta.smallest_key)
+                == std::cmp::Ordering::Less
+            {
+                meta.smallest_key = smallest_key;
+            }
+            let largest_key = range_tombstone.largest_key();
+            if VersionedComparator::compare_key(&largest_key, &meta.largest_key)
+                == std::cmp::Ordering::Greater
+            {
+                meta.largest_key = largest_key;
+            }
+        }
+        meta.range_tombstones = clipped;
+    }
+}
@@ -19,12 +19,16 @@ mod tests {
 
     use bytes::Bytes;
     use risingwave_common::config::StorageConfig;
+    use risingwave_hummock_sdk::key::{key_with_epoch, Epoch};
     use risingwave_meta::hummock::test_utils::setup_compute_env;
     use risingwave_meta::hummock::MockHummockMetaClient;
+    use risingwave_pb::hummock::{KeyRange, Level, LevelType, SstableInfo};
     use risingwave_rpc_client::HummockMetaClient;
 
     use crate::hummock::compactor::{Compactor, CompactorContext};
-    use crate::hummock::{HummockStorage, LocalVersionManager, SstableStore};
+    use crate::hummock::test_utils::{count_iter, default_builder_opt_for_test, gen_test_sstable};
+    use crate::hummock::value::HummockValue;
+    use crate::hummock::{HummockStorage, LocalVersionManager, RangeTombstone, SstableStore};
     use crate::monitor::StateStoreMetrics;
     use crate::object::{InMemObjectStore, ObjectStoreImpl};
     use crate::storage_value::StorageValue;
@@ -88,6 +92,7 @@ mod tests {
             hummock_meta_client: hummock_meta_client.clone(),
             stats: Arc::new(StateStoreMetrics::unused()),
             is_share_buffer_compact: false,
+            sst_upload_limiter: None,
         };
 
         // 1. add sstables
@@ -172,4 +177,97 @@ mod tests {
 
         assert!(compact_task.is_none());
     }
+
+    #[tokio::test]
+    async fn test_compaction_keeps_range_tombstones() {
+        let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+            setup_compute_env(8080).await;
+        let hummock_meta_client = Arc::new(MockHummockMetaClient::new(
+            hummock_manager_ref.clone(),
+            worker_node.id,
+        ));
+        let storage = get_hummock_storage(hummock_meta_client.clone()).await;
+        let compact_ctx = CompactorContext {
+            options: storage.options().clone(),
+            sstable_store: storage.sstable_store(),
+            hummock_meta_client: hummock_meta_client.clone(),
+            stats: Arc::new(StateStoreMetrics::unused()),
+            is_share_buffer_compact: false,
+            sst_upload_limiter: None,
+        };
+
+        // 1. An SST with the keys written in epoch 1, which is put in a lower level later.
+        let epoch1 = 1;
+        let lower_sst = gen_test_sstable(
+            default_builder_opt_for_test(),
+            hummock_meta_client.get_new_table_id().await.unwrap(),
+            [b"ca", b"de"].into_iter().map(|key| {
+                (
+                    key_with_epoch(key.to_vec(), epoch1),
+                    HummockValue::put(key.to_vec()),
+                )
+            }),
+            storage.sstable_store(),
+        )
+        .await;
+
+        // 2. Delete [dd, ff) in epoch 2, which is uploaded to an SST without any key.
+        let epoch2 = epoch1 + 1;
+        storage
+            .delete_ranges(vec![(Bytes::from("dd"), Bytes::from("ff"))], epoch2)
+            .await
+            .unwrap();
+        storage.sync(Some(epoch2)).await.unwrap();
+        hummock_meta_client.commit_epoch(epoch2).await.unwrap();
+
+        // 3. Compact the SST. The tombstone is not below the watermark, so it's kept in an SST
+        // without any key, whose key range covers it.
+        let compact_task = hummock_manager_ref
+            .get_compact_task(worker_node.id)
+            .await
+            .unwrap()
+            .unwrap();
+        Compactor::compact(Arc::new(compact_ctx), compact_task).await;
+        let mut version = hummock_manager_ref.get_current_version().await;
+        let output = &version.get_levels()[1].table_infos;
+        assert_eq!(output.len(), 1);
+        let key_range = output[0].key_range.as_ref().unwrap();
+        assert_eq!(key_range.left, key_with_epoch(b"dd".to_vec(), Epoch::MAX));
+        assert_eq!(key_range.right, key_with_epoch(b"ff".to_vec(), Epoch::MAX));
+        let table = storage
+            .sstable_store()
+            .sstables(&[output[0].id])
+            .await
+            .unwrap()
+            .first()
+            .cloned()
+            .unwrap();
+        assert_eq!(table.meta.key_count, 0);
+        assert_eq!(
+            table.meta.range_tombstones,
+            vec![RangeTombstone::new(b"dd".to_vec(), b"ff".to_vec(), epoch2)]
+        );
+
+        // 4. The keys in the lower level are still deleted by the compacted tombstone.
+        version.id += 1;
+        version.levels.push(Level {
+            level_type: LevelType::Nonoverlapping as i32,
+            table_infos: vec![SstableInfo {
+                id: lower_sst.id,
+                key_range: Some(KeyRange {
+                    left: lower_sst.meta.smallest_key.clone(),
+                    right: lower_sst.meta.largest_key.clone(),
+                    inf: false,
+                }),
+                keyspace_stats: vec![],
+            }],
+        });
+        storage.local_version_manager().try_set_version(version);
+        for (key, exists) in [("ca", true), ("de", false)] {
+            let value = storage.get(key.as_bytes(), epoch2).await.unwrap();
+            assert_eq!(value.is_some(), exists, "key {}", key);
+        }
+        let mut iter = storage.iter(..=b"ee".to_vec(), epoch2).await.unwrap();
+        assert_eq!(count_iter(&mut iter).await, 1);
+    }
 }
@@ -19,7 +19,7 @@ use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::hummock::{
     CompactTask, HummockVersion, SstableInfo, SubscribeCompactTasksResponse, VacuumTask,
 };
-use risingwave_rpc_client::{HummockMetaClient, MetaClient};
+use risingwave_rpc_client::{HummockMetaClient, MetaClient, VersionDeltaStream};
 use tonic::Streaming;
 
 use crate::hummock::{HummockEpoch, HummockError, HummockSSTableId, HummockVersionId};
@@ -124,4 +124,11 @@ impl HummockMetaClient for MonitoredHummockMetaClient {
     async fn report_vacuum_task(&self, vacuum_task: VacuumTask) -> Result<()> {
         self.meta_client.report_vacuum_task(vacuum_task).await
     }
+
+    async fn subscribe_version_deltas(
+        &self,
+        last_pinned: HummockVersionId,
+    ) -> Result<Box<dyn VersionDeltaStream>> {
+        self.meta_client.subscribe_version_deltas(last_pinned).await
+    }
 }
@@ -50,8 +50,16 @@ impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
         }
     }
 
-    /// Seeks to a table, and then seeks to the key if `seek_key` is given.
+    /// Seeks to a table, and then seeks to the key if `seek_key` is given. The tables without any
+    /// key, which only hold range tombstones, are skipped.
     async fn seek_idx(&mut self, idx: usize, seek_key: Option<&[u8]>) -> HummockResult<()> {
+        let idx = idx
+            + self
+                .tables
+                .iter()
+                .skip(idx)
+                .take_while(|table| table.block_count() == 0)
+                .count();
         if idx >= self.tables.len() {
             self.sstable_iter = None;
         } else {
@@ -60,6 +68,7 @@ impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
                 sstable_iter.seek(key).await?;
             } else {
                 sstable_iter.rewind().await?;
+                self.prefetch(idx + 1);
             }
 
             self.sstable_iter = Some(sstable_iter);
@@ -67,6 +76,24 @@ impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
         }
         Ok(())
     }
+
+    /// Prefetches the first block of the table at `idx` when iterating sequentially, so that
+    /// moving to the table doesn't stall on reading it.
+    fn prefetch(&self, idx: usize) {
+        if self.sstable_store.prefetch_depth() == 0 || idx >= self.tables.len() {
+            return;
+        }
+        let table = self.tables[idx].clone();
+        if table.block_count() == 0 {
+            return;
+        }
+        let block_idx = match TI::DIRECTION {
+            FORWARD => 0,
+            BACKWARD => table.block_count().saturating_sub(1),
+            _ => unreachable!(),
+        };
+        self.sstable_store.prefetch(table, block_idx as u64);
+    }
 }
 
 #[async_trait]
@@ -52,6 +52,10 @@ impl<const DIRECTION: usize> Ord for Node<'_, DIRECTION> {
 }
 
 /// Iterates on multiple iterators, a.k.a. `MergeIterator`.
+///
+/// The sstable and concat iterators merged by it read ahead by themselves, so the blocks of all
+/// the sstables are fetched concurrently while merging, see
+/// [`SstableStore::prefetch`](crate::hummock::SstableStore::prefetch).
 pub struct MergeIteratorInner<'a, const DIRECTION: usize> {
     /// Invalid or non-initialized iterators.
     unused_iters: LinkedList<BoxedHummockIterator<'a>>,
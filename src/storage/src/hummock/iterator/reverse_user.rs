@@ -20,7 +20,7 @@ use risingwave_hummock_sdk::key::{get_epoch, key_with_epoch, user_key as to_user
 use crate::hummock::iterator::{HummockIterator, ReverseMergeIterator};
 use crate::hummock::local_version_manager::ScopedLocalVersion;
 use crate::hummock::value::HummockValue;
-use crate::hummock::HummockResult;
+use crate::hummock::{HummockResult, RangeTombstone};
 
 /// [`ReverseUserIterator`] can be used by user directly.
 pub struct ReverseUserIterator<'a> {
@@ -48,6 +48,9 @@ pub struct ReverseUserIterator<'a> {
     /// Only reads values if `epoch <= self.read_epoch`.
     read_epoch: Epoch,
 
+    /// Deletes the values they cover. Only the ones with `epoch <= self.read_epoch` are kept.
+    range_tombstones: Vec<RangeTombstone>,
+
     /// Ensures the SSTs needed by `iterator` won't be vacuumed.
     _version: Option<Arc<ScopedLocalVersion>>,
 }
@@ -59,7 +62,7 @@ impl<'a> ReverseUserIterator<'a> {
         iterator: ReverseMergeIterator<'a>,
         key_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
     ) -> Self {
-        Self::new_with_epoch(iterator, key_range, Epoch::MAX, None)
+        Self::new_with_epoch(iterator, key_range, Epoch::MAX, vec![], None)
     }
 
     /// Creates [`ReverseUserIterator`] with given `read_epoch`.
@@ -67,8 +70,10 @@ impl<'a> ReverseUserIterator<'a> {
         iterator: ReverseMergeIterator<'a>,
         key_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
         read_epoch: u64,
+        mut range_tombstones: Vec<RangeTombstone>,
         version: Option<Arc<ScopedLocalVersion>>,
     ) -> Self {
+        range_tombstones.retain(|range_tombstone| range_tombstone.epoch <= read_epoch);
         Self {
             iterator,
             out_of_range: false,
@@ -78,6 +83,7 @@ impl<'a> ReverseUserIterator<'a> {
             last_val: Vec::new(),
             last_delete: true,
             read_epoch,
+            range_tombstones,
             _version: version,
         }
     }
@@ -167,13 +173,14 @@ impl<'a> ReverseUserIterator<'a> {
                 // been seeing the same key for too many times.
 
                 // 1 and 2(a)
+                let range_deleted = self.range_tombstones.iter().any(|t| t.covers(full_key));
                 match self.iterator.value() {
-                    HummockValue::Put(_, val) => {
+                    HummockValue::Put(_, val) if !range_deleted => {
                         self.last_val.clear();
                         self.last_val.extend_from_slice(val);
                         self.last_delete = false;
                     }
-                    HummockValue::Delete(_) => {
+                    _ => {
                         self.last_delete = true;
                     }
                 }
@@ -473,6 +480,50 @@ mod tests {
         assert!(!ui.is_valid());
     }
 
+    #[tokio::test]
+    async fn test_reverse_user_range_tombstone() {
+        let sstable_store = mock_sstable_store();
+
+        // key=[idx, epoch], value
+        let kv_pairs = (1..=3)
+            .map(|i| (i, 100, HummockValue::put(iterator_test_value_of(i))))
+            .collect();
+        let table0 =
+            gen_iterator_test_sstable_from_kv_pair(0, kv_pairs, sstable_store.clone()).await;
+        let kv_pairs = vec![(2, 300, HummockValue::put(iterator_test_value_of(4)))];
+        let table1 =
+            gen_iterator_test_sstable_from_kv_pair(1, kv_pairs, sstable_store.clone()).await;
+        let iters: Vec<BoxedHummockIterator> = vec![
+            Box::new(ReverseSSTableIterator::new(
+                Arc::new(table0),
+                sstable_store.clone(),
+            )),
+            Box::new(ReverseSSTableIterator::new(Arc::new(table1), sstable_store)),
+        ];
+        let mi = ReverseMergeIterator::new(iters, Arc::new(StateStoreMetrics::unused()));
+        // Deletes key 1 and 2 written in or before epoch 200.
+        let range_tombstone = RangeTombstone::new(
+            user_key(&iterator_test_key_of(1)).to_vec(),
+            user_key(&iterator_test_key_of(3)).to_vec(),
+            200,
+        );
+        let mut ui = ReverseUserIterator::new_with_epoch(
+            mi,
+            (Unbounded, Unbounded),
+            Epoch::MAX,
+            vec![range_tombstone],
+            None,
+        );
+        ui.rewind().await.unwrap();
+
+        for (key_idx, value_idx) in [(3, 3), (2, 4)] {
+            assert_eq!(ui.key(), user_key(iterator_test_key_of(key_idx).as_slice()));
+            assert_eq!(ui.value(), iterator_test_value_of(value_idx));
+            ui.next().await.unwrap();
+        }
+        assert!(!ui.is_valid());
+    }
+
     // left..=end
     #[tokio::test]
     async fn test_reverse_user_range_inclusive() {
@@ -21,7 +21,7 @@ use super::{HummockIterator, MergeIterator};
 use crate::hummock::iterator::ReverseUserIterator;
 use crate::hummock::local_version_manager::ScopedLocalVersion;
 use crate::hummock::value::HummockValue;
-use crate::hummock::HummockResult;
+use crate::hummock::{HummockResult, RangeTombstone};
 
 pub enum DirectedUserIterator<'a> {
     Forward(UserIterator<'a>),
@@ -96,6 +96,9 @@ pub struct UserIterator<'a> {
     /// Only reads values if `ts <= self.read_epoch`.
     read_epoch: Epoch,
 
+    /// Deletes the values they cover. Only the ones with `epoch <= self.read_epoch` are kept.
+    range_tombstones: Vec<RangeTombstone>,
+
     /// Ensures the SSTs needed by `iterator` won't be vacuumed.
     _version: Option<Arc<ScopedLocalVersion>>,
 }
@@ -108,7 +111,7 @@ impl<'a> UserIterator<'a> {
         iterator: MergeIterator<'a>,
         key_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
     ) -> Self {
-        Self::new(iterator, key_range, Epoch::MAX, None)
+        Self::new(iterator, key_range, Epoch::MAX, vec![], None)
     }
 
     /// Create [`UserIterator`] with given `read_epoch`.
@@ -116,8 +119,10 @@ impl<'a> UserIterator<'a> {
         iterator: MergeIterator<'a>,
         key_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
         read_epoch: u64,
+        mut range_tombstones: Vec<RangeTombstone>,
         version: Option<Arc<ScopedLocalVersion>>,
     ) -> Self {
+        range_tombstones.retain(|range_tombstone| range_tombstone.epoch <= read_epoch);
         Self {
             iterator,
             out_of_range: false,
@@ -125,6 +130,7 @@ impl<'a> UserIterator<'a> {
             last_key: Vec::new(),
             last_val: Vec::new(),
             read_epoch,
+            range_tombstones,
             _version: version,
         }
     }
@@ -147,8 +153,9 @@ impl<'a> UserIterator<'a> {
                 self.last_key.extend_from_slice(key);
 
                 // handle delete operation
+                let range_deleted = self.range_tombstones.iter().any(|t| t.covers(full_key));
                 match self.iterator.value() {
-                    HummockValue::Put(_, val) => {
+                    HummockValue::Put(_, val) if !range_deleted => {
                         self.last_val.clear();
                         self.last_val.extend_from_slice(val);
 
@@ -160,10 +167,10 @@ impl<'a> UserIterator<'a> {
                         };
                         return Ok(());
                     }
-                    // It means that the key is deleted from the storage.
-                    // Deleted kv and the previous versions (if any) of the key should not be
-                    // returned to user.
-                    HummockValue::Delete(_) => {}
+                    // It means that the key is deleted from the storage, either by itself or by a
+                    // range tombstone. Deleted kv and the previous versions (if any) of the key
+                    // should not be returned to user.
+                    _ => {}
                 }
             }
 
@@ -455,6 +462,56 @@ mod tests {
     }
 
     // left..=end
+    #[tokio::test]
+    async fn test_range_tombstone() {
+        let sstable_store = mock_sstable_store();
+
+        // key=[idx, epoch], value
+        let kv_pairs = (1..=3)
+            .map(|i| (i, 100, HummockValue::put(iterator_test_value_of(i))))
+            .collect();
+        let table0 = Arc::new(
+            gen_iterator_test_sstable_from_kv_pair(0, kv_pairs, sstable_store.clone()).await,
+        );
+        let kv_pairs = vec![(2, 300, HummockValue::put(iterator_test_value_of(4)))];
+        let table1 = Arc::new(
+            gen_iterator_test_sstable_from_kv_pair(1, kv_pairs, sstable_store.clone()).await,
+        );
+        // Deletes key 1 and 2 written in or before epoch 200.
+        let range_tombstone = RangeTombstone::new(
+            user_key(&iterator_test_key_of(1)).to_vec(),
+            user_key(&iterator_test_key_of(3)).to_vec(),
+            200,
+        );
+
+        for (read_epoch, expected) in [
+            (150, vec![(1, 1), (2, 2), (3, 3)]),
+            (250, vec![(3, 3)]),
+            (350, vec![(2, 4), (3, 3)]),
+        ] {
+            let iters: Vec<BoxedHummockIterator> = vec![
+                Box::new(SSTableIterator::new(table0.clone(), sstable_store.clone())),
+                Box::new(SSTableIterator::new(table1.clone(), sstable_store.clone())),
+            ];
+            let mi = MergeIterator::new(iters, Arc::new(StateStoreMetrics::unused()));
+            let mut ui = UserIterator::new(
+                mi,
+                (Unbounded, Unbounded),
+                read_epoch,
+                vec![range_tombstone.clone()],
+                None,
+            );
+            ui.rewind().await.unwrap();
+
+            for (key_idx, value_idx) in expected {
+                assert_eq!(ui.key(), user_key(iterator_test_key_of(key_idx).as_slice()));
+                assert_eq!(ui.value(), iterator_test_value_of(value_idx));
+                ui.next().await.unwrap();
+            }
+            assert!(!ui.is_valid());
+        }
+    }
+
     #[tokio::test]
     async fn test_range_inclusive() {
         let sstable_store = mock_sstable_store();
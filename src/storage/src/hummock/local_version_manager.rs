@@ -20,6 +20,8 @@ use std::time::Duration;
 
 use itertools::Itertools;
 use parking_lot::{Mutex, RwLock};
+use risingwave_hummock_sdk::version_delta::apply_version_delta;
+use risingwave_pb::hummock::subscribe_version_deltas_response::Payload;
 use risingwave_pb::hummock::{HummockVersion, Level};
 use risingwave_rpc_client::HummockMetaClient;
 use tokio::sync::mpsc::error::TryRecvError;
@@ -114,8 +116,8 @@ impl LocalVersionManager {
     ) {
         let unpin_worker_rx = local_version_manager.unpin_worker_rx.lock().take();
         if let Some(unpin_worker_rx) = unpin_worker_rx {
-            // Pin and get the latest version.
-            tokio::spawn(LocalVersionManager::start_pin_worker(
+            // Pin and get the versions pushed by meta.
+            tokio::spawn(LocalVersionManager::start_version_delta_worker(
                 Arc::downgrade(&local_version_manager),
                 hummock_meta_client.clone(),
             ));
@@ -192,46 +194,71 @@ impl LocalVersionManager {
         }
     }
 
-    async fn start_pin_worker(
+    /// Subscribes to the versions pushed by meta, and applies them to the local version. Each
+    /// version is pinned by meta before being pushed, as a delta from the previous one whenever
+    /// possible.
+    async fn start_version_delta_worker(
         local_version_manager: Weak<LocalVersionManager>,
         hummock_meta_client: Arc<dyn HummockMetaClient>,
     ) {
         let max_retry_interval = Duration::from_secs(10);
-        let min_execute_interval = Duration::from_millis(100);
         let get_backoff_strategy = || {
             tokio_retry::strategy::ExponentialBackoff::from_millis(10)
                 .max_delay(max_retry_interval)
                 .map(jitter)
         };
         let mut retry_backoff = get_backoff_strategy();
-        let mut min_execute_interval_tick = tokio::time::interval(min_execute_interval);
+        // The last version pushed by meta, which the next delta is applied on.
+        let mut last_version: Option<HummockVersion> = None;
         loop {
-            min_execute_interval_tick.tick().await;
-            let local_version_manager = match local_version_manager.upgrade() {
-                None => {
-                    tracing::info!("Shutdown hummock pin worker");
-                    return;
-                }
-                Some(local_version_manager) => local_version_manager,
-            };
-            let last_pinned = match local_version_manager.current_version.read().as_ref() {
-                None => INVALID_VERSION_ID,
-                Some(v) => v.version.id,
-            };
-            match hummock_meta_client.pin_version(last_pinned).await {
-                Ok(version) => {
-                    local_version_manager.try_set_version(version);
+            let last_pinned = last_version.as_ref().map_or(INVALID_VERSION_ID, |v| v.id);
+            let result: HummockResult<()> = match hummock_meta_client
+                .subscribe_version_deltas(last_pinned)
+                .await
+            {
+                Ok(mut stream) => loop {
+                    let response = match stream.next().await {
+                        Ok(Some(response)) => response,
+                        Ok(None) => break Err(HummockError::meta_error("stream terminated")),
+                        Err(err) => break Err(HummockError::meta_error(err)),
+                    };
+                    let local_version_manager = match local_version_manager.upgrade() {
+                        None => {
+                            tracing::info!("Shutdown hummock version delta worker");
+                            return;
+                        }
+                        Some(local_version_manager) => local_version_manager,
+                    };
+                    let version = match response.payload {
+                        Some(Payload::Version(version)) => version,
+                        Some(Payload::VersionDelta(delta)) => match last_version.take() {
+                            Some(mut version) if version.id == delta.prev_id => {
+                                apply_version_delta(&mut version, &delta);
+                                version
+                            }
+                            // Subscribe again to get the full version.
+                            _ => break Err(HummockError::meta_error("unexpected version delta")),
+                        },
+                        None => continue,
+                    };
+                    local_version_manager.try_set_version(version.clone());
+                    last_version = Some(version);
                     retry_backoff = get_backoff_strategy();
+                },
+                Err(err) => Err(HummockError::meta_error(err)),
+            };
+            if let Err(err) = result {
+                if local_version_manager.strong_count() == 0 {
+                    tracing::info!("Shutdown hummock version delta worker");
+                    return;
                 }
-                Err(err) => {
-                    let retry_after = retry_backoff.next().unwrap_or(max_retry_interval);
-                    tracing::warn!(
-                        "Failed to pin version {:?}. Will retry after about {} milliseconds",
-                        err,
-                        retry_after.as_millis()
-                    );
-                    tokio::time::sleep(retry_after).await;
-                }
+                let retry_after = retry_backoff.next().unwrap_or(max_retry_interval);
+                tracing::warn!(
+                    "Failed to subscribe versions {:?}. Will retry after about {} milliseconds",
+                    err,
+                    retry_after.as_millis()
+                );
+                tokio::time::sleep(retry_after).await;
             }
         }
     }
@@ -14,6 +14,7 @@
 
 //! Hummock is the state store of the streaming system.
 
+use std::collections::HashSet;
 use std::fmt;
 use std::sync::Arc;
 
@@ -22,6 +23,8 @@ use risingwave_common::config::StorageConfig;
 use risingwave_hummock_sdk::*;
 use risingwave_rpc_client::HummockMetaClient;
 
+mod block_access_sketch;
+pub use block_access_sketch::*;
 mod block_cache;
 pub use block_cache::*;
 mod sstable;
@@ -190,6 +193,21 @@ impl HummockStorage {
     pub fn shared_buffer_manager(&self) -> &SharedBufferManager {
         &self.shared_buffer_manager
     }
+
+    /// Prefetches the hottest blocks of the SSTs in the current version into the block cache,
+    /// before serving any read after restart. Returns the number of blocks fetched.
+    pub async fn warm_up_block_cache(&self) -> HummockResult<usize> {
+        let version = self.local_version_manager.get_version()?;
+        let live_sst_ids: HashSet<_> = version
+            .levels()
+            .iter()
+            .flat_map(|level| level.table_infos.iter().map(|info| info.id))
+            .collect();
+        Ok(self
+            .sstable_store
+            .warm_up(&live_sst_ids, self.options.block_cache_warmup_blocks)
+            .await)
+    }
 }
 
 impl fmt::Debug for HummockStorage {
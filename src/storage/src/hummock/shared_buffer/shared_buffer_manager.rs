@@ -20,6 +20,7 @@ use std::sync::{atomic, Arc};
 use itertools::Itertools;
 use parking_lot::{Mutex, RwLock as PLRwLock};
 use risingwave_common::config::StorageConfig;
+use risingwave_common::util::memory_budget::{MemoryBudget, MemoryBudgetRef};
 use risingwave_rpc_client::HummockMetaClient;
 use tokio::sync::watch::{Receiver as WatchReceiver, Sender as WatchSender};
 use tokio::task::JoinHandle;
@@ -35,20 +36,26 @@ use crate::hummock::shared_buffer::shared_buffer_uploader::{
 };
 use crate::hummock::utils::range_overlap;
 use crate::hummock::value::HummockValue;
-use crate::hummock::{HummockEpoch, HummockError, HummockResult, SstableStoreRef};
+use crate::hummock::{
+    HummockEpoch, HummockError, HummockResult, KeyspaceTtl, RangeTombstone, SstableStoreRef,
+};
 use crate::monitor::StateStoreMetrics;
 
 #[derive(Debug)]
 pub struct SharedBufferMetrics {
     pub shared_buffer_cur_size: atomic::AtomicU64,
-    pub shared_buffer_threshold_size: u64,
+    /// Size to trigger a flush of the shared buffer, which may be resized by the memory governor.
+    pub shared_buffer_threshold: MemoryBudgetRef,
 }
 
 impl SharedBufferMetrics {
     pub fn new(options: &StorageConfig) -> Self {
         Self {
             shared_buffer_cur_size: atomic::AtomicU64::new(0),
-            shared_buffer_threshold_size: options.shared_buffer_threshold_size as u64,
+            shared_buffer_threshold: Arc::new(MemoryBudget::new(
+                "shared_buffer",
+                options.shared_buffer_threshold_size as usize,
+            )),
         }
     }
 }
@@ -58,6 +65,10 @@ impl SharedBufferMetrics {
 pub struct SharedBufferManager {
     /// `shared_buffer` is a collection of immutable batches grouped by (epoch, end_key)
     shared_buffer: PLRwLock<BTreeMap<HummockEpoch, BTreeMap<Vec<u8>, SharedBufferBatch>>>,
+    /// Range tombstones grouped by epoch
+    range_tombstones: PLRwLock<BTreeMap<HummockEpoch, Vec<RangeTombstone>>>,
+    /// Ttls of the keyspaces, shared with the uploader to be recorded in the SSTs.
+    keyspace_ttls: Arc<PLRwLock<BTreeMap<Vec<u8>, HummockEpoch>>>,
     uploader_tx: tokio::sync::mpsc::UnboundedSender<SharedBufferUploaderItem>,
     uploader_handle: JoinHandle<StorageResult<()>>,
     stats: SharedBufferMetrics,
@@ -108,17 +119,21 @@ impl SharedBufferManager {
     ) -> Self {
         let (uploader_tx, uploader_rx) = tokio::sync::mpsc::unbounded_channel();
         let stats = SharedBufferMetrics::new(options.as_ref());
+        let keyspace_ttls = Arc::new(PLRwLock::new(BTreeMap::new()));
         let uploader = SharedBufferUploader::new(
             options,
             local_version_manager,
             sstable_store,
             state_store_stats,
             hummock_meta_client,
+            keyspace_ttls.clone(),
             uploader_rx,
         );
         let uploader_handle = tokio::spawn(uploader.run());
         Self {
             shared_buffer: PLRwLock::new(BTreeMap::new()),
+            range_tombstones: PLRwLock::new(BTreeMap::new()),
+            keyspace_ttls,
             uploader_tx,
             uploader_handle,
             stats,
@@ -133,7 +148,13 @@ impl SharedBufferManager {
     /// Allocates a shared buffer budget.
     async fn allocate_space(&self, batch_size: u64) -> HummockResult<()> {
         let mut current_size = self.stats.shared_buffer_cur_size.load(Ordering::SeqCst);
-        let threshold = self.stats.shared_buffer_threshold_size;
+        let threshold = self.stats.shared_buffer_threshold.bytes() as u64;
+        // Writes stalled by flushes count as misses of the shared buffer.
+        if threshold < current_size + batch_size {
+            self.stats.shared_buffer_threshold.record_miss();
+        } else {
+            self.stats.shared_buffer_threshold.record_hit();
+        }
 
         // Atomically allocates space,
         // since there could be concurrent Actors write to the shared buffer.
@@ -191,6 +212,34 @@ impl SharedBufferManager {
         Ok(size)
     }
 
+    /// Puts range tombstones into shared buffer. They will be synced to S3 asynchronously, along
+    /// with the write batches of the same epoch.
+    pub async fn delete_ranges(
+        &self,
+        range_tombstones: Vec<RangeTombstone>,
+        epoch: HummockEpoch,
+    ) -> HummockResult<u64> {
+        let size = range_tombstones
+            .iter()
+            .map(|range_tombstone| range_tombstone.encoded_size() as u64)
+            .sum();
+
+        self.allocate_space(size).await?;
+
+        self.range_tombstones
+            .write()
+            .entry(epoch)
+            .or_insert(Vec::new())
+            .extend(range_tombstones.iter().cloned());
+        self.uploader_tx
+            .send(SharedBufferUploaderItem::RangeTombstones(
+                epoch,
+                range_tombstones,
+            ))
+            .map_err(HummockError::shared_buffer_error)?;
+        Ok(size)
+    }
+
     /// Puts a write batch into shared buffer. The batch will won't be synced to S3 asynchronously.
     pub fn replicate_remote_batch(
         &self,
@@ -345,11 +394,37 @@ impl SharedBufferManager {
             .collect_vec()
     }
 
+    /// Gets the range tombstones within the `epoch_range`.
+    pub fn range_tombstones(&self, epoch_range: impl RangeBounds<u64>) -> Vec<RangeTombstone> {
+        self.range_tombstones
+            .read()
+            .range(epoch_range)
+            .flat_map(|(_, range_tombstones)| range_tombstones.iter().cloned())
+            .collect_vec()
+    }
+
+    /// Sets the ttl of the keyspace with `prefix`, which takes effect on the SSTs synced later.
+    pub fn set_keyspace_ttl(&self, prefix: Vec<u8>, ttl: HummockEpoch) {
+        self.keyspace_ttls.write().insert(prefix, ttl);
+    }
+
+    /// Gets the ttls of all the keyspaces.
+    pub fn keyspace_ttls(&self) -> Vec<KeyspaceTtl> {
+        self.keyspace_ttls
+            .read()
+            .iter()
+            .map(|(prefix, ttl)| KeyspaceTtl::new(prefix.clone(), *ttl))
+            .collect_vec()
+    }
+
     /// Deletes shared buffers before a given `epoch` exclusively.
     pub fn delete_before(&self, epoch: u64) {
         let mut guard = self.shared_buffer.write();
         let new = guard.split_off(&epoch);
         *guard = new;
+        let mut guard = self.range_tombstones.write();
+        let new = guard.split_off(&epoch);
+        *guard = new;
     }
 
     /// This function was called while [`SharedBufferManager`] exited.
@@ -364,6 +439,7 @@ impl SharedBufferManager {
             .unwrap();
         // Remove items of the given epoch from shared buffer
         self.shared_buffer.write().remove(&epoch);
+        self.range_tombstones.write().remove(&epoch);
     }
 
     pub fn stats(&self) -> &SharedBufferMetrics {
@@ -12,10 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
-use itertools::Itertools;
+use parking_lot::RwLock;
 use risingwave_common::config::StorageConfig;
 use risingwave_pb::hummock::SstableInfo;
 use risingwave_rpc_client::HummockMetaClient;
@@ -25,7 +25,10 @@ use crate::hummock::compactor::{Compactor, CompactorContext};
 use crate::hummock::conflict_detector::ConflictDetector;
 use crate::hummock::local_version_manager::LocalVersionManager;
 use crate::hummock::shared_buffer::shared_buffer_batch::SharedBufferBatch;
-use crate::hummock::{HummockError, HummockResult, SstableStoreRef};
+use crate::hummock::shared_buffer::upload_limiter::UploadLimiter;
+use crate::hummock::{
+    HummockEpoch, HummockError, HummockResult, KeyspaceTtl, RangeTombstone, SstableStoreRef,
+};
 use crate::monitor::StateStoreMetrics;
 
 #[derive(Debug)]
@@ -39,6 +42,7 @@ pub struct SyncItem {
 #[derive(Debug)]
 pub enum SharedBufferUploaderItem {
     Batch(SharedBufferBatch),
+    RangeTombstones(u64, Vec<RangeTombstone>),
     Sync(SyncItem),
     Reset(u64),
 }
@@ -46,6 +50,10 @@ pub enum SharedBufferUploaderItem {
 pub struct SharedBufferUploader {
     /// Batches to upload grouped by epoch
     batches_to_upload: BTreeMap<u64, Vec<SharedBufferBatch>>,
+    /// Range tombstones to upload grouped by epoch
+    range_tombstones_to_upload: BTreeMap<u64, Vec<RangeTombstone>>,
+    /// Ttls of the keyspaces to record in the uploaded SSTs
+    keyspace_ttls: Arc<RwLock<BTreeMap<Vec<u8>, HummockEpoch>>>,
     local_version_manager: Arc<LocalVersionManager>,
     options: Arc<StorageConfig>,
 
@@ -59,6 +67,10 @@ pub struct SharedBufferUploader {
     /// in `StorageConfig`
     write_conflict_detector: Option<Arc<ConflictDetector>>,
 
+    /// Caps the bandwidth of uploading SSTs. Enabled by setting `sst_upload_bandwidth` in
+    /// `StorageConfig`
+    sst_upload_limiter: Option<Arc<UploadLimiter>>,
+
     uploader_rx: tokio::sync::mpsc::UnboundedReceiver<SharedBufferUploaderItem>,
 }
 
@@ -69,10 +81,13 @@ impl SharedBufferUploader {
         sstable_store: SstableStoreRef,
         stats: Arc<StateStoreMetrics>,
         hummock_meta_client: Arc<dyn HummockMetaClient>,
+        keyspace_ttls: Arc<RwLock<BTreeMap<Vec<u8>, HummockEpoch>>>,
         uploader_rx: tokio::sync::mpsc::UnboundedReceiver<SharedBufferUploaderItem>,
     ) -> Self {
         Self {
             batches_to_upload: BTreeMap::new(),
+            range_tombstones_to_upload: BTreeMap::new(),
+            keyspace_ttls,
             options: options.clone(),
             local_version_manager,
 
@@ -84,6 +99,11 @@ impl SharedBufferUploader {
             } else {
                 None
             },
+            sst_upload_limiter: if options.sst_upload_bandwidth > 0 {
+                Some(Arc::new(UploadLimiter::new(options.sst_upload_bandwidth)))
+            } else {
+                None
+            },
             uploader_rx,
         }
     }
@@ -94,12 +114,20 @@ impl SharedBufferUploader {
             detector.archive_epoch(epoch);
         }
 
-        let buffers = match self.batches_to_upload.remove(&epoch) {
-            Some(m) => m,
-            None => return Ok(0),
-        };
+        let buffers = self.batches_to_upload.remove(&epoch);
+        let range_tombstones = self.range_tombstones_to_upload.remove(&epoch);
+        if buffers.is_none() && range_tombstones.is_none() {
+            return Ok(0);
+        }
+        let timer = self.stats.shared_buffer_upload_epoch_duration.start_timer();
+        let buffers = buffers.unwrap_or_default();
+        let range_tombstones = range_tombstones.unwrap_or_default();
 
-        let sync_size: u64 = buffers.iter().map(|batch| batch.size).sum();
+        let sync_size: u64 = buffers.iter().map(|batch| batch.size).sum::<u64>()
+            + range_tombstones
+                .iter()
+                .map(|range_tombstone| range_tombstone.encoded_size() as u64)
+                .sum::<u64>();
 
         // Compact buffers into SSTs
         let mem_compactor_ctx = CompactorContext {
@@ -108,11 +136,20 @@ impl SharedBufferUploader {
             sstable_store: self.sstable_store.clone(),
             stats: self.stats.clone(),
             is_share_buffer_compact: true,
+            sst_upload_limiter: self.sst_upload_limiter.clone(),
         };
 
+        let keyspace_ttls = self
+            .keyspace_ttls
+            .read()
+            .iter()
+            .map(|(prefix, ttl)| KeyspaceTtl::new(prefix.clone(), *ttl))
+            .collect();
         let tables = Compactor::compact_shared_buffer(
             Arc::new(mem_compactor_ctx),
             buffers,
+            range_tombstones,
+            keyspace_ttls,
             self.stats.clone(),
         )
         .await?;
@@ -131,6 +168,7 @@ impl SharedBufferUploader {
                             right: sst.meta.largest_key.clone(),
                             inf: false,
                         }),
+                        keyspace_stats: sst.meta.keyspace_stats.iter().map(Into::into).collect(),
                     })
                     .collect(),
             )
@@ -139,6 +177,7 @@ impl SharedBufferUploader {
 
         // Ensure the added data is available locally
         self.local_version_manager.try_set_version(version);
+        timer.observe_duration();
 
         Ok(sync_size)
     }
@@ -156,6 +195,13 @@ impl SharedBufferUploader {
                     .push(m);
                 Ok(())
             }
+            SharedBufferUploaderItem::RangeTombstones(epoch, range_tombstones) => {
+                self.range_tombstones_to_upload
+                    .entry(epoch)
+                    .or_insert(Vec::new())
+                    .extend(range_tombstones);
+                Ok(())
+            }
             SharedBufferUploaderItem::Sync(sync_item) => {
                 let res = match sync_item.epoch {
                     Some(e) => {
@@ -164,7 +210,12 @@ impl SharedBufferUploader {
                     }
                     None => {
                         // Sync all epochs
-                        let epochs = self.batches_to_upload.keys().copied().collect_vec();
+                        let epochs = self
+                            .batches_to_upload
+                            .keys()
+                            .chain(self.range_tombstones_to_upload.keys())
+                            .copied()
+                            .collect::<BTreeSet<_>>();
                         let mut res = Ok(0);
                         let mut size_total: u64 = 0;
 
@@ -191,6 +242,7 @@ impl SharedBufferUploader {
             }
             SharedBufferUploaderItem::Reset(epoch) => {
                 self.batches_to_upload.remove(&epoch);
+                self.range_tombstones_to_upload.remove(&epoch);
                 Ok(())
             }
         }
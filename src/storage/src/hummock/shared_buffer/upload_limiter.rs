@@ -0,0 +1,67 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+/// `UploadLimiter` caps the bandwidth of uploading SSTs. Each upload reserves the time to transfer
+/// its bytes at the capped bandwidth after the ones reserved before it, and waits until then.
+pub struct UploadLimiter {
+    bytes_per_sec: u64,
+    /// When the reserved uploads are done at the capped bandwidth.
+    next_available: Mutex<Instant>,
+}
+
+impl UploadLimiter {
+    pub fn new(bytes_per_sec: u64) -> Self {
+        assert!(bytes_per_sec > 0, "upload bandwidth should be positive");
+        Self {
+            bytes_per_sec,
+            next_available: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until `size` bytes can be uploaded without exceeding the bandwidth.
+    pub async fn acquire(&self, size: usize) {
+        let wait = {
+            let mut next_available = self.next_available.lock();
+            let now = Instant::now();
+            let start = std::cmp::max(*next_available, now);
+            *next_available =
+                start + Duration::from_secs_f64(size as f64 / self.bytes_per_sec as f64);
+            start - now
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_upload_limiter() {
+        let limiter = UploadLimiter::new(1000);
+        let start = Instant::now();
+        // The first upload starts immediately, and the second one waits for it.
+        limiter.acquire(100).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+        limiter.acquire(100).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -13,13 +13,13 @@
 // limitations under the License.
 
 use bytes::{BufMut, Bytes, BytesMut};
-use risingwave_hummock_sdk::key::user_key;
+use risingwave_hummock_sdk::key::{keyspace_prefix, user_key};
 
 use super::bloom::Bloom;
 use super::utils::CompressionAlgorithm;
 use super::{
-    BlockBuilder, BlockBuilderOptions, BlockMeta, SstableMeta, DEFAULT_BLOCK_SIZE,
-    DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
+    BlockBuilder, BlockBuilderOptions, BlockMeta, KeyspaceStats, RangeTombstone, SstableMeta,
+    DEFAULT_BLOCK_SIZE, DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
 };
 use crate::hummock::value::HummockValue;
 
@@ -66,6 +66,9 @@ pub struct SSTableBuilder {
     /// Last added full key.
     last_full_key: Bytes,
     key_count: usize,
+    range_tombstones: Vec<RangeTombstone>,
+    /// Statistics of the keyspaces, in the order of the added keys.
+    keyspace_stats: Vec<KeyspaceStats>,
 }
 
 impl SSTableBuilder {
@@ -78,6 +81,8 @@ impl SSTableBuilder {
             user_key_hashes: Vec::with_capacity(options.capacity / DEFAULT_ENTRY_SIZE + 1),
             last_full_key: Bytes::default(),
             key_count: 0,
+            range_tombstones: vec![],
+            keyspace_stats: vec![],
         }
     }
 
@@ -110,6 +115,19 @@ impl SSTableBuilder {
         let user_key = user_key(full_key);
         self.user_key_hashes.push(farmhash::fingerprint32(user_key));
 
+        let prefix = keyspace_prefix(user_key);
+        match self.keyspace_stats.last_mut() {
+            Some(stats) if stats.prefix == prefix => {}
+            _ => self.keyspace_stats.push(KeyspaceStats {
+                prefix: prefix.to_vec(),
+                key_count: 0,
+                total_size: 0,
+            }),
+        }
+        let stats = self.keyspace_stats.last_mut().unwrap();
+        stats.key_count += 1;
+        stats.total_size += (full_key.len() + raw_value.len()) as u64;
+
         if self.last_full_key.is_empty() {
             self.block_metas.last_mut().unwrap().smallest_key = full_key.to_vec();
         }
@@ -121,6 +139,13 @@ impl SSTableBuilder {
         self.key_count += 1;
     }
 
+    /// Adds a range tombstone to the sstable meta. If no kv pair is added, the key range of the
+    /// sstable is derived from the tombstones. Otherwise, the tombstones are expected to fall in
+    /// the key range of the kv pairs.
+    pub fn add_range_tombstone(&mut self, range_tombstone: RangeTombstone) {
+        self.range_tombstones.push(range_tombstone);
+    }
+
     /// Finish building sst.
     ///
     /// Unlike most LSM-Tree implementations, sstable meta and data are encoded separately.
@@ -134,14 +159,34 @@ impl SSTableBuilder {
     /// | Block 0 | ... | Block N-1 | N (4B) |
     /// ```
     pub fn finish(mut self) -> (Bytes, SstableMeta) {
-        let smallest_key = self.block_metas[0].smallest_key.clone();
-        let largest_key = self.last_full_key.to_vec();
+        let (smallest_key, largest_key) = if self.block_metas.is_empty() {
+            // Covers the tombstones. The end user keys are exclusive, so the largest key is the
+            // smallest full key of the largest end user key.
+            let start = self
+                .range_tombstones
+                .iter()
+                .min_by(|a, b| a.start_user_key.cmp(&b.start_user_key))
+                .expect("empty sstable");
+            let end = self
+                .range_tombstones
+                .iter()
+                .max_by(|a, b| a.end_user_key.cmp(&b.end_user_key))
+                .expect("empty sstable");
+            (start.smallest_key(), end.largest_key())
+        } else {
+            (
+                self.block_metas[0].smallest_key.clone(),
+                self.last_full_key.to_vec(),
+            )
+        };
         self.build_block();
         self.buf.put_u32_le(self.block_metas.len() as u32);
 
         let meta = SstableMeta {
             block_metas: self.block_metas,
-            bloom_filter: if self.options.bloom_false_positive > 0.0 {
+            bloom_filter: if self.options.bloom_false_positive > 0.0
+                && !self.user_key_hashes.is_empty()
+            {
                 let bits_per_key = Bloom::bloom_bits_per_key(
                     self.user_key_hashes.len(),
                     self.options.bloom_false_positive,
@@ -154,6 +199,9 @@ impl SSTableBuilder {
             key_count: self.key_count as u32,
             smallest_key,
             largest_key,
+            range_tombstones: self.range_tombstones,
+            keyspace_ttls: vec![],
+            keyspace_stats: self.keyspace_stats,
             version: VERSION,
         };
 
@@ -191,6 +239,8 @@ impl SSTableBuilder {
 
 #[cfg(test)]
 pub(super) mod tests {
+    use risingwave_hummock_sdk::key::{key_with_epoch, Epoch};
+
     use super::*;
     use crate::hummock::iterator::test_utils::mock_sstable_store;
     use crate::hummock::test_utils::{
@@ -228,6 +278,52 @@ pub(super) mod tests {
         assert_eq!(test_key_of(TEST_KEYS_COUNT - 1), meta.largest_key);
     }
 
+    #[test]
+    fn test_range_tombstone_only() {
+        let mut b = SSTableBuilder::new(default_builder_opt_for_test());
+        b.add_range_tombstone(RangeTombstone::new(b"b".to_vec(), b"c".to_vec(), 2));
+        b.add_range_tombstone(RangeTombstone::new(b"a".to_vec(), b"b".to_vec(), 1));
+
+        let (_, meta) = b.finish();
+
+        assert_eq!(meta.key_count, 0);
+        assert_eq!(meta.range_tombstones.len(), 2);
+        assert_eq!(key_with_epoch(b"a".to_vec(), Epoch::MAX), meta.smallest_key);
+        assert_eq!(key_with_epoch(b"c".to_vec(), Epoch::MAX), meta.largest_key);
+    }
+
+    #[test]
+    fn test_keyspace_stats() {
+        let mut b = SSTableBuilder::new(default_builder_opt_for_test());
+        for (user_key, value) in [
+            (&b"t\0\0\0\x01a"[..], &b"v1"[..]),
+            (b"t\0\0\0\x01b", b"v2"),
+            (b"t\0\0\0\x02a", b"v3"),
+        ] {
+            b.add(
+                &key_with_epoch(user_key.to_vec(), 1),
+                HummockValue::put(value),
+            );
+        }
+
+        let (_, meta) = b.finish();
+
+        let stats = meta
+            .keyspace_stats
+            .iter()
+            .map(|stats| (stats.prefix.as_slice(), stats.key_count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stats,
+            vec![(&b"t\0\0\0\x01"[..], 2), (&b"t\0\0\0\x02"[..], 1)]
+        );
+        let full_key_len = b"t\0\0\0\x02a".len() + 8;
+        assert_eq!(
+            meta.keyspace_stats[1].total_size as usize,
+            full_key_len + HummockValue::put(&b"v3"[..]).encoded_len()
+        );
+    }
+
     async fn test_with_bloom_filter(with_blooms: bool) {
         let key_count = 1000;
 
@@ -248,6 +344,20 @@ pub(super) mod tests {
             let full_key = test_key_of(i);
             assert!(!table.surely_not_have_user_key(user_key(full_key.as_slice())));
         }
+
+        // The test table only contains even keys, so the odd ones are absent and most of them
+        // should be filtered out.
+        let false_positive_count = (0..key_count)
+            .filter(|i| {
+                let absent_key = format!("key_test_{:05}", i * 2 + 1);
+                !table.surely_not_have_user_key(absent_key.as_bytes())
+            })
+            .count();
+        if with_blooms {
+            assert!(false_positive_count < key_count / 20);
+        } else {
+            assert_eq!(false_positive_count, key_count);
+        }
     }
 
     #[tokio::test]
@@ -26,6 +26,7 @@ pub use builder::*;
 pub mod multi_builder;
 mod sstable_iterator;
 use bytes::{Buf, BufMut};
+use risingwave_hummock_sdk::key::{get_epoch, key_with_epoch, next_key, user_key, Epoch};
 pub use sstable_iterator::*;
 mod reverse_sstable_iterator;
 pub use reverse_sstable_iterator::*;
@@ -38,7 +39,7 @@ use super::{HummockError, HummockResult};
 
 const DEFAULT_META_BUFFER_CAPACITY: usize = 4096;
 const MAGIC: u32 = 0x5785ab73;
-const VERSION: u32 = 1;
+const VERSION: u32 = 4;
 
 #[derive(Clone, Debug)]
 /// [`Sstable`] is a handle for accessing SST.
@@ -112,6 +113,203 @@ impl BlockMeta {
     }
 }
 
+/// [`RangeTombstone`] deletes the versions of the user keys in `[start_user_key, end_user_key)`
+/// which are written in or before `epoch`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RangeTombstone {
+    pub start_user_key: Vec<u8>,
+    pub end_user_key: Vec<u8>,
+    pub epoch: Epoch,
+}
+
+impl RangeTombstone {
+    pub fn new(start_user_key: Vec<u8>, end_user_key: Vec<u8>, epoch: Epoch) -> Self {
+        Self {
+            start_user_key,
+            end_user_key,
+            epoch,
+        }
+    }
+
+    /// Whether the version of `full_key` is deleted by the tombstone.
+    pub fn covers(&self, full_key: &[u8]) -> bool {
+        get_epoch(full_key) <= self.epoch && self.contains_user_key(user_key(full_key))
+    }
+
+    /// Whether `user_key` is in the range of the tombstone, regardless of the epoch.
+    pub fn contains_user_key(&self, user_key: &[u8]) -> bool {
+        self.start_user_key.as_slice() <= user_key && user_key < self.end_user_key.as_slice()
+    }
+
+    /// Returns the part of the tombstone in `[start_user_key, end_user_key)`, where `None` means
+    /// unbounded, or `None` if they don't overlap.
+    pub fn clip(
+        &self,
+        start_user_key: Option<&[u8]>,
+        end_user_key: Option<&[u8]>,
+    ) -> Option<RangeTombstone> {
+        let start_user_key = match start_user_key {
+            Some(start_user_key) => std::cmp::max(self.start_user_key.as_slice(), start_user_key),
+            None => self.start_user_key.as_slice(),
+        };
+        let end_user_key = match end_user_key {
+            Some(end_user_key) => std::cmp::min(self.end_user_key.as_slice(), end_user_key),
+            None => self.end_user_key.as_slice(),
+        };
+        (start_user_key < end_user_key).then(|| {
+            RangeTombstone::new(start_user_key.to_vec(), end_user_key.to_vec(), self.epoch)
+        })
+    }
+
+    /// The smallest full key in the range of the tombstone.
+    pub fn smallest_key(&self) -> Vec<u8> {
+        key_with_epoch(self.start_user_key.clone(), Epoch::MAX)
+    }
+
+    /// The inclusive upper bound of the full keys in the range of the tombstone. As the end user
+    /// key is exclusive, it's the smallest full key of the end user key, which sorts before any
+    /// version written to it.
+    pub fn largest_key(&self) -> Vec<u8> {
+        key_with_epoch(self.end_user_key.clone(), Epoch::MAX)
+    }
+
+    /// Format:
+    ///
+    /// ```plain
+    /// | start key len (4B) | start key | end key len (4B) | end key | epoch (8B) |
+    /// ```
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        put_length_prefixed_slice(buf, &self.start_user_key);
+        put_length_prefixed_slice(buf, &self.end_user_key);
+        buf.put_u64_le(self.epoch);
+    }
+
+    pub fn decode(buf: &mut &[u8]) -> Self {
+        let start_user_key = get_length_prefixed_slice(buf);
+        let end_user_key = get_length_prefixed_slice(buf);
+        let epoch = buf.get_u64_le();
+        Self {
+            start_user_key,
+            end_user_key,
+            epoch,
+        }
+    }
+
+    #[inline]
+    pub fn encoded_size(&self) -> usize {
+        16 /* key lens + epoch */ + self.start_user_key.len() + self.end_user_key.len()
+    }
+}
+
+/// [`KeyspaceTtl`] expires the versions of the user keys with `prefix` which are written more than
+/// `ttl` epochs before the read epoch. The expired versions are invisible to reads and dropped by
+/// compaction, without explicit deletes.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KeyspaceTtl {
+    pub prefix: Vec<u8>,
+    pub ttl: Epoch,
+}
+
+impl KeyspaceTtl {
+    pub fn new(prefix: Vec<u8>, ttl: Epoch) -> Self {
+        debug_assert!(!next_key(&prefix).is_empty(), "unbounded keyspace");
+        Self { prefix, ttl }
+    }
+
+    /// Returns the range tombstone deleting the versions expired at `epoch`, if any.
+    pub fn expired_at(&self, epoch: Epoch) -> Option<RangeTombstone> {
+        (epoch > self.ttl).then(|| {
+            RangeTombstone::new(
+                self.prefix.clone(),
+                next_key(&self.prefix),
+                epoch - self.ttl - 1,
+            )
+        })
+    }
+
+    /// Whether `user_key` has the prefix of the keyspace.
+    pub fn contains_user_key(&self, user_key: &[u8]) -> bool {
+        user_key.starts_with(&self.prefix)
+    }
+
+    /// Whether the keyspace overlaps with the user keys in `[smallest_user_key,
+    /// largest_user_key]`.
+    pub fn overlaps(&self, smallest_user_key: &[u8], largest_user_key: &[u8]) -> bool {
+        self.prefix.as_slice() <= largest_user_key
+            && smallest_user_key < next_key(&self.prefix).as_slice()
+    }
+
+    /// Format:
+    ///
+    /// ```plain
+    /// | prefix len (4B) | prefix | ttl (8B) |
+    /// ```
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        put_length_prefixed_slice(buf, &self.prefix);
+        buf.put_u64_le(self.ttl);
+    }
+
+    pub fn decode(buf: &mut &[u8]) -> Self {
+        let prefix = get_length_prefixed_slice(buf);
+        let ttl = buf.get_u64_le();
+        Self { prefix, ttl }
+    }
+
+    #[inline]
+    pub fn encoded_size(&self) -> usize {
+        12 /* prefix len + ttl */ + self.prefix.len()
+    }
+}
+
+/// [`KeyspaceStats`] counts the versions in the SST of a root keyspace, whose `prefix` is returned
+/// by [`keyspace_prefix`](risingwave_hummock_sdk::key::keyspace_prefix).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KeyspaceStats {
+    pub prefix: Vec<u8>,
+    pub key_count: u64,
+    /// Total size of the full keys and the encoded values.
+    pub total_size: u64,
+}
+
+impl KeyspaceStats {
+    /// Format:
+    ///
+    /// ```plain
+    /// | prefix len (4B) | prefix | key count (8B) | total size (8B) |
+    /// ```
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        put_length_prefixed_slice(buf, &self.prefix);
+        buf.put_u64_le(self.key_count);
+        buf.put_u64_le(self.total_size);
+    }
+
+    pub fn decode(buf: &mut &[u8]) -> Self {
+        let prefix = get_length_prefixed_slice(buf);
+        let key_count = buf.get_u64_le();
+        let total_size = buf.get_u64_le();
+        Self {
+            prefix,
+            key_count,
+            total_size,
+        }
+    }
+
+    #[inline]
+    pub fn encoded_size(&self) -> usize {
+        20 /* prefix len + key count + total size */ + self.prefix.len()
+    }
+}
+
+impl From<&KeyspaceStats> for risingwave_pb::hummock::KeyspaceStats {
+    fn from(stats: &KeyspaceStats) -> Self {
+        Self {
+            prefix: stats.prefix.clone(),
+            key_count: stats.key_count,
+            total_size: stats.total_size,
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct SstableMeta {
     pub block_metas: Vec<BlockMeta>,
@@ -120,6 +318,9 @@ pub struct SstableMeta {
     pub key_count: u32,
     pub smallest_key: Vec<u8>,
     pub largest_key: Vec<u8>,
+    pub range_tombstones: Vec<RangeTombstone>,
+    pub keyspace_ttls: Vec<KeyspaceTtl>,
+    pub keyspace_stats: Vec<KeyspaceStats>,
     /// Format version, for further compatibility.
     pub version: u32,
 }
@@ -134,6 +335,9 @@ impl SstableMeta {
     /// | estimated size (4B) | key count (4B) |
     /// | smallest key len (4B) | smallest key |
     /// | largest key len (4B) | largest key |
+    /// | M (4B) | range tombstone 0 | ... | range tombstone M-1 |
+    /// | K (4B) | keyspace ttl 0 | ... | keyspace ttl K-1 |
+    /// | S (4B) | keyspace stats 0 | ... | keyspace stats S-1 |
     /// | checksum (8B) | version (4B) | magic (4B) |
     /// ```
     pub fn encode_to_bytes(&self) -> Vec<u8> {
@@ -147,6 +351,18 @@ impl SstableMeta {
         buf.put_u32_le(self.key_count as u32);
         put_length_prefixed_slice(&mut buf, &self.smallest_key);
         put_length_prefixed_slice(&mut buf, &self.largest_key);
+        buf.put_u32_le(self.range_tombstones.len() as u32);
+        for range_tombstone in &self.range_tombstones {
+            range_tombstone.encode(&mut buf);
+        }
+        buf.put_u32_le(self.keyspace_ttls.len() as u32);
+        for keyspace_ttl in &self.keyspace_ttls {
+            keyspace_ttl.encode(&mut buf);
+        }
+        buf.put_u32_le(self.keyspace_stats.len() as u32);
+        for keyspace_stats in &self.keyspace_stats {
+            keyspace_stats.encode(&mut buf);
+        }
         let checksum = xxhash64_checksum(&buf);
         buf.put_u64_le(checksum);
         buf.put_u32_le(VERSION);
@@ -165,7 +381,9 @@ impl SstableMeta {
 
         cursor -= 4;
         let version = (&buf[cursor..cursor + 4]).get_u32_le();
-        if version != VERSION {
+        // Version 1 has no range tombstones, version 2 has no keyspace ttls, and version 3 has no
+        // keyspace stats.
+        if version == 0 || version > VERSION {
             return Err(HummockError::invalid_format_version(version));
         }
 
@@ -184,6 +402,30 @@ impl SstableMeta {
         let key_count = buf.get_u32_le();
         let smallest_key = get_length_prefixed_slice(buf);
         let largest_key = get_length_prefixed_slice(buf);
+        let mut range_tombstones = vec![];
+        if version >= 2 {
+            let range_tombstone_count = buf.get_u32_le() as usize;
+            range_tombstones.reserve(range_tombstone_count);
+            for _ in 0..range_tombstone_count {
+                range_tombstones.push(RangeTombstone::decode(buf));
+            }
+        }
+        let mut keyspace_ttls = vec![];
+        if version >= 3 {
+            let keyspace_ttl_count = buf.get_u32_le() as usize;
+            keyspace_ttls.reserve(keyspace_ttl_count);
+            for _ in 0..keyspace_ttl_count {
+                keyspace_ttls.push(KeyspaceTtl::decode(buf));
+            }
+        }
+        let mut keyspace_stats = vec![];
+        if version >= 4 {
+            let keyspace_stats_count = buf.get_u32_le() as usize;
+            keyspace_stats.reserve(keyspace_stats_count);
+            for _ in 0..keyspace_stats_count {
+                keyspace_stats.push(KeyspaceStats::decode(buf));
+            }
+        }
 
         Ok(Self {
             block_metas,
@@ -192,6 +434,9 @@ impl SstableMeta {
             key_count,
             smallest_key,
             largest_key,
+            range_tombstones,
+            keyspace_ttls,
+            keyspace_stats,
             version,
         })
     }
@@ -212,6 +457,24 @@ impl SstableMeta {
             + self.smallest_key.len()
             + 4 // key len
             + self.largest_key.len()
+            + 4 // range tombstone count
+            + self
+            .range_tombstones
+            .iter()
+            .map(|range_tombstone| range_tombstone.encoded_size())
+            .sum::<usize>()
+            + 4 // keyspace ttl count
+            + self
+            .keyspace_ttls
+            .iter()
+            .map(|keyspace_ttl| keyspace_ttl.encoded_size())
+            .sum::<usize>()
+            + 4 // keyspace stats count
+            + self
+            .keyspace_stats
+            .iter()
+            .map(|keyspace_stats| keyspace_stats.encoded_size())
+            .sum::<usize>()
             + 8 // checksum
             + 4 // version
             + 4 // magic
@@ -242,9 +505,21 @@ mod tests {
             key_count: 123,
             smallest_key: b"0-smallest-key".to_vec(),
             largest_key: b"9-largest-key".to_vec(),
+            range_tombstones: vec![RangeTombstone::new(
+                b"1-start-key".to_vec(),
+                b"2-end-key".to_vec(),
+                233,
+            )],
+            keyspace_ttls: vec![KeyspaceTtl::new(b"1-prefix".to_vec(), 100)],
+            keyspace_stats: vec![KeyspaceStats {
+                prefix: b"0".to_vec(),
+                key_count: 123,
+                total_size: 4567,
+            }],
             version: VERSION,
         };
         let buf = meta.encode_to_bytes();
+        assert_eq!(buf.len(), meta.encoded_size());
         let decoded_meta = SstableMeta::decode(&mut &buf[..]).unwrap();
         assert_eq!(decoded_meta, meta);
     }
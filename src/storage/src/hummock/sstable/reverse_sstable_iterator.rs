@@ -44,7 +44,7 @@ impl ReverseSSTableIterator {
     pub fn new(table: Arc<Sstable>, sstable_store: SstableStoreRef) -> Self {
         Self {
             block_iter: None,
-            cur_idx: table.meta.block_metas.len() - 1,
+            cur_idx: table.meta.block_metas.len().saturating_sub(1),
             sst: table,
             sstable_store,
         }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::min;
 use std::cmp::Ordering::{Equal, Less};
 use std::sync::Arc;
 
@@ -41,6 +42,9 @@ pub struct SSTableIterator {
     /// Current block index.
     cur_idx: usize,
 
+    /// Blocks before this index have been prefetched.
+    prefetched_idx: usize,
+
     /// Reference to the sst
     pub sst: Arc<Sstable>,
 
@@ -52,6 +56,7 @@ impl SSTableIterator {
         Self {
             block_iter: None,
             cur_idx: 0,
+            prefetched_idx: 0,
             sst: table,
             sstable_store,
         }
@@ -77,6 +82,7 @@ impl SSTableIterator {
                 block_iter.seek(key);
             } else {
                 block_iter.seek_to_first();
+                self.prefetch(idx + 1);
             }
 
             self.block_iter = Some(block_iter);
@@ -85,6 +91,19 @@ impl SSTableIterator {
 
         Ok(())
     }
+
+    /// Prefetches the blocks from `idx` up to the prefetch depth when iterating sequentially.
+    /// Point lookups only seek, so they don't read ahead.
+    fn prefetch(&mut self, idx: usize) {
+        let end = min(
+            idx + self.sstable_store.prefetch_depth(),
+            self.sst.block_count(),
+        );
+        for i in self.prefetched_idx.max(idx)..end {
+            self.sstable_store.prefetch(self.sst.clone(), i as u64);
+        }
+        self.prefetched_idx = self.prefetched_idx.max(end);
+    }
 }
 
 #[async_trait]
@@ -116,6 +135,7 @@ impl HummockIterator for SSTableIterator {
     }
 
     async fn rewind(&mut self) -> HummockResult<()> {
+        self.prefetched_idx = 0;
         self.seek_idx(0, None).await
     }
 
@@ -133,6 +153,7 @@ impl HummockIterator for SSTableIterator {
             })
             .saturating_sub(1); // considering the boundary of 0
 
+        self.prefetched_idx = 0;
         self.seek_idx(block_idx, Some(key)).await?;
         if !self.is_valid() {
             // seek to next block
@@ -12,18 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use bytes::Bytes;
 use fail::fail_point;
+use futures::stream::{self, StreamExt};
 use moka::future::Cache;
+use risingwave_common::util::memory_budget::MemoryBudgetRef;
 
-use super::{Block, BlockCache, Sstable, SstableMeta};
+use super::{Block, BlockAccessSketch, BlockCache, Sstable, SstableMeta};
 use crate::hummock::{HummockError, HummockResult};
 use crate::monitor::StateStoreMetrics;
 use crate::object::{BlockLocation, ObjectStoreRef};
 
 const DEFAULT_META_CACHE_INIT_CAPACITY: usize = 1024;
+/// Number of blocks fetched concurrently when warming up the block cache.
+const WARM_UP_CONCURRENCY: usize = 16;
 
 // TODO: Define policy based on use cases (read / compaction / ...).
 pub enum CachePolicy {
@@ -37,6 +42,10 @@ pub struct SstableStore {
     store: ObjectStoreRef,
     block_cache: BlockCache,
     meta_cache: Cache<u64, Arc<Sstable>>,
+    /// Number of blocks read ahead by sequential iterators.
+    prefetch_depth: usize,
+    /// Tracks the hottest blocks to warm up the block cache on restart.
+    access_sketch: Option<Arc<BlockAccessSketch>>,
     /// Statistics.
     stats: Arc<StateStoreMetrics>,
 }
@@ -60,10 +69,37 @@ impl SstableStore {
             store,
             block_cache: BlockCache::new(block_cache_capacity),
             meta_cache,
+            prefetch_depth: 0,
+            access_sketch: None,
             stats,
         }
     }
 
+    /// Sets the number of blocks read ahead by sequential iterators, see [`Self::prefetch`].
+    pub fn with_prefetch_depth(mut self, prefetch_depth: usize) -> Self {
+        self.prefetch_depth = prefetch_depth;
+        self
+    }
+
+    pub fn prefetch_depth(&self) -> usize {
+        self.prefetch_depth
+    }
+
+    /// Records the block reads to `access_sketch`, see [`Self::warm_up`].
+    pub fn with_access_sketch(mut self, access_sketch: Arc<BlockAccessSketch>) -> Self {
+        self.access_sketch = Some(access_sketch);
+        self
+    }
+
+    pub fn access_sketch(&self) -> Option<&Arc<BlockAccessSketch>> {
+        self.access_sketch.as_ref()
+    }
+
+    /// The memory budget of the block cache.
+    pub fn block_cache_budget(&self) -> MemoryBudgetRef {
+        self.block_cache.budget()
+    }
+
     pub async fn put(
         &self,
         sst: &Sstable,
@@ -116,42 +152,109 @@ impl SstableStore {
     ) -> HummockResult<Arc<Block>> {
         self.stats.sst_store_block_request_counts.inc();
 
-        let fetch_block = async move {
-            let timer = self.stats.sst_store_get_remote_duration.start_timer();
-
-            let block_meta = sst
-                .meta
-                .block_metas
-                .get(block_index as usize)
-                .ok_or_else(HummockError::invalid_block)?;
-            let block_loc = BlockLocation {
-                offset: block_meta.offset as usize,
-                size: block_meta.len as usize,
-            };
-            let data_path = self.get_sst_data_path(sst.id);
-            let block_data = self
-                .store
-                .read(&data_path, Some(block_loc))
-                .await
-                .map_err(HummockError::object_io_error)?;
-            let block = Block::decode(block_data)?;
-
-            timer.observe_duration();
-            Ok(Arc::new(block))
-        };
+        let fetch_block = self.fetch_block(sst, block_index);
 
+        if let CachePolicy::Disable = policy {
+            return fetch_block.await;
+        }
+        if let Some(access_sketch) = &self.access_sketch {
+            access_sketch.record(sst.id, block_index);
+        }
+        if let Some(block) = self.block_cache.get(sst.id, block_index) {
+            self.stats.sst_store_block_cache_hit_counts.inc();
+            return Ok(block);
+        }
+        self.stats.sst_store_block_cache_miss_counts.inc();
         match policy {
             CachePolicy::Fill => {
                 self.block_cache
                     .get_or_insert_with(sst.id, block_index, fetch_block)
                     .await
             }
-            CachePolicy::NotFill => match self.block_cache.get(sst.id, block_index) {
-                Some(block) => Ok(block),
-                None => fetch_block.await,
-            },
-            CachePolicy::Disable => fetch_block.await,
+            CachePolicy::NotFill | CachePolicy::Disable => fetch_block.await,
+        }
+    }
+
+    /// Fetches a block into the block cache in background, so that the following `get` of it is
+    /// likely to be served by the cache. A `get` issued while the block is still being fetched
+    /// waits for it, and gets the error if it fails. Failed blocks are not cached, and will be
+    /// fetched again by the next `get`.
+    pub fn prefetch(self: &Arc<Self>, sst: Arc<Sstable>, block_index: u64) {
+        if block_index as usize >= sst.block_count() {
+            return;
         }
+        let sstable_store = self.clone();
+        tokio::spawn(async move {
+            let fetch_block = sstable_store.fetch_block(&sst, block_index);
+            if let Err(e) = sstable_store
+                .block_cache
+                .get_or_insert_with(sst.id, block_index, fetch_block)
+                .await
+            {
+                tracing::debug!(
+                    "failed to prefetch block {} of sst {}: {}",
+                    block_index,
+                    sst.id,
+                    e
+                );
+            }
+        });
+    }
+
+    /// Fetches up to `blocks` of the hottest blocks recorded by the access sketch into the block
+    /// cache, skipping the ones not in `live_sst_ids`. Returns the number of blocks fetched.
+    /// Failures are only logged, as warming up is merely an optimization.
+    pub async fn warm_up(&self, live_sst_ids: &HashSet<u64>, blocks: usize) -> usize {
+        let access_sketch = match &self.access_sketch {
+            Some(access_sketch) => access_sketch,
+            None => return 0,
+        };
+        let hottest = access_sketch
+            .hottest()
+            .into_iter()
+            .filter(|(sst_id, _)| live_sst_ids.contains(sst_id))
+            .take(blocks);
+        stream::iter(hottest)
+            .map(|(sst_id, block_index)| async move {
+                let sst = self.sstable(sst_id).await?;
+                let fetch_block = self.fetch_block(&sst, block_index);
+                self.block_cache
+                    .get_or_insert_with(sst_id, block_index, fetch_block)
+                    .await
+            })
+            .buffer_unordered(WARM_UP_CONCURRENCY)
+            .filter(|result| {
+                if let Err(e) = result {
+                    tracing::debug!("failed to warm up block: {}", e);
+                }
+                futures::future::ready(result.is_ok())
+            })
+            .count()
+            .await
+    }
+
+    async fn fetch_block(&self, sst: &Sstable, block_index: u64) -> HummockResult<Arc<Block>> {
+        let timer = self.stats.sst_store_get_remote_duration.start_timer();
+
+        let block_meta = sst
+            .meta
+            .block_metas
+            .get(block_index as usize)
+            .ok_or_else(HummockError::invalid_block)?;
+        let block_loc = BlockLocation {
+            offset: block_meta.offset as usize,
+            size: block_meta.len as usize,
+        };
+        let data_path = self.get_sst_data_path(sst.id);
+        let block_data = self
+            .store
+            .read(&data_path, Some(block_loc))
+            .await
+            .map_err(HummockError::object_io_error)?;
+        let block = Block::decode(block_data)?;
+
+        timer.observe_duration();
+        Ok(Arc::new(block))
     }
 
     pub async fn sstable(&self, sst_id: u64) -> HummockResult<Arc<Sstable>> {
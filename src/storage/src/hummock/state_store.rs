@@ -27,7 +27,7 @@ use super::iterator::{
     ReverseConcatIterator, ReverseMergeIterator, ReverseUserIterator, UserIterator,
 };
 use super::utils::{range_overlap, validate_epoch, validate_table_key_range};
-use super::{HummockStorage, ReverseSSTableIterator, SSTableIterator};
+use super::{HummockStorage, KeyspaceTtl, RangeTombstone, ReverseSSTableIterator, SSTableIterator};
 use crate::error::StorageResult;
 use crate::storage_value::StorageValue;
 use crate::store::*;
@@ -52,6 +52,8 @@ impl HummockStorage {
 
         // Filter out tables that overlap with given `key_range`
         let mut overlapped_sstable_iters = vec![];
+        let mut range_tombstones = vec![];
+        let mut keyspace_ttls = self.shared_buffer_manager.keyspace_ttls();
         for level in &levels {
             let table_ids = level
                 .table_infos
@@ -69,6 +71,16 @@ impl HummockStorage {
             }
 
             let tables = self.sstable_store.sstables(&table_ids).await?;
+            range_tombstones.extend(
+                tables
+                    .iter()
+                    .flat_map(|table| table.meta.range_tombstones.iter().cloned()),
+            );
+            keyspace_ttls.extend(
+                tables
+                    .iter()
+                    .flat_map(|table| table.meta.keyspace_ttls.iter().cloned()),
+            );
             match level.level_type() {
                 LevelType::Overlapping => {
                     for table in tables.into_iter().rev() {
@@ -99,6 +111,19 @@ impl HummockStorage {
             .iter_merge_sstable_counts
             .observe(overlapped_sstable_iters.len() as f64);
 
+        if version.max_committed_epoch() < epoch {
+            range_tombstones.extend(
+                self.shared_buffer_manager
+                    .range_tombstones((version.max_committed_epoch() + 1)..=epoch),
+            );
+        }
+        // The expired versions are filtered out in the same way as the range deleted ones.
+        range_tombstones.extend(
+            keyspace_ttls
+                .iter()
+                .filter_map(|keyspace_ttl| keyspace_ttl.expired_at(epoch)),
+        );
+
         let mut user_iterator = if reversed {
             let reverse_merge_iterator = if version.max_committed_epoch() < epoch {
                 // Take shared buffers into consideration if the read epoch is above the max
@@ -124,6 +149,7 @@ impl HummockStorage {
                     key_range.start_bound().map(|b| b.as_ref().to_owned()),
                 ),
                 epoch,
+                range_tombstones,
                 Some(version),
             ))
         } else {
@@ -150,6 +176,7 @@ impl HummockStorage {
                     key_range.end_bound().map(|b| b.as_ref().to_owned()),
                 ),
                 epoch,
+                range_tombstones,
                 Some(version),
             ))
         };
@@ -157,6 +184,27 @@ impl HummockStorage {
         user_iterator.rewind().await?;
         Ok(HummockStateStoreIter::new(user_iterator))
     }
+
+    /// Gets the value of `key` by an iterator, which applies the range tombstones and the keyspace
+    /// ttls.
+    async fn get_by_iter(&self, key: &[u8], epoch: u64) -> StorageResult<Option<Bytes>> {
+        let mut iter = self.iter_inner(key..=key, epoch, false).await?;
+        Ok(iter.next().await?.map(|(_, value)| value))
+    }
+}
+
+/// Whether `key` may be deleted by one of the `range_tombstones` visible at `epoch`.
+fn may_be_range_deleted(range_tombstones: &[RangeTombstone], key: &[u8], epoch: u64) -> bool {
+    range_tombstones.iter().any(|range_tombstone| {
+        range_tombstone.epoch <= epoch && range_tombstone.contains_user_key(key)
+    })
+}
+
+/// Whether `key` may be expired by one of the `keyspace_ttls`.
+fn may_be_expired(keyspace_ttls: &[KeyspaceTtl], key: &[u8]) -> bool {
+    keyspace_ttls
+        .iter()
+        .any(|keyspace_ttl| keyspace_ttl.contains_user_key(key))
 }
 
 impl StateStore for HummockStorage {
@@ -176,8 +224,18 @@ impl StateStore for HummockStorage {
             // check epoch validity
             validate_epoch(version.safe_epoch(), epoch)?;
 
+            if may_be_expired(&self.shared_buffer_manager.keyspace_ttls(), key) {
+                return self.get_by_iter(key, epoch).await;
+            }
+
             // Query shared buffer. Return the value without iterating SSTs if found
             if version.max_committed_epoch() < epoch {
+                let range_tombstones = self
+                    .shared_buffer_manager
+                    .range_tombstones((version.max_committed_epoch() + 1)..=epoch);
+                if may_be_range_deleted(&range_tombstones, key, epoch) {
+                    return self.get_by_iter(key, epoch).await;
+                }
                 if let Some(v) = self
                     .shared_buffer_manager
                     .get(key, (version.max_committed_epoch() + 1)..=epoch)
@@ -208,6 +266,13 @@ impl StateStore for HummockStorage {
                             .collect_vec();
                         let tables = self.sstable_store.sstables(&table_infos).await?;
                         for table in tables.into_iter().rev() {
+                            // Newer tables are checked first, so the key may only be deleted by
+                            // the range tombstones in this table or the previous ones.
+                            if may_be_range_deleted(&table.meta.range_tombstones, key, epoch)
+                                || may_be_expired(&table.meta.keyspace_ttls, key)
+                            {
+                                return self.get_by_iter(key, epoch).await;
+                            }
                             table_counts += 1;
                             if let Some(v) = self.get_from_table(table, &internal_key, key).await? {
                                 return Ok(Some(v));
@@ -233,6 +298,11 @@ impl StateStore for HummockStorage {
                             .sstable_store
                             .sstables(&[level.table_infos[table_idx].id])
                             .await?;
+                        if may_be_range_deleted(&tables[0].meta.range_tombstones, key, epoch)
+                            || may_be_expired(&tables[0].meta.keyspace_ttls, key)
+                        {
+                            return self.get_by_iter(key, epoch).await;
+                        }
                         if let Some(v) = self
                             .get_from_table(tables.first().unwrap().clone(), &internal_key, key)
                             .await?
@@ -338,6 +408,36 @@ impl StateStore for HummockStorage {
         }
     }
 
+    /// Deletes the keys in the ranges by putting range tombstones into the shared buffer. They
+    /// are uploaded to an SST without keys on sync, and reclaimed on compaction.
+    fn delete_ranges(
+        &self,
+        ranges: Vec<(Bytes, Bytes)>,
+        epoch: u64,
+    ) -> Self::DeleteRangesFuture<'_> {
+        async move {
+            let range_tombstones = ranges
+                .into_iter()
+                .map(|(start, end)| RangeTombstone::new(start.to_vec(), end.to_vec(), epoch))
+                .collect_vec();
+            self.shared_buffer_manager
+                .delete_ranges(range_tombstones, epoch)
+                .await?;
+
+            if !self.options.async_checkpoint_enabled {
+                self.shared_buffer_manager.sync(Some(epoch)).await?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Registers the ttl of the keyspace, which is recorded in the SSTs synced later, and then
+    /// respected by compaction.
+    fn set_keyspace_ttl(&self, prefix: &[u8], ttl: u64) {
+        self.shared_buffer_manager
+            .set_keyspace_ttl(prefix.to_vec(), ttl);
+    }
+
     /// Returns an iterator that scan from the begin key to the end key
     /// The result is based on a snapshot corresponding to the given `epoch`.
     fn iter<R, B>(&self, key_range: R, epoch: u64) -> Self::IterFuture<'_, R, B>
@@ -377,3 +377,154 @@ async fn test_reload_storage() {
     let len = count_iter(&mut iter).await;
     assert_eq!(len, 3);
 }
+
+#[tokio::test]
+async fn test_delete_ranges() {
+    let sstable_store = mock_sstable_store_with_object_store(Arc::new(ObjectStoreImpl::Mem(
+        InMemObjectStore::new(),
+    )));
+    let hummock_options = Arc::new(default_config_for_test());
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref.clone(),
+        worker_node.id,
+    ));
+    let hummock_storage = HummockStorage::with_default_stats(
+        hummock_options,
+        sstable_store,
+        Arc::new(LocalVersionManager::new()),
+        meta_client.clone(),
+        Arc::new(StateStoreMetrics::unused()),
+    )
+    .await
+    .unwrap();
+
+    let epoch1: u64 = 1;
+    let batch1 = ["aa", "bb", "cc", "dd"]
+        .into_iter()
+        .map(|key| (Bytes::from(key), StorageValue::new_default_put(key)))
+        .collect();
+    hummock_storage.ingest_batch(batch1, epoch1).await.unwrap();
+
+    // Delete bb and cc.
+    let epoch2 = epoch1 + 1;
+    hummock_storage
+        .delete_ranges(vec![(Bytes::from("bb"), Bytes::from("dd"))], epoch2)
+        .await
+        .unwrap();
+
+    // Write bc after the range is deleted.
+    let epoch3 = epoch2 + 1;
+    hummock_storage
+        .ingest_batch(
+            vec![(Bytes::from("bc"), StorageValue::new_default_put("bc"))],
+            epoch3,
+        )
+        .await
+        .unwrap();
+
+    // Read from the shared buffer first, and then from the SSTs, where the range tombstone is in a
+    // separate SST.
+    for commit in [false, true] {
+        if commit {
+            hummock_storage.sync(None).await.unwrap();
+            for epoch in [epoch1, epoch2, epoch3] {
+                meta_client.commit_epoch(epoch).await.unwrap();
+            }
+            hummock_storage.wait_epoch(epoch3).await.unwrap();
+        }
+
+        for (key, epoch, exists) in [
+            ("aa", epoch2, true),
+            ("bb", epoch1, true),
+            ("bb", epoch2, false),
+            ("cc", epoch2, false),
+            ("dd", epoch2, true),
+            ("bc", epoch3, true),
+        ] {
+            let value = hummock_storage.get(&Bytes::from(key), epoch).await.unwrap();
+            assert_eq!(value.is_some(), exists, "key {} at epoch {}", key, epoch);
+        }
+
+        for (epoch, expected) in [(epoch1, 4), (epoch2, 2), (epoch3, 3)] {
+            let mut iter = hummock_storage
+                .iter(..=b"ee".to_vec(), epoch)
+                .await
+                .unwrap();
+            assert_eq!(count_iter(&mut iter).await, expected);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_keyspace_ttl() {
+    let sstable_store = mock_sstable_store_with_object_store(Arc::new(ObjectStoreImpl::Mem(
+        InMemObjectStore::new(),
+    )));
+    let hummock_options = Arc::new(default_config_for_test());
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref.clone(),
+        worker_node.id,
+    ));
+    let hummock_storage = HummockStorage::with_default_stats(
+        hummock_options,
+        sstable_store,
+        Arc::new(LocalVersionManager::new()),
+        meta_client.clone(),
+        Arc::new(StateStoreMetrics::unused()),
+    )
+    .await
+    .unwrap();
+
+    // The versions of the keys with prefix a expire after 1 epoch.
+    hummock_storage.set_keyspace_ttl(b"a", 1);
+
+    let epoch1: u64 = 1;
+    let batch1 = ["aa", "ab", "ba"]
+        .into_iter()
+        .map(|key| (Bytes::from(key), StorageValue::new_default_put(key)))
+        .collect();
+    hummock_storage.ingest_batch(batch1, epoch1).await.unwrap();
+
+    let epoch2 = epoch1 + 1;
+    let batch2 = ["ab", "ac"]
+        .into_iter()
+        .map(|key| (Bytes::from(key), StorageValue::new_default_put(key)))
+        .collect();
+    hummock_storage.ingest_batch(batch2, epoch2).await.unwrap();
+
+    let epoch3 = epoch2 + 1;
+    for commit in [false, true] {
+        if commit {
+            hummock_storage.sync(None).await.unwrap();
+            for epoch in [epoch1, epoch2] {
+                meta_client.commit_epoch(epoch).await.unwrap();
+            }
+            hummock_storage.wait_epoch(epoch2).await.unwrap();
+        }
+
+        for (key, epoch, exists) in [
+            ("aa", epoch2, true),
+            ("aa", epoch3, false),
+            // Refreshed in epoch 2.
+            ("ab", epoch3, true),
+            ("ac", epoch3, true),
+            // Not in the keyspace.
+            ("ba", epoch3, true),
+        ] {
+            let value = hummock_storage.get(&Bytes::from(key), epoch).await.unwrap();
+            assert_eq!(value.is_some(), exists, "key {} at epoch {}", key, epoch);
+        }
+
+        for (epoch, expected) in [(epoch2, 4), (epoch3, 3)] {
+            let mut iter = hummock_storage
+                .iter(..=b"ee".to_vec(), epoch)
+                .await
+                .unwrap();
+            assert_eq!(count_iter(&mut iter).await, expected);
+        }
+    }
+}
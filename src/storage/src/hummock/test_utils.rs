@@ -46,6 +46,12 @@ pub fn default_config_for_test() -> StorageConfig {
         write_conflict_detection_enabled: true,
         block_cache_capacity: 64 << 20,
         meta_cache_capacity: 64 << 20,
+        block_prefetch_depth: 2,
+        block_cache_warmup_path: "".to_string(),
+        block_cache_warmup_blocks: 0,
+        sst_upload_concurrency: 4,
+        sst_upload_bandwidth: 0,
+        multipart_upload_part_size: 16 << 20,
     }
 }
 
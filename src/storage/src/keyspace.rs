@@ -12,9 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt;
+use std::time::Duration;
+
 use bytes::{BufMut, Bytes, BytesMut};
 use risingwave_common::catalog::TableId;
 use risingwave_hummock_sdk::key::next_key;
+use risingwave_hummock_sdk::EPOCH_PHYSICAL_SHIFT_BITS;
 
 use crate::error::StorageResult;
 use crate::StateStore;
@@ -166,8 +170,103 @@ impl<S: StateStore> Keyspace<S> {
         self.store.iter(range, epoch).await
     }
 
+    /// Expires the values in the keyspace which are written more than `ttl` before the read epoch,
+    /// so that windowed states can be bounded without explicit deletes. Executors should set the
+    /// ttl every time they're built, before writing to the keyspace.
+    pub fn set_ttl(&self, ttl: Duration) {
+        let ttl_epochs = (ttl.as_millis() as u64) << EPOCH_PHYSICAL_SHIFT_BITS;
+        self.store.set_keyspace_ttl(&self.prefix, ttl_epochs);
+    }
+
     /// Gets the underlying state store.
     pub fn state_store(&self) -> S {
         self.store.clone()
     }
 }
+
+/// The owner of a key, decoded from the root segment of its [`Keyspace`]. It's used to attribute
+/// writes to the tables and executors producing them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum KeyspaceOwner {
+    /// Created by [`Keyspace::table_root`].
+    Table(u32),
+    /// Created by [`Keyspace::executor_root`].
+    Executor(u64),
+    /// Created by [`Keyspace::shared_executor_root`].
+    Operator(u64),
+}
+
+impl KeyspaceOwner {
+    /// Decodes the owner of `key`. Returns `None` if `key` is not in any root keyspace.
+    pub fn from_key(key: &[u8]) -> Option<Self> {
+        match key.first()? {
+            b't' => Some(Self::Table(u32::from_be_bytes(
+                key.get(1..5)?.try_into().ok()?,
+            ))),
+            b'e' => Some(Self::Executor(u64::from_be_bytes(
+                key.get(1..9)?.try_into().ok()?,
+            ))),
+            b's' => Some(Self::Operator(u64::from_be_bytes(
+                key.get(1..9)?.try_into().ok()?,
+            ))),
+            _ => None,
+        }
+    }
+
+    /// Returns the id of the actor owning the keyspace, if it's the keyspace of an executor. The
+    /// actor id is the high 32 bits of the executor id.
+    pub fn actor_id(&self) -> Option<u32> {
+        match self {
+            Self::Executor(executor_id) => Some((executor_id >> 32) as u32),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for KeyspaceOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Table(id) => write!(f, "table-{}", id),
+            Self::Executor(id) => write!(f, "executor-{}", id),
+            Self::Operator(id) => write!(f, "operator-{}", id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryStateStore;
+
+    #[test]
+    fn test_keyspace_owner() {
+        let store = MemoryStateStore::new();
+        let cases = [
+            (
+                Keyspace::table_root(store.clone(), &TableId::new(42)),
+                KeyspaceOwner::Table(42),
+            ),
+            (
+                Keyspace::executor_root(store.clone(), (7 << 32) + 3),
+                KeyspaceOwner::Executor((7 << 32) + 3),
+            ),
+            (
+                Keyspace::shared_executor_root(store, 5),
+                KeyspaceOwner::Operator(5),
+            ),
+        ];
+        for (keyspace, owner) in cases {
+            assert_eq!(KeyspaceOwner::from_key(keyspace.key()), Some(owner));
+            assert_eq!(
+                KeyspaceOwner::from_key(&keyspace.prefixed_key(b"key")),
+                Some(owner)
+            );
+        }
+        assert_eq!(KeyspaceOwner::Executor((7 << 32) + 3).actor_id(), Some(7));
+        assert_eq!(KeyspaceOwner::Table(42).actor_id(), None);
+
+        assert_eq!(KeyspaceOwner::from_key(b""), None);
+        assert_eq!(KeyspaceOwner::from_key(b"t\x00"), None);
+        assert_eq!(KeyspaceOwner::from_key(b"x\x00\x00\x00\x01"), None);
+    }
+}
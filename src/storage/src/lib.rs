@@ -67,7 +67,7 @@ pub mod tikv;
 #[path = "tikv_mock.rs"]
 pub mod tikv;
 
-pub use keyspace::Keyspace;
+pub use keyspace::{Keyspace, KeyspaceOwner};
 extern crate test;
 pub use store::{StateStore, StateStoreIter};
 pub use store_impl::StateStoreImpl;
@@ -21,6 +21,7 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 use lazy_static::lazy_static;
+use parking_lot::RwLock;
 use tokio::sync::Mutex;
 
 use crate::storage_value::StorageValue;
@@ -39,6 +40,9 @@ type KeyWithEpoch = (Bytes, Reverse<u64>);
 pub struct MemoryStateStore {
     /// Stores (key, epoch) -> user value. We currently don't consider value meta here.
     inner: Arc<Mutex<BTreeMap<KeyWithEpoch, Option<Bytes>>>>,
+
+    /// Stores prefix -> ttl of the keyspaces. The expired versions are filtered out on reads.
+    keyspace_ttls: Arc<RwLock<BTreeMap<Bytes, u64>>>,
 }
 
 impl Default for MemoryStateStore {
@@ -69,6 +73,7 @@ impl MemoryStateStore {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(BTreeMap::new())),
+            keyspace_ttls: Arc::new(RwLock::new(BTreeMap::new())),
         }
     }
 
@@ -114,6 +119,12 @@ impl StateStore for MemoryStateStore {
                 return Ok(vec![]);
             }
             let inner = self.inner.lock().await;
+            let keyspace_ttls = self.keyspace_ttls.read().clone();
+            let expired = |key: &Bytes, key_epoch: u64| {
+                keyspace_ttls.iter().any(|(prefix, ttl)| {
+                    key.starts_with(prefix) && key_epoch.saturating_add(*ttl) < epoch
+                })
+            };
 
             let mut last_key = None;
             for ((key, Reverse(key_epoch)), value) in inner.range(to_bytes_range(key_range)) {
@@ -121,7 +132,7 @@ impl StateStore for MemoryStateStore {
                     continue;
                 }
                 if Some(key) != last_key.as_ref() {
-                    if let Some(value) = value {
+                    if let Some(value) = value && !expired(key, *key_epoch) {
                         data.push((key.clone(), value.clone()));
                     }
                     last_key = Some(key.clone());
@@ -171,6 +182,33 @@ impl StateStore for MemoryStateStore {
         async move { unimplemented!() }
     }
 
+    fn delete_ranges(
+        &self,
+        ranges: Vec<(Bytes, Bytes)>,
+        epoch: u64,
+    ) -> Self::DeleteRangesFuture<'_> {
+        async move {
+            let mut inner = self.inner.lock().await;
+            for (start, end) in ranges {
+                let mut keys: Vec<_> = inner
+                    .range(to_bytes_range(start..end))
+                    .map(|((key, _), _)| key.clone())
+                    .collect();
+                keys.dedup();
+                for key in keys {
+                    inner.insert((key, Reverse(epoch)), None);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn set_keyspace_ttl(&self, prefix: &[u8], ttl: u64) {
+        self.keyspace_ttls
+            .write()
+            .insert(Bytes::copy_from_slice(prefix), ttl);
+    }
+
     fn iter<R, B>(&self, key_range: R, epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -12,17 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeMap, HashMap};
 use std::ops::RangeBounds;
 use std::sync::Arc;
 
 use bytes::Bytes;
 use futures::Future;
+use parking_lot::Mutex;
 
 use super::StateStoreMetrics;
 use crate::error::StorageResult;
 use crate::storage_value::StorageValue;
 use crate::store::*;
-use crate::{define_state_store_associated_type, StateStore, StateStoreIter};
+use crate::{define_state_store_associated_type, KeyspaceOwner, StateStore, StateStoreIter};
+
+/// Bytes written by each [`KeyspaceOwner`].
+pub type OwnerWriteBytes = HashMap<KeyspaceOwner, u64>;
+
+#[derive(Default)]
+struct WriteBytesTracker {
+    /// Bytes written in each epoch that is not synced yet.
+    unsynced: BTreeMap<u64, OwnerWriteBytes>,
+    /// Bytes written in the synced epochs, until they're taken.
+    synced: OwnerWriteBytes,
+}
 
 /// A state store wrapper for monitoring metrics.
 #[derive(Clone)]
@@ -30,16 +43,70 @@ pub struct MonitoredStateStore<S> {
     inner: S,
 
     stats: Arc<StateStoreMetrics>,
+
+    /// Attributes the writes to their tables and executors.
+    write_bytes: Arc<Mutex<WriteBytesTracker>>,
 }
 
 impl<S> MonitoredStateStore<S> {
     pub fn new(inner: S, stats: Arc<StateStoreMetrics>) -> Self {
-        Self { inner, stats }
+        Self {
+            inner,
+            stats,
+            write_bytes: Default::default(),
+        }
     }
 
     pub fn inner(&self) -> &S {
         &self.inner
     }
+
+    /// Takes the bytes written by each table and executor in the epochs synced since the last
+    /// call.
+    pub fn take_synced_write_bytes(&self) -> OwnerWriteBytes {
+        std::mem::take(&mut self.write_bytes.lock().synced)
+    }
+
+    fn track_write_bytes(&self, kv_pairs: &[(Bytes, StorageValue)], epoch: u64) {
+        let mut batch_bytes = OwnerWriteBytes::new();
+        for (key, value) in kv_pairs {
+            if let Some(owner) = KeyspaceOwner::from_key(key) {
+                *batch_bytes.entry(owner).or_default() += (key.len() + value.size()) as u64;
+            }
+        }
+        for (owner, bytes) in &batch_bytes {
+            self.stats
+                .write_owner_bytes
+                .with_label_values(&[&owner.to_string()])
+                .inc_by(*bytes);
+        }
+
+        let mut tracker = self.write_bytes.lock();
+        let epoch_bytes = tracker.unsynced.entry(epoch).or_default();
+        for (owner, bytes) in batch_bytes {
+            *epoch_bytes.entry(owner).or_default() += bytes;
+        }
+    }
+
+    /// Moves the bytes written in the epochs up to `epoch`, or all epochs if it's `None`, to the
+    /// synced ones.
+    fn sync_write_bytes(&self, epoch: Option<u64>) {
+        let mut tracker = self.write_bytes.lock();
+        let synced_epochs = match epoch {
+            Some(epoch) => {
+                let unsynced = tracker.unsynced.split_off(&(epoch + 1));
+                std::mem::replace(&mut tracker.unsynced, unsynced)
+            }
+            None => std::mem::take(&mut tracker.unsynced),
+        };
+        for (owner, bytes) in synced_epochs.into_values().flatten() {
+            self.stats
+                .write_epoch_owner_bytes
+                .with_label_values(&[&owner.to_string()])
+                .observe(bytes as _);
+            *tracker.synced.entry(owner).or_default() += bytes;
+        }
+    }
 }
 
 impl<S> MonitoredStateStore<S>
@@ -68,7 +135,10 @@ impl<S> StateStore for MonitoredStateStore<S>
 where
     S: StateStore,
 {
-    type Iter<'a> = MonitoredStateStoreIter<S::Iter<'a>> where Self: 'a;
+    type Iter<'a>
+        = MonitoredStateStoreIter<S::Iter<'a>>
+    where
+        Self: 'a;
 
     define_state_store_associated_type!();
 
@@ -146,6 +216,7 @@ where
             self.stats
                 .write_batch_tuple_counts
                 .inc_by(kv_pairs.len() as _);
+            self.track_write_bytes(&kv_pairs, epoch);
             let timer = self.stats.write_batch_duration.start_timer();
             let batch_size = self.inner.ingest_batch(kv_pairs, epoch).await?;
             timer.observe_duration();
@@ -183,6 +254,7 @@ where
             let timer = self.stats.shared_buffer_to_l0_duration.start_timer();
             self.inner.sync(epoch).await?;
             timer.observe_duration();
+            self.sync_write_bytes(epoch);
             Ok(())
         }
     }
@@ -198,6 +270,18 @@ where
     ) -> Self::ReplicateBatchFuture<'_> {
         async move { self.inner.replicate_batch(kv_pairs, epoch).await }
     }
+
+    fn delete_ranges(
+        &self,
+        ranges: Vec<(Bytes, Bytes)>,
+        epoch: u64,
+    ) -> Self::DeleteRangesFuture<'_> {
+        async move { self.inner.delete_ranges(ranges, epoch).await }
+    }
+
+    fn set_keyspace_ttl(&self, prefix: &[u8], ttl: u64) {
+        self.inner.set_keyspace_ttl(prefix, ttl)
+    }
 }
 
 /// A state store iterator wrapper for monitoring metrics.
@@ -211,7 +295,10 @@ where
 {
     type Item = (Bytes, Bytes);
 
-    type NextFuture<'a> = impl Future<Output = crate::error::StorageResult<Option<Self::Item>>> where Self: 'a;
+    type NextFuture<'a>
+        = impl Future<Output = crate::error::StorageResult<Option<Self::Item>>>
+    where
+        Self: 'a;
 
     fn next(&mut self) -> Self::NextFuture<'_> {
         async move {
@@ -14,8 +14,10 @@
 
 use prometheus::core::{AtomicU64, GenericCounter};
 use prometheus::{
-    histogram_opts, register_histogram_with_registry, register_int_counter_with_registry,
-    Histogram, Registry,
+    exponential_buckets, histogram_opts, register_histogram_vec_with_registry,
+    register_histogram_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, register_int_gauge_with_registry, Histogram, HistogramVec,
+    IntCounterVec, IntGauge, Registry,
 };
 
 use super::monitor_process;
@@ -63,16 +65,22 @@ macro_rules! for_all_metrics {
             write_batch_duration: Histogram,
             write_batch_size: Histogram,
             write_build_l0_sst_duration: Histogram,
+            write_owner_bytes: IntCounterVec,
+            write_epoch_owner_bytes: HistogramVec,
 
             iter_merge_sstable_counts: Histogram,
             iter_merge_seek_duration: Histogram,
 
             sst_store_block_request_counts: GenericCounter<AtomicU64>,
+            sst_store_block_cache_hit_counts: GenericCounter<AtomicU64>,
+            sst_store_block_cache_miss_counts: GenericCounter<AtomicU64>,
             sst_store_get_remote_duration: Histogram,
             sst_store_put_remote_duration: Histogram,
 
             shared_buffer_to_l0_duration: Histogram,
             shared_buffer_to_sstable_size: Histogram,
+            shared_buffer_upload_queue_depth: IntGauge,
+            shared_buffer_upload_epoch_duration: Histogram,
 
             compaction_upload_sst_counts: GenericCounter<AtomicU64>,
         }
@@ -208,6 +216,22 @@ impl StateStoreMetrics {
         );
         let write_batch_size = register_histogram_with_registry!(opts, registry).unwrap();
 
+        let write_owner_bytes = register_int_counter_vec_with_registry!(
+            "state_store_write_owner_bytes",
+            "Total bytes written to state store by each table or executor",
+            &["owner"],
+            registry
+        )
+        .unwrap();
+
+        let opts = histogram_opts!(
+            "state_store_write_epoch_owner_bytes",
+            "Bytes written to state store by each table or executor in an epoch",
+            exponential_buckets(64.0, 4.0, 14).unwrap() // max 4GB
+        );
+        let write_epoch_owner_bytes =
+            register_histogram_vec_with_registry!(opts, &["owner"], registry).unwrap();
+
         let buckets = DEFAULT_BUCKETS
             .map(|x| x * BATCH_WRITE_BUILD_TABLE_LATENCY_SCALE)
             .to_vec();
@@ -239,6 +263,24 @@ impl StateStoreMetrics {
         let shared_buffer_to_sstable_size =
             register_histogram_with_registry!(opts, registry).unwrap();
 
+        let shared_buffer_upload_queue_depth = register_int_gauge_with_registry!(
+            "state_store_shared_buffer_upload_queue_depth",
+            "Number of SSTs synced from shared buffer waiting to be uploaded to remote storage",
+            registry
+        )
+        .unwrap();
+
+        let buckets = DEFAULT_BUCKETS
+            .map(|x| x * BATCH_WRITE_BUILD_TABLE_LATENCY_SCALE)
+            .to_vec();
+        let opts = histogram_opts!(
+            "state_store_shared_buffer_upload_epoch_duration",
+            "Time spent syncing the shared buffer of an epoch to remote storage, including building and uploading the SSTs.",
+            buckets
+        );
+        let shared_buffer_upload_epoch_duration =
+            register_histogram_with_registry!(opts, registry).unwrap();
+
         // ----- iter -----
         let buckets = DEFAULT_BUCKETS.map(|x| x * ITER_MERGE_SST_COUNTS).to_vec();
         let opts = histogram_opts!(
@@ -264,6 +306,20 @@ impl StateStoreMetrics {
         )
         .unwrap();
 
+        let sst_store_block_cache_hit_counts = register_int_counter_with_registry!(
+            "state_store_sst_store_block_cache_hit_counts",
+            "Total number of sst block requests served by the block cache",
+            registry
+        )
+        .unwrap();
+
+        let sst_store_block_cache_miss_counts = register_int_counter_with_registry!(
+            "state_store_sst_store_block_cache_miss_counts",
+            "Total number of sst block requests missing the block cache",
+            registry
+        )
+        .unwrap();
+
         let buckets = DEFAULT_BUCKETS.map(|x| x * ITER_NEXT_SIZE_SCALE).to_vec();
         let opts = histogram_opts!(
             "state_store_sst_store_get_remote_duration",
@@ -309,16 +365,22 @@ impl StateStoreMetrics {
             write_batch_duration,
             write_batch_size,
             write_build_l0_sst_duration,
+            write_owner_bytes,
+            write_epoch_owner_bytes,
 
             iter_merge_sstable_counts,
             iter_merge_seek_duration,
 
             sst_store_block_request_counts,
+            sst_store_block_cache_hit_counts,
+            sst_store_block_cache_miss_counts,
             sst_store_get_remote_duration,
             sst_store_put_remote_duration,
 
             shared_buffer_to_l0_duration,
             shared_buffer_to_sstable_size,
+            shared_buffer_upload_queue_depth,
+            shared_buffer_upload_epoch_duration,
             compaction_upload_sst_counts,
         }
     }
@@ -87,6 +87,20 @@ impl StateStore for PanicStateStore {
         }
     }
 
+    fn delete_ranges(
+        &self,
+        _ranges: Vec<(Bytes, Bytes)>,
+        _epoch: u64,
+    ) -> Self::DeleteRangesFuture<'_> {
+        async move {
+            panic!("should not delete ranges from the state store!");
+        }
+    }
+
+    fn set_keyspace_ttl(&self, _prefix: &[u8], _ttl: u64) {
+        panic!("should not set ttl in the state store!");
+    }
+
     fn iter<R, B>(&self, _key_range: R, _epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
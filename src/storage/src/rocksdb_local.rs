@@ -111,6 +111,18 @@ impl StateStore for RocksDBStateStore {
         async move { unimplemented!() }
     }
 
+    fn delete_ranges(
+        &self,
+        _ranges: Vec<(Bytes, Bytes)>,
+        _epoch: u64,
+    ) -> Self::DeleteRangesFuture<'_> {
+        async move { unimplemented!() }
+    }
+
+    fn set_keyspace_ttl(&self, _prefix: &[u8], _ttl: u64) {
+        unimplemented!()
+    }
+
     fn iter<R, B>(&self, key_range: R, _epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
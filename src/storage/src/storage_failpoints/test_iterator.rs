@@ -26,8 +26,9 @@ use crate::hummock::iterator::{
     ReverseMergeIterator, ReverseUserIterator, UserIterator,
 };
 use crate::hummock::test_utils::default_builder_opt_for_test;
-use crate::hummock::{ReverseSSTableIterator, SSTableIterator};
+use crate::hummock::{ReverseSSTableIterator, SSTableIterator, SstableStore};
 use crate::monitor::StateStoreMetrics;
+use crate::object::{InMemObjectStore, ObjectStoreImpl};
 
 #[tokio::test]
 #[cfg(feature = "failpoints")]
@@ -331,3 +332,123 @@ async fn test_failpoint_reverse_user_read_err() {
     assert!(!ui.is_valid());
     fail::remove(mem_read_err);
 }
+#[tokio::test]
+#[cfg(feature = "failpoints")]
+async fn test_failpoint_block_cache_read_err() {
+    let mem_read_err = "mem_read_err";
+    let stats = Arc::new(StateStoreMetrics::unused());
+    let sstable_store = Arc::new(SstableStore::new(
+        Arc::new(ObjectStoreImpl::Mem(InMemObjectStore::new())),
+        "test".to_string(),
+        stats.clone(),
+        64 << 20,
+        64 << 20,
+    ));
+    let table = Arc::new(
+        gen_iterator_test_sstable_base_without_buf(
+            0,
+            default_builder_opt_for_test(),
+            |x| x,
+            sstable_store.clone(),
+            200,
+        )
+        .await,
+    );
+    let block_count = table.meta.block_metas.len() as u64;
+
+    // A failed read is not cached.
+    fail::cfg(mem_read_err, "return").unwrap();
+    let mut iter = SSTableIterator::new(table.clone(), sstable_store.clone());
+    assert!(iter.rewind().await.is_err());
+    fail::remove(mem_read_err);
+    assert_eq!(stats.sst_store_block_cache_hit_counts.get(), 0);
+    assert_eq!(stats.sst_store_block_cache_miss_counts.get(), 1);
+
+    // The first scan reads all blocks from the object store and fills the cache.
+    let mut i = 0;
+    iter.rewind().await.unwrap();
+    while iter.is_valid() {
+        i += 1;
+        iter.next().await.unwrap();
+    }
+    assert_eq!(i, 200);
+    assert_eq!(stats.sst_store_block_cache_hit_counts.get(), 0);
+    assert_eq!(
+        stats.sst_store_block_cache_miss_counts.get(),
+        block_count + 1
+    );
+
+    // The second scan is served by the cache, even if the object store is unavailable.
+    fail::cfg(mem_read_err, "return").unwrap();
+    let mut iter = SSTableIterator::new(table, sstable_store);
+    let mut i = 0;
+    iter.rewind().await.unwrap();
+    while iter.is_valid() {
+        let key = iter.key();
+        assert_eq!(key, iterator_test_key_of(i).as_slice());
+        i += 1;
+        iter.next().await.unwrap();
+    }
+    assert_eq!(i, 200);
+    assert_eq!(stats.sst_store_block_cache_hit_counts.get(), block_count);
+    fail::remove(mem_read_err);
+}
+#[tokio::test]
+#[cfg(feature = "failpoints")]
+async fn test_failpoint_concat_prefetch_read_err() {
+    let mem_read_err = "mem_read_err";
+    let sstable_store = Arc::new(
+        SstableStore::new(
+            Arc::new(ObjectStoreImpl::Mem(InMemObjectStore::new())),
+            "test".to_string(),
+            Arc::new(StateStoreMetrics::unused()),
+            64 << 20,
+            64 << 20,
+        )
+        .with_prefetch_depth(2),
+    );
+    let table0 = gen_iterator_test_sstable_base_without_buf(
+        0,
+        default_builder_opt_for_test(),
+        |x| x,
+        sstable_store.clone(),
+        200,
+    )
+    .await;
+    let table1 = gen_iterator_test_sstable_base_without_buf(
+        1,
+        default_builder_opt_for_test(),
+        |x| 200 + x,
+        sstable_store.clone(),
+        200,
+    )
+    .await;
+    assert!(table0.block_count() > 1);
+    let tables = vec![Arc::new(table0), Arc::new(table1)];
+
+    // The prefetching issued by rewind fails as well as the reads waiting for it.
+    let mut iter = ConcatIterator::new(tables.clone(), sstable_store.clone());
+    iter.rewind().await.unwrap();
+    fail::cfg(mem_read_err, "return").unwrap();
+    let mut i = 0;
+    while iter.is_valid() {
+        assert_eq!(iter.key(), iterator_test_key_of(i).as_slice());
+        i += 1;
+        if iter.next().await.is_err() {
+            break;
+        }
+    }
+    assert!(i < 400);
+    fail::remove(mem_read_err);
+
+    // The failed blocks are not cached, and are read again.
+    let mut iter = ConcatIterator::new(tables, sstable_store);
+    iter.rewind().await.unwrap();
+    let mut i = 0;
+    while iter.is_valid() {
+        assert_eq!(iter.key(), iterator_test_key_of(i).as_slice());
+        i += 1;
+        iter.next().await.unwrap();
+    }
+    assert_eq!(i, 400);
+}
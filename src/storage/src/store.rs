@@ -35,6 +35,7 @@ macro_rules! define_state_store_associated_type {
         type ReverseScanFuture<'a, R, B> = impl ScanFutureTrait<'a, R, B> where R: 'static + Send, B: 'static + Send;
         type IngestBatchFuture<'a> = impl IngestBatchFutureTrait<'a>;
         type ReplicateBatchFuture<'a> = impl EmptyFutureTrait<'a>;
+        type DeleteRangesFuture<'a> = impl EmptyFutureTrait<'a>;
         type WaitEpochFuture<'a> = impl EmptyFutureTrait<'a>;
         type SyncFuture<'a> = impl EmptyFutureTrait<'a>;
         type IterFuture<'a, R, B> = impl Future<Output = $crate::error::StorageResult<Self::Iter<'a>>> + Send where R: 'static + Send, B: 'static + Send;
@@ -63,6 +64,8 @@ pub trait StateStore: Send + Sync + 'static + Clone {
 
     type ReplicateBatchFuture<'a>: EmptyFutureTrait<'a>;
 
+    type DeleteRangesFuture<'a>: EmptyFutureTrait<'a>;
+
     type WaitEpochFuture<'a>: EmptyFutureTrait<'a>;
 
     type SyncFuture<'a>: EmptyFutureTrait<'a>;
@@ -128,6 +131,20 @@ pub trait StateStore: Send + Sync + 'static + Clone {
         epoch: u64,
     ) -> Self::ReplicateBatchFuture<'_>;
 
+    /// Deletes all the keys in the given ranges `[start, end)` that are written in or before
+    /// `epoch`, including the ones ingested in `epoch` by other batches. Keys written after `epoch`
+    /// are not affected.
+    fn delete_ranges(
+        &self,
+        ranges: Vec<(Bytes, Bytes)>,
+        epoch: u64,
+    ) -> Self::DeleteRangesFuture<'_>;
+
+    /// Expires the versions of the keys with `prefix` which are written more than `ttl` epochs
+    /// before the read epoch. The expired versions are invisible to reads, and may be reclaimed by
+    /// the state store without explicit deletes.
+    fn set_keyspace_ttl(&self, prefix: &[u8], ttl: u64);
+
     /// Opens and returns an iterator for given `key_range`.
     /// The returned iterator will iterate data based on a snapshot corresponding to the given
     /// `epoch`.
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::fmt::Debug;
+use std::path::Path;
 use std::sync::Arc;
 
 use enum_as_inner::EnumAsInner;
@@ -21,7 +22,7 @@ use risingwave_rpc_client::HummockMetaClient;
 
 use crate::error::StorageResult;
 use crate::hummock::local_version_manager::LocalVersionManager;
-use crate::hummock::{HummockStorage, SstableStore};
+use crate::hummock::{BlockAccessSketch, HummockStorage, SstableStore};
 use crate::memory::MemoryStateStore;
 use crate::monitor::{MonitoredStateStore as Monitored, StateStoreMetrics};
 use crate::object::{InMemObjectStore, ObjectStoreImpl, S3ObjectStore};
@@ -95,11 +96,13 @@ impl StateStoreImpl {
                 let object_store = Arc::new(match hummock {
                     s3 if s3.starts_with("hummock+s3://") => ObjectStoreImpl::S3(
                         S3ObjectStore::new(s3.strip_prefix("hummock+s3://").unwrap().to_string())
-                            .await,
+                            .await
+                            .with_multipart_upload_part_size(config.multipart_upload_part_size),
                     ),
                     minio if minio.starts_with("hummock+minio://") => ObjectStoreImpl::S3(
                         S3ObjectStore::new_with_minio(minio.strip_prefix("hummock+").unwrap())
-                            .await,
+                            .await
+                            .with_multipart_upload_part_size(config.multipart_upload_part_size),
                     ),
                     memory if memory.starts_with("hummock+memory") => {
                         tracing::warn!("You're using Hummock in-memory object store. This should never be used in benchmarks and production environment.");
@@ -113,13 +116,28 @@ impl StateStoreImpl {
                     }
                 });
 
-                let sstable_store = Arc::new(SstableStore::new(
+                let mut sstable_store = SstableStore::new(
                     object_store,
                     config.data_directory.to_string(),
                     state_store_stats.clone(),
                     config.block_cache_capacity,
                     config.meta_cache_capacity,
-                ));
+                )
+                .with_prefetch_depth(config.block_prefetch_depth);
+                if !config.block_cache_warmup_path.is_empty() {
+                    // Track more blocks than warmed up, so that the hottest ones survive aging.
+                    let capacity = config.block_cache_warmup_blocks * 2;
+                    let access_sketch = BlockAccessSketch::load(
+                        Path::new(&config.block_cache_warmup_path),
+                        capacity,
+                    )
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("Failed to load block access sketch: {}", e);
+                        BlockAccessSketch::new(capacity)
+                    });
+                    sstable_store = sstable_store.with_access_sketch(Arc::new(access_sketch));
+                }
+                let sstable_store = Arc::new(sstable_store);
                 let inner = HummockStorage::new(
                     config.clone(),
                     sstable_store.clone(),
@@ -30,7 +30,7 @@ use crate::cell_based_row_serializer::CellBasedRowSerializer;
 use crate::error::{StorageError, StorageResult};
 use crate::monitor::StateStoreMetrics;
 use crate::storage_value::StorageValue;
-use crate::{Keyspace, StateStore};
+use crate::{Keyspace, StateStore, StateStoreIter};
 
 /// `CellBasedTable` is the interface accessing relational data in KV(`StateStore`) with encoding
 /// format: [keyspace | pk | `column_id` (4B)] -> value.
@@ -183,6 +183,58 @@ impl<S: StateStore> CellBasedTable<S> {
         }
     }
 
+    /// Scans the rows whose pk is within `[start, end]` in the snapshot of `epoch`, where `start`
+    /// and `end` are prefixes of the pk, and `None` means unbounded. At most `limit` rows are
+    /// returned if specified.
+    pub async fn scan_range(
+        &self,
+        start: Option<&Row>,
+        end: Option<&Row>,
+        limit: Option<usize>,
+        epoch: u64,
+    ) -> StorageResult<Vec<Row>> {
+        let pk_serializer = self.pk_serializer.as_ref().expect("pk_serializer is None");
+        let serialize_prefix =
+            |prefix: &Row| serialize_pk(prefix, &pk_serializer.prefix(prefix.size())).map_err(err);
+        let start_key = match start {
+            Some(start) => self.keyspace.prefixed_key(serialize_prefix(start)?),
+            None => self.keyspace.key().to_vec(),
+        };
+        let end_key = match end {
+            Some(end) => next_key(&self.keyspace.prefixed_key(serialize_prefix(end)?)),
+            None => next_key(self.keyspace.key()),
+        };
+
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut rows = vec![];
+        if limit == 0 || start_key >= end_key {
+            return Ok(rows);
+        }
+
+        let mut iter = self
+            .keyspace
+            .state_store()
+            .iter(start_key..end_key, epoch)
+            .await?;
+        let mut cell_based_row_deserializer =
+            CellBasedRowDeserializer::new(self.column_descs.clone());
+        while let Some((key, value)) = iter.next().await? {
+            if let Some((_pk, row)) = cell_based_row_deserializer
+                .deserialize(&key, &value)
+                .map_err(err)?
+            {
+                rows.push(row);
+                if rows.len() == limit {
+                    return Ok(rows);
+                }
+            }
+        }
+        if let Some((_pk, row)) = cell_based_row_deserializer.take() {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
     pub async fn batch_write_rows(
         &mut self,
         rows: Vec<(Row, Option<Row>)>,
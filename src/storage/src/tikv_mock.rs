@@ -64,6 +64,18 @@ impl StateStore for TikvStateStore {
         async move { unimplemented!() }
     }
 
+    fn delete_ranges(
+        &self,
+        _ranges: Vec<(Bytes, Bytes)>,
+        _epoch: u64,
+    ) -> Self::DeleteRangesFuture<'_> {
+        async move { unimplemented!() }
+    }
+
+    fn set_keyspace_ttl(&self, _prefix: &[u8], _ttl: u64) {
+        unimplemented!()
+    }
+
     fn reverse_iter<R, B>(&self, _key_range: R, _epoch: u64) -> Self::ReverseIterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -24,6 +24,9 @@ pub struct WriteBatch<S: StateStore> {
     store: S,
 
     batch: Vec<(Bytes, StorageValue)>,
+
+    /// Ranges `[start, end)` of keys to delete.
+    delete_ranges: Vec<(Bytes, Bytes)>,
 }
 
 impl<S> WriteBatch<S>
@@ -35,6 +38,7 @@ where
         Self {
             store,
             batch: Vec::new(),
+            delete_ranges: Vec::new(),
         }
     }
 
@@ -43,6 +47,7 @@ where
         Self {
             store,
             batch: Vec::with_capacity(capacity),
+            delete_ranges: Vec::new(),
         }
     }
 
@@ -74,15 +79,24 @@ where
         }
     }
 
-    /// Returns `true` if the batch contains no key-value pairs.
+    /// Returns `true` if the batch contains no key-value pairs or delete ranges.
     pub fn is_empty(&self) -> bool {
-        self.batch.is_empty()
+        self.batch.is_empty() && self.delete_ranges.is_empty()
+    }
+
+    /// Deletes all the keys in `[start, end)` when the batch is ingested, including the ones
+    /// written in the same epoch and in this batch.
+    pub fn delete_range(&mut self, start: impl Into<Bytes>, end: impl Into<Bytes>) {
+        self.delete_ranges.push((start.into(), end.into()));
     }
 
     /// Ingests this batch into the associated state store.
     pub async fn ingest(mut self, epoch: u64) -> StorageResult<()> {
         self.preprocess()?;
         self.store.ingest_batch(self.batch, epoch).await?;
+        if !self.delete_ranges.is_empty() {
+            self.store.delete_ranges(self.delete_ranges, epoch).await?;
+        }
         Ok(())
     }
 
@@ -150,6 +164,14 @@ impl<'a, S: StateStore> KeySpaceWriteBatch<'a, S> {
     pub fn delete_with_value_meta(&mut self, key: impl AsRef<[u8]>, value_meta: ValueMeta) {
         self.do_push(Some(key.as_ref()), StorageValue::new_delete(value_meta));
     }
+
+    /// Deletes the values in `[start, end)`, with both keys prepended by the prefix of `keyspace`.
+    pub fn delete_range(&mut self, start: impl AsRef<[u8]>, end: impl AsRef<[u8]>) {
+        self.global.delete_range(
+            self.keyspace.prefixed_key(start),
+            self.keyspace.prefixed_key(end),
+        );
+    }
 }
 
 #[cfg(test)]
@@ -179,4 +201,34 @@ mod tests {
             .await
             .expect_err("Should panic here because of duplicate key.");
     }
+
+    #[tokio::test]
+    async fn test_delete_range() {
+        let state_store = MemoryStateStore::new();
+        let key_space = Keyspace::executor_root(state_store.clone(), 0x118);
+
+        let mut write_batch = WriteBatch::new(state_store.clone());
+        let mut key_space_batch = write_batch.prefixify(&key_space);
+        for key in ["aa", "bb", "cc"] {
+            key_space_batch.put(Bytes::from(key), StorageValue::new_default_put("444"));
+        }
+        write_batch.ingest(1).await.unwrap();
+
+        let mut write_batch = WriteBatch::new(state_store);
+        let mut key_space_batch = write_batch.prefixify(&key_space);
+        key_space_batch.put(Bytes::from("dd"), StorageValue::new_default_put("444"));
+        key_space_batch.delete_range(Bytes::from("aa"), Bytes::from("cc"));
+        write_batch.ingest(2).await.unwrap();
+
+        for (epoch, expected) in [(1, vec!["aa", "bb", "cc"]), (2, vec!["cc", "dd"])] {
+            let keys = key_space
+                .scan_strip_prefix(None, epoch)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>();
+            assert_eq!(keys, expected);
+        }
+    }
 }
@@ -98,6 +98,11 @@ impl StreamChunkBuilder {
         Ok(())
     }
 
+    /// Number of rows appended to the builder.
+    pub fn size(&self) -> usize {
+        self.ops.len()
+    }
+
     pub fn finish(self) -> Result<StreamChunk> {
         let new_arrays = self
             .column_builders
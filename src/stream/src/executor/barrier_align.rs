@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use async_stream::try_stream;
 use futures::StreamExt;
 use risingwave_common::error::Result;
+use risingwave_common::types::ScalarImpl;
 use tokio::select;
 
-use super::{Barrier, Executor, Message, StreamChunk};
+use super::{Barrier, Executor, Message, StreamChunk, Watermark};
 use crate::executor::BoxedExecutorStream;
 
 #[derive(Debug, PartialEq)]
@@ -32,6 +35,8 @@ pub enum AlignedMessage {
     Left(Result<StreamChunk>),
     Right(Result<StreamChunk>),
     Barrier(Barrier),
+    /// The watermark that both inputs have reached on a column.
+    Watermark(Watermark),
 }
 
 impl<'a> TryFrom<&'a AlignedMessage> for &'a Barrier {
@@ -52,6 +57,12 @@ pub struct BarrierAligner {
     input_r: BoxedExecutorStream,
     /// The barrier state
     state: BarrierWaitState,
+    /// The latest watermarks of the left input, keyed by column index
+    watermarks_l: HashMap<usize, Watermark>,
+    /// The latest watermarks of the right input, keyed by column index
+    watermarks_r: HashMap<usize, Watermark>,
+    /// The latest aligned watermarks yielded, keyed by column index
+    aligned_watermarks: HashMap<usize, ScalarImpl>,
 }
 
 impl BarrierAligner {
@@ -73,6 +84,33 @@ impl BarrierAligner {
             input_l: Box::pin(input_l),
             input_r: Box::pin(input_r),
             state: BarrierWaitState::Either,
+            watermarks_l: HashMap::new(),
+            watermarks_r: HashMap::new(),
+            aligned_watermarks: HashMap::new(),
+        }
+    }
+
+    /// Records the watermark of one input, and returns the watermark on the same column that both
+    /// inputs have reached, if it advances. The column indices of both inputs are assumed to refer
+    /// to the same output column, e.g. for set operations.
+    fn align_watermark(&mut self, watermark: Watermark, is_left: bool) -> Option<Watermark> {
+        let col_idx = watermark.col_idx;
+        let (this, other) = if is_left {
+            (&mut self.watermarks_l, &self.watermarks_r)
+        } else {
+            (&mut self.watermarks_r, &self.watermarks_l)
+        };
+        this.insert(col_idx, watermark);
+        let aligned = std::cmp::min_by(this.get(&col_idx)?, other.get(&col_idx)?, |a, b| {
+            a.val.cmp(&b.val)
+        })
+        .clone();
+        match self.aligned_watermarks.get(&col_idx) {
+            Some(val) if *val >= aligned.val => None,
+            _ => {
+                self.aligned_watermarks.insert(col_idx, aligned.val.clone());
+                Some(aligned)
+            }
         }
     }
 
@@ -83,6 +121,11 @@ impl BarrierAligner {
                 match message.unwrap() {
                     Ok(message) => match message {
                         Message::Chunk(chunk) => break AlignedMessage::Left(Ok(chunk)),
+                            Message::Watermark(watermark) => {
+                                if let Some(watermark) = self.align_watermark(watermark, true) {
+                                    break AlignedMessage::Watermark(watermark);
+                                }
+                            }
                             Message::Barrier(barrier) => {
                                 match self.state {
                                     BarrierWaitState::Left => {
@@ -103,6 +146,11 @@ impl BarrierAligner {
                     match message.unwrap() {
                         Ok(message) => match message {
                             Message::Chunk(chunk) => break AlignedMessage::Right(Ok(chunk)),
+                            Message::Watermark(watermark) => {
+                                if let Some(watermark) = self.align_watermark(watermark, false) {
+                                    break AlignedMessage::Watermark(watermark);
+                                }
+                            }
                             Message::Barrier(barrier) => match self.state {
                                 BarrierWaitState::Right => {
                                     self.state = BarrierWaitState::Either;
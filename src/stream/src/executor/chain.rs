@@ -56,6 +56,7 @@ impl ExecutorBuilder for ChainExecutorBuilder {
             notifier,
             schema,
             column_idxs,
+            node.snapshot_only,
             params.op_info,
         ));
 
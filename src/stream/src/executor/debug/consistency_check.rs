@@ -0,0 +1,339 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::sync::Once;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::error::Result;
+use risingwave_common::types::ToOwnedDatum;
+
+use crate::executor::{Executor, Message};
+
+pub const CONSISTENCY_CHECK_ENABLED_ENV_VAR_KEY: &str = "RW_CONSISTENCY_CHECK";
+
+/// [`ConsistencyCheckExecutor`] validates the invariants of the stream chunks yielded by `input`:
+/// - the columns match the schema of `input` in number and physical type;
+/// - the visibility bitmap and all columns have the same length as the ops;
+/// - every `UpdateDelete` is immediately followed by an `UpdateInsert`;
+/// - if `input` declares a primary key, a key is never inserted twice and never deleted twice.
+///
+/// The executor is rebuilt on recovery, so the keys inserted before are unknown to it. Deleting
+/// such a key is accepted, and the key is remembered until the next barrier to catch it being
+/// deleted again.
+///
+/// All keys inserted since the executor is built are kept in memory, so this check is only
+/// enabled on demand.
+#[derive(Debug)]
+pub struct ConsistencyCheckExecutor {
+    /// The input of the current executor.
+    input: Box<dyn Executor>,
+
+    /// Primary keys of the rows currently present in the output of `input`, among those inserted
+    /// since the executor is built.
+    keys: HashSet<Row>,
+
+    /// Primary keys deleted in the current epoch without being inserted since the executor is
+    /// built, which must have been inserted before recovery.
+    deleted_unseen_keys: HashSet<Row>,
+}
+
+impl ConsistencyCheckExecutor {
+    pub fn new(input: Box<dyn Executor>) -> Self {
+        static ONCE: Once = Once::new();
+        ONCE.call_once(|| info!("ConsistencyCheckExecutor enabled."));
+
+        Self {
+            input,
+            keys: HashSet::new(),
+            deleted_unseen_keys: HashSet::new(),
+        }
+    }
+
+    fn check_schema(&self, chunk: &StreamChunk) {
+        let fields = &self.input.schema().fields;
+        if chunk.columns().len() != fields.len() {
+            panic!(
+                "consistency check failed on `{}` ({}): schema has {} fields, but chunk has {} columns",
+                self.input.logical_operator_info(),
+                self.input.identity(),
+                fields.len(),
+                chunk.columns().len(),
+            );
+        }
+        for (idx, (column, field)) in chunk.columns().iter().zip_eq(fields).enumerate() {
+            // Only the physical type is checked, as the array doesn't carry the logical one.
+            let expected = field.data_type.create_array_builder(0).unwrap().get_ident();
+            let actual = column.array_ref().get_ident();
+            if actual != expected {
+                panic!(
+                    "consistency check failed on `{}` ({}): column {} should be {} of {:?}, but chunk gives {}",
+                    self.input.logical_operator_info(),
+                    self.input.identity(),
+                    idx,
+                    expected,
+                    field.data_type,
+                    actual,
+                );
+            }
+        }
+    }
+
+    fn check_visibility(&self, chunk: &StreamChunk) {
+        let len = chunk.ops().len();
+        if let Some(bitmap) = chunk.visibility() {
+            if bitmap.len() != len {
+                panic!(
+                    "consistency check failed on `{}` ({}): visibility bitmap has {} bits, but chunk has {} ops",
+                    self.input.logical_operator_info(),
+                    self.input.identity(),
+                    bitmap.len(),
+                    len,
+                );
+            }
+        }
+        for (idx, column) in chunk.columns().iter().enumerate() {
+            if column.array_ref().len() != len {
+                panic!(
+                    "consistency check failed on `{}` ({}): column {} has {} rows, but chunk has {} ops",
+                    self.input.logical_operator_info(),
+                    self.input.identity(),
+                    idx,
+                    column.array_ref().len(),
+                    len,
+                );
+            }
+        }
+    }
+
+    fn check_updates(&self, chunk: &StreamChunk) {
+        let mut rows = chunk.rows().peekable();
+        while let Some(row) = rows.next() {
+            let next_op = rows.peek().map(|r| r.op());
+            if row.op() == Op::UpdateDelete && next_op != Some(Op::UpdateInsert) {
+                panic!(
+                    "consistency check failed on `{}` ({}): expect U+ after U-, but got {:?} after U- {:?}",
+                    self.input.logical_operator_info(),
+                    self.input.identity(),
+                    next_op,
+                    row.values().collect_vec(),
+                );
+            }
+        }
+    }
+
+    fn check_keys(&mut self, chunk: &StreamChunk) {
+        let pk_indices = self.input.pk_indices();
+        if pk_indices.is_empty() {
+            return;
+        }
+
+        for row in chunk.rows() {
+            let key = Row(pk_indices
+                .iter()
+                .map(|&idx| row.value_at(idx).to_owned_datum())
+                .collect());
+            match row.op() {
+                Op::Insert | Op::UpdateInsert => {
+                    if self.keys.contains(&key) {
+                        panic!(
+                            "consistency check failed on `{}` ({}): {:?} of existing key {:?}, row: {:?}",
+                            self.input.logical_operator_info(),
+                            self.input.identity(),
+                            row.op(),
+                            key,
+                            row.values().collect_vec(),
+                        );
+                    }
+                    self.deleted_unseen_keys.remove(&key);
+                    self.keys.insert(key);
+                }
+                Op::Delete | Op::UpdateDelete => {
+                    if !self.keys.remove(&key) && !self.deleted_unseen_keys.insert(key.clone()) {
+                        panic!(
+                            "consistency check failed on `{}` ({}): {:?} of deleted key {:?}, row: {:?}",
+                            self.input.logical_operator_info(),
+                            self.input.identity(),
+                            row.op(),
+                            key,
+                            row.values().collect_vec(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl super::DebugExecutor for ConsistencyCheckExecutor {
+    async fn next(&mut self) -> Result<Message> {
+        let message = self.input.next().await?;
+
+        match &message {
+            Message::Chunk(chunk) => {
+                self.check_schema(chunk);
+                self.check_visibility(chunk);
+                self.check_updates(chunk);
+                self.check_keys(chunk);
+            }
+            Message::Barrier(_) => self.deleted_unseen_keys.clear(),
+            Message::Watermark(_) => {}
+        }
+
+        Ok(message)
+    }
+
+    fn input(&self) -> &dyn Executor {
+        self.input.as_ref()
+    }
+
+    fn input_mut(&mut self) -> &mut dyn Executor {
+        self.input.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::once;
+
+    use risingwave_common::array::{F64Array, I64Array, StreamChunk};
+    use risingwave_common::buffer::Bitmap;
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::executor::test_utils::MockSource;
+
+    fn schema() -> Schema {
+        Schema::new(vec![Field::unnamed(DataType::Int64)])
+    }
+
+    fn checked(chunks: Vec<StreamChunk>) -> ConsistencyCheckExecutor {
+        let mut source = MockSource::new(schema(), vec![0]);
+        source.push_chunks(chunks.into_iter());
+        ConsistencyCheckExecutor::new(Box::new(source))
+    }
+
+    #[tokio::test]
+    async fn test_consistent() {
+        let chunk1 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::UpdateDelete, Op::UpdateInsert],
+            vec![column_nonnull! { I64Array, [1, 2, 1, 1] }],
+            None,
+        );
+        let chunk2 = StreamChunk::new(
+            vec![Op::Delete, Op::Insert, Op::Delete],
+            vec![column_nonnull! { I64Array, [2, 2, 2] }],
+            Some(Bitmap::try_from(vec![true, false, false]).unwrap()),
+        );
+
+        let mut checked = checked(vec![chunk1, chunk2]);
+        checked.next().await.unwrap();
+        checked.next().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_inserted_before_recovery() {
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::Delete, Op::Insert],
+            vec![column_nonnull! { I64Array, [1, 2, 2] }],
+            None,
+        );
+
+        let mut checked = checked(vec![chunk]);
+        checked.next().await.unwrap();
+    }
+
+    #[should_panic]
+    #[tokio::test]
+    async fn test_delete_twice() {
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::Delete, Op::Delete],
+            vec![column_nonnull! { I64Array, [1, 2, 2] }],
+            None,
+        );
+
+        let mut checked = checked(vec![chunk]);
+        checked.next().await.unwrap(); // should panic
+    }
+
+    #[should_panic]
+    #[tokio::test]
+    async fn test_insert_twice() {
+        let chunk1 = StreamChunk::new(
+            vec![Op::Insert],
+            vec![column_nonnull! { I64Array, [1] }],
+            None,
+        );
+        let chunk2 = StreamChunk::new(
+            vec![Op::Insert],
+            vec![column_nonnull! { I64Array, [1] }],
+            None,
+        );
+
+        let mut checked = checked(vec![chunk1, chunk2]);
+        checked.next().await.unwrap();
+        checked.next().await.unwrap(); // should panic
+    }
+
+    #[should_panic]
+    #[tokio::test]
+    async fn test_update_delete_not_followed() {
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::UpdateDelete, Op::Insert],
+            vec![column_nonnull! { I64Array, [1, 1, 2] }],
+            None,
+        );
+
+        let mut checked = checked(vec![chunk]);
+        checked.next().await.unwrap(); // should panic
+    }
+
+    #[should_panic]
+    #[tokio::test]
+    async fn test_data_type_mismatch() {
+        let chunk = StreamChunk::new(
+            vec![Op::Insert],
+            vec![column_nonnull! { F64Array, [1.0] }],
+            None,
+        );
+
+        let mut source = MockSource::new(schema(), vec![]);
+        source.push_chunks(once(chunk));
+
+        let mut checked = ConsistencyCheckExecutor::new(Box::new(source));
+        checked.next().await.unwrap(); // should panic
+    }
+
+    #[should_panic]
+    #[tokio::test]
+    async fn test_visibility_mismatch() {
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::Insert],
+            vec![column_nonnull! { I64Array, [1, 2] }],
+            Some(Bitmap::try_from(vec![true, true, true]).unwrap()),
+        );
+
+        let mut source = MockSource::new(schema(), vec![]);
+        source.push_chunks(once(chunk));
+
+        let mut checked = ConsistencyCheckExecutor::new(Box::new(source));
+        checked.next().await.unwrap(); // should panic
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod cache_clear;
+mod consistency_check;
 mod epoch_check;
 mod schema_check;
 mod trace;
@@ -24,6 +25,7 @@ use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
 
 pub use self::cache_clear::*;
+pub use self::consistency_check::*;
 pub use self::epoch_check::*;
 pub use self::schema_check::*;
 pub use self::trace::*;
@@ -51,8 +51,9 @@ impl super::DebugExecutor for UpdateCheckExecutor {
                     || (op1 == Some(Op::UpdateDelete) && op2 != Some(Op::UpdateInsert))
                 {
                     panic!(
-                        "update check failed on `{}`: expect U+ after  U-:\n first row: {:?}\nsecond row: {:?}",
+                        "update check failed on `{}` ({}): expect U+ after  U-:\n first row: {:?}\nsecond row: {:?}",
                         self.input.logical_operator_info(),
+                        self.input.identity(),
                         values1.map(Itertools::collect_vec),
                         values2.map(Itertools::collect_vec),
                     )
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::future::Future;
@@ -23,11 +24,13 @@ use futures::SinkExt;
 use itertools::Itertools;
 use risingwave_common::array::Op;
 use risingwave_common::hash::VIRTUAL_NODE_COUNT;
+use risingwave_common::types::DataType;
 use risingwave_common::util::addr::{is_local_address, HostAddr};
 use risingwave_common::util::hash_util::CRC32FastBuilder;
 use tracing::event;
 
-use super::{Barrier, Executor, Message, Mutation, Result, StreamChunk, StreamConsumer};
+use super::{Barrier, Executor, Message, Mutation, Result, StreamChunk, StreamConsumer, Watermark};
+use crate::common::StreamChunkBuilder;
 use crate::task::{ActorId, SharedContext};
 
 /// `Output` provides an interface for `Dispatcher` to send data into downstream actors.
@@ -168,11 +171,16 @@ impl DispatchExecutor {
                 self.inner.dispatch_data(chunk).await?;
             }
             Message::Barrier(barrier) => {
+                // The buffered rows are sent to the outputs before they are changed.
+                self.inner.flush().await?;
                 let mutation = barrier.mutation.clone();
                 self.pre_mutate_outputs(&mutation).await?;
                 self.inner.dispatch_barrier(barrier).await?;
                 self.post_mutate_outputs(&mutation).await?;
             }
+            Message::Watermark(watermark) => {
+                self.inner.dispatch_watermark(watermark).await?;
+            }
         };
         Ok(())
     }
@@ -263,6 +271,7 @@ pub enum DispatcherImpl {
     Broadcast(BroadcastDispatcher),
     Simple(SimpleDispatcher),
     RoundRobin(RoundRobinDataDispatcher),
+    NoShuffle(NoShuffleDispatcher),
 }
 
 macro_rules! impl_dispatcher {
@@ -280,6 +289,12 @@ macro_rules! impl_dispatcher {
                 }
             }
 
+            pub async fn dispatch_watermark(&mut self, watermark: Watermark) -> Result<()> {
+                match self {
+                    $( Self::$variant_name(inner) => inner.dispatch_watermark(watermark).await, )*
+                }
+            }
+
             pub fn set_outputs(&mut self, outputs: impl IntoIterator<Item = BoxedOutput>) {
                 match self {
                     $( Self::$variant_name(inner) => inner.set_outputs(outputs), )*
@@ -308,17 +323,29 @@ macro_rules! for_all_dispatcher_variants {
             { Hash },
             { Broadcast },
             { Simple },
-            { RoundRobin }
+            { RoundRobin },
+            { NoShuffle }
         }
     };
 }
 
 for_all_dispatcher_variants! { impl_dispatcher }
 
+impl DispatcherImpl {
+    /// Sends the rows buffered by the dispatcher to the outputs.
+    pub async fn flush(&mut self) -> Result<()> {
+        match self {
+            Self::Hash(inner) => inner.flush().await,
+            _ => Ok(()),
+        }
+    }
+}
+
 macro_rules! define_dispatcher_associated_types {
     () => {
         type DataFuture<'a> = impl DispatchFuture<'a>;
         type BarrierFuture<'a> = impl DispatchFuture<'a>;
+        type WatermarkFuture<'a> = impl DispatchFuture<'a>;
     };
 }
 
@@ -327,8 +354,12 @@ pub trait DispatchFuture<'a> = Future<Output = Result<()>> + Send;
 pub trait Dispatcher: Debug + 'static {
     type DataFuture<'a>: DispatchFuture<'a>;
     type BarrierFuture<'a>: DispatchFuture<'a>;
+    type WatermarkFuture<'a>: DispatchFuture<'a>;
     fn dispatch_data(&mut self, chunk: StreamChunk) -> Self::DataFuture<'_>;
     fn dispatch_barrier(&mut self, barrier: Barrier) -> Self::BarrierFuture<'_>;
+    /// Watermarks are always broadcast, as every downstream needs to know the progress of the
+    /// event time.
+    fn dispatch_watermark(&mut self, watermark: Watermark) -> Self::WatermarkFuture<'_>;
 
     fn set_outputs(&mut self, outputs: impl IntoIterator<Item = BoxedOutput>);
     fn add_outputs(&mut self, outputs: impl IntoIterator<Item = BoxedOutput>);
@@ -376,6 +407,15 @@ impl Dispatcher for RoundRobinDataDispatcher {
         }
     }
 
+    fn dispatch_watermark(&mut self, watermark: Watermark) -> Self::WatermarkFuture<'_> {
+        async move {
+            for output in &mut self.outputs {
+                output.send(Message::Watermark(watermark.clone())).await?;
+            }
+            Ok(())
+        }
+    }
+
     fn set_outputs(&mut self, outputs: impl IntoIterator<Item = BoxedOutput>) {
         self.outputs = outputs.into_iter().collect();
         self.cur = self.cur.min(self.outputs.len() - 1);
@@ -399,6 +439,13 @@ pub struct HashDataDispatcher {
     /// Mapping from virtual node to actor id, used for hash data dispatcher to dispatch tasks to
     /// different downstream actors.
     hash_mapping: Vec<ActorId>,
+    /// Number of rows buffered for each downstream actor before they are sent as a chunk. 0 means
+    /// the rows are sent as soon as a chunk is dispatched.
+    chunk_size: usize,
+    /// Data types of the dispatched chunks, used to build the buffered chunks.
+    data_types: Vec<DataType>,
+    /// Rows buffered for each downstream actor.
+    builders: HashMap<ActorId, StreamChunkBuilder>,
 }
 
 impl Debug for HashDataDispatcher {
@@ -422,7 +469,64 @@ impl HashDataDispatcher {
             outputs,
             keys,
             hash_mapping,
+            chunk_size: 0,
+            data_types: vec![],
+            builders: HashMap::new(),
+        }
+    }
+
+    /// Buffers the rows dispatched to each downstream actor until there are `chunk_size` rows or a
+    /// barrier comes, so that small chunks are not sent when there are many downstream actors.
+    pub fn with_batching(self, chunk_size: usize, data_types: Vec<DataType>) -> Self {
+        Self {
+            chunk_size,
+            data_types,
+            ..self
+        }
+    }
+
+    /// Sends the rows buffered for all the downstream actors.
+    pub async fn flush(&mut self) -> Result<()> {
+        for output in &mut self.outputs {
+            if let Some(builder) = self.builders.remove(&output.actor_id()) {
+                if builder.size() > 0 {
+                    output.send(Message::Chunk(builder.finish()?)).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Buffers the visible rows of `chunk` for `output`, and sends them once there are enough rows.
+    async fn buffer_and_send(
+        output: &mut BoxedOutput,
+        builders: &mut HashMap<ActorId, StreamChunkBuilder>,
+        chunk_size: usize,
+        data_types: &[DataType],
+        chunk: StreamChunk,
+    ) -> Result<()> {
+        let builder = match builders.entry(output.actor_id()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert(StreamChunkBuilder::new(chunk_size, data_types, 0, 0)?)
+            }
+        };
+        for (pos, op) in chunk.ops().iter().enumerate() {
+            let (row, visible) = chunk.row_at(pos)?;
+            if !visible {
+                continue;
+            }
+            builder.append_row_update(*op, &row)?;
+            // An `UpdateDelete` must be sent in the same chunk as the `UpdateInsert` after it.
+            if builder.size() >= chunk_size && *op != Op::UpdateDelete {
+                let builder = std::mem::replace(
+                    builder,
+                    StreamChunkBuilder::new(chunk_size, data_types, 0, 0)?,
+                );
+                output.send(Message::Chunk(builder.finish()?)).await?;
+            }
         }
+        Ok(())
     }
 }
 
@@ -439,6 +543,7 @@ impl Dispatcher for HashDataDispatcher {
 
     fn dispatch_barrier(&mut self, barrier: Barrier) -> Self::BarrierFuture<'_> {
         async move {
+            self.flush().await?;
             // always broadcast barrier
             for output in &mut self.outputs {
                 output.send(Message::Barrier(barrier.clone())).await?;
@@ -447,6 +552,17 @@ impl Dispatcher for HashDataDispatcher {
         }
     }
 
+    fn dispatch_watermark(&mut self, watermark: Watermark) -> Self::WatermarkFuture<'_> {
+        async move {
+            // The buffered rows precede the watermark.
+            self.flush().await?;
+            for output in &mut self.outputs {
+                output.send(Message::Watermark(watermark.clone())).await?;
+            }
+            Ok(())
+        }
+    }
+
     fn dispatch_data(&mut self, chunk: StreamChunk) -> Self::DataFuture<'_> {
         async move {
             // A chunk can be shuffled into multiple output chunks that to be sent to downstreams.
@@ -542,7 +658,16 @@ impl Dispatcher for HashDataDispatcher {
                 // columns is not changed in this function
                 let new_stream_chunk =
                     StreamChunk::new(ops.clone(), columns.clone(), Some(vis_map));
-                if new_stream_chunk.cardinality() > 0 {
+                if self.chunk_size > 0 {
+                    Self::buffer_and_send(
+                        output,
+                        &mut self.builders,
+                        self.chunk_size,
+                        &self.data_types,
+                        new_stream_chunk,
+                    )
+                    .await?;
+                } else if new_stream_chunk.cardinality() > 0 {
                     event!(
                         tracing::Level::TRACE,
                         msg = "chunk",
@@ -614,6 +739,15 @@ impl Dispatcher for BroadcastDispatcher {
         }
     }
 
+    fn dispatch_watermark(&mut self, watermark: Watermark) -> Self::WatermarkFuture<'_> {
+        async move {
+            for output in self.outputs.values_mut() {
+                output.send(Message::Watermark(watermark.clone())).await?;
+            }
+            Ok(())
+        }
+    }
+
     fn set_outputs(&mut self, outputs: impl IntoIterator<Item = BoxedOutput>) {
         self.outputs = Self::into_pairs(outputs).collect()
     }
@@ -666,6 +800,13 @@ impl Dispatcher for SimpleDispatcher {
         }
     }
 
+    fn dispatch_watermark(&mut self, watermark: Watermark) -> Self::WatermarkFuture<'_> {
+        async move {
+            self.output.send(Message::Watermark(watermark)).await?;
+            Ok(())
+        }
+    }
+
     fn dispatch_data(&mut self, chunk: StreamChunk) -> Self::DataFuture<'_> {
         async move {
             self.output.send(Message::Chunk(chunk)).await?;
@@ -680,6 +821,78 @@ impl Dispatcher for SimpleDispatcher {
     }
 }
 
+/// `NoShuffleDispatcher` pipes messages to the paired downstream actor as they are. It is used
+/// between fragments with the same distribution, where the i-th upstream actor always feeds the
+/// i-th downstream actor and thus owns exactly the same virtual nodes.
+pub struct NoShuffleDispatcher {
+    output: BoxedOutput,
+}
+
+impl Debug for NoShuffleDispatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NoShuffleDispatcher")
+            .field("output", &self.output)
+            .finish()
+    }
+}
+
+impl NoShuffleDispatcher {
+    pub fn new(output: BoxedOutput) -> Self {
+        Self { output }
+    }
+
+    fn replace_output(&mut self, outputs: impl IntoIterator<Item = BoxedOutput>) {
+        let mut outputs = outputs.into_iter();
+        self.output = outputs
+            .next()
+            .expect("no-shuffle dispatcher requires exactly one output");
+        assert!(
+            outputs.next().is_none(),
+            "no-shuffle dispatcher requires exactly one output"
+        );
+    }
+}
+
+impl Dispatcher for NoShuffleDispatcher {
+    define_dispatcher_associated_types!();
+
+    fn dispatch_data(&mut self, chunk: StreamChunk) -> Self::DataFuture<'_> {
+        async move {
+            self.output.send(Message::Chunk(chunk)).await?;
+            Ok(())
+        }
+    }
+
+    fn dispatch_barrier(&mut self, barrier: Barrier) -> Self::BarrierFuture<'_> {
+        async move {
+            self.output.send(Message::Barrier(barrier)).await?;
+            Ok(())
+        }
+    }
+
+    fn dispatch_watermark(&mut self, watermark: Watermark) -> Self::WatermarkFuture<'_> {
+        async move {
+            self.output.send(Message::Watermark(watermark)).await?;
+            Ok(())
+        }
+    }
+
+    fn set_outputs(&mut self, outputs: impl IntoIterator<Item = BoxedOutput>) {
+        self.replace_output(outputs);
+    }
+
+    fn add_outputs(&mut self, outputs: impl IntoIterator<Item = BoxedOutput>) {
+        self.replace_output(outputs);
+    }
+
+    fn remove_outputs(&mut self, actor_ids: &HashSet<ActorId>) {
+        // The paired downstream actor can only be dropped together with this actor.
+        if actor_ids.contains(&self.output.actor_id()) {
+            panic!("cannot remove outputs from NoShuffleDispatcher");
+        }
+    }
+}
+
 #[cfg(test)]
 mod sender_consumer {
     use super::*;
@@ -728,6 +941,7 @@ mod tests {
     use risingwave_common::catalog::Schema;
     use risingwave_common::column_nonnull;
     use risingwave_common::hash::VIRTUAL_NODE_COUNT;
+    use risingwave_common::types::ScalarImpl;
     use risingwave_pb::common::{ActorInfo, HostAddress};
 
     use super::*;
@@ -963,6 +1177,121 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_no_shuffle_dispatcher() {
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let output = Box::new(MockOutput::new(1, data.clone())) as BoxedOutput;
+        let mut dispatcher = NoShuffleDispatcher::new(output);
+
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::UpdateDelete, Op::UpdateInsert],
+            vec![column_nonnull! { I64Array, [1, 2, 2] }],
+            Some(Bitmap::try_from(vec![true, false, true]).unwrap()),
+        );
+        dispatcher.dispatch_data(chunk).await.unwrap();
+        dispatcher
+            .dispatch_barrier(Barrier::new_test_barrier(1))
+            .await
+            .unwrap();
+
+        let guard = data.lock().unwrap();
+        assert_eq!(guard.len(), 2);
+        match &guard[0] {
+            Message::Chunk(chunk) => {
+                // The chunk is forwarded as-is, without compaction or op rewriting.
+                assert_eq!(chunk.capacity(), 3);
+                assert_eq!(chunk.cardinality(), 2);
+                assert_eq!(chunk.ops()[1], Op::UpdateDelete);
+            }
+            _ => unreachable!(),
+        }
+        assert!(matches!(guard[1], Message::Barrier(_)));
+    }
+
+    #[tokio::test]
+    async fn test_hash_dispatcher_batching() {
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let output = Box::new(MockOutput::new(1, data.clone())) as BoxedOutput;
+        let mut hash_dispatcher =
+            HashDataDispatcher::new(vec![0], vec![output], vec![0], vec![1; VIRTUAL_NODE_COUNT])
+                .with_batching(4, vec![DataType::Int64]);
+
+        let chunk = || {
+            StreamChunk::new(
+                vec![Op::Insert, Op::UpdateDelete, Op::UpdateInsert],
+                vec![column_nonnull! { I64Array, [1, 2, 2] }],
+                None,
+            )
+        };
+        // Fewer rows than the chunk size are buffered.
+        hash_dispatcher.dispatch_data(chunk()).await.unwrap();
+        assert!(data.lock().unwrap().is_empty());
+
+        hash_dispatcher.dispatch_data(chunk()).await.unwrap();
+        {
+            let guard = data.lock().unwrap();
+            assert_eq!(guard.len(), 1);
+            match &guard[0] {
+                Message::Chunk(chunk) => assert_eq!(
+                    chunk.ops(),
+                    &[Op::Insert, Op::UpdateDelete, Op::UpdateInsert, Op::Insert]
+                ),
+                _ => unreachable!(),
+            }
+        }
+
+        // The buffered rows are sent before the barrier.
+        hash_dispatcher
+            .dispatch_barrier(Barrier::new_test_barrier(1))
+            .await
+            .unwrap();
+        let guard = data.lock().unwrap();
+        assert_eq!(guard.len(), 3);
+        match &guard[1] {
+            Message::Chunk(chunk) => {
+                assert_eq!(chunk.ops(), &[Op::UpdateDelete, Op::UpdateInsert])
+            }
+            _ => unreachable!(),
+        }
+        assert!(matches!(guard[2], Message::Barrier(_)));
+    }
+
+    #[tokio::test]
+    async fn test_hash_dispatcher_watermark() {
+        let data_1 = Arc::new(Mutex::new(Vec::new()));
+        let data_2 = Arc::new(Mutex::new(Vec::new()));
+        let outputs = vec![
+            Box::new(MockOutput::new(1, data_1.clone())) as BoxedOutput,
+            Box::new(MockOutput::new(2, data_2.clone())) as BoxedOutput,
+        ];
+        let mut hash_dispatcher =
+            HashDataDispatcher::new(vec![0], outputs, vec![0], vec![1; VIRTUAL_NODE_COUNT])
+                .with_batching(4, vec![DataType::Int64]);
+
+        hash_dispatcher
+            .dispatch_data(StreamChunk::new(
+                vec![Op::Insert],
+                vec![column_nonnull! { I64Array, [1] }],
+                None,
+            ))
+            .await
+            .unwrap();
+        let watermark = Watermark::new(0, DataType::Int64, ScalarImpl::Int64(1));
+        hash_dispatcher
+            .dispatch_watermark(watermark.clone())
+            .await
+            .unwrap();
+
+        // The watermark is broadcast to all outputs, after the buffered rows.
+        let guard = data_1.lock().unwrap();
+        assert_eq!(guard.len(), 2);
+        assert!(matches!(guard[0], Message::Chunk(_)));
+        assert!(matches!(&guard[1], Message::Watermark(w) if *w == watermark));
+        let guard = data_2.lock().unwrap();
+        assert_eq!(guard.len(), 1);
+        assert!(matches!(&guard[0], Message::Watermark(w) if *w == watermark));
+    }
+
     #[tokio::test]
     async fn test_hash_dispatcher() {
         let num_outputs = 5; // actor id ranges from 1 to 5
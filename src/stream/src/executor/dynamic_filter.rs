@@ -0,0 +1,554 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, RowDeserializer, StreamChunk};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::try_match_expand;
+use risingwave_common::types::{DataType, Datum, ToOwnedDatum};
+use risingwave_common::util::value_encoding::{deserialize_cell, serialize_cell};
+use risingwave_hummock_sdk::key::next_key;
+use risingwave_pb::expr::expr_node::Type as ExprType;
+use risingwave_pb::stream_plan;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::barrier_align::{AlignedMessage, BarrierAligner};
+use super::{Executor, ExecutorState, Message, PkIndices, PkIndicesRef, StatefulExecutor};
+use crate::executor::ExecutorBuilder;
+use crate::task::{ExecutorParams, LocalStreamManagerCore};
+
+pub struct DynamicFilterExecutorBuilder {}
+
+impl ExecutorBuilder for DynamicFilterExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &stream_plan::StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<Box<dyn Executor>> {
+        let node = try_match_expand!(node.get_node().unwrap(), Node::DynamicFilterNode)?;
+        let source_r = params.input.remove(1);
+        let source_l = params.input.remove(0);
+
+        let comparator = match node.get_comparator()? {
+            comparator @ (ExprType::LessThan
+            | ExprType::LessThanOrEqual
+            | ExprType::GreaterThan
+            | ExprType::GreaterThanOrEqual) => comparator,
+            comparator => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("{:?} in streaming dynamic filter", comparator),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+
+        Ok(Box::new(DynamicFilterExecutor::new(
+            source_l,
+            source_r,
+            node.get_left_key() as usize,
+            comparator,
+            params.pk_indices,
+            // The left rows are scanned by ranges of the filter column, so the state must not be
+            // shared with other parallel units.
+            Keyspace::executor_root(store, params.executor_id),
+            params.executor_id,
+            params.op_info,
+        )))
+    }
+}
+
+/// `DynamicFilterExecutor` filters the left input with `left[key] <comparator> value`, where
+/// `value` is the single value of the right input, e.g. the result of a simple aggregation. The
+/// output columns are the same as the left input.
+///
+/// The left rows are stored ordered by the filter column. Once the right value moves, only the
+/// rows between the old and the new value are scanned, and emitted as `Insert`s if they start
+/// satisfying the condition or `Delete`s if they stop satisfying it.
+pub struct DynamicFilterExecutor<S: StateStore> {
+    /// Barrier aligner that combines two input streams and aligns their barriers
+    aligner: BarrierAligner,
+    /// The schema of the executor, same as the left input
+    schema: Schema,
+    /// The primary key indices of the schema
+    pk_indices: PkIndices,
+    /// The index of the filter column in the left input
+    key_l: usize,
+    comparator: ExprType,
+    /// The left rows, keyed by the filter column followed by the primary key.
+    left_keyspace: Keyspace<S>,
+    /// The left rows written since the last flush. `None` stands for a deletion.
+    left_dirty: BTreeMap<Vec<u8>, Option<Row>>,
+    /// The current value of the right input, treated as a single key.
+    right_keyspace: Keyspace<S>,
+    /// The data type of the right value.
+    right_data_type: DataType,
+    /// The current value of the right input, or `None` if not loaded from the state store yet.
+    /// A `NULL` value means that no left row satisfies the condition.
+    value: Option<Datum>,
+    /// Whether `value` should be written to the state store on the next flush.
+    value_dirty: bool,
+    /// Debug info for the left executor
+    debug_l: String,
+    /// Debug info for the right executor
+    debug_r: String,
+    /// Identity string
+    identity: String,
+    /// Logical Operator Info
+    op_info: String,
+    /// Executor state
+    executor_state: ExecutorState,
+}
+
+impl<S: StateStore> std::fmt::Debug for DynamicFilterExecutor<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicFilterExecutor")
+            .field("key_l", &self.key_l)
+            .field("comparator", &self.comparator)
+            .field("input_left", &format_args!("{}", &self.debug_l))
+            .field("input_right", &format_args!("{}", &self.debug_r))
+            .field("pk_indices", &self.pk_indices)
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S: StateStore> Executor for DynamicFilterExecutor<S> {
+    async fn next(&mut self) -> Result<Message> {
+        let msg = loop {
+            match self.aligner.next().await {
+                // The right input has no column in the output, so only the watermarks of the
+                // left input would make sense, which are not propagated yet.
+                AlignedMessage::Watermark(_) => continue,
+                msg => break msg,
+            }
+        };
+        if let Some(barrier) = self.try_init_executor(&msg) {
+            return Ok(Message::Barrier(barrier));
+        }
+        match msg {
+            AlignedMessage::Left(message) => self.consume_chunk_left(message?).await,
+            AlignedMessage::Right(message) => self.consume_chunk_right(message?).await,
+            AlignedMessage::Barrier(barrier) => {
+                self.flush_data().await?;
+                self.update_executor_state(ExecutorState::Active(barrier.epoch.curr));
+                Ok(Message::Barrier(barrier))
+            }
+            AlignedMessage::Watermark(_) => unreachable!(),
+        }
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        self.identity.as_str()
+    }
+
+    fn logical_operator_info(&self) -> &str {
+        &self.op_info
+    }
+
+    fn clear_cache(&mut self) -> Result<()> {
+        assert!(
+            self.left_dirty.is_empty() && !self.value_dirty,
+            "cannot clear cache while states of dynamic filter are dirty"
+        );
+        self.value = None;
+        Ok(())
+    }
+}
+
+/// Returns the parts of range `a` that are not covered by range `b`.
+fn range_minus(a: &Range<Vec<u8>>, b: &Option<Range<Vec<u8>>>) -> Vec<Range<Vec<u8>>> {
+    let b = match b {
+        Some(b) if b.start < b.end => b,
+        _ => return vec![a.clone()],
+    };
+    [
+        a.start.clone()..(&a.end).min(&b.start).clone(),
+        (&a.start).max(&b.end).clone()..a.end.clone(),
+    ]
+    .into_iter()
+    .filter(|range| range.start < range.end)
+    .collect()
+}
+
+impl<S: StateStore> DynamicFilterExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input_l: Box<dyn Executor>,
+        input_r: Box<dyn Executor>,
+        key_l: usize,
+        comparator: ExprType,
+        pk_indices: PkIndices,
+        keyspace: Keyspace<S>,
+        executor_id: u64,
+        op_info: String,
+    ) -> Self {
+        let debug_l = format!("{:#?}", &input_l);
+        let debug_r = format!("{:#?}", &input_r);
+        assert_eq!(
+            input_r.schema().len(),
+            1,
+            "the right input of dynamic filter must have exactly one column"
+        );
+        let schema = input_l.schema().clone();
+        let right_data_type = input_r.schema().fields[0].data_type();
+
+        Self {
+            aligner: BarrierAligner::new(input_l, input_r),
+            schema,
+            pk_indices,
+            key_l,
+            comparator,
+            left_keyspace: keyspace.append_u8(b'l'),
+            left_dirty: BTreeMap::new(),
+            right_keyspace: keyspace.append_u8(b'r'),
+            right_data_type,
+            value: None,
+            value_dirty: false,
+            debug_l,
+            debug_r,
+            identity: format!("DynamicFilterExecutor {:X}", executor_id),
+            op_info,
+            executor_state: ExecutorState::Init,
+        }
+    }
+
+    /// The state key of a left row: the filter column followed by the primary key, so that the
+    /// rows are ordered by the filter column.
+    fn left_key(&self, row: &Row) -> Result<Vec<u8>> {
+        let key = Row(std::iter::once(self.key_l)
+            .chain(self.pk_indices.iter().copied())
+            .map(|idx| row.0[idx].clone())
+            .collect());
+        Ok(key.serialize()?)
+    }
+
+    /// The range of the state keys of the left rows satisfying the condition against `value`.
+    fn satisfied_range(&self, value: &Datum) -> Result<Option<Range<Vec<u8>>>> {
+        if value.is_none() {
+            return Ok(None);
+        }
+        let value = Row(vec![value.clone()]).serialize()?;
+        // A non-null datum is encoded with a leading `1`, so the `NULL`s are never included.
+        let min = vec![1u8];
+        let max = vec![2u8];
+        let range = match self.comparator {
+            ExprType::LessThan => min..value,
+            ExprType::LessThanOrEqual => min..next_key(&value),
+            ExprType::GreaterThan => next_key(&value)..max,
+            ExprType::GreaterThanOrEqual => value..max,
+            _ => unreachable!(),
+        };
+        Ok(Some(range))
+    }
+
+    async fn get_value(&mut self, epoch: u64) -> Result<Datum> {
+        if let Some(value) = &self.value {
+            return Ok(value.clone());
+        }
+        let value = match self.right_keyspace.value(epoch).await? {
+            Some(data) => {
+                let mut deserializer = value_encoding::Deserializer::new(data);
+                deserialize_cell(&mut deserializer, &self.right_data_type)?
+            }
+            None => None,
+        };
+        self.value = Some(value.clone());
+        Ok(value)
+    }
+
+    /// Scans the left rows within `range`, merging the state store with the pending writes.
+    async fn scan_left(&self, range: Range<Vec<u8>>, epoch: u64) -> Result<Vec<Row>> {
+        let deserializer = RowDeserializer::new(self.schema.data_types());
+        let store_range = self.left_keyspace.prefixed_key(&range.start)
+            ..self.left_keyspace.prefixed_key(&range.end);
+        let prefix_len = self.left_keyspace.key().len();
+
+        let mut rows = BTreeMap::new();
+        for (key, value) in self
+            .left_keyspace
+            .state_store()
+            .scan(store_range, None, epoch)
+            .await?
+        {
+            rows.insert(
+                key[prefix_len..].to_vec(),
+                Some(deserializer.deserialize(&value)?),
+            );
+        }
+        for (key, row) in self.left_dirty.range(range) {
+            rows.insert(key.clone(), row.clone());
+        }
+        Ok(rows.into_values().flatten().collect())
+    }
+
+    async fn consume_chunk_left(&mut self, chunk: StreamChunk) -> Result<Message> {
+        let epoch = self.executor_state().epoch();
+        let value = self.get_value(epoch).await?;
+        let range = self.satisfied_range(&value)?;
+        let (data_chunk, ops) = chunk.compact()?.into_parts();
+
+        let mut output = vec![];
+        for (row, op) in data_chunk.rows().zip_eq(ops.iter()) {
+            let row: Row = row.into();
+            let key = self.left_key(&row)?;
+            // An update may move the row across the value, so it's emitted as a separate delete
+            // and insert.
+            let op = match op {
+                Op::Insert | Op::UpdateInsert => Op::Insert,
+                Op::Delete | Op::UpdateDelete => Op::Delete,
+            };
+            if range.as_ref().map_or(false, |range| range.contains(&key)) {
+                output.push((op, row.clone()));
+            }
+            match op {
+                Op::Insert => self.left_dirty.insert(key, Some(row)),
+                _ => self.left_dirty.insert(key, None),
+            };
+        }
+
+        let chunk = StreamChunk::from_rows(&output, &self.schema.data_types())?;
+        Ok(Message::Chunk(chunk))
+    }
+
+    async fn consume_chunk_right(&mut self, chunk: StreamChunk) -> Result<Message> {
+        let epoch = self.executor_state().epoch();
+        let old_value = self.get_value(epoch).await?;
+
+        // Only the last value matters, e.g. `U-` and `U+` of a simple aggregation.
+        let mut new_value = old_value.clone();
+        for (op, datum) in chunk
+            .ops()
+            .iter()
+            .zip_eq(chunk.column_at(0).array_ref().iter())
+        {
+            new_value = match op {
+                Op::Insert | Op::UpdateInsert => datum.to_owned_datum(),
+                Op::Delete | Op::UpdateDelete => None,
+            };
+        }
+        let old_range = self.satisfied_range(&old_value)?;
+        let new_range = self.satisfied_range(&new_value)?;
+        let mut output = vec![];
+        if let Some(old_range) = &old_range {
+            for range in range_minus(old_range, &new_range) {
+                for row in self.scan_left(range, epoch).await? {
+                    output.push((Op::Delete, row));
+                }
+            }
+        }
+        if let Some(new_range) = &new_range {
+            for range in range_minus(new_range, &old_range) {
+                for row in self.scan_left(range, epoch).await? {
+                    output.push((Op::Insert, row));
+                }
+            }
+        }
+
+        self.value = Some(new_value);
+        self.value_dirty = true;
+        let chunk = StreamChunk::from_rows(&output, &self.schema.data_types())?;
+        Ok(Message::Chunk(chunk))
+    }
+
+    async fn flush_data(&mut self) -> Result<()> {
+        let epoch = self.executor_state().epoch();
+        let mut write_batch = self.left_keyspace.state_store().start_write_batch();
+        {
+            let mut local = write_batch.prefixify(&self.left_keyspace);
+            for (key, row) in std::mem::take(&mut self.left_dirty) {
+                match row {
+                    Some(row) => local.put(key, StorageValue::new_default_put(row.serialize()?)),
+                    None => local.delete(key),
+                }
+            }
+        }
+        if self.value_dirty {
+            let mut local = write_batch.prefixify(&self.right_keyspace);
+            match self.value.as_ref().unwrap() {
+                Some(value) => local.put_single(StorageValue::new_default_put(serialize_cell(
+                    &Some(value.clone()),
+                )?)),
+                None => local.delete_single(),
+            }
+            self.value_dirty = false;
+        }
+        write_batch.ingest(epoch).await?;
+        Ok(())
+    }
+}
+
+impl<S: StateStore> StatefulExecutor for DynamicFilterExecutor<S> {
+    fn executor_state(&self) -> &ExecutorState {
+        &self.executor_state
+    }
+
+    fn update_executor_state(&mut self, new_state: ExecutorState) {
+        self.executor_state = new_state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::*;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::column_nonnull;
+    use risingwave_storage::memory::MemoryStateStore;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+    use super::*;
+    use crate::executor::test_utils::{create_in_memory_keyspace, MockAsyncSource};
+
+    fn create_executor(
+        comparator: ExprType,
+    ) -> (
+        UnboundedSender<Message>,
+        UnboundedSender<Message>,
+        DynamicFilterExecutor<MemoryStateStore>,
+    ) {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let (tx_l, rx_l) = unbounded_channel();
+        let (tx_r, rx_r) = unbounded_channel();
+        let source_l = MockAsyncSource::with_pk_indices(schema.clone(), rx_l, vec![0]);
+        let source_r = MockAsyncSource::with_pk_indices(schema, rx_r, vec![]);
+        let executor = DynamicFilterExecutor::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            0,
+            comparator,
+            vec![0],
+            create_in_memory_keyspace(),
+            1,
+            "DynamicFilterExecutor".to_string(),
+        );
+        (tx_l, tx_r, executor)
+    }
+
+    async fn next_chunk(executor: &mut impl Executor) -> Vec<(Op, Option<i64>)> {
+        match executor.next().await.unwrap() {
+            Message::Chunk(chunk) => chunk
+                .ops()
+                .iter()
+                .cloned()
+                .zip_eq(chunk.column_at(0).array_ref().as_int64().iter())
+                .collect(),
+            Message::Barrier(_) | Message::Watermark(_) => unreachable!(),
+        }
+    }
+
+    async fn barrier(
+        tx_l: &mut UnboundedSender<Message>,
+        tx_r: &mut UnboundedSender<Message>,
+        executor: &mut impl Executor,
+        epoch: u64,
+    ) {
+        MockAsyncSource::push_barrier(tx_l, epoch, false);
+        MockAsyncSource::push_barrier(tx_r, epoch, false);
+        assert!(matches!(
+            executor.next().await.unwrap(),
+            Message::Barrier(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_dynamic_filter() {
+        let (mut tx_l, mut tx_r, mut executor) = create_executor(ExprType::GreaterThan);
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 1).await;
+
+        // No row passes before the right value arrives.
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![StreamChunk::new(
+                vec![Op::Insert, Op::Insert, Op::Insert, Op::Insert],
+                vec![column_nonnull! { I64Array, [1, 2, 3, 4] }],
+                None,
+            )],
+        );
+        assert_eq!(next_chunk(&mut executor).await, vec![]);
+
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![StreamChunk::new(
+                vec![Op::Insert],
+                vec![column_nonnull! { I64Array, [2] }],
+                None,
+            )],
+        );
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![(Op::Insert, Some(3)), (Op::Insert, Some(4))]
+        );
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 2).await;
+
+        // Only the rows between the old and the new value are touched.
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![StreamChunk::new(
+                vec![Op::UpdateDelete, Op::UpdateInsert],
+                vec![column_nonnull! { I64Array, [2, 3] }],
+                None,
+            )],
+        );
+        assert_eq!(next_chunk(&mut executor).await, vec![(Op::Delete, Some(3))]);
+
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![StreamChunk::new(
+                vec![Op::Insert, Op::Insert, Op::Delete],
+                vec![column_nonnull! { I64Array, [0, 5, 4] }],
+                None,
+            )],
+        );
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![(Op::Insert, Some(5)), (Op::Delete, Some(4))]
+        );
+
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![StreamChunk::new(
+                vec![Op::UpdateDelete, Op::UpdateInsert],
+                vec![column_nonnull! { I64Array, [3, 0] }],
+                None,
+            )],
+        );
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![
+                (Op::Insert, Some(1)),
+                (Op::Insert, Some(2)),
+                (Op::Insert, Some(3))
+            ]
+        );
+    }
+}
@@ -47,16 +47,24 @@ impl ExecutorBuilder for SimpleAggExecutorBuilder {
             .iter()
             .map(|key| *key as usize)
             .collect::<Vec<_>>();
+        let extreme_cache_miss_count = params
+            .executor_stats
+            .agg_extreme_cache_miss_count
+            .with_label_values(&[&params.actor_id.to_string()]);
         Ok(Box::new(
-            Box::new(SimpleAggExecutorV2::new_from_v1(
-                params.input.remove(0),
-                agg_calls,
-                keyspace,
-                params.pk_indices,
-                params.executor_id,
-                params.op_info,
-                key_indices,
-            )?)
+            Box::new(
+                SimpleAggExecutorV2::new_from_v1(
+                    params.input.remove(0),
+                    agg_calls,
+                    keyspace,
+                    params.pk_indices,
+                    params.executor_id,
+                    params.op_info,
+                    key_indices,
+                )?
+                .with_extreme_cache_capacity(params.env.config().extreme_cache_capacity)
+                .with_extreme_cache_miss_count(extreme_cache_miss_count),
+            )
             .v1(),
         ))
     }
@@ -15,8 +15,10 @@
 //! Global Streaming Hash Aggregators
 
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use itertools::Itertools;
+use prometheus::IntCounter;
 use risingwave_common::error::Result;
 use risingwave_common::hash::{calc_hash_key_kind, HashKey, HashKeyDispatcher};
 use risingwave_common::try_match_expand;
@@ -27,8 +29,10 @@ use risingwave_storage::{Keyspace, StateStore};
 use super::Executor;
 use crate::executor::{ExecutorBuilder, PkIndices};
 use crate::executor_v2::aggregation::AggCall;
-use crate::executor_v2::{Executor as ExecutorV2, HashAggExecutor};
-use crate::task::{build_agg_call_from_prost, ExecutorParams, LocalStreamManagerCore};
+use crate::executor_v2::{Executor as ExecutorV2, HashAggExecutor, MiniBatchConfig};
+use crate::task::{
+    build_agg_call_from_prost, CacheMemoryHandle, ExecutorParams, LocalStreamManagerCore,
+};
 
 struct HashAggExecutorDispatcher<S: StateStore>(PhantomData<S>);
 
@@ -40,6 +44,11 @@ struct HashAggExecutorDispatcherArgs<S: StateStore> {
     pk_indices: PkIndices,
     executor_id: u64,
     op_info: String,
+    mini_batch: Option<MiniBatchConfig>,
+    sorted_output: bool,
+    extreme_cache_capacity: usize,
+    extreme_cache_miss_count: IntCounter,
+    memory_handle: CacheMemoryHandle,
 }
 
 impl<S: StateStore> HashKeyDispatcher for HashAggExecutorDispatcher<S> {
@@ -47,18 +56,25 @@ impl<S: StateStore> HashKeyDispatcher for HashAggExecutorDispatcher<S> {
     type Output = Result<Box<dyn Executor>>;
 
     fn dispatch<K: HashKey>(args: Self::Input) -> Self::Output {
-        Ok(Box::new(
-            Box::new(HashAggExecutor::<K, S>::new_from_v1(
-                args.input,
-                args.agg_calls,
-                args.key_indices,
-                args.keyspace,
-                args.pk_indices,
-                args.executor_id,
-                args.op_info,
-            )?)
-            .v1(),
-        ))
+        let mut executor = HashAggExecutor::<K, S>::new_from_v1(
+            args.input,
+            args.agg_calls,
+            args.key_indices,
+            args.keyspace,
+            args.pk_indices,
+            args.executor_id,
+            args.op_info,
+        )?
+        .with_extreme_cache_capacity(args.extreme_cache_capacity)
+        .with_extreme_cache_miss_count(args.extreme_cache_miss_count)
+        .with_memory_handle(args.memory_handle);
+        if let Some(mini_batch) = args.mini_batch {
+            executor = executor.with_mini_batch(mini_batch);
+        }
+        if args.sorted_output {
+            executor = executor.with_sorted_output();
+        }
+        Ok(Box::new(Box::new(executor).v1()))
     }
 }
 
@@ -89,6 +105,14 @@ impl ExecutorBuilder for HashAggExecutorBuilder {
             .map(|idx| input.schema().fields[*idx].data_type())
             .collect_vec();
         let kind = calc_hash_key_kind(&keys);
+        let mini_batch = MiniBatchConfig::new(
+            node.mini_batch_max_rows as usize,
+            Duration::from_millis(node.mini_batch_interval_ms),
+        );
+        let memory_handle = params
+            .env
+            .memory_manager()
+            .register(format!("HashAggExecutor {:X}", params.executor_id));
         let args = HashAggExecutorDispatcherArgs {
             input,
             agg_calls,
@@ -97,6 +121,14 @@ impl ExecutorBuilder for HashAggExecutorBuilder {
             pk_indices: params.pk_indices,
             executor_id: params.executor_id,
             op_info: params.op_info,
+            mini_batch,
+            sorted_output: params.env.config().sorted_agg_output,
+            extreme_cache_capacity: params.env.config().extreme_cache_capacity,
+            extreme_cache_miss_count: params
+                .executor_stats
+                .agg_extreme_cache_miss_count
+                .with_label_values(&[&params.actor_id.to_string()]),
+            memory_handle,
         };
         HashAggExecutorDispatcher::dispatch_by_kind(kind, args)
     }
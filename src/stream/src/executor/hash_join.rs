@@ -30,7 +30,16 @@ use super::managed_state::join::*;
 use super::{Executor, ExecutorState, Message, PkIndices, PkIndicesRef, StatefulExecutor};
 use crate::common::StreamChunkBuilder;
 use crate::executor::ExecutorBuilder;
-use crate::task::{ExecutorParams, LocalStreamManagerCore};
+use crate::task::{
+    CacheMemoryHandle, ExecutorParams, LocalStreamManagerCore, StreamMemoryManagerRef,
+};
+
+/// Number of join keys cached by each side when there is no memory pressure.
+const CACHE_CAPACITY: usize = 1 << 16;
+
+/// Estimated memory usage of the rows of a join key (bytes), used to convert the number of cached
+/// join keys into bytes for the memory manager.
+const CACHE_ENTRY_SIZE: usize = 1024;
 
 /// The `JoinType` and `SideType` are to mimic a enum, because currently
 /// enum is not supported in const generic.
@@ -92,6 +101,8 @@ struct JoinSide<S: StateStore> {
     start_pos: usize,
     /// The join side operates on this keyspace.
     keyspace: Keyspace<S>,
+    /// Reports the size of `ht` to the memory manager, if registered.
+    memory_handle: Option<CacheMemoryHandle>,
 }
 
 impl<S: StateStore> std::fmt::Debug for JoinSide<S> {
@@ -119,6 +130,23 @@ impl<S: StateStore> JoinSide<S> {
         // TODO: not working with rearranged chain
         // self.ht.clear();
     }
+
+    /// Evict the least recently used join keys to keep the cache within its capacity, which is
+    /// lowered under memory pressure if the memory manager is registered.
+    fn evict(&mut self) {
+        assert!(!self.is_dirty());
+        if let Some(handle) = &self.memory_handle {
+            handle.set_bytes(self.ht.len() * CACHE_ENTRY_SIZE);
+            let target_cap = handle.eviction_target().map_or(CACHE_CAPACITY, |bytes| {
+                (bytes / CACHE_ENTRY_SIZE).min(CACHE_CAPACITY)
+            });
+            self.ht.set_target_cap(target_cap);
+        }
+        self.ht.evict_to_target_cap();
+        if let Some(handle) = &self.memory_handle {
+            handle.set_bytes(self.ht.len() * CACHE_ENTRY_SIZE);
+        }
+    }
 }
 
 pub struct HashJoinExecutorBuilder {}
@@ -157,6 +185,8 @@ impl ExecutorBuilder for HashJoinExecutorBuilder {
             .iter()
             .map(|key| *key as usize)
             .collect::<Vec<_>>();
+        let is_append_only = node.is_append_only;
+        let memory_manager = params.env.memory_manager();
 
         macro_rules! impl_create_hash_join_executor {
             ($( { $join_type_proto:ident, $join_type:ident } ),*) => {
@@ -172,7 +202,8 @@ impl ExecutorBuilder for HashJoinExecutorBuilder {
                         condition,
                         params.op_info,
                         key_indices,
-                    )) as Box<dyn Executor>, )*
+                        is_append_only,
+                    ).with_memory_manager(&memory_manager)) as Box<dyn Executor>, )*
                     _ => todo!("Join type {:?} not implemented", typ),
                 }
             }
@@ -228,6 +259,12 @@ pub struct HashJoinExecutor<S: StateStore, const T: JoinTypePrimitive> {
     #[allow(dead_code)]
     /// Indices of the columns on which key distribution depends.
     key_indices: Vec<usize>,
+
+    /// Whether both inputs are append-only and the join key of each side contains its primary
+    /// key. In this case every row matches at most one row from the other side, and both of them
+    /// will never be matched again, so the matched row can be removed from the state and the
+    /// incoming row doesn't need to be stored.
+    append_only_optimize: bool,
 }
 
 impl<S: StateStore, const T: JoinTypePrimitive> std::fmt::Debug for HashJoinExecutor<S, T> {
@@ -241,6 +278,7 @@ impl<S: StateStore, const T: JoinTypePrimitive> std::fmt::Debug for HashJoinExec
             .field("pk_indices", &self.pk_indices)
             .field("schema", &self.schema)
             .field("output_data_types", &self.output_data_types)
+            .field("append_only_optimize", &self.append_only_optimize)
             .finish()
     }
 }
@@ -248,7 +286,14 @@ impl<S: StateStore, const T: JoinTypePrimitive> std::fmt::Debug for HashJoinExec
 #[async_trait]
 impl<S: StateStore, const T: JoinTypePrimitive> Executor for HashJoinExecutor<S, T> {
     async fn next(&mut self) -> Result<Message> {
-        let msg = self.aligner.next().await;
+        let msg = loop {
+            match self.aligner.next().await {
+                // The output columns do not map to the same column on both sides, so the
+                // watermarks are not propagated through joins yet.
+                AlignedMessage::Watermark(_) => continue,
+                msg => break msg,
+            }
+        };
         if let Some(barrier) = self.try_init_executor(&msg) {
             self.side_l.ht.update_epoch(barrier.epoch.curr);
             self.side_r.ht.update_epoch(barrier.epoch.curr);
@@ -271,6 +316,7 @@ impl<S: StateStore, const T: JoinTypePrimitive> Executor for HashJoinExecutor<S,
                 self.update_executor_state(ExecutorState::Active(barrier.epoch.curr));
                 Ok(Message::Barrier(barrier))
             }
+            AlignedMessage::Watermark(_) => unreachable!(),
         }
     }
 
@@ -311,6 +357,7 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
         cond: Option<RowExpression>,
         op_info: String,
         key_indices: Vec<usize>,
+        is_append_only: bool,
     ) -> Self {
         let debug_l = format!("{:#?}", &input_l);
         let debug_r = format!("{:#?}", &input_r);
@@ -345,6 +392,14 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
         let pk_indices_l = input_l.pk_indices().to_vec();
         let pk_indices_r = input_r.pk_indices().to_vec();
 
+        let pk_contained_in_key = |pk_indices: &[usize], key_indices: &[usize]| {
+            pk_indices.iter().all(|idx| key_indices.contains(idx))
+        };
+        let append_only_optimize = is_append_only
+            && T == JoinType::Inner
+            && pk_contained_in_key(&pk_indices_l, &params_l.key_indices)
+            && pk_contained_in_key(&pk_indices_r, &params_r.key_indices);
+
         let ks_l = keyspace.append_u8(JOIN_LEFT_PATH);
         let ks_r = keyspace.append_u8(JOIN_RIGHT_PATH);
         Self {
@@ -355,7 +410,7 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
             },
             side_l: JoinSide {
                 ht: JoinHashMap::new(
-                    1 << 16,
+                    CACHE_CAPACITY,
                     pk_indices_l.clone(),
                     col_l_datatypes.clone(),
                     ks_l.clone(),
@@ -365,10 +420,11 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
                 pk_indices: pk_indices_l,
                 start_pos: 0,
                 keyspace: ks_l,
+                memory_handle: None,
             },
             side_r: JoinSide {
                 ht: JoinHashMap::new(
-                    1 << 16,
+                    CACHE_CAPACITY,
                     pk_indices_r.clone(),
                     col_r_datatypes.clone(),
                     ks_r.clone(),
@@ -378,6 +434,7 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
                 pk_indices: pk_indices_r,
                 start_pos: side_l_column_n,
                 keyspace: ks_r,
+                memory_handle: None,
             },
             pk_indices,
             cond,
@@ -387,6 +444,7 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
             op_info,
             executor_state: ExecutorState::Init,
             key_indices,
+            append_only_optimize,
         }
     }
 
@@ -401,13 +459,21 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
         }
 
         // evict the LRU cache
-        assert!(!self.side_l.is_dirty());
-        self.side_l.ht.evict_to_target_cap();
-        assert!(!self.side_r.is_dirty());
-        self.side_r.ht.evict_to_target_cap();
+        self.side_l.evict();
+        self.side_r.evict();
         Ok(())
     }
 
+    /// Register the caches of both sides to `memory_manager`, so that they are evicted under memory
+    /// pressure.
+    pub fn with_memory_manager(mut self, memory_manager: &StreamMemoryManagerRef) -> Self {
+        self.side_l.memory_handle =
+            Some(memory_manager.register(format!("{} (left side)", self.identity)));
+        self.side_r.memory_handle =
+            Some(memory_manager.register(format!("{} (right side)", self.identity)));
+        self
+    }
+
     /// the data the hash table and match the coming
     /// data chunk with the executor state
     async fn hash_eq_match<'a>(
@@ -436,6 +502,10 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
         row.row_by_slice(pk_indices)
     }
 
+    fn pk_from_row(row: &Row, pk_indices: &[usize]) -> Row {
+        Row(pk_indices.iter().map(|idx| row[*idx].clone()).collect_vec())
+    }
+
     fn row_concat(
         row_update: &RowRef<'_>,
         update_start_pos: usize,
@@ -512,11 +582,14 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
             let pk = Self::pk_from_row_ref(&row, &side_update.pk_indices);
             let matched_rows = Self::hash_eq_match(&key, &mut side_match.ht).await;
             if let Some(matched_rows) = matched_rows {
+                // The number of rows in `matched_rows` that `row` joins with.
+                let mut degree = 0;
+                // The rows whose degree has changed and needs to be flushed.
+                let mut updated_pks = vec![];
                 match *op {
                     Op::Insert | Op::UpdateInsert => {
-                        let entry_value = side_update.ht.get_or_init_without_cache(&key).await?;
-                        let mut degree = 0;
-                        for matched_row in matched_rows.values_mut(epoch).await {
+                        let mut append_only_matched_pk = None;
+                        for (matched_pk, matched_row) in matched_rows.iter_mut(epoch).await {
                             // TODO(yuhao-su): We should find a better way to eval the
                             // expression without concat
                             // two rows.
@@ -536,7 +609,8 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
                             if cond_match {
                                 degree += 1;
                                 if matched_row.is_zero_degree() && outer_side_null(T, SIDE) {
-                                    // if the matched_row does not have any current matches
+                                    // The matched row was emitted padded with nulls, as it had no
+                                    // matches. Retract it and emit the joined row instead.
                                     stream_chunk_builder
                                         .append_row_matched(Op::UpdateDelete, &matched_row.row)?;
                                     stream_chunk_builder.append_row(
@@ -546,8 +620,6 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
                                     )?;
                                 } else {
                                     // concat with the matched_row and append the new row
-                                    // FIXME: we always use `Op::Delete` here to avoid violating
-                                    // the assumption for U+ after U-.
                                     stream_chunk_builder.append_row(
                                         Op::Insert,
                                         &row,
@@ -555,66 +627,85 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<S, T> {
                                     )?;
                                 }
                                 matched_row.inc_degree();
-                            } else {
-                                // not matched
-                                if outer_side_keep(T, SIDE) {
-                                    stream_chunk_builder.append_row_update(*op, &row)?;
+                                updated_pks.push(matched_pk.clone());
+                                if self.append_only_optimize {
+                                    append_only_matched_pk = Some(Self::pk_from_row(
+                                        &matched_row.row,
+                                        &side_match.pk_indices,
+                                    ));
                                 }
                             }
                         }
-                        entry_value.insert(pk, JoinRow::new(value, degree));
+                        for updated_pk in updated_pks {
+                            matched_rows.mark_updated(updated_pk);
+                        }
+                        // The row has no matches, so it's emitted padded with nulls if it's on
+                        // the outer side.
+                        if degree == 0 && outer_side_keep(T, SIDE) {
+                            stream_chunk_builder.append_row_update(*op, &row)?;
+                        }
+                        if let Some(matched_pk) = append_only_matched_pk {
+                            // Both rows can't be matched anymore, so there's no need to keep them.
+                            matched_rows.remove(matched_pk);
+                        } else {
+                            let entry_value =
+                                side_update.ht.get_or_init_without_cache(&key).await?;
+                            entry_value.insert(pk, JoinRow::new(value, degree));
+                        }
                     }
                     Op::Delete | Op::UpdateDelete => {
                         if let Some(v) = side_update.ht.get_mut_without_cached(&key).await {
                             // remove the row by it's primary key
                             v.remove(pk);
+                        }
 
-                            for matched_row in matched_rows.values_mut(epoch).await {
-                                let new_row = Self::row_concat(
-                                    &row,
-                                    side_update.start_pos,
-                                    &matched_row.row,
-                                    side_match.start_pos,
-                                );
+                        for (matched_pk, matched_row) in matched_rows.iter_mut(epoch).await {
+                            let new_row = Self::row_concat(
+                                &row,
+                                side_update.start_pos,
+                                &matched_row.row,
+                                side_match.start_pos,
+                            );
 
-                                let mut cond_match = true;
-                                // if there are non-equi expressions
-                                if let Some(ref mut cond) = self.cond {
-                                    cond_match = Self::bool_from_array_ref(
-                                        cond.eval(&new_row, &self.output_data_types)?,
-                                    );
-                                }
-                                if cond_match {
-                                    if matched_row.is_zero_degree() && outer_side_null(T, SIDE) {
-                                        // if the matched_row does not have any current matches
-                                        stream_chunk_builder.append_row(
-                                            Op::UpdateDelete,
-                                            &row,
-                                            &matched_row.row,
-                                        )?;
-                                        stream_chunk_builder.append_row_matched(
-                                            Op::UpdateInsert,
-                                            &matched_row.row,
-                                        )?;
-                                    } else {
-                                        // concat with the matched_row and append the new row
-                                        // FIXME: we always use `Op::Delete` here to avoid violating
-                                        // the assumption for U+ after U-.
-                                        stream_chunk_builder.append_row(
-                                            Op::Delete,
-                                            &row,
-                                            &matched_row.row,
-                                        )?;
-                                    }
-                                    matched_row.dec_degree();
+                            let mut cond_match = true;
+                            // if there are non-equi expressions
+                            if let Some(ref mut cond) = self.cond {
+                                cond_match = Self::bool_from_array_ref(
+                                    cond.eval(&new_row, &self.output_data_types)?,
+                                );
+                            }
+                            if cond_match {
+                                degree += 1;
+                                matched_row.dec_degree();
+                                updated_pks.push(matched_pk.clone());
+                                if matched_row.is_zero_degree() && outer_side_null(T, SIDE) {
+                                    // The matched row loses its last match. Retract the joined row
+                                    // and emit the matched row padded with nulls instead.
+                                    stream_chunk_builder.append_row(
+                                        Op::UpdateDelete,
+                                        &row,
+                                        &matched_row.row,
+                                    )?;
+                                    stream_chunk_builder
+                                        .append_row_matched(Op::UpdateInsert, &matched_row.row)?;
                                 } else {
-                                    // not matched
-                                    if outer_side_keep(T, SIDE) {
-                                        stream_chunk_builder.append_row_update(*op, &row)?;
-                                    }
+                                    // concat with the matched_row and append the new row
+                                    stream_chunk_builder.append_row(
+                                        Op::Delete,
+                                        &row,
+                                        &matched_row.row,
+                                    )?;
                                 }
                             }
                         }
+                        for updated_pk in updated_pks {
+                            matched_rows.mark_updated(updated_pk);
+                        }
+                        // The row had no matches, so it was emitted padded with nulls if it's on
+                        // the outer side.
+                        if degree == 0 && outer_side_keep(T, SIDE) {
+                            stream_chunk_builder.append_row_update(*op, &row)?;
+                        }
                     }
                 };
             } else {
@@ -658,10 +749,14 @@ impl<S: StateStore, const T: JoinTypePrimitive> StatefulExecutor for HashJoinExe
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use itertools::Itertools;
+    use risingwave_common::array::column::Column;
     use risingwave_common::array::*;
     use risingwave_common::catalog::{Field, Schema};
     use risingwave_common::column_nonnull;
+    use risingwave_common::types::ScalarImpl;
     use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
     use risingwave_expr::expr::{InputRefExpression, RowExpression};
     use risingwave_pb::expr::expr_node::Type;
@@ -676,6 +771,17 @@ mod tests {
         Keyspace::executor_root(MemoryStateStore::new(), 0x2333)
     }
 
+    fn key(a: i64, b: i64) -> Row {
+        Row(vec![Some(ScalarImpl::Int64(a)), Some(ScalarImpl::Int64(b))])
+    }
+
+    async fn count_rows(ht: &mut JoinHashMap<MemoryStateStore>, key: &Row, epoch: u64) -> usize {
+        match ht.get_mut(key).await {
+            Some(state) => state.values(epoch).await.count(),
+            None => 0,
+        }
+    }
+
     fn create_cond() -> Option<RowExpression> {
         let left_expr = InputRefExpression::new(DataType::Int64, 1);
         let right_expr = InputRefExpression::new(DataType::Int64, 3);
@@ -688,6 +794,74 @@ mod tests {
         Some(RowExpression::new(cond))
     }
 
+    type ChannelPair = (
+        tokio::sync::mpsc::UnboundedSender<Message>,
+        tokio::sync::mpsc::UnboundedSender<Message>,
+    );
+
+    /// Creates an executor joining two `(i64, i64)` inputs on their first columns.
+    fn create_executor<const T: JoinTypePrimitive>(
+        keyspace: Keyspace<MemoryStateStore>,
+        cond: Option<RowExpression>,
+    ) -> (ChannelPair, HashJoinExecutor<MemoryStateStore, T>) {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let (tx_l, rx_l) = unbounded_channel();
+        let (tx_r, rx_r) = unbounded_channel();
+        let source_l = MockAsyncSource::with_pk_indices(schema.clone(), rx_l, vec![0, 1]);
+        let source_r = MockAsyncSource::with_pk_indices(schema, rx_r, vec![0, 1]);
+        let executor = HashJoinExecutor::<_, T>::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            JoinParams::new(vec![0]),
+            JoinParams::new(vec![0]),
+            vec![],
+            keyspace,
+            1,
+            cond,
+            "HashJoinExecutor".to_string(),
+            vec![],
+            false,
+        );
+        ((tx_l, tx_r), executor)
+    }
+
+    fn i64_chunk(ops: Vec<Op>, col_0: Vec<i64>, col_1: Vec<i64>) -> StreamChunk {
+        let col_0 = I64Array::from_slice(&col_0.into_iter().map(Some).collect_vec()).unwrap();
+        let col_1 = I64Array::from_slice(&col_1.into_iter().map(Some).collect_vec()).unwrap();
+        StreamChunk::new(
+            ops,
+            vec![
+                Column::new(Arc::new(col_0.into())),
+                Column::new(Arc::new(col_1.into())),
+            ],
+            None,
+        )
+    }
+
+    fn assert_chunk(msg: Message, ops: Vec<Op>, rows: Vec<[Option<i64>; 4]>) {
+        if let Message::Chunk(chunk) = msg {
+            assert_eq!(chunk.ops(), ops);
+            for i in 0..4 {
+                assert_eq!(
+                    chunk
+                        .column_at(i)
+                        .array_ref()
+                        .as_int64()
+                        .iter()
+                        .collect_vec(),
+                    rows.iter().map(|row| row[i]).collect_vec()
+                );
+            }
+        } else {
+            unreachable!();
+        }
+    }
+
     #[tokio::test]
     async fn test_streaming_hash_inner_join() {
         let chunk_l1 = StreamChunk::new(
@@ -751,6 +925,7 @@ mod tests {
             None,
             "HashJoinExecutor".to_string(),
             vec![],
+            false,
         );
 
         // push the init barrier for left and right
@@ -955,6 +1130,7 @@ mod tests {
             None,
             "HashJoinExecutor".to_string(),
             vec![],
+            false,
         );
 
         // push the init barrier for left and right
@@ -1201,6 +1377,7 @@ mod tests {
             None,
             "HashJoinExecutor".to_string(),
             vec![],
+            false,
         );
 
         // push the init barrier for left and right
@@ -1451,6 +1628,7 @@ mod tests {
             None,
             "HashJoinExecutor".to_string(),
             vec![],
+            false,
         );
 
         // push the init barrier for left and right
@@ -1651,6 +1829,7 @@ mod tests {
             None,
             "HashJoinExecutor".to_string(),
             vec![],
+            false,
         );
 
         // push the init barrier for left and right
@@ -1906,6 +2085,7 @@ mod tests {
             cond,
             "HashJoinExecutor".to_string(),
             vec![],
+            false,
         );
 
         // push the init barrier for left and right
@@ -2161,6 +2341,7 @@ mod tests {
             cond,
             "HashJoinExecutor".to_string(),
             vec![],
+            false,
         );
 
         // push the init barrier for left and right
@@ -2272,4 +2453,291 @@ mod tests {
             unreachable!();
         }
     }
+
+    #[tokio::test]
+    async fn test_streaming_hash_inner_join_append_only() {
+        let chunk_l1 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [1, 2, 3] },
+                column_nonnull! { I64Array, [4, 5, 6] },
+            ],
+            None,
+        );
+        let chunk_r1 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [2, 4] },
+                column_nonnull! { I64Array, [5, 8] },
+            ],
+            None,
+        );
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+
+        let (mut tx_l, rx_l) = unbounded_channel();
+        let (mut tx_r, rx_r) = unbounded_channel();
+
+        let source_l = MockAsyncSource::with_pk_indices(schema.clone(), rx_l, vec![0, 1]);
+        let source_r = MockAsyncSource::with_pk_indices(schema.clone(), rx_r, vec![0, 1]);
+
+        let keyspace = create_in_memory_keyspace();
+
+        // The join keys contain the pk of both sides.
+        let params_l = JoinParams::new(vec![0, 1]);
+        let params_r = JoinParams::new(vec![0, 1]);
+
+        let mut hash_join = HashJoinExecutor::<_, { JoinType::Inner }>::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            params_l,
+            params_r,
+            vec![],
+            keyspace,
+            1,
+            None,
+            "HashJoinExecutor".to_string(),
+            vec![],
+            true,
+        );
+        assert!(hash_join.append_only_optimize);
+
+        // push the init barrier for left and right
+        MockAsyncSource::push_barrier(&mut tx_l, 1, false);
+        MockAsyncSource::push_barrier(&mut tx_r, 1, false);
+        hash_join.next().await.unwrap();
+
+        // push the 1st left chunk
+        MockAsyncSource::push_chunks(&mut tx_l, vec![chunk_l1]);
+        if let Message::Chunk(chunk) = hash_join.next().await.unwrap() {
+            assert_eq!(chunk.ops().len(), 0);
+        } else {
+            unreachable!();
+        }
+
+        // push the 1st right chunk
+        MockAsyncSource::push_chunks(&mut tx_r, vec![chunk_r1]);
+        if let Message::Chunk(chunk) = hash_join.next().await.unwrap() {
+            assert_eq!(chunk.ops(), vec![Op::Insert]);
+            for (i, expected) in [2, 5, 2, 5].into_iter().enumerate() {
+                assert_eq!(
+                    chunk
+                        .column_at(i)
+                        .array_ref()
+                        .as_int64()
+                        .iter()
+                        .collect_vec(),
+                    vec![Some(expected)]
+                );
+            }
+        } else {
+            unreachable!();
+        }
+
+        let epoch = hash_join.executor_state().epoch();
+        // The matched left row is removed and the matching right row is not stored.
+        assert_eq!(
+            count_rows(&mut hash_join.side_l.ht, &key(2, 5), epoch).await,
+            0
+        );
+        assert_eq!(
+            count_rows(&mut hash_join.side_l.ht, &key(1, 4), epoch).await,
+            1
+        );
+        assert_eq!(
+            count_rows(&mut hash_join.side_r.ht, &key(2, 5), epoch).await,
+            0
+        );
+        assert_eq!(
+            count_rows(&mut hash_join.side_r.ht, &key(4, 8), epoch).await,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_hash_left_join_retract_after_recovery() {
+        let keyspace = create_in_memory_keyspace();
+        let ((mut tx_l, mut tx_r), mut hash_join) =
+            create_executor::<{ JoinType::LeftOuter }>(keyspace.clone(), None);
+        MockAsyncSource::push_barrier(&mut tx_l, 1, false);
+        MockAsyncSource::push_barrier(&mut tx_r, 1, false);
+        hash_join.next().await.unwrap();
+
+        // The left row has no match, so it's padded with nulls.
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![i64_chunk(vec![Op::Insert], vec![1], vec![4])],
+        );
+        assert_chunk(
+            hash_join.next().await.unwrap(),
+            vec![Op::Insert],
+            vec![[Some(1), Some(4), None, None]],
+        );
+
+        // The null-padded row is retracted by its first match.
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![i64_chunk(
+                vec![Op::Insert, Op::Insert],
+                vec![1, 1],
+                vec![7, 8],
+            )],
+        );
+        assert_chunk(
+            hash_join.next().await.unwrap(),
+            vec![Op::UpdateDelete, Op::UpdateInsert, Op::Insert],
+            vec![
+                [Some(1), Some(4), None, None],
+                [Some(1), Some(4), Some(1), Some(7)],
+                [Some(1), Some(4), Some(1), Some(8)],
+            ],
+        );
+
+        MockAsyncSource::push_barrier(&mut tx_l, 2, false);
+        MockAsyncSource::push_barrier(&mut tx_r, 2, false);
+        assert!(matches!(
+            hash_join.next().await.unwrap(),
+            Message::Barrier(_)
+        ));
+
+        // Recover from the state store, where the degrees of the matched rows must be persisted.
+        let ((mut tx_l, mut tx_r), mut hash_join) =
+            create_executor::<{ JoinType::LeftOuter }>(keyspace, None);
+        MockAsyncSource::push_barrier(&mut tx_l, 2, false);
+        MockAsyncSource::push_barrier(&mut tx_r, 2, false);
+        hash_join.next().await.unwrap();
+
+        // The left row is padded with nulls again once its last match is deleted.
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![i64_chunk(
+                vec![Op::Delete, Op::Delete],
+                vec![1, 1],
+                vec![7, 8],
+            )],
+        );
+        assert_chunk(
+            hash_join.next().await.unwrap(),
+            vec![Op::Delete, Op::UpdateDelete, Op::UpdateInsert],
+            vec![
+                [Some(1), Some(4), Some(1), Some(7)],
+                [Some(1), Some(4), Some(1), Some(8)],
+                [Some(1), Some(4), None, None],
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_hash_right_join_with_nonequi_condition_retract() {
+        let ((mut tx_l, mut tx_r), mut hash_join) =
+            create_executor::<{ JoinType::RightOuter }>(create_in_memory_keyspace(), create_cond());
+        MockAsyncSource::push_barrier(&mut tx_l, 1, false);
+        MockAsyncSource::push_barrier(&mut tx_r, 1, false);
+        hash_join.next().await.unwrap();
+
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![i64_chunk(
+                vec![Op::Insert, Op::Insert],
+                vec![1, 1],
+                vec![4, 5],
+            )],
+        );
+        assert_chunk(hash_join.next().await.unwrap(), vec![], vec![]);
+
+        // None of the left rows satisfies the condition, so the right row is padded only once.
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![i64_chunk(vec![Op::Insert], vec![1], vec![3])],
+        );
+        assert_chunk(
+            hash_join.next().await.unwrap(),
+            vec![Op::Insert],
+            vec![[None, None, Some(1), Some(3)]],
+        );
+
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![i64_chunk(vec![Op::Insert], vec![1], vec![2])],
+        );
+        assert_chunk(
+            hash_join.next().await.unwrap(),
+            vec![Op::UpdateDelete, Op::UpdateInsert],
+            vec![
+                [None, None, Some(1), Some(3)],
+                [Some(1), Some(2), Some(1), Some(3)],
+            ],
+        );
+
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![i64_chunk(vec![Op::Delete], vec![1], vec![2])],
+        );
+        assert_chunk(
+            hash_join.next().await.unwrap(),
+            vec![Op::UpdateDelete, Op::UpdateInsert],
+            vec![
+                [Some(1), Some(2), Some(1), Some(3)],
+                [None, None, Some(1), Some(3)],
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_hash_full_outer_join_with_nonequi_condition_retract() {
+        let ((mut tx_l, mut tx_r), mut hash_join) =
+            create_executor::<{ JoinType::FullOuter }>(create_in_memory_keyspace(), create_cond());
+        MockAsyncSource::push_barrier(&mut tx_l, 1, false);
+        MockAsyncSource::push_barrier(&mut tx_r, 1, false);
+        hash_join.next().await.unwrap();
+
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![i64_chunk(
+                vec![Op::Insert, Op::Insert],
+                vec![1, 1],
+                vec![4, 5],
+            )],
+        );
+        assert_chunk(
+            hash_join.next().await.unwrap(),
+            vec![Op::Insert, Op::Insert],
+            vec![
+                [Some(1), Some(4), None, None],
+                [Some(1), Some(5), None, None],
+            ],
+        );
+
+        // The right row only satisfies the condition with `(1, 4)`, so `(1, 5)` stays padded.
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![i64_chunk(vec![Op::Insert], vec![1], vec![5])],
+        );
+        assert_chunk(
+            hash_join.next().await.unwrap(),
+            vec![Op::UpdateDelete, Op::UpdateInsert],
+            vec![
+                [Some(1), Some(4), None, None],
+                [Some(1), Some(4), Some(1), Some(5)],
+            ],
+        );
+
+        // Both sides lose their only match when the right row is deleted.
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![i64_chunk(vec![Op::Delete], vec![1], vec![5])],
+        );
+        assert_chunk(
+            hash_join.next().await.unwrap(),
+            vec![Op::UpdateDelete, Op::UpdateInsert],
+            vec![
+                [Some(1), Some(4), Some(1), Some(5)],
+                [Some(1), Some(4), None, None],
+            ],
+        );
+    }
 }
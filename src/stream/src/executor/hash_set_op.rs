@@ -0,0 +1,489 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::Result;
+use risingwave_common::try_match_expand;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::value_encoding::{deserialize_cell, serialize_cell};
+use risingwave_pb::plan::SetOpType;
+use risingwave_pb::stream_plan;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::barrier_align::{AlignedMessage, BarrierAligner};
+use super::{Executor, ExecutorState, Message, PkIndices, PkIndicesRef, StatefulExecutor};
+use crate::executor::ExecutorBuilder;
+use crate::task::{ExecutorParams, LocalStreamManagerCore};
+
+pub struct HashSetOpExecutorBuilder {}
+
+impl ExecutorBuilder for HashSetOpExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &stream_plan::StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<Box<dyn Executor>> {
+        let node = try_match_expand!(node.get_node().unwrap(), Node::HashSetOpNode)?;
+        let source_r = params.input.remove(1);
+        let source_l = params.input.remove(0);
+        let key_indices = node
+            .get_distribution_keys()
+            .iter()
+            .map(|key| *key as usize)
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(HashSetOpExecutor::new(
+            source_l,
+            source_r,
+            node.get_set_op_type()?,
+            params.pk_indices,
+            Keyspace::shared_executor_root(store, params.operator_id),
+            params.executor_id,
+            params.op_info,
+            key_indices,
+        )))
+    }
+}
+
+/// The number of occurrences of a row on each side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct RowCount {
+    left: i64,
+    right: i64,
+}
+
+impl RowCount {
+    /// Whether the row is in the output of `set_op`.
+    fn is_visible(&self, set_op: SetOpType) -> bool {
+        match set_op {
+            SetOpType::Intersect => self.left > 0 && self.right > 0,
+            SetOpType::Except => self.left > 0 && self.right == 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.left == 0 && self.right == 0
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>> {
+        Ok([
+            serialize_cell(&Some(ScalarImpl::Int64(self.left)))?,
+            serialize_cell(&Some(ScalarImpl::Int64(self.right)))?,
+        ]
+        .concat())
+    }
+
+    fn deserialize(data: bytes::Bytes) -> Result<Self> {
+        let mut deserializer = value_encoding::Deserializer::new(data);
+        let mut next = || -> Result<i64> {
+            Ok(deserialize_cell(&mut deserializer, &DataType::Int64)?
+                .map(|v| v.into_int64())
+                .unwrap_or_default())
+        };
+        Ok(Self {
+            left: next()?,
+            right: next()?,
+        })
+    }
+}
+
+/// `HashSetOpExecutor` takes two input streams and runs `INTERSECT` or `EXCEPT` on them with set
+/// semantics. The output columns are the same as the left input.
+///
+/// The number of occurrences of every row on both sides is maintained, so the executor can tell
+/// when a row starts or stops satisfying the set operation, and emits an `Insert` or a `Delete`
+/// for it accordingly. Retractions from both inputs are handled in the same way.
+pub struct HashSetOpExecutor<S: StateStore> {
+    /// Barrier aligner that combines two input streams and aligns their barriers
+    aligner: BarrierAligner,
+    /// The schema of the executor, same as the left input
+    schema: Schema,
+    /// The primary key indices of the schema
+    pk_indices: PkIndices,
+    set_op: SetOpType,
+    /// The counts of rows are stored in this keyspace, keyed by the serialized rows.
+    keyspace: Keyspace<S>,
+    /// The counts of rows fetched from the state store or updated since the last flush.
+    cache: HashMap<Row, RowCount>,
+    /// The rows whose counts should be written to the state store on the next flush.
+    dirty: HashSet<Row>,
+    /// Debug info for the left executor
+    debug_l: String,
+    /// Debug info for the right executor
+    debug_r: String,
+    /// Identity string
+    identity: String,
+    /// Logical Operator Info
+    op_info: String,
+    /// Executor state
+    executor_state: ExecutorState,
+
+    #[allow(dead_code)]
+    /// Indices of the columns on which key distribution depends.
+    key_indices: Vec<usize>,
+}
+
+impl<S: StateStore> std::fmt::Debug for HashSetOpExecutor<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HashSetOpExecutor")
+            .field("set_op", &self.set_op)
+            .field("input_left", &format_args!("{}", &self.debug_l))
+            .field("input_right", &format_args!("{}", &self.debug_r))
+            .field("pk_indices", &self.pk_indices)
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S: StateStore> Executor for HashSetOpExecutor<S> {
+    async fn next(&mut self) -> Result<Message> {
+        let msg = self.aligner.next().await;
+        if let Some(barrier) = self.try_init_executor(&msg) {
+            return Ok(Message::Barrier(barrier));
+        }
+        match msg {
+            AlignedMessage::Left(message) => self.consume_chunk(message?, true).await,
+            AlignedMessage::Right(message) => self.consume_chunk(message?, false).await,
+            AlignedMessage::Barrier(barrier) => {
+                self.flush_data().await?;
+                self.update_executor_state(ExecutorState::Active(barrier.epoch.curr));
+                Ok(Message::Barrier(barrier))
+            }
+            // The output columns are the same as both inputs.
+            AlignedMessage::Watermark(watermark) => Ok(Message::Watermark(watermark)),
+        }
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        self.identity.as_str()
+    }
+
+    fn logical_operator_info(&self) -> &str {
+        &self.op_info
+    }
+
+    fn clear_cache(&mut self) -> Result<()> {
+        assert!(
+            self.dirty.is_empty(),
+            "cannot clear cache while states of hash set op are dirty"
+        );
+        self.cache.clear();
+        Ok(())
+    }
+}
+
+impl<S: StateStore> HashSetOpExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input_l: Box<dyn Executor>,
+        input_r: Box<dyn Executor>,
+        set_op: SetOpType,
+        pk_indices: PkIndices,
+        keyspace: Keyspace<S>,
+        executor_id: u64,
+        op_info: String,
+        key_indices: Vec<usize>,
+    ) -> Self {
+        let debug_l = format!("{:#?}", &input_l);
+        let debug_r = format!("{:#?}", &input_r);
+        assert_eq!(input_l.schema().data_types(), input_r.schema().data_types());
+        let schema = input_l.schema().clone();
+
+        Self {
+            aligner: BarrierAligner::new(input_l, input_r),
+            schema,
+            pk_indices,
+            set_op,
+            keyspace,
+            cache: HashMap::new(),
+            dirty: HashSet::new(),
+            debug_l,
+            debug_r,
+            identity: format!("HashSetOpExecutor {:X}", executor_id),
+            op_info,
+            executor_state: ExecutorState::Init,
+            key_indices,
+        }
+    }
+
+    async fn get_count(&mut self, row: &Row, epoch: u64) -> Result<RowCount> {
+        if let Some(count) = self.cache.get(row) {
+            return Ok(*count);
+        }
+        let count = match self.keyspace.get(row.serialize()?, epoch).await? {
+            Some(data) => RowCount::deserialize(data)?,
+            None => RowCount::default(),
+        };
+        self.cache.insert(row.clone(), count);
+        Ok(count)
+    }
+
+    async fn consume_chunk(&mut self, chunk: StreamChunk, is_left: bool) -> Result<Message> {
+        let epoch = self.executor_state().epoch();
+        let (data_chunk, ops) = chunk.compact()?.into_parts();
+
+        let mut output = vec![];
+        for (row, op) in data_chunk.rows().zip_eq(ops.iter()) {
+            let row: Row = row.into();
+            let mut count = self.get_count(&row, epoch).await?;
+            let was_visible = count.is_visible(self.set_op);
+
+            let delta = match op {
+                Op::Insert | Op::UpdateInsert => 1,
+                Op::Delete | Op::UpdateDelete => -1,
+            };
+            if is_left {
+                count.left += delta;
+            } else {
+                count.right += delta;
+            }
+            assert!(count.left >= 0 && count.right >= 0, "row count underflow");
+
+            match (was_visible, count.is_visible(self.set_op)) {
+                (false, true) => output.push((Op::Insert, row.clone())),
+                (true, false) => output.push((Op::Delete, row.clone())),
+                _ => {}
+            }
+            self.cache.insert(row.clone(), count);
+            self.dirty.insert(row);
+        }
+
+        let chunk = StreamChunk::from_rows(&output, &self.schema.data_types())?;
+        Ok(Message::Chunk(chunk))
+    }
+
+    async fn flush_data(&mut self) -> Result<()> {
+        let epoch = self.executor_state().epoch();
+        let mut write_batch = self.keyspace.state_store().start_write_batch();
+        {
+            let mut local = write_batch.prefixify(&self.keyspace);
+            for row in self.dirty.drain() {
+                let count = self.cache[&row];
+                if count.is_empty() {
+                    local.delete(row.serialize()?);
+                    self.cache.remove(&row);
+                } else {
+                    local.put(
+                        row.serialize()?,
+                        StorageValue::new_default_put(count.serialize()?),
+                    );
+                }
+            }
+        }
+        write_batch.ingest(epoch).await?;
+        Ok(())
+    }
+}
+
+impl<S: StateStore> StatefulExecutor for HashSetOpExecutor<S> {
+    fn executor_state(&self) -> &ExecutorState {
+        &self.executor_state
+    }
+
+    fn update_executor_state(&mut self, new_state: ExecutorState) {
+        self.executor_state = new_state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::*;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::column_nonnull;
+    use risingwave_storage::memory::MemoryStateStore;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+    use super::*;
+    use crate::executor::test_utils::{create_in_memory_keyspace, MockAsyncSource};
+    use crate::executor::Watermark;
+
+    fn create_executor(
+        set_op: SetOpType,
+    ) -> (
+        UnboundedSender<Message>,
+        UnboundedSender<Message>,
+        HashSetOpExecutor<MemoryStateStore>,
+    ) {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let (tx_l, rx_l) = unbounded_channel();
+        let (tx_r, rx_r) = unbounded_channel();
+        let source_l = MockAsyncSource::with_pk_indices(schema.clone(), rx_l, vec![0]);
+        let source_r = MockAsyncSource::with_pk_indices(schema, rx_r, vec![0]);
+        let executor = HashSetOpExecutor::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            set_op,
+            vec![0],
+            create_in_memory_keyspace(),
+            1,
+            "HashSetOpExecutor".to_string(),
+            vec![0],
+        );
+        (tx_l, tx_r, executor)
+    }
+
+    async fn next_chunk(executor: &mut impl Executor) -> Vec<(Op, Option<i64>)> {
+        match executor.next().await.unwrap() {
+            Message::Chunk(chunk) => chunk
+                .ops()
+                .iter()
+                .cloned()
+                .zip_eq(chunk.column_at(0).array_ref().as_int64().iter())
+                .collect(),
+            Message::Barrier(_) | Message::Watermark(_) => unreachable!(),
+        }
+    }
+
+    async fn barrier(
+        tx_l: &mut UnboundedSender<Message>,
+        tx_r: &mut UnboundedSender<Message>,
+        executor: &mut impl Executor,
+        epoch: u64,
+    ) {
+        MockAsyncSource::push_barrier(tx_l, epoch, false);
+        MockAsyncSource::push_barrier(tx_r, epoch, false);
+        assert!(matches!(
+            executor.next().await.unwrap(),
+            Message::Barrier(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_hash_intersect() {
+        let (mut tx_l, mut tx_r, mut executor) = create_executor(SetOpType::Intersect);
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 1).await;
+
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![StreamChunk::new(
+                vec![Op::Insert, Op::Insert, Op::Insert],
+                vec![column_nonnull! { I64Array, [1, 2, 2] }],
+                None,
+            )],
+        );
+        assert_eq!(next_chunk(&mut executor).await, vec![]);
+
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![StreamChunk::new(
+                vec![Op::Insert, Op::Insert, Op::Insert],
+                vec![column_nonnull! { I64Array, [2, 3, 2] }],
+                None,
+            )],
+        );
+        assert_eq!(next_chunk(&mut executor).await, vec![(Op::Insert, Some(2))]);
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 2).await;
+
+        // The row is still visible until all of its occurrences on one side are deleted.
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![StreamChunk::new(
+                vec![Op::Delete, Op::Delete, Op::Insert],
+                vec![column_nonnull! { I64Array, [2, 2, 3] }],
+                None,
+            )],
+        );
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![(Op::Delete, Some(2)), (Op::Insert, Some(3))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_hash_except() {
+        let (mut tx_l, mut tx_r, mut executor) = create_executor(SetOpType::Except);
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 1).await;
+
+        MockAsyncSource::push_chunks(
+            &mut tx_l,
+            vec![StreamChunk::new(
+                vec![Op::Insert, Op::Insert, Op::Insert],
+                vec![column_nonnull! { I64Array, [1, 2, 2] }],
+                None,
+            )],
+        );
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![(Op::Insert, Some(1)), (Op::Insert, Some(2))]
+        );
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 2).await;
+
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![StreamChunk::new(
+                vec![Op::Insert, Op::Insert],
+                vec![column_nonnull! { I64Array, [2, 3] }],
+                None,
+            )],
+        );
+        assert_eq!(next_chunk(&mut executor).await, vec![(Op::Delete, Some(2))]);
+
+        MockAsyncSource::push_chunks(
+            &mut tx_r,
+            vec![StreamChunk::new(
+                vec![Op::Delete],
+                vec![column_nonnull! { I64Array, [2] }],
+                None,
+            )],
+        );
+        assert_eq!(next_chunk(&mut executor).await, vec![(Op::Insert, Some(2))]);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_hash_set_op_watermark() {
+        let (mut tx_l, mut tx_r, mut executor) = create_executor(SetOpType::Intersect);
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 1).await;
+
+        let watermark = |val| Watermark::new(0, DataType::Int64, ScalarImpl::Int64(val));
+
+        // The watermark is held back until both inputs reach it.
+        MockAsyncSource::push_watermark(&mut tx_l, 0, DataType::Int64, ScalarImpl::Int64(10));
+        MockAsyncSource::push_watermark(&mut tx_r, 0, DataType::Int64, ScalarImpl::Int64(5));
+        assert!(matches!(
+            executor.next().await.unwrap(),
+            Message::Watermark(w) if w == watermark(5)
+        ));
+
+        // The aligned watermark is the minimum of both inputs, and never regresses.
+        MockAsyncSource::push_watermark(&mut tx_r, 0, DataType::Int64, ScalarImpl::Int64(20));
+        assert!(matches!(
+            executor.next().await.unwrap(),
+            Message::Watermark(w) if w == watermark(10)
+        ));
+        MockAsyncSource::push_watermark(&mut tx_l, 0, DataType::Int64, ScalarImpl::Int64(30));
+        assert!(matches!(
+            executor.next().await.unwrap(),
+            Message::Watermark(w) if w == watermark(20)
+        ));
+    }
+}
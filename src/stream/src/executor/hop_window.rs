@@ -0,0 +1,65 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::Result;
+use risingwave_common::try_match_expand;
+use risingwave_common::types::{DataType, IntervalUnit};
+use risingwave_pb::stream_plan;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_storage::StateStore;
+
+use super::Executor;
+use crate::executor::ExecutorBuilder;
+use crate::executor_v2::{
+    Executor as ExecutorV2, ExecutorInfo, ExecutorV1AsV2, HopWindowExecutor as HopWindowExecutorV2,
+};
+use crate::task::{ExecutorParams, LocalStreamManagerCore};
+
+pub struct HopWindowExecutorBuilder;
+
+impl ExecutorBuilder for HopWindowExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &stream_plan::StreamNode,
+        _store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<Box<dyn Executor>> {
+        let node = try_match_expand!(node.get_node().unwrap(), Node::HopWindowNode)?;
+        let input = params.input.remove(0);
+        let time_col_idx = node.get_time_col()?.column_idx as usize;
+        let window_slide = IntervalUnit::from(node.get_window_slide()?);
+        let window_size = IntervalUnit::from(node.get_window_size()?);
+
+        let mut fields = input.schema().fields.clone();
+        fields.extend([
+            Field::with_name(DataType::Timestamp, "window_start"),
+            Field::with_name(DataType::Timestamp, "window_end"),
+        ]);
+
+        Ok(Box::new(
+            Box::new(HopWindowExecutorV2::new(
+                Box::new(ExecutorV1AsV2(input)),
+                ExecutorInfo {
+                    schema: Schema::new(fields),
+                    pk_indices: params.pk_indices,
+                    identity: format!("HopWindowExecutor {:X}", params.executor_id),
+                },
+                time_col_idx,
+                window_slide,
+                window_size,
+            ))
+            .v1(),
+        ))
+    }
+}
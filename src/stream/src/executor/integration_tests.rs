@@ -56,6 +56,7 @@ impl StreamConsumer for MockConsumer {
         match self.input.next().await? {
             Message::Chunk(chunk) => self.data.lock().unwrap().push(chunk),
             Message::Barrier(barrier) => return Ok(Some(barrier)),
+            Message::Watermark(_) => {}
         }
         Ok(None)
     }
@@ -81,11 +82,15 @@ async fn test_merger_sum_aggr() {
                         kind: AggKind::RowCount,
                         args: AggArgs::None,
                         return_type: DataType::Int64,
+                        order_pairs: vec![],
+                        distinct: false,
                     },
                     AggCall {
                         kind: AggKind::Sum,
                         args: AggArgs::Unary(DataType::Int64, 0),
                         return_type: DataType::Int64,
+                        order_pairs: vec![],
+                        distinct: false,
                     },
                 ],
                 vec![],
@@ -149,11 +154,15 @@ async fn test_merger_sum_aggr() {
                     kind: AggKind::Sum,
                     args: AggArgs::Unary(DataType::Int64, 0),
                     return_type: DataType::Int64,
+                    order_pairs: vec![],
+                    distinct: false,
                 },
                 AggCall {
                     kind: AggKind::Sum,
                     args: AggArgs::Unary(DataType::Int64, 1),
                     return_type: DataType::Int64,
+                    order_pairs: vec![],
+                    distinct: false,
                 },
             ],
             create_in_memory_keyspace(),
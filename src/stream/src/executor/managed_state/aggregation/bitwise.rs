@@ -0,0 +1,372 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use bytes::{Buf, BufMut};
+use risingwave_common::array::stream_chunk::{Op, Ops};
+use risingwave_common::array::ArrayImpl;
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::Result;
+use risingwave_common::types::{BitString, DataType, Datum, ScalarImpl, ScalarRefImpl};
+use risingwave_expr::expr::AggKind;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::write_batch::WriteBatch;
+use risingwave_storage::{Keyspace, StateStore};
+
+use crate::executor::managed_state::aggregation::ManagedTableState;
+use crate::executor_v2::aggregation::{AggArgs, AggCall};
+
+/// [`ManagedBitwiseState`] keeps, for each bit position, how many non-null input values of a group
+/// have the bit set. Unlike `&` and `|` themselves, the counters can be decremented when a value is
+/// retracted, so the result never needs to be rebuilt from the input rows. The state is stored as a
+/// single value in its keyspace.
+///
+/// A bit of `bit_or` is set if any value has it set, and a bit of `bit_and` is set if all values
+/// have it set. Bits are indexed from the least significant one for integers, and from the leftmost
+/// one for bit strings.
+pub struct ManagedBitwiseState<S: StateStore> {
+    agg_kind: AggKind,
+
+    value_type: DataType,
+
+    /// Number of non-null values in the state.
+    non_null_count: u64,
+
+    /// Number of values with each bit set. For bit strings, it is empty until the first value
+    /// decides the length.
+    bit_counts: Vec<u64>,
+
+    /// Marks whether there are modifications, i.e. memory != storage
+    dirty: bool,
+
+    /// The keyspace to operate on.
+    keyspace: Keyspace<S>,
+}
+
+impl<S: StateStore> ManagedBitwiseState<S> {
+    /// Create a managed bitwise state based on `Keyspace`. The counters are loaded from the state
+    /// store unless the group is known to be empty.
+    pub async fn new(
+        agg_kind: AggKind,
+        keyspace: Keyspace<S>,
+        row_count: usize,
+        value_type: DataType,
+    ) -> Result<Self> {
+        let mut state = Self {
+            agg_kind,
+            value_type,
+            non_null_count: 0,
+            bit_counts: vec![],
+            dirty: false,
+            keyspace,
+        };
+        if row_count != 0 {
+            // TODO: use the correct epoch
+            if let Some(mut raw_data) = state.keyspace.value(u64::MAX).await? {
+                state.non_null_count = raw_data.get_u64();
+                let width = raw_data.get_u32() as usize;
+                state.bit_counts = (0..width).map(|_| raw_data.get_u64()).collect();
+            }
+        }
+        if state.non_null_count == 0 {
+            state.reset_width();
+        }
+        Ok(state)
+    }
+
+    /// Resets the counters of an empty state. Integers always have all their bits counted, while
+    /// bit strings take the length of the first value after the state becomes empty.
+    fn reset_width(&mut self) {
+        debug_assert_eq!(self.non_null_count, 0);
+        let width = match self.value_type {
+            DataType::Int16 => 16,
+            DataType::Int32 => 32,
+            DataType::Int64 => 64,
+            _ => 0,
+        };
+        self.bit_counts = vec![0; width];
+    }
+
+    /// Adds `delta` to the counter of each bit set in `value`.
+    fn update_counts(&mut self, value: ScalarRefImpl<'_>, delta: i64) -> Result<()> {
+        let update = |count: &mut u64| *count = count.wrapping_add(delta as u64);
+        match value {
+            ScalarRefImpl::Int16(v) => self.update_int_counts(v as i64, update),
+            ScalarRefImpl::Int32(v) => self.update_int_counts(v as i64, update),
+            ScalarRefImpl::Int64(v) => self.update_int_counts(v, update),
+            ScalarRefImpl::BitString(v) => {
+                if self.bit_counts.is_empty() {
+                    self.bit_counts = vec![0; v.len()];
+                } else if self.bit_counts.len() != v.len() {
+                    return Err(InternalError(format!(
+                        "cannot {} bit strings of different sizes",
+                        self.agg_kind
+                    ))
+                    .into());
+                }
+                for (count, bit) in self.bit_counts.iter_mut().zip(v.iter()) {
+                    if bit {
+                        update(count);
+                    }
+                }
+            }
+            _ => {
+                return Err(InternalError(format!(
+                    "unsupported input of {}: {:?}",
+                    self.agg_kind, value
+                ))
+                .into())
+            }
+        }
+        self.non_null_count = self.non_null_count.wrapping_add(delta as u64);
+        Ok(())
+    }
+
+    fn update_int_counts(&mut self, value: i64, update: impl Fn(&mut u64)) {
+        for (idx, count) in self.bit_counts.iter_mut().enumerate() {
+            if (value >> idx) & 1 != 0 {
+                update(count);
+            }
+        }
+    }
+
+    /// Whether each bit of the result is set.
+    fn result_bits(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bit_counts.iter().map(|count| match self.agg_kind {
+            AggKind::BitAnd => *count == self.non_null_count,
+            _ => *count > 0,
+        })
+    }
+}
+
+#[async_trait]
+impl<S: StateStore> ManagedTableState<S> for ManagedBitwiseState<S> {
+    async fn apply_batch(
+        &mut self,
+        ops: Ops<'_>,
+        visibility: Option<&Bitmap>,
+        data: &[&ArrayImpl],
+        _epoch: u64,
+    ) -> Result<()> {
+        debug_assert!(super::verify_batch(ops, visibility, data));
+
+        for (row_idx, op) in ops.iter().enumerate() {
+            let visible = visibility
+                .map(|x| x.is_set(row_idx).unwrap())
+                .unwrap_or(true);
+            if !visible {
+                continue;
+            }
+            if let Some(value) = data[0].value_at(row_idx) {
+                let delta = match op {
+                    Op::Insert | Op::UpdateInsert => 1,
+                    Op::Delete | Op::UpdateDelete => -1,
+                };
+                self.update_counts(value, delta)?;
+                if self.non_null_count == 0 {
+                    self.reset_width();
+                }
+                self.dirty = true;
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_output(&mut self, _epoch: u64) -> Result<Datum> {
+        if self.non_null_count == 0 {
+            return Ok(None);
+        }
+        let mask = || {
+            self.result_bits()
+                .enumerate()
+                .fold(0u64, |mask, (idx, bit)| mask | ((bit as u64) << idx))
+        };
+        let result = match self.value_type {
+            DataType::Int16 => ScalarImpl::Int16(mask() as i16),
+            DataType::Int32 => ScalarImpl::Int32(mask() as i32),
+            DataType::Int64 => ScalarImpl::Int64(mask() as i64),
+            _ => ScalarImpl::BitString(self.result_bits().collect::<BitString>()),
+        };
+        Ok(Some(result))
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn flush(&mut self, write_batch: &mut WriteBatch<S>) -> Result<()> {
+        if !self.is_dirty() {
+            return Ok(());
+        }
+
+        let mut bytes = Vec::with_capacity(12 + self.bit_counts.len() * 8);
+        bytes.put_u64(self.non_null_count);
+        bytes.put_u32(self.bit_counts.len() as u32);
+        for count in &self.bit_counts {
+            bytes.put_u64(*count);
+        }
+        let mut local = write_batch.prefixify(&self.keyspace);
+        local.put_single(StorageValue::new_default_put(bytes));
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Create a managed bitwise state for `agg_call`. The input arrays are expected to be laid out as
+/// `[value, ...]`.
+pub async fn create_streaming_bitwise_state<S: StateStore>(
+    agg_call: AggCall,
+    keyspace: Keyspace<S>,
+    row_count: usize,
+) -> Result<Box<dyn ManagedTableState<S>>> {
+    let value_type = match &agg_call.args {
+        AggArgs::Unary(value_type, _) => value_type.clone(),
+        _ => panic!("bitwise state should have a single input: {:?}", agg_call),
+    };
+    Ok(Box::new(
+        ManagedBitwiseState::new(agg_call.kind, keyspace, row_count, value_type).await?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{BitStringArray, I16Array, Op};
+    use risingwave_common::types::Scalar;
+
+    use super::*;
+    use crate::executor::test_utils::create_in_memory_keyspace;
+
+    #[tokio::test]
+    async fn test_managed_bitwise_state() {
+        let keyspace = create_in_memory_keyspace();
+        let store = keyspace.state_store();
+        let mut bit_and = ManagedBitwiseState::new(
+            AggKind::BitAnd,
+            keyspace.append_u8(b'a'),
+            0,
+            DataType::Int16,
+        )
+        .await
+        .unwrap();
+        let mut bit_or =
+            ManagedBitwiseState::new(AggKind::BitOr, keyspace.append_u8(b'o'), 0, DataType::Int16)
+                .await
+                .unwrap();
+        let epoch: u64 = 0;
+
+        let array = I16Array::from_slice(&[Some(0b1101), None, Some(0b0111), Some(-1)])
+            .unwrap()
+            .into();
+        for state in [&mut bit_and, &mut bit_or] {
+            state
+                .apply_batch(&[Op::Insert; 4], None, &[&array], epoch)
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            bit_and.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int16(0b0101))
+        );
+        assert_eq!(
+            bit_or.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int16(-1))
+        );
+
+        let mut write_batch = store.start_write_batch();
+        bit_and.flush(&mut write_batch).unwrap();
+        bit_or.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+
+        // Recover the states and retract the last value.
+        let mut bit_and = ManagedBitwiseState::new(
+            AggKind::BitAnd,
+            keyspace.append_u8(b'a'),
+            4,
+            DataType::Int16,
+        )
+        .await
+        .unwrap();
+        let mut bit_or =
+            ManagedBitwiseState::new(AggKind::BitOr, keyspace.append_u8(b'o'), 4, DataType::Int16)
+                .await
+                .unwrap();
+        let array = I16Array::from_slice(&[Some(-1)]).unwrap().into();
+        for state in [&mut bit_and, &mut bit_or] {
+            state
+                .apply_batch(&[Op::Delete], None, &[&array], epoch)
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            bit_and.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int16(0b0101))
+        );
+        assert_eq!(
+            bit_or.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int16(0b1111))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_managed_bitwise_bit_string_state() {
+        let keyspace = create_in_memory_keyspace();
+        let mut state = ManagedBitwiseState::new(AggKind::BitOr, keyspace, 0, DataType::Varbit)
+            .await
+            .unwrap();
+        let epoch: u64 = 0;
+
+        let values = ["1100", "1010", "1"]
+            .map(|s| s.parse::<BitString>().unwrap())
+            .to_vec();
+        let array = BitStringArray::from_slice(&[
+            Some(values[0].as_scalar_ref()),
+            Some(values[1].as_scalar_ref()),
+        ])
+        .unwrap()
+        .into();
+        state
+            .apply_batch(&[Op::Insert, Op::Insert], None, &[&array], epoch)
+            .await
+            .unwrap();
+        assert_eq!(
+            state.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::BitString("1110".parse().unwrap()))
+        );
+
+        // Bit strings of different lengths cannot be combined.
+        let short = BitStringArray::from_slice(&[Some(values[2].as_scalar_ref())])
+            .unwrap()
+            .into();
+        assert!(state
+            .apply_batch(&[Op::Insert], None, &[&short], epoch)
+            .await
+            .is_err());
+
+        // Once all values are retracted, a value of another length can be accepted.
+        state
+            .apply_batch(&[Op::Delete, Op::Delete], None, &[&array], epoch)
+            .await
+            .unwrap();
+        assert_eq!(state.get_output(epoch).await.unwrap(), None);
+        state
+            .apply_batch(&[Op::Insert], None, &[&short], epoch)
+            .await
+            .unwrap();
+        assert_eq!(
+            state.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::BitString("1".parse().unwrap()))
+        );
+    }
+}
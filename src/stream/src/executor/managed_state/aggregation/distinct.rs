@@ -0,0 +1,182 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use risingwave_common::array::stream_chunk::Ops;
+use risingwave_common::array::{ArrayImpl, Op, Row};
+use risingwave_common::buffer::{Bitmap, BitmapBuilder};
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::value_encoding::{deserialize_cell, serialize_cell};
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::write_batch::WriteBatch;
+use risingwave_storage::{Keyspace, StateStore};
+
+/// Deduplicates the arguments of a `DISTINCT` aggregation, e.g. `count(DISTINCT a)`, for one
+/// group. The number of occurrences of each distinct argument value is maintained in the state
+/// store, keyed by the serialized value under the keyspace of the group.
+///
+/// Only the first insertion and the last deletion of a value are passed to the aggregation state,
+/// so the aggregation sees every distinct value exactly once, under both inserts and deletes.
+pub struct ManagedDistinctState<S: StateStore> {
+    /// The keyspace to operate on.
+    keyspace: Keyspace<S>,
+
+    /// The number of argument columns at the front of the input data.
+    arg_count: usize,
+
+    /// The counts of values fetched from the state store or updated since the last flush.
+    cache: HashMap<Row, i64>,
+
+    /// The values whose counts should be written to the state store on the next flush.
+    dirty: HashSet<Row>,
+}
+
+impl<S: StateStore> ManagedDistinctState<S> {
+    pub fn new(keyspace: Keyspace<S>, arg_count: usize) -> Self {
+        Self {
+            keyspace,
+            arg_count,
+            cache: HashMap::new(),
+            dirty: HashSet::new(),
+        }
+    }
+
+    async fn get_count(&mut self, value: &Row, epoch: u64) -> Result<i64> {
+        if let Some(count) = self.cache.get(value) {
+            return Ok(*count);
+        }
+        let count = match self.keyspace.get(value.serialize()?, epoch).await? {
+            Some(raw_data) => {
+                let mut deserializer = value_encoding::Deserializer::new(raw_data);
+                deserialize_cell(&mut deserializer, &DataType::Int64)?
+                    .map(|v| v.into_int64())
+                    .unwrap_or_default()
+            }
+            None => 0,
+        };
+        self.cache.insert(value.clone(), count);
+        Ok(count)
+    }
+
+    /// Apply a batch of data to the state, and return the visibility of the rows that should be
+    /// passed to the aggregation state. `data` starts with the argument columns.
+    pub async fn apply_batch(
+        &mut self,
+        ops: Ops<'_>,
+        visibility: Option<&Bitmap>,
+        data: &[&ArrayImpl],
+        epoch: u64,
+    ) -> Result<Bitmap> {
+        debug_assert!(super::verify_batch(ops, visibility, data));
+        let mut distinct_visibility = BitmapBuilder::with_capacity(ops.len());
+        for (row_idx, op) in ops.iter().enumerate() {
+            if let Some(visibility) = visibility && !visibility.is_set(row_idx)? {
+                distinct_visibility.append(false);
+                continue;
+            }
+            let value = Row(data[..self.arg_count]
+                .iter()
+                .map(|array| array.datum_at(row_idx))
+                .collect());
+            let count = self.get_count(&value, epoch).await?;
+            let (new_count, visible) = match op {
+                Op::Insert | Op::UpdateInsert => (count + 1, count == 0),
+                Op::Delete | Op::UpdateDelete => (count - 1, count == 1),
+            };
+            assert!(new_count >= 0, "distinct value count underflow");
+            distinct_visibility.append(visible);
+            self.cache.insert(value.clone(), new_count);
+            self.dirty.insert(value);
+        }
+        Ok(distinct_visibility.finish())
+    }
+
+    /// Check if this state needs a flush.
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Flush the internal state to a write batch.
+    pub fn flush(&mut self, write_batch: &mut WriteBatch<S>) -> Result<()> {
+        let mut local = write_batch.prefixify(&self.keyspace);
+        for value in self.dirty.drain() {
+            let count = self.cache[&value];
+            if count == 0 {
+                local.delete(value.serialize()?);
+                self.cache.remove(&value);
+            } else {
+                local.put(
+                    value.serialize()?,
+                    StorageValue::new_default_put(serialize_cell(&Some(ScalarImpl::Int64(count)))?),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::I64Array;
+    use risingwave_common::array_nonnull;
+
+    use super::*;
+    use crate::executor::test_utils::create_in_memory_keyspace;
+
+    #[tokio::test]
+    async fn test_managed_distinct_state() {
+        let keyspace = create_in_memory_keyspace();
+        let store = keyspace.state_store();
+        let mut state = ManagedDistinctState::new(keyspace.clone(), 1);
+        let epoch: u64 = 0;
+
+        let visibility = state
+            .apply_batch(
+                &[Op::Insert, Op::Insert, Op::Insert, Op::Insert],
+                None,
+                &[&array_nonnull!(I64Array, [1, 2, 1, 3]).into()],
+                epoch,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            visibility.iter().collect::<Vec<_>>(),
+            vec![true, true, false, true]
+        );
+
+        let mut write_batch = store.start_write_batch();
+        state.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+        assert!(!state.is_dirty());
+
+        // The counts are read back from the state store.
+        let epoch = epoch + 1;
+        let mut state = ManagedDistinctState::new(keyspace, 1);
+        let visibility = state
+            .apply_batch(
+                &[Op::Delete, Op::Delete, Op::Delete, Op::Insert],
+                None,
+                &[&array_nonnull!(I64Array, [1, 2, 1, 2]).into()],
+                epoch,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            visibility.iter().collect::<Vec<_>>(),
+            vec![false, true, true, true]
+        );
+    }
+}
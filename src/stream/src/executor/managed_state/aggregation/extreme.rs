@@ -16,6 +16,7 @@ use std::collections::BTreeMap;
 
 use async_trait::async_trait;
 use itertools::Itertools;
+use prometheus::IntCounter;
 use risingwave_common::array::stream_chunk::{Op, Ops};
 use risingwave_common::array::{Array, ArrayImpl};
 use risingwave_common::buffer::Bitmap;
@@ -36,6 +37,9 @@ use crate::executor_v2::aggregation::{AggArgs, AggCall};
 pub type ManagedMinState<S, A> = GenericExtremeState<S, A, { variants::EXTREME_MIN }>;
 pub type ManagedMaxState<S, A> = GenericExtremeState<S, A, { variants::EXTREME_MAX }>;
 
+/// Number of entries to read from the state store on each refill, if the cache size is unbounded.
+const DEFAULT_REFILL_COUNT: usize = 1024;
+
 /// Manages a `BTreeMap` in memory for top N entries, and the state store for remaining entries.
 ///
 /// There are several prerequisites for using the `MinState`.
@@ -57,6 +61,10 @@ pub type ManagedMaxState<S, A> = GenericExtremeState<S, A, { variants::EXTREME_M
 /// * The output of an `ExtremeState` is only correct when all changes have been flushed to the
 ///   state store.
 /// * The `RowIDs` must be i64
+///
+/// The top n cache always holds the first entries of the state in sort order. When it underflows
+/// on retractions, it's refilled incrementally with only the next candidates after the last cached
+/// entry, instead of scanning the whole state.
 pub struct GenericExtremeState<S: StateStore, A: Array, const EXTREME_TYPE: usize>
 where
     A::OwnedItem: Ord,
@@ -75,6 +83,9 @@ where
     /// Number of entries to retain in memory after each flush.
     top_n_count: Option<usize>,
 
+    /// Counts the reads from the state store when the cache underflows.
+    cache_miss_count: Option<IntCounter>,
+
     /// Data type of the sort column
     data_type: DataType,
 
@@ -138,12 +149,19 @@ where
             total_count: row_count,
             keyspace,
             top_n_count,
+            cache_miss_count: None,
             data_type: data_type.clone(),
             serializer: ExtremeSerializer::new(data_type, pk_data_types),
             vnode: (group_key_hash_code % (VIRTUAL_NODE_COUNT as u64)) as VirtualNode,
         })
     }
 
+    /// Count the refills of the cache from the state store with `cache_miss_count`.
+    pub fn with_cache_miss_count(mut self, cache_miss_count: Option<IntCounter>) -> Self {
+        self.cache_miss_count = cache_miss_count;
+        self
+    }
+
     fn pk_data_types(&self) -> &[DataType] {
         self.serializer.pk_data_types.as_slice()
     }
@@ -283,6 +301,64 @@ where
         None
     }
 
+    /// Number of entries the cache is refilled to.
+    fn refill_count(&self) -> usize {
+        self.top_n_count.unwrap_or(DEFAULT_REFILL_COUNT)
+    }
+
+    /// The last cached entry in the sort order of the state store.
+    fn last_cached_key(&self) -> Option<&(Option<A::OwnedItem>, ExtremePk)> {
+        match EXTREME_TYPE {
+            variants::EXTREME_MIN => self.top_n.last_key_value().map(|(k, _)| k),
+            variants::EXTREME_MAX => self.top_n.first_key_value().map(|(k, _)| k),
+            _ => unimplemented!(),
+        }
+    }
+
+    /// Refill the cache with the next candidates after the last cached entry from the state store.
+    ///
+    /// To future developers: please make **SURE** you have taken `EXTREME_TYPE` into account.
+    /// EXTREME_MIN and EXTREME_MAX will significantly impact the following logic.
+    async fn refill(&mut self, epoch: u64) -> Result<()> {
+        if let Some(counter) = &self.cache_miss_count {
+            counter.inc();
+        }
+
+        let refill_count = self.refill_count();
+        let data = match self.last_cached_key() {
+            // As the start key is inclusive, the last cached entry is read again.
+            Some((key, pks)) => {
+                let start_key = self
+                    .keyspace
+                    .prefixed_key(self.serializer.serialize(key.clone(), pks)?);
+                let limit = refill_count.saturating_sub(self.top_n.len()) + 1;
+                let prefix_len = self.keyspace.key().len();
+                let mut data = self
+                    .keyspace
+                    .scan_with_start_key(start_key, Some(limit), epoch)
+                    .await?;
+                data.iter_mut()
+                    .for_each(|(k, _v)| *k = k.slice(prefix_len..));
+                data
+            }
+            None => {
+                self.keyspace
+                    .scan_strip_prefix(Some(refill_count), epoch)
+                    .await?
+            }
+        };
+
+        for (raw_key, raw_value) in data {
+            let mut deserializer = value_encoding::Deserializer::new(raw_value);
+            let value = deserialize_cell(&mut deserializer, &self.data_type)?;
+            let key = value.clone().map(|x| x.try_into().unwrap());
+            let pks = self.serializer.get_pk(&raw_key[..])?;
+            self.top_n.insert((key, pks), value);
+        }
+
+        Ok(())
+    }
+
     async fn get_output_inner(&mut self, epoch: u64) -> Result<Datum> {
         // To make things easier, we do not allow get_output before flushing. Otherwise we will need
         // to merge data from flush_buffer and state store, which is hard to implement.
@@ -292,34 +368,13 @@ where
         // only generates a write batch without flushing to store.
         debug_assert!(!self.is_dirty());
 
-        // Firstly, check if datum is available in cache.
-        if let Some(v) = self.get_output_from_cache() {
-            Ok(Some(v))
-        } else {
-            // Then, fetch from the state store.
-            //
-            // To future developers: please make **SURE** you have taken `EXTREME_TYPE` into
-            // account. EXTREME_MIN and EXTREME_MAX will significantly impact the
-            // following logic.
-            let all_data = self
-                .keyspace
-                .scan_strip_prefix(self.top_n_count, epoch)
-                .await?;
-
-            for (raw_key, raw_value) in all_data {
-                let mut deserializer = value_encoding::Deserializer::new(raw_value);
-                let value = deserialize_cell(&mut deserializer, &self.data_type)?;
-                let key = value.clone().map(|x| x.try_into().unwrap());
-                let pks = self.serializer.get_pk(&raw_key[..])?;
-                self.top_n.insert((key, pks), value);
-            }
-
-            if let Some(v) = self.get_output_from_cache() {
-                Ok(Some(v))
-            } else {
-                Ok(None)
-            }
+        // Top up the cache once it's drained below half by retractions, so that a burst of
+        // deletions doesn't end up with an empty cache and a large read on the next output.
+        if self.top_n.len() < self.total_count && self.top_n.len() <= self.refill_count() / 2 {
+            self.refill(epoch).await?;
         }
+
+        Ok(self.get_output_from_cache())
     }
 
     /// Flush the internal state to a write batch.
@@ -424,6 +479,7 @@ pub async fn create_streaming_extreme_state<S: StateStore>(
     top_n_count: Option<usize>,
     pk_data_types: PkDataTypes,
     key_hash_code: Option<HashCode>,
+    cache_miss_count: Option<IntCounter>,
 ) -> Result<Box<dyn ManagedTableState<S>>> {
     match &agg_call.args {
         AggArgs::Unary(x, _) => {
@@ -452,7 +508,7 @@ pub async fn create_streaming_extreme_state<S: StateStore>(
                             row_count,
                             pk_data_types,
                             key_hash_code.unwrap_or_default().hash_code()
-                        ).await?,
+                        ).await?.with_cache_miss_count(cache_miss_count),
                     )),
                     (AggKind::Min, $( $kind )|+) => Ok(Box::new(
                         ManagedMinState::<_, $array>::new(
@@ -462,7 +518,7 @@ pub async fn create_streaming_extreme_state<S: StateStore>(
                             row_count,
                             pk_data_types,
                             key_hash_code.unwrap_or_default().hash_code()
-                        ).await?,
+                        ).await?.with_cache_miss_count(cache_miss_count),
                     )),
                 )*
                 (kind, return_type) => unimplemented!("unsupported extreme agg, kind: {:?}, return type: {:?}", kind, return_type),
@@ -661,6 +717,80 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_extreme_state_incremental_refill() {
+        let store = MemoryStateStore::new();
+        let keyspace = Keyspace::executor_root(store.clone(), 0x2333);
+        let cache_miss_count = IntCounter::new("cache_miss_count", "cache_miss_count").unwrap();
+        let mut managed_state = ManagedMinState::<_, I64Array>::new(
+            keyspace,
+            DataType::Int64,
+            Some(4),
+            0,
+            PkDataTypes::new(),
+            567,
+        )
+        .await
+        .unwrap()
+        .with_cache_miss_count(Some(cache_miss_count.clone()));
+
+        let epoch: u64 = 0;
+        managed_state
+            .apply_batch(
+                &[Op::Insert; 10],
+                None,
+                &[&I64Array::from_slice(&(0..10).map(Some).collect_vec())
+                    .unwrap()
+                    .into()],
+                epoch,
+            )
+            .await
+            .unwrap();
+        let mut write_batch = store.start_write_batch();
+        managed_state.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+
+        assert_eq!(
+            managed_state.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int64(0))
+        );
+        assert_eq!(cache_miss_count.get(), 0);
+
+        // delete 0, 1, 2, so that only 3 is left in the cache.
+        let epoch = epoch + 1;
+        managed_state
+            .apply_batch(
+                &[Op::Delete; 3],
+                None,
+                &[&I64Array::from_slice(&[Some(0), Some(1), Some(2)])
+                    .unwrap()
+                    .into()],
+                epoch,
+            )
+            .await
+            .unwrap();
+        let mut write_batch = store.start_write_batch();
+        managed_state.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+
+        // The cache is topped up with the next candidates after 3 only.
+        assert_eq!(
+            managed_state.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int64(3))
+        );
+        assert_eq!(cache_miss_count.get(), 1);
+        assert_eq!(
+            managed_state
+                .iterate_topn_cache()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(k, _)| k)
+                .collect_vec(),
+            vec![Some(3), Some(4), Some(5), Some(6)]
+        );
+    }
+
     #[tokio::test]
     async fn test_replicated_value_min() {
         test_replicated_value_not_null::<{ variants::EXTREME_MIN }>().await
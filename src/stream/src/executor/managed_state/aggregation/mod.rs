@@ -14,26 +14,37 @@
 
 //! Aggregators with state store support
 
+pub use bitwise::*;
+pub use distinct::*;
 pub use extreme::*;
+use prometheus::IntCounter;
 use risingwave_common::array::stream_chunk::Ops;
 use risingwave_common::array::ArrayImpl;
 use risingwave_common::buffer::Bitmap;
-use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::error::Result;
 use risingwave_common::hash::HashCode;
 use risingwave_common::types::Datum;
 use risingwave_expr::expr::AggKind;
 use risingwave_storage::write_batch::WriteBatch;
 use risingwave_storage::{Keyspace, StateStore};
+pub use string_agg::*;
+pub use time_series::*;
 pub use value::*;
 
 use super::super::PkDataTypes;
 use crate::executor_v2::aggregation::AggCall;
 
+mod bitwise;
+mod distinct;
 mod extreme;
 mod extreme_serializer;
 mod string_agg;
+mod time_series;
 mod value;
 
+/// Number of entries cached by each min/max state, if not specified by the config.
+pub const DEFAULT_EXTREME_CACHE_CAPACITY: usize = 1024;
+
 /// Verify if the data going through the state is valid by checking if `ops.len() ==
 /// visibility.len() == data[x].len()`.
 pub fn verify_batch(
@@ -56,7 +67,7 @@ pub enum ManagedStateImpl<S: StateStore> {
     /// States as single scalar value e.g. `COUNT`, `SUM`
     Value(ManagedValueState<S>),
 
-    /// States as table structure e.g. `MAX`, `STRING_AGG`
+    /// States as table structure e.g. `MAX`, `STRING_AGG`, `FIRST`, `BIT_AND`
     Table(Box<dyn ManagedTableState<S>>),
 }
 
@@ -98,7 +109,10 @@ impl<S: StateStore> ManagedStateImpl<S> {
         }
     }
 
-    /// Create a managed state from `agg_call`.
+    /// Create a managed state from `agg_call`. Min/max states cache at most
+    /// `extreme_cache_capacity` entries, and their refills from the state store are counted with
+    /// `extreme_cache_miss_count`.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_managed_state(
         agg_call: AggCall,
         keyspace: Keyspace<S>,
@@ -106,6 +120,8 @@ impl<S: StateStore> ManagedStateImpl<S> {
         pk_data_types: PkDataTypes,
         is_row_count: bool,
         key_hash_code: Option<HashCode>,
+        extreme_cache_capacity: usize,
+        extreme_cache_miss_count: Option<IntCounter>,
     ) -> Result<Self> {
         match agg_call.kind {
             AggKind::Max | AggKind::Min => {
@@ -118,22 +134,52 @@ impl<S: StateStore> ManagedStateImpl<S> {
                         agg_call,
                         keyspace,
                         row_count.unwrap(),
-                        // TODO: estimate a good cache size instead of hard-coding
-                        Some(1024),
+                        Some(extreme_cache_capacity),
                         pk_data_types,
                         key_hash_code,
+                        extreme_cache_miss_count,
                     )
                     .await?,
                 ))
             }
             AggKind::StringAgg => {
-                // TODO, It seems with `order by`, `StringAgg` needs more stuff from `AggCall`
-                Err(ErrorCode::NotImplemented(
-                    "It seems with `order by`, `StringAgg` needs more stuff from `AggCall`"
-                        .to_string(),
-                    None.into(),
-                )
-                .into())
+                assert!(
+                    row_count.is_some(),
+                    "should set row_count for value states other than AggKind::RowCount"
+                );
+                Ok(Self::Table(
+                    create_streaming_string_agg_state(
+                        agg_call,
+                        keyspace,
+                        row_count.unwrap(),
+                        pk_data_types,
+                    )
+                    .await?,
+                ))
+            }
+            AggKind::First | AggKind::Last | AggKind::TimeWeightedAvg => {
+                assert!(
+                    row_count.is_some(),
+                    "should set row_count for value states other than AggKind::RowCount"
+                );
+                Ok(Self::Table(
+                    create_streaming_time_series_state(
+                        agg_call,
+                        keyspace,
+                        row_count.unwrap(),
+                        pk_data_types,
+                    )
+                    .await?,
+                ))
+            }
+            AggKind::BitAnd | AggKind::BitOr => {
+                assert!(
+                    row_count.is_some(),
+                    "should set row_count for value states other than AggKind::RowCount"
+                );
+                Ok(Self::Table(
+                    create_streaming_bitwise_state(agg_call, keyspace, row_count.unwrap()).await?,
+                ))
             }
             // TODO: for append-only lists, we can create `ManagedValueState` instead of
             // `ManagedExtremeState`.
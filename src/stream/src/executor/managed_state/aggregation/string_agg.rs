@@ -21,8 +21,9 @@ use risingwave_common::array::stream_chunk::{Op, Ops};
 use risingwave_common::array::ArrayImpl;
 use risingwave_common::buffer::Bitmap;
 use risingwave_common::error::Result;
-use risingwave_common::types::{DataType, Datum, ScalarImpl};
+use risingwave_common::types::{DataType, Datum};
 use risingwave_common::util::ordered::OrderedArraysSerializer;
+use risingwave_common::util::sort_util::{OrderPair, OrderType};
 use risingwave_common::util::value_encoding::{deserialize_cell_not_null, serialize_cell_not_null};
 use risingwave_storage::storage_value::StorageValue;
 use risingwave_storage::write_batch::WriteBatch;
@@ -30,9 +31,18 @@ use risingwave_storage::{Keyspace, StateStore};
 
 use crate::executor::managed_state::aggregation::ManagedTableState;
 use crate::executor::managed_state::flush_status::BtreeMapFlushStatus as FlushStatus;
+use crate::executor::PkDataTypes;
+use crate::executor_v2::aggregation::{AggArgs, AggCall};
+
+/// A value of `string_agg` together with the delimiter that precedes it in the result.
+#[derive(Clone, Debug, PartialEq)]
+struct StringAggData {
+    value: String,
+    delimiter: String,
+}
 
 pub struct ManagedStringAggState<S: StateStore> {
-    cache: BTreeMap<Bytes, FlushStatus<ScalarImpl>>,
+    cache: BTreeMap<Bytes, FlushStatus<StringAggData>>,
 
     /// A cached result.
     result: Option<String>,
@@ -59,8 +69,10 @@ pub struct ManagedStringAggState<S: StateStore> {
     /// `ManagedStringAggState` require only one column as the value.
     value_index: usize,
 
-    /// Delimiter.
-    delimiter: String,
+    /// Delimiter index.
+    /// As in postgres, the delimiter of each row is placed before its value, except for the first
+    /// row in the sort order.
+    delimiter_index: usize,
 
     /// The keyspace to operate on.
     keyspace: Keyspace<S>,
@@ -71,14 +83,12 @@ pub struct ManagedStringAggState<S: StateStore> {
 
 impl<S: StateStore> ManagedStringAggState<S> {
     /// Create a managed string agg state based on `Keyspace`.
-    // TODO: enable string agg state
-    #[allow(dead_code)]
     pub async fn new(
         keyspace: Keyspace<S>,
         row_count: usize,
         sort_key_indices: Vec<usize>,
         value_index: usize,
-        delimiter: String,
+        delimiter_index: usize,
         sort_key_serializer: OrderedArraysSerializer,
     ) -> Result<Self> {
         Ok(Self {
@@ -88,7 +98,7 @@ impl<S: StateStore> ManagedStringAggState<S> {
             total_count: row_count,
             sort_key_indices,
             value_index,
-            delimiter,
+            delimiter_index,
             keyspace,
             sorted_arrays_serializer: sort_key_serializer,
         })
@@ -120,12 +130,16 @@ impl<S: StateStore> ManagedStringAggState<S> {
             // We only need to deserialize the value, and keep the key as bytes.
             let mut deserializer = value_encoding::Deserializer::new(raw_value);
             let value = deserialize_cell_not_null(&mut deserializer, DataType::Varchar)?.unwrap();
-            let value_string: String = value.into_utf8();
+            let delimiter =
+                deserialize_cell_not_null(&mut deserializer, DataType::Varchar)?.unwrap();
             self.cache.insert(
                 raw_key,
                 // Here we abuse the semantics of `DeleteInsert` for those values already existed
                 // on the storage, and now we are loading them into memory.
-                FlushStatus::DeleteInsert(value_string.into()),
+                FlushStatus::DeleteInsert(StringAggData {
+                    value: value.into_utf8(),
+                    delimiter: delimiter.into_utf8(),
+                }),
             );
         }
         self.dirty = false;
@@ -139,12 +153,18 @@ impl<S: StateStore> ManagedStringAggState<S> {
         if self.total_count == 0 {
             return;
         }
-        let res = self
+        let mut res = String::new();
+        for (idx, data) in self
             .cache
             .values()
             .filter_map(|value| value.as_option())
-            .map(|scalar| scalar.as_utf8())
-            .join(&self.delimiter);
+            .enumerate()
+        {
+            if idx > 0 {
+                res.push_str(&data.delimiter);
+            }
+            res.push_str(&data.value);
+        }
         self.result = Some(res);
     }
 
@@ -167,6 +187,7 @@ impl<S: StateStore> ManagedTableState<S> for ManagedStringAggState<S> {
             debug_assert!(*sort_key_index < data.len());
         }
         debug_assert!(self.value_index < data.len());
+        debug_assert!(self.delimiter_index < data.len());
 
         if self.total_count > self.cache.len() {
             assert_eq!(self.cache.len(), 0);
@@ -190,9 +211,16 @@ impl<S: StateStore> ManagedTableState<S> for ManagedStringAggState<S> {
                 Some(scalar) => scalar.into_utf8(),
                 None => "".to_string(),
             };
+            let delimiter = match data[self.delimiter_index].datum_at(row_idx) {
+                Some(scalar) => scalar.into_utf8(),
+                None => "".to_string(),
+            };
             match op {
                 Op::Insert | Op::UpdateInsert => {
-                    FlushStatus::do_insert(self.cache.entry(key_bytes.into()), value.into());
+                    FlushStatus::do_insert(
+                        self.cache.entry(key_bytes.into()),
+                        StringAggData { value, delimiter },
+                    );
                     self.total_count += 1;
                 }
                 Op::Delete | Op::UpdateDelete => {
@@ -251,12 +279,11 @@ impl<S: StateStore> ManagedTableState<S> for ManagedStringAggState<S> {
         for (key, value) in std::mem::take(&mut self.cache) {
             let value = value.into_option();
             match value {
-                Some(val) => {
+                Some(StringAggData { value, delimiter }) => {
+                    let mut bytes = serialize_cell_not_null(&Some(value.into()))?;
+                    bytes.extend(serialize_cell_not_null(&Some(delimiter.into()))?);
                     // TODO(Yuanxin): Implement value meta
-                    local.put(
-                        key,
-                        StorageValue::new_default_put(serialize_cell_not_null(&Some(val))?),
-                    );
+                    local.put(key, StorageValue::new_default_put(bytes));
                 }
                 None => {
                     local.delete(key);
@@ -268,11 +295,51 @@ impl<S: StateStore> ManagedTableState<S> for ManagedStringAggState<S> {
     }
 }
 
+/// Create a managed string agg state for `agg_call`. The input arrays are expected to be laid out
+/// as `[value, delimiter, order by columns..., pk columns...]`.
+pub async fn create_streaming_string_agg_state<S: StateStore>(
+    agg_call: AggCall,
+    keyspace: Keyspace<S>,
+    row_count: usize,
+    pk_data_types: PkDataTypes,
+) -> Result<Box<dyn ManagedTableState<S>>> {
+    match &agg_call.args {
+        AggArgs::Binary([DataType::Varchar, DataType::Varchar], _) => {}
+        _ => panic!(
+            "string agg state should have a value and a delimiter of varchar: {:?}",
+            agg_call
+        ),
+    }
+
+    // Rows are sorted by the order by columns first, and then by the pk columns to tell apart
+    // rows with the same order by key.
+    let order_pairs = agg_call
+        .order_pairs
+        .iter()
+        .map(|o| o.order_type)
+        .chain(std::iter::repeat(OrderType::Ascending).take(pk_data_types.len()))
+        .enumerate()
+        .map(|(idx, order_type)| OrderPair::new(idx + 2, order_type))
+        .collect_vec();
+    let sort_key_indices = order_pairs.iter().map(|o| o.column_idx).collect_vec();
+
+    Ok(Box::new(
+        ManagedStringAggState::new(
+            keyspace,
+            row_count,
+            sort_key_indices,
+            0,
+            1,
+            OrderedArraysSerializer::new(order_pairs),
+        )
+        .await?,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use risingwave_common::array::{I64Array, Op, Utf8Array};
     use risingwave_common::types::ScalarImpl;
-    use risingwave_common::util::sort_util::{OrderPair, OrderType};
     use risingwave_storage::{Keyspace, StateStore};
 
     use super::*;
@@ -286,6 +353,7 @@ mod tests {
     ) -> ManagedStringAggState<S> {
         let sort_key_indices = vec![0, 1];
         let value_index = 0;
+        let delimiter_index = 2;
         let orderings = vec![OrderType::Descending, OrderType::Ascending];
         let order_pairs = orderings
             .clone()
@@ -299,7 +367,7 @@ mod tests {
             row_count,
             sort_key_indices,
             value_index,
-            "||".to_string(),
+            delimiter_index,
             sort_key_serializer,
         )
         .await
@@ -307,6 +375,12 @@ mod tests {
         managed_state
     }
 
+    fn delimiters(len: usize) -> ArrayImpl {
+        Utf8Array::from_slice(&vec![Some("||"); len])
+            .unwrap()
+            .into()
+    }
+
     #[tokio::test]
     async fn test_managed_string_agg_state() {
         let keyspace = create_in_memory_keyspace();
@@ -327,6 +401,7 @@ mod tests {
                     &I64Array::from_slice(&[Some(0), Some(1), Some(2)])
                         .unwrap()
                         .into(),
+                    &delimiters(3),
                 ],
                 epoch,
             )
@@ -357,6 +432,7 @@ mod tests {
                     &I64Array::from_slice(&[Some(3), Some(0), Some(4)])
                         .unwrap()
                         .into(),
+                    &delimiters(3),
                 ],
                 epoch,
             )
@@ -388,6 +464,7 @@ mod tests {
                     &I64Array::from_slice(&[Some(3), Some(1), Some(4)])
                         .unwrap()
                         .into(),
+                    &delimiters(3),
                 ],
                 epoch,
             )
@@ -439,6 +516,7 @@ mod tests {
                     &I64Array::from_slice(&[Some(5), Some(2), Some(6)])
                         .unwrap()
                         .into(),
+                    &delimiters(3),
                 ],
                 epoch,
             )
@@ -463,6 +541,7 @@ mod tests {
                         .unwrap()
                         .into(),
                     &I64Array::from_slice(&[Some(5), Some(6)]).unwrap().into(),
+                    &delimiters(2),
                 ],
                 epoch,
             )
@@ -481,6 +560,7 @@ mod tests {
                         .unwrap()
                         .into(),
                     &I64Array::from_slice(&[Some(7), Some(8)]).unwrap().into(),
+                    &delimiters(2),
                 ],
                 epoch,
             )
@@ -506,6 +586,7 @@ mod tests {
                         .unwrap()
                         .into(),
                     &I64Array::from_slice(&[Some(7), Some(9)]).unwrap().into(),
+                    &delimiters(2),
                 ],
                 epoch,
             )
@@ -543,6 +624,7 @@ mod tests {
                     &I64Array::from_slice(&[Some(10), Some(9), Some(11)])
                         .unwrap()
                         .into(),
+                    &delimiters(3),
                 ],
                 epoch,
             )
@@ -564,4 +646,37 @@ mod tests {
             Some(ScalarImpl::Utf8("miko||miko".to_string()))
         );
     }
+
+    #[tokio::test]
+    async fn test_managed_string_agg_state_with_row_delimiters() {
+        let keyspace = create_in_memory_keyspace();
+        let mut managed_state = create_managed_state(&keyspace, 0).await;
+        let epoch: u64 = 0;
+
+        managed_state
+            .apply_batch(
+                &[Op::Insert, Op::Insert, Op::Insert],
+                None,
+                &[
+                    &Utf8Array::from_slice(&[Some("abc"), Some("def"), Some("ghi")])
+                        .unwrap()
+                        .into(),
+                    &I64Array::from_slice(&[Some(0), Some(1), Some(2)])
+                        .unwrap()
+                        .into(),
+                    &Utf8Array::from_slice(&[Some("-"), Some("+"), Some(",")])
+                        .unwrap()
+                        .into(),
+                ],
+                epoch,
+            )
+            .await
+            .unwrap();
+
+        // The delimiter of the first value in the sort order is omitted.
+        assert_eq!(
+            managed_state.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Utf8("ghi+def-abc".to_string()))
+        );
+    }
 }
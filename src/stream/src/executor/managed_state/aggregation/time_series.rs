@@ -0,0 +1,455 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use itertools::Itertools;
+use risingwave_common::array::stream_chunk::{Op, Ops};
+use risingwave_common::array::ArrayImpl;
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, Datum};
+use risingwave_common::util::ordered::OrderedArraysSerializer;
+use risingwave_common::util::sort_util::{OrderPair, OrderType};
+use risingwave_common::util::value_encoding::{deserialize_cell, serialize_cell};
+use risingwave_expr::expr::AggKind;
+use risingwave_expr::vector_op::agg::eval_time_series_agg;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::write_batch::WriteBatch;
+use risingwave_storage::{Keyspace, StateStore};
+
+use crate::executor::managed_state::aggregation::ManagedTableState;
+use crate::executor::managed_state::flush_status::BtreeMapFlushStatus as FlushStatus;
+use crate::executor::PkDataTypes;
+use crate::executor_v2::aggregation::{AggArgs, AggCall};
+
+/// A point of the time series, i.e. the time and the value of an input row.
+#[derive(Clone, Debug, PartialEq)]
+struct TimeSeriesPoint {
+    time: Datum,
+    value: Datum,
+}
+
+/// [`ManagedTimeSeriesState`] keeps all input rows of a group ordered by time, for aggregations
+/// like `first(value, time)`, `last(value, time)` and `time_weighted_avg(value, time)`. Like
+/// [`super::ManagedStringAggState`], it has an all-or-nothing cache policy.
+pub struct ManagedTimeSeriesState<S: StateStore> {
+    agg_kind: AggKind,
+
+    cache: BTreeMap<Bytes, FlushStatus<TimeSeriesPoint>>,
+
+    /// A cached result.
+    result: Option<Datum>,
+
+    /// Marks whether there are modifications, i.e. cache != storage
+    dirty: bool,
+
+    /// Number of items in the state.
+    total_count: usize,
+
+    /// Value index.
+    value_index: usize,
+
+    /// Time index.
+    time_index: usize,
+
+    value_type: DataType,
+
+    time_type: DataType,
+
+    /// The keyspace to operate on.
+    keyspace: Keyspace<S>,
+
+    /// Serializer to get the bytes of sorted columns, i.e. the time followed by the pk columns.
+    sorted_arrays_serializer: OrderedArraysSerializer,
+}
+
+impl<S: StateStore> ManagedTimeSeriesState<S> {
+    /// Create a managed time series state based on `Keyspace`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new(
+        agg_kind: AggKind,
+        keyspace: Keyspace<S>,
+        row_count: usize,
+        value_index: usize,
+        time_index: usize,
+        value_type: DataType,
+        time_type: DataType,
+        sort_key_serializer: OrderedArraysSerializer,
+    ) -> Result<Self> {
+        Ok(Self {
+            agg_kind,
+            cache: BTreeMap::new(),
+            result: None,
+            dirty: false,
+            total_count: row_count,
+            value_index,
+            time_index,
+            value_type,
+            time_type,
+            keyspace,
+            sorted_arrays_serializer: sort_key_serializer,
+        })
+    }
+
+    #[cfg(test)]
+    pub fn get_row_count(&self) -> usize {
+        self.total_count
+    }
+
+    async fn read_all_into_memory(&mut self, epoch: u64) -> Result<()> {
+        // We cannot read from storage into memory when the cache has not been flushed onto the
+        // storage.
+        assert!(!self.is_dirty());
+        let all_data = self.keyspace.scan_strip_prefix(None, epoch).await?;
+        for (raw_key, raw_value) in all_data {
+            let mut deserializer = value_encoding::Deserializer::new(raw_value);
+            let time = deserialize_cell(&mut deserializer, &self.time_type)?;
+            let value = deserialize_cell(&mut deserializer, &self.value_type)?;
+            self.cache.insert(
+                raw_key,
+                // Here we abuse the semantics of `DeleteInsert` for those values already existed
+                // on the storage, and now we are loading them into memory.
+                FlushStatus::DeleteInsert(TimeSeriesPoint { time, value }),
+            );
+        }
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S: StateStore> ManagedTableState<S> for ManagedTimeSeriesState<S> {
+    async fn apply_batch(
+        &mut self,
+        ops: Ops<'_>,
+        visibility: Option<&Bitmap>,
+        data: &[&ArrayImpl],
+        epoch: u64,
+    ) -> Result<()> {
+        debug_assert!(super::verify_batch(ops, visibility, data));
+        debug_assert!(self.value_index < data.len());
+        debug_assert!(self.time_index < data.len());
+
+        if self.total_count > self.cache.len() {
+            assert_eq!(self.cache.len(), 0);
+            // The current policy is all-or-nothing, so no values in the memory.
+            self.read_all_into_memory(epoch).await?;
+        }
+
+        let mut row_keys = vec![];
+        self.sorted_arrays_serializer.serialize(data, &mut row_keys);
+
+        for (row_idx, (op, key_bytes)) in ops.iter().zip_eq(row_keys.into_iter()).enumerate() {
+            let visible = visibility
+                .map(|x| x.is_set(row_idx).unwrap())
+                .unwrap_or(true);
+            if !visible {
+                continue;
+            }
+
+            // Rows without a time are kept as well, so that the number of items matches the row
+            // count of the group. They are skipped when the result is evaluated.
+            match op {
+                Op::Insert | Op::UpdateInsert => {
+                    let point = TimeSeriesPoint {
+                        time: data[self.time_index].datum_at(row_idx),
+                        value: data[self.value_index].datum_at(row_idx),
+                    };
+                    FlushStatus::do_insert(self.cache.entry(key_bytes.into()), point);
+                    self.total_count += 1;
+                }
+                Op::Delete | Op::UpdateDelete => {
+                    FlushStatus::do_delete(self.cache.entry(key_bytes.into()));
+                    self.total_count -= 1;
+                }
+            }
+            self.dirty = true;
+            self.result = None;
+        }
+        Ok(())
+    }
+
+    async fn get_output(&mut self, epoch: u64) -> Result<Datum> {
+        if let Some(result) = &self.result {
+            return Ok(result.clone());
+        }
+        if self.total_count == 0 {
+            return Ok(None);
+        }
+        if self.cache.is_empty() {
+            // Since we have a all-or-nothing policy, cache must either contain all the values or
+            // be empty.
+            self.read_all_into_memory(epoch).await?;
+        }
+        let points = self
+            .cache
+            .values()
+            .filter_map(|point| point.as_option())
+            .filter_map(|point| {
+                let time = point.time.as_ref()?.as_scalar_ref_impl();
+                Some((time, point.value.as_ref().map(|v| v.as_scalar_ref_impl())))
+            });
+        let result = eval_time_series_agg(&self.agg_kind, points)?;
+        self.result = Some(result.clone());
+        Ok(result)
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn flush(&mut self, write_batch: &mut WriteBatch<S>) -> Result<()> {
+        if !self.is_dirty() {
+            return Ok(());
+        }
+
+        let mut local = write_batch.prefixify(&self.keyspace);
+
+        for (key, point) in std::mem::take(&mut self.cache) {
+            match point.into_option() {
+                Some(TimeSeriesPoint { time, value }) => {
+                    let mut bytes = serialize_cell(&time)?;
+                    bytes.extend(serialize_cell(&value)?);
+                    local.put(key, StorageValue::new_default_put(bytes));
+                }
+                None => {
+                    local.delete(key);
+                }
+            }
+        }
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Create a managed time series state for `agg_call`. The input arrays are expected to be laid
+/// out as `[value, time, pk columns...]`.
+pub async fn create_streaming_time_series_state<S: StateStore>(
+    agg_call: AggCall,
+    keyspace: Keyspace<S>,
+    row_count: usize,
+    pk_data_types: PkDataTypes,
+) -> Result<Box<dyn ManagedTableState<S>>> {
+    let (value_type, time_type) = match &agg_call.args {
+        AggArgs::Binary([value_type, time_type], _) => (value_type.clone(), time_type.clone()),
+        _ => panic!(
+            "time series state should have a value and a time: {:?}",
+            agg_call
+        ),
+    };
+    assert!(
+        agg_call.order_pairs.is_empty(),
+        "time series state is ordered by time: {:?}",
+        agg_call
+    );
+
+    // Rows are sorted by time first, and then by the pk columns to tell apart rows at the same
+    // time.
+    let order_pairs = std::iter::once(OrderPair::new(1, OrderType::Ascending))
+        .chain((0..pk_data_types.len()).map(|idx| OrderPair::new(idx + 2, OrderType::Ascending)))
+        .collect_vec();
+
+    Ok(Box::new(
+        ManagedTimeSeriesState::new(
+            agg_call.kind,
+            keyspace,
+            row_count,
+            0,
+            1,
+            value_type,
+            time_type,
+            OrderedArraysSerializer::new(order_pairs),
+        )
+        .await?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{I32Array, I64Array, Op};
+    use risingwave_common::types::ScalarImpl;
+    use risingwave_storage::{Keyspace, StateStore};
+
+    use super::*;
+    use crate::executor::test_utils::create_in_memory_keyspace;
+
+    async fn create_managed_state<S: StateStore>(
+        agg_kind: AggKind,
+        keyspace: &Keyspace<S>,
+        row_count: usize,
+    ) -> ManagedTimeSeriesState<S> {
+        // The input is laid out as `[value, time, pk]`.
+        let order_pairs = vec![
+            OrderPair::new(1, OrderType::Ascending),
+            OrderPair::new(2, OrderType::Ascending),
+        ];
+        ManagedTimeSeriesState::new(
+            agg_kind,
+            keyspace.clone(),
+            row_count,
+            0,
+            1,
+            DataType::Int32,
+            DataType::Int64,
+            OrderedArraysSerializer::new(order_pairs),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn apply<S: StateStore>(
+        state: &mut ManagedTimeSeriesState<S>,
+        ops: &[Op],
+        values: &[Option<i32>],
+        times: &[Option<i64>],
+        pks: &[Option<i64>],
+        epoch: u64,
+    ) {
+        state
+            .apply_batch(
+                ops,
+                None,
+                &[
+                    &I32Array::from_slice(values).unwrap().into(),
+                    &I64Array::from_slice(times).unwrap().into(),
+                    &I64Array::from_slice(pks).unwrap().into(),
+                ],
+                epoch,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_managed_first_last_state() {
+        let keyspace = create_in_memory_keyspace();
+        let store = keyspace.state_store();
+        let mut first = create_managed_state(AggKind::First, &keyspace.append_u8(b'f'), 0).await;
+        let mut last = create_managed_state(AggKind::Last, &keyspace.append_u8(b'l'), 0).await;
+        let epoch: u64 = 0;
+
+        for state in [&mut first, &mut last] {
+            apply(
+                state,
+                &[Op::Insert, Op::Insert, Op::Insert],
+                &[Some(1), Some(2), Some(3)],
+                &[Some(20), Some(10), Some(30)],
+                &[Some(0), Some(1), Some(2)],
+                epoch,
+            )
+            .await;
+        }
+        assert_eq!(
+            first.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int32(2))
+        );
+        assert_eq!(
+            last.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int32(3))
+        );
+
+        let mut write_batch = store.start_write_batch();
+        first.flush(&mut write_batch).unwrap();
+        last.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+
+        // Retract the first and the last points.
+        for state in [&mut first, &mut last] {
+            apply(
+                state,
+                &[Op::Delete, Op::Delete],
+                &[Some(2), Some(3)],
+                &[Some(10), Some(30)],
+                &[Some(1), Some(2)],
+                epoch,
+            )
+            .await;
+        }
+        assert_eq!(
+            first.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int32(1))
+        );
+        assert_eq!(
+            last.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int32(1))
+        );
+
+        let epoch = epoch + 1;
+        let mut write_batch = store.start_write_batch();
+        first.flush(&mut write_batch).unwrap();
+        write_batch.ingest(epoch).await.unwrap();
+
+        // Recover the state by `row_count`.
+        let row_count = first.get_row_count();
+        drop(first);
+        let mut first =
+            create_managed_state(AggKind::First, &keyspace.append_u8(b'f'), row_count).await;
+        assert_eq!(
+            first.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Int32(1))
+        );
+
+        // Delete right after recovery.
+        apply(
+            &mut first,
+            &[Op::Delete],
+            &[Some(1)],
+            &[Some(20)],
+            &[Some(0)],
+            epoch,
+        )
+        .await;
+        assert_eq!(first.get_output(epoch).await.unwrap(), None);
+        assert_eq!(first.get_row_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_managed_time_weighted_avg_state() {
+        let keyspace = create_in_memory_keyspace();
+        let mut state = create_managed_state(AggKind::TimeWeightedAvg, &keyspace, 0).await;
+        let epoch: u64 = 0;
+
+        // Rows without a time are ignored.
+        apply(
+            &mut state,
+            &[Op::Insert, Op::Insert, Op::Insert, Op::Insert],
+            &[Some(10), Some(0), Some(10), Some(100)],
+            &[Some(30), Some(0), Some(10), None],
+            &[Some(0), Some(1), Some(2), Some(3)],
+            epoch,
+        )
+        .await;
+        assert_eq!(
+            state.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Float64((250.0 / 30.0).into()))
+        );
+
+        apply(
+            &mut state,
+            &[Op::UpdateDelete, Op::UpdateInsert],
+            &[Some(0), Some(10)],
+            &[Some(0), Some(0)],
+            &[Some(1), Some(1)],
+            epoch,
+        )
+        .await;
+        assert_eq!(
+            state.get_output(epoch).await.unwrap(),
+            Some(ScalarImpl::Float64(10.0.into()))
+        );
+    }
+}
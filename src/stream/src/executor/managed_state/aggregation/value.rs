@@ -130,6 +130,8 @@ mod tests {
             kind: risingwave_expr::expr::AggKind::Count,
             args: AggArgs::Unary(DataType::Int64, 0),
             return_type: DataType::Int64,
+            order_pairs: vec![],
+            distinct: false,
         }
     }
 
@@ -30,7 +30,7 @@ type JoinEntryStateIter<'a> = btree_map::Iter<'a, PkType, StateValueType>;
 
 type JoinEntryStateValues<'a> = btree_map::Values<'a, PkType, StateValueType>;
 
-type JoinEntryStateValuesMut<'a> = btree_map::ValuesMut<'a, PkType, StateValueType>;
+type JoinEntryStateIterMut<'a> = btree_map::IterMut<'a, PkType, StateValueType>;
 
 /// Manages a `BTreeMap` in memory for all entries. When evicted, `BTreeMap` does not hold any
 /// entries.
@@ -202,11 +202,42 @@ impl<S: StateStore> JoinEntryState<S> {
         self.cached.as_ref().unwrap().values()
     }
 
-    pub async fn values_mut(&mut self, epoch: u64) -> JoinEntryStateValuesMut<'_> {
+    /// Iterates the cached rows mutably. A row updated in place must be reported by
+    /// [`JoinEntryState::mark_updated`] afterwards, otherwise the update won't be flushed.
+    pub async fn iter_mut(&mut self, epoch: u64) -> JoinEntryStateIterMut<'_> {
         if self.cached.is_none() {
             self.populate_cache(epoch).await.unwrap();
         }
-        self.cached.as_mut().unwrap().values_mut()
+        self.cached.as_mut().unwrap().iter_mut()
+    }
+
+    /// Records that the cached row of `pk` has been updated in place, e.g. its degree has
+    /// changed, so that the new value will be written on the next flush.
+    pub fn mark_updated(&mut self, pk: PkType) {
+        let value = self
+            .cached
+            .as_ref()
+            .and_then(|cached| cached.get(&pk))
+            .expect("updated row must be cached")
+            .clone();
+        match self.flush_buffer.entry(pk) {
+            btree_map::Entry::Vacant(e) => {
+                // The row in the state store is overwritten.
+                e.insert(FlushStatus::DeleteInsert(value));
+            }
+            btree_map::Entry::Occupied(mut e) => {
+                assert!(
+                    !e.get().is_delete(),
+                    "cannot update deleted row {:?}",
+                    e.key()
+                );
+                if e.get().is_insert() {
+                    e.insert(FlushStatus::Insert(value));
+                } else {
+                    e.insert(FlushStatus::DeleteInsert(value));
+                }
+            }
+        }
     }
 }
 
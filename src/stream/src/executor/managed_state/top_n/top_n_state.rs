@@ -88,18 +88,29 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
         !self.flush_buffer.is_empty()
     }
 
+    /// Number of entries cached in memory.
+    pub fn cache_len(&self) -> usize {
+        self.top_n.len()
+    }
+
     pub fn retain_top_n(&mut self) {
         if let Some(count) = self.top_n_count {
-            while self.top_n.len() > count {
-                match TOP_N_TYPE {
-                    TOP_N_MIN => {
-                        self.top_n.pop_last();
-                    }
-                    TOP_N_MAX => {
-                        self.top_n.pop_first();
-                    }
-                    _ => unreachable!(),
+            self.evict_to(count);
+        }
+    }
+
+    /// Evict the cached entries furthest from the end we are interested in, so that at most
+    /// `count` entries are cached. The evicted entries stay in the storage.
+    pub fn evict_to(&mut self, count: usize) {
+        while self.top_n.len() > count {
+            match TOP_N_TYPE {
+                TOP_N_MIN => {
+                    self.top_n.pop_last();
+                }
+                TOP_N_MAX => {
+                    self.top_n.pop_first();
                 }
+                _ => unreachable!(),
             }
         }
     }
@@ -361,14 +372,6 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
     }
 }
 
-/// Test-related methods
-impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
-    #[cfg(test)]
-    fn get_cache_len(&self) -> usize {
-        self.top_n.len()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use risingwave_common::catalog::ColumnDesc;
@@ -444,7 +447,7 @@ mod tests {
             Some((&ordered_rows[3], &rows[3]))
         );
         assert!(managed_state.is_dirty());
-        assert_eq!(managed_state.get_cache_len(), 1);
+        assert_eq!(managed_state.cache_len(), 1);
 
         managed_state
             .insert(ordered_rows[2].clone(), rows[2].clone(), epoch)
@@ -457,7 +460,7 @@ mod tests {
             Some((&ordered_rows[3], &rows[3]))
         );
         assert!(managed_state.is_dirty());
-        assert_eq!(managed_state.get_cache_len(), 2);
+        assert_eq!(managed_state.cache_len(), 2);
 
         managed_state
             .insert(ordered_rows[1].clone(), rows[1].clone(), epoch)
@@ -470,13 +473,13 @@ mod tests {
             managed_state.top_element(),
             Some((&ordered_rows[3], &rows[3]))
         );
-        assert_eq!(managed_state.get_cache_len(), 3);
+        assert_eq!(managed_state.cache_len(), 3);
         managed_state.flush(epoch).await.unwrap();
         assert!(!managed_state.is_dirty());
         let row_count = managed_state.total_count;
         assert_eq!(row_count, 3);
         // After flush, only 2 elements should be kept in the cache.
-        assert_eq!(managed_state.get_cache_len(), 2);
+        assert_eq!(managed_state.cache_len(), 2);
 
         drop(managed_state);
         let mut managed_state = create_managed_top_n_state::<_, TOP_N_MAX>(
@@ -494,7 +497,7 @@ mod tests {
         );
         // Right after recovery.
         assert!(!managed_state.is_dirty());
-        assert_eq!(managed_state.get_cache_len(), 2);
+        assert_eq!(managed_state.cache_len(), 2);
         assert_eq!(managed_state.total_count, 3);
 
         assert_eq!(
@@ -504,7 +507,7 @@ mod tests {
         // now ("abd", 3) on storage -> ("abc", 3) in memory
         assert!(managed_state.is_dirty());
         assert_eq!(managed_state.total_count, 2);
-        assert_eq!(managed_state.get_cache_len(), 1);
+        assert_eq!(managed_state.cache_len(), 1);
         assert_eq!(
             managed_state.pop_top_element(epoch).await.unwrap(),
             Some((ordered_rows[1].clone(), rows[1].clone()))
@@ -515,7 +518,7 @@ mod tests {
         // The state is dirty as we didn't flush.
         assert!(managed_state.is_dirty());
         assert_eq!(managed_state.total_count, 1);
-        assert_eq!(managed_state.get_cache_len(), 1);
+        assert_eq!(managed_state.cache_len(), 1);
         // now ("abd", 3) in memory
 
         assert_eq!(
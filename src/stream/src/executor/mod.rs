@@ -23,38 +23,51 @@ pub use batch_query::*;
 pub use chain::*;
 pub use debug::*;
 pub use dispatch::*;
+pub use dynamic_filter::*;
 use enum_as_inner::EnumAsInner;
 pub use filter::*;
 use futures::Stream;
 pub use global_simple_agg::*;
 pub use hash_agg::*;
 pub use hash_join::*;
+pub use hash_set_op::*;
+pub use hop_window::*;
+pub use local_hash_agg::*;
 pub use local_simple_agg::*;
 pub use merge::*;
 pub use monitor::*;
 pub use mview::*;
+pub use nested_loop_join::*;
 pub use project::*;
+pub use project_set::*;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{ArrayImpl, ArrayRef, DataChunk, StreamChunk};
 use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result, RwError};
-use risingwave_common::types::DataType;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::value_encoding::{deserialize_cell, serialize_cell};
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::barrier::Mutation as ProstMutation;
 use risingwave_pb::data::stream_message::StreamMessage;
 use risingwave_pb::data::{
     Actors as MutationActors, AddMutation, Barrier as ProstBarrier, Epoch as ProstEpoch,
-    NothingMutation, StopMutation, StreamMessage as ProstStreamMessage, UpdateMutation,
+    NothingMutation, PauseMutation, ResumeMutation, SourceChangeSplitMutation, SourceSplits,
+    SourceThrottle, StopMutation, StreamMessage as ProstStreamMessage, ThrottleMutation,
+    UpdateMutation, Watermark as ProstWatermark,
 };
 use risingwave_pb::stream_plan;
 use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_storage::StateStore;
+pub use sink::*;
 use smallvec::SmallVec;
 pub use source::*;
 pub use top_n::*;
 pub use top_n_appendonly::*;
 use tracing::trace_span;
+pub use union::*;
+pub use values::*;
+pub use watermark_filter::*;
 
 use crate::executor_v2::LookupExecutorBuilder;
 use crate::task::{ActorId, ExecutorParams, LocalStreamManagerCore, ENABLE_BARRIER_AGGREGATION};
@@ -65,19 +78,29 @@ mod batch_query;
 mod chain;
 mod debug;
 mod dispatch;
+mod dynamic_filter;
 mod filter;
 mod global_simple_agg;
 mod hash_agg;
 mod hash_join;
+mod hash_set_op;
+mod hop_window;
+mod local_hash_agg;
 mod local_simple_agg;
 pub(crate) mod managed_state;
 mod merge;
 pub mod monitor;
 mod mview;
+mod nested_loop_join;
 mod project;
+mod project_set;
+mod sink;
 mod source;
 mod top_n;
 mod top_n_appendonly;
+mod union;
+mod values;
+mod watermark_filter;
 
 #[cfg(test)]
 mod integration_tests;
@@ -97,6 +120,16 @@ pub enum Mutation {
     Stop(HashSet<ActorId>),
     UpdateOutputs(HashMap<ActorId, Vec<ActorInfo>>),
     AddOutput(HashMap<ActorId, Vec<ActorInfo>>),
+    /// Changes the rate limits of some source actors.
+    Throttle(HashMap<ActorId, SourceThrottle>),
+    /// Stops all the sources from reading data, while barriers are still forwarded, so that the
+    /// dataflow is quiescent during configuration changes like actor migration.
+    Pause,
+    /// Resumes the sources stopped by [`Mutation::Pause`].
+    Resume,
+    /// Changes the splits read by some source actors, e.g. to read the new partitions of a Kafka
+    /// topic.
+    SourceChangeSplit(HashMap<ActorId, SourceSplits>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -138,6 +171,8 @@ pub struct Barrier {
     pub epoch: Epoch,
     pub mutation: Option<Arc<Mutation>>,
     pub span: tracing::Span,
+    /// Whether the barrier is a checkpoint forced by meta, instead of a periodic one.
+    pub checkpoint: bool,
 }
 
 impl Default for Barrier {
@@ -146,6 +181,7 @@ impl Default for Barrier {
             span: tracing::Span::none(),
             epoch: Epoch::default(),
             mutation: None,
+            checkpoint: false,
         }
     }
 }
@@ -187,6 +223,30 @@ impl Barrier {
                 .any(|info| info.actor_id == actor_id)
         )
     }
+
+    /// Returns the new rate limit of the source actor `actor_id`, if the barrier changes it.
+    pub fn throttle(&self, actor_id: ActorId) -> Option<&SourceThrottle> {
+        match self.mutation.as_deref() {
+            Some(Mutation::Throttle(actors)) => actors.get(&actor_id),
+            _ => None,
+        }
+    }
+
+    /// Returns the new splits of the source actor `actor_id`, if the barrier changes them.
+    pub fn source_splits(&self, actor_id: ActorId) -> Option<&SourceSplits> {
+        match self.mutation.as_deref() {
+            Some(Mutation::SourceChangeSplit(actor_splits)) => actor_splits.get(&actor_id),
+            _ => None,
+        }
+    }
+
+    pub fn is_pause(&self) -> bool {
+        matches!(self.mutation.as_deref(), Some(Mutation::Pause))
+    }
+
+    pub fn is_resume(&self) -> bool {
+        matches!(self.mutation.as_deref(), Some(Mutation::Resume))
+    }
 }
 
 impl PartialEq for Barrier {
@@ -208,7 +268,10 @@ impl Mutation {
 impl Barrier {
     pub fn to_protobuf(&self) -> ProstBarrier {
         let Barrier {
-            epoch, mutation, ..
+            epoch,
+            mutation,
+            span,
+            checkpoint,
         }: Barrier = self.clone();
         ProstBarrier {
             epoch: Some(ProstEpoch {
@@ -248,8 +311,21 @@ impl Barrier {
                         })
                         .collect(),
                 })),
+                Some(Mutation::Throttle(actors)) => {
+                    Some(ProstMutation::Throttle(ThrottleMutation {
+                        actors: actors.clone(),
+                    }))
+                }
+                Some(Mutation::Pause) => Some(ProstMutation::Pause(PauseMutation {})),
+                Some(Mutation::Resume) => Some(ProstMutation::Resume(ResumeMutation {})),
+                Some(Mutation::SourceChangeSplit(actor_splits)) => {
+                    Some(ProstMutation::Splits(SourceChangeSplitMutation {
+                        actor_splits: actor_splits.clone(),
+                    }))
+                }
             },
-            span: vec![],
+            span: risingwave_logging::encode_trace_context(&span),
+            checkpoint,
         }
     }
 
@@ -278,16 +354,76 @@ impl Barrier {
                 )
                 .into(),
             ),
+            ProstMutation::Throttle(throttle) => {
+                Some(Mutation::Throttle(throttle.actors.clone()).into())
+            }
+            ProstMutation::Pause(_) => Some(Mutation::Pause.into()),
+            ProstMutation::Resume(_) => Some(Mutation::Resume.into()),
+            ProstMutation::Splits(splits) => {
+                Some(Mutation::SourceChangeSplit(splits.actor_splits.clone()).into())
+            }
         };
         let epoch = prost.get_epoch().unwrap();
+        // Attach the span to the trace of the sender, so that a barrier can be traced across
+        // actors and nodes.
+        let span = if ENABLE_BARRIER_AGGREGATION || !prost.span.is_empty() {
+            trace_span!("barrier", epoch = ?epoch, mutation = ?mutation)
+        } else {
+            tracing::Span::none()
+        };
+        risingwave_logging::set_trace_context_parent(&span, &prost.span);
         Ok(Barrier {
-            span: if ENABLE_BARRIER_AGGREGATION {
-                trace_span!("barrier", epoch = ?epoch, mutation = ?mutation)
-            } else {
-                tracing::Span::none()
-            },
+            span,
             epoch: Epoch::new(epoch.curr, epoch.prev),
             mutation,
+            checkpoint: prost.checkpoint,
+        })
+    }
+}
+
+/// A watermark promises that no row whose value on column `col_idx` is less than `val` will
+/// follow in the stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watermark {
+    pub col_idx: usize,
+    pub data_type: DataType,
+    pub val: ScalarImpl,
+}
+
+impl Watermark {
+    pub fn new(col_idx: usize, data_type: DataType, val: ScalarImpl) -> Self {
+        Self {
+            col_idx,
+            data_type,
+            val,
+        }
+    }
+
+    /// Moves the watermark to column `col_idx`, e.g. after a projection.
+    pub fn with_idx(self, col_idx: usize) -> Self {
+        Self { col_idx, ..self }
+    }
+
+    pub fn to_protobuf(&self) -> Result<ProstWatermark> {
+        Ok(ProstWatermark {
+            column_index: self.col_idx as u32,
+            data_type: Some(self.data_type.to_protobuf()),
+            value: serialize_cell(&Some(self.val.clone()))?,
+        })
+    }
+
+    pub fn from_protobuf(prost: &ProstWatermark) -> Result<Self> {
+        let data_type = DataType::from(prost.get_data_type()?);
+        let mut deserializer = value_encoding::Deserializer::new(prost.value.as_slice());
+        let val = deserialize_cell(&mut deserializer, &data_type)?.ok_or_else(|| {
+            RwError::from(ErrorCode::InternalError(
+                "watermark value must not be null".to_string(),
+            ))
+        })?;
+        Ok(Self {
+            col_idx: prost.column_index as usize,
+            data_type,
+            val,
         })
     }
 }
@@ -296,6 +432,7 @@ impl Barrier {
 pub enum Message {
     Chunk(StreamChunk),
     Barrier(Barrier),
+    Watermark(Watermark),
 }
 
 impl<'a> TryFrom<&'a Message> for &'a Barrier {
@@ -303,7 +440,7 @@ impl<'a> TryFrom<&'a Message> for &'a Barrier {
 
     fn try_from(m: &'a Message) -> std::result::Result<Self, Self::Error> {
         match m {
-            Message::Chunk(_) => Err(()),
+            Message::Chunk(_) | Message::Watermark(_) => Err(()),
             Message::Barrier(b) => Ok(b),
         }
     }
@@ -326,12 +463,19 @@ impl Message {
     }
 
     pub fn to_protobuf(&self) -> Result<ProstStreamMessage> {
+        self.to_protobuf_with_version(0)
+    }
+
+    /// Serializes the message for a stream exchange whose receiver speaks `protocol_version`, see
+    /// [`risingwave_common::array::stream_chunk::STREAM_EXCHANGE_PROTOCOL_VERSION`].
+    pub fn to_protobuf_with_version(&self, protocol_version: u32) -> Result<ProstStreamMessage> {
         let prost = match self {
             Self::Chunk(stream_chunk) => {
-                let prost_stream_chunk = stream_chunk.to_protobuf();
+                let prost_stream_chunk = stream_chunk.to_protobuf_with_version(protocol_version)?;
                 StreamMessage::StreamChunk(prost_stream_chunk)
             }
             Self::Barrier(barrier) => StreamMessage::Barrier(barrier.clone().to_protobuf()),
+            Self::Watermark(watermark) => StreamMessage::Watermark(watermark.to_protobuf()?),
         };
         let prost_stream_msg = ProstStreamMessage {
             stream_message: Some(prost),
@@ -347,6 +491,9 @@ impl Message {
             StreamMessage::Barrier(ref barrier) => {
                 Message::Barrier(Barrier::from_protobuf(barrier)?)
             }
+            StreamMessage::Watermark(ref watermark) => {
+                Message::Watermark(Watermark::from_protobuf(watermark)?)
+            }
         };
         Ok(res)
     }
@@ -485,12 +632,29 @@ macro_rules! build_executor {
     }
 }
 
+/// Builds the executor of `node`, whose inputs and output are wrapped by [`MonitoredExecutor`] to
+/// export per-executor metrics.
 pub fn create_executor(
-    executor_params: ExecutorParams,
+    mut executor_params: ExecutorParams,
     stream: &mut LocalStreamManagerCore,
     node: &stream_plan::StreamNode,
     store: impl StateStore,
 ) -> Result<Box<dyn Executor>> {
+    let actor_id = executor_params.actor_id;
+    let executor_id = executor_params.executor_id;
+    let metrics = executor_params.executor_stats.clone();
+    executor_params.input = std::mem::take(&mut executor_params.input)
+        .into_iter()
+        .map(|input| -> Box<dyn Executor> {
+            Box::new(MonitoredExecutor::input(
+                input,
+                actor_id,
+                executor_id,
+                &metrics,
+            ))
+        })
+        .collect();
+
     let real_executor = build_executor! {
         executor_params,
         node,
@@ -498,21 +662,36 @@ pub fn create_executor(
         stream,
         Node::SourceNode => SourceExecutorBuilder,
         Node::ProjectNode => ProjectExecutorBuilder,
+        Node::ProjectSetNode => ProjectSetExecutorBuilder,
         Node::TopNNode => TopNExecutorBuilder,
         Node::AppendOnlyTopNNode => AppendOnlyTopNExecutorBuilder,
         Node::LocalSimpleAggNode => LocalSimpleAggExecutorBuilder,
+        Node::LocalHashAggNode => LocalHashAggExecutorBuilder,
         Node::GlobalSimpleAggNode => SimpleAggExecutorBuilder,
         Node::HashAggNode => HashAggExecutorBuilder,
         Node::HashJoinNode => HashJoinExecutorBuilder,
+        Node::HashSetOpNode => HashSetOpExecutorBuilder,
+        Node::NestedLoopJoinNode => NestedLoopJoinExecutorBuilder,
+        Node::DynamicFilterNode => DynamicFilterExecutorBuilder,
+        Node::HopWindowNode => HopWindowExecutorBuilder,
         Node::ChainNode => ChainExecutorBuilder,
         Node::BatchPlanNode => BatchQueryExecutorBuilder,
         Node::MergeNode => MergeExecutorBuilder,
         Node::MaterializeNode => MaterializeExecutorBuilder,
         Node::FilterNode => FilterExecutorBuilder,
         Node::ArrangeNode => ArrangeExecutorBuilder,
-        Node::LookupNode => LookupExecutorBuilder
+        Node::LookupNode => LookupExecutorBuilder,
+        Node::WatermarkFilterNode => WatermarkFilterExecutorBuilder,
+        Node::ValuesNode => ValuesExecutorBuilder,
+        Node::UnionNode => UnionExecutorBuilder,
+        Node::SinkNode => SinkExecutorBuilder
     }?;
-    Ok(real_executor)
+    Ok(Box::new(MonitoredExecutor::output(
+        real_executor,
+        actor_id,
+        executor_id,
+        &metrics,
+    )))
 }
 
 /// `StreamConsumer` is the last step in an actor
@@ -0,0 +1,106 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::StreamingMetrics;
+use crate::task::ActorId;
+
+/// Decrease the running actor count and remove the metrics labeled with the actor when the actor
+/// task finishes or is aborted.
+struct ActorMetricsGuard {
+    actor_id: ActorId,
+    metrics: Arc<StreamingMetrics>,
+}
+
+impl Drop for ActorMetricsGuard {
+    fn drop(&mut self) {
+        self.metrics.actor_count.dec();
+        self.metrics.remove_actor_metrics(self.actor_id);
+    }
+}
+
+/// Run the actor future with instrumentation of its polls:
+///
+/// * `stream_actor_poll_duration`: time spent in each poll, i.e. the actor is busy with CPU work.
+/// * `stream_actor_idle_duration`: time between a poll returning `Pending` and the next poll, i.e.
+///   the actor is blocked on IO, channels or barriers, or waiting to be scheduled.
+/// * `stream_actor_poll_count` and `stream_actor_count`.
+///
+/// A high poll duration indicates CPU saturation, while a high idle duration indicates waiting.
+pub async fn instrument_actor<F: Future>(
+    actor_id: ActorId,
+    metrics: Arc<StreamingMetrics>,
+    future: F,
+) -> F::Output {
+    let id = actor_id.to_string();
+    let poll_duration = metrics.actor_poll_duration.with_label_values(&[&id]);
+    let poll_count = metrics.actor_poll_count.with_label_values(&[&id]);
+    let idle_duration = metrics.actor_idle_duration.with_label_values(&[&id]);
+
+    metrics.actor_count.inc();
+    let _guard = ActorMetricsGuard {
+        actor_id,
+        metrics: metrics.clone(),
+    };
+
+    let mut future = Box::pin(future);
+    let mut pending_since: Option<Instant> = None;
+    futures::future::poll_fn(|cx| {
+        if let Some(since) = pending_since.take() {
+            idle_duration.inc_by(since.elapsed().as_secs_f64());
+        }
+
+        let start = Instant::now();
+        let poll = future.as_mut().poll(cx);
+        poll_duration.observe(start.elapsed().as_secs_f64());
+        poll_count.inc();
+
+        if poll.is_pending() {
+            pending_since = Some(Instant::now());
+        }
+        poll
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_instrument_actor() {
+        let metrics = Arc::new(StreamingMetrics::unused());
+        let poll_count = metrics.actor_poll_count.with_label_values(&["1"]);
+        let poll_duration = metrics.actor_poll_duration.with_label_values(&["1"]);
+        let output = instrument_actor(1, metrics.clone(), async {
+            tokio::task::yield_now().await;
+            42
+        })
+        .await;
+        assert_eq!(output, 42);
+
+        assert_eq!(poll_count.get(), 2);
+        assert_eq!(poll_duration.get_sample_count(), 2);
+        assert_eq!(metrics.actor_count.get(), 0);
+
+        // The labels of the stopped actor are removed.
+        assert!(metrics
+            .actor_poll_count
+            .remove_label_values(&["1"])
+            .is_err());
+    }
+}
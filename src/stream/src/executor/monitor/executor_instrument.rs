@@ -0,0 +1,202 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Formatter};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use prometheus::core::{AtomicU64, GenericCounter};
+use prometheus::Histogram;
+use risingwave_common::error::Result;
+
+use super::StreamingMetrics;
+use crate::executor::{DebugExecutor, Executor, Message};
+use crate::task::ActorId;
+
+/// Latency histograms, only recorded on the output side of an executor.
+struct LatencyMetrics {
+    processing_duration: Histogram,
+    barrier_latency: Histogram,
+}
+
+/// `MonitoredExecutor` counts the rows and chunks passing through it, which is used to export
+/// per-executor metrics to Prometheus. Every executor built by
+/// [`create_executor`](crate::executor::create_executor) is monitored on both sides:
+///
+/// ```plain
+/// Next <- Monitored(output) <- Executor <- Monitored(input) <- Input
+/// ```
+///
+/// * `stream_executor_{input,output}_{row,chunk}_count`: rows and chunks received and output by the
+///   executor.
+/// * `stream_executor_processing_duration`: time spent in each `next` call returning a chunk.
+/// * `stream_executor_barrier_latency`: time spent in each `next` call returning a barrier, i.e.
+///   the time to align the barrier from all inputs and flush the states.
+///
+/// As the executors are pulled, both durations include the time spent in the inputs.
+pub struct MonitoredExecutor {
+    input: Box<dyn Executor>,
+    row_count: GenericCounter<AtomicU64>,
+    chunk_count: GenericCounter<AtomicU64>,
+    latency: Option<LatencyMetrics>,
+}
+
+impl Debug for MonitoredExecutor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MonitoredExecutor")
+            .field("input", &self.input)
+            .finish()
+    }
+}
+
+impl MonitoredExecutor {
+    /// Monitors the output of the executor with `executor_id`.
+    pub fn output(
+        executor: Box<dyn Executor>,
+        actor_id: ActorId,
+        executor_id: u64,
+        metrics: &StreamingMetrics,
+    ) -> Self {
+        let labels = [actor_id.to_string(), format!("{:X}", executor_id)];
+        let labels = [labels[0].as_str(), labels[1].as_str()];
+        Self {
+            input: executor,
+            row_count: metrics.executor_output_row_count.with_label_values(&labels),
+            chunk_count: metrics
+                .executor_output_chunk_count
+                .with_label_values(&labels),
+            latency: Some(LatencyMetrics {
+                processing_duration: metrics
+                    .executor_processing_duration
+                    .with_label_values(&labels),
+                barrier_latency: metrics.executor_barrier_latency.with_label_values(&labels),
+            }),
+        }
+    }
+
+    /// Monitors one of the inputs of the executor with `executor_id`.
+    pub fn input(
+        input: Box<dyn Executor>,
+        actor_id: ActorId,
+        executor_id: u64,
+        metrics: &StreamingMetrics,
+    ) -> Self {
+        let labels = [actor_id.to_string(), format!("{:X}", executor_id)];
+        let labels = [labels[0].as_str(), labels[1].as_str()];
+        Self {
+            input,
+            row_count: metrics.executor_input_row_count.with_label_values(&labels),
+            chunk_count: metrics
+                .executor_input_chunk_count
+                .with_label_values(&labels),
+            latency: None,
+        }
+    }
+}
+
+#[async_trait]
+impl DebugExecutor for MonitoredExecutor {
+    async fn next(&mut self) -> Result<Message> {
+        let start = Instant::now();
+        let message = self.input.next().await?;
+        match &message {
+            Message::Chunk(chunk) => {
+                self.chunk_count.inc();
+                self.row_count.inc_by(chunk.cardinality() as u64);
+                if let Some(latency) = &self.latency {
+                    latency
+                        .processing_duration
+                        .observe(start.elapsed().as_secs_f64());
+                }
+            }
+            Message::Barrier(_) => {
+                if let Some(latency) = &self.latency {
+                    latency
+                        .barrier_latency
+                        .observe(start.elapsed().as_secs_f64());
+                }
+            }
+            Message::Watermark(_) => {}
+        }
+        Ok(message)
+    }
+
+    fn input(&self) -> &dyn Executor {
+        self.input.as_ref()
+    }
+
+    fn input_mut(&mut self) -> &mut dyn Executor {
+        self.input.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{I64Array, Op, StreamChunk};
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::executor::test_utils::MockSource;
+
+    #[tokio::test]
+    async fn test_monitored_executor() {
+        let schema = Schema::new(vec![Field::unnamed(DataType::Int64)]);
+        let mut source = MockSource::new(schema, vec![0]);
+        source.push_chunks(
+            [
+                StreamChunk::new(
+                    vec![Op::Insert, Op::Insert],
+                    vec![column_nonnull! { I64Array, [1, 2] }],
+                    None,
+                ),
+                StreamChunk::new(
+                    vec![Op::Delete],
+                    vec![column_nonnull! { I64Array, [1] }],
+                    None,
+                ),
+            ]
+            .into_iter(),
+        );
+        source.push_barrier(1, false);
+
+        let metrics = StreamingMetrics::unused();
+        let input = MonitoredExecutor::input(Box::new(source), 1, 0x10, &metrics);
+        let mut executor = MonitoredExecutor::output(Box::new(input), 1, 0x10, &metrics);
+        for _ in 0..3 {
+            Executor::next(&mut executor).await.unwrap();
+        }
+
+        let labels = ["1", "10"];
+        for counter in [
+            &metrics.executor_input_row_count,
+            &metrics.executor_output_row_count,
+        ] {
+            assert_eq!(counter.with_label_values(&labels).get(), 3);
+        }
+        for counter in [
+            &metrics.executor_input_chunk_count,
+            &metrics.executor_output_chunk_count,
+        ] {
+            assert_eq!(counter.with_label_values(&labels).get(), 2);
+        }
+        let processing_duration = metrics
+            .executor_processing_duration
+            .with_label_values(&labels);
+        assert_eq!(processing_duration.get_sample_count(), 2);
+        let barrier_latency = metrics.executor_barrier_latency.with_label_values(&labels);
+        assert_eq!(barrier_latency.get_sample_count(), 1);
+    }
+}
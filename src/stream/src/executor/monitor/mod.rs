@@ -12,5 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod actor_instrument;
+pub use actor_instrument::*;
+mod executor_instrument;
+pub use executor_instrument::*;
 pub mod streaming_stats;
 pub use streaming_stats::*;
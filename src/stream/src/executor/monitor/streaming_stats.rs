@@ -12,14 +12,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use prometheus::core::{AtomicU64, GenericCounterVec};
-use prometheus::{register_int_counter_vec_with_registry, Registry};
+use prometheus::core::{AtomicF64, AtomicU64, GenericCounterVec};
+use prometheus::{
+    exponential_buckets, histogram_opts, register_counter_vec_with_registry,
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_with_registry, HistogramVec, IntGauge, Registry,
+};
+
+use crate::task::ActorId;
 
 pub struct StreamingMetrics {
     pub registry: Registry,
     pub actor_row_count: GenericCounterVec<AtomicU64>,
 
     pub source_output_row_count: GenericCounterVec<AtomicU64>,
+
+    pub actor_poll_duration: HistogramVec,
+    pub actor_poll_count: GenericCounterVec<AtomicU64>,
+    pub actor_idle_duration: GenericCounterVec<AtomicF64>,
+    pub actor_count: IntGauge,
+
+    pub lookup_cache_hit_count: GenericCounterVec<AtomicU64>,
+    pub lookup_cache_miss_count: GenericCounterVec<AtomicU64>,
+
+    pub agg_extreme_cache_miss_count: GenericCounterVec<AtomicU64>,
+
+    pub executor_input_row_count: GenericCounterVec<AtomicU64>,
+    pub executor_input_chunk_count: GenericCounterVec<AtomicU64>,
+    pub executor_output_row_count: GenericCounterVec<AtomicU64>,
+    pub executor_output_chunk_count: GenericCounterVec<AtomicU64>,
+    pub executor_processing_duration: HistogramVec,
+    pub executor_barrier_latency: HistogramVec,
 }
 
 impl StreamingMetrics {
@@ -40,10 +63,128 @@ impl StreamingMetrics {
         )
         .unwrap();
 
+        let opts = histogram_opts!(
+            "stream_actor_poll_duration",
+            "Time spent in each poll of the actor task",
+            exponential_buckets(0.00001, 2.0, 20).unwrap() // max 5.24s
+        );
+        let actor_poll_duration =
+            register_histogram_vec_with_registry!(opts, &["actor_id"], registry).unwrap();
+
+        let actor_poll_count = register_int_counter_vec_with_registry!(
+            "stream_actor_poll_count",
+            "Total number of polls of the actor task",
+            &["actor_id"],
+            registry
+        )
+        .unwrap();
+
+        let actor_idle_duration = register_counter_vec_with_registry!(
+            "stream_actor_idle_duration",
+            "Total time in seconds the actor task waited between polls",
+            &["actor_id"],
+            registry
+        )
+        .unwrap();
+
+        let actor_count = register_int_gauge_with_registry!(
+            "stream_actor_count",
+            "Number of running actor tasks",
+            registry
+        )
+        .unwrap();
+
+        let lookup_cache_hit_count = register_int_counter_vec_with_registry!(
+            "stream_lookup_cache_hit_count",
+            "Number of lookups served by the lookup cache, including negative entries",
+            &["actor_id"],
+            registry
+        )
+        .unwrap();
+
+        let lookup_cache_miss_count = register_int_counter_vec_with_registry!(
+            "stream_lookup_cache_miss_count",
+            "Number of lookups missing the lookup cache and reading the state store",
+            &["actor_id"],
+            registry
+        )
+        .unwrap();
+
+        let agg_extreme_cache_miss_count = register_int_counter_vec_with_registry!(
+            "stream_agg_extreme_cache_miss_count",
+            "Number of times the min/max cache of aggregation underflows and reads the state store",
+            &["actor_id"],
+            registry
+        )
+        .unwrap();
+
+        let executor_input_row_count = register_int_counter_vec_with_registry!(
+            "stream_executor_input_row_count",
+            "Total number of rows that have been received by each executor",
+            &["actor_id", "executor_id"],
+            registry
+        )
+        .unwrap();
+
+        let executor_input_chunk_count = register_int_counter_vec_with_registry!(
+            "stream_executor_input_chunk_count",
+            "Total number of chunks that have been received by each executor",
+            &["actor_id", "executor_id"],
+            registry
+        )
+        .unwrap();
+
+        let executor_output_row_count = register_int_counter_vec_with_registry!(
+            "stream_executor_output_row_count",
+            "Total number of rows that have been output from each executor",
+            &["actor_id", "executor_id"],
+            registry
+        )
+        .unwrap();
+
+        let executor_output_chunk_count = register_int_counter_vec_with_registry!(
+            "stream_executor_output_chunk_count",
+            "Total number of chunks that have been output from each executor",
+            &["actor_id", "executor_id"],
+            registry
+        )
+        .unwrap();
+
+        let opts = histogram_opts!(
+            "stream_executor_processing_duration",
+            "Time spent by each executor to output a chunk, including its inputs",
+            exponential_buckets(0.00001, 2.0, 20).unwrap() // max 5.24s
+        );
+        let executor_processing_duration =
+            register_histogram_vec_with_registry!(opts, &["actor_id", "executor_id"], registry)
+                .unwrap();
+
+        let opts = histogram_opts!(
+            "stream_executor_barrier_latency",
+            "Time spent by each executor to output a barrier, including alignment and flushing",
+            exponential_buckets(0.00001, 2.0, 20).unwrap() // max 5.24s
+        );
+        let executor_barrier_latency =
+            register_histogram_vec_with_registry!(opts, &["actor_id", "executor_id"], registry)
+                .unwrap();
+
         Self {
             registry,
             actor_row_count,
             source_output_row_count,
+            actor_poll_duration,
+            actor_poll_count,
+            actor_idle_duration,
+            actor_count,
+            lookup_cache_hit_count,
+            lookup_cache_miss_count,
+            agg_extreme_cache_miss_count,
+            executor_input_row_count,
+            executor_input_chunk_count,
+            executor_output_row_count,
+            executor_output_chunk_count,
+            executor_processing_duration,
+            executor_barrier_latency,
         }
     }
 
@@ -51,4 +192,16 @@ impl StreamingMetrics {
     pub fn unused() -> Self {
         Self::new(prometheus::Registry::new())
     }
+
+    /// Remove the metrics labeled with a stopped actor, so that they are no longer exported.
+    pub fn remove_actor_metrics(&self, actor_id: ActorId) {
+        let id = actor_id.to_string();
+        // The metrics may never have been created for the actor, so errors are ignored.
+        let _ = self.actor_poll_duration.remove_label_values(&[&id]);
+        let _ = self.actor_poll_count.remove_label_values(&[&id]);
+        let _ = self.actor_idle_duration.remove_label_values(&[&id]);
+        let _ = self
+            .agg_extreme_cache_miss_count
+            .remove_label_values(&[&id]);
+    }
 }
@@ -12,16 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risingwave_common::catalog::{ColumnId, TableId};
+use std::sync::Arc;
+
+use itertools::Itertools;
+use risingwave_common::catalog::{ColumnDesc, ColumnId, TableId};
 use risingwave_common::try_match_expand;
+use risingwave_common::util::ordered::OrderedRowSerializer;
 use risingwave_common::util::sort_util::OrderPair;
 use risingwave_pb::stream_plan;
+use risingwave_pb::stream_plan::conflict_overwrite::OptionalVersionColumnIndex;
 use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_storage::monitor::StateStoreMetrics;
+use risingwave_storage::table::cell_based_table::CellBasedTable;
 use risingwave_storage::{Keyspace, StateStore};
 
 use crate::executor::{Executor, ExecutorBuilder, Result};
 use crate::executor_v2::{Executor as ExecutorV2, MaterializeExecutor as MaterializeExecutorV2};
-use crate::task::{ExecutorParams, LocalStreamManagerCore};
+use crate::task::{ArrangementDesc, ExecutorParams, LocalStreamManagerCore};
 
 pub struct MaterializeExecutorBuilder;
 
@@ -35,27 +42,75 @@ impl ExecutorBuilder for MaterializeExecutorBuilder {
         let node = try_match_expand!(node.get_node().unwrap(), Node::MaterializeNode)?;
 
         let table_id = TableId::from(&node.table_ref_id);
-        let keys = node
+        let keys: Vec<OrderPair> = node
             .column_orders
             .iter()
             .map(OrderPair::from_prost)
             .collect();
-        let column_ids = node
+        let column_ids: Vec<ColumnId> = node
             .column_ids
             .iter()
             .map(|id| ColumnId::from(*id))
             .collect();
 
+        let input = params.input.remove(0);
+        let arrangement_desc = ArrangementDesc {
+            column_descs: column_ids
+                .iter()
+                .zip_eq(input.schema().fields())
+                .map(|(column_id, field)| ColumnDesc::unnamed(*column_id, field.data_type()))
+                .collect(),
+            order_types: keys.iter().map(|k| k.order_type).collect(),
+        };
         let keyspace = Keyspace::table_root(store, &table_id);
 
-        let v2 = Box::new(MaterializeExecutorV2::new_from_v1(
-            params.input.remove(0),
+        // The existing rows are looked up by the pk to be overwritten.
+        let overwrite = node.overwrite.as_ref().map(|overwrite| -> Result<_> {
+            let table = CellBasedTable::new(
+                keyspace.clone(),
+                arrangement_desc.column_descs.clone(),
+                Some(OrderedRowSerializer::new(
+                    arrangement_desc.order_types.clone(),
+                )),
+                Arc::new(StateStoreMetrics::unused()),
+            );
+            let version_column = overwrite
+                .optional_version_column_index
+                .as_ref()
+                .map(|OptionalVersionColumnIndex::VersionColumnIndex(idx)| *idx as usize);
+            // The rows of `INSERT ... ON CONFLICT` are registered in the table source, which is
+            // created before the actors.
+            let table_source = match &overwrite.table_source_ref_id {
+                Some(table_source_ref_id) => {
+                    let source_id = TableId::new(table_source_ref_id.table_id as u32);
+                    let source_desc = params.env.source_manager().get_source(&source_id)?;
+                    Some((source_desc.source, overwrite.row_id_index as usize))
+                }
+                None => None,
+            };
+            Ok((table, version_column, table_source))
+        });
+        let overwrite = overwrite.transpose()?;
+
+        let registry = params.env.arrangement_registry();
+        let arrangement = registry.register(table_id, params.actor_id, arrangement_desc);
+
+        let mut v2 = MaterializeExecutorV2::new_from_v1(
+            input,
             keyspace,
             keys,
             column_ids,
             params.executor_id,
             params.op_info,
-        ));
+        )
+        .with_arrangement(arrangement);
+        if let Some((table, version_column, table_source)) = overwrite {
+            v2 = v2.with_conflict_overwrite(table, version_column);
+            if let Some((table_source, row_id_index)) = table_source {
+                v2 = v2.with_insert_on_conflict(table_source, row_id_index);
+            }
+        }
+        let v2 = Box::new(v2);
 
         Ok(Box::new(v2.v1()))
     }
@@ -0,0 +1,719 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, RowDeserializer, StreamChunk};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::try_match_expand;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::value_encoding::{deserialize_cell, serialize_cell};
+use risingwave_expr::expr::{build_from_prost, RowExpression};
+use risingwave_pb::plan::JoinType;
+use risingwave_pb::stream_plan;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::write_batch::WriteBatch;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::barrier_align::{AlignedMessage, BarrierAligner};
+use super::{Executor, ExecutorState, Message, PkIndices, PkIndicesRef, StatefulExecutor};
+use crate::executor::ExecutorBuilder;
+use crate::task::{ExecutorParams, LocalStreamManagerCore};
+
+pub struct NestedLoopJoinExecutorBuilder {}
+
+impl ExecutorBuilder for NestedLoopJoinExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &stream_plan::StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<Box<dyn Executor>> {
+        let node = try_match_expand!(node.get_node().unwrap(), Node::NestedLoopJoinNode)?;
+        let source_r = params.input.remove(1);
+        let source_l = params.input.remove(0);
+
+        let join_type = match node.get_join_type()? {
+            join_type @ (JoinType::Inner | JoinType::LeftOuter) => join_type,
+            join_type => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("{:?} in streaming nested loop join", join_type),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+        let condition = match node.get_condition() {
+            Ok(cond_prost) => Some(RowExpression::new(build_from_prost(cond_prost)?)),
+            Err(_) => None,
+        };
+
+        Ok(Box::new(NestedLoopJoinExecutor::new(
+            source_l,
+            source_r,
+            join_type,
+            condition,
+            params.pk_indices,
+            Keyspace::executor_root(store, params.executor_id),
+            params.executor_id,
+            params.op_info,
+        )))
+    }
+}
+
+/// The state of a distinct row of one side of the join.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct RowState {
+    /// The number of occurrences of the row.
+    count: i64,
+    /// The number of right rows the row is joined with. It's only maintained for the left side of
+    /// a left outer join, to tell when the row should be padded with `NULL`s.
+    degree: i64,
+}
+
+impl RowState {
+    fn serialize(&self) -> Result<Vec<u8>> {
+        let mut value = serialize_cell(&Some(ScalarImpl::Int64(self.count)))?;
+        value.extend(serialize_cell(&Some(ScalarImpl::Int64(self.degree)))?);
+        Ok(value)
+    }
+
+    fn deserialize(data: bytes::Bytes) -> Result<Self> {
+        let mut deserializer = value_encoding::Deserializer::new(data);
+        let mut next = || -> Result<i64> {
+            Ok(deserialize_cell(&mut deserializer, &DataType::Int64)?
+                .map(|v| v.into_int64())
+                .unwrap_or_default())
+        };
+        let count = next()?;
+        let degree = next()?;
+        Ok(Self { count, degree })
+    }
+}
+
+/// The rows of one side of the join, stored as a multiset: the key is the serialized row and the
+/// value is the [`RowState`] of the row.
+struct JoinSide<S: StateStore> {
+    keyspace: Keyspace<S>,
+    data_types: Vec<DataType>,
+    /// The states of rows fetched from the state store or updated since the last flush.
+    cache: HashMap<Row, RowState>,
+    /// The rows whose states should be written to the state store on the next flush.
+    dirty: HashSet<Row>,
+    /// Whether `cache` contains every row of this side.
+    fully_cached: bool,
+}
+
+impl<S: StateStore> JoinSide<S> {
+    fn new(keyspace: Keyspace<S>, data_types: Vec<DataType>) -> Self {
+        Self {
+            keyspace,
+            data_types,
+            cache: HashMap::new(),
+            dirty: HashSet::new(),
+            fully_cached: false,
+        }
+    }
+
+    async fn get(&mut self, row: &Row, epoch: u64) -> Result<RowState> {
+        if let Some(state) = self.cache.get(row) {
+            return Ok(*state);
+        }
+        if self.fully_cached {
+            return Ok(RowState::default());
+        }
+        let state = match self.keyspace.get(row.serialize()?, epoch).await? {
+            Some(data) => RowState::deserialize(data)?,
+            None => RowState::default(),
+        };
+        self.cache.insert(row.clone(), state);
+        Ok(state)
+    }
+
+    fn put(&mut self, row: Row, state: RowState) {
+        self.cache.insert(row.clone(), state);
+        self.dirty.insert(row);
+    }
+
+    /// Applies `op` on the count of `row`, and sets the degree of the row to `degree`.
+    async fn apply(&mut self, row: Row, op: Op, degree: i64, epoch: u64) -> Result<()> {
+        let delta = match op {
+            Op::Insert | Op::UpdateInsert => 1,
+            Op::Delete | Op::UpdateDelete => -1,
+        };
+        let mut state = self.get(&row, epoch).await?;
+        state.count += delta;
+        state.degree = degree;
+        assert!(state.count >= 0, "row count underflow");
+        self.put(row, state);
+        Ok(())
+    }
+
+    /// Returns all rows of this side with their states, merging the state store with the pending
+    /// updates in the cache.
+    async fn all_rows(&self, epoch: u64) -> Result<HashMap<Row, RowState>> {
+        let mut rows = HashMap::new();
+        if !self.fully_cached {
+            let deserializer = RowDeserializer::new(self.data_types.clone());
+            for (key, value) in self.keyspace.scan_strip_prefix(None, epoch).await? {
+                rows.insert(
+                    deserializer.deserialize(&key)?,
+                    RowState::deserialize(value)?,
+                );
+            }
+        }
+        rows.extend(self.cache.iter().map(|(row, state)| (row.clone(), *state)));
+        rows.retain(|_, state| state.count > 0);
+        Ok(rows)
+    }
+
+    /// Loads all rows of this side into the cache, so that later lookups never hit the storage.
+    async fn load_all(&mut self, epoch: u64) -> Result<()> {
+        if !self.fully_cached {
+            self.cache = self.all_rows(epoch).await?;
+            self.fully_cached = true;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, write_batch: &mut WriteBatch<S>) -> Result<()> {
+        let mut local = write_batch.prefixify(&self.keyspace);
+        for row in self.dirty.drain() {
+            let state = self.cache[&row];
+            if state.count == 0 {
+                local.delete(row.serialize()?);
+                self.cache.remove(&row);
+            } else {
+                local.put(
+                    row.serialize()?,
+                    StorageValue::new_default_put(state.serialize()?),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn clear_cache(&mut self) {
+        assert!(
+            self.dirty.is_empty(),
+            "cannot clear cache while states of nested loop join are dirty"
+        );
+        self.cache.clear();
+        self.fully_cached = false;
+    }
+}
+
+/// `NestedLoopJoinExecutor` joins two input streams without equi-conditions. Only `INNER` and
+/// `LEFT OUTER` joins are supported.
+///
+/// The right input is expected to be small and broadcast to every parallel unit of the join, so
+/// it is kept fully in memory once loaded. A change on the left side is joined against all the
+/// right rows, while a change on the right side scans all the left rows of this parallel unit. For
+/// left outer join, the degree of each left row, i.e. the number of right rows it's joined with,
+/// is kept in the state, so that a change on the right side knows whether a left row becomes or
+/// stops being padded with `NULL`s without joining it against the whole right side again.
+pub struct NestedLoopJoinExecutor<S: StateStore> {
+    /// Barrier aligner that combines two input streams and aligns their barriers
+    aligner: BarrierAligner,
+    /// The schema of the executor, the concatenation of both inputs
+    schema: Schema,
+    /// The primary key indices of the schema
+    pk_indices: PkIndices,
+    join_type: JoinType,
+    /// The join condition, evaluated on the concatenated row
+    cond: Option<RowExpression>,
+    /// Data types of the output rows
+    output_data_types: Vec<DataType>,
+    /// State of the left (probe) side
+    left: JoinSide<S>,
+    /// State of the right (broadcast) side
+    right: JoinSide<S>,
+    /// Debug info for the left executor
+    debug_l: String,
+    /// Debug info for the right executor
+    debug_r: String,
+    /// Identity string
+    identity: String,
+    /// Logical Operator Info
+    op_info: String,
+    /// Executor state
+    executor_state: ExecutorState,
+}
+
+impl<S: StateStore> std::fmt::Debug for NestedLoopJoinExecutor<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NestedLoopJoinExecutor")
+            .field("join_type", &self.join_type)
+            .field("input_left", &format_args!("{}", &self.debug_l))
+            .field("input_right", &format_args!("{}", &self.debug_r))
+            .field("pk_indices", &self.pk_indices)
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S: StateStore> Executor for NestedLoopJoinExecutor<S> {
+    async fn next(&mut self) -> Result<Message> {
+        let msg = loop {
+            match self.aligner.next().await {
+                // The output columns do not map to the same column on both sides, so the
+                // watermarks are not propagated through joins yet.
+                AlignedMessage::Watermark(_) => continue,
+                msg => break msg,
+            }
+        };
+        if let Some(barrier) = self.try_init_executor(&msg) {
+            return Ok(Message::Barrier(barrier));
+        }
+        match msg {
+            AlignedMessage::Left(message) => self.consume_chunk_left(message?).await,
+            AlignedMessage::Right(message) => self.consume_chunk_right(message?).await,
+            AlignedMessage::Barrier(barrier) => {
+                self.flush_data().await?;
+                self.update_executor_state(ExecutorState::Active(barrier.epoch.curr));
+                Ok(Message::Barrier(barrier))
+            }
+            AlignedMessage::Watermark(_) => unreachable!(),
+        }
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        self.identity.as_str()
+    }
+
+    fn logical_operator_info(&self) -> &str {
+        &self.op_info
+    }
+
+    fn clear_cache(&mut self) -> Result<()> {
+        self.left.clear_cache();
+        self.right.clear_cache();
+        Ok(())
+    }
+}
+
+impl<S: StateStore> NestedLoopJoinExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input_l: Box<dyn Executor>,
+        input_r: Box<dyn Executor>,
+        join_type: JoinType,
+        cond: Option<RowExpression>,
+        pk_indices: PkIndices,
+        keyspace: Keyspace<S>,
+        executor_id: u64,
+        op_info: String,
+    ) -> Self {
+        assert!(
+            matches!(join_type, JoinType::Inner | JoinType::LeftOuter),
+            "unsupported join type for nested loop join: {:?}",
+            join_type
+        );
+        let debug_l = format!("{:#?}", &input_l);
+        let debug_r = format!("{:#?}", &input_r);
+        let schema = Schema {
+            fields: [
+                input_l.schema().fields.clone(),
+                input_r.schema().fields.clone(),
+            ]
+            .concat(),
+        };
+        let output_data_types = schema.data_types();
+        let left = JoinSide::new(keyspace.append_u8(b'l'), input_l.schema().data_types());
+        let right = JoinSide::new(keyspace.append_u8(b'r'), input_r.schema().data_types());
+
+        Self {
+            aligner: BarrierAligner::new(input_l, input_r),
+            schema,
+            pk_indices,
+            join_type,
+            cond,
+            output_data_types,
+            left,
+            right,
+            debug_l,
+            debug_r,
+            identity: format!("NestedLoopJoinExecutor {:X}", executor_id),
+            op_info,
+            executor_state: ExecutorState::Init,
+        }
+    }
+
+    fn row_concat(left: &Row, right: &Row) -> Row {
+        Row([left.0.as_slice(), right.0.as_slice()].concat())
+    }
+
+    fn row_concat_null(&self, left: &Row) -> Row {
+        Self::row_concat(left, &Row(vec![None; self.right.data_types.len()]))
+    }
+
+    /// Evaluates the join condition on the concatenated row. A `NULL` result is not a match.
+    fn matches(
+        cond: &mut Option<RowExpression>,
+        row: &Row,
+        output_data_types: &[DataType],
+    ) -> Result<bool> {
+        match cond {
+            Some(cond) => Ok(cond
+                .eval(row, output_data_types)?
+                .as_bool()
+                .value_at(0)
+                .unwrap_or(false)),
+            None => Ok(true),
+        }
+    }
+
+    fn normalize_op(op: Op) -> Op {
+        match op {
+            Op::Insert | Op::UpdateInsert => Op::Insert,
+            Op::Delete | Op::UpdateDelete => Op::Delete,
+        }
+    }
+
+    async fn consume_chunk_left(&mut self, chunk: StreamChunk) -> Result<Message> {
+        let epoch = self.executor_state().epoch();
+        self.right.load_all(epoch).await?;
+        let (data_chunk, ops) = chunk.compact()?.into_parts();
+
+        let mut output = vec![];
+        for (row, op) in data_chunk.rows().zip_eq(ops.iter()) {
+            let left_row: Row = row.into();
+            let op = Self::normalize_op(*op);
+            let mut degree = 0;
+            for (right_row, state) in &self.right.cache {
+                let row = Self::row_concat(&left_row, right_row);
+                if state.count > 0 && Self::matches(&mut self.cond, &row, &self.output_data_types)?
+                {
+                    degree += state.count;
+                    output.extend(std::iter::repeat((op, row)).take(state.count as usize));
+                }
+            }
+            if degree == 0 && self.join_type == JoinType::LeftOuter {
+                output.push((op, self.row_concat_null(&left_row)));
+            }
+            self.left.apply(left_row, op, degree, epoch).await?;
+        }
+
+        let chunk = StreamChunk::from_rows(&output, &self.output_data_types)?;
+        Ok(Message::Chunk(chunk))
+    }
+
+    async fn consume_chunk_right(&mut self, chunk: StreamChunk) -> Result<Message> {
+        let epoch = self.executor_state().epoch();
+        self.right.load_all(epoch).await?;
+        let mut left_rows = self.left.all_rows(epoch).await?;
+        let (data_chunk, ops) = chunk.compact()?.into_parts();
+        let left_outer = self.join_type == JoinType::LeftOuter;
+
+        let mut output = vec![];
+        let mut updated_left_rows = HashSet::new();
+        for (row, op) in data_chunk.rows().zip_eq(ops.iter()) {
+            let right_row: Row = row.into();
+            let op = Self::normalize_op(*op);
+            for (left_row, state) in &mut left_rows {
+                let count = state.count as usize;
+                let row = Self::row_concat(left_row, &right_row);
+                if !Self::matches(&mut self.cond, &row, &self.output_data_types)? {
+                    continue;
+                }
+                match op {
+                    Op::Insert => {
+                        if left_outer && state.degree == 0 {
+                            let null_row = self.row_concat_null(left_row);
+                            output.extend(std::iter::repeat((Op::Delete, null_row)).take(count));
+                        }
+                        output.extend(std::iter::repeat((Op::Insert, row)).take(count));
+                        state.degree += 1;
+                    }
+                    Op::Delete => {
+                        output.extend(std::iter::repeat((Op::Delete, row)).take(count));
+                        state.degree -= 1;
+                        if left_outer && state.degree == 0 {
+                            let null_row = self.row_concat_null(left_row);
+                            output.extend(std::iter::repeat((Op::Insert, null_row)).take(count));
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+                if left_outer {
+                    updated_left_rows.insert(left_row.clone());
+                }
+            }
+            self.right.apply(right_row, op, 0, epoch).await?;
+        }
+        for row in updated_left_rows {
+            let state = left_rows[&row];
+            self.left.put(row, state);
+        }
+
+        let chunk = StreamChunk::from_rows(&output, &self.output_data_types)?;
+        Ok(Message::Chunk(chunk))
+    }
+
+    async fn flush_data(&mut self) -> Result<()> {
+        let epoch = self.executor_state().epoch();
+        let mut write_batch = self.left.keyspace.state_store().start_write_batch();
+        self.left.flush(&mut write_batch)?;
+        self.right.flush(&mut write_batch)?;
+        write_batch.ingest(epoch).await?;
+        // Only the broadcast side is kept in memory across epochs.
+        self.left.clear_cache();
+        Ok(())
+    }
+}
+
+impl<S: StateStore> StatefulExecutor for NestedLoopJoinExecutor<S> {
+    fn executor_state(&self) -> &ExecutorState {
+        &self.executor_state
+    }
+
+    fn update_executor_state(&mut self, new_state: ExecutorState) {
+        self.executor_state = new_state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::*;
+    use risingwave_common::catalog::Field;
+    use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
+    use risingwave_expr::expr::InputRefExpression;
+    use risingwave_pb::expr::expr_node::Type;
+    use risingwave_storage::memory::MemoryStateStore;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+    use super::*;
+    use crate::executor::test_utils::{create_in_memory_keyspace, MockAsyncSource};
+
+    /// Creates an executor joining two `i64` inputs on `left < right`.
+    fn create_executor(
+        join_type: JoinType,
+    ) -> (
+        UnboundedSender<Message>,
+        UnboundedSender<Message>,
+        NestedLoopJoinExecutor<MemoryStateStore>,
+    ) {
+        create_executor_with_keyspace(join_type, create_in_memory_keyspace(), 1)
+    }
+
+    fn create_executor_with_keyspace(
+        join_type: JoinType,
+        keyspace: Keyspace<MemoryStateStore>,
+        executor_id: u64,
+    ) -> (
+        UnboundedSender<Message>,
+        UnboundedSender<Message>,
+        NestedLoopJoinExecutor<MemoryStateStore>,
+    ) {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let (tx_l, rx_l) = unbounded_channel();
+        let (tx_r, rx_r) = unbounded_channel();
+        let source_l = MockAsyncSource::with_pk_indices(schema.clone(), rx_l, vec![0]);
+        let source_r = MockAsyncSource::with_pk_indices(schema, rx_r, vec![0]);
+        let cond = new_binary_expr(
+            Type::LessThan,
+            DataType::Boolean,
+            Box::new(InputRefExpression::new(DataType::Int64, 0)),
+            Box::new(InputRefExpression::new(DataType::Int64, 1)),
+        );
+        let executor = NestedLoopJoinExecutor::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            join_type,
+            Some(RowExpression::new(cond)),
+            vec![0, 1],
+            keyspace,
+            executor_id,
+            "NestedLoopJoinExecutor".to_string(),
+        );
+        (tx_l, tx_r, executor)
+    }
+
+    fn push(tx: &mut UnboundedSender<Message>, ops: Vec<Op>, values: Vec<i64>) {
+        let rows = ops
+            .into_iter()
+            .zip_eq(values)
+            .map(|(op, v)| (op, Row(vec![Some(ScalarImpl::Int64(v))])))
+            .collect_vec();
+        let chunk = StreamChunk::from_rows(&rows, &[DataType::Int64]).unwrap();
+        MockAsyncSource::push_chunks(tx, vec![chunk]);
+    }
+
+    /// Returns the rows of the next chunk, sorted since the order of matches is not determined.
+    async fn next_chunk(executor: &mut impl Executor) -> Vec<(Op, Option<i64>, Option<i64>)> {
+        match executor.next().await.unwrap() {
+            Message::Chunk(chunk) => {
+                let mut rows = chunk
+                    .ops()
+                    .iter()
+                    .cloned()
+                    .zip_eq(chunk.column_at(0).array_ref().as_int64().iter())
+                    .zip_eq(chunk.column_at(1).array_ref().as_int64().iter())
+                    .map(|((op, l), r)| (op, l, r))
+                    .collect_vec();
+                rows.sort();
+                rows
+            }
+            Message::Barrier(_) | Message::Watermark(_) => unreachable!(),
+        }
+    }
+
+    async fn barrier(
+        tx_l: &mut UnboundedSender<Message>,
+        tx_r: &mut UnboundedSender<Message>,
+        executor: &mut impl Executor,
+        epoch: u64,
+    ) {
+        MockAsyncSource::push_barrier(tx_l, epoch, false);
+        MockAsyncSource::push_barrier(tx_r, epoch, false);
+        assert!(matches!(
+            executor.next().await.unwrap(),
+            Message::Barrier(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_nested_loop_inner_join() {
+        let (mut tx_l, mut tx_r, mut executor) = create_executor(JoinType::Inner);
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 1).await;
+
+        push(&mut tx_r, vec![Op::Insert], vec![5]);
+        assert_eq!(next_chunk(&mut executor).await, vec![]);
+
+        push(&mut tx_l, vec![Op::Insert; 3], vec![1, 6, 3]);
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![
+                (Op::Insert, Some(1), Some(5)),
+                (Op::Insert, Some(3), Some(5))
+            ]
+        );
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 2).await;
+
+        // Left rows are read back from the state store.
+        push(&mut tx_r, vec![Op::Insert], vec![2]);
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![(Op::Insert, Some(1), Some(2))]
+        );
+
+        push(&mut tx_r, vec![Op::Delete], vec![5]);
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![
+                (Op::Delete, Some(1), Some(5)),
+                (Op::Delete, Some(3), Some(5))
+            ]
+        );
+
+        push(&mut tx_l, vec![Op::Delete], vec![1]);
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![(Op::Delete, Some(1), Some(2))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_nested_loop_left_outer_join() {
+        let (mut tx_l, mut tx_r, mut executor) = create_executor(JoinType::LeftOuter);
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 1).await;
+
+        push(&mut tx_r, vec![Op::Insert], vec![5]);
+        assert_eq!(next_chunk(&mut executor).await, vec![]);
+
+        push(&mut tx_l, vec![Op::Insert; 2], vec![1, 6]);
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![(Op::Insert, Some(1), Some(5)), (Op::Insert, Some(6), None)]
+        );
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 2).await;
+
+        push(&mut tx_r, vec![Op::Insert], vec![7]);
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![
+                (Op::Insert, Some(1), Some(7)),
+                (Op::Insert, Some(6), Some(7)),
+                (Op::Delete, Some(6), None)
+            ]
+        );
+        barrier(&mut tx_l, &mut tx_r, &mut executor, 3).await;
+
+        push(&mut tx_r, vec![Op::Delete], vec![7]);
+        assert_eq!(
+            next_chunk(&mut executor).await,
+            vec![
+                (Op::Insert, Some(6), None),
+                (Op::Delete, Some(1), Some(7)),
+                (Op::Delete, Some(6), Some(7))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_nested_loop_join_parallel() {
+        // Two parallel units of the join share the state store, and the right side is broadcast
+        // to both of them.
+        let store = MemoryStateStore::new();
+        let mut units = [1, 2]
+            .into_iter()
+            .map(|executor_id| {
+                create_executor_with_keyspace(
+                    JoinType::Inner,
+                    Keyspace::executor_root(store.clone(), executor_id),
+                    executor_id,
+                )
+            })
+            .collect_vec();
+        for (tx_l, tx_r, executor) in &mut units {
+            barrier(tx_l, tx_r, executor, 1).await;
+            push(tx_r, vec![Op::Insert], vec![5]);
+            assert_eq!(next_chunk(executor).await, vec![]);
+        }
+
+        for ((tx_l, tx_r, executor), left) in units.iter_mut().zip_eq([1, 3]) {
+            push(tx_l, vec![Op::Insert], vec![left]);
+            assert_eq!(
+                next_chunk(executor).await,
+                vec![(Op::Insert, Some(left), Some(5))]
+            );
+            barrier(tx_l, tx_r, executor, 2).await;
+        }
+
+        // Each unit only joins the left rows it owns, which are read back from the state store.
+        for ((tx_l, tx_r, executor), left) in units.iter_mut().zip_eq([1, 3]) {
+            push(tx_r, vec![Op::Delete], vec![5]);
+            assert_eq!(
+                next_chunk(executor).await,
+                vec![(Op::Delete, Some(left), Some(5))]
+            );
+            barrier(tx_l, tx_r, executor, 3).await;
+        }
+    }
+}
@@ -17,11 +17,13 @@ use risingwave_common::try_match_expand;
 use risingwave_expr::expr::build_from_prost;
 use risingwave_pb::stream_plan;
 use risingwave_pb::stream_plan::stream_node::Node;
-use risingwave_storage::StateStore;
+use risingwave_storage::{Keyspace, StateStore};
 
 use super::Executor;
 use crate::executor::ExecutorBuilder;
-use crate::executor_v2::{Executor as ExecutorV2, ProjectExecutor as ProjectExecutorV2};
+use crate::executor_v2::{
+    Executor as ExecutorV2, ProjectExecutor as ProjectExecutorV2, StatefulProjectExecutor,
+};
 use crate::task::{ExecutorParams, LocalStreamManagerCore};
 
 pub struct ProjectExecutorBuilder;
@@ -30,7 +32,7 @@ impl ExecutorBuilder for ProjectExecutorBuilder {
     fn new_boxed_executor(
         mut params: ExecutorParams,
         node: &stream_plan::StreamNode,
-        _store: impl StateStore,
+        store: impl StateStore,
         _stream: &mut LocalStreamManagerCore,
     ) -> Result<Box<dyn Executor>> {
         let node = try_match_expand!(node.get_node().unwrap(), Node::ProjectNode)?;
@@ -39,6 +41,20 @@ impl ExecutorBuilder for ProjectExecutorBuilder {
             .iter()
             .map(build_from_prost)
             .collect::<Result<Vec<_>>>()?;
+        if node.stateful {
+            let keyspace = Keyspace::executor_root(store, params.executor_id);
+            return Ok(Box::new(
+                Box::new(StatefulProjectExecutor::new_from_v1(
+                    params.input.remove(0),
+                    params.pk_indices,
+                    project_exprs,
+                    keyspace,
+                    params.executor_id,
+                    params.op_info,
+                ))
+                .v1(),
+            ));
+        }
         Ok(Box::new(
             Box::new(ProjectExecutorV2::new_from_v1(
                 params.input.remove(0),
@@ -0,0 +1,73 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures::FutureExt;
+use risingwave_common::catalog::TableId;
+use risingwave_common::error::{Result, ToRwResult};
+use risingwave_common::try_match_expand;
+use risingwave_connector::sink::{build_sink_writer, dead_letter_retention};
+use risingwave_connector::Properties;
+use risingwave_pb::stream_plan;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_storage::monitor::StateStoreMetrics;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::Executor;
+use crate::executor::ExecutorBuilder;
+use crate::executor_v2::{
+    DeadLetterTable, Executor as ExecutorV2, ExecutorV1AsV2, SinkExecutor as SinkExecutorV2,
+};
+use crate::task::{ExecutorParams, LocalStreamManagerCore};
+
+pub struct SinkExecutorBuilder;
+
+impl ExecutorBuilder for SinkExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &stream_plan::StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<Box<dyn Executor>> {
+        let node = try_match_expand!(node.get_node().unwrap(), Node::SinkNode)?;
+        let properties = Properties::new(node.get_properties().clone());
+        let retention = dead_letter_retention(&properties).to_rw_result()?;
+
+        let input = params.input.remove(0);
+        let schema = input.schema().clone();
+        let pk_indices = input.pk_indices().to_vec();
+        // The writer is created when the actor starts, since connecting to the external system is
+        // async. It's identified by the actor, which is kept by recovery.
+        let writer =
+            build_sink_writer(properties, schema, pk_indices, params.actor_id.to_string()).boxed();
+
+        let dead_letters = DeadLetterTable::new(
+            Keyspace::table_root(store.clone(), &TableId::new(node.dead_letter_table_id)),
+            params.actor_id,
+            retention,
+            Arc::new(StateStoreMetrics::unused()),
+        );
+        Ok(Box::new(
+            Box::new(SinkExecutorV2::new(
+                Box::new(ExecutorV1AsV2(input)),
+                writer,
+                Keyspace::executor_root(store, params.executor_id),
+                dead_letters,
+                params.executor_id,
+            ))
+            .v1(),
+        ))
+    }
+}
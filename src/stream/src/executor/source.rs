@@ -16,6 +16,7 @@ use std::fmt::{Debug, Formatter};
 use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use either::Either;
@@ -23,22 +24,31 @@ use futures::stream::{select_with_strategy, PollNext};
 use futures::{Future, Stream, StreamExt};
 use futures_async_stream::try_stream;
 use risingwave_common::array::column::Column;
-use risingwave_common::array::{ArrayBuilder, ArrayImpl, I64ArrayBuilder, StreamChunk};
+use risingwave_common::array::{ArrayBuilder, ArrayImpl, DataChunk, I64ArrayBuilder, StreamChunk};
 use risingwave_common::catalog::{ColumnId, Field, Schema, TableId};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError, ToRwResult};
 use risingwave_common::try_match_expand;
+use risingwave_common::types::ScalarRefImpl;
 use risingwave_connector::{state, SplitImpl};
+use risingwave_expr::expr::{build_from_prost, BoxedExpression};
+use risingwave_pb::data::{SourceSplits, SourceThrottle};
+use risingwave_pb::meta::event::EventType;
 use risingwave_pb::stream_plan;
 use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_source::connector_source::ConnectorStreamSource;
 use risingwave_source::*;
 use risingwave_storage::{Keyspace, StateStore};
-use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
 
 use crate::executor::monitor::StreamingMetrics;
 use crate::executor::{Executor, ExecutorBuilder, Message, PkIndices, PkIndicesRef};
-use crate::task::{ExecutorParams, LocalStreamManagerCore};
+use crate::task::{ActorId, EventReporter, ExecutorParams, LocalStreamManagerCore};
+
+/// The amount of data a throttled source may read at once after being idle, in seconds of its
+/// rate limit.
+const THROTTLE_BURST: Duration = Duration::from_secs(1);
 
 struct SourceReader {
     /// the future that builds stream_reader. It is required because source should not establish
@@ -48,6 +58,70 @@ struct SourceReader {
     pub stream_reader: Option<Box<dyn StreamSourceReader>>,
     /// The reader for barrier
     pub barrier_receiver: UnboundedReceiver<Message>,
+    /// Reports polling errors of the stream source
+    pub event_reporter: EventReporter,
+    pub source_id: TableId,
+    /// Delays the chunks read from the stream source to respect its rate limit
+    rate_limiter: RateLimiter,
+    /// Whether the source is paused by a `Pause` barrier
+    paused: watch::Receiver<bool>,
+    /// The splits assigned to the source by `SourceChangeSplit` barriers
+    splits: UnboundedReceiver<Vec<SplitImpl>>,
+}
+
+/// Limits the rate of a source by delaying the chunks read from it, once the source has read more
+/// than its rate limit allows. The rate limit can be changed at any time through the
+/// [`watch::Sender`] paired with `throttle`.
+struct RateLimiter {
+    throttle: watch::Receiver<SourceThrottle>,
+
+    /// The time after which all the chunks read so far are allowed to be emitted.
+    allowed_at: Instant,
+}
+
+impl RateLimiter {
+    fn new(throttle: watch::Receiver<SourceThrottle>) -> Self {
+        Self {
+            throttle,
+            allowed_at: Instant::now(),
+        }
+    }
+
+    /// Returns how long the `chunk` read at `now` should be delayed.
+    fn delay(&mut self, chunk: &StreamChunk, now: Instant) -> Duration {
+        let throttle = self.throttle.borrow().clone();
+        let cost = |amount: u64, rate: u64| {
+            if rate == 0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64(amount as f64 / rate as f64)
+            }
+        };
+        let rows_cost = cost(chunk.cardinality() as u64, throttle.rows_per_second);
+        let bytes_cost = cost(estimate_chunk_bytes(chunk), throttle.bytes_per_second);
+
+        // The quota saved up while the source is idle is capped by `THROTTLE_BURST`.
+        let earliest = now.checked_sub(THROTTLE_BURST).unwrap_or(now);
+        self.allowed_at = self.allowed_at.max(earliest) + rows_cost.max(bytes_cost);
+        self.allowed_at.saturating_duration_since(now)
+    }
+}
+
+/// Roughly estimates the size of the visible rows in `chunk`.
+fn estimate_chunk_bytes(chunk: &StreamChunk) -> u64 {
+    chunk
+        .rows()
+        .map(|row| {
+            row.values()
+                .map(|datum| match datum {
+                    Some(ScalarRefImpl::Utf8(s)) => s.len(),
+                    Some(ScalarRefImpl::Bytea(b)) => b.len(),
+                    Some(_) => std::mem::size_of::<i64>(),
+                    None => 0,
+                })
+                .sum::<usize>()
+        })
+        .sum::<usize>() as u64
 }
 
 /// `SourceReader` will be turned into this stream type.
@@ -86,10 +160,23 @@ pub struct SourceExecutor {
     metrics: Arc<StreamingMetrics>,
 
     /// Split info for stream source
-    #[allow(dead_code)]
     stream_source_splits: Vec<SplitImpl>,
 
     source_identify: String,
+
+    actor_id: ActorId,
+
+    /// Changes the rate limit of the source on `Throttle` barriers
+    throttle_tx: watch::Sender<SourceThrottle>,
+
+    /// Pauses and resumes the source on `Pause` and `Resume` barriers
+    pause_tx: watch::Sender<bool>,
+
+    /// Assigns new splits to the source on `SourceChangeSplit` barriers
+    splits_tx: UnboundedSender<Vec<SplitImpl>>,
+
+    /// The index of the event time column and the `EVENT TIME AS` expression filling it
+    event_time: Option<(usize, BoxedExpression)>,
 }
 
 pub struct SourceExecutorBuilder {}
@@ -140,21 +227,31 @@ impl ExecutorBuilder for SourceExecutorBuilder {
         }
         let schema = Schema::new(fields);
         let keyspace = Keyspace::executor_root(store, params.executor_id);
+        let event_time = match &node.event_time_expr {
+            Some(expr) => Some((node.event_time_idx as usize, build_from_prost(expr)?)),
+            None => None,
+        };
 
-        Ok(Box::new(SourceExecutor::new(
-            source_id,
-            source_desc,
-            keyspace,
-            column_ids,
-            schema,
-            params.pk_indices,
-            barrier_receiver,
-            params.executor_id,
-            params.operator_id,
-            params.op_info,
-            params.executor_stats,
-            stream_source_splits,
-        )?))
+        Ok(Box::new(
+            SourceExecutor::new(
+                source_id,
+                source_desc,
+                keyspace,
+                column_ids,
+                schema,
+                params.pk_indices,
+                barrier_receiver,
+                params.actor_id,
+                params.executor_id,
+                params.operator_id,
+                params.op_info,
+                params.executor_stats,
+                stream_source_splits,
+                params.env.event_reporter().clone(),
+            )?
+            .with_throttle(node.throttle.clone().unwrap_or_default())
+            .with_event_time(event_time),
+        ))
     }
 }
 
@@ -192,11 +289,13 @@ impl SourceExecutor {
         schema: Schema,
         pk_indices: PkIndices,
         barrier_receiver: UnboundedReceiver<Message>,
+        actor_id: ActorId,
         executor_id: u64,
         operator_id: u64,
         op_info: String,
         streaming_metrics: Arc<StreamingMetrics>,
         stream_source_splits: Vec<SplitImpl>,
+        event_reporter: EventReporter,
     ) -> Result<Self> {
         let source = source_desc.clone().source;
         let stream_reader_future: StreamReaderFuture = Box::pin(build_stream_reader(
@@ -205,6 +304,9 @@ impl SourceExecutor {
             column_ids.clone(),
             keyspace,
         ));
+        let (throttle_tx, throttle_rx) = watch::channel(SourceThrottle::default());
+        let (pause_tx, pause_rx) = watch::channel(false);
+        let (splits_tx, splits_rx) = unbounded_channel();
 
         Ok(Self {
             source_id,
@@ -216,6 +318,11 @@ impl SourceExecutor {
                 stream_reader_future: Some(stream_reader_future),
                 stream_reader: None,
                 barrier_receiver,
+                event_reporter,
+                source_id,
+                rate_limiter: RateLimiter::new(throttle_rx),
+                paused: pause_rx,
+                splits: splits_rx,
             }),
             next_row_id: AtomicU64::from(0u64),
             identity: format!("SourceExecutor {:X}", executor_id),
@@ -224,9 +331,56 @@ impl SourceExecutor {
             metrics: streaming_metrics,
             stream_source_splits,
             source_identify: "Table_".to_string() + &source_id.table_id().to_string(),
+            actor_id,
+            throttle_tx,
+            pause_tx,
+            splits_tx,
+            event_time: None,
         })
     }
 
+    /// Set the rate limit of the source.
+    pub fn with_throttle(self, throttle: SourceThrottle) -> Self {
+        self.set_throttle(throttle);
+        self
+    }
+
+    /// Fill the event time column at the given index with the expression evaluated on each row.
+    pub fn with_event_time(mut self, event_time: Option<(usize, BoxedExpression)>) -> Self {
+        self.event_time = event_time;
+        self
+    }
+
+    fn set_throttle(&self, throttle: SourceThrottle) {
+        // The receiver lives as long as the reader stream, which may have been dropped.
+        let _ = self.throttle_tx.send(throttle);
+    }
+
+    fn set_paused(&self, paused: bool) {
+        // The receiver lives as long as the reader stream, which may have been dropped.
+        let _ = self.pause_tx.send(paused);
+    }
+
+    /// Replaces the splits of the source with the ones assigned by a `SourceChangeSplit` barrier.
+    /// The reader starts reading the new splits, while the others continue.
+    fn change_splits(&mut self, splits: &SourceSplits) -> Result<()> {
+        let splits = splits
+            .splits
+            .iter()
+            .map(|split| SplitImpl::restore_from_bytes(splits.split_type.clone(), split))
+            .collect::<anyhow::Result<Vec<SplitImpl>>>()
+            .to_rw_result()?;
+        info!(
+            "source actor {} is assigned splits {:?}",
+            self.actor_id,
+            splits.iter().map(SplitImpl::id).collect::<Vec<_>>()
+        );
+        self.stream_source_splits = splits.clone();
+        // The receiver lives as long as the reader stream, which may have been dropped.
+        let _ = self.splits_tx.send(splits);
+        Ok(())
+    }
+
     fn gen_row_column(&mut self, len: usize) -> Column {
         let mut builder = I64ArrayBuilder::new(len).unwrap();
 
@@ -255,29 +409,89 @@ impl SourceExecutor {
         }
         chunk
     }
+
+    fn fill_event_time_column(&self, chunk: StreamChunk) -> Result<StreamChunk> {
+        match &self.event_time {
+            Some((idx, expr)) => {
+                let (ops, mut columns, bitmap) = chunk.into_inner();
+                let data_chunk = DataChunk::builder().columns(columns.clone()).build();
+                columns[*idx] = Column::new(expr.eval(&data_chunk)?);
+                Ok(StreamChunk::new(ops, columns, bitmap))
+            }
+            None => Ok(chunk),
+        }
+    }
 }
 
 impl SourceReader {
     #[try_stream(ok = StreamChunk, error = RwError)]
-    async fn stream_reader(mut stream_reader: Box<dyn StreamSourceReader>) {
+    async fn stream_reader(
+        mut stream_reader: Box<dyn StreamSourceReader>,
+        event_reporter: EventReporter,
+        source_id: TableId,
+        mut rate_limiter: RateLimiter,
+        mut paused: watch::Receiver<bool>,
+        mut splits: UnboundedReceiver<Vec<SplitImpl>>,
+    ) {
         loop {
-            match stream_reader.next().await {
+            Self::wait_for_resume(&mut paused).await;
+            // The reader may block until its new splits are assigned, e.g. with no splits yet.
+            let result = tokio::select! {
+                biased;
+                Some(splits) = splits.recv() => {
+                    stream_reader.assign_splits(splits).await.map(|_| None)
+                }
+                chunk = stream_reader.next() => chunk.map(Some),
+            };
+            match result {
                 Err(e) => {
-                    // TODO: report this error to meta service to mark the actors failed.
+                    // TODO: mark the actors failed in meta service.
                     error!("hang up stream reader due to polling error: {}", e);
+                    event_reporter.report(
+                        EventType::SourcePollFailure,
+                        source_id.table_id(),
+                        format!("hang up stream reader due to polling error: {}", e),
+                    );
 
                     // Drop the reader, then the error might be caught by the writer side.
                     drop(stream_reader);
                     // Then hang up this stream by breaking the loop.
                     break;
                 }
-                Ok(chunk) => yield chunk,
+                Ok(None) => {}
+                Ok(Some(chunk)) => {
+                    // The messages failed to be parsed are skipped by the reader.
+                    for failure in stream_reader.take_parse_failures() {
+                        event_reporter.report(
+                            EventType::SourceParseFailure,
+                            source_id.table_id(),
+                            failure,
+                        );
+                    }
+                    let delay = rate_limiter.delay(&chunk, Instant::now());
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    // The source may have been paused while reading the chunk.
+                    Self::wait_for_resume(&mut paused).await;
+                    yield chunk
+                }
             }
         }
 
         futures::future::pending().await
     }
 
+    /// Waits until the source is not paused. If the executor is dropped while the source is
+    /// paused, this hangs up forever, just as the stream reader does after errors.
+    async fn wait_for_resume(paused: &mut watch::Receiver<bool>) {
+        while *paused.borrow() {
+            if paused.changed().await.is_err() {
+                futures::future::pending::<()>().await;
+            }
+        }
+    }
+
     #[try_stream(ok = Message, error = RwError)]
     async fn barrier_receiver(mut barrier_receiver: UnboundedReceiver<Message>) {
         while let Some(msg) = barrier_receiver.recv().await {
@@ -293,7 +507,14 @@ impl SourceReader {
     }
 
     pub fn into_stream(self) -> impl Stream<Item = Either<Result<Message>, Result<StreamChunk>>> {
-        let stream_reader = Self::stream_reader(self.stream_reader.unwrap());
+        let stream_reader = Self::stream_reader(
+            self.stream_reader.unwrap(),
+            self.event_reporter,
+            self.source_id,
+            self.rate_limiter,
+            self.paused,
+            self.splits,
+        );
         let barrier_receiver = Self::barrier_receiver(self.barrier_receiver);
         select_with_strategy(
             barrier_receiver.map(Either::Left),
@@ -311,12 +532,36 @@ impl Executor for SourceExecutor {
                 .stream_reader
                 .replace(reader.stream_reader_future.as_mut().unwrap().await?);
             reader.stream_reader.as_mut().unwrap().open().await?;
+            if !self.stream_source_splits.is_empty() {
+                reader
+                    .stream_reader
+                    .as_mut()
+                    .unwrap()
+                    .assign_splits(self.stream_source_splits.clone())
+                    .await?;
+            }
             self.reader_stream.replace(reader.into_stream().boxed());
         }
 
         match self.reader_stream.as_mut().unwrap().next().await {
             // This branch will be preferred.
-            Some(Either::Left(message)) => message,
+            Some(Either::Left(message)) => {
+                let message = message?;
+                if let Message::Barrier(barrier) = &message {
+                    if let Some(throttle) = barrier.throttle(self.actor_id) {
+                        self.set_throttle(throttle.clone());
+                    }
+                    if let Some(splits) = barrier.source_splits(self.actor_id) {
+                        self.change_splits(splits)?;
+                    }
+                    if barrier.is_pause() {
+                        self.set_paused(true);
+                    } else if barrier.is_resume() {
+                        self.set_paused(false);
+                    }
+                }
+                Ok(message)
+            }
 
             // If there's barrier, this branch will be deferred.
             Some(Either::Right(chunk)) => {
@@ -330,6 +575,7 @@ impl Executor for SourceExecutor {
                 if !matches!(self.source_desc.source.as_ref(), SourceImpl::TableV2(_)) {
                     chunk = self.refill_row_id_column(chunk);
                 }
+                chunk = self.fill_event_time_column(chunk)?;
 
                 self.metrics
                     .source_output_row_count
@@ -473,9 +719,11 @@ mod tests {
             barrier_receiver,
             1,
             1,
+            1,
             "SourceExecutor".to_string(),
             Arc::new(StreamingMetrics::new(prometheus::Registry::new())),
             vec![],
+            EventReporter::disabled("127.0.0.1:5688".parse().unwrap()),
         )
         .unwrap();
 
@@ -514,6 +762,7 @@ mod tests {
                 Message::Barrier(barrier) => {
                     assert_eq!(barrier.epoch, Epoch::new_test_epoch(1))
                 }
+                Message::Watermark(_) => unreachable!(),
             }
         }
 
@@ -611,9 +860,11 @@ mod tests {
             barrier_receiver,
             1,
             1,
+            1,
             "SourceExecutor".to_string(),
             Arc::new(StreamingMetrics::unused()),
             vec![],
+            EventReporter::disabled("127.0.0.1:5688".parse().unwrap()),
         )
         .unwrap();
 
@@ -639,4 +890,167 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_pause_and_resume() -> Result<()> {
+        let table_id = TableId::default();
+
+        let rowid_type = DataType::Int64;
+        let col1_type = DataType::Int32;
+        let col2_type = DataType::Varchar;
+
+        let table_columns = vec![
+            ColumnDesc {
+                column_id: ColumnId::from(0),
+                data_type: rowid_type.clone(),
+                name: String::new(),
+                field_descs: vec![],
+                type_name: "".to_string(),
+            },
+            ColumnDesc {
+                column_id: ColumnId::from(1),
+                data_type: col1_type.clone(),
+                name: String::new(),
+                field_descs: vec![],
+                type_name: "".to_string(),
+            },
+            ColumnDesc {
+                column_id: ColumnId::from(2),
+                data_type: col2_type.clone(),
+                name: String::new(),
+                field_descs: vec![],
+                type_name: "".to_string(),
+            },
+        ];
+        let source_manager = MemSourceManager::new();
+        source_manager.create_table_source_v2(&table_id, table_columns)?;
+        let source_desc = source_manager.get_source(&table_id)?;
+        let source = source_desc.clone().source;
+
+        // Prepare test data chunks
+        let rowid_arr1: Arc<ArrayImpl> = Arc::new(array_nonnull! { I64Array, [0, 0, 0] }.into());
+        let col1_arr1: Arc<ArrayImpl> = Arc::new(array_nonnull! { I32Array, [1, 2, 3] }.into());
+        let col2_arr1: Arc<ArrayImpl> =
+            Arc::new(array_nonnull! { Utf8Array, ["foo", "bar", "baz"] }.into());
+
+        let chunk = {
+            let rowid = Column::new(rowid_arr1.clone());
+            let col1 = Column::new(col1_arr1.clone());
+            let col2 = Column::new(col2_arr1.clone());
+            let vis = vec![Op::Insert, Op::Insert, Op::Insert];
+            StreamChunk::new(vis, vec![rowid, col1, col2], None)
+        };
+
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(rowid_type),
+                Field::unnamed(col1_type),
+                Field::unnamed(col2_type),
+            ],
+        };
+
+        let column_ids = vec![0.into(), 1.into(), 2.into()];
+        let pk_indices = vec![0];
+
+        let (barrier_sender, barrier_receiver) = unbounded_channel();
+        let keyspace = Keyspace::executor_root(MemoryStateStore::new(), 0x2333);
+        let mut source_executor = SourceExecutor::new(
+            table_id,
+            source_desc,
+            keyspace,
+            column_ids,
+            schema,
+            pk_indices,
+            barrier_receiver,
+            1,
+            1,
+            1,
+            "SourceExecutor".to_string(),
+            Arc::new(StreamingMetrics::unused()),
+            vec![],
+            EventReporter::disabled("127.0.0.1:5688".parse().unwrap()),
+        )
+        .unwrap();
+
+        let write_chunk = |chunk: StreamChunk| {
+            let source = source.clone();
+            tokio::spawn(async move {
+                let table_source = source.as_table_v2().unwrap();
+                table_source.blocking_write_chunk(chunk).await.unwrap();
+            });
+        };
+
+        barrier_sender
+            .send(Message::Barrier(
+                Barrier::new_test_barrier(1).with_mutation(Mutation::Pause),
+            ))
+            .unwrap();
+        assert!(matches!(
+            source_executor.next().await.unwrap(),
+            Message::Barrier(barrier) if barrier.is_pause()
+        ));
+
+        // No data is read while the source is paused.
+        write_chunk(chunk);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), source_executor.next())
+                .await
+                .is_err()
+        );
+
+        barrier_sender
+            .send(Message::Barrier(
+                Barrier::new_test_barrier(2).with_mutation(Mutation::Resume),
+            ))
+            .unwrap();
+        assert!(matches!(
+            source_executor.next().await.unwrap(),
+            Message::Barrier(barrier) if barrier.is_resume()
+        ));
+        assert!(matches!(
+            source_executor.next().await.unwrap(),
+            Message::Chunk(chunk) if chunk.cardinality() == 3
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let (throttle_tx, throttle_rx) = watch::channel(SourceThrottle::default());
+        let mut rate_limiter = RateLimiter::new(throttle_rx);
+        let chunk = StreamChunk::new(
+            vec![Op::Insert; 3],
+            vec![Column::new(Arc::new(
+                array_nonnull! { I64Array, [1, 2, 3] }.into(),
+            ))],
+            None,
+        );
+        let second = Duration::from_secs(1);
+        let now = Instant::now() + 2 * second;
+
+        // Unlimited.
+        assert_eq!(rate_limiter.delay(&chunk, now), Duration::ZERO);
+        assert_eq!(rate_limiter.delay(&chunk, now), Duration::ZERO);
+
+        throttle_tx
+            .send(SourceThrottle {
+                rows_per_second: 3,
+                bytes_per_second: 0,
+            })
+            .unwrap();
+        // A burst of one second is allowed.
+        assert_eq!(rate_limiter.delay(&chunk, now), Duration::ZERO);
+        assert_eq!(rate_limiter.delay(&chunk, now), second);
+        assert_eq!(rate_limiter.delay(&chunk, now + second), second);
+
+        // 3 rows of 8 bytes take 2 seconds.
+        throttle_tx
+            .send(SourceThrottle {
+                rows_per_second: 0,
+                bytes_per_second: 12,
+            })
+            .unwrap();
+        assert_eq!(rate_limiter.delay(&chunk, now + 5 * second), second);
+    }
 }
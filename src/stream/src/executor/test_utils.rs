@@ -81,6 +81,12 @@ impl MockSource {
         }
         self.msgs.push_back(Message::Barrier(barrier));
     }
+
+    #[allow(dead_code)]
+    pub fn push_watermark(&mut self, col_idx: usize, data_type: DataType, val: ScalarImpl) {
+        self.msgs
+            .push_back(Message::Watermark(Watermark::new(col_idx, data_type, val)));
+    }
 }
 
 #[async_trait]
@@ -170,6 +176,17 @@ impl MockAsyncSource {
         }
         tx.send(Message::Barrier(barrier)).expect("Receiver closed");
     }
+
+    #[allow(dead_code)]
+    pub fn push_watermark(
+        tx: &mut UnboundedSender<Message>,
+        col_idx: usize,
+        data_type: DataType,
+        val: ScalarImpl,
+    ) {
+        tx.send(Message::Watermark(Watermark::new(col_idx, data_type, val)))
+            .expect("Receiver closed");
+    }
 }
 
 #[async_trait]
@@ -54,19 +54,26 @@ impl ExecutorBuilder for TopNExecutorBuilder {
             .iter()
             .map(|key| *key as usize)
             .collect::<Vec<_>>();
+        let memory_handle = params
+            .env
+            .memory_manager()
+            .register(format!("TopNExecutor {:X}", params.executor_id));
         Ok(Box::new(
-            Box::new(TopNExecutorV2::new_from_v1(
-                params.input.remove(0),
-                order_types,
-                (node.offset as usize, limit),
-                params.pk_indices,
-                keyspace,
-                cache_size,
-                total_count,
-                params.executor_id,
-                params.op_info,
-                key_indices,
-            )?)
+            Box::new(
+                TopNExecutorV2::new_from_v1(
+                    params.input.remove(0),
+                    order_types,
+                    (node.offset as usize, limit),
+                    params.pk_indices,
+                    keyspace,
+                    cache_size,
+                    total_count,
+                    params.executor_id,
+                    params.op_info,
+                    key_indices,
+                )?
+                .with_memory_handle(memory_handle),
+            )
             .v1(),
         ))
     }
@@ -0,0 +1,58 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::Result;
+use risingwave_common::try_match_expand;
+use risingwave_pb::stream_plan;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_storage::StateStore;
+
+use super::Executor;
+use crate::executor::ExecutorBuilder;
+use crate::executor_v2::{
+    BoxedExecutor, Executor as ExecutorV2, ExecutorInfo, ExecutorV1AsV2,
+    UnionExecutor as UnionExecutorV2,
+};
+use crate::task::{ExecutorParams, LocalStreamManagerCore};
+
+pub struct UnionExecutorBuilder;
+
+impl ExecutorBuilder for UnionExecutorBuilder {
+    fn new_boxed_executor(
+        params: ExecutorParams,
+        node: &stream_plan::StreamNode,
+        _store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<Box<dyn Executor>> {
+        try_match_expand!(node.get_node().unwrap(), Node::UnionNode)?;
+        let schema = params.input[0].schema().clone();
+        let inputs = params
+            .input
+            .into_iter()
+            .map(|input| Box::new(ExecutorV1AsV2(input)) as BoxedExecutor)
+            .collect();
+
+        Ok(Box::new(
+            Box::new(UnionExecutorV2::new(
+                ExecutorInfo {
+                    schema,
+                    pk_indices: params.pk_indices,
+                    identity: format!("UnionExecutor {:X}", params.executor_id),
+                },
+                inputs,
+            ))
+            .v1(),
+        ))
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::Result;
+use risingwave_common::try_match_expand;
+use risingwave_expr::expr::{build_from_prost, BoxedExpression};
+use risingwave_pb::stream_plan;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_storage::{Keyspace, StateStore};
+use tokio::sync::mpsc::unbounded_channel;
+
+use super::Executor;
+use crate::executor::ExecutorBuilder;
+use crate::executor_v2::{
+    Executor as ExecutorV2, ExecutorInfo, ValuesExecutor as ValuesExecutorV2,
+};
+use crate::task::{ExecutorParams, LocalStreamManagerCore};
+
+pub struct ValuesExecutorBuilder;
+
+impl ExecutorBuilder for ValuesExecutorBuilder {
+    fn new_boxed_executor(
+        params: ExecutorParams,
+        node: &stream_plan::StreamNode,
+        store: impl StateStore,
+        stream: &mut LocalStreamManagerCore,
+    ) -> Result<Box<dyn Executor>> {
+        let node = try_match_expand!(node.get_node().unwrap(), Node::ValuesNode)?;
+        let (sender, barrier_receiver) = unbounded_channel();
+        stream
+            .context
+            .lock_barrier_manager()
+            .register_sender(params.actor_id, sender);
+
+        let rows = node
+            .get_tuples()
+            .iter()
+            .map(|tuple| {
+                tuple
+                    .get_cells()
+                    .iter()
+                    .map(build_from_prost)
+                    .collect::<Result<Vec<BoxedExpression>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let schema = Schema::new(node.get_fields().iter().map(Field::from).collect());
+
+        Ok(Box::new(
+            Box::new(ValuesExecutorV2::new(
+                ExecutorInfo {
+                    schema,
+                    pk_indices: params.pk_indices,
+                    identity: format!("ValuesExecutor {:X}", params.executor_id),
+                },
+                rows,
+                barrier_receiver,
+                Keyspace::executor_root(store, params.executor_id),
+            ))
+            .v1(),
+        ))
+    }
+}
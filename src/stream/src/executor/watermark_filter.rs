@@ -0,0 +1,55 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use risingwave_common::error::Result;
+use risingwave_common::try_match_expand;
+use risingwave_expr::expr::build_from_prost;
+use risingwave_pb::stream_plan;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::Executor;
+use crate::executor::ExecutorBuilder;
+use crate::executor_v2::{
+    Executor as ExecutorV2, ExecutorV1AsV2, WatermarkFilterExecutor as WatermarkFilterExecutorV2,
+};
+use crate::task::{ExecutorParams, LocalStreamManagerCore};
+
+pub struct WatermarkFilterExecutorBuilder;
+
+impl ExecutorBuilder for WatermarkFilterExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &stream_plan::StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<Box<dyn Executor>> {
+        let node = try_match_expand!(node.get_node().unwrap(), Node::WatermarkFilterNode)?;
+        let watermark_expr = build_from_prost(node.get_watermark_expr()?)?;
+        let keyspace = Keyspace::executor_root(store, params.executor_id);
+        Ok(Box::new(
+            Box::new(WatermarkFilterExecutorV2::new(
+                Box::new(ExecutorV1AsV2(params.input.remove(0))),
+                node.event_time_col_idx as usize,
+                watermark_expr,
+                (node.idle_timeout_ms > 0).then(|| Duration::from_millis(node.idle_timeout_ms)),
+                keyspace,
+                params.executor_id,
+            ))
+            .v1(),
+        ))
+    }
+}
@@ -15,6 +15,7 @@
 use std::slice;
 
 use risingwave_common::types::DataType;
+use risingwave_common::util::sort_util::OrderPair;
 use risingwave_expr::expr::AggKind;
 
 /// An aggregation function may accept 0, 1 or 2 arguments.
@@ -60,4 +61,10 @@ pub struct AggCall {
     pub args: AggArgs,
     /// The return type of aggregation function.
     pub return_type: DataType,
+    /// Order requirements specified in the aggregation, e.g. `string_agg(a, ',' ORDER BY b)`.
+    /// The column indices refer to [`risingwave_common::array::StreamChunk`].
+    pub order_pairs: Vec<OrderPair>,
+    /// Whether the aggregation only takes distinct values of its arguments, e.g.
+    /// `count(DISTINCT a)`.
+    pub distinct: bool,
 }
@@ -18,17 +18,21 @@ use async_trait::async_trait;
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
+use prometheus::IntCounter;
 use risingwave_common::array::column::Column;
+use risingwave_common::array::stream_chunk::Ops;
 use risingwave_common::array::{ArrayBuilderImpl, ArrayImpl, ArrayRef, Op, Row, StreamChunk};
+use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::{Field, Schema};
 use risingwave_common::error::Result;
 use risingwave_common::hash::HashCode;
 use risingwave_common::types::Datum;
+use risingwave_storage::write_batch::WriteBatch;
 use risingwave_storage::{Keyspace, StateStore};
 use static_assertions::const_assert_eq;
 
 use super::AggCall;
-use crate::executor::managed_state::aggregation::ManagedStateImpl;
+use crate::executor::managed_state::aggregation::{ManagedDistinctState, ManagedStateImpl};
 use crate::executor::PkDataTypes;
 use crate::executor_v2::error::{
     StreamExecutorError, StreamExecutorResult, TracedStreamExecutorError,
@@ -41,6 +45,9 @@ pub struct AggState<S: StateStore> {
     /// Current managed states for all [`AggCall`]s.
     pub managed_states: Vec<ManagedStateImpl<S>>,
 
+    /// Deduplication states for `DISTINCT` [`AggCall`]s, `None` for the others.
+    pub distinct_states: Vec<Option<ManagedDistinctState<S>>>,
+
     /// Previous outputs of managed states. Initializing with `None`.
     pub prev_states: Option<Vec<Datum>>,
 }
@@ -97,6 +104,51 @@ impl<S: StateStore> AggState<S> {
         Ok(())
     }
 
+    /// Apply a batch to the states of all [`AggCall`]s, where `data[i]` holds the inputs of the
+    /// `i`-th one. The inputs of a `DISTINCT` aggregation are deduplicated before being applied.
+    pub async fn apply_batch(
+        &mut self,
+        ops: Ops<'_>,
+        visibility: Option<&Bitmap>,
+        data: &[Vec<&ArrayImpl>],
+        epoch: u64,
+    ) -> Result<()> {
+        for ((managed_state, distinct_state), data) in self
+            .managed_states
+            .iter_mut()
+            .zip_eq(self.distinct_states.iter_mut())
+            .zip_eq(data.iter())
+        {
+            match distinct_state {
+                Some(distinct_state) => {
+                    let visibility = distinct_state
+                        .apply_batch(ops, visibility, data, epoch)
+                        .await?;
+                    managed_state
+                        .apply_batch(ops, Some(&visibility), data, epoch)
+                        .await?;
+                }
+                None => {
+                    managed_state
+                        .apply_batch(ops, visibility, data, epoch)
+                        .await?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Flush the states of all [`AggCall`]s to a write batch.
+    pub fn flush(&mut self, write_batch: &mut WriteBatch<S>) -> Result<()> {
+        for state in &mut self.managed_states {
+            state.flush(write_batch)?;
+        }
+        for state in self.distinct_states.iter_mut().flatten() {
+            state.flush(write_batch)?;
+        }
+        Ok(())
+    }
+
     /// Build changes into `builders` and `new_ops`, according to previous and current states. Note
     /// that for [`crate::executor_v2::HashAggExecutor`].
     ///
@@ -188,7 +240,8 @@ impl<S: StateStore> AggState<S> {
     }
 }
 
-/// Get clones of aggregation inputs by `agg_calls` and `columns`.
+/// Get clones of aggregation inputs by `agg_calls` and `columns`. The order-by columns of each
+/// aggregation, if any, follow its arguments.
 pub fn agg_input_arrays(agg_calls: &[AggCall], columns: &[Column]) -> Vec<Vec<ArrayRef>> {
     agg_calls
         .iter()
@@ -196,13 +249,15 @@ pub fn agg_input_arrays(agg_calls: &[AggCall], columns: &[Column]) -> Vec<Vec<Ar
             agg.args
                 .val_indices()
                 .iter()
+                .chain(agg.order_pairs.iter().map(|o| &o.column_idx))
                 .map(|val_idx| columns[*val_idx].array())
                 .collect()
         })
         .collect()
 }
 
-/// Get references to aggregation inputs by `agg_calls` and `columns`.
+/// Get references to aggregation inputs by `agg_calls` and `columns`. The order-by columns of
+/// each aggregation, if any, follow its arguments.
 pub fn agg_input_array_refs<'a>(
     agg_calls: &[AggCall],
     columns: &'a [Column],
@@ -213,6 +268,7 @@ pub fn agg_input_array_refs<'a>(
             agg.args
                 .val_indices()
                 .iter()
+                .chain(agg.order_pairs.iter().map(|o| &o.column_idx))
                 .map(|val_idx| columns[*val_idx].array_ref())
                 .collect()
         })
@@ -296,6 +352,8 @@ where
                     yield Message::Barrier(barrier);
                     epoch = next_epoch;
                 }
+                // TODO: propagate the watermarks on the group keys.
+                Message::Watermark(_) => {}
             }
         }
     }
@@ -328,6 +386,7 @@ pub fn generate_agg_schema(
 
 /// Generate initial [`AggState`] from `agg_calls`. For [`crate::executor_v2::HashAggExecutor`], the
 /// group key should be provided.
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_agg_state<S: StateStore>(
     key: Option<&Row>,
     agg_calls: &[AggCall],
@@ -335,23 +394,37 @@ pub async fn generate_agg_state<S: StateStore>(
     pk_data_types: PkDataTypes,
     epoch: u64,
     key_hash_code: Option<HashCode>,
+    extreme_cache_capacity: usize,
+    extreme_cache_miss_count: Option<&IntCounter>,
 ) -> StreamExecutorResult<AggState<S>> {
     let mut managed_states = vec![];
+    let mut distinct_states = vec![];
 
     // Currently the loop here only works if `ROW_COUNT_COLUMN` is 0.
     const_assert_eq!(ROW_COUNT_COLUMN, 0);
     let mut row_count = None;
 
-    for (idx, agg_call) in agg_calls.iter().enumerate() {
-        // TODO: in pure in-memory engine, we should not do this serialization.
-
-        // The prefix of the state is `agg_call_idx / [group_key]`
-        let keyspace = if let Some(key) = key {
+    // TODO: in pure in-memory engine, we should not do this serialization.
+    let state_keyspace = |prefix: usize| {
+        if let Some(key) = key {
             let bytes = key.serialize().unwrap();
-            keyspace.append_u16(idx as u16).append(bytes)
+            keyspace.append_u16(prefix as u16).append(bytes)
         } else {
-            keyspace.append_u16(idx as u16)
-        };
+            keyspace.append_u16(prefix as u16)
+        }
+    };
+
+    for (idx, agg_call) in agg_calls.iter().enumerate() {
+        // The prefix of the state is `agg_call_idx / [group_key]`
+        let keyspace = state_keyspace(idx);
+
+        // The prefix of the distinct state is `agg_calls.len() + agg_call_idx / [group_key]`
+        distinct_states.push(agg_call.distinct.then(|| {
+            ManagedDistinctState::new(
+                state_keyspace(agg_calls.len() + idx),
+                agg_call.args.val_indices().len(),
+            )
+        }));
 
         let mut managed_state = ManagedStateImpl::create_managed_state(
             agg_call.clone(),
@@ -360,6 +433,8 @@ pub async fn generate_agg_state<S: StateStore>(
             pk_data_types.clone(),
             idx == ROW_COUNT_COLUMN,
             key_hash_code.clone(),
+            extreme_cache_capacity,
+            extreme_cache_miss_count.cloned(),
         )
         .await
         .map_err(StreamExecutorError::agg_state_error)?;
@@ -378,6 +453,7 @@ pub async fn generate_agg_state<S: StateStore>(
 
     Ok(AggState {
         managed_states,
+        distinct_states,
         prev_states: None,
     })
 }
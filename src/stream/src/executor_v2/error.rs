@@ -47,6 +47,9 @@ pub enum StreamExecutorError {
     #[error("TopN state error: {0}")]
     TopNStateError(RwError),
 
+    #[error("Sink error: {0}")]
+    SinkError(anyhow::Error),
+
     #[error("Channel `{0}` closed")]
     ChannelClosed(String),
 
@@ -79,6 +82,10 @@ impl StreamExecutorError {
         Self::TopNStateError(error.into()).into()
     }
 
+    pub fn sink_error(error: impl Into<anyhow::Error>) -> TracedStreamExecutorError {
+        Self::SinkError(error.into()).into()
+    }
+
     pub fn channel_closed(name: impl Into<String>) -> TracedStreamExecutorError {
         Self::ChannelClosed(name.into()).into()
     }
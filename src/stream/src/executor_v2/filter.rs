@@ -62,6 +62,43 @@ impl SimpleFilterExecutor {
     }
 }
 
+impl SimpleFilterExecutor {
+    /// Filters a chunk without updates, whose ops are kept as they are. Instead of compacting the
+    /// chunk, the predicate is evaluated on all the rows, and its result is merged into the
+    /// incoming visibility by a bitmap AND.
+    fn filter_inserts_and_deletes(
+        &self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let (ops, columns, visibility) = chunk.into_inner();
+        let data_chunk = DataChunk::builder().columns(columns).build();
+
+        let pred_output = self
+            .expr
+            .eval(&data_chunk)
+            .map_err(StreamExecutorError::eval_error)?;
+        let pred_visibility = match &*pred_output {
+            ArrayImpl::Bool(bool_array) => bool_array
+                .to_bitmap()
+                .map_err(StreamExecutorError::eval_error)?,
+            _ => panic!("unmatched type: filter expr returns a non-null array"),
+        };
+        let new_visibility = match visibility {
+            Some(visibility) => {
+                (&visibility & &pred_visibility).map_err(StreamExecutorError::eval_error)?
+            }
+            None => pred_visibility,
+        };
+
+        let (columns, _) = data_chunk.into_parts();
+        Ok(if new_visibility.num_high_bits() > 0 {
+            Some(StreamChunk::new(ops, columns, Some(new_visibility)))
+        } else {
+            None
+        })
+    }
+}
+
 impl Debug for SimpleFilterExecutor {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("FilterExecutor")
@@ -75,6 +112,14 @@ impl SimpleExecutor for SimpleFilterExecutor {
         &mut self,
         chunk: StreamChunk,
     ) -> StreamExecutorResult<Option<StreamChunk>> {
+        if chunk
+            .ops()
+            .iter()
+            .all(|op| matches!(op, Op::Insert | Op::Delete))
+        {
+            return self.filter_inserts_and_deletes(chunk);
+        }
+
         let chunk = chunk.compact().map_err(StreamExecutorError::eval_error)?;
 
         let (ops, columns, _visibility) = chunk.into_inner();
@@ -272,4 +317,48 @@ mod tests {
 
         assert!(filter.next().await.unwrap().unwrap().is_stop());
     }
+
+    #[tokio::test]
+    async fn test_filter_with_visibility() {
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::Delete, Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [1, 5, 6, 7] },
+                column_nonnull! { I64Array, [4, 2, 3, 5] },
+            ],
+            Some(vec![true, true, false, true].try_into().unwrap()),
+        );
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let source = MockSource::with_chunks(schema, PkIndices::new(), vec![chunk]);
+
+        let test_expr = new_binary_expr(
+            Type::GreaterThan,
+            DataType::Boolean,
+            Box::new(InputRefExpression::new(DataType::Int64, 0)),
+            Box::new(InputRefExpression::new(DataType::Int64, 1)),
+        );
+        let filter = Box::new(FilterExecutor::new(Box::new(source), test_expr, 1));
+        let mut filter = filter.execute();
+
+        // The invisible row stays invisible even if it satisfies the predicate.
+        if let Message::Chunk(chunk) = filter.next().await.unwrap().unwrap() {
+            assert_eq!(
+                chunk.ops(),
+                vec![Op::Insert, Op::Insert, Op::Delete, Op::Insert]
+            );
+            assert_eq!(
+                chunk.visibility().as_ref().unwrap().iter().collect_vec(),
+                vec![false, true, false, true]
+            );
+        } else {
+            unreachable!();
+        }
+
+        assert!(filter.next().await.unwrap().unwrap().is_stop());
+    }
 }
@@ -0,0 +1,243 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Formatter};
+
+use itertools::Itertools;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_expr::expr::build_from_prost;
+use risingwave_pb::stream_plan::stream_node::Node;
+use risingwave_pb::stream_plan::StreamNode;
+
+use super::filter::SimpleFilterExecutor;
+use super::project::SimpleProjectExecutor;
+use super::{
+    Barrier, BoxedExecutor, ExecutorInfo, PkIndicesRef, SimpleExecutor, SimpleExecutorWrapper,
+    StreamChunk, StreamExecutorResult, Watermark,
+};
+
+pub type FusedExecutor = SimpleExecutorWrapper<SimpleFusedExecutor>;
+
+impl FusedExecutor {
+    /// Creates an executor running the given nodes back-to-back on each chunk. The nodes are
+    /// ordered from the bottom up, each paired with its executor id.
+    pub fn new(
+        input: BoxedExecutor,
+        nodes: &[(&StreamNode, u64)],
+        executor_id: u64,
+    ) -> Result<Self> {
+        let mut stages: Vec<Box<dyn SimpleExecutor>> = Vec::with_capacity(nodes.len());
+        let mut input_info = input.info();
+        for (node, executor_id) in nodes {
+            let stage: Box<dyn SimpleExecutor> = match node.get_node()? {
+                Node::ProjectNode(project) => {
+                    let exprs = project
+                        .get_select_list()
+                        .iter()
+                        .map(build_from_prost)
+                        .collect::<Result<Vec<_>>>()?;
+                    let info = ExecutorInfo {
+                        pk_indices: node.pk_indices.iter().map(|idx| *idx as usize).collect(),
+                        ..input_info
+                    };
+                    Box::new(SimpleProjectExecutor::new(info, exprs, *executor_id))
+                }
+                Node::FilterNode(filter) => {
+                    let expr = build_from_prost(filter.get_search_condition()?)?;
+                    Box::new(SimpleFilterExecutor::new(input_info, expr, *executor_id))
+                }
+                node => {
+                    return Err(
+                        ErrorCode::InternalError(format!("{:?} cannot be fused", node)).into(),
+                    )
+                }
+            };
+            input_info = ExecutorInfo {
+                schema: stage.schema().clone(),
+                pk_indices: stage.pk_indices().to_vec(),
+                identity: stage.identity().to_string(),
+            };
+            stages.push(stage);
+        }
+
+        let info = ExecutorInfo {
+            identity: format!("FusedExecutor {:X}", executor_id),
+            ..input_info
+        };
+        Ok(SimpleExecutorWrapper {
+            input,
+            inner: SimpleFusedExecutor { info, stages },
+        })
+    }
+}
+
+/// `FusedExecutor` runs a chain of stateless executors, i.e. projects and filters, in one
+/// executor. Each chunk goes through all the stages without being passed between executors.
+pub struct SimpleFusedExecutor {
+    info: ExecutorInfo,
+
+    /// The fused executors, from the bottom up.
+    stages: Vec<Box<dyn SimpleExecutor>>,
+}
+
+impl Debug for SimpleFusedExecutor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FusedExecutor")
+            .field(
+                "stages",
+                &self
+                    .stages
+                    .iter()
+                    .map(|stage| stage.identity())
+                    .collect_vec(),
+            )
+            .finish()
+    }
+}
+
+impl SimpleExecutor for SimpleFusedExecutor {
+    fn map_filter_chunk(
+        &mut self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let mut chunk = chunk;
+        for stage in &mut self.stages {
+            chunk = match stage.map_filter_chunk(chunk)? {
+                Some(chunk) => chunk,
+                None => return Ok(None),
+            };
+        }
+        Ok(Some(chunk))
+    }
+
+    fn on_barrier(&mut self, barrier: &Barrier) -> StreamExecutorResult<()> {
+        for stage in &mut self.stages {
+            stage.on_barrier(barrier)?;
+        }
+        Ok(())
+    }
+
+    fn map_watermark(&mut self, watermark: Watermark) -> Option<Watermark> {
+        self.stages
+            .iter_mut()
+            .try_fold(watermark, |watermark, stage| stage.map_watermark(watermark))
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::{I64Array, Op, StreamChunk};
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::{DataType, ScalarImpl};
+    use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
+    use risingwave_expr::expr::{Expression, InputRefExpression, LiteralExpression};
+    use risingwave_pb::expr::expr_node::Type;
+
+    use super::super::test_utils::MockSource;
+    use super::super::*;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fused_project_filter() {
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::Delete],
+            vec![
+                column_nonnull! { I64Array, [1, 2, 3] },
+                column_nonnull! { I64Array, [4, 5, 6] },
+            ],
+            None,
+        );
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let source = MockSource::with_chunks(schema, PkIndices::new(), vec![chunk]);
+
+        // SELECT $0 + $1 WHERE $0 + $1 > 6
+        let project = SimpleProjectExecutor::new(
+            source.info(),
+            vec![new_binary_expr(
+                Type::Add,
+                DataType::Int64,
+                InputRefExpression::new(DataType::Int64, 0).boxed(),
+                InputRefExpression::new(DataType::Int64, 1).boxed(),
+            )],
+            1,
+        );
+        let filter_info = ExecutorInfo {
+            schema: project.schema().clone(),
+            pk_indices: vec![],
+            identity: "FilterExecutor".to_string(),
+        };
+        let filter = SimpleFilterExecutor::new(
+            filter_info,
+            new_binary_expr(
+                Type::GreaterThan,
+                DataType::Boolean,
+                InputRefExpression::new(DataType::Int64, 0).boxed(),
+                LiteralExpression::new(DataType::Int64, Some(ScalarImpl::Int64(6))).boxed(),
+            ),
+            2,
+        );
+        let fused = Box::new(SimpleExecutorWrapper {
+            input: Box::new(source) as BoxedExecutor,
+            inner: SimpleFusedExecutor {
+                info: ExecutorInfo {
+                    schema: filter.schema().clone(),
+                    pk_indices: vec![],
+                    identity: "FusedExecutor".to_string(),
+                },
+                stages: vec![Box::new(project), Box::new(filter)],
+            },
+        });
+        let mut fused = fused.execute();
+
+        if let Message::Chunk(chunk) = fused.next().await.unwrap().unwrap() {
+            assert_eq!(chunk.ops(), vec![Op::Insert, Op::Insert, Op::Delete]);
+            assert_eq!(
+                chunk.visibility().as_ref().unwrap().iter().collect_vec(),
+                vec![false, true, true]
+            );
+            assert_eq!(
+                chunk
+                    .column_at(0)
+                    .array_ref()
+                    .as_int64()
+                    .iter()
+                    .collect_vec(),
+                vec![Some(5), Some(7), Some(9)]
+            );
+        } else {
+            unreachable!();
+        }
+        assert!(fused.next().await.unwrap().unwrap().is_stop());
+    }
+}
@@ -0,0 +1,363 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Randomized tests of stateful executors. Random changes of a table, consistent with its pk, are
+//! fed to an executor with barriers at random positions. The outputs are materialized and checked
+//! against a brute-force model on each barrier, so that mistakes in retractions are caught without
+//! handcrafting the cases.
+
+use std::collections::{BTreeMap, HashMap};
+
+use futures::StreamExt;
+use itertools::Itertools;
+use rand::prelude::*;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::hash::KeySerialized;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_expr::expr::AggKind;
+
+use super::aggregation::{AggArgs, AggCall};
+use super::test_utils::*;
+use super::{BoxedMessageStream, Executor, ExecutorV1AsV2, HashAggExecutor, Message, TopNExecutor};
+use crate::executor::{HashJoinExecutor, JoinParams, JoinType};
+
+/// Number of random cases run for each executor.
+const NUM_SEEDS: u64 = 16;
+/// Number of epochs in each case.
+const NUM_EPOCHS: usize = 24;
+/// Maximum number of chunks in each epoch.
+const MAX_CHUNKS_PER_EPOCH: usize = 3;
+/// Maximum number of changes in each chunk.
+const MAX_CHANGES_PER_CHUNK: usize = 8;
+/// Groups and values are drawn from small ranges to make collisions common.
+const NUM_GROUPS: i64 = 4;
+const MAX_VALUE: i64 = 10;
+
+/// Generates random changes of a table `(pk, group, value)` of `Int64` columns. Deletions and
+/// updates only touch the existing rows, so the changes are always consistent with the pk.
+struct ChangeGenerator {
+    rng: StdRng,
+    /// The current rows of the table, by pk.
+    rows: BTreeMap<i64, Row>,
+    next_pk: i64,
+}
+
+impl ChangeGenerator {
+    fn new(rng: StdRng) -> Self {
+        Self {
+            rng,
+            rows: BTreeMap::new(),
+            next_pk: 0,
+        }
+    }
+
+    fn schema() -> Schema {
+        Schema::new(vec![Field::unnamed(DataType::Int64); 3])
+    }
+
+    fn random_row(&mut self, pk: i64) -> Row {
+        let group = self.rng.gen_range(0..NUM_GROUPS);
+        let value = self.rng.gen_range(0..MAX_VALUE);
+        Row(vec![
+            Some(pk.into()),
+            Some(group.into()),
+            Some(value.into()),
+        ])
+    }
+
+    fn random_existing_pk(&mut self) -> i64 {
+        let idx = self.rng.gen_range(0..self.rows.len());
+        *self.rows.keys().nth(idx).unwrap()
+    }
+
+    fn gen_chunk(&mut self) -> StreamChunk {
+        let mut changes = vec![];
+        for _ in 0..self.rng.gen_range(1..=MAX_CHANGES_PER_CHUNK) {
+            match self.rng.gen_range(0..4) {
+                // Insert more than delete, so that the table grows.
+                _ if self.rows.is_empty() => self.insert(&mut changes),
+                0 | 1 => self.insert(&mut changes),
+                2 => {
+                    let pk = self.random_existing_pk();
+                    let old_row = self.rows.remove(&pk).unwrap();
+                    changes.push((Op::Delete, old_row));
+                }
+                _ => {
+                    let pk = self.random_existing_pk();
+                    let new_row = self.random_row(pk);
+                    let old_row = self.rows.insert(pk, new_row.clone()).unwrap();
+                    changes.push((Op::UpdateDelete, old_row));
+                    changes.push((Op::UpdateInsert, new_row));
+                }
+            }
+        }
+        StreamChunk::from_rows(
+            &changes,
+            &[DataType::Int64, DataType::Int64, DataType::Int64],
+        )
+        .unwrap()
+    }
+
+    fn insert(&mut self, changes: &mut Vec<(Op, Row)>) {
+        let pk = self.next_pk;
+        self.next_pk += 1;
+        let row = self.random_row(pk);
+        self.rows.insert(pk, row.clone());
+        changes.push((Op::Insert, row));
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &Row> {
+        self.rows.values()
+    }
+}
+
+/// Random changes of one input, with a barrier after each epoch.
+struct Workload {
+    source: MockSource,
+    /// The rows of the table at the end of each epoch.
+    snapshots: Vec<Vec<Row>>,
+}
+
+fn gen_workload(rng: StdRng, pk_indices: Vec<usize>, chunks_per_epoch: &[usize]) -> Workload {
+    let mut generator = ChangeGenerator::new(rng);
+    let mut source = MockSource::new(ChangeGenerator::schema(), pk_indices).stop_on_finish(false);
+    let mut snapshots = vec![];
+
+    source.push_barrier(1, false);
+    for (epoch, &num_chunks) in chunks_per_epoch.iter().enumerate() {
+        let chunks = (0..num_chunks).map(|_| generator.gen_chunk()).collect_vec();
+        source.push_chunks(chunks.into_iter());
+        let is_last = epoch + 1 == chunks_per_epoch.len();
+        source.push_barrier(epoch as u64 + 2, is_last);
+        snapshots.push(generator.rows().cloned().collect());
+    }
+
+    Workload { source, snapshots }
+}
+
+fn gen_chunks_per_epoch(rng: &mut StdRng) -> Vec<usize> {
+    // Some epochs are empty, so that consecutive barriers are covered.
+    (0..NUM_EPOCHS)
+        .map(|_| rng.gen_range(0..=MAX_CHUNKS_PER_EPOCH))
+        .collect()
+}
+
+/// Materializes the output of an executor as a multiset of rows, and checks it against the
+/// expected rows on each barrier.
+async fn check_output(
+    seed: u64,
+    mut output: BoxedMessageStream,
+    expected: impl Fn(usize) -> Vec<Row>,
+) {
+    let mut materialized: HashMap<Row, usize> = HashMap::new();
+
+    // The first barrier initializes the executor.
+    assert!(matches!(
+        output.next().await.unwrap().unwrap(),
+        Message::Barrier(_)
+    ));
+
+    let mut epoch = 0;
+    while let Some(msg) = output.next().await {
+        match msg.unwrap() {
+            Message::Chunk(chunk) => {
+                for row in chunk.rows() {
+                    let owned_row = row.to_owned_row();
+                    match row.op() {
+                        Op::Insert | Op::UpdateInsert => {
+                            *materialized.entry(owned_row).or_default() += 1;
+                        }
+                        Op::Delete | Op::UpdateDelete => {
+                            let count = materialized.get_mut(&owned_row).unwrap_or_else(|| {
+                                panic!(
+                                    "seed {}: retracted row {:?} which is not in the output",
+                                    seed, owned_row
+                                )
+                            });
+                            *count -= 1;
+                            if *count == 0 {
+                                materialized.remove(&owned_row);
+                            }
+                        }
+                    }
+                }
+            }
+            Message::Barrier(barrier) => {
+                let actual = materialized
+                    .iter()
+                    .flat_map(|(row, count)| std::iter::repeat(row.clone()).take(*count))
+                    .sorted()
+                    .collect_vec();
+                let expected = expected(epoch).into_iter().sorted().collect_vec();
+                assert_eq!(
+                    actual, expected,
+                    "seed {}: mismatch at epoch {}",
+                    seed, epoch
+                );
+
+                epoch += 1;
+                if barrier.mutation.map_or(false, |m| m.is_stop()) {
+                    break;
+                }
+            }
+            Message::Watermark(_) => {}
+        }
+    }
+    assert_eq!(epoch, NUM_EPOCHS, "seed {}: output ends early", seed);
+}
+
+fn int64(datum: &Option<ScalarImpl>) -> i64 {
+    *datum.as_ref().unwrap().as_int64()
+}
+
+#[tokio::test]
+async fn fuzz_hash_agg() {
+    for seed in 0..NUM_SEEDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let chunks_per_epoch = gen_chunks_per_epoch(&mut rng);
+        let workload = gen_workload(rng, vec![0], &chunks_per_epoch);
+
+        // SELECT group, count(*), sum(value), max(value) FROM t GROUP BY group
+        let agg_calls = vec![
+            AggCall {
+                kind: AggKind::RowCount,
+                args: AggArgs::None,
+                return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
+            },
+            AggCall {
+                kind: AggKind::Sum,
+                args: AggArgs::Unary(DataType::Int64, 2),
+                return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
+            },
+            AggCall {
+                kind: AggKind::Max,
+                args: AggArgs::Unary(DataType::Int64, 2),
+                return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
+            },
+        ];
+        let hash_agg = HashAggExecutor::<KeySerialized, _>::new(
+            Box::new(workload.source),
+            agg_calls,
+            create_in_memory_keyspace(),
+            vec![],
+            1,
+            vec![1],
+        )
+        .unwrap();
+
+        let snapshots = workload.snapshots;
+        check_output(seed, Box::new(hash_agg).execute(), |epoch| {
+            snapshots[epoch]
+                .iter()
+                .into_group_map_by(|row| int64(&row.0[1]))
+                .into_iter()
+                .map(|(group, rows)| {
+                    let values = rows.iter().map(|row| int64(&row.0[2])).collect_vec();
+                    Row(vec![
+                        Some(group.into()),
+                        Some((values.len() as i64).into()),
+                        Some(values.iter().sum::<i64>().into()),
+                        Some((*values.iter().max().unwrap()).into()),
+                    ])
+                })
+                .collect()
+        })
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn fuzz_top_n() {
+    const LIMIT: usize = 5;
+
+    for seed in 0..NUM_SEEDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let chunks_per_epoch = gen_chunks_per_epoch(&mut rng);
+        let workload = gen_workload(rng, vec![0], &chunks_per_epoch);
+
+        // SELECT * FROM t ORDER BY value, pk LIMIT 5, with a small cache so that the rows are
+        // reloaded from the state store.
+        let top_n = TopNExecutor::new(
+            Box::new(workload.source),
+            vec![OrderType::Ascending, OrderType::Ascending],
+            (0, Some(LIMIT)),
+            vec![2, 0],
+            create_in_memory_keyspace(),
+            Some(2),
+            (0, 0, 0),
+            1,
+            vec![],
+        )
+        .unwrap();
+
+        let snapshots = workload.snapshots;
+        check_output(seed, Box::new(top_n).execute(), |epoch| {
+            snapshots[epoch]
+                .iter()
+                .sorted_by_key(|row| (int64(&row.0[2]), int64(&row.0[0])))
+                .take(LIMIT)
+                .cloned()
+                .collect()
+        })
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn fuzz_hash_join() {
+    for seed in 0..NUM_SEEDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        // Both sides have barriers of the same epochs, with random changes in between.
+        let chunks_per_epoch = gen_chunks_per_epoch(&mut rng);
+        let left = gen_workload(StdRng::seed_from_u64(rng.gen()), vec![0], &chunks_per_epoch);
+        let right = gen_workload(StdRng::seed_from_u64(rng.gen()), vec![0], &chunks_per_epoch);
+
+        let source_l = Box::new(left.source).v1();
+        let source_r = Box::new(right.source).v1();
+
+        // SELECT * FROM l JOIN r ON l.group = r.group
+        let hash_join = HashJoinExecutor::<_, { JoinType::Inner }>::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            JoinParams::new(vec![1]),
+            JoinParams::new(vec![1]),
+            vec![0, 3],
+            create_in_memory_keyspace(),
+            1,
+            None,
+            "HashJoinExecutor".to_string(),
+            vec![],
+        );
+
+        let (snapshots_l, snapshots_r) = (left.snapshots, right.snapshots);
+        let output = Box::new(ExecutorV1AsV2(Box::new(hash_join))).execute();
+        check_output(seed, output, |epoch| {
+            snapshots_l[epoch]
+                .iter()
+                .cartesian_product(snapshots_r[epoch].iter())
+                .filter(|(l, r)| l.0[1] == r.0[1])
+                .map(|(l, r)| Row(l.0.iter().chain(r.0.iter()).cloned().collect()))
+                .collect()
+        })
+        .await;
+    }
+}
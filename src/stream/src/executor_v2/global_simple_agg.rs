@@ -17,6 +17,7 @@ use std::sync::Arc;
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
+use prometheus::IntCounter;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::StreamChunk;
 use risingwave_common::catalog::Schema;
@@ -24,6 +25,7 @@ use risingwave_common::error::Result;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::{Executor, ExecutorInfo, StreamExecutorResult};
+use crate::executor::managed_state::aggregation::DEFAULT_EXTREME_CACHE_CAPACITY;
 use crate::executor::{pk_input_array_refs, PkIndicesRef};
 use crate::executor_v2::aggregation::{
     agg_input_array_refs, generate_agg_schema, generate_agg_state, AggCall, AggState,
@@ -68,6 +70,12 @@ pub struct SimpleAggExecutor<S: StateStore> {
     #[allow(dead_code)]
     /// Indices of the columns on which key distribution depends.
     key_indices: Vec<usize>,
+
+    /// Number of entries cached by each min/max state.
+    extreme_cache_capacity: usize,
+
+    /// Counts the refills of the min/max states from the state store.
+    extreme_cache_miss_count: Option<IntCounter>,
 }
 
 impl<S: StateStore> Executor for SimpleAggExecutor<S> {
@@ -113,9 +121,24 @@ impl<S: StateStore> SimpleAggExecutor<S> {
             states: None,
             agg_calls,
             key_indices,
+            extreme_cache_capacity: DEFAULT_EXTREME_CACHE_CAPACITY,
+            extreme_cache_miss_count: None,
         })
     }
 
+    /// Cache at most `capacity` entries in each min/max state.
+    pub fn with_extreme_cache_capacity(mut self, capacity: usize) -> Self {
+        self.extreme_cache_capacity = capacity;
+        self
+    }
+
+    /// Count the refills of the min/max states from the state store with `counter`.
+    pub fn with_extreme_cache_miss_count(mut self, counter: IntCounter) -> Self {
+        self.extreme_cache_miss_count = Some(counter);
+        self
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn apply_chunk(
         agg_calls: &[AggCall],
         input_pk_indices: &[usize],
@@ -124,6 +147,8 @@ impl<S: StateStore> SimpleAggExecutor<S> {
         keyspace: &Keyspace<S>,
         chunk: StreamChunk,
         epoch: u64,
+        extreme_cache_capacity: usize,
+        extreme_cache_miss_count: Option<&IntCounter>,
     ) -> StreamExecutorResult<()> {
         let (ops, columns, visibility) = chunk.into_inner();
 
@@ -147,9 +172,17 @@ impl<S: StateStore> SimpleAggExecutor<S> {
         // 1. Retrieve previous state from the KeyedState. If they didn't exist, the ManagedState
         // will automatically create new ones for them.
         if states.is_none() {
-            let state =
-                generate_agg_state(None, agg_calls, keyspace, input_pk_data_types, epoch, None)
-                    .await?;
+            let state = generate_agg_state(
+                None,
+                agg_calls,
+                keyspace,
+                input_pk_data_types,
+                epoch,
+                None,
+                extreme_cache_capacity,
+                extreme_cache_miss_count,
+            )
+            .await?;
             *states = Some(state);
         }
         let states = states.as_mut().unwrap();
@@ -161,12 +194,10 @@ impl<S: StateStore> SimpleAggExecutor<S> {
             .map_err(StreamExecutorError::agg_state_error)?;
 
         // 3. Apply batch to each of the state (per agg_call)
-        for (agg_state, data) in states.managed_states.iter_mut().zip_eq(all_agg_data.iter()) {
-            agg_state
-                .apply_batch(&ops, visibility.as_ref(), data, epoch)
-                .await
-                .map_err(StreamExecutorError::agg_state_error)?;
-        }
+        states
+            .apply_batch(&ops, visibility.as_ref(), &all_agg_data, epoch)
+            .await
+            .map_err(StreamExecutorError::agg_state_error)?;
 
         Ok(())
     }
@@ -187,11 +218,9 @@ impl<S: StateStore> SimpleAggExecutor<S> {
         };
 
         let mut write_batch = keyspace.state_store().start_write_batch();
-        for state in &mut states.managed_states {
-            state
-                .flush(&mut write_batch)
-                .map_err(StreamExecutorError::agg_state_error)?;
-        }
+        states
+            .flush(&mut write_batch)
+            .map_err(StreamExecutorError::agg_state_error)?;
         write_batch
             .ingest(epoch)
             .await
@@ -233,6 +262,8 @@ impl<S: StateStore> SimpleAggExecutor<S> {
             mut states,
             agg_calls,
             key_indices: _,
+            extreme_cache_capacity,
+            extreme_cache_miss_count,
         } = self;
         let mut input = input.execute();
         let first_msg = input.next().await.unwrap()?;
@@ -255,6 +286,8 @@ impl<S: StateStore> SimpleAggExecutor<S> {
                         &keyspace,
                         chunk,
                         epoch,
+                        extreme_cache_capacity,
+                        extreme_cache_miss_count.as_ref(),
                     )
                     .await?;
                 }
@@ -269,6 +302,8 @@ impl<S: StateStore> SimpleAggExecutor<S> {
                     yield Message::Barrier(barrier);
                     epoch = next_epoch;
                 }
+                // A simple aggregation has no group key to carry the watermark.
+                Message::Watermark(_) => {}
             }
         }
     }
@@ -338,21 +373,29 @@ mod tests {
                 kind: AggKind::RowCount,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 0),
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 1),
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Min,
                 args: AggArgs::Unary(DataType::Int64, 0),
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
         ];
 
@@ -14,12 +14,14 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use futures::{stream, StreamExt};
 use itertools::Itertools;
+use prometheus::IntCounter;
 use risingwave_common::array::column::Column;
-use risingwave_common::array::StreamChunk;
+use risingwave_common::array::{Row, StreamChunk};
 use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::Schema;
 use risingwave_common::collection::evictable::EvictableHashMap;
@@ -29,6 +31,7 @@ use risingwave_common::util::hash_util::CRC32FastBuilder;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::{Executor, ExecutorInfo, StreamExecutorResult};
+use crate::executor::managed_state::aggregation::DEFAULT_EXTREME_CACHE_CAPACITY;
 use crate::executor::{pk_input_arrays, PkDataTypes, PkIndicesRef};
 use crate::executor_v2::aggregation::{
     agg_input_arrays, generate_agg_schema, generate_agg_state, AggCall, AggExecutor,
@@ -36,6 +39,14 @@ use crate::executor_v2::aggregation::{
 };
 use crate::executor_v2::error::StreamExecutorError;
 use crate::executor_v2::PkIndices;
+use crate::task::CacheMemoryHandle;
+
+/// Number of groups cached by each [`HashAggExecutor`] when there is no memory pressure.
+const CACHE_CAPACITY: usize = 1 << 16;
+
+/// Estimated memory usage of the states of a group (bytes), used to convert the number of cached
+/// groups into bytes for the memory manager.
+const CACHE_ENTRY_SIZE: usize = 256;
 
 /// [`HashAggExecutor`] could process large amounts of data using a state backend. It works as
 /// follows:
@@ -74,6 +85,103 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             )?,
         })
     }
+
+    /// Enable the mini-batch mode, see [`MiniBatchConfig`].
+    pub fn with_mini_batch(mut self, config: MiniBatchConfig) -> Self {
+        self.inner.mini_batch = Some(MiniBatch::new(config));
+        self
+    }
+
+    /// Emit the changes of each epoch in the order of group keys instead of the hash order, so that
+    /// the output is deterministic across runs.
+    pub fn with_sorted_output(mut self) -> Self {
+        self.inner.sorted_output = true;
+        self
+    }
+
+    /// Cache at most `capacity` entries in each min/max state.
+    pub fn with_extreme_cache_capacity(mut self, capacity: usize) -> Self {
+        self.inner.extreme_cache_capacity = capacity;
+        self
+    }
+
+    /// Count the refills of the min/max states from the state store with `counter`.
+    pub fn with_extreme_cache_miss_count(mut self, counter: IntCounter) -> Self {
+        self.inner.extreme_cache_miss_count = Some(counter);
+        self
+    }
+
+    /// Report the size of the cached groups with `handle`, and evict them under memory pressure.
+    pub fn with_memory_handle(mut self, handle: CacheMemoryHandle) -> Self {
+        self.inner.memory_handle = Some(handle);
+        self
+    }
+}
+
+/// In mini-batch mode, [`HashAggExecutor`] buffers input chunks and applies them together, so that
+/// rows of the same key in a mini-batch are pre-aggregated in memory and the state of the key is
+/// only touched once. This reduces state store reads for hot keys at the cost of a bit of latency.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MiniBatchConfig {
+    /// Apply the mini-batch once this many rows are buffered. 0 means no limit.
+    pub max_rows: usize,
+
+    /// Apply the mini-batch once it has been buffered for this long. Zero means no limit. As the
+    /// interval is checked on new chunks, an idle mini-batch is applied on the next barrier.
+    pub interval: Duration,
+}
+
+impl MiniBatchConfig {
+    /// Returns `None` if neither limit is set, which means the mini-batch mode is disabled.
+    pub fn new(max_rows: usize, interval: Duration) -> Option<Self> {
+        if max_rows == 0 && interval.is_zero() {
+            None
+        } else {
+            Some(Self { max_rows, interval })
+        }
+    }
+}
+
+/// Chunks buffered in mini-batch mode.
+struct MiniBatch {
+    config: MiniBatchConfig,
+    chunks: Vec<StreamChunk>,
+    rows: usize,
+    started_at: Instant,
+}
+
+impl MiniBatch {
+    fn new(config: MiniBatchConfig) -> Self {
+        Self {
+            config,
+            chunks: vec![],
+            rows: 0,
+            started_at: Instant::now(),
+        }
+    }
+
+    fn push(&mut self, chunk: StreamChunk) {
+        if self.chunks.is_empty() {
+            self.started_at = Instant::now();
+        }
+        self.rows += chunk.cardinality();
+        self.chunks.push(chunk);
+    }
+
+    fn is_full(&self) -> bool {
+        (self.config.max_rows > 0 && self.rows >= self.config.max_rows)
+            || (!self.config.interval.is_zero()
+                && self.started_at.elapsed() >= self.config.interval)
+    }
+
+    /// Take all buffered chunks as a single chunk.
+    fn take(&mut self) -> Result<Option<StreamChunk>> {
+        if self.chunks.is_empty() {
+            return Ok(None);
+        }
+        self.rows = 0;
+        StreamChunk::concat(std::mem::take(&mut self.chunks)).map(Some)
+    }
 }
 
 pub struct AggHashAggExecutor<K: HashKey, S: StateStore> {
@@ -97,6 +205,21 @@ pub struct AggHashAggExecutor<K: HashKey, S: StateStore> {
     /// Indices of the columns
     /// all of the aggregation functions in this executor should depend on same group of keys
     key_indices: Vec<usize>,
+
+    /// The buffered chunks in mini-batch mode. `None` if the mini-batch mode is disabled.
+    mini_batch: Option<MiniBatch>,
+
+    /// Whether to emit changes in the order of group keys.
+    sorted_output: bool,
+
+    /// Number of entries cached by each min/max state.
+    extreme_cache_capacity: usize,
+
+    /// Counts the refills of the min/max states from the state store.
+    extreme_cache_miss_count: Option<IntCounter>,
+
+    /// Reports the size of `state_map` to the memory manager, if registered.
+    memory_handle: Option<CacheMemoryHandle>,
 }
 
 impl<K: HashKey, S: StateStore> AggHashAggExecutor<K, S> {
@@ -118,9 +241,14 @@ impl<K: HashKey, S: StateStore> AggHashAggExecutor<K, S> {
             schema,
             pk_indices,
             keyspace,
-            state_map: EvictableHashMap::new(1 << 16),
+            state_map: EvictableHashMap::new(CACHE_CAPACITY),
             agg_calls,
             key_indices,
+            mini_batch: None,
+            sorted_output: false,
+            extreme_cache_capacity: DEFAULT_EXTREME_CACHE_CAPACITY,
+            extreme_cache_miss_count: None,
+            memory_handle: None,
         })
     }
 
@@ -181,11 +309,44 @@ impl<K: HashKey, S: StateStore> AggHashAggExecutor<K, S> {
             .values()
             .any(|state| state.as_ref().unwrap().is_dirty())
     }
-}
 
-#[async_trait]
-impl<K: HashKey, S: StateStore> AggExecutor for AggHashAggExecutor<K, S> {
-    async fn apply_chunk(&mut self, chunk: StreamChunk, epoch: u64) -> StreamExecutorResult<()> {
+    /// Get the keys of dirty states, in the order of group keys if `sorted_output` is set, or in
+    /// the iteration order of the state map otherwise.
+    fn dirty_keys(&self) -> Result<Vec<K>> {
+        let keys = self
+            .state_map
+            .iter()
+            .filter(|(_, states)| states.as_ref().unwrap().is_dirty())
+            .map(|(key, _)| key.clone());
+        if !self.sorted_output {
+            return Ok(keys.collect());
+        }
+
+        let key_data_types = &self.schema.data_types()[..self.key_indices.len()];
+        let mut keys: Vec<(Row, K)> = keys
+            .map(|key| Ok::<_, RwError>((key.clone().deserialize(key_data_types.iter())?, key)))
+            .try_collect()?;
+        keys.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(keys.into_iter().map(|(_, key)| key).collect())
+    }
+
+    /// Apply the chunks buffered in mini-batch mode, if any.
+    async fn apply_mini_batch(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+        let chunk = match &mut self.mini_batch {
+            Some(mini_batch) => mini_batch.take().map_err(StreamExecutorError::eval_error)?,
+            None => None,
+        };
+        match chunk {
+            Some(chunk) => self.apply_chunk_inner(chunk, epoch).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn apply_chunk_inner(
+        &mut self,
+        chunk: StreamChunk,
+        epoch: u64,
+    ) -> StreamExecutorResult<()> {
         let (data_chunk, ops) = chunk.into_parts();
 
         // Compute hash code here before serializing keys to avoid duplicate hash code computation.
@@ -251,6 +412,8 @@ impl<K: HashKey, S: StateStore> AggExecutor for AggHashAggExecutor<K, S> {
                                 input_pk_data_types.clone(),
                                 epoch,
                                 Some(hash_code),
+                                self.extreme_cache_capacity,
+                                self.extreme_cache_miss_count.as_ref(),
                             )
                             .await?,
                         ),
@@ -264,15 +427,14 @@ impl<K: HashKey, S: StateStore> AggExecutor for AggHashAggExecutor<K, S> {
                     .map_err(StreamExecutorError::agg_state_error)?;
 
                 // 3. Apply batch to each of the state (per agg_call)
-                for (agg_state, data) in
-                    states.managed_states.iter_mut().zip_eq(all_agg_data.iter())
-                {
-                    let data = data.iter().map(|d| &**d).collect_vec();
-                    agg_state
-                        .apply_batch(&ops, Some(&vis_map), &data, epoch)
-                        .await
-                        .map_err(StreamExecutorError::agg_state_error)?;
-                }
+                let data = all_agg_data
+                    .iter()
+                    .map(|data| data.iter().map(|d| &**d).collect_vec())
+                    .collect_vec();
+                states
+                    .apply_batch(&ops, Some(&vis_map), &data, epoch)
+                    .await
+                    .map_err(StreamExecutorError::agg_state_error)?;
 
                 Ok::<(_, Box<AggState<S>>), RwError>((key, states))
             });
@@ -286,8 +448,27 @@ impl<K: HashKey, S: StateStore> AggExecutor for AggHashAggExecutor<K, S> {
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl<K: HashKey, S: StateStore> AggExecutor for AggHashAggExecutor<K, S> {
+    async fn apply_chunk(&mut self, chunk: StreamChunk, epoch: u64) -> StreamExecutorResult<()> {
+        match &mut self.mini_batch {
+            Some(mini_batch) => {
+                mini_batch.push(chunk);
+                if mini_batch.is_full() {
+                    self.apply_mini_batch(epoch).await?;
+                }
+                Ok(())
+            }
+            None => self.apply_chunk_inner(chunk, epoch).await,
+        }
+    }
 
     async fn flush_data(&mut self, epoch: u64) -> StreamExecutorResult<Option<StreamChunk>> {
+        // Rows buffered in mini-batch mode must be applied before the barrier passes.
+        self.apply_mini_batch(epoch).await?;
+
         // --- Flush states to the state store ---
         // Some state will have the correct output only after their internal states have been fully
         // flushed.
@@ -298,11 +479,11 @@ impl<K: HashKey, S: StateStore> AggExecutor for AggHashAggExecutor<K, S> {
             for states in self.state_map.values_mut() {
                 if states.as_ref().unwrap().is_dirty() {
                     dirty_cnt += 1;
-                    for state in &mut states.as_mut().unwrap().managed_states {
-                        state
-                            .flush(&mut write_batch)
-                            .map_err(StreamExecutorError::agg_state_error)?;
-                    }
+                    states
+                        .as_mut()
+                        .unwrap()
+                        .flush(&mut write_batch)
+                        .map_err(StreamExecutorError::agg_state_error)?;
                 }
             }
             (write_batch, dirty_cnt)
@@ -331,7 +512,8 @@ impl<K: HashKey, S: StateStore> AggExecutor for AggHashAggExecutor<K, S> {
         let mut new_ops = Vec::with_capacity(dirty_cnt);
 
         // --- Retrieve modified states and put the changes into the builders ---
-        for (key, states) in self.state_map.iter_mut() {
+        for key in self.dirty_keys().map_err(StreamExecutorError::eval_error)? {
+            let states = self.state_map.peek_mut(&key).unwrap();
             let appended = states
                 .as_mut()
                 .unwrap()
@@ -350,7 +532,17 @@ impl<K: HashKey, S: StateStore> AggExecutor for AggHashAggExecutor<K, S> {
         // In current implementation, we need to fetch the RowCount from the state store once a key
         // is deleted and added again. We should find a way to eliminate this extra fetch.
         assert!(!self.is_dirty());
+        if let Some(handle) = &self.memory_handle {
+            handle.set_bytes(self.state_map.len() * CACHE_ENTRY_SIZE);
+            let target_cap = handle.eviction_target().map_or(CACHE_CAPACITY, |bytes| {
+                (bytes / CACHE_ENTRY_SIZE).min(CACHE_CAPACITY)
+            });
+            self.state_map.set_target_cap(target_cap);
+        }
         self.state_map.evict_to_target_cap();
+        if let Some(handle) = &self.memory_handle {
+            handle.set_bytes(self.state_map.len() * CACHE_ENTRY_SIZE);
+        }
 
         let columns: Vec<Column> = builders
             .into_iter()
@@ -380,6 +572,7 @@ impl<K: HashKey, S: StateStore> AggExecutor for AggHashAggExecutor<K, S> {
 #[cfg(test)]
 mod tests {
     use std::marker::PhantomData;
+    use std::time::Duration;
 
     use assert_matches::assert_matches;
     use futures::StreamExt;
@@ -389,14 +582,14 @@ mod tests {
     use risingwave_common::catalog::{Field, Schema};
     use risingwave_common::column_nonnull;
     use risingwave_common::error::Result;
-    use risingwave_common::hash::{calc_hash_key_kind, HashKey, HashKeyDispatcher};
+    use risingwave_common::hash::{calc_hash_key_kind, HashKey, HashKeyDispatcher, KeySerialized};
     use risingwave_common::types::DataType;
     use risingwave_expr::expr::*;
     use risingwave_storage::{Keyspace, StateStore};
 
     use crate::executor_v2::aggregation::{AggArgs, AggCall};
     use crate::executor_v2::test_utils::*;
-    use crate::executor_v2::{Executor, HashAggExecutor, Message, PkIndices};
+    use crate::executor_v2::{Executor, HashAggExecutor, Message, MiniBatchConfig, PkIndices};
     use crate::row_nonnull;
 
     struct HashAggExecutorDispatcher<S: StateStore>(PhantomData<S>);
@@ -467,6 +660,134 @@ mod tests {
         test_local_hash_aggregation_max(create_in_memory_keyspace()).await
     }
 
+    #[tokio::test]
+    async fn test_hash_aggregation_mini_batch() {
+        let chunk1 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::Insert],
+            vec![column_nonnull! { I64Array, [1, 2, 2] }],
+            None,
+        );
+        let chunk2 = StreamChunk::new(
+            vec![Op::Insert, Op::Delete, Op::Insert],
+            vec![column_nonnull! { I64Array, [2, 1, 3] }],
+            Some((vec![true, false, true]).try_into().unwrap()),
+        );
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let mut source = MockSource::new(schema, PkIndices::new());
+        source.push_barrier(1, false);
+        source.push_chunks([chunk1, chunk2].into_iter());
+        source.push_barrier(2, false);
+
+        let agg_calls = vec![AggCall {
+            kind: AggKind::RowCount,
+            args: AggArgs::None,
+            return_type: DataType::Int64,
+            order_pairs: vec![],
+            distinct: false,
+        }];
+        let hash_agg = HashAggExecutor::<KeySerialized, _>::new(
+            Box::new(source),
+            agg_calls,
+            create_in_memory_keyspace(),
+            vec![],
+            1,
+            vec![0],
+        )
+        .unwrap()
+        .with_mini_batch(MiniBatchConfig::new(100, Duration::ZERO).unwrap());
+        let mut hash_agg = Box::new(hash_agg).execute();
+
+        // Consume the init barrier
+        hash_agg.next().await.unwrap().unwrap();
+        // Both chunks are applied as a single mini-batch on the barrier.
+        let msg = hash_agg.next().await.unwrap().unwrap();
+        if let Message::Chunk(chunk) = msg {
+            let (data_chunk, ops) = chunk.into_parts();
+
+            assert_eq!(ops, vec![Op::Insert, Op::Insert, Op::Insert]);
+
+            let rows = data_chunk.rows().map(Row::from).sorted().collect_vec();
+            let expected_rows = [
+                row_nonnull![1i64, 1i64],
+                row_nonnull![2i64, 3i64],
+                row_nonnull![3i64, 1i64],
+            ]
+            .into_iter()
+            .sorted()
+            .collect_vec();
+
+            assert_eq!(rows, expected_rows);
+        } else {
+            unreachable!("unexpected message {:?}", msg);
+        }
+
+        assert_matches!(
+            hash_agg.next().await.unwrap().unwrap(),
+            Message::Barrier { .. }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hash_aggregation_sorted_output() {
+        let chunk = StreamChunk::new(
+            vec![Op::Insert; 5],
+            vec![column_nonnull! { I64Array, [3, 1, 5, 2, 1] }],
+            None,
+        );
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let mut source = MockSource::new(schema, PkIndices::new());
+        source.push_barrier(1, false);
+        source.push_chunks([chunk].into_iter());
+        source.push_barrier(2, false);
+
+        let agg_calls = vec![AggCall {
+            kind: AggKind::RowCount,
+            args: AggArgs::None,
+            return_type: DataType::Int64,
+            order_pairs: vec![],
+            distinct: false,
+        }];
+        let hash_agg = HashAggExecutor::<KeySerialized, _>::new(
+            Box::new(source),
+            agg_calls,
+            create_in_memory_keyspace(),
+            vec![],
+            1,
+            vec![0],
+        )
+        .unwrap()
+        .with_sorted_output();
+        let mut hash_agg = Box::new(hash_agg).execute();
+
+        // Consume the init barrier
+        hash_agg.next().await.unwrap().unwrap();
+        let msg = hash_agg.next().await.unwrap().unwrap();
+        if let Message::Chunk(chunk) = msg {
+            let (data_chunk, _) = chunk.into_parts();
+            let rows = data_chunk.rows().map(Row::from).collect_vec();
+            let expected_rows = vec![
+                row_nonnull![1i64, 2i64],
+                row_nonnull![2i64, 1i64],
+                row_nonnull![3i64, 1i64],
+                row_nonnull![5i64, 1i64],
+            ];
+            assert_eq!(rows, expected_rows);
+        } else {
+            unreachable!("unexpected message {:?}", msg);
+        }
+    }
+
+    #[test]
+    fn test_mini_batch_config() {
+        assert_eq!(MiniBatchConfig::new(0, Duration::ZERO), None);
+        assert!(MiniBatchConfig::new(0, Duration::from_millis(10)).is_some());
+        assert!(MiniBatchConfig::new(1024, Duration::ZERO).is_some());
+    }
+
     async fn test_local_hash_aggregation_count(keyspace: Keyspace<impl StateStore>) {
         let chunk1 = StreamChunk::new(
             vec![Op::Insert, Op::Insert, Op::Insert],
@@ -495,16 +816,22 @@ mod tests {
                 kind: AggKind::RowCount,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Count,
                 args: AggArgs::Unary(DataType::Int64, 0),
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Count,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
         ];
 
@@ -604,17 +931,23 @@ mod tests {
                 kind: AggKind::RowCount,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 1),
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             // This is local hash aggregation, so we add another sum state
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 2),
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
         ];
 
@@ -728,11 +1061,15 @@ mod tests {
                 kind: AggKind::RowCount,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Min,
                 args: AggArgs::Unary(DataType::Int64, 1),
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
         ];
 
@@ -27,7 +27,6 @@ use risingwave_pb::expr::expr_node;
 use super::error::{StreamExecutorError, TracedStreamExecutorError};
 use super::{BoxedExecutor, Executor, ExecutorInfo, Message};
 
-#[allow(unused)]
 pub struct HopWindowExecutor {
     pub input: BoxedExecutor,
     pub info: ExecutorInfo,
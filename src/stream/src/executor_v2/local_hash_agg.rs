@@ -0,0 +1,341 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::Result;
+
+use super::{BoxedMessageStream, Executor, ExecutorInfo, Message, StreamExecutorResult};
+use crate::executor::PkIndicesRef;
+use crate::executor_v2::aggregation::{
+    create_streaming_agg_state, generate_agg_schema, AggCall, StreamingAggStateImpl,
+};
+use crate::executor_v2::error::{StreamExecutorError, TracedStreamExecutorError};
+use crate::executor_v2::PkIndices;
+
+type GroupStates = Vec<Box<dyn StreamingAggStateImpl>>;
+
+/// `LocalHashAggExecutor` is the first phase of a two-phase hash aggregation. It aggregates the
+/// rows of each epoch by the group keys in memory, and emits the partial results of the groups
+/// touched in the epoch as inserts on barriers. The partial results are then shuffled to the
+/// [`super::HashAggExecutor`], which sums up the partial counts and sums. So for high-cardinality
+/// group keys, only one row per group, instead of all the rows, are shuffled in each epoch.
+pub struct LocalHashAggExecutor {
+    input: Box<dyn Executor>,
+    info: ExecutorInfo,
+    agg_calls: Vec<AggCall>,
+    key_indices: Vec<usize>,
+}
+
+impl Executor for LocalHashAggExecutor {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+impl LocalHashAggExecutor {
+    pub fn new(
+        input: Box<dyn Executor>,
+        agg_calls: Vec<AggCall>,
+        key_indices: Vec<usize>,
+        pk_indices: PkIndices,
+        executor_id: u64,
+    ) -> Result<Self> {
+        let schema = generate_agg_schema(input.as_ref(), &agg_calls, Some(&key_indices));
+        let info = ExecutorInfo {
+            schema,
+            pk_indices,
+            identity: format!("LocalHashAggExecutor-{}", executor_id),
+        };
+
+        Ok(LocalHashAggExecutor {
+            input,
+            info,
+            agg_calls,
+            key_indices,
+        })
+    }
+
+    fn create_states(agg_calls: &[AggCall]) -> Result<GroupStates> {
+        agg_calls
+            .iter()
+            .map(|agg_call| {
+                create_streaming_agg_state(
+                    agg_call.args.arg_types(),
+                    &agg_call.kind,
+                    &agg_call.return_type,
+                    None,
+                )
+            })
+            .try_collect()
+    }
+
+    fn apply_chunk(
+        agg_calls: &[AggCall],
+        key_indices: &[usize],
+        groups: &mut HashMap<Row, GroupStates>,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<()> {
+        let capacity = chunk.capacity();
+
+        // The visibility map of each group, shadowing the rows of other groups.
+        let mut key_to_vis_maps = HashMap::new();
+        for row_idx in 0..capacity {
+            let (row, visible) = chunk
+                .row_at(row_idx)
+                .map_err(StreamExecutorError::eval_error)?;
+            if visible {
+                key_to_vis_maps
+                    .entry(row.row_by_slice(key_indices))
+                    .or_insert_with(|| vec![false; capacity])[row_idx] = true;
+            }
+        }
+
+        let (ops, columns, _) = chunk.into_inner();
+        for (key, vis_map) in key_to_vis_maps {
+            let vis_map = Bitmap::try_from(vis_map).map_err(StreamExecutorError::eval_error)?;
+            let states = match groups.entry(key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    Self::create_states(agg_calls).map_err(StreamExecutorError::agg_state_error)?,
+                ),
+            };
+            agg_calls
+                .iter()
+                .zip_eq(states.iter_mut())
+                .try_for_each(|(agg_call, state)| {
+                    let cols = agg_call
+                        .args
+                        .val_indices()
+                        .iter()
+                        .map(|idx| columns[*idx].array_ref())
+                        .collect_vec();
+                    state.apply_batch(&ops, Some(&vis_map), &cols[..])
+                })
+                .map_err(StreamExecutorError::agg_state_error)?;
+        }
+        Ok(())
+    }
+
+    /// Builds the partial results of the groups, ordered by the group keys.
+    fn flush(
+        schema: &Schema,
+        key_indices: &[usize],
+        groups: &mut HashMap<Row, GroupStates>,
+    ) -> Result<StreamChunk> {
+        let mut groups = groups.drain().collect_vec();
+        groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut builders = schema.create_array_builders(groups.len())?;
+        let (key_builders, agg_builders) = builders.split_at_mut(key_indices.len());
+        for (key, states) in &groups {
+            for (datum, builder) in key.0.iter().zip_eq(key_builders.iter_mut()) {
+                builder.append_datum(datum)?;
+            }
+            for (state, builder) in states.iter().zip_eq(agg_builders.iter_mut()) {
+                builder.append_datum(&state.get_output()?)?;
+            }
+        }
+        let columns: Vec<Column> = builders
+            .into_iter()
+            .map(|builder| -> Result<_> { Ok(Column::new(Arc::new(builder.finish()?))) })
+            .try_collect()?;
+        let ops = vec![Op::Insert; groups.len()];
+
+        Ok(StreamChunk::new(ops, columns, None))
+    }
+
+    #[try_stream(ok = Message, error = TracedStreamExecutorError)]
+    async fn execute_inner(self) {
+        let LocalHashAggExecutor {
+            input,
+            info,
+            agg_calls,
+            key_indices,
+        } = self;
+        let input = input.execute();
+        let mut groups = HashMap::new();
+
+        #[for_await]
+        for msg in input {
+            let msg = msg?;
+            match msg {
+                Message::Chunk(chunk) => {
+                    Self::apply_chunk(&agg_calls, &key_indices, &mut groups, chunk)?;
+                }
+                m @ Message::Barrier(_) => {
+                    if !groups.is_empty() {
+                        let chunk = Self::flush(&info.schema, &key_indices, &mut groups)
+                            .map_err(StreamExecutorError::agg_state_error)?;
+                        yield Message::Chunk(chunk);
+                    }
+
+                    yield m;
+                }
+                // The rows are held until the barrier, so a watermark can't be forwarded before
+                // them. Same as the hash agg, watermarks are dropped.
+                Message::Watermark(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use futures::StreamExt;
+    use itertools::Itertools;
+    use risingwave_common::array::{I64Array, Op, Row, StreamChunk};
+    use risingwave_common::catalog::schema_test_utils;
+    use risingwave_common::column_nonnull;
+    use risingwave_common::error::Result;
+    use risingwave_common::types::DataType;
+    use risingwave_expr::expr::AggKind;
+
+    use crate::executor::Message;
+    use crate::executor_v2::aggregation::{AggArgs, AggCall};
+    use crate::executor_v2::test_utils::MockSource;
+    use crate::executor_v2::{Executor, LocalHashAggExecutor};
+    use crate::row_nonnull;
+
+    #[tokio::test]
+    async fn test_local_hash_agg() -> Result<()> {
+        let chunk1 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [1, 2, 1] },
+                column_nonnull! { I64Array, [10, 20, 30] },
+                // primary key column
+                column_nonnull! { I64Array, [1001, 1002, 1003] },
+            ],
+            None,
+        );
+        let chunk2 = StreamChunk::new(
+            vec![Op::Delete, Op::Delete, Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [1, 2, 3] },
+                column_nonnull! { I64Array, [10, 20, 40] },
+                // primary key column
+                column_nonnull! { I64Array, [1001, 1002, 1004] },
+            ],
+            Some((vec![true, false, true]).try_into().unwrap()),
+        );
+        let schema = schema_test_utils::iii();
+
+        let mut source = MockSource::new(schema, vec![2]);
+        source.push_barrier(1, false);
+        source.push_chunks([chunk1].into_iter());
+        source.push_barrier(2, false);
+        source.push_chunks([chunk2].into_iter());
+        source.push_barrier(3, false);
+        source.push_barrier(4, false);
+
+        let agg_calls = vec![
+            AggCall {
+                kind: AggKind::RowCount,
+                args: AggArgs::None,
+                return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
+            },
+            AggCall {
+                kind: AggKind::Sum,
+                args: AggArgs::Unary(DataType::Int64, 1),
+                return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
+            },
+        ];
+
+        let hash_agg = Box::new(LocalHashAggExecutor::new(
+            Box::new(source),
+            agg_calls,
+            vec![0],
+            vec![0],
+            1,
+        )?);
+        let mut hash_agg = hash_agg.execute();
+
+        let mut next_rows = || async {
+            match hash_agg.next().await.unwrap().unwrap() {
+                Message::Chunk(chunk) => {
+                    let (data_chunk, ops) = chunk.into_parts();
+                    ops.into_iter()
+                        .zip_eq(data_chunk.rows().map(Row::from))
+                        .collect_vec()
+                }
+                msg => unreachable!("unexpected message {:?}", msg),
+            }
+        };
+
+        // Consume the init barrier
+        assert_matches!(
+            hash_agg.next().await.unwrap().unwrap(),
+            Message::Barrier { .. }
+        );
+        assert_eq!(
+            next_rows().await,
+            vec![
+                (Op::Insert, row_nonnull![1_i64, 2_i64, 40_i64]),
+                (Op::Insert, row_nonnull![2_i64, 1_i64, 20_i64]),
+            ]
+        );
+        assert_matches!(
+            hash_agg.next().await.unwrap().unwrap(),
+            Message::Barrier { .. }
+        );
+
+        // The partial results of an epoch are the deltas, so the count can be negative.
+        assert_eq!(
+            next_rows().await,
+            vec![
+                (Op::Insert, row_nonnull![1_i64, -1_i64, -10_i64]),
+                (Op::Insert, row_nonnull![3_i64, 1_i64, 40_i64]),
+            ]
+        );
+        assert_matches!(
+            hash_agg.next().await.unwrap().unwrap(),
+            Message::Barrier { .. }
+        );
+
+        // Nothing is emitted for an epoch without any row.
+        assert_matches!(
+            hash_agg.next().await.unwrap().unwrap(),
+            Message::Barrier { .. }
+        );
+
+        Ok(())
+    }
+}
@@ -140,6 +140,8 @@ impl LocalSimpleAggExecutor {
 
                     yield m;
                 }
+                // A simple aggregation has no group key to carry the watermark.
+                Message::Watermark(_) => {}
             }
         }
     }
@@ -197,6 +199,8 @@ mod tests {
             kind: AggKind::RowCount,
             args: AggArgs::None,
             return_type: DataType::Int64,
+            order_pairs: vec![],
+            distinct: false,
         }];
 
         let simple_agg = Box::new(LocalSimpleAggExecutor::new(
@@ -260,16 +264,22 @@ mod tests {
                 kind: AggKind::RowCount,
                 args: AggArgs::None,
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 0),
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
             AggCall {
                 kind: AggKind::Sum,
                 args: AggArgs::Unary(DataType::Int64, 1),
                 return_type: DataType::Int64,
+                order_pairs: vec![],
+                distinct: false,
             },
         ];
 
@@ -14,6 +14,7 @@
 
 use async_trait::async_trait;
 use futures::StreamExt;
+use prometheus::core::{AtomicU64, GenericCounter};
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
 use risingwave_common::try_match_expand;
@@ -26,7 +27,10 @@ use crate::executor::{Executor as ExecutorV1, ExecutorBuilder};
 use crate::executor_v2::{Barrier, BoxedMessageStream, Executor, PkIndices, PkIndicesRef};
 use crate::task::{ExecutorParams, LocalStreamManagerCore};
 
+mod cache;
 mod sides;
+use self::cache::LookupCache;
+pub use self::cache::LOOKUP_CACHE_ENTRY_SIZE;
 use self::sides::*;
 mod impl_;
 
@@ -66,6 +70,13 @@ pub struct LookupExecutor<S: StateStore> {
 
     /// The last received barrier.
     last_barrier: Option<Barrier>,
+
+    /// The cache of lookup results. `None` if the cache is disabled.
+    cache: Option<LookupCache>,
+
+    cache_hit_count: GenericCounter<AtomicU64>,
+
+    cache_miss_count: GenericCounter<AtomicU64>,
 }
 
 #[async_trait]
@@ -114,6 +125,10 @@ impl ExecutorBuilder for LookupExecutorBuilder {
                 use_current_epoch: node.use_current_epoch,
                 stream_join_key_indices: node.stream_key.iter().map(|x| *x as usize).collect(),
                 arrange_join_key_indices: node.arrange_key.iter().map(|x| *x as usize).collect(),
+                cache_capacity: params.env.config().lookup_cache_capacity,
+                cache_budget: params.env.cache_budget(),
+                actor_id: params.actor_id,
+                metrics: params.executor_stats,
             }))
             .v1(),
         ))
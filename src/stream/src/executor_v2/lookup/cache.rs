@@ -0,0 +1,189 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use risingwave_common::array::Row;
+use risingwave_common::collection::evictable::EvictableHashMap;
+use risingwave_common::util::memory_budget::MemoryShare;
+
+/// Estimated memory usage of a cache entry (bytes), used to convert the memory budget into the
+/// number of entries.
+pub const LOOKUP_CACHE_ENTRY_SIZE: usize = 256;
+
+/// A LRU cache of the lookup results of the arrangement, keyed by the serialized join key. It
+/// only holds the recently probed join keys, and the others are read from the state store on miss.
+///
+/// Join keys absent in the arrangement are cached as negative entries, i.e. an empty result, so
+/// that probes that mostly miss don't hit the storage every time. An entry stays valid across
+/// epochs until its join key is updated in the arrangement. As the lookups of an epoch may still
+/// read the snapshot before the update, the updated keys are only invalidated once the lookup
+/// epoch advances.
+pub struct LookupCache {
+    data: EvictableHashMap<Vec<u8>, Vec<Row>>,
+
+    /// The epoch the entries are looked up in.
+    epoch: u64,
+
+    /// The join keys updated in the arrangement, to be invalidated once the lookup epoch advances.
+    updated_keys: HashSet<Vec<u8>>,
+
+    /// Whether more keys are updated than the cache can hold, so that all entries are to be
+    /// invalidated instead of tracking the keys.
+    all_updated: bool,
+
+    /// The share of the streaming cache budget, if the memory governor is enabled. The capacity
+    /// follows the share instead of being fixed then.
+    budget: Option<MemoryShare>,
+}
+
+impl LookupCache {
+    pub fn new(capacity: usize, budget: Option<MemoryShare>) -> Self {
+        Self {
+            data: EvictableHashMap::new(capacity),
+            epoch: 0,
+            updated_keys: HashSet::new(),
+            all_updated: false,
+            budget,
+        }
+    }
+
+    /// Lookup the cached rows of the join key. An empty result means the key is known to be
+    /// absent in the arrangement.
+    pub fn lookup(&mut self, key: &[u8]) -> Option<&Vec<Row>> {
+        let rows = self.data.get(key);
+        if let Some(budget) = &self.budget {
+            match rows {
+                Some(_) => budget.record_hit(),
+                None => budget.record_miss(),
+            }
+        }
+        rows
+    }
+
+    pub fn insert(&mut self, key: Vec<u8>, rows: Vec<Row>) {
+        self.data.put(key, rows);
+    }
+
+    /// Records that the rows of the join key are updated in the arrangement.
+    pub fn update_key(&mut self, key: Vec<u8>) {
+        if self.all_updated {
+            return;
+        }
+        if self.updated_keys.len() >= self.data.target_cap() {
+            self.updated_keys.clear();
+            self.all_updated = true;
+        } else {
+            self.updated_keys.insert(key);
+        }
+    }
+
+    /// Invalidate the entries of the updated join keys if the lookup epoch is different from the
+    /// epoch of the cache.
+    pub fn update_epoch(&mut self, epoch: u64) {
+        if self.epoch == epoch {
+            return;
+        }
+        if self.all_updated {
+            self.data.clear();
+        } else {
+            for key in self.updated_keys.drain() {
+                self.data.pop(&key);
+            }
+        }
+        self.updated_keys.clear();
+        self.all_updated = false;
+        self.epoch = epoch;
+    }
+
+    /// Evict the least recently used entries to keep the cache within its capacity.
+    pub fn evict(&mut self) {
+        if let Some(budget) = &self.budget {
+            self.data
+                .set_target_cap((budget.bytes() / LOOKUP_CACHE_ENTRY_SIZE).max(1));
+        }
+        self.data.evict_to_target_cap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use risingwave_common::util::memory_budget::MemoryBudget;
+
+    use super::*;
+    use crate::row_nonnull;
+
+    #[test]
+    fn test_lookup_cache() {
+        let mut cache = LookupCache::new(2, None);
+        cache.update_epoch(1);
+        cache.insert(vec![1], vec![row_nonnull![1i32]]);
+        cache.insert(vec![2], vec![]);
+        assert_eq!(cache.lookup(&[1]), Some(&vec![row_nonnull![1i32]]));
+        // Negative entry.
+        assert_eq!(cache.lookup(&[2]), Some(&vec![]));
+        assert_eq!(cache.lookup(&[3]), None);
+
+        cache.insert(vec![3], vec![]);
+        cache.evict();
+        // The least recently used entry is evicted.
+        assert_eq!(cache.lookup(&[1]), None);
+        assert!(cache.lookup(&[3]).is_some());
+
+        // The entries stay valid across epochs until their keys are updated.
+        cache.insert(vec![1], vec![row_nonnull![1i32]]);
+        cache.update_epoch(2);
+        assert!(cache.lookup(&[1]).is_some());
+        assert!(cache.lookup(&[3]).is_some());
+
+        // The updated keys are invalidated once the epoch advances.
+        cache.update_key(vec![3]);
+        cache.update_epoch(2);
+        assert!(cache.lookup(&[3]).is_some());
+        cache.update_epoch(3);
+        assert_eq!(cache.lookup(&[3]), None);
+        assert!(cache.lookup(&[1]).is_some());
+
+        // All entries are invalidated if more keys are updated than the cache can hold.
+        cache.insert(vec![3], vec![]);
+        for key in 4..7 {
+            cache.update_key(vec![key]);
+        }
+        cache.update_epoch(4);
+        assert_eq!(cache.lookup(&[1]), None);
+        assert_eq!(cache.lookup(&[3]), None);
+    }
+
+    #[test]
+    fn test_lookup_cache_with_budget() {
+        let budget = Arc::new(MemoryBudget::new("test", 2 * LOOKUP_CACHE_ENTRY_SIZE));
+        let mut cache = LookupCache::new(65536, Some(budget.share()));
+        cache.update_epoch(1);
+        for key in 0..4 {
+            cache.insert(vec![key], vec![]);
+        }
+        assert!(cache.lookup(&[3]).is_some());
+        assert!(cache.lookup(&[4]).is_none());
+        assert_eq!(budget.take_stats(), (1, 1));
+
+        // The capacity follows the budget.
+        cache.evict();
+        assert_eq!(cache.data.len(), 2);
+        budget.set_bytes(LOOKUP_CACHE_ENTRY_SIZE);
+        cache.evict();
+        assert_eq!(cache.data.len(), 1);
+    }
+}
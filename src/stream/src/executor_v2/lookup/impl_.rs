@@ -12,23 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::{Row, RowRef, StreamChunk};
 use risingwave_common::catalog::{ColumnDesc, Schema};
 use risingwave_common::error::Result;
+use risingwave_common::util::memory_budget::MemoryBudgetRef;
 use risingwave_common::util::ordered::OrderedRowSerializer;
 use risingwave_common::util::sort_util::OrderPair;
 use risingwave_storage::cell_based_row_deserializer::CellBasedRowDeserializer;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::sides::{stream_lookup_arrange_prev_epoch, stream_lookup_arrange_this_epoch};
+use super::LookupCache;
 use crate::common::StreamChunkBuilder;
+use crate::executor::monitor::StreamingMetrics;
 use crate::executor_v2::error::{StreamExecutorError, TracedStreamExecutorError};
 use crate::executor_v2::lookup::sides::{ArrangeJoinSide, ArrangeMessage, StreamJoinSide};
 use crate::executor_v2::lookup::LookupExecutor;
 use crate::executor_v2::{Barrier, Executor, Message, PkIndices};
+use crate::task::ActorId;
 
 /// Parameters for [`LookupExecutor`].
 pub struct LookupExecutorParams<S: StateStore> {
@@ -98,6 +104,17 @@ pub struct LookupExecutorParams<S: StateStore> {
 
     /// The join keys on the arrangement side.
     pub arrange_join_key_indices: Vec<usize>,
+
+    /// The capacity of the lookup cache, in number of join keys. 0 disables the cache.
+    pub cache_capacity: usize,
+
+    /// The budget of streaming caches shared by all executors on the node, if the memory governor
+    /// is enabled. The cache is sized by its share of the budget instead of `cache_capacity` then.
+    pub cache_budget: Option<MemoryBudgetRef>,
+
+    pub actor_id: ActorId,
+
+    pub metrics: Arc<StreamingMetrics>,
 }
 
 impl<S: StateStore> LookupExecutor<S> {
@@ -112,16 +129,20 @@ impl<S: StateStore> LookupExecutor<S> {
             use_current_epoch,
             stream_join_key_indices,
             arrange_join_key_indices,
+            cache_capacity,
+            cache_budget,
+            actor_id,
+            metrics,
         } = params;
 
         let output_column_length = stream.schema().len() + arrangement.schema().len();
 
-        // output schema: | arrange | stream |
-        let schema_fields = arrangement
+        // output schema: | stream | arrange |
+        let schema_fields = stream
             .schema()
             .fields
             .iter()
-            .chain(stream.schema().fields.iter())
+            .chain(arrangement.schema().fields.iter())
             .cloned()
             .collect_vec();
 
@@ -189,6 +210,15 @@ impl<S: StateStore> LookupExecutor<S> {
                 keyspace: arrangement_keyspace,
                 use_current_epoch,
             },
+            cache: (cache_capacity > 0).then(|| {
+                LookupCache::new(cache_capacity, cache_budget.map(|budget| budget.share()))
+            }),
+            cache_hit_count: metrics
+                .lookup_cache_hit_count
+                .with_label_values(&[&actor_id.to_string()]),
+            cache_miss_count: metrics
+                .lookup_cache_miss_count
+                .with_label_values(&[&actor_id.to_string()]),
         }
     }
 
@@ -223,6 +253,10 @@ impl<S: StateStore> LookupExecutor<S> {
                         .map_err(StreamExecutorError::eval_error)?;
                     yield Message::Barrier(barrier)
                 }
+                ArrangeMessage::ArrangeUpdate(chunk) => {
+                    self.process_arrange_update(chunk)
+                        .map_err(StreamExecutorError::eval_error)?;
+                }
                 ArrangeMessage::ArrangeReady => {
                     // The arrangement is ready, and we will receive a bunch of stream messages for
                     // the next poll.
@@ -244,6 +278,31 @@ impl<S: StateStore> LookupExecutor<S> {
         Ok(())
     }
 
+    /// Records the join keys updated in the arrangement, so that their cached lookup results are
+    /// invalidated.
+    fn process_arrange_update(&mut self, chunk: StreamChunk) -> Result<()> {
+        let cache = match &mut self.cache {
+            Some(cache) => cache,
+            None => return Ok(()),
+        };
+        let (chunk, _) = chunk.compact()?.into_parts();
+        for row in chunk.rows() {
+            let key = RowRef(
+                self.arrangement
+                    .join_key_indices
+                    .iter()
+                    .map(|idx| row.0[self.arrangement.order_rules[*idx].column_idx])
+                    .collect_vec(),
+            );
+            let mut key_bytes = vec![];
+            self.arrangement
+                .serializer
+                .serialize_row_ref(&key, &mut key_bytes);
+            cache.update_key(key_bytes);
+        }
+        Ok(())
+    }
+
     /// Lookup the data in the shared buffer.
     async fn lookup(&mut self, chunk: StreamChunk) -> Result<StreamChunk> {
         let last_barrier = self
@@ -255,6 +314,9 @@ impl<S: StateStore> LookupExecutor<S> {
         } else {
             last_barrier.epoch.prev
         };
+        if let Some(cache) = &mut self.cache {
+            cache.update_epoch(lookup_epoch);
+        }
         let chunk = chunk.compact()?;
         let (chunk, ops) = chunk.into_parts();
 
@@ -272,13 +334,15 @@ impl<S: StateStore> LookupExecutor<S> {
             // TODO: support outer join (return null if no rows are matched)
         }
 
+        if let Some(cache) = &mut self.cache {
+            cache.evict();
+        }
+
         builder.finish()
     }
 
     /// Lookup all rows corresponding to a join key in shared buffer.
     async fn lookup_one_row(&mut self, row: &RowRef<'_>, lookup_epoch: u64) -> Result<Vec<Row>> {
-        // TODO: add a cache for arrangement in an upstream executor
-
         // Serialize join key to a state store key.
         let key_prefix = {
             let row = RowRef(
@@ -296,7 +360,15 @@ impl<S: StateStore> LookupExecutor<S> {
             key_prefix
         };
 
-        let arrange_keyspace = self.arrangement.keyspace.append(key_prefix);
+        if let Some(cache) = &mut self.cache {
+            if let Some(rows) = cache.lookup(&key_prefix) {
+                self.cache_hit_count.inc();
+                return Ok(rows.clone());
+            }
+            self.cache_miss_count.inc();
+        }
+
+        let arrange_keyspace = self.arrangement.keyspace.append(key_prefix.clone());
         let all_cells = arrange_keyspace
             .scan_strip_prefix(None, lookup_epoch)
             .await?;
@@ -317,6 +389,11 @@ impl<S: StateStore> LookupExecutor<S> {
             all_rows.push(last_row);
         }
 
+        // Cache the result, even if it's empty.
+        if let Some(cache) = &mut self.cache {
+            cache.insert(key_prefix, all_rows.clone());
+        }
+
         Ok(all_rows)
     }
 }
@@ -87,6 +87,10 @@ pub enum ArrangeMessage {
     /// joins.
     ArrangeReady,
 
+    /// There's an update of the arrangement side in this epoch, which comes before the
+    /// `ArrangeReady` of the epoch.
+    ArrangeUpdate(StreamChunk),
+
     /// There's a message from stream side.
     Stream(StreamChunk),
 
@@ -110,6 +114,7 @@ pub async fn poll_until_barrier(stream: impl MessageStream, expected_barrier: Ba
                     break;
                 }
             }
+            Message::Watermark(_) => {}
         }
     }
 }
@@ -120,6 +125,8 @@ fn prefer_right(_: &mut ()) -> PollNext {
 
 /// A biased barrier aligner which prefers message from the right side. Barrier message will be
 /// available for both left and right side, instead of being combined.
+///
+/// The watermarks of both sides are dropped, as they are not propagated through lookup joins yet.
 #[try_stream(ok = BarrierAlignedMessage, error = TracedStreamExecutorError)]
 pub async fn align_barrier(left: impl MessageStream, right: impl MessageStream) {
     let mut left = Box::pin(left);
@@ -153,6 +160,8 @@ pub async fn align_barrier(left: impl MessageStream, right: impl MessageStream)
                     yield Either::Right(Message::Barrier(b.clone()));
                     break 'inner (SideStatus::RightBarrier, b);
                 }
+                Some(Either::Left(Ok(Message::Watermark(_))))
+                | Some(Either::Right(Ok(Message::Watermark(_)))) => {}
                 Some(Either::Left(Err(e))) | Some(Either::Right(Err(e))) => return Err(e),
                 None => {
                     break 'outer;
@@ -201,8 +210,9 @@ pub async fn stream_lookup_arrange_prev_epoch(
                 // stream side.
                 yield ArrangeMessage::Stream(msg);
             }
-            Either::Right(Message::Chunk(_)) => {
-                // For message from the arrangement side, just ignore it.
+            Either::Right(Message::Chunk(msg)) => {
+                // The arrangement side is only used to invalidate the lookup cache.
+                yield ArrangeMessage::ArrangeUpdate(msg);
             }
             Either::Left(Message::Barrier(barrier)) => {
                 yield ArrangeMessage::Barrier(barrier);
@@ -210,6 +220,9 @@ pub async fn stream_lookup_arrange_prev_epoch(
             Either::Right(Message::Barrier(_)) => {
                 yield ArrangeMessage::ArrangeReady;
             }
+            Either::Left(Message::Watermark(_)) | Either::Right(Message::Watermark(_)) => {
+                unreachable!()
+            }
         }
     }
 }
@@ -249,8 +262,9 @@ pub async fn stream_lookup_arrange_this_epoch(
                     // Should wait until arrangement from this epoch is available.
                     stream_buf.push(msg);
                 }
-                Either::Right(Message::Chunk(_)) => {
-                    // For message from the arrangement side, just ignore it.
+                Either::Right(Message::Chunk(msg)) => {
+                    // The arrangement side is only used to invalidate the lookup cache.
+                    yield ArrangeMessage::ArrangeUpdate(msg);
                 }
                 Either::Left(Message::Barrier(barrier)) => {
                     break 'inner Status::StreamReady(barrier);
@@ -262,6 +276,9 @@ pub async fn stream_lookup_arrange_this_epoch(
                     }
                     break 'inner Status::ArrangeReady;
                 }
+                Either::Left(Message::Watermark(_)) | Either::Right(Message::Watermark(_)) => {
+                    unreachable!()
+                }
             }
         };
         match status {
@@ -278,7 +295,7 @@ pub async fn stream_lookup_arrange_this_epoch(
                         yield ArrangeMessage::Barrier(b);
                         break;
                     }
-                    Either::Right(_) => unreachable!(),
+                    Either::Left(Message::Watermark(_)) | Either::Right(_) => unreachable!(),
                 }
             },
             // Stream is done in this epoch, but arrangement is not ready -- we wait for the
@@ -289,8 +306,8 @@ pub async fn stream_lookup_arrange_this_epoch(
                     .await
                     .expect("unexpected close of barrier aligner")?
                 {
-                    Either::Left(_) => unreachable!(),
-                    Either::Right(Message::Chunk(_)) => {}
+                    Either::Left(_) | Either::Right(Message::Watermark(_)) => unreachable!(),
+                    Either::Right(Message::Chunk(msg)) => yield ArrangeMessage::ArrangeUpdate(msg),
                     Either::Right(Message::Barrier(_)) => {
                         yield ArrangeMessage::ArrangeReady;
                         for msg in std::mem::take(&mut stream_buf) {
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use assert_matches::assert_matches;
 use futures::StreamExt;
 use itertools::Itertools;
@@ -23,6 +25,7 @@ use risingwave_common::util::sort_util::{OrderPair, OrderType};
 use risingwave_storage::memory::MemoryStateStore;
 use risingwave_storage::{Keyspace, StateStore};
 
+use crate::executor::monitor::StreamingMetrics;
 use crate::executor_v2::lookup::impl_::LookupExecutorParams;
 use crate::executor_v2::lookup::LookupExecutor;
 use crate::executor_v2::test_utils::*;
@@ -228,6 +231,10 @@ async fn test_lookup_this_epoch() {
         use_current_epoch: true,
         stream_join_key_indices: vec![1],
         arrange_join_key_indices: vec![0],
+        cache_capacity: 1024,
+        cache_budget: None,
+        actor_id: 1,
+        metrics: Arc::new(StreamingMetrics::unused()),
     }));
     let mut lookup_executor = lookup_executor.execute();
 
@@ -298,6 +305,10 @@ async fn test_lookup_last_epoch() {
         use_current_epoch: false,
         stream_join_key_indices: vec![1],
         arrange_join_key_indices: vec![0],
+        cache_capacity: 1024,
+        cache_budget: None,
+        actor_id: 1,
+        metrics: Arc::new(StreamingMetrics::unused()),
     }));
     let mut lookup_executor = lookup_executor.execute();
 
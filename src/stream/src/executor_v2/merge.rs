@@ -12,20 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use futures::channel::mpsc::{Receiver, Sender};
 use futures::future::select_all;
-use futures::{SinkExt, StreamExt};
+use futures::{Future, FutureExt, SinkExt, StreamExt};
 use futures_async_stream::{for_await, try_stream};
 use itertools::Itertools;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
+use risingwave_common::types::ScalarImpl;
 use risingwave_pb::task_service::GetStreamResponse;
 use risingwave_rpc_client::ComputeClient;
 use tonic::Streaming;
 use tracing_futures::Instrument;
 
-use super::{Executor, Message, PkIndicesRef};
+use super::{Executor, Message, PkIndicesRef, Watermark};
 use crate::executor::PkIndices;
 use crate::executor_v2::error::TracedStreamExecutorError;
 use crate::executor_v2::{BoxedMessageStream, ExecutorInfo};
@@ -142,16 +145,73 @@ impl Executor for MergeExecutor {
     }
 }
 
+/// Aligns the watermarks of the upstreams of a [`MergeExecutor`] or a [`super::UnionExecutor`].
+/// The watermark on a column is yielded when all the upstreams have reached it, i.e. it's the
+/// minimum of theirs.
+pub(super) struct WatermarkAligner {
+    num_inputs: usize,
+    /// The latest watermark of each upstream, keyed by column index.
+    watermarks: HashMap<usize, Vec<Option<ScalarImpl>>>,
+    /// The latest aligned watermarks yielded, keyed by column index.
+    aligned: HashMap<usize, ScalarImpl>,
+}
+
+impl WatermarkAligner {
+    pub(super) fn new(num_inputs: usize) -> Self {
+        Self {
+            num_inputs,
+            watermarks: HashMap::new(),
+            aligned: HashMap::new(),
+        }
+    }
+
+    /// Records the watermark of the `input_idx`-th upstream, and returns the aligned watermark if
+    /// it advances.
+    pub(super) fn handle(&mut self, input_idx: usize, watermark: Watermark) -> Option<Watermark> {
+        let watermarks = self
+            .watermarks
+            .entry(watermark.col_idx)
+            .or_insert_with(|| vec![None; self.num_inputs]);
+        watermarks[input_idx] = Some(watermark.val.clone());
+        let min = watermarks
+            .iter()
+            .map(|val| val.as_ref())
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .min()?
+            .clone();
+        match self.aligned.get(&watermark.col_idx) {
+            Some(aligned) if *aligned >= min => None,
+            _ => {
+                self.aligned.insert(watermark.col_idx, min.clone());
+                Some(Watermark {
+                    val: min,
+                    ..watermark
+                })
+            }
+        }
+    }
+}
+
+/// Polls the next message of the `idx`-th upstream.
+fn next_message(
+    idx: usize,
+    ch: Receiver<Message>,
+) -> impl Future<Output = (usize, (Option<Message>, Receiver<Message>))> + Unpin {
+    ch.into_future().map(move |res| (idx, res))
+}
+
 impl MergeExecutor {
     #[try_stream(ok = Message, error = TracedStreamExecutorError)]
     async fn execute_inner(self) {
-        let mut upstreams = self.upstreams;
+        let mut upstreams = self.upstreams.into_iter().enumerate().collect_vec();
+        let mut watermark_aligner = WatermarkAligner::new(self.num_inputs);
 
         loop {
             // Futures of all active upstreams.
             let mut active = upstreams
                 .into_iter()
-                .map(|ch| ch.into_future())
+                .map(|(idx, ch)| next_message(idx, ch))
                 .collect_vec();
             // Channels that're blocked by the barrier to align.
             let mut blocked = Vec::with_capacity(active.len());
@@ -161,7 +221,7 @@ impl MergeExecutor {
             // 1. Align the barriers.
             while !active.is_empty() {
                 // Poll upstreams and get a message from the ready one.
-                let ((message, from), _id, remainings) = select_all(active)
+                let ((idx, (message, from)), _id, remainings) = select_all(active)
                     .instrument(tracing::trace_span!("idle"))
                     .await;
 
@@ -175,9 +235,15 @@ impl MergeExecutor {
                 match message {
                     Message::Chunk(_) => {
                         // We may still receive message from this channel.
-                        active.push(from.into_future());
+                        active.push(next_message(idx, from));
                         yield message;
                     }
+                    Message::Watermark(watermark) => {
+                        active.push(next_message(idx, from));
+                        if let Some(watermark) = watermark_aligner.handle(idx, watermark) {
+                            yield Message::Watermark(watermark);
+                        }
+                    }
                     Message::Barrier(barrier) => {
                         // Align the barrier.
                         if let Some(current_barrier) = current_barrier.as_ref() {
@@ -186,7 +252,7 @@ impl MergeExecutor {
                             current_barrier = Some(barrier);
                         }
                         // We'll not receive message from this channel during this epoch.
-                        blocked.push(from);
+                        blocked.push((idx, from));
                     }
                 }
             }
@@ -219,6 +285,7 @@ mod tests {
     use futures::SinkExt;
     use itertools::Itertools;
     use risingwave_common::array::{Op, StreamChunk};
+    use risingwave_common::types::DataType;
     use risingwave_pb::data::StreamMessage;
     use risingwave_pb::task_service::exchange_service_server::{
         ExchangeService, ExchangeServiceServer,
@@ -304,6 +371,26 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_merger_watermark() {
+        let (mut tx1, rx1) = channel(16);
+        let (mut tx2, rx2) = channel(16);
+        let merger = MergeExecutor::new(Schema::default(), vec![], 0, vec![rx1, rx2]);
+        let mut merger = Box::new(merger).v1();
+        let watermark = |val| Watermark::new(0, DataType::Int64, ScalarImpl::Int64(val));
+
+        // The watermark is held back until all the upstreams reach it.
+        tx1.send(Message::Watermark(watermark(10))).await.unwrap();
+        tx2.send(Message::Watermark(watermark(5))).await.unwrap();
+        assert_matches!(merger.next().await.unwrap(), Message::Watermark(w) => {
+            assert_eq!(w, watermark(5));
+        });
+        tx2.send(Message::Watermark(watermark(20))).await.unwrap();
+        assert_matches!(merger.next().await.unwrap(), Message::Watermark(w) => {
+            assert_eq!(w, watermark(10));
+        });
+    }
+
     struct FakeExchangeService {
         rpc_called: Arc<AtomicBool>,
     }
@@ -20,7 +20,7 @@ pub use risingwave_common::array::StreamChunk;
 use risingwave_common::catalog::Schema;
 
 pub use super::executor::{
-    Barrier, Executor as ExecutorV1, Message, Mutation, PkIndices, PkIndicesRef,
+    Barrier, Executor as ExecutorV1, Message, Mutation, PkIndices, PkIndicesRef, Watermark,
 };
 
 pub mod aggregation;
@@ -28,39 +28,55 @@ mod batch_query;
 #[allow(dead_code)]
 mod chain;
 mod filter;
+mod fused;
+#[cfg(test)]
+mod fuzz_tests;
 mod global_simple_agg;
 mod hash_agg;
 mod hop_window;
+mod local_hash_agg;
 mod local_simple_agg;
 mod lookup;
 pub mod merge;
 pub(crate) mod mview;
 mod project;
+mod project_set;
 mod rearranged_chain;
 pub mod receiver;
 mod simple;
+mod sink;
 #[cfg(test)]
 mod test_utils;
 mod top_n;
 mod top_n_appendonly;
 mod top_n_executor;
+mod union;
 mod v1_compat;
+mod values;
+mod watermark_filter;
 
 pub use batch_query::BatchQueryExecutor;
 pub use filter::FilterExecutor;
+pub use fused::FusedExecutor;
 pub use global_simple_agg::SimpleAggExecutor;
-pub use hash_agg::HashAggExecutor;
+pub use hash_agg::{HashAggExecutor, MiniBatchConfig};
 pub use hop_window::HopWindowExecutor;
+pub use local_hash_agg::LocalHashAggExecutor;
 pub use local_simple_agg::LocalSimpleAggExecutor;
 pub use lookup::*;
 pub use merge::MergeExecutor;
 pub use mview::*;
-pub use project::ProjectExecutor;
+pub use project::{ProjectExecutor, StatefulProjectExecutor};
+pub use project_set::ProjectSetExecutor;
 pub use rearranged_chain::RearrangedChainExecutor as ChainExecutor;
 pub(crate) use simple::{SimpleExecutor, SimpleExecutorWrapper};
+pub use sink::{DeadLetterTable, SinkExecutor};
 pub use top_n::TopNExecutor;
 pub use top_n_appendonly::AppendOnlyTopNExecutor;
+pub use union::UnionExecutor;
 pub use v1_compat::{ExecutorV1AsV2, StreamExecutorV1};
+pub use values::ValuesExecutor;
+pub use watermark_filter::WatermarkFilterExecutor;
 
 pub type BoxedExecutor = Box<dyn Executor>;
 pub type BoxedMessageStream = BoxStream<'static, StreamExecutorResult<Message>>;
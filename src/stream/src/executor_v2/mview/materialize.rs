@@ -12,20 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::Op::*;
-use risingwave_common::array::Row;
+use risingwave_common::array::{DataChunk, Row, StreamChunk};
 use risingwave_common::catalog::{ColumnId, Schema};
+use risingwave_common::types::{DataType, Datum};
 use risingwave_common::util::sort_util::OrderPair;
+use risingwave_expr::expr::{build_from_prost, BoxedExpression};
+use risingwave_pb::plan::InsertOnConflict;
+use risingwave_source::{OnConflictWrite, SourceRef};
+use risingwave_storage::table::cell_based_table::CellBasedTable;
 use risingwave_storage::{Keyspace, StateStore};
 
-use crate::executor_v2::error::{StreamExecutorError, TracedStreamExecutorError};
+use crate::executor_v2::error::{
+    StreamExecutorError, StreamExecutorResult, TracedStreamExecutorError,
+};
 use crate::executor_v2::mview::ManagedMViewState;
 use crate::executor_v2::{
     BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef,
 };
+use crate::task::ArrangementHandle;
 
 /// `MaterializeExecutor` materializes changes in stream into a materialized view on storage.
 pub struct MaterializeExecutor<S: StateStore> {
@@ -37,6 +48,173 @@ pub struct MaterializeExecutor<S: StateStore> {
     arrange_columns: Vec<usize>,
 
     info: ExecutorInfo,
+
+    /// Registration of the materialized view for external point lookups, notified of the flushed
+    /// epochs, which are served once committed.
+    arrangement: Option<ArrangementHandle>,
+
+    /// Set if a row overwrites the existing row of the same key, for a table created with
+    /// `on_conflict = 'overwrite'`.
+    overwrite: Option<ConflictOverwrite<S>>,
+}
+
+/// The state to deduplicate the rows by the arrange key.
+struct ConflictOverwrite<S: StateStore> {
+    /// The materialized rows, to look up the existing row of a key.
+    table: CellBasedTable<S>,
+
+    /// A row overwrites the existing one only if its version in this column is not lower.
+    /// Otherwise the latest row always wins.
+    version_column: Option<usize>,
+
+    /// The latest rows of the keys changed in the current epoch, `None` if deleted, as they can't
+    /// be read from `table` until flushed.
+    epoch_rows: HashMap<Row, Option<Row>>,
+
+    /// The epoch to read `table` in, i.e. the current epoch.
+    epoch: u64,
+
+    /// The table source the rows are inserted into, where `INSERT ... ON CONFLICT` registers its
+    /// rows by their row ids in `row_id_index`.
+    table_source: Option<SourceRef>,
+
+    row_id_index: usize,
+
+    /// The `INSERT ... ON CONFLICT` of the last row inserted by one.
+    on_conflict: Option<OnConflict>,
+}
+
+impl<S: StateStore> ConflictOverwrite<S> {
+    async fn get_row(&self, key: &Row) -> StreamExecutorResult<Option<Row>> {
+        match self.epoch_rows.get(key) {
+            Some(row) => Ok(row.clone()),
+            None => self
+                .table
+                .get_row(key, self.epoch)
+                .await
+                .map_err(StreamExecutorError::storage),
+        }
+    }
+
+    /// Whether `row` overwrites `old_row` of the same key.
+    fn overwrites(&self, row: &Row, old_row: &Row) -> bool {
+        match self.version_column {
+            // A null version is lower than any other.
+            Some(idx) => row[idx] >= old_row[idx],
+            None => true,
+        }
+    }
+
+    /// Takes the `INSERT ... ON CONFLICT` `row` is inserted by from the table source, if any.
+    fn take_on_conflict_write(&self, row: &Row) -> Option<Arc<OnConflictWrite>> {
+        let table_source = self.table_source.as_ref()?.as_table_v2()?;
+        let row_id = row[self.row_id_index].as_ref()?.clone().into_int64();
+        table_source.take_on_conflict_row(row_id)
+    }
+
+    /// Prepares to resolve the rows inserted by `desc`, unless it's the last one seen.
+    fn set_on_conflict(
+        &mut self,
+        desc: &Arc<InsertOnConflict>,
+        data_types: &[DataType],
+    ) -> StreamExecutorResult<&OnConflict> {
+        if self
+            .on_conflict
+            .as_ref()
+            .map_or(true, |c| !Arc::ptr_eq(&c.desc, desc))
+        {
+            self.on_conflict = Some(OnConflict::new(
+                desc.clone(),
+                data_types,
+                self.row_id_index,
+            )?);
+        }
+        Ok(self.on_conflict.as_ref().unwrap())
+    }
+}
+
+/// Resolves the rows inserted by `INSERT ... ON CONFLICT` against the existing rows of the same
+/// key, instead of overwriting them.
+struct OnConflict {
+    desc: Arc<InsertOnConflict>,
+
+    /// The new values of the inserted columns of a conflicting row, over `input_types`. `None` for
+    /// `DO NOTHING`.
+    update_exprs: Option<Vec<BoxedExpression>>,
+
+    /// The columns of the existing row followed by the inserted columns of the proposed one, i.e.
+    /// except the row id.
+    input_types: Vec<DataType>,
+
+    /// Index of the row id column, which an updated row keeps.
+    row_id_index: usize,
+}
+
+impl OnConflict {
+    fn new(
+        desc: Arc<InsertOnConflict>,
+        data_types: &[DataType],
+        row_id_index: usize,
+    ) -> StreamExecutorResult<Self> {
+        let update_exprs = match desc.update_exprs.as_slice() {
+            [] => None,
+            exprs => Some(
+                exprs
+                    .iter()
+                    .map(build_from_prost)
+                    .try_collect()
+                    .map_err(StreamExecutorError::eval_error)?,
+            ),
+        };
+        let input_types = data_types
+            .iter()
+            .chain(
+                data_types
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| *idx != row_id_index)
+                    .map(|(_, data_type)| data_type),
+            )
+            .cloned()
+            .collect();
+        Ok(Self {
+            desc,
+            update_exprs,
+            input_types,
+            row_id_index,
+        })
+    }
+
+    /// Returns the row replacing `old_row` for the proposed `row`, or `None` to keep `old_row`. The
+    /// updated row keeps the row id of `old_row`.
+    fn resolve(&self, old_row: &Row, row: Row) -> StreamExecutorResult<Option<Row>> {
+        let update_exprs = match &self.update_exprs {
+            Some(update_exprs) => update_exprs,
+            None => return Ok(None),
+        };
+        let row_id_index = self.row_id_index;
+        let input_row = Row(old_row
+            .0
+            .iter()
+            .cloned()
+            .chain(
+                row.0
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(idx, _)| *idx != row_id_index)
+                    .map(|(_, datum)| datum),
+            )
+            .collect());
+        let input = DataChunk::from_rows(&[input_row], &self.input_types)
+            .map_err(StreamExecutorError::eval_error)?;
+        let mut values: Vec<Datum> = update_exprs
+            .iter()
+            .map(|expr| expr.eval(&input).map(|array| array.datum_at(0)))
+            .try_collect()
+            .map_err(StreamExecutorError::eval_error)?;
+        values.insert(row_id_index, old_row[row_id_index].clone());
+        Ok(Some(Row(values)))
+    }
 }
 
 impl<S: StateStore> MaterializeExecutor<S> {
@@ -59,9 +237,137 @@ impl<S: StateStore> MaterializeExecutor<S> {
                 pk_indices: arrange_columns,
                 identity: format!("MaterializeExecutor {:X}", executor_id),
             },
+            arrangement: None,
+            overwrite: None,
         }
     }
 
+    /// Serves the materialized rows to external point lookups with `arrangement`.
+    pub fn with_arrangement(mut self, arrangement: ArrangementHandle) -> Self {
+        self.arrangement = Some(arrangement);
+        self
+    }
+
+    /// Makes a row overwrite the existing row of the same key in `table`, instead of being added.
+    /// The overwritten rows are retracted from the output. A row whose version in
+    /// `version_column` is lower than the existing one is dropped.
+    pub fn with_conflict_overwrite(
+        mut self,
+        table: CellBasedTable<S>,
+        version_column: Option<usize>,
+    ) -> Self {
+        self.overwrite = Some(ConflictOverwrite {
+            table,
+            version_column,
+            epoch_rows: HashMap::new(),
+            epoch: 0,
+            table_source: None,
+            row_id_index: 0,
+            on_conflict: None,
+        });
+        self
+    }
+
+    /// Resolves the rows registered in `table_source` by `INSERT ... ON CONFLICT` with their row
+    /// ids in `row_id_index`, instead of overwriting with them. Must be called after
+    /// [`Self::with_conflict_overwrite`].
+    pub fn with_insert_on_conflict(mut self, table_source: SourceRef, row_id_index: usize) -> Self {
+        let overwrite = self.overwrite.as_mut().unwrap();
+        overwrite.table_source = Some(table_source);
+        overwrite.row_id_index = row_id_index;
+        self
+    }
+
+    /// Materializes the visible rows of `chunk` by overwriting the rows of the same key, and
+    /// returns the changes actually made, if any. The rows inserted by `INSERT ... ON CONFLICT` are
+    /// resolved by it instead, regardless of the version column, and reported to it as resolved.
+    async fn overwrite_chunk(
+        &mut self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let data_types = self.info.schema.data_types();
+        let overwrite = self.overwrite.as_mut().unwrap();
+
+        let mut changes = vec![];
+        for (idx, op) in chunk.ops().iter().enumerate() {
+            let visible = chunk
+                .visibility()
+                .as_ref()
+                .map(|x| x.is_set(idx).unwrap())
+                .unwrap_or(true);
+            if !visible {
+                continue;
+            }
+
+            let arrange_row = Row(self
+                .arrange_columns
+                .iter()
+                .map(|col_idx| chunk.column_at(*col_idx).array_ref().datum_at(idx))
+                .collect_vec());
+            let row = Row(chunk
+                .columns()
+                .iter()
+                .map(|x| x.array_ref().datum_at(idx))
+                .collect_vec());
+
+            let old_row = overwrite.get_row(&arrange_row).await?;
+            match op {
+                Insert | UpdateInsert => {
+                    let on_conflict_write = match op {
+                        Insert => overwrite.take_on_conflict_write(&row),
+                        _ => None,
+                    };
+                    let row = match old_row {
+                        Some(old_row) => {
+                            let new_row = match &on_conflict_write {
+                                Some(write) => overwrite
+                                    .set_on_conflict(write.on_conflict(), &data_types)?
+                                    .resolve(&old_row, row)?,
+                                None if overwrite.overwrites(&row, &old_row) => Some(row),
+                                None => None,
+                            };
+                            if let Some(write) = &on_conflict_write {
+                                write.resolve_row(new_row.is_some());
+                            }
+                            let row = match new_row {
+                                Some(row) => row,
+                                None => continue,
+                            };
+                            changes.push((UpdateDelete, old_row));
+                            changes.push((UpdateInsert, row.clone()));
+                            row
+                        }
+                        None => {
+                            if let Some(write) = &on_conflict_write {
+                                write.resolve_row(true);
+                            }
+                            changes.push((Insert, row.clone()));
+                            row
+                        }
+                    };
+                    self.local_state.put(arrange_row.clone(), row.clone());
+                    overwrite.epoch_rows.insert(arrange_row, Some(row));
+                }
+                // The stored row is deleted, which may differ from the given one if it's
+                // overwritten.
+                Delete | UpdateDelete => {
+                    if let Some(old_row) = old_row {
+                        changes.push((Delete, old_row));
+                        self.local_state.delete(arrange_row.clone());
+                        overwrite.epoch_rows.insert(arrange_row, None);
+                    }
+                }
+            }
+        }
+
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        let chunk = StreamChunk::from_rows(&changes, &data_types)
+            .map_err(StreamExecutorError::eval_error)?;
+        Ok(Some(chunk))
+    }
+
     #[try_stream(ok = Message, error = TracedStreamExecutorError)]
     async fn execute_inner(mut self) {
         let input = self.input.execute();
@@ -69,6 +375,13 @@ impl<S: StateStore> MaterializeExecutor<S> {
         for msg in input {
             let msg = msg?;
             yield match msg {
+                Message::Chunk(chunk) if self.overwrite.is_some() => {
+                    let chunk = self.overwrite_chunk(chunk).await?;
+                    match chunk {
+                        Some(chunk) => Message::Chunk(chunk),
+                        None => continue,
+                    }
+                }
                 Message::Chunk(chunk) => {
                     for (idx, op) in chunk.ops().iter().enumerate() {
                         // check visibility
@@ -113,8 +426,16 @@ impl<S: StateStore> MaterializeExecutor<S> {
                         .flush(b.epoch.prev)
                         .await
                         .map_err(StreamExecutorError::ExecutorV1)?;
+                    if let Some(arrangement) = &self.arrangement {
+                        arrangement.flush_epoch(self.local_state.state_store(), b.epoch.prev);
+                    }
+                    if let Some(overwrite) = &mut self.overwrite {
+                        overwrite.epoch_rows.clear();
+                        overwrite.epoch = b.epoch.curr;
+                    }
                     Message::Barrier(b)
                 }
+                Message::Watermark(w) => Message::Watermark(w),
             }
         }
     }
@@ -151,6 +472,7 @@ impl<S: StateStore> std::fmt::Debug for MaterializeExecutor<S> {
 mod tests {
 
     use futures::stream::StreamExt;
+    use itertools::Itertools;
     use risingwave_common::array::{I32Array, Op, Row};
     use risingwave_common::catalog::{ColumnDesc, Field, Schema, TableId};
     use risingwave_common::column_nonnull;
@@ -244,4 +566,308 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    /// Returns the ops and the columns of the next chunk of int32 columns, skipping the barriers.
+    async fn next_chunk(stream: &mut BoxedMessageStream) -> (Vec<Op>, Vec<Vec<Option<i32>>>) {
+        loop {
+            match stream.next().await.unwrap().unwrap() {
+                Message::Chunk(chunk) => {
+                    let columns = chunk
+                        .columns()
+                        .iter()
+                        .map(|column| column.array_ref().as_int32().iter().collect_vec())
+                        .collect_vec();
+                    return (chunk.ops().to_vec(), columns);
+                }
+                Message::Barrier(_) => continue,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_materialize_executor_conflict_overwrite() {
+        let memory_state_store = MemoryStateStore::new();
+        let table_id = TableId::new(1);
+        // The first column is the pk, and the third one is the version.
+        let schema = Schema::new(vec![Field::unnamed(DataType::Int32); 3]);
+        let column_ids = vec![0.into(), 1.into(), 2.into()];
+
+        let chunk1 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::Insert],
+            vec![
+                column_nonnull! { I32Array, [1, 2, 1] },
+                column_nonnull! { I32Array, [10, 20, 11] },
+                column_nonnull! { I32Array, [5, 5, 3] },
+            ],
+            None,
+        );
+        let chunk2 = StreamChunk::new(
+            vec![Op::Insert, Op::Delete, Op::Insert],
+            vec![
+                column_nonnull! { I32Array, [1, 2, 3] },
+                column_nonnull! { I32Array, [12, 0, 30] },
+                column_nonnull! { I32Array, [5, 0, 1] },
+            ],
+            None,
+        );
+        let source = MockSource::with_messages(
+            schema.clone(),
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(chunk1),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Chunk(chunk2),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+            ],
+        );
+
+        let keyspace = Keyspace::table_root(memory_state_store.clone(), &table_id);
+        let order_types = vec![OrderType::Ascending];
+        let column_descs = column_ids
+            .iter()
+            .map(|id| ColumnDesc::unnamed(*id, DataType::Int32))
+            .collect_vec();
+        let table = CellBasedTable::new_for_test(keyspace.clone(), column_descs, order_types);
+        let mut materialize_executor = Box::new(
+            MaterializeExecutor::new(
+                Box::new(source),
+                keyspace,
+                vec![OrderPair::new(0, OrderType::Ascending)],
+                column_ids,
+                1,
+            )
+            .with_conflict_overwrite(table.clone(), Some(2)),
+        )
+        .execute();
+
+        // The row of an older version is dropped.
+        assert_eq!(
+            next_chunk(&mut materialize_executor).await,
+            (
+                vec![Op::Insert, Op::Insert],
+                vec![
+                    vec![Some(1), Some(2)],
+                    vec![Some(10), Some(20)],
+                    vec![Some(5), Some(5)]
+                ]
+            )
+        );
+        // The overwritten row is retracted, and the stored row is deleted.
+        assert_eq!(
+            next_chunk(&mut materialize_executor).await,
+            (
+                vec![Op::UpdateDelete, Op::UpdateInsert, Op::Delete, Op::Insert],
+                vec![
+                    vec![Some(1), Some(1), Some(2), Some(3)],
+                    vec![Some(10), Some(12), Some(20), Some(30)],
+                    vec![Some(5), Some(5), Some(5), Some(1)]
+                ]
+            )
+        );
+
+        materialize_executor.next().await.transpose().unwrap();
+        let row = table
+            .get_row(&Row(vec![Some(1_i32.into())]), u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            row,
+            Some(Row(vec![
+                Some(1_i32.into()),
+                Some(12_i32.into()),
+                Some(5_i32.into())
+            ]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_materialize_executor_insert_on_conflict() {
+        use std::sync::Arc;
+
+        use risingwave_common::array::I64Array;
+        use risingwave_common::catalog::ColumnId;
+        use risingwave_pb::data::data_type::TypeName;
+        use risingwave_pb::data::DataType as ProstDataType;
+        use risingwave_pb::expr::expr_node::{RexNode, Type};
+        use risingwave_pb::expr::{ExprNode, FunctionCall, InputRefExpr};
+        use risingwave_pb::plan::InsertOnConflict;
+        use risingwave_source::{Source, SourceImpl, TableSourceV2, TableV2ReaderContext};
+
+        let memory_state_store = MemoryStateStore::new();
+        let table_id = TableId::new(1);
+        // The columns are the row id, the pk, and a value.
+        let data_types = vec![DataType::Int64, DataType::Int32, DataType::Int32];
+        let schema = Schema::new(data_types.iter().cloned().map(Field::unnamed).collect());
+        let column_ids: Vec<ColumnId> = vec![0.into(), 1.into(), 2.into()];
+        let column_descs = column_ids
+            .iter()
+            .zip_eq(&data_types)
+            .map(|(id, data_type)| ColumnDesc::unnamed(*id, data_type.clone()))
+            .collect_vec();
+
+        // The rows of `INSERT ... ON CONFLICT` are registered in the table source.
+        let table_source = Arc::new(SourceImpl::TableV2(TableSourceV2::new(
+            column_descs.clone(),
+        )));
+        let _reader = table_source
+            .as_table_v2()
+            .unwrap()
+            .stream_reader(TableV2ReaderContext, column_ids.clone())
+            .unwrap();
+
+        let input_ref = |column_idx| ExprNode {
+            expr_type: Type::InputRef as i32,
+            return_type: Some(ProstDataType {
+                type_name: TypeName::Int32 as i32,
+                ..Default::default()
+            }),
+            rex_node: Some(RexNode::InputRef(InputRefExpr { column_idx })),
+        };
+        let do_nothing = Arc::new(InsertOnConflict {
+            pk_indices: vec![0],
+            update_exprs: vec![],
+        });
+        // `DO UPDATE SET v = t.v + excluded.v`, over the existing row followed by the inserted
+        // columns of the proposed one.
+        let do_update = Arc::new(InsertOnConflict {
+            pk_indices: vec![0],
+            update_exprs: vec![
+                input_ref(1),
+                ExprNode {
+                    expr_type: Type::Add as i32,
+                    return_type: Some(ProstDataType {
+                        type_name: TypeName::Int32 as i32,
+                        ..Default::default()
+                    }),
+                    rex_node: Some(RexNode::FuncCall(FunctionCall {
+                        children: vec![input_ref(2), input_ref(4)],
+                    })),
+                },
+            ],
+        });
+
+        // All the inserts are in the same epoch, so none of them is visible in the storage when
+        // the next one is resolved.
+        let chunk1 = StreamChunk::new(
+            vec![Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [0] },
+                column_nonnull! { I32Array, [1] },
+                column_nonnull! { I32Array, [10] },
+            ],
+            None,
+        );
+        let chunk2 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [1, 2, 3] },
+                column_nonnull! { I32Array, [1, 2, 2] },
+                column_nonnull! { I32Array, [100, 20, 200] },
+            ],
+            None,
+        );
+        let chunk3 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [4, 5] },
+                column_nonnull! { I32Array, [1, 2] },
+                column_nonnull! { I32Array, [5, 7] },
+            ],
+            None,
+        );
+        let do_nothing_rx = table_source
+            .as_table_v2()
+            .unwrap()
+            .write_chunk_on_conflict(chunk2.clone(), &[1, 2, 3], do_nothing)
+            .unwrap();
+        let do_update_rx = table_source
+            .as_table_v2()
+            .unwrap()
+            .write_chunk_on_conflict(chunk3.clone(), &[4, 5], do_update)
+            .unwrap();
+        let source = MockSource::with_messages(
+            schema.clone(),
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(chunk1),
+                Message::Chunk(chunk2),
+                Message::Chunk(chunk3),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+            ],
+        );
+
+        let keyspace = Keyspace::table_root(memory_state_store.clone(), &table_id);
+        let order_types = vec![OrderType::Ascending];
+        let table = CellBasedTable::new_for_test(keyspace.clone(), column_descs, order_types);
+        let mut materialize_executor = Box::new(
+            MaterializeExecutor::new(
+                Box::new(source),
+                keyspace,
+                vec![OrderPair::new(1, OrderType::Ascending)],
+                column_ids,
+                1,
+            )
+            .with_conflict_overwrite(table.clone(), None)
+            .with_insert_on_conflict(table_source, 0),
+        )
+        .execute();
+
+        async fn next_rows(stream: &mut BoxedMessageStream) -> Vec<(Op, Row)> {
+            loop {
+                match stream.next().await.unwrap().unwrap() {
+                    Message::Chunk(chunk) => {
+                        return chunk
+                            .rows()
+                            .map(|row| (row.op(), row.to_owned_row()))
+                            .collect_vec()
+                    }
+                    Message::Barrier(_) => continue,
+                    _ => unreachable!(),
+                }
+            }
+        }
+        let row = |row_id: i64, pk: i32, v: i32| {
+            Row(vec![Some(row_id.into()), Some(pk.into()), Some(v.into())])
+        };
+
+        assert_eq!(
+            next_rows(&mut materialize_executor).await,
+            vec![(Op::Insert, row(0, 1, 10))]
+        );
+        // `DO NOTHING` keeps the row inserted before in the epoch, and the row inserted before by
+        // the same statement. Only the row inserted is counted.
+        assert_eq!(
+            next_rows(&mut materialize_executor).await,
+            vec![(Op::Insert, row(2, 2, 20))]
+        );
+        assert_eq!(do_nothing_rx.await.unwrap(), 1);
+        // `DO UPDATE` updates the rows inserted before in the epoch, keeping their row ids.
+        assert_eq!(
+            next_rows(&mut materialize_executor).await,
+            vec![
+                (Op::UpdateDelete, row(0, 1, 10)),
+                (Op::UpdateInsert, row(0, 1, 15)),
+                (Op::UpdateDelete, row(2, 2, 20)),
+                (Op::UpdateInsert, row(2, 2, 27)),
+            ]
+        );
+        assert_eq!(do_update_rx.await.unwrap(), 2);
+
+        materialize_executor.next().await.transpose().unwrap();
+        let row = table
+            .get_row(&Row(vec![Some(2_i32.into())]), u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            row,
+            Some(Row(vec![
+                Some(2_i64.into()),
+                Some(2_i32.into()),
+                Some(27_i32.into())
+            ]))
+        );
+    }
 }
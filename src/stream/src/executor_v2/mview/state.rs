@@ -12,20 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use risingwave_common::array::Row;
 use risingwave_common::catalog::ColumnId;
+use risingwave_common::collection::evictable::EvictableHashMap;
 use risingwave_common::error::Result;
 use risingwave_common::util::ordered::*;
 use risingwave_common::util::sort_util::OrderType;
+use risingwave_common::util::value_encoding::serialize_cell;
 use risingwave_storage::storage_value::StorageValue;
 use risingwave_storage::{Keyspace, StateStore};
 
 use crate::executor::managed_state::flush_status::HashMapFlushStatus as FlushStatus;
 
+/// The number of recently written rows to remember, so that their unchanged cells are not
+/// rewritten.
+const WRITTEN_ROWS_CAPACITY: usize = 1 << 14;
+
 /// `ManagedMViewState` buffers recent mutations. Data will be written
 /// to backend storage on calling `flush`.
+///
+/// Mutations on the same key are coalesced in the cache. The latest rows written of recently
+/// updated keys are remembered, so that only the changed cells of a hot key are rewritten in every
+/// epoch.
 pub struct ManagedMViewState<S: StateStore> {
     keyspace: Keyspace<S>,
 
@@ -40,6 +50,10 @@ pub struct ManagedMViewState<S: StateStore> {
 
     /// Cached key/values
     cache: HashMap<Row, FlushStatus<Row>>,
+
+    /// The latest rows written into the state store, of at most [`WRITTEN_ROWS_CAPACITY`]
+    /// recently updated keys.
+    written_rows: EvictableHashMap<Row, Row>,
 }
 
 impl<S: StateStore> ManagedMViewState<S> {
@@ -54,11 +68,17 @@ impl<S: StateStore> ManagedMViewState<S> {
             keyspace,
             column_ids,
             cache: HashMap::new(),
+            written_rows: EvictableHashMap::new(WRITTEN_ROWS_CAPACITY),
             order_types: order_types.clone(),
             key_serializer: OrderedRowSerializer::new(order_types),
         }
     }
 
+    /// The state store the rows are flushed to.
+    pub fn state_store(&self) -> S {
+        self.keyspace.state_store()
+    }
+
     /// Put a key into the managed mview state. `arrange_keys` is composed of group keys and
     /// primary keys.
     pub fn put(&mut self, pk: Row, value: Row) {
@@ -77,24 +97,81 @@ impl<S: StateStore> ManagedMViewState<S> {
     }
 
     pub async fn flush(&mut self, epoch: u64) -> Result<()> {
+        let cells = self.serialize_cache()?;
+
         let mut batch = self.keyspace.state_store().start_write_batch();
-        batch.reserve(self.cache.len() * self.column_ids.len());
+        batch.reserve(cells.len());
         let mut local = batch.prefixify(&self.keyspace);
+        for (key, value) in cells {
+            match value {
+                // TODO(Yuanxin): Implement value meta
+                Some(val) => local.put(key, StorageValue::new_default_put(val)),
+                None => local.delete(key),
+            }
+        }
+        batch.ingest(epoch).await?;
+        self.written_rows.evict_to_target_cap();
+        Ok(())
+    }
 
+    /// Drain the buffered rows into the serialized cells to write, `None` for deletion, skipping
+    /// the cells unchanged since they were written.
+    fn serialize_cache(&mut self) -> Result<BTreeMap<Vec<u8>, Option<Vec<u8>>>> {
+        let mut cells_to_write = BTreeMap::new();
         for (arrange_keys, cells) in self.cache.drain() {
-            let row = cells.into_option();
             let arrange_key_buf = serialize_pk(&arrange_keys, &self.key_serializer)?;
-            let bytes = serialize_pk_and_row(&arrange_key_buf, &row, &self.column_ids)?;
-            for (key, value) in bytes {
-                match value {
-                    // TODO(Yuanxin): Implement value meta
-                    Some(val) => local.put(key, StorageValue::new_default_put(val)),
-                    None => local.delete(key),
+            let cell_key = |column_id: &ColumnId| -> Result<Vec<u8>> {
+                Ok([&arrange_key_buf[..], &serialize_column_id(column_id)?].concat())
+            };
+
+            let is_delete_insert = cells.is_delete_insert();
+            match (cells.into_option(), self.written_rows.get(&arrange_keys)) {
+                // Only rewrite the changed cells of the row.
+                (Some(row), Some(written_row)) => {
+                    for (column_id, (datum, written_datum)) in self
+                        .column_ids
+                        .iter()
+                        .zip(row.0.iter().zip(written_row.0.iter()))
+                    {
+                        if datum == written_datum {
+                            continue;
+                        }
+                        let value = match datum {
+                            Some(_) => Some(serialize_cell(datum)?),
+                            None => None,
+                        };
+                        cells_to_write.insert(cell_key(column_id)?, value);
+                    }
+                    self.written_rows.put(arrange_keys, row);
+                }
+                (Some(row), None) => {
+                    // The cells of the old row which become null must be deleted.
+                    if is_delete_insert {
+                        for (column_id, datum) in self.column_ids.iter().zip(row.0.iter()) {
+                            if datum.is_none() {
+                                cells_to_write.insert(cell_key(column_id)?, None);
+                            }
+                        }
+                    }
+                    let row = Some(row);
+                    for (key, value) in
+                        serialize_pk_and_row(&arrange_key_buf, &row, &self.column_ids)?
+                    {
+                        cells_to_write.insert(key, value);
+                    }
+                    self.written_rows.put(arrange_keys, row.unwrap());
+                }
+                (None, _) => {
+                    for (key, value) in
+                        serialize_pk_and_row(&arrange_key_buf, &None, &self.column_ids)?
+                    {
+                        cells_to_write.insert(key, value);
+                    }
+                    self.written_rows.pop(&arrange_keys);
                 }
             }
         }
-        batch.ingest(epoch).await?;
-        Ok(())
+        Ok(cells_to_write)
     }
 }
 
@@ -145,4 +222,36 @@ mod tests {
         let data = keyspace.scan(None, epoch).await.unwrap();
         assert_eq!(data.len(), 3);
     }
+
+    #[tokio::test]
+    async fn test_mview_state_coalesce_written_cells() {
+        let state_store = MemoryStateStore::new();
+        let keyspace = Keyspace::executor_root(state_store.clone(), 0x42);
+
+        let mut state = ManagedMViewState::new(
+            keyspace.clone(),
+            vec![0.into(), 1.into()],
+            vec![OrderType::Ascending],
+        );
+        let pk = Row(vec![Some(1_i32.into())]);
+        state.put(
+            pk.clone(),
+            Row(vec![Some(1_i32.into()), Some(11_i32.into())]),
+        );
+        state.flush(0).await.unwrap();
+
+        // Rewriting the same row writes nothing.
+        state.put(
+            pk.clone(),
+            Row(vec![Some(1_i32.into()), Some(11_i32.into())]),
+        );
+        assert!(state.serialize_cache().unwrap().is_empty());
+
+        // Only the cell becoming null is deleted.
+        state.delete(pk.clone());
+        state.put(pk, Row(vec![Some(1_i32.into()), None]));
+        let cells = state.serialize_cache().unwrap();
+        assert_eq!(cells.len(), 1);
+        assert!(cells.values().all(Option::is_none));
+    }
 }
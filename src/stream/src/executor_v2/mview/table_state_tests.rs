@@ -520,3 +520,52 @@ async fn test_shuffled_column_id_for_get_row() {
         .unwrap();
     assert_eq!(get_no_exist_res, None);
 }
+
+#[tokio::test]
+async fn test_scan_range() {
+    let state_store = MemoryStateStore::new();
+    let order_types = vec![OrderType::Ascending, OrderType::Descending];
+    let keyspace = Keyspace::executor_root(state_store, 0x42);
+    let column_ids = vec![ColumnId::from(0), ColumnId::from(1), ColumnId::from(2)];
+    let column_descs = vec![
+        ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[2], DataType::Int32),
+    ];
+
+    let mut state = ManagedMViewState::new(keyspace.clone(), column_ids, order_types.clone());
+    let table = CellBasedTable::new_for_test(keyspace.clone(), column_descs, order_types);
+    let epoch: u64 = 0;
+
+    let row = |a: i32, b: i32| Row(vec![Some(a.into()), Some(b.into()), Some((a + b).into())]);
+    for (a, b) in [(1, 11), (1, 12), (2, 22), (3, 33)] {
+        state.put(Row(vec![Some(a.into()), Some(b.into())]), row(a, b));
+    }
+    state.flush(epoch).await.unwrap();
+
+    let epoch = u64::MAX;
+    let all = table.scan_range(None, None, None, epoch).await.unwrap();
+    assert_eq!(all, vec![row(1, 12), row(1, 11), row(2, 22), row(3, 33)]);
+
+    // Bounds are inclusive prefixes of the pk.
+    let start = Row(vec![Some(1_i32.into())]);
+    let end = Row(vec![Some(2_i32.into())]);
+    let res = table
+        .scan_range(Some(&start), Some(&end), None, epoch)
+        .await
+        .unwrap();
+    assert_eq!(res, vec![row(1, 12), row(1, 11), row(2, 22)]);
+
+    let res = table
+        .scan_range(Some(&start), Some(&end), Some(2), epoch)
+        .await
+        .unwrap();
+    assert_eq!(res, vec![row(1, 12), row(1, 11)]);
+
+    let start = Row(vec![Some(1_i32.into()), Some(11_i32.into())]);
+    let res = table
+        .scan_range(Some(&start), None, None, epoch)
+        .await
+        .unwrap();
+    assert_eq!(res, vec![row(1, 11), row(2, 22), row(3, 33)]);
+}
@@ -14,15 +14,22 @@
 
 use std::fmt::{Debug, Formatter};
 
+use futures::StreamExt;
+use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{DataChunk, StreamChunk};
 use risingwave_common::catalog::{Field, Schema};
 use risingwave_expr::expr::BoxedExpression;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::{Keyspace, StateStore};
 
-use super::{Executor, ExecutorInfo, SimpleExecutor, SimpleExecutorWrapper, StreamExecutorResult};
+use super::{
+    BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, SimpleExecutor,
+    SimpleExecutorWrapper, StreamExecutorResult, Watermark,
+};
 use crate::executor::PkIndicesRef;
-use crate::executor_v2::error::StreamExecutorError;
+use crate::executor_v2::error::{StreamExecutorError, TracedStreamExecutorError};
 
 pub type ProjectExecutor = SimpleExecutorWrapper<SimpleProjectExecutor>;
 
@@ -37,6 +44,92 @@ impl ProjectExecutor {
     }
 }
 
+/// `StatefulProjectExecutor` is a [`ProjectExecutor`] with stateful expressions in the select list,
+/// e.g. `monotonic_id()`. The states of the expressions are restored on the first barrier, and
+/// checkpointed on each barrier afterwards.
+pub struct StatefulProjectExecutor<S: StateStore> {
+    pub(super) input: BoxedExecutor,
+    pub(super) inner: SimpleProjectExecutor,
+
+    /// The states of the expressions are stored in this keyspace, keyed by their positions.
+    pub(super) keyspace: Keyspace<S>,
+}
+
+impl<S: StateStore> StatefulProjectExecutor<S> {
+    pub fn new(
+        input: BoxedExecutor,
+        exprs: Vec<BoxedExpression>,
+        keyspace: Keyspace<S>,
+        executor_id: u64,
+    ) -> Self {
+        let info = input.info();
+        Self {
+            input,
+            inner: SimpleProjectExecutor::new(info, exprs, executor_id),
+            keyspace,
+        }
+    }
+
+    #[try_stream(ok = Message, error = TracedStreamExecutorError)]
+    async fn execute_inner(self) {
+        let StatefulProjectExecutor {
+            input,
+            mut inner,
+            keyspace,
+        } = self;
+        let mut input = input.execute();
+        let barrier = input
+            .next()
+            .await
+            .unwrap()?
+            .into_barrier()
+            .expect("the first message received by project executor must be a barrier");
+        inner.restore_states(&keyspace, barrier.epoch.prev).await?;
+        let mut epoch = barrier.epoch.curr;
+        yield Message::Barrier(barrier);
+
+        #[for_await]
+        for msg in input {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    if let Some(chunk) = inner.map_filter_chunk(chunk)? {
+                        yield Message::Chunk(chunk);
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    inner.checkpoint_states(&keyspace, epoch).await?;
+                    inner.on_barrier(&barrier)?;
+                    epoch = barrier.epoch.curr;
+                    yield Message::Barrier(barrier);
+                }
+                Message::Watermark(watermark) => {
+                    if let Some(watermark) = inner.map_watermark(watermark) {
+                        yield Message::Watermark(watermark);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for StatefulProjectExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        self.inner.schema()
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        self.inner.pk_indices()
+    }
+
+    fn identity(&self) -> &str {
+        self.inner.identity()
+    }
+}
+
 /// `ProjectExecutor` project data with the `expr`. The `expr` takes a chunk of data,
 /// and returns a new data chunk. And then, `ProjectExecutor` will insert, delete
 /// or update element into next operator according to the result of the expression.
@@ -66,6 +159,47 @@ impl SimpleProjectExecutor {
     }
 }
 
+impl SimpleProjectExecutor {
+    /// Restores the states of the stateful expressions, which are keyed by their positions in the
+    /// select list.
+    async fn restore_states<S: StateStore>(
+        &self,
+        keyspace: &Keyspace<S>,
+        epoch: u64,
+    ) -> StreamExecutorResult<()> {
+        for (idx, expr) in self.exprs.iter().enumerate() {
+            if expr.state().is_none() {
+                continue;
+            }
+            if let Some(state) = keyspace.get((idx as u32).to_be_bytes(), epoch).await? {
+                expr.restore_state(&state)
+                    .map_err(StreamExecutorError::eval_error)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checkpoints the states of the stateful expressions in `epoch`.
+    async fn checkpoint_states<S: StateStore>(
+        &self,
+        keyspace: &Keyspace<S>,
+        epoch: u64,
+    ) -> StreamExecutorResult<()> {
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        let mut local = write_batch.prefixify(keyspace);
+        for (idx, expr) in self.exprs.iter().enumerate() {
+            if let Some(state) = expr.state() {
+                local.put(
+                    (idx as u32).to_be_bytes(),
+                    StorageValue::new_default_put(state),
+                );
+            }
+        }
+        write_batch.ingest(epoch).await?;
+        Ok(())
+    }
+}
+
 impl Debug for SimpleProjectExecutor {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ProjectExecutor")
@@ -105,6 +239,14 @@ impl SimpleExecutor for SimpleProjectExecutor {
         Ok(Some(new_chunk))
     }
 
+    /// The watermark is moved to the first output column that directly refers to its column.
+    fn map_watermark(&mut self, watermark: Watermark) -> Option<Watermark> {
+        self.exprs
+            .iter()
+            .position(|expr| expr.input_ref_index() == Some(watermark.col_idx))
+            .map(|idx| watermark.with_idx(idx))
+    }
+
     fn schema(&self) -> &Schema {
         &self.info.schema
     }
@@ -125,14 +267,15 @@ mod tests {
     use risingwave_common::array::{I64Array, *};
     use risingwave_common::catalog::{Field, Schema};
     use risingwave_common::column_nonnull;
-    use risingwave_common::types::DataType;
+    use risingwave_common::types::{DataType, ScalarImpl};
     use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
-    use risingwave_expr::expr::InputRefExpression;
+    use risingwave_expr::expr::{Expression, InputRefExpression, MonotonicIdExpression};
     use risingwave_pb::expr::expr_node::Type;
 
     use super::super::test_utils::MockSource;
     use super::super::*;
     use super::*;
+    use crate::executor::test_utils::create_in_memory_keyspace;
 
     #[tokio::test]
     async fn test_projection() {
@@ -206,4 +349,109 @@ mod tests {
 
         assert!(project.next().await.unwrap().unwrap().is_stop());
     }
+
+    #[tokio::test]
+    async fn test_stateful_projection() {
+        let keyspace = create_in_memory_keyspace();
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let chunk = || {
+            StreamChunk::new(
+                vec![Op::Insert, Op::Insert],
+                vec![column_nonnull! { I64Array, [1, 2] }],
+                None,
+            )
+        };
+        let stateful_project = |messages| {
+            let source = MockSource::with_messages(schema.clone(), PkIndices::new(), messages);
+            let exprs = vec![
+                InputRefExpression::new(DataType::Int64, 0).boxed(),
+                MonotonicIdExpression::new().boxed(),
+            ];
+            Box::new(StatefulProjectExecutor::new(
+                Box::new(source),
+                exprs,
+                keyspace.clone(),
+                1,
+            ))
+            .execute()
+        };
+        let ids = |message: Message| {
+            message
+                .into_chunk()
+                .unwrap()
+                .column_at(1)
+                .array_ref()
+                .as_int64()
+                .iter()
+                .collect_vec()
+        };
+
+        let mut project = stateful_project(vec![
+            Message::Barrier(Barrier::new_test_barrier(1)),
+            Message::Chunk(chunk()),
+            Message::Barrier(Barrier::new_test_barrier(2)),
+        ]);
+        assert!(project.next().await.unwrap().unwrap().is_barrier());
+        let message = project.next().await.unwrap().unwrap();
+        assert_eq!(ids(message), vec![Some(0), Some(1)]);
+        assert!(project.next().await.unwrap().unwrap().is_barrier());
+
+        // After recovery, the ids continue from the checkpointed state.
+        let mut project = stateful_project(vec![
+            Message::Barrier(Barrier::new_test_barrier(2)),
+            Message::Chunk(chunk()),
+        ]);
+        assert!(project.next().await.unwrap().unwrap().is_barrier());
+        let message = project.next().await.unwrap().unwrap();
+        assert_eq!(ids(message), vec![Some(2), Some(3)]);
+    }
+
+    #[tokio::test]
+    async fn test_project_watermark() {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let watermark =
+            |col_idx, val| Watermark::new(col_idx, DataType::Int64, ScalarImpl::Int64(val));
+        let source = MockSource::with_messages(
+            schema,
+            PkIndices::new(),
+            vec![
+                Message::Watermark(watermark(0, 10)),
+                Message::Watermark(watermark(1, 20)),
+            ],
+        );
+
+        // SELECT $1, $0 + $1
+        let exprs = vec![
+            InputRefExpression::new(DataType::Int64, 1).boxed(),
+            new_binary_expr(
+                Type::Add,
+                DataType::Int64,
+                InputRefExpression::new(DataType::Int64, 0).boxed(),
+                InputRefExpression::new(DataType::Int64, 1).boxed(),
+            ),
+        ];
+        let project = Box::new(ProjectExecutor::new(Box::new(source), exprs, 1));
+        let mut project = project.execute();
+
+        // The watermark on $0 is dropped as $0 is not in the output, and the one on $1 is moved to
+        // the 1st output column.
+        assert_eq!(
+            project
+                .next()
+                .await
+                .unwrap()
+                .unwrap()
+                .into_watermark()
+                .unwrap(),
+            watermark(0, 20)
+        );
+        assert!(project.next().await.unwrap().unwrap().is_stop());
+    }
 }
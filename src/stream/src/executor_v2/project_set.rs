@@ -0,0 +1,214 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Formatter};
+
+use risingwave_common::array::{DataChunk, Op, StreamChunk};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::DataType;
+use risingwave_expr::table_function::{eval_project_set, ProjectSetSelectItem};
+
+use super::{Executor, ExecutorInfo, PkIndices, SimpleExecutor, SimpleExecutorWrapper, Watermark};
+use crate::executor::PkIndicesRef;
+use crate::executor_v2::error::{StreamExecutorError, StreamExecutorResult};
+
+pub type ProjectSetExecutor = SimpleExecutorWrapper<SimpleProjectSetExecutor>;
+
+impl ProjectSetExecutor {
+    pub fn new(
+        input: Box<dyn Executor>,
+        pk_indices: PkIndices,
+        select_list: Vec<ProjectSetSelectItem>,
+        executor_id: u64,
+    ) -> Self {
+        SimpleExecutorWrapper {
+            input,
+            inner: SimpleProjectSetExecutor::new(pk_indices, select_list, executor_id),
+        }
+    }
+}
+
+/// `ProjectSetExecutor` expands each input row with the table functions in `select_list`, and
+/// outputs `projected_row_id` followed by the items of the select list. All the rows expanded from
+/// an input row carry the operation of it.
+pub struct SimpleProjectSetExecutor {
+    info: ExecutorInfo,
+
+    select_list: Vec<ProjectSetSelectItem>,
+}
+
+impl SimpleProjectSetExecutor {
+    pub fn new(
+        pk_indices: PkIndices,
+        select_list: Vec<ProjectSetSelectItem>,
+        executor_id: u64,
+    ) -> Self {
+        let schema = Schema {
+            fields: std::iter::once(Field::unnamed(DataType::Int64))
+                .chain(
+                    select_list
+                        .iter()
+                        .map(|item| Field::unnamed(item.return_type())),
+                )
+                .collect(),
+        };
+        Self {
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("ProjectSetExecutor {:X}", executor_id),
+            },
+            select_list,
+        }
+    }
+}
+
+impl Debug for SimpleProjectSetExecutor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProjectSetExecutor")
+            .field("select_list", &self.select_list)
+            .finish()
+    }
+}
+
+impl SimpleExecutor for SimpleProjectSetExecutor {
+    fn map_filter_chunk(
+        &mut self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let chunk = chunk.compact().map_err(StreamExecutorError::eval_error)?;
+
+        let (ops, columns, _) = chunk.into_inner();
+        let data_chunk = DataChunk::builder().columns(columns).build();
+        let (columns, input_row_indices) = eval_project_set(&self.select_list, &data_chunk)
+            .map_err(StreamExecutorError::eval_error)?;
+        if input_row_indices.is_empty() {
+            return Ok(None);
+        }
+
+        // An update may be expanded to different numbers of rows before and after, so it's
+        // emitted as a pair of delete and insert.
+        let ops = input_row_indices
+            .into_iter()
+            .map(|idx| match ops[idx] {
+                Op::Insert | Op::UpdateInsert => Op::Insert,
+                Op::Delete | Op::UpdateDelete => Op::Delete,
+            })
+            .collect();
+        Ok(Some(StreamChunk::new(ops, columns, None)))
+    }
+
+    /// The watermark is moved to the first scalar item that directly refers to its column, after
+    /// `projected_row_id`.
+    fn map_watermark(&mut self, watermark: Watermark) -> Option<Watermark> {
+        self.select_list
+            .iter()
+            .position(|item| {
+                matches!(item, ProjectSetSelectItem::Expr(expr)
+                    if expr.input_ref_index() == Some(watermark.col_idx))
+            })
+            .map(|idx| watermark.with_idx(idx + 1))
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::StreamExt;
+    use itertools::Itertools;
+    use risingwave_common::array::column::Column;
+    use risingwave_common::array::{I64Array, *};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::ScalarImpl;
+    use risingwave_expr::expr::{Expression, InputRefExpression};
+
+    use super::super::test_utils::MockSource;
+    use super::super::*;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_project_set() {
+        let list_type = DataType::List {
+            datatype: Box::new(DataType::Int64),
+        };
+        let mut list_builder = list_type.create_array_builder(3).unwrap();
+        for values in [vec![1, 2], vec![3], vec![]] {
+            list_builder
+                .append_datum(&Some(ScalarImpl::List(ListValue::new(
+                    values.into_iter().map(|v: i64| Some(v.into())).collect(),
+                ))))
+                .unwrap();
+        }
+        let chunk = StreamChunk::new(
+            vec![Op::Insert, Op::UpdateDelete, Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [10, 20, 30] },
+                Column::new(Arc::new(list_builder.finish().unwrap())),
+            ],
+            None,
+        );
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(list_type.clone()),
+            ],
+        };
+        let source = MockSource::with_chunks(schema, PkIndices::new(), vec![chunk]);
+
+        let select_list = vec![
+            ProjectSetSelectItem::Expr(InputRefExpression::new(DataType::Int64, 0).boxed()),
+            ProjectSetSelectItem::Unnest(InputRefExpression::new(list_type, 1).boxed()),
+        ];
+        let project_set = Box::new(ProjectSetExecutor::new(
+            Box::new(source),
+            vec![1, 0],
+            select_list,
+            1,
+        ));
+        let mut project_set = project_set.execute();
+
+        let chunk = project_set
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_chunk()
+            .unwrap();
+        assert_eq!(chunk.ops(), vec![Op::Insert, Op::Insert, Op::Delete]);
+        let column = |idx: usize| {
+            chunk
+                .column_at(idx)
+                .array_ref()
+                .as_int64()
+                .iter()
+                .collect_vec()
+        };
+        assert_eq!(column(0), vec![Some(0), Some(1), Some(0)]);
+        assert_eq!(column(1), vec![Some(10), Some(10), Some(20)]);
+        assert_eq!(column(2), vec![Some(1), Some(2), Some(3)]);
+    }
+}
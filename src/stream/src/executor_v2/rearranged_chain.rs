@@ -17,7 +17,7 @@ use std::sync::Arc;
 use either::Either;
 use futures::channel::{mpsc, oneshot};
 use futures::stream::select_with_strategy;
-use futures::{stream, FutureExt, Stream, StreamExt};
+use futures::{future, stream, FutureExt, Stream, StreamExt};
 use futures_async_stream::{for_await, try_stream};
 use risingwave_common::array::StreamChunk;
 use risingwave_common::catalog::Schema;
@@ -34,6 +34,10 @@ use crate::task::{ActorId, FinishCreateMviewNotifier};
 ///
 /// [`RearrangedChainExecutor`] resolves the latency problem when creating MV with a huge amount of
 /// existing data, by rearranging the barrier from the upstream. Check the design doc for details.
+///
+/// If `snapshot_only` is set, the existing MV is read as a bounded stream: only the snapshot is
+/// emitted, and the upstream is only used for the barriers. After the snapshot is consumed, the
+/// creation finish is reported on the next barrier as usual, and no more data will be emitted.
 pub struct RearrangedChainExecutor {
     snapshot: BoxedExecutor,
 
@@ -41,6 +45,8 @@ pub struct RearrangedChainExecutor {
 
     upstream_indices: Arc<[usize]>,
 
+    snapshot_only: bool,
+
     notifier: FinishCreateMviewNotifier,
 
     actor_id: ActorId,
@@ -48,7 +54,7 @@ pub struct RearrangedChainExecutor {
     info: ExecutorInfo,
 }
 
-fn mapping(upstream_indices: &[usize], msg: Message) -> Message {
+fn mapping(upstream_indices: &[usize], msg: Message) -> Option<Message> {
     match msg {
         Message::Chunk(chunk) => {
             let (ops, columns, visibility) = chunk.into_inner();
@@ -56,9 +62,18 @@ fn mapping(upstream_indices: &[usize], msg: Message) -> Message {
                 .iter()
                 .map(|&i| columns[i].clone())
                 .collect();
-            Message::Chunk(StreamChunk::new(ops, mapped_columns, visibility))
+            Some(Message::Chunk(StreamChunk::new(
+                ops,
+                mapped_columns,
+                visibility,
+            )))
         }
-        _ => msg,
+        // Watermarks on the columns not in the output are dropped.
+        Message::Watermark(watermark) => upstream_indices
+            .iter()
+            .position(|&i| i == watermark.col_idx)
+            .map(|idx| Message::Watermark(watermark.with_idx(idx))),
+        _ => Some(msg),
     }
 }
 
@@ -84,6 +99,7 @@ impl From<Message> for RearrangedMessage {
         match msg {
             Message::Chunk(chunk) => RearrangedMessage::Chunk(chunk),
             Message::Barrier(barrier) => RearrangedMessage::RearrangedBarrier(barrier),
+            Message::Watermark(_) => unreachable!("the snapshot never yields watermarks"),
         }
     }
 }
@@ -93,6 +109,7 @@ impl RearrangedChainExecutor {
         snapshot: BoxedExecutor,
         upstream: BoxedExecutor,
         upstream_indices: Vec<usize>,
+        snapshot_only: bool,
         notifier: FinishCreateMviewNotifier,
         actor_id: ActorId,
         info: ExecutorInfo,
@@ -101,6 +118,7 @@ impl RearrangedChainExecutor {
             snapshot,
             upstream,
             upstream_indices: upstream_indices.into(),
+            snapshot_only,
             notifier,
             actor_id,
             info,
@@ -109,12 +127,21 @@ impl RearrangedChainExecutor {
 
     #[try_stream(ok = Message, error = TracedStreamExecutorError)]
     async fn execute_inner(self) {
-        // 0. Project the upstream with `upstream_indices`.
+        // 0. Project the upstream with `upstream_indices`. If only the snapshot is required, keep
+        // the barriers of the upstream only.
         let upstream_indices = self.upstream_indices.clone();
-        let mut upstream = self
-            .upstream
-            .execute()
-            .map(move |result| result.map(|msg| mapping(&upstream_indices, msg)));
+        let snapshot_only = self.snapshot_only;
+        let mut upstream = self.upstream.execute().filter_map(move |result| {
+            future::ready(
+                result
+                    .map(|msg| match msg {
+                        Message::Barrier(_) => Some(msg),
+                        _ if snapshot_only => None,
+                        _ => mapping(&upstream_indices, msg),
+                    })
+                    .transpose(),
+            )
+        });
 
         // 1. Poll the upstream to get the first barrier.
         let first_msg = upstream.next().await.unwrap()?;
@@ -303,6 +330,10 @@ impl RearrangedChainExecutor {
                                 StreamExecutorError::channel_closed("rearranged upstream")
                             })?;
                     }
+
+                    // The rows in the snapshot may be older than the watermarks of the upstream,
+                    // so the watermarks are dropped until the snapshot is consumed.
+                    Message::Watermark(_) => {}
                 },
             }
         }
@@ -331,4 +362,98 @@ impl Executor for RearrangedChainExecutor {
     }
 }
 
-// TODO: add new unit tests for rearranged chain
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use futures::StreamExt;
+    use itertools::Itertools;
+    use risingwave_common::array::{Array, I32Array, Op, StreamChunk};
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::DataType;
+    use risingwave_pb::common::ActorInfo;
+
+    use super::RearrangedChainExecutor;
+    use crate::executor::{Barrier, Message, Mutation, PkIndices};
+    use crate::executor_v2::test_utils::MockSource;
+    use crate::executor_v2::{Executor, ExecutorInfo};
+    use crate::task::{FinishCreateMviewNotifier, LocalBarrierManager};
+
+    #[tokio::test]
+    async fn test_snapshot_only() {
+        let schema = Schema::new(vec![Field::unnamed(DataType::Int32)]);
+        let chunk = |val: i32| {
+            StreamChunk::new(
+                vec![Op::Insert],
+                vec![column_nonnull! { I32Array, [val] }],
+                None,
+            )
+        };
+        let snapshot = Box::new(
+            MockSource::with_chunks(schema.clone(), PkIndices::new(), vec![chunk(1), chunk(2)])
+                .stop_on_finish(false),
+        );
+
+        let add_output = Mutation::AddOutput(HashMap::from([(
+            1,
+            vec![ActorInfo {
+                actor_id: 0,
+                host: None,
+            }],
+        )]));
+        let upstream = Box::new(
+            MockSource::with_messages(
+                schema.clone(),
+                PkIndices::new(),
+                vec![
+                    Message::Barrier(Barrier::new_test_barrier(1).with_mutation(add_output)),
+                    Message::Chunk(chunk(3)),
+                    Message::Barrier(Barrier::new_test_barrier(2)),
+                    Message::Chunk(chunk(4)),
+                    Message::Barrier(Barrier::new_test_barrier(3)),
+                ],
+            )
+            .stop_on_finish(false),
+        );
+
+        let notifier = FinishCreateMviewNotifier {
+            barrier_manager: Arc::new(parking_lot::Mutex::new(LocalBarrierManager::for_test())),
+            actor_id: 0,
+        };
+        let chain = RearrangedChainExecutor::new(
+            snapshot,
+            upstream,
+            vec![0],
+            true,
+            notifier,
+            0,
+            ExecutorInfo {
+                schema,
+                pk_indices: PkIndices::new(),
+                identity: "Chain".to_owned(),
+            },
+        );
+        let messages: Vec<Message> = Box::new(chain)
+            .execute()
+            .map(|message| message.unwrap())
+            .collect()
+            .await;
+
+        // Only the snapshot is emitted, while all the barriers are still forwarded.
+        let values = messages
+            .iter()
+            .filter_map(|message| match message {
+                Message::Chunk(chunk) => chunk.column_at(0).array_ref().as_int32().value_at(0),
+                _ => None,
+            })
+            .collect_vec();
+        assert_eq!(values, vec![1, 2]);
+        let epochs = messages
+            .iter()
+            .filter_map(|message| message.as_barrier().map(|barrier| barrier.epoch.curr))
+            .collect_vec();
+        assert_eq!(epochs, vec![1, 2, 3]);
+    }
+}
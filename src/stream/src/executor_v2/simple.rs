@@ -17,7 +17,10 @@ use futures_async_stream::try_stream;
 use risingwave_common::catalog::Schema;
 
 use super::error::{StreamExecutorResult, TracedStreamExecutorError};
-use super::{BoxedExecutor, BoxedMessageStream, Executor, Message, PkIndicesRef, StreamChunk};
+use super::{
+    Barrier, BoxedExecutor, BoxedMessageStream, Executor, Message, PkIndicesRef, StreamChunk,
+    Watermark,
+};
 
 /// Executor which can handle [`StreamChunk`]s one by one.
 pub trait SimpleExecutor: Send + 'static {
@@ -25,6 +28,25 @@ pub trait SimpleExecutor: Send + 'static {
     fn map_filter_chunk(&mut self, chunk: StreamChunk)
         -> StreamExecutorResult<Option<StreamChunk>>;
 
+    /// Handle a barrier before it's passed to the downstream, e.g. to advance the idle watermark
+    /// of a [`super::watermark_filter::SimpleWatermarkFilterExecutor`].
+    fn on_barrier(&mut self, _barrier: &Barrier) -> StreamExecutorResult<()> {
+        Ok(())
+    }
+
+    /// Map a watermark of the input to the output, or drop it if the column is not in the output.
+    /// By default the watermark is passed through as-is.
+    fn map_watermark(&mut self, watermark: Watermark) -> Option<Watermark> {
+        Some(watermark)
+    }
+
+    /// Take the watermark generated by the executor itself, which is yielded after each chunk and
+    /// before each barrier, e.g. when the watermark of a [`super::WatermarkFilterExecutor`]
+    /// advances.
+    fn take_watermark(&mut self) -> Option<Watermark> {
+        None
+    }
+
     /// See [`super::Executor::schema`].
     fn schema(&self) -> &Schema;
 
@@ -74,11 +96,26 @@ where
         for msg in input {
             let msg = msg?;
             match msg {
-                Message::Chunk(chunk) => match inner.map_filter_chunk(chunk)? {
-                    Some(new_chunk) => yield Message::Chunk(new_chunk),
-                    None => continue,
-                },
-                m => yield m,
+                Message::Chunk(chunk) => {
+                    if let Some(new_chunk) = inner.map_filter_chunk(chunk)? {
+                        yield Message::Chunk(new_chunk);
+                    }
+                    if let Some(watermark) = inner.take_watermark() {
+                        yield Message::Watermark(watermark);
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    inner.on_barrier(&barrier)?;
+                    if let Some(watermark) = inner.take_watermark() {
+                        yield Message::Watermark(watermark);
+                    }
+                    yield Message::Barrier(barrier);
+                }
+                Message::Watermark(watermark) => {
+                    if let Some(watermark) = inner.map_watermark(watermark) {
+                        yield Message::Watermark(watermark);
+                    }
+                }
             }
         }
     }
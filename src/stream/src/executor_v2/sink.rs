@@ -0,0 +1,638 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use prost::Message as _;
+use risingwave_common::array::{Row, StreamChunk};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::ordered::OrderedRowSerializer;
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_connector::sink::{DeadLetter, SinkWriter};
+use risingwave_pb::data::StreamChunk as ProstStreamChunk;
+use risingwave_storage::monitor::StateStoreMetrics;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::table::cell_based_table::CellBasedTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::error::{StreamExecutorError, StreamExecutorResult, TracedStreamExecutorError};
+use super::{BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef};
+
+/// `DeadLetterTable` keeps the rows a sink permanently fails to deliver, so that users can query
+/// them instead of the sink failing. It's encoded in the same way as materialized views, with
+/// columns:
+///
+/// * `epoch BIGINT`: the epoch in which the row was written to the sink.
+/// * `actor_id INT`: the sink actor the row was written by.
+/// * `seq INT`: the order of the row among the dead letters of the actor in the epoch.
+/// * `reason VARCHAR`: why the row can't be delivered.
+/// * `record VARCHAR`: the change of the row.
+///
+/// `(epoch, actor_id, seq)` is the primary key, so that the actors of a sink can share the table.
+/// The rows older than the retention are expired by the ttl of the keyspace.
+pub struct DeadLetterTable<S: StateStore> {
+    table: CellBasedTable<S>,
+    actor_id: u32,
+
+    /// The dead letters of the current epoch, written on the barrier.
+    buffer: Vec<DeadLetter>,
+}
+
+impl<S: StateStore> DeadLetterTable<S> {
+    pub fn new(
+        keyspace: Keyspace<S>,
+        actor_id: u32,
+        retention: Option<Duration>,
+        stats: Arc<StateStoreMetrics>,
+    ) -> Self {
+        if let Some(retention) = retention {
+            keyspace.set_ttl(retention);
+        }
+        let table = CellBasedTable::new(
+            keyspace,
+            Self::column_descs(),
+            Some(OrderedRowSerializer::new(vec![OrderType::Ascending; 3])),
+            stats,
+        );
+        Self {
+            table,
+            actor_id,
+            buffer: vec![],
+        }
+    }
+
+    pub fn column_descs() -> Vec<ColumnDesc> {
+        [
+            ("epoch", DataType::Int64),
+            ("actor_id", DataType::Int32),
+            ("seq", DataType::Int32),
+            ("reason", DataType::Varchar),
+            ("record", DataType::Varchar),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(id, (name, data_type))| ColumnDesc {
+            name: name.to_string(),
+            ..ColumnDesc::unnamed(ColumnId::new(id as i32), data_type)
+        })
+        .collect()
+    }
+
+    fn push(&mut self, dead_letters: Vec<DeadLetter>) {
+        self.buffer.extend(dead_letters);
+    }
+
+    async fn flush(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let actor_id = self.actor_id;
+        let rows = std::mem::take(&mut self.buffer)
+            .into_iter()
+            .enumerate()
+            .map(|(seq, dead_letter)| {
+                let pk = Row(vec![
+                    Some(ScalarImpl::Int64(epoch as i64)),
+                    Some(ScalarImpl::Int32(actor_id as i32)),
+                    Some(ScalarImpl::Int32(seq as i32)),
+                ]);
+                let mut row = pk.0.clone();
+                row.push(Some(ScalarImpl::Utf8(dead_letter.reason)));
+                row.push(Some(ScalarImpl::Utf8(dead_letter.record)));
+                (pk, Some(Row(row)))
+            })
+            .collect();
+        self.table.batch_write_rows(rows, epoch).await?;
+        Ok(())
+    }
+}
+
+/// `SinkLog` keeps the chunks of each epoch written to a sink until the epoch is committed to the
+/// sink. An epoch is committed to the sink only after it's committed in the state store, so the
+/// epochs whose commit to the sink may be lost by a failure are still in the log after recovery,
+/// and are written to the sink again, unless the sink reports them committed.
+///
+/// The key of a chunk is the epoch followed by its order in the epoch, both in big-endian, and the
+/// value is the encoded chunk.
+struct SinkLog<S: StateStore> {
+    keyspace: Keyspace<S>,
+
+    /// The chunks of the current epoch, written on the barrier.
+    buffer: Vec<StreamChunk>,
+
+    /// The epochs committed to the sink, whose chunks are deleted on the barrier.
+    committed: Vec<u64>,
+}
+
+impl<S: StateStore> SinkLog<S> {
+    fn new(keyspace: Keyspace<S>) -> Self {
+        Self {
+            keyspace,
+            buffer: vec![],
+            committed: vec![],
+        }
+    }
+
+    fn push(&mut self, chunk: StreamChunk) {
+        self.buffer.push(chunk);
+    }
+
+    fn truncate(&mut self, epoch: u64) {
+        self.committed.push(epoch);
+    }
+
+    async fn flush(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+        if self.buffer.is_empty() && self.committed.is_empty() {
+            return Ok(());
+        }
+        let mut batch = self.keyspace.state_store().start_write_batch();
+        let mut local = batch.prefixify(&self.keyspace);
+        for committed in self.committed.drain(..) {
+            local.delete_range(committed.to_be_bytes(), (committed + 1).to_be_bytes());
+        }
+        for (seq, chunk) in self.buffer.drain(..).enumerate() {
+            let chunk = chunk.compact().map_err(StreamExecutorError::executor_v1)?;
+            let key = [epoch.to_be_bytes(), (seq as u64).to_be_bytes()].concat();
+            local.put(
+                key,
+                StorageValue::new_default_put(chunk.to_protobuf().encode_to_vec()),
+            );
+        }
+        batch.ingest(epoch).await?;
+        Ok(())
+    }
+
+    /// Writes the epochs in the log, read at the committed `epoch`, to the sink again. The epochs
+    /// committed to the sink before the failure are only deleted, so consumers see each epoch once.
+    async fn replay(
+        &mut self,
+        writer: &mut dyn SinkWriter,
+        epoch: u64,
+    ) -> StreamExecutorResult<()> {
+        let mut epochs: BTreeMap<u64, Vec<StreamChunk>> = BTreeMap::new();
+        for (key, value) in self.keyspace.scan_strip_prefix(None, epoch).await? {
+            let logged_epoch = u64::from_be_bytes(key[..8].try_into().unwrap());
+            let chunk = ProstStreamChunk::decode(value).map_err(StreamExecutorError::sink_error)?;
+            let chunk =
+                StreamChunk::from_protobuf(&chunk).map_err(StreamExecutorError::executor_v1)?;
+            epochs.entry(logged_epoch).or_default().push(chunk);
+        }
+
+        let committed_epoch = writer
+            .last_committed_epoch()
+            .await
+            .map_err(StreamExecutorError::sink_error)?;
+        for (logged_epoch, chunks) in epochs {
+            if committed_epoch.map_or(false, |committed| logged_epoch <= committed) {
+                self.truncate(logged_epoch);
+                continue;
+            }
+            writer
+                .begin_epoch(logged_epoch)
+                .await
+                .map_err(StreamExecutorError::sink_error)?;
+            for chunk in chunks {
+                // The dead letters were kept when the epoch was first written.
+                writer
+                    .write_chunk(chunk)
+                    .await
+                    .map_err(StreamExecutorError::sink_error)?;
+            }
+            writer
+                .flush(logged_epoch)
+                .await
+                .map_err(StreamExecutorError::sink_error)?;
+            writer
+                .commit(logged_epoch)
+                .await
+                .map_err(StreamExecutorError::sink_error)?;
+            self.truncate(logged_epoch);
+        }
+        Ok(())
+    }
+}
+
+/// `SinkExecutor` writes the changes of its input to an external system with a [`SinkWriter`], and
+/// forwards them unchanged. The changes between two barriers form an epoch, which is committed to
+/// the external system once it's committed in the state store, so each epoch is delivered
+/// atomically, and an epoch rolled back by recovery is never delivered. The rows that can't be
+/// delivered go to the [`DeadLetterTable`] in the same epoch.
+pub struct SinkExecutor<S: StateStore> {
+    input: BoxedExecutor,
+
+    /// Creates the writer, which may connect to the external system, when the executor starts.
+    writer: BoxFuture<'static, anyhow::Result<Box<dyn SinkWriter>>>,
+
+    log: SinkLog<S>,
+    dead_letters: DeadLetterTable<S>,
+    info: ExecutorInfo,
+}
+
+impl<S: StateStore> SinkExecutor<S> {
+    pub fn new(
+        input: BoxedExecutor,
+        writer: BoxFuture<'static, anyhow::Result<Box<dyn SinkWriter>>>,
+        keyspace: Keyspace<S>,
+        dead_letters: DeadLetterTable<S>,
+        executor_id: u64,
+    ) -> Self {
+        let info = input.info();
+        Self {
+            input,
+            writer,
+            log: SinkLog::new(keyspace),
+            dead_letters,
+            info: ExecutorInfo {
+                schema: info.schema,
+                pk_indices: info.pk_indices,
+                identity: format!("SinkExecutor {:X}", executor_id),
+            },
+        }
+    }
+
+    #[try_stream(ok = Message, error = TracedStreamExecutorError)]
+    async fn execute_inner(mut self) {
+        let mut writer = self.writer.await.map_err(StreamExecutorError::sink_error)?;
+        let store = self.log.keyspace.state_store();
+
+        // Whether an epoch is being written, i.e. after the first barrier.
+        let mut in_epoch = false;
+        // Whether the log left by the previous run is replayed, which is done on the first barrier.
+        let mut replayed = false;
+
+        let input = self.input.execute();
+        #[for_await]
+        for msg in input {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    if !in_epoch {
+                        return Err(StreamExecutorError::sink_error(anyhow::anyhow!(
+                            "sink receives a chunk before the first barrier"
+                        )));
+                    }
+                    let dead_letters = writer
+                        .write_chunk(chunk.clone())
+                        .await
+                        .map_err(StreamExecutorError::sink_error)?;
+                    self.dead_letters.push(dead_letters);
+                    self.log.push(chunk.clone());
+                    yield Message::Chunk(chunk);
+                }
+                Message::Barrier(barrier) => {
+                    if !replayed {
+                        self.log.replay(writer.as_mut(), barrier.epoch.prev).await?;
+                        replayed = true;
+                    }
+
+                    // The epoch ended by the barrier, to be committed to the sink once it's
+                    // committed in the state store.
+                    let mut committing = None;
+                    if in_epoch {
+                        let epoch = barrier.epoch.prev;
+                        writer
+                            .flush(epoch)
+                            .await
+                            .map_err(StreamExecutorError::sink_error)?;
+                        self.dead_letters.flush(epoch).await?;
+                        self.log.flush(epoch).await?;
+                        committing = Some(epoch);
+                    }
+                    let is_stop = matches!(barrier.mutation.as_deref(), Some(m) if m.is_stop());
+                    if is_stop {
+                        // No message is handled after a stop barrier, so commit the last epoch in
+                        // the background. Its log is left, since the sink is gone.
+                        if let Some(epoch) = committing {
+                            tokio::spawn(async move {
+                                let result = match store.wait_epoch(epoch).await {
+                                    Ok(()) => writer.commit(epoch).await,
+                                    Err(e) => Err(e.into()),
+                                };
+                                if let Err(e) = result {
+                                    tracing::warn!(
+                                        "failed to commit epoch {} to the stopped sink: {}",
+                                        epoch,
+                                        e
+                                    );
+                                }
+                            });
+                        }
+                        yield Message::Barrier(barrier);
+                        break;
+                    }
+                    in_epoch = true;
+                    let next_epoch = barrier.epoch.curr;
+
+                    // The epoch is committed only after the barrier is collected from all actors,
+                    // so wait for it after yielding the barrier.
+                    yield Message::Barrier(barrier);
+
+                    if let Some(epoch) = committing {
+                        store.wait_epoch(epoch).await?;
+                        writer
+                            .commit(epoch)
+                            .await
+                            .map_err(StreamExecutorError::sink_error)?;
+                        self.log.truncate(epoch);
+                    }
+                    writer
+                        .begin_epoch(next_epoch)
+                        .await
+                        .map_err(StreamExecutorError::sink_error)?;
+                }
+                Message::Watermark(watermark) => yield Message::Watermark(watermark),
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for SinkExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+impl<S: StateStore> std::fmt::Debug for SinkExecutor<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SinkExecutor")
+            .field("input info", &self.input.info())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use async_trait::async_trait;
+    use futures::FutureExt;
+    use parking_lot::Mutex;
+    use risingwave_common::array::{I64Array, Op};
+    use risingwave_common::catalog::{Field, TableId};
+    use risingwave_common::column_nonnull;
+    use risingwave_storage::memory::MemoryStateStore;
+    use risingwave_storage::table::TableIter;
+
+    use super::super::test_utils::MockSource;
+    use super::super::{Barrier, Mutation, PkIndices};
+    use super::*;
+
+    /// Records the calls as strings, and fails to deliver the deletions.
+    struct MockSinkWriter {
+        calls: Arc<Mutex<Vec<String>>>,
+        committed_epoch: Option<u64>,
+    }
+
+    #[async_trait]
+    impl SinkWriter for MockSinkWriter {
+        async fn begin_epoch(&mut self, epoch: u64) -> anyhow::Result<()> {
+            self.calls.lock().push(format!("begin {}", epoch));
+            Ok(())
+        }
+
+        async fn write_chunk(&mut self, chunk: StreamChunk) -> anyhow::Result<Vec<DeadLetter>> {
+            self.calls
+                .lock()
+                .push(format!("write {}", chunk.cardinality()));
+            let dead_letters = chunk
+                .ops()
+                .iter()
+                .filter(|op| **op == Op::Delete)
+                .map(|_| DeadLetter {
+                    record: "delete".to_string(),
+                    reason: "unsupported".to_string(),
+                })
+                .collect();
+            Ok(dead_letters)
+        }
+
+        async fn flush(&mut self, epoch: u64) -> anyhow::Result<()> {
+            self.calls.lock().push(format!("flush {}", epoch));
+            Ok(())
+        }
+
+        async fn commit(&mut self, epoch: u64) -> anyhow::Result<()> {
+            self.calls.lock().push(format!("commit {}", epoch));
+            self.committed_epoch = Some(epoch);
+            Ok(())
+        }
+
+        async fn last_committed_epoch(&mut self) -> anyhow::Result<Option<u64>> {
+            Ok(self.committed_epoch)
+        }
+    }
+
+    fn chunk() -> StreamChunk {
+        StreamChunk::new(
+            vec![Op::Insert, Op::Delete],
+            vec![column_nonnull! { I64Array, [1, 2] }],
+            None,
+        )
+    }
+
+    fn sink_executor(
+        messages: Vec<Message>,
+        calls: Arc<Mutex<Vec<String>>>,
+        committed_epoch: Option<u64>,
+        store: MemoryStateStore,
+    ) -> BoxedMessageStream {
+        let schema = Schema::new(vec![Field::unnamed(DataType::Int64)]);
+        let source =
+            MockSource::with_messages(schema, PkIndices::new(), messages).stop_on_finish(false);
+        let dead_letters = DeadLetterTable::new(
+            Keyspace::table_root(store.clone(), &TableId::new(1)),
+            1,
+            None,
+            Arc::new(StateStoreMetrics::unused()),
+        );
+        let writer: Box<dyn SinkWriter> = Box::new(MockSinkWriter {
+            calls,
+            committed_epoch,
+        });
+        let sink = Box::new(SinkExecutor::new(
+            Box::new(source),
+            async move { Ok(writer) }.boxed(),
+            Keyspace::executor_root(store, 1),
+            dead_letters,
+            1,
+        ));
+        sink.execute()
+    }
+
+    #[tokio::test]
+    async fn test_sink_commits_after_barrier() {
+        let store = MemoryStateStore::new();
+        let calls = Arc::new(Mutex::new(vec![]));
+        let mut sink = sink_executor(
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(chunk()),
+                Message::Chunk(chunk()),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Chunk(chunk()),
+                Message::Barrier(
+                    Barrier::new_test_barrier(3).with_mutation(Mutation::Stop(HashSet::default())),
+                ),
+            ],
+            calls.clone(),
+            None,
+            store.clone(),
+        );
+        while let Some(msg) = sink.next().await {
+            if msg.unwrap().is_stop() {
+                break;
+            }
+        }
+
+        // Each epoch is flushed on the barrier and committed after it. The stop barrier doesn't
+        // begin a new epoch, and the last epoch is committed in the background.
+        let expected = vec![
+            "begin 1", "write 2", "write 2", "flush 1", "commit 1", "begin 2", "write 2",
+            "flush 2", "commit 2",
+        ];
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while calls.lock().len() < expected.len() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(*calls.lock(), expected);
+
+        // The dead letters can be scanned like a materialized view.
+        let table = CellBasedTable::new_adhoc(
+            Keyspace::table_root(store.clone(), &TableId::new(1)),
+            DeadLetterTable::<MemoryStateStore>::column_descs(),
+            Arc::new(StateStoreMetrics::unused()),
+        );
+        let mut iter = table.iter(u64::MAX).await.unwrap();
+        let mut rows = vec![];
+        while let Some(row) = iter.next().await.unwrap() {
+            rows.push(row);
+        }
+        let dead_letter = |epoch: i64, seq: i32| {
+            Row(vec![
+                Some(ScalarImpl::Int64(epoch)),
+                Some(ScalarImpl::Int32(1)),
+                Some(ScalarImpl::Int32(seq)),
+                Some(ScalarImpl::Utf8("unsupported".to_string())),
+                Some(ScalarImpl::Utf8("delete".to_string())),
+            ])
+        };
+        assert_eq!(
+            rows,
+            vec![dead_letter(1, 0), dead_letter(1, 1), dead_letter(2, 0)]
+        );
+
+        // The log of epoch 1 is deleted in epoch 2, and the one of the last epoch is left.
+        let log = Keyspace::executor_root(store, 1)
+            .scan_strip_prefix(None, u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(&log[0].0[..8], &2u64.to_be_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_sink_replays_log() {
+        let store = MemoryStateStore::new();
+        let mut log = SinkLog::new(Keyspace::executor_root(store.clone(), 1));
+        log.push(chunk());
+        log.push(chunk());
+        log.flush(1).await.unwrap();
+
+        // The sink recovers from epoch 1, which is committed in the state store but not in the
+        // sink.
+        let calls = Arc::new(Mutex::new(vec![]));
+        let mut sink = sink_executor(
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+            ],
+            calls.clone(),
+            None,
+            store.clone(),
+        );
+        while let Some(msg) = sink.next().await {
+            msg.unwrap();
+        }
+
+        let expected = vec![
+            "begin 1", "write 2", "write 2", "flush 1", "commit 1", "begin 2", "flush 2",
+            "commit 2", "begin 3",
+        ];
+        assert_eq!(*calls.lock(), expected);
+
+        // The replayed log is deleted.
+        let log = Keyspace::executor_root(store, 1)
+            .scan_strip_prefix(None, u64::MAX)
+            .await
+            .unwrap();
+        assert!(log.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sink_skips_committed_epochs() {
+        let store = MemoryStateStore::new();
+        let mut log = SinkLog::new(Keyspace::executor_root(store.clone(), 1));
+        log.push(chunk());
+        log.flush(1).await.unwrap();
+        log.push(chunk());
+        log.flush(2).await.unwrap();
+
+        // The sink recovers from epoch 2, and epoch 1 was committed to the sink before the failure.
+        let calls = Arc::new(Mutex::new(vec![]));
+        let mut sink = sink_executor(
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(3)),
+                Message::Barrier(Barrier::new_test_barrier(4)),
+            ],
+            calls.clone(),
+            Some(1),
+            store.clone(),
+        );
+        while let Some(msg) = sink.next().await {
+            msg.unwrap();
+        }
+
+        let expected = vec![
+            "begin 2", "write 2", "flush 2", "commit 2", "begin 3", "flush 3", "commit 3",
+            "begin 4",
+        ];
+        assert_eq!(*calls.lock(), expected);
+
+        // Both epochs are deleted from the log.
+        let log = Keyspace::executor_root(store, 1)
+            .scan_strip_prefix(None, u64::MAX)
+            .await
+            .unwrap();
+        assert!(log.is_empty());
+    }
+}
@@ -27,6 +27,11 @@ use crate::executor::managed_state::top_n::{ManagedTopNBottomNState, ManagedTopN
 use crate::executor_v2::error::{StreamExecutorError, StreamExecutorResult};
 use crate::executor_v2::top_n_executor::{generate_output, TopNExecutorBase, TopNExecutorWrapper};
 use crate::executor_v2::{BoxedMessageStream, Executor, ExecutorInfo, PkIndices, PkIndicesRef};
+use crate::task::CacheMemoryHandle;
+
+/// Estimated memory usage of a cached row (bytes), used to convert the number of cached rows into
+/// bytes for the memory manager.
+const CACHE_ENTRY_SIZE: usize = 128;
 
 /// `TopNExecutor` works with input with modification, it keeps all the data
 /// records/rows that have been seen, and returns topN records overall.
@@ -64,6 +69,12 @@ impl<S: StateStore> TopNExecutor<S> {
             )?,
         })
     }
+
+    /// Report the size of the caches with `handle`, and evict them under memory pressure.
+    pub fn with_memory_handle(mut self, handle: CacheMemoryHandle) -> Self {
+        self.inner.memory_handle = Some(handle);
+        self
+    }
 }
 
 pub struct InnerTopNExecutor<S: StateStore> {
@@ -95,6 +106,11 @@ pub struct InnerTopNExecutor<S: StateStore> {
     #[allow(dead_code)]
     /// Indices of the columns on which key distribution depends.
     key_indices: Vec<usize>,
+
+    /// Reports the size of the caches of `managed_lowest_state` and `managed_highest_state` to the
+    /// memory manager, if registered. The cache of `managed_middle_state` holds the output range,
+    /// and is not evicted.
+    memory_handle: Option<CacheMemoryHandle>,
 }
 
 impl<S: StateStore> InnerTopNExecutor<S> {
@@ -173,6 +189,7 @@ impl<S: StateStore> InnerTopNExecutor<S> {
             pk_indices,
             first_execution: true,
             key_indices,
+            memory_handle: None,
         })
     }
 
@@ -188,7 +205,32 @@ impl<S: StateStore> InnerTopNExecutor<S> {
         self.managed_lowest_state
             .flush(epoch)
             .await
-            .map_err(StreamExecutorError::top_n_state_error)
+            .map_err(StreamExecutorError::top_n_state_error)?;
+        self.evict();
+        Ok(())
+    }
+
+    /// Evict the caches of the lowest and the highest ranges by the same ratio, if the memory
+    /// manager asks for it.
+    fn evict(&mut self) {
+        let handle = match &self.memory_handle {
+            Some(handle) => handle,
+            None => return,
+        };
+        let lowest_len = self.managed_lowest_state.cache_len();
+        let highest_len = self.managed_highest_state.cache_len();
+        handle.set_bytes((lowest_len + highest_len) * CACHE_ENTRY_SIZE);
+        if let Some(bytes) = handle.eviction_target() {
+            let ratio = bytes as f64 / ((lowest_len + highest_len) * CACHE_ENTRY_SIZE) as f64;
+            self.managed_lowest_state
+                .evict_to((lowest_len as f64 * ratio) as usize);
+            self.managed_highest_state
+                .evict_to((highest_len as f64 * ratio) as usize);
+            handle.set_bytes(
+                (self.managed_lowest_state.cache_len() + self.managed_highest_state.cache_len())
+                    * CACHE_ENTRY_SIZE,
+            );
+        }
     }
 }
 
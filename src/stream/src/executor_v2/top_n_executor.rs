@@ -106,6 +106,9 @@ where
                     epoch = barrier.epoch.curr;
                     yield Message::Barrier(barrier)
                 }
+                // A row behind the watermark may still enter the top n after a deletion, so the
+                // watermarks are not propagated.
+                Message::Watermark(_) => {}
             };
         }
     }
@@ -0,0 +1,218 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::future::select_all;
+use futures::{Future, FutureExt, StreamExt};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::catalog::Schema;
+
+use super::error::{StreamExecutorError, TracedStreamExecutorError};
+use super::merge::WatermarkAligner;
+use super::{
+    Barrier, BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, MessageStreamItem,
+    PkIndicesRef,
+};
+
+/// `UnionExecutor` merges the outputs of multiple executors in the same actor, for `UNION ALL`.
+/// Unlike [`super::MergeExecutor`], the inputs are executors rather than channels, so no exchange
+/// is involved. The chunks are interleaved as they arrive, while the barriers are aligned.
+pub struct UnionExecutor {
+    inputs: Vec<BoxedExecutor>,
+
+    info: ExecutorInfo,
+}
+
+impl std::fmt::Debug for UnionExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnionExecutor")
+            .field("schema", &self.info.schema)
+            .field("pk_indices", &self.info.pk_indices)
+            .field("num_inputs", &self.inputs.len())
+            .finish()
+    }
+}
+
+impl UnionExecutor {
+    pub fn new(info: ExecutorInfo, inputs: Vec<BoxedExecutor>) -> Self {
+        Self { inputs, info }
+    }
+}
+
+impl Executor for UnionExecutor {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+/// Polls the next message of the `idx`-th input.
+fn next_message(
+    idx: usize,
+    stream: BoxedMessageStream,
+) -> impl Future<Output = (usize, (Option<MessageStreamItem>, BoxedMessageStream))> + Unpin {
+    stream.into_future().map(move |res| (idx, res))
+}
+
+impl UnionExecutor {
+    #[try_stream(ok = Message, error = TracedStreamExecutorError)]
+    async fn execute_inner(self) {
+        let num_inputs = self.inputs.len();
+        let mut inputs = self
+            .inputs
+            .into_iter()
+            .map(|input| input.execute())
+            .enumerate()
+            .collect_vec();
+        let mut watermark_aligner = WatermarkAligner::new(num_inputs);
+
+        loop {
+            // Futures of all active inputs.
+            let mut active = inputs
+                .into_iter()
+                .map(|(idx, stream)| next_message(idx, stream))
+                .collect_vec();
+            // Inputs that're blocked by the barrier to align.
+            let mut blocked = Vec::with_capacity(num_inputs);
+            // The current barrier to align.
+            let mut current_barrier: Option<Barrier> = None;
+
+            // 1. Align the barriers.
+            while !active.is_empty() {
+                let ((idx, (message, from)), _id, remainings) = select_all(active).await;
+                active = remainings;
+
+                let message = match message {
+                    Some(message) => message?,
+                    // The inputs only finish after the stop barrier of the actor is yielded.
+                    None => return Ok(()),
+                };
+
+                match message {
+                    Message::Chunk(_) => {
+                        active.push(next_message(idx, from));
+                        yield message;
+                    }
+                    Message::Watermark(watermark) => {
+                        active.push(next_message(idx, from));
+                        if let Some(watermark) = watermark_aligner.handle(idx, watermark) {
+                            yield Message::Watermark(watermark);
+                        }
+                    }
+                    Message::Barrier(barrier) => {
+                        match current_barrier.as_ref() {
+                            Some(current) if current.epoch != barrier.epoch => {
+                                return Err(StreamExecutorError::align_barrier(
+                                    current.clone(),
+                                    barrier,
+                                ));
+                            }
+                            Some(_) => {}
+                            None => current_barrier = Some(barrier),
+                        }
+                        // We'll not receive message from this input during this epoch.
+                        blocked.push((idx, from));
+                    }
+                }
+            }
+
+            // 2. Yield the barrier to downstream once all barriers collected from the inputs.
+            yield Message::Barrier(current_barrier.unwrap());
+
+            // 3. Put back the inputs.
+            inputs = blocked;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::{I64Array, Op, StreamChunk};
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::DataType;
+
+    use super::super::test_utils::MockSource;
+    use super::super::*;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_union() {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let chunk = |vals: [i64; 2]| {
+            StreamChunk::new(
+                vec![Op::Insert, Op::Insert],
+                vec![column_nonnull! { I64Array, vals }],
+                None,
+            )
+        };
+        let source = |chunk: StreamChunk| {
+            Box::new(
+                MockSource::with_messages(
+                    schema.clone(),
+                    PkIndices::new(),
+                    vec![
+                        Message::Barrier(Barrier::new_test_barrier(1)),
+                        Message::Chunk(chunk),
+                        Message::Barrier(Barrier::new_test_barrier(2)),
+                    ],
+                )
+                .stop_on_finish(false),
+            ) as BoxedExecutor
+        };
+
+        let union = Box::new(UnionExecutor::new(
+            ExecutorInfo {
+                schema: schema.clone(),
+                pk_indices: PkIndices::new(),
+                identity: "UnionExecutor".to_string(),
+            },
+            vec![source(chunk([1, 2])), source(chunk([3, 4]))],
+        ));
+        let messages: Vec<Message> = union
+            .execute()
+            .map(|message| message.unwrap())
+            .collect()
+            .await;
+
+        // The barriers of both inputs are merged into one, with the chunks between them.
+        assert_eq!(messages.len(), 4);
+        assert!(matches!(&messages[0], Message::Barrier(b) if b.epoch.curr == 1));
+        let mut rows = messages[1..3]
+            .iter()
+            .flat_map(|message| match message {
+                Message::Chunk(chunk) => chunk.column_at(0).array_ref().as_int64().iter(),
+                _ => unreachable!(),
+            })
+            .map(|val| val.unwrap())
+            .collect_vec();
+        rows.sort_unstable();
+        assert_eq!(rows, vec![1, 2, 3, 4]);
+        assert!(matches!(&messages[3], Message::Barrier(b) if b.epoch.curr == 2));
+    }
+}
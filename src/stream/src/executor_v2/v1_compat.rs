@@ -23,6 +23,7 @@ use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::hash::HashKey;
 use risingwave_common::util::sort_util::{OrderPair, OrderType};
 use risingwave_expr::expr::BoxedExpression;
+use risingwave_expr::table_function::ProjectSetSelectItem;
 use risingwave_pb::stream_plan::BatchParallelInfo;
 use risingwave_storage::table::cell_based_table::CellBasedTable;
 use risingwave_storage::{Keyspace, StateStore};
@@ -30,9 +31,11 @@ use risingwave_storage::{Keyspace, StateStore};
 use super::error::{StreamExecutorError, TracedStreamExecutorError};
 use super::filter::SimpleFilterExecutor;
 use super::project::SimpleProjectExecutor;
+use super::project_set::SimpleProjectSetExecutor;
 use super::{
     BatchQueryExecutor, BoxedExecutor, ChainExecutor, Executor, ExecutorInfo, FilterExecutor,
-    HashAggExecutor, LocalSimpleAggExecutor, MaterializeExecutor, ProjectExecutor,
+    HashAggExecutor, LocalHashAggExecutor, LocalSimpleAggExecutor, MaterializeExecutor,
+    ProjectExecutor, ProjectSetExecutor, StatefulProjectExecutor,
 };
 pub use super::{BoxedMessageStream, ExecutorV1, Message, PkIndices, PkIndicesRef};
 use crate::executor_v2::aggregation::AggCall;
@@ -177,6 +180,45 @@ impl ProjectExecutor {
     }
 }
 
+impl<S: StateStore> StatefulProjectExecutor<S> {
+    pub fn new_from_v1(
+        input: Box<dyn ExecutorV1>,
+        pk_indices: PkIndices,
+        exprs: Vec<BoxedExpression>,
+        keyspace: Keyspace<S>,
+        executor_id: u64,
+        _op_info: String,
+    ) -> Self {
+        let info = ExecutorInfo {
+            schema: input.schema().to_owned(),
+            pk_indices,
+            identity: "Project".to_owned(),
+        };
+        let input = Box::new(ExecutorV1AsV2(input));
+        Self {
+            input,
+            inner: SimpleProjectExecutor::new(info, exprs, executor_id),
+            keyspace,
+        }
+    }
+}
+
+impl ProjectSetExecutor {
+    pub fn new_from_v1(
+        input: Box<dyn ExecutorV1>,
+        pk_indices: PkIndices,
+        select_list: Vec<ProjectSetSelectItem>,
+        executor_id: u64,
+        _op_info: String,
+    ) -> Self {
+        let input = Box::new(ExecutorV1AsV2(input));
+        super::SimpleExecutorWrapper {
+            input,
+            inner: SimpleProjectSetExecutor::new(pk_indices, select_list, executor_id),
+        }
+    }
+}
+
 impl ChainExecutor {
     pub fn new_from_v1(
         snapshot: Box<dyn ExecutorV1>,
@@ -184,6 +226,7 @@ impl ChainExecutor {
         notifier: FinishCreateMviewNotifier,
         schema: Schema,
         column_idxs: Vec<usize>,
+        snapshot_only: bool,
         _op_info: String,
     ) -> Self {
         let info = ExecutorInfo {
@@ -198,6 +241,7 @@ impl ChainExecutor {
             Box::new(ExecutorV1AsV2(snapshot)),
             Box::new(ExecutorV1AsV2(mview)),
             column_idxs,
+            snapshot_only,
             notifier,
             actor_id,
             info,
@@ -237,6 +281,20 @@ impl LocalSimpleAggExecutor {
     }
 }
 
+impl LocalHashAggExecutor {
+    pub fn new_from_v1(
+        input: Box<dyn ExecutorV1>,
+        agg_calls: Vec<AggCall>,
+        key_indices: Vec<usize>,
+        pk_indices: PkIndices,
+        executor_id: u64,
+        _op_info: String,
+    ) -> Result<Self> {
+        let input = Box::new(ExecutorV1AsV2(input));
+        Self::new(input, agg_calls, key_indices, pk_indices, executor_id)
+    }
+}
+
 impl<S: StateStore> SimpleAggExecutor<S> {
     pub fn new_from_v1(
         input: Box<dyn ExecutorV1>,
@@ -0,0 +1,289 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{DataChunk, I32Array, Op, StreamChunk};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::Result;
+use risingwave_common::types::ScalarImpl;
+use risingwave_common::util::value_encoding::serialize_cell;
+use risingwave_expr::expr::BoxedExpression;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::{Keyspace, StateStore};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use super::error::{StreamExecutorError, TracedStreamExecutorError};
+use super::{
+    BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef, StreamExecutorResult,
+};
+
+/// The key under which the epoch the rows are emitted in is stored.
+const EMITTED_EPOCH_KEY: &[u8] = b"emitted_epoch";
+
+/// `ValuesExecutor` is the streaming source of `CREATE MATERIALIZED VIEW ... AS VALUES`. It emits
+/// all the rows as insertions right after the first barrier, and then only forwards barriers.
+///
+/// The emission is checkpointed on the next barrier, so that the rows are not emitted again after
+/// recovery.
+pub struct ValuesExecutor<S: StateStore> {
+    info: ExecutorInfo,
+
+    rows: Vec<Vec<BoxedExpression>>,
+
+    /// Receives barriers from the barrier manager, as the executor has no input.
+    barrier_receiver: UnboundedReceiver<Message>,
+
+    /// The emission of the rows is recorded in this keyspace under [`EMITTED_EPOCH_KEY`].
+    keyspace: Keyspace<S>,
+}
+
+impl<S: StateStore> std::fmt::Debug for ValuesExecutor<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValuesExecutor")
+            .field("schema", &self.info.schema)
+            .field("pk_indices", &self.info.pk_indices)
+            .field("rows", &self.rows.len())
+            .finish()
+    }
+}
+
+impl<S: StateStore> ValuesExecutor<S> {
+    pub fn new(
+        info: ExecutorInfo,
+        rows: Vec<Vec<BoxedExpression>>,
+        barrier_receiver: UnboundedReceiver<Message>,
+        keyspace: Keyspace<S>,
+    ) -> Self {
+        Self {
+            info,
+            rows,
+            barrier_receiver,
+            keyspace,
+        }
+    }
+
+    /// Evaluates all the rows into one chunk of insertions.
+    fn build_chunk(schema: &Schema, rows: Vec<Vec<BoxedExpression>>) -> Result<StreamChunk> {
+        // Constant expressions return as many rows as the input chunk, so a one-row chunk is
+        // needed rather than an empty one.
+        let one_row_array = I32Array::from_slice(&[Some(1)])?;
+        let one_row_chunk = DataChunk::builder()
+            .columns(vec![Column::new(Arc::new(one_row_array.into()))])
+            .build();
+
+        let cardinality = rows.len();
+        let mut array_builders = schema.create_array_builders(cardinality)?;
+        for row in rows {
+            for (expr, builder) in row.into_iter().zip_eq(&mut array_builders) {
+                let out = expr.eval(&one_row_chunk)?;
+                builder.append_array(&out)?;
+            }
+        }
+        let columns = array_builders
+            .into_iter()
+            .map(|builder| builder.finish().map(|arr| Column::new(Arc::new(arr))))
+            .collect::<Result<Vec<Column>>>()?;
+
+        Ok(StreamChunk::new(
+            vec![Op::Insert; cardinality],
+            columns,
+            None,
+        ))
+    }
+
+    /// Records that the rows have been emitted in `epoch`.
+    async fn checkpoint_emitted(keyspace: &Keyspace<S>, epoch: u64) -> StreamExecutorResult<()> {
+        let value = serialize_cell(&Some(ScalarImpl::Int64(epoch as i64)))
+            .map_err(StreamExecutorError::eval_error)?;
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        let mut local = write_batch.prefixify(keyspace);
+        local.put(EMITTED_EPOCH_KEY, StorageValue::new_default_put(value));
+        write_batch.ingest(epoch).await?;
+        Ok(())
+    }
+
+    #[try_stream(ok = Message, error = TracedStreamExecutorError)]
+    async fn execute_inner(self) {
+        let Self {
+            info,
+            rows,
+            mut barrier_receiver,
+            keyspace,
+        } = self;
+
+        let mut chunk = if rows.is_empty() {
+            None
+        } else {
+            Some(Self::build_chunk(&info.schema, rows).map_err(StreamExecutorError::eval_error)?)
+        };
+        let mut is_first_barrier = true;
+        // The epoch the rows are emitted in, to be checkpointed on the next barrier.
+        let mut emitted_epoch = None;
+
+        while let Some(msg) = barrier_receiver.recv().await {
+            let epoch = match &msg {
+                Message::Barrier(barrier) => barrier.epoch,
+                _ => unreachable!("the values executor only receives barriers"),
+            };
+            if let Some(emitted_epoch) = emitted_epoch.take() {
+                Self::checkpoint_emitted(&keyspace, emitted_epoch).await?;
+            }
+            if std::mem::take(&mut is_first_barrier)
+                && keyspace.get(EMITTED_EPOCH_KEY, epoch.prev).await?.is_some()
+            {
+                // The rows have been emitted before recovery.
+                chunk = None;
+            }
+            yield msg;
+            // The rows are emitted exactly once, right after the first barrier.
+            if let Some(chunk) = chunk.take() {
+                emitted_epoch = Some(epoch.curr);
+                yield Message::Chunk(chunk);
+            }
+        }
+
+        return Err(StreamExecutorError::input_error(InternalError(
+            "barrier reader closed unexpectedly".to_string(),
+        )));
+    }
+}
+
+impl<S: StateStore> Executor for ValuesExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use itertools::Itertools;
+    use risingwave_common::array::Op;
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::types::{DataType, ScalarImpl};
+    use risingwave_expr::expr::{Expression, LiteralExpression};
+    use risingwave_storage::memory::MemoryStateStore;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::super::*;
+    use super::*;
+    use crate::executor::test_utils::create_in_memory_keyspace;
+
+    fn literal(v: i64) -> BoxedExpression {
+        LiteralExpression::new(DataType::Int64, Some(ScalarImpl::Int64(v))).boxed()
+    }
+
+    fn values_executor(
+        barrier_receiver: UnboundedReceiver<Message>,
+        keyspace: Keyspace<MemoryStateStore>,
+    ) -> BoxedMessageStream {
+        let info = ExecutorInfo {
+            schema: Schema::new(vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ]),
+            pk_indices: vec![1],
+            identity: "ValuesExecutor".to_string(),
+        };
+        Box::new(ValuesExecutor::new(
+            info,
+            vec![vec![literal(10), literal(0)], vec![literal(20), literal(1)]],
+            barrier_receiver,
+            keyspace,
+        ))
+        .execute()
+    }
+
+    #[tokio::test]
+    async fn test_values() {
+        let (barrier_sender, barrier_receiver) = unbounded_channel();
+        let mut values = values_executor(barrier_receiver, create_in_memory_keyspace());
+
+        for epoch in 1..=2 {
+            barrier_sender
+                .send(Message::Barrier(Barrier::new_test_barrier(epoch)))
+                .unwrap();
+        }
+
+        // The rows follow the first barrier.
+        assert!(values.next().await.unwrap().unwrap().is_barrier());
+        let chunk = values.next().await.unwrap().unwrap().into_chunk().unwrap();
+        assert_eq!(chunk.ops(), vec![Op::Insert, Op::Insert]);
+        assert_eq!(
+            chunk
+                .column_at(0)
+                .array_ref()
+                .as_int64()
+                .iter()
+                .collect_vec(),
+            vec![Some(10), Some(20)]
+        );
+        assert_eq!(
+            chunk
+                .column_at(1)
+                .array_ref()
+                .as_int64()
+                .iter()
+                .collect_vec(),
+            vec![Some(0), Some(1)]
+        );
+
+        // Afterwards only barriers are forwarded.
+        assert!(values.next().await.unwrap().unwrap().is_barrier());
+    }
+
+    #[tokio::test]
+    async fn test_values_recovery() {
+        let keyspace = create_in_memory_keyspace();
+        let (barrier_sender, barrier_receiver) = unbounded_channel();
+        let mut values = values_executor(barrier_receiver, keyspace.clone());
+        for epoch in 1..=2 {
+            barrier_sender
+                .send(Message::Barrier(Barrier::new_test_barrier(epoch)))
+                .unwrap();
+        }
+        assert!(values.next().await.unwrap().unwrap().is_barrier());
+        assert!(values.next().await.unwrap().unwrap().is_chunk());
+        assert!(values.next().await.unwrap().unwrap().is_barrier());
+
+        // The rows are not emitted again after recovery.
+        let (barrier_sender, barrier_receiver) = unbounded_channel();
+        let mut values = values_executor(barrier_receiver, keyspace);
+        for epoch in 2..=3 {
+            barrier_sender
+                .send(Message::Barrier(Barrier::new_test_barrier(epoch)))
+                .unwrap();
+        }
+        assert!(values.next().await.unwrap().unwrap().is_barrier());
+        assert!(values.next().await.unwrap().unwrap().is_barrier());
+    }
+}
@@ -0,0 +1,615 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Formatter};
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use risingwave_common::array::{DataChunk, Op, StreamChunk};
+use risingwave_common::buffer::BitmapBuilder;
+use risingwave_common::catalog::Schema;
+use risingwave_common::types::{DataType, Datum, NaiveDateTimeWrapper, ScalarImpl};
+use risingwave_common::util::value_encoding::{deserialize_cell, serialize_cell};
+use risingwave_expr::expr::BoxedExpression;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::{
+    Barrier, BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, SimpleExecutor,
+    StreamExecutorResult, Watermark,
+};
+use crate::executor::PkIndicesRef;
+use crate::executor_v2::error::{StreamExecutorError, TracedStreamExecutorError};
+
+/// The key of the watermark in the keyspace of the executor.
+const WATERMARK_KEY: &[u8] = b"watermark";
+
+/// `WatermarkFilterExecutor` generates the watermark of a source with `WATERMARK FOR`, see
+/// [`SimpleWatermarkFilterExecutor`]. The watermark is restored on the first barrier, and
+/// checkpointed on each barrier afterwards, so that late rows are still dropped after recovery.
+pub struct WatermarkFilterExecutor<S: StateStore> {
+    input: BoxedExecutor,
+    inner: SimpleWatermarkFilterExecutor,
+
+    /// The watermark is stored in this keyspace under [`WATERMARK_KEY`].
+    keyspace: Keyspace<S>,
+}
+
+impl<S: StateStore> WatermarkFilterExecutor<S> {
+    pub fn new(
+        input: BoxedExecutor,
+        event_time_col_idx: usize,
+        watermark_expr: BoxedExpression,
+        idle_timeout: Option<Duration>,
+        keyspace: Keyspace<S>,
+        executor_id: u64,
+    ) -> Self {
+        let info = input.info();
+        Self {
+            input,
+            inner: SimpleWatermarkFilterExecutor::new(
+                info,
+                event_time_col_idx,
+                watermark_expr,
+                idle_timeout,
+                executor_id,
+            ),
+            keyspace,
+        }
+    }
+
+    #[try_stream(ok = Message, error = TracedStreamExecutorError)]
+    async fn execute_inner(self) {
+        let WatermarkFilterExecutor {
+            input,
+            mut inner,
+            keyspace,
+        } = self;
+        let mut input = input.execute();
+        let barrier = input
+            .next()
+            .await
+            .unwrap()?
+            .into_barrier()
+            .expect("the first message received by watermark filter must be a barrier");
+        inner
+            .restore_watermark(&keyspace, barrier.epoch.prev)
+            .await?;
+        let mut epoch = barrier.epoch.curr;
+        yield Message::Barrier(barrier);
+
+        #[for_await]
+        for msg in input {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    if let Some(chunk) = inner.map_filter_chunk(chunk)? {
+                        yield Message::Chunk(chunk);
+                    }
+                    if let Some(watermark) = inner.take_watermark() {
+                        yield Message::Watermark(watermark);
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    inner.on_barrier(&barrier)?;
+                    if let Some(watermark) = inner.take_watermark() {
+                        yield Message::Watermark(watermark);
+                    }
+                    inner.checkpoint_watermark(&keyspace, epoch).await?;
+                    epoch = barrier.epoch.curr;
+                    yield Message::Barrier(barrier);
+                }
+                Message::Watermark(watermark) => {
+                    if let Some(watermark) = inner.map_watermark(watermark) {
+                        yield Message::Watermark(watermark);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for WatermarkFilterExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        self.inner.schema()
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        self.inner.pk_indices()
+    }
+
+    fn identity(&self) -> &str {
+        self.inner.identity()
+    }
+}
+
+/// `SimpleWatermarkFilterExecutor` generates the watermark of a source with `WATERMARK FOR`. The
+/// watermark is the maximum of `watermark_expr` evaluated on the rows seen so far, and rows whose
+/// event time is behind the watermark are late and dropped, whether they are inserted or deleted.
+/// So the retraction of a row dropped as late is dropped as well, and rows behind the watermark are
+/// never changed downstream. An update with only one half late is passed as an insertion or a
+/// deletion of the other half.
+///
+/// Whenever the watermark advances, it's yielded as a [`Watermark`] on the event time column after
+/// the chunk.
+///
+/// If `idle_timeout` is set and no row arrives for that long, the watermark advances by the
+/// elapsed wall-clock time on the barriers, so that the windows downstream still close while the
+/// source is idle.
+pub struct SimpleWatermarkFilterExecutor {
+    info: ExecutorInfo,
+
+    event_time_col_idx: usize,
+
+    /// Generates the watermark candidate of each row, e.g. `ts - INTERVAL '5' SECOND`.
+    watermark_expr: BoxedExpression,
+
+    /// The current watermark, `None` before any row is seen.
+    watermark: Datum,
+
+    /// Whether the watermark has advanced since it was last yielded.
+    watermark_advanced: bool,
+
+    /// Whether the watermark has advanced since it was last checkpointed.
+    watermark_dirty: bool,
+
+    idle_timeout: Option<Duration>,
+
+    /// When the last row arrived, or the watermark last advanced while the source is idle.
+    last_active: Instant,
+}
+
+impl SimpleWatermarkFilterExecutor {
+    pub fn new(
+        input_info: ExecutorInfo,
+        event_time_col_idx: usize,
+        watermark_expr: BoxedExpression,
+        idle_timeout: Option<Duration>,
+        executor_id: u64,
+    ) -> Self {
+        Self {
+            info: ExecutorInfo {
+                schema: input_info.schema,
+                pk_indices: input_info.pk_indices,
+                identity: format!("WatermarkFilterExecutor {:X}", executor_id),
+            },
+            event_time_col_idx,
+            watermark_expr,
+            watermark: None,
+            watermark_advanced: false,
+            watermark_dirty: false,
+            idle_timeout,
+            last_active: Instant::now(),
+        }
+    }
+
+    /// Advances the watermark by `elapsed`, if it's a timestamp.
+    fn advance_watermark(&mut self, elapsed: Duration) {
+        let advanced = match (self.watermark_expr.return_type(), &self.watermark) {
+            (DataType::Timestamp, Some(ScalarImpl::NaiveDateTime(watermark))) => {
+                chrono::Duration::from_std(elapsed)
+                    .ok()
+                    .and_then(|elapsed| watermark.0.checked_add_signed(elapsed))
+                    .map(|watermark| {
+                        ScalarImpl::NaiveDateTime(NaiveDateTimeWrapper::new(watermark))
+                    })
+            }
+            (DataType::Timestampz, Some(ScalarImpl::Int64(watermark))) => {
+                i64::try_from(elapsed.as_micros())
+                    .ok()
+                    .and_then(|elapsed| watermark.checked_add(elapsed))
+                    .map(ScalarImpl::Int64)
+            }
+            _ => None,
+        };
+        if let Some(watermark) = advanced {
+            self.watermark = Some(watermark);
+            self.watermark_advanced = true;
+            self.watermark_dirty = true;
+        }
+    }
+
+    /// Restores the watermark checkpointed in `epoch`, if any.
+    async fn restore_watermark<S: StateStore>(
+        &mut self,
+        keyspace: &Keyspace<S>,
+        epoch: u64,
+    ) -> StreamExecutorResult<()> {
+        if let Some(raw_data) = keyspace.get(WATERMARK_KEY, epoch).await? {
+            let mut deserializer = value_encoding::Deserializer::new(raw_data);
+            self.watermark =
+                deserialize_cell(&mut deserializer, &self.watermark_expr.return_type())
+                    .map_err(StreamExecutorError::eval_error)?;
+        }
+        Ok(())
+    }
+
+    /// Checkpoints the watermark in `epoch`, if it has advanced since the last checkpoint.
+    async fn checkpoint_watermark<S: StateStore>(
+        &mut self,
+        keyspace: &Keyspace<S>,
+        epoch: u64,
+    ) -> StreamExecutorResult<()> {
+        if !std::mem::take(&mut self.watermark_dirty) {
+            return Ok(());
+        }
+        let value = serialize_cell(&self.watermark).map_err(StreamExecutorError::eval_error)?;
+        let mut write_batch = keyspace.state_store().start_write_batch();
+        let mut local = write_batch.prefixify(keyspace);
+        local.put(WATERMARK_KEY, StorageValue::new_default_put(value));
+        write_batch.ingest(epoch).await?;
+        Ok(())
+    }
+}
+
+impl Debug for SimpleWatermarkFilterExecutor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatermarkFilterExecutor")
+            .field("event_time_col_idx", &self.event_time_col_idx)
+            .field("watermark_expr", &self.watermark_expr)
+            .field("watermark", &self.watermark)
+            .finish()
+    }
+}
+
+impl SimpleExecutor for SimpleWatermarkFilterExecutor {
+    fn map_filter_chunk(
+        &mut self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let chunk = chunk.compact().map_err(StreamExecutorError::eval_error)?;
+        if chunk.cardinality() > 0 {
+            self.last_active = Instant::now();
+        }
+
+        let (mut ops, columns, _visibility) = chunk.into_inner();
+        let data_chunk = DataChunk::builder().columns(columns).build();
+
+        let watermarks = self
+            .watermark_expr
+            .eval(&data_chunk)
+            .map_err(StreamExecutorError::eval_error)?;
+        let event_times = data_chunk.column_at(self.event_time_col_idx).array_ref();
+
+        let mut visible = Vec::with_capacity(ops.len());
+        for idx in 0..ops.len() {
+            let late = matches!(
+                (event_times.datum_at(idx), &self.watermark),
+                (Some(event_time), Some(watermark)) if event_time < *watermark
+            );
+            visible.push(!late);
+
+            if let Some(watermark) = watermarks.datum_at(idx) {
+                if self.watermark.as_ref().map_or(true, |w| watermark > *w) {
+                    self.watermark = Some(watermark);
+                    self.watermark_advanced = true;
+                    self.watermark_dirty = true;
+                }
+            }
+        }
+
+        // An update with one half dropped degenerates into an insertion or a deletion.
+        for idx in 0..ops.len() {
+            if ops[idx] == Op::UpdateDelete {
+                match (visible[idx], visible[idx + 1]) {
+                    (true, false) => ops[idx] = Op::Delete,
+                    (false, true) => ops[idx + 1] = Op::Insert,
+                    _ => {}
+                }
+            }
+        }
+
+        let (columns, _) = data_chunk.into_parts();
+        let mut new_visibility = BitmapBuilder::with_capacity(ops.len());
+        for visible in visible {
+            new_visibility.append(visible);
+        }
+        let new_visibility = new_visibility.finish();
+
+        Ok(if new_visibility.num_high_bits() > 0 {
+            Some(StreamChunk::new(ops, columns, Some(new_visibility)))
+        } else {
+            None
+        })
+    }
+
+    fn on_barrier(&mut self, _barrier: &Barrier) -> StreamExecutorResult<()> {
+        if let Some(idle_timeout) = self.idle_timeout {
+            let elapsed = self.last_active.elapsed();
+            if elapsed >= idle_timeout {
+                self.advance_watermark(elapsed);
+                self.last_active = Instant::now();
+            }
+        }
+        Ok(())
+    }
+
+    fn take_watermark(&mut self) -> Option<Watermark> {
+        if !std::mem::take(&mut self.watermark_advanced) {
+            return None;
+        }
+        self.watermark.clone().map(|val| {
+            Watermark::new(
+                self.event_time_col_idx,
+                self.watermark_expr.return_type(),
+                val,
+            )
+        })
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use futures::StreamExt;
+    use itertools::Itertools;
+    use risingwave_common::array::{I64Array, Op, StreamChunk};
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::{DataType, ScalarImpl};
+    use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
+    use risingwave_expr::expr::{Expression, InputRefExpression, LiteralExpression};
+    use risingwave_pb::expr::expr_node::Type;
+    use risingwave_storage::memory::MemoryStateStore;
+
+    use super::super::test_utils::{create_in_memory_keyspace, MockSource};
+    use super::super::*;
+    use super::*;
+
+    fn watermark_filter(
+        messages: Vec<Message>,
+        keyspace: Keyspace<MemoryStateStore>,
+    ) -> BoxedMessageStream {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let source = MockSource::with_messages(schema, PkIndices::new(), messages);
+
+        // WATERMARK FOR $0 AS $0 - 5
+        let watermark_expr = new_binary_expr(
+            Type::Subtract,
+            DataType::Int64,
+            InputRefExpression::new(DataType::Int64, 0).boxed(),
+            LiteralExpression::new(DataType::Int64, Some(ScalarImpl::Int64(5))).boxed(),
+        );
+        Box::new(WatermarkFilterExecutor::new(
+            Box::new(source),
+            0,
+            watermark_expr,
+            None,
+            keyspace,
+            1,
+        ))
+        .execute()
+    }
+
+    async fn next_visibility(watermark_filter: &mut BoxedMessageStream) -> Vec<bool> {
+        let chunk = watermark_filter
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_chunk()
+            .unwrap();
+        chunk.visibility().as_ref().unwrap().iter().collect_vec()
+    }
+
+    async fn next_watermark(watermark_filter: &mut BoxedMessageStream) -> Watermark {
+        watermark_filter
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_watermark()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_watermark_filter() {
+        let chunk1 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::Insert],
+            vec![
+                column_nonnull! { I64Array, [10, 3, 7] },
+                column_nonnull! { I64Array, [1, 2, 3] },
+            ],
+            None,
+        );
+        let chunk2 = StreamChunk::new(
+            vec![Op::Insert, Op::Insert, Op::Delete, Op::Delete],
+            vec![
+                column_nonnull! { I64Array, [4, 20, 3, 7] },
+                column_nonnull! { I64Array, [4, 5, 2, 3] },
+            ],
+            None,
+        );
+        let chunk3 = StreamChunk::new(
+            vec![
+                Op::UpdateDelete,
+                Op::UpdateInsert,
+                Op::UpdateDelete,
+                Op::UpdateInsert,
+            ],
+            vec![
+                column_nonnull! { I64Array, [10, 12, 14, 21] },
+                column_nonnull! { I64Array, [1, 1, 5, 5] },
+            ],
+            None,
+        );
+        let mut watermark_filter = watermark_filter(
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(chunk1),
+                Message::Chunk(chunk2),
+                Message::Chunk(chunk3),
+            ],
+            create_in_memory_keyspace(),
+        );
+        assert!(watermark_filter.next().await.unwrap().unwrap().is_barrier());
+
+        // The watermark is 5 after the 1st row, so 3 is late.
+        assert_eq!(
+            next_visibility(&mut watermark_filter).await,
+            vec![true, false, true]
+        );
+        assert_eq!(
+            next_watermark(&mut watermark_filter).await,
+            Watermark::new(0, DataType::Int64, ScalarImpl::Int64(5))
+        );
+
+        // 4 is late, and so is the deletion of 3, which has been dropped on insertion. The
+        // deletion of 7 is late as well, since it's behind the watermark 15.
+        assert_eq!(
+            next_visibility(&mut watermark_filter).await,
+            vec![false, true, false, false]
+        );
+        assert_eq!(
+            next_watermark(&mut watermark_filter).await,
+            Watermark::new(0, DataType::Int64, ScalarImpl::Int64(15))
+        );
+
+        // The update from 10 to 12 is late as a whole. The update from 14 to 21 only has its
+        // insertion passed, as 14 is behind the watermark 15.
+        let chunk = watermark_filter
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_chunk()
+            .unwrap();
+        assert_eq!(
+            chunk.visibility().as_ref().unwrap().iter().collect_vec(),
+            vec![false, false, false, true]
+        );
+        assert_eq!(chunk.ops()[3], Op::Insert);
+        assert_eq!(
+            next_watermark(&mut watermark_filter).await,
+            Watermark::new(0, DataType::Int64, ScalarImpl::Int64(16))
+        );
+
+        assert!(watermark_filter.next().await.unwrap().unwrap().is_stop());
+    }
+
+    #[tokio::test]
+    async fn test_watermark_filter_recovery() {
+        let keyspace = create_in_memory_keyspace();
+        let chunk = |event_time| {
+            StreamChunk::new(
+                vec![Op::Insert],
+                vec![
+                    column_nonnull! { I64Array, [event_time] },
+                    column_nonnull! { I64Array, [1] },
+                ],
+                None,
+            )
+        };
+
+        let mut watermark_filter = watermark_filter(
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(chunk(10)),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+            ],
+            keyspace.clone(),
+        );
+        assert!(watermark_filter.next().await.unwrap().unwrap().is_barrier());
+        assert_eq!(next_visibility(&mut watermark_filter).await, vec![true]);
+        assert_eq!(
+            next_watermark(&mut watermark_filter).await,
+            Watermark::new(0, DataType::Int64, ScalarImpl::Int64(5))
+        );
+        assert!(watermark_filter.next().await.unwrap().unwrap().is_barrier());
+
+        // After recovery, rows behind the checkpointed watermark are still late.
+        let mut watermark_filter = watermark_filter(
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Chunk(chunk(3)),
+            ],
+            keyspace,
+        );
+        assert!(watermark_filter.next().await.unwrap().unwrap().is_barrier());
+        assert!(watermark_filter.next().await.unwrap().unwrap().is_stop());
+    }
+
+    #[test]
+    fn test_watermark_filter_idle_timeout() {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Timestampz)],
+        };
+        let info = ExecutorInfo {
+            schema,
+            pk_indices: PkIndices::new(),
+            identity: "MockSource".to_string(),
+        };
+        let mut watermark_filter = SimpleWatermarkFilterExecutor::new(
+            info,
+            0,
+            InputRefExpression::new(DataType::Timestampz, 0).boxed(),
+            Some(Duration::from_millis(100)),
+            1,
+        );
+
+        // Nothing to advance before any row is seen.
+        std::thread::sleep(Duration::from_millis(200));
+        watermark_filter.on_barrier(&Barrier::default()).unwrap();
+        assert!(watermark_filter.take_watermark().is_none());
+
+        let chunk = StreamChunk::new(
+            vec![Op::Insert],
+            vec![column_nonnull! { I64Array, [1_000_000] }],
+            None,
+        );
+        watermark_filter.map_filter_chunk(chunk).unwrap();
+        assert_eq!(
+            watermark_filter.take_watermark(),
+            Some(Watermark::new(
+                0,
+                DataType::Timestampz,
+                ScalarImpl::Int64(1_000_000)
+            ))
+        );
+
+        // The source is not idle yet.
+        watermark_filter.on_barrier(&Barrier::default()).unwrap();
+        assert!(watermark_filter.take_watermark().is_none());
+
+        // The watermark advances by the time elapsed since the last row.
+        std::thread::sleep(Duration::from_millis(200));
+        watermark_filter.on_barrier(&Barrier::default()).unwrap();
+        let watermark = watermark_filter.take_watermark().unwrap();
+        assert_matches!(
+            watermark.val,
+            ScalarImpl::Int64(val) if val >= 1_200_000
+        );
+    }
+}
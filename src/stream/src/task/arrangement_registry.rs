@@ -0,0 +1,202 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use risingwave_common::catalog::{ColumnDesc, TableId};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_storage::StateStore;
+
+use super::ActorId;
+
+pub type ArrangementRegistryRef = Arc<ArrangementRegistry>;
+
+/// Describes how a materialized view is arranged in the state store, so that it can be read as a
+/// [`risingwave_storage::table::cell_based_table::CellBasedTable`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrangementDesc {
+    /// All columns of the materialized view.
+    pub column_descs: Vec<ColumnDesc>,
+
+    /// Order types of the arrange key.
+    pub order_types: Vec<OrderType>,
+}
+
+#[derive(Debug)]
+struct ArrangementEntry {
+    desc: Arc<ArrangementDesc>,
+
+    /// The latest committed epoch flushed by each local actor materializing the arrangement, or
+    /// `None` if no epoch flushed by the actor is committed yet.
+    epochs: HashMap<ActorId, Option<u64>>,
+}
+
+/// `ArrangementRegistry` keeps track of the arrangements of materialized views owned by the local
+/// actors, so that they can be served to external point lookups without batch planning.
+#[derive(Debug, Default)]
+pub struct ArrangementRegistry {
+    arrangements: Mutex<HashMap<TableId, ArrangementEntry>>,
+}
+
+impl ArrangementRegistry {
+    /// Registers the arrangement of `table_id` materialized by `actor_id`. The actor should report
+    /// its flushed epochs with the returned handle, and the registration is removed once the handle
+    /// is dropped.
+    pub fn register(
+        self: &Arc<Self>,
+        table_id: TableId,
+        actor_id: ActorId,
+        desc: ArrangementDesc,
+    ) -> ArrangementHandle {
+        let mut arrangements = self.arrangements.lock();
+        let entry = arrangements
+            .entry(table_id)
+            .or_insert_with(|| ArrangementEntry {
+                desc: Arc::new(desc),
+                epochs: HashMap::new(),
+            });
+        entry.epochs.insert(actor_id, None);
+
+        ArrangementHandle {
+            registry: self.clone(),
+            table_id,
+            actor_id,
+        }
+    }
+
+    /// Returns the arrangement of `table_id` and the epoch to read it at, i.e., the minimum
+    /// committed epoch flushed by all the local actors of it. Returns `None` if the arrangement is
+    /// not owned by this node, or no epoch flushed by some actor is committed yet.
+    pub fn get(&self, table_id: &TableId) -> Option<(Arc<ArrangementDesc>, u64)> {
+        let arrangements = self.arrangements.lock();
+        let entry = arrangements.get(table_id)?;
+        let epoch = entry
+            .epochs
+            .values()
+            .copied()
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .min()?;
+        Some((entry.desc.clone(), epoch))
+    }
+
+    fn update_epoch(&self, table_id: &TableId, actor_id: ActorId, epoch: u64) {
+        let mut arrangements = self.arrangements.lock();
+        if let Some(entry) = arrangements.get_mut(table_id) {
+            // The actor may be deregistered before its epochs are committed, and the commits may
+            // be reported out of order.
+            if let Some(committed) = entry.epochs.get_mut(&actor_id) {
+                *committed = (*committed).max(Some(epoch));
+            }
+        }
+    }
+
+    fn deregister(&self, table_id: &TableId, actor_id: ActorId) {
+        let mut arrangements = self.arrangements.lock();
+        if let Some(entry) = arrangements.get_mut(table_id) {
+            entry.epochs.remove(&actor_id);
+            if entry.epochs.is_empty() {
+                arrangements.remove(table_id);
+            }
+        }
+    }
+}
+
+/// The registration of an arrangement by an actor, removed from the registry on drop.
+#[derive(Debug)]
+pub struct ArrangementHandle {
+    registry: ArrangementRegistryRef,
+    table_id: TableId,
+    actor_id: ActorId,
+}
+
+impl ArrangementHandle {
+    /// Reports that the actor has flushed all the changes up to `epoch` to `store`. The arrangement
+    /// is served at `epoch` only once it's committed, since the changes of an uncommitted epoch may
+    /// be rolled back by recovery. The epoch is committed after the barrier is collected from all
+    /// actors, so it's waited for in the background.
+    pub fn flush_epoch(&self, store: impl StateStore, epoch: u64) {
+        let registry = self.registry.clone();
+        let table_id = self.table_id;
+        let actor_id = self.actor_id;
+        tokio::spawn(async move {
+            match store.wait_epoch(epoch).await {
+                Ok(()) => registry.update_epoch(&table_id, actor_id, epoch),
+                Err(e) => tracing::warn!(
+                    "failed to wait for epoch {} of arrangement {}: {}",
+                    epoch,
+                    table_id,
+                    e
+                ),
+            }
+        });
+    }
+
+    /// Reports that all the changes of the actor up to `epoch` are committed.
+    pub fn update_epoch(&self, epoch: u64) {
+        self.registry
+            .update_epoch(&self.table_id, self.actor_id, epoch);
+    }
+}
+
+impl Drop for ArrangementHandle {
+    fn drop(&mut self) {
+        self.registry.deregister(&self.table_id, self.actor_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::types::DataType;
+
+    use super::*;
+
+    #[test]
+    fn test_arrangement_registry() {
+        let registry = Arc::new(ArrangementRegistry::default());
+        let table_id = TableId::new(1);
+        let desc = ArrangementDesc {
+            column_descs: vec![ColumnDesc::unnamed(0.into(), DataType::Int32)],
+            order_types: vec![OrderType::Ascending],
+        };
+
+        let handle1 = registry.register(table_id, 1, desc.clone());
+        let handle2 = registry.register(table_id, 2, desc.clone());
+        // Not readable until all actors have flushed.
+        assert!(registry.get(&table_id).is_none());
+
+        handle1.update_epoch(3);
+        assert!(registry.get(&table_id).is_none());
+        handle2.update_epoch(2);
+        let (got_desc, epoch) = registry.get(&table_id).unwrap();
+        assert_eq!(*got_desc, desc);
+        assert_eq!(epoch, 2);
+        // A commit reported late doesn't move the epoch back.
+        handle1.update_epoch(1);
+        assert_eq!(registry.get(&table_id).unwrap().1, 2);
+
+        drop(handle2);
+        assert_eq!(registry.get(&table_id).unwrap().1, 3);
+        drop(handle1);
+        assert!(registry.get(&table_id).is_none());
+
+        // A commit reported after the actor is deregistered is ignored.
+        let handle3 = registry.register(table_id, 3, desc);
+        registry.update_epoch(&table_id, 1, 4);
+        assert!(registry.get(&table_id).is_none());
+        drop(handle3);
+    }
+}
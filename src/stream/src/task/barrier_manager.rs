@@ -14,9 +14,11 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use risingwave_common::error::Result;
 use risingwave_pb::stream_service::inject_barrier_response::FinishedCreateMview as ProstFinishedCreateMview;
+use risingwave_storage::monitor::OwnerWriteBytes;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
 
@@ -32,6 +34,9 @@ mod tests;
 /// Note that this option will significantly increase the overhead of tracing.
 pub const ENABLE_BARRIER_AGGREGATION: bool = false;
 
+/// Number of the slowest actors reported to the meta service on each barrier collection.
+pub const SLOWEST_ACTORS_TO_REPORT: usize = 3;
+
 /// Represents the Create MV DDL with `epoch` is finished on the actor with `actor_id`.
 #[derive(Debug)]
 pub struct FinishedCreateMview {
@@ -79,6 +84,13 @@ impl std::fmt::Debug for FinishCreateMviewNotifier {
 pub struct CollectResult {
     /// Finished Create MV DDLs in current epoch.
     pub finished_create_mviews: Vec<FinishedCreateMview>,
+
+    /// The slowest actors to collect the barrier since it's issued, slowest first.
+    pub slowest_actors: Vec<(ActorId, Duration)>,
+
+    /// Bytes written to the state store by each table and executor in the epoch, filled after the
+    /// epoch is synced.
+    pub write_bytes: OwnerWriteBytes,
 }
 
 enum BarrierState {
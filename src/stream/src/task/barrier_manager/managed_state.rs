@@ -14,10 +14,11 @@
 
 use std::collections::HashSet;
 use std::iter::once;
+use std::time::{Duration, Instant};
 
 use tokio::sync::oneshot;
 
-use super::{CollectResult, FinishedCreateMview};
+use super::{CollectResult, FinishedCreateMview, SLOWEST_ACTORS_TO_REPORT};
 use crate::executor::Barrier;
 use crate::task::ActorId;
 
@@ -47,6 +48,12 @@ enum ManagedBarrierStateInner {
         /// Actor ids remaining to be collected.
         remaining_actors: HashSet<ActorId>,
 
+        /// When the `send_barrier` request is issued.
+        issued_at: Instant,
+
+        /// Time taken by each actor collected after issuing to collect the barrier.
+        collect_times: Vec<(ActorId, Duration)>,
+
         /// Notify that the collection is finished.
         collect_notifier: oneshot::Sender<CollectResult>,
     },
@@ -98,11 +105,18 @@ impl ManagedBarrierState {
 
             match state {
                 ManagedBarrierStateInner::Issued {
-                    collect_notifier, ..
+                    collect_notifier,
+                    mut collect_times,
+                    ..
                 } => {
+                    collect_times.sort_by(|a, b| b.1.cmp(&a.1));
+                    collect_times.truncate(SLOWEST_ACTORS_TO_REPORT);
+
                     // Notify about barrier finishing.
                     let result = CollectResult {
                         finished_create_mviews,
+                        slowest_actors: collect_times,
+                        write_bytes: Default::default(),
                     };
                     if collect_notifier.send(result).is_err() {
                         warn!("failed to notify barrier collection with epoch {}", epoch)
@@ -149,12 +163,15 @@ impl ManagedBarrierState {
             ManagedBarrierStateInner::Issued {
                 epoch,
                 remaining_actors,
+                issued_at,
+                collect_times,
                 ..
             } => {
                 assert_eq!(barrier.epoch.curr, *epoch);
 
                 let exist = remaining_actors.remove(&actor_id);
                 assert!(exist);
+                collect_times.push((actor_id, issued_at.elapsed()));
                 self.may_notify();
             }
         }
@@ -175,6 +192,8 @@ impl ManagedBarrierState {
                 *self.inner_mut() = ManagedBarrierStateInner::Issued {
                     epoch: barrier.epoch.curr,
                     remaining_actors,
+                    issued_at: Instant::now(),
+                    collect_times: vec![],
                     collect_notifier,
                 };
                 self.may_notify();
@@ -194,6 +213,8 @@ impl ManagedBarrierState {
                 *self.inner_mut() = ManagedBarrierStateInner::Issued {
                     epoch: barrier.epoch.curr,
                     remaining_actors,
+                    issued_at: Instant::now(),
+                    collect_times: vec![],
                     collect_notifier,
                 };
                 self.may_notify();
@@ -16,9 +16,15 @@ use std::sync::Arc;
 
 use risingwave_common::config::StreamingConfig;
 use risingwave_common::util::addr::HostAddr;
+use risingwave_common::util::memory_budget::MemoryBudgetRef;
 use risingwave_source::{SourceManager, SourceManagerRef};
 use risingwave_storage::StateStoreImpl;
 
+use super::{
+    ArrangementRegistry, ArrangementRegistryRef, EventReporter, StreamMemoryManager,
+    StreamMemoryManagerRef,
+};
+
 pub(crate) type WorkerNodeId = u32;
 
 /// The global environment for task execution.
@@ -39,6 +45,18 @@ pub struct StreamEnvironment {
 
     /// State store for table scanning.
     state_store: StateStoreImpl,
+
+    /// Memory budget of executor caches, if the memory governor is enabled.
+    cache_budget: Option<MemoryBudgetRef>,
+
+    /// Arrangements of materialized views owned by the local actors.
+    arrangement_registry: ArrangementRegistryRef,
+
+    /// Tracks the sizes of executor caches and decides their eviction under memory pressure.
+    memory_manager: StreamMemoryManagerRef,
+
+    /// Reporter of stream errors to the event log of meta.
+    event_reporter: EventReporter,
 }
 
 impl StreamEnvironment {
@@ -48,13 +66,20 @@ impl StreamEnvironment {
         config: Arc<StreamingConfig>,
         worker_id: WorkerNodeId,
         state_store: StateStoreImpl,
+        cache_budget: Option<MemoryBudgetRef>,
+        event_reporter: EventReporter,
     ) -> Self {
+        let memory_manager = Arc::new(StreamMemoryManager::new(cache_budget.clone()));
         StreamEnvironment {
             server_addr,
             source_manager,
             config,
             worker_id,
             state_store,
+            cache_budget,
+            arrangement_registry: Arc::new(ArrangementRegistry::default()),
+            memory_manager,
+            event_reporter,
         }
     }
 
@@ -72,6 +97,10 @@ impl StreamEnvironment {
             state_store: StateStoreImpl::shared_in_memory_store(Arc::new(
                 StateStoreMetrics::unused(),
             )),
+            cache_budget: None,
+            arrangement_registry: Arc::new(ArrangementRegistry::default()),
+            memory_manager: Arc::new(StreamMemoryManager::new(None)),
+            event_reporter: EventReporter::disabled("127.0.0.1:5688".parse().unwrap()),
         }
     }
 
@@ -98,4 +127,20 @@ impl StreamEnvironment {
     pub fn state_store(&self) -> StateStoreImpl {
         self.state_store.clone()
     }
+
+    pub fn cache_budget(&self) -> Option<MemoryBudgetRef> {
+        self.cache_budget.clone()
+    }
+
+    pub fn arrangement_registry(&self) -> ArrangementRegistryRef {
+        self.arrangement_registry.clone()
+    }
+
+    pub fn memory_manager(&self) -> StreamMemoryManagerRef {
+        self.memory_manager.clone()
+    }
+
+    pub fn event_reporter(&self) -> &EventReporter {
+        &self.event_reporter
+    }
 }
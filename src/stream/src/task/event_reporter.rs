@@ -0,0 +1,85 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::meta::event::EventType;
+use risingwave_rpc_client::MetaClient;
+
+/// The longest time to wait for an event report in [`EventReporter::report_and_wait`].
+const EVENT_REPORT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// [`EventReporter`] forwards stream errors, e.g. actor failures and source parse failures, to the
+/// event log of meta, which exposes them as `rw_catalog.rw_events`. Failing to report is only
+/// logged.
+#[derive(Clone)]
+pub struct EventReporter {
+    meta_client: Option<MetaClient>,
+    addr: HostAddr,
+}
+
+impl Debug for EventReporter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventReporter")
+            .field("enabled", &self.meta_client.is_some())
+            .field("addr", &self.addr)
+            .finish()
+    }
+}
+
+impl EventReporter {
+    pub fn new(meta_client: Option<MetaClient>, addr: HostAddr) -> Self {
+        Self { meta_client, addr }
+    }
+
+    /// A reporter that drops all events, used when there's no meta service, e.g. in tests.
+    pub fn disabled(addr: HostAddr) -> Self {
+        Self::new(None, addr)
+    }
+
+    /// Reports the event in the background.
+    pub fn report(&self, event_type: EventType, object_id: u32, info: String) {
+        if self.meta_client.is_none() {
+            return;
+        }
+        let reporter = self.clone();
+        tokio::spawn(async move { reporter.report_event(event_type, object_id, info).await });
+    }
+
+    /// Reports the event and waits for it for at most [`EVENT_REPORT_TIMEOUT`]. Used before the
+    /// task is torn down, e.g. by a panic, which may cancel a report left in the background.
+    pub async fn report_and_wait(&self, event_type: EventType, object_id: u32, info: String) {
+        let report = self.report_event(event_type, object_id, info);
+        if tokio::time::timeout(EVENT_REPORT_TIMEOUT, report)
+            .await
+            .is_err()
+        {
+            tracing::warn!("timed out reporting {:?} event to meta", event_type);
+        }
+    }
+
+    async fn report_event(&self, event_type: EventType, object_id: u32, info: String) {
+        let meta_client = match &self.meta_client {
+            Some(meta_client) => meta_client,
+            None => return,
+        };
+        if let Err(e) = meta_client
+            .report_event(event_type, object_id, &self.addr, info)
+            .await
+        {
+            tracing::warn!("failed to report {:?} event to meta: {}", event_type, e);
+        }
+    }
+}
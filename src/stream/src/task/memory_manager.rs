@@ -0,0 +1,212 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use risingwave_common::util::memory_budget::MemoryBudgetRef;
+
+pub type StreamMemoryManagerRef = Arc<StreamMemoryManager>;
+
+/// Caches start to be evicted once their total size exceeds this fraction of the limit.
+const HIGH_WATERMARK: f64 = 0.9;
+
+/// Caches are evicted until their total size falls to this fraction of the limit.
+const LOW_WATERMARK: f64 = 0.7;
+
+#[derive(Debug)]
+struct CacheEntry {
+    /// Identity of the executor owning the cache.
+    identity: String,
+
+    /// Estimated size of the cache (bytes).
+    bytes: usize,
+}
+
+#[derive(Debug, Default)]
+struct Caches {
+    entries: HashMap<u64, CacheEntry>,
+
+    /// Sum of the sizes of all entries.
+    total_bytes: usize,
+
+    next_id: u64,
+}
+
+/// `StreamMemoryManager` tracks the estimated sizes of the executor caches on this node, e.g. of
+/// hash aggregations, hash joins and top-n, and decides how much each of them should evict when
+/// their total size approaches the memory limit. The limit is the `stream_cache` budget given by
+/// the memory governor of the node, so it follows the resizes of the governor.
+///
+/// Eviction is watermark-based: once the total size exceeds the high watermark, every cache is
+/// asked to shrink by the same ratio, so that the total falls to the low watermark. Each cache
+/// evicts its least recently used entries instead of clearing itself.
+#[derive(Debug)]
+pub struct StreamMemoryManager {
+    /// Memory budget of all caches. `None` means no limit, when the memory governor is disabled.
+    budget: Option<MemoryBudgetRef>,
+
+    caches: Mutex<Caches>,
+}
+
+impl StreamMemoryManager {
+    pub fn new(budget: Option<MemoryBudgetRef>) -> Self {
+        Self {
+            budget,
+            caches: Mutex::new(Caches::default()),
+        }
+    }
+
+    /// Registers the cache of an executor. The executor should report the size of the cache with
+    /// the returned handle, and the cache is deregistered once the handle is dropped.
+    pub fn register(self: &Arc<Self>, identity: impl Into<String>) -> CacheMemoryHandle {
+        let mut caches = self.caches.lock();
+        let id = caches.next_id;
+        caches.next_id += 1;
+        caches.entries.insert(
+            id,
+            CacheEntry {
+                identity: identity.into(),
+                bytes: 0,
+            },
+        );
+
+        CacheMemoryHandle {
+            manager: self.clone(),
+            id,
+        }
+    }
+
+    /// Returns the total estimated size of all caches.
+    pub fn total_bytes(&self) -> usize {
+        self.caches.lock().total_bytes
+    }
+
+    /// Returns the identity and the estimated size of each cache, the largest first.
+    pub fn cache_sizes(&self) -> Vec<(String, usize)> {
+        let caches = self.caches.lock();
+        let mut sizes = caches
+            .entries
+            .values()
+            .map(|entry| (entry.identity.clone(), entry.bytes))
+            .collect::<Vec<_>>();
+        sizes.sort_by(|a, b| b.1.cmp(&a.1));
+        sizes
+    }
+
+    fn set_bytes(&self, id: u64, bytes: usize) {
+        let mut caches = self.caches.lock();
+        let caches = &mut *caches;
+        if let Some(entry) = caches.entries.get_mut(&id) {
+            caches.total_bytes = caches.total_bytes - entry.bytes + bytes;
+            entry.bytes = bytes;
+        }
+    }
+
+    fn eviction_target(&self, id: u64) -> Option<usize> {
+        let limit = self.budget.as_ref()?.bytes();
+        let caches = self.caches.lock();
+        let bytes = caches.entries.get(&id)?.bytes;
+        if (caches.total_bytes as f64) <= limit as f64 * HIGH_WATERMARK {
+            return None;
+        }
+        let ratio = limit as f64 * LOW_WATERMARK / caches.total_bytes as f64;
+        Some((bytes as f64 * ratio) as usize)
+    }
+
+    fn deregister(&self, id: u64) {
+        let mut caches = self.caches.lock();
+        if let Some(entry) = caches.entries.remove(&id) {
+            caches.total_bytes -= entry.bytes;
+        }
+    }
+}
+
+/// The registration of an executor cache, removed from the manager on drop.
+#[derive(Debug)]
+pub struct CacheMemoryHandle {
+    manager: StreamMemoryManagerRef,
+    id: u64,
+}
+
+impl CacheMemoryHandle {
+    /// Reports the current estimated size of the cache. It should be reported again after the
+    /// cache is evicted.
+    pub fn set_bytes(&self, bytes: usize) {
+        self.manager.set_bytes(self.id, bytes);
+    }
+
+    /// Returns the size that the cache should be evicted down to, or `None` if the caches on this
+    /// node are below the high watermark and no eviction is needed.
+    pub fn eviction_target(&self) -> Option<usize> {
+        self.manager.eviction_target(self.id)
+    }
+}
+
+impl Drop for CacheMemoryHandle {
+    fn drop(&mut self) {
+        self.manager.deregister(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::util::memory_budget::MemoryBudget;
+
+    use super::*;
+
+    #[test]
+    fn test_memory_manager() {
+        let budget = Arc::new(MemoryBudget::new("stream_cache", 1000));
+        let manager = Arc::new(StreamMemoryManager::new(Some(budget.clone())));
+        let handle1 = manager.register("executor 1");
+        let handle2 = manager.register("executor 2");
+
+        handle1.set_bytes(300);
+        handle2.set_bytes(500);
+        assert_eq!(manager.total_bytes(), 800);
+        // Below the high watermark.
+        assert_eq!(handle1.eviction_target(), None);
+
+        handle2.set_bytes(900);
+        assert_eq!(
+            manager.cache_sizes(),
+            vec![
+                ("executor 2".to_string(), 900),
+                ("executor 1".to_string(), 300)
+            ]
+        );
+        // Both shrink by the same ratio to the low watermark, i.e. 700 bytes in total.
+        assert_eq!(handle1.eviction_target(), Some(175));
+        assert_eq!(handle2.eviction_target(), Some(525));
+
+        handle2.set_bytes(525);
+        drop(handle1);
+        assert_eq!(manager.total_bytes(), 525);
+        assert_eq!(handle2.eviction_target(), None);
+
+        // The limit follows the budget resized by the memory governor.
+        budget.set_bytes(500);
+        assert_eq!(handle2.eviction_target(), Some(350));
+    }
+
+    #[test]
+    fn test_memory_manager_no_limit() {
+        let manager = Arc::new(StreamMemoryManager::new(None));
+        let handle = manager.register("executor");
+        handle.set_bytes(usize::MAX / 2);
+        assert_eq!(handle.eviction_target(), None);
+    }
+}
@@ -22,14 +22,20 @@ use risingwave_common::util::addr::HostAddr;
 
 use crate::executor::Message;
 
+mod arrangement_registry;
 mod barrier_manager;
 mod compute_client_pool;
 mod env;
+mod event_reporter;
+mod memory_manager;
 mod stream_manager;
 
+pub use arrangement_registry::*;
 pub use barrier_manager::*;
 pub use compute_client_pool::*;
 pub use env::*;
+pub use event_reporter::*;
+pub use memory_manager::*;
 pub use stream_manager::*;
 
 /// Default capacity of channel if two actors are on the same node
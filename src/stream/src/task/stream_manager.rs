@@ -12,22 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::any::Any;
 use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::Debug;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
 
 use futures::channel::mpsc::{channel, Receiver};
+use futures::FutureExt;
 use itertools::Itertools;
 use parking_lot::Mutex;
 use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::config::StreamingConfig;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::try_match_expand;
 use risingwave_common::types::DataType;
 use risingwave_common::util::addr::{is_local_address, HostAddr};
 use risingwave_common::util::env_var::env_var_is_true;
+use risingwave_common::util::sort_util::{OrderPair, OrderType};
 use risingwave_expr::expr::AggKind;
 use risingwave_pb::common::ActorInfo;
+use risingwave_pb::meta::event::EventType;
 use risingwave_pb::stream_plan::stream_node::Node;
 use risingwave_pb::{expr, stream_plan, stream_service};
 use risingwave_storage::{dispatch_state_store, StateStore, StateStoreImpl};
@@ -39,7 +45,10 @@ use crate::executor::*;
 use crate::executor_v2::aggregation::{AggArgs, AggCall};
 use crate::executor_v2::merge::RemoteInput;
 use crate::executor_v2::receiver::ReceiverExecutor;
-use crate::executor_v2::{Executor as ExecutorV2, MergeExecutor as MergeExecutorV2};
+use crate::executor_v2::{
+    Executor as ExecutorV2, ExecutorV1AsV2, FusedExecutor as FusedExecutorV2,
+    MergeExecutor as MergeExecutorV2,
+};
 use crate::task::{
     ActorId, ConsumableChannelPair, SharedContext, StreamEnvironment, UpDownActorIds,
     LOCAL_OUTPUT_CHANNEL_SIZE,
@@ -175,7 +184,7 @@ impl LocalStreamManager {
         let rx = self.send_barrier(barrier, actor_ids_to_send, actor_ids_to_collect)?;
 
         // Wait for all actors finishing this barrier.
-        let collect_result = rx.await.unwrap();
+        let mut collect_result = rx.await.unwrap();
 
         // Sync states from shared buffer to S3 before telling meta service we've done.
         dispatch_state_store!(self.state_store(), store, {
@@ -188,6 +197,7 @@ impl LocalStreamManager {
                     barrier, e
                 ),
             }
+            collect_result.write_bytes = store.take_synced_write_bytes();
         });
 
         Ok(collect_result)
@@ -230,6 +240,7 @@ impl LocalStreamManager {
             epoch,
             mutation: Some(Arc::new(Mutation::Stop(actor_ids_to_collect.clone()))),
             span: tracing::Span::none(),
+            checkpoint: false,
         };
 
         self.send_and_collect_barrier(&barrier, actor_ids_to_send, actor_ids_to_collect)
@@ -314,6 +325,16 @@ pub fn build_agg_call_from_prost(agg_call_proto: &expr::AggCall) -> Result<AggCa
                 DataType::from(arg.get_type()?),
                 arg.get_input()?.column_idx as usize,
             ),
+            [arg1, arg2] => AggArgs::Binary(
+                [
+                    DataType::from(arg1.get_type()?),
+                    DataType::from(arg2.get_type()?),
+                ],
+                [
+                    arg1.get_input()?.column_idx as usize,
+                    arg2.get_input()?.column_idx as usize,
+                ],
+            ),
             _ => {
                 return Err(RwError::from(ErrorCode::NotImplemented(
                     "multiple aggregation args".to_string(),
@@ -322,13 +343,63 @@ pub fn build_agg_call_from_prost(agg_call_proto: &expr::AggCall) -> Result<AggCa
             }
         }
     };
+    let order_pairs = agg_call_proto
+        .get_order_by_fields()
+        .iter()
+        .map(|field| {
+            let order_type = if field.descending {
+                OrderType::Descending
+            } else {
+                OrderType::Ascending
+            };
+            Ok(OrderPair::new(
+                field.get_input()?.column_idx as usize,
+                order_type,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
     Ok(AggCall {
         kind: AggKind::try_from(agg_call_proto.get_type()?)?,
         args,
         return_type: DataType::from(agg_call_proto.get_return_type()?),
+        order_pairs,
+        distinct: agg_call_proto.distinct,
     })
 }
 
+/// Returns the chain of adjacent projects and filters starting from `node`, from the top down.
+/// They are stateless and handle chunks one by one, so they can run in one executor. Projects with
+/// stateful expressions checkpoint their states, so they're not fused.
+fn fusible_chain(node: &stream_plan::StreamNode) -> Vec<&stream_plan::StreamNode> {
+    let is_fusible = |node: &stream_plan::StreamNode| {
+        let fusible = match &node.node {
+            Some(Node::ProjectNode(project)) => !project.stateful,
+            Some(Node::FilterNode(_)) => true,
+            _ => false,
+        };
+        fusible && node.input.len() == 1
+    };
+
+    let mut chain = vec![];
+    let mut node = node;
+    while is_fusible(node) {
+        chain.push(node);
+        node = &node.input[0];
+    }
+    chain
+}
+
+/// Returns the message of a panic, which is a string if raised by `panic!`.
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
 fn update_upstreams(context: &SharedContext, ids: &[UpDownActorIds]) {
     ids.iter()
         .map(|id| {
@@ -394,6 +465,7 @@ impl LocalStreamManagerCore {
         input: Box<dyn Executor>,
         dispatcher: &stream_plan::Dispatcher,
         actor_id: ActorId,
+        config: &StreamingConfig,
     ) -> Result<Box<dyn StreamConsumer>> {
         // create downstream receivers
         let outputs = dispatcher
@@ -423,14 +495,18 @@ impl LocalStreamManagerCore {
                         ))
                     })?
                     .hash_mapping;
+                let data_types = input.schema().data_types();
                 Box::new(DispatchExecutor::new(
                     input,
-                    DispatcherImpl::Hash(HashDataDispatcher::new(
-                        dispatcher.downstream_actor_id.to_vec(),
-                        outputs,
-                        column_indices,
-                        hash_mapping,
-                    )),
+                    DispatcherImpl::Hash(
+                        HashDataDispatcher::new(
+                            dispatcher.downstream_actor_id.to_vec(),
+                            outputs,
+                            column_indices,
+                            hash_mapping,
+                        )
+                        .with_batching(config.dispatch_chunk_size, data_types),
+                    ),
                     actor_id,
                     self.context.clone(),
                 ))
@@ -441,7 +517,7 @@ impl LocalStreamManagerCore {
                 actor_id,
                 self.context.clone(),
             )),
-            Simple | NoShuffle => {
+            Simple => {
                 assert_eq!(outputs.len(), 1);
                 let output = outputs.into_iter().next().unwrap();
                 Box::new(DispatchExecutor::new(
@@ -451,6 +527,16 @@ impl LocalStreamManagerCore {
                     self.context.clone(),
                 ))
             }
+            NoShuffle => {
+                assert_eq!(outputs.len(), 1);
+                let output = outputs.into_iter().next().unwrap();
+                Box::new(DispatchExecutor::new(
+                    input,
+                    DispatcherImpl::NoShuffle(NoShuffleDispatcher::new(output)),
+                    actor_id,
+                    self.context.clone(),
+                ))
+            }
             Invalid => unreachable!(),
         };
         Ok(dispatcher)
@@ -466,6 +552,20 @@ impl LocalStreamManagerCore {
         env: StreamEnvironment,
         store: impl StateStore,
     ) -> Result<Box<dyn Executor>> {
+        if env.config().fuse_stateless_operators {
+            let chain = fusible_chain(node);
+            if chain.len() > 1 {
+                return self.create_fused_nodes(
+                    fragment_id,
+                    actor_id,
+                    chain,
+                    input_pos,
+                    env,
+                    store,
+                );
+            }
+        }
+
         let op_info = node.get_identity().clone();
         // Create the input executor before creating itself
         // The node with no input must be a `MergeNode`
@@ -514,10 +614,58 @@ impl LocalStreamManagerCore {
             actor_id,
             input_pos,
             self.streaming_metrics.clone(),
+            env.config(),
         )?;
         Ok(executor)
     }
 
+    /// Create one executor for a chain of fusible nodes, given from the top down. See
+    /// [`fusible_chain`].
+    fn create_fused_nodes(
+        &mut self,
+        fragment_id: u32,
+        actor_id: ActorId,
+        chain: Vec<&stream_plan::StreamNode>,
+        input_pos: usize,
+        env: StreamEnvironment,
+        store: impl StateStore,
+    ) -> Result<Box<dyn Executor>> {
+        let bottom = chain.last().unwrap();
+        let input = self.create_nodes_inner(
+            fragment_id,
+            actor_id,
+            &bottom.input[0],
+            0,
+            env.clone(),
+            store,
+        )?;
+
+        let nodes = chain
+            .iter()
+            .rev()
+            .map(|node| {
+                assert!(node.get_operator_id() <= u32::MAX as u64);
+                (*node, ((actor_id as u64) << 32) + node.get_operator_id())
+            })
+            .collect_vec();
+        // The fused executor takes the id of the top node.
+        let executor_id = nodes.last().unwrap().1;
+        let executor = Box::new(FusedExecutorV2::new(
+            Box::new(ExecutorV1AsV2(input)),
+            &nodes,
+            executor_id,
+        )?)
+        .v1();
+
+        Self::wrap_executor_for_debug(
+            Box::new(executor),
+            actor_id,
+            input_pos,
+            self.streaming_metrics.clone(),
+            env.config(),
+        )
+    }
+
     /// Create a chain(tree) of nodes and return the head executor.
     fn create_nodes(
         &mut self,
@@ -536,6 +684,7 @@ impl LocalStreamManagerCore {
         actor_id: ActorId,
         input_pos: usize,
         streaming_metrics: Arc<StreamingMetrics>,
+        config: &StreamingConfig,
     ) -> Result<Box<dyn Executor>> {
         if !cfg!(debug_assertions) {
             return Ok(executor);
@@ -560,6 +709,10 @@ impl LocalStreamManagerCore {
         }
         // Update check
         executor = Box::new(UpdateCheckExecutor::new(executor));
+        // Consistency check
+        if config.consistency_check || env_var_is_true(CONSISTENCY_CHECK_ENABLED_ENV_VAR_KEY) {
+            executor = Box::new(ConsistencyCheckExecutor::new(executor));
+        }
 
         Ok(executor)
     }
@@ -668,17 +821,46 @@ impl LocalStreamManagerCore {
                 1,
                 "compute node currently only supports single dispatcher"
             );
-            let dispatcher = self.create_dispatcher(executor, &dispatchers[0], actor_id)?;
+            let dispatcher =
+                self.create_dispatcher(executor, &dispatchers[0], actor_id, env.config())?;
 
             trace!("build actor: {:#?}", &dispatcher);
 
             let actor = Actor::new(dispatcher, actor_id, self.context.clone());
+            let event_reporter = env.event_reporter().clone();
             self.handles.insert(
                 actor_id,
-                tokio::spawn(async move {
-                    // unwrap the actor result to panic on error
-                    actor.run().await.expect("actor failed");
-                }),
+                tokio::spawn(instrument_actor(
+                    actor_id,
+                    self.streaming_metrics.clone(),
+                    async move {
+                        match AssertUnwindSafe(actor.run()).catch_unwind().await {
+                            Ok(Ok(())) => {}
+                            Ok(Err(e)) => {
+                                event_reporter
+                                    .report_and_wait(
+                                        EventType::ActorFailure,
+                                        actor_id,
+                                        format!("actor failed: {}", e),
+                                    )
+                                    .await;
+                                // panic on error after reporting it
+                                panic!("actor failed: {:?}", e);
+                            }
+                            // Report the panics of executors as well, then keep panicking.
+                            Err(panic) => {
+                                event_reporter
+                                    .report_and_wait(
+                                        EventType::ActorFailure,
+                                        actor_id,
+                                        format!("actor panicked: {}", panic_message(&*panic)),
+                                    )
+                                    .await;
+                                std::panic::resume_unwind(panic);
+                            }
+                        }
+                    },
+                )),
             );
         }
 
@@ -14,6 +14,7 @@
 
 #![feature(let_chains)]
 
+mod trace_context;
 mod trace_runtime;
 
 use std::time::Duration;
@@ -23,6 +24,8 @@ use tracing_subscriber::filter;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::prelude::*;
 
+pub use self::trace_context::{encode_trace_context, set_trace_context_parent};
+
 /// Configure log targets for all `RisingWave` crates. When new crates are added and TRACE level
 /// logs are needed, add them here.
 fn configure_risingwave_targets_jaeger(targets: filter::Targets) -> filter::Targets {
@@ -32,6 +35,8 @@ fn configure_risingwave_targets_jaeger(targets: filter::Targets) -> filter::Targ
         .with_target("risingwave_batch", Level::TRACE)
         .with_target("risingwave_storage", Level::TRACE)
         .with_target("risingwave_sqlparser", Level::INFO)
+        // the root span of each barrier, to which the barrier spans of the actors are attached
+        .with_target("risingwave_meta::barrier", Level::TRACE)
         // disable events that are too verbose
         // if you want to enable any of them, find the target name and set it to `TRACE`
         // .with_target("events::stream::mview::scan", Level::TRACE)
@@ -0,0 +1,84 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Propagation of the trace context of a span through protobuf messages, encoded as a W3C
+//! `traceparent` header.
+
+use std::collections::HashMap;
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::sdk::propagation::TraceContextPropagator;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+const TRACE_PARENT_HEADER: &str = "traceparent";
+
+/// Encodes the trace context of `span` as a W3C `traceparent` header, to be sent to another actor
+/// or node along with a message. Returns empty bytes if `span` is not traced.
+pub fn encode_trace_context(span: &tracing::Span) -> Vec<u8> {
+    let mut carrier = HashMap::new();
+    TraceContextPropagator::new().inject_context(&span.context(), &mut carrier);
+    carrier
+        .remove(TRACE_PARENT_HEADER)
+        .map(String::into_bytes)
+        .unwrap_or_default()
+}
+
+/// Sets the parent of `span` to the trace context encoded by [`encode_trace_context`]. Empty or
+/// malformed trace contexts are ignored.
+pub fn set_trace_context_parent(span: &tracing::Span, trace_context: &[u8]) {
+    let trace_parent = match std::str::from_utf8(trace_context) {
+        Ok(trace_parent) if !trace_parent.is_empty() => trace_parent,
+        _ => return,
+    };
+    let carrier = HashMap::from([(TRACE_PARENT_HEADER.to_owned(), trace_parent.to_owned())]);
+    span.set_parent(TraceContextPropagator::new().extract(&carrier));
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::sdk::trace::TracerProvider;
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn test_trace_context() {
+        assert!(encode_trace_context(&tracing::Span::none()).is_empty());
+
+        let tracer = TracerProvider::builder().build().tracer("test", None);
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        tracing::subscriber::with_default(subscriber, || {
+            let sender = tracing::info_span!("sender");
+            let trace_context = encode_trace_context(&sender);
+            assert!(!trace_context.is_empty());
+
+            let receiver = tracing::info_span!(parent: None, "receiver");
+            set_trace_context_parent(&receiver, &trace_context);
+            assert_eq!(
+                receiver.context().span().span_context().trace_id(),
+                sender.context().span().span_context().trace_id()
+            );
+
+            // A malformed trace context leaves the span as a root.
+            let root = tracing::info_span!(parent: None, "root");
+            set_trace_context_parent(&root, b"malformed");
+            assert_ne!(
+                root.context().span().span_context().trace_id(),
+                sender.context().span().span_context().trace_id()
+            );
+        });
+    }
+}
@@ -590,6 +590,11 @@ impl<B: Buf> Deserializer<B> {
         Ok((mantissa, scale as u8))
     }
 
+    /// Deserialize a byte array value. Returns the raw bytes.
+    pub fn deserialize_byte_array(&mut self) -> Result<Vec<u8>> {
+        self.read_bytes()
+    }
+
     /// Deserialize a NaiveDateWrapper value. Returns `days`.
     pub fn deserialize_naivedate(&mut self) -> Result<i32> {
         let days = self.input.get_i32() ^ (1 << 31);
@@ -804,6 +809,25 @@ mod tests {
         deserializer.deserialize_decimal().unwrap()
     }
 
+    #[test]
+    fn test_byte_array() {
+        for bytes in [vec![], vec![0u8, 1, 2], (0..=255).collect::<Vec<u8>>()] {
+            let bytes0 = deserialize_byte_array(&serialize_byte_array(&bytes));
+            assert_eq!(bytes, bytes0);
+        }
+    }
+
+    fn serialize_byte_array(bytes: &[u8]) -> Vec<u8> {
+        let mut serializer = crate::Serializer::new(vec![]);
+        serializer.serialize_byte_array(bytes).unwrap();
+        serializer.into_inner()
+    }
+
+    fn deserialize_byte_array(bytes: &[u8]) -> Vec<u8> {
+        let mut deserializer = Deserializer::new(bytes);
+        deserializer.deserialize_byte_array().unwrap()
+    }
+
     #[test]
     fn test_naivedate() {
         let days = 12_3456;
@@ -556,6 +556,11 @@ impl<B: BufMut> Serializer<B> {
         (e100 as i8, byte_array)
     }
 
+    /// Serialize a byte array value, with the same layout as a string.
+    pub fn serialize_byte_array(&mut self, v: &[u8]) -> Result<()> {
+        serde::Serializer::serialize_bytes(self, v)
+    }
+
     /// Serialize a NaiveDateWrapper value.
     ///
     /// - `days`: From `chrono::Datelike::num_days_from_ce()`.
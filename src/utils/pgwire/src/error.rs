@@ -12,8 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::error::Error as StdError;
+
 use thiserror::Error;
 
+/// The SQLSTATE of errors without a more specific one, i.e. `internal_error`.
+pub const SQLSTATE_INTERNAL_ERROR: &str = "XX000";
+/// The SQLSTATE of statements canceled by the user, i.e. `query_canceled`.
+pub const SQLSTATE_QUERY_CANCELED: &str = "57014";
+
 /// Error type used in pgwire crates.
 #[derive(Error, Debug)]
 pub enum PsqlError {
@@ -27,3 +34,43 @@ impl PsqlError {
         PsqlError::CancelError("ERROR:  canceling statement due to user request".to_string())
     }
 }
+
+/// An error reported to the client with its SQLSTATE and an optional detail, so that the client
+/// can tell e.g. a syntax error from a transient failure worth retrying.
+#[derive(Error, Debug)]
+#[error("{source}")]
+pub struct PgError {
+    sqlstate: String,
+    detail: Option<String>,
+    source: Box<dyn StdError + Send + Sync>,
+}
+
+impl PgError {
+    pub fn new(sqlstate: impl Into<String>, source: Box<dyn StdError + Send + Sync>) -> Self {
+        Self {
+            sqlstate: sqlstate.into(),
+            detail: None,
+            source,
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    /// Returns the SQLSTATE of any error sent to the client.
+    pub fn sqlstate_of(error: &(dyn StdError + Send + Sync + 'static)) -> &str {
+        if let Some(error) = error.downcast_ref::<PgError>() {
+            &error.sqlstate
+        } else if let Some(PsqlError::CancelError(_)) = error.downcast_ref::<PsqlError>() {
+            SQLSTATE_QUERY_CANCELED
+        } else {
+            SQLSTATE_INTERNAL_ERROR
+        }
+    }
+}
@@ -44,7 +44,11 @@ impl PgFieldDescriptor {
             | TypeOid::Time
             | TypeOid::Timestampz => 8,
             TypeOid::SmallInt => 2,
-            TypeOid::CharArray | TypeOid::Varchar | TypeOid::Decimal => -1,
+            TypeOid::CharArray
+            | TypeOid::Varchar
+            | TypeOid::Bytea
+            | TypeOid::Varbit
+            | TypeOid::Decimal => -1,
         };
 
         Self {
@@ -90,6 +94,7 @@ impl PgFieldDescriptor {
 #[derive(Debug, Copy, Clone)]
 pub enum TypeOid {
     Boolean,
+    Bytea,
     BigInt,
     SmallInt,
     Int,
@@ -102,12 +107,14 @@ pub enum TypeOid {
     Timestamp,
     Timestampz,
     Decimal,
+    Varbit,
 }
 
 impl TypeOid {
     pub fn as_number(&self) -> i32 {
         match self {
             TypeOid::Boolean => 16,
+            TypeOid::Bytea => 17,
             TypeOid::BigInt => 20,
             TypeOid::SmallInt => 21,
             TypeOid::Int => 23,
@@ -120,6 +127,7 @@ impl TypeOid {
             TypeOid::Timestamp => 1114,
             TypeOid::Timestampz => 1184,
             TypeOid::Decimal => 1231,
+            TypeOid::Varbit => 1562,
         }
     }
 }
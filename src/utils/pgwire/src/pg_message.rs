@@ -19,6 +19,7 @@ use byteorder::{BigEndian, ByteOrder};
 use bytes::{BufMut, Bytes, BytesMut};
 use tokio::io::{AsyncRead, AsyncReadExt};
 
+use crate::error::PgError;
 use crate::pg_field_descriptor::PgFieldDescriptor;
 use crate::pg_response::StatementType;
 use crate::types::Row;
@@ -281,8 +282,8 @@ impl<'a> BeMessage<'a> {
             }
 
             BeMessage::ErrorResponse(error) => {
-                // For all the errors set Severity to Error and error code to
-                // 'internal error'.
+                // For all the errors set Severity to Error. The error code is 'internal error'
+                // unless the error carries its SQLSTATE.
 
                 // 'E' signalizes ErrorResponse messages
                 buf.put_u8(b'E');
@@ -291,11 +292,17 @@ impl<'a> BeMessage<'a> {
                     write_cstr(buf, &Bytes::from("ERROR"))?;
 
                     buf.put_u8(b'C'); // SQLSTATE error code
-                    write_cstr(buf, &Bytes::from("XX000"))?;
+                    write_cstr(buf, PgError::sqlstate_of(error.as_ref()).as_bytes())?;
 
                     buf.put_u8(b'M'); // the message
                     write_cstr(buf, error.to_string().as_bytes())?;
 
+                    if let Some(detail) = error.downcast_ref::<PgError>().and_then(PgError::detail)
+                    {
+                        buf.put_u8(b'D'); // the detail
+                        write_cstr(buf, detail.as_bytes())?;
+                    }
+
                     buf.put_u8(0); // terminator
                     Ok(())
                 })
@@ -43,6 +43,7 @@ pub enum StatementType {
     SHOW_PARAMETERS,
     SHOW_COMMAND,
     FLUSH,
+    CANCEL_JOB,
     OTHER,
     // EMPTY is used when query statement is empty (e.g. ";").
     EMPTY,